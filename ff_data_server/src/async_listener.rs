@@ -1,19 +1,24 @@
 use std::io;
-use rustls::ServerConfig;
 use std::net::SocketAddr;
-use tokio_rustls::TlsAcceptor;
+use crate::server_features::tls_config::tls_acceptor;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
 use tokio::net::{TcpListener, TcpStream};
 use tokio_rustls::server::TlsStream;
-use ff_standard_lib::messages::data_server_messaging::{DataServerRequest, DataServerResponse};
+use ff_standard_lib::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
 use ff_standard_lib::standardized_types::enums::StrategyMode;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use ff_standard_lib::standardized_types::bytes_trait::Bytes;
 use crate::request_handlers::manage_async_requests;
+use crate::server_features::api_keys::authenticate;
+use crate::server_features::trade_copier::COPIER_STREAM_NAME;
+use crate::server_features::websocket_bridge::BRIDGE_STREAM_NAMES;
+use ff_standard_lib::helpers::logging::stream_span;
+use tracing::Instrument;
 use crate::subscribe_server_shutdown;
 use socket2::{Socket, Domain, Type, Protocol};
 use tokio::sync::Notify;
+use std::time::Duration;
 
 pub(crate) async fn create_listener(addr: SocketAddr) -> io::Result<TcpListener> {
     let domain = if addr.is_ipv4() { Domain::IPV4 } else { Domain::IPV6 };
@@ -41,18 +46,17 @@ pub(crate) async fn create_listener(addr: SocketAddr) -> io::Result<TcpListener>
 use std::collections::HashSet;
 use std::sync::Mutex;
 
-pub(crate) async fn async_server(config: ServerConfig, addr: SocketAddr) {
-    let acceptor = TlsAcceptor::from(Arc::new(config));
-
+/// Accepts strategy connections, strategies not heard from within the `heartbeat_timeout` are dropped, `None` keeps them until they disconnect.
+pub(crate) async fn async_server(addr: SocketAddr, heartbeat_timeout: Option<Duration>) {
     let listener = match create_listener(addr).await {
         Ok(l) => l,
         Err(e) => {
-            eprintln!("Server: Failed to create listener on {}: {}", addr, e);
+            tracing::error!("Server: Failed to create listener on {}: {}", addr, e);
             return;
         }
     };
 
-    println!("Listening on: {}", addr);
+    tracing::info!("Listening on: {}", addr);
 
     let mut shutdown_receiver = subscribe_server_shutdown();
     let active_connections = Arc::new(AtomicUsize::new(0));
@@ -69,7 +73,7 @@ pub(crate) async fn async_server(config: ServerConfig, addr: SocketAddr) {
             result = listener.accept() => {
                 match result {
                     Ok((stream, _peer_addr)) => {
-                        let acceptor = acceptor.clone();
+                        let acceptor = tls_acceptor();
                         let active_connections = active_connections.clone();
                         let shutdown_complete = shutdown_complete_tx.clone();
                         let active_ids = active_ids.clone();
@@ -81,8 +85,8 @@ pub(crate) async fn async_server(config: ServerConfig, addr: SocketAddr) {
                         let conn_id = {
                             let mut ids = active_ids.lock().unwrap();
                             let mut candidate = next_id.load(Ordering::SeqCst);
-                            // Keep trying until we find an unused ID
-                            while ids.contains(&candidate) {
+                            // Keep trying until we find an unused ID, the bridge names are allocated by the websocket bridge
+                            while ids.contains(&candidate) || candidate == COPIER_STREAM_NAME || BRIDGE_STREAM_NAMES.contains(&candidate) {
                                 candidate = candidate.wrapping_add(1);
                             }
                             ids.insert(candidate);
@@ -93,7 +97,7 @@ pub(crate) async fn async_server(config: ServerConfig, addr: SocketAddr) {
                         tokio::spawn(async move {
                             match acceptor.accept(stream).await {
                                 Ok(tls_stream) => {
-                                    handle_async_connection(tls_stream, conn_id).await;
+                                    handle_async_connection(tls_stream, conn_id, heartbeat_timeout).await;
                                 }
                                 Err(_e) => {
                                     //eprintln!("Server: Failed to accept TLS connection: {:?}", e);
@@ -117,7 +121,7 @@ pub(crate) async fn async_server(config: ServerConfig, addr: SocketAddr) {
             },
 
             _ = shutdown_receiver.recv() => {
-                println!("Server: Shutdown signal received, stopping accept loop");
+                tracing::info!("Server: Shutdown signal received, stopping accept loop");
                 break;
             }
         }
@@ -128,23 +132,32 @@ pub(crate) async fn async_server(config: ServerConfig, addr: SocketAddr) {
 
     tokio::select! {
         _ = shutdown_complete_rx.notified() => {
-            println!("Server: All connections completed gracefully");
+            tracing::info!("Server: All connections completed gracefully");
         }
         _ = &mut timeout => {
-            println!("Server: Shutdown timeout reached, forcing close");
+            tracing::info!("Server: Shutdown timeout reached, forcing close");
         }
     }
 
     drop(listener);
 }
 
-async fn handle_async_connection(mut tls_stream: TlsStream<TcpStream>, stream_name: u16) {
+/// The largest message accepted before a connection registers, a `DataServerRequest::Register` is far smaller,
+/// so an unauthenticated client can't make the server allocate a large buffer.
+pub(crate) const MAX_REGISTER_LENGTH: usize = 4096;
+
+async fn handle_async_connection(mut tls_stream: TlsStream<TcpStream>, stream_name: u16, heartbeat_timeout: Option<Duration>) {
     const LENGTH: usize = 4;
     let mut length_bytes = [0u8; LENGTH];
     let mut mode = StrategyMode::Backtest;
+    let mut access = None;
     while let Ok(_) = tls_stream.read_exact(&mut length_bytes).await {
         // Parse the length from the header
         let msg_length = u32::from_be_bytes(length_bytes) as usize;
+        if msg_length > MAX_REGISTER_LENGTH {
+            tracing::warn!("Server: Rejected connection {}, the register message length {} is above the limit", stream_name, msg_length);
+            return;
+        }
         let mut message_body = vec![0u8; msg_length];
 
         // Read the message body based on the length
@@ -167,30 +180,34 @@ async fn handle_async_connection(mut tls_stream: TlsStream<TcpStream>, stream_na
         //println!("{:?}", request);
         // Handle the request and generate a response
         match request {
-            DataServerRequest::Register(registered_mode) => {
+            DataServerRequest::Register { mode: registered_mode, api_key } => {
                 mode = registered_mode;
+                match authenticate(stream_name, api_key) {
+                    Ok(connection_access) => access = Some(connection_access),
+                    Err(reason) => {
+                        tracing::warn!("Server: Rejected connection {}: {}", stream_name, reason);
+                        let response = DataServerResponse::Error { callback_id: 0, error: FundForgeError::InvalidApiKey };
+                        let _ = write_response(&mut tls_stream, &response).await;
+                        return;
+                    }
+                }
                 break;
             },
-            _ => eprintln!("Server: Strategy Did not register a Strategy mode")
+            _ => tracing::warn!("Server: Strategy Did not register a Strategy mode")
         }
     }
+    let access = match access {
+        Some(access) => access,
+        None => return,
+    };
     //println!("Server: TLS connection established with {:?}", peer_addr);
 
 
     // If we are using live stream send the stream response so that the strategy can
     if mode == StrategyMode::Live || mode == StrategyMode::LivePaperTrading {
         let response = DataServerResponse::RegistrationResponse(stream_name.clone());
-        // Convert the response to bytes
-        let bytes = response.to_bytes();
-
-        // Prepare the message with a 4-byte length header in big-endian format
-        let length = (bytes.len() as u64).to_be_bytes();
-        let mut prefixed_msg = Vec::with_capacity(8 + bytes.len());
-        prefixed_msg.extend_from_slice(&length);
-        prefixed_msg.extend_from_slice(&bytes);
-
         // Write the response to the stream
-        if let Err(_e) = tls_stream.write_all(&prefixed_msg).await {
+        if let Err(_e) = write_response(&mut tls_stream, &response).await {
             return;
             // Handle the error (log it or take some other action)
         }
@@ -199,6 +216,21 @@ async fn handle_async_connection(mut tls_stream: TlsStream<TcpStream>, stream_na
     manage_async_requests(
         mode,
         tls_stream,
-        stream_name
-    ).await;
+        stream_name,
+        heartbeat_timeout,
+        access
+    ).instrument(stream_span(stream_name)).await;
+}
+
+/// Writes the response with an 8-byte big-endian length header, before the connection is handed to the request handler.
+async fn write_response(tls_stream: &mut TlsStream<TcpStream>, response: &DataServerResponse) -> io::Result<()> {
+    // Convert the response to bytes
+    let bytes = response.to_bytes();
+
+    // Prepare the message with the length header
+    let length = (bytes.len() as u64).to_be_bytes();
+    let mut prefixed_msg = Vec::with_capacity(8 + bytes.len());
+    prefixed_msg.extend_from_slice(&length);
+    prefixed_msg.extend_from_slice(&bytes);
+    tls_stream.write_all(&prefixed_msg).await
 }
\ No newline at end of file
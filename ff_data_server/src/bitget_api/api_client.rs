@@ -1,5 +1,10 @@
 use std::collections::VecDeque;
+use std::fmt;
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use reqwest::{Client, Method};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 use futures::{SinkExt, StreamExt};
@@ -14,9 +19,17 @@ use tungstenite::Message;
 use ff_standard_lib::messages::data_server_messaging::FundForgeError;
 use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use tokio::sync::{broadcast, Mutex as TokioMutex};
+use ff_standard_lib::server_launch_options::ServerLaunchOptions;
+use ff_standard_lib::standardized_types::accounts::{Account, AccountId, AccountInfo, Currency};
+use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+use ff_standard_lib::standardized_types::orders::{Order, OrderId};
 use ff_standard_lib::standardized_types::subscriptions::SymbolName;
+use ff_standard_lib::StreamName;
 use crate::bitget_api::login;
 use crate::bitget_api::login::BitGetCredentials;
+use crate::bitget_api::models::{BitgetResponse, FuturesAccount, FuturesPosition, SpotAsset, fund_forge_symbol};
+use crate::bitget_api::user_stream::handle_user_stream;
+use crate::rate_limiter::RateLimiter;
 
 pub static BITGET_CLIENT: OnceCell<Arc<BitgetClient>> = OnceCell::new();
 
@@ -24,36 +37,48 @@ pub static BITGET_CLIENT: OnceCell<Arc<BitgetClient>> = OnceCell::new();
 type HmacSha256 = Hmac<Sha256>;
 
 const WEBSOCKET_URL: &str = "wss://ws.bitget.com/v2/ws/public";
+const REST_URL: &str = "https://api.bitget.com";
+/// The quote coin of the accounts, account values are in USDT which we treat as USD.
+pub(crate) const MARGIN_COIN: &str = "USDT";
 #[allow(unused)]
 const MAX_SUBSCRIPTIONS: usize = 1000;
 #[allow(unused)]
 const RECOMMENDED_SUBSCRIPTIONS: usize = 50;
 #[allow(unused)]
 const MESSAGE_RATE_LIMIT: usize = 10;
-#[allow(unused)]
-const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+pub(crate) const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
 #[allow(unused)]
 const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(30);
 
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Bitget accounts are named after the instrument type they trade, `SPOT` and `USDT-FUTURES`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum InstType {
     Spot,
     Futures,
 }
-impl InstType {
-    #[allow(unused)]
-    fn to_string(&self) -> String {
+impl fmt::Display for InstType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            InstType::Spot => "SPOT".to_string(),
-            InstType::Futures => "USDT-FUTURES".to_string()
+            InstType::Spot => write!(f, "SPOT"),
+            InstType::Futures => write!(f, "USDT-FUTURES")
+        }
+    }
+}
+
+impl InstType {
+    pub(crate) fn from_account_id(account_id: &str) -> Option<InstType> {
+        match account_id {
+            "SPOT" => Some(InstType::Spot),
+            "USDT-FUTURES" => Some(InstType::Futures),
+            _ => None
         }
     }
 }
 
 #[allow(unused)]
 pub struct BitgetClient {
-    credentials: BitGetCredentials,
+    pub(crate) credentials: BitGetCredentials,
     tick_data_write: Arc<TokioMutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>>,
     tick_subscriptions: Arc<DashMap<InstType, DashMap<SymbolName, broadcast::Sender<BaseDataEnum>>>>,
 
@@ -63,6 +88,19 @@ pub struct BitgetClient {
     sync_socket: Arc<TokioMutex<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
     message_queue: Arc<Mutex<VecDeque<String>>>,
     last_activity: Arc<Mutex<Instant>>,
+
+    http_client: Client,
+    rate_limiter: Arc<RateLimiter>,
+    pub accounts: Vec<Account>,
+    pub account_info: DashMap<AccountId, AccountInfo>,
+    /// The coin balances of the spot account, the spot account has no positions, only balances.
+    pub spot_balances: DashMap<String, Decimal>,
+    /// The USDT-M futures positions, Bitget accounts must be in one-way position mode.
+    pub positions: DashMap<SymbolName, FuturesPosition>,
+    pub open_orders: DashMap<OrderId, Order>,
+    pub id_stream_name_map: DashMap<OrderId, StreamName>,
+    /// The funding rate and open interest feeds, one polling task per symbol, see `fundamentals`.
+    pub fundamental_broadcasters: DashMap<SymbolName, broadcast::Sender<BaseDataEnum>>,
 }
 
 impl BitgetClient {
@@ -74,25 +112,24 @@ impl BitgetClient {
 
         // Connect and login for data feed
         let tick_subscriptions = Arc::new(DashMap::new());
-        let (mut data_stream, _r) = match connect_async(WEBSOCKET_URL).await {
+        let (data_stream, _r) = match connect_async(WEBSOCKET_URL).await {
             Ok((stream, r)) => (stream, r),
             Err(e) => {
                 return Err(FundForgeError::ServerErrorDebug(format!("Failed to connect to BitGet: {}", e)))
             }
         };
-        login::login(&credentials, &mut data_stream).await?;
+        // Public channels do not require a login
         let (data_write, data_read) = data_stream.split();
         BitgetClient::receive_data_event_loop(data_read, tick_subscriptions.clone()).await;
 
         // Connect to quote stream
         let quote_subscriptions = Arc::new(DashMap::new());
-        let (mut quote_data_stream, _) = match connect_async(WEBSOCKET_URL).await {
+        let (quote_data_stream, _) = match connect_async(WEBSOCKET_URL).await {
             Ok((stream, r)) => (stream, r),
             Err(e) => {
                 return Err(FundForgeError::ServerErrorDebug(format!("Failed to connect to BitGet: {}", e)))
             }
         };
-        login::login(&credentials, &mut quote_data_stream).await?;
         let (quote_data_write, quote_data_read) = quote_data_stream.split();
         BitgetClient::receive_data_event_loop(quote_data_read, quote_subscriptions.clone()).await;
 
@@ -113,11 +150,39 @@ impl BitgetClient {
             quote_subscriptions,
             message_queue: Arc::new(Mutex::new(VecDeque::new())),
             last_activity: Arc::new(Mutex::new(Instant::now())),
+            http_client: Client::new(),
+            rate_limiter: RateLimiter::new(10, Duration::from_secs(1)),
+            accounts: vec![
+                Account::new(Brokerage::Bitget, InstType::Spot.to_string()),
+                Account::new(Brokerage::Bitget, InstType::Futures.to_string()),
+            ],
+            account_info: Default::default(),
+            spot_balances: Default::default(),
+            positions: Default::default(),
+            open_orders: Default::default(),
+            id_stream_name_map: Default::default(),
+            fundamental_broadcasters: Default::default(),
         };
 
         Ok(client)
     }
 
+    /// The open feeds and the number of receivers of each, as `(feed, symbol, receivers)`.
+    pub(crate) fn feed_receiver_counts(&self) -> Vec<(&'static str, SymbolName, usize)> {
+        let mut counts = vec![];
+        for (feed, subscriptions) in [("ticks", &self.tick_subscriptions), ("quotes", &self.quote_subscriptions)] {
+            for inst_type in subscriptions.iter() {
+                for broadcaster in inst_type.value().iter() {
+                    counts.push((feed, broadcaster.key().clone(), broadcaster.value().receiver_count()));
+                }
+            }
+        }
+        for broadcaster in self.fundamental_broadcasters.iter() {
+            counts.push(("fundamentals", broadcaster.key().clone(), broadcaster.value().receiver_count()));
+        }
+        counts
+    }
+
 
     async fn receive_data_event_loop(mut data_read: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>, _subscriptions: Arc<DashMap<InstType, DashMap<SymbolName, broadcast::Sender<BaseDataEnum>>>>) {
         //todo need to handle sending heartbeat every 30 seconds when no incoming data
@@ -136,7 +201,7 @@ impl BitgetClient {
                         }
                     },
                     Err(e) => {
-                        eprintln!("WebSocket error on data stream: {}", e);
+                        tracing::error!("WebSocket error on data stream: {}", e);
                         // Implement reconnection logic here
                         break;
                     }
@@ -176,3 +241,151 @@ impl BitgetClient {
     }
 }
 
+
+pub(crate) async fn bitget_init(options: ServerLaunchOptions) {
+    if options.disable_bitget_server != 0 || login::get_bitget_credentials().is_none() {
+        return;
+    }
+    let client = match BitgetClient::new().await {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("Bitget client failed to initialize: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = client.refresh_accounts().await {
+        tracing::error!("Error getting Bitget accounts: {}", e);
+    }
+    let client = Arc::new(client);
+    handle_user_stream(client.clone());
+    tracing::info!("Bitget client initialized");
+    let _ = BITGET_CLIENT.set(client);
+}
+
+impl BitgetClient {
+    /// Sends a signed REST request and returns the `data` of the response, `query` is the url encoded query string without the `?`.
+    pub(crate) async fn send_signed_request(&self, method: Method, path: &str, query: &str, body: Option<serde_json::Value>) -> Result<serde_json::Value, FundForgeError> {
+        let request_path = match query.is_empty() {
+            true => path.to_string(),
+            false => format!("{}?{}", path, query),
+        };
+        let body = body.map(|body| body.to_string()).unwrap_or_default();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+            .to_string();
+        let pre_hash = format!("{}{}{}{}", timestamp, method.as_str(), request_path, body);
+        let sign = login::sign(&self.credentials.secret_key, &pre_hash)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Error signing Bitget request: {}", e)))?;
+
+        let _permit = self.rate_limiter.acquire().await;
+        let mut request = self.http_client.request(method, format!("{}{}", REST_URL, request_path))
+            .header("ACCESS-KEY", &self.credentials.api_key)
+            .header("ACCESS-SIGN", sign)
+            .header("ACCESS-TIMESTAMP", timestamp)
+            .header("ACCESS-PASSPHRASE", &self.credentials.passphrase)
+            .header("Content-Type", "application/json")
+            .header("locale", "en-US");
+        if !body.is_empty() {
+            request = request.body(body);
+        }
+        let content = request.send().await
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to send Bitget request: {}", e)))?
+            .text().await
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to read Bitget response: {}", e)))?;
+
+        let response: BitgetResponse<serde_json::Value> = serde_json::from_str(&content)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to parse Bitget response: {}, {}", e, content)))?;
+        match response.code.as_str() {
+            "00000" => Ok(response.data.unwrap_or_default()),
+            code => Err(FundForgeError::ServerErrorDebug(format!("Bitget error {}: {}", code, response.msg))),
+        }
+    }
+
+    /// Sends an unsigned REST request to a public market endpoint and returns the `data` of the response.
+    pub(crate) async fn send_public_request(&self, path: &str, query: &str) -> Result<serde_json::Value, FundForgeError> {
+        let _permit = self.rate_limiter.acquire().await;
+        let content = self.http_client.get(format!("{}{}?{}", REST_URL, path, query))
+            .header("locale", "en-US")
+            .send().await
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to send Bitget request: {}", e)))?
+            .text().await
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to read Bitget response: {}", e)))?;
+
+        let response: BitgetResponse<serde_json::Value> = serde_json::from_str(&content)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to parse Bitget response: {}, {}", e, content)))?;
+        match response.code.as_str() {
+            "00000" => Ok(response.data.unwrap_or_default()),
+            code => Err(FundForgeError::ServerErrorDebug(format!("Bitget error {}: {}", code, response.msg))),
+        }
+    }
+
+    /// Updates the account info, spot balances and futures positions from the REST snapshots.
+    /// Called on start up and after the user stream reconnects, the user stream keeps them updated in between.
+    pub(crate) async fn refresh_accounts(&self) -> Result<(), FundForgeError> {
+        let assets = self.send_signed_request(Method::GET, "/api/v2/spot/account/assets", "", None).await?;
+        let assets: Vec<SpotAsset> = serde_json::from_value(assets)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to parse Bitget spot assets: {}", e)))?;
+        self.update_spot_balances(&assets);
+
+        let query = format!("productType={}", InstType::Futures);
+        let accounts = self.send_signed_request(Method::GET, "/api/v2/mix/account/accounts", &query, None).await?;
+        let accounts: Vec<FuturesAccount> = serde_json::from_value(accounts)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to parse Bitget futures accounts: {}", e)))?;
+        if let Some(account) = accounts.iter().find(|account| account.margin_coin == MARGIN_COIN) {
+            self.update_account_info(InstType::Futures, account.account_equity, account.available, account.locked, account.unrealized_pl);
+        }
+
+        let query = format!("productType={}&marginCoin={}", InstType::Futures, MARGIN_COIN);
+        let positions = self.send_signed_request(Method::GET, "/api/v2/mix/position/all-position", &query, None).await?;
+        let positions: Vec<FuturesPosition> = serde_json::from_value(positions)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to parse Bitget positions: {}", e)))?;
+        self.positions.clear();
+        for position in positions {
+            if position.total > dec!(0) {
+                self.positions.insert(fund_forge_symbol(&position.symbol), position);
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the coin balances and the USDT values of the spot account.
+    pub(crate) fn update_spot_balances(&self, assets: &[SpotAsset]) {
+        for asset in assets {
+            self.spot_balances.insert(asset.coin.clone(), asset.available);
+            if asset.coin == MARGIN_COIN {
+                let used = asset.frozen + asset.locked;
+                self.update_account_info(InstType::Spot, asset.available + used, asset.available, used, dec!(0));
+            }
+        }
+    }
+
+    pub(crate) fn update_account_info(&self, inst_type: InstType, cash_value: Decimal, cash_available: Decimal, cash_used: Decimal, open_pnl: Decimal) {
+        let account_id = inst_type.to_string();
+        let mut account_info = self.account_info.entry(account_id.clone()).or_insert_with(|| AccountInfo {
+            account_id,
+            brokerage: Brokerage::Bitget,
+            cash_value: dec!(0),
+            cash_available: dec!(0),
+            currency: Currency::USD,
+            open_pnl: dec!(0),
+            booked_pnl: dec!(0),
+            day_open_pnl: dec!(0),
+            day_booked_pnl: dec!(0),
+            cash_used: dec!(0),
+            positions: vec![],
+            is_hedging: false,
+            buy_limit: None,
+            sell_limit: None,
+            max_orders: None,
+            daily_max_loss: None,
+            daily_max_loss_reset_time: None,
+            leverage: 1,
+        });
+        account_info.cash_value = cash_value;
+        account_info.cash_available = cash_available;
+        account_info.cash_used = cash_used;
+        account_info.open_pnl = open_pnl;
+    }
+}
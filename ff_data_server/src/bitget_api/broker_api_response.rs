@@ -1,84 +1,412 @@
+use std::str::FromStr;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use ff_standard_lib::messages::data_server_messaging::DataServerResponse;
+use reqwest::Method;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+use ff_standard_lib::messages::data_server_messaging::{DataServerResponse, FundForgeError};
 use crate::server_features::server_side_brokerage::BrokerApiResponse;
-use ff_standard_lib::standardized_types::accounts::{Account, AccountId};
-use ff_standard_lib::standardized_types::enums::StrategyMode;
-use ff_standard_lib::standardized_types::orders::{Order, OrderId, OrderUpdateEvent, OrderUpdateType};
+use ff_standard_lib::standardized_types::accounts::{Account, AccountId, Currency};
+use ff_standard_lib::standardized_types::enums::{OrderSide, PositionSide, StrategyMode};
+use ff_standard_lib::standardized_types::orders::{Order, OrderId, OrderState, OrderType, OrderUpdateEvent, OrderUpdateType, TimeInForce};
 use ff_standard_lib::standardized_types::subscriptions::SymbolName;
+use ff_standard_lib::standardized_types::symbol_info::SymbolInfo;
 use ff_standard_lib::StreamName;
-use crate::bitget_api::api_client::BitgetClient;
+use crate::bitget_api::api_client::{BitgetClient, InstType, MARGIN_COIN};
+use crate::bitget_api::models::{bitget_symbol, fund_forge_symbol};
+
+fn order_rejected(order: &Order, reason: String) -> OrderUpdateEvent {
+    OrderUpdateEvent::OrderRejected {
+        account: order.account.clone(),
+        symbol_name: order.symbol_name.clone(),
+        symbol_code: order.symbol_name.clone(),
+        order_id: order.id.clone(),
+        reason,
+        tag: order.tag.clone(),
+        time: Utc::now().to_string(),
+    }
+}
+
+fn decimal_places(value: &serde_json::Value) -> Option<u32> {
+    value.as_str().and_then(|places| places.parse().ok())
+}
+
+fn decimal(value: &serde_json::Value) -> Option<Decimal> {
+    value.as_str().and_then(|value| Decimal::from_str(value).ok())
+}
+
+impl BitgetClient {
+    /// Places a market or limit order, `reduce_only` futures orders can only reduce the open position.
+    /// The order is tracked before the request is sent, so updates pushed on the user stream before the response arrives are not missed.
+    async fn place_order(&self, stream_name: StreamName, mut order: Order, reduce_only: bool) -> Result<(), OrderUpdateEvent> {
+        let inst_type = InstType::from_account_id(&order.account.account_id)
+            .ok_or_else(|| order_rejected(&order, format!("Unknown Bitget account: {}, use SPOT or USDT-FUTURES", order.account.account_id)))?;
+        let (order_type, price) = match order.order_type {
+            OrderType::Market | OrderType::EnterLong | OrderType::EnterShort | OrderType::ExitLong | OrderType::ExitShort => ("market", None),
+            OrderType::Limit => match order.limit_price {
+                Some(price) => ("limit", Some(price)),
+                None => return Err(order_rejected(&order, "No limit price provided".to_string())),
+            },
+            _ => return Err(order_rejected(&order, "Order type not supported with Bitget, use market or limit orders".to_string())),
+        };
+        let force = match order.time_in_force {
+            TimeInForce::GTC => "gtc",
+            TimeInForce::IOC => "ioc",
+            TimeInForce::FOK => "fok",
+            TimeInForce::Day | TimeInForce::Time(_) | TimeInForce::TimeNanos(_) => return Err(order_rejected(&order, "Bitget only supports GTC, IOC and FOK time in force".to_string())),
+        };
+        let side = match order.side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+        let symbol = bitget_symbol(&order.symbol_name);
+
+        let body = match inst_type {
+            InstType::Spot => {
+                // Spot market buys are sized in the quote coin
+                let size = match order_type == "market" && order.side == OrderSide::Buy {
+                    true => self.spot_quote_size(&symbol, order.quantity_open).await.map_err(|e| order_rejected(&order, e.to_string()))?,
+                    false => order.quantity_open,
+                };
+                let mut body = serde_json::json!({
+                    "symbol": symbol,
+                    "side": side,
+                    "orderType": order_type,
+                    "force": force,
+                    "size": size.to_string(),
+                    "clientOid": order.id,
+                });
+                if let Some(price) = price {
+                    body["price"] = serde_json::Value::String(price.to_string());
+                }
+                body
+            }
+            InstType::Futures => {
+                let mut body = serde_json::json!({
+                    "symbol": symbol,
+                    "productType": inst_type.to_string(),
+                    "marginMode": "crossed",
+                    "marginCoin": MARGIN_COIN,
+                    "size": order.quantity_open.to_string(),
+                    "side": side,
+                    "orderType": order_type,
+                    "force": force,
+                    "clientOid": order.id,
+                    "reduceOnly": if reduce_only { "YES" } else { "NO" },
+                });
+                if let Some(price) = price {
+                    body["price"] = serde_json::Value::String(price.to_string());
+                }
+                body
+            }
+        };
+        let path = match inst_type {
+            InstType::Spot => "/api/v2/spot/trade/place-order",
+            InstType::Futures => "/api/v2/mix/order/place-order",
+        };
+
+        order.state = OrderState::Created;
+        self.open_orders.insert(order.id.clone(), order.clone());
+        if stream_name != 0 {
+            self.id_stream_name_map.insert(order.id.clone(), stream_name);
+        }
+
+        if let Err(e) = self.send_signed_request(Method::POST, path, "", Some(body)).await {
+            self.open_orders.remove(&order.id);
+            self.id_stream_name_map.remove(&order.id);
+            return Err(order_rejected(&order, e.to_string()));
+        }
+
+        // The user stream may have accepted the order already
+        let accepted = match self.open_orders.get_mut(&order.id) {
+            Some(mut open_order) if open_order.state == OrderState::Created => {
+                open_order.state = OrderState::Accepted;
+                true
+            }
+            _ => false,
+        };
+        if accepted {
+            let event = OrderUpdateEvent::OrderAccepted {
+                account: order.account.clone(),
+                symbol_name: order.symbol_name.clone(),
+                symbol_code: order.symbol_name.clone(),
+                order_id: order.id.clone(),
+                tag: order.tag.clone(),
+                time: Utc::now().to_string(),
+            };
+            self.send_order_update(&order.id, event).await;
+        }
+        Ok(())
+    }
+
+    /// The quote coin amount of a spot market buy of the quantity, priced at the best ask.
+    async fn spot_quote_size(&self, symbol: &str, quantity: Decimal) -> Result<Decimal, FundForgeError> {
+        let tickers = self.send_signed_request(Method::GET, "/api/v2/spot/market/tickers", &format!("symbol={}", symbol), None).await?;
+        let ask = tickers[0]["askPr"].as_str()
+            .and_then(|price| Decimal::from_str(price).ok())
+            .ok_or_else(|| FundForgeError::ServerErrorDebug(format!("No ask price for: {}", symbol)))?;
+        Ok((quantity * ask).round_dp(2))
+    }
+
+    /// The side and quantity of the open futures position, spot accounts have no positions.
+    fn open_position(&self, account: &Account, symbol_name: &SymbolName) -> Option<(PositionSide, Decimal)> {
+        if InstType::from_account_id(&account.account_id) != Some(InstType::Futures) {
+            return None;
+        }
+        self.positions.get(symbol_name).map(|position| (position.side(), position.total))
+    }
+
+    /// Entering against an open futures position reverses it in a single order, since Bitget accounts are in one-way position mode.
+    async fn enter(&self, stream_name: StreamName, mut order: Order, opposite: PositionSide) -> Result<(), OrderUpdateEvent> {
+        if InstType::from_account_id(&order.account.account_id) == Some(InstType::Spot) && order.side == OrderSide::Sell {
+            return Err(order_rejected(&order, "Bitget spot accounts can not be shorted".to_string()));
+        }
+        if let Some((side, quantity)) = self.open_position(&order.account, &order.symbol_name) {
+            if side == opposite {
+                order.quantity_open += quantity;
+            }
+        }
+        self.place_order(stream_name, order, false).await
+    }
+
+    async fn exit(&self, stream_name: StreamName, order: Order) -> Result<(), OrderUpdateEvent> {
+        match InstType::from_account_id(&order.account.account_id) {
+            Some(InstType::Spot) if order.order_type == OrderType::ExitShort => Err(order_rejected(&order, "Bitget spot accounts can not be shorted".to_string())),
+            Some(InstType::Spot) => self.place_order(stream_name, order, false).await,
+            _ => self.place_order(stream_name, order, true).await,
+        }
+    }
+}
 
 #[async_trait]
 impl BrokerApiResponse for BitgetClient {
     #[allow(unused)]
     async fn symbol_names_response(&self, mode: StrategyMode, time: Option<DateTime<Utc>>, stream_name: StreamName, callback_id: u64) -> DataServerResponse {
-        todo!()
+        let mut symbol_names: Vec<SymbolName> = Vec::new();
+        let requests = [
+            ("/api/v2/spot/public/symbols", String::new()),
+            ("/api/v2/mix/market/contracts", format!("productType={}", InstType::Futures)),
+        ];
+        for (path, query) in requests {
+            match self.send_signed_request(Method::GET, path, &query, None).await {
+                Ok(symbols) => {
+                    for symbol in symbols.as_array().into_iter().flatten() {
+                        if let Some(symbol) = symbol["symbol"].as_str() {
+                            let symbol_name = fund_forge_symbol(symbol);
+                            if !symbol_names.contains(&symbol_name) {
+                                symbol_names.push(symbol_name);
+                            }
+                        }
+                    }
+                }
+                Err(e) => return DataServerResponse::Error { callback_id, error: e },
+            }
+        }
+        DataServerResponse::SymbolNames {
+            callback_id,
+            symbol_names,
+        }
     }
 
     #[allow(unused)]
     async fn account_info_response(&self, mode: StrategyMode, stream_name: StreamName, account_id: AccountId, callback_id: u64) -> DataServerResponse {
-        todo!()
+        match self.account_info.get(&account_id) {
+            None => {
+                DataServerResponse::Error {callback_id, error: FundForgeError::ClientSideErrorDebug(
+                    format!("No account found for id: {}", account_id)
+                )}
+            }
+            Some(account_info) => {
+                DataServerResponse::AccountInfo {callback_id, account_info: account_info.clone()}
+            }
+        }
     }
 
+    /// Futures contracts take precedence over the spot symbol with the same name, the value per tick is for one unit of the base coin.
+    /// The lot size is the quantity precision of the contract or spot symbol and the minimum quantity is its minimum trade size.
     #[allow(unused)]
     async fn symbol_info_response(&self, mode: StrategyMode, stream_name: StreamName, symbol_name: SymbolName, callback_id: u64) -> DataServerResponse {
-        todo!()
+        let symbol = bitget_symbol(&symbol_name);
+        let futures_query = format!("productType={}&symbol={}", InstType::Futures, symbol);
+        let precision = match self.send_signed_request(Method::GET, "/api/v2/mix/market/contracts", &futures_query, None).await {
+            Ok(contracts) => decimal_places(&contracts[0]["pricePlace"])
+                .map(|places| (places, decimal_places(&contracts[0]["volumePlace"]), decimal(&contracts[0]["minTradeNum"]))),
+            Err(_) => None,
+        };
+        let precision = match precision {
+            Some(precision) => Some(precision),
+            None => match self.send_signed_request(Method::GET, "/api/v2/spot/public/symbols", &format!("symbol={}", symbol), None).await {
+                Ok(symbols) => decimal_places(&symbols[0]["pricePrecision"])
+                    .map(|places| (places, decimal_places(&symbols[0]["quantityPrecision"]), decimal(&symbols[0]["minTradeAmount"]))),
+                Err(_) => None,
+            }
+        };
+        match precision {
+            Some((places, quantity_places, min_quantity)) => {
+                let tick_size = Decimal::new(1, places);
+                let mut symbol_info = SymbolInfo::new(symbol_name, None, Currency::USD, tick_size, tick_size, places);
+                if let Some(quantity_places) = quantity_places {
+                    symbol_info = symbol_info.with_lot_size(Decimal::new(1, quantity_places), min_quantity.filter(|min| *min > dec!(0)));
+                }
+                DataServerResponse::SymbolInfo {
+                    callback_id,
+                    symbol_info,
+                }
+            }
+            None => DataServerResponse::Error {
+                callback_id,
+                error: FundForgeError::ClientSideErrorDebug(format!("Symbol not found: {}", symbol_name)),
+            }
+        }
     }
 
     #[allow(unused)]
     async fn accounts_response(&self, mode: StrategyMode, stream_name: StreamName, callback_id: u64) -> DataServerResponse {
-        todo!()
+        let accounts: Vec<AccountId> = self.accounts.iter().map(|a| a.account_id.clone()).collect();
+        DataServerResponse::Accounts {
+            callback_id,
+            accounts,
+        }
     }
 
+    /// Orders placed by the strategy stay open on Bitget, their updates are no longer forwarded.
     #[allow(unused)]
     async fn logout_command(&self, stream_name: StreamName) {
-        todo!()
+        self.id_stream_name_map.retain(|_, order_stream| *order_stream != stream_name);
     }
 
     #[allow(unused)]
     async fn commission_info_response(&self, mode: StrategyMode, stream_name: StreamName, symbol_name: SymbolName, callback_id: u64) -> DataServerResponse {
-        todo!()
+        DataServerResponse::Error {
+            callback_id,
+            error: FundForgeError::ClientSideErrorDebug("Commission info is not available for Bitget".to_string()),
+        }
     }
+
     #[allow(unused)]
     async fn live_market_order(&self, stream_name: StreamName, mode: StrategyMode, order: Order) -> Result<(), OrderUpdateEvent> {
-        todo!()
+        self.place_order(stream_name, order, false).await
     }
+
     #[allow(unused)]
     async fn live_enter_long(&self, stream_name: StreamName, mode: StrategyMode, order: Order) -> Result<(), OrderUpdateEvent> {
-        todo!()
+        self.enter(stream_name, order, PositionSide::Short).await
     }
+
     #[allow(unused)]
     async fn live_enter_short(&self, stream_name: StreamName, mode: StrategyMode, order: Order) -> Result<(), OrderUpdateEvent> {
-        todo!()
+        self.enter(stream_name, order, PositionSide::Long).await
     }
+
     #[allow(unused)]
     async fn live_exit_short(&self, stream_name: StreamName, mode: StrategyMode, order: Order) -> Result<(), OrderUpdateEvent> {
-        todo!()
+        self.exit(stream_name, order).await
     }
+
     #[allow(unused)]
     async fn live_exit_long(&self, stream_name: StreamName, mode: StrategyMode, order: Order) -> Result<(), OrderUpdateEvent> {
-        todo!()
+        self.exit(stream_name, order).await
     }
+
     #[allow(unused)]
     async fn other_orders(&self, stream_name: StreamName, mode: StrategyMode, order: Order) -> Result<(), OrderUpdateEvent> {
-        todo!()
+        self.place_order(stream_name, order, false).await
     }
+
     #[allow(unused)]
     async fn cancel_orders_on_account(&self, account: Account) {
-        todo!()
+        let order_ids: Vec<OrderId> = self.open_orders.iter()
+            .filter(|order| order.account == account)
+            .map(|order| order.id.clone())
+            .collect();
+        for order_id in order_ids {
+            self.cancel_order(account.clone(), order_id).await;
+        }
     }
+
+    /// The cancellation is confirmed on the user stream.
     #[allow(unused)]
     async fn cancel_order(&self, account: Account, order_id: OrderId) {
-        todo!()
+        let symbol = match self.open_orders.get(&order_id) {
+            Some(order) => bitget_symbol(&order.symbol_name),
+            None => return,
+        };
+        let (path, body) = match InstType::from_account_id(&account.account_id) {
+            Some(InstType::Spot) => ("/api/v2/spot/trade/cancel-order", serde_json::json!({
+                "symbol": symbol,
+                "clientOid": order_id,
+            })),
+            Some(InstType::Futures) => ("/api/v2/mix/order/cancel-order", serde_json::json!({
+                "symbol": symbol,
+                "productType": InstType::Futures.to_string(),
+                "marginCoin": MARGIN_COIN,
+                "clientOid": order_id,
+            })),
+            None => return,
+        };
+        if let Err(e) = self.send_signed_request(Method::POST, path, "", Some(body)).await {
+            tracing::error!("Failed to cancel Bitget order {}: {}", order_id, e);
+        }
     }
+
+    /// Futures positions are closed at market, spot accounts sell every coin balance for USDT.
     #[allow(unused)]
     async fn flatten_all_for(&self, account: Account) {
-        todo!()
+        self.cancel_orders_on_account(account.clone()).await;
+        match InstType::from_account_id(&account.account_id) {
+            Some(InstType::Futures) => {
+                let body = serde_json::json!({"productType": InstType::Futures.to_string()});
+                if let Err(e) = self.send_signed_request(Method::POST, "/api/v2/mix/order/close-positions", "", Some(body)).await {
+                    tracing::error!("Failed to flatten Bitget futures positions: {}", e);
+                }
+            }
+            Some(InstType::Spot) => {
+                let balances: Vec<(String, Decimal)> = self.spot_balances.iter()
+                    .filter(|balance| balance.key() != MARGIN_COIN && *balance.value() > dec!(0))
+                    .map(|balance| (balance.key().clone(), *balance.value()))
+                    .collect();
+                for (coin, quantity) in balances {
+                    let symbol_name = format!("{}-{}", coin, MARGIN_COIN);
+                    let exit_order = Order {
+                        id: Uuid::new_v4().to_string(),
+                        time_created_utc: Utc::now().to_string(),
+                        time_filled_utc: None,
+                        state: OrderState::Created,
+                        fees: Default::default(),
+                        value: Default::default(),
+                        account: account.clone(),
+                        symbol_name: symbol_name.clone(),
+                        side: OrderSide::Sell,
+                        order_type: OrderType::ExitLong,
+                        quantity_open: quantity,
+                        quantity_filled: dec!(0),
+                        average_fill_price: None,
+                        limit_price: None,
+                        trigger_price: None,
+                        time_in_force: TimeInForce::FOK,
+                        tag: "Flatten Long".to_string(),
+                        symbol_code: symbol_name,
+                        exchange: None,
+                        position_id: None,
+                        trigger_source: None,
+                    };
+                    if let Err(e) = self.place_order(0, exit_order, false).await {
+                        tracing::error!("Failed to flatten Bitget {} balance: {:?}", coin, e);
+                    }
+                }
+            }
+            None => {}
+        }
     }
 
     #[allow(unused)]
     async fn update_order(&self, account: Account, order_id: OrderId, update: OrderUpdateType) -> Result<(), OrderUpdateEvent> {
-        todo!()
+        Err(OrderUpdateEvent::OrderUpdateRejected {
+            account,
+            order_id,
+            reason: "Order updates not supported with Bitget, please cancel order and replace".to_string(),
+            time: Utc::now().to_string(),
+        })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast;
+use tokio::time::interval;
+use ff_standard_lib::messages::data_server_messaging::FundForgeError;
+use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use ff_standard_lib::standardized_types::base_data::fundamental::Fundamental;
+use ff_standard_lib::standardized_types::subscriptions::{Symbol, SymbolName};
+use crate::bitget_api::api_client::{BitgetClient, InstType, BITGET_CLIENT};
+use crate::bitget_api::models::{bitget_symbol, FundingRate, OpenInterest};
+
+/// How often the funding rate and open interest are polled, Bitget updates the open interest about once a minute.
+const FUNDAMENTALS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Polls the funding rate and open interest of a USDT-M perpetual and broadcasts them as `Fundamental` data.
+/// The task ends once the last stream has unsubscribed, the next subscription starts a new one.
+pub(crate) fn poll_fundamentals(symbol: Symbol, sender: broadcast::Sender<BaseDataEnum>) {
+    tokio::spawn(async move {
+        let mut interval = interval(FUNDAMENTALS_INTERVAL);
+        loop {
+            interval.tick().await;
+            let client = match BITGET_CLIENT.get() {
+                Some(client) => client,
+                None => break,
+            };
+            if sender.receiver_count() == 0 {
+                client.fundamental_broadcasters.remove(&symbol.name);
+                break;
+            }
+            match fetch_fundamentals(client, &symbol).await {
+                Ok(fundamentals) => {
+                    for fundamental in fundamentals {
+                        let _ = sender.send(BaseDataEnum::Fundamental(fundamental));
+                    }
+                }
+                Err(e) => tracing::error!("Error polling Bitget fundamentals for {}: {}", symbol.name, e),
+            }
+        }
+    });
+}
+
+async fn fetch_fundamentals(client: &BitgetClient, symbol: &Symbol) -> Result<Vec<Fundamental>, FundForgeError> {
+    let query = query(&symbol.name);
+    let funding = client.send_public_request("/api/v2/mix/market/current-fund-rate", &query).await?;
+    let funding: Vec<FundingRate> = serde_json::from_value(funding)
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to parse Bitget funding rate: {}", e)))?;
+    let open_interest = client.send_public_request("/api/v2/mix/market/open-interest", &query).await?;
+    let open_interest: OpenInterest = serde_json::from_value(open_interest)
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to parse Bitget open interest: {}", e)))?;
+    Ok(to_fundamentals(symbol, Utc::now(), &funding, &open_interest))
+}
+
+fn query(symbol_name: &SymbolName) -> String {
+    format!("symbol={}&productType={}", bitget_symbol(symbol_name), InstType::Futures)
+}
+
+fn millis_to_time(millis: &str) -> Option<DateTime<Utc>> {
+    millis.parse::<i64>().ok().and_then(DateTime::from_timestamp_millis)
+}
+
+/// Converts the REST responses, the funding rate is stamped with the poll time and the open interest with the exchange time.
+fn to_fundamentals(symbol: &Symbol, time: DateTime<Utc>, funding: &[FundingRate], open_interest: &OpenInterest) -> Vec<Fundamental> {
+    let mut fundamentals = Vec::new();
+    if let Some(funding) = funding.first() {
+        let next_funding_time = funding.next_update.as_deref().and_then(millis_to_time);
+        fundamentals.push(Fundamental::funding_rate(symbol.clone(), time, funding.funding_rate, next_funding_time));
+    }
+    if let Some(size) = open_interest.open_interest_list.first() {
+        let time = millis_to_time(&open_interest.ts).unwrap_or(time);
+        fundamentals.push(Fundamental::open_interest(symbol.clone(), time, size.size));
+    }
+    fundamentals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+    use ff_standard_lib::standardized_types::enums::MarketType;
+
+    #[test]
+    fn test_to_fundamentals() {
+        let funding: Vec<FundingRate> = serde_json::from_str(r#"[{"symbol":"BTCUSDT","fundingRate":"-0.000125","fundingRateInterval":"8","nextUpdate":"1700006400000"}]"#).unwrap();
+        let open_interest: OpenInterest = serde_json::from_str(r#"{"openInterestList":[{"symbol":"BTCUSDT","size":"34278.06"}],"ts":"1700000030000"}"#).unwrap();
+        let symbol = Symbol::new("BTC-USDT".to_string(), DataVendor::Bitget, MarketType::Fundamentals);
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        let fundamentals = to_fundamentals(&symbol, now, &funding, &open_interest);
+        assert_eq!(fundamentals.len(), 2);
+        assert_eq!(fundamentals[0].funding_rate_value(), Some(dec!(-0.000125)));
+        assert_eq!(fundamentals[0].next_funding_time(), DateTime::from_timestamp(1_700_006_400, 0));
+        assert_eq!(fundamentals[0].time_utc(), now);
+        assert_eq!(fundamentals[1].open_interest_value(), Some(dec!(34278.06)));
+        assert_eq!(fundamentals[1].time_utc(), DateTime::from_timestamp(1_700_000_030, 0).unwrap());
+        assert_eq!(query(&symbol.name), "symbol=BTCUSDT&productType=USDT-FUTURES");
+    }
+}
@@ -81,7 +81,7 @@ pub async fn login(credentials: &BitGetCredentials, stream: &mut WebSocketStream
                 match response.event.as_str() {
                     "login" => {
                         if response.code == "0" {
-                            println!("Login successful");
+                            tracing::info!("Login successful");
                             Ok(())
                         } else {
                             Err(FundForgeError::ServerErrorDebug(format!("Login failed with code: {}, msg: {}", response.code, response.msg)))
@@ -100,13 +100,17 @@ pub async fn login(credentials: &BitGetCredentials, stream: &mut WebSocketStream
 }
 
 fn generate_signature(secret_key: &str, timestamp: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let message = format!("{}GET/user/verify", timestamp);
+    sign(secret_key, &format!("{}GET/user/verify", timestamp))
+}
 
+/// Signs the pre-hash string, the signature is the base64 encoded HMAC-SHA256 of the pre-hash using the secret key.
+/// REST requests sign `timestamp + method + request_path + body`, the request path includes the query string.
+pub(crate) fn sign(secret_key: &str, pre_hash: &str) -> Result<String, Box<dyn std::error::Error>> {
     // Create a new HMAC-SHA256 instance using the secret key
     let mut mac = HmacSha256::new_from_slice(secret_key.as_bytes())?;
 
     // Update the MAC instance with the message
-    mac.update(message.as_bytes());
+    mac.update(pre_hash.as_bytes());
 
     // Finalize the MAC computation and get_requests the result
     let result = mac.finalize().into_bytes();
@@ -136,7 +140,7 @@ pub fn get_bitget_credentials() -> Option<BitGetCredentials> {
 
 #[derive(Deserialize, Serialize)]
 pub struct BitGetCredentials {
-    api_key: String,
-    secret_key: String,
-    passphrase: String,
+    pub(crate) api_key: String,
+    pub(crate) secret_key: String,
+    pub(crate) passphrase: String,
 }
\ No newline at end of file
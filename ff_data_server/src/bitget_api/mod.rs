@@ -1,4 +1,7 @@
 pub mod api_client;
 mod login;
 pub mod vendor_api_response;
-pub mod broker_api_response;
\ No newline at end of file
+pub mod broker_api_response;
+pub mod models;
+pub mod user_stream;
+pub mod fundamentals;
\ No newline at end of file
@@ -0,0 +1,186 @@
+use std::str::FromStr;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer};
+use ff_standard_lib::standardized_types::enums::{OrderSide, PositionSide};
+use ff_standard_lib::standardized_types::subscriptions::SymbolName;
+
+/// The quote coins Bitget symbols are split on, `BTCUSDT` is `BTC-USDT` in fund forge.
+const QUOTE_COINS: [&str; 5] = ["USDT", "USDC", "BTC", "ETH", "EUR"];
+
+/// Bitget sends numbers as strings and empty strings for values that are not set yet, such as the fill price of a live order.
+fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    match value.is_empty() {
+        true => Ok(Decimal::ZERO),
+        false => Decimal::from_str(&value).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Converts the fund forge symbol name to the Bitget instrument id, `BTC-USDT` becomes `BTCUSDT`.
+pub fn bitget_symbol(symbol_name: &SymbolName) -> String {
+    symbol_name.replace('-', "")
+}
+
+/// Converts the Bitget instrument id to the fund forge symbol name, `BTCUSDT` becomes `BTC-USDT`.
+pub fn fund_forge_symbol(inst_id: &str) -> SymbolName {
+    for quote in QUOTE_COINS {
+        if let Some(base) = inst_id.strip_suffix(quote) {
+            if !base.is_empty() {
+                return format!("{}-{}", base, quote);
+            }
+        }
+    }
+    inst_id.to_string()
+}
+
+/// The envelope of every Bitget REST response, `code` is "00000" on success.
+#[derive(Debug, Deserialize)]
+pub struct BitgetResponse<T> {
+    pub code: String,
+    pub msg: String,
+    pub data: Option<T>,
+}
+
+/// A coin balance of the spot account.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotAsset {
+    pub coin: String,
+    #[serde(default, deserialize_with = "decimal")]
+    pub available: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub frozen: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub locked: Decimal,
+}
+
+/// The margin account of the USDT-M futures, from the REST snapshot.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesAccount {
+    pub margin_coin: String,
+    #[serde(default, deserialize_with = "decimal")]
+    pub locked: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub available: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub account_equity: Decimal,
+    #[serde(default, deserialize_with = "decimal", rename = "unrealizedPL")]
+    pub unrealized_pl: Decimal,
+}
+
+/// The margin account of the USDT-M futures, from the user stream `account` channel.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesAccountPush {
+    pub margin_coin: String,
+    #[serde(default, deserialize_with = "decimal")]
+    pub frozen: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub available: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub equity: Decimal,
+    #[serde(default, deserialize_with = "decimal", rename = "unrealizedPL")]
+    pub unrealized_pl: Decimal,
+}
+
+/// A USDT-M futures position, the REST snapshot and the user stream `positions` channel share the format.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesPosition {
+    #[serde(alias = "instId")]
+    pub symbol: String,
+    pub hold_side: String,
+    #[serde(default, deserialize_with = "decimal")]
+    pub total: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub open_price_avg: Decimal,
+    #[serde(default, deserialize_with = "decimal", rename = "unrealizedPL")]
+    pub unrealized_pl: Decimal,
+}
+
+impl FuturesPosition {
+    pub fn side(&self) -> PositionSide {
+        match self.hold_side.as_str() {
+            "long" => PositionSide::Long,
+            "short" => PositionSide::Short,
+            _ => PositionSide::Flat,
+        }
+    }
+}
+
+/// An order update from the user stream `orders` channel, spot and futures share the fields we use.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderPush {
+    pub inst_id: String,
+    #[serde(default)]
+    pub order_id: String,
+    #[serde(default)]
+    pub client_oid: String,
+    pub side: String,
+    pub status: String,
+    /// The quantity filled by this update.
+    #[serde(default, deserialize_with = "decimal")]
+    pub base_volume: Decimal,
+    /// The total quantity filled.
+    #[serde(default, deserialize_with = "decimal")]
+    pub acc_base_volume: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub fill_price: Decimal,
+    #[serde(default, deserialize_with = "decimal")]
+    pub price_avg: Decimal,
+}
+
+impl OrderPush {
+    pub fn side(&self) -> OrderSide {
+        match self.side.as_str() {
+            "sell" => OrderSide::Sell,
+            _ => OrderSide::Buy,
+        }
+    }
+}
+
+/// The channel a user stream message was pushed on.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushArg {
+    pub inst_type: String,
+    pub channel: String,
+}
+
+/// A message pushed on the user stream, the data is parsed once the channel is known.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushMessage {
+    pub arg: PushArg,
+    #[serde(default)]
+    pub data: Vec<serde_json::Value>,
+}
+
+/// The current funding rate of a USDT-M perpetual, from `/api/v2/mix/market/current-fund-rate`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    pub symbol: String,
+    #[serde(default, deserialize_with = "decimal")]
+    pub funding_rate: Decimal,
+    /// The next funding time in unix milliseconds.
+    #[serde(default)]
+    pub next_update: Option<String>,
+}
+
+/// The open interest of a USDT-M perpetual in base units.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenInterestSize {
+    pub symbol: String,
+    #[serde(default, deserialize_with = "decimal")]
+    pub size: Decimal,
+}
+
+/// The response of `/api/v2/mix/market/open-interest`, `ts` is in unix milliseconds.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterest {
+    pub open_interest_list: Vec<OpenInterestSize>,
+    pub ts: String,
+}
@@ -0,0 +1,352 @@
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
+use rust_decimal_macros::dec;
+use tokio::time::{interval, sleep};
+use tokio_tungstenite::connect_async;
+use tungstenite::Message;
+use ff_standard_lib::messages::data_server_messaging::{DataServerResponse, FundForgeError};
+use ff_standard_lib::standardized_types::accounts::Account;
+use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+use ff_standard_lib::standardized_types::enums::PositionSide;
+use ff_standard_lib::standardized_types::orders::{Order, OrderState, OrderUpdateEvent};
+use ff_standard_lib::standardized_types::new_types::{Price, Volume};
+use ff_standard_lib::standardized_types::subscriptions::SymbolName;
+use crate::bitget_api::api_client::{BitgetClient, InstType, HEARTBEAT_INTERVAL, MARGIN_COIN};
+use crate::bitget_api::login;
+use crate::bitget_api::models::{fund_forge_symbol, FuturesAccountPush, FuturesPosition, OrderPush, PushMessage, SpotAsset};
+use crate::request_handlers::RESPONSE_SENDERS;
+use crate::server_features::webhooks::notify_webhooks;
+use crate::server_features::trade_copier::copy_trades;
+
+const PRIVATE_WEBSOCKET_URL: &str = "wss://ws.bitget.com/v2/ws/private";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Keeps the user data stream connected, reconnecting after a delay when it drops.
+/// Updates pushed while disconnected are lost, so the account snapshots are refreshed after each reconnect.
+pub(crate) fn handle_user_stream(client: Arc<BitgetClient>) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = run_user_stream(&client).await {
+                tracing::warn!("Bitget user stream disconnected: {}", e);
+            }
+            sleep(RECONNECT_DELAY).await;
+            if let Err(e) = client.refresh_accounts().await {
+                tracing::error!("Error refreshing Bitget accounts: {}", e);
+            }
+        }
+    });
+}
+
+async fn run_user_stream(client: &BitgetClient) -> Result<(), FundForgeError> {
+    let (mut stream, _) = connect_async(PRIVATE_WEBSOCKET_URL).await
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to connect to Bitget user stream: {}", e)))?;
+    login::login(&client.credentials, &mut stream).await?;
+
+    let spot = InstType::Spot.to_string();
+    let futures = InstType::Futures.to_string();
+    let subscribe = serde_json::json!({
+        "op": "subscribe",
+        "args": [
+            {"instType": spot, "channel": "orders", "instId": "default"},
+            {"instType": spot, "channel": "account", "coin": "default"},
+            {"instType": futures, "channel": "orders", "instId": "default"},
+            {"instType": futures, "channel": "account", "coin": "default"},
+            {"instType": futures, "channel": "positions", "instId": "default"},
+        ]
+    });
+    stream.send(Message::Text(subscribe.to_string())).await
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to subscribe to Bitget user stream: {}", e)))?;
+
+    let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                stream.send(Message::Text("ping".to_string())).await
+                    .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to send Bitget heartbeat: {}", e)))?;
+            }
+            message = stream.next() => {
+                match message {
+                    Some(Ok(Message::Text(text))) => {
+                        if text != "pong" {
+                            client.handle_push(&text).await;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Err(FundForgeError::ServerErrorDebug("Connection closed".to_string())),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(FundForgeError::ServerErrorDebug(e.to_string())),
+                }
+            }
+        }
+    }
+}
+
+/// Updates the order with the pushed status and returns the event for the strategy, `None` if the push does not change the order.
+/// Fill quantities are taken from the accumulated fill volume so a missed or repeated push does not double count fills.
+pub(crate) fn order_update_event(order: &mut Order, push: &OrderPush) -> Option<OrderUpdateEvent> {
+    let time = Utc::now().to_string();
+    match push.status.as_str() {
+        "live" | "new" | "init" => {
+            if order.state != OrderState::Created {
+                return None;
+            }
+            order.state = OrderState::Accepted;
+            Some(OrderUpdateEvent::OrderAccepted {
+                account: order.account.clone(),
+                symbol_name: order.symbol_name.clone(),
+                symbol_code: order.symbol_name.clone(),
+                order_id: order.id.clone(),
+                tag: order.tag.clone(),
+                time,
+            })
+        }
+        "partially_filled" | "filled" => {
+            let quantity = push.acc_base_volume - order.quantity_filled;
+            if quantity <= dec!(0) {
+                return None;
+            }
+            let price = match push.fill_price > dec!(0) {
+                true => push.fill_price,
+                false => push.price_avg,
+            };
+            order.quantity_filled += quantity;
+            order.quantity_open = (order.quantity_open - quantity).max(dec!(0));
+            order.average_fill_price = Some(push.price_avg);
+            match push.status.as_str() {
+                "filled" => {
+                    order.state = OrderState::Filled;
+                    order.time_filled_utc = Some(time.clone());
+                    Some(OrderUpdateEvent::OrderFilled {
+                        account: order.account.clone(),
+                        symbol_name: order.symbol_name.clone(),
+                        symbol_code: order.symbol_name.clone(),
+                        order_id: order.id.clone(),
+                        side: order.side,
+                        price,
+                        quantity,
+                        tag: order.tag.clone(),
+                        time,
+                    })
+                }
+                _ => {
+                    order.state = OrderState::PartiallyFilled;
+                    Some(OrderUpdateEvent::OrderPartiallyFilled {
+                        account: order.account.clone(),
+                        symbol_name: order.symbol_name.clone(),
+                        symbol_code: order.symbol_name.clone(),
+                        order_id: order.id.clone(),
+                        side: order.side,
+                        price,
+                        quantity,
+                        tag: order.tag.clone(),
+                        time,
+                    })
+                }
+            }
+        }
+        "canceled" | "cancelled" => {
+            order.state = OrderState::Cancelled;
+            Some(OrderUpdateEvent::OrderCancelled {
+                account: order.account.clone(),
+                symbol_name: order.symbol_name.clone(),
+                symbol_code: order.symbol_name.clone(),
+                order_id: order.id.clone(),
+                reason: "Cancelled by Bitget".to_string(),
+                tag: order.tag.clone(),
+                time,
+            })
+        }
+        _ => None
+    }
+}
+
+impl BitgetClient {
+    async fn handle_push(&self, text: &str) {
+        let push: PushMessage = match serde_json::from_str(text) {
+            Ok(push) => push,
+            Err(_) => {
+                if text.contains("\"error\"") {
+                    tracing::error!("Bitget user stream error: {}", text);
+                }
+                return;
+            }
+        };
+        let inst_type = match push.arg.inst_type.as_str() {
+            "SPOT" => InstType::Spot,
+            _ => InstType::Futures,
+        };
+        match push.arg.channel.as_str() {
+            "orders" => {
+                for data in push.data {
+                    match serde_json::from_value::<OrderPush>(data) {
+                        Ok(order_push) => self.handle_order_push(order_push).await,
+                        Err(e) => tracing::error!("Failed to parse Bitget order update: {}", e),
+                    }
+                }
+            }
+            "account" => self.handle_account_push(inst_type, push.data).await,
+            "positions" => {
+                let positions: Vec<FuturesPosition> = push.data.into_iter()
+                    .filter_map(|data| serde_json::from_value(data).ok())
+                    .collect();
+                self.handle_positions_push(positions).await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Orders placed outside of fund forge are ignored.
+    async fn handle_order_push(&self, push: OrderPush) {
+        let event = match self.open_orders.get_mut(&push.client_oid) {
+            Some(mut order) => match order_update_event(&mut order, &push) {
+                Some(event) => event,
+                None => return,
+            },
+            None => return,
+        };
+        let order_id = push.client_oid.clone();
+        let is_closed = matches!(event, OrderUpdateEvent::OrderFilled { .. } | OrderUpdateEvent::OrderCancelled { .. });
+        self.send_order_update(&order_id, event).await;
+        if is_closed {
+            self.open_orders.remove(&order_id);
+            self.id_stream_name_map.remove(&order_id);
+        }
+    }
+
+    /// Sends the order update to the strategy that placed the order.
+    pub(crate) async fn send_order_update(&self, order_id: &str, event: OrderUpdateEvent) {
+        let order_event = DataServerResponse::OrderUpdates { event, time: Utc::now().to_string() };
+        notify_webhooks(&order_event);
+        copy_trades(&order_event);
+        if let Some(stream_name) = self.id_stream_name_map.get(order_id) {
+            if let Some(sender) = RESPONSE_SENDERS.get(stream_name.value()) {
+                if let Err(e) = sender.send(order_event).await {
+                    tracing::error!("failed to forward Bitget order update to strategy stream {}", e);
+                }
+            }
+        }
+    }
+
+    async fn handle_account_push(&self, inst_type: InstType, data: Vec<serde_json::Value>) {
+        match inst_type {
+            InstType::Spot => {
+                let assets: Vec<SpotAsset> = data.into_iter().filter_map(|data| serde_json::from_value(data).ok()).collect();
+                self.update_spot_balances(&assets);
+            }
+            InstType::Futures => {
+                for data in data {
+                    if let Ok(account) = serde_json::from_value::<FuturesAccountPush>(data) {
+                        if account.margin_coin == MARGIN_COIN {
+                            self.update_account_info(InstType::Futures, account.equity, account.available, account.frozen, account.unrealized_pl);
+                        }
+                    }
+                }
+            }
+        }
+        let account_info = match self.account_info.get(&inst_type.to_string()) {
+            Some(account_info) => account_info.clone(),
+            None => return,
+        };
+        let message = DataServerResponse::LiveAccountUpdates {
+            account: Account::new(Brokerage::Bitget, account_info.account_id),
+            cash_value: account_info.cash_value,
+            cash_available: account_info.cash_available,
+            cash_used: account_info.cash_used,
+        };
+        broadcast(message).await;
+    }
+
+    /// The positions channel pushes every open position, positions missing from the push have been closed.
+    async fn handle_positions_push(&self, positions: Vec<FuturesPosition>) {
+        let account = Account::new(Brokerage::Bitget, InstType::Futures.to_string());
+        let open: Vec<SymbolName> = positions.iter().map(|position| fund_forge_symbol(&position.symbol)).collect();
+        let closed: Vec<SymbolName> = self.positions.iter()
+            .map(|position| position.key().clone())
+            .filter(|symbol_name| !open.contains(symbol_name))
+            .collect();
+        for symbol_name in closed {
+            self.positions.remove(&symbol_name);
+            broadcast(position_update(&account, symbol_name, PositionSide::Flat, dec!(0), dec!(0), dec!(0))).await;
+        }
+        for (symbol_name, position) in open.into_iter().zip(positions) {
+            let message = position_update(
+                &account,
+                symbol_name.clone(),
+                position.side(),
+                position.total,
+                position.open_price_avg,
+                position.unrealized_pl,
+            );
+            self.positions.insert(symbol_name, position);
+            broadcast(message).await;
+        }
+    }
+}
+
+fn position_update(account: &Account, symbol_name: SymbolName, side: PositionSide, open_quantity: Volume, average_price: Price, open_pnl: Price) -> DataServerResponse {
+    DataServerResponse::LivePositionUpdates {
+        symbol_code: symbol_name.clone(),
+        symbol_name,
+        account: account.clone(),
+        open_quantity,
+        average_price,
+        side,
+        open_pnl,
+        time: Utc::now().to_string(),
+    }
+}
+
+/// Account and position updates are sent to every connected strategy.
+async fn broadcast(message: DataServerResponse) {
+    notify_webhooks(&message);
+    for stream_name in RESPONSE_SENDERS.iter() {
+        if let Err(e) = stream_name.value().send(message.clone()).await {
+            tracing::error!("failed to forward Bitget account update to strategy stream {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use ff_standard_lib::standardized_types::enums::OrderSide;
+    use ff_standard_lib::standardized_types::orders::TimeInForce;
+
+    #[test]
+    fn test_order_push_events() {
+        let account = Account::new(Brokerage::Bitget, "USDT-FUTURES".to_string());
+        let mut order = Order::limit_order("BTC-USDT".to_string(), None, &account, dec!(0.03), OrderSide::Buy, "test".to_string(), "ff-1".to_string(), Utc::now(), dec!(60000), TimeInForce::GTC, None);
+        let push = |status: &str, acc_base_volume: &str, fill_price: &str| -> OrderPush {
+            serde_json::from_value(serde_json::json!({
+                "instId": "BTCUSDT", "orderId": "1", "clientOid": "ff-1", "side": "buy", "status": status,
+                "baseVolume": "", "accBaseVolume": acc_base_volume, "fillPrice": fill_price, "priceAvg": "60000"
+            })).unwrap()
+        };
+        assert_eq!(fund_forge_symbol(&push("live", "", "").inst_id), "BTC-USDT");
+
+        assert!(matches!(order_update_event(&mut order, &push("live", "0", "")), Some(OrderUpdateEvent::OrderAccepted { .. })));
+        assert_eq!(order_update_event(&mut order, &push("live", "0", "")), None);
+
+        match order_update_event(&mut order, &push("partially_filled", "0.01", "60000")) {
+            Some(OrderUpdateEvent::OrderPartiallyFilled { quantity, price, .. }) => {
+                assert_eq!(quantity, dec!(0.01));
+                assert_eq!(price, dec!(60000));
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        // a repeated push does not double count the fill
+        assert_eq!(order_update_event(&mut order, &push("partially_filled", "0.01", "60000")), None);
+
+        match order_update_event(&mut order, &push("filled", "0.03", "59999.5")) {
+            Some(OrderUpdateEvent::OrderFilled { quantity, price, .. }) => {
+                assert_eq!(quantity, dec!(0.02));
+                assert_eq!(price, dec!(59999.5));
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(order.state, OrderState::Filled);
+        assert_eq!(order.quantity_open, dec!(0));
+    }
+}
@@ -8,7 +8,10 @@ use ff_standard_lib::standardized_types::enums::{MarketType, StrategyMode};
 use ff_standard_lib::standardized_types::resolution::Resolution;
 use ff_standard_lib::standardized_types::subscriptions::{DataSubscription, Symbol, SymbolName};
 use ff_standard_lib::StreamName;
+use tokio::sync::broadcast;
 use crate::bitget_api::api_client::BitgetClient;
+use crate::bitget_api::fundamentals::poll_fundamentals;
+use crate::stream_tasks::{subscribe_stream, unsubscribe_stream};
 
 #[async_trait]
 impl VendorApiResponse for BitgetClient {
@@ -32,16 +35,49 @@ impl VendorApiResponse for BitgetClient {
     async fn tick_size_response(&self, mode: StrategyMode, stream_name: StreamName, symbol_name: SymbolName, callback_id: u64) -> DataServerResponse {
         todo!()
     }
-    #[allow(unused)]
+    /// Live Bitget data is limited to the funding rate and open interest of the USDT-M perpetuals, subscribe with `DataSubscription::new_fundamental()`.
     async fn data_feed_subscribe(&self, stream_name: StreamName, subscription: DataSubscription) -> DataServerResponse {
+        if subscription.base_data_type != BaseDataType::Fundamentals {
+            return DataServerResponse::SubscribeResponse {
+                success: false,
+                subscription,
+                reason: Some("Live Bitget only supports Fundamentals: funding rate and open interest".to_string()),
+            };
+        }
+        if let Some(broadcaster) = self.fundamental_broadcasters.get(&subscription.symbol.name) {
+            let receiver = broadcaster.value().subscribe();
+            subscribe_stream(&stream_name, subscription.clone(), receiver).await;
+        } else {
+            let (sender, receiver) = broadcast::channel(20);
+            self.fundamental_broadcasters.insert(subscription.symbol.name.clone(), sender.clone());
+            subscribe_stream(&stream_name, subscription.clone(), receiver).await;
+            poll_fundamentals(subscription.symbol.clone(), sender);
+        }
+        DataServerResponse::SubscribeResponse {
+            success: true,
+            subscription,
+            reason: None,
+        }
+    }
+
+    async fn data_feed_unsubscribe(&self, stream_name: StreamName, subscription: DataSubscription) -> DataServerResponse {
+        unsubscribe_stream(&stream_name, &subscription).await;
+        DataServerResponse::UnSubscribeResponse {
+            success: true,
+            subscription,
+            reason: None,
+        }
+    }
+    #[allow(unused)]
+    async fn base_data_types_response(&self, mode: StrategyMode, stream_name: StreamName, callback_id: u64) -> DataServerResponse {
         todo!()
     }
     #[allow(unused)]
-    async fn data_feed_unsubscribe(&self, stream_name: StreamName, subscription: DataSubscription) -> DataServerResponse {
+    async fn vendor_capabilities_response(&self, mode: StrategyMode, stream_name: StreamName, callback_id: u64) -> DataServerResponse {
         todo!()
     }
     #[allow(unused)]
-    async fn base_data_types_response(&self, mode: StrategyMode, stream_name: StreamName, callback_id: u64) -> DataServerResponse {
+    async fn symbol_search_response(&self, mode: StrategyMode, stream_name: StreamName, query: String, callback_id: u64) -> DataServerResponse {
         todo!()
     }
     #[allow(unused)]
@@ -0,0 +1,50 @@
+use ahash::AHashSet;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use ff_standard_lib::messages::chart_updates::{ChartName, ChartUpdate};
+use ff_standard_lib::messages::data_server_messaging::DataServerResponse;
+use ff_standard_lib::StreamName;
+use crate::request_handlers::RESPONSE_SENDERS;
+
+lazy_static! {
+    /// The connections subscribed to each chart name.
+    static ref CHART_SUBSCRIBERS: DashMap<ChartName, AHashSet<StreamName>> = DashMap::new();
+}
+
+pub fn subscribe_chart(stream_name: StreamName, chart_name: ChartName) {
+    tracing::info!("Stream {}: Subscribed to chart {}", stream_name, chart_name);
+    CHART_SUBSCRIBERS.entry(chart_name).or_default().insert(stream_name);
+}
+
+pub fn unsubscribe_chart(stream_name: &StreamName, chart_name: &ChartName) {
+    CHART_SUBSCRIBERS.remove_if_mut(chart_name, |_, subscribers| {
+        subscribers.remove(stream_name);
+        subscribers.is_empty()
+    });
+}
+
+/// Removes the connection from all charts, called when the connection is closed.
+pub fn deregister_chart_subscriber(stream_name: &StreamName) {
+    CHART_SUBSCRIBERS.retain(|_, subscribers| {
+        subscribers.remove(stream_name);
+        !subscribers.is_empty()
+    });
+}
+
+/// Forwards the updates to every connection subscribed to the chart, updates for charts without subscribers are discarded.
+pub async fn republish_chart_updates(chart_name: ChartName, updates: Vec<ChartUpdate>) {
+    let subscribers: Vec<StreamName> = match CHART_SUBSCRIBERS.get(&chart_name) {
+        Some(subscribers) => subscribers.iter().cloned().collect(),
+        None => return
+    };
+    for stream_name in subscribers {
+        let sender = match RESPONSE_SENDERS.get(&stream_name) {
+            Some(sender) => sender.value().clone(),
+            None => continue
+        };
+        let response = DataServerResponse::ChartUpdates { chart_name: chart_name.clone(), updates: updates.clone() };
+        if sender.send(response).await.is_err() {
+            tracing::error!("Failed to send chart updates for {} to stream: {}", chart_name, stream_name);
+        }
+    }
+}
@@ -15,7 +15,7 @@ pub async fn data_bento_init(options: ServerLaunchOptions) -> Result<(), FundFor
     DATA_BENTO_CLIENT.set(Arc::new(client)).map_err(|_| {
         FundForgeError::ServerErrorDebug("Failed to set Data Bento client".to_string())
     })?;
-    println!("Data Bento client initialized");
+    tracing::info!("Data Bento client initialized");
     Ok(())
 }
 
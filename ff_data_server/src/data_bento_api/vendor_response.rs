@@ -6,6 +6,8 @@ use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType
 use ff_standard_lib::standardized_types::enums::{FuturesExchange, MarketType, PrimarySubscription, StrategyMode};
 use ff_standard_lib::standardized_types::resolution::Resolution;
 use ff_standard_lib::standardized_types::subscriptions::{DataSubscription, Symbol, SymbolName};
+use ff_standard_lib::standardized_types::symbol_info::SymbolSearchResult;
+use ff_standard_lib::standardized_types::vendor_capabilities::VendorCapabilities;
 use ff_standard_lib::StreamName;
 use chrono::{DateTime, Utc};
 use databento::dbn::{Schema, TradeMsg};
@@ -16,7 +18,7 @@ use time::macros::{datetime};
 use ff_standard_lib::product_maps::rithmic::maps::{get_futures_exchange, get_futures_symbol_info};
 use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
 use crate::data_bento_api::api_client::DataBentoClient;
-use crate::rithmic_api::products::get_futures_symbols;
+use crate::rithmic_api::products::{get_futures_description, get_futures_symbols};
 
 #[async_trait]
 impl VendorApiResponse for DataBentoClient {
@@ -81,7 +83,7 @@ impl VendorApiResponse for DataBentoClient {
             ).await.map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to resolve symbols: {}", e)));
 
 
-            println!("Symbols: {:?}", symbols);
+            tracing::debug!("Symbols: {:?}", symbols);
         }*/
         DataServerResponse::Error{callback_id, error: FundForgeError::ServerErrorDebug("No time provided".to_string())}
     }
@@ -153,6 +155,45 @@ impl VendorApiResponse for DataBentoClient {
         }
     }
 
+    async fn vendor_capabilities_response(&self, _mode: StrategyMode, _stream_name: StreamName, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::VendorCapabilities {
+            callback_id,
+            capabilities: VendorCapabilities {
+                data_vendor: DataVendor::DataBento,
+                base_data_types: vec![BaseDataType::Ticks, BaseDataType::Quotes, BaseDataType::Candles],
+                market_depth: true,
+                historical_from: None,
+                max_requests_per_second: None,
+                order_types: vec![],
+            },
+        }
+    }
+
+    /// Searches the supported futures products, contract codes are not resolved.
+    async fn symbol_search_response(&self, _mode: StrategyMode, _stream_name: StreamName, query: String, callback_id: u64) -> DataServerResponse {
+        let mut results = vec![];
+        for symbol_name in get_futures_symbols() {
+            let exchange = match get_futures_exchange(&symbol_name) {
+                Ok(exchange) => exchange,
+                Err(_) => continue
+            };
+            let result = SymbolSearchResult {
+                description: get_futures_description(&symbol_name).unwrap_or_default().to_string(),
+                symbol: Symbol::new(symbol_name, DataVendor::DataBento, MarketType::Futures(exchange)),
+                symbol_code: None,
+                exchange: Some(exchange.to_string()),
+                expiration: None,
+            };
+            if result.matches(&query) {
+                results.push(result);
+            }
+        }
+        DataServerResponse::SymbolSearch {
+            callback_id,
+            results,
+        }
+    }
+
     #[allow(unused)]
     async fn logout_command_vendors(&self, _stream_name: StreamName) {
         todo!()
@@ -198,8 +239,8 @@ impl VendorApiResponse for DataBentoClient {
             .get_range(&ranges).await.unwrap();
 
         match decoder.decode_record::<TradeMsg>().await {
-            Ok(trade) => println!("{trade:#?}"),
-            Err(e) => eprintln!("Failed to decode: {}", e)
+            Ok(trade) => tracing::info!("{trade:#?}"),
+            Err(e) => tracing::error!("Failed to decode: {}", e)
         };
 
         Ok(())
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use once_cell::sync::OnceCell;
+use reqwest::Client;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+use ff_standard_lib::messages::data_server_messaging::FundForgeError;
+use ff_standard_lib::server_launch_options::ServerLaunchOptions;
+use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use ff_standard_lib::standardized_types::base_data::fundamental::Fundamental;
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+use ff_standard_lib::standardized_types::economic_calendar::EconomicEvent;
+use ff_standard_lib::standardized_types::enums::MarketType;
+use ff_standard_lib::standardized_types::subscriptions::{Symbol, SymbolName};
+use crate::forex_factory_api::models::{schedules_by_currency, CalendarEvent};
+use crate::update_functions::DATA_STORAGE;
+
+pub static FOREX_FACTORY_CLIENT: OnceCell<Arc<ForexFactoryClient>> = OnceCell::new();
+
+const CALENDAR_URL: &str = "https://nfs.faireconomy.media/ff_calendar_thisweek.json";
+/// The export is cached by ForexFactory and requests are rate limited, the schedule rarely changes within the hour.
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The currencies of the ForexFactory calendar.
+pub(crate) const CURRENCIES: [&str; 9] = ["AUD", "CAD", "CHF", "CNY", "EUR", "GBP", "JPY", "NZD", "USD"];
+
+/// Publishes the ForexFactory economic calendar as `Fundamental::economic_calendar()` data, one calendar per currency.
+pub struct ForexFactoryClient {
+    http_client: Client,
+    /// The latest schedule of each currency.
+    pub schedules: DashMap<SymbolName, Vec<EconomicEvent>>,
+    pub broadcasters: DashMap<SymbolName, broadcast::Sender<BaseDataEnum>>,
+}
+
+pub(crate) async fn forex_factory_init(options: ServerLaunchOptions) {
+    if options.disable_forex_factory_server != 0 {
+        return;
+    }
+    let client = Arc::new(ForexFactoryClient::new());
+    if let Err(e) = client.refresh().await {
+        tracing::error!("Error getting the ForexFactory calendar: {}", e);
+    }
+    poll_calendar(client.clone());
+    tracing::info!("ForexFactory client initialized");
+    let _ = FOREX_FACTORY_CLIENT.set(client);
+}
+
+impl ForexFactoryClient {
+    fn new() -> Self {
+        Self {
+            http_client: Client::new(),
+            schedules: Default::default(),
+            broadcasters: Default::default(),
+        }
+    }
+
+    /// The economic calendar of the currency at the time, `None` if the schedule has not been downloaded.
+    pub(crate) fn calendar(&self, currency: &SymbolName, time: DateTime<Utc>) -> Option<BaseDataEnum> {
+        let schedule = self.schedules.get(currency)?;
+        Some(BaseDataEnum::Fundamental(Fundamental::economic_calendar(calendar_symbol(currency), time, schedule.value())))
+    }
+
+    async fn fetch_calendar(&self) -> Result<Vec<EconomicEvent>, FundForgeError> {
+        let response = self.http_client.get(CALENDAR_URL).send().await
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to request the ForexFactory calendar: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(FundForgeError::ServerErrorDebug(format!("ForexFactory calendar request failed: {}", response.status())));
+        }
+        let events: Vec<CalendarEvent> = response.json().await
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to parse the ForexFactory calendar: {}", e)))?;
+        Ok(events.iter().filter_map(|event| event.to_economic_event()).collect())
+    }
+
+    /// Downloads the calendar of the week, broadcasts the schedules to the subscribers and saves them stamped at the start of the day,
+    /// so backtests with at least a day of warm up know the releases of the day before they happen.
+    pub(crate) async fn refresh(&self) -> Result<(), FundForgeError> {
+        let events = self.fetch_calendar().await?;
+        let now = Utc::now();
+        let start_of_day = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        for (currency, schedule) in schedules_by_currency(events) {
+            self.schedules.insert(currency, schedule);
+        }
+
+        let mut save_data = vec![];
+        for schedule in self.schedules.iter() {
+            if let Some(sender) = self.broadcasters.get(schedule.key()) {
+                let _ = sender.send(BaseDataEnum::Fundamental(Fundamental::economic_calendar(calendar_symbol(schedule.key()), now, schedule.value())));
+            }
+            save_data.push(BaseDataEnum::Fundamental(Fundamental::economic_calendar(calendar_symbol(schedule.key()), start_of_day, schedule.value())));
+        }
+        if let Some(data_storage) = DATA_STORAGE.get() {
+            data_storage.save_data_bulk(save_data).await
+                .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to save the ForexFactory calendar: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn calendar_symbol(currency: &SymbolName) -> Symbol {
+    Symbol::new(currency.clone(), DataVendor::ForexFactory, MarketType::Fundamentals)
+}
+
+fn poll_calendar(client: Arc<ForexFactoryClient>) {
+    tokio::spawn(async move {
+        let mut interval = interval(POLL_INTERVAL);
+        // The first tick completes immediately and the calendar was just downloaded.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = client.refresh().await {
+                tracing::error!("Error polling the ForexFactory calendar: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,3 @@
+pub mod api_client;
+pub mod vendor_api_response;
+pub mod models;
@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use ff_standard_lib::standardized_types::economic_calendar::{EconomicEvent, EventImpact};
+
+/// An event of the ForexFactory weekly calendar export.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CalendarEvent {
+    pub title: String,
+    /// The currency of the event, eg. "USD".
+    pub country: String,
+    /// The release time in RFC3339 with the offset of the export, eg. "2024-06-07T08:30:00-04:00".
+    pub date: String,
+    pub impact: String,
+    #[serde(default)]
+    pub forecast: Option<String>,
+    #[serde(default)]
+    pub previous: Option<String>,
+}
+
+impl CalendarEvent {
+    /// Returns None if the date can not be parsed, blank forecasts and previous values are None.
+    pub fn to_economic_event(&self) -> Option<EconomicEvent> {
+        let time = DateTime::parse_from_rfc3339(&self.date).ok()?.with_timezone(&Utc);
+        let non_empty = |value: &Option<String>| value.clone().filter(|value| !value.trim().is_empty());
+        Some(EconomicEvent {
+            currency: self.country.to_uppercase(),
+            title: self.title.clone(),
+            time,
+            impact: EventImpact::from_str(&self.impact),
+            forecast: non_empty(&self.forecast),
+            previous: non_empty(&self.previous),
+        })
+    }
+}
+
+/// Groups the events by currency, each schedule is in time order.
+pub fn schedules_by_currency(events: Vec<EconomicEvent>) -> BTreeMap<String, Vec<EconomicEvent>> {
+    let mut schedules: BTreeMap<String, Vec<EconomicEvent>> = BTreeMap::new();
+    for event in events {
+        schedules.entry(event.currency.clone()).or_default().push(event);
+    }
+    for schedule in schedules.values_mut() {
+        schedule.sort_by_key(|event| event.time);
+    }
+    schedules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_calendar_events() {
+        let json = r#"[
+            {"title":"Non-Farm Employment Change","country":"USD","date":"2024-06-07T08:30:00-04:00","impact":"High","forecast":"185K","previous":"175K"},
+            {"title":"German Factory Orders m/m","country":"EUR","date":"2024-06-06T02:00:00-04:00","impact":"Low","forecast":"0.5%","previous":"-0.4%"},
+            {"title":"Bank Holiday","country":"USD","date":"2024-06-03T00:00:00-04:00","impact":"Holiday","forecast":"","previous":""}
+        ]"#;
+        let events: Vec<CalendarEvent> = serde_json::from_str(json).unwrap();
+        let events: Vec<EconomicEvent> = events.iter().filter_map(|event| event.to_economic_event()).collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].time, Utc.with_ymd_and_hms(2024, 6, 7, 12, 30, 0).unwrap());
+        assert_eq!(events[0].impact, EventImpact::High);
+        assert_eq!(events[2].forecast, None);
+
+        let schedules = schedules_by_currency(events);
+        assert_eq!(schedules.len(), 2);
+        let usd = &schedules["USD"];
+        assert_eq!(usd.len(), 2);
+        assert_eq!(usd[0].title, "Bank Holiday");
+        assert_eq!(schedules["EUR"][0].impact, EventImpact::Low);
+    }
+}
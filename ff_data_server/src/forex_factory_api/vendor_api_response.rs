@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use indicatif::ProgressBar;
+use tokio::sync::broadcast;
+use ff_standard_lib::messages::data_server_messaging::{DataServerResponse, FundForgeError};
+use crate::server_features::server_side_datavendor::VendorApiResponse;
+use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType;
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+use ff_standard_lib::standardized_types::enums::{MarketType, StrategyMode, PrimarySubscription};
+use ff_standard_lib::standardized_types::resolution::Resolution;
+use ff_standard_lib::standardized_types::subscriptions::{DataSubscription, Symbol, SymbolName};
+use ff_standard_lib::standardized_types::symbol_info::SymbolSearchResult;
+use ff_standard_lib::standardized_types::vendor_capabilities::VendorCapabilities;
+use ff_standard_lib::StreamName;
+use crate::forex_factory_api::api_client::{calendar_symbol, ForexFactoryClient, CURRENCIES};
+use crate::stream_tasks::{subscribe_stream, unsubscribe_stream};
+
+#[async_trait]
+impl VendorApiResponse for ForexFactoryClient {
+    async fn symbols_response(&self, _mode: StrategyMode, _stream_name: StreamName, market_type: MarketType, _time: Option<DateTime<Utc>>, callback_id: u64) -> DataServerResponse {
+        let symbols = CURRENCIES.iter()
+            .map(|currency| calendar_symbol(&currency.to_string()))
+            .collect();
+        DataServerResponse::Symbols {
+            callback_id,
+            symbols,
+            market_type,
+        }
+    }
+
+    async fn resolutions_response(&self, _mode: StrategyMode, _stream_name: StreamName, market_type: MarketType, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::Resolutions {
+            callback_id,
+            market_type,
+            subscription_resolutions_types: vec![PrimarySubscription::new(Resolution::Instant, BaseDataType::Fundamentals)],
+        }
+    }
+
+    async fn markets_response(&self, _mode: StrategyMode, _stream_name: StreamName, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::Markets {
+            callback_id,
+            markets: vec![MarketType::Fundamentals],
+        }
+    }
+
+    async fn decimal_accuracy_response(&self, _mode: StrategyMode, _stream_name: StreamName, symbol_name: SymbolName, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::Error {
+            callback_id,
+            error: FundForgeError::ClientSideErrorDebug(format!("ForexFactory calendars have no prices: {}", symbol_name)),
+        }
+    }
+
+    async fn tick_size_response(&self, _mode: StrategyMode, _stream_name: StreamName, symbol_name: SymbolName, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::Error {
+            callback_id,
+            error: FundForgeError::ClientSideErrorDebug(format!("ForexFactory calendars have no prices: {}", symbol_name)),
+        }
+    }
+
+    /// The symbol name is the currency of the calendar, subscribe with `DataSubscription::new_fundamental()`.
+    /// The latest schedule is sent as soon as the stream is subscribed, then each time the calendar is polled.
+    async fn data_feed_subscribe(&self, stream_name: StreamName, subscription: DataSubscription) -> DataServerResponse {
+        if subscription.base_data_type != BaseDataType::Fundamentals {
+            return DataServerResponse::SubscribeResponse {
+                success: false,
+                subscription,
+                reason: Some("ForexFactory only supports Fundamentals: the economic calendar".to_string()),
+            };
+        }
+        if !CURRENCIES.contains(&subscription.symbol.name.as_str()) {
+            return DataServerResponse::SubscribeResponse {
+                success: false,
+                reason: Some(format!("ForexFactory has no calendar for: {}", subscription.symbol.name)),
+                subscription,
+            };
+        }
+        let sender = match self.broadcasters.get(&subscription.symbol.name) {
+            Some(broadcaster) => broadcaster.value().clone(),
+            None => {
+                let (sender, _) = broadcast::channel(20);
+                self.broadcasters.insert(subscription.symbol.name.clone(), sender.clone());
+                sender
+            }
+        };
+        subscribe_stream(&stream_name, subscription.clone(), sender.subscribe()).await;
+        if let Some(calendar) = self.calendar(&subscription.symbol.name, Utc::now()) {
+            let _ = sender.send(calendar);
+        }
+        DataServerResponse::SubscribeResponse {
+            success: true,
+            subscription,
+            reason: None,
+        }
+    }
+
+    async fn data_feed_unsubscribe(&self, stream_name: StreamName, subscription: DataSubscription) -> DataServerResponse {
+        unsubscribe_stream(&stream_name, &subscription).await;
+        DataServerResponse::UnSubscribeResponse {
+            success: true,
+            subscription,
+            reason: None,
+        }
+    }
+
+    async fn base_data_types_response(&self, _mode: StrategyMode, _stream_name: StreamName, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::BaseDataTypes {
+            callback_id,
+            base_data_types: vec![BaseDataType::Fundamentals],
+        }
+    }
+
+    async fn vendor_capabilities_response(&self, _mode: StrategyMode, _stream_name: StreamName, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::VendorCapabilities {
+            callback_id,
+            capabilities: VendorCapabilities {
+                data_vendor: DataVendor::ForexFactory,
+                base_data_types: vec![BaseDataType::Fundamentals],
+                market_depth: false,
+                historical_from: None,
+                max_requests_per_second: None,
+                order_types: vec![],
+            },
+        }
+    }
+
+    async fn symbol_search_response(&self, _mode: StrategyMode, _stream_name: StreamName, query: String, callback_id: u64) -> DataServerResponse {
+        let results = CURRENCIES.iter()
+            .map(|currency| SymbolSearchResult {
+                symbol: calendar_symbol(&currency.to_string()),
+                symbol_code: None,
+                description: format!("{} Economic Calendar", currency),
+                exchange: None,
+                expiration: None,
+            })
+            .filter(|result| result.matches(&query))
+            .collect();
+        DataServerResponse::SymbolSearch {
+            callback_id,
+            results,
+        }
+    }
+
+    async fn logout_command_vendors(&self, _stream_name: StreamName) {}
+
+    async fn session_market_hours_response(&self, _mode: StrategyMode, _stream_name: StreamName, symbol_name: SymbolName, _date_time: DateTime<Utc>, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::Error {
+            callback_id,
+            error: FundForgeError::ClientSideErrorDebug(format!("ForexFactory calendars have no sessions: {}", symbol_name)),
+        }
+    }
+
+    /// ForexFactory only exports the calendar of the current week, so the history is built up by the server saving each day's schedule.
+    async fn update_historical_data(&self, _symbol: Symbol, _base_data_type: BaseDataType, _resolution: Resolution, _from: DateTime<Utc>, _to: DateTime<Utc>, _from_back: bool, progress_bar: ProgressBar) -> Result<(), FundForgeError> {
+        let result = self.refresh().await;
+        progress_bar.finish_and_clear();
+        result
+    }
+}
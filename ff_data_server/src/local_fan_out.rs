@@ -0,0 +1,154 @@
+use std::net::{SocketAddr, SocketAddrV4};
+use std::time::Duration;
+use ahash::AHashSet;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use lazy_static::lazy_static;
+use tokio::sync::{broadcast, oneshot};
+use tokio::net::UdpSocket;
+use tokio::time::interval;
+use ff_standard_lib::messages::local_fan_out::{fan_out_sender_socket, to_frames, MAX_FRAME_ITEMS};
+use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use ff_standard_lib::standardized_types::subscriptions::DataSubscription;
+use ff_standard_lib::StreamName;
+use crate::stream_tasks::poll_interval;
+
+/// A subscription being published to a local multicast group, shared by all the local streamers subscribed to it.
+struct FanOutPublication {
+    subscribers: AHashSet<StreamName>,
+    shutdown: oneshot::Sender<()>,
+}
+
+lazy_static! {
+    /// The multicast group and buffer duration for each streamer that registered for local fan out.
+    static ref FAN_OUT_STREAMS: DashMap<StreamName, (SocketAddrV4, Duration)> = DashMap::new();
+    static ref PUBLICATIONS: DashMap<(SocketAddrV4, DataSubscription), FanOutPublication> = DashMap::new();
+}
+
+pub fn register_fan_out(stream_name: StreamName, group: SocketAddrV4, buffer: Duration) {
+    tracing::info!("Stream {}: Using local fan out group {}", stream_name, group);
+    FAN_OUT_STREAMS.insert(stream_name, (group, buffer));
+}
+
+pub fn is_fan_out(stream_name: &StreamName) -> bool {
+    FAN_OUT_STREAMS.contains_key(stream_name)
+}
+
+/// Adds the streamer as a subscriber to the publication, the first subscriber starts publishing the data to the group.
+pub fn fan_out_subscribe(stream_name: &StreamName, subscription: DataSubscription, receiver: broadcast::Receiver<BaseDataEnum>) {
+    let (group, buffer) = match FAN_OUT_STREAMS.get(stream_name) {
+        Some(entry) => *entry.value(),
+        None => return
+    };
+    match PUBLICATIONS.entry((group, subscription.clone())) {
+        Entry::Occupied(mut publication) => {
+            publication.get_mut().subscribers.insert(*stream_name);
+        }
+        Entry::Vacant(entry) => {
+            let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+            let mut subscribers = AHashSet::new();
+            subscribers.insert(*stream_name);
+            entry.insert(FanOutPublication { subscribers, shutdown: shutdown_sender });
+            tokio::spawn(publish(group, buffer, subscription, receiver, shutdown_receiver));
+        }
+    }
+}
+
+/// Removes the streamer from the publication, the last subscriber stops publishing the data.
+pub fn fan_out_unsubscribe(stream_name: &StreamName, subscription: &DataSubscription) {
+    let group = match FAN_OUT_STREAMS.get(stream_name) {
+        Some(entry) => entry.value().0,
+        None => return
+    };
+    let key = (group, subscription.clone());
+    let is_empty = match PUBLICATIONS.get_mut(&key) {
+        Some(mut publication) => {
+            publication.subscribers.remove(stream_name);
+            publication.subscribers.is_empty()
+        }
+        None => false
+    };
+    if is_empty {
+        if let Some((_, publication)) = PUBLICATIONS.remove(&key) {
+            let _ = publication.shutdown.send(());
+        }
+    }
+}
+
+/// Removes the streamer from all publications.
+pub fn deregister_fan_out(stream_name: &StreamName) {
+    let group = match FAN_OUT_STREAMS.remove(stream_name) {
+        Some((_, (group, _))) => group,
+        None => return
+    };
+    let mut empty = vec![];
+    for mut publication in PUBLICATIONS.iter_mut() {
+        if publication.key().0 != group {
+            continue;
+        }
+        publication.subscribers.remove(stream_name);
+        if publication.subscribers.is_empty() {
+            empty.push(publication.key().clone());
+        }
+    }
+    for key in empty {
+        if let Some((_, publication)) = PUBLICATIONS.remove(&key) {
+            let _ = publication.shutdown.send(());
+        }
+    }
+}
+
+async fn publish(
+    group: SocketAddrV4,
+    buffer: Duration,
+    subscription: DataSubscription,
+    mut receiver: broadcast::Receiver<BaseDataEnum>,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let socket = match fan_out_sender_socket() {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!("Local fan out: Failed to create socket for {}: {}", group, e);
+            return;
+        }
+    };
+    let target = SocketAddr::V4(group);
+    let mut pending: Vec<BaseDataEnum> = Vec::with_capacity(MAX_FRAME_ITEMS);
+    let mut interval = interval(poll_interval(buffer));
+    let mut sequence: u64 = 0;
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if !pending.is_empty() {
+                    send_frames(&socket, target, &subscription, std::mem::take(&mut pending), &mut sequence).await;
+                }
+            }
+            result = receiver.recv() => {
+                match result {
+                    Ok(data) => {
+                        pending.push(data);
+                        if buffer.is_zero() {
+                            send_frames(&socket, target, &subscription, std::mem::take(&mut pending), &mut sequence).await;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            _ = &mut shutdown => {
+                break;
+            }
+        }
+    }
+    // If the vendor closed the broadcaster, remove the publication so a new subscription can start publishing again.
+    drop(shutdown);
+    PUBLICATIONS.remove_if(&(group, subscription), |_, publication| publication.shutdown.is_closed());
+}
+
+async fn send_frames(socket: &UdpSocket, target: SocketAddr, subscription: &DataSubscription, data: Vec<BaseDataEnum>, sequence: &mut u64) {
+    for frame in to_frames(subscription, data, sequence) {
+        if let Err(e) = socket.send_to(&frame, target).await {
+            tracing::error!("Local fan out: Failed to send to {}: {}", target, e);
+        }
+    }
+}
@@ -3,7 +3,7 @@ use rustls_pemfile::{certs, private_key};
 use std::fs::File;
 use std::io;
 use std::io::BufReader;
-use std::net::{SocketAddr};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -16,10 +16,23 @@ use tokio::sync::{broadcast, OnceCell};
 use tokio_rustls::server::TlsStream;
 use ff_standard_lib::database::hybrid_storage::{HybridStorage};
 use ff_standard_lib::server_launch_options::ServerLaunchOptions;
+use ff_standard_lib::helpers::logging::init_logging;
 use crate::data_bento_api::api_client::{data_bento_init};
 use crate::oanda_api::api_client::{oanda_init};
+use crate::bitget_api::api_client::bitget_init;
+use crate::forex_factory_api::api_client::forex_factory_init;
 use crate::rithmic_api::api_client::{RithmicBrokerageClient, RITHMIC_CLIENTS};
 use crate::update_functions::run_update_schedule;
+use crate::server_features::position_limits::init_position_limits;
+use crate::server_features::webhooks::init_webhooks;
+use crate::server_features::trade_copier::init_trade_copier;
+use crate::server_features::api_keys::init_api_keys;
+use ff_standard_lib::product_maps::symbol_aliases::init_symbol_aliases;
+use crate::server_features::market_replay::init_market_replay;
+use crate::server_features::live_recording::{enable_recording, flush_recordings};
+use crate::server_features::websocket_bridge::websocket_server;
+use crate::server_features::admin::admin_server;
+use crate::server_features::tls_config::{init_tls_config, watch_certificates};
 
 pub mod request_handlers;
 mod stream_listener;
@@ -31,19 +44,22 @@ pub mod server_side_brokerage;
 pub mod server_side_datavendor;
 pub mod bitget_api;
 pub mod stream_tasks;
+pub mod local_fan_out;
+pub mod chart_republish;
 pub mod oanda_api;
+pub mod forex_factory_api;
 pub mod server_features;
 pub mod update_functions;
 use crate::update_functions::DATA_STORAGE;
 
 async fn logout_apis() {
-    println!("Logging Out Apis Function Started");
+    tracing::info!("Logging Out Apis Function Started");
     if !RITHMIC_CLIENTS.is_empty() {
         for api_client in RITHMIC_CLIENTS.iter() {
             api_client.shutdown().await;
         }
     }
-    println!("Logging Out Apis Function Ended");
+    tracing::info!("Logging Out Apis Function Ended");
 }
 
 static SHUTDOWN_CHANNEL: Lazy<broadcast::Sender<()>> = Lazy::new(|| {
@@ -69,30 +85,43 @@ pub fn get_data_folder() -> &'static PathBuf {
 #[tokio::main]
 async fn main() -> io::Result<()> {
     let options = ServerLaunchOptions::from_args();
+    if let Err(e) = init_logging(options.log_folder.to_str(), options.log_level) {
+        eprintln!("{}", e);
+    }
     let _ = DATA_FOLDER.set(options.data_folder.clone());
-    println!("Data Folder: {:?}", get_data_folder());
+    tracing::info!("Data Folder: {:?}", get_data_folder());
+    init_position_limits(get_data_folder());
+    init_webhooks(get_data_folder());
+    init_trade_copier(get_data_folder());
+    init_api_keys(get_data_folder());
+    init_symbol_aliases(get_data_folder());
+    init_market_replay(&options);
     let _ = DATA_STORAGE.set(Arc::new(HybridStorage::new(Duration::from_secs(450), options.clone(), options.max_downloads, options.update_seconds)));
 
     // Start the background task for cache management
     HybridStorage::start_cache_management(DATA_STORAGE.get().unwrap().clone());
 
-    let cert = Path::join(&options.ssl_auth_folder, "cert.pem");
-    let key = Path::join(&options.ssl_auth_folder, "key.pem");
+    if options.migrate_data == 1 {
+        match DATA_STORAGE.get().unwrap().migrate_format().await {
+            Ok(migrated) => tracing::info!("Migrated {} data files to the current format", migrated),
+            Err(e) => tracing::error!("Failed to migrate data files: {}", e),
+        }
+    }
 
-    let certs = load_certs(&cert)?;
-    let key = load_keys(&key)
-        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No keys found"))?;
+    if options.record_live == 1 {
+        enable_recording();
+    }
 
-    let config = rustls::ServerConfig::builder()
-        .with_no_client_auth()
-        .with_single_cert(certs, key)
-        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    init_tls_config(&options)?;
+    watch_certificates(options.clone());
 
     RithmicBrokerageClient::init_rithmic_apis(options.clone()).await;
     oanda_init(options.clone()).await;
+    bitget_init(options.clone()).await;
+    forex_factory_init(options.clone()).await;
     match data_bento_init(options.clone()).await {
         Ok(_) =>{
-            eprintln!("Data Bento Initialized");
+            tracing::info!("Data Bento Initialized");
         /*    let client = get_data_bento_client().unwrap();
             client.symbols_response(StrategyMode::Backtest, 1, MarketType::Futures(FuturesExchange::CME), Some(Utc::now()), 1).await;*/
         }
@@ -100,7 +129,7 @@ async fn main() -> io::Result<()> {
     }
 
 
-    run_servers(config, options.clone());
+    run_servers(options.clone());
 
     sleep(Duration::from_secs(5)).await;
 
@@ -108,17 +137,19 @@ async fn main() -> io::Result<()> {
 
     // Wait for Ctrl+C
     signal::ctrl_c().await.expect("Failed to listen for ctrl-c");
-    println!("Ctrl+C received, logging out APIs...");
+    tracing::info!("Ctrl+C received, logging out APIs...");
     match get_shutdown_sender().send(()) {
-        Ok(_) => eprintln!("Shutdown Signal Sent"),
-        Err(e) =>  eprintln!("Shutdown Signal Failed: {}", e),
+        Ok(_) => tracing::info!("Shutdown Signal Sent"),
+        Err(e) =>  tracing::error!("Shutdown Signal Failed: {}", e),
     }
 
+    flush_recordings().await;
+
     // Perform logout
     logout_apis().await;
 
 
-    println!("Shutdown complete");
+    tracing::info!("Shutdown complete");
     Ok(())
 }
 
@@ -128,23 +159,32 @@ async fn get_ip_addresses(stream: &TlsStream<TcpStream>) -> SocketAddr {
     tcp_stream.0.peer_addr().unwrap()
 }
 
-fn run_servers(
-    config: rustls::ServerConfig,
-    options: ServerLaunchOptions,
-) {
-    let config_clone = config.clone();
+fn run_servers(options: ServerLaunchOptions) {
     let options_clone = options.clone();
+    let heartbeat_timeout = match options.heartbeat_timeout {
+        0 => None,
+        seconds => Some(Duration::from_secs(seconds)),
+    };
 
     let _ = task::spawn(async move  {
         async_listener::async_server(
-            config_clone,
             SocketAddr::new(options_clone.listener_address, options_clone.port),
+            heartbeat_timeout,
         ).await
     });
 
+    if options.websocket_port != 0 {
+        let address = SocketAddr::new(options.listener_address, options.websocket_port);
+        let _ = task::spawn(websocket_server(address));
+    }
+
+    if options.admin_port != 0 {
+        let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), options.admin_port);
+        let _ = task::spawn(admin_server(address, options.clone()));
+    }
+
     let _ = task::spawn(async move  {
          stream_listener::stream_server(
-            config,
             SocketAddr::new(options.stream_address, options.stream_port),
         ).await
     });
@@ -109,7 +109,7 @@ pub(crate) async fn oanda_init(options: ServerLaunchOptions) {
         Some(s) => s,
         None => {
             OANDA_IS_CONNECTED.store(false, Ordering::SeqCst);
-            eprintln!("No oanda settings retrieved");
+            tracing::warn!("No oanda settings retrieved");
             return;
         }
     };
@@ -130,12 +130,12 @@ pub(crate) async fn oanda_init(options: ServerLaunchOptions) {
         .build()
     {
         Ok(client) => {
-            eprintln!("Oanda streaming client connected");
+            tracing::info!("Oanda streaming client connected");
             OANDA_IS_CONNECTED.store(true, Ordering::SeqCst);
             client
         }
         Err(_) => {
-            eprintln!("Oanda streaming client failed to connect");
+            tracing::error!("Oanda streaming client failed to connect");
             OANDA_IS_CONNECTED.store(false, Ordering::SeqCst);
             return;
         }
@@ -154,11 +154,11 @@ pub(crate) async fn oanda_init(options: ServerLaunchOptions) {
         .build()
     {
         Ok(client) => {
-            eprintln!("Oanda client connected");
+            tracing::info!("Oanda client connected");
             client
         }
         Err(_) => {
-            eprintln!("Oanda client failed to connect");
+            tracing::error!("Oanda client failed to connect");
             OANDA_IS_CONNECTED.store(false, Ordering::SeqCst);
             return;
         }
@@ -196,7 +196,7 @@ pub(crate) async fn oanda_init(options: ServerLaunchOptions) {
     };
     match get_oanda_accounts_list(&oanda_client).await {
         Ok(accounts) => oanda_client.accounts = accounts.clone(),
-        Err(e) => eprintln!("Error getting accounts: {:?}", e)
+        Err(e) => tracing::error!("Error getting accounts: {:?}", e)
     };
     if let Some(account) = oanda_client.accounts.get(0) {
         let instruments = get_oanda_instruments(&oanda_client, &account.account_id).await.unwrap_or_else(|| vec![]);
@@ -230,7 +230,7 @@ pub(crate) async fn oanda_init(options: ServerLaunchOptions) {
                 };
                 oanda_client.account_info.insert(account.account_id.clone(), info);
             }
-            Err(e) => eprintln!("Error getting oanda account info: {}", e)
+            Err(e) => tracing::error!("Error getting oanda account info: {}", e)
         }
         match get_oanda_account_details(&oanda_client, &account.account_id).await {
             Ok(details) => {
@@ -245,7 +245,7 @@ pub(crate) async fn oanda_init(options: ServerLaunchOptions) {
                 }
 
             }
-            Err(e) => eprintln!("Error getting oanda account positions: {}", e)
+            Err(e) => tracing::error!("Error getting oanda account positions: {}", e)
         }
     }
     let stream_limit = Arc::new(Semaphore::new(20));
@@ -254,7 +254,7 @@ pub(crate) async fn oanda_init(options: ServerLaunchOptions) {
     }
     let client =Arc::new(oanda_client);
     handle_quotebar_subscribers(client.clone(), client.accounts.get(0).unwrap().account_id.clone());
-    eprintln!("Oanda client initialized");
+    tracing::info!("Oanda client initialized");
     let _ = OANDA_CLIENT.set(client);
 }
 
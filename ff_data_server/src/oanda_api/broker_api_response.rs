@@ -10,7 +10,7 @@ use ff_standard_lib::product_maps::oanda::maps::{OANDA_SYMBOL_INFO};
 use crate::server_features::server_side_brokerage::BrokerApiResponse;
 use ff_standard_lib::standardized_types::accounts::{Account, AccountId};
 use ff_standard_lib::standardized_types::enums::{OrderSide, PositionSide, StrategyMode};
-use ff_standard_lib::standardized_types::orders::{Order, OrderId, OrderState, OrderType, OrderUpdateEvent, OrderUpdateType, TimeInForce};
+use ff_standard_lib::standardized_types::orders::{Order, OrderId, OrderState, OrderType, OrderUpdateEvent, OrderUpdateType, TimeInForce, TriggerSource};
 use ff_standard_lib::standardized_types::subscriptions::{SymbolName};
 use ff_standard_lib::StreamName;
 use crate::oanda_api::api_client::OandaClient;
@@ -18,6 +18,8 @@ use crate::oanda_api::models::order::order_related;
 use crate::oanda_api::models::order::order_related::{OrderPositionFill};
 use crate::oanda_api::models::transaction_related::ClientExtensions;
 use crate::request_handlers::RESPONSE_SENDERS;
+use crate::server_features::webhooks::notify_webhooks;
+use crate::server_features::trade_copier::copy_trades;
 
 #[async_trait]
 impl BrokerApiResponse for OandaClient {
@@ -110,6 +112,8 @@ impl BrokerApiResponse for OandaClient {
                         tag: "Exit Short, Before Enter Long".to_string(),
                         symbol_code: order.symbol_code.clone(),
                         exchange: order.exchange.clone(),
+                        position_id: None,
+                        trigger_source: None,
                     };
                     match self.other_orders(stream_name.clone(), mode, exit_long_order).await {
                         Ok(_) => {}
@@ -148,6 +152,8 @@ impl BrokerApiResponse for OandaClient {
                         tag: "Exit Long, Before Enter Short".to_string(),
                         symbol_code: order.symbol_code.clone(),
                         exchange: order.exchange.clone(),
+                        position_id: None,
+                        trigger_source: None,
                     };
                     match self.other_orders(stream_name.clone(), mode, exit_long_order).await {
                         Ok(_) => {}
@@ -201,8 +207,8 @@ impl BrokerApiResponse for OandaClient {
             TimeInForce::IOC => ("IOC".to_string(), None),
             TimeInForce::FOK => ("FOK".to_string(), None),
             TimeInForce::Day => ("GFD".to_string(), None),
-            TimeInForce::Time(time_stamp) => {
-                let time = match DateTime::<Utc>::from_timestamp(time_stamp, 0) {
+            TimeInForce::Time(_) | TimeInForce::TimeNanos(_) => {
+                let time = match order.time_in_force.expiry_time() {
                     Some(t) => t,
                     None => {
                         return Err(OrderUpdateEvent::OrderRejected {
@@ -242,6 +248,23 @@ impl BrokerApiResponse for OandaClient {
             },
         };
 
+        // Oanda compares the trigger price to the ask of buy orders and the bid of sell orders by default, there is no last trade price to trigger on.
+        let trigger_condition = match order.trigger_source() {
+            TriggerSource::BidAsk => "DEFAULT",
+            TriggerSource::Midpoint => "MID",
+            TriggerSource::Last => {
+                return Err(OrderUpdateEvent::OrderRejected {
+                    account: order.account,
+                    symbol_name: order.symbol_name.to_string(),
+                    symbol_code: order.symbol_name,
+                    order_id: order.id,
+                    reason: "Oanda orders can not trigger on the last trade price, use TriggerSource::BidAsk or TriggerSource::Midpoint".to_string(),
+                    tag: order.tag,
+                    time: Utc::now().to_string(),
+                });
+            }
+        };
+
         let client_extensions = Some(ClientExtensions {
             id: order.id.clone(),
             tag: order.tag.clone(),
@@ -297,7 +320,7 @@ impl BrokerApiResponse for OandaClient {
                         "gtd_time": gtd_time,
                         "positionFill": "REDUCE_FIRST".to_string(),
                         "clientExtensions": client_extensions,
-                        "trigger_condition": "DEFAULT".to_string(),
+                        "triggerCondition": trigger_condition,
                         "price": price,
 
                     }
@@ -328,7 +351,7 @@ impl BrokerApiResponse for OandaClient {
                         "gtd_time": gtd_time,
                         "positionFill": "REDUCE_FIRST".to_string(),
                         "clientExtensions": client_extensions,
-                        "trigger_condition": "DEFAULT".to_string(),
+                        "triggerCondition": trigger_condition,
                         "price": price,
                         "price_bound": order.limit_price,
 
@@ -359,7 +382,7 @@ impl BrokerApiResponse for OandaClient {
                         "gtd_time": gtd_time,
                         "positionFill": "REDUCE_FIRST".to_string(),
                         "clientExtensions": client_extensions,
-                        "trigger_condition": "DEFAULT".to_string(),
+                        "triggerCondition": trigger_condition,
                         "price": price,
                     }
                 })
@@ -388,7 +411,7 @@ impl BrokerApiResponse for OandaClient {
                         "gtd_time": gtd_time,
                         "positionFill": "REDUCE_FIRST".to_string(),
                         "clientExtensions": client_extensions,
-                        "trigger_condition": "DEFAULT".to_string(),
+                        "triggerCondition": trigger_condition,
                         "price": price,
                         "price_bound": order.limit_price,
                     }
@@ -403,7 +426,7 @@ impl BrokerApiResponse for OandaClient {
             .send()
             .await {
             Ok(response) => {
-                println!("Response: {:?}", response);
+                tracing::info!("Response: {:?}", response);
                 match response.status() {
                     StatusCode::CREATED => {
                         match response.json::<serde_json::Value>().await {
@@ -476,11 +499,14 @@ impl BrokerApiResponse for OandaClient {
                                     order.quantity_open -= quantity;
                                     order.quantity_filled += quantity;
 
+                                    let fill_update = DataServerResponse::OrderUpdates {
+                                        event: fill_event,
+                                        time: Utc::now().to_string(),
+                                    };
+                                    notify_webhooks(&fill_update);
+                                    copy_trades(&fill_update);
                                     if let Some(stream_receiver) = RESPONSE_SENDERS.get(&stream_name) {
-                                        stream_receiver.send(DataServerResponse::OrderUpdates {
-                                            event: fill_event,
-                                            time: Utc::now().to_string(),
-                                        }).await;
+                                        stream_receiver.send(fill_update).await;
                                     }
                                 }
 
@@ -610,6 +636,8 @@ impl BrokerApiResponse for OandaClient {
                     tag,
                     symbol_code: position.symbol_name.clone(),
                     exchange: None,
+                    position_id: None,
+                    trigger_source: None,
                 };
                 let _ = self.other_orders(0, StrategyMode::Live, exit_order).await;
             }
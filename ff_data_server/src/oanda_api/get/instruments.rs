@@ -14,13 +14,13 @@ pub(crate) async fn get_oanda_instruments(oanda_client: &OandaClient, account: &
 
     let response = oanda_client.send_rest_request(&url).await.unwrap();
     if !response.status().is_success() {
-        println!("Error getting instruments: {:?}", response)
+        tracing::info!("Error getting instruments: {:?}", response)
     }
 
     let content = match response.text().await {
         Ok(content) => content,
         Err(e) => {
-            println!("Error getting instruments: {:?}", e);
+            tracing::info!("Error getting instruments: {:?}", e);
             return None;
         }
     };
@@ -29,13 +29,13 @@ pub(crate) async fn get_oanda_instruments(oanda_client: &OandaClient, account: &
     let instruments_json = match json["instruments"].as_array() {
         Some(instruments) => instruments,
         None => {
-            println!("Error getting oanda instruments: {:?}", json);
+            tracing::info!("Error getting oanda instruments: {:?}", json);
             return None;
         }
     };
 
     if instruments_json.len() == 0 {
-        println!("No instruments found for account: {}", account);
+        tracing::info!("No instruments found for account: {}", account);
         return None;
     }
 
@@ -79,7 +79,7 @@ impl OandaClient {
                         match oanda_quotebar_from_candle(price_data, symbol.clone(), resolution.clone()) {
                             Ok(quotebar) => BaseDataEnum::QuoteBar(quotebar),
                             Err(e) => {
-                                eprintln!("Failed to create quote bar: {}", e);
+                                tracing::error!("Failed to create quote bar: {}", e);
                                 continue;
                             }
                         }
@@ -88,7 +88,7 @@ impl OandaClient {
                         match candle_from_candle(price_data, symbol.clone(), resolution.clone()) {
                             Ok(candle) => BaseDataEnum::Candle(candle),
                             Err(e) => {
-                                eprintln!("Failed to create candle: {}", e);
+                                tracing::error!("Failed to create candle: {}", e);
                                 continue;
                             }
                         }
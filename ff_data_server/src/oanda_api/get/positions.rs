@@ -5,9 +5,10 @@ use rust_decimal_macros::dec;
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 use uuid::Uuid;
-use ff_standard_lib::helpers::converters::fund_forge_formatted_symbol_name;
+use ff_standard_lib::product_maps::symbol_aliases::symbol_from_venue;
 use ff_standard_lib::product_maps::oanda::maps::OANDA_SYMBOL_INFO;
 use ff_standard_lib::standardized_types::accounts::Account;
+use ff_standard_lib::standardized_types::broker_enum::Brokerage;
 use ff_standard_lib::standardized_types::position::{Position, PositionCalculationMode};
 use crate::oanda_api::models::primitives::InstrumentName;
 
@@ -123,7 +124,7 @@ pub struct CalculatedPositionState {
 }
 
 pub fn parse_oanda_position(position: OandaPosition, account: Account) -> Option<Position> {
-    let symbol_name = fund_forge_formatted_symbol_name(&position.instrument);
+    let symbol_name = symbol_from_venue(&position.instrument, &Brokerage::Oanda);
     let (side, quantity, average_price, open_pnl) = match position.long.units > dec!(0) {
         true => (
             ff_standard_lib::standardized_types::enums::PositionSide::Long,
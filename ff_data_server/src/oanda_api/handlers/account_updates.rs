@@ -5,7 +5,6 @@ use std::time::Duration;
 use ahash::AHashMap;
 use dashmap::DashMap;
 use chrono::Utc;
-use rust_decimal::prelude::ToPrimitive;
 use ff_standard_lib::standardized_types::accounts::AccountId;
 use ff_standard_lib::standardized_types::orders::{OrderState, OrderUpdateEvent};
 use rust_decimal_macros::dec;
@@ -14,6 +13,7 @@ use crate::oanda_api::get::accounts::account_changes::get_account_changes;
 use crate::oanda_api::get::positions::parse_oanda_position;
 use crate::oanda_api::models::order::order_related::OandaOrderState;
 use crate::request_handlers::RESPONSE_SENDERS;
+use crate::server_features::webhooks::notify_webhooks;
 
 pub fn handle_account_updates(client: Arc<OandaClient>) {
     tokio::spawn(async move {
@@ -66,36 +66,22 @@ pub fn handle_account_updates(client: Arc<OandaClient>) {
                                                 parsed_position.clone()
                                             );
 
-                                        let quantity_open = match parsed_position.quantity_open.to_f64() {
-                                            None => continue,
-                                            Some(q) => q
-                                        };
-
-                                        let average_price = match parsed_position.average_price.to_f64() {
-                                            None => continue,
-                                            Some(p) => p
-                                        };
-
-                                        let open_pnl = match parsed_position.open_pnl.to_f64() {
-                                            None => continue,
-                                            Some(p) => p
-                                        };
-
                                         let message = DataServerResponse::LivePositionUpdates {
                                             symbol_name: parsed_position.symbol_name.clone(),
                                             symbol_code: parsed_position.symbol_name.clone(),
                                             account: account.clone(),
-                                            open_quantity: quantity_open,
-                                            average_price,
+                                            open_quantity: parsed_position.quantity_open,
+                                            average_price: parsed_position.average_price,
                                             side: parsed_position.side,
                                             time: Utc::now().to_string(),
-                                            open_pnl,
+                                            open_pnl: parsed_position.open_pnl,
                                         };
+                                        notify_webhooks(&message);
                                         for stream_name in RESPONSE_SENDERS.iter() {
                                             match stream_name.value().send(message.clone()).await {
                                                 Ok(_) => {}
                                                 Err(e) => {
-                                                    eprintln!("failed to forward ResponseNewOrder 313 to strategy stream {}", e);
+                                                    tracing::error!("failed to forward ResponseNewOrder 313 to strategy stream {}", e);
                                                 }
                                             }
                                         }
@@ -121,7 +107,7 @@ pub fn handle_account_updates(client: Arc<OandaClient>) {
                                 match stream_name.value().send(account_updates.clone()).await {
                                     Ok(_) => {}
                                     Err(e) => {
-                                        eprintln!("failed to forward ResponseNewOrder 313 to strategy stream {}", e);
+                                        tracing::error!("failed to forward ResponseNewOrder 313 to strategy stream {}", e);
 
                                     }
                                 }
@@ -129,7 +115,7 @@ pub fn handle_account_updates(client: Arc<OandaClient>) {
                         }
                     }
                     Err(e) => {
-                        eprintln!("Error polling account changes: {}", e);
+                        tracing::error!("Error polling account changes: {}", e);
                     }
                 }
 
@@ -197,11 +183,12 @@ pub fn handle_account_updates(client: Arc<OandaClient>) {
                                     }
                                     _ => continue
                                 };
+                                notify_webhooks(&message);
                                 for stream_name in RESPONSE_SENDERS.iter() {
                                     match stream_name.value().send(message.clone()).await {
                                         Ok(_) => {}
                                         Err(e) => {
-                                            eprintln!("failed to forward ResponseNewOrder 313 to strategy stream {}", e);
+                                            tracing::error!("failed to forward ResponseNewOrder 313 to strategy stream {}", e);
                                         }
                                     }
                                 }
@@ -209,7 +196,7 @@ pub fn handle_account_updates(client: Arc<OandaClient>) {
                         },
                         Err(e) => {
                             to_remove.push(order.key().clone());
-                            eprintln!("Failed to get_requests order: {}", e);
+                            tracing::error!("Failed to get_requests order: {}", e);
                             continue;
                         }
                     };
@@ -44,7 +44,7 @@ pub fn handle_quotebar_subscribers(
                 ).await {
                     Ok(bars) => bars,
                     Err(e) => {
-                        eprintln!("Failed to get_requests latest bars for quotebar subscriber: {}", e);
+                        tracing::error!("Failed to get_requests latest bars for quotebar subscriber: {}", e);
                         continue
                     }
                 };
@@ -31,7 +31,7 @@ pub async fn establish_stream(
     api_key: &str
 ) -> Result<impl Stream<Item = Result<Bytes, reqwest::Error>>, FundForgeError> {
     let url = format!("{}{}", stream_endpoint, stream_endpoint_suffix);
-    eprintln!("Attempting to connect to URL: {}", url);
+    tracing::info!("Attempting to connect to URL: {}", url);
 
     // Acquire a stream permit asynchronously.
     let _stream_permit = stream_limit.acquire().await.expect("Failed to acquire stream permit");
@@ -43,18 +43,18 @@ pub async fn establish_stream(
         .await {
         Ok(response) => response,
         Err(e) => {
-            eprintln!("Stream request failed: {}", e);
+            tracing::error!("Stream request failed: {}", e);
             return Err(FundForgeError::ServerErrorDebug(format!("Stream request failed: {}", e)));
         }
     };
 
     // Check response status
     let status = response.status();
-    eprintln!("Stream response status: {}", status);
+    tracing::debug!("Stream response status: {}", status);
 
     if !status.is_success() {
         let error_text = response.text().await.unwrap_or_else(|_| "Could not get_requests error text".to_string());
-        eprintln!("Stream request failed: {}", error_text);
+        tracing::error!("Stream request failed: {}", error_text);
         return Err(FundForgeError::ServerErrorDebug(format!(
             "Stream request failed with status {}: {}",
             status,
@@ -63,7 +63,7 @@ pub async fn establish_stream(
     }
 
     // Log headers for debugging
-    eprintln!("Response headers: {:?}", response.headers());
+    tracing::debug!("Response headers: {:?}", response.headers());
 
     OANDA_IS_CONNECTED.store(true, Ordering::SeqCst);
     Ok(response.bytes_stream())
@@ -137,7 +137,7 @@ pub fn handle_price_stream(
                                     OANDA_IS_CONNECTED.store(true, Ordering::SeqCst);
                                 }
                                 Err(e) => {
-                                    eprintln!("Failed to establish stream: {}", e);
+                                    tracing::error!("Failed to establish stream: {}", e);
                                     OANDA_IS_CONNECTED.store(false, Ordering::SeqCst);
                                 }
                             }
@@ -163,7 +163,7 @@ pub fn handle_price_stream(
                                         OANDA_IS_CONNECTED.store(true, Ordering::SeqCst);
                                     }
                                     Err(e) => {
-                                        eprintln!("Failed to establish stream: {}", e);
+                                        tracing::error!("Failed to establish stream: {}", e);
                                         OANDA_IS_CONNECTED.store(false, Ordering::SeqCst);
                                     }
                                 }
@@ -181,13 +181,13 @@ pub fn handle_price_stream(
                             let text = match String::from_utf8(chunk.to_vec()) {
                                 Ok(t) => t,
                                 Err(e) => {
-                                    eprintln!("Invalid UTF-8 in chunk: {}", e);
+                                    tracing::error!("Invalid UTF-8 in chunk: {}", e);
                                     continue;
                                 }
                             };
 
                             if text.starts_with("<html>") || text.contains("404 Not Found") {
-                                eprintln!("Received HTML error response, clearing stream");
+                                tracing::error!("Received HTML error response, clearing stream");
                                 current_stream = None;
                                 OANDA_IS_CONNECTED.store(false, Ordering::SeqCst);
                                 continue;
@@ -1,6 +1,7 @@
 use rust_decimal::Decimal;
 use chrono::{NaiveDateTime, DateTime as ChronoDateTime, Utc};
 use serde::{self, Deserialize, Deserializer, Serializer, Serialize, de};
+use ff_standard_lib::standardized_types::symbol_info::FinancingRate;
 use crate::oanda_api::models::pricing_common::PriceBucket;
 
 /// A tag associated with an entity.
@@ -65,6 +66,30 @@ pub struct InstrumentFinancing {
     pub financing_days_of_week: Vec<FinancingDayOfWeek>,
 }
 
+impl InstrumentFinancing {
+    /// The financing in the fund forge format, days missing from `financing_days_of_week` are not charged.
+    pub fn to_financing_rate(&self) -> FinancingRate {
+        let mut days_charged = [0; 7];
+        for day in &self.financing_days_of_week {
+            let index = match day.day_of_week {
+                DayOfWeek::Monday => 0,
+                DayOfWeek::Tuesday => 1,
+                DayOfWeek::Wednesday => 2,
+                DayOfWeek::Thursday => 3,
+                DayOfWeek::Friday => 4,
+                DayOfWeek::Saturday => 5,
+                DayOfWeek::Sunday => 6,
+            };
+            days_charged[index] = day.days_charged.max(0) as u32;
+        }
+        FinancingRate {
+            long_rate: self.long_rate,
+            short_rate: self.short_rate,
+            days_charged,
+        }
+    }
+}
+
 /// Full specification of an Instrument.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Instrument {
@@ -19,14 +19,14 @@ impl OandaSettings {
         let contents = match fs::read_to_string(path) {
             Ok(c) => c,
             Err(e) => {
-                eprintln!("Error reading oanda settings file: {}", e);
+                tracing::error!("Error reading oanda settings file: {}", e);
                 return None;
             }
         };
         let settings: OandaSettings = match toml::from_str(&contents) {
             Ok(s) => s,
             Err(e) => {
-                eprintln!("Error parsing oanda settings: {}", e);
+                tracing::error!("Error parsing oanda settings: {}", e);
                 return None;
             }
         };
@@ -3,6 +3,8 @@ use std::str::FromStr;
 use chrono::{DateTime, Duration, Utc};
 use structopt::StructOpt;
 use ff_standard_lib::standardized_types::resolution::Resolution;
+use ff_standard_lib::product_maps::symbol_aliases::venue_symbol;
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
 use ff_standard_lib::standardized_types::subscriptions::{CandleType, Symbol, SymbolName};
 use serde_json::Value;
 use ff_standard_lib::standardized_types::base_data::quotebar::QuoteBar;
@@ -70,8 +72,9 @@ impl FromStr for Interval {
     }
 }
 
+/// The Oanda instrument of the symbol, see `venue_symbol()`, names without an Oanda code are cleaned to the Oanda format.
 pub(crate) async fn oanda_clean_instrument(symbol_name: &SymbolName) -> SymbolName {
-    symbol_name
+    venue_symbol(symbol_name, &DataVendor::Oanda)
         .replace("/", "_")
         .replace(":", "_")
         .replace("?", "_")
@@ -16,6 +16,9 @@ use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
 use ff_standard_lib::standardized_types::enums::{MarketType, StrategyMode, PrimarySubscription};
 use ff_standard_lib::standardized_types::resolution::Resolution;
 use ff_standard_lib::standardized_types::subscriptions::{DataSubscription, Symbol, SymbolName};
+use ff_standard_lib::standardized_types::symbol_info::SymbolSearchResult;
+use ff_standard_lib::standardized_types::orders::OrderType;
+use ff_standard_lib::standardized_types::vendor_capabilities::VendorCapabilities;
 use ff_standard_lib::StreamName;
 use crate::oanda_api::api_client::{OandaClient, OANDA_IS_CONNECTED};
 use crate::oanda_api::support_and_conversions::oanda_quotebar_from_candle;
@@ -198,6 +201,41 @@ impl VendorApiResponse for OandaClient {
         }
     }
 
+    async fn vendor_capabilities_response(&self, mode: StrategyMode, _stream_name: StreamName, callback_id: u64) -> DataServerResponse {
+        let base_data_types = match mode {
+            StrategyMode::Backtest => vec![BaseDataType::QuoteBars],
+            StrategyMode::Live | StrategyMode::LivePaperTrading => vec![BaseDataType::QuoteBars, BaseDataType::Quotes],
+        };
+        DataServerResponse::VendorCapabilities {
+            callback_id,
+            capabilities: VendorCapabilities {
+                data_vendor: DataVendor::Oanda,
+                base_data_types,
+                market_depth: false,
+                historical_from: Some("2005-01-01 00:00:00 UTC".to_string()),
+                max_requests_per_second: Some(120),
+                order_types: vec![OrderType::Market, OrderType::Limit, OrderType::MarketIfTouched, OrderType::StopMarket, OrderType::StopLimit],
+            },
+        }
+    }
+
+    async fn symbol_search_response(&self, _mode: StrategyMode, _stream_name: StreamName, query: String, callback_id: u64) -> DataServerResponse {
+        let results = self.instruments_map.iter()
+            .map(|instrument| SymbolSearchResult {
+                symbol: Symbol::new(instrument.key().clone(), DataVendor::Oanda, instrument.value().market_type),
+                symbol_code: None,
+                description: instrument.value().display_name.clone(),
+                exchange: None,
+                expiration: None,
+            })
+            .filter(|result| result.matches(&query))
+            .collect();
+        DataServerResponse::SymbolSearch {
+            callback_id,
+            results,
+        }
+    }
+
     #[allow(unused)]
     async fn logout_command_vendors(&self, stream_name: StreamName) {
         todo!()
@@ -338,7 +376,7 @@ impl VendorApiResponse for OandaClient {
                                 progress_bar.inc(1);
                             },
                             Err(e) => {
-                                eprintln!("Error saving data batch: {}", e);
+                                tracing::error!("Error saving data batch: {}", e);
                                 progress_bar.set_message(format!("Error saving data batch: {}", e));
                                 break 'main_loop;
                             }
@@ -366,7 +404,7 @@ impl VendorApiResponse for OandaClient {
         if !new_data.is_empty() {
             let data_vec: Vec<BaseDataEnum> = new_data.values().cloned().collect();
             if let Err(e) = data_storage.save_data_bulk(data_vec).await {
-                eprintln!("Error saving final data batch: {}", e);
+                tracing::error!("Error saving final data batch: {}", e);
                 progress_bar.set_message(format!("Error saving final data batch: {}", e));
             }
         }
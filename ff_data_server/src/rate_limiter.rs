@@ -142,7 +142,7 @@ mod tests {
             throughput_data.push(interval_count);
 
             // Output interval results for monitoring
-            println!("Interval {}: {} operations", i + 1, interval_count);
+            tracing::info!("Interval {}: {} operations", i + 1, interval_count);
 
             // Check that throughput is within an acceptable range (90%-100% of rate)
             let min_expected_throughput = (rate as f64 * 0.9) as usize;
@@ -164,8 +164,8 @@ mod tests {
 
         // Final summary and checks
         let average_throughput: f64 = throughput_data.iter().copied().sum::<usize>() as f64 / intervals as f64;
-        println!("\nFinal 1-Minute Summary:");
-        println!("Average throughput per interval: {:.1}", average_throughput);
+        tracing::info!("\nFinal 1-Minute Summary:");
+        tracing::info!("Average throughput per interval: {:.1}", average_throughput);
         assert!(
             average_throughput >= (rate as f64 * 0.9),
             "Average throughput too low: {:.1} (should be at least {:.1})",
@@ -1,5 +1,5 @@
 use std::future::Future;
-use ff_standard_lib::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
+use ff_standard_lib::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError, StreamRequest};
 use ff_standard_lib::standardized_types::subscriptions::DataSubscription;
 use ff_standard_lib::standardized_types::bytes_trait::Bytes;
 use chrono::{DateTime, Utc};
@@ -16,51 +16,75 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::time::timeout;
 use tokio_rustls::server::TlsStream;
-use crate::server_side_brokerage::{account_info_response, accounts_response, commission_info_response, live_market_order, symbol_info_response, symbol_names_response, live_enter_long, live_exit_long, live_exit_short, live_enter_short, other_orders, cancel_order, flatten_all_for, update_order, cancel_orders_on_account, exchange_rate_response, front_month_info_response};
-use crate::server_side_datavendor::{base_data_types_response, decimal_accuracy_response, markets_response, resolutions_response, symbols_response, tick_size_response};
+use crate::server_side_brokerage::{logout_command, account_info_response, accounts_response, commission_info_response, live_market_order, symbol_info_response, symbol_names_response, live_enter_long, live_exit_long, live_exit_short, live_enter_short, other_orders, cancel_order, flatten_all_for, update_order, cancel_orders_on_account, exchange_rate_response, financing_rate_response, front_month_info_response};
+use crate::server_side_datavendor::{logout_command_vendors, base_data_types_response, decimal_accuracy_response, markets_response, options_chain_response, resolutions_response, symbol_search_response, symbols_response, tick_size_response, vendor_capabilities_response};
 use ff_standard_lib::standardized_types::enums::StrategyMode;
-use ff_standard_lib::standardized_types::orders::{Order, OrderRequest, OrderType, OrderUpdateEvent};
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+use ff_standard_lib::standardized_types::orders::{BasketPolicy, Order, OrderId, OrderRequest, OrderType, OrderUpdateEvent};
 use ff_standard_lib::StreamName;
 use crate::{stream_listener, subscribe_server_shutdown};
 use crate::stream_tasks::deregister_streamer;
-use crate::update_functions::{pre_subscribe_updates, MULTIBAR};
+use crate::server_features::position_limits::check_position_limits;
+use crate::server_features::market_replay::check_replay;
+use crate::server_features::api_keys::{deregister_connection_key, ConnectionAccess};
+use crate::server_features::webhooks::notify_webhooks;
+use crate::server_features::subscription_profiles::{save_subscription_profile_response, subscription_profile_response};
+use crate::server_features::vendor_failover::{deregister_failover_feeds, failover_subscribe};
+use crate::server_features::history_stream::{acknowledge_history_part, deregister_history_streams, stream_compressed_files};
+use crate::server_features::message_bus::{deregister_topic_subscriber, publish_message, subscribe_topic, unsubscribe_topic};
+use crate::chart_republish::{deregister_chart_subscriber, republish_chart_updates, subscribe_chart, unsubscribe_chart};
+use crate::update_functions::MULTIBAR;
+use crate::rithmic_api::api_client::RITHMIC_CLIENTS;
+use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+use ff_standard_lib::apis::rithmic::rithmic_systems::RithmicSystem;
+use strum::IntoEnumIterator;
+use tracing::{Instrument, Span};
+use ff_standard_lib::helpers::logging::{log_order_event, order_span};
 use crate::update_functions::DATA_STORAGE;
 
 lazy_static!(
     pub static ref RESPONSE_SENDERS: Arc<DashMap<StreamName, Sender<DataServerResponse>>> = Arc::new(DashMap::new());
 );
 
-pub async fn compressed_file_response(
+pub async fn data_version_response(
     subscriptions: Vec<DataSubscription>,
     from_time: String,
     to_time: String,
     callback_id: u64,
 ) -> DataServerResponse {
-    //eprintln!("Getting compressed files in range: {:?}", subscriptions);
-    let from_time = match from_time.parse::<DateTime<Utc>>() {
-        Ok(t) => t,
-        Err(e) => return DataServerResponse::Error {
+    let (from_time, to_time) = match (from_time.parse::<DateTime<Utc>>(), to_time.parse::<DateTime<Utc>>()) {
+        (Ok(from_time), Ok(to_time)) => (from_time, to_time),
+        _ => return DataServerResponse::Error {
             callback_id,
-            error: FundForgeError::ServerErrorDebug(format!("Invalid from_time: {}", e))
+            error: FundForgeError::ServerErrorDebug(format!("Invalid time range: {} to {}", from_time, to_time))
         }
     };
-    let to_time = match to_time.parse::<DateTime<Utc>>() {
-        Ok(t) => t,
-        Err(e) => return DataServerResponse::Error {
+
+    let data_storage = match DATA_STORAGE.get() {
+        Some(storage) => storage,
+        None => return DataServerResponse::Error {
             callback_id,
-            error: FundForgeError::ServerErrorDebug(format!("Invalid to_time: {}", e))
+            error: FundForgeError::ServerErrorDebug("Data storage not initialized".to_string())
         }
     };
 
-    // Limit the date range to prevent huge requests
-    if (to_time - from_time).num_days() > 365 { //we arent actually opening the files here so its not to bad if we have large limit.
-        return DataServerResponse::Error {
+    match data_storage.data_version(&subscriptions, from_time, to_time).await {
+        Ok(version) => DataServerResponse::DataVersion {
             callback_id,
-            error: FundForgeError::ServerErrorDebug("Date range exceeds maximum of 365 days".to_string())
+            version
+        },
+        Err(e) => DataServerResponse::Error {
+            callback_id,
+            error: FundForgeError::ServerErrorDebug(e.to_string())
         }
     }
+}
 
-
+pub async fn symbol_catalog_response(
+    data_vendor: DataVendor,
+    query: String,
+    callback_id: u64,
+) -> DataServerResponse {
     let data_storage = match DATA_STORAGE.get() {
         Some(storage) => storage,
         None => return DataServerResponse::Error {
@@ -69,35 +93,14 @@ pub async fn compressed_file_response(
         }
     };
 
-    if to_time.date_naive() >= Utc::now().date_naive() {
-
-        let tasks: Vec<_> = subscriptions.iter().map(|subscription| {
-            pre_subscribe_updates(
-                DATA_STORAGE.get().unwrap().clone(),
-                subscription.symbol.clone(),
-                subscription.resolution,
-                subscription.base_data_type,
-            )
-        }).collect();
-
-        futures::future::join_all(tasks).await;
-    }
-
-    //todo i need to debug this and determine cause of time outs
-    match data_storage.get_compressed_files_in_range(subscriptions, from_time, to_time).await {
-        Ok(data) => {
-            //eprintln!("Got compressed files");
-            DataServerResponse::CompressedHistoricalData {
-                callback_id,
-                payload: data
-            }
+    match data_storage.symbol_catalog(&data_vendor, &query).await {
+        Ok(symbols) => DataServerResponse::SymbolCatalog {
+            callback_id,
+            symbols
         },
-        Err(e) => {
-            //eprintln!("Error getting compressed files: {:?}", e);
-            DataServerResponse::Error {
-                callback_id,
-                error: FundForgeError::ServerErrorDebug(e.to_string())
-            }
+        Err(e) => DataServerResponse::Error {
+            callback_id,
+            error: FundForgeError::ServerErrorDebug(e.to_string())
         }
     }
 }
@@ -106,6 +109,8 @@ pub async fn manage_async_requests(
     strategy_mode: StrategyMode,
     stream: TlsStream<TcpStream>,
     stream_name: StreamName,
+    heartbeat_timeout: Option<Duration>,
+    access: ConnectionAccess,
 ) {
     //println!("stream name: {}", stream_name);
     let (read_half, write_half) = io::split(stream);
@@ -115,7 +120,7 @@ pub async fn manage_async_requests(
     // Response handler for outgoing messages
     let write_task = tokio::spawn(async move {
         let _ = response_handler(request_receiver, write_half).await;
-    });
+    }.in_current_span());
     tokio::spawn(async move {
         const LENGTH: usize = 4;
         let mut receiver = read_half;
@@ -129,7 +134,23 @@ pub async fn manage_async_requests(
                 .template("{spinner:.green} {prefix} {msg}")
                 .expect("Failed to set style"),
         );
-        while let Ok(_) = receiver.read_exact(&mut length_bytes).await {
+        loop {
+            // Strategies send heartbeats, so a strategy we have not heard from within the timeout is dead and is dropped
+            let length_read = receiver.read_exact(&mut length_bytes);
+            let length_read = match heartbeat_timeout {
+                Some(heartbeat_timeout) => match timeout(heartbeat_timeout, length_read).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        let msg = format!("No heartbeat for {} seconds, dropping strategy", heartbeat_timeout.as_secs());
+                        message_bar.set_message(msg);
+                        break;
+                    }
+                },
+                None => length_read.await,
+            };
+            if length_read.is_err() {
+                break;
+            }
             let msg_length = u32::from_be_bytes(length_bytes) as usize;
             let mut message_body = vec![0u8; msg_length];
 
@@ -154,14 +175,24 @@ pub async fn manage_async_requests(
             let msg  = format!("Last Request: {:?}", request);
             message_bar.set_message(msg);
 
+            if let Err(reason) = access.check(&request).and_then(|_| check_replay(strategy_mode, &request)) {
+                tracing::warn!("Request rejected: {}", reason);
+                reject_request(request, reason, &response_sender, &stream_name).await;
+                continue;
+            }
+
             let stream_name = stream_name.clone();
             let mode = strategy_mode.clone();
             let sender = response_sender.clone();
+            let access = access.clone();
 
             tokio::spawn(async move {
                 // Handle the request and generate a response
                 match request {
-                    DataServerRequest::Register(_) => {},
+                    DataServerRequest::Register { .. } => {},
+                    DataServerRequest::Heartbeat => {
+                        let _ = sender.send(DataServerResponse::Heartbeat).await;
+                    }
                     DataServerRequest::ExchangeRate {
                         callback_id,
                         from_currency,
@@ -196,10 +227,18 @@ pub async fn manage_async_requests(
                         callback_id
                     ).await,
 
-                    DataServerRequest::GetCompressedHistoricalData { callback_id, subscriptions, from_time, to_time } => {
-                        handle_callback_no_timeouts (
-                            || compressed_file_response(subscriptions, from_time, to_time, callback_id),
-                            sender.clone()).await
+                    DataServerRequest::GetCompressedHistoricalData { callback_id, subscriptions, from_time, to_time, compression } => {
+                        stream_compressed_files(stream_name, subscriptions, from_time, to_time, compression, callback_id, sender.clone()).await
+                    }
+
+                    DataServerRequest::HistoricalDataAck { callback_id, .. } => {
+                        acknowledge_history_part(stream_name, callback_id)
+                    }
+
+                    DataServerRequest::DataVersion { callback_id, subscriptions, from_time, to_time } => {
+                        handle_callback(
+                            || data_version_response(subscriptions, from_time, to_time, callback_id),
+                            sender.clone(), callback_id).await
                     }
 
                     DataServerRequest::SymbolsVendor {
@@ -274,7 +313,7 @@ pub async fn manage_async_requests(
                         callback_id,
                         brokerage
                     } => handle_callback(
-                        || accounts_response(brokerage, mode, stream_name, callback_id),
+                        || async { access.filter_accounts(&brokerage, accounts_response(brokerage, mode, stream_name, callback_id).await) },
                         sender.clone(),callback_id).await,
 
                     DataServerRequest::BaseDataTypes {
@@ -284,6 +323,13 @@ pub async fn manage_async_requests(
                         || base_data_types_response(data_vendor, mode, stream_name, callback_id),
                         sender.clone(),callback_id).await,
 
+                    DataServerRequest::VendorCapabilities {
+                        callback_id,
+                        data_vendor
+                    } => handle_callback(
+                        || vendor_capabilities_response(data_vendor, mode, stream_name, callback_id),
+                        sender.clone(),callback_id).await,
+
                     DataServerRequest::SymbolNames { callback_id, brokerage, time } => {
                         let time = match time {
                             None => None,
@@ -297,12 +343,48 @@ pub async fn manage_async_requests(
                             sender.clone(),callback_id).await
                     }
 
+                    DataServerRequest::SymbolSearch { callback_id, data_vendor, query } => {
+                        handle_callback(
+                            || symbol_search_response(data_vendor, mode, stream_name, query, callback_id),
+                            sender.clone(),callback_id).await
+                    }
+
+                    DataServerRequest::OptionsChain { callback_id, data_vendor, underlying, expiry } => {
+                        handle_callback(
+                            || options_chain_response(data_vendor, mode, stream_name, underlying, expiry, callback_id),
+                            sender.clone(),callback_id).await
+                    }
+
+                    DataServerRequest::SymbolCatalog { callback_id, data_vendor, query } => {
+                        handle_callback(
+                            || symbol_catalog_response(data_vendor, query, callback_id),
+                            sender.clone(),callback_id).await
+                    }
+
+                    DataServerRequest::SaveSubscriptionProfile { callback_id, profile } => {
+                        handle_callback(
+                            || save_subscription_profile_response(profile, callback_id),
+                            sender.clone(),callback_id).await
+                    }
+
+                    DataServerRequest::SubscriptionProfile { callback_id, name } => {
+                        handle_callback(
+                            || subscription_profile_response(name, callback_id),
+                            sender.clone(),callback_id).await
+                    }
+
                     DataServerRequest::CommissionInfo { callback_id, brokerage, symbol_name } => {
                         handle_callback(
                             || commission_info_response(mode, brokerage, symbol_name, stream_name, callback_id),
                             sender.clone(),callback_id).await
                     }
 
+                    DataServerRequest::FinancingRate { callback_id, brokerage, symbol_name } => {
+                        handle_callback(
+                            || financing_rate_response(brokerage, symbol_name, callback_id),
+                            sender.clone(),callback_id).await
+                    }
+
                     DataServerRequest::FrontMonthInfo { callback_id, symbol_name, exchange, brokerage } => {
                         handle_callback(
                             || front_month_info_response(brokerage, symbol_name, exchange, stream_name, callback_id),
@@ -318,9 +400,16 @@ pub async fn manage_async_requests(
                         }
                         //1. download latest data and await
                         //println!("{:?}", request);
-                        handle_callback_no_timeouts(
-                            || stream_listener::stream_response(stream_name, request),
-                            sender.clone()).await
+                        match request {
+                            StreamRequest::Subscribe(subscription) if !subscription.fallback_vendors.is_empty() => {
+                                failover_subscribe(stream_name, subscription, sender.clone()).await
+                            }
+                            request => {
+                                handle_callback_no_timeouts(
+                                    || stream_listener::stream_response(stream_name, request),
+                                    sender.clone()).await
+                            }
+                        }
                     },
 
                     DataServerRequest::OrderRequest {
@@ -330,8 +419,9 @@ pub async fn manage_async_requests(
                             //eprintln!("Incorrect strategy mode for orders: {:?}", strategy_mode);
                             return;
                         }
-                        //println!("{:?}", request);
-                        order_response(stream_name, mode, request, sender.clone()).await;
+                        let span = order_request_span(&request);
+                        span.in_scope(|| tracing::debug!("Order Request: {:?}", request));
+                        order_response(stream_name, mode, request, sender.clone()).instrument(span).await;
                     },
 
                     DataServerRequest::PrimarySubscriptionFor { .. } => {
@@ -340,17 +430,46 @@ pub async fn manage_async_requests(
                     DataServerRequest::RegisterStreamer { .. } => {
                         //no need to handle here
                     }
+
+                    DataServerRequest::PublishChartUpdates { chart_name, updates } => {
+                        republish_chart_updates(chart_name, updates).await
+                    }
+
+                    DataServerRequest::SubscribeChart { chart_name } => {
+                        subscribe_chart(stream_name, chart_name)
+                    }
+
+                    DataServerRequest::UnsubscribeChart { chart_name } => {
+                        unsubscribe_chart(&stream_name, &chart_name)
+                    }
+
+                    DataServerRequest::PublishMessage { topic, payload } => {
+                        publish_message(topic, payload).await
+                    }
+
+                    DataServerRequest::SubscribeTopic { topic } => {
+                        subscribe_topic(stream_name, topic)
+                    }
+
+                    DataServerRequest::UnsubscribeTopic { topic } => {
+                        unsubscribe_topic(&stream_name, &topic)
+                    }
                 }
-            });
+            }.in_current_span());
         }
         // Deregister when disconnected
         if strategy_mode != StrategyMode::Backtest {
             deregister_streamer(&stream_name).await;
         }
+        deregister_chart_subscriber(&stream_name);
+        deregister_topic_subscriber(&stream_name);
+        deregister_history_streams(&stream_name);
+        deregister_connection_key(&stream_name);
+        logout_stream(stream_name).await;
         write_task.abort();
         RESPONSE_SENDERS.remove(&stream_name);
         message_bar.finish_and_clear();
-    });
+    }.in_current_span());
 }
 
 async fn response_handler(
@@ -382,6 +501,21 @@ async fn response_handler(
     Ok(())
 }
 
+/// Drops the callbacks and other items the vendor and brokerage clients hold for a disconnected strategy,
+/// so responses to its pending requests are not waited on or sent.
+async fn logout_stream(stream_name: StreamName) {
+    deregister_failover_feeds(&stream_name);
+    for data_vendor in DataVendor::iter() {
+        logout_command_vendors(data_vendor, stream_name).await;
+    }
+    let rithmic_systems: Vec<RithmicSystem> = RITHMIC_CLIENTS.iter().map(|entry| entry.key().clone()).collect();
+    for system in rithmic_systems {
+        logout_command(Brokerage::Rithmic(system), stream_name).await;
+    }
+    logout_command(Brokerage::Bitget, stream_name).await;
+    logout_command(Brokerage::Oanda, stream_name).await;
+}
+
 async fn handle_callback<F, Fut>(
     callback: F,
     sender: tokio::sync::mpsc::Sender<DataServerResponse>,
@@ -397,7 +531,7 @@ where
             // Successfully received response, try sending it to the stream handler
             if let Err(e) = sender.send(response).await {
                 // Handle send error (e.g., log it)
-                println!("Failed to send response to stream handler: {:?}", e);
+                tracing::info!("Failed to send response to stream handler: {:?}", e);
             }
         }
         Err(_) => {
@@ -407,7 +541,7 @@ where
             };
             if let Err(e) = sender.send(response).await {
                 // Handle send error (e.g., log it)
-                println!("Failed to send response to stream handler: {:?}", e);
+                tracing::info!("Failed to send response to stream handler: {:?}", e);
             }
         }
     }
@@ -427,14 +561,55 @@ where
     // Try sending the response to the stream handler
     if let Err(e) = sender.send(response).await {
         // Handle send error (e.g., log it)
-        println!("Failed to send response to stream handler: {:?}", e);
+        tracing::info!("Failed to send response to stream handler: {:?}", e);
     }
 }
 
 async fn send_error_response(sender: &tokio::sync::mpsc::Sender<DataServerResponse>, error: OrderUpdateEvent, stream_name: &StreamName) {
+    log_order_event(&error);
     let event = DataServerResponse::OrderUpdates{event: error, time: Utc::now().to_string()};
+    notify_webhooks(&event);
     if let Err(_) = sender.send(event).await {
-        eprintln!("Failed to send order response to: {}", stream_name);
+        tracing::error!("Failed to send order response to: {}", stream_name);
+    }
+}
+
+/// Answers a request the api key of the connection is not allowed to make, see `ConnectionAccess::check()`, or which can't be handled while replaying, see `check_replay()`.
+async fn reject_request(request: DataServerRequest, reason: String, sender: &Sender<DataServerResponse>, stream_name: &StreamName) {
+    let callback_id = match request {
+        DataServerRequest::OrderRequest { request } => {
+            let event = match request {
+                OrderRequest::Create { order, .. } => create_order_rejected(&order, reason),
+                OrderRequest::Cancel { account, order_id } | OrderRequest::Update { account, order_id, .. } => {
+                    OrderUpdateEvent::OrderUpdateRejected { account, order_id, reason, time: Utc::now().to_string() }
+                }
+                OrderRequest::CancelAll { account } | OrderRequest::FlattenAllFor { account } => {
+                    OrderUpdateEvent::OrderUpdateRejected { account, order_id: String::new(), reason, time: Utc::now().to_string() }
+                }
+                OrderRequest::Basket { orders, .. } => {
+                    for order in &orders {
+                        send_error_response(sender, create_order_rejected(order, reason.clone()), stream_name).await;
+                    }
+                    return;
+                }
+            };
+            send_error_response(sender, event, stream_name).await;
+            return;
+        }
+        DataServerRequest::AccountInfo { callback_id, .. }
+        | DataServerRequest::Accounts { callback_id, .. }
+        | DataServerRequest::SaveSubscriptionProfile { callback_id, .. } => callback_id,
+        _ => return,
+    };
+    let _ = sender.send(DataServerResponse::Error { callback_id, error: FundForgeError::ClientSideErrorDebug(reason) }).await;
+}
+
+/// The span of the order the request is for, requests for all orders of an account are logged in the span of the connection.
+fn order_request_span(request: &OrderRequest) -> Span {
+    match request {
+        OrderRequest::Create { account, order, .. } => order_span(&order.id, account, &order.time_created_utc),
+        OrderRequest::Cancel { account, order_id } | OrderRequest::Update { account, order_id, .. } => order_span(order_id, account, Utc::now()),
+        OrderRequest::CancelAll { .. } | OrderRequest::FlattenAllFor { .. } | OrderRequest::Basket { .. } => Span::current(),
     }
 }
 
@@ -451,86 +626,76 @@ fn create_order_rejected(order: &Order, reason: String) -> OrderUpdateEvent {
 }
 
 const TIMEOUT_DURATION: Duration = Duration::from_secs(320);
+/// Sends the order to the brokerage, a placement which times out is rejected.
+async fn place_order(stream_name: StreamName, mode: StrategyMode, order: Order, order_type: OrderType) -> Result<(), OrderUpdateEvent> {
+    let send_order_result = match order_type {
+        OrderType::Market => timeout(TIMEOUT_DURATION, live_market_order(stream_name, mode, order.clone())).await,
+        OrderType::MarketIfTouched |  OrderType::StopMarket | OrderType::StopLimit | OrderType::Limit => timeout(TIMEOUT_DURATION, other_orders(stream_name, mode, order.clone())).await,
+        OrderType::EnterLong => timeout(TIMEOUT_DURATION, live_enter_long(stream_name, mode, order.clone())).await,
+        OrderType::EnterShort => timeout(TIMEOUT_DURATION, live_enter_short(stream_name, mode, order.clone())).await,
+        OrderType::ExitLong => timeout(TIMEOUT_DURATION, live_exit_long(stream_name, mode, order.clone())).await,
+        OrderType::ExitShort => timeout(TIMEOUT_DURATION, live_exit_short(stream_name, mode, order.clone())).await,
+    };
+    match send_order_result {
+        Ok(result) => result,
+        Err(_) => Err(create_order_rejected(&order, "Order placement timed out".to_string())),
+    }
+}
+
+fn rejected_reason(event: &OrderUpdateEvent) -> String {
+    match event {
+        OrderUpdateEvent::OrderRejected { reason, .. } | OrderUpdateEvent::OrderUpdateRejected { reason, .. } => reason.clone(),
+        event => event.to_string(),
+    }
+}
+
 #[allow(dead_code, unused)]
 async fn order_response(stream_name: StreamName, mode: StrategyMode, request: OrderRequest, sender: tokio::sync::mpsc::Sender<DataServerResponse>) {
     match request {
         OrderRequest::Create { account, order, order_type } => {
-            match order_type {
-                OrderType::Market => {
-                    let send_order_result = timeout(TIMEOUT_DURATION, live_market_order(stream_name.clone(), mode, order.clone())).await;
-                    match send_order_result {
-                        Ok(Ok(_)) => {} // Order placed successfully
-                        Ok(Err(e)) => {
-                            send_error_response(&sender, e, &stream_name).await;
-                        }
-                        Err(_) => {
-                            let timeout_error = create_order_rejected(&order, "Order placement timed out".to_string());
-                            send_error_response(&sender, timeout_error, &stream_name).await;
-                        }
-                    }
-                }
-                OrderType::MarketIfTouched |  OrderType::StopMarket | OrderType::StopLimit | OrderType::Limit => {
-                    let send_order_result = timeout(TIMEOUT_DURATION, other_orders(stream_name.clone(), mode, order.clone())).await;
-                    match send_order_result {
-                        Ok(Ok(_)) => {} // Order placed successfully
-                        Ok(Err(e)) => {
-                            send_error_response(&sender, e, &stream_name).await;
-                        }
-                        Err(_) => {
-                            let timeout_error = create_order_rejected(&order, "Order placement timed out".to_string());
-                            send_error_response(&sender, timeout_error, &stream_name).await;
-                        }
-                    }
-                }
-                OrderType::EnterLong => {
-                    let send_order_result = timeout(TIMEOUT_DURATION, live_enter_long(stream_name.clone(), mode, order.clone())).await;
-                    match send_order_result {
-                        Ok(Ok(_)) => {} // Order placed successfully
-                        Ok(Err(e)) => {
-                            send_error_response(&sender, e, &stream_name).await;
-                        }
-                        Err(_) => {
-                            let timeout_error = create_order_rejected(&order, "Order placement timed out".to_string());
-                            send_error_response(&sender, timeout_error, &stream_name).await;
-                        }
-                    }
-                }
-                OrderType::EnterShort => {
-                    let send_order_result = timeout(TIMEOUT_DURATION, live_enter_short(stream_name.clone(), mode, order.clone())).await;
-                    match send_order_result {
-                        Ok(Ok(_)) => {} // Order placed successfully
-                        Ok(Err(e)) => {
-                            send_error_response(&sender, e, &stream_name).await;
-                        }
-                        Err(_) => {
-                            let timeout_error = create_order_rejected(&order, "Order placement timed out".to_string());
-                            send_error_response(&sender, timeout_error, &stream_name).await;
-                        }
-                    }
-                }
-                OrderType::ExitLong => {
-                    let send_order_result = timeout(TIMEOUT_DURATION, live_exit_long(stream_name.clone(), mode, order.clone())).await;
-                    match send_order_result {
-                        Ok(Ok(_)) => {} // Order placed successfully
-                        Ok(Err(e)) => {
-                            send_error_response(&sender, e, &stream_name).await;
-                        }
-                        Err(_) => {
-                            let timeout_error = create_order_rejected(&order, "Order placement timed out".to_string());
-                            send_error_response(&sender, timeout_error, &stream_name).await;
-                        }
-                    }
+            if let Err(reason) = check_position_limits(&order) {
+                let rejected = create_order_rejected(&order, reason);
+                send_error_response(&sender, rejected, &stream_name).await;
+                return;
+            }
+            if let Err(e) = place_order(stream_name.clone(), mode, order, order_type).await {
+                send_error_response(&sender, e, &stream_name).await;
+            }
+        }
+        OrderRequest::Basket { account, basket_id, orders, policy } => {
+            let mut accepted = vec![];
+            let mut rejected = vec![];
+            for order in orders {
+                match check_position_limits(&order) {
+                    Ok(_) => accepted.push(order),
+                    Err(reason) => rejected.push(create_order_rejected(&order, reason)),
                 }
-                OrderType::ExitShort => {
-                    let send_order_result = timeout(TIMEOUT_DURATION, live_exit_short(stream_name.clone(), mode, order.clone())).await;
-                    match send_order_result {
-                        Ok(Ok(_)) => {} // Order placed successfully
-                        Ok(Err(e)) => {
-                            send_error_response(&sender, e, &stream_name).await;
-                        }
-                        Err(_) => {
-                            let timeout_error = create_order_rejected(&order, "Order placement timed out".to_string());
-                            send_error_response(&sender, timeout_error, &stream_name).await;
+            }
+            if policy == BasketPolicy::AllOrNone && !rejected.is_empty() {
+                let reason = format!("Basket {} rejected: {}", basket_id, rejected_reason(&rejected[0]));
+                rejected.extend(accepted.drain(..).map(|order| create_order_rejected(&order, reason.clone())));
+            }
+            for event in rejected {
+                send_error_response(&sender, event, &stream_name).await;
+            }
+            // The brokerages have no atomic baskets, with all-or-none the orders already placed are cancelled when a later order is rejected.
+            let mut placed: Vec<OrderId> = vec![];
+            let mut orders = accepted.into_iter();
+            while let Some(order) = orders.next() {
+                let order_id = order.id.clone();
+                let order_type = order.order_type.clone();
+                match place_order(stream_name.clone(), mode, order, order_type).await {
+                    Ok(_) => placed.push(order_id),
+                    Err(e) => {
+                        let reason = format!("Basket {} rejected: {}", basket_id, rejected_reason(&e));
+                        send_error_response(&sender, e, &stream_name).await;
+                        if policy == BasketPolicy::AllOrNone {
+                            for order_id in placed.drain(..) {
+                                cancel_order(account.clone(), order_id).await;
+                            }
+                            for order in orders.by_ref() {
+                                send_error_response(&sender, create_order_rejected(&order, reason.clone()), &stream_name).await;
+                            }
                         }
                     }
                 }
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
@@ -19,9 +20,9 @@ use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum
 use ff_standard_lib::standardized_types::broker_enum::Brokerage;
 use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
 use ff_standard_lib::standardized_types::enums::{FuturesExchange, MarketType, OrderSide, PositionSide, StrategyMode};
-use ff_standard_lib::standardized_types::orders::{Order, OrderId, OrderType, OrderUpdateEvent, OrderUpdateType, TimeInForce};
-use ff_standard_lib::standardized_types::subscriptions::{Symbol, SymbolName};
-use ff_standard_lib::standardized_types::symbol_info::{FrontMonthInfo};
+use ff_standard_lib::standardized_types::orders::{Order, OrderId, OrderType, OrderUpdateEvent, OrderUpdateType, TimeInForce, TriggerSource};
+use ff_standard_lib::standardized_types::subscriptions::{DataSubscription, Symbol, SymbolName};
+use ff_standard_lib::standardized_types::symbol_info::{FrontMonthInfo, SymbolSearchResult};
 use ff_standard_lib::standardized_types::books::BookLevel;
 use ff_standard_lib::standardized_types::accounts::AccountId;
 use ff_standard_lib::StreamName;
@@ -47,14 +48,17 @@ use crate::rithmic_api::client_base::api_base::{RithmicApiClient, TEMPLATE_VERSI
 use crate::rithmic_api::client_base::credentials::RithmicCredentials;
 use crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_login::SysInfraType;
 #[allow(unused_imports)]
-use crate::rithmic_api::client_base::rithmic_proto_objects::rti::{request_tick_bar_replay, RequestAccountList, RequestAccountRmsInfo, RequestFrontMonthContract, RequestHeartbeat, RequestNewOrder, RequestPnLPositionUpdates, RequestReferenceData, RequestShowOrders, RequestSubscribeForOrderUpdates, RequestTickBarReplay, RequestTimeBarReplay, RequestTradeRoutes};
+use crate::rithmic_api::client_base::rithmic_proto_objects::rti::{request_tick_bar_replay, RequestAccountList, RequestAccountRmsInfo, RequestFrontMonthContract, RequestHeartbeat, RequestNewOrder, RequestPnLPositionUpdates, RequestReferenceData, RequestSearchSymbols, RequestShowOrders, RequestSubscribeForOrderUpdates, RequestTickBarReplay, RequestTimeBarReplay, RequestTradeRoutes};
 use crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_new_order::{OrderPlacement, PriceType, TransactionType};
+use crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_search_symbols::{InstrumentType, Pattern};
+use crate::rithmic_api::client_base::rithmic_proto_objects::rti::ResponseSearchSymbols;
 use crate::rithmic_api::plant_handlers::handler_loop::handle_rithmic_responses;
 use ff_standard_lib::product_maps::rithmic::maps::{get_exchange_by_symbol_name};
 use once_cell::sync::OnceCell;
 use ff_standard_lib::server_launch_options::ServerLaunchOptions;
 use ff_standard_lib::standardized_types::resolution::Resolution;
 use crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_time_bar_replay::{Direction, TimeOrder};
+use crate::rithmic_api::order_session::{OrderSession, ORDER_MESSAGES_PER_SECOND};
 
 lazy_static! {
     pub static ref RITHMIC_CLIENTS: DashMap<RithmicSystem , Arc<RithmicBrokerageClient>> = DashMap::with_capacity(16);
@@ -121,6 +125,11 @@ pub struct RithmicBrokerageClient {
     //products
     pub products: DashMap<MarketType, Vec<Symbol>>,
     pub historical_callbacks: DashMap<u64, oneshot::Sender<BTreeMap<DateTime<Utc>,BaseDataEnum>>>,
+    /// Market data subscription requests waiting on the plant response, the error is the reason text supplied by rithmic.
+    pub subscription_callbacks: DashMap<u64, oneshot::Sender<Result<(), String>>>,
+    /// Symbol search requests waiting on the plant, rithmic sends one response per instrument so the results are buffered until the last response.
+    pub symbol_search_callbacks: DashMap<u64, oneshot::Sender<Result<Vec<SymbolSearchResult>, String>>>,
+    pub symbol_search_results: DashMap<u64, Vec<SymbolSearchResult>>,
 
     //todo, since only 1 connection is used for data this could all be moved, we could have a rithmic data client and a rithmic broker client
     //subscribers
@@ -135,6 +144,8 @@ pub struct RithmicBrokerageClient {
     pub ask_book: DashMap<SymbolName, BTreeMap<u16, BookLevel>>,
 
     pub order_broadcaster: broadcast::Sender<DataServerResponse>,
+    /// Shares the order plant between the strategy streams, see `OrderSession`.
+    pub order_session: OrderSession,
 }
 
 impl RithmicBrokerageClient {
@@ -144,7 +155,7 @@ impl RithmicBrokerageClient {
         let brokerage = Brokerage::Rithmic(system.clone());
         let data_vendor = DataVendor::Rithmic;
         let credentials = RithmicBrokerageClient::rithmic_credentials(&brokerage)?;
-        println!("Activating {} {} on Rithmic Server: {}, Template Version: {}", credentials.user, credentials.system_name, credentials.server_name, TEMPLATE_VERSION);
+        tracing::info!("Activating {} {} on Rithmic Server: {}, Template Version: {}", credentials.user, credentials.system_name, credentials.server_name, TEMPLATE_VERSION);
         let data_folder = get_data_folder();
         let server_domains_toml = PathBuf::from(data_folder)
             .join("credentials")
@@ -190,6 +201,7 @@ impl RithmicBrokerageClient {
             candle_feed_broadcasters: Arc::new(Default::default()),
             ask_book: Default::default(),
             order_broadcaster: sender,
+            order_session: OrderSession::new(ORDER_MESSAGES_PER_SECOND),
             long_quantity: Default::default(),
             short_quantity: Default::default(),
             default_trade_route: DashMap::new(),
@@ -198,6 +210,9 @@ impl RithmicBrokerageClient {
             id_to_basket_id_map: Default::default(),
             pending_order_updates: Default::default(),
             historical_callbacks: Default::default(),
+            subscription_callbacks: Default::default(),
+            symbol_search_callbacks: Default::default(),
+            symbol_search_results: Default::default(),
         };
 
         Ok(client)
@@ -266,7 +281,7 @@ impl RithmicBrokerageClient {
                     Ok(_) => {}
                     Err(e) => {
                         disconnected = true;
-                        eprintln!("Callback error: {:?} Dumping subscriber: {}", e, stream_name);
+                        tracing::error!("Callback error: {:?} Dumping subscriber: {}", e, stream_name);
                     }
                 }
             }
@@ -287,6 +302,128 @@ impl RithmicBrokerageClient {
         self.send_message(plant, request).await;
     }
 
+    /// Sends a market data subscription request and waits for the plant to respond.
+    /// The request `user_msg` must contain the `callback_id` as the first element so the response can be matched.
+    /// Returns `Err(reason)` with the rithmic reason text if the request was rejected, eg. if the user does not have market data permissions for the exchange.
+    pub async fn send_subscription_request<T: ProstMessage>(&self, plant: &SysInfraType, callback_id: u64, request: T) -> Result<(), String> {
+        let (sender, receiver) = oneshot::channel();
+        self.subscription_callbacks.insert(callback_id, sender);
+        self.send_message(plant, request).await;
+        match timeout(Duration::from_secs(15), receiver).await {
+            Ok(receiver_result) => match receiver_result {
+                Ok(result) => result,
+                Err(e) => Err(format!("Receiver error at subscription callback recv: {}", e))
+            },
+            Err(_) => {
+                self.subscription_callbacks.remove(&callback_id);
+                Err("Subscription request timed out after 15 seconds".to_string())
+            }
+        }
+    }
+
+    /// Returns a subscription result to the waiting `send_subscription_request()` call.
+    pub fn return_subscription_callback(&self, user_msg: &Vec<String>, rp_code: &Vec<String>) {
+        let callback_id = match user_msg.get(0).and_then(|id| u64::from_str(id).ok()) {
+            Some(id) => id,
+            None => return
+        };
+        if let Some((_, sender)) = self.subscription_callbacks.remove(&callback_id) {
+            let _ = sender.send(rp_code_result(rp_code));
+        }
+    }
+
+    /// Searches the futures contracts containing the `query` on the ticker plant.
+    pub async fn search_symbols(&self, query: String) -> Result<Vec<SymbolSearchResult>, String> {
+        const PLANT: SysInfraType = SysInfraType::TickerPlant;
+        let callback_id = self.generate_callback_id().await;
+        let request = RequestSearchSymbols {
+            template_id: 109,
+            user_msg: vec![callback_id.to_string()],
+            search_text: Some(query),
+            exchange: None,
+            product_code: None,
+            instrument_type: Some(InstrumentType::Future.into()),
+            pattern: Some(Pattern::Contains.into()),
+        };
+        let (sender, receiver) = oneshot::channel();
+        self.symbol_search_callbacks.insert(callback_id, sender);
+        self.send_message(&PLANT, request).await;
+        match timeout(Duration::from_secs(8), receiver).await {
+            Ok(receiver_result) => match receiver_result {
+                Ok(result) => result,
+                Err(e) => Err(format!("Receiver error at symbol search callback recv: {}", e))
+            },
+            Err(_) => {
+                self.symbol_search_callbacks.remove(&callback_id);
+                self.symbol_search_results.remove(&callback_id);
+                Err("Symbol search timed out after 8 seconds".to_string())
+            }
+        }
+    }
+
+    /// Buffers a symbol search response and returns the results to the waiting `search_symbols()` call when the last response is received.
+    pub fn return_symbol_search(&self, msg: ResponseSearchSymbols) {
+        let callback_id = match msg.user_msg.first().and_then(|id| u64::from_str(id).ok()) {
+            Some(id) => id,
+            None => return
+        };
+        if let Some(result) = self.symbol_search_result(&msg) {
+            self.symbol_search_results.entry(callback_id).or_default().push(result);
+        }
+        // More responses are coming until the rp code is sent.
+        if msg.rp_code.is_empty() && !msg.rq_handler_rp_code.is_empty() {
+            return;
+        }
+        let results = self.symbol_search_results.remove(&callback_id).map(|(_, results)| results).unwrap_or_default();
+        if let Some((_, sender)) = self.symbol_search_callbacks.remove(&callback_id) {
+            let result = match results.is_empty() {
+                true => rp_code_result(&msg.rp_code).map(|_| results),
+                false => Ok(results)
+            };
+            let _ = sender.send(result);
+        }
+    }
+
+    fn symbol_search_result(&self, msg: &ResponseSearchSymbols) -> Option<SymbolSearchResult> {
+        let symbol_code = msg.symbol.clone()?;
+        let exchange = FuturesExchange::from_string(msg.exchange.as_ref()?).ok()?;
+        let symbol_name = msg.product_code.clone().unwrap_or_else(|| symbol_code.clone());
+        Some(SymbolSearchResult {
+            symbol: Symbol::new(symbol_name, self.data_vendor, MarketType::Futures(exchange)),
+            symbol_code: Some(symbol_code),
+            description: msg.symbol_name.clone().unwrap_or_default(),
+            exchange: msg.exchange.clone(),
+            expiration: msg.expiration_date.clone(),
+        })
+    }
+
+    /// Drops the receiver of a subscription which rithmic rejected, eg. due to missing market data permissions,
+    /// and returns a failed `SubscribeResponse` with the vendor reason so the strategy knows which entitlement is missing.
+    /// The broadcaster of the symbol is only removed if no other stream subscribed to it while the request was pending.
+    pub async fn subscription_rejected(&self, stream_name: &StreamName, subscription: DataSubscription, receiver: broadcast::Receiver<BaseDataEnum>, reason: String) -> DataServerResponse {
+        drop(receiver);
+        let broadcaster_map = match subscription.base_data_type {
+            BaseDataType::Ticks => Some(&self.tick_feed_broadcasters),
+            BaseDataType::Quotes => Some(&self.quote_feed_broadcasters),
+            BaseDataType::Candles => Some(&self.candle_feed_broadcasters),
+            _ => None
+        };
+        if let Some(broadcaster_map) = broadcaster_map {
+            let symbol = &subscription.symbol.name;
+            if broadcaster_map.remove_if(symbol, |_, broadcaster| broadcaster.receiver_count() == 0).is_some()
+                && subscription.base_data_type == BaseDataType::Quotes {
+                self.ask_book.remove(symbol);
+                self.bid_book.remove(symbol);
+            }
+        }
+        tracing::warn!("{} Subscription rejected: {}, {}", stream_name, subscription, reason);
+        DataServerResponse::SubscribeResponse {
+            success: false,
+            subscription: subscription.clone(),
+            reason: Some(format!("{} rejected subscription {}: {}", self.data_vendor, subscription, reason)),
+        }
+    }
+
     pub async fn generate_callback_id(&self) -> u64 {
         let mut callback_id= self.callback_id.lock().await;
         *callback_id = callback_id.wrapping_add(1);
@@ -320,7 +457,7 @@ impl RithmicBrokerageClient {
         match message.encode(&mut buf).map_err(|e| format!("Failed to encode message: {}", e)) {
             Ok(_) => {}
             Err(e) => {
-                eprintln!("Failed to encode rithmic proto message: {}", e);
+                tracing::error!("Failed to encode rithmic proto message: {}", e);
                 return;
             }
         }
@@ -342,7 +479,7 @@ impl RithmicBrokerageClient {
                         },
                         _ => {}
                     }
-                    eprintln!("Failed to send message to {:?}: {}. Retrying...", plant, e)
+                    tracing::error!("Failed to send message to {:?}: {}. Retrying...", plant, e)
                 },
             }
             match write_stream.flush().await {
@@ -397,7 +534,7 @@ impl RithmicBrokerageClient {
                             };
                             let mut buf = Vec::new();
                             if let Err(e) = request.encode(&mut buf) {
-                                eprintln!("Failed to encode message for {:?}: {}", plant, e);
+                                tracing::error!("Failed to encode message for {:?}: {}", plant, e);
                                 continue 'heartbeat_loop;
                             }
 
@@ -412,7 +549,7 @@ impl RithmicBrokerageClient {
                                     //eprintln!("Heartbeat sent for {:?} at {:?}", plant, now);
                                 },
                                 Err(e) => {
-                                    eprintln!("Failed to send heartbeat to {:?}: {}", plant, e);
+                                    tracing::error!("Failed to send heartbeat to {:?}: {}", plant, e);
                                     break 'heartbeat_loop;
                                 }
                             }
@@ -422,7 +559,7 @@ impl RithmicBrokerageClient {
                         }
                     }
                     _ = shutdown_receiver.recv() => {
-                        println!("Shutting down heartbeat task for system {:?}", plant);
+                        tracing::info!("Shutting down heartbeat task for system {:?}", plant);
                         break 'heartbeat_loop;
                     }
                 }
@@ -708,10 +845,10 @@ impl RithmicBrokerageClient {
             TimeInForce::FOK => (crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_bracket_order::Duration::Fok.into(), None, None),
             TimeInForce::GTC => (crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_bracket_order::Duration::Gtc.into(), None, None),
             TimeInForce::Day => (crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_bracket_order::Duration::Day.into(), None, None),
-            TimeInForce::Time(ref time_stamp) => {
-                let cancel_time = match DateTime::<Utc>::from_timestamp(*time_stamp, 0) {
+            TimeInForce::Time(_) | TimeInForce::TimeNanos(_) => {
+                let cancel_time = match order.time_in_force.expiry_time() {
                     Some(dt) => dt,
-                    None => return Err(Self::reject_order(&order, format!("Failed to parse time stamp: {}", time_stamp)))
+                    None => return Err(Self::reject_order(&order, format!("Failed to parse time stamp: {:?}", order.time_in_force)))
                 };
 
                 (crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_bracket_order::Duration::Gtc.into(),
@@ -722,8 +859,8 @@ impl RithmicBrokerageClient {
 
         // Rest of the function remains the same...
         match order.side {
-            OrderSide::Buy => eprintln!("Buying {}" , order.quantity_open),
-            OrderSide::Sell => eprintln!("Selling {}" , order.quantity_open),
+            OrderSide::Buy => tracing::info!("Buying {}" , order.quantity_open),
+            OrderSide::Sell => tracing::info!("Selling {}" , order.quantity_open),
         }
 
         let trigger_price = match order.trigger_price {
@@ -746,6 +883,12 @@ impl RithmicBrokerageClient {
             }
         };
 
+        // Rithmic stops are triggered by the exchange on the last trade price.
+        let is_triggered = matches!(order.order_type, OrderType::MarketIfTouched | OrderType::StopMarket | OrderType::StopLimit);
+        if is_triggered && order.trigger_source() != TriggerSource::Last {
+            return Err(Self::reject_order(&order, format!("Rithmic orders can only trigger on the last trade price, not {}", order.trigger_source())));
+        }
+
         let order_type = match order.order_type {
             OrderType::Limit => 1,
             OrderType::Market => 2,
@@ -796,8 +939,10 @@ impl RithmicBrokerageClient {
         };
 
         if let Some(account_map) = self.last_tag.get(&order.account.account_id) {
-            account_map.insert(details.symbol_code, order.tag.clone());
+            account_map.insert(details.symbol_code.clone(), order.tag.clone());
         }
+        self.order_session.register_order(stream_name, &order.account.account_id, &details.symbol_code);
+        self.order_session.acquire(stream_name).await;
         self.send_message(&SysInfraType::OrderPlant, req).await;
         Ok(())
     }
@@ -848,11 +993,14 @@ impl RithmicBrokerageClient {
 
         //this is used to update positions when synchronise positions is used
         if let Some(account_map) = self.last_tag.get(&order.account.account_id) {
-            account_map.insert(details.symbol_code, order.tag.clone());
+            account_map.insert(details.symbol_code.clone(), order.tag.clone());
         }
+        self.order_session.register_order(stream_name, &order.account.account_id, &details.symbol_code);
+        self.order_session.acquire(stream_name).await;
         self.send_message(&SysInfraType::OrderPlant, req).await;
     }
 
+    /// Systems which already have a client are skipped, so calling this again only connects the credentials added since the last call.
     pub(crate) async fn init_rithmic_apis(options: ServerLaunchOptions) {
         let options = options;
         if options.disable_rithmic_server != 0 {
@@ -863,6 +1011,7 @@ impl RithmicBrokerageClient {
         if toml_files.is_empty() {
             return;
         }
+        let connected_systems: Vec<RithmicSystem> = RITHMIC_CLIENTS.iter().map(|client| client.key().clone()).collect();
 
         // First, find the system to use for history and ticker plants
         let market_data_system = Arc::new(
@@ -891,7 +1040,7 @@ impl RithmicBrokerageClient {
 
         let init_tasks = toml_files.into_iter().filter_map(|file| {
             let market_data_system = Arc::clone(&market_data_system);
-            RithmicSystem::from_file_string(file.as_str()).map(|system| {
+            RithmicSystem::from_file_string(file.as_str()).filter(|system| !connected_systems.contains(system)).map(|system| {
                 task::spawn(async move {
 
                     match RithmicBrokerageClient::new(system).await {
@@ -905,7 +1054,7 @@ impl RithmicBrokerageClient {
                                         handle_rithmic_responses(client.clone(), receiver, plant_type);
                                     }
                                     Err(e) => {
-                                        eprintln!("Failed to connect {:?} for system {}, reason: {}",
+                                        tracing::error!("Failed to connect {:?} for system {}, reason: {}",
                                                   plant_type, system, e);
                                     }
                                 }
@@ -920,7 +1069,7 @@ impl RithmicBrokerageClient {
                                             RITHMIC_DATA_IS_CONNECTED.store(true, Ordering::SeqCst);
                                         }
                                         Err(e) => {
-                                            eprintln!("Failed to connect {:?} for system {}, reason: {}",
+                                            tracing::error!("Failed to connect {:?} for system {}, reason: {}",
                                                       plant_type, system, e);
                                             RITHMIC_DATA_IS_CONNECTED.store(false, Ordering::SeqCst);
                                         }
@@ -931,7 +1080,7 @@ impl RithmicBrokerageClient {
                             RITHMIC_CLIENTS.insert(system, client.clone());
                         }
                         Err(e) => {
-                            eprintln!("Failed to create rithmic client for: {}, reason: {}", system, e);
+                            tracing::error!("Failed to create rithmic client for: {}, reason: {}", system, e);
                         }
                     }
 
@@ -944,6 +1093,9 @@ impl RithmicBrokerageClient {
 
         // Send RMS info and trade routes requests for all clients
         for api in RITHMIC_CLIENTS.iter() {
+            if connected_systems.contains(api.key()) {
+                continue;
+            }
             let api = api.value().clone();
             let rms_req = RequestAccountRmsInfo {
                 template_id: 304,
@@ -1033,3 +1185,18 @@ pub struct CommonRithmicOrderDetails {
     pub route: String,
     pub quantity: i32
 }
+
+/// Rithmic responses contain an `rp_code` where the first element is "0" on success, otherwise the following elements contain the reason text.
+pub fn rp_code_result(rp_code: &Vec<String>) -> Result<(), String> {
+    match rp_code.first() {
+        None => Ok(()),
+        Some(code) if code == "0" => Ok(()),
+        Some(code) => {
+            let reason = rp_code.iter().skip(1).cloned().collect::<Vec<String>>().join(", ");
+            match reason.is_empty() {
+                true => Err(format!("Rithmic rejected the request with code: {}", code)),
+                false => Err(format!("Rithmic rejected the request with code {}: {}", code, reason))
+            }
+        }
+    }
+}
@@ -18,6 +18,7 @@ use crate::request_handlers::RESPONSE_SENDERS;
 use crate::rithmic_api::api_client::RithmicBrokerageClient;
 use crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_login::SysInfraType;
 use crate::rithmic_api::client_base::rithmic_proto_objects::rti::{RequestAccountRmsInfo, RequestCancelAllOrders, RequestCancelOrder, RequestExitPosition, RequestModifyOrder};
+use crate::rithmic_api::plant_handlers::handle_order_plant::ID_TO_STREAM_NAME_MAP;
 
 #[async_trait]
 impl BrokerApiResponse for RithmicBrokerageClient {
@@ -123,6 +124,7 @@ impl BrokerApiResponse for RithmicBrokerageClient {
     async fn logout_command(&self, stream_name: StreamName) {
         //todo handle dynamically from server using stream name to remove subscriptions and callbacks
         self.callbacks.remove(&stream_name);
+        self.order_session.remove_stream(stream_name).await;
     }
 
     async fn commission_info_response(&self, _mode: StrategyMode, _stream_name: StreamName, symbol_name: SymbolName, callback_id: u64) -> DataServerResponse {
@@ -265,7 +267,7 @@ impl BrokerApiResponse for RithmicBrokerageClient {
                     if let Some(sender) = RESPONSE_SENDERS.get(&stream_name) {
                         match sender.send(order_event).await {
                             Ok(_) => {}
-                            Err(e) => eprintln!("failed to forward ResponseNewOrder 313 to strategy stream {}", e)
+                            Err(e) => tracing::error!("failed to forward ResponseNewOrder 313 to strategy stream {}", e)
                         }
                     }
                 }
@@ -333,7 +335,7 @@ impl BrokerApiResponse for RithmicBrokerageClient {
                     if let Some(sender) = RESPONSE_SENDERS.get(&stream_name) {
                         match sender.send(order_event).await {
                             Ok(_) => {}
-                            Err(e) => eprintln!("failed to forward ResponseNewOrder 313 to strategy stream {}", e)
+                            Err(e) => tracing::error!("failed to forward ResponseNewOrder 313 to strategy stream {}", e)
                         }
                     }
                 }
@@ -407,7 +409,7 @@ impl BrokerApiResponse for RithmicBrokerageClient {
             for symbol in tag_map.value() {
                 let exchange = match get_exchange_by_symbol_name(symbol.key()) {
                     None => {
-                        eprintln!("Rithmic `flatten_all_for()` error, No exchange found for symbol: {}", symbol.key());
+                        tracing::error!("Rithmic `flatten_all_for()` error, No exchange found for symbol: {}", symbol.key());
                         continue
                     },
                     Some(exchange) => exchange
@@ -431,7 +433,7 @@ impl BrokerApiResponse for RithmicBrokerageClient {
             for symbol in tag_map.value() {
                 let exchange = match get_exchange_by_symbol_name(symbol.key()) {
                     None => {
-                        eprintln!("Rithmic `flatten_all_for()` error, No exchange found for symbol: {}", symbol.key());
+                        tracing::error!("Rithmic `flatten_all_for()` error, No exchange found for symbol: {}", symbol.key());
                         continue
                     },
                     Some(exchange) => exchange
@@ -480,6 +482,13 @@ impl BrokerApiResponse for RithmicBrokerageClient {
             },
         };
 
+        // Modifications use the share of the stream which placed the order
+        let stream_name = ID_TO_STREAM_NAME_MAP.get(&account.brokerage)
+            .and_then(|stream_map| stream_map.get(&order_id).map(|stream_name| *stream_name.value()));
+        if let Some(stream_name) = stream_name {
+            self.order_session.acquire(stream_name).await;
+        }
+
         match self.orders_open.get(&order_id) {
             None => {
                 Err(OrderUpdateEvent::OrderUpdateRejected {
@@ -490,6 +499,14 @@ impl BrokerApiResponse for RithmicBrokerageClient {
                 })
             }
             Some(order) => {
+                if let Err(reason) = order.validate_update(&update) {
+                    return Err(OrderUpdateEvent::OrderUpdateRejected {
+                        account,
+                        order_id,
+                        reason,
+                        time: Utc::now().to_string(),
+                    })
+                }
                 let (quantity, limit_price, stop_price) = match update {
                     OrderUpdateType::Quantity(q) => match q.to_i32() {
                         None => {
@@ -113,7 +113,7 @@ impl RithmicApiClient {
             Ok((stream, response)) => (stream, response),
             Err(e) => return Err(RithmicApiError::ServerErrorDebug(format!("Failed to connect to rithmic: {}", e)))
         };
-        println!("Rithmic connection established: {:?}", response);
+        tracing::info!("Rithmic connection established: {:?}", response);
         // Rithmic System Info Request 16 From Client
         let request = RequestRithmicSystemInfo {
             template_id: 16,
@@ -124,7 +124,7 @@ impl RithmicApiClient {
         // Rithmic System Info Response 17
         // Step 2: Read the full message based on the length
         let message: ResponseRithmicSystemInfo = RithmicApiClient::read_single_protobuf_message(&mut stream).await?;
-        println!("{:?}", message);
+        tracing::info!("{:?}", message);
         // Now we have the system name we can do the handshake
         Ok(message.system_name)
     }
@@ -172,12 +172,12 @@ impl RithmicApiClient {
 
         // Login Response 11 From Server
         let response: ResponseLogin = RithmicApiClient::read_single_protobuf_message(&mut stream).await?;
-        println!("{:?}:{:?}", response, plant);
+        tracing::info!("{:?}:{:?}", response, plant);
         if response.rp_code.is_empty() {
-            eprintln!("{:?}",response);
+            tracing::debug!("{:?}",response);
         }
         if response.rp_code[0] != "0".to_string() {
-            eprintln!("{:?}",response);
+            tracing::debug!("{:?}",response);
         }
 
         match response.fcm_id {
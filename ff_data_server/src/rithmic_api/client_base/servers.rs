@@ -77,8 +77,8 @@ pub(crate) fn server_domains(file_path: String) -> Result<BTreeMap<RithmicServer
     // Parse the TOML string
     let toml_value: Value = toml::from_str(cleaned_str)
         .map_err(|e| {
-            println!("TOML parse error: {:?}", e);
-            println!("TOML content:\n{}", cleaned_str);
+            tracing::info!("TOML parse error: {:?}", e);
+            tracing::info!("TOML content:\n{}", cleaned_str);
             RithmicApiError::TomlParse(e)
         })?;
 
@@ -86,7 +86,7 @@ pub(crate) fn server_domains(file_path: String) -> Result<BTreeMap<RithmicServer
     let rithmic_servers = toml_value.get("rithmic_servers")
         .and_then(|v| v.as_table())
         .ok_or_else(|| {
-            println!("Missing or invalid 'rithmic_servers' table in TOML");
+            tracing::info!("Missing or invalid 'rithmic_servers' table in TOML");
             RithmicApiError::InvalidConfig("Missing 'rithmic_servers' table".to_string())
         })?;
 
@@ -96,12 +96,12 @@ pub(crate) fn server_domains(file_path: String) -> Result<BTreeMap<RithmicServer
         .map(|(k, v)| {
             let server = RithmicServer::from_str(k)
                 .map_err(|e| {
-                    println!("Invalid server name: {}", k);
+                    tracing::info!("Invalid server name: {}", k);
                     RithmicApiError::InvalidServerName(e)
                 })?;
             let domain = v.as_str()
                 .ok_or_else(|| {
-                    println!("Invalid domain for server {}: {:?}", k, v);
+                    tracing::info!("Invalid domain for server {}: {:?}", k, v);
                     RithmicApiError::InvalidConfig(format!("Invalid domain for server {}", k))
                 })?
                 .to_string();
@@ -4,3 +4,4 @@ pub mod plant_handlers;
 pub mod broker_api_response;
 pub mod vendor_api_response;
 pub mod client_base;
+pub mod order_session;
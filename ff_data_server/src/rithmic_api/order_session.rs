@@ -0,0 +1,181 @@
+use std::time::Duration;
+use ahash::{AHashMap, AHashSet};
+use dashmap::DashMap;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Instant};
+use ff_standard_lib::messages::data_server_messaging::DataServerResponse;
+use ff_standard_lib::standardized_types::accounts::AccountId;
+use ff_standard_lib::standardized_types::enums::PositionSide;
+use ff_standard_lib::standardized_types::subscriptions::SymbolCode;
+use ff_standard_lib::StreamName;
+use crate::request_handlers::RESPONSE_SENDERS;
+
+/// The order plant messages rithmic accepts per second from one session, shared by every strategy trading through the session.
+pub const ORDER_MESSAGES_PER_SECOND: f64 = 20.0;
+
+/// Shares the single rithmic order plant session of a system between the strategies connected to the server.
+///
+/// Rithmic limits the concurrent sessions of a user, so every strategy stream sends its orders through the same `RithmicBrokerageClient`.
+/// The stream name is carried in the `user_msg` and `window_name` of each order, order updates are routed back to the stream which placed the order,
+/// and position updates are only sent to the streams which have traded the account and symbol.
+///
+/// The order message rate is shared fairly, each stream with orders in flight gets an equal share of `ORDER_MESSAGES_PER_SECOND`,
+/// so a strategy sending a burst of orders can not delay the orders of the other strategies.
+/// Cancels and flattens are never throttled.
+pub struct OrderSession {
+    messages_per_second: f64,
+    /// The streams which have placed orders for an account and symbol code, these streams receive the position updates.
+    position_owners: DashMap<(AccountId, SymbolCode), AHashSet<StreamName>>,
+    buckets: Mutex<AHashMap<StreamName, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl OrderSession {
+    pub fn new(messages_per_second: f64) -> Self {
+        Self {
+            messages_per_second,
+            position_owners: DashMap::new(),
+            buckets: Mutex::new(AHashMap::new()),
+        }
+    }
+
+    /// Records the stream as an owner of the account and symbol, so it receives the position updates.
+    pub fn register_order(&self, stream_name: StreamName, account_id: &AccountId, symbol_code: &SymbolCode) {
+        self.position_owners
+            .entry((account_id.clone(), symbol_code.clone()))
+            .or_default()
+            .insert(stream_name);
+    }
+
+    /// The streams which receive the position updates of the account and symbol.
+    pub fn owners(&self, account_id: &AccountId, symbol_code: &SymbolCode) -> Vec<StreamName> {
+        match self.position_owners.get(&(account_id.clone(), symbol_code.clone())) {
+            Some(owners) => owners.iter().cloned().collect(),
+            None => vec![],
+        }
+    }
+
+    /// Sends the position update to the streams which own the position, positions opened outside of fund forge have no owner and are sent to every stream.
+    /// The owners are released once the position is flat.
+    pub async fn send_position_update(&self, account_id: &AccountId, symbol_code: &SymbolCode, side: PositionSide, update: DataServerResponse) {
+        let owners = self.owners(account_id, symbol_code);
+        let streams: Vec<StreamName> = match owners.is_empty() {
+            true => RESPONSE_SENDERS.iter().map(|sender| *sender.key()).collect(),
+            false => owners,
+        };
+        for stream_name in streams {
+            let sender = match RESPONSE_SENDERS.get(&stream_name) {
+                Some(sender) => sender.value().clone(),
+                None => continue,
+            };
+            if let Err(e) = sender.send(update.clone()).await {
+                tracing::error!("failed to forward position update to strategy stream {}: {}", stream_name, e);
+            }
+        }
+        if side == PositionSide::Flat {
+            self.position_owners.remove(&(account_id.clone(), symbol_code.clone()));
+        }
+    }
+
+    /// Waits for the stream's share of the order message rate.
+    pub async fn acquire(&self, stream_name: StreamName) {
+        loop {
+            let wait = self.try_acquire(stream_name, Instant::now()).await;
+            match wait {
+                None => return,
+                Some(wait) => sleep(wait).await,
+            }
+        }
+    }
+
+    /// Takes a message from the stream's bucket, or returns the time until the bucket has a message.
+    /// Each bucket refills at an equal share of the session rate and holds at most one second of its share, the share is recalculated as streams start and stop sending.
+    async fn try_acquire(&self, stream_name: StreamName, now: Instant) -> Option<Duration> {
+        let mut buckets = self.buckets.lock().await;
+        // Streams which have refilled a full second of their share are idle, they no longer reduce the share of the active streams.
+        let idle_after = Duration::from_secs(1);
+        buckets.retain(|name, bucket| *name == stream_name || now.saturating_duration_since(bucket.updated) < idle_after);
+        let active_streams = match buckets.contains_key(&stream_name) {
+            true => buckets.len(),
+            false => buckets.len() + 1,
+        };
+        let share = self.messages_per_second / active_streams as f64;
+        let capacity = share.max(1.0);
+
+        let bucket = buckets.entry(stream_name).or_insert(Bucket { tokens: capacity, updated: now });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * share).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return None;
+        }
+        Some(Duration::from_secs_f64((1.0 - bucket.tokens) / share))
+    }
+
+    /// Forgets the stream when its strategy disconnects.
+    pub async fn remove_stream(&self, stream_name: StreamName) {
+        self.buckets.lock().await.remove(&stream_name);
+        self.position_owners.retain(|_, owners| {
+            owners.remove(&stream_name);
+            !owners.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rate_is_shared_between_streams() {
+        let session = OrderSession::new(10.0);
+        let start = Instant::now();
+
+        // A single stream can use the whole rate
+        for _ in 0..10 {
+            assert_eq!(session.try_acquire(1, start).await, None);
+        }
+        assert!(session.try_acquire(1, start).await.is_some());
+
+        // A second stream halves the share of the first, but is not starved by the first stream's burst
+        let later = start + Duration::from_millis(500);
+        for _ in 0..5 {
+            assert_eq!(session.try_acquire(2, later).await, None);
+        }
+        assert!(session.try_acquire(2, later).await.is_some());
+        // The first stream refilled half a second at its new share
+        assert_eq!(session.try_acquire(1, later).await, None);
+        assert_eq!(session.try_acquire(1, later).await, None);
+        assert!(session.try_acquire(1, later).await.is_some());
+
+        // Once the second stream is idle the first stream gets the whole rate again
+        let idle = later + Duration::from_secs(2);
+        for _ in 0..10 {
+            assert_eq!(session.try_acquire(1, idle).await, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_position_owners() {
+        let session = OrderSession::new(10.0);
+        let account = "S1Sep246906077".to_string();
+        session.register_order(1, &account, &"MNQZ4".to_string());
+        session.register_order(2, &account, &"MNQZ4".to_string());
+        session.register_order(2, &account, &"MESZ4".to_string());
+
+        let mut owners = session.owners(&account, &"MNQZ4".to_string());
+        owners.sort();
+        assert_eq!(owners, vec![1, 2]);
+        assert!(session.owners(&account, &"M2KZ4".to_string()).is_empty());
+
+        session.remove_stream(2).await;
+        assert_eq!(session.owners(&account, &"MNQZ4".to_string()), vec![1]);
+        assert!(session.owners(&account, &"MESZ4".to_string()).is_empty());
+    }
+}
@@ -16,14 +16,16 @@ use rust_decimal_macros::dec;
 use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType;
 use ff_standard_lib::standardized_types::base_data::candle::Candle;
+use ff_standard_lib::standardized_types::base_data::data_quality::DataQuality;
 use ff_standard_lib::standardized_types::base_data::tick::{Aggressor, Tick};
 use ff_standard_lib::standardized_types::base_data::traits::BaseData;
 use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
 use ff_standard_lib::standardized_types::enums::{FuturesExchange, MarketType};
-use ff_standard_lib::standardized_types::new_types::{Price, Volume};
+use ff_standard_lib::standardized_types::new_types::Volume;
 use ff_standard_lib::standardized_types::resolution::Resolution;
 use ff_standard_lib::standardized_types::subscriptions::{CandleType, Symbol};
 use crate::rithmic_api::api_client::RithmicBrokerageClient;
+use crate::rithmic_api::plant_handlers::rithmic_price;
 
 lazy_static! {
     //we use the callback id as key for historical data
@@ -45,35 +47,35 @@ pub async fn match_history_plant_id(
             if let Ok(msg) = Reject::decode(&message_buf[..]) {
                 // Login Response
                 // From Server
-                println!("Reject Response (Template ID: 11) from Server: {:?}", msg);
+                tracing::info!("Reject Response (Template ID: 11) from Server: {:?}", msg);
             }
         }
         11 => {
             if let Ok(msg) = ResponseLogin::decode(&message_buf[..]) {
                 // Login Response
                 // From Server
-                println!("Login Response (Template ID: 11) from Server: {:?}", msg);
+                tracing::info!("Login Response (Template ID: 11) from Server: {:?}", msg);
             }
         },
         13 => {
             if let Ok(msg) = ResponseLogout::decode(&message_buf[..]) {
                 // Logout Response
                 // From Server
-                println!("Logout Response (Template ID: 13) from Server: {:?}", msg);
+                tracing::info!("Logout Response (Template ID: 13) from Server: {:?}", msg);
             }
         },
         15 => {
             if let Ok(msg) = ResponseReferenceData::decode(&message_buf[..]) {
                 // Reference Data Response
                 // From Server
-                println!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
+                tracing::info!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
             }
         },
         17 => {
             if let Ok(msg) = ResponseRithmicSystemInfo::decode(&message_buf[..]) {
                 // Rithmic System Info Response
                 // From Server
-                println!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
+                tracing::info!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
             }
         },
         19 => {
@@ -102,7 +104,8 @@ pub async fn match_history_plant_id(
             if let Ok(msg) = ResponseTimeBarUpdate::decode(&message_buf[..]) {
                 // Time Bar Update Response
                 // From Server
-                println!("Time Bar Update Response (Template ID: 201) from Server: {:?}", msg);
+                tracing::info!("Time Bar Update Response (Template ID: 201) from Server: {:?}", msg);
+                client.return_subscription_callback(&msg.user_msg, &msg.rp_code);
             }
         },
         203 => {
@@ -148,7 +151,7 @@ pub async fn match_history_plant_id(
             if let Ok(msg) = ResponseTickBarUpdate::decode(&message_buf[..]) {
                 // Tick Bar Update Response
                 // From Server
-                println!("Tick Bar Update Response (Template ID: 205) from Server: {:?}", msg);
+                tracing::info!("Tick Bar Update Response (Template ID: 205) from Server: {:?}", msg);
             }
         },
         207 => {
@@ -234,21 +237,21 @@ pub async fn match_history_plant_id(
             if let Ok(msg) = RequestVolumeProfileMinuteBars::decode(&message_buf[..]) {
                 // Volume Profile Minute Bars Request
                 // From Client
-                println!("Volume Profile Minute Bars Request (Template ID: 208) from Client: {:?}", msg);
+                tracing::info!("Volume Profile Minute Bars Request (Template ID: 208) from Client: {:?}", msg);
             }
         },
         209 => {
             if let Ok(msg) = ResponseVolumeProfileMinuteBars::decode(&message_buf[..]) {
                 // Volume Profile Minute Bars Response
                 // From Server
-                println!("Volume Profile Minute Bars Response (Template ID: 209) from Server: {:?}", msg);
+                tracing::info!("Volume Profile Minute Bars Response (Template ID: 209) from Server: {:?}", msg);
             }
         },
         211 => {
             if let Ok(msg) = ResponseResumeBars::decode(&message_buf[..]) {
                 // Resume Bars Response
                 // From Server
-                println!("Resume Bars Response (Template ID: 211) from Server: {:?}", msg);
+                tracing::info!("Resume Bars Response (Template ID: 211) from Server: {:?}", msg);
             }
         },
         250 => {
@@ -263,10 +266,10 @@ pub async fn match_history_plant_id(
             if let Ok(msg) = TickBar::decode(&message_buf[..]) {
                 // Tick Bar
                 // From Server
-                println!("Tick Bar (Template ID: 251) from Server: {:?}", msg);
+                tracing::info!("Tick Bar (Template ID: 251) from Server: {:?}", msg);
             }
         },
-        _ => println!("No match for template_id: {}", template_id)
+        _ => tracing::info!("No match for template_id: {}", template_id)
     }
 }
 
@@ -286,7 +289,7 @@ async fn handle_candle(client: Arc<RithmicBrokerageClient>, msg: TimeBar) {
     let exchange = match msg.exchange.as_deref().and_then(|e| FuturesExchange::from_string(e).ok()) {
         Some(ex) => ex,
         None => {
-            eprintln!("Error deserializing Exchange for symbol {}", symbol);
+            tracing::error!("Error deserializing Exchange for symbol {}", symbol);
             return;
         }
     };
@@ -308,22 +311,22 @@ async fn handle_candle(client: Arc<RithmicBrokerageClient>, msg: TimeBar) {
             MarketType::Futures(exchange),
         );
 
-        let high = match msg.high_price.and_then(Decimal::from_f64) {
+        let high = match msg.high_price.and_then(|price| rithmic_price(&symbol, exchange, price)) {
             Some(price) => price,
             None => return,  // Exit if high price is invalid
         };
 
-        let low = match msg.low_price.and_then(Decimal::from_f64) {
+        let low = match msg.low_price.and_then(|price| rithmic_price(&symbol, exchange, price)) {
             Some(price) => price,
             None => return,  // Exit if low price is invalid
         };
 
-        let open = match msg.open_price.and_then(Decimal::from_f64) {
+        let open = match msg.open_price.and_then(|price| rithmic_price(&symbol, exchange, price)) {
             Some(price) => price,
             None => return,  // Exit if open price is invalid
         };
 
-        let close = match msg.close_price.and_then(Decimal::from_f64) {
+        let close = match msg.close_price.and_then(|price| rithmic_price(&symbol, exchange, price)) {
             Some(price) => price,
             None => return,  // Exit if close price is invalid
         };
@@ -380,6 +383,7 @@ async fn handle_candle(client: Arc<RithmicBrokerageClient>, msg: TimeBar) {
             is_closed: true,
             resolution,
             candle_type: CandleType::CandleStick,
+            quality: DataQuality::default(),
         });
 
 
@@ -419,7 +423,7 @@ async fn handle_candle(client: Arc<RithmicBrokerageClient>, msg: TimeBar) {
 
                 const PLANT: SysInfraType = SysInfraType::HistoryPlant;
                 client.send_message(&PLANT, req).await;
-                println!("Unsubscribed {} Candles {}, {}", symbol, bar_type, period);
+                tracing::info!("Unsubscribed {} Candles {}, {}", symbol, bar_type, period);
             }
         }
     }
@@ -438,11 +442,6 @@ fn parse_tick_response(response: &ResponseTickBarReplay) -> Option<Tick> {
     let volume = response.volume?;
     let price = response.close_price?;
 
-    let price = match Decimal::from_f64(price) {
-        Some(price) => price,
-        None => return None,
-    };
-
     let volume = match Decimal::from_u64(volume) {
         Some(volume) => volume,
         None => return None,
@@ -470,12 +469,16 @@ fn parse_tick_response(response: &ResponseTickBarReplay) -> Option<Tick> {
         Err(_) => return None,
     };
 
+    let price = rithmic_price(symbol_name, exchange, price)?;
+
     Some(Tick {
         symbol: Symbol::new(symbol_name.clone(), DataVendor::Rithmic, MarketType::Futures(exchange)),
         price,
         time: datetime.to_string(),
         volume,
         aggressor,
+        quality: DataQuality::default(),
+        conditions: None,
     })
 }
 
@@ -496,12 +499,15 @@ fn parse_time_bar(response: &ResponseTimeBarReplay) -> Option<Candle> {
     let volume = response.volume?;
     let bid_volume = response.bid_volume.unwrap_or_default();
     let ask_volume = response.ask_volume.unwrap_or_default();
-    let range = high - low;
     let exchange = response.exchange.as_ref()?;
     let exchange = match FuturesExchange::from_string(exchange) {
         Ok(exchange) => exchange,
         Err(_) => return None,
     };
+    let open = rithmic_price(symbol, exchange, open)?;
+    let high = rithmic_price(symbol, exchange, high)?;
+    let low = rithmic_price(symbol, exchange, low)?;
+    let close = rithmic_price(symbol, exchange, close)?;
 
     // Get timestamp from marker field
     let marker = response.marker?;
@@ -558,17 +564,18 @@ fn parse_time_bar(response: &ResponseTimeBarReplay) -> Option<Candle> {
 
     Some(Candle {
         symbol: Symbol::new(symbol.clone(), DataVendor::Rithmic, MarketType::Futures(exchange)),
-        high: Price::from_f64(high).unwrap(),
-        low: Price::from_f64(low).unwrap(),
-        open: Price::from_f64(open).unwrap(),
-        close: Price::from_f64(close).unwrap(),
+        high,
+        low,
+        open,
+        close,
         volume: Volume::from_u64(volume).unwrap(),
         ask_volume:Volume::from_u64(ask_volume).unwrap(),
         bid_volume: Volume::from_u64(bid_volume).unwrap(),
-        range: Price::from_f64(range).unwrap(),
+        range: high - low,
         time: datetime.to_string(),
         is_closed: true,
         resolution,
         candle_type: CandleType::CandleStick,
+        quality: DataQuality::default(),
     })
 }
@@ -17,13 +17,15 @@ use ff_standard_lib::standardized_types::broker_enum::Brokerage;
 use ff_standard_lib::standardized_types::enums::{FuturesExchange, OrderSide};
 use ff_standard_lib::standardized_types::accounts::Currency;
 use ff_standard_lib::standardized_types::new_types::{Price, Volume};
-use ff_standard_lib::standardized_types::orders::{OrderId, OrderState, OrderUpdateEvent, OrderUpdateType};
+use ff_standard_lib::standardized_types::orders::{OrderId, OrderState, OrderUpdateEvent};
 use ff_standard_lib::StreamName;
 use crate::request_handlers::RESPONSE_SENDERS;
 use crate::rithmic_api::api_client::RithmicBrokerageClient;
 use crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_login::SysInfraType;
 use crate::rithmic_api::plant_handlers::create_datetime;
 use crate::rithmic_api::plant_handlers::handler_loop::send_updates;
+use crate::server_features::webhooks::notify_webhooks;
+use crate::server_features::trade_copier::copy_trades;
 use ff_standard_lib::product_maps::rithmic::maps::find_base_symbol;
 
 type BasketId = String;
@@ -45,21 +47,21 @@ pub async fn match_order_plant_id(
             if let Ok(msg) = Reject::decode(&message_buf[..]) {
                 // Login Response
                 // From Server
-                println!("Reject Response (Template ID: 11) from Server: {:?}", msg);
+                tracing::info!("Reject Response (Template ID: 11) from Server: {:?}", msg);
             }
         }
         15 => {
             if let Ok(msg) = ResponseReferenceData::decode(&message_buf[..]) {
                 // Reference Data Response
                 // From Server
-                println!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
+                tracing::info!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
             }
         },
         17 => {
             if let Ok(msg) = ResponseRithmicSystemInfo::decode(&message_buf[..]) {
                 // Rithmic System Info Response
                 // From Server
-                println!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
+                tracing::info!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
             }
         },
         19 => {
@@ -88,19 +90,19 @@ pub async fn match_order_plant_id(
             if let Ok(msg) = ResponseLoginInfo::decode(&message_buf[..]) {
                 // Account List Response
                 // From Server
-                println!("Response Login Info (Template ID: 303) from Server: {:?}", msg);
+                tracing::info!("Response Login Info (Template ID: 303) from Server: {:?}", msg);
             }
         }
         303 => {
             if let Ok(msg) = ResponseAccountList::decode(&message_buf[..]) {
                 // Account List Response
                 // From Server
-                println!("Account List Response (Template ID: 303) from Server: {:?}", msg);
+                tracing::info!("Account List Response (Template ID: 303) from Server: {:?}", msg);
             }
         },
         305 => {
             if let Ok(msg) = ResponseAccountRmsInfo::decode(&message_buf[..]) {
-                println!("Response Account Rms Info (Template ID: 305) from Server: {:?}", msg);
+                tracing::info!("Response Account Rms Info (Template ID: 305) from Server: {:?}", msg);
                 if let Some(id) = msg.account_id {
                     client.open_orders.insert(id.clone(), DashMap::new());
                     let mut account_info = AccountInfo {
@@ -140,7 +142,7 @@ pub async fn match_order_plant_id(
                     }
                     client.account_info.insert(id.clone(), account_info);
                     client.request_updates(id.to_string()).await;
-                    println!("Account Info Added: {}", id);
+                    tracing::info!("Account Info Added: {}", id);
                 }
             }
         },
@@ -148,14 +150,14 @@ pub async fn match_order_plant_id(
             if let Ok(msg) = ResponseProductRmsInfo::decode(&message_buf[..]) {
                 // Product RMS Info Response
                 // From Server
-                println!("Product RMS Info Response (Template ID: 307) from Server: {:?}", msg);
+                tracing::info!("Product RMS Info Response (Template ID: 307) from Server: {:?}", msg);
             }
         },
         309 => {
             if let Ok(msg) = ResponseSubscribeForOrderUpdates::decode(&message_buf[..]) {
                 // Subscribe For Order Updates Response
                 // From Server
-                println!("Subscribe For Order Updates Response (Template ID: 309) from Server: {:?}", msg);
+                tracing::info!("Subscribe For Order Updates Response (Template ID: 309) from Server: {:?}", msg);
             }
         },
         311 => {
@@ -262,126 +264,126 @@ pub async fn match_order_plant_id(
             if let Ok(msg) = ResponseModifyOrder::decode(&message_buf[..]) {
                 // Modify Order Response
                 // From Server
-                println!("Modify Order Response (Template ID: 315) from Server: {:?}", msg);
+                tracing::info!("Modify Order Response (Template ID: 315) from Server: {:?}", msg);
             }
         },
         317 => {
             if let Ok(msg) = ResponseCancelOrder::decode(&message_buf[..]) {
                 // Cancel Order Response
                 // From Server
-                println!("Cancel Order Response (Template ID: 317) from Server: {:?}", msg);
+                tracing::info!("Cancel Order Response (Template ID: 317) from Server: {:?}", msg);
             }
         },
         319 => {
             if let Ok(msg) = ResponseShowOrderHistoryDates::decode(&message_buf[..]) {
                 // Show Order History Dates Response
                 // From Server
-                println!("Show Order History Dates Response (Template ID: 319) from Server: {:?}", msg);
+                tracing::info!("Show Order History Dates Response (Template ID: 319) from Server: {:?}", msg);
             }
         },
         321 => {
             if let Ok(msg) = ResponseShowOrders::decode(&message_buf[..]) {
                 // Show Orders Response
                 // From Server
-                println!("Show Orders Response (Template ID: 321) from Server: {:?}", msg);
+                tracing::info!("Show Orders Response (Template ID: 321) from Server: {:?}", msg);
             }
         },
         323 => {
             if let Ok(msg) = ResponseShowOrderHistory::decode(&message_buf[..]) {
                 // Show Order History Response
                 // From Server
-                println!("Show Order History Response (Template ID: 323) from Server: {:?}", msg);
+                tracing::info!("Show Order History Response (Template ID: 323) from Server: {:?}", msg);
             }
         },
         325 => {
             if let Ok(msg) = ResponseShowOrderHistorySummary::decode(&message_buf[..]) {
                 // Show Order History Summary Response
                 // From Server
-                println!("Show Order History Summary Response (Template ID: 325) from Server: {:?}", msg);
+                tracing::info!("Show Order History Summary Response (Template ID: 325) from Server: {:?}", msg);
             }
         },
         327 => {
             if let Ok(msg) = ResponseShowOrderHistoryDetail::decode(&message_buf[..]) {
                 // Show Order History Detail Response
                 // From Server
-                println!("Show Order History Detail Response (Template ID: 327) from Server: {:?}", msg);
+                tracing::info!("Show Order History Detail Response (Template ID: 327) from Server: {:?}", msg);
             }
         },
         329 => {
             if let Ok(msg) = ResponseOcoOrder::decode(&message_buf[..]) {
                 // OCO Order Response
                 // From Server
-                println!("OCO Order Response (Template ID: 329) from Server: {:?}", msg);
+                tracing::info!("OCO Order Response (Template ID: 329) from Server: {:?}", msg);
             }
         },
         331 => {
             if let Ok(msg) = ResponseBracketOrder::decode(&message_buf[..]) {
                 // Bracket Order Response
                 // From Server
-                println!("Bracket Order Response (Template ID: 331) from Server: {:?}", msg);
+                tracing::info!("Bracket Order Response (Template ID: 331) from Server: {:?}", msg);
             }
         },
         333 => {
             if let Ok(msg) = ResponseUpdateTargetBracketLevel::decode(&message_buf[..]) {
                 // Update Target Bracket Level Response
                 // From Server
-                println!("Update Target Bracket Level Response (Template ID: 333) from Server: {:?}", msg);
+                tracing::info!("Update Target Bracket Level Response (Template ID: 333) from Server: {:?}", msg);
             }
         },
         335 => {
             if let Ok(msg) = ResponseUpdateStopBracketLevel::decode(&message_buf[..]) {
                 // Update Stop Bracket Level Response
                 // From Server
-                println!("Update Stop Bracket Level Response (Template ID: 335) from Server: {:?}", msg);
+                tracing::info!("Update Stop Bracket Level Response (Template ID: 335) from Server: {:?}", msg);
             }
         },
         337 => {
             if let Ok(msg) = ResponseSubscribeToBracketUpdates::decode(&message_buf[..]) {
                 // Subscribe To Bracket Updates Response
                 // From Server
-                println!("Subscribe To Bracket Updates Response (Template ID: 337) from Server: {:?}", msg);
+                tracing::info!("Subscribe To Bracket Updates Response (Template ID: 337) from Server: {:?}", msg);
             }
         },
         339 => {
             if let Ok(msg) = ResponseShowBrackets::decode(&message_buf[..]) {
                 // Show Brackets Response
                 // From Server
-                println!("Show Brackets Response (Template ID: 339) from Server: {:?}", msg);
+                tracing::info!("Show Brackets Response (Template ID: 339) from Server: {:?}", msg);
             }
         },
         341 => {
             if let Ok(msg) = ResponseShowBracketStops::decode(&message_buf[..]) {
                 // Show Bracket Stops Response
                 // From Server
-                println!("Show Bracket Stops Response (Template ID: 341) from Server: {:?}", msg);
+                tracing::info!("Show Bracket Stops Response (Template ID: 341) from Server: {:?}", msg);
             }
         },
         343 => {
             if let Ok(msg) = ResponseListExchangePermissions::decode(&message_buf[..]) {
                 // List Exchange Permissions Response
                 // From Server
-                println!("List Exchange Permissions Response (Template ID: 343) from Server: {:?}", msg);
+                tracing::info!("List Exchange Permissions Response (Template ID: 343) from Server: {:?}", msg);
             }
         },
         345 => {
             if let Ok(msg) = ResponseLinkOrders::decode(&message_buf[..]) {
                 // Link Orders Response
                 // From Server
-                println!("Link Orders Response (Template ID: 345) from Server: {:?}", msg);
+                tracing::info!("Link Orders Response (Template ID: 345) from Server: {:?}", msg);
             }
         },
         347 => {
             if let Ok(msg) = ResponseCancelAllOrders::decode(&message_buf[..]) {
                 // Cancel All Orders Response
                 // From Server
-                println!("Cancel All Orders Response (Template ID: 347) from Server: {:?}", msg);
+                tracing::info!("Cancel All Orders Response (Template ID: 347) from Server: {:?}", msg);
             }
         },
         349 => {
             if let Ok(msg) = ResponseEasyToBorrowList::decode(&message_buf[..]) {
                 // Easy To Borrow List Response
                 // From Server
-                println!("Easy To Borrow List Response (Template ID: 349) from Server: {:?}", msg);
+                tracing::info!("Easy To Borrow List Response (Template ID: 349) from Server: {:?}", msg);
             }
         },
         350 => {
@@ -442,7 +444,7 @@ pub async fn match_order_plant_id(
 
                     let tag = match ID_TO_TAG.get_requests(&order_id) {
                         None => {
-                            eprintln!("Tag not found for order: {}", order_id);
+                            tracing::warn!("Tag not found for order: {}", order_id);
                             return;
                         },
                         Some(tag) => tag.value().clone()
@@ -620,7 +622,7 @@ pub async fn match_order_plant_id(
 
                                 let side = match msg.transaction_type {
                                     None => {
-                                        eprintln!("NO SIDE ON TRANSACTION");
+                                        tracing::warn!("NO SIDE ON TRANSACTION");
                                         return;
                                     },
                                     Some(tt) => {
@@ -716,11 +718,7 @@ pub async fn match_order_plant_id(
 
                                    if let Some(account_map) = client.open_orders.get_mut(&account_id) {
                                        if let Some(mut order) = account_map.get_mut(&order_id) {
-                                           match &update_type {
-                                               OrderUpdateType::LimitPrice(price) => order.limit_price = Some(price.clone()),
-                                               OrderUpdateType::TriggerPrice(price) => order.trigger_price = Some(price.clone()),
-                                               OrderUpdateType::Quantity(q) => order.quantity_open = q.clone(),
-                                           }
+                                           order.apply_update(&update_type);
                                        }
                                    }
 
@@ -776,78 +774,80 @@ pub async fn match_order_plant_id(
             if let Ok(msg) = BracketUpdates::decode(&message_buf[..]) {
                 // Bracket Updates
                 // From Server
-                println!("Bracket Updates (Template ID: 353) from Server: {:?}", msg);
+                tracing::info!("Bracket Updates (Template ID: 353) from Server: {:?}", msg);
             }
         },
         354 => {
             if let Ok(msg) = AccountListUpdates::decode(&message_buf[..]) {
                 // Account List Updates
                 // From Server
-                println!("Account List Updates (Template ID: 354) from Server: {:?}", msg);
+                tracing::info!("Account List Updates (Template ID: 354) from Server: {:?}", msg);
             }
         },
         355 => {
             if let Ok(msg) = UpdateEasyToBorrowList::decode(&message_buf[..]) {
                 // Update Easy To Borrow List
                 // From Server
-                println!("Update Easy To Borrow List (Template ID: 355) from Server: {:?}", msg);
+                tracing::info!("Update Easy To Borrow List (Template ID: 355) from Server: {:?}", msg);
             }
         },
         3501 => {
             if let Ok(msg) = ResponseModifyOrderReferenceData::decode(&message_buf[..]) {
                 // Modify Order Reference Data Response
                 // From Server
-                println!("Modify Order Reference Data Response (Template ID: 3501) from Server: {:?}", msg);
+                tracing::info!("Modify Order Reference Data Response (Template ID: 3501) from Server: {:?}", msg);
             }
         },
         3503 => {
             if let Ok(msg) = ResponseOrderSessionConfig::decode(&message_buf[..]) {
                 // Order Session Config Response
                 // From Server
-                println!("Order Session Config Response (Template ID: 3503) from Server: {:?}", msg);
+                tracing::info!("Order Session Config Response (Template ID: 3503) from Server: {:?}", msg);
             }
         },
         3505 => {
             if let Ok(msg) = ResponseExitPosition::decode(&message_buf[..]) {
                 // Exit Position Response
                 // From Server
-                println!("Exit Position Response (Template ID: 3505) from Server: {:?}", msg);
+                tracing::info!("Exit Position Response (Template ID: 3505) from Server: {:?}", msg);
             }
         },
         3507 => {
             if let Ok(msg) = ResponseReplayExecutions::decode(&message_buf[..]) {
                 // Replay Executions Response
                 // From Server
-                println!("Replay Executions Response (Template ID: 3507) from Server: {:?}", msg);
+                tracing::info!("Replay Executions Response (Template ID: 3507) from Server: {:?}", msg);
             }
         },
         3509 => {
             if let Ok(msg) = ResponseAccountRmsUpdates::decode(&message_buf[..]) {
                 // Account RMS Updates Response
                 // From Server
-                println!("Account RMS Updates Response (Template ID: 3509) from Server: {:?}", msg);
+                tracing::info!("Account RMS Updates Response (Template ID: 3509) from Server: {:?}", msg);
             }
         },
         356 => {
             if let Ok(msg) = AccountRmsUpdates::decode(&message_buf[..]) {
                 // Account RMS Updates
                 // From Server
-                println!("Account RMS Updates (Template ID: 356) from Server: {:?}", msg);
+                tracing::info!("Account RMS Updates (Template ID: 356) from Server: {:?}", msg);
             }
         },
 
-        _ => println!("No match for template_id: {}", template_id)
+        _ => tracing::info!("No match for template_id: {}", template_id)
     }
 }
 
 async fn send_order_update(brokerage: Brokerage, order_id: &OrderId, event: OrderUpdateEvent, time: String) {
+    let order_event = DataServerResponse::OrderUpdates{event, time};
+    notify_webhooks(&order_event);
+    copy_trades(&order_event);
     if let Some(broker_map) = ID_TO_STREAM_NAME_MAP.get(&brokerage) {
         if let Some(stream_name) = broker_map.value().get(order_id) {
-            let order_event = DataServerResponse::OrderUpdates{event, time};
             if let Some(sender) = RESPONSE_SENDERS.get(&stream_name.value()) {
                 match sender.send(order_event).await {
                     Ok(_) => {}
-                    Err(e) => eprintln!("failed to forward ResponseNewOrder 313 to strategy stream {}", e)
+                    Err(e) => tracing::error!("failed to forward ResponseNewOrder 313 to strategy stream {}", e)
                 }
             }
         }
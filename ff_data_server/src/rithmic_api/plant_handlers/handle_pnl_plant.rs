@@ -13,6 +13,7 @@ use rust_decimal::{Decimal};
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal_macros::dec;
 use ff_standard_lib::messages::data_server_messaging::DataServerResponse;
+use ff_standard_lib::product_maps::rithmic::maps::get_futures_symbol_info;
 use ff_standard_lib::standardized_types::accounts::Account;
 #[allow(unused_imports)]
 use ff_standard_lib::standardized_types::broker_enum::Brokerage;
@@ -23,6 +24,7 @@ use crate::rithmic_api::api_client::RithmicBrokerageClient;
 use crate::rithmic_api::plant_handlers::create_datetime;
 use crate::rithmic_api::plant_handlers::handler_loop::send_updates;
 use crate::update_functions::{MULTIBAR};
+use crate::server_features::webhooks::notify_webhooks;
 
 lazy_static! {
     pub static ref PROGRESS_PNL: DashMap<(Brokerage, Account, SymbolCode), ProgressBar> = DashMap::new();
@@ -39,35 +41,35 @@ pub async fn match_pnl_plant_id(
             if let Ok(msg) = Reject::decode(&message_buf[..]) {
                 // Login Response
                 // From Server
-                println!("Reject Response (Template ID: 11) from Server: {:?}", msg);
+                tracing::info!("Reject Response (Template ID: 11) from Server: {:?}", msg);
             }
         }
         11 => {
             if let Ok(msg) = ResponseLogin::decode(&message_buf[..]) {
                 // Login Response
                 // From Server
-                println!("Login Response (Template ID: 11) from Server: {:?}", msg);
+                tracing::info!("Login Response (Template ID: 11) from Server: {:?}", msg);
             }
         },
         13 => {
             if let Ok(msg) = ResponseLogout::decode(&message_buf[..]) {
                 // Logout Response
                 // From Server
-                println!("Logout Response (Template ID: 13) from Server: {:?}", msg);
+                tracing::info!("Logout Response (Template ID: 13) from Server: {:?}", msg);
             }
         },
         15 => {
             if let Ok(msg) = ResponseReferenceData::decode(&message_buf[..]) {
                 // Reference Data Response
                 // From Server
-                println!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
+                tracing::info!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
             }
         },
         17 => {
             if let Ok(msg) = ResponseRithmicSystemInfo::decode(&message_buf[..]) {
                 // Rithmic System Info Response
                 // From Server
-                println!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
+                tracing::info!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
             }
         },
         19 => {
@@ -169,16 +171,22 @@ pub async fn match_pnl_plant_id(
                     // fwd position updates to the client
                     if let (Some(pnl), Some(average_price)) = (msg.open_position_pnl.clone(), msg.avg_open_fill_price) {
                         //todo do this with a simple message, quantity open, and position side, symbol name, symbol code
-                        let open_position_pnl = match f64::from_str(&pnl) {
+                        let open_position_pnl = match Decimal::from_str(&pnl) {
                             Ok(open_position_pnl) => open_position_pnl,
                             Err(_) => return
                         };
 
                         let open_position_quantity = match msg.open_position_quantity {
                             None => return,
-                            Some(open_position_quantity) => match f64::from_str(&open_position_quantity.to_string()) {
-                                Ok(open_position_quantity) => open_position_quantity,
-                                Err(_) => return
+                            Some(open_position_quantity) => Volume::from(open_position_quantity)
+                        };
+
+                        // The average of the fills is not on a tick, so it is only rounded to the decimal accuracy of the symbol
+                        let average_price = match Decimal::from_f64(average_price) {
+                            None => return,
+                            Some(average_price) => match get_futures_symbol_info(&symbol_name) {
+                                Ok(symbol_info) => average_price.round_dp(symbol_info.decimal_accuracy),
+                                Err(_) => average_price
                             }
                         };
 
@@ -192,19 +200,21 @@ pub async fn match_pnl_plant_id(
                             time,
                             open_pnl: open_position_pnl,
                         };
-                        send_updates(position_update).await;
+                        notify_webhooks(&position_update);
+                        client.order_session.send_position_update(&account_id, symbol_code, side, position_update).await;
                     } else if side == PositionSide::Flat {
                         let position_update = DataServerResponse::LivePositionUpdates {
                             symbol_name: symbol_name.clone(),
                             symbol_code: symbol_code.clone(),
-                            average_price: 0.0,
+                            average_price: dec!(0),
                             account: Account::new(client.brokerage, account_id.clone()),
-                            open_quantity: 0.0,
+                            open_quantity: dec!(0),
                             side,
                             time,
-                            open_pnl: 0.0,
+                            open_pnl: dec!(0),
                         };
-                        send_updates(position_update).await;
+                        notify_webhooks(&position_update);
+                        client.order_session.send_position_update(&account_id, symbol_code, side, position_update).await;
                     }
                 }
 
@@ -352,7 +362,7 @@ pub async fn match_pnl_plant_id(
                 }
             }
         },
-        _ => println!("No match for template_id: {}", template_id)
+        _ => tracing::info!("No match for template_id: {}", template_id)
     }
 }
 
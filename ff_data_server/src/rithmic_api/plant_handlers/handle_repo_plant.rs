@@ -18,28 +18,28 @@ pub async fn match_repo_plant_id(
             if let Ok(msg) = ResponseLogin::decode(&message_buf[..]) {
                 // Login Response
                 // From Server
-                println!("Login Response (Template ID: 11) from Server: {:?}", msg);
+                tracing::info!("Login Response (Template ID: 11) from Server: {:?}", msg);
             }
         },
         13 => {
             if let Ok(msg) = ResponseLogout::decode(&message_buf[..]) {
                 // Logout Response
                 // From Server
-                println!("Logout Response (Template ID: 13) from Server: {:?}", msg);
+                tracing::info!("Logout Response (Template ID: 13) from Server: {:?}", msg);
             }
         },
         15 => {
             if let Ok(msg) = ResponseReferenceData::decode(&message_buf[..]) {
                 // Reference Data Response
                 // From Server
-                println!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
+                tracing::info!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
             }
         },
         17 => {
             if let Ok(msg) = ResponseRithmicSystemInfo::decode(&message_buf[..]) {
                 // Rithmic System Info Response
                 // From Server
-                println!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
+                tracing::info!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
             }
         },
         19 => {
@@ -53,37 +53,37 @@ pub async fn match_repo_plant_id(
             if let Ok(msg) = ResponseListUnacceptedAgreements::decode(&message_buf[..]) {
                 // List Unaccepted Agreements Response
                 // From Server
-                println!("List Unaccepted Agreements Response (Template ID: 501) from Server: {:?}", msg);
+                tracing::info!("List Unaccepted Agreements Response (Template ID: 501) from Server: {:?}", msg);
             }
         },
         503 => {
             if let Ok(msg) = ResponseListAcceptedAgreements::decode(&message_buf[..]) {
                 // List Accepted Agreements Response
                 // From Server
-                println!("List Accepted Agreements Response (Template ID: 503) from Server: {:?}", msg);
+                tracing::info!("List Accepted Agreements Response (Template ID: 503) from Server: {:?}", msg);
             }
         },
         505 => {
             if let Ok(msg) = ResponseAcceptAgreement::decode(&message_buf[..]) {
                 // Accept Agreement Response
                 // From Server
-                println!("Accept Agreement Response (Template ID: 505) from Server: {:?}", msg);
+                tracing::info!("Accept Agreement Response (Template ID: 505) from Server: {:?}", msg);
             }
         },
         507 => {
             if let Ok(msg) = ResponseShowAgreement::decode(&message_buf[..]) {
                 // Show Agreement Response
                 // From Server
-                println!("Show Agreement Response (Template ID: 507) from Server: {:?}", msg);
+                tracing::info!("Show Agreement Response (Template ID: 507) from Server: {:?}", msg);
             }
         },
         509 => {
             if let Ok(msg) = ResponseSetRithmicMrktDataSelfCertStatus::decode(&message_buf[..]) {
                 // Set Rithmic MarketData Self Certification Status Response
                 // From Server
-                println!("Set Rithmic MarketData Self Certification Status Response (Template ID: 509) from Server: {:?}", msg);
+                tracing::info!("Set Rithmic MarketData Self Certification Status Response (Template ID: 509) from Server: {:?}", msg);
             }
         },
-        _ => println!("No match for template_id: {}", template_id)
+        _ => tracing::info!("No match for template_id: {}", template_id)
     }
 }
\ No newline at end of file
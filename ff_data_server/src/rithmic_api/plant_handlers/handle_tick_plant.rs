@@ -18,12 +18,14 @@ use ff_standard_lib::standardized_types::broker_enum::Brokerage;
 use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use ff_standard_lib::standardized_types::base_data::quote::Quote;
 use ff_standard_lib::standardized_types::base_data::tick::{Aggressor, Tick};
+use ff_standard_lib::standardized_types::base_data::data_quality::DataQuality;
 use ff_standard_lib::standardized_types::enums::{FuturesExchange, MarketType};
 use ff_standard_lib::standardized_types::subscriptions::{Symbol};
 use ff_standard_lib::standardized_types::symbol_info::FrontMonthInfo;
 use ff_standard_lib::standardized_types::books::BookLevel;
 use ff_standard_lib::StreamName;
 use crate::rithmic_api::api_client::RithmicBrokerageClient;
+use crate::rithmic_api::plant_handlers::rithmic_price;
 use crate::rithmic_api::client_base::rithmic_proto_objects::rti::request_login::SysInfraType;
 
 
@@ -38,35 +40,35 @@ pub async fn match_ticker_plant_id(
             if let Ok(msg) = Reject::decode(&message_buf[..]) {
                 // Login Response
                 // From Server
-                println!("Reject Response (Template ID: 11) from Server: {:?}", msg);
+                tracing::info!("Reject Response (Template ID: 11) from Server: {:?}", msg);
             }
         }
         11 => {
             if let Ok(msg) = ResponseLogin::decode(&message_buf[..]) {
                 // Login Response
                 // From Server
-                println!("Login Response (Template ID: 11) from Server: {:?}", msg);
+                tracing::info!("Login Response (Template ID: 11) from Server: {:?}", msg);
             }
         },
         13 => {
             if let Ok(msg) = ResponseLogout::decode(&message_buf[..]) {
                 // Logout Response
                 // From Server
-                println!("Logout Response (Template ID: 13) from Server: {:?}", msg);
+                tracing::info!("Logout Response (Template ID: 13) from Server: {:?}", msg);
             }
         },
         15 => {
             if let Ok(msg) = ResponseReferenceData::decode(&message_buf[..]) {
                 // Reference Data Response
                 // From Server
-                println!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
+                tracing::info!("Reference Data Response (Template ID: 15) from Server: {:?}", msg);
             }
         },
         17 => {
             if let Ok(msg) = ResponseRithmicSystemInfo::decode(&message_buf[..]) {
                 // Rithmic System Info Response
                 // From Server
-                println!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
+                tracing::info!("Rithmic System Info Response (Template ID: 17) from Server: {:?}", msg);
             }
         },
         19 => {
@@ -95,62 +97,63 @@ pub async fn match_ticker_plant_id(
             if let Ok(msg) = ResponseMarketDataUpdate::decode(&message_buf[..]) {
                 // Market Data Update Response
                 // From Server
-                println!("Market Data Update Response (Template ID: 101) from Server: {:?}", msg);
+                tracing::info!("Market Data Update Response (Template ID: 101) from Server: {:?}", msg);
+                client.return_subscription_callback(&msg.user_msg, &msg.rp_code);
             }
         },
         103 => {
             if let Ok(msg) = ResponseGetInstrumentByUnderlying::decode(&message_buf[..]) {
                 // Get Instrument by Underlying Response
                 // From Server
-                println!("Get Instrument by Underlying Response (Template ID: 103) from Server: {:?}", msg);
+                tracing::info!("Get Instrument by Underlying Response (Template ID: 103) from Server: {:?}", msg);
             }
         },
         104 => {
             if let Ok(msg) = ResponseGetInstrumentByUnderlyingKeys::decode(&message_buf[..]) {
                 // Get Instrument by Underlying Keys Response
                 // From Server
-                println!("Get Instrument by Underlying Keys Response (Template ID: 104) from Server: {:?}", msg);
+                tracing::info!("Get Instrument by Underlying Keys Response (Template ID: 104) from Server: {:?}", msg);
             }
         },
         106 => {
             if let Ok(msg) = ResponseMarketDataUpdateByUnderlying::decode(&message_buf[..]) {
                 // Market Data Update by Underlying Response
                 // From Server
-                println!("Market Data Update by Underlying Response (Template ID: 106) from Server: {:?}", msg);
+                tracing::info!("Market Data Update by Underlying Response (Template ID: 106) from Server: {:?}", msg);
             }
         },
         108 => {
             if let Ok(msg) = ResponseGiveTickSizeTypeTable::decode(&message_buf[..]) {
                 // Give Tick Size Type Table Response
                 // From Server
-                println!("Give Tick Size Type Table Response (Template ID: 108) from Server: {:?}", msg);
+                tracing::info!("Give Tick Size Type Table Response (Template ID: 108) from Server: {:?}", msg);
             }
         },
         110 => {
             if let Ok(msg) = ResponseSearchSymbols::decode(&message_buf[..]) {
                 // Search Symbols Response
                 // From Server
-                println!("Search Symbols Response (Template ID: 110) from Server: {:?}", msg);
+                client.return_symbol_search(msg);
             }
         },
         112 => {
             if let Ok(msg) = ResponseProductCodes::decode(&message_buf[..]) {
                 // Product Codes Response
                 // From Server
-                println!("Product Codes Response (Template ID: 112) from Server: {:?}", msg);
+                tracing::info!("Product Codes Response (Template ID: 112) from Server: {:?}", msg);
             }
         },
         114 => {
             if let Ok(msg) = ResponseFrontMonthContract::decode(&message_buf[..]) {
                 // Front Month Contract Response
                 // From Server
-                println!("Front Month Contract Response (Template ID: 114) from Server: {:?}", msg);
+                tracing::info!("Front Month Contract Response (Template ID: 114) from Server: {:?}", msg);
                 if let Some(symbol) =  msg.symbol {
                     if let Some(exchange) = msg.exchange {
                         let exchange = match FuturesExchange::from_string(&exchange) {
                             Ok(exchange) => exchange,
                             Err(e) => {
-                                eprintln!("{}", e);
+                                tracing::error!("{}", e);
                                 return;
                             }
                         };
@@ -183,28 +186,28 @@ pub async fn match_ticker_plant_id(
             if let Ok(msg) = ResponseDepthByOrderSnapshot::decode(&message_buf[..]) {
                 // Depth By Order Snapshot Response
                 // From Server
-                println!("Depth By Order Snapshot Response (Template ID: 116) from Server: {:?}", msg);
+                tracing::info!("Depth By Order Snapshot Response (Template ID: 116) from Server: {:?}", msg);
             }
         },
         118 => {
             if let Ok(msg) = ResponseDepthByOrderUpdates::decode(&message_buf[..]) {
                 // Depth By Order Updates Response
                 // From Server
-                println!("Depth By Order Updates Response (Template ID: 118) from Server: {:?}", msg);
+                tracing::info!("Depth By Order Updates Response (Template ID: 118) from Server: {:?}", msg);
             }
         },
         120 => {
             if let Ok(msg) = ResponseGetVolumeAtPrice::decode(&message_buf[..]) {
                 // Get Volume At Price Response
                 // From Server
-                println!("Get Volume At Price Response (Template ID: 120) from Server: {:?}", msg);
+                tracing::info!("Get Volume At Price Response (Template ID: 120) from Server: {:?}", msg);
             }
         },
         122 => {
             if let Ok(msg) = ResponseAuxilliaryReferenceData::decode(&message_buf[..]) {
                 // Auxiliary Reference Data Response
                 // From Server
-                println!("Auxiliary Reference Data Response (Template ID: 122) from Server: {:?}", msg);
+                tracing::info!("Auxiliary Reference Data Response (Template ID: 122) from Server: {:?}", msg);
             }
         },
         150 => {
@@ -225,87 +228,87 @@ pub async fn match_ticker_plant_id(
             if let Ok(msg) = TradeStatistics::decode(&message_buf[..]) {
                 // Trade Statistics
                 // From Server
-                println!("Trade Statistics (Template ID: 152) from Server: {:?}", msg);
+                tracing::info!("Trade Statistics (Template ID: 152) from Server: {:?}", msg);
             }
         },
         153 => {
             if let Ok(msg) = QuoteStatistics::decode(&message_buf[..]) {
                 // Quote Statistics
                 // From Server
-                println!("Quote Statistics (Template ID: 153) from Server: {:?}", msg);
+                tracing::info!("Quote Statistics (Template ID: 153) from Server: {:?}", msg);
             }
         },
         154 => {
             if let Ok(msg) = IndicatorPrices::decode(&message_buf[..]) {
                 // Indicator Prices
                 // From Server
-                println!("Indicator Prices (Template ID: 154) from Server: {:?}", msg);
+                tracing::info!("Indicator Prices (Template ID: 154) from Server: {:?}", msg);
             }
         },
         155 => {
             if let Ok(msg) = EndOfDayPrices::decode(&message_buf[..]) {
                 // End Of Day Prices
                 // From Server
-                println!("End Of Day Prices (Template ID: 155) from Server: {:?}", msg);
+                tracing::info!("End Of Day Prices (Template ID: 155) from Server: {:?}", msg);
             }
         },
         156 => {
             if let Ok(msg) = OrderBook::decode(&message_buf[..]) {
                 // Order Book
                 // From Server
-                println!("Order Book (Template ID: 156) from Server: {:?}", msg);
+                tracing::info!("Order Book (Template ID: 156) from Server: {:?}", msg);
             }
         },
         157 => {
             if let Ok(msg) = MarketMode::decode(&message_buf[..]) {
                 // Market Mode
                 // From Server
-                println!("Market Mode (Template ID: 157) from Server: {:?}", msg);
+                tracing::info!("Market Mode (Template ID: 157) from Server: {:?}", msg);
             }
         },
         158 => {
             if let Ok(msg) = OpenInterest::decode(&message_buf[..]) {
                 // Open Interest
                 // From Server
-                println!("Open Interest (Template ID: 158) from Server: {:?}", msg);
+                tracing::info!("Open Interest (Template ID: 158) from Server: {:?}", msg);
             }
         },
         159 => {
             if let Ok(msg) = FrontMonthContractUpdate::decode(&message_buf[..]) {
                 // Front Month Contract Update
                 // From Server
-                println!("Front Month Contract Update (Template ID: 159) from Server: {:?}", msg);
+                tracing::info!("Front Month Contract Update (Template ID: 159) from Server: {:?}", msg);
             }
         },
         160 => {
             if let Ok(msg) = DepthByOrder::decode(&message_buf[..]) {
                 // Depth By Order
                 // From Server
-                println!("Depth By Order (Template ID: 160) from Server: {:?}", msg);
+                tracing::info!("Depth By Order (Template ID: 160) from Server: {:?}", msg);
             }
         },
         161 => {
             if let Ok(msg) = DepthByOrderEndEvent::decode(&message_buf[..]) {
                 // Depth By Order End Event
                 // From Server
-                println!("DepthByOrderEndEvent (Template ID: 161) from Server: {:?}", msg);
+                tracing::info!("DepthByOrderEndEvent (Template ID: 161) from Server: {:?}", msg);
             }
         },
         162 => {
             if let Ok(msg) = SymbolMarginRate::decode(&message_buf[..]) {
                 // Symbol Margin Rate
                 // From Server
-                println!("Symbol Margin Rate (Template ID: 162) from Server: {:?}", msg);
+                tracing::info!("Symbol Margin Rate (Template ID: 162) from Server: {:?}", msg);
             }
         },
         163 => {
             if let Ok(msg) = OrderPriceLimits::decode(&message_buf[..]) {
                 // Order Price Limits
                 // From Server
-                println!("Order Price Limits (Template ID: 163) from Server: {:?}", msg);
+                tracing::info!("Order Price Limits (Template ID: 163) from Server: {:?}", msg);
             }
         },
-        _ => println!("No match for template_id: {}", template_id)
+        _ => tracing::info!("No match for template_id: {}", template_id)
     }
 }
 
@@ -334,9 +337,14 @@ async fn handle_tick(client: Arc<RithmicBrokerageClient>, msg: LastTrade) {
         }
     };
 
+    let symbol = match msg.symbol {
+        None => return,
+        Some(symbol) => symbol
+    };
+
     let price = match msg.trade_price {
         None => return,
-        Some(price) => match Decimal::from_f64(price) {
+        Some(price) => match rithmic_price(&symbol, exchange, price) {
             None => return,
             Some(price) => price
         }
@@ -351,13 +359,13 @@ async fn handle_tick(client: Arc<RithmicBrokerageClient>, msg: LastTrade) {
             }
         }
     };
-    let symbol = match msg.symbol {
-        None => return,
-        Some(symbol) => symbol
-    };
-
     let symbol = Symbol::new(symbol, client.data_vendor.clone(), MarketType::Futures(exchange));
-    let tick = Tick::new(symbol.clone(), price, time.to_string(), volume, side);
+    // LastTrade carries no trade condition codes, the presence and clear bits only flag which fields were updated, so conditions stay None
+    let mut tick = Tick::new(symbol.clone(), price, time.to_string(), volume, side);
+    // the snapshot is the last trade before we subscribed, so it was not received in real time
+    if msg.is_snapshot == Some(true) {
+        tick.quality.insert(DataQuality::LATE);
+    }
 
     let mut remove_broadcaster = false;
     if let Some(broadcaster) = client.tick_feed_broadcasters.get(&tick.symbol.name) {
@@ -385,7 +393,7 @@ async fn handle_tick(client: Arc<RithmicBrokerageClient>, msg: LastTrade) {
 
                 const PLANT: SysInfraType = SysInfraType::TickerPlant;
                 client.send_message(&PLANT, req).await;
-                println!("Unsubscribed: {} Ticks", tick.symbol.name.clone());
+                tracing::info!("Unsubscribed: {} Ticks", tick.symbol.name.clone());
             }
         }
     }
@@ -418,11 +426,19 @@ async fn handle_quote(client: Arc<RithmicBrokerageClient>, msg: BestBidOffer) {
         Some(symbol) => symbol
     };
 
+    let exchange = match msg.exchange.as_deref().and_then(|e| FuturesExchange::from_string(e).ok()) {
+        Some(ex) => ex,
+        None => {
+            tracing::error!("Error deserializing Exchange for symbol {}", symbol);
+            return;
+        }
+    };
+
     let mut updated = false;
 
     // Handle ask update
     if let (Some(price), Some(volume)) = (msg.ask_price, msg.ask_size) {
-        if let (Some(ask_price), Some(ask_volume)) = (Decimal::from_f64(price), Decimal::from_i32(volume)) {
+        if let (Some(ask_price), Some(ask_volume)) = (rithmic_price(&symbol, exchange, price), Decimal::from_i32(volume)) {
             client.ask_book.entry(symbol.clone()).or_default().insert(0, BookLevel::new(0, ask_price, ask_volume));
             updated = true;
         }
@@ -430,7 +446,7 @@ async fn handle_quote(client: Arc<RithmicBrokerageClient>, msg: BestBidOffer) {
 
     // Handle bid update
     if let (Some(price), Some(volume)) = (msg.bid_price, msg.bid_size) {
-        if let (Some(bid_price), Some(bid_volume)) = (Decimal::from_f64(price), Decimal::from_i32(volume)) {
+        if let (Some(bid_price), Some(bid_volume)) = (rithmic_price(&symbol, exchange, price), Decimal::from_i32(volume)) {
             client.bid_book.entry(symbol.clone()).or_default().insert(0, BookLevel::new(0, bid_price, bid_volume));
             updated = true;
         }
@@ -440,14 +456,6 @@ async fn handle_quote(client: Arc<RithmicBrokerageClient>, msg: BestBidOffer) {
         return;
     }
 
-    let exchange = match msg.exchange.as_deref().and_then(|e| FuturesExchange::from_string(e).ok()) {
-        Some(ex) => ex,
-        None => {
-            eprintln!("Error deserializing Exchange for symbol {}", symbol);
-            return;
-        }
-    };
-
     let mut remove_broadcaster = false;
     if let Some(broadcaster) = client.quote_feed_broadcasters.get(&symbol) {
         let (ask, ask_volume) = client.ask_book
@@ -500,7 +508,7 @@ async fn handle_quote(client: Arc<RithmicBrokerageClient>, msg: BestBidOffer) {
 
                 const PLANT: SysInfraType = SysInfraType::TickerPlant;
                 client.send_message(&PLANT, req).await;
-                println!("Unsubscribed: {} Quotes", symbol);
+                tracing::info!("Unsubscribed: {} Quotes", symbol);
             }
         }
     }
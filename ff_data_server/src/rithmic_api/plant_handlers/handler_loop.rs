@@ -71,7 +71,7 @@ pub fn handle_rithmic_responses(
 
                                 // Validate message length
                                 if bytes.len() != length + 4 {
-                                    eprintln!("Invalid message length. Expected: {}, Got: {}", length + 4, bytes.len());
+                                    tracing::error!("Invalid message length. Expected: {}, Got: {}", length + 4, bytes.len());
                                     continue;
                                 }
 
@@ -81,16 +81,16 @@ pub fn handle_rithmic_responses(
                                     let message_data = bytes[4..].to_vec();
 
                                     if let Err(e) = tx.send((template_id, message_data)).await {
-                                        eprintln!("Fatal error sending message to processor: {}", e);
+                                        tracing::error!("Fatal error sending message to processor: {}", e);
                                         break 'main_loop;
                                     }
                                 }
                             } else {
-                                eprintln!("Message too short: {} bytes", bytes.len());
+                                tracing::warn!("Message too short: {} bytes", bytes.len());
                             }
                         }
                         Message::Close(close_frame) => {
-                            println!("Received close message: {:?}. Attempting reconnection.", close_frame);
+                            tracing::info!("Received close message: {:?}. Attempting reconnection.", close_frame);
                                 task::spawn(async move {
                                     attempt_reconnect(client.clone(), plant.clone()).await
                                 });
@@ -98,22 +98,22 @@ pub fn handle_rithmic_responses(
                         }
                         Message::Frame(frame) => {
                             if format!("{:?}", frame).contains("CloseFrame") {
-                                println!("Received close frame. Attempting reconnection.");
+                                tracing::info!("Received close frame. Attempting reconnection.");
                                 task::spawn(async move {
                                     attempt_reconnect(client.clone(), plant.clone()).await
                                 });
                                 break 'main_loop;
                             }
                         }
-                        Message::Text(text) => println!("{}", text),
+                        Message::Text(text) => tracing::info!("{}", text),
                         Message::Ping(_) | Message::Pong(_) => {}
                     }
                 },
                 _ = shutdown_receiver.recv() => {
-                    println!("Shutdown signal received. Stopping Rithmic response handler.");
+                    tracing::info!("Shutdown signal received. Stopping Rithmic response handler.");
                     if let Some((_, writer)) = client.writers.remove(&plant) {
                         if let Err(e) = shutdown_plant(writer).await {
-                            eprintln!("Error shutting down plant: {:?}", e);
+                            tracing::error!("Error shutting down plant: {:?}", e);
                         }
                     }
                     break;
@@ -122,7 +122,7 @@ pub fn handle_rithmic_responses(
         }
 
         // Cleanup
-        println!("Cleaning up Rithmic response handler for plant: {:?}", plant);
+        tracing::info!("Cleaning up Rithmic response handler for plant: {:?}", plant);
         drop(tx);
     });
 }
@@ -158,7 +158,7 @@ pub async fn send_updates(event: DataServerResponse) {
         match stream_name.value().send(event.clone()).await {
             Ok(_) => {}
             Err(e) => {
-                eprintln!("failed to forward ResponseNewOrder 313 to strategy stream {}", e);
+                tracing::error!("failed to forward ResponseNewOrder 313 to strategy stream {}", e);
 
             }
         }
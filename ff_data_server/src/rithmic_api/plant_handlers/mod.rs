@@ -1,4 +1,9 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use ff_standard_lib::product_maps::rithmic::maps::{extract_symbol_from_contract, get_futures_symbol_info};
+use ff_standard_lib::standardized_types::enums::{FuturesExchange, MarketType};
+use ff_standard_lib::standardized_types::new_types::Price;
 
 pub(crate) mod handle_tick_plant;
 pub(crate) mod handle_order_plant;
@@ -11,4 +16,15 @@ pub(crate) mod handler_loop;
 pub(crate) fn create_datetime(ssboe: i64, usecs: i64) -> DateTime<Utc> {
     let nanosecs = usecs * 1000; // Convert microseconds to nanoseconds
     DateTime::<Utc>::from_timestamp(ssboe, nanosecs as u32).unwrap()
-}
\ No newline at end of file
+}
+
+/// Converts a rithmic float price to a `Price` rounded to the tick size of the symbol, the symbol can be a product or contract code.
+/// Symbols missing from the product maps keep the precision of the float.
+pub(crate) fn rithmic_price(symbol: &str, exchange: FuturesExchange, price: f64) -> Option<Price> {
+    let symbol_info = get_futures_symbol_info(symbol)
+        .or_else(|_| get_futures_symbol_info(&extract_symbol_from_contract(symbol)));
+    match symbol_info {
+        Ok(symbol_info) => symbol_info.price_from_f64(MarketType::Futures(exchange), price),
+        Err(_) => Decimal::from_f64(price),
+    }
+}
@@ -23,7 +23,7 @@ pub(crate) async fn attempt_reconnect(
             let reconnect_time = next_market_open - chrono::Duration::minutes(5);
             let wait_duration = reconnect_time - now;
 
-            eprintln!(
+            tracing::warn!(
                 "Outside of market hours. Waiting until 5 mins before market open: {}",
                 reconnect_time
             );
@@ -32,11 +32,11 @@ pub(crate) async fn attempt_reconnect(
         } else {
             match client.connect_plant(plant).await {
                 Ok(new_connection) => {
-                    println!("Reconnected successfully");
+                    tracing::info!("Reconnected successfully");
                     handle_rithmic_responses(client.clone(), new_connection, plant);
                 }
                 Err(e) => {
-                    eprintln!(
+                    tracing::warn!(
                         "Failed to reconnect: {:?}: {}. Retrying in {:?}",
                         plant, e, delay
                     );
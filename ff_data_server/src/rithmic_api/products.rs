@@ -28,6 +28,11 @@ pub fn get_futures_symbols() -> Vec<String> {
     AVAILABLE_SYMBOL_NAMES.clone()
 }
 
+/// The exchange product name of the futures symbol, eg. "Corn Futures" for "ZC".
+pub fn get_futures_description(symbol_name: &str) -> Option<&'static str> {
+    FUTURES_CODE_TO_NAME.get(symbol_name).copied()
+}
+
 lazy_static! {
     static ref FUTURES_CODE_TO_NAME: AHashMap<&'static str, &'static str> = {
         let mut futures = AHashMap::new();
@@ -158,6 +163,9 @@ lazy_static! {
                     tick_size: dec!($tick_size),
                     decimal_accuracy: $accuracy,
                     base_currency: None,
+                    option_contract: None,
+                    lot_size: Some(dec!(1)),
+                    min_quantity: None,
                 });
             };
         }
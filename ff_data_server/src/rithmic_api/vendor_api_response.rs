@@ -12,6 +12,10 @@ use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType
 use ff_standard_lib::standardized_types::enums::{FuturesExchange, MarketType, StrategyMode, PrimarySubscription};
 use ff_standard_lib::standardized_types::resolution::Resolution;
 use ff_standard_lib::standardized_types::subscriptions::{DataSubscription, Symbol, SymbolName};
+use ff_standard_lib::standardized_types::symbol_info::SymbolSearchResult;
+use ff_standard_lib::standardized_types::orders::OrderType;
+use ff_standard_lib::standardized_types::vendor_capabilities::VendorCapabilities;
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
 use ff_standard_lib::StreamName;
 use tokio::sync::{broadcast, oneshot};
 use tokio::time::timeout;
@@ -148,7 +152,8 @@ impl VendorApiResponse for RithmicBrokerageClient {
             return DataServerResponse::SubscribeResponse{ success: false, subscription: subscription.clone(), reason: Some(format!("This subscription is not available with {}: {}", subscription.symbol.data_vendor, subscription))}
         };
 
-        let mut is_subscribed = true;
+        // The receiver of a new broadcaster is only sent to the stream once rithmic accepts the subscription.
+        let mut new_receiver = None;
         //todo have a unique function per base data type.
         match subscription.base_data_type {
             BaseDataType::Ticks => {
@@ -158,8 +163,7 @@ impl VendorApiResponse for RithmicBrokerageClient {
                 } else {
                     let (sender, receiver) = broadcast::channel(500);
                     self.tick_feed_broadcasters.insert(subscription.symbol.name.clone(), sender);
-                    subscribe_stream(&stream_name, subscription.clone(), receiver).await;
-                    is_subscribed = false;
+                    new_receiver = Some(receiver);
                 }
             }
             BaseDataType::Quotes => {
@@ -169,10 +173,9 @@ impl VendorApiResponse for RithmicBrokerageClient {
                 } else {
                     let (sender, receiver) = broadcast::channel(500);
                     self.quote_feed_broadcasters.insert(subscription.symbol.name.clone(), sender);
-                    subscribe_stream(&stream_name, subscription.clone(), receiver).await;
+                    new_receiver = Some(receiver);
                     self.ask_book.insert(subscription.symbol.name.clone(), BTreeMap::new());
                     self.ask_book.insert(subscription.symbol.name.clone(), BTreeMap::new());
-                    is_subscribed = false;
                 }
             }
             BaseDataType::Candles => {
@@ -182,14 +185,13 @@ impl VendorApiResponse for RithmicBrokerageClient {
                 } else {
                     let (sender, receiver) = broadcast::channel(500);
                     self.candle_feed_broadcasters.insert(subscription.symbol.name.clone(), sender);
-                    subscribe_stream(&stream_name, subscription.clone(), receiver).await;
-                    is_subscribed = false;
+                    new_receiver = Some(receiver);
                 }
             }
             _ => todo!("Handle gracefully by returning err")
         }
 
-        if !is_subscribed {
+        if let Some(receiver) = new_receiver {
             if subscription.base_data_type == BaseDataType::Quotes || subscription.base_data_type == BaseDataType::Ticks {
                 let bits = match subscription.base_data_type {
                     BaseDataType::Ticks => 1,
@@ -197,9 +199,10 @@ impl VendorApiResponse for RithmicBrokerageClient {
                     _ => return DataServerResponse::SubscribeResponse { success: false, subscription: subscription.clone(), reason: Some(format!("This subscription is not available with {}: {}", self.data_vendor, subscription)) }
                     //BaseDataType::Candles => {}
                 };
+                let callback_id = self.generate_callback_id().await;
                 let req = RequestMarketDataUpdate {
                     template_id: 100,
-                    user_msg: vec![],
+                    user_msg: vec![callback_id.to_string()],
                     symbol: Some(subscription.symbol.name.to_string()),
                     exchange: Some(exchange),
                     request: Some(1), //1 subscribe 2 unsubscribe
@@ -207,7 +210,9 @@ impl VendorApiResponse for RithmicBrokerageClient {
                 };
 
                 const PLANT: SysInfraType = SysInfraType::TickerPlant;
-                self.send_message(&PLANT, req).await;
+                if let Err(reason) = self.send_subscription_request(&PLANT, callback_id, req).await {
+                    return self.subscription_rejected(&stream_name, subscription, receiver, reason).await;
+                }
             } else if subscription.base_data_type == BaseDataType::Candles {
                 let (num, res_type) = match subscription.resolution {
                     Resolution::Seconds(num) => (num as i32, BarType::SecondBar),
@@ -221,9 +226,10 @@ impl VendorApiResponse for RithmicBrokerageClient {
                     _ => return DataServerResponse::SubscribeResponse { success: false, subscription: subscription.clone(), reason: Some(format!("This subscription is not available with {}: {}", self.data_vendor,subscription)) }
                 };
 
+                let callback_id = self.generate_callback_id().await;
                 let req =RequestTimeBarUpdate {
                     template_id: 200,
-                    user_msg: vec![],
+                    user_msg: vec![callback_id.to_string()],
                     symbol: Some(subscription.symbol.name.to_string()),
                     exchange: Some(exchange),
                     request: Some(1), //1 subscribe 2 unsubscribe
@@ -231,10 +237,13 @@ impl VendorApiResponse for RithmicBrokerageClient {
                     bar_type_period: Some(num),
                 };
                 const PLANT: SysInfraType = SysInfraType::HistoryPlant;
-                self.send_message(&PLANT, req).await;
+                if let Err(reason) = self.send_subscription_request(&PLANT, callback_id, req).await {
+                    return self.subscription_rejected(&stream_name, subscription, receiver, reason).await;
+                }
             }
+            subscribe_stream(&stream_name, subscription.clone(), receiver).await;
         }
-        println!("{} Subscribed: {}", stream_name, subscription);
+        tracing::info!("{} Subscribed: {}", stream_name, subscription);
         DataServerResponse::SubscribeResponse{ success: true, subscription: subscription.clone(), reason: None}
     }
 
@@ -322,7 +331,7 @@ impl VendorApiResponse for RithmicBrokerageClient {
         {
             //todo fix in ff_rithmic api this causes a lock
          /*   if let Err(e) = self.client.switch_heartbeat_required(SysInfraType::TickerPlant, true).await {
-                eprintln!("Failed to switch heartbeat: {}", e);
+                tracing::error!("Failed to switch heartbeat: {}", e);
             }*/
         }
 
@@ -341,6 +350,45 @@ impl VendorApiResponse for RithmicBrokerageClient {
         }
     }
 
+    async fn vendor_capabilities_response(&self, _mode: StrategyMode, _stream_name: StreamName, callback_id: u64) -> DataServerResponse {
+        DataServerResponse::VendorCapabilities {
+            callback_id,
+            capabilities: VendorCapabilities {
+                data_vendor: DataVendor::Rithmic,
+                base_data_types: vec![BaseDataType::Ticks, BaseDataType::Quotes, BaseDataType::Candles],
+                market_depth: true,
+                // The earliest date used by `update_historical_data`.
+                historical_from: Some("2019-06-03 00:00:00 UTC".to_string()),
+                max_requests_per_second: None,
+                order_types: vec![OrderType::Market, OrderType::Limit, OrderType::MarketIfTouched, OrderType::StopMarket, OrderType::StopLimit],
+            },
+        }
+    }
+
+    /// Searches the listed futures contracts with rithmic, if the ticker plant is not connected the known products are searched without contract codes.
+    async fn symbol_search_response(&self, _mode: StrategyMode, _stream_name: StreamName, query: String, callback_id: u64) -> DataServerResponse {
+        if !self.writers.contains_key(&SysInfraType::TickerPlant) {
+            let results = get_available_rithmic_symbol_names().iter()
+                .filter_map(|name| {
+                    let exchange = get_exchange_by_symbol_name(name)?;
+                    Some(SymbolSearchResult {
+                        symbol: Symbol::new(name.clone(), self.data_vendor, MarketType::Futures(exchange)),
+                        symbol_code: None,
+                        description: String::new(),
+                        exchange: Some(exchange.to_string()),
+                        expiration: None,
+                    })
+                })
+                .filter(|result| result.matches(&query))
+                .collect();
+            return DataServerResponse::SymbolSearch { callback_id, results };
+        }
+        match self.search_symbols(query).await {
+            Ok(results) => DataServerResponse::SymbolSearch { callback_id, results },
+            Err(e) => DataServerResponse::Error { callback_id, error: FundForgeError::ServerErrorDebug(e) }
+        }
+    }
+
     async fn logout_command_vendors(&self, stream_name: StreamName) {
         self.callbacks.remove(&stream_name);
     }
@@ -0,0 +1,294 @@
+use std::net::SocketAddr;
+use std::str::FromStr;
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+use subtle::ConstantTimeEq;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use ff_standard_lib::server_launch_options::ServerLaunchOptions;
+use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType;
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+use ff_standard_lib::standardized_types::enums::MarketType;
+use ff_standard_lib::standardized_types::resolution::Resolution;
+use ff_standard_lib::standardized_types::subscriptions::Symbol;
+use ff_standard_lib::StreamName;
+use crate::async_listener::create_listener;
+use crate::bitget_api::api_client::{bitget_init, BITGET_CLIENT};
+use crate::data_bento_api::api_client::{data_bento_init, get_data_bento_client};
+use crate::forex_factory_api::api_client::{forex_factory_init, FOREX_FACTORY_CLIENT};
+use crate::oanda_api::api_client::{oanda_init, OANDA_CLIENT};
+use crate::rithmic_api::api_client::{RithmicBrokerageClient, RITHMIC_CLIENTS};
+use crate::server_features::tls_config::reload_tls_config;
+use crate::stream_tasks::{connected_streams, deregister_streamer, is_connected};
+use crate::update_functions::{pre_subscribe_updates, update_data, DATA_STORAGE};
+use crate::{get_data_folder, subscribe_server_shutdown};
+
+/// The longest request line accepted, longer lines close the connection before the token is checked.
+const MAX_REQUEST_LENGTH: usize = 4096;
+
+/// The symbol to download for the `download` command, `resolution` uses the `Resolution` string format eg. `"1-M"`, `vendor` is the `DataVendor` name.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct AdminDownload {
+    symbol: String,
+    vendor: String,
+    market_type: MarketType,
+    base_data_type: BaseDataType,
+    resolution: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum AdminCommand {
+    Streams,
+    VendorFeeds,
+    Disconnect { stream: StreamName },
+    Download(AdminDownload),
+    UpdateAll,
+    ReloadCredentials,
+    ReloadCertificates,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct AdminRequest {
+    token: String,
+    #[serde(flatten)]
+    command: AdminCommand,
+}
+
+/// Serves administration commands for the running server, enabled with the `--admin_port` launch option.
+/// The socket is not encrypted so it is always bound to localhost, whatever the `listener_address`, use an ssh tunnel to administer a remote server.
+/// Each request must include the token saved in `credentials/admin_token.txt` in the data folder and the admin server does not start if the file is missing or empty.
+///
+/// # Commands
+/// Requests and replies are JSON, one per line, so the commands can be sent with a tool like `nc`:
+/// ```json
+/// {"token": "..", "command": "streams"}
+/// {"token": "..", "command": "vendor_feeds"}
+/// {"token": "..", "command": "disconnect", "stream": 52144}
+/// {"token": "..", "command": "download", "symbol": "MNQ", "vendor": "Rithmic", "market_type": {"Futures": "CME"}, "base_data_type": "Candles", "resolution": "1-M"}
+/// {"token": "..", "command": "update_all"}
+/// {"token": "..", "command": "reload_credentials"}
+/// {"token": "..", "command": "reload_certificates"}
+/// ```
+/// - `streams`: the connected strategy streams and their subscriptions.
+/// - `vendor_feeds`: the open vendor feeds and the number of streams receiving each one.
+/// - `disconnect`: drops the stream, its subscriptions are released the same as when the strategy disconnects.
+/// - `download`: updates the symbol from its latest stored data, or from its start date in the `download_list.toml` of the vendor, the same as the scheduled updates.
+/// - `update_all`: runs the scheduled update of the download lists now.
+/// - `reload_credentials`: connects the vendors and Rithmic systems whose credentials were added or fixed since the server started,
+///   vendors which are already connected keep their session.
+/// - `reload_certificates`: reloads the tls certificates now instead of at the next check for changed files, established connections keep their session.
+///
+/// Each request is answered with `{"success": true, "result": ..}` or `{"success": false, "reason": ..}`.
+pub(crate) async fn admin_server(addr: SocketAddr, options: ServerLaunchOptions) {
+    let token = match std::fs::read_to_string(get_data_folder().join("credentials").join("admin_token.txt")) {
+        Ok(token) if !token.trim().is_empty() => token.trim().to_string(),
+        _ => {
+            tracing::error!("Admin: No token found at credentials/admin_token.txt, the admin server was not started");
+            return;
+        }
+    };
+    let listener = match create_listener(addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("Admin: Failed to create listener on {}: {}", addr, e);
+            return;
+        }
+    };
+    tracing::info!("Admin: Listening on: {}", addr);
+
+    let mut shutdown_receiver = subscribe_server_shutdown();
+    loop {
+        tokio::select! {
+            result = listener.accept() => {
+                if let Ok((stream, peer_addr)) = result {
+                    tokio::spawn(handle_connection(stream, peer_addr, token.clone(), options.clone()));
+                }
+            }
+            _ = shutdown_receiver.recv() => {
+                tracing::info!("Admin: Shutdown signal received, stopping accept loop");
+                break;
+            }
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, peer_addr: SocketAddr, token: String, options: ServerLaunchOptions) {
+    let (read_half, mut write_half) = stream.into_split();
+    let mut reader = BufReader::new(read_half);
+    let mut line = String::new();
+    let mut shutdown_receiver = subscribe_server_shutdown();
+    loop {
+        line.clear();
+        let mut request = (&mut reader).take(MAX_REQUEST_LENGTH as u64);
+        tokio::select! {
+            read = request.read_line(&mut line) => match read {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if !line.ends_with('\n') && line.len() >= MAX_REQUEST_LENGTH {
+                        tracing::warn!("Admin: Closed the connection from {}, the request is longer than {} bytes", peer_addr, MAX_REQUEST_LENGTH);
+                        let _ = write_half.write_all(format!("{}\n", json!({"success": false, "reason": "Request too long"})).as_bytes()).await;
+                        break;
+                    }
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let reply = handle_request(&line, &token, &options, peer_addr).await;
+                    if write_half.write_all(format!("{}\n", reply).as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            },
+            _ = shutdown_receiver.recv() => break,
+        }
+    }
+}
+
+async fn handle_request(line: &str, token: &str, options: &ServerLaunchOptions, peer_addr: SocketAddr) -> Value {
+    let request: AdminRequest = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return json!({"success": false, "reason": format!("Invalid request: {}", e)}),
+    };
+    if !bool::from(request.token.as_bytes().ct_eq(token.as_bytes())) {
+        tracing::warn!("Admin: Rejected a request with an invalid token from {}", peer_addr);
+        return json!({"success": false, "reason": "Invalid token"});
+    }
+    tracing::info!("Admin: {:?} from {}", request.command, peer_addr);
+    match run_command(request.command, options).await {
+        Ok(result) => json!({"success": true, "result": result}),
+        Err(reason) => json!({"success": false, "reason": reason}),
+    }
+}
+
+async fn run_command(command: AdminCommand, options: &ServerLaunchOptions) -> Result<Value, String> {
+    match command {
+        AdminCommand::Streams => {
+            let streams: Vec<Value> = connected_streams().await.into_iter()
+                .map(|(stream_name, subscriptions)| json!({
+                    "stream": stream_name,
+                    "subscriptions": subscriptions.iter().map(|subscription| subscription.to_string()).collect::<Vec<_>>(),
+                }))
+                .collect();
+            Ok(json!(streams))
+        }
+        AdminCommand::VendorFeeds => Ok(json!(vendor_feeds())),
+        AdminCommand::Disconnect { stream } => {
+            if !is_connected(&stream) {
+                return Err(format!("Stream {} is not connected", stream));
+            }
+            deregister_streamer(&stream).await;
+            Ok(json!(format!("Stream {} disconnected", stream)))
+        }
+        AdminCommand::Download(download) => {
+            let storage = DATA_STORAGE.get().ok_or("Data storage is not initialized".to_string())?.clone();
+            let vendor = DataVendor::from_str(&download.vendor).map_err(|e| format!("{:?}", e))?;
+            let resolution = Resolution::from_str(&download.resolution)?;
+            let symbol = Symbol::new(download.symbol, vendor, download.market_type);
+            let description = format!("{} {} {} {}", symbol.name, vendor, resolution, download.base_data_type);
+            tokio::spawn(pre_subscribe_updates(storage, symbol, resolution, download.base_data_type));
+            Ok(json!(format!("Download started: {}", description)))
+        }
+        AdminCommand::UpdateAll => {
+            let storage = DATA_STORAGE.get().ok_or("Data storage is not initialized".to_string())?.clone();
+            tokio::spawn(async move {
+                if let Err(e) = update_data(storage, false).await {
+                    tracing::error!("Admin: Update failed: {}", e);
+                }
+            });
+            Ok(json!("Update started"))
+        }
+        AdminCommand::ReloadCredentials => Ok(reload_credentials(options.clone()).await),
+        AdminCommand::ReloadCertificates => reload_tls_config(options).map(|_| json!("Certificates reloaded")),
+    }
+}
+
+fn feed(vendor: DataVendor, feed: &str, symbol: String, receivers: usize) -> Value {
+    json!({"vendor": vendor.to_string(), "feed": feed, "symbol": symbol, "receivers": receivers})
+}
+
+/// The open vendor feeds, `receivers` is the number of streams and server tasks receiving the feed.
+fn vendor_feeds() -> Vec<Value> {
+    let mut feeds = vec![];
+    for client in RITHMIC_CLIENTS.iter() {
+        for (name, broadcasters) in [("ticks", &client.tick_feed_broadcasters), ("quotes", &client.quote_feed_broadcasters), ("candles", &client.candle_feed_broadcasters)] {
+            for broadcaster in broadcasters.iter() {
+                feeds.push(feed(DataVendor::Rithmic, name, broadcaster.key().clone(), broadcaster.value().receiver_count()));
+            }
+        }
+    }
+    if let Some(client) = OANDA_CLIENT.get() {
+        for broadcaster in client.quote_feed_broadcasters.iter() {
+            feeds.push(feed(DataVendor::Oanda, "quotes", broadcaster.key().clone(), broadcaster.value().receiver_count()));
+        }
+        for broadcaster in client.quotebar_broadcasters.iter() {
+            feeds.push(feed(DataVendor::Oanda, "quote_bars", broadcaster.key().to_string(), broadcaster.value().receiver_count()));
+        }
+    }
+    if let Some(client) = BITGET_CLIENT.get() {
+        for (name, symbol, receivers) in client.feed_receiver_counts() {
+            feeds.push(feed(DataVendor::Bitget, name, symbol, receivers));
+        }
+    }
+    if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+        for broadcaster in client.broadcasters.iter() {
+            feeds.push(feed(DataVendor::ForexFactory, "calendar", broadcaster.key().clone(), broadcaster.value().receiver_count()));
+        }
+    }
+    feeds
+}
+
+/// Runs the initialization of the vendors which are not connected, the credentials are read from the data folder the same as at launch.
+async fn reload_credentials(options: ServerLaunchOptions) -> Value {
+    RithmicBrokerageClient::init_rithmic_apis(options.clone()).await;
+    if OANDA_CLIENT.get().is_none() {
+        oanda_init(options.clone()).await;
+    }
+    if BITGET_CLIENT.get().is_none() {
+        bitget_init(options.clone()).await;
+    }
+    if FOREX_FACTORY_CLIENT.get().is_none() {
+        forex_factory_init(options.clone()).await;
+    }
+    if get_data_bento_client().is_err() {
+        if let Err(e) = data_bento_init(options).await {
+            tracing::info!("Admin: Data Bento not initialized: {}", e);
+        }
+    }
+    let mut rithmic_systems: Vec<String> = RITHMIC_CLIENTS.iter().map(|client| client.key().to_string()).collect();
+    rithmic_systems.sort();
+    json!({
+        "rithmic": rithmic_systems,
+        "oanda": OANDA_CLIENT.get().is_some(),
+        "bitget": BITGET_CLIENT.get().is_some(),
+        "forex_factory": FOREX_FACTORY_CLIENT.get().is_some(),
+        "data_bento": get_data_bento_client().is_ok(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff_standard_lib::standardized_types::enums::FuturesExchange;
+
+    #[test]
+    fn test_admin_requests() {
+        let request: AdminRequest = serde_json::from_str(r#"{"token": "secret", "command": "disconnect", "stream": 52144}"#).unwrap();
+        assert_eq!(request.token, "secret");
+        assert_eq!(request.command, AdminCommand::Disconnect { stream: 52144 });
+
+        let request = r#"{"token": "secret", "command": "download", "symbol": "MNQ", "vendor": "Rithmic", "market_type": {"Futures": "CME"}, "base_data_type": "Candles", "resolution": "1-M"}"#;
+        match serde_json::from_str::<AdminRequest>(request).unwrap().command {
+            AdminCommand::Download(download) => {
+                assert_eq!(download.market_type, MarketType::Futures(FuturesExchange::CME));
+                assert_eq!(Resolution::from_str(&download.resolution).unwrap(), Resolution::Minutes(1));
+            }
+            command => panic!("Expected a download command, got {:?}", command),
+        }
+
+        assert_eq!(serde_json::from_str::<AdminRequest>(r#"{"token": "secret", "command": "reload_credentials"}"#).unwrap().command, AdminCommand::ReloadCredentials);
+        assert_eq!(serde_json::from_str::<AdminRequest>(r#"{"token": "secret", "command": "reload_certificates"}"#).unwrap().command, AdminCommand::ReloadCertificates);
+        // The token is required and unknown commands are rejected.
+        assert!(serde_json::from_str::<AdminRequest>(r#"{"command": "streams"}"#).is_err());
+        assert!(serde_json::from_str::<AdminRequest>(r#"{"token": "secret", "command": "restart"}"#).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use serde_derive::Deserialize;
+use ff_standard_lib::messages::data_server_messaging::{DataServerRequest, DataServerResponse};
+use ff_standard_lib::standardized_types::accounts::{Account, AccountId};
+use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+use ff_standard_lib::StreamName;
+
+/// `None` when no keys file exists, connections are then not authenticated.
+static API_KEYS: OnceLock<Option<HashMap<String, ApiKey>>> = OnceLock::new();
+
+lazy_static! {
+    /// The key each connection registered with, the live data stream of a connection must register with the same key.
+    static ref CONNECTION_KEYS: DashMap<StreamName, String> = DashMap::new();
+}
+
+/// What a key is allowed to do, each permission includes the permissions before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyPermission {
+    /// Market data, historical data, symbol info and charts, no account, order or message requests.
+    Data,
+    /// Account info and orders for the `accounts` of the key, and publishing and subscribing to message topics.
+    Trade,
+    /// Account info and orders for every account, and requests which change the server settings, eg. saving subscription profiles.
+    Admin,
+}
+
+/// An api key strategies register with, see `init_api_keys()`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiKey {
+    /// A name for the logs, so the key itself is never logged.
+    pub name: String,
+    pub permission: ApiKeyPermission,
+    /// The accounts a `Trade` key may use, an account id is only allowed with the brokerage it is listed with, ignored for `Admin` keys.
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiKeysFile {
+    #[serde(default)]
+    keys: HashMap<String, ApiKey>,
+}
+
+/// Loads the api keys from `{data_folder}/credentials/api_keys.toml` when the server starts.
+/// If the file does not exist connections are not authenticated and have full access, which is only safe while the server is bound to localhost.
+/// If the file exists every connection must register with one of its keys, a file which fails to parse rejects every connection.
+///
+/// # File Format
+/// The keys are the table names:
+/// ```toml
+/// [keys."3f9c1e..."]
+/// name = "research"
+/// permission = "data"
+///
+/// [keys."a81d44..."]
+/// name = "apex bot"
+/// permission = "trade"
+/// accounts = [{ brokerage = { Rithmic = "Apex" }, account_id = "APEX-3396-168" }]
+///
+/// [keys."77be02..."]
+/// name = "desk"
+/// permission = "admin"
+/// ```
+pub fn init_api_keys(data_folder: &Path) {
+    let path = data_folder.join("credentials").join("api_keys.toml");
+    let keys = match fs::read_to_string(&path) {
+        Ok(content) => match toml::from_str::<ApiKeysFile>(&content) {
+            Ok(file) => Some(file.keys),
+            Err(e) => {
+                tracing::error!("Failed to parse api keys file {}, every connection will be rejected: {}", path.display(), e);
+                Some(HashMap::new())
+            }
+        },
+        Err(_) => None
+    };
+    match &keys {
+        Some(keys) => {
+            for key in keys.values() {
+                tracing::info!("Api Keys: {}: {:?} {:?}", key.name, key.permission, key.accounts);
+            }
+        }
+        None => tracing::warn!("Api Keys: No api keys file, connections are not authenticated, only bind the server to localhost"),
+    }
+    let _ = API_KEYS.set(keys);
+}
+
+/// Authenticates a connection with the key of its `DataServerRequest::Register`.
+pub(crate) fn authenticate(stream_name: StreamName, api_key: Option<String>) -> Result<ConnectionAccess, String> {
+    let keys = match API_KEYS.get() {
+        Some(Some(keys)) => keys,
+        _ => return Ok(ConnectionAccess { key: None }),
+    };
+    let api_key = api_key.ok_or_else(|| "No api key".to_string())?;
+    let key = keys.get(&api_key).ok_or_else(|| "Invalid api key".to_string())?;
+    CONNECTION_KEYS.insert(stream_name, api_key);
+    Ok(ConnectionAccess { key: Some(key.clone()) })
+}
+
+/// True if the live data stream may attach to the connection, the stream must send the key the connection registered with.
+pub(crate) fn authorize_streamer(stream_name: &StreamName, api_key: Option<&String>) -> bool {
+    match API_KEYS.get() {
+        Some(Some(_)) => {}
+        _ => return true,
+    }
+    match (CONNECTION_KEYS.get(stream_name), api_key) {
+        (Some(key), Some(api_key)) => key.value() == api_key,
+        _ => false,
+    }
+}
+
+pub(crate) fn deregister_connection_key(stream_name: &StreamName) {
+    CONNECTION_KEYS.remove(stream_name);
+}
+
+/// The access of an authenticated connection, `key` is `None` when api keys are not configured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionAccess {
+    key: Option<ApiKey>,
+}
+
+impl ConnectionAccess {
+    pub fn allows_account(&self, brokerage: &Brokerage, account_id: &AccountId) -> bool {
+        match &self.key {
+            None => true,
+            Some(key) => match key.permission {
+                ApiKeyPermission::Data => false,
+                ApiKeyPermission::Trade => key.accounts.iter().any(|account| account.brokerage == *brokerage && account.account_id == *account_id),
+                ApiKeyPermission::Admin => true,
+            }
+        }
+    }
+
+    /// Removes the accounts the key may not use from the `DataServerResponse::Accounts` of the brokerage.
+    pub fn filter_accounts(&self, brokerage: &Brokerage, response: DataServerResponse) -> DataServerResponse {
+        match response {
+            DataServerResponse::Accounts { callback_id, accounts } => DataServerResponse::Accounts {
+                callback_id,
+                accounts: accounts.into_iter().filter(|account_id| self.allows_account(brokerage, account_id)).collect(),
+            },
+            response => response,
+        }
+    }
+
+    /// Checks the request against the permission of the key, returns the reason if the request is not allowed.
+    /// Every request is matched explicitly, so a new request must be given a permission before it compiles.
+    pub fn check(&self, request: &DataServerRequest) -> Result<(), String> {
+        let key = match &self.key {
+            None => return Ok(()),
+            Some(key) => key,
+        };
+        let required = match request {
+            DataServerRequest::OrderRequest { request } => return self.check_account(key, &request.brokerage(), request.account_id()),
+            DataServerRequest::AccountInfo { brokerage, account_id, .. } => return self.check_account(key, brokerage, account_id),
+            DataServerRequest::Accounts { .. }
+            | DataServerRequest::PublishMessage { .. }
+            | DataServerRequest::SubscribeTopic { .. } => ApiKeyPermission::Trade,
+            DataServerRequest::SaveSubscriptionProfile { .. } => ApiKeyPermission::Admin,
+            DataServerRequest::Register { .. }
+            | DataServerRequest::SymbolsVendor { .. }
+            | DataServerRequest::BaseDataTypes { .. }
+            | DataServerRequest::VendorCapabilities { .. }
+            | DataServerRequest::Resolutions { .. }
+            | DataServerRequest::WarmUpResolutions { .. }
+            | DataServerRequest::Markets { .. }
+            | DataServerRequest::TickSize { .. }
+            | DataServerRequest::DecimalAccuracy { .. }
+            | DataServerRequest::SymbolInfo { .. }
+            | DataServerRequest::StreamRequest { .. }
+            | DataServerRequest::PrimarySubscriptionFor { .. }
+            | DataServerRequest::CommissionInfo { .. }
+            | DataServerRequest::FinancingRate { .. }
+            | DataServerRequest::ExchangeRate { .. }
+            | DataServerRequest::GetCompressedHistoricalData { .. }
+            | DataServerRequest::HistoricalDataAck { .. }
+            | DataServerRequest::DataVersion { .. }
+            | DataServerRequest::FrontMonthInfo { .. }
+            | DataServerRequest::SymbolNames { .. }
+            | DataServerRequest::SymbolSearch { .. }
+            | DataServerRequest::SymbolCatalog { .. }
+            | DataServerRequest::OptionsChain { .. }
+            | DataServerRequest::SubscriptionProfile { .. }
+            | DataServerRequest::RegisterStreamer { .. }
+            | DataServerRequest::PublishChartUpdates { .. }
+            | DataServerRequest::SubscribeChart { .. }
+            | DataServerRequest::UnsubscribeChart { .. }
+            | DataServerRequest::UnsubscribeTopic { .. }
+            | DataServerRequest::Heartbeat => ApiKeyPermission::Data,
+        };
+        match key.permission >= required {
+            true => Ok(()),
+            false => Err(format!("Api key '{}' does not have {:?} permission", key.name, required)),
+        }
+    }
+
+    fn check_account(&self, key: &ApiKey, brokerage: &Brokerage, account_id: &AccountId) -> Result<(), String> {
+        match self.allows_account(brokerage, account_id) {
+            true => Ok(()),
+            false => Err(format!("Api key '{}' is not allowed to use {} account {}", key.name, brokerage, account_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff_standard_lib::apis::rithmic::rithmic_systems::RithmicSystem;
+    use ff_standard_lib::standardized_types::orders::OrderRequest;
+    use ff_standard_lib::strategies::subscription_profiles::SubscriptionProfile;
+
+    #[test]
+    fn test_api_key_permissions() {
+        let file: ApiKeysFile = toml::from_str(r#"
+            [keys.data-key]
+            name = "research"
+            permission = "data"
+
+            [keys.trade-key]
+            name = "bot"
+            permission = "trade"
+            accounts = [{ brokerage = "Test", account_id = "APEX-1" }]
+
+            [keys.admin-key]
+            name = "desk"
+            permission = "admin"
+        "#).unwrap();
+        let access = |key: &str| ConnectionAccess { key: file.keys.get(key).cloned() };
+        let flatten_for = |brokerage: Brokerage, account_id: &str| DataServerRequest::OrderRequest {
+            request: OrderRequest::FlattenAllFor { account: Account::new(brokerage, account_id.to_string()) }
+        };
+        let flatten = |account_id: &str| flatten_for(Brokerage::Test, account_id);
+        let accounts = DataServerRequest::Accounts { callback_id: 1, brokerage: Brokerage::Test };
+        let save_profile = DataServerRequest::SaveSubscriptionProfile { callback_id: 1, profile: SubscriptionProfile::new("profile") };
+        let publish = DataServerRequest::PublishMessage { topic: "signals".to_string(), payload: vec![1] };
+        let subscribe_topic = DataServerRequest::SubscribeTopic { topic: "signals".to_string() };
+
+        assert!(access("data-key").check(&flatten("APEX-1")).is_err());
+        assert!(access("data-key").check(&accounts).is_err());
+        assert!(access("data-key").check(&DataServerRequest::Heartbeat).is_ok());
+        assert!(access("data-key").check(&publish).is_err());
+        assert!(access("data-key").check(&subscribe_topic).is_err());
+
+        assert!(access("trade-key").check(&flatten("APEX-1")).is_ok());
+        assert!(access("trade-key").check(&flatten("APEX-2")).is_err());
+        // the same account id at another brokerage is a different account
+        assert!(access("trade-key").check(&flatten_for(Brokerage::Rithmic(RithmicSystem::Apex), "APEX-1")).is_err());
+        let account_info = |brokerage: Brokerage| DataServerRequest::AccountInfo { callback_id: 1, brokerage, account_id: "APEX-1".to_string() };
+        assert!(access("trade-key").check(&account_info(Brokerage::Test)).is_ok());
+        assert!(access("trade-key").check(&account_info(Brokerage::Oanda)).is_err());
+        assert!(access("trade-key").check(&accounts).is_ok());
+        assert!(access("trade-key").check(&publish).is_ok());
+        assert!(access("trade-key").check(&subscribe_topic).is_ok());
+        let response = DataServerResponse::Accounts { callback_id: 1, accounts: vec!["APEX-1".to_string(), "APEX-2".to_string()] };
+        match access("trade-key").filter_accounts(&Brokerage::Test, response.clone()) {
+            DataServerResponse::Accounts { accounts, .. } => assert_eq!(accounts, vec!["APEX-1".to_string()]),
+            _ => panic!("Expected an accounts response"),
+        }
+        match access("trade-key").filter_accounts(&Brokerage::Oanda, response) {
+            DataServerResponse::Accounts { accounts, .. } => assert!(accounts.is_empty()),
+            _ => panic!("Expected an accounts response"),
+        }
+        assert!(access("trade-key").check(&save_profile).is_err());
+
+        assert!(access("admin-key").check(&flatten("APEX-2")).is_ok());
+        assert!(access("admin-key").check(&save_profile).is_ok());
+
+        // Without keys configured every request is allowed.
+        assert!(ConnectionAccess { key: None }.check(&flatten("APEX-2")).is_ok());
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use lazy_static::lazy_static;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+use ff_standard_lib::messages::data_server_messaging::{DataServerResponse, FundForgeError, HISTORY_PARTS_IN_FLIGHT};
+use ff_standard_lib::messages::stream_compression::StreamCompression;
+use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+use ff_standard_lib::standardized_types::subscriptions::DataSubscription;
+use ff_standard_lib::StreamName;
+use crate::update_functions::{pre_subscribe_updates, DATA_STORAGE};
+
+/// How long a part waits for the client to acknowledge the earlier parts before the response is abandoned.
+const ACK_TIMEOUT: Duration = Duration::from_secs(120);
+
+lazy_static! {
+    /// The parts each history response may still send ahead of the client acknowledgements, by connection and callback id.
+    static ref HISTORY_CREDITS: DashMap<(StreamName, u64), Arc<Semaphore>> = DashMap::new();
+}
+
+/// Streams the stored files of the subscriptions in the range as `DataServerResponse::HistoricalDataPart`s followed by `DataServerResponse::HistoricalDataComplete`.
+/// Files are read one at a time and at most `HISTORY_PARTS_IN_FLIGHT` parts are sent before the client acknowledges them, so a long tick range never sits in memory.
+/// Days the vendor of a subscription has no file for are filled from its fallback vendors, see `merge_vendor_files()`.
+pub async fn stream_compressed_files(
+    stream_name: StreamName,
+    subscriptions: Vec<DataSubscription>,
+    from_time: String,
+    to_time: String,
+    compression: StreamCompression,
+    callback_id: u64,
+    sender: Sender<DataServerResponse>,
+) {
+    let paths = match history_file_paths(subscriptions, from_time, to_time).await {
+        Ok(paths) => paths,
+        Err(error) => {
+            let _ = sender.send(DataServerResponse::Error { callback_id, error }).await;
+            return;
+        }
+    };
+
+    let credits = Arc::new(Semaphore::new(HISTORY_PARTS_IN_FLIGHT));
+    HISTORY_CREDITS.insert((stream_name, callback_id), credits.clone());
+    let response = send_parts(paths, compression, callback_id, &credits, &sender).await;
+    HISTORY_CREDITS.remove(&(stream_name, callback_id));
+    let response = match response {
+        Ok(parts) => DataServerResponse::HistoricalDataComplete { callback_id, parts },
+        Err(error) => DataServerResponse::Error { callback_id, error },
+    };
+    let _ = sender.send(response).await;
+}
+
+async fn send_parts(
+    paths: Vec<HistoryFile>,
+    compression: StreamCompression,
+    callback_id: u64,
+    credits: &Semaphore,
+    sender: &Sender<DataServerResponse>,
+) -> Result<u64, FundForgeError> {
+    let data_storage = DATA_STORAGE.get()
+        .ok_or_else(|| FundForgeError::ServerErrorDebug("Data storage not initialized".to_string()))?;
+    let mut sequence = 0;
+    for file in paths {
+        match timeout(ACK_TIMEOUT, credits.acquire()).await {
+            Ok(Ok(permit)) => permit.forget(),
+            Ok(Err(_)) => return Err(FundForgeError::ServerErrorDebug("History response cancelled".to_string())),
+            Err(_) => return Err(FundForgeError::ServerErrorDebug("Timed out waiting for history acknowledgement".to_string())),
+        }
+        let compressed = data_storage.read_compressed_file(&file.path).await?;
+        let payload = match file.deliver_as {
+            Some(data_vendor) => rewrite_vendor(compression, compressed, data_vendor)?,
+            None => encode_file(compression, compressed)?,
+        };
+        let part = DataServerResponse::HistoricalDataPart { callback_id, sequence, payload };
+        if sender.send(part).await.is_err() {
+            return Err(FundForgeError::ServerErrorDebug("Connection closed".to_string()));
+        }
+        sequence += 1;
+    }
+    Ok(sequence)
+}
+
+/// The stored files are gzip compressed, they are sent as they are unless zstd was requested.
+fn encode_file(compression: StreamCompression, file: Vec<u8>) -> Result<Vec<u8>, FundForgeError> {
+    match compression {
+        StreamCompression::None => Ok(file),
+        StreamCompression::Zstd => {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(file.as_slice()).read_to_end(&mut decompressed)
+                .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to decompress file: {}", e)))?;
+            compression.compress(decompressed)
+        }
+    }
+}
+
+/// The data of a fallback vendor file is decoded and delivered as the data of the subscribed vendor, so the client can not tell the vendors apart.
+fn rewrite_vendor(compression: StreamCompression, file: Vec<u8>, data_vendor: DataVendor) -> Result<Vec<u8>, FundForgeError> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(file.as_slice()).read_to_end(&mut decompressed)
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to decompress file: {}", e)))?;
+    let mut data = BaseDataEnum::from_array_bytes(&decompressed)
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to deserialize file: {}", e)))?;
+    for data_point in data.iter_mut() {
+        data_point.set_data_vendor(data_vendor);
+    }
+    let bytes = BaseDataEnum::vec_to_storage_bytes(data);
+    match compression {
+        StreamCompression::None => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&bytes)
+                .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to compress file: {}", e)))?;
+            encoder.finish()
+                .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to compress file: {}", e)))
+        }
+        StreamCompression::Zstd => compression.compress(bytes),
+    }
+}
+
+/// A stored file to send, `deliver_as` is the subscribed vendor when the file is from one of its fallback vendors.
+struct HistoryFile {
+    path: PathBuf,
+    deliver_as: Option<DataVendor>,
+}
+
+/// Merges the daily files of the vendors of a subscription, given in fallback order starting with the subscribed vendor.
+/// Each day is taken from the first vendor with a file for it, the files are returned in date order.
+fn merge_vendor_files(files_by_vendor: Vec<(DataVendor, Vec<PathBuf>)>) -> Vec<HistoryFile> {
+    let subscribed_vendor = match files_by_vendor.first() {
+        Some((vendor, _)) => *vendor,
+        None => return vec![],
+    };
+    let mut days: BTreeMap<OsString, HistoryFile> = BTreeMap::new();
+    for (vendor, paths) in files_by_vendor {
+        for path in paths {
+            let day = match path.file_name() {
+                Some(day) => day.to_os_string(),
+                None => continue,
+            };
+            let deliver_as = match vendor == subscribed_vendor {
+                true => None,
+                false => Some(subscribed_vendor),
+            };
+            days.entry(day).or_insert(HistoryFile { path, deliver_as });
+        }
+    }
+    days.into_values().collect()
+}
+
+async fn history_file_paths(subscriptions: Vec<DataSubscription>, from_time: String, to_time: String) -> Result<Vec<HistoryFile>, FundForgeError> {
+    let from_time = from_time.parse::<DateTime<Utc>>()
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Invalid from_time: {}", e)))?;
+    let to_time = to_time.parse::<DateTime<Utc>>()
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Invalid to_time: {}", e)))?;
+
+    // Limit the date range to prevent huge requests
+    if (to_time - from_time).num_days() > 365 {
+        return Err(FundForgeError::ServerErrorDebug("Date range exceeds maximum of 365 days".to_string()));
+    }
+
+    let data_storage = DATA_STORAGE.get()
+        .ok_or_else(|| FundForgeError::ServerErrorDebug("Data storage not initialized".to_string()))?;
+
+    if subscriptions.is_empty() {
+        return Err(FundForgeError::ClientSideErrorDebug("No subscriptions provided for file range".to_string()));
+    }
+
+    if to_time.date_naive() >= Utc::now().date_naive() {
+        // Trading periods are not downloaded, they are rolled up from the stored data below
+        let tasks: Vec<_> = subscriptions.iter()
+            .filter(|subscription| !subscription.resolution.is_trading_period())
+            .flat_map(|subscription| subscription.vendor_subscriptions()).map(|subscription| {
+            pre_subscribe_updates(
+                data_storage.clone(),
+                subscription.symbol.clone(),
+                subscription.resolution,
+                subscription.base_data_type,
+            )
+        }).collect();
+
+        futures::future::join_all(tasks).await;
+    }
+
+    let mut files = Vec::new();
+    for subscription in subscriptions {
+        let mut files_by_vendor = Vec::new();
+        for vendor_subscription in subscription.vendor_subscriptions() {
+            if let Err(e) = data_storage.roll_up_trading_periods(&vendor_subscription, from_time, to_time).await {
+                tracing::warn!("History: Unable to roll up {}: {}", vendor_subscription, e);
+            }
+            let paths = data_storage.get_files_in_range(
+                &vendor_subscription.symbol,
+                &vendor_subscription.resolution,
+                &vendor_subscription.base_data_type,
+                from_time,
+                to_time
+            ).await?;
+            files_by_vendor.push((vendor_subscription.symbol.data_vendor, paths));
+        }
+        files.extend(merge_vendor_files(files_by_vendor));
+    }
+
+    if files.is_empty() {
+        return Err(FundForgeError::ServerErrorDebug("No files found in range".to_string()));
+    }
+    Ok(files)
+}
+
+/// Allows the history response to send another part, called when the client acknowledges a part.
+pub fn acknowledge_history_part(stream_name: StreamName, callback_id: u64) {
+    if let Some(credits) = HISTORY_CREDITS.get(&(stream_name, callback_id)) {
+        credits.add_permits(1);
+    }
+}
+
+/// Cancels the history responses of the connection, called when the connection is closed.
+pub fn deregister_history_streams(stream_name: &StreamName) {
+    HISTORY_CREDITS.retain(|(name, _), credits| {
+        if name == stream_name {
+            credits.close();
+        }
+        name != stream_name
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff_standard_lib::standardized_types::base_data::candle::generate_5_day_candle_data;
+    use ff_standard_lib::standardized_types::base_data::traits::BaseData;
+
+    #[test]
+    fn test_encode_file() {
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i % 11) as u8).collect();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes).unwrap();
+        let file = encoder.finish().unwrap();
+
+        assert_eq!(encode_file(StreamCompression::None, file.clone()).unwrap(), file);
+        let zstd = encode_file(StreamCompression::Zstd, file).unwrap();
+        assert_eq!(StreamCompression::Zstd.decompress(zstd).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_merge_vendor_files() {
+        let rithmic = vec![PathBuf::from("rithmic/2024/06/20240603.bin"), PathBuf::from("rithmic/2024/06/20240605.bin")];
+        let data_bento = vec![
+            PathBuf::from("databento/2024/06/20240603.bin"),
+            PathBuf::from("databento/2024/06/20240604.bin"),
+            PathBuf::from("databento/2024/06/20240606.bin"),
+        ];
+        let files = merge_vendor_files(vec![(DataVendor::Rithmic, rithmic), (DataVendor::DataBento, data_bento)]);
+        let merged: Vec<(&str, Option<DataVendor>)> = files.iter().map(|file| (file.path.to_str().unwrap(), file.deliver_as)).collect();
+        assert_eq!(merged, vec![
+            ("rithmic/2024/06/20240603.bin", None),
+            ("databento/2024/06/20240604.bin", Some(DataVendor::Rithmic)),
+            ("rithmic/2024/06/20240605.bin", None),
+            ("databento/2024/06/20240606.bin", Some(DataVendor::Rithmic)),
+        ]);
+    }
+
+    #[test]
+    fn test_rewrite_vendor() {
+        let candles: Vec<BaseDataEnum> = generate_5_day_candle_data().into_iter().take(10).map(BaseDataEnum::Candle).collect();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&BaseDataEnum::vec_to_bytes(candles.clone())).unwrap();
+        let file = encoder.finish().unwrap();
+
+        let zstd = rewrite_vendor(StreamCompression::Zstd, file, DataVendor::Oanda).unwrap();
+        let rewritten = BaseDataEnum::from_array_bytes(&StreamCompression::Zstd.decompress(zstd).unwrap()).unwrap();
+        assert_eq!(rewritten.len(), candles.len());
+        assert!(rewritten.iter().all(|data| data.data_vendor() == DataVendor::Oanda));
+        assert_eq!(rewritten[0].time_closed_utc(), candles[0].time_closed_utc());
+    }
+
+    #[test]
+    fn test_history_credits() {
+        // all parts in flight, the next part waits for an acknowledgement
+        let credits = Arc::new(Semaphore::new(0));
+        HISTORY_CREDITS.insert((7, 1), credits.clone());
+        acknowledge_history_part(7, 1);
+        acknowledge_history_part(7, 2);
+        assert_eq!(credits.available_permits(), 1);
+
+        deregister_history_streams(&7);
+        assert!(credits.is_closed());
+        assert!(HISTORY_CREDITS.get(&(7, 1)).is_none());
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use lazy_static::lazy_static;
+use tokio::sync::{broadcast, Mutex};
+use tokio::sync::broadcast::error::RecvError;
+use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use ff_standard_lib::standardized_types::base_data::traits::BaseData;
+use ff_standard_lib::standardized_types::subscriptions::DataSubscription;
+use crate::subscribe_server_shutdown;
+use crate::update_functions::{pre_subscribe_updates, DATA_STORAGE};
+
+/// Record mode persists the live data streamed for each subscription into the historical store, so the next backtest includes the current session without waiting for a download.
+/// Enabled with the `--record 1` launch option.
+static RECORDING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How often the recorded data is saved, each save rewrites the file for the day so this should not be too frequent for tick data.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Recording {
+    buffer: Vec<BaseDataEnum>,
+    /// The number of updates of the historical store still running, data is only saved while there are none so a recording never leaves a gap before it.
+    /// An update is run when recording starts and after each gap in the feed.
+    catching_up: usize,
+}
+
+lazy_static! {
+    /// The subscriptions being recorded, a feed shared by several streams is only recorded once.
+    static ref RECORDINGS: DashMap<DataSubscription, Arc<Mutex<Recording>>> = DashMap::new();
+}
+
+pub fn enable_recording() {
+    RECORDING_ENABLED.store(true, Ordering::SeqCst);
+    tracing::info!("Live Recording: Live data will be saved to the historical store");
+}
+
+/// Starts recording the subscription if record mode is enabled and it is not already being recorded, the receiver is a copy of the feed streamed to the client.
+pub fn record_subscription(subscription: &DataSubscription, receiver: &broadcast::Receiver<BaseDataEnum>) {
+    if !RECORDING_ENABLED.load(Ordering::SeqCst) {
+        return;
+    }
+    let recording = match RECORDINGS.entry(subscription.clone()) {
+        Entry::Occupied(_) => return,
+        Entry::Vacant(entry) => entry.insert(Arc::new(Mutex::new(Recording { buffer: vec![], catching_up: 1 }))).clone(),
+    };
+    tracing::info!("Live Recording: Recording {}", subscription);
+    catch_up(subscription.clone(), recording.clone());
+
+    let subscription = subscription.clone();
+    let mut receiver = receiver.resubscribe();
+    let mut shutdown_receiver = subscribe_server_shutdown();
+    tokio::spawn(async move {
+        let mut flush_interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = shutdown_receiver.recv() => break,
+                _ = flush_interval.tick() => flush(&subscription, &recording).await,
+                data = receiver.recv() => match data {
+                    Ok(data) => {
+                        if is_recorded(&subscription, &data) {
+                            recording.lock().await.buffer.push(data);
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Live Recording: {} skipped {} data points, updating the store to fill the gap", subscription, skipped);
+                        // The data before the gap is saved, the data after it is held until the update has filled the gap.
+                        flush(&subscription, &recording).await;
+                        recording.lock().await.catching_up += 1;
+                        catch_up(subscription.clone(), recording.clone());
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+        flush(&subscription, &recording).await;
+        RECORDINGS.remove(&subscription);
+    });
+}
+
+/// Updates the historical store from the vendor up to the current time, the recording is not saved until every update has finished.
+/// The recording must already count the update in `catching_up`.
+fn catch_up(subscription: DataSubscription, recording: Arc<Mutex<Recording>>) {
+    tokio::spawn(async move {
+        if let Some(storage) = DATA_STORAGE.get() {
+            pre_subscribe_updates(storage.clone(), subscription.symbol.clone(), subscription.resolution, subscription.base_data_type).await;
+        }
+        recording.lock().await.catching_up -= 1;
+    });
+}
+
+/// Saves the data of every recording, called before the server shuts down.
+pub async fn flush_recordings() {
+    let recordings: Vec<(DataSubscription, Arc<Mutex<Recording>>)> = RECORDINGS.iter()
+        .map(|recording| (recording.key().clone(), recording.value().clone()))
+        .collect();
+    for (subscription, recording) in recordings {
+        flush(&subscription, &recording).await;
+    }
+}
+
+/// The broadcasters of some vendors are shared by every resolution of a symbol, so only the data of the subscription is recorded, open bars are never recorded.
+fn is_recorded(subscription: &DataSubscription, data: &BaseDataEnum) -> bool {
+    data.is_closed() && data.base_data_type() == subscription.base_data_type && data.resolution() == subscription.resolution
+}
+
+/// Removes the data already in the store, a download which overlapped the recording is kept over the live data.
+fn unsaved_data(data: Vec<BaseDataEnum>, latest_saved: Option<DateTime<Utc>>) -> Vec<BaseDataEnum> {
+    match latest_saved {
+        Some(latest_saved) => data.into_iter().filter(|data| data.time_closed_utc() > latest_saved).collect(),
+        None => data,
+    }
+}
+
+/// The lock is held while saving so the batches of a recording are saved in order.
+async fn flush(subscription: &DataSubscription, recording: &Arc<Mutex<Recording>>) {
+    let mut recording = recording.lock().await;
+    if recording.catching_up > 0 || recording.buffer.is_empty() {
+        return;
+    }
+    let storage = match DATA_STORAGE.get() {
+        Some(storage) => storage,
+        None => return,
+    };
+    let data = std::mem::take(&mut recording.buffer);
+    let latest_saved = storage.get_latest_data_time(&subscription.symbol, &subscription.resolution, &subscription.base_data_type).await.ok().flatten();
+    let data = unsaved_data(data, latest_saved);
+    if data.is_empty() {
+        return;
+    }
+    if let Err(e) = storage.save_data_bulk(data).await {
+        tracing::error!("Live Recording: Failed to save {}: {}", subscription, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType;
+    use ff_standard_lib::standardized_types::base_data::candle::Candle;
+    use ff_standard_lib::standardized_types::base_data::tick::{Aggressor, Tick};
+    use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+    use ff_standard_lib::standardized_types::enums::{FuturesExchange, MarketType};
+    use ff_standard_lib::standardized_types::subscriptions::CandleType;
+    use ff_standard_lib::standardized_types::resolution::Resolution;
+
+    #[test]
+    fn test_recorded_data() {
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Ticks(1), BaseDataType::Ticks, MarketType::Futures(FuturesExchange::CME));
+        let start = DateTime::<Utc>::from_timestamp(1_717_425_000, 0).unwrap();
+        let tick = |seconds: i64| BaseDataEnum::Tick(Tick::new(subscription.symbol.clone(), dec!(20100), (start + chrono::Duration::seconds(seconds)).to_string(), dec!(1), Aggressor::Buy));
+
+        assert!(is_recorded(&subscription, &tick(0)));
+        let mut candle = Candle::new(subscription.symbol.clone(), dec!(20100), dec!(1), dec!(0), dec!(0), start.to_string(), Resolution::Minutes(1), CandleType::CandleStick);
+        candle.is_closed = true;
+        assert!(!is_recorded(&subscription, &BaseDataEnum::Candle(candle)));
+
+        // a download has already saved the first two ticks
+        let data = unsaved_data(vec![tick(0), tick(1), tick(2)], Some(start + chrono::Duration::seconds(1)));
+        assert_eq!(data, vec![tick(2)]);
+        assert_eq!(unsaved_data(vec![tick(0)], None).len(), 1);
+    }
+}
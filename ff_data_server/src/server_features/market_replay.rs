@@ -0,0 +1,199 @@
+use std::sync::OnceLock;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use tokio::sync::broadcast;
+use ff_standard_lib::messages::data_server_messaging::{DataServerRequest, DataServerResponse};
+use ff_standard_lib::server_launch_options::ServerLaunchOptions;
+use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use ff_standard_lib::standardized_types::base_data::traits::BaseData;
+use ff_standard_lib::standardized_types::enums::StrategyMode;
+use ff_standard_lib::standardized_types::subscriptions::DataSubscription;
+use ff_standard_lib::StreamName;
+use crate::stream_tasks::{register_stream, unsubscribe_stream};
+use crate::subscribe_server_shutdown;
+use crate::update_functions::DATA_STORAGE;
+
+/// The span of stored data loaded at a time by each replay feed.
+const LOAD_WINDOW: Duration = Duration::hours(1);
+
+/// Set when the server is launched with `--replay_from`.
+static REPLAY_CLOCK: OnceLock<ReplayClock> = OnceLock::new();
+
+lazy_static! {
+    /// The replay feed of each subscription, shared by the streams subscribed to it.
+    static ref REPLAY_FEEDS: DashMap<DataSubscription, broadcast::Sender<BaseDataEnum>> = DashMap::new();
+}
+
+/// Maps the wall clock to the historical time being replayed.
+/// All feeds share the clock, so the subscriptions of a replay stay in step with each other however late they subscribe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ReplayClock {
+    /// The historical time the replay started at.
+    from: DateTime<Utc>,
+    /// The wall clock time the replay started at.
+    started: DateTime<Utc>,
+    speed: f64,
+}
+
+impl ReplayClock {
+    pub fn new(from: DateTime<Utc>, started: DateTime<Utc>, speed: f64) -> Self {
+        Self {
+            from,
+            started,
+            speed,
+        }
+    }
+
+    /// The historical time being replayed at the wall clock time.
+    pub fn replay_time(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.from + scale(now - self.started, self.speed)
+    }
+
+    /// The wall clock time at which the historical time is replayed.
+    pub fn wall_time(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        self.started + scale(time - self.from, 1.0 / self.speed)
+    }
+
+    /// Added to the time of the replayed data, so at speed 1 the data is stamped with the time it is sent.
+    pub fn offset(&self) -> Duration {
+        self.started - self.from
+    }
+}
+
+fn scale(duration: Duration, factor: f64) -> Duration {
+    match duration.num_microseconds() {
+        Some(micros) => Duration::microseconds((micros as f64 * factor) as i64),
+        None => Duration::milliseconds((duration.num_milliseconds() as f64 * factor) as i64),
+    }
+}
+
+/// Starts the replay clock when the server is launched with `--replay_from`, the live data is then replayed from the stored data instead of streamed from the vendors.
+///
+/// Strategies in `StrategyMode::Live` and `StrategyMode::LivePaperTrading` subscribe and receive the replay over the normal stream, the same as live data.
+/// The data is sent as the replay clock reaches its close time, keeping the original time between data points divided by the `--replay_speed`,
+/// and the times are moved forward to the time the replay started, so the strategy sees the data as current.
+/// Faster speeds run ahead of the wall clock, so timed events and consolidators which close on the clock will not line up with the data.
+///
+/// Orders from `Live` strategies are rejected so nothing is sent to a broker at replayed prices, run the strategy in `LivePaperTrading` to trade the replay on the paper engine.
+pub fn init_market_replay(options: &ServerLaunchOptions) {
+    let from = match options.replay_from {
+        Some(from) => from,
+        None => return,
+    };
+    let speed = match options.replay_speed > 0.0 {
+        true => options.replay_speed,
+        false => {
+            tracing::error!("Market Replay: Invalid replay speed {}, replaying at speed 1", options.replay_speed);
+            1.0
+        }
+    };
+    let _ = REPLAY_CLOCK.set(ReplayClock::new(from, Utc::now(), speed));
+    tracing::info!("Market Replay: Replaying stored data from {} at speed {}", from, speed);
+}
+
+/// True if the server was launched in replay mode.
+pub(crate) fn is_replaying() -> bool {
+    REPLAY_CLOCK.get().is_some()
+}
+
+/// Subscribes the stream to the replay feed of the subscription, starting the feed if no other stream is receiving it.
+pub(crate) async fn replay_subscribe(stream_name: StreamName, subscription: DataSubscription) -> DataServerResponse {
+    let clock = match REPLAY_CLOCK.get() {
+        Some(clock) => *clock,
+        None => return DataServerResponse::SubscribeResponse { success: false, subscription, reason: Some("The server is not replaying".to_string()) },
+    };
+    let receiver = match REPLAY_FEEDS.get(&subscription) {
+        Some(sender) => sender.subscribe(),
+        None => {
+            let (sender, receiver) = broadcast::channel(1000);
+            REPLAY_FEEDS.insert(subscription.clone(), sender.clone());
+            replay_feed(clock, subscription.clone(), sender);
+            receiver
+        }
+    };
+    register_stream(&stream_name, subscription.clone(), receiver).await;
+    DataServerResponse::SubscribeResponse { success: true, subscription, reason: None }
+}
+
+pub(crate) async fn replay_unsubscribe(stream_name: StreamName, subscription: DataSubscription) -> DataServerResponse {
+    unsubscribe_stream(&stream_name, &subscription).await;
+    DataServerResponse::UnSubscribeResponse { success: true, subscription, reason: None }
+}
+
+/// Returns the reason the request can't be handled while replaying, orders from `Live` strategies are not sent to the broker.
+pub(crate) fn check_replay(mode: StrategyMode, request: &DataServerRequest) -> Result<(), String> {
+    match request {
+        DataServerRequest::OrderRequest { .. } if mode == StrategyMode::Live && is_replaying() => {
+            Err("The server is replaying market data, orders are not sent to the broker, run the strategy in LivePaperTrading to trade the replay".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Sends the stored data of the subscription as the replay clock reaches it, until no stream is receiving the feed.
+fn replay_feed(clock: ReplayClock, subscription: DataSubscription, sender: broadcast::Sender<BaseDataEnum>) {
+    let mut shutdown_receiver = subscribe_server_shutdown();
+    tokio::spawn(async move {
+        let data_storage = match DATA_STORAGE.get() {
+            Some(data_storage) => data_storage.clone(),
+            None => {
+                REPLAY_FEEDS.remove(&subscription);
+                return;
+            }
+        };
+        let mut from = clock.replay_time(Utc::now());
+        'replay: loop {
+            let to = from + LOAD_WINDOW;
+            let data = match data_storage.get_data_range(&subscription.symbol, &subscription.resolution, &subscription.base_data_type, from, to).await {
+                Ok(data) => data,
+                Err(e) => {
+                    tracing::error!("Market Replay: Failed to load {}: {}", subscription, e);
+                    vec![]
+                }
+            };
+            // The range includes both ends, data at `from` was sent with the previous window.
+            for mut base_data in data.into_iter().filter(|base_data| base_data.time_closed_utc() > from) {
+                if !wait_until(clock.wall_time(base_data.time_closed_utc()), &mut shutdown_receiver).await {
+                    break 'replay;
+                }
+                base_data.shift_time(clock.offset());
+                if sender.send(base_data).is_err() {
+                    break 'replay;
+                }
+            }
+            if !wait_until(clock.wall_time(to), &mut shutdown_receiver).await || sender.receiver_count() == 0 {
+                break;
+            }
+            from = to;
+        }
+        REPLAY_FEEDS.remove(&subscription);
+    });
+}
+
+/// Sleeps until the wall clock time, returns false if the server shuts down first.
+async fn wait_until(time: DateTime<Utc>, shutdown_receiver: &mut broadcast::Receiver<()>) -> bool {
+    let wait = (time - Utc::now()).to_std().unwrap_or_default();
+    tokio::select! {
+        _ = tokio::time::sleep(wait) => true,
+        _ = shutdown_receiver.recv() => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_replay_clock() {
+        let time = |time: &str| DateTime::<Utc>::from_str(time).unwrap();
+        let clock = ReplayClock::new(time("2024-06-05T13:00:00Z"), time("2024-10-01T09:00:00Z"), 2.0);
+        // Two seconds of data are replayed each second.
+        assert_eq!(clock.replay_time(time("2024-10-01T09:30:00Z")), time("2024-06-05T14:00:00Z"));
+        assert_eq!(clock.wall_time(time("2024-06-05T14:00:00Z")), time("2024-10-01T09:30:00Z"));
+        assert_eq!(time("2024-06-05T13:00:00Z") + clock.offset(), time("2024-10-01T09:00:00Z"));
+
+        assert!(check_replay(StrategyMode::LivePaperTrading, &DataServerRequest::Heartbeat).is_ok());
+    }
+}
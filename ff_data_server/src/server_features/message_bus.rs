@@ -0,0 +1,76 @@
+use ahash::AHashSet;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use ff_standard_lib::messages::data_server_messaging::DataServerResponse;
+use ff_standard_lib::messages::message_bus::Topic;
+use ff_standard_lib::StreamName;
+use crate::request_handlers::RESPONSE_SENDERS;
+
+lazy_static! {
+    /// The connections subscribed to each topic.
+    static ref TOPIC_SUBSCRIBERS: DashMap<Topic, AHashSet<StreamName>> = DashMap::new();
+}
+
+pub fn subscribe_topic(stream_name: StreamName, topic: Topic) {
+    tracing::info!("Stream {}: Subscribed to topic {}", stream_name, topic);
+    TOPIC_SUBSCRIBERS.entry(topic).or_default().insert(stream_name);
+}
+
+pub fn unsubscribe_topic(stream_name: &StreamName, topic: &Topic) {
+    TOPIC_SUBSCRIBERS.remove_if_mut(topic, |_, subscribers| {
+        subscribers.remove(stream_name);
+        subscribers.is_empty()
+    });
+}
+
+/// Removes the connection from all topics, called when the connection is closed.
+pub fn deregister_topic_subscriber(stream_name: &StreamName) {
+    TOPIC_SUBSCRIBERS.retain(|_, subscribers| {
+        subscribers.remove(stream_name);
+        !subscribers.is_empty()
+    });
+}
+
+/// The connections subscribed to the topic.
+fn subscribers(topic: &Topic) -> Vec<StreamName> {
+    match TOPIC_SUBSCRIBERS.get(topic) {
+        Some(subscribers) => subscribers.iter().cloned().collect(),
+        None => vec![]
+    }
+}
+
+/// Forwards the message to every connection subscribed to the topic, messages for topics without subscribers are discarded.
+/// The payload is passed through untouched, only the strategies know the message type.
+pub async fn publish_message(topic: Topic, payload: Vec<u8>) {
+    for stream_name in subscribers(&topic) {
+        let sender = match RESPONSE_SENDERS.get(&stream_name) {
+            Some(sender) => sender.value().clone(),
+            None => continue
+        };
+        let response = DataServerResponse::CustomMessage { topic: topic.clone(), payload: payload.clone() };
+        if sender.send(response).await.is_err() {
+            tracing::error!("Failed to send message on topic {} to stream: {}", topic, stream_name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_subscribers() {
+        let topic = "test_topic_subscribers".to_string();
+        subscribe_topic(1, topic.clone());
+        subscribe_topic(2, topic.clone());
+        subscribe_topic(2, "test_other_topic".to_string());
+        assert_eq!(subscribers(&topic).len(), 2);
+
+        unsubscribe_topic(&1, &topic);
+        assert_eq!(subscribers(&topic), vec![2]);
+
+        deregister_topic_subscriber(&2);
+        assert!(subscribers(&topic).is_empty());
+        assert!(subscribers(&"test_other_topic".to_string()).is_empty());
+    }
+}
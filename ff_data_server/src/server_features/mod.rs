@@ -1,2 +1,15 @@
 pub mod server_side_brokerage;
 pub mod server_side_datavendor;
+pub mod position_limits;
+pub mod webhooks;
+pub mod trade_copier;
+pub mod subscription_profiles;
+pub mod live_recording;
+pub mod websocket_bridge;
+pub mod message_bus;
+pub mod history_stream;
+pub mod vendor_failover;
+pub mod admin;
+pub mod api_keys;
+pub mod tls_config;
+pub mod market_replay;
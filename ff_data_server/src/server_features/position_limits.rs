@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use chrono::{DateTime, Utc};
+use rust_decimal_macros::dec;
+use serde_derive::Deserialize;
+use ff_standard_lib::standardized_types::accounts::{Account, AccountId};
+use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+use ff_standard_lib::standardized_types::enums::{OrderSide, PositionSide};
+use ff_standard_lib::standardized_types::new_types::{Price, Volume};
+use ff_standard_lib::standardized_types::orders::Order;
+use ff_standard_lib::standardized_types::subscriptions::SymbolName;
+use ff_standard_lib::strategies::ledgers::margin_engine::{load_margin_engines, margin_engine, MarginEngine, MarginedPosition};
+use crate::oanda_api::api_client::OANDA_CLIENT;
+use crate::rithmic_api::api_client::RITHMIC_CLIENTS;
+
+static POSITION_LIMITS: OnceLock<HashMap<AccountId, PositionLimits>> = OnceLock::new();
+
+/// Position limits enforced by the server for an account, independent of the strategy code.
+/// Orders which would take the account past a limit are rejected with an `OrderUpdateEvent::OrderRejected` before they are sent to the broker.
+/// The max order quantity applies to every order, the part of an order which reduces a position is not limited by the symbol and total limits,
+/// so a strategy can still exit a position that is over the limit.
+///
+/// # File Format
+/// The limits are loaded from `{data_folder}/risk/position_limits.toml` when the server starts, accounts without a section are not limited.
+/// ```toml
+/// [accounts."APEX-3396-168"]
+/// # The max quantity of a single order.
+/// max_order_quantity = 5
+/// # The max open quantity for each symbol name, long or short.
+/// max_symbol_quantity = 10
+/// # The max open quantity across all symbols on the account.
+/// max_total_quantity = 20
+/// # The max portfolio margin of the open positions, calculated by the margin engine of the brokerage.
+/// max_margin = 5000
+/// ```
+/// The margin engines are loaded from `{data_folder}/risk/margins.toml`, see `load_margin_engines()` for the format.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct PositionLimits {
+    pub max_order_quantity: Option<Volume>,
+    pub max_symbol_quantity: Option<Volume>,
+    pub max_total_quantity: Option<Volume>,
+    pub max_margin: Option<Price>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PositionLimitsFile {
+    #[serde(default)]
+    accounts: HashMap<AccountId, PositionLimits>,
+}
+
+impl PositionLimits {
+    /// Checks the order against the limits, `open_quantities` are the signed open quantities of the account, long positive and short negative.
+    /// Working orders are not included, each order is checked as if it were the only order that fills.
+    pub fn check(&self, open_quantities: &BTreeMap<SymbolName, Volume>, order: &Order) -> Result<(), String> {
+        let current = open_quantities.get(&order.symbol_name).cloned().unwrap_or(dec!(0));
+        let signed_quantity = match order.side {
+            OrderSide::Buy => order.quantity_open,
+            OrderSide::Sell => -order.quantity_open,
+        };
+        if let Some(max) = self.max_order_quantity {
+            if order.quantity_open > max {
+                return Err(format!("Order quantity {} exceeds the server max order quantity of {} for account {}", order.quantity_open, max, order.account.account_id));
+            }
+        }
+        let new_symbol_quantity = current + signed_quantity;
+        if new_symbol_quantity.abs() <= current.abs() {
+            return Ok(());
+        }
+
+        if let Some(max) = self.max_symbol_quantity {
+            if new_symbol_quantity.abs() > max {
+                return Err(format!("Order would take {} position to {}, exceeding the server max symbol quantity of {} for account {}", order.symbol_name, new_symbol_quantity, max, order.account.account_id));
+            }
+        }
+        if let Some(max) = self.max_total_quantity {
+            let current_total: Volume = open_quantities.values().map(|quantity| quantity.abs()).sum();
+            let new_total = current_total - current.abs() + new_symbol_quantity.abs();
+            if new_total > max {
+                return Err(format!("Order would take the total open quantity to {}, exceeding the server max total quantity of {} for account {}", new_total, max, order.account.account_id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the portfolio margin the account would hold after the order fills against `max_margin`, using the margins which apply at the time.
+    /// Margins are summed in the pnl currency of each symbol, if the margin of a symbol is unknown to the engine the order is allowed.
+    pub fn check_margin(&self, engine: &MarginEngine, open_quantities: &BTreeMap<SymbolName, Volume>, order: &Order, time: DateTime<Utc>) -> Result<(), String> {
+        let max = match self.max_margin {
+            Some(max) => max,
+            None => return Ok(()),
+        };
+        let current = open_quantities.get(&order.symbol_name).cloned().unwrap_or(dec!(0));
+        let signed_quantity = match order.side {
+            OrderSide::Buy => order.quantity_open,
+            OrderSide::Sell => -order.quantity_open,
+        };
+        if (current + signed_quantity).abs() <= current.abs() {
+            return Ok(());
+        }
+
+        let mut quantities = open_quantities.clone();
+        *quantities.entry(order.symbol_name.clone()).or_insert(dec!(0)) += signed_quantity;
+        let mut positions = vec![];
+        for (symbol_name, quantity) in quantities {
+            if quantity == dec!(0) {
+                continue;
+            }
+            let margin = match engine.margin_required(order.account.brokerage, &symbol_name, quantity.abs(), time) {
+                Some(margin) => margin,
+                None => return Ok(()),
+            };
+            positions.push(MarginedPosition { symbol_name, quantity, margin });
+        }
+        let margin = engine.portfolio_margin(&positions);
+        if margin > max {
+            return Err(format!("Order would take the portfolio margin to {}, exceeding the server max margin of {} for account {}", margin, max, order.account.account_id));
+        }
+        Ok(())
+    }
+}
+
+/// Loads the position limits file from the data folder, if the file does not exist no accounts are limited.
+pub fn init_position_limits(data_folder: &Path) {
+    let path = data_folder.join("risk").join("position_limits.toml");
+    let limits = match fs::read_to_string(&path) {
+        Ok(content) => match toml::from_str::<PositionLimitsFile>(&content) {
+            Ok(file) => file.accounts,
+            Err(e) => {
+                tracing::error!("Failed to parse position limits file {}: {}", path.display(), e);
+                HashMap::new()
+            }
+        },
+        Err(_) => HashMap::new()
+    };
+    for (account_id, account_limits) in &limits {
+        tracing::info!("Position Limits: {}: {:?}", account_id, account_limits);
+    }
+    let _ = POSITION_LIMITS.set(limits);
+
+    let margins_path = data_folder.join("risk").join("margins.toml");
+    if margins_path.exists() {
+        match load_margin_engines(&margins_path) {
+            Ok(brokerages) => {
+                for brokerage in brokerages {
+                    tracing::info!("Position Limits: Loaded margins for {}", brokerage);
+                }
+            }
+            Err(e) => tracing::error!("{:?}", e),
+        }
+    }
+}
+
+/// Checks a new order against the position limits for its account.
+pub fn check_position_limits(order: &Order) -> Result<(), String> {
+    let limits = match POSITION_LIMITS.get().and_then(|limits| limits.get(&order.account.account_id)) {
+        Some(limits) => limits,
+        None => return Ok(())
+    };
+    let open_quantities = match open_quantities(&order.account) {
+        Some(quantities) => quantities,
+        None => {
+            tracing::warn!("Position Limits: Open positions unknown for {}, checking {} as if flat", order.account, order.symbol_name);
+            BTreeMap::new()
+        }
+    };
+    limits.check(&open_quantities, order)?;
+    limits.check_margin(&margin_engine(order.account.brokerage), &open_quantities, order, Utc::now())
+}
+
+/// The signed open quantity of each symbol on the account as tracked by the broker client, `None` if the broker positions are not tracked by the server.
+fn open_quantities(account: &Account) -> Option<BTreeMap<SymbolName, Volume>> {
+    match &account.brokerage {
+        Brokerage::Rithmic(system) => {
+            let client = RITHMIC_CLIENTS.get(system)?;
+            let mut quantities = BTreeMap::new();
+            if let Some(long) = client.long_quantity.get(&account.account_id) {
+                for entry in long.iter() {
+                    *quantities.entry(entry.key().clone()).or_insert(dec!(0)) += *entry.value();
+                }
+            }
+            if let Some(short) = client.short_quantity.get(&account.account_id) {
+                for entry in short.iter() {
+                    *quantities.entry(entry.key().clone()).or_insert(dec!(0)) -= *entry.value();
+                }
+            }
+            Some(quantities)
+        }
+        Brokerage::Oanda => {
+            let client = OANDA_CLIENT.get()?;
+            let mut quantities = BTreeMap::new();
+            if let Some(positions) = client.positions.get(&account.account_id) {
+                for position in positions.iter() {
+                    let quantity = match position.side {
+                        PositionSide::Long => position.quantity_open,
+                        PositionSide::Short => -position.quantity_open,
+                        PositionSide::Flat => dec!(0),
+                    };
+                    *quantities.entry(position.key().clone()).or_insert(dec!(0)) += quantity;
+                }
+            }
+            Some(quantities)
+        }
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff_standard_lib::strategies::ledgers::margin_engine::SpreadCredit;
+
+    #[test]
+    fn test_limits_allow_reducing_orders() {
+        let limits = PositionLimits { max_order_quantity: Some(dec!(5)), max_symbol_quantity: Some(dec!(10)), max_total_quantity: Some(dec!(15)), max_margin: None };
+        let account = Account::new(Brokerage::Test, "TestAccount".to_string());
+        let mut open = BTreeMap::new();
+        open.insert("MNQ".to_string(), dec!(8));
+        open.insert("MES".to_string(), dec!(-6));
+
+        let order = |quantity, side| Order::market_order("MNQ".to_string(), None, &account, quantity, side, "test".to_string(), "1".to_string(), Utc::now(), None);
+        let buy = |quantity| order(quantity, OrderSide::Buy);
+        assert!(limits.check(&open, &buy(dec!(1))).is_ok());
+        // exceeds the symbol and total limits
+        assert!(limits.check(&open, &buy(dec!(3))).is_err());
+        assert!(limits.check(&open, &buy(dec!(6))).is_err());
+
+        // the max order quantity applies to reducing orders, a strategy flipping between long and short 8 is rejected
+        assert!(limits.check(&open, &order(dec!(5), OrderSide::Sell)).is_ok());
+        assert!(limits.check(&open, &order(dec!(8), OrderSide::Sell)).is_err());
+        assert!(limits.check(&open, &order(dec!(16), OrderSide::Sell)).is_err());
+
+        // without a max order quantity a large sell reduces the position, only the new short is limited
+        let limits = PositionLimits { max_order_quantity: None, ..limits };
+        assert!(limits.check(&open, &order(dec!(8), OrderSide::Sell)).is_ok());
+        assert!(limits.check(&open, &order(dec!(12), OrderSide::Sell)).is_ok());
+        assert!(limits.check(&open, &order(dec!(19), OrderSide::Sell)).is_err());
+    }
+
+    #[test]
+    fn test_margin_limit_uses_spread_credits() {
+        let limits = PositionLimits { max_margin: Some(dec!(1000)), ..Default::default() };
+        let mut engine = MarginEngine::default();
+        engine.margin_overrides.insert("ES".to_string(), dec!(1000));
+        engine.margin_overrides.insert("NQ".to_string(), dec!(1500));
+        engine.spread_credits.push(SpreadCredit::new("ES", dec!(1), "NQ", dec!(1), dec!(0.5)));
+        let account = Account::new(Brokerage::Test, "TestAccount".to_string());
+        let mut open = BTreeMap::new();
+        open.insert("ES".to_string(), dec!(1));
+
+        let order = |symbol: &str, side| Order::market_order(symbol.to_string(), None, &account, dec!(1), side, "test".to_string(), "1".to_string(), Utc::now(), None);
+        // 1 ES long and 1 NQ short margin 2500 less half as a spread
+        assert!(limits.check_margin(&engine, &open, &order("NQ", OrderSide::Sell), Utc::now()).is_err());
+        let limits = PositionLimits { max_margin: Some(dec!(1250)), ..Default::default() };
+        assert!(limits.check_margin(&engine, &open, &order("NQ", OrderSide::Sell), Utc::now()).is_ok());
+        assert!(limits.check_margin(&engine, &open, &order("ES", OrderSide::Buy), Utc::now()).is_err());
+        assert!(limits.check_margin(&engine, &open, &order("ES", OrderSide::Sell), Utc::now()).is_ok());
+    }
+}
@@ -123,9 +123,46 @@ pub trait VendorApiResponse: Sync + Send {
         callback_id: u64
     ) -> DataServerResponse;
 
+    /// return `DataServerResponse::VendorCapabilities` or `DataServerResponse::Error(FundForgeError)`
+    ///
+    /// The capabilities are requested once by each strategy and cached, so strategies can choose the best available data and order types for the vendor.
+    /// The `base_data_types` should match the `base_data_types_response` for the `StrategyMode`.
+    async fn vendor_capabilities_response(
+        &self,
+        mode: StrategyMode,
+        stream_name: StreamName,
+        callback_id: u64
+    ) -> DataServerResponse;
+
     /// This command doesn't require a response,
     /// it is sent when a connection is dropped so that we can remove any items associated with the stream
     /// (strategy that is connected to this port)
+    /// return `DataServerResponse::SymbolSearch` or `DataServerResponse::Error(FundForgeError)`
+    /// The results are the vendor instruments matching the `query`, with the tradable contract codes for futures.
+    async fn symbol_search_response(
+        &self,
+        mode: StrategyMode,
+        // The `stream_name` is just the u16 port number of the strategy which the server is connecting to,
+        // it is used to link the streaming port to a async port, you just need to know it represents a single strategy instance.
+        stream_name: StreamName,
+        query: String,
+        callback_id: u64
+    ) -> DataServerResponse;
+
+    /// return `DataServerResponse::OptionsChain` or `DataServerResponse::Error(FundForgeError)`
+    /// The option contracts listed on the `underlying`, only those expiring on the `expiry` date (`YYYY-MM-DD`) if it is `Some`.
+    /// Vendors without options keep the default implementation, which returns an error.
+    async fn options_chain_response(
+        &self,
+        _mode: StrategyMode,
+        _stream_name: StreamName,
+        _underlying: SymbolName,
+        _expiry: Option<String>,
+        callback_id: u64
+    ) -> DataServerResponse {
+        DataServerResponse::Error { callback_id, error: FundForgeError::ClientSideErrorDebug("The vendor does not support options".to_string()) }
+    }
+
     async fn logout_command_vendors(
         &self,
         // The `stream_name` is just the u16 port number of the strategy which the server is connecting to,
@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use ff_standard_lib::messages::data_server_messaging::{DataServerResponse, FundForgeError};
+use ff_standard_lib::strategies::subscription_profiles::{is_valid_profile_name, SubscriptionProfile};
+use crate::get_data_folder;
+
+/// Saves the profile to `{data_folder}/subscription_profiles/{name}.profile` and returns it as a `DataServerResponse::SubscriptionProfile`.
+pub async fn save_subscription_profile_response(profile: SubscriptionProfile, callback_id: u64) -> DataServerResponse {
+    match save_profile(get_data_folder(), &profile) {
+        Ok(_) => DataServerResponse::SubscriptionProfile { callback_id, profile },
+        Err(error) => DataServerResponse::Error { callback_id, error }
+    }
+}
+
+/// return `DataServerResponse::SubscriptionProfile` or `DataServerResponse::Error(FundForgeError)` if no profile is saved with the name.
+pub async fn subscription_profile_response(name: String, callback_id: u64) -> DataServerResponse {
+    match load_profile(get_data_folder(), &name) {
+        Ok(profile) => DataServerResponse::SubscriptionProfile { callback_id, profile },
+        Err(error) => DataServerResponse::Error { callback_id, error }
+    }
+}
+
+fn profile_path(data_folder: &Path, name: &str) -> Result<PathBuf, FundForgeError> {
+    if !is_valid_profile_name(name) {
+        return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid subscription profile name: {}", name)));
+    }
+    Ok(data_folder.join("subscription_profiles").join(format!("{}.profile", name)))
+}
+
+fn save_profile(data_folder: &Path, profile: &SubscriptionProfile) -> Result<(), FundForgeError> {
+    let path = profile_path(data_folder, &profile.name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to create subscription profile folder: {}", e)))?;
+    }
+    let bytes = rkyv::to_bytes::<_, 1024>(profile)
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to serialize subscription profile {}: {}", profile.name, e)))?;
+    fs::write(&path, &bytes)
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to write subscription profile {}: {}", path.display(), e)))
+}
+
+fn load_profile(data_folder: &Path, name: &str) -> Result<SubscriptionProfile, FundForgeError> {
+    let path = profile_path(data_folder, name)?;
+    let bytes = fs::read(&path)
+        .map_err(|_| FundForgeError::ClientSideErrorDebug(format!("No subscription profile saved with name: {}", name)))?;
+    rkyv::from_bytes::<SubscriptionProfile>(&bytes)
+        .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to read subscription profile {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType;
+    use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+    use ff_standard_lib::standardized_types::enums::{FuturesExchange, MarketType};
+    use ff_standard_lib::standardized_types::resolution::Resolution;
+    use ff_standard_lib::standardized_types::subscriptions::DataSubscription;
+
+    #[test]
+    fn test_save_and_load_profile() {
+        let data_folder = std::env::temp_dir().join(format!("ff_subscription_profiles_{}", std::process::id()));
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(5), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME));
+        let profile = SubscriptionProfile::new("mnq").with_subscription(subscription, 100, false);
+
+        save_profile(&data_folder, &profile).unwrap();
+        assert_eq!(load_profile(&data_folder, "mnq").unwrap(), profile);
+        assert!(load_profile(&data_folder, "missing").is_err());
+        assert!(load_profile(&data_folder, "../mnq").is_err());
+        let _ = fs::remove_dir_all(&data_folder);
+    }
+}
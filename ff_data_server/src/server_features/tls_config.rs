@@ -0,0 +1,161 @@
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use lazy_static::lazy_static;
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use ff_standard_lib::server_launch_options::ServerLaunchOptions;
+use crate::{load_certs, load_keys, subscribe_server_shutdown};
+
+/// How often the certificate files are checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    static ref TLS_CONFIG: RwLock<Option<Arc<ServerConfig>>> = RwLock::new(None);
+}
+
+/// The certificate files of the server, in the `--ssl_folder`.
+///
+/// - `cert.pem` and `key.pem`: the server certificate chain and private key.
+/// - `client_ca.crt`: with `--client_auth 1` strategies must present a certificate signed by this CA, issue one certificate per strategy machine.
+///   Strategies present the `client.crt` and `client.key` in their own ssl folder.
+/// - `client_crl.pem`: optional revocation lists for the client certificates, so the certificate of a single machine can be revoked.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+    pub client_ca: Option<PathBuf>,
+    pub client_crl: Option<PathBuf>,
+}
+
+impl TlsFiles {
+    pub fn new(options: &ServerLaunchOptions) -> Self {
+        let folder = &options.ssl_auth_folder;
+        let client_crl = folder.join("client_crl.pem");
+        Self {
+            cert: folder.join("cert.pem"),
+            key: folder.join("key.pem"),
+            client_ca: match options.client_auth {
+                0 => None,
+                _ => Some(folder.join("client_ca.crt")),
+            },
+            client_crl: match options.client_auth != 0 && client_crl.exists() {
+                true => Some(client_crl),
+                false => None,
+            },
+        }
+    }
+
+    /// The latest modification time of the files, a change means the certificates were rotated.
+    fn modified(&self) -> Option<SystemTime> {
+        [Some(&self.cert), Some(&self.key), self.client_ca.as_ref(), self.client_crl.as_ref()].into_iter()
+            .flatten()
+            .filter_map(|path| path.metadata().and_then(|metadata| metadata.modified()).ok())
+            .max()
+    }
+
+    pub fn server_config(&self) -> io::Result<ServerConfig> {
+        let certs = load_certs(&self.cert)?;
+        let key = load_keys(&self.key)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No keys found"))?;
+
+        let builder = match &self.client_ca {
+            None => ServerConfig::builder().with_no_client_auth(),
+            Some(client_ca) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(client_ca)? {
+                    roots.add(cert).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                }
+                let mut verifier = WebPkiClientVerifier::builder(Arc::new(roots));
+                if let Some(client_crl) = &self.client_crl {
+                    let crls = rustls_pemfile::crls(&mut BufReader::new(File::open(client_crl)?)).collect::<Result<Vec<_>, _>>()?;
+                    verifier = verifier.with_crls(crls);
+                }
+                let verifier = verifier.build().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                ServerConfig::builder().with_client_cert_verifier(verifier)
+            }
+        };
+        builder.with_single_cert(certs, key)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+    }
+}
+
+/// Loads the tls config used by the listeners, the server does not start if the certificates can't be loaded.
+pub(crate) fn init_tls_config(options: &ServerLaunchOptions) -> io::Result<()> {
+    let files = TlsFiles::new(options);
+    let config = files.server_config()?;
+    *TLS_CONFIG.write().unwrap() = Some(Arc::new(config));
+    match files.client_ca {
+        Some(client_ca) => tracing::info!("Tls: Client certificates signed by {} are required", client_ca.display()),
+        None => tracing::info!("Tls: Client certificates are not required"),
+    }
+    Ok(())
+}
+
+/// An acceptor for the current certificates, the listeners take a new acceptor for each connection so rotated certificates apply to the next connection.
+pub(crate) fn tls_acceptor() -> TlsAcceptor {
+    let config = TLS_CONFIG.read().unwrap().clone().expect("Tls config has not been initialized");
+    TlsAcceptor::from(config)
+}
+
+/// Reloads the certificates, connections which are already established keep their session.
+/// If the new files can't be loaded the current certificates stay in use.
+pub(crate) fn reload_tls_config(options: &ServerLaunchOptions) -> Result<(), String> {
+    let config = TlsFiles::new(options).server_config().map_err(|e| format!("Failed to reload certificates: {}", e))?;
+    *TLS_CONFIG.write().unwrap() = Some(Arc::new(config));
+    tracing::info!("Tls: Certificates reloaded");
+    Ok(())
+}
+
+/// Reloads the certificates when the files change, so certificates can be rotated by replacing the files without restarting the server.
+pub(crate) fn watch_certificates(options: ServerLaunchOptions) {
+    let mut shutdown_receiver = subscribe_server_shutdown();
+    tokio::spawn(async move {
+        let mut last_modified = TlsFiles::new(&options).modified();
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let modified = TlsFiles::new(&options).modified();
+                    if modified == last_modified {
+                        continue;
+                    }
+                    // Files written one after the other can be caught half way, a failed reload is retried at the next check.
+                    match reload_tls_config(&options) {
+                        Ok(_) => last_modified = modified,
+                        Err(e) => tracing::error!("Tls: {}", e),
+                    }
+                }
+                _ = shutdown_receiver.recv() => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_config() {
+        let folder = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources").join("keys");
+        let options = ServerLaunchOptions {
+            ssl_auth_folder: folder.clone(),
+            ..Default::default()
+        };
+        let mut files = TlsFiles::new(&options);
+        assert_eq!(files.client_ca, None);
+        assert!(files.server_config().is_ok());
+        assert!(files.modified().is_some());
+
+        // The client certificates are verified against the CA.
+        files.client_ca = Some(folder.join("rootCA.crt"));
+        assert!(files.server_config().is_ok());
+        files.client_ca = Some(folder.join("missing.crt"));
+        assert!(files.server_config().is_err());
+    }
+}
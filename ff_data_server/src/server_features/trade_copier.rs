@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use chrono::Utc;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+use serde_derive::Deserialize;
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+use ff_standard_lib::messages::data_server_messaging::DataServerResponse;
+use ff_standard_lib::standardized_types::accounts::{Account, AccountId};
+use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+use ff_standard_lib::standardized_types::enums::StrategyMode;
+use ff_standard_lib::standardized_types::new_types::Volume;
+use ff_standard_lib::standardized_types::orders::{Order, OrderId, OrderUpdateEvent};
+use ff_standard_lib::standardized_types::subscriptions::{SymbolCode, SymbolName};
+use ff_standard_lib::StreamName;
+use crate::rate_limiter::RateLimiter;
+use crate::server_features::webhooks::notify_copy_failed;
+use crate::server_side_brokerage::live_market_order;
+
+/// The stream name of the orders placed by the trade copier, the listener never gives this name to a connection, so the updates of copied orders are not sent to any strategy.
+pub const COPIER_STREAM_NAME: StreamName = 0;
+
+/// The tag of every copied order starts with this prefix, fills of copied orders are never copied again, so accounts can not copy each other in a loop.
+const COPY_TAG_PREFIX: &str = "copy:";
+
+/// The number of copied orders which can wait for the throttle of a destination, fills beyond the queue are reported as failed.
+const QUEUE_LEN: usize = 1000;
+
+static TRADE_COPIERS: OnceLock<HashMap<Account, Vec<Arc<CopyDestination>>>> = OnceLock::new();
+
+lazy_static! {
+    /// The quantity filled on each source order and the quantity copied so far, by destination, so partial fills are scaled without losing the remainders.
+    static ref COPIED_FILLS: DashMap<(Account, OrderId), CopiedFill> = DashMap::new();
+}
+
+static NEXT_COPY_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Copies the fills of a source account to destination accounts, which can be on other brokerages.
+/// The copier runs on the server, so it keeps copying if the strategy trading the source account stops.
+/// Each fill is copied as a market order, the quantity is scaled and rounded toward zero, and the symbol name can be mapped to a different symbol on the destination.
+///
+/// # File Format
+/// The copiers are loaded from `{data_folder}/copier/trade_copier.toml` when the server starts, if the file does not exist no trades are copied.
+/// ```toml
+/// [[copiers]]
+/// source_brokerage = "Rithmic Apex"
+/// source_account = "APEX-3396-168"
+///
+/// [[copiers.destinations]]
+/// brokerage = "Rithmic TopstepTrader"
+/// account_id = "S1JUL1234"
+/// # The multiplier of the source quantity, 1 by default.
+/// scale = 2
+/// # The decimal places of the copied quantity, 0 by default, eg. whole contracts.
+/// quantity_decimals = 0
+/// # Copies the fills of the source symbol name as fills of another symbol name, the contract month of the symbol code is kept.
+/// symbol_map = { MNQ = "NQ" }
+/// # Orders over the limit wait for the next minute, there is no limit by default.
+/// max_orders_per_minute = 30
+/// ```
+/// Copies which are rejected by the destination, or can not be queued, are logged and posted to the `copy_failed` webhooks of the destination account.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TradeCopier {
+    pub source_brokerage: String,
+    pub source_account: AccountId,
+    pub destinations: Vec<CopyDestinationSettings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CopyDestinationSettings {
+    pub brokerage: String,
+    pub account_id: AccountId,
+    #[serde(default = "default_scale")]
+    pub scale: Decimal,
+    #[serde(default)]
+    pub quantity_decimals: u32,
+    #[serde(default)]
+    pub symbol_map: HashMap<SymbolName, SymbolName>,
+    #[serde(default)]
+    pub max_orders_per_minute: Option<usize>,
+}
+
+fn default_scale() -> Decimal {
+    dec!(1)
+}
+
+#[derive(Debug, Deserialize)]
+struct TradeCopierFile {
+    #[serde(default)]
+    copiers: Vec<TradeCopier>,
+}
+
+impl CopyDestinationSettings {
+    /// The quantity to copy for a fill of the source order, the remainders of the rounding are carried to the next fill of the order.
+    fn copy_quantity(&self, copied: &mut CopiedFill, fill_quantity: Volume) -> Volume {
+        copied.source_filled += fill_quantity;
+        let target = (copied.source_filled * self.scale).round_dp_with_strategy(self.quantity_decimals, RoundingStrategy::ToZero);
+        let quantity = target - copied.copied;
+        copied.copied = target;
+        quantity
+    }
+
+    /// The symbol name and code on the destination, a mapped symbol code keeps the contract month of the source code, eg. MNQZ4 becomes NQZ4.
+    fn map_symbol(&self, symbol_name: &SymbolName, symbol_code: &SymbolCode) -> (SymbolName, SymbolCode) {
+        match self.symbol_map.get(symbol_name) {
+            Some(mapped) => {
+                let symbol_code = match symbol_code.strip_prefix(symbol_name.as_str()) {
+                    Some(contract) => format!("{}{}", mapped, contract),
+                    None => mapped.clone(),
+                };
+                (mapped.clone(), symbol_code)
+            }
+            None => (symbol_name.clone(), symbol_code.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct CopiedFill {
+    source_filled: Volume,
+    copied: Volume,
+}
+
+struct CopyDestination {
+    source: Account,
+    account: Account,
+    settings: CopyDestinationSettings,
+    queue: mpsc::Sender<Order>,
+}
+
+/// Loads the trade copier file from the data folder and starts a task for each destination, if the file does not exist no trades are copied.
+pub fn init_trade_copier(data_folder: &Path) {
+    let path = data_folder.join("copier").join("trade_copier.toml");
+    let copiers = match fs::read_to_string(&path) {
+        Ok(content) => match parse_copiers(&content) {
+            Ok(copiers) => copiers,
+            Err(e) => {
+                tracing::error!("Failed to parse trade copier file {}: {}", path.display(), e);
+                vec![]
+            }
+        },
+        Err(_) => vec![]
+    };
+    let mut destinations_by_source: HashMap<Account, Vec<Arc<CopyDestination>>> = HashMap::new();
+    for (source, destinations) in copiers {
+        for (account, settings) in destinations {
+            tracing::info!("Trade Copier: {} {} to {} {}, scale: {}", source.brokerage, source.account_id, account.brokerage, account.account_id, settings.scale);
+            let (queue, receiver) = mpsc::channel(QUEUE_LEN);
+            let limiter = settings.max_orders_per_minute.map(|max| RateLimiter::new(max, Duration::from_secs(60)));
+            let destination = Arc::new(CopyDestination { source: source.clone(), account, settings, queue });
+            tokio::task::spawn(place_copies(destination.clone(), receiver, limiter));
+            destinations_by_source.entry(source.clone()).or_default().push(destination);
+        }
+    }
+    let _ = TRADE_COPIERS.set(destinations_by_source);
+}
+
+/// The source account and the destination accounts of each copier in the file.
+fn parse_copiers(content: &str) -> Result<Vec<(Account, Vec<(Account, CopyDestinationSettings)>)>, String> {
+    let file: TradeCopierFile = toml::from_str(content).map_err(|e| e.to_string())?;
+    file.copiers.into_iter()
+        .map(|copier| {
+            let source = Account::new(Brokerage::from_str(&copier.source_brokerage).map_err(|e| e.to_string())?, copier.source_account);
+            let destinations = copier.destinations.into_iter()
+                .map(|settings| {
+                    let account = Account::new(Brokerage::from_str(&settings.brokerage).map_err(|e| e.to_string())?, settings.account_id.clone());
+                    if account == source {
+                        return Err(format!("Account {} can not copy its own trades", account.account_id));
+                    }
+                    if settings.scale <= dec!(0) {
+                        return Err(format!("The scale of {} must be greater than 0", account.account_id));
+                    }
+                    Ok((account, settings))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok((source, destinations))
+        })
+        .collect()
+}
+
+/// Copies the fills of source accounts and reports the rejections of copied orders, other responses are ignored.
+/// The copies are queued for the destination tasks so the broker handlers are not delayed.
+pub fn copy_trades(response: &DataServerResponse) {
+    let copiers = match TRADE_COPIERS.get() {
+        Some(copiers) if !copiers.is_empty() => copiers,
+        _ => return
+    };
+    let event = match response {
+        DataServerResponse::OrderUpdates { event, .. } => event,
+        _ => return
+    };
+    match event {
+        OrderUpdateEvent::OrderFilled { account, symbol_name, symbol_code, order_id, side, quantity, tag, .. } |
+        OrderUpdateEvent::OrderPartiallyFilled { account, symbol_name, symbol_code, order_id, side, quantity, tag, .. } => {
+            if tag.starts_with(COPY_TAG_PREFIX) {
+                return;
+            }
+            let destinations = match copiers.get(account) {
+                Some(destinations) => destinations,
+                None => return
+            };
+            let is_complete = matches!(event, OrderUpdateEvent::OrderFilled { .. });
+            for destination in destinations {
+                let key = (destination.account.clone(), order_id.clone());
+                let copy_quantity = {
+                    let mut copied = COPIED_FILLS.entry(key.clone()).or_default();
+                    destination.settings.copy_quantity(copied.value_mut(), *quantity)
+                };
+                if is_complete {
+                    COPIED_FILLS.remove(&key);
+                }
+                if copy_quantity <= dec!(0) {
+                    continue;
+                }
+                let (symbol_name, symbol_code) = destination.settings.map_symbol(symbol_name, symbol_code);
+                let order = Order::market_order(
+                    symbol_name,
+                    Some(symbol_code),
+                    &destination.account,
+                    copy_quantity,
+                    *side,
+                    format!("{}{}:{}", COPY_TAG_PREFIX, account.account_id, order_id),
+                    format!("copy-{}-{}", Utc::now().timestamp_millis(), NEXT_COPY_ID.fetch_add(1, Ordering::Relaxed)),
+                    Utc::now(),
+                    None,
+                );
+                if let Err(e) = destination.queue.try_send(order) {
+                    report_failure(destination, &e.into_inner().symbol_name, "The copy queue is full".to_string());
+                }
+            }
+        }
+        OrderUpdateEvent::OrderRejected { account, symbol_name, reason, tag, .. } => {
+            if !tag.starts_with(COPY_TAG_PREFIX) {
+                return;
+            }
+            let destination = copiers.values().flatten().find(|destination| &destination.account == account);
+            if let Some(destination) = destination {
+                report_failure(destination, symbol_name, reason.clone());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Places the copied orders of a destination in order, waiting for the throttle if there is one.
+async fn place_copies(destination: Arc<CopyDestination>, mut receiver: mpsc::Receiver<Order>, limiter: Option<Arc<RateLimiter>>) {
+    while let Some(order) = receiver.recv().await {
+        if let Some(limiter) = &limiter {
+            limiter.acquire().await;
+        }
+        let symbol_name = order.symbol_name.clone();
+        if let Err(OrderUpdateEvent::OrderRejected { reason, .. }) = live_market_order(COPIER_STREAM_NAME, StrategyMode::Live, order).await {
+            report_failure(&destination, &symbol_name, reason);
+        }
+    }
+}
+
+fn report_failure(destination: &CopyDestination, symbol_name: &SymbolName, reason: String) {
+    tracing::error!("Trade Copier: Failed to copy {} from {} to {}: {}", symbol_name, destination.source.account_id, destination.account.account_id, reason);
+    notify_copy_failed(&destination.account, &destination.source, symbol_name, &reason);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_copier() {
+        let content = r#"
+[[copiers]]
+source_brokerage = "Oanda"
+source_account = "001-011-1"
+
+[[copiers.destinations]]
+brokerage = "Bitget"
+account_id = "bitget-main"
+scale = 0.5
+symbol_map = { MNQ = "NQ" }
+max_orders_per_minute = 30
+
+[[copiers.destinations]]
+brokerage = "Oanda"
+account_id = "001-011-2"
+"#;
+        let copiers = parse_copiers(content).unwrap();
+        let (source, destinations) = &copiers[0];
+        assert_eq!(source, &Account::new(Brokerage::Oanda, "001-011-1".to_string()));
+        let (account, half) = &destinations[0];
+        assert_eq!(account.brokerage, Brokerage::Bitget);
+        assert_eq!(half.max_orders_per_minute, Some(30));
+        assert_eq!(destinations[1].1.scale, dec!(1));
+
+        // Half of three single lot fills is copied as 0, 1 and 0 lots, then the last lot when the order completes.
+        let mut copied = CopiedFill::default();
+        let quantities: Vec<Volume> = [dec!(1), dec!(1), dec!(1), dec!(1)].iter().map(|fill| half.copy_quantity(&mut copied, *fill)).collect();
+        assert_eq!(quantities, vec![dec!(0), dec!(1), dec!(0), dec!(1)]);
+
+        assert_eq!(half.map_symbol(&"MNQ".to_string(), &"MNQZ4".to_string()), ("NQ".to_string(), "NQZ4".to_string()));
+        assert_eq!(half.map_symbol(&"MES".to_string(), &"MESZ4".to_string()), ("MES".to_string(), "MESZ4".to_string()));
+
+        let own_account = "[[copiers]]\nsource_brokerage = \"Oanda\"\nsource_account = \"A\"\n[[copiers.destinations]]\nbrokerage = \"Oanda\"\naccount_id = \"A\"\n";
+        assert!(parse_copiers(own_account).is_err());
+        assert!(parse_copiers("[[copiers]]\nsource_brokerage = \"Unknown\"\nsource_account = \"A\"\ndestinations = []\n").is_err());
+    }
+}
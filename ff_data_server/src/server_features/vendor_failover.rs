@@ -0,0 +1,201 @@
+use std::time::Duration;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::interval;
+use ff_standard_lib::messages::data_server_messaging::DataServerResponse;
+use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+use ff_standard_lib::standardized_types::subscriptions::DataSubscription;
+use ff_standard_lib::StreamName;
+use crate::server_side_datavendor::{data_feed_subscribe, data_feed_unsubscribe};
+use crate::stream_tasks::{register_stream, unsubscribe_stream};
+
+/// How often a feed running on a fallback vendor tries to return to the subscribed vendor, and how often a feed with no working vendor retries.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    /// The vendor feeds being opened by failover feeds, `subscribe_stream()` puts the receiver here instead of sending it to the connection.
+    static ref PENDING_RECEIVERS: DashMap<(StreamName, DataSubscription), Option<broadcast::Receiver<BaseDataEnum>>> = DashMap::new();
+    /// The running failover feeds by connection and subscription.
+    static ref FAILOVER_FEEDS: DashMap<(StreamName, DataSubscription), oneshot::Sender<()>> = DashMap::new();
+}
+
+/// Takes the receiver of a vendor feed opened by a failover feed, returns the receiver if the feed was not opened by a failover feed.
+pub(crate) fn take_failover_receiver(stream_name: &StreamName, subscription: &DataSubscription, receiver: broadcast::Receiver<BaseDataEnum>) -> Option<broadcast::Receiver<BaseDataEnum>> {
+    match PENDING_RECEIVERS.get_mut(&(*stream_name, subscription.clone())) {
+        Some(mut pending) => {
+            *pending = Some(receiver);
+            None
+        }
+        None => Some(receiver),
+    }
+}
+
+/// Subscribes the connection to a subscription with fallback vendors.
+///
+/// The vendors are tried in order starting with the vendor of the symbol, the data of the first vendor with a working feed is sent to the connection as the data of the subscribed vendor.
+/// When the active vendor closes its feed the next working vendor takes over, while a fallback vendor is active the subscribed vendor is retried every `RETRY_INTERVAL`.
+/// The connection receives the `DataServerResponse::SubscribeResponse` followed by a `DataServerResponse::SubscriptionVendor` each time the active vendor changes.
+pub async fn failover_subscribe(stream_name: StreamName, subscription: DataSubscription, sender: mpsc::Sender<DataServerResponse>) {
+    let key = (stream_name, subscription.clone());
+    if FAILOVER_FEEDS.contains_key(&key) {
+        let _ = sender.send(DataServerResponse::SubscribeResponse { success: true, subscription, reason: None }).await;
+        return;
+    }
+
+    let (vendor, vendor_receiver) = match subscribe_first_vendor(stream_name, &subscription, None).await {
+        Ok(feed) => feed,
+        Err(reason) => {
+            let _ = sender.send(DataServerResponse::SubscribeResponse { success: false, subscription, reason: Some(reason) }).await;
+            return;
+        }
+    };
+
+    let (data_sender, data_receiver) = broadcast::channel(500);
+    register_stream(&stream_name, subscription.clone(), data_receiver).await;
+    let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+    FAILOVER_FEEDS.insert(key, shutdown_sender);
+
+    let _ = sender.send(DataServerResponse::SubscribeResponse { success: true, subscription: subscription.clone(), reason: None }).await;
+    let reason = match vendor == subscription.symbol.data_vendor {
+        true => "Subscribed".to_string(),
+        false => format!("{} is unavailable", subscription.symbol.data_vendor),
+    };
+    let _ = sender.send(DataServerResponse::SubscriptionVendor { subscription: subscription.clone(), vendor, reason }).await;
+
+    tokio::spawn(run_failover_feed(stream_name, subscription, vendor, vendor_receiver, data_sender, sender, shutdown_receiver));
+}
+
+/// Stops the failover feed of the subscription, returns false if the subscription has no failover feed.
+pub async fn failover_unsubscribe(stream_name: StreamName, subscription: &DataSubscription) -> bool {
+    match FAILOVER_FEEDS.remove(&(stream_name, subscription.clone())) {
+        Some((_, shutdown_sender)) => {
+            let _ = shutdown_sender.send(());
+            unsubscribe_stream(&stream_name, subscription).await;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Stops the failover feeds of the connection, called when the connection is closed.
+pub fn deregister_failover_feeds(stream_name: &StreamName) {
+    let keys: Vec<(StreamName, DataSubscription)> = FAILOVER_FEEDS.iter()
+        .filter(|entry| entry.key().0 == *stream_name)
+        .map(|entry| entry.key().clone())
+        .collect();
+    for key in keys {
+        if let Some((_, shutdown_sender)) = FAILOVER_FEEDS.remove(&key) {
+            let _ = shutdown_sender.send(());
+        }
+    }
+}
+
+/// Opens the feed of the vendor, the receiver is taken from `subscribe_stream()` when the vendor hands it over.
+async fn subscribe_vendor(stream_name: StreamName, subscription: &DataSubscription, vendor: DataVendor) -> Result<broadcast::Receiver<BaseDataEnum>, String> {
+    let vendor_subscription = subscription.for_vendor(vendor);
+    let key = (stream_name, vendor_subscription.clone());
+    PENDING_RECEIVERS.insert(key.clone(), None);
+    let response = data_feed_subscribe(stream_name, vendor_subscription.clone()).await;
+    let receiver = PENDING_RECEIVERS.remove(&key).and_then(|(_, receiver)| receiver);
+    match (response, receiver) {
+        (DataServerResponse::SubscribeResponse { success: true, .. }, Some(receiver)) => Ok(receiver),
+        (DataServerResponse::SubscribeResponse { success: true, .. }, None) => {
+            // the subscribed vendor shares the key of the connection stream, unsubscribing it would also stop the stream
+            if vendor != subscription.symbol.data_vendor {
+                data_feed_unsubscribe(vendor, stream_name, vendor_subscription).await;
+            }
+            Err(format!("{}: No feed was opened", vendor))
+        }
+        (DataServerResponse::SubscribeResponse { reason, .. }, _) | (DataServerResponse::UnSubscribeResponse { reason, .. }, _) => {
+            Err(format!("{}: {}", vendor, reason.unwrap_or_else(|| "No reason given".to_string())))
+        }
+        (response, _) => Err(format!("{}: Unexpected response {:?}", vendor, response)),
+    }
+}
+
+/// Opens the feed of the first vendor that succeeds, in fallback order, skipping the `failed` vendor unless it is the only one.
+async fn subscribe_first_vendor(stream_name: StreamName, subscription: &DataSubscription, failed: Option<DataVendor>) -> Result<(DataVendor, broadcast::Receiver<BaseDataEnum>), String> {
+    let mut vendors: Vec<DataVendor> = subscription.vendor_subscriptions().iter().map(|subscription| subscription.symbol.data_vendor).collect();
+    if vendors.len() > 1 {
+        vendors.retain(|vendor| Some(*vendor) != failed);
+    }
+    let mut reasons = vec![];
+    for vendor in vendors {
+        match subscribe_vendor(stream_name, subscription, vendor).await {
+            Ok(receiver) => return Ok((vendor, receiver)),
+            Err(reason) => reasons.push(reason),
+        }
+    }
+    Err(reasons.join(", "))
+}
+
+async fn run_failover_feed(
+    stream_name: StreamName,
+    subscription: DataSubscription,
+    mut vendor: DataVendor,
+    vendor_receiver: broadcast::Receiver<BaseDataEnum>,
+    data_sender: broadcast::Sender<BaseDataEnum>,
+    sender: mpsc::Sender<DataServerResponse>,
+    mut shutdown_receiver: oneshot::Receiver<()>,
+) {
+    let subscribed_vendor = subscription.symbol.data_vendor;
+    let mut vendor_receiver = Some(vendor_receiver);
+    let mut retry = interval(RETRY_INTERVAL);
+    retry.tick().await;
+    loop {
+        tokio::select! {
+            _ = &mut shutdown_receiver => {
+                if vendor_receiver.is_some() {
+                    data_feed_unsubscribe(vendor, stream_name, subscription.for_vendor(vendor)).await;
+                }
+                return;
+            }
+            result = async { vendor_receiver.as_mut().unwrap().recv().await }, if vendor_receiver.is_some() => {
+                match result {
+                    Ok(mut data) => {
+                        if vendor != subscribed_vendor {
+                            data.set_data_vendor(subscribed_vendor);
+                        }
+                        let _ = data_sender.send(data);
+                    }
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => {
+                        // the vendor has dropped its feed, so there is nothing to unsubscribe from
+                        vendor_receiver = None;
+                        retry.reset();
+                        let reason = format!("{} feed closed", vendor);
+                        match subscribe_first_vendor(stream_name, &subscription, Some(vendor)).await {
+                            Ok((new_vendor, new_receiver)) => {
+                                vendor = new_vendor;
+                                vendor_receiver = Some(new_receiver);
+                                let _ = sender.send(DataServerResponse::SubscriptionVendor { subscription: subscription.clone(), vendor, reason }).await;
+                            }
+                            Err(e) => tracing::warn!("Stream {}: No vendor available for {}: {}, {}", stream_name, subscription, reason, e),
+                        }
+                    }
+                }
+            }
+            _ = retry.tick(), if vendor_receiver.is_none() || vendor != subscribed_vendor => {
+                let reason = match vendor_receiver.is_some() {
+                    true => format!("{} is available again", subscribed_vendor),
+                    false => "Feed restored".to_string(),
+                };
+                let attempt = match vendor_receiver.is_some() {
+                    true => subscribe_vendor(stream_name, &subscription, subscribed_vendor).await.map(|receiver| (subscribed_vendor, receiver)),
+                    false => subscribe_first_vendor(stream_name, &subscription, None).await,
+                };
+                if let Ok((new_vendor, new_receiver)) = attempt {
+                    if vendor_receiver.is_some() && new_vendor != vendor {
+                        data_feed_unsubscribe(vendor, stream_name, subscription.for_vendor(vendor)).await;
+                    }
+                    vendor = new_vendor;
+                    vendor_receiver = Some(new_receiver);
+                    let _ = sender.send(DataServerResponse::SubscriptionVendor { subscription: subscription.clone(), vendor, reason }).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use reqwest::Client;
+use serde_derive::{Deserialize, Serialize};
+use chrono::Utc;
+use tokio::time::sleep;
+use ff_standard_lib::messages::data_server_messaging::DataServerResponse;
+use ff_standard_lib::standardized_types::accounts::{Account, AccountId};
+use ff_standard_lib::standardized_types::enums::PositionSide;
+use ff_standard_lib::standardized_types::new_types::{Price, Volume};
+use ff_standard_lib::standardized_types::orders::OrderUpdateEvent;
+use ff_standard_lib::standardized_types::subscriptions::SymbolCode;
+
+static WEBHOOKS: OnceLock<HashMap<AccountId, AccountWebhooks>> = OnceLock::new();
+
+lazy_static! {
+    static ref WEBHOOK_CLIENT: Client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+
+    /// The last known side of each position, used to post only when a position opens or closes.
+    static ref POSITION_SIDES: DashMap<(Account, SymbolCode), PositionSide> = DashMap::new();
+}
+
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Webhooks posted by the server for an account, independent of any strategy, so external dashboards and journals stay informed even if a strategy crashes.
+/// Each event is posted as JSON to every url of the account, see `WebhookEvent` for the payloads.
+///
+/// # File Format
+/// The webhooks are loaded from `{data_folder}/webhooks/webhooks.toml` when the server starts, accounts without a section have no webhooks.
+/// ```toml
+/// [accounts."APEX-3396-168"]
+/// urls = ["https://example.com/hooks/trading"]
+/// # The events to post, all events are posted if this is omitted.
+/// events = ["order_filled", "order_rejected", "position_opened", "position_closed", "copy_failed"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountWebhooks {
+    pub urls: Vec<String>,
+    #[serde(default)]
+    pub events: Option<Vec<WebhookEventType>>,
+}
+
+impl AccountWebhooks {
+    fn posts(&self, event_type: WebhookEventType) -> bool {
+        self.events.as_ref().is_none_or(|events| events.contains(&event_type))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    OrderFilled,
+    OrderRejected,
+    PositionOpened,
+    PositionClosed,
+    CopyFailed,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhooksFile {
+    #[serde(default)]
+    accounts: HashMap<AccountId, AccountWebhooks>,
+}
+
+/// The JSON body of a webhook post, the `event` field is the snake case name of the variant.
+/// Partial fills are posted as `order_filled` with `partial` set to true.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    OrderFilled {brokerage: String, account_id: AccountId, symbol_name: String, symbol_code: String, order_id: String, side: String, price: String, quantity: String, partial: bool, tag: String, time: String},
+    OrderRejected {brokerage: String, account_id: AccountId, symbol_name: String, symbol_code: String, order_id: String, reason: String, tag: String, time: String},
+    PositionOpened {brokerage: String, account_id: AccountId, symbol_name: String, symbol_code: String, side: String, quantity: String, average_price: String, time: String},
+    PositionClosed {brokerage: String, account_id: AccountId, symbol_name: String, symbol_code: String, side: String, time: String},
+    /// A fill of the source account which the trade copier could not copy to the account, see `TradeCopier`.
+    CopyFailed {brokerage: String, account_id: AccountId, source_brokerage: String, source_account_id: AccountId, symbol_name: String, reason: String, time: String},
+}
+
+impl WebhookEvent {
+    fn event_type(&self) -> WebhookEventType {
+        match self {
+            WebhookEvent::OrderFilled { .. } => WebhookEventType::OrderFilled,
+            WebhookEvent::OrderRejected { .. } => WebhookEventType::OrderRejected,
+            WebhookEvent::PositionOpened { .. } => WebhookEventType::PositionOpened,
+            WebhookEvent::PositionClosed { .. } => WebhookEventType::PositionClosed,
+            WebhookEvent::CopyFailed { .. } => WebhookEventType::CopyFailed,
+        }
+    }
+
+    fn account_id(&self) -> &AccountId {
+        match self {
+            WebhookEvent::OrderFilled { account_id, .. } => account_id,
+            WebhookEvent::OrderRejected { account_id, .. } => account_id,
+            WebhookEvent::PositionOpened { account_id, .. } => account_id,
+            WebhookEvent::PositionClosed { account_id, .. } => account_id,
+            WebhookEvent::CopyFailed { account_id, .. } => account_id,
+        }
+    }
+}
+
+/// Loads the webhooks file from the data folder, if the file does not exist no webhooks are posted.
+pub fn init_webhooks(data_folder: &Path) {
+    let path = data_folder.join("webhooks").join("webhooks.toml");
+    let webhooks = match fs::read_to_string(&path) {
+        Ok(content) => match toml::from_str::<WebhooksFile>(&content) {
+            Ok(file) => file.accounts,
+            Err(e) => {
+                tracing::error!("Failed to parse webhooks file {}: {}", path.display(), e);
+                HashMap::new()
+            }
+        },
+        Err(_) => HashMap::new()
+    };
+    for (account_id, account_webhooks) in &webhooks {
+        tracing::info!("Webhooks: {}: {:?}", account_id, account_webhooks.urls);
+    }
+    let _ = WEBHOOKS.set(webhooks);
+}
+
+/// Posts the webhooks for an order fill, order rejection or position update, other responses are ignored.
+/// The posts are sent on their own tasks so the broker handlers are not delayed.
+pub fn notify_webhooks(response: &DataServerResponse) {
+    let webhooks = match WEBHOOKS.get() {
+        Some(webhooks) if !webhooks.is_empty() => webhooks,
+        _ => return
+    };
+    let events = match response {
+        DataServerResponse::OrderUpdates { event, .. } => order_webhook_event(event).into_iter().collect(),
+        DataServerResponse::LivePositionUpdates { symbol_name, symbol_code, account, open_quantity, average_price, side, time, .. } => {
+            if !webhooks.contains_key(&account.account_id) {
+                return;
+            }
+            let previous = POSITION_SIDES.insert((account.clone(), symbol_code.clone()), *side);
+            position_webhook_events(previous, account, symbol_name, symbol_code, *side, *open_quantity, *average_price, time)
+        }
+        _ => return
+    };
+    for event in events {
+        post_webhook_event(webhooks, event);
+    }
+}
+
+/// Posts a `WebhookEvent::CopyFailed` to the webhooks of the destination account.
+pub fn notify_copy_failed(destination: &Account, source: &Account, symbol_name: &str, reason: &str) {
+    let webhooks = match WEBHOOKS.get() {
+        Some(webhooks) if !webhooks.is_empty() => webhooks,
+        _ => return
+    };
+    post_webhook_event(webhooks, WebhookEvent::CopyFailed {
+        brokerage: destination.brokerage.to_string(),
+        account_id: destination.account_id.clone(),
+        source_brokerage: source.brokerage.to_string(),
+        source_account_id: source.account_id.clone(),
+        symbol_name: symbol_name.to_string(),
+        reason: reason.to_string(),
+        time: Utc::now().to_string(),
+    });
+}
+
+fn post_webhook_event(webhooks: &HashMap<AccountId, AccountWebhooks>, event: WebhookEvent) {
+    let account_webhooks = match webhooks.get(event.account_id()) {
+        Some(account_webhooks) if account_webhooks.posts(event.event_type()) => account_webhooks,
+        _ => return
+    };
+    for url in &account_webhooks.urls {
+        tokio::task::spawn(post_event(url.clone(), event.clone()));
+    }
+}
+
+fn order_webhook_event(event: &OrderUpdateEvent) -> Option<WebhookEvent> {
+    match event {
+        OrderUpdateEvent::OrderFilled { account, symbol_name, symbol_code, order_id, side, price, quantity, tag, time } |
+        OrderUpdateEvent::OrderPartiallyFilled { account, symbol_name, symbol_code, order_id, side, price, quantity, tag, time } => Some(WebhookEvent::OrderFilled {
+            brokerage: account.brokerage.to_string(),
+            account_id: account.account_id.clone(),
+            symbol_name: symbol_name.clone(),
+            symbol_code: symbol_code.clone(),
+            order_id: order_id.clone(),
+            side: side.to_string(),
+            price: price.to_string(),
+            quantity: quantity.to_string(),
+            partial: matches!(event, OrderUpdateEvent::OrderPartiallyFilled { .. }),
+            tag: tag.clone(),
+            time: time.clone(),
+        }),
+        OrderUpdateEvent::OrderRejected { account, symbol_name, symbol_code, order_id, reason, tag, time } => Some(WebhookEvent::OrderRejected {
+            brokerage: account.brokerage.to_string(),
+            account_id: account.account_id.clone(),
+            symbol_name: symbol_name.clone(),
+            symbol_code: symbol_code.clone(),
+            order_id: order_id.clone(),
+            reason: reason.clone(),
+            tag: tag.clone(),
+            time: time.clone(),
+        }),
+        _ => None
+    }
+}
+
+/// The events for a change in the side of a position, a position which reverses is posted as closed then opened.
+#[allow(clippy::too_many_arguments)]
+fn position_webhook_events(previous: Option<PositionSide>, account: &Account, symbol_name: &str, symbol_code: &str, side: PositionSide, quantity: Volume, average_price: Price, time: &str) -> Vec<WebhookEvent> {
+    let previous = previous.unwrap_or(PositionSide::Flat);
+    if previous == side {
+        return vec![];
+    }
+    let mut events = vec![];
+    if previous != PositionSide::Flat {
+        events.push(WebhookEvent::PositionClosed {
+            brokerage: account.brokerage.to_string(),
+            account_id: account.account_id.clone(),
+            symbol_name: symbol_name.to_string(),
+            symbol_code: symbol_code.to_string(),
+            side: previous.to_string(),
+            time: time.to_string(),
+        });
+    }
+    if side != PositionSide::Flat {
+        events.push(WebhookEvent::PositionOpened {
+            brokerage: account.brokerage.to_string(),
+            account_id: account.account_id.clone(),
+            symbol_name: symbol_name.to_string(),
+            symbol_code: symbol_code.to_string(),
+            side: side.to_string(),
+            quantity: quantity.to_string(),
+            average_price: average_price.to_string(),
+            time: time.to_string(),
+        });
+    }
+    events
+}
+
+async fn post_event(url: String, event: WebhookEvent) {
+    for attempt in 1..=MAX_ATTEMPTS {
+        match WEBHOOK_CLIENT.post(&url).json(&event).send().await {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response) => tracing::warn!("Webhook {} returned {} on attempt {}", url, response.status(), attempt),
+            Err(e) => tracing::error!("Webhook {} failed on attempt {}: {}", url, attempt, e),
+        }
+        if attempt < MAX_ATTEMPTS {
+            sleep(Duration::from_secs(2u64.pow(attempt))).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_position_webhook_events() {
+        let account = Account::new(Brokerage::Test, "TestAccount".to_string());
+        let events = |previous, side| position_webhook_events(previous, &account, "MNQ", "MNQZ4", side, dec!(2), dec!(20100.25), "2024-11-01 14:30:00 UTC");
+
+        assert!(matches!(events(None, PositionSide::Long)[..], [WebhookEvent::PositionOpened { .. }]));
+        assert!(events(Some(PositionSide::Long), PositionSide::Long).is_empty());
+        assert!(matches!(events(Some(PositionSide::Long), PositionSide::Flat)[..], [WebhookEvent::PositionClosed { .. }]));
+        let reversed = events(Some(PositionSide::Long), PositionSide::Short);
+        assert!(matches!(reversed[..], [WebhookEvent::PositionClosed { .. }, WebhookEvent::PositionOpened { .. }]));
+
+        let json = serde_json::to_value(&reversed[1]).unwrap();
+        assert_eq!(json["event"], "position_opened");
+        assert_eq!(json["side"], "Short");
+
+        let file: WebhooksFile = toml::from_str("[accounts.TestAccount]\nurls = [\"http://localhost/hook\"]\nevents = [\"order_filled\"]").unwrap();
+        let webhooks = &file.accounts["TestAccount"];
+        assert!(webhooks.posts(WebhookEventType::OrderFilled));
+        assert!(!webhooks.posts(WebhookEventType::PositionClosed));
+    }
+}
@@ -0,0 +1,416 @@
+use std::net::SocketAddr;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::Duration;
+use ahash::AHashMap;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use futures_util::{SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::interval;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Message};
+use tokio_tungstenite::accept_hdr_async;
+use ff_standard_lib::messages::data_server_messaging::{DataServerRequest, DataServerResponse, StreamRequest};
+use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType;
+use ff_standard_lib::standardized_types::base_data::traits::BaseData;
+use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
+use ff_standard_lib::standardized_types::enums::MarketType;
+use ff_standard_lib::standardized_types::new_types::Price;
+use ff_standard_lib::standardized_types::resolution::Resolution;
+use ff_standard_lib::standardized_types::subscriptions::{CandleType, DataSubscription};
+use ff_standard_lib::StreamName;
+use crate::async_listener::create_listener;
+use crate::server_features::api_keys::{authenticate, deregister_connection_key, ConnectionAccess};
+use crate::server_side_datavendor::{data_feed_subscribe, data_feed_unsubscribe};
+use crate::subscribe_server_shutdown;
+
+/// How often the data received for a connection is sent as one message.
+const BRIDGE_BUFFER: Duration = Duration::from_millis(100);
+
+/// The stream names of bridge connections, the tls listener never allocates these so both kinds of stream can share the stream statics.
+pub(crate) const BRIDGE_STREAM_NAMES: RangeInclusive<StreamName> = 0xF000..=StreamName::MAX;
+
+/// A websocket connection and the feeds of the subscriptions it holds.
+struct BridgeConnection {
+    data_sender: mpsc::Sender<(String, BaseDataEnum)>,
+    feeds: AHashMap<DataSubscription, oneshot::Sender<()>>,
+}
+
+lazy_static! {
+    static ref BRIDGE_CONNECTIONS: DashMap<StreamName, BridgeConnection> = DashMap::new();
+    static ref NEXT_BRIDGE_STREAM: AtomicU16 = AtomicU16::new(*BRIDGE_STREAM_NAMES.start());
+}
+
+/// A subscription request from a charting client, the fields are parsed into a `DataSubscription`.
+/// `resolution` uses the `Resolution` string format eg. `"1-M"`, `"5-S"`, `"1-T"`, `vendor` is the `DataVendor` name.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct BridgeSubscription {
+    symbol: String,
+    vendor: String,
+    market_type: MarketType,
+    base_data_type: BaseDataType,
+    resolution: String,
+    candle_type: Option<String>,
+}
+
+impl BridgeSubscription {
+    fn subscription(&self) -> Result<DataSubscription, String> {
+        let vendor = DataVendor::from_str(&self.vendor).map_err(|e| format!("{:?}", e))?;
+        let resolution = Resolution::from_str(&self.resolution)?;
+        let mut subscription = DataSubscription::new(self.symbol.clone(), vendor, resolution, self.base_data_type, self.market_type);
+        if let Some(candle_type) = &self.candle_type {
+            subscription.candle_type = Some(CandleType::from_str(candle_type)?);
+        }
+        Ok(subscription)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum BridgeRequest {
+    Subscribe(BridgeSubscription),
+    Unsubscribe(BridgeSubscription),
+}
+
+/// Republishes the live data feeds as JSON over a websocket for lightweight charting front-ends, enabled with the `--websocket_port` launch option.
+/// The websocket is not encrypted, it should only be bound to a local address or served behind a tls proxy.
+/// When api keys are configured the client must send its key as the `api_key` query parameter, eg. `ws://127.0.0.1:8083/?api_key=3f9c1e...`,
+/// any permission may subscribe to data, see `init_api_keys()`.
+///
+/// Each connection manages its own subscriptions, the same as a strategy stream, subscriptions are forwarded to the vendor with `data_feed_subscribe()`
+/// so a feed shared by several connections is only subscribed once, and a connection's subscriptions are released when it closes.
+///
+/// # Messages
+/// Requests are JSON text messages:
+/// ```json
+/// {"action": "subscribe", "symbol": "MNQ", "vendor": "Rithmic", "market_type": {"Futures": "CME"}, "base_data_type": "Candles", "resolution": "1-M"}
+/// {"action": "unsubscribe", "symbol": "MNQ", "vendor": "Rithmic", "market_type": {"Futures": "CME"}, "base_data_type": "Candles", "resolution": "1-M"}
+/// ```
+/// Each request is answered with `{"type": "subscribed" | "unsubscribed", "id": .., "success": .., "reason": ..}`, the `id` names the subscription in the data messages.
+/// The data received every 100ms is sent as `{"type": "data", "data": [..]}`, times are the open times in unix milliseconds, bars use the TradingView field names:
+/// ```json
+/// {"id": "MNQ Rithmic 1-M Candles Futures(CME): CandleStick", "type": "candle", "time": 1717425000000, "open": 20100.25, "high": 20102.5, "low": 20099.75, "close": 20101.0, "volume": 152.0, "closed": true}
+/// ```
+pub(crate) async fn websocket_server(addr: SocketAddr) {
+    let listener = match create_listener(addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("Websocket Bridge: Failed to create listener on {}: {}", addr, e);
+            return;
+        }
+    };
+    tracing::info!("Websocket Bridge: Listening on: {}", addr);
+
+    let mut shutdown_receiver = subscribe_server_shutdown();
+    loop {
+        tokio::select! {
+            result = listener.accept() => {
+                if let Ok((stream, peer_addr)) = result {
+                    tokio::spawn(handle_connection(stream, peer_addr));
+                }
+            }
+            _ = shutdown_receiver.recv() => {
+                tracing::info!("Websocket Bridge: Shutdown signal received, stopping accept loop");
+                break;
+            }
+        }
+    }
+}
+
+/// Reserves the next free name in `BRIDGE_STREAM_NAMES` for the connection, `None` if every name is in use.
+fn register_connection(data_sender: mpsc::Sender<(String, BaseDataEnum)>) -> Option<StreamName> {
+    let names = (*BRIDGE_STREAM_NAMES.end() - *BRIDGE_STREAM_NAMES.start()) as usize + 1;
+    for _ in 0..names {
+        let candidate = NEXT_BRIDGE_STREAM.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |name| {
+            Some(if name == *BRIDGE_STREAM_NAMES.end() { *BRIDGE_STREAM_NAMES.start() } else { name + 1 })
+        }).unwrap_or(*BRIDGE_STREAM_NAMES.start());
+        if let Entry::Vacant(entry) = BRIDGE_CONNECTIONS.entry(candidate) {
+            entry.insert(BridgeConnection { data_sender, feeds: AHashMap::new() });
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// The `api_key` query parameter of the websocket request.
+fn api_key_param(request: &Request) -> Option<String> {
+    request.uri().query()?
+        .split('&')
+        .find_map(|param| param.strip_prefix("api_key="))
+        .map(|key| key.to_string())
+}
+
+async fn handle_connection(stream: TcpStream, peer_addr: SocketAddr) {
+    let mut api_key = None;
+    let websocket = match accept_hdr_async(stream, |request: &Request, response: Response| {
+        api_key = api_key_param(request);
+        Ok(response)
+    }).await {
+        Ok(websocket) => websocket,
+        Err(e) => {
+            tracing::error!("Websocket Bridge: Failed to accept connection from {}: {}", peer_addr, e);
+            return;
+        }
+    };
+    let (mut sink, mut source) = websocket.split();
+    let (data_sender, mut data_receiver) = mpsc::channel(1000);
+    let stream_name = match register_connection(data_sender) {
+        Some(stream_name) => stream_name,
+        None => {
+            tracing::error!("Websocket Bridge: Rejected {}, too many connections", peer_addr);
+            let _ = sink.send(close_message(CloseCode::Again, "Too many connections")).await;
+            return;
+        }
+    };
+    let access = match authenticate(stream_name, api_key) {
+        Ok(access) => access,
+        Err(reason) => {
+            tracing::warn!("Websocket Bridge: Rejected {}: {}", peer_addr, reason);
+            BRIDGE_CONNECTIONS.remove(&stream_name);
+            let _ = sink.send(close_message(CloseCode::Policy, &reason)).await;
+            return;
+        }
+    };
+    tracing::info!("Websocket Bridge: {} connected as stream {}", peer_addr, stream_name);
+
+    let mut shutdown_receiver = subscribe_server_shutdown();
+    let mut interval = interval(BRIDGE_BUFFER);
+    let mut pending: Vec<Value> = vec![];
+    loop {
+        tokio::select! {
+            message = source.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = handle_request(stream_name, &access, &text).await;
+                    if sink.send(Message::Text(reply.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            Some((id, data)) = data_receiver.recv() => {
+                if let Some(data) = data_to_json(&id, &data) {
+                    pending.push(data);
+                }
+            }
+            _ = interval.tick() => {
+                if !pending.is_empty() {
+                    let message = json!({"type": "data", "data": std::mem::take(&mut pending)});
+                    if sink.send(Message::Text(message.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            _ = shutdown_receiver.recv() => break,
+        }
+    }
+
+    // Dropping the connection stops its feeds, then the vendor releases the subscriptions no other stream holds.
+    if let Some((_, connection)) = BRIDGE_CONNECTIONS.remove(&stream_name) {
+        for (subscription, _) in connection.feeds {
+            data_feed_unsubscribe(subscription.symbol.data_vendor, stream_name, subscription).await;
+        }
+    }
+    deregister_connection_key(&stream_name);
+    tracing::info!("Websocket Bridge: Stream {} disconnected", stream_name);
+}
+
+fn close_message(code: CloseCode, reason: &str) -> Message {
+    Message::Close(Some(CloseFrame { code, reason: reason.to_string().into() }))
+}
+
+async fn handle_request(stream_name: StreamName, access: &ConnectionAccess, text: &str) -> Value {
+    let request = match serde_json::from_str::<BridgeRequest>(text) {
+        Ok(request) => request,
+        Err(e) => return json!({"type": "error", "reason": format!("Invalid request: {}", e)}),
+    };
+    let (request_type, subscription) = match &request {
+        BridgeRequest::Subscribe(subscription) => ("subscribed", subscription.subscription()),
+        BridgeRequest::Unsubscribe(subscription) => ("unsubscribed", subscription.subscription()),
+    };
+    let subscription = match subscription {
+        Ok(subscription) => subscription,
+        Err(e) => return json!({"type": "error", "reason": format!("Invalid subscription: {}", e)}),
+    };
+    let stream_request = match request {
+        BridgeRequest::Subscribe(_) => StreamRequest::Subscribe(subscription.clone()),
+        BridgeRequest::Unsubscribe(_) => StreamRequest::Unsubscribe(subscription.clone()),
+    };
+    if let Err(reason) = access.check(&DataServerRequest::StreamRequest { request: stream_request }) {
+        return json!({"type": request_type, "id": subscription.to_string(), "success": false, "reason": reason});
+    }
+    let is_subscribed = BRIDGE_CONNECTIONS.get(&stream_name).is_some_and(|connection| connection.feeds.contains_key(&subscription));
+    let response = match request {
+        BridgeRequest::Subscribe(_) if is_subscribed => DataServerResponse::SubscribeResponse { success: true, subscription: subscription.clone(), reason: None },
+        BridgeRequest::Subscribe(_) => data_feed_subscribe(stream_name, subscription.clone()).await,
+        // The vendor feed is shared with the other streams, only release it for a connection holding the subscription.
+        BridgeRequest::Unsubscribe(_) if !is_subscribed => DataServerResponse::UnSubscribeResponse { success: false, subscription: subscription.clone(), reason: Some("Not subscribed".to_string()) },
+        BridgeRequest::Unsubscribe(_) => {
+            let response = data_feed_unsubscribe(subscription.symbol.data_vendor, stream_name, subscription.clone()).await;
+            websocket_unsubscribe(&stream_name, &subscription);
+            response
+        }
+    };
+    let (success, reason) = match response {
+        DataServerResponse::SubscribeResponse { success, reason, .. } | DataServerResponse::UnSubscribeResponse { success, reason, .. } => (success, reason),
+        response => (false, Some(format!("Unexpected response: {:?}", response))),
+    };
+    json!({"type": request_type, "id": subscription.to_string(), "success": success, "reason": reason})
+}
+
+pub fn is_websocket(stream_name: &StreamName) -> bool {
+    BRIDGE_CONNECTIONS.contains_key(stream_name)
+}
+
+/// Forwards the vendor feed of the subscription to the connection, called by `subscribe_stream()` for websocket streams.
+pub fn websocket_subscribe(stream_name: &StreamName, subscription: DataSubscription, receiver: broadcast::Receiver<BaseDataEnum>) {
+    let mut connection = match BRIDGE_CONNECTIONS.get_mut(stream_name) {
+        Some(connection) => connection,
+        None => return,
+    };
+    if connection.feeds.contains_key(&subscription) {
+        return;
+    }
+    let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+    tokio::spawn(forward_feed(subscription.clone(), receiver, connection.data_sender.clone(), shutdown_receiver));
+    connection.feeds.insert(subscription, shutdown_sender);
+}
+
+pub fn websocket_unsubscribe(stream_name: &StreamName, subscription: &DataSubscription) {
+    if let Some(mut connection) = BRIDGE_CONNECTIONS.get_mut(stream_name) {
+        if let Some(shutdown) = connection.feeds.remove(subscription) {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+async fn forward_feed(
+    subscription: DataSubscription,
+    mut receiver: broadcast::Receiver<BaseDataEnum>,
+    data_sender: mpsc::Sender<(String, BaseDataEnum)>,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let id = subscription.to_string();
+    loop {
+        tokio::select! {
+            result = receiver.recv() => match result {
+                Ok(data) => {
+                    if is_subscribed_data(&subscription, &data) && data_sender.send((id.clone(), data)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = &mut shutdown => break,
+        }
+    }
+}
+
+/// The broadcasters of some vendors are shared by every resolution of a symbol, so only the data of the subscription is forwarded.
+fn is_subscribed_data(subscription: &DataSubscription, data: &BaseDataEnum) -> bool {
+    data.base_data_type() == subscription.base_data_type && data.resolution() == subscription.resolution
+}
+
+fn number(value: Price) -> Value {
+    json!(value.to_f64().unwrap_or_default())
+}
+
+/// The JSON of the data for charting clients, fundamentals are not republished.
+fn data_to_json(id: &str, data: &BaseDataEnum) -> Option<Value> {
+    let time = data.time_utc().timestamp_millis();
+    let value = match data {
+        BaseDataEnum::Candle(candle) => json!({
+            "id": id, "type": "candle", "time": time,
+            "open": number(candle.open), "high": number(candle.high), "low": number(candle.low), "close": number(candle.close),
+            "volume": number(candle.volume), "closed": candle.is_closed,
+        }),
+        BaseDataEnum::QuoteBar(bar) => json!({
+            "id": id, "type": "quote_bar", "time": time,
+            "open": number((bar.bid_open + bar.ask_open) / dec!(2)),
+            "high": number((bar.bid_high + bar.ask_high) / dec!(2)),
+            "low": number((bar.bid_low + bar.ask_low) / dec!(2)),
+            "close": number((bar.bid_close + bar.ask_close) / dec!(2)),
+            "bid_close": number(bar.bid_close), "ask_close": number(bar.ask_close),
+            "volume": number(bar.volume), "closed": bar.is_closed,
+        }),
+        BaseDataEnum::Tick(tick) => json!({
+            "id": id, "type": "tick", "time": time,
+            "price": number(tick.price), "volume": number(tick.volume), "aggressor": format!("{:?}", tick.aggressor),
+        }),
+        BaseDataEnum::Quote(quote) => json!({
+            "id": id, "type": "quote", "time": time,
+            "bid": number(quote.bid), "ask": number(quote.ask), "bid_volume": number(quote.bid_volume), "ask_volume": number(quote.ask_volume),
+        }),
+        BaseDataEnum::Footprint(footprint) => json!({
+            "id": id, "type": "footprint", "time": time,
+            "open": number(footprint.open), "high": number(footprint.high), "low": number(footprint.low), "close": number(footprint.close),
+            "volume": number(footprint.volume), "closed": footprint.is_closed,
+            "levels": footprint.levels.iter()
+                .map(|level| json!({"price": number(level.price), "bid_volume": number(level.bid_volume), "ask_volume": number(level.ask_volume)}))
+                .collect::<Vec<Value>>(),
+        }),
+        BaseDataEnum::Fundamental(_) => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff_standard_lib::standardized_types::base_data::candle::Candle;
+    use ff_standard_lib::standardized_types::base_data::tick::{Aggressor, Tick};
+    use ff_standard_lib::standardized_types::enums::FuturesExchange;
+
+    #[test]
+    fn test_bridge_requests_and_json() {
+        let request = r#"{"action": "subscribe", "symbol": "MNQ", "vendor": "Rithmic", "market_type": {"Futures": "CME"}, "base_data_type": "Candles", "resolution": "1-M"}"#;
+        let subscription = match serde_json::from_str::<BridgeRequest>(request).unwrap() {
+            BridgeRequest::Subscribe(subscription) => subscription.subscription().unwrap(),
+            _ => panic!("Expected a subscribe request"),
+        };
+        assert_eq!(subscription, DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(1), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME)));
+        assert!(serde_json::from_str::<BridgeRequest>(r#"{"action": "subscribe", "symbol": "MNQ"}"#).is_err());
+
+        let mut candle = Candle::new(subscription.symbol.clone(), dec!(20100.25), dec!(3), dec!(1), dec!(2), "2024-06-03 14:30:00 UTC".to_string(), Resolution::Minutes(1), CandleType::CandleStick);
+        candle.is_closed = true;
+        let candle = BaseDataEnum::Candle(candle);
+        assert!(is_subscribed_data(&subscription, &candle));
+        let json = data_to_json("MNQ", &candle).unwrap();
+        assert_eq!(json["type"], "candle");
+        assert_eq!(json["time"], 1_717_425_000_000i64);
+        assert_eq!(json["close"], 20100.25);
+        assert_eq!(json["closed"], true);
+
+        // the tick feed of the symbol is not forwarded to a candle subscription
+        let tick = BaseDataEnum::Tick(Tick::new(subscription.symbol.clone(), dec!(20100.5), "2024-06-03 14:30:01 UTC".to_string(), dec!(2), Aggressor::Buy));
+        assert!(!is_subscribed_data(&subscription, &tick));
+        assert_eq!(data_to_json("MNQ", &tick).unwrap()["price"], 20100.5);
+    }
+
+    #[test]
+    fn test_bridge_stream_names_and_api_key() {
+        let (data_sender, _data_receiver) = mpsc::channel(1);
+        let first = register_connection(data_sender.clone()).unwrap();
+        let second = register_connection(data_sender).unwrap();
+        assert_ne!(first, second);
+        assert!(BRIDGE_STREAM_NAMES.contains(&first) && BRIDGE_STREAM_NAMES.contains(&second));
+        BRIDGE_CONNECTIONS.remove(&first);
+        BRIDGE_CONNECTIONS.remove(&second);
+
+        let request = Request::builder().uri("ws://127.0.0.1:8083/?chart=1&api_key=3f9c1e").body(()).unwrap();
+        assert_eq!(api_key_param(&request), Some("3f9c1e".to_string()));
+        let request = Request::builder().uri("ws://127.0.0.1:8083/").body(()).unwrap();
+        assert_eq!(api_key_param(&request), None);
+    }
+}
@@ -68,6 +68,25 @@ pub async fn commission_info_response(mode: StrategyMode, brokerage: Brokerage,
     timeout(TIMEOUT_DURATION, operation).await.unwrap_or_else(|_| DataServerResponse::Error { callback_id, error: FundForgeError::ServerErrorDebug("Operation timed out".to_string()) })
 }
 
+/// return `DataServerResponse::FinancingRate` or `DataServerResponse::Error(FundForgeError)`, only Oanda provides financing rates.
+pub async fn financing_rate_response(brokerage: Brokerage, symbol_name: SymbolName, callback_id: u64) -> DataServerResponse {
+    match brokerage {
+        Brokerage::Oanda => {
+            if let Some(client) = OANDA_CLIENT.get() {
+                return match client.instruments_map.get(&symbol_name) {
+                    Some(instrument) => DataServerResponse::FinancingRate { callback_id, financing_rate: instrument.financing.to_financing_rate() },
+                    None => DataServerResponse::Error { callback_id, error: FundForgeError::ClientSideErrorDebug(format!("No financing rate found for: {}", symbol_name)) }
+                }
+            }
+            DataServerResponse::Error { callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", brokerage)) }
+        }
+        _ => DataServerResponse::Error {
+            callback_id,
+            error: FundForgeError::ClientSideErrorDebug(format!("{} does not provide financing rates", brokerage))
+        }
+    }
+}
+
 pub async fn front_month_info_response(brokerage: Brokerage, symbol_name: SymbolName, exchange: FuturesExchange, stream_name: StreamName, callback_id: u64) -> DataServerResponse {
     let operation = async {
         match brokerage {
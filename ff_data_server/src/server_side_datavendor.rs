@@ -2,7 +2,9 @@ use std::str::FromStr;
 use chrono::{DateTime, Utc};
 use ff_standard_lib::messages::data_server_messaging::{DataServerResponse, FundForgeError};
 use ff_standard_lib::standardized_types::datavendor_enum::DataVendor;
-use ff_standard_lib::standardized_types::enums::{MarketType, StrategyMode};
+use ff_standard_lib::standardized_types::enums::{MarketType, PrimarySubscription, StrategyMode};
+use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType;
+use ff_standard_lib::standardized_types::resolution::Resolution;
 use ff_standard_lib::standardized_types::subscriptions::{DataSubscription, SymbolName};
 use ff_standard_lib::StreamName;
 use crate::bitget_api::api_client::BITGET_CLIENT;
@@ -10,6 +12,7 @@ use crate::rithmic_api::api_client::{get_rithmic_market_data_system, RITHMIC_CLI
 use tokio::time::{timeout, Duration};
 use crate::data_bento_api::api_client::get_data_bento_client;
 use crate::oanda_api::api_client::OANDA_CLIENT;
+use crate::forex_factory_api::api_client::FOREX_FACTORY_CLIENT;
 use crate::server_features::server_side_datavendor::VendorApiResponse;
 
 const TIMEOUT_DURATION: Duration = Duration::from_secs(10);
@@ -48,6 +51,11 @@ pub async fn session_market_hours_response(mode: StrategyMode, data_vendor: Data
                     return client.session_market_hours_response(mode, stream_name, symbol_name, time, callback_id).await
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.session_market_hours_response(mode, stream_name, symbol_name, time, callback_id).await
+                }
+            }
         }
         DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
     };
@@ -93,6 +101,11 @@ pub async fn symbols_response(
                     return client.symbols_response(mode, stream_name, market_type, time, callback_id).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.symbols_response(mode, stream_name, market_type, time, callback_id).await;
+                }
+            }
         }
         DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
     };
@@ -140,11 +153,43 @@ pub async fn resolutions_response(
                     return client.resolutions_response(mode, stream_name, market_type, callback_id).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.resolutions_response(mode, stream_name, market_type, callback_id).await;
+                }
+            }
         }
         DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
     };
 
-    timeout(TIMEOUT_DURATION, operation).await.unwrap_or_else(|_| DataServerResponse::Error { callback_id, error: FundForgeError::ServerErrorDebug("Operation timed out".to_string()) })
+    let response = timeout(TIMEOUT_DURATION, operation).await.unwrap_or_else(|_| DataServerResponse::Error { callback_id, error: FundForgeError::ServerErrorDebug("Operation timed out".to_string()) });
+    match (mode, response) {
+        (StrategyMode::Backtest, DataServerResponse::Resolutions { callback_id, subscription_resolutions_types, market_type }) => {
+            DataServerResponse::Resolutions { callback_id, subscription_resolutions_types: with_trading_periods(subscription_resolutions_types), market_type }
+        }
+        (_, response) => response,
+    }
+}
+
+/// The day, week and month bars the server rolls up from the stored history, see `HybridStorage::roll_up_trading_periods()`,
+/// candles from ticks or candles and quote bars from quotes or quote bars.
+fn with_trading_periods(mut resolutions: Vec<PrimarySubscription>) -> Vec<PrimarySubscription> {
+    let mut rolled_up = vec![];
+    if resolutions.iter().any(|primary| primary.base_data_type == BaseDataType::Candles || *primary == PrimarySubscription::new(Resolution::Ticks(1), BaseDataType::Ticks)) {
+        rolled_up.push(BaseDataType::Candles);
+    }
+    if resolutions.iter().any(|primary| primary.base_data_type == BaseDataType::Quotes || primary.base_data_type == BaseDataType::QuoteBars) {
+        rolled_up.push(BaseDataType::QuoteBars);
+    }
+    for base_data_type in rolled_up {
+        for resolution in [Resolution::Day, Resolution::Week, Resolution::Month] {
+            let primary = PrimarySubscription::new(resolution, base_data_type);
+            if !resolutions.contains(&primary) {
+                resolutions.push(primary);
+            }
+        }
+    }
+    resolutions
 }
 
 /// return `DataServerResponse::Markets` or `DataServerResponse::Error(FundForgeError)`
@@ -182,6 +227,11 @@ pub async fn markets_response(
                     return client.markets_response(mode, stream_name, callback_id).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.markets_response(mode, stream_name, callback_id).await;
+                }
+            }
         }
         DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
     };
@@ -226,6 +276,11 @@ pub async fn decimal_accuracy_response(
                     return client.decimal_accuracy_response(mode, stream_name, symbol_name, callback_id).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.decimal_accuracy_response(mode, stream_name, symbol_name, callback_id).await;
+                }
+            }
         }
         DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
     };
@@ -269,6 +324,11 @@ pub async fn tick_size_response(
                     return client.tick_size_response(mode, stream_name, symbol_name, callback_id).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.tick_size_response(mode, stream_name, symbol_name, callback_id).await;
+                }
+            }
         }
         DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
     };
@@ -310,6 +370,11 @@ pub async fn data_feed_subscribe(
                     return client.data_feed_subscribe(stream_name, subscription.clone()).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.data_feed_subscribe(stream_name, subscription.clone()).await;
+                }
+            }
         }
         DataServerResponse::SubscribeResponse{ success: false, subscription: subscription.clone(), reason: Some(format!("Unable to find api client instance for: {}", subscription.symbol.data_vendor))}
     };
@@ -356,6 +421,11 @@ pub async fn data_feed_unsubscribe(
                     return client.data_feed_unsubscribe(stream_name, subscription.clone()).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.data_feed_unsubscribe(stream_name, subscription.clone()).await;
+                }
+            }
         }
         DataServerResponse::UnSubscribeResponse{ success: false, subscription: subscription.clone(), reason: Some(format!("Unable to find api client instance for: {}", data_vendor))}
     };
@@ -402,6 +472,152 @@ pub async fn base_data_types_response(
                     return client.base_data_types_response(mode, stream_name, callback_id).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.base_data_types_response(mode, stream_name, callback_id).await;
+                }
+            }
+        }
+        DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
+    };
+
+    timeout(TIMEOUT_DURATION, operation).await.unwrap_or_else(|_| DataServerResponse::Error { callback_id, error: FundForgeError::ServerErrorDebug("Operation timed out".to_string()) })
+}
+
+/// return `DataServerResponse::VendorCapabilities` or `DataServerResponse::Error(FundForgeError)`
+pub async fn vendor_capabilities_response(
+    data_vendor: DataVendor,
+    mode: StrategyMode,
+    stream_name: StreamName,
+    callback_id: u64
+) -> DataServerResponse {
+    let operation = async {
+        match data_vendor {
+            DataVendor::Rithmic => {
+                let system = match get_rithmic_market_data_system() {
+                    Some(system) => system,
+                    None => return DataServerResponse::Error {error: FundForgeError::ServerErrorDebug("Rithmic market data system not found".to_string()), callback_id}
+                };
+                if let Some(client) = RITHMIC_CLIENTS.get(&system) {
+                    return client.vendor_capabilities_response(mode, stream_name, callback_id).await
+                }
+            },
+            DataVendor::DataBento => {
+                return match get_data_bento_client() {
+                    Ok(client) => client.vendor_capabilities_response(mode, stream_name, callback_id).await,
+                    Err(e) => DataServerResponse::Error { error: e, callback_id }
+                }
+            },
+            DataVendor::Bitget => {
+                if let Some(client) = BITGET_CLIENT.get() {
+                    return client.vendor_capabilities_response(mode, stream_name, callback_id).await;
+                }
+            }
+            DataVendor::Oanda => {
+                if let Some(client) = OANDA_CLIENT.get() {
+                    return client.vendor_capabilities_response(mode, stream_name, callback_id).await;
+                }
+            }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.vendor_capabilities_response(mode, stream_name, callback_id).await;
+                }
+            }
+        }
+        DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
+    };
+
+    timeout(TIMEOUT_DURATION, operation).await.unwrap_or_else(|_| DataServerResponse::Error { callback_id, error: FundForgeError::ServerErrorDebug("Operation timed out".to_string()) })
+}
+
+/// return `DataServerResponse::SymbolSearch` or `DataServerResponse::Error(FundForgeError)`
+pub async fn symbol_search_response(
+    data_vendor: DataVendor,
+    mode: StrategyMode,
+    stream_name: StreamName,
+    query: String,
+    callback_id: u64
+) -> DataServerResponse {
+    let operation = async {
+        match data_vendor {
+            DataVendor::Rithmic => {
+                let system = match get_rithmic_market_data_system() {
+                    Some(system) => system,
+                    None => return DataServerResponse::Error {error: FundForgeError::ServerErrorDebug("Rithmic market data system not found".to_string()), callback_id}
+                };
+                if let Some(client) = RITHMIC_CLIENTS.get(&system) {
+                    return client.symbol_search_response(mode, stream_name, query, callback_id).await
+                }
+            },
+            DataVendor::DataBento => {
+                return match get_data_bento_client() {
+                    Ok(client) => client.symbol_search_response(mode, stream_name, query, callback_id).await,
+                    Err(e) => DataServerResponse::Error { error: e, callback_id }
+                }
+            },
+            DataVendor::Bitget => {
+                if let Some(client) = BITGET_CLIENT.get() {
+                    return client.symbol_search_response(mode, stream_name, query, callback_id).await;
+                }
+            }
+            DataVendor::Oanda => {
+                if let Some(client) = OANDA_CLIENT.get() {
+                    return client.symbol_search_response(mode, stream_name, query, callback_id).await;
+                }
+            }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.symbol_search_response(mode, stream_name, query, callback_id).await;
+                }
+            }
+        }
+        DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
+    };
+
+    timeout(TIMEOUT_DURATION, operation).await.unwrap_or_else(|_| DataServerResponse::Error { callback_id, error: FundForgeError::ServerErrorDebug("Operation timed out".to_string()) })
+}
+
+/// return `DataServerResponse::OptionsChain` or `DataServerResponse::Error(FundForgeError)`
+pub async fn options_chain_response(
+    data_vendor: DataVendor,
+    mode: StrategyMode,
+    stream_name: StreamName,
+    underlying: SymbolName,
+    expiry: Option<String>,
+    callback_id: u64
+) -> DataServerResponse {
+    let operation = async {
+        match data_vendor {
+            DataVendor::Rithmic => {
+                let system = match get_rithmic_market_data_system() {
+                    Some(system) => system,
+                    None => return DataServerResponse::Error {error: FundForgeError::ServerErrorDebug("Rithmic market data system not found".to_string()), callback_id}
+                };
+                if let Some(client) = RITHMIC_CLIENTS.get(&system) {
+                    return client.options_chain_response(mode, stream_name, underlying, expiry, callback_id).await
+                }
+            },
+            DataVendor::DataBento => {
+                return match get_data_bento_client() {
+                    Ok(client) => client.options_chain_response(mode, stream_name, underlying, expiry, callback_id).await,
+                    Err(e) => DataServerResponse::Error { error: e, callback_id }
+                }
+            },
+            DataVendor::Bitget => {
+                if let Some(client) = BITGET_CLIENT.get() {
+                    return client.options_chain_response(mode, stream_name, underlying, expiry, callback_id).await;
+                }
+            }
+            DataVendor::Oanda => {
+                if let Some(client) = OANDA_CLIENT.get() {
+                    return client.options_chain_response(mode, stream_name, underlying, expiry, callback_id).await;
+                }
+            }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    return client.options_chain_response(mode, stream_name, underlying, expiry, callback_id).await;
+                }
+            }
         }
         DataServerResponse::Error{ callback_id, error: FundForgeError::ServerErrorDebug(format!("Unable to find api client instance for: {}", data_vendor))}
     };
@@ -439,11 +655,16 @@ pub async fn logout_command_vendors(data_vendor: DataVendor, stream_name: Stream
                     client.logout_command_vendors(stream_name).await;
                 }
             }
+            DataVendor::ForexFactory => {
+                if let Some(client) = FOREX_FACTORY_CLIENT.get() {
+                    client.logout_command_vendors(stream_name).await;
+                }
+            }
         }
     };
 
     match timeout(TIMEOUT_DURATION, operation).await {
         Ok(_) => {},
-        Err(_) => eprintln!("Logout command for {} timed out after {} seconds", stream_name, TIMEOUT_DURATION.as_secs()),
+        Err(_) => tracing::warn!("Logout command for {} timed out after {} seconds", stream_name, TIMEOUT_DURATION.as_secs()),
     }
 }
@@ -1,6 +1,5 @@
-use rustls::ServerConfig;
 use std::net::SocketAddr;
-use tokio_rustls::TlsAcceptor;
+use crate::server_features::tls_config::tls_acceptor;
 use std::sync::Arc;
 use tokio::net::{TcpStream};
 use tokio_rustls::server::TlsStream;
@@ -11,22 +10,25 @@ use ff_standard_lib::StreamName;
 use crate::{subscribe_server_shutdown};
 use crate::server_side_datavendor::{data_feed_subscribe, data_feed_unsubscribe};
 use crate::stream_tasks::initialize_streamer;
+use crate::server_features::vendor_failover::failover_unsubscribe;
 use tokio::sync::Notify;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use crate::async_listener::create_listener;
-
-pub(crate) async fn stream_server(config: ServerConfig, addr: SocketAddr) {
-    let acceptor = TlsAcceptor::from(Arc::new(config));
+use crate::async_listener::{create_listener, MAX_REGISTER_LENGTH};
+use ff_standard_lib::helpers::logging::stream_span;
+use tracing::Instrument;
+use crate::server_features::api_keys::authorize_streamer;
+use crate::server_features::market_replay::{is_replaying, replay_subscribe, replay_unsubscribe};
 
+pub(crate) async fn stream_server(addr: SocketAddr) {
     let listener = match create_listener(addr).await {
         Ok(l) => l,
         Err(e) => {
-            eprintln!("Stream: Failed to create listener on {}: {}", addr, e);
+            tracing::error!("Stream: Failed to create listener on {}: {}", addr, e);
             return;
         }
     };
 
-    println!("Stream: Listening on: {}", addr);
+    tracing::info!("Stream: Listening on: {}", addr);
 
     let mut shutdown_receiver = subscribe_server_shutdown();
     let active_connections = Arc::new(AtomicUsize::new(0));
@@ -40,7 +42,7 @@ pub(crate) async fn stream_server(config: ServerConfig, addr: SocketAddr) {
                 match result {
                     Ok((stream, peer_addr)) => {
                         //println!("Stream: {}, peer_addr: {:?}", Utc::now(), peer_addr);
-                        let acceptor = acceptor.clone();
+                        let acceptor = tls_acceptor();
                         let active_connections = active_connections.clone();
                         let shutdown_complete = shutdown_complete_tx.clone();
 
@@ -52,7 +54,7 @@ pub(crate) async fn stream_server(config: ServerConfig, addr: SocketAddr) {
                                     handle_stream_connection(tls_stream, peer_addr).await;
                                 }
                                 Err(e) => {
-                                    eprintln!("Stream: Failed to accept TLS connection: {:?}", e);
+                                    tracing::error!("Stream: Failed to accept TLS connection: {:?}", e);
                                 }
                             }
                             if active_connections.fetch_sub(1, Ordering::SeqCst) == 1 {
@@ -68,7 +70,7 @@ pub(crate) async fn stream_server(config: ServerConfig, addr: SocketAddr) {
             },
 
             _ = shutdown_receiver.recv() => {
-                println!("Stream: Shutdown signal received, stopping accept loop");
+                tracing::info!("Stream: Shutdown signal received, stopping accept loop");
                 break;
             }
         }
@@ -79,15 +81,15 @@ pub(crate) async fn stream_server(config: ServerConfig, addr: SocketAddr) {
 
     tokio::select! {
         _ = shutdown_complete_rx.notified() => {
-            println!("Stream: All connections completed gracefully");
+            tracing::info!("Stream: All connections completed gracefully");
         }
         _ = &mut timeout => {
-            println!("Stream: Shutdown timeout reached, forcing close");
+            tracing::info!("Stream: Shutdown timeout reached, forcing close");
         }
     }
 
     drop(listener);
-    println!("Stream: Server stopped.");
+    tracing::info!("Stream: Server stopped.");
 }
 const LENGTH: usize = 4;
 async fn handle_stream_connection(mut tls_stream: TlsStream<TcpStream>, _peer_addr: SocketAddr) {
@@ -95,6 +97,10 @@ async fn handle_stream_connection(mut tls_stream: TlsStream<TcpStream>, _peer_ad
     while let Ok(_) = tls_stream.read_exact(&mut length_bytes).await {
         // Parse the length from the header
         let msg_length = u32::from_be_bytes(length_bytes) as usize;
+        if msg_length > MAX_REGISTER_LENGTH {
+            tracing::warn!("Stream: Rejected stream, the register message length {} is above the limit", msg_length);
+            return;
+        }
         let mut message_body = vec![0u8; msg_length];
 
         // Read the message body based on the length
@@ -118,12 +124,16 @@ async fn handle_stream_connection(mut tls_stream: TlsStream<TcpStream>, _peer_ad
 
         // Handle the request and generate a response
         match request {
-            DataServerRequest::RegisterStreamer{port, secs, subsec } => {
-                initialize_streamer(port, Duration::new(secs, subsec), tls_stream).await;
+            DataServerRequest::RegisterStreamer{port, secs, subsec, compression, local_fan_out, api_key } => {
+                if !authorize_streamer(&port, api_key.as_ref()) {
+                    tracing::warn!("Stream: Rejected stream for connection {}, the api key does not match the connection", port);
+                    return;
+                }
+                initialize_streamer(port, Duration::new(secs, subsec), compression, local_fan_out, tls_stream).instrument(stream_span(port)).await;
                 //println!("Streamer Registered");
                 return;
             },
-            _ => eprintln!("Stream: Strategy Did not register a Strategy mode")
+            _ => tracing::warn!("Stream: Strategy Did not register a Strategy mode")
         }
     }
     //println!("Stream: TLS connection established with {:?}", peer_addr);
@@ -133,10 +143,19 @@ async fn handle_stream_connection(mut tls_stream: TlsStream<TcpStream>, _peer_ad
 pub async fn stream_response(stream_name: StreamName, request: StreamRequest) -> DataServerResponse {
     match request {
         StreamRequest::Subscribe(subscription) => {
+            if is_replaying() {
+                return replay_subscribe(stream_name, subscription).await
+            }
             //it is not when we subscribe that we need to update data, only when we request historical data
             data_feed_subscribe(stream_name, subscription).await
         }
         StreamRequest::Unsubscribe(sub) => {
+            if is_replaying() {
+                return replay_unsubscribe(stream_name, sub).await
+            }
+            if failover_unsubscribe(stream_name, &sub).await {
+                return DataServerResponse::UnSubscribeResponse { success: true, subscription: sub, reason: None }
+            }
             data_feed_unsubscribe(sub.symbol.data_vendor.clone(), stream_name, sub).await
         }
     }
@@ -1,4 +1,5 @@
 use std::sync::{Arc};
+use tracing::Instrument;
 use std::time::Duration;
 use ahash::AHashMap;
 use dashmap::DashMap;
@@ -13,6 +14,12 @@ use ff_standard_lib::standardized_types::bytes_trait::Bytes;
 use ff_standard_lib::standardized_types::subscriptions::DataSubscription;
 use ff_standard_lib::standardized_types::time_slices::TimeSlice;
 use ff_standard_lib::StreamName;
+use std::net::SocketAddrV4;
+use ff_standard_lib::messages::stream_compression::StreamCompression;
+use crate::server_features::live_recording::record_subscription;
+use crate::server_features::websocket_bridge::{is_websocket, websocket_subscribe, websocket_unsubscribe};
+use crate::server_features::vendor_failover::take_failover_receiver;
+use crate::local_fan_out::{deregister_fan_out, fan_out_subscribe, is_fan_out, fan_out_unsubscribe, register_fan_out};
 
 lazy_static! {
     static ref STREAM_RECEIVERS: DashMap<u16 , Arc<DashMap<DataSubscription ,broadcast::Receiver<BaseDataEnum>>>> = DashMap::new();
@@ -42,22 +49,48 @@ pub async fn shutdown_all_stream_tasks() {
     SHUTDOWN_CLIENT.clear();
 }
 
-pub async fn initialize_streamer(stream_name: StreamName, buffer: Duration, stream: TlsStream<TcpStream>) {
+pub async fn initialize_streamer(stream_name: StreamName, buffer: Duration, compression: StreamCompression, local_fan_out: Option<SocketAddrV4>, stream: TlsStream<TcpStream>) {
+    if let Some(group) = local_fan_out {
+        register_fan_out(stream_name, group, buffer);
+    }
     let map = Arc::new(DashMap::new());
     let list = Arc::new(RwLock::new(vec![]));
     SUBSCRIPTIONS.insert(stream_name, list.clone());
     STREAM_RECEIVERS.insert(stream_name, map.clone());
     let (shutdown_sender, _) = broadcast::channel(100);
     SHUTDOWN_CLIENT.insert(stream_name, shutdown_sender);
-    stream_handler(stream_name, buffer, stream, map, list).await;
+    stream_handler(stream_name, buffer, compression, stream, map, list).await;
 }
 
 pub async fn deregister_streamer(stream_name: &StreamName) {
     STREAM_RECEIVERS.remove(stream_name);
+    deregister_fan_out(stream_name);
     broadcast_shutdown(stream_name.clone()).await;
 }
 
+/// Streamers registered for local fan out receive the data from the multicast group instead of the tls stream.
+/// Websocket bridge connections receive the data as JSON, see `websocket_bridge`.
+/// In record mode the first stream of a subscription also starts recording it, see `live_recording`.
+/// Vendor feeds opened for a subscription with fallback vendors are handed to its failover feed instead, see `vendor_failover`.
 pub async fn subscribe_stream(stream_name: &StreamName, subscription: DataSubscription, receiver: broadcast::Receiver<BaseDataEnum>) {
+    record_subscription(&subscription, &receiver);
+    let receiver = match take_failover_receiver(stream_name, &subscription, receiver) {
+        Some(receiver) => receiver,
+        None => return,
+    };
+    register_stream(stream_name, subscription, receiver).await;
+}
+
+/// Sends the data of the receiver to the connection.
+pub(crate) async fn register_stream(stream_name: &StreamName, subscription: DataSubscription, receiver: broadcast::Receiver<BaseDataEnum>) {
+    if is_websocket(stream_name) {
+        websocket_subscribe(stream_name, subscription, receiver);
+        return;
+    }
+    if is_fan_out(stream_name) {
+        fan_out_subscribe(stream_name, subscription, receiver);
+        return;
+    }
     if let Some(sender_ref) = STREAM_RECEIVERS.get(stream_name) {
         sender_ref.insert(subscription.clone(), receiver);
         let sub_list = SUBSCRIPTIONS.entry(stream_name.clone()).or_insert(Arc::new(RwLock::new(Vec::new())));
@@ -67,17 +100,70 @@ pub async fn subscribe_stream(stream_name: &StreamName, subscription: DataSubscr
 }
 
 pub async fn unsubscribe_stream(stream_name: &StreamName, subscription: &DataSubscription) {
+    fan_out_unsubscribe(stream_name, subscription);
+    websocket_unsubscribe(stream_name, subscription);
     if let Some(sub_list) = SUBSCRIPTIONS.get(&stream_name) {
         let mut list = sub_list.write().await;
         list.retain(|sub| sub != subscription);
     }
 }
 
+/// The connected streams and the subscriptions sent to each, sorted by stream name.
+pub(crate) async fn connected_streams() -> Vec<(StreamName, Vec<DataSubscription>)> {
+    let mut stream_names: Vec<StreamName> = STREAM_RECEIVERS.iter().map(|stream| *stream.key()).collect();
+    stream_names.sort();
+    let mut streams = vec![];
+    for stream_name in stream_names {
+        let sub_list = SUBSCRIPTIONS.get(&stream_name).map(|list| list.value().clone());
+        let subscriptions = match sub_list {
+            Some(sub_list) => sub_list.read().await.clone(),
+            None => vec![],
+        };
+        streams.push((stream_name, subscriptions));
+    }
+    streams
+}
+
+/// True if the stream is connected.
+pub(crate) fn is_connected(stream_name: &StreamName) -> bool {
+    STREAM_RECEIVERS.contains_key(stream_name)
+}
+
 const LENGTH: usize = 4;
 
+/// How often an unbuffered stream checks for subscription changes, unbuffered streams send each data point as soon as it arrives.
+const UNBUFFERED_POLL: Duration = Duration::from_millis(100);
+
+/// A zero buffer registers an unbuffered stream, the intervals of an unbuffered stream only poll for subscription changes.
+pub(crate) fn poll_interval(buffer: Duration) -> Duration {
+    match buffer.is_zero() {
+        true => UNBUFFERED_POLL,
+        false => buffer
+    }
+}
+
+async fn write_time_slice(stream: &mut TlsStream<TcpStream>, compression: &StreamCompression, time_slice: &TimeSlice) -> Result<(), ()> {
+    let bytes = match compression.compress(time_slice.to_bytes()) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("{}", e);
+            return Ok(());
+        }
+    };
+    let length = (bytes.len() as u32).to_be_bytes();
+    let mut prefixed_msg = Vec::with_capacity(LENGTH + bytes.len());
+    prefixed_msg.extend_from_slice(&length);
+    prefixed_msg.extend_from_slice(&bytes);
+
+    // Flush after each write so the data is not held back by the tls layer
+    stream.write_all(&prefixed_msg).await.map_err(|_| ())?;
+    stream.flush().await.map_err(|_| ())
+}
+
 pub async fn stream_handler(
     stream_name: StreamName,
     buffer: Duration,
+    compression: StreamCompression,
     mut stream: TlsStream<TcpStream>,
     stream_receivers: Arc<DashMap<DataSubscription, broadcast::Receiver<BaseDataEnum>>>,
     subscriptions: Arc<RwLock<Vec<DataSubscription>>>,
@@ -92,7 +178,7 @@ pub async fn stream_handler(
         let subscriptions = subscriptions.clone();
         async move {
             let mut task_1_shutdown_receiver = SHUTDOWN_CLIENT.get(&stream_name).unwrap().subscribe();
-            let mut interval = interval(buffer);
+            let mut interval = interval(poll_interval(buffer));
             let running_streams: Arc<RwLock<AHashMap<DataSubscription, oneshot::Sender<()>>>> = Arc::new(RwLock::new(AHashMap::new()));
             'subscriber_loop: loop {
                 interval.tick().await;
@@ -166,57 +252,49 @@ pub async fn stream_handler(
                     }
                 }
             }
-        }
+        }.in_current_span()
     });
 
     let _ = tokio::spawn(async move {
         let mut time_slice = TimeSlice::new();
-        let mut interval = interval(buffer.clone());
+        let mut interval = interval(poll_interval(buffer));
         let mut task_2_shutdown_receiver = SHUTDOWN_CLIENT.get(&stream_name).unwrap().subscribe();
         loop {
             tokio::select! {
                 _ = interval.tick() => {
                     if !time_slice.is_empty() {
-                        let bytes = time_slice.to_bytes();
-                        let length = (bytes.len() as u32).to_be_bytes();
-                        let mut prefixed_msg = Vec::with_capacity(LENGTH + bytes.len());
-                        prefixed_msg.extend_from_slice(&length);
-                        prefixed_msg.extend_from_slice(&bytes);
-
-                        match stream.write_all(&prefixed_msg).await {
-                            Ok(_) => {
-                                // Add explicit flush after successful write
-                                if let Err(_e) = stream.flush().await {
-                                    //eprintln!("Error flushing stream: {}", e);
-                                    broadcast_shutdown(stream_name).await;
-                                    break;
-                                }
-                            }
-                            Err(_e) => {
-                                //eprintln!("Error writing to stream: {}", e);
-                                broadcast_shutdown(stream_name).await;
-                                break;
-                            }
+                        if write_time_slice(&mut stream, &compression, &time_slice).await.is_err() {
+                            broadcast_shutdown(stream_name).await;
+                            break;
                         }
                         time_slice.clear();
                     }
                 }
                 result = data_receiver.recv() => {
                     match result {
-                        Some(slice) => time_slice.extend(slice),
+                        Some(slice) => {
+                            time_slice.extend(slice);
+                            if buffer.is_zero() {
+                                if write_time_slice(&mut stream, &compression, &time_slice).await.is_err() {
+                                    broadcast_shutdown(stream_name).await;
+                                    break;
+                                }
+                                time_slice.clear();
+                            }
+                        }
                         None => {
-                            sleep(buffer.clone()).await;
+                            sleep(poll_interval(buffer)).await;
                         }
                     }
                 }
                 _ = task_2_shutdown_receiver.recv() => {
-                    println!("Shutdown signal received. Stopping stream handler.");
+                    tracing::info!("Shutdown signal received. Stopping stream handler.");
                     break;
                 }
             }
         }
-        println!("Stream handler for {} has shut down", stream_name);
-    });
+        tracing::info!("Stream handler for {} has shut down", stream_name);
+    }.in_current_span());
 }
 async fn process_receiver(
     mut rx: broadcast::Receiver<BaseDataEnum>,
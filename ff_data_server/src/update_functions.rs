@@ -72,7 +72,7 @@ pub fn run_update_schedule(storage: Arc<HybridStorage>) {
             tokio::select! {
             // Handle shutdown signal
             _ = shutdown_receiver.recv() => {
-                println!("Received shutdown signal, stopping update schedule");
+                tracing::info!("Received shutdown signal, stopping update schedule");
                 // Actively abort all running tasks
                 for task in storage.download_tasks.iter() {
                     task.value().abort();
@@ -104,7 +104,7 @@ pub fn run_update_schedule(storage: Arc<HybridStorage>) {
 
                 // Run forward update
                 if let Err(e) = update_data(storage.clone(), false).await {
-                    eprintln!("Forward update failed: {}", e);
+                    tracing::error!("Forward update failed: {}", e);
                 }
 
                 // Wait for forward tasks to complete before starting backward tasks
@@ -115,7 +115,7 @@ pub fn run_update_schedule(storage: Arc<HybridStorage>) {
 
                 //todo Run backward update after forward completes, these need to be modified to use a dif fn and download from earliest time backwards
            /*     if let Err(e) = HybridStorage::update_data(self.clone(), true).await {
-                    eprintln!("Backward update failed: {}", e);
+                    tracing::error!("Backward update failed: {}", e);
                 }*/
             }
         }
@@ -210,7 +210,7 @@ pub async fn pre_subscribe_updates(storage: Arc<HybridStorage>, symbol: Symbol,
     }
 }
 
-async fn update_data(storage: Arc<HybridStorage>, from_back: bool) -> Result<(), FundForgeError> {
+pub(crate) async fn update_data(storage: Arc<HybridStorage>, from_back: bool) -> Result<(), FundForgeError> {
     let options = storage.options.clone();
     // Create a semaphore to limit concurrent downloads
     let semaphore = storage.download_semaphore.clone();
@@ -223,7 +223,7 @@ async fn update_data(storage: Arc<HybridStorage>, from_back: bool) -> Result<(),
             DataVendor::Oanda if !OANDA_IS_CONNECTED.load(Ordering::SeqCst) => {
                 continue
             },
-            DataVendor::DataBento | DataVendor::Bitget => {
+            DataVendor::DataBento | DataVendor::Bitget | DataVendor::ForexFactory => {
                 continue
             },
             _ => (),
@@ -252,7 +252,7 @@ fn rank_format_time(time: i64, previous_time: i64, resolution: &Resolution) -> T
                 Resolution::Seconds(_) => (DateScaleImportance::TimeOfDay, "%H:%M:%S"),
                 Resolution::Minutes(_) => (DateScaleImportance::TimeOfDay, "%H:%M"),
                 Resolution::Hours(_) =>  (DateScaleImportance::TimeOfDay, "%H:%M"),
-                Resolution::Day => (DateScaleImportance::TimeOfDay, "%D"),
+                Resolution::Day | Resolution::Week | Resolution::Month => (DateScaleImportance::TimeOfDay, "%D"),
             }
         };
 
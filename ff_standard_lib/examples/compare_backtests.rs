@@ -0,0 +1,62 @@
+//! Compares the trade exports of two or more backtests and prints the comparison report.
+//!
+//! The first file is the baseline, files ending in `.json` are loaded as `TradeExportFormat::RoundTrip` exports,
+//! other files as the csv written by `strategy.export_trades_to_csv()`. Each run is named after its file.
+//!
+//! ```text
+//! cargo run -p ff_standard_lib --example compare_backtests -- baseline.json candidate.json [--equity-curves ./comparison]
+//! ```
+use std::path::Path;
+use std::process::exit;
+use ff_standard_lib::messages::data_server_messaging::FundForgeError;
+use ff_standard_lib::strategies::statistics::backtest_comparison::{BacktestComparison, BacktestRun};
+
+fn load_run(path: &Path) -> Result<BacktestRun, FundForgeError> {
+    let name = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string());
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => BacktestRun::from_trades_json(&name, path),
+        _ => BacktestRun::from_trades_csv(&name, path),
+    }
+}
+
+fn main() {
+    let mut files = vec![];
+    let mut equity_curves_folder = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--equity-curves" => equity_curves_folder = args.next(),
+            _ => files.push(arg),
+        }
+    }
+    if files.len() < 2 {
+        eprintln!("Usage: compare_backtests <baseline trades file> <trades file>... [--equity-curves <folder>]");
+        exit(2);
+    }
+
+    let mut runs = vec![];
+    for file in &files {
+        match load_run(Path::new(file)) {
+            Ok(run) => runs.push(run),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1);
+            }
+        }
+    }
+    let comparison = match BacktestComparison::new(runs) {
+        Ok(comparison) => comparison,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+    println!("{}", comparison.report());
+    if let Some(folder) = equity_curves_folder {
+        if let Err(e) = comparison.export_equity_curves_csv(&folder) {
+            eprintln!("{}", e);
+            exit(1);
+        }
+        println!("Exported the equity curves to {}", folder);
+    }
+}
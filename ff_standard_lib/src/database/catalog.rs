@@ -1,111 +1,51 @@
 use std::{fs, io};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use chrono::{DateTime, Utc};
 use crate::database::hybrid_storage::HybridStorage;
-use crate::product_maps::rithmic::maps::get_futures_exchange;
+use crate::product_maps::rithmic::maps::{extract_symbol_from_contract, get_available_rithmic_symbol_names, get_futures_exchange};
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::datavendor_enum::DataVendor;
-use crate::standardized_types::enums::MarketType;
+use crate::standardized_types::enums::{Exchange, MarketType};
 use crate::standardized_types::resolution::Resolution;
 use crate::standardized_types::subscriptions::Symbol;
+use crate::standardized_types::symbol_info::{AvailableHistory, SymbolMetadata};
 
 impl HybridStorage {
     pub async fn catalog_available_data(&self, export_path: PathBuf) -> io::Result<()> {
         let mut available_data = Vec::new();
         let rithmic_base = self.base_path.join("Rithmic").join("Futures");
 
-        println!("Checking base path: {:?}", self.base_path);
-        println!("Checking rithmic path: {:?}", rithmic_base);
-        println!("rithmic_base exists: {}", rithmic_base.exists());
+        tracing::debug!("Checking base path: {:?}", self.base_path);
+        tracing::debug!("Checking rithmic path: {:?}", rithmic_base);
+        tracing::debug!("rithmic_base exists: {}", rithmic_base.exists());
 
         if !rithmic_base.exists() {
-            println!("Parent directory contents:");
+            tracing::debug!("Parent directory contents:");
             if let Ok(entries) = fs::read_dir(&self.base_path) {
                 for entry in entries {
                     if let Ok(entry) = entry {
-                        println!("  {:?}", entry.path());
+                        tracing::debug!("  {:?}", entry.path());
                     }
                 }
             }
             return Err(io::Error::new(io::ErrorKind::NotFound, "No Rithmic data directory found"));
         }
 
-        // Walk through symbols
-        for symbol_entry in fs::read_dir(&rithmic_base)? {
-            let symbol_entry = symbol_entry?;
-            let symbol_name = symbol_entry.file_name().to_string_lossy().to_string();
-            eprintln!("Symbol name: {}", symbol_name);
-            if !symbol_name.starts_with(|c: char| c.is_alphabetic()) {
+        for symbol in self.stored_symbols(&DataVendor::Rithmic)? {
+            if !matches!(symbol.market_type, MarketType::Futures(_)) {
                 continue;
             }
-            // Get exchange from symbol name, skip if not recognized
-            let exchange = match get_futures_exchange(&symbol_name) {
-                Ok(e) => e,
-                Err(e) => {
-                    eprintln!("Skipping symbol {}: {}", symbol_name, e);
-                    continue;
-                }
-            };
-            let market_type = MarketType::Futures(exchange);
-            let symbol = Symbol::new(
-                symbol_name.clone(),
-                DataVendor::Rithmic,
-                market_type.clone()
-            );
-
-            // Walk through resolutions
-            for resolution_entry in fs::read_dir(symbol_entry.path())? {
-                let resolution_entry = resolution_entry?;
-                let file_name = resolution_entry.file_name().to_string_lossy().to_string();
-                eprintln!("File name: {}", file_name);
-
-                if !file_name.starts_with(|c: char| c.is_alphabetic()) {
-                    continue;
-                }
-
-                let resolution = Resolution::from_str(
-                    &file_name
-                ).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-                // Walk through data types
-                for data_type_entry in fs::read_dir(resolution_entry.path())? {
-                    let file_name = data_type_entry?.file_name(); // Unwraps the DirEntry from the Result
-                    let file_name_str = file_name
-                        .to_str()
-                        .unwrap_or("")
-                        .to_string();
-
-                    let data_type_str = file_name_str.split('/').last().unwrap_or("");
-                    println!("Data type string: {}", data_type_str);
-                    let data_type = BaseDataType::from_str(data_type_str)
-                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-                    println!("File name is: {}", file_name_str);
-
-                    // Get earliest and latest data times
-                    let earliest = self.get_earliest_data_time(
-                        &symbol,
-                        &resolution,
-                        &data_type
-                    ).await.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
-
-                    let latest = self.get_latest_data_time(
-                        &symbol,
-                        &resolution,
-                        &data_type
-                    ).await.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
-
-                    if let (Some(earliest), Some(latest)) = (earliest, latest) {
-                        available_data.push(AvailableData {
-                            symbol: symbol_name.clone(),
-                            market_type: market_type.to_string(),
-                            resolution: resolution.to_string(),
-                            data_type: data_type.to_string(),
-                            earliest_date: earliest,
-                            latest_date: latest,
-                        });
-                    }
+            for history in self.available_history(&symbol).await? {
+                if let (Some(earliest), Some(latest)) = (history.earliest_utc(), history.latest_utc()) {
+                    available_data.push(AvailableData {
+                        symbol: symbol.name.clone(),
+                        market_type: symbol.market_type.to_string(),
+                        resolution: history.resolution.to_string(),
+                        data_type: history.base_data_type.to_string(),
+                        earliest_date: earliest,
+                        latest_date: latest,
+                    });
                 }
             }
         }
@@ -146,6 +86,114 @@ impl HybridStorage {
     }
 }
 
+impl HybridStorage {
+    /// The symbols the vendor has data stored for, futures are stored under the product, eg. "MNQ", not the contract code.
+    pub fn stored_symbols(&self, data_vendor: &DataVendor) -> io::Result<Vec<Symbol>> {
+        let mut symbols = vec![];
+        let vendor_path = self.base_path.join(data_vendor.to_string());
+        for market_entry in read_visible_dirs(&vendor_path)? {
+            let market_name = market_entry.file_name().to_string_lossy().to_string();
+            for symbol_entry in read_visible_dirs(&market_entry.path())? {
+                let symbol_name = symbol_entry.file_name().to_string_lossy().to_string();
+                let market_type = match market_type_from_dir(&market_name, &symbol_name) {
+                    Some(market_type) => market_type,
+                    None => continue,
+                };
+                symbols.push(Symbol::new(symbol_name, *data_vendor, market_type));
+            }
+        }
+        symbols.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(symbols)
+    }
+
+    /// The resolutions and data types stored for the symbol with the time of the first and last data point.
+    pub async fn available_history(&self, symbol: &Symbol) -> io::Result<Vec<AvailableHistory>> {
+        let mut available = vec![];
+        let symbol_path = self.base_path
+            .join(symbol.data_vendor.to_string())
+            .join(symbol.market_type.to_string())
+            .join(&symbol.name);
+        for resolution_entry in read_visible_dirs(&symbol_path)? {
+            let resolution = match Resolution::from_str(&resolution_entry.file_name().to_string_lossy()) {
+                Ok(resolution) => resolution,
+                Err(_) => continue,
+            };
+            for data_type_entry in read_visible_dirs(&resolution_entry.path())? {
+                let data_type = match BaseDataType::from_str(&data_type_entry.file_name().to_string_lossy()) {
+                    Ok(data_type) => data_type,
+                    Err(_) => continue,
+                };
+                let earliest = self.get_earliest_data_time(symbol, &resolution, &data_type).await
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+                let latest = self.get_latest_data_time(symbol, &resolution, &data_type).await
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+                if let (Some(earliest), Some(latest)) = (earliest, latest) {
+                    available.push(AvailableHistory::new(resolution, data_type, earliest, latest));
+                }
+            }
+        }
+        available.sort_by(|a, b| a.resolution.cmp(&b.resolution).then(a.base_data_type.to_string().cmp(&b.base_data_type.to_string())));
+        Ok(available)
+    }
+
+    /// The metadata of the vendor symbols matching the query, the symbols with stored data and the Rithmic futures products from the product maps.
+    pub async fn symbol_catalog(&self, data_vendor: &DataVendor, query: &str) -> io::Result<Vec<SymbolMetadata>> {
+        let mut symbols = self.stored_symbols(data_vendor)?;
+        if *data_vendor == DataVendor::Rithmic {
+            for symbol_name in get_available_rithmic_symbol_names() {
+                if let Ok(exchange) = get_futures_exchange(symbol_name) {
+                    let symbol = Symbol::new(symbol_name.clone(), DataVendor::Rithmic, MarketType::Futures(exchange));
+                    if !symbols.contains(&symbol) {
+                        symbols.push(symbol);
+                    }
+                }
+            }
+        }
+
+        let mut catalog = vec![];
+        for symbol in symbols {
+            let mut metadata = SymbolMetadata::from_product_maps(symbol);
+            if !metadata.matches(query) {
+                continue;
+            }
+            metadata.history = self.available_history(&metadata.symbol).await?;
+            catalog.push(metadata);
+        }
+        catalog.sort_by(|a, b| a.symbol.name.cmp(&b.symbol.name));
+        Ok(catalog)
+    }
+}
+
+/// The sub directories of the path, skipping hidden directories, empty if the path does not exist.
+fn read_visible_dirs(path: &Path) -> io::Result<Vec<fs::DirEntry>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let mut entries = vec![];
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.path().is_dir() && !entry.file_name().to_string_lossy().starts_with('.') {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Parses the market type directory of the storage, futures are stored as "Futures" so the exchange is found from the symbol.
+fn market_type_from_dir(market_name: &str, symbol_name: &str) -> Option<MarketType> {
+    match market_name {
+        "Forex" => Some(MarketType::Forex),
+        "CFD" => Some(MarketType::CFD),
+        "Futures" => get_futures_exchange(&extract_symbol_from_contract(symbol_name)).ok().map(MarketType::Futures),
+        "Equities" => Some(MarketType::Equities(Exchange::NASDAQ)),
+        "Crypto" => Some(MarketType::Crypto),
+        "ETF" => Some(MarketType::ETF),
+        "Fundamentals" => Some(MarketType::Fundamentals),
+        "Options" => Some(MarketType::Options),
+        _ => None,
+    }
+}
+
 #[derive(Debug)]
 struct AvailableData {
     symbol: String,
@@ -271,4 +319,42 @@ mod tests {
             Err(e) => panic!("Failed to create catalog: {}", e),
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_symbol_catalog() {
+        use crate::standardized_types::base_data::traits::BaseData;
+        let temp_dir = TempDir::new().unwrap();
+        let options = ServerLaunchOptions {
+            data_folder: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let storage = HybridStorage::new(Duration::from_secs(3600), options, 5, 300);
+
+        let mut test_data = generate_5_day_candle_data();
+        for candle in &mut test_data {
+            candle.symbol = Symbol::new("MNQ".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME));
+            candle.resolution = Resolution::Hours(1);
+        }
+        let first = test_data.iter().map(|candle| candle.time_closed_utc()).min().unwrap();
+        let last = test_data.iter().map(|candle| candle.time_closed_utc()).max().unwrap();
+        storage.save_data_bulk(test_data.into_iter().map(BaseDataEnum::Candle).collect()).await.unwrap();
+
+        let catalog = storage.symbol_catalog(&DataVendor::Rithmic, "mnq").await.unwrap();
+        let mnq = catalog.iter().find(|metadata| metadata.symbol.name == "MNQ").unwrap();
+        assert_eq!(mnq.symbol.market_type, MarketType::Futures(FuturesExchange::CME));
+        assert_eq!(mnq.exchange, Some("CME".to_string()));
+        assert_eq!(mnq.tick_size, Some(rust_decimal_macros::dec!(0.25)));
+        assert!(mnq.trading_hours.is_some());
+        assert_eq!(mnq.history.len(), 1);
+        assert_eq!(mnq.history[0].resolution, Resolution::Hours(1));
+        assert_eq!(mnq.history[0].base_data_type, BaseDataType::Candles);
+        assert_eq!(mnq.history[0].earliest_utc(), Some(first));
+        assert_eq!(mnq.history[0].latest_utc(), Some(last));
+
+        // Products from the product maps are listed without history
+        let catalog = storage.symbol_catalog(&DataVendor::Rithmic, "ES").await.unwrap();
+        let es = catalog.iter().find(|metadata| metadata.symbol.name == "ES").unwrap();
+        assert!(es.history.is_empty());
+        assert!(storage.symbol_catalog(&DataVendor::Oanda, "").await.unwrap().is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use std::{fs};
 use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use crate::database::hybrid_storage::HybridStorage;
 use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::archived_data::ArchivedData;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::base_data::traits::BaseData;
@@ -49,10 +50,9 @@ impl HybridStorage {
 
                 if let Some(earliest_file) = days.first() {
                     if let Ok(mmap) = self.get_or_create_mmap(&earliest_file.path(), resolution.clone()).await {
-                        // Create a properly aligned copy
-                        let aligned_data = mmap.as_ref().to_vec();
-                        if let Ok(day_data) = BaseDataEnum::from_array_bytes(&aligned_data) {
-                            return Ok(day_data.into_iter().map(|d| d.time_closed_utc()).min());
+                        // The files are in time order, only the first data point is deserialized
+                        if let Ok(day_data) = ArchivedData::new(&mmap) {
+                            return Ok(day_data.first().map(|d| d.time_closed_utc()));
                         }
                     }
                 }
@@ -105,10 +105,9 @@ impl HybridStorage {
 
                 if let Some(latest_file) = days.first() {
                     if let Ok(mmap) = self.get_or_create_mmap(&latest_file.path(), resolution.clone()).await {
-                        // Create a properly aligned copy
-                        let aligned_data = mmap.as_ref().to_vec();
-                        if let Ok(day_data) = BaseDataEnum::from_array_bytes(&aligned_data) {
-                            return Ok(day_data.into_iter().map(|d| d.time_closed_utc()).max());
+                        // The files are in time order, only the last data point is deserialized
+                        if let Ok(day_data) = ArchivedData::new(&mmap) {
+                            return Ok(day_data.last().map(|d| d.time_closed_utc()));
                         }
                     }
                 }
@@ -132,16 +131,10 @@ impl HybridStorage {
         // If the file exists for the target date, check it first
         if file_path.exists() {
             if let Ok(mmap) = self.get_or_create_mmap(&file_path, resolution.clone()).await {
-                // Create a properly aligned copy of the memory-mapped data
-                let aligned_data = mmap.as_ref().to_vec();
-                if let Ok(day_data) = BaseDataEnum::from_array_bytes(&aligned_data) {
+                if let Ok(day_data) = ArchivedData::new(&mmap) {
                     //eprintln!("Found data for {} in {}", target_time, file_path.display());
                     // Find the latest data point that satisfies the condition
-                    if let Some(latest_data) = day_data
-                        .into_iter()
-                        .filter(|d| d.time_closed_utc() <= target_time)
-                        .max_by_key(|d| d.time_closed_utc())
-                    {
+                    if let Some(latest_data) = day_data.latest_asof(target_time) {
                         return Ok(Some(latest_data));
                     }
                 }
@@ -183,17 +176,10 @@ impl HybridStorage {
             }
 
             if let Ok(mmap) = self.get_or_create_mmap(&file_path, resolution.clone()).await {
-                // Create a properly aligned copy of the memory-mapped data
-                let aligned_data = mmap.as_ref().to_vec();
-                if let Ok(day_data) = BaseDataEnum::from_array_bytes(&aligned_data) {
+                if let Ok(day_data) = ArchivedData::new(&mmap) {
                     //eprintln!("Found data for {}", current_date);
                     // Find the latest data point that satisfies the condition
-                    if let Some(latest_data) = day_data
-                        .into_iter()
-                        .filter(|d| d.time_closed_utc() <= target_time)
-                        .max_by_key(|d| d.time_closed_utc())
-                    {
-                        drop(mmap);
+                    if let Some(latest_data) = day_data.latest_asof(target_time) {
                         return Ok(Some(latest_data));
                     }
                 }
@@ -256,17 +242,11 @@ impl HybridStorage {
 
                     if file_path.exists() {
                         if let Ok(mmap) = self.get_or_create_mmap(&file_path, resolution.clone()).await {
-                            // Create a properly aligned copy of the memory-mapped data
-                            let aligned_data = mmap.as_ref().to_vec();
-                            if let Ok(mut day_data) = BaseDataEnum::from_array_bytes(&aligned_data) {
-                                // Filter data within the time range
-                                day_data.retain(|d| {
-                                    let time = d.time_closed_utc();
-                                    time >= start && time <= end
-                                });
-                                all_data.extend(day_data);
+                            // Only the data within the time range is deserialized from the memory map
+                            if let Ok(day_data) = ArchivedData::new(&mmap) {
+                                all_data.extend(day_data.iter_range(start, end));
                             } else {
-                                eprintln!("Error deserializing data from {}", file_path.display());
+                                tracing::error!("Error deserializing data from {}", file_path.display());
                             }
                         }
                     }
@@ -4,6 +4,7 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::json;
 use strum_macros::Display;
 use crate::database::hybrid_storage::HybridStorage;
+use crate::standardized_types::base_data::archived_data::ArchivedData;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::subscriptions::DataSubscription;
@@ -64,9 +65,10 @@ impl HybridStorage {
 
             // Read and process file
             if let Ok(mmap) = self.get_or_create_mmap(&file_path, subscription.resolution.clone()).await {
-                let aligned_data = mmap.as_ref().to_vec();
-                let data = BaseDataEnum::from_array_bytes(&aligned_data)
-                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let data: Vec<BaseDataEnum> = ArchivedData::new(&mmap)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?
+                    .iter()
+                    .collect();
 
                 match format {
                     ExportFormat::CSV => export_to_csv(&export_path, data, subscription.base_data_type.clone())?,
@@ -6,13 +6,12 @@ use std::path::{Path, PathBuf};
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{self, Read, Write, Seek, SeekFrom};
 use std::sync::{Arc};
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use dashmap::DashMap;
 use flate2::Compression;
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
-use futures_util::future::join_all;
 use memmap2::{Mmap};
 use tokio::sync::{Semaphore};
 use tokio::task;
@@ -21,9 +20,11 @@ use tokio::time::{interval, timeout};
 use crate::messages::data_server_messaging::FundForgeError;
 use crate::server_launch_options::ServerLaunchOptions;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::base_data::quote_codec::{encode_quotes, is_compact_quotes};
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::resolution::Resolution;
 use crate::standardized_types::subscriptions::{DataSubscription, Symbol, SymbolName};
+use crate::strategies::statistics::backtest_cache::stable_hash;
 
 
 #[allow(unused)]
@@ -188,7 +189,7 @@ impl HybridStorage {
 
         if let Some(mmap) = self.mmap_cache.get(&path_str) {
             self.cache_last_accessed.insert(path_str.clone(), Utc::now());
-            println!("Found mmap in cache for file: {:?}", file_path);
+            tracing::debug!("Found mmap in cache for file: {:?}", file_path);
             return Ok(Arc::clone(mmap.value()));
         }
 
@@ -228,17 +229,6 @@ impl HybridStorage {
             }
         };
 
-        let mut file = File::open(file_path)?;
-        let file_size = file.metadata()?.len() as usize;
-
-        // Pre-allocate for compressed data
-        let mut compressed_data = Vec::new();
-        compressed_data.try_reserve(file_size)
-            .map_err(|e| io::Error::new(
-                io::ErrorKind::OutOfMemory,
-                format!("Failed to allocate memory for compressed data: {}", e)
-            ))?;
-
         const MB: usize = 1024 * 1024;
         let buffer_size = match resolution {
             Resolution::Minutes(_) => 1 * MB,
@@ -248,60 +238,16 @@ impl HybridStorage {
             Resolution::Seconds(_) => 5 * MB,
             _ => 1 * MB
         };
-        // Create buffer on heap instead of stack
-        let mut buffer = vec![0; buffer_size];
-        loop {
-            match file.read(&mut buffer) {
-                Ok(0) => break,
-                Ok(n) => {
-                    compressed_data.try_reserve(n)
-                        .map_err(|e| io::Error::new(
-                            io::ErrorKind::OutOfMemory,
-                            format!("Failed to allocate during read: {}", e)
-                        ))?;
-                    compressed_data.extend_from_slice(&buffer[..n]);
-                }
-                Err(e) => return Err(e),
-            }
-        }
-
-        // Pre-allocate decompressed data - estimate size based on compression ratio
-        let mut decompressed = Vec::new();
-        let estimated_size = compressed_data.len() * 3; // Assuming ~3:1 compression ratio
-        decompressed.try_reserve(estimated_size)
-            .map_err(|e| io::Error::new(
-                io::ErrorKind::OutOfMemory,
-                format!("Failed to allocate memory for decompressed data: {}", e)
-            ))?;
-
-        // Decompress in chunks
-        let cursor = std::io::Cursor::new(&compressed_data);
-        let buf_reader = std::io::BufReader::new(cursor);
-        let mut decoder = GzDecoder::new(buf_reader);
-        loop {
-            match decoder.read(&mut buffer) {
-                Ok(0) => break,
-                Ok(n) => {
-                    decompressed.try_reserve(n)
-                        .map_err(|e| io::Error::new(
-                            io::ErrorKind::OutOfMemory,
-                            format!("Failed to allocate during decompression: {}", e)
-                        ))?;
-                    decompressed.extend_from_slice(&buffer[..n]);
-                }
-                Err(e) => return Err(e),
-            }
-        }
 
-        // Create temporary file
+        // Decompress straight into the temporary file, so the memory used does not grow with the size of the file,
+        // the data is then read through the mmap with `ArchivedData` without being copied.
         let temp_path = file_path.with_extension("tmp");
         {
-            let mut temp_file = File::create(&temp_path)?;
-            // Write in chunks to avoid large buffer allocations
-            for chunk in decompressed.chunks(8192) {
-                temp_file.write_all(chunk)?;
-            }
-            temp_file.sync_all()?;
+            let file = File::open(file_path)?;
+            let mut decoder = GzDecoder::new(io::BufReader::with_capacity(buffer_size, file));
+            let mut temp_file = io::BufWriter::with_capacity(buffer_size, File::create(&temp_path)?);
+            io::copy(&mut decoder, &mut temp_file)?;
+            temp_file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
         }
 
         // Create mmap
@@ -402,8 +348,7 @@ impl HybridStorage {
 
         let all_data: Vec<BaseDataEnum> = data_map.into_values().collect();
 
-        // Serialize with rkyv
-        let bytes = BaseDataEnum::vec_to_bytes(all_data);
+        let bytes = BaseDataEnum::vec_to_storage_bytes(all_data);
 
         // Compress the serialized data
         // Compress the serialized data using a Cursor
@@ -426,7 +371,7 @@ impl HybridStorage {
             Err(e) => {
                 drop(file);
                 if let Err(remove_err) = std::fs::remove_file(&file_path) {
-                    eprintln!("Failed to remove corrupt file {}: {}", file_path.display(), remove_err);
+                    tracing::error!("Failed to remove corrupt file {}: {}", file_path.display(), remove_err);
                 }
                 return Err(e);
             }
@@ -489,7 +434,7 @@ impl HybridStorage {
                     current_date = match current_date.succ_opt() {
                         Some(date) => date,
                         None => {
-                            eprintln!("Failed to get next day");
+                            tracing::error!("Failed to get next day");
                             break
                         },
                     }
@@ -500,26 +445,16 @@ impl HybridStorage {
         Ok(file_paths)
     }
 
-    // Optionally, we could also make this more efficient by using parallel reads with a bounded semaphore:
-    pub async fn get_compressed_files_in_range(
+    /// A fingerprint of the stored files for the subscriptions in the range, based on the path, length and modified time of each file.
+    /// The files of the fallback vendors are included since they can be merged into the history of a subscription.
+    pub async fn data_version(
         &self,
-        subscription: Vec<DataSubscription>,
+        subscriptions: &Vec<DataSubscription>,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
-    ) -> Result<Vec<Vec<u8>>, FundForgeError> {
-        const TIMEOUT_DURATION: Duration = Duration::from_secs(60);
-        const MAX_CONCURRENT_FILES: usize = 10;
-
-        if subscription.is_empty() {
-            return Err(FundForgeError::ClientSideErrorDebug("No subscriptions provided for file range".to_string()));
-        }
-
-        // Create a bounded semaphore for concurrent file operations
-       // println!("Creating file semaphore");
-        let file_semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FILES));
-        let mut file_tasks = Vec::new();
-
-        for subscription in subscription {
+    ) -> Result<u64, FundForgeError> {
+        let mut fingerprint = String::new();
+        for subscription in subscriptions.iter().flat_map(|subscription| subscription.vendor_subscriptions()) {
             let file_paths = self.get_files_in_range(
                 &subscription.symbol,
                 &subscription.resolution,
@@ -527,115 +462,117 @@ impl HybridStorage {
                 start,
                 end
             ).await?;
-
             for file_path in file_paths {
-                if !file_path.exists() {
-                    continue;
+                if let Ok(metadata) = fs::metadata(&file_path) {
+                    let modified = metadata.modified().ok()
+                        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                        .map(|duration| duration.as_nanos())
+                        .unwrap_or_default();
+                    fingerprint.push_str(&format!("{}|{}|{}\n", file_path.display(), metadata.len(), modified));
                 }
-                //println!("Adding file task for {:?}", file_path);
-
-                let path_str = file_path.to_string_lossy().to_string();
-                let file_lock = self.file_locks
-                    .entry(path_str.clone())
-                    .or_insert_with(|| Arc::new(Semaphore::new(1)))
-                    .clone();
-
-                let file_sem = file_semaphore.clone();
-
-                // Create an async task for each file read
-                let task = async move {
-                    // Acquire the global file semaphore
-                    let _file_permit = match file_sem.acquire().await {
-                        Ok(p) => p,
-                        Err(e) => return Err(FundForgeError::ServerErrorDebug(
-                            format!("Error acquiring global file semaphore: {}", e)
-                        )),
-                    };
-
-                    // Acquire the file-specific lock with timeout
-                    //println!("Acquiring file lock for {:?}", file_path);
-                    let file_permit = match timeout(
-                        Duration::from_secs(60),
-                        file_lock.acquire()
-                    ).await {
-                        Ok(permit) => match permit {
-                            Ok(p) => p,
-                            Err(e) => return Err(FundForgeError::ServerErrorDebug(
-                                format!("Error acquiring file lock: {}", e)
-                            )),
-                        }
-                        Err(_) => return Err(FundForgeError::ServerErrorDebug(
-                            format!("Timeout waiting for file lock: {}", path_str)
-                        )),
-                    };
-
-                    //println!("Reading file {:?}", file_path);
-                    // Open and read the file with timeout
-                    let result = timeout(
-                        Duration::from_secs(60),
-                        async {
-                            let mut file = File::open(&file_path)
-                                .map_err(|e| FundForgeError::ServerErrorDebug(
-                                    format!("Error opening file {:?}: {}", file_path, e)
-                                ))?;
-
-                            let file_size = file.metadata()
-                                .map_err(|e| FundForgeError::ServerErrorDebug(
-                                    format!("Error getting file size {:?}: {}", file_path, e)
-                                ))?.len() as usize;
-                            //println!("File size: {}", file_size);
-
-                            let mut compressed_data = Vec::with_capacity(file_size);
-                            file.read_to_end(&mut compressed_data)
-                                .map_err(|e| FundForgeError::ServerErrorDebug(
-                                    format!("Error reading file {:?}: {}", file_path, e)
-                                ))?;
-
-                            Ok::<Vec<u8>, FundForgeError>(compressed_data)
-                        }
-                    ).await;
+            }
+        }
+        Ok(stable_hash(fingerprint.as_bytes()))
+    }
 
-                    //println!("Releasing file lock for {:?}", file_path);
-                    drop(file_permit);
+    /// Rewrites every stored file written by an older format version in the current format, see `BaseDataEnum::FORMAT_VERSION`,
+    /// and every quote file which is not yet compacted with the quote codec, see `BaseDataEnum::vec_to_storage_bytes()`.
+    /// Old files can be read without migrating, they are also rewritten the next time new data is saved to them. Returns the number of files migrated.
+    pub async fn migrate_format(&self) -> io::Result<usize> {
+        let mut directories = vec![self.base_path.clone()];
+        let mut migrated = 0;
+        while let Some(directory) = directories.pop() {
+            if !directory.exists() {
+                continue;
+            }
+            for entry in fs::read_dir(&directory)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+                if path.extension().is_none_or(|ext| ext != "bin") {
+                    continue;
+                }
 
-                    //println!("Releasing global file semaphore");
-                    match result {
-                        Ok(data) => data,
-                        Err(_) => Err(FundForgeError::ServerErrorDebug(
-                            format!("Timeout reading file: {}", path_str)
-                        )),
+                let mut decompressed = Vec::new();
+                GzDecoder::new(File::open(&path)?).read_to_end(&mut decompressed)?;
+                if is_compact_quotes(&decompressed) {
+                    continue;
+                }
+                let data = match BaseDataEnum::from_array_bytes(&decompressed) {
+                    Ok(data) => data,
+                    Err(_) => {
+                        tracing::error!("Failed to migrate {}, the file could not be read", path.display());
+                        continue;
                     }
                 };
-
-                file_tasks.push(task);
+                // Files in the current format are only rewritten if the quote codec can compact them.
+                if BaseDataEnum::format_version(&decompressed) == BaseDataEnum::FORMAT_VERSION && encode_quotes(&data).is_none() {
+                    continue;
+                }
+                // saving reads the old file and rewrites it, merged with the same data, in the current format
+                self.save_data_to_file(&path, &data).await?;
+                migrated += 1;
             }
         }
+        Ok(migrated)
+    }
 
-        //println!("Executing file tasks");
-        // Execute all file tasks with an overall timeout
-        let results = match timeout(TIMEOUT_DURATION, join_all(file_tasks)).await {
-            Ok(results) => results,
-            Err(_) => return Err(FundForgeError::ServerErrorDebug(
-                "Overall operation timeout exceeded".to_string()
-            )),
-        };
+    /// The stored files of the subscriptions in the range, in subscription then date order.
+    /// The files are read one at a time with `read_compressed_file()` so long ranges are never held in memory at once.
+    pub async fn get_compressed_file_paths_in_range(
+        &self,
+        subscriptions: Vec<DataSubscription>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<PathBuf>, FundForgeError> {
+        if subscriptions.is_empty() {
+            return Err(FundForgeError::ClientSideErrorDebug("No subscriptions provided for file range".to_string()));
+        }
 
-        // Collect successful results
-        //println!("Processing file results");
-        let mut files_data = Vec::new();
-        for result in results {
-            match result {
-                Ok(data) => files_data.push(data),
-                Err(e) => eprintln!("Error processing file: {}", e),
-            }
+        let mut paths = Vec::new();
+        for subscription in subscriptions {
+            let file_paths = self.get_files_in_range(
+                &subscription.symbol,
+                &subscription.resolution,
+                &subscription.base_data_type,
+                start,
+                end
+            ).await?;
+            paths.extend(file_paths.into_iter().filter(|path| path.exists()));
         }
 
-        //println!("Returning {} files", files_data.len());
-        if files_data.is_empty() {
+        if paths.is_empty() {
             return Err(FundForgeError::ServerErrorDebug("No files found in range".to_string()));
         }
+        Ok(paths)
+    }
+
+    /// Reads a stored file as it is on disk, gzip compressed, waiting for any write to the file to finish first.
+    pub async fn read_compressed_file(&self, file_path: &Path) -> Result<Vec<u8>, FundForgeError> {
+        const TIMEOUT_DURATION: Duration = Duration::from_secs(60);
+        let path_str = file_path.to_string_lossy().to_string();
+        let file_lock = self.file_locks
+            .entry(path_str.clone())
+            .or_insert_with(|| Arc::new(Semaphore::new(1)))
+            .clone();
+
+        let _file_permit = match timeout(TIMEOUT_DURATION, file_lock.acquire()).await {
+            Ok(Ok(permit)) => permit,
+            Ok(Err(e)) => return Err(FundForgeError::ServerErrorDebug(format!("Error acquiring file lock: {}", e))),
+            Err(_) => return Err(FundForgeError::ServerErrorDebug(format!("Timeout waiting for file lock: {}", path_str))),
+        };
 
-        Ok(files_data)
+        let mut file = File::open(file_path)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Error opening file {:?}: {}", file_path, e)))?;
+        let file_size = file.metadata()
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Error getting file size {:?}: {}", file_path, e)))?
+            .len() as usize;
+        let mut compressed_data = Vec::with_capacity(file_size);
+        file.read_to_end(&mut compressed_data)
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Error reading file {:?}: {}", file_path, e)))?;
+        Ok(compressed_data)
     }
 }
 
@@ -929,6 +866,42 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_quote_files_are_compacted() {
+        use crate::standardized_types::base_data::quote::Quote;
+        use crate::standardized_types::datavendor_enum::DataVendor;
+        use crate::standardized_types::enums::MarketType;
+        use chrono::TimeZone;
+        use rust_decimal::Decimal;
+        use rust_decimal_macros::dec;
+
+        let (storage, _temp) = setup_test_storage();
+        let symbol = Symbol::new("EUR-USD".to_string(), DataVendor::Oanda, MarketType::Forex);
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 23, 0, 0).unwrap();
+        // Two hours of quotes every second, across the end of the day.
+        let quotes: Vec<BaseDataEnum> = (0..7200)
+            .map(|i| BaseDataEnum::Quote(Quote {
+                symbol: symbol.clone(),
+                ask: dec!(1.08462) + Decimal::new(i % 40, 5),
+                bid: dec!(1.08450) + Decimal::new(i % 40, 5),
+                ask_volume: dec!(1000000),
+                bid_volume: dec!(1000000),
+                time: (start + chrono::Duration::seconds(i)).to_string(),
+            }))
+            .collect();
+        storage.save_data_bulk(quotes.clone()).await.unwrap();
+
+        let file_path = storage.get_file_path(&symbol, &Resolution::Instant, &BaseDataType::Quotes, &start, false);
+        let mut decompressed = Vec::new();
+        GzDecoder::new(File::open(&file_path).unwrap()).read_to_end(&mut decompressed).unwrap();
+        assert!(is_compact_quotes(&decompressed));
+
+        let data = storage.get_data_range(&symbol, &Resolution::Instant, &BaseDataType::Quotes, start, start + chrono::Duration::hours(2)).await.unwrap();
+        assert_eq!(data, quotes);
+        // Compacted files are already current.
+        assert_eq!(storage.migrate_format().await.unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn test_get_data_point_asof() {
         let (storage, _temp) = setup_test_storage();
@@ -1092,4 +1065,74 @@ mod test {
             assert!(file_size > 0, "File is empty: {:?}", file);
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_read_compressed_files_in_range() {
+        let (storage, _temp) = setup_test_storage();
+        let test_data = generate_5_day_candle_data().iter()
+            .map(|c| BaseDataEnum::Candle(c.clone()))
+            .collect::<Vec<_>>();
+        storage.save_data_bulk(test_data.clone()).await.unwrap();
+
+        let subscription = test_data[0].subscription();
+        let start = test_data.first().unwrap().time_closed_utc();
+        let end = test_data.last().unwrap().time_closed_utc();
+        let paths = storage.get_compressed_file_paths_in_range(vec![subscription], start, end).await.unwrap();
+
+        let mut count = 0;
+        for path in &paths {
+            let compressed = storage.read_compressed_file(path).await.unwrap();
+            let mut decompressed = Vec::new();
+            GzDecoder::new(compressed.as_slice()).read_to_end(&mut decompressed).unwrap();
+            count += BaseDataEnum::from_array_bytes(&decompressed).unwrap().len();
+        }
+        assert_eq!(count, test_data.len());
+        assert!(storage.get_compressed_file_paths_in_range(vec![], start, end).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_roll_up_weekly_candles() {
+        use chrono::TimeZone;
+        use chrono_tz::America::Chicago;
+        use rust_decimal_macros::dec;
+        use crate::product_maps::rithmic::maps::CME_HOURS;
+        use crate::standardized_types::base_data::candle::Candle;
+        use crate::standardized_types::datavendor_enum::DataVendor;
+        use crate::standardized_types::enums::{FuturesExchange, MarketType};
+        use crate::standardized_types::subscriptions::CandleType;
+
+        let (storage, _temp) = setup_test_storage();
+        let market_type = MarketType::Futures(FuturesExchange::CME);
+        let symbol = Symbol::new("MNQ".to_string(), DataVendor::Rithmic, market_type);
+        // Two weeks of hourly candles for the hours the market is open, from the Sunday 7 January 17:00 CT open
+        let mut time = Chicago.with_ymd_and_hms(2024, 1, 7, 17, 0, 0).unwrap().to_utc();
+        let end = Chicago.with_ymd_and_hms(2024, 1, 19, 16, 0, 0).unwrap().to_utc();
+        let mut candles = vec![];
+        while time < end {
+            if CME_HOURS.is_market_open(time) {
+                let mut candle = Candle::new(symbol.clone(), dec!(100), dec!(1), dec!(0), dec!(0), time.to_string(), Resolution::Hours(1), CandleType::CandleStick);
+                candle.is_closed = true;
+                candles.push(BaseDataEnum::Candle(candle));
+            }
+            time += chrono::Duration::hours(1);
+        }
+        let hourly_volume = candles.len();
+        storage.save_data_bulk(candles).await.unwrap();
+
+        let weekly = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Week, BaseDataType::Candles, market_type);
+        let (start, to) = (Utc.with_ymd_and_hms(2024, 1, 8, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2024, 1, 20, 0, 0, 0).unwrap());
+        assert_eq!(storage.roll_up_trading_periods(&weekly, start, to).await.unwrap(), 2);
+        // The source files did not change, so the range is not rolled up again
+        assert_eq!(storage.roll_up_trading_periods(&weekly, start, to).await.unwrap(), 0);
+
+        let weeks = storage.get_data_range(&symbol, &Resolution::Week, &BaseDataType::Candles, start, to + chrono::Duration::days(7)).await.unwrap();
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].time_utc(), Chicago.with_ymd_and_hms(2024, 1, 7, 17, 0, 0).unwrap().to_utc());
+        assert_eq!(weeks[1].time_utc(), Chicago.with_ymd_and_hms(2024, 1, 14, 17, 0, 0).unwrap().to_utc());
+        let volume: rust_decimal::Decimal = weeks.iter().map(|week| match week {
+            BaseDataEnum::Candle(candle) => candle.volume,
+            _ => dec!(0),
+        }).sum();
+        assert_eq!(volume, rust_decimal::Decimal::from(hourly_volume));
+    }
+}
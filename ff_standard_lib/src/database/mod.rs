@@ -2,4 +2,5 @@ pub mod hybrid_storage;
 pub mod decompressed_functions;
 pub mod exchange_rate;
 pub mod export_formats;
-mod catalog;
\ No newline at end of file
+mod catalog;
+mod roll_ups;
\ No newline at end of file
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use crate::database::hybrid_storage::HybridStorage;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::product_maps::rithmic::maps::{extract_symbol_from_contract, get_futures_trading_hours};
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::enums::MarketType;
+use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::subscriptions::{DataSubscription, Symbol};
+use crate::strategies::consolidators::consolidator_enum::is_valid_warmup_source;
+use crate::strategies::consolidators::trading_periods::TradingPeriodConsolidator;
+
+lazy_static! {
+    /// The version of the source data each range was rolled up from, so a range is only rolled up again when its source files change.
+    static ref ROLLED_UP: DashMap<(Symbol, Resolution, BaseDataType, DateTime<Utc>, DateTime<Utc>), u64> = DashMap::new();
+}
+
+/// The source data is read a week at a time, so a range of ticks never sits in memory.
+const ROLL_UP_CHUNK_DAYS: i64 = 7;
+
+impl HybridStorage {
+    /// Consolidates the stored data of the symbol into the `Resolution::Day`, `Resolution::Week` or `Resolution::Month` bars of the subscription
+    /// and stores the closed bars, so strategies stream the higher timeframe bars rather than consolidating months of lower resolution data on every run.
+    ///
+    /// The bars are consolidated by the `TradingPeriodConsolidator` from the highest stored resolution which can be consolidated into the subscription,
+    /// with the trading hours of futures. A bar is only stored once the source data reaches the close of its period,
+    /// and the first bar is skipped if the source data starts after its period opened. Returns the number of bars stored.
+    pub async fn roll_up_trading_periods(
+        &self,
+        subscription: &DataSubscription,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<usize, FundForgeError> {
+        if !subscription.resolution.is_trading_period() {
+            return Ok(0);
+        }
+        let source = match self.roll_up_source(subscription).await {
+            Some(source) => source,
+            None => return Ok(0),
+        };
+        let hours = match subscription.market_type {
+            MarketType::Futures(_) => get_futures_trading_hours(&extract_symbol_from_contract(&subscription.symbol.name)).cloned(),
+            _ => None,
+        };
+        let mut consolidator = TradingPeriodConsolidator::new(subscription.clone(), hours)?;
+        let from = consolidator.period_open_of(start);
+
+        let key = (subscription.symbol.clone(), subscription.resolution, subscription.base_data_type, from, end);
+        let version = self.data_version(&vec![source.clone()], from, end).await?;
+        if ROLLED_UP.get(&key).is_some_and(|rolled_version| *rolled_version == version) {
+            return Ok(0);
+        }
+
+        let mut bars = vec![];
+        let mut first_data_time = None;
+        let mut chunk_start = from;
+        while chunk_start < end {
+            let chunk_end = (chunk_start + Duration::days(ROLL_UP_CHUNK_DAYS)).min(end);
+            let data = self.get_data_range(&source.symbol, &source.resolution, &source.base_data_type, chunk_start, chunk_end).await?;
+            for base_data in &data {
+                // The ranges are inclusive, data on the boundary was read with the previous chunk
+                if base_data.time_closed_utc() == chunk_start && chunk_start != from {
+                    continue;
+                }
+                first_data_time.get_or_insert(base_data.time_utc());
+                if let Some(closed_bar) = consolidator.update(base_data)?.closed_data {
+                    bars.push(closed_bar);
+                }
+            }
+            if let Some(closed_bar) = data.last().and_then(|last| consolidator.update_time(last.time_closed_utc())) {
+                bars.push(closed_bar);
+            }
+            chunk_start = chunk_end;
+        }
+
+        // The first bar is missing the data before the source history starts
+        if let (Some(first_bar), Some(first_data_time)) = (bars.first(), first_data_time) {
+            if first_data_time > first_bar.time_utc() + Duration::hours(1) {
+                bars.remove(0);
+            }
+        }
+        let stored = bars.len();
+        self.save_data_bulk(bars).await
+            .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to store {} bars: {}", subscription, e)))?;
+        ROLLED_UP.insert(key, version);
+        Ok(stored)
+    }
+
+    /// The highest stored resolution of the symbol which can be consolidated into the subscription.
+    async fn roll_up_source(&self, subscription: &DataSubscription) -> Option<DataSubscription> {
+        let available = self.available_history(&subscription.symbol).await.ok()?;
+        available.into_iter()
+            .map(|history| DataSubscription::new(subscription.symbol.name.clone(), subscription.symbol.data_vendor, history.resolution, history.base_data_type, subscription.market_type))
+            .filter(|source| source != subscription && is_valid_warmup_source(source, subscription))
+            .max_by_key(|source| source.resolution)
+    }
+}
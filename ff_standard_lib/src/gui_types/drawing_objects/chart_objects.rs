@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use crate::gui_types::settings::Color;
+use crate::standardized_types::new_types::Price;
+use crate::standardized_types::subscriptions::DataSubscription;
+
+/// The symbol drawn by `ChartShape::Marker`.
+#[derive(Clone, Copy, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Eq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum MarkerShape {
+    ArrowUp,
+    ArrowDown,
+    Circle,
+    Cross,
+}
+
+/// The geometry of a `ChartObject`, times are utc strings in the same format as the times of the base data.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum ChartShape {
+    /// A line across the whole chart at the price, eg. a stop or target.
+    HorizontalLine { price: Price },
+    /// A line from the first point through the second point, extended to the right edge of the chart.
+    Ray { from_time: String, from_price: Price, through_time: String, through_price: Price },
+    /// A box between the times and prices, eg. an opening range or a session range.
+    Rectangle { from_time: String, to_time: String, high: Price, low: Price },
+    /// Text anchored at the time and price.
+    Label { time: String, price: Price, text: String },
+    /// A marker at the time and price, eg. an entry or exit.
+    Marker { time: String, price: Price, shape: MarkerShape },
+}
+
+/// An object the strategy draws on the chart of a subscription, see `strategy.draw()`.
+/// Drawing an object with the id of an object already drawn on the subscription replaces the object.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct ChartObject {
+    pub id: String,
+    pub subscription: DataSubscription,
+    pub shape: ChartShape,
+    pub color: Color,
+}
+
+impl ChartObject {
+    pub fn new(id: impl Into<String>, subscription: DataSubscription, shape: ChartShape, color: Color) -> Self {
+        Self {
+            id: id.into(),
+            subscription,
+            shape,
+            color,
+        }
+    }
+
+    pub fn horizontal_line(id: impl Into<String>, subscription: DataSubscription, price: Price, color: Color) -> Self {
+        Self::new(id, subscription, ChartShape::HorizontalLine { price }, color)
+    }
+
+    pub fn ray(id: impl Into<String>, subscription: DataSubscription, from: (DateTime<Utc>, Price), through: (DateTime<Utc>, Price), color: Color) -> Self {
+        let shape = ChartShape::Ray {
+            from_time: from.0.to_string(),
+            from_price: from.1,
+            through_time: through.0.to_string(),
+            through_price: through.1,
+        };
+        Self::new(id, subscription, shape, color)
+    }
+
+    pub fn rectangle(id: impl Into<String>, subscription: DataSubscription, from_time: DateTime<Utc>, to_time: DateTime<Utc>, high: Price, low: Price, color: Color) -> Self {
+        let shape = ChartShape::Rectangle {
+            from_time: from_time.to_string(),
+            to_time: to_time.to_string(),
+            high,
+            low,
+        };
+        Self::new(id, subscription, shape, color)
+    }
+
+    pub fn label(id: impl Into<String>, subscription: DataSubscription, time: DateTime<Utc>, price: Price, text: impl Into<String>, color: Color) -> Self {
+        Self::new(id, subscription, ChartShape::Label { time: time.to_string(), price, text: text.into() }, color)
+    }
+
+    pub fn marker(id: impl Into<String>, subscription: DataSubscription, time: DateTime<Utc>, price: Price, shape: MarkerShape, color: Color) -> Self {
+        Self::new(id, subscription, ChartShape::Marker { time: time.to_string(), price, shape }, color)
+    }
+}
@@ -1,2 +1,3 @@
+pub mod chart_objects;
 pub mod lines;
 pub mod drawing_tool_enum;
@@ -60,24 +60,34 @@ pub fn calculate_theoretical_pnl(
 
     let pnl = raw_ticks * symbol_info.value_per_tick * quantity;
 
-    // Convert PnL to account currency
+    to_account_currency(pnl, entry_price, symbol_info, exchange_rate_multiplier, account_currency)
+}
+
+/// Converts an amount in the pnl currency of the symbol to the account currency, currency pairs at the `price` are converted in the same way as `calculate_theoretical_pnl()`.
+pub fn to_account_currency(
+    amount: Price,
+    price: Price,
+    symbol_info: &SymbolInfo,
+    exchange_rate_multiplier: Decimal,
+    account_currency: Currency,
+) -> Price {
     if let Some(base_curr) = symbol_info.base_currency {
         if account_currency == base_curr {
             // Case 1: Account currency is base currency
             // Example: AUD account trading AUD/JPY
-            pnl / entry_price
+            amount / price
         } else if account_currency == symbol_info.pnl_currency {
             // Case 2: Account currency is quote/pnl currency
             // Example: JPY account trading AUD/JPY
-            pnl
+            amount
         } else {
             // Case 3: Account currency is neither
             // Example: EUR account trading AUD/JPY
-            pnl / exchange_rate_multiplier
+            amount / exchange_rate_multiplier
         }
     } else {
         // Not a currency pair
-        pnl * exchange_rate_multiplier
+        amount * exchange_rate_multiplier
     }
 }
 
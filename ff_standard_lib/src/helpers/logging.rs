@@ -0,0 +1,305 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write as FmtWrite;
+use std::fs::{create_dir_all, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use chrono::Utc;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Span, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::writer::MakeWriterExt;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{reload, Layer, Registry};
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::orders::OrderUpdateEvent;
+use crate::StreamName;
+
+static LOG_LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// The most detailed level of log messages that are written, messages below the level are discarded.
+#[derive(Clone, Copy, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Eq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(&self) -> LevelFilter {
+        match self {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(LogLevel::Off),
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err(format!("Invalid log level: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.filter())
+    }
+}
+
+/// Installs the logging subscriber of the process, this must be called before `FundForgeStrategy::initialize()` or at the start of the server.
+///
+/// Log messages are written to the console, warnings and errors to stderr. When a `directory` is given the messages are also written to files in the directory,
+/// messages logged inside a `stream_span()` go to `strategy_{stream_name}.log` and all other messages go to `fund_forge.log`.
+/// The level can be changed while running with `set_log_level()` or a `StrategyControls::SetLogLevel` control.
+/// ```rust,no_run
+/// use ff_standard_lib::helpers::logging::{init_logging, LogLevel};
+///
+/// init_logging(Some("./logs"), LogLevel::Info).unwrap();
+/// ```
+pub fn init_logging(directory: Option<&str>, level: LogLevel) -> Result<(), FundForgeError> {
+    let directory = match directory {
+        Some(directory) => {
+            create_dir_all(directory)
+                .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create log directory {}: {}", directory, e)))?;
+            Some(PathBuf::from(directory))
+        }
+        None => None,
+    };
+    let (filter, handle) = reload::Layer::new(level.filter());
+    let subscriber = Registry::default()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr.with_max_level(Level::WARN).or_else(std::io::stdout)))
+        .with(directory.map(StreamFileLayer::new));
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|_| FundForgeError::ClientSideErrorDebug("Logging is already initialized".to_string()))?;
+    let _ = LOG_LEVEL_HANDLE.set(handle);
+    Ok(())
+}
+
+/// Installs console logging at `LogLevel::Info` if `init_logging()` was not called, so engine messages are not lost.
+pub(crate) fn init_default_logging() {
+    if LOG_LEVEL_HANDLE.get().is_none() {
+        let _ = init_logging(None, LogLevel::Info);
+    }
+}
+
+/// Changes the level of the subscriber installed by `init_logging()`.
+pub fn set_log_level(level: LogLevel) -> Result<(), FundForgeError> {
+    let handle = LOG_LEVEL_HANDLE.get()
+        .ok_or_else(|| FundForgeError::ClientSideErrorDebug("Logging is not initialized".to_string()))?;
+    handle.reload(level.filter())
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to set log level: {}", e)))?;
+    tracing::info!("Log level set to {}", level);
+    Ok(())
+}
+
+/// The span of a strategy connection, messages logged inside the span are written to the log file of the stream.
+pub fn stream_span(stream_name: StreamName) -> Span {
+    tracing::info_span!("strategy", stream_name)
+}
+
+/// The span of an order, attached to the messages logged while the order is handled.
+pub fn order_span(order_id: &str, account: &Account, engine_time: impl fmt::Display) -> Span {
+    tracing::info_span!("order", order_id, %account, %engine_time)
+}
+
+/// Logs the order update inside the span of the order, rejections are logged as warnings.
+pub fn log_order_event(event: &OrderUpdateEvent) {
+    let time = match event {
+        OrderUpdateEvent::OrderAccepted { time, .. }
+        | OrderUpdateEvent::OrderFilled { time, .. }
+        | OrderUpdateEvent::OrderPartiallyFilled { time, .. }
+        | OrderUpdateEvent::OrderCancelled { time, .. }
+        | OrderUpdateEvent::OrderRejected { time, .. }
+        | OrderUpdateEvent::OrderUpdated { time, .. }
+        | OrderUpdateEvent::OrderUpdateRejected { time, .. } => time,
+    };
+    order_span(event.order_id(), event.account(), time).in_scope(|| match event {
+        OrderUpdateEvent::OrderRejected { .. } | OrderUpdateEvent::OrderUpdateRejected { .. } => tracing::warn!("{}", event),
+        _ => tracing::info!("{}", event),
+    });
+}
+
+/// The fields of a span or event as `name=value` pairs, the `message` of an event is written first without a name.
+#[derive(Default)]
+struct FieldVisitor {
+    stream_name: Option<StreamName>,
+    message: String,
+    fields: String,
+}
+
+impl Visit for FieldVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "stream_name" {
+            self.stream_name = StreamName::try_from(value).ok();
+        }
+        self.record_debug(field, &value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+            return;
+        }
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+        let _ = write!(self.fields, "{}={:?}", field.name(), value);
+    }
+}
+
+/// The stream and formatted fields of a span, stored in the span extensions when the span is created.
+struct SpanContext {
+    stream_name: Option<StreamName>,
+    fields: String,
+}
+
+/// Writes each message to the log file of the innermost stream span it was logged in.
+struct StreamFileLayer {
+    directory: PathBuf,
+    files: Mutex<HashMap<Option<StreamName>, File>>,
+}
+
+impl StreamFileLayer {
+    fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn file_name(stream_name: Option<StreamName>) -> String {
+        match stream_name {
+            Some(stream_name) => format!("strategy_{}.log", stream_name),
+            None => "fund_forge.log".to_string(),
+        }
+    }
+
+    fn write(&self, stream_name: Option<StreamName>, line: &str) {
+        let mut files = match self.files.lock() {
+            Ok(files) => files,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let file = match files.entry(stream_name) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let path = self.directory.join(Self::file_name(stream_name));
+                match OpenOptions::new().create(true).append(true).open(&path) {
+                    Ok(file) => entry.insert(file),
+                    Err(e) => {
+                        // Logging from inside the layer would recurse, the error can only go to stderr
+                        eprintln!("Failed to open log file {}: {}", path.display(), e);
+                        return;
+                    }
+                }
+            }
+        };
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+impl<S> Layer<S> for StreamFileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut visitor = FieldVisitor::default();
+            attrs.record(&mut visitor);
+            span.extensions_mut().insert(SpanContext { stream_name: visitor.stream_name, fields: visitor.fields });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut stream_name = None;
+        let mut spans = String::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                let _ = write!(spans, "{}", span.name());
+                if let Some(context) = extensions.get::<SpanContext>() {
+                    if context.stream_name.is_some() {
+                        stream_name = context.stream_name;
+                    }
+                    if !context.fields.is_empty() {
+                        let _ = write!(spans, "{{{}}}", context.fields);
+                    }
+                }
+                spans.push_str(": ");
+            }
+        }
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let mut line = format!("{} {:>5} {}{}", Utc::now().format("%Y-%m-%dT%H:%M:%S%.6fZ"), event.metadata().level(), spans, visitor.message);
+        if !visitor.fields.is_empty() {
+            let _ = write!(line, " {}", visitor.fields);
+        }
+        line.push('\n');
+        self.write(stream_name, &line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standardized_types::broker_enum::Brokerage;
+
+    #[test]
+    fn test_stream_log_files() {
+        let directory = std::env::temp_dir().join("ff_test_stream_log_files");
+        let _ = std::fs::remove_dir_all(&directory);
+        create_dir_all(&directory).unwrap();
+        let (filter, handle) = reload::Layer::new(LevelFilter::INFO);
+        let subscriber = Registry::default().with(filter).with(StreamFileLayer::new(directory.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("Server started");
+            let account = Account::new(Brokerage::Test, "Test_Account_1".to_string());
+            stream_span(7).in_scope(|| {
+                tracing::debug!("Not written at info");
+                order_span("order-1", &account, "2024-01-02 14:00:00 UTC").in_scope(|| tracing::warn!("Order rejected"));
+            });
+            handle.reload(LevelFilter::DEBUG).unwrap();
+            stream_span(7).in_scope(|| tracing::debug!(subscriptions = 2, "Subscribed"));
+        });
+
+        let server_log = std::fs::read_to_string(directory.join("fund_forge.log")).unwrap();
+        assert!(server_log.ends_with(" INFO Server started\n"));
+        let strategy_log = std::fs::read_to_string(directory.join("strategy_7.log")).unwrap();
+        let lines: Vec<&str> = strategy_log.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(" WARN strategy{stream_name=7}: order{order_id=order-1 account=Test - Test_Account_1 engine_time=2024-01-02 14:00:00 UTC}: Order rejected"), "{}", lines[0]);
+        assert!(lines[1].ends_with("DEBUG strategy{stream_name=7}: Subscribed subscriptions=2"), "{}", lines[1]);
+        let _ = std::fs::remove_dir_all(directory);
+    }
+}
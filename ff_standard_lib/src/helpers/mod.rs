@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 pub mod converters;
 pub mod decimal_calculators;
+pub mod logging;
 
 /// this just points to your fund-forge/resources folder, where all SSL key and server configuration toml file is located.
 /// I am aware this is not an optimal way of doing things but it will do for now
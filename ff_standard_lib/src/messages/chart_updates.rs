@@ -0,0 +1,26 @@
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use crate::gui_types::drawing_objects::chart_objects::ChartObject;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::indicators::indicator_values::IndicatorValues;
+
+/// The name a strategy republishes its chart updates under, charting front-ends subscribe to the name with `DataServerRequest::SubscribeChart`.
+pub type ChartName = String;
+
+/// What the strategy sees, republished through the data server so external charting front-ends can plot it.
+///
+/// Strategies opt in with `strategy.publish_chart_updates(chart_name)`, the updates are sent to the server in `DataServerRequest::PublishChartUpdates`
+/// and the server forwards them to every connection subscribed to the chart name as a `DataServerResponse::ChartUpdates`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum ChartUpdate {
+    /// A closed bar from the strategy time slice, including bars created by the strategy consolidators.
+    Bar(BaseDataEnum),
+    /// The values of any indicator the strategy has subscribed, including custom indicators.
+    Indicator(IndicatorValues),
+    /// An object drawn by the strategy with `strategy.draw()`, it replaces the object with the same id on the subscription.
+    Draw(ChartObject),
+    /// Removes the object with the id from the chart of the subscription, see `strategy.erase()`.
+    Erase { subscription: DataSubscription, id: String },
+}
@@ -4,19 +4,29 @@ use crate::standardized_types::bytes_trait::Bytes;
 use rkyv::{Archive, Deserialize, Serialize};
 use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
 use std::fmt::{Debug, Display};
+use std::net::SocketAddrV4;
 use rkyv::ser::Serializer;
 use rust_decimal::Decimal;
 use crate::standardized_types::accounts::{Account, AccountId, AccountInfo, Currency};
 use crate::standardized_types::broker_enum::Brokerage;
 use crate::standardized_types::datavendor_enum::DataVendor;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
-use crate::standardized_types::new_types::{Price};
+use crate::standardized_types::new_types::{Price, Volume};
 use crate::standardized_types::orders::{OrderRequest, OrderUpdateEvent};
-use crate::standardized_types::symbol_info::{CommissionInfo, FrontMonthInfo, SymbolInfo};
+use crate::strategies::subscription_profiles::SubscriptionProfile;
+use crate::standardized_types::options::OptionContract;
+use crate::standardized_types::symbol_info::{CommissionInfo, FinancingRate, FrontMonthInfo, SymbolInfo, SymbolMetadata, SymbolSearchResult};
+use crate::standardized_types::vendor_capabilities::VendorCapabilities;
+use crate::messages::stream_compression::StreamCompression;
+use crate::messages::message_bus::Topic;
+use crate::messages::chart_updates::{ChartName, ChartUpdate};
 
 /// An Api key String
 pub type ApiKey = String;
 
+/// The number of `DataServerResponse::HistoricalDataPart`s the server sends ahead of the client acknowledgements.
+pub const HISTORY_PARTS_IN_FLIGHT: usize = 4;
+
 #[derive(Clone, Serialize, Deserialize, Archive, Debug, PartialEq)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
@@ -51,7 +61,8 @@ pub enum StreamRequest {
 /// # Variants
 /// * [`SynchronousRequestType::HistoricalBaseData`](ff_data_vendors::networks::RequestType) : Requests the Base data for the specified subscriptions. Server returns a ResponseType::HistoricalBaseData with the data payload.
 pub enum DataServerRequest {
-    Register(StrategyMode),
+    /// The first message of a connection, `api_key` is required when the server has api keys configured, see `ConnectionSettings::api_key`.
+    Register{mode: StrategyMode, api_key: Option<String>},
 
     /// Requests a list of instruments all instruments available with the `DataVendor` from the server, an instrument object is the vendors specific data type.
     /// # Fields
@@ -67,6 +78,11 @@ pub enum DataServerRequest {
         callback_id: u64,
         data_vendor: DataVendor
     },
+    /// Requests the `VendorCapabilities` of the `DataVendor`, the server returns a `DataServerResponse::VendorCapabilities`.
+    VendorCapabilities {
+        callback_id: u64,
+        data_vendor: DataVendor
+    },
     /// Requests a list of resolutions available with the `DataVendor` from the server
     Resolutions {
         callback_id: u64,
@@ -117,6 +133,12 @@ pub enum DataServerRequest {
         brokerage: Brokerage,
         symbol_name: SymbolName
     },
+    /// The overnight financing rates of the symbol, returns `DataServerResponse::FinancingRate` or an error if the brokerage does not provide financing rates.
+    FinancingRate{
+        callback_id: u64,
+        brokerage: Brokerage,
+        symbol_name: SymbolName
+    },
     ExchangeRate {
         callback_id: u64,
         from_currency: Currency,
@@ -125,7 +147,24 @@ pub enum DataServerRequest {
         data_vendor: DataVendor,
         side: OrderSide
     },
+    /// Requests the stored data files for the subscriptions in the range, the server streams one `DataServerResponse::HistoricalDataPart` per file
+    /// followed by `DataServerResponse::HistoricalDataComplete`. At most `HISTORY_PARTS_IN_FLIGHT` parts are sent ahead of the `HistoricalDataAck`s.
+    /// `StreamCompression::None` sends the files as stored (gzip), `StreamCompression::Zstd` recompresses them with zstd, which is faster to decompress.
     GetCompressedHistoricalData {
+        callback_id: u64,
+        subscriptions: Vec<DataSubscription>,
+        from_time: String,
+        to_time: String,
+        compression: StreamCompression
+    },
+    /// Acknowledges a `DataServerResponse::HistoricalDataPart` once it has been processed, allowing the server to send the next part.
+    HistoricalDataAck {
+        callback_id: u64,
+        sequence: u64
+    },
+    /// Requests a fingerprint of the stored data files for the subscriptions in the range, the server returns a `DataServerResponse::DataVersion`.
+    /// The version changes when any file in the range is added, removed or updated, it is used to invalidate cached backtest results.
+    DataVersion {
         callback_id: u64,
         subscriptions: Vec<DataSubscription>,
         from_time: String,
@@ -139,7 +178,35 @@ pub enum DataServerRequest {
     },
     Accounts{callback_id: u64, brokerage: Brokerage},
     SymbolNames{callback_id: u64, brokerage: Brokerage, time: Option<String>},
-    RegisterStreamer{port: u16, secs: u64, subsec: u32},
+    /// Searches the instruments of the `DataVendor` for the `query`, the server returns a `DataServerResponse::SymbolSearch`.
+    SymbolSearch{callback_id: u64, data_vendor: DataVendor, query: String},
+    /// Requests the server catalog of the `DataVendor` symbols matching the `query`, the server returns a `DataServerResponse::SymbolCatalog`.
+    /// The catalog is built from the product maps and the stored data, an empty query returns every symbol.
+    SymbolCatalog{callback_id: u64, data_vendor: DataVendor, query: String},
+    /// Requests the option contracts listed on the `underlying`, only those expiring on the `expiry` date (`YYYY-MM-DD`) if it is `Some`.
+    /// The server returns a `DataServerResponse::OptionsChain`.
+    OptionsChain{callback_id: u64, data_vendor: DataVendor, underlying: SymbolName, expiry: Option<String>},
+    /// Saves the profile on the server, the server returns the saved profile as a `DataServerResponse::SubscriptionProfile`.
+    SaveSubscriptionProfile{callback_id: u64, profile: SubscriptionProfile},
+    /// Requests the profile saved with the `name`, the server returns a `DataServerResponse::SubscriptionProfile`.
+    SubscriptionProfile{callback_id: u64, name: String},
+    /// Registers the live data stream connection, `compression` is applied by the server to every frame it writes to the stream.
+    /// If `local_fan_out` is `Some(group)`, market data is published to the local multicast group instead of being written to the stream, see `FanOutFrame`.
+    /// The `api_key` must be the key the connection at `port` registered with.
+    RegisterStreamer{port: u16, secs: u64, subsec: u32, compression: StreamCompression, local_fan_out: Option<SocketAddrV4>, api_key: Option<String>},
+    /// Republishes the strategy chart updates to the connections subscribed to the `chart_name`, see `ChartUpdate`.
+    PublishChartUpdates{chart_name: ChartName, updates: Vec<ChartUpdate>},
+    /// Subscribes the connection to the chart updates published under the `chart_name`, the server forwards them as `DataServerResponse::ChartUpdates`.
+    SubscribeChart{chart_name: ChartName},
+    UnsubscribeChart{chart_name: ChartName},
+    /// Forwards the serialized message to every connection subscribed to the `topic`, see `strategy.publish_message()`.
+    PublishMessage{topic: Topic, payload: Vec<u8>},
+    /// Subscribes the connection to the messages published under the `topic`, the server forwards them as `DataServerResponse::CustomMessage`.
+    SubscribeTopic{topic: Topic},
+    UnsubscribeTopic{topic: Topic},
+    /// Sent by strategies every `heartbeat_interval_secs` of the connection settings, the server answers with `DataServerResponse::Heartbeat`.
+    /// A server drops strategies it has not received any message from within its heartbeat timeout.
+    Heartbeat,
 }
 
 impl DataServerRequest {
@@ -161,6 +228,7 @@ impl DataServerRequest {
             DataServerRequest::Resolutions {callback_id, .. } => {*callback_id = id}
             DataServerRequest::AccountInfo { callback_id, .. } => {*callback_id = id}
             DataServerRequest::BaseDataTypes { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::VendorCapabilities { callback_id, .. } => {*callback_id = id}
             DataServerRequest::Markets { callback_id, .. } => {*callback_id = id}
             DataServerRequest::TickSize { callback_id, .. } => {*callback_id = id}
             DataServerRequest::DecimalAccuracy { callback_id, .. } => {*callback_id = id}
@@ -171,11 +239,26 @@ impl DataServerRequest {
             DataServerRequest::Accounts { callback_id, .. } => {*callback_id = id}
             DataServerRequest::PrimarySubscriptionFor { callback_id, .. } => {*callback_id = id}
             DataServerRequest::SymbolNames { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::SymbolSearch { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::SymbolCatalog { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::OptionsChain { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::SaveSubscriptionProfile { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::SubscriptionProfile { callback_id, .. } => {*callback_id = id}
             DataServerRequest::RegisterStreamer{..} => {}
             DataServerRequest::CommissionInfo { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::FinancingRate { callback_id, .. } => {*callback_id = id}
             DataServerRequest::WarmUpResolutions { callback_id, .. } => {*callback_id = id}
             DataServerRequest::ExchangeRate { callback_id, .. } => {*callback_id = id}
             DataServerRequest::GetCompressedHistoricalData { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::HistoricalDataAck { .. } => {}
+            DataServerRequest::DataVersion { callback_id, .. } => {*callback_id = id}
+            DataServerRequest::PublishChartUpdates { .. } => {}
+            DataServerRequest::SubscribeChart { .. } => {}
+            DataServerRequest::UnsubscribeChart { .. } => {}
+            DataServerRequest::PublishMessage { .. } => {}
+            DataServerRequest::SubscribeTopic { .. } => {}
+            DataServerRequest::UnsubscribeTopic { .. } => {}
+            DataServerRequest::Heartbeat => {}
             DataServerRequest::FrontMonthInfo { callback_id, .. } => {*callback_id = id}
         }
     }
@@ -194,11 +277,39 @@ pub enum SubscriptionResponse {
 /// Represents a request type for the network message. This enum is used to specify the type of request and the returning response
 pub enum
 DataServerResponse {
-    CompressedHistoricalData {
+    /// One file of a `DataServerRequest::GetCompressedHistoricalData` response, `sequence` counts up from 0.
+    HistoricalDataPart {
+        callback_id: u64,
+        sequence: u64,
+        payload: Vec<u8>
+    },
+
+    /// Marks the end of a `DataServerRequest::GetCompressedHistoricalData` response, `parts` is the number of parts sent.
+    HistoricalDataComplete {
+        callback_id: u64,
+        parts: u64
+    },
+
+    DataVersion {
         callback_id: u64,
-        payload: Vec<Vec<u8>>
+        version: u64
     },
 
+    /// Chart updates published by a strategy, sent to each connection subscribed to the `chart_name`.
+    ChartUpdates {
+        chart_name: ChartName,
+        updates: Vec<ChartUpdate>
+    },
+
+    /// A message published by a strategy, sent to each connection subscribed to the `topic`.
+    CustomMessage {
+        topic: Topic,
+        payload: Vec<u8>
+    },
+
+    /// The answer to a `DataServerRequest::Heartbeat`, strategies treat a server they have not received any message from within the `heartbeat_timeout_secs` as dead.
+    Heartbeat,
+
     /// Responds with `instruments` as `Vec<InstrumentEnum>` which contains:
     /// *  `Vec<Symbol>` for all symbols available on the server, to fullfill this the vendor will need a fn that converts from its instrument format into a `Symbol` object.
     Symbols {
@@ -212,6 +323,11 @@ DataServerResponse {
         base_data_types: Vec<BaseDataType>
     },
 
+    VendorCapabilities {
+        callback_id: u64,
+        capabilities: VendorCapabilities
+    },
+
 /*    Rates{
         callback_id: u64,
         rates: BTreeMap<(Currency, Currency), Decimal>
@@ -289,6 +405,13 @@ DataServerResponse {
         reason: Option<String>
     },
 
+    /// The vendor live data of a subscription with fallback vendors is coming from, sent when the subscription starts and whenever the server switches vendor.
+    SubscriptionVendor {
+        subscription: DataSubscription,
+        vendor: DataVendor,
+        reason: String
+    },
+
     FrontMonthInfo{
         callback_id: u64,
         info: FrontMonthInfo
@@ -296,6 +419,14 @@ DataServerResponse {
 
     SymbolNames{callback_id: u64, symbol_names: Vec<SymbolName>},
 
+    SymbolSearch{callback_id: u64, results: Vec<SymbolSearchResult>},
+
+    SymbolCatalog{callback_id: u64, symbols: Vec<SymbolMetadata>},
+
+    OptionsChain{callback_id: u64, contracts: Vec<OptionContract>},
+
+    SubscriptionProfile{callback_id: u64, profile: SubscriptionProfile},
+
     Accounts{callback_id: u64, accounts: Vec<AccountId>},
 
     PrimarySubscriptionFor{callback_id: u64, primary_subscription: DataSubscription},
@@ -306,10 +437,13 @@ DataServerResponse {
 
     CommissionInfo{callback_id: u64, commission_info: CommissionInfo},
 
+    FinancingRate{callback_id: u64, financing_rate: FinancingRate},
+
     LiveAccountUpdates {account: Account, cash_value: Decimal, cash_available: Decimal, cash_used: Decimal},
 
     /// Booked pnl is only sent for closed positions, it is the amount of booked pnl since the last side change from none to long or short
-    LivePositionUpdates {symbol_name: SymbolName, symbol_code: SymbolCode, account: Account, open_quantity: f64, average_price: f64, side: PositionSide, open_pnl: f64, time: String},
+    /// The prices are rounded to the precision of the symbol by the vendor api.
+    LivePositionUpdates {symbol_name: SymbolName, symbol_code: SymbolCode, account: Account, open_quantity: Volume, average_price: Price, side: PositionSide, open_pnl: Price, time: String},
 }
 
 impl Bytes<DataServerResponse> for DataServerResponse {
@@ -343,20 +477,32 @@ impl DataServerResponse {
             DataServerResponse::SymbolInfo  { callback_id,.. } => Some(callback_id.clone()),
             DataServerResponse::SymbolInfoMany  { callback_id,.. } => Some(callback_id.clone()),
             DataServerResponse::BaseDataTypes { callback_id,.. } => Some(callback_id.clone()),
+            DataServerResponse::VendorCapabilities { callback_id,.. } => Some(callback_id.clone()),
             DataServerResponse::SubscribeResponse { .. } => None,
             DataServerResponse::UnSubscribeResponse { .. } => None,
+            DataServerResponse::SubscriptionVendor { .. } => None,
             DataServerResponse::Accounts {callback_id, ..} => Some(callback_id.clone()),
             DataServerResponse::OrderUpdates{..} => None,
             DataServerResponse::PrimarySubscriptionFor {callback_id, ..} => Some(callback_id.clone()),
             DataServerResponse::SymbolNames {callback_id, ..} => Some(callback_id.clone()),
+            DataServerResponse::SymbolSearch {callback_id, ..} => Some(callback_id.clone()),
+            DataServerResponse::SymbolCatalog {callback_id, ..} => Some(callback_id.clone()),
+            DataServerResponse::OptionsChain {callback_id, ..} => Some(*callback_id),
+            DataServerResponse::SubscriptionProfile {callback_id, ..} => Some(callback_id.clone()),
             DataServerResponse::RegistrationResponse(_) => None,
             DataServerResponse::CommissionInfo { callback_id,.. } => Some(callback_id.clone()),
+            DataServerResponse::FinancingRate { callback_id,.. } => Some(callback_id.clone()),
             DataServerResponse::FrontMonthInfo { callback_id, .. } => Some(callback_id.clone()),
             DataServerResponse::LiveAccountUpdates { .. } => None,
             DataServerResponse::LivePositionUpdates { .. } => None,
             DataServerResponse::AsyncError { .. } => None,
             DataServerResponse::ExchangeRate { callback_id, .. } => Some(callback_id.clone()),
-            DataServerResponse::CompressedHistoricalData { callback_id, .. } => Some(callback_id.clone()),
+            DataServerResponse::HistoricalDataPart { callback_id, .. } => Some(callback_id.clone()),
+            DataServerResponse::HistoricalDataComplete { callback_id, .. } => Some(callback_id.clone()),
+            DataServerResponse::DataVersion { callback_id, .. } => Some(callback_id.clone()),
+            DataServerResponse::ChartUpdates { .. } => None,
+            DataServerResponse::CustomMessage { .. } => None,
+            DataServerResponse::Heartbeat => None,
         }
     }
 }
@@ -371,7 +517,7 @@ impl DataServerResponse {
 /// * `ServerErrorDebug` - A server side error occurred, the debug message is provided as `String`. [`DataServerError::ServerErrorDebug`](ff_data_vendors::networks::DataServerError)
 /// * `ClientSideErrorDebug` - A client side error occurred, the debug message is provided as `String`. [`DataServerError::ClientSideErrorDebug`](ff_data_vendors::networks::DataServerError)
 pub enum FundForgeError {
-    /// The API key used to authenticate the request is invalid.
+    /// The API key used to authenticate the connection is missing or invalid.
     InvalidApiKey,
     /// The type of request being made is invalid.
     InvalidRequestType(String),
@@ -383,6 +529,10 @@ pub enum FundForgeError {
     UnknownBlameError(String),
     /// An unknown error occurred, the debug message is provided as `String`.
     ConnectionNotFound(String),
+    /// No response arrived within the request timeout of the connection, see `ConnectionSettings::request_timeout_secs`.
+    RequestTimedOut(String),
+    /// The request was cancelled with its `CancellationToken` before the response arrived.
+    RequestCancelled(String),
 }
 
 impl Debug for FundForgeError {
@@ -398,6 +548,8 @@ impl Debug for FundForgeError {
             }
             FundForgeError::UnknownBlameError(debug) => write!(f, "UnknownBlameError: {}", debug),
             FundForgeError::ConnectionNotFound(debug) => write!(f, "ConnectionNotFound {}:", debug),
+            FundForgeError::RequestTimedOut(debug) => write!(f, "RequestTimedOut: {}", debug),
+            FundForgeError::RequestCancelled(debug) => write!(f, "RequestCancelled: {}", debug),
         }
     }
 }
@@ -417,6 +569,8 @@ impl Display for FundForgeError {
             FundForgeError::ConnectionNotFound(debug) => {
                 write!(f, "ConnectionNotFound: {}:", debug)
             }
+            FundForgeError::RequestTimedOut(debug) => write!(f, "RequestTimedOut: {}", debug),
+            FundForgeError::RequestCancelled(debug) => write!(f, "RequestCancelled: {}", debug),
         }
     }
 }
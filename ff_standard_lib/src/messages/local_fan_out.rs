@@ -0,0 +1,132 @@
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket as StdUdpSocket};
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::bytes_trait::Bytes;
+use crate::standardized_types::subscriptions::DataSubscription;
+
+/// The max number of data points sent in a single frame.
+pub const MAX_FRAME_ITEMS: usize = 100;
+
+/// The max size of a udp datagram.
+pub const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+const RECEIVE_BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+/// Local market data fan-out for strategies running on the same machine as the data server.
+///
+/// When a strategy sets `local_fan_out` in its connection settings, it passes the multicast group to the server in `DataServerRequest::RegisterStreamer`.
+/// The server then publishes each subscription to the group once, no matter how many local strategies are subscribed,
+/// instead of writing a copy of the data down every TLS stream. Each strategy joins the group on the loopback interface and keeps only the
+/// frames for its own subscriptions. Orders and all other requests still use the TLS connections.
+///
+/// Multicast is not reliable delivery, the receiver buffer is enlarged to reduce the chance of dropped frames under heavy load.
+///
+/// A `FanOutFrame` is a single multicast datagram, containing the data for one subscription.
+/// The frames of each subscription are numbered by the server, so a receiver can detect the frames it missed.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct FanOutFrame {
+    pub subscription: DataSubscription,
+    /// The sequence number of the frame in the stream of the subscription, starting at 0 when the server starts publishing the subscription.
+    pub sequence: u64,
+    pub data: Vec<BaseDataEnum>,
+}
+
+impl Bytes<Self> for FanOutFrame {
+    fn from_bytes(archived: &[u8]) -> Result<FanOutFrame, FundForgeError> {
+        match rkyv::from_bytes::<FanOutFrame>(archived) {
+            Ok(frame) => Ok(frame),
+            Err(e) => Err(FundForgeError::ClientSideErrorDebug(e.to_string())),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        rkyv::to_bytes::<_, 1024>(self).unwrap().into()
+    }
+}
+
+/// Creates the server side socket which publishes to the multicast group on the loopback interface only.
+pub fn fan_out_sender_socket() -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_multicast_if_v4(&Ipv4Addr::LOCALHOST)?;
+    socket.set_multicast_loop_v4(true)?;
+    // Never leave the host
+    socket.set_multicast_ttl_v4(0)?;
+    socket.bind(&SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)).into())?;
+    socket.set_nonblocking(true)?;
+    UdpSocket::from_std(StdUdpSocket::from(socket))
+}
+
+/// Creates the client side socket, joined to the multicast group on the loopback interface.
+/// The address is reused so that multiple strategies on the same machine can join the same group.
+///
+/// Returns a std socket so it can be converted with `tokio::net::UdpSocket::from_std()` inside the runtime that will read it.
+pub fn fan_out_receiver_socket(group: SocketAddrV4) -> io::Result<StdUdpSocket> {
+    if !group.ip().is_multicast() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a multicast address", group)));
+    }
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    socket.set_recv_buffer_size(RECEIVE_BUFFER_SIZE)?;
+    socket.bind(&SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, group.port())).into())?;
+    socket.join_multicast_v4(group.ip(), &Ipv4Addr::LOCALHOST)?;
+    socket.set_nonblocking(true)?;
+    Ok(StdUdpSocket::from(socket))
+}
+
+/// Serializes the data into as many frames as needed to keep each datagram under the max size,
+/// numbering the frames from `sequence`, which is left at the sequence number of the next frame.
+pub fn to_frames(subscription: &DataSubscription, data: Vec<BaseDataEnum>, sequence: &mut u64) -> Vec<Vec<u8>> {
+    let mut frames = Vec::new();
+    for chunk in data.chunks(MAX_FRAME_ITEMS) {
+        let frame = FanOutFrame { subscription: subscription.clone(), sequence: *sequence, data: chunk.to_vec() };
+        let bytes = frame.to_bytes();
+        if bytes.len() <= MAX_DATAGRAM_SIZE || chunk.len() == 1 {
+            frames.push(bytes);
+            *sequence += 1;
+        } else {
+            let (first, second) = chunk.split_at(chunk.len() / 2);
+            frames.extend(to_frames(subscription, first.to_vec(), sequence));
+            frames.extend(to_frames(subscription, second.to_vec(), sequence));
+        }
+    }
+    frames
+}
+
+/// The number of frames missed between the `last` frame received for a subscription and the `next` one,
+/// a sequence number at or below the last one means the server started publishing the subscription again.
+pub fn missed_frames(last: u64, next: u64) -> u64 {
+    next.saturating_sub(last + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::base_data_type::BaseDataType;
+    use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::resolution::Resolution;
+
+    #[test]
+    fn test_frame_sequence() {
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Ticks(1), BaseDataType::Ticks, MarketType::Futures(FuturesExchange::CME));
+        let tick = BaseDataEnum::Tick(Tick::new(subscription.symbol.clone(), dec!(100), "2024-06-03 14:30:00 UTC".to_string(), dec!(1), Aggressor::Sell));
+        let mut sequence = 0;
+        let frames = to_frames(&subscription, vec![tick; MAX_FRAME_ITEMS + 1], &mut sequence);
+        assert_eq!(sequence, 2);
+        let sequences: Vec<u64> = frames.iter().map(|frame| FanOutFrame::from_bytes(frame).unwrap().sequence).collect();
+        assert_eq!(sequences, vec![0, 1]);
+
+        assert_eq!(missed_frames(1, 2), 0);
+        assert_eq!(missed_frames(1, 5), 3);
+        // The server started publishing again
+        assert_eq!(missed_frames(7, 0), 0);
+    }
+}
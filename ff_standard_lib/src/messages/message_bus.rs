@@ -0,0 +1,51 @@
+use rkyv::ser::serializers::AllocSerializer;
+use rkyv::validation::validators::DefaultValidator;
+use rkyv::de::deserializers::SharedDeserializeMap;
+use rkyv::{Archive, CheckBytes, Deserialize, Serialize};
+use crate::messages::data_server_messaging::FundForgeError;
+
+/// The topic strategies publish messages under, strategies receive the messages of a topic after subscribing with `DataServerRequest::SubscribeTopic`.
+pub type Topic = String;
+
+/// Serializes a message for `strategy.publish_message()`, any rkyv struct can be sent between strategies.
+pub fn encode_message<T: Serialize<AllocSerializer<256>>>(message: &T) -> Result<Vec<u8>, FundForgeError> {
+    match rkyv::to_bytes::<_, 256>(message) {
+        Ok(bytes) => Ok(bytes.into()),
+        Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Failed to encode message: {}", e))),
+    }
+}
+
+/// Deserializes the payload of a `StrategyEvent::CustomMessage`, the receiving strategy must know the type published on the topic.
+pub fn decode_message<T>(payload: &[u8]) -> Result<T, FundForgeError>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<DefaultValidator<'a>> + Deserialize<T, SharedDeserializeMap>,
+{
+    match rkyv::from_bytes::<T>(payload) {
+        Ok(message) => Ok(message),
+        Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Failed to decode message: {}", e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rkyv::{Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+
+    #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+    #[archive(compare(PartialEq), check_bytes)]
+    #[archive_attr(derive(Debug))]
+    struct Signal {
+        symbol_name: String,
+        long: bool,
+        strength: u32,
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let signal = Signal { symbol_name: "MNQ".to_string(), long: true, strength: 7 };
+        let payload = encode_message(&signal).unwrap();
+        assert_eq!(decode_message::<Signal>(&payload).unwrap(), signal);
+        assert!(decode_message::<Signal>(&payload[..2]).is_err());
+    }
+}
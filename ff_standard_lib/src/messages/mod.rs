@@ -1,2 +1,5 @@
 pub mod registry_messages;
-pub mod data_server_messaging;
\ No newline at end of file
+pub mod data_server_messaging;
+pub mod stream_compression;
+pub mod local_fan_out;
+pub mod chart_updates;pub mod message_bus;
@@ -0,0 +1,64 @@
+use std::fmt::{Display, Formatter};
+use rkyv::{Archive, Deserialize, Serialize};
+use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
+use crate::messages::data_server_messaging::FundForgeError;
+
+/// The compression applied to each frame sent over the live data stream connection.
+///
+/// The client requests the compression when it sends `DataServerRequest::RegisterStreamer`, the server then compresses every
+/// `TimeSlice` frame before writing it to the stream. This is most useful for remote strategies connecting to a colocated server over WAN,
+/// for strategies on the same machine or LAN `StreamCompression::None` will have the lowest latency.
+///
+/// Set `stream_compression = "Zstd"` for the connection in the client `server_settings.toml` to enable it.
+#[derive(Clone, Copy, Serialize, Deserialize, Archive, Debug, SerdeSerialize, SerdeDeserialize, PartialEq, Eq, Hash, Default)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum StreamCompression {
+    #[default]
+    None,
+    Zstd,
+}
+
+impl StreamCompression {
+    /// Low levels are fast enough for tick and quote streams and still give most of the size reduction.
+    const ZSTD_LEVEL: i32 = 3;
+
+    pub fn compress(&self, bytes: Vec<u8>) -> Result<Vec<u8>, FundForgeError> {
+        match self {
+            StreamCompression::None => Ok(bytes),
+            StreamCompression::Zstd => zstd::bulk::compress(&bytes, StreamCompression::ZSTD_LEVEL)
+                .map_err(|e| FundForgeError::ServerErrorDebug(format!("Failed to compress stream frame: {}", e))),
+        }
+    }
+
+    pub fn decompress(&self, bytes: Vec<u8>) -> Result<Vec<u8>, FundForgeError> {
+        match self {
+            StreamCompression::None => Ok(bytes),
+            StreamCompression::Zstd => zstd::stream::decode_all(bytes.as_slice())
+                .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to decompress stream frame: {}", e))),
+        }
+    }
+}
+
+impl Display for StreamCompression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamCompression::None => write!(f, "None"),
+            StreamCompression::Zstd => write!(f, "Zstd"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zstd_round_trip() {
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i % 7) as u8).collect();
+        let compressed = StreamCompression::Zstd.compress(bytes.clone()).unwrap();
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(StreamCompression::Zstd.decompress(compressed).unwrap(), bytes);
+        assert_eq!(StreamCompression::None.compress(bytes.clone()).unwrap(), bytes);
+    }
+}
@@ -1,2 +1,2 @@
 pub mod oanda;
-pub mod rithmic;
\ No newline at end of file
+pub mod rithmic;pub mod symbol_aliases;
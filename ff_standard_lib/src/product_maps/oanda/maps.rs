@@ -17,6 +17,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // USD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-USD".to_string(), SymbolInfo {
@@ -26,6 +29,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // USD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("GBP-USD".to_string(), SymbolInfo {
@@ -35,6 +41,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // USD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("NZD-USD".to_string(), SymbolInfo {
@@ -44,6 +53,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // USD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-CAD".to_string(), SymbolInfo {
@@ -53,6 +65,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CAD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-CHF".to_string(), SymbolInfo {
@@ -62,6 +77,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CHF 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-JPY".to_string(), SymbolInfo {
@@ -71,6 +89,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),      // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-GBP".to_string(), SymbolInfo {
@@ -80,6 +101,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // GBP 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-JPY".to_string(), SymbolInfo {
@@ -89,6 +113,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),      // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-CHF".to_string(), SymbolInfo {
@@ -98,6 +125,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CHF 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("AUD-CAD".to_string(), SymbolInfo {
@@ -107,6 +137,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CAD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("AUD-CHF".to_string(), SymbolInfo {
@@ -116,6 +149,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CHF 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("AUD-JPY".to_string(), SymbolInfo {
@@ -125,6 +161,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),      // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("AUD-NZD".to_string(), SymbolInfo {
@@ -134,6 +173,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // NZD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("CAD-CHF".to_string(), SymbolInfo {
@@ -143,6 +185,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CHF 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("CAD-JPY".to_string(), SymbolInfo {
@@ -152,6 +197,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),      // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("CHF-JPY".to_string(), SymbolInfo {
@@ -161,6 +209,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),      // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-AUD".to_string(), SymbolInfo {
@@ -170,6 +221,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // AUD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
                m.insert("EUR-CAD".to_string(), SymbolInfo {
@@ -179,6 +233,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CAD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-NOK".to_string(), SymbolInfo {
@@ -188,6 +245,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // NOK 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-NZD".to_string(), SymbolInfo {
@@ -197,6 +257,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // NZD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-SEK".to_string(), SymbolInfo {
@@ -206,6 +269,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // SEK 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("GBP-AUD".to_string(), SymbolInfo {
@@ -215,6 +281,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // AUD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("GBP-CAD".to_string(), SymbolInfo {
@@ -224,6 +293,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CAD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("GBP-CHF".to_string(), SymbolInfo {
@@ -233,6 +305,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),   // CHF 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("GBP-JPY".to_string(), SymbolInfo {
@@ -242,6 +317,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),     // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("GBP-NZD".to_string(), SymbolInfo {
@@ -251,6 +329,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // NZD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("NZD-CAD".to_string(), SymbolInfo {
@@ -260,6 +341,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // CAD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("NZD-CHF".to_string(), SymbolInfo {
@@ -269,6 +353,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // CHF 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("NZD-JPY".to_string(), SymbolInfo {
@@ -278,6 +365,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),     // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-NOK".to_string(), SymbolInfo {
@@ -287,6 +377,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // NOK 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-SEK".to_string(), SymbolInfo {
@@ -296,6 +389,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // SEK 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
                 m.insert("USD-CNH".to_string(), SymbolInfo {
@@ -305,6 +401,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // CNH 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-MXN".to_string(), SymbolInfo {
@@ -314,6 +413,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // MXN 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-ZAR".to_string(), SymbolInfo {
@@ -323,6 +425,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // ZAR 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("SGD-JPY".to_string(), SymbolInfo {
@@ -332,6 +437,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),     // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-HKD".to_string(), SymbolInfo {
@@ -341,6 +449,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // HKD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-SGD".to_string(), SymbolInfo {
@@ -350,6 +461,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // SGD 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-CZK".to_string(), SymbolInfo {
@@ -359,6 +473,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // CZK 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-HUF".to_string(), SymbolInfo {
@@ -368,6 +485,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // HUF 0.00001 per 0.001 tick size for 1 unit
             tick_size: dec!(0.001),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-PLN".to_string(), SymbolInfo {
@@ -377,6 +497,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // PLN 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-CZK".to_string(), SymbolInfo {
@@ -386,6 +509,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // CZK 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-HUF".to_string(), SymbolInfo {
@@ -395,6 +521,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // HUF 0.00001 per 0.001 tick size for 1 unit
             tick_size: dec!(0.001),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-PLN".to_string(), SymbolInfo {
@@ -404,6 +533,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // PLN 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("ZAR-JPY".to_string(), SymbolInfo {
@@ -413,6 +545,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),     // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USD-TRY".to_string(), SymbolInfo {
@@ -422,6 +557,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // TRY 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EUR-TRY".to_string(), SymbolInfo {
@@ -431,6 +569,9 @@ lazy_static! {
             value_per_tick: dec!(0.00001),  // TRY 0.00001 per 0.00001 tick size for 1 unit
             tick_size: dec!(0.00001),
             decimal_accuracy: 5,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("TRY-JPY".to_string(), SymbolInfo {
@@ -440,6 +581,9 @@ lazy_static! {
             value_per_tick: dec!(0.01),     // JPY 0.01 per 0.01 tick size for 1 unit
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("BTC-USD".to_string(), SymbolInfo {
@@ -449,6 +593,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: None,
+            min_quantity: None,
         });
 
         m.insert("BCH-USD".to_string(), SymbolInfo {
@@ -458,6 +605,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: None,
+            min_quantity: None,
         });
 
         m.insert("ETH-USD".to_string(), SymbolInfo {
@@ -467,6 +617,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: None,
+            min_quantity: None,
         });
 
         m.insert("LTC-USD".to_string(), SymbolInfo {
@@ -476,6 +629,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: None,
+            min_quantity: None,
         });
         m.insert("AUS200-USD".to_string(), SymbolInfo {
             symbol_name: "AUS200-USD".to_string(),
@@ -484,6 +640,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("CHINA50-USD".to_string(), SymbolInfo {
@@ -493,6 +652,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("EU50-USD".to_string(), SymbolInfo {
@@ -502,6 +664,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("GER30-USD".to_string(), SymbolInfo {
@@ -511,6 +676,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("HK50-USD".to_string(), SymbolInfo {
@@ -520,6 +688,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("US100-USD".to_string(), SymbolInfo {
@@ -529,6 +700,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("NAS100-USD".to_string(), SymbolInfo {
@@ -538,6 +712,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("US30-USD".to_string(), SymbolInfo {
@@ -547,6 +724,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("US500-USD".to_string(), SymbolInfo {
@@ -556,6 +736,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("US2000-USD".to_string(), SymbolInfo {
@@ -565,6 +748,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(0.001),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("FRA40-USD".to_string(), SymbolInfo {
@@ -574,6 +760,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("UK100-USD".to_string(), SymbolInfo {
@@ -583,6 +772,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("INDIA50-USD".to_string(), SymbolInfo {
@@ -592,6 +784,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("JP225-USD".to_string(), SymbolInfo {
@@ -601,6 +796,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("TWIX-USD".to_string(), SymbolInfo {
@@ -610,6 +808,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("NL25-USD".to_string(), SymbolInfo {
@@ -619,6 +820,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(0.001),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("SING30-USD".to_string(), SymbolInfo {
@@ -628,6 +832,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("CH20-USD".to_string(), SymbolInfo {
@@ -637,6 +844,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("ES35-USD".to_string(), SymbolInfo {
@@ -646,6 +856,9 @@ lazy_static! {
             value_per_tick: dec!(1.0),     // USD 1 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("UKOIL-USD".to_string(), SymbolInfo {
@@ -655,6 +868,9 @@ lazy_static! {
             value_per_tick: dec!(10.0),    // USD 10 per 0.01 tick size for lot
             tick_size: dec!(0.01),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("USOIL-USD".to_string(), SymbolInfo {
@@ -664,6 +880,9 @@ lazy_static! {
             value_per_tick: dec!(10.0),    // USD 10 per 0.01 tick size for lot
             tick_size: dec!(0.01),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("NATGAS-USD".to_string(), SymbolInfo {
@@ -673,6 +892,9 @@ lazy_static! {
             value_per_tick: dec!(10.0),    // USD 10 per 0.001 tick size for lot
             tick_size: dec!(0.001),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("COPPER-USD".to_string(), SymbolInfo {
@@ -682,6 +904,9 @@ lazy_static! {
             value_per_tick: dec!(2.5),     // USD 2.5 per 0.0001 tick size for lot
             tick_size: dec!(0.0001),
             decimal_accuracy: 4,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("WHEAT-USD".to_string(), SymbolInfo {
@@ -691,6 +916,9 @@ lazy_static! {
             value_per_tick: dec!(5.0),     // USD 5 per 0.001 tick size for lot
             tick_size: dec!(0.001),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("CORN-USD".to_string(), SymbolInfo {
@@ -700,6 +928,9 @@ lazy_static! {
             value_per_tick: dec!(5.0),     // USD 5 per 0.001 tick size for lot
             tick_size: dec!(0.001),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("SOYBEANS-USD".to_string(), SymbolInfo {
@@ -709,6 +940,9 @@ lazy_static! {
             value_per_tick: dec!(6.0),     // USD 6 per 0.01 tick size for lot
             tick_size: dec!(0.01),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("SUGAR-USD".to_string(), SymbolInfo {
@@ -718,6 +952,9 @@ lazy_static! {
             value_per_tick: dec!(4.0),     // USD 4 per 0.0001 tick size for lot
             tick_size: dec!(0.0001),
             decimal_accuracy: 4,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("XAG-USD".to_string(), SymbolInfo {
@@ -727,6 +964,9 @@ lazy_static! {
             value_per_tick: dec!(5.0),     // USD 5 per 0.001 tick size for lot
             tick_size: dec!(0.001),
             decimal_accuracy: 3,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m.insert("XAU-USD".to_string(), SymbolInfo {
@@ -736,6 +976,9 @@ lazy_static! {
             value_per_tick: dec!(100.0),   // USD 100 per 1.0 tick size for lot
             tick_size: dec!(1.0),
             decimal_accuracy: 2,
+            option_contract: None,
+            lot_size: Some(dec!(1)),
+            min_quantity: None,
         });
 
         m
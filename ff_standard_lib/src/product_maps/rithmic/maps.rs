@@ -167,6 +167,9 @@ lazy_static! {
                     tick_size: dec!($tick_size),
                     decimal_accuracy: $accuracy,
                     base_currency: None,
+                    option_contract: None,
+                    lot_size: Some(dec!(1)),
+                    min_quantity: None,
                 });
             };
         }
@@ -573,6 +576,65 @@ pub fn get_futures_trading_hours(symbol: &str) -> Option<&'static TradingHours>
     TRADING_HOURS.get(symbol).copied()
 }
 
+/// A micro contract and the standard (mini) contract of the same underlying, `micros_per_mini` micros have the same notional as one mini.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelatedContracts {
+    pub micro: SymbolName,
+    pub mini: SymbolName,
+    pub micros_per_mini: Decimal,
+}
+
+impl RelatedContracts {
+    /// The number of minis the quantity of the symbol is equal to, `None` if the symbol is not one of the contracts.
+    pub fn mini_equivalent(&self, symbol_name: &str, quantity: Decimal) -> Option<Decimal> {
+        match symbol_name {
+            s if s == self.mini => Some(quantity),
+            s if s == self.micro => Some(quantity / self.micros_per_mini),
+            _ => None,
+        }
+    }
+
+    /// The micro contract code for a mini contract code, eg. `ESZ4` -> `MESZ4`.
+    pub fn micro_code(&self, mini_code: &str) -> Option<String> {
+        mini_code.strip_prefix(self.mini.as_str()).map(|expiry| format!("{}{}", self.micro, expiry))
+    }
+}
+
+lazy_static! {
+    static ref RELATED_CONTRACTS: Vec<(&'static str, &'static str, Decimal)> = vec![
+        ("MES", "ES", dec!(10)),
+        ("MNQ", "NQ", dec!(10)),
+        ("MYM", "YM", dec!(10)),
+        ("M2K", "RTY", dec!(10)),
+        ("MGC", "GC", dec!(10)),
+        ("MCL", "CL", dec!(10)),
+        ("MHG", "HG", dec!(10)),
+        ("SIL", "SI", dec!(5)),
+        ("M6A", "6A", dec!(10)),
+        ("M6B", "6B", dec!(5)),
+        ("M6E", "6E", dec!(10)),
+        ("MJY", "6J", dec!(10)),
+    ];
+}
+
+/// The micro and mini contracts related to the symbol, the symbol can be either contract.
+pub fn get_related_contracts(symbol_name: &str) -> Option<RelatedContracts> {
+    RELATED_CONTRACTS.iter()
+        .find(|(micro, mini, _)| *micro == symbol_name || *mini == symbol_name)
+        .map(|(micro, mini, micros_per_mini)| RelatedContracts {
+            micro: micro.to_string(),
+            mini: mini.to_string(),
+            micros_per_mini: *micros_per_mini,
+        })
+}
+
+/// All the micro and mini contract pairs.
+pub fn get_all_related_contracts() -> Vec<RelatedContracts> {
+    RELATED_CONTRACTS.iter()
+        .filter_map(|(micro, _, _)| get_related_contracts(micro))
+        .collect()
+}
+
 const fn const_time(hour: u32, min: u32, sec: u32) -> NaiveTime {
     match NaiveTime::from_hms_opt(hour, min, sec) {
         Some(t) => t,
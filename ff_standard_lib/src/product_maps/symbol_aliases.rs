@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde_derive::Deserialize;
+use crate::helpers::converters::fund_forge_formatted_symbol_name;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::product_maps::oanda::maps::OANDA_SYMBOL_INFO;
+use crate::standardized_types::broker_enum::Brokerage;
+use crate::standardized_types::datavendor_enum::DataVendor;
+use crate::standardized_types::subscriptions::SymbolName;
+
+lazy_static! {
+    static ref SYMBOL_ALIASES: RwLock<SymbolAliases> = RwLock::new(SymbolAliases::built_in());
+}
+
+/// A data vendor or brokerage with its own symbol codes, the codes of every Rithmic system are the same.
+pub trait SymbolVenue {
+    fn venue_name(&self) -> String;
+}
+
+impl SymbolVenue for DataVendor {
+    fn venue_name(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl SymbolVenue for Brokerage {
+    fn venue_name(&self) -> String {
+        match self {
+            Brokerage::Rithmic(_) => "Rithmic".to_string(),
+            brokerage => brokerage.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SymbolAliasesFile {
+    #[serde(default)]
+    aliases: HashMap<String, SymbolName>,
+    #[serde(default)]
+    venues: HashMap<String, HashMap<SymbolName, String>>,
+}
+
+/// Maps the names strategies use for symbols to the codes of each vendor and brokerage, see `load_symbol_aliases()`.
+///
+/// The canonical symbol name is the fund forge name, eg. "EUR-USD" or "MES", it is the name used by subscriptions, orders and the storage keys of the data server.
+/// Aliases are other names for a canonical name, eg. "EURUSD", they are replaced by the canonical name when a `DataSubscription` is created.
+/// Venue codes are the codes a vendor or brokerage uses for a canonical name, eg. "EUR_USD" on Oanda, symbols without a venue code use the canonical name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolAliases {
+    aliases: HashMap<SymbolName, SymbolName>,
+    to_venue: HashMap<String, HashMap<SymbolName, String>>,
+    from_venue: HashMap<String, HashMap<String, SymbolName>>,
+}
+
+impl SymbolAliases {
+    /// The codes of the product maps, the Oanda instruments use '_' where fund forge uses '-'.
+    fn built_in() -> Self {
+        let mut aliases = SymbolAliases::default();
+        for symbol_name in OANDA_SYMBOL_INFO.keys() {
+            aliases.add_venue_code(&DataVendor::Oanda.venue_name(), symbol_name.clone(), symbol_name.replace('-', "_"));
+        }
+        aliases
+    }
+
+    fn add_venue_code(&mut self, venue: &str, symbol_name: SymbolName, code: String) {
+        let symbol_name = fund_forge_formatted_symbol_name(&symbol_name);
+        self.from_venue.entry(venue.to_string()).or_default().insert(code.clone(), symbol_name.clone());
+        self.to_venue.entry(venue.to_string()).or_default().insert(symbol_name, code);
+    }
+
+    /// Adds the aliases and venue codes of the toml, replacing existing entries with the same name.
+    fn merge_toml(&mut self, content: &str) -> Result<(), FundForgeError> {
+        let file: SymbolAliasesFile = toml::from_str(content)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse symbol aliases: {}", e)))?;
+        for (alias, symbol_name) in file.aliases {
+            self.aliases.insert(fund_forge_formatted_symbol_name(&alias), fund_forge_formatted_symbol_name(&symbol_name));
+        }
+        for (venue, codes) in file.venues {
+            for (symbol_name, code) in codes {
+                self.add_venue_code(&venue, symbol_name, code);
+            }
+        }
+        Ok(())
+    }
+
+    fn canonical_name(&self, symbol_name: &str) -> SymbolName {
+        let symbol_name = fund_forge_formatted_symbol_name(symbol_name);
+        self.aliases.get(&symbol_name).cloned().unwrap_or(symbol_name)
+    }
+
+    fn venue_code(&self, symbol_name: &SymbolName, venue: &str) -> String {
+        let symbol_name = self.canonical_name(symbol_name);
+        self.to_venue.get(venue).and_then(|codes| codes.get(&symbol_name)).cloned().unwrap_or(symbol_name)
+    }
+
+    fn canonical_from_venue(&self, code: &str, venue: &str) -> SymbolName {
+        match self.from_venue.get(venue).and_then(|names| names.get(code)) {
+            Some(symbol_name) => symbol_name.clone(),
+            None => self.canonical_name(code),
+        }
+    }
+}
+
+/// Adds the aliases and venue codes of a toml file to the built in maps, entries in the file replace the built in entries.
+/// Strategies load the file before creating their subscriptions, the data server loads `{data_folder}/symbol_aliases.toml` when it starts, see `init_symbol_aliases()`.
+///
+/// # File Format
+/// ```toml
+/// [aliases]
+/// "EURUSD" = "EUR-USD"
+/// "MICRO-ES" = "MES"
+///
+/// [venues.Oanda]
+/// "SPX500-USD" = "SPX500_USD"
+///
+/// [venues.DataBento]
+/// "MESZ4" = "MESZ24"
+/// ```
+/// The venue names are the `DataVendor` and `Brokerage` names, "Rithmic" for every Rithmic system.
+pub fn load_symbol_aliases(path: &Path) -> Result<(), FundForgeError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to read symbol aliases {}: {}", path.display(), e)))?;
+    SYMBOL_ALIASES.write().unwrap().merge_toml(&content)
+}
+
+/// Loads `{data_folder}/symbol_aliases.toml` if it exists, only the built in maps are used without the file.
+pub fn init_symbol_aliases(data_folder: &Path) {
+    let path = data_folder.join("symbol_aliases.toml");
+    if !path.exists() {
+        return;
+    }
+    match load_symbol_aliases(&path) {
+        Ok(_) => tracing::info!("Symbol Aliases: Loaded {}", path.display()),
+        Err(e) => tracing::error!("Symbol Aliases: {}", e),
+    }
+}
+
+/// The canonical symbol name of a name or alias, eg. "EURUSD" to "EUR-USD" when the alias is configured.
+pub fn canonical_symbol_name(symbol_name: &str) -> SymbolName {
+    SYMBOL_ALIASES.read().unwrap().canonical_name(symbol_name)
+}
+
+/// The code the vendor or brokerage uses for the symbol, eg. "EUR_USD" for "EUR-USD" on Oanda.
+pub fn venue_symbol(symbol_name: &SymbolName, venue: &impl SymbolVenue) -> String {
+    SYMBOL_ALIASES.read().unwrap().venue_code(symbol_name, &venue.venue_name())
+}
+
+/// The canonical symbol name of a code received from the vendor or brokerage, eg. "EUR-USD" for "EUR_USD" from Oanda.
+pub fn symbol_from_venue(code: &str, venue: &impl SymbolVenue) -> SymbolName {
+    SYMBOL_ALIASES.read().unwrap().canonical_from_venue(code, &venue.venue_name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apis::rithmic::rithmic_systems::RithmicSystem;
+
+    #[test]
+    fn test_symbol_aliases() {
+        let mut aliases = SymbolAliases::built_in();
+        let oanda = DataVendor::Oanda.venue_name();
+        assert_eq!(aliases.venue_code(&"EUR-USD".to_string(), &oanda), "EUR_USD");
+        assert_eq!(aliases.canonical_from_venue("EUR_USD", &oanda), "EUR-USD");
+
+        aliases.merge_toml(r#"
+            [aliases]
+            "EURUSD" = "EUR-USD"
+            "micro es" = "MES"
+
+            [venues.Rithmic]
+            "MES" = "MES-CME"
+        "#).unwrap();
+        assert_eq!(aliases.canonical_name("EURUSD"), "EUR-USD");
+        assert_eq!(aliases.venue_code(&"EURUSD".to_string(), &oanda), "EUR_USD");
+
+        // Every Rithmic system shares the codes.
+        let rithmic = Brokerage::Rithmic(RithmicSystem::Apex).venue_name();
+        assert_eq!(aliases.venue_code(&"MICRO-ES".to_string(), &rithmic), "MES-CME");
+        assert_eq!(aliases.canonical_from_venue("MES-CME", &rithmic), "MES");
+        // Symbols without a code use the canonical name.
+        assert_eq!(aliases.venue_code(&"MNQ".to_string(), &rithmic), "MNQ");
+        assert_eq!(aliases.canonical_from_venue("mnq", &rithmic), "MNQ");
+    }
+}
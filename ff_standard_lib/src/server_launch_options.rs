@@ -1,6 +1,8 @@
 use std::net::{IpAddr, Ipv4Addr};
 use std::path::PathBuf;
+use chrono::{DateTime, Utc};
 use structopt::StructOpt;
+use crate::helpers::logging::LogLevel;
 
 #[derive(Debug, StructOpt, Clone)]
 #[allow(dead_code)]
@@ -71,6 +73,13 @@ pub struct ServerLaunchOptions {
     )]
     pub disable_bitget_server: u64,
 
+    #[structopt(
+        short = "e",
+        long = "forex_factory",
+        default_value = "0"
+    )]
+    pub disable_forex_factory_server: u64,
+
     /// Sets the maximum number of concurrent downloads
     #[structopt(
         short = "m",
@@ -86,6 +95,78 @@ pub struct ServerLaunchOptions {
         default_value = "900"
     )]
     pub update_seconds: u64,
+
+    /// Rewrites stored data written by an older format version before starting the servers, 1 to migrate
+    #[structopt(
+        short = "g",
+        long = "migrate",
+        default_value = "0"
+    )]
+    pub migrate_data: u64,
+
+    /// Saves the live data streamed to clients into the historical store as it arrives, 1 to record
+    #[structopt(
+        short = "c",
+        long = "record",
+        default_value = "0"
+    )]
+    pub record_live: u64,
+
+    /// Serves the live data as JSON to charting clients on a websocket at this port on the listener address, 0 to disable
+    #[structopt(
+        short = "w",
+        long = "websocket_port",
+        default_value = "0"
+    )]
+    pub websocket_port: u16,
+
+    /// Drops strategies that have not sent a message or heartbeat within this many seconds, cleaning up their subscriptions, 0 to disable
+    #[structopt(
+        short = "t",
+        long = "heartbeat_timeout",
+        default_value = "60"
+    )]
+    pub heartbeat_timeout: u64,
+
+    /// Sets the log folder, the messages of each strategy connection are written to their own file
+    #[structopt(
+        long = "log_folder",
+        parse(from_os_str),
+        default_value = "./logs"
+    )]
+    pub log_folder: PathBuf,
+
+    /// Sets the log level: off, error, warn, info, debug or trace
+    #[structopt(
+        long = "log_level",
+        default_value = "info"
+    )]
+    pub log_level: LogLevel,
+
+    /// Serves the admin commands on this port on localhost, 0 to disable, requests must include the token saved in `credentials/admin_token.txt` in the data folder
+    #[structopt(
+        long = "admin_port",
+        default_value = "0"
+    )]
+    pub admin_port: u16,
+
+    /// Requires strategies to present a client certificate signed by `client_ca.crt` in the ssl folder, 1 to require
+    #[structopt(
+        long = "client_auth",
+        default_value = "0"
+    )]
+    pub client_auth: u64,
+
+    /// Replays the stored data from this UTC time as the live data instead of streaming from the vendors, eg. 2024-06-05T13:00:00Z
+    #[structopt(long = "replay_from")]
+    pub replay_from: Option<DateTime<Utc>>,
+
+    /// The speed of the replay, 2 replays two seconds of data each second
+    #[structopt(
+        long = "replay_speed",
+        default_value = "1"
+    )]
+    pub replay_speed: f64,
 }
 impl Default for ServerLaunchOptions {
     fn default() -> Self {
@@ -99,8 +180,19 @@ impl Default for ServerLaunchOptions {
             disable_rithmic_server: 0,
             disable_oanda_server: 0,
             disable_bitget_server: 0,
+            disable_forex_factory_server: 0,
             max_downloads: 20,
             update_seconds: 900,
+            migrate_data: 0,
+            record_live: 0,
+            websocket_port: 0,
+            heartbeat_timeout: 60,
+            log_folder: PathBuf::from("./logs"),
+            log_level: LogLevel::Info,
+            admin_port: 0,
+            client_auth: 0,
+            replay_from: None,
+            replay_speed: 1.0,
         }
     }
 }
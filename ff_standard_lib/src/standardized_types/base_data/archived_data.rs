@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use rkyv::{Archived, Deserialize};
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::legacy::BaseDataEnumV1;
+use crate::standardized_types::base_data::quote_codec::{decode_quotes, is_compact_quotes};
+use crate::standardized_types::base_data::traits::BaseData;
+
+enum ArchivedItems<'a> {
+    Current(&'a Archived<Vec<BaseDataEnum>>),
+    Legacy(&'a Archived<Vec<BaseDataEnumV1>>),
+    /// Quote files are not archived, they are decoded in full when opened, see `quote_codec`.
+    Decoded(Vec<BaseDataEnum>),
+}
+
+/// Zero-copy access to serialized `Vec<BaseDataEnum>` bytes, eg. a memory mapped data file.
+///
+/// The archive is validated once, then each item is only deserialized when it is read, so reading a range of a large file only allocates the data in the range.
+/// The range lookups assume the items are in `time_closed_utc()` order, which is the order the data server stores them in.
+pub struct ArchivedData<'a> {
+    items: ArchivedItems<'a>,
+}
+
+impl<'a> ArchivedData<'a> {
+    /// Validates the archive, data written by an older format version is converted to the current types as it is read, see `BaseDataEnum::FORMAT_VERSION`.
+    /// The bytes must be aligned for the archive, memory maps are page aligned.
+    pub fn new(data: &'a [u8]) -> Result<Self, FundForgeError> {
+        if is_compact_quotes(data) {
+            return Ok(Self { items: ArchivedItems::Decoded(decode_quotes(data)?) });
+        }
+        let items = match BaseDataEnum::format_version(data) {
+            BaseDataEnum::FORMAT_VERSION => rkyv::check_archived_root::<Vec<BaseDataEnum>>(&data[BaseDataEnum::FORMAT_HEADER_LEN..])
+                .map(ArchivedItems::Current)
+                .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to validate data: {}", e)))?,
+            1 => rkyv::check_archived_root::<Vec<BaseDataEnumV1>>(data)
+                .map(ArchivedItems::Legacy)
+                .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to validate unversioned data: {}", e)))?,
+            version => return Err(FundForgeError::ClientSideErrorDebug(format!("Unsupported format version {}, the data was written by a newer version", version))),
+        };
+        Ok(Self { items })
+    }
+
+    pub fn len(&self) -> usize {
+        match self.items {
+            ArchivedItems::Current(items) => items.len(),
+            ArchivedItems::Legacy(items) => items.len(),
+            ArchivedItems::Decoded(ref items) => items.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Deserializes the item at the index.
+    pub fn get(&self, index: usize) -> Option<BaseDataEnum> {
+        match self.items {
+            ArchivedItems::Current(items) => items.get(index).map(|item| item.deserialize(&mut rkyv::Infallible).unwrap()),
+            ArchivedItems::Legacy(items) => items.get(index).map(|item| {
+                let item: BaseDataEnumV1 = item.deserialize(&mut rkyv::Infallible).unwrap();
+                BaseDataEnum::from(item)
+            }),
+            ArchivedItems::Decoded(ref items) => items.get(index).cloned(),
+        }
+    }
+
+    pub fn first(&self) -> Option<BaseDataEnum> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<BaseDataEnum> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Deserializes the items one at a time as they are iterated.
+    pub fn iter(&self) -> impl Iterator<Item = BaseDataEnum> + '_ {
+        (0..self.len()).filter_map(move |index| self.get(index))
+    }
+
+    /// The items closing between `from` and `to` inclusive, in time order.
+    /// The first item is found with a binary search, so only the items in the range and a few items to find the start are deserialized.
+    pub fn iter_range(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> impl Iterator<Item = BaseDataEnum> + '_ {
+        let start = self.partition_point(|data| data.time_closed_utc() < from);
+        (start..self.len()).map_while(move |index| self.get(index).filter(|data| data.time_closed_utc() <= to))
+    }
+
+    /// The last item closing at or before the time.
+    pub fn latest_asof(&self, time: DateTime<Utc>) -> Option<BaseDataEnum> {
+        let end = self.partition_point(|data| data.time_closed_utc() <= time);
+        end.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// The index of the first item for which the predicate is false, the items must be partitioned by the predicate.
+    fn partition_point(&self, predicate: impl Fn(&BaseDataEnum) -> bool) -> usize {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.get(middle).is_some_and(|data| predicate(&data)) {
+                true => low = middle + 1,
+                false => high = middle,
+            }
+        }
+        low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::subscriptions::Symbol;
+
+    #[test]
+    fn test_archived_data_range() {
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 13, 30, 0).unwrap();
+        let ticks: Vec<BaseDataEnum> = (0..100)
+            .map(|i| BaseDataEnum::Tick(Tick::new(
+                Symbol::new("MES".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME)),
+                dec!(5300) + rust_decimal::Decimal::from(i),
+                (start + Duration::seconds(i)).to_string(),
+                dec!(1),
+                Aggressor::Buy,
+            )))
+            .collect();
+        let bytes = BaseDataEnum::vec_to_aligned(ticks.clone());
+        let archived = ArchivedData::new(&bytes).unwrap();
+        assert_eq!(archived.len(), 100);
+        assert_eq!(archived.first(), Some(ticks[0].clone()));
+        assert_eq!(archived.last(), Some(ticks[99].clone()));
+
+        let range: Vec<BaseDataEnum> = archived.iter_range(start + Duration::seconds(10), start + Duration::seconds(19)).collect();
+        assert_eq!(range, ticks[10..20].to_vec());
+        assert_eq!(archived.iter_range(start + Duration::hours(1), start + Duration::hours(2)).count(), 0);
+
+        assert_eq!(archived.latest_asof(start + Duration::milliseconds(42500)), Some(ticks[42].clone()));
+        assert_eq!(archived.latest_asof(start - Duration::seconds(1)), None);
+        assert_eq!(archived.iter().collect::<Vec<_>>(), ticks);
+    }
+}
@@ -1,15 +1,19 @@
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::footprint::Footprint;
 use crate::standardized_types::base_data::fundamental::Fundamental;
+use crate::standardized_types::base_data::legacy::BaseDataEnumV1;
 use crate::standardized_types::base_data::quote::Quote;
 use crate::standardized_types::base_data::quotebar::QuoteBar;
+use crate::standardized_types::base_data::quote_codec::{decode_quotes, encode_quotes, is_compact_quotes};
 use crate::standardized_types::base_data::tick::Tick;
 use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::base_data::data_quality::DataQuality;
 use crate::messages::data_server_messaging::FundForgeError;
 use crate::standardized_types::enums::MarketType;
 use crate::standardized_types::subscriptions::{DataSubscription, Symbol};
 use crate::standardized_types::bytes_trait::Bytes;
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use chrono_tz::Tz;
 use rkyv::ser::serializers::AllocSerializer;
 use rkyv::ser::Serializer;
@@ -30,6 +34,7 @@ use crate::standardized_types::resolution::Resolution;
 /// * `Tick`        see [`BaseDataEnum::Tick`](ff_data_vendors::base_data_types::base_data_enum::BaseDataEnum::Tick)
 /// * `Quote`       see [`BaseDataEnum::Quote`](ff_data_vendors::base_data_types::base_data_enum::BaseDataEnum::Quote)
 /// * `Fundamental` see [`BaseDataEnum::Fundamental`](ff_data_vendors::base_data_types::base_data_enum::BaseDataEnum::Fundamental)
+/// * `Footprint`   see [`Footprint`](crate::standardized_types::base_data::footprint::Footprint)
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
@@ -110,6 +115,17 @@ pub enum BaseDataEnum {
     /// * `bias` - `Bias` enum The bias of the fundamental data `Bias` enum variant.
     /// * `data_vendor` - `DataVendor` enum The data vendor of the fundamental data `DataVendor` enum variant.
     Fundamental(Fundamental),
+
+    /// The `Footprint` struct is a bar with the traded volume at each price split by the aggressor side, consolidated from ticks. see [`Footprint`](crate::standardized_types::base_data::footprint::Footprint)
+    /// # Properties
+    /// * `symbol` - The symbol of the asset.
+    /// * `open`, `high`, `low`, `close` - The prices of the bar.
+    /// * `volume`, `bid_volume`, `ask_volume` - The total volume and the volume where the seller or buyer was the aggressor.
+    /// * `levels` - The bid and ask volume at each traded price.
+    /// * `time` - The opening time of the bar.
+    /// * `is_closed` - A boolean value indicating if the bar is closed.
+    /// * `resolution` - The resolution of the bar.
+    Footprint(Footprint),
 }
 
 impl Display for BaseDataEnum {
@@ -164,6 +180,20 @@ impl Display for BaseDataEnum {
                 fundamental.time,
                 fundamental.name
             ),
+            BaseDataEnum::Footprint(footprint) => write!(
+                f,
+                "{}: {}, {}: {}, {}, {}, {}, {}, delta: {}, {}",
+                footprint.symbol.name,
+                footprint.resolution,
+                footprint.symbol.data_vendor,
+                footprint.open,
+                footprint.high,
+                footprint.low,
+                footprint.close,
+                footprint.volume,
+                footprint.delta(),
+                footprint.time
+            ),
         }
     }
 }
@@ -176,6 +206,7 @@ impl BaseDataEnum {
         match self {
             BaseDataEnum::Candle(candle) => candle.is_closed,
             BaseDataEnum::QuoteBar(quote_bar) => quote_bar.is_closed,
+            BaseDataEnum::Footprint(footprint) => footprint.is_closed,
             _ => true,
         }
     }
@@ -188,6 +219,50 @@ impl BaseDataEnum {
             BaseDataEnum::Tick(_) => BaseDataType::Ticks,
             BaseDataEnum::Quote(_) => BaseDataType::Quotes,
             BaseDataEnum::Fundamental(_) => BaseDataType::Fundamentals,
+            BaseDataEnum::Footprint(_) => BaseDataType::Footprints,
+        }
+    }
+
+    /// Returns the `DataQuality` flags of the `BaseDataEnum` variant for variants that implement it, else returns a clean `DataQuality`.
+    pub fn quality(&self) -> DataQuality {
+        match self {
+            BaseDataEnum::Candle(candle) => candle.quality,
+            BaseDataEnum::QuoteBar(bar) => bar.quality,
+            BaseDataEnum::Tick(tick) => tick.quality,
+            BaseDataEnum::Footprint(footprint) => footprint.quality,
+            _ => DataQuality::default(),
+        }
+    }
+
+    /// Returns true if the data has been flagged as estimated, corrected or reported late by the vendor.
+    pub fn is_suspect(&self) -> bool {
+        self.quality().is_suspect()
+    }
+
+    /// Sets the vendor of the symbol, used by the server to deliver the data of a fallback vendor as the data of the subscribed vendor.
+    pub fn set_data_vendor(&mut self, data_vendor: DataVendor) {
+        match self {
+            BaseDataEnum::Candle(candle) => candle.symbol.data_vendor = data_vendor,
+            BaseDataEnum::QuoteBar(bar) => bar.symbol.data_vendor = data_vendor,
+            BaseDataEnum::Tick(tick) => tick.symbol.data_vendor = data_vendor,
+            BaseDataEnum::Quote(quote) => quote.symbol.data_vendor = data_vendor,
+            BaseDataEnum::Fundamental(fundamental) => fundamental.symbol.data_vendor = data_vendor,
+            BaseDataEnum::Footprint(footprint) => footprint.symbol.data_vendor = data_vendor,
+        }
+    }
+
+    /// Moves the time of the data by the offset, used by the server to replay stored data as live data.
+    pub fn shift_time(&mut self, offset: Duration) {
+        let time = match self {
+            BaseDataEnum::Candle(candle) => &mut candle.time,
+            BaseDataEnum::QuoteBar(bar) => &mut bar.time,
+            BaseDataEnum::Tick(tick) => &mut tick.time,
+            BaseDataEnum::Quote(quote) => &mut quote.time,
+            BaseDataEnum::Fundamental(fundamental) => &mut fundamental.time,
+            BaseDataEnum::Footprint(footprint) => &mut footprint.time,
+        };
+        if let Ok(shifted) = DateTime::<Utc>::from_str(time) {
+            *time = (shifted + offset).to_string();
         }
     }
 
@@ -195,16 +270,53 @@ impl BaseDataEnum {
         match self {
             BaseDataEnum::Candle(candle) => candle.is_closed = is_closed,
             BaseDataEnum::QuoteBar(bar) => bar.is_closed = is_closed,
+            BaseDataEnum::Footprint(footprint) => footprint.is_closed = is_closed,
             _ => {}
         }
     }
 
+    /// The version of the stored `Vec<BaseDataEnum>` format written by `vec_to_bytes`.
+    /// Increment this when a stored type changes and keep the previous layout in the `legacy` module so existing files can still be read.
+    ///
+    /// - 1: Unversioned files with no header.
+    /// - 2: Adds the format header, the `DataQuality` of candles, quote bars and ticks, `Tick::conditions` and `Footprint`.
+    ///   Data read from version 1 files has clean quality flags.
+    pub const FORMAT_VERSION: u32 = 2;
+
+    /// The magic bytes and version of the format header, padded to 16 bytes so the archive after it keeps its alignment.
+    /// The first byte can not start an unversioned archive, which begins with string bytes or a variant tag.
+    const FORMAT_MAGIC: [u8; 4] = [0xFF, b'F', b'F', b'D'];
+    pub(crate) const FORMAT_HEADER_LEN: usize = 16;
+
+    /// Returns the format version of serialized `Vec<BaseDataEnum>` bytes, 1 for bytes written before the format was versioned.
+    pub fn format_version(data: &[u8]) -> u32 {
+        if data.len() < BaseDataEnum::FORMAT_HEADER_LEN || data[..4] != BaseDataEnum::FORMAT_MAGIC {
+            return 1;
+        }
+        u32::from_le_bytes([data[4], data[5], data[6], data[7]])
+    }
+
     /// Deserializes from `Vec<u8>` to `Vec<BaseDataEnum>`
+    /// Data written by an older format version is converted to the current types, see `BaseDataEnum::FORMAT_VERSION`.
     pub fn from_array_bytes(data: &Vec<u8>) -> Result<Vec<BaseDataEnum>, Error> {
-        let archived_quotebars = match rkyv::check_archived_root::<Vec<BaseDataEnum>>(&data[..]) {
+        if is_compact_quotes(data) {
+            return decode_quotes(data).map_err(|e| {
+                tracing::error!("Failed to deserialize data: {}", e);
+                Error
+            });
+        }
+        match BaseDataEnum::format_version(data) {
+            BaseDataEnum::FORMAT_VERSION => {}
+            1 => return BaseDataEnum::from_legacy_bytes(data),
+            version => {
+                tracing::error!("Failed to deserialize data: unsupported format version {}, the data was written by a newer version", version);
+                return Err(Error);
+            }
+        }
+        let archived_quotebars = match rkyv::check_archived_root::<Vec<BaseDataEnum>>(&data[BaseDataEnum::FORMAT_HEADER_LEN..]) {
             Ok(data) => data,
             Err(e) => {
-                eprintln!("Failed to deserialize data: {}", e);
+                tracing::error!("Failed to deserialize data: {}", e);
                 return Err(Error);
             }
         };
@@ -215,11 +327,28 @@ impl BaseDataEnum {
             .unwrap())
     }
 
-    /// Serializes a `Vec<PriceDataEnum>` into `AlignedVec`
+    fn from_legacy_bytes(data: &[u8]) -> Result<Vec<BaseDataEnum>, Error> {
+        let archived = match rkyv::check_archived_root::<Vec<BaseDataEnumV1>>(data) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Failed to deserialize unversioned data: {}", e);
+                return Err(Error);
+            }
+        };
+        let data: Vec<BaseDataEnumV1> = archived.deserialize(&mut rkyv::Infallible).unwrap();
+        Ok(data.into_iter().map(BaseDataEnum::from).collect())
+    }
+
+    /// Serializes a `Vec<PriceDataEnum>` into `AlignedVec`, prefixed with the format header.
     pub fn vec_to_aligned(price_data: Vec<BaseDataEnum>) -> AlignedVec {
         // Create a new serializer
         let mut serializer = AllocSerializer::<20971520>::default();
 
+        let mut header = [0u8; BaseDataEnum::FORMAT_HEADER_LEN];
+        header[..4].copy_from_slice(&BaseDataEnum::FORMAT_MAGIC);
+        header[4..8].copy_from_slice(&BaseDataEnum::FORMAT_VERSION.to_le_bytes());
+        serializer.write(&header).unwrap();
+
         // Serialize the Vec<QuoteBar>
         serializer.serialize_value(&price_data).unwrap();
 
@@ -235,6 +364,16 @@ impl BaseDataEnum {
         let vec = BaseDataEnum::vec_to_aligned(price_data);
         vec.to_vec()
     }
+
+    /// Serializes a `Vec<BaseDataEnum>` as it is saved in the historical data store.
+    /// Files of quotes are written with the compact quote codec, see `quote_codec`, everything else as `vec_to_bytes()`.
+    /// Both are read by `from_array_bytes()` and `ArchivedData`.
+    pub fn vec_to_storage_bytes(price_data: Vec<BaseDataEnum>) -> Vec<u8> {
+        match encode_quotes(&price_data) {
+            Some(bytes) => bytes,
+            None => BaseDataEnum::vec_to_bytes(price_data),
+        }
+    }
 }
 
 impl Bytes<Self> for BaseDataEnum {
@@ -262,6 +401,7 @@ impl BaseData for BaseDataEnum {
             BaseDataEnum::Tick(tick) => tick.symbol.clone(),
             BaseDataEnum::Quote(quote) => quote.symbol.clone(),
             BaseDataEnum::Fundamental(fundamental) => fundamental.symbol.clone(),
+            BaseDataEnum::Footprint(footprint) => footprint.symbol.clone(),
         }
     }
 
@@ -280,18 +420,20 @@ impl BaseData for BaseDataEnum {
             BaseDataEnum::Fundamental(fundamental) => {
                 DateTime::from_str(&fundamental.time).unwrap()
             }
+            BaseDataEnum::Footprint(footprint) => DateTime::from_str(&footprint.time).unwrap(),
         }
     }
 
     fn time_closed_utc(&self) -> DateTime<Utc> {
         match self {
-            BaseDataEnum::Candle(candle) => candle.time_utc() + candle.resolution.as_duration(),
+            BaseDataEnum::Candle(candle) => candle.resolution.close_time(candle.time_utc()),
             BaseDataEnum::QuoteBar(quote_bar) => {
-                quote_bar.time_utc() + quote_bar.resolution.as_duration()
+                quote_bar.resolution.close_time(quote_bar.time_utc())
             }
             BaseDataEnum::Tick(tick) => tick.time_utc(),
             BaseDataEnum::Quote(quote) => quote.time_utc(),
             BaseDataEnum::Fundamental(fundamental) => fundamental.time_utc(),
+            BaseDataEnum::Footprint(footprint) => footprint.time_closed_utc(),
         }
     }
 
@@ -307,6 +449,7 @@ impl BaseData for BaseDataEnum {
             BaseDataEnum::Tick(tick) => tick.symbol.data_vendor.clone(),
             BaseDataEnum::Quote(quote) => quote.symbol.data_vendor.clone(),
             BaseDataEnum::Fundamental(fundamental) => fundamental.symbol.data_vendor.clone(),
+            BaseDataEnum::Footprint(footprint) => footprint.symbol.data_vendor.clone(),
         }
     }
 
@@ -317,6 +460,7 @@ impl BaseData for BaseDataEnum {
             BaseDataEnum::Tick(tick) => tick.symbol.market_type.clone(),
             BaseDataEnum::Quote(quote) => quote.symbol.market_type.clone(),
             BaseDataEnum::Fundamental(fundamental) => fundamental.symbol.market_type.clone(),
+            BaseDataEnum::Footprint(footprint) => footprint.symbol.market_type.clone(),
         }
     }
 
@@ -327,6 +471,7 @@ impl BaseData for BaseDataEnum {
             // this works because tick candles will be candles not ticks so number is always 1
             BaseDataEnum::Tick(_) => Resolution::Ticks(1),
             BaseDataEnum::Fundamental(data) => data.resolution ,
+            BaseDataEnum::Footprint(footprint) => footprint.resolution,
             _ => Resolution::Instant,
         }
     }
@@ -338,6 +483,7 @@ impl BaseData for BaseDataEnum {
             BaseDataEnum::Tick(tick) => &tick.symbol,
             BaseDataEnum::Quote(quote) => &quote.symbol,
             BaseDataEnum::Fundamental(fundamental) => &fundamental.symbol,
+            BaseDataEnum::Footprint(footprint) => &footprint.symbol,
         }
     }
 
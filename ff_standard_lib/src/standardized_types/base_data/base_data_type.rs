@@ -1,4 +1,5 @@
 use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::footprint::Footprint;
 use crate::standardized_types::base_data::quote::Quote;
 use crate::standardized_types::base_data::quotebar::QuoteBar;
 use crate::standardized_types::base_data::tick::Tick;
@@ -34,6 +35,8 @@ pub enum BaseDataType {
     QuoteBars = 2,
     Candles = 3,
     Fundamentals = 4,
+    /// Volume at each price split by the aggressor side, consolidated from ticks, see `Footprint`.
+    Footprints = 5,
 }
 impl BaseDataType {
     // Function to get_requests the TypeId of the associated data type
@@ -44,6 +47,7 @@ impl BaseDataType {
             BaseDataType::QuoteBars => TypeId::of::<QuoteBar>(),
             BaseDataType::Candles => TypeId::of::<Candle>(),
             BaseDataType::Fundamentals => TypeId::of::<String>(),
+            BaseDataType::Footprints => TypeId::of::<Footprint>(),
             //BaseDataType::OrderBooks => TypeId::of::<OrderBook>(),
         }
     }
@@ -56,6 +60,7 @@ impl BaseDataType {
             "quotebars" => Ok(BaseDataType::QuoteBars),
             "candles" => Ok(BaseDataType::Candles),
             "fundamentals" => Ok(BaseDataType::Fundamentals),
+            "footprints" => Ok(BaseDataType::Footprints),
             // "order books" => Ok(BaseDataType::OrderBooks),
             _ => Err(format!("Unknown BaseDataType: {}", string_ref)),
         }
@@ -69,6 +74,7 @@ impl BaseDataType {
             BaseDataType::QuoteBars => "Quotebars".to_string(),
             BaseDataType::Candles => "Candles".to_string(),
             BaseDataType::Fundamentals => "Fundamentals".to_string(),
+            BaseDataType::Footprints => "Footprints".to_string(),
             //BaseDataType::OrderBooks => "order books".to_string(),
         }
     }
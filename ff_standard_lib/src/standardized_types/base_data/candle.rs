@@ -14,6 +14,7 @@ use crate::standardized_types::datavendor_enum::DataVendor;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::new_types::{Price, TimeString, Volume};
 use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::base_data::data_quality::DataQuality;
 
 #[derive(
     Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug, Eq, PartialOrd, Ord,
@@ -47,6 +48,7 @@ impl Default for CandleCalculationType {
 /// - `is_closed`: Indicates whether the candles is closed.
 /// - `data_vendor`: The data vendor that provided the candles.
 /// - `resolution`: The resolution of the candles.
+/// - `quality`: Quality flags reported by the vendor or set during consolidation, see `DataQuality`.
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq)]
 #[archive(
 // This will generate a PartialEq impl between our unarchived and archived
@@ -71,6 +73,7 @@ pub struct Candle {
     pub is_closed: bool,
     pub resolution: Resolution,
     pub candle_type: CandleType,
+    pub quality: DataQuality,
 }
 
 impl Display for Candle {
@@ -111,11 +114,11 @@ impl BaseData for Candle {
     }
 
     fn time_closed_utc(&self) -> DateTime<Utc> {
-        self.time_utc() + self.resolution.as_duration()
+        self.resolution.close_time(self.time_utc())
     }
 
     fn time_closed_local(&self, time_zone: &Tz) -> DateTime<Tz> {
-        time_zone.from_utc_datetime(&self.time_utc().naive_utc()) + self.resolution.as_duration()
+        time_zone.from_utc_datetime(&self.time_closed_utc().naive_utc())
     }
 
     fn data_vendor(&self) -> DataVendor {
@@ -183,6 +186,7 @@ impl Candle {
             range: high - low,
             resolution: quotebar.resolution,
             candle_type: CandleType::CandleStick,
+            quality: quotebar.quality,
         }
     }
 
@@ -210,6 +214,7 @@ impl Candle {
             is_closed: false,
             resolution,
             candle_type,
+            quality: DataQuality::default(),
         }
     }
 
@@ -243,6 +248,7 @@ impl Candle {
             is_closed: true,
             resolution,
             candle_type,
+            quality: DataQuality::default(),
         }
     }
 
@@ -265,8 +271,8 @@ impl fmt::Debug for Candle {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Candle {{ resolution {}, symbol: {:?}, high: {}, low: {}, open: {}, close: {}, volume: {}, ask_volume: {}, bid_volume: {} range: {}, time: {}, is_closed: {}, candle_type {} }}",
-            self.resolution, self.symbol, self.high, self.low, self.open, self.close, self.volume, self.ask_volume, self.bid_volume, self.range, self.time, self.is_closed, self.candle_type
+            "Candle {{ resolution {}, symbol: {:?}, high: {}, low: {}, open: {}, close: {}, volume: {}, ask_volume: {}, bid_volume: {} range: {}, time: {}, is_closed: {}, candle_type {}, quality: {} }}",
+            self.resolution, self.symbol, self.high, self.low, self.open, self.close, self.volume, self.ask_volume, self.bid_volume, self.range, self.time, self.is_closed, self.candle_type, self.quality
         )
     }
 }
@@ -310,6 +316,7 @@ pub fn generate_5_day_candle_data() -> Vec<Candle> {
                 is_closed: true,                   // Assume candles are closed
                 resolution: Resolution::Hours(1),  // 1-hour resolution
                 candle_type: CandleType::CandleStick, // Assume standard candles
+                quality: DataQuality::default(),
             });
         }
     }
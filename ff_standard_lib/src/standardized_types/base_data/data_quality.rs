@@ -0,0 +1,153 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+
+/// Quality metadata attached to `Candle`, `QuoteBar` and `Tick` data.
+///
+/// The flags are stored as a bit set so they are cheap to serialize and are preserved through storage and streaming.
+/// A default `DataQuality` has no flags set, meaning the data was reported normally by the vendor.
+///
+/// Strategies and the statistics module can use `is_suspect()` or `weight()` to exclude or down-weight data.
+#[derive(Clone, Copy, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Eq, Hash, Debug, Default)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct DataQuality {
+    flags: u8,
+}
+
+impl DataQuality {
+    /// The vendor estimated the values, eg. a bar filled in for a period with no trades.
+    pub const ESTIMATED: u8 = 1;
+    /// The vendor corrected the values after they were first published.
+    pub const CORRECTED: u8 = 1 << 1;
+    /// The data was consolidated from a lower resolution, rather than being supplied by the vendor at this resolution.
+    pub const CONSOLIDATED: u8 = 1 << 2;
+    /// The vendor reported the data late, so it may not have been available in real time.
+    pub const LATE: u8 = 1 << 3;
+    /// The bar was filled for a period with no data and has no prices, see `FillPolicy::Empty`.
+    pub const EMPTY: u8 = 1 << 4;
+
+    pub fn new(flags: u8) -> Self {
+        Self { flags }
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    pub fn contains(&self, flag: u8) -> bool {
+        self.flags & flag == flag
+    }
+
+    pub fn insert(&mut self, flag: u8) {
+        self.flags |= flag;
+    }
+
+    pub fn remove(&mut self, flag: u8) {
+        self.flags &= !flag;
+    }
+
+    /// Combines the flags of another data point into this one, used when consolidating data.
+    pub fn merge(&mut self, other: &DataQuality) {
+        self.flags |= other.flags;
+    }
+
+    /// Returns true if no flags are set.
+    pub fn is_clean(&self) -> bool {
+        self.flags == 0
+    }
+
+    pub fn is_estimated(&self) -> bool {
+        self.contains(DataQuality::ESTIMATED)
+    }
+
+    pub fn is_corrected(&self) -> bool {
+        self.contains(DataQuality::CORRECTED)
+    }
+
+    pub fn is_consolidated(&self) -> bool {
+        self.contains(DataQuality::CONSOLIDATED)
+    }
+
+    pub fn is_late(&self) -> bool {
+        self.contains(DataQuality::LATE)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contains(DataQuality::EMPTY)
+    }
+
+    /// Returns true if the data was estimated, corrected, reported late or is an empty bar.
+    /// Consolidated data is not considered suspect by itself.
+    pub fn is_suspect(&self) -> bool {
+        self.is_estimated() || self.is_corrected() || self.is_late() || self.is_empty()
+    }
+
+    /// A simple weighting for statistics, 1.0 for clean or consolidated data, halved for each suspect flag.
+    /// Empty bars have no prices, so they have no weight.
+    pub fn weight(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let mut weight = 1.0;
+        for flag in [DataQuality::ESTIMATED, DataQuality::CORRECTED, DataQuality::LATE] {
+            if self.contains(flag) {
+                weight /= 2.0;
+            }
+        }
+        weight
+    }
+}
+
+impl Display for DataQuality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "Clean");
+        }
+        let mut names = Vec::new();
+        if self.is_estimated() {
+            names.push("Estimated");
+        }
+        if self.is_corrected() {
+            names.push("Corrected");
+        }
+        if self.is_consolidated() {
+            names.push("Consolidated");
+        }
+        if self.is_late() {
+            names.push("Late");
+        }
+        if self.is_empty() {
+            names.push("Empty");
+        }
+        write!(f, "{}", names.join("|"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_merge_and_weight() {
+        let mut quality = DataQuality::default();
+        assert!(quality.is_clean());
+        quality.insert(DataQuality::CONSOLIDATED);
+        assert!(!quality.is_suspect());
+        assert_eq!(quality.weight(), 1.0);
+
+        quality.merge(&DataQuality::new(DataQuality::ESTIMATED | DataQuality::LATE));
+        assert!(quality.is_suspect());
+        assert!(quality.is_consolidated() && quality.is_estimated() && quality.is_late());
+        assert_eq!(quality.weight(), 0.25);
+        assert_eq!(quality.to_string(), "Estimated|Consolidated|Late");
+
+        quality.remove(DataQuality::LATE);
+        assert!(!quality.is_late());
+
+        let empty = DataQuality::new(DataQuality::EMPTY | DataQuality::CONSOLIDATED);
+        assert!(empty.is_suspect());
+        assert_eq!(empty.weight(), 0.0);
+        assert_eq!(empty.to_string(), "Consolidated|Empty");
+    }
+}
@@ -0,0 +1,273 @@
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::base_data::data_quality::DataQuality;
+use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::datavendor_enum::DataVendor;
+use crate::standardized_types::enums::MarketType;
+use crate::standardized_types::new_types::{Price, TimeString, Volume};
+use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::subscriptions::{DataSubscription, Symbol};
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal_macros::dec;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The volume traded at a single price within a `Footprint`.
+///
+/// - `bid_volume`: The volume of trades where the seller was the aggressor, hitting the bid.
+/// - `ask_volume`: The volume of trades where the buyer was the aggressor, lifting the ask.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct FootprintLevel {
+    pub price: Price,
+    pub bid_volume: Volume,
+    pub ask_volume: Volume,
+}
+
+impl FootprintLevel {
+    pub fn volume(&self) -> Volume {
+        self.bid_volume + self.ask_volume
+    }
+
+    /// The aggressive buying less the aggressive selling at this price.
+    pub fn delta(&self) -> Volume {
+        self.ask_volume - self.bid_volume
+    }
+}
+
+/// A footprint bar, the traded volume at each price within the bar split by the side of the aggressor, consolidated from ticks.
+/// Subscribe with `BaseDataType::Footprints` and a time resolution, the primary subscription must be 1 tick so each trade has an aggressor.
+///
+/// # Fields
+///
+/// - `symbol`: The trading symbol of the asset.
+/// - `open`, `high`, `low`, `close`: The prices of the bar.
+/// - `volume`: The total volume, including trades with no aggressor.
+/// - `bid_volume`: The total volume where the seller was the aggressor.
+/// - `ask_volume`: The total volume where the buyer was the aggressor.
+/// - `levels`: The volume at each traded price, in ascending price order.
+/// - `time`: The opening time of the bar.
+/// - `is_closed`: Indicates whether the bar is closed.
+/// - `resolution`: The resolution of the bar.
+/// - `quality`: Quality flags merged from the ticks, see `DataQuality`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct Footprint {
+    pub symbol: Symbol,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    pub volume: Volume,
+    pub bid_volume: Volume,
+    pub ask_volume: Volume,
+    pub levels: Vec<FootprintLevel>,
+    pub time: TimeString,
+    pub is_closed: bool,
+    pub resolution: Resolution,
+    pub quality: DataQuality,
+}
+
+impl Footprint {
+    /// Opens a new footprint bar with the first tick.
+    pub fn new(tick: &Tick, time: TimeString, resolution: Resolution) -> Self {
+        let mut footprint = Footprint {
+            symbol: tick.symbol.clone(),
+            open: tick.price,
+            high: tick.price,
+            low: tick.price,
+            close: tick.price,
+            volume: dec!(0),
+            bid_volume: dec!(0),
+            ask_volume: dec!(0),
+            levels: vec![],
+            time,
+            is_closed: false,
+            resolution,
+            quality: tick.quality,
+        };
+        footprint.add_volume(tick);
+        footprint
+    }
+
+    /// Adds a tick to the bar.
+    pub fn update(&mut self, tick: &Tick) {
+        self.high = self.high.max(tick.price);
+        self.low = self.low.min(tick.price);
+        self.close = tick.price;
+        self.quality.merge(&tick.quality);
+        self.add_volume(tick);
+    }
+
+    fn add_volume(&mut self, tick: &Tick) {
+        self.volume += tick.volume;
+        let index = match self.levels.binary_search_by(|level| level.price.cmp(&tick.price)) {
+            Ok(index) => index,
+            Err(index) => {
+                self.levels.insert(index, FootprintLevel { price: tick.price, bid_volume: dec!(0), ask_volume: dec!(0) });
+                index
+            }
+        };
+        match tick.aggressor {
+            Aggressor::Buy => {
+                self.ask_volume += tick.volume;
+                self.levels[index].ask_volume += tick.volume;
+            }
+            Aggressor::Sell => {
+                self.bid_volume += tick.volume;
+                self.levels[index].bid_volume += tick.volume;
+            }
+            Aggressor::None => {}
+        }
+    }
+
+    /// The aggressive buying less the aggressive selling for the bar.
+    pub fn delta(&self) -> Volume {
+        self.ask_volume - self.bid_volume
+    }
+
+    pub fn level(&self, price: Price) -> Option<&FootprintLevel> {
+        self.levels.binary_search_by(|level| level.price.cmp(&price))
+            .ok()
+            .map(|index| &self.levels[index])
+    }
+
+    /// The price with the most aggressive volume, the lowest price wins a tie.
+    pub fn point_of_control(&self) -> Option<Price> {
+        self.levels.iter()
+            .fold(None, |max: Option<&FootprintLevel>, level| match max {
+                Some(max) if max.volume() >= level.volume() => Some(max),
+                _ => Some(level),
+            })
+            .map(|level| level.price)
+    }
+
+    /// The prices where the ask volume is at least `ratio` times the bid volume of the next lower traded price, the diagonal comparison used by footprint charts.
+    pub fn ask_imbalances(&self, ratio: Volume) -> Vec<Price> {
+        self.levels.windows(2)
+            .filter(|pair| pair[1].ask_volume > dec!(0) && pair[1].ask_volume >= pair[0].bid_volume * ratio)
+            .map(|pair| pair[1].price)
+            .collect()
+    }
+
+    /// The prices where the bid volume is at least `ratio` times the ask volume of the next higher traded price.
+    pub fn bid_imbalances(&self, ratio: Volume) -> Vec<Price> {
+        self.levels.windows(2)
+            .filter(|pair| pair[0].bid_volume > dec!(0) && pair[0].bid_volume >= pair[1].ask_volume * ratio)
+            .map(|pair| pair[0].price)
+            .collect()
+    }
+}
+
+impl Display for Footprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?},{},{},{},{},{},{},{},{},{},{},{}",
+            self.symbol,
+            self.resolution,
+            self.high,
+            self.low,
+            self.open,
+            self.close,
+            self.volume,
+            self.ask_volume,
+            self.bid_volume,
+            self.levels.len(),
+            self.time,
+            self.is_closed
+        )
+    }
+}
+
+impl BaseData for Footprint {
+    fn symbol_name(&self) -> Symbol {
+        self.symbol.clone()
+    }
+
+    fn time_local(&self, time_zone: &Tz) -> DateTime<Tz> {
+        time_zone.from_utc_datetime(&self.time_utc().naive_utc())
+    }
+
+    fn time_utc(&self) -> DateTime<Utc> {
+        DateTime::from_str(&self.time).unwrap()
+    }
+
+    fn time_closed_utc(&self) -> DateTime<Utc> {
+        self.resolution.close_time(self.time_utc())
+    }
+
+    fn time_closed_local(&self, time_zone: &Tz) -> DateTime<Tz> {
+        time_zone.from_utc_datetime(&self.time_closed_utc().naive_utc())
+    }
+
+    fn data_vendor(&self) -> DataVendor {
+        self.symbol.data_vendor.clone()
+    }
+
+    fn market_type(&self) -> MarketType {
+        self.symbol.market_type.clone()
+    }
+
+    fn resolution(&self) -> Resolution {
+        self.resolution.clone()
+    }
+
+    fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    fn subscription(&self) -> DataSubscription {
+        DataSubscription::from_base_data(
+            self.symbol.name.clone(),
+            self.symbol.data_vendor.clone(),
+            self.resolution(),
+            BaseDataType::Footprints,
+            self.symbol.market_type.clone(),
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standardized_types::enums::FuturesExchange;
+
+    fn tick(price: Price, volume: Volume, aggressor: Aggressor) -> Tick {
+        Tick {
+            symbol: Symbol::new("MNQ".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME)),
+            price,
+            time: Utc::now().to_string(),
+            volume,
+            aggressor,
+            quality: DataQuality::default(),
+            conditions: None,
+        }
+    }
+
+    #[test]
+    fn test_footprint_levels() {
+        let mut footprint = Footprint::new(&tick(dec!(100.25), dec!(2), Aggressor::Sell), Utc::now().to_string(), Resolution::Minutes(1));
+        footprint.update(&tick(dec!(100.50), dec!(12), Aggressor::Buy));
+        footprint.update(&tick(dec!(100.00), dec!(3), Aggressor::Sell));
+        footprint.update(&tick(dec!(100.25), dec!(1), Aggressor::Buy));
+        footprint.update(&tick(dec!(100.25), dec!(5), Aggressor::None));
+
+        let prices: Vec<Price> = footprint.levels.iter().map(|level| level.price).collect();
+        assert_eq!(prices, vec![dec!(100.00), dec!(100.25), dec!(100.50)]);
+        assert_eq!((footprint.open, footprint.high, footprint.low, footprint.close), (dec!(100.25), dec!(100.50), dec!(100.00), dec!(100.25)));
+        assert_eq!((footprint.volume, footprint.ask_volume, footprint.bid_volume), (dec!(23), dec!(13), dec!(5)));
+        assert_eq!(footprint.delta(), dec!(8));
+        assert_eq!(footprint.level(dec!(100.25)).unwrap().delta(), dec!(-1));
+        assert_eq!(footprint.point_of_control(), Some(dec!(100.50)));
+        // 12 lifted at 100.50 against 2 sold at 100.25, 3 sold at 100.00 against 1 lifted at 100.25.
+        assert_eq!(footprint.ask_imbalances(dec!(3)), vec![dec!(100.50)]);
+        assert_eq!(footprint.bid_imbalances(dec!(3)), vec![dec!(100.00)]);
+    }
+}
@@ -9,9 +9,33 @@ use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv
 use std::fmt::{Debug, Display};
 use std::str::FromStr;
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use crate::standardized_types::datavendor_enum::DataVendor;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::economic_calendar::EconomicEvent;
+use crate::standardized_types::options::Greeks;
+
+/// The `Fundamental.name` of a perpetual futures funding rate, see `Fundamental::funding_rate()`.
+pub const FUNDING_RATE: &str = "funding_rate";
+/// The `Fundamental.name` of a futures open interest, see `Fundamental::open_interest()`.
+pub const OPEN_INTEREST: &str = "open_interest";
+/// The `Fundamental.name` of the economic calendar of a currency, see `Fundamental::economic_calendar()`.
+pub const ECONOMIC_CALENDAR: &str = "economic_calendar";
+/// The `values` key of the number of events in an economic calendar.
+pub const EVENT_COUNT: &str = "event_count";
+/// The `values` key of the next funding time of a funding rate, as a unix timestamp in seconds.
+pub const NEXT_FUNDING_TIME: &str = "next_funding_time";
+/// The `Fundamental.name` of the greeks of an option contract, see `Fundamental::greeks()`.
+pub const GREEKS: &str = "greeks";
+/// The `values` keys of the greeks.
+pub const DELTA: &str = "delta";
+pub const GAMMA: &str = "gamma";
+pub const THETA: &str = "theta";
+pub const VEGA: &str = "vega";
+pub const RHO: &str = "rho";
+pub const IMPLIED_VOLATILITY: &str = "implied_volatility";
+pub const UNDERLYING_PRICE: &str = "underlying_price";
 
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq)]
 #[archive(compare(PartialEq), check_bytes)]
@@ -131,6 +155,101 @@ impl Fundamental {
         }
     }
 
+    /// Create a funding rate for a perpetual future, the rate is the fraction paid by longs to shorts each funding period, negative rates are paid by shorts.
+    /// The data is named `FUNDING_RATE` and the rate is stored under the same key of `values`.
+    pub fn funding_rate(symbol: Symbol, time: DateTime<Utc>, rate: Decimal, next_funding_time: Option<DateTime<Utc>>) -> Self {
+        let mut values = BTreeMap::new();
+        values.insert(FUNDING_RATE.to_string(), rate);
+        if let Some(next_funding_time) = next_funding_time {
+            values.insert(NEXT_FUNDING_TIME.to_string(), Decimal::from(next_funding_time.timestamp()));
+        }
+        Fundamental::new(symbol, time.to_string(), Resolution::Instant, values, None, None, FUNDING_RATE.to_string())
+    }
+
+    /// Create an open interest for a futures contract, the total size of the open positions in base units.
+    /// The data is named `OPEN_INTEREST` and the size is stored under the same key of `values`.
+    pub fn open_interest(symbol: Symbol, time: DateTime<Utc>, open_interest: Decimal) -> Self {
+        let mut values = BTreeMap::new();
+        values.insert(OPEN_INTEREST.to_string(), open_interest);
+        Fundamental::new(symbol, time.to_string(), Resolution::Instant, values, None, None, OPEN_INTEREST.to_string())
+    }
+
+    /// Create the economic calendar of a currency, the symbol name is the currency and the events are stored as json in `value_string`.
+    /// The time should be when the schedule was known, so backtests see the releases before they happen.
+    /// The data is named `ECONOMIC_CALENDAR` and the number of events is stored under `EVENT_COUNT` in `values`.
+    pub fn economic_calendar(symbol: Symbol, time: DateTime<Utc>, events: &[EconomicEvent]) -> Self {
+        let mut values = BTreeMap::new();
+        values.insert(EVENT_COUNT.to_string(), Decimal::from(events.len()));
+        let events = serde_json::to_string(events).ok();
+        Fundamental::new(symbol, time.to_string(), Resolution::Instant, values, events, None, ECONOMIC_CALENDAR.to_string())
+    }
+
+    /// Create the greeks of an option contract, the symbol is the `MarketType::Options` symbol of the contract.
+    /// The data is named `GREEKS` and each greek is stored under its own key of `values`, eg. `DELTA`.
+    pub fn greeks(symbol: Symbol, time: DateTime<Utc>, greeks: &Greeks) -> Self {
+        let mut values = BTreeMap::new();
+        values.insert(DELTA.to_string(), greeks.delta);
+        values.insert(GAMMA.to_string(), greeks.gamma);
+        values.insert(THETA.to_string(), greeks.theta);
+        values.insert(VEGA.to_string(), greeks.vega);
+        values.insert(RHO.to_string(), greeks.rho);
+        values.insert(IMPLIED_VOLATILITY.to_string(), greeks.implied_volatility);
+        if let Some(underlying_price) = greeks.underlying_price {
+            values.insert(UNDERLYING_PRICE.to_string(), underlying_price);
+        }
+        Fundamental::new(symbol, time.to_string(), Resolution::Instant, values, None, None, GREEKS.to_string())
+    }
+
+    /// Returns the greeks if this is the greeks of an option contract.
+    pub fn greeks_value(&self) -> Option<Greeks> {
+        if self.name != GREEKS {
+            return None;
+        }
+        Some(Greeks {
+            delta: *self.values.get(DELTA)?,
+            gamma: *self.values.get(GAMMA)?,
+            theta: *self.values.get(THETA)?,
+            vega: *self.values.get(VEGA)?,
+            rho: *self.values.get(RHO)?,
+            implied_volatility: *self.values.get(IMPLIED_VOLATILITY)?,
+            underlying_price: self.values.get(UNDERLYING_PRICE).cloned(),
+        })
+    }
+
+    /// Returns the scheduled events if this is an economic calendar.
+    pub fn economic_events(&self) -> Option<Vec<EconomicEvent>> {
+        match self.name == ECONOMIC_CALENDAR {
+            true => self.value_string.as_ref().and_then(|events| serde_json::from_str(events).ok()),
+            false => None,
+        }
+    }
+
+    /// Returns the rate if this is a funding rate.
+    pub fn funding_rate_value(&self) -> Option<Decimal> {
+        match self.name == FUNDING_RATE {
+            true => self.values.get(FUNDING_RATE).cloned(),
+            false => None,
+        }
+    }
+
+    /// Returns the next funding time if this is a funding rate and the vendor provided it.
+    pub fn next_funding_time(&self) -> Option<DateTime<Utc>> {
+        match self.name == FUNDING_RATE {
+            true => self.values.get(NEXT_FUNDING_TIME)
+                .and_then(|time| time.to_i64())
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0)),
+            false => None,
+        }
+    }
+
+    /// Returns the size if this is an open interest.
+    pub fn open_interest_value(&self) -> Option<Decimal> {
+        match self.name == OPEN_INTEREST {
+            true => self.values.get(OPEN_INTEREST).cloned(),
+            false => None,
+        }
+    }
+
     pub fn resolution(&self) -> Resolution {
         Resolution::Instant
     }
@@ -167,3 +286,72 @@ impl Display for Fundamental {
         write!(f, "Fundamental {{ symbol: {}, resolution: {}, time: {}, values: {:?}, value_string: {:?}, name: {} }}", self.symbol, self.resolution, self.time, self.values, self.value_string, self.name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_funding_rate_and_open_interest() {
+        let symbol = Symbol::new("BTC-USDT".to_string(), DataVendor::Bitget, MarketType::Fundamentals);
+        let time = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let next = DateTime::from_timestamp(1_700_006_400, 0).unwrap();
+
+        let funding = Fundamental::funding_rate(symbol.clone(), time, dec!(0.0001), Some(next));
+        assert_eq!(funding.funding_rate_value(), Some(dec!(0.0001)));
+        assert_eq!(funding.next_funding_time(), Some(next));
+        assert_eq!(funding.open_interest_value(), None);
+        assert_eq!(funding.time_utc(), time);
+        assert_eq!(funding.subscription().base_data_type, BaseDataType::Fundamentals);
+
+        let open_interest = Fundamental::open_interest(symbol, time, dec!(34278.06));
+        assert_eq!(open_interest.open_interest_value(), Some(dec!(34278.06)));
+        assert_eq!(open_interest.funding_rate_value(), None);
+        assert_eq!(open_interest.next_funding_time(), None);
+        assert_eq!(open_interest.economic_events(), None);
+    }
+
+    #[test]
+    fn test_economic_calendar() {
+        use crate::standardized_types::economic_calendar::EventImpact;
+        let symbol = Symbol::new("USD".to_string(), DataVendor::ForexFactory, MarketType::Fundamentals);
+        let time = DateTime::from_timestamp(1_717_286_400, 0).unwrap();
+        let events = vec![EconomicEvent {
+            currency: "USD".to_string(),
+            title: "Non-Farm Employment Change".to_string(),
+            time: DateTime::from_timestamp(1_717_763_400, 0).unwrap(),
+            impact: EventImpact::High,
+            forecast: Some("185K".to_string()),
+            previous: Some("175K".to_string()),
+        }];
+
+        let calendar = Fundamental::economic_calendar(symbol, time, &events);
+        assert_eq!(calendar.economic_events(), Some(events));
+        assert_eq!(calendar.values.get(EVENT_COUNT), Some(&dec!(1)));
+        assert_eq!(calendar.funding_rate_value(), None);
+    }
+
+    #[test]
+    fn test_greeks() {
+        use crate::standardized_types::options::{OptionContract, OptionRight};
+        let expiry = chrono::NaiveDate::from_ymd_opt(2024, 12, 20).unwrap();
+        let contract = OptionContract::new("AAPL".to_string(), dec!(180), expiry, OptionRight::Call, dec!(100));
+        let symbol = Symbol::option(&contract, DataVendor::Rithmic);
+        assert_eq!(symbol.market_type, MarketType::Options);
+        let time = DateTime::from_timestamp(1_717_286_400, 0).unwrap();
+        let greeks = Greeks {
+            delta: dec!(0.55),
+            gamma: dec!(0.03),
+            theta: dec!(-0.08),
+            vega: dec!(0.21),
+            rho: dec!(0.05),
+            implied_volatility: dec!(0.24),
+            underlying_price: None,
+        };
+
+        let fundamental = Fundamental::greeks(symbol, time, &greeks);
+        assert_eq!(fundamental.greeks_value(), Some(greeks));
+        assert_eq!(fundamental.funding_rate_value(), None);
+    }
+}
@@ -1,9 +1,11 @@
 use crate::helpers::converters::next_month;
+use crate::standardized_types::base_data::archived_data::ArchivedData;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::messages::data_server_messaging::{
-    DataServerRequest, DataServerResponse, FundForgeError,
+    DataServerRequest, DataServerResponse, FundForgeError, HISTORY_PARTS_IN_FLIGHT,
 };
+use crate::messages::stream_compression::StreamCompression;
 use crate::standardized_types::resolution::Resolution;
 use crate::standardized_types::subscriptions::{DataSubscription, Symbol};
 use crate::standardized_types::time_slices::TimeSlice;
@@ -13,19 +15,20 @@ use std::io::Read;
 use ahash::AHashMap;
 use flate2::bufread::GzDecoder;
 use futures::future::join_all;
-use tokio::sync::oneshot;
+use tokio::sync::mpsc;
 use crate::strategies::client_features::connection_types::ConnectionType;
 use crate::standardized_types::enums::{StrategyMode, PrimarySubscription};
 use crate::standardized_types::market_hours::TradingHours;
-use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::client_features::request_handler::{callback_request, send_request, StrategyRequest};
 use crate::strategies::client_features::server_connections::SETTINGS_MAP;
 use crate::strategies::consolidators::consolidator_enum::ConsolidatorEnum;
+use crate::strategies::statistics::backtest_cache::stable_hash;
 
 
-// Helper function to process a single compressed payload
+// Helper function to decompress a single compressed payload
 async fn process_compressed_payload(
     compressed_data: &[u8],
-) -> Result<Vec<BaseDataEnum>, FundForgeError> {
+) -> Result<Vec<u8>, FundForgeError> {
     // Pre-allocate decompressed data with estimated size (3:1 ratio)
     let mut decompressed = Vec::new();
     decompressed.try_reserve(compressed_data.len() * 98)
@@ -55,37 +58,95 @@ async fn process_compressed_payload(
             )),
         }
     }
+    Ok(decompressed)
+}
+
+/// Decompresses a `DataServerResponse::HistoricalDataPart` payload, which is one stored file in the requested compression.
+/// The part is read with `ArchivedData`, so only the data in the requested range is deserialized.
+async fn decode_part(compression: StreamCompression, payload: Vec<u8>) -> Result<Vec<u8>, FundForgeError> {
+    match compression {
+        StreamCompression::None => process_compressed_payload(&payload).await,
+        StreamCompression::Zstd => compression.decompress(payload),
+    }
+}
+
+fn add_to_time_slices(
+    combined_data: &mut BTreeMap<i64, TimeSlice>,
+    base_data: impl Iterator<Item = BaseDataEnum>,
+) -> Result<(), FundForgeError> {
+    for data in base_data {
+        let timestamp = data.time_closed_utc().timestamp_nanos_opt()
+            .ok_or_else(|| FundForgeError::ClientSideErrorDebug(
+                "Failed to convert timestamp to nanos".to_string()
+            ))?;
 
-    BaseDataEnum::from_array_bytes(&decompressed)
-        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse data: {}", e)))
+        combined_data
+            .entry(timestamp)
+            .or_insert_with(TimeSlice::new)
+            .add(data);
+    }
+    Ok(())
 }
 
-async fn process_payload(
-    payload: Vec<Vec<u8>>,
+/// Requests the stored data from a single server, each part is decoded as it arrives and then acknowledged so the server sends the next one,
+/// only the decoded data in range is kept, so long ranges never hold every compressed file in memory at once.
+async fn fetch_history(
+    connection_type: ConnectionType,
+    subscriptions: Vec<DataSubscription>,
     from_time: DateTime<Utc>,
-    to_time: DateTime<Utc>
+    to_time: DateTime<Utc>,
 ) -> Result<BTreeMap<i64, TimeSlice>, FundForgeError> {
-    let mut combined_data: BTreeMap<i64, TimeSlice> = BTreeMap::new();
+    let connection_type = match SETTINGS_MAP.contains_key(&connection_type) {
+        true => connection_type,
+        false => ConnectionType::Default
+    };
+    let compression = SETTINGS_MAP.get(&connection_type)
+        .map(|settings| settings.history_compression)
+        .unwrap_or_default();
 
-    for compressed_data in payload {
-        if let Ok(base_data_vec) = process_compressed_payload(&compressed_data).await {
-            for data in base_data_vec {
-                if data.time_closed_utc() >= from_time && data.time_closed_utc() <= to_time {
-                    let timestamp = data.time_closed_utc().timestamp_nanos_opt()
-                        .ok_or_else(|| FundForgeError::ClientSideErrorDebug(
-                            "Failed to convert timestamp to nanos".to_string()
-                        ))?;
+    let (tx, mut rx) = mpsc::channel(HISTORY_PARTS_IN_FLIGHT);
+    let request = StrategyRequest::Stream(
+        connection_type.clone(),
+        DataServerRequest::GetCompressedHistoricalData {
+            callback_id: 0,
+            subscriptions,
+            from_time: from_time.to_string(),
+            to_time: to_time.to_string(),
+            compression,
+        },
+        tx
+    );
+    send_request(request).await;
 
-                    combined_data
-                        .entry(timestamp)
-                        .or_insert_with(TimeSlice::new)
-                        .add(data);
+    let mut combined_data: BTreeMap<i64, TimeSlice> = BTreeMap::new();
+    let mut received: u64 = 0;
+    while let Some(response) = rx.recv().await {
+        match response {
+            DataServerResponse::HistoricalDataPart { callback_id, sequence, payload } => {
+                if sequence != received {
+                    return Err(FundForgeError::ClientSideErrorDebug(format!("History part {} received out of order, expected {}", sequence, received)));
+                }
+                received += 1;
+                match decode_part(compression, payload).await {
+                    Ok(bytes) => match ArchivedData::new(&bytes) {
+                        Ok(part) => add_to_time_slices(&mut combined_data, part.iter_range(from_time, to_time))?,
+                        Err(e) => tracing::error!("Failed to decode history part {}: {}", sequence, e),
+                    },
+                    Err(e) => tracing::error!("Failed to decode history part {}: {}", sequence, e),
+                }
+                send_request(StrategyRequest::OneWay(connection_type.clone(), DataServerRequest::HistoricalDataAck { callback_id, sequence })).await;
+            }
+            DataServerResponse::HistoricalDataComplete { parts, .. } => {
+                if parts != received {
+                    return Err(FundForgeError::ClientSideErrorDebug(format!("History incomplete, received {} of {} parts", received, parts)));
                 }
+                return Ok(combined_data);
             }
+            DataServerResponse::Error { error, .. } => return Err(error),
+            _ => return Err(FundForgeError::UnknownBlameError("Incorrect response received at callback".to_string()))
         }
     }
-
-    Ok(combined_data)
+    Err(FundForgeError::ClientSideErrorDebug("History stream closed before it was complete".to_string()))
 }
 
 pub async fn get_compressed_historical_data(
@@ -96,28 +157,7 @@ pub async fn get_compressed_historical_data(
     let connections = SETTINGS_MAP.clone();
     if connections.len() <= 2 {
         // Single connection case
-        let (tx, rx) = oneshot::channel();
-        let request = StrategyRequest::CallBack(
-            ConnectionType::Default,
-            DataServerRequest::GetCompressedHistoricalData {
-                callback_id: 0,
-                subscriptions,
-                from_time: from_time.to_string(),
-                to_time: to_time.to_string(),
-            },
-            tx
-        );
-        //println!("{:?}", request);
-        send_request(request).await;
-        let response = rx.await.expect(&"Failed to receive callback data");
-        //println!("Received data response for {} to {}", from_time, to_time);
-        match response {
-            DataServerResponse::CompressedHistoricalData { payload, .. } => {
-                process_payload(payload, from_time, to_time).await
-            },
-            DataServerResponse::Error { error, .. } => Err(error),
-            _ => Err(FundForgeError::UnknownBlameError("Incorrect response received at callback".to_string()))
-        }
+        fetch_history(ConnectionType::Default, subscriptions, from_time, to_time).await
     } else {
         // Multi-connection case
         let mut requests_map: AHashMap<ConnectionType, Vec<DataSubscription>> = AHashMap::new();
@@ -134,47 +174,19 @@ pub async fn get_compressed_historical_data(
 
         let futures: Vec<_> = requests_map
             .into_iter()
-            .map(|(connection_type, subs)| {
-                let (tx, rx) = oneshot::channel();
-                let request = StrategyRequest::CallBack(
-                    connection_type,
-                    DataServerRequest::GetCompressedHistoricalData {
-                        callback_id: 0,
-                        subscriptions: subs,
-                        from_time: from_time.to_string(),
-                        to_time: to_time.to_string(),
-                    },
-                    tx
-                );
-
-                async move {
-                    send_request(request).await;
-                    let response = rx.await.expect(&"Failed to receive callback data");
-
-                    match response {
-                        DataServerResponse::CompressedHistoricalData { payload, .. } => Ok(payload),
-                        DataServerResponse::Error { error, .. } => Err(error),
-                        _ => Err(FundForgeError::UnknownBlameError("Incorrect response received at callback".to_string()))
-                    }
-                }
-            })
+            .map(|(connection_type, subs)| fetch_history(connection_type, subs, from_time, to_time))
             .collect();
 
         let results = join_all(futures).await;
         let mut combined_data = BTreeMap::new();
         for result in results {
-            match result {
-                Ok(payload) => {
-                    let partial_data = process_payload(payload, from_time, to_time).await?;
-                    // Properly merge TimeSlices for same timestamps
-                    for (timestamp, time_slice) in partial_data {
-                        combined_data
-                            .entry(timestamp)
-                            .and_modify(|existing: &mut TimeSlice| existing.merge(time_slice.clone()))
-                            .or_insert(time_slice);
-                    }
-                },
-                Err(e) => return Err(e),
+            let partial_data = result?;
+            // Properly merge TimeSlices for same timestamps
+            for (timestamp, time_slice) in partial_data {
+                combined_data
+                    .entry(timestamp)
+                    .and_modify(|existing: &mut TimeSlice| existing.merge(time_slice.clone()))
+                    .or_insert(time_slice);
             }
         }
 
@@ -182,6 +194,42 @@ pub async fn get_compressed_historical_data(
     }
 }
 
+/// Requests the version of the stored data for the subscriptions in the range from each server the data would be loaded from.
+/// The versions are combined, so the result changes if the data on any of the servers changes.
+pub async fn get_data_version(
+    subscriptions: Vec<DataSubscription>,
+    from_time: DateTime<Utc>,
+    to_time: DateTime<Utc>,
+) -> Result<u64, FundForgeError> {
+    let connections = SETTINGS_MAP.clone();
+    let mut requests_map: BTreeMap<ConnectionType, Vec<DataSubscription>> = BTreeMap::new();
+    for sub in subscriptions {
+        let vendor_connection = ConnectionType::Vendor(sub.symbol.data_vendor);
+        let entry = if connections.len() > 2 && connections.contains_key(&vendor_connection) {
+            vendor_connection
+        } else {
+            ConnectionType::Default
+        };
+        requests_map.entry(entry).or_default().push(sub);
+    }
+
+    let mut versions = String::new();
+    for (connection_type, subs) in requests_map {
+        let request = DataServerRequest::DataVersion {
+            callback_id: 0,
+            subscriptions: subs,
+            from_time: from_time.to_string(),
+            to_time: to_time.to_string(),
+        };
+        match callback_request(connection_type.clone(), request).await? {
+            DataServerResponse::DataVersion { version, .. } => versions.push_str(&format!("{}:{};", connection_type, version)),
+            DataServerResponse::Error { error, .. } => return Err(error),
+            _ => return Err(FundForgeError::UnknownBlameError("Incorrect response received at callback".to_string())),
+        }
+    }
+    Ok(stable_hash(versions.as_bytes()))
+}
+
 pub fn get_lowest_resolution(
     all_symbol_subscriptions: &HashMap<Symbol, Vec<DataSubscription>>,
     symbol: &Symbol,
@@ -208,7 +256,13 @@ pub async fn range_history_data(
         panic!("From time cannot be greater than to time");
     }
     let sub_res_type = PrimarySubscription::new(subscription.resolution, subscription.base_data_type);
-    let resolutions = subscription.symbol.data_vendor.warm_up_resolutions(subscription.symbol.market_type).await.unwrap();
+    let resolutions = match subscription.symbol.data_vendor.warm_up_resolutions(subscription.symbol.market_type.clone()).await {
+        Ok(resolutions) => resolutions,
+        Err(e) => {
+            tracing::warn!("History: Unable to get warm up resolutions for {}: {}", subscription, e);
+            return BTreeMap::new();
+        }
+    };
     if resolutions.contains(&sub_res_type) {
         let data = match get_compressed_historical_data(vec![subscription.clone()], from_time, to_time).await {
             Ok(data) => {
@@ -238,7 +292,13 @@ pub async fn range_history_data(
         let resolution_ns = subscription.resolution.as_duration().num_nanoseconds().unwrap(); // Total nanoseconds in `resolution`
 
         let history_to_retain = duration_ns / resolution_ns;
-        let consolidator = ConsolidatorEnum::create_consolidator(subscription, false, market_hours).await;
+        let consolidator = match ConsolidatorEnum::create_consolidator(subscription.clone(), false, market_hours).await {
+            Ok(consolidator) => consolidator,
+            Err(e) => {
+                tracing::warn!("History: Unable to consolidate {}: {}", subscription, e);
+                return BTreeMap::new();
+            }
+        };
         let (_, window) = ConsolidatorEnum::warmup(consolidator, to_time, history_to_retain as i32, mode).await;
         let mut map:BTreeMap<DateTime<Utc>, BaseDataEnum> = BTreeMap::new();
         for base_data in window.history() {
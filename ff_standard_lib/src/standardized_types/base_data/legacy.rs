@@ -0,0 +1,272 @@
+//! The layouts of the unversioned storage format, files written before the format header was added, see `BaseDataEnum::FORMAT_VERSION`.
+//! These types are only used to read old files, the data is converted to the current types as it is loaded and rewritten in the current format the next time the file is saved.
+//!
+//! The structs are copies of the stored types as they were before the format was versioned and must never change,
+//! the types they contain, eg. `Symbol` and `Resolution`, may only gain unit variants at the end of their enums.
+
+use std::collections::BTreeMap;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::data_quality::DataQuality;
+use crate::standardized_types::base_data::fundamental::Fundamental;
+use crate::standardized_types::base_data::quote::Quote;
+use crate::standardized_types::base_data::quotebar::QuoteBar;
+use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+use crate::standardized_types::new_types::{Price, TimeString, Volume};
+use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::subscriptions::{CandleType, Symbol};
+
+/// A `Candle` without data quality flags.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub(crate) struct CandleV1 {
+    pub symbol: Symbol,
+    pub high: Price,
+    pub low: Price,
+    pub open: Price,
+    pub close: Price,
+    pub volume: Volume,
+    pub ask_volume: Volume,
+    pub bid_volume: Volume,
+    pub range: Price,
+    pub time: TimeString,
+    pub is_closed: bool,
+    pub resolution: Resolution,
+    pub candle_type: CandleType,
+}
+
+impl From<CandleV1> for Candle {
+    fn from(candle: CandleV1) -> Self {
+        Candle {
+            symbol: candle.symbol,
+            high: candle.high,
+            low: candle.low,
+            open: candle.open,
+            close: candle.close,
+            volume: candle.volume,
+            ask_volume: candle.ask_volume,
+            bid_volume: candle.bid_volume,
+            range: candle.range,
+            time: candle.time,
+            is_closed: candle.is_closed,
+            resolution: candle.resolution,
+            candle_type: candle.candle_type,
+            quality: DataQuality::default(),
+        }
+    }
+}
+
+/// A `QuoteBar` without data quality flags.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub(crate) struct QuoteBarV1 {
+    pub symbol: Symbol,
+    pub bid_high: Price,
+    pub bid_low: Price,
+    pub bid_open: Price,
+    pub bid_close: Price,
+    pub ask_high: Price,
+    pub ask_low: Price,
+    pub ask_open: Price,
+    pub ask_close: Price,
+    pub volume: Volume,
+    pub ask_volume: Volume,
+    pub bid_volume: Volume,
+    pub range: Price,
+    pub time: TimeString,
+    pub spread: Price,
+    pub is_closed: bool,
+    pub resolution: Resolution,
+    pub candle_type: CandleType,
+}
+
+impl From<QuoteBarV1> for QuoteBar {
+    fn from(bar: QuoteBarV1) -> Self {
+        QuoteBar {
+            symbol: bar.symbol,
+            bid_high: bar.bid_high,
+            bid_low: bar.bid_low,
+            bid_open: bar.bid_open,
+            bid_close: bar.bid_close,
+            ask_high: bar.ask_high,
+            ask_low: bar.ask_low,
+            ask_open: bar.ask_open,
+            ask_close: bar.ask_close,
+            volume: bar.volume,
+            ask_volume: bar.ask_volume,
+            bid_volume: bar.bid_volume,
+            range: bar.range,
+            time: bar.time,
+            spread: bar.spread,
+            is_closed: bar.is_closed,
+            resolution: bar.resolution,
+            candle_type: bar.candle_type,
+            quality: DataQuality::default(),
+        }
+    }
+}
+
+/// A `Tick` without data quality flags or trade conditions.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub(crate) struct TickV1 {
+    pub symbol: Symbol,
+    pub price: Price,
+    pub time: TimeString,
+    pub volume: Volume,
+    pub aggressor: Aggressor,
+}
+
+impl From<TickV1> for Tick {
+    fn from(tick: TickV1) -> Self {
+        Tick {
+            symbol: tick.symbol,
+            price: tick.price,
+            time: tick.time,
+            volume: tick.volume,
+            aggressor: tick.aggressor,
+            quality: DataQuality::default(),
+            conditions: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub(crate) struct QuoteV1 {
+    pub symbol: Symbol,
+    pub ask: Price,
+    pub bid: Price,
+    pub ask_volume: Volume,
+    pub bid_volume: Volume,
+    pub time: TimeString,
+}
+
+impl From<QuoteV1> for Quote {
+    fn from(quote: QuoteV1) -> Self {
+        Quote {
+            symbol: quote.symbol,
+            ask: quote.ask,
+            bid: quote.bid,
+            ask_volume: quote.ask_volume,
+            bid_volume: quote.bid_volume,
+            time: quote.time,
+        }
+    }
+}
+
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub(crate) struct FundamentalV1 {
+    pub symbol: Symbol,
+    pub time: TimeString,
+    pub resolution: Resolution,
+    pub values: BTreeMap<String, Decimal>,
+    pub value_string: Option<String>,
+    pub value_bytes: Option<Vec<u8>>,
+    pub name: String,
+}
+
+impl From<FundamentalV1> for Fundamental {
+    fn from(fundamental: FundamentalV1) -> Self {
+        Fundamental {
+            symbol: fundamental.symbol,
+            time: fundamental.time,
+            resolution: fundamental.resolution,
+            values: fundamental.values,
+            value_string: fundamental.value_string,
+            value_bytes: fundamental.value_bytes,
+            name: fundamental.name,
+        }
+    }
+}
+
+/// The unversioned `BaseDataEnum`, the variants must stay in this order.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub(crate) enum BaseDataEnumV1 {
+    Candle(CandleV1),
+    QuoteBar(QuoteBarV1),
+    Tick(TickV1),
+    Quote(QuoteV1),
+    Fundamental(FundamentalV1),
+}
+
+impl From<BaseDataEnumV1> for BaseDataEnum {
+    fn from(data: BaseDataEnumV1) -> Self {
+        match data {
+            BaseDataEnumV1::Candle(candle) => BaseDataEnum::Candle(candle.into()),
+            BaseDataEnumV1::QuoteBar(bar) => BaseDataEnum::QuoteBar(bar.into()),
+            BaseDataEnumV1::Tick(tick) => BaseDataEnum::Tick(tick.into()),
+            BaseDataEnumV1::Quote(quote) => BaseDataEnum::Quote(quote.into()),
+            BaseDataEnumV1::Fundamental(fundamental) => BaseDataEnum::Fundamental(fundamental.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use rkyv::AlignedVec;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::archived_data::ArchivedData;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+
+    /// A candle, quote bar, tick, quote and fundamental written by `BaseDataEnum::vec_to_bytes()` before the format was versioned.
+    fn unversioned_fixture() -> AlignedVec {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources").join("test_data").join("unversioned_base_data.bin");
+        let mut bytes = AlignedVec::new();
+        bytes.extend_from_slice(&fs::read(path).unwrap());
+        bytes
+    }
+
+    fn expected() -> Vec<BaseDataEnum> {
+        let symbol = Symbol::new("MNQ".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME));
+        let mut candle = Candle::new(symbol.clone(), dec!(20100.25), dec!(10), dec!(6), dec!(4), "2024-06-03 14:30:00 UTC".to_string(), Resolution::Minutes(1), CandleType::CandleStick);
+        candle.is_closed = true;
+        let mut bar = QuoteBar::new(symbol.clone(), dec!(20100), dec!(20100.25), dec!(10), dec!(6), dec!(4), "2024-06-03 14:31:00 UTC".to_string(), Resolution::Minutes(1), CandleType::CandleStick);
+        bar.is_closed = true;
+        let tick = Tick::new(symbol.clone(), dec!(20100.5), "2024-06-03 14:32:00 UTC".to_string(), dec!(2), Aggressor::Sell);
+        let quote = Quote::new(symbol.clone(), dec!(20100.75), dec!(20100.5), dec!(3), dec!(5), "2024-06-03 14:33:00 UTC".to_string());
+        let mut values = BTreeMap::new();
+        values.insert("open_interest".to_string(), dec!(250000));
+        let fundamental = Fundamental::new(symbol, "2024-06-03 14:34:00 UTC".to_string(), Resolution::Instant, values, None, None, "open_interest".to_string());
+        vec![BaseDataEnum::Candle(candle), BaseDataEnum::QuoteBar(bar), BaseDataEnum::Tick(tick), BaseDataEnum::Quote(quote), BaseDataEnum::Fundamental(fundamental)]
+    }
+
+    #[test]
+    fn test_unversioned_data_is_migrated() {
+        let bytes = unversioned_fixture();
+        assert_eq!(BaseDataEnum::format_version(&bytes), 1);
+        let migrated = BaseDataEnum::from_array_bytes(&bytes.to_vec()).unwrap();
+        assert_eq!(migrated, expected());
+        assert_eq!(ArchivedData::new(&bytes).unwrap().iter().collect::<Vec<_>>(), expected());
+        // data stored before the quality flags were added is not suspect
+        assert!(migrated.iter().all(|data| data.quality() == DataQuality::default()));
+
+        // rewriting the data uses the current format, which keeps the trade conditions
+        let mut data = expected();
+        if let BaseDataEnum::Tick(tick) = &mut data[2] {
+            tick.conditions = Some(vec!["I".to_string()]);
+        }
+        let bytes = BaseDataEnum::vec_to_bytes(data.clone());
+        assert_eq!(BaseDataEnum::format_version(&bytes), BaseDataEnum::FORMAT_VERSION);
+        let current = BaseDataEnum::from_array_bytes(&bytes).unwrap();
+        assert_eq!(current, data);
+        match &current[2] {
+            BaseDataEnum::Tick(tick) => assert!(tick.has_condition("I")),
+            _ => panic!("Expected a tick"),
+        }
+    }
+}
@@ -1,9 +1,15 @@
+pub mod archived_data;
 pub mod base_data_type;
 pub mod base_data_enum;
 pub mod candle;
+pub mod data_quality;
+pub mod footprint;
 pub mod fundamental;
 pub mod history;
+pub(crate) mod legacy;
 pub mod quotebar;
 pub mod quote;
+pub mod quote_codec;
 pub mod tick;
-pub mod traits;
\ No newline at end of file
+pub mod traits;
+pub mod warmup_batch;
\ No newline at end of file
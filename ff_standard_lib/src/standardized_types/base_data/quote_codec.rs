@@ -0,0 +1,289 @@
+//! The compact storage codec for quote files, see `BaseDataEnum::vec_to_storage_bytes()`.
+//!
+//! Quote history is stored as one `Quote` per record in the rkyv format, which repeats the symbol and a time string in every quote.
+//! The codec stores the symbol once, then the quotes in blocks of columns, each column delta encoded as zigzag varints,
+//! so the consecutive times and prices of a file become a few bytes per quote before the file is gzip compressed.
+//!
+//! # Layout
+//! - Header: `QUOTE_CODEC_MAGIC`, the codec version as a u32 and the number of quotes as a u64, 16 bytes little endian.
+//! - The symbol, a u32 length and the rkyv bytes.
+//! - Blocks of up to `BLOCK_LEN` quotes, each a u32 quote count followed by the columns:
+//!   the times as nanoseconds, then the ask, bid, ask volume and bid volume as a scale byte and the mantissas at that scale.
+//!   The first value of each column is stored in full and the rest as the difference from the previous value,
+//!   each price is followed by its own scale so the decimals are decoded exactly as they were saved.
+//!
+//! Files which the codec can not represent exactly, mixed data, more than one symbol or time strings which are not in the standard format,
+//! are stored in the rkyv format instead.
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use rkyv::AlignedVec;
+use rust_decimal::Decimal;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::quote::Quote;
+use crate::standardized_types::subscriptions::Symbol;
+
+/// Distinct from the rkyv format header, `BaseDataEnum::FORMAT_MAGIC`, so both formats can be told apart by the first bytes.
+const QUOTE_CODEC_MAGIC: [u8; 4] = [0xFF, b'F', b'F', b'Q'];
+const QUOTE_CODEC_VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+/// Each block has its own price scales, so an unusual price only widens the mantissas of its own block.
+const BLOCK_LEN: usize = 4096;
+
+/// Returns true if the bytes were written by the quote codec.
+pub fn is_compact_quotes(data: &[u8]) -> bool {
+    data.len() >= HEADER_LEN && data[..4] == QUOTE_CODEC_MAGIC
+}
+
+/// Encodes the data with the quote codec, `None` if the data is not only quotes of a single symbol or can not be decoded exactly.
+pub fn encode_quotes(data: &[BaseDataEnum]) -> Option<Vec<u8>> {
+    let quotes: Vec<&Quote> = data.iter()
+        .map(|data| match data {
+            BaseDataEnum::Quote(quote) => Some(quote),
+            _ => None,
+        })
+        .collect::<Option<Vec<&Quote>>>()?;
+    let symbol = &quotes.first()?.symbol;
+    if quotes.iter().any(|quote| &quote.symbol != symbol) {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + quotes.len() * 8);
+    bytes.extend_from_slice(&QUOTE_CODEC_MAGIC);
+    bytes.extend_from_slice(&QUOTE_CODEC_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(quotes.len() as u64).to_le_bytes());
+    let symbol_bytes = rkyv::to_bytes::<_, 256>(symbol).ok()?;
+    bytes.extend_from_slice(&(symbol_bytes.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&symbol_bytes);
+
+    for block in quotes.chunks(BLOCK_LEN) {
+        bytes.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        let mut times = Vec::with_capacity(block.len());
+        for quote in block {
+            times.push(encoded_time(&quote.time)? as i128);
+        }
+        write_deltas(&mut bytes, &times);
+        write_decimals(&mut bytes, block.iter().map(|quote| quote.ask))?;
+        write_decimals(&mut bytes, block.iter().map(|quote| quote.bid))?;
+        write_decimals(&mut bytes, block.iter().map(|quote| quote.ask_volume))?;
+        write_decimals(&mut bytes, block.iter().map(|quote| quote.bid_volume))?;
+    }
+    Some(bytes)
+}
+
+/// Decodes bytes written by `encode_quotes()`.
+pub fn decode_quotes(data: &[u8]) -> Result<Vec<BaseDataEnum>, FundForgeError> {
+    if !is_compact_quotes(data) {
+        return Err(codec_error("missing quote codec header"));
+    }
+    let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
+    if version != QUOTE_CODEC_VERSION {
+        return Err(codec_error(&format!("unsupported quote codec version {}, the data was written by a newer version", version)));
+    }
+    let count = u64::from_le_bytes(data[8..16].try_into().unwrap()) as usize;
+    let mut reader = Reader { data, position: HEADER_LEN };
+
+    let symbol_len = reader.read_u32()? as usize;
+    // The symbol archive must be aligned before it can be read.
+    let mut symbol_bytes = AlignedVec::with_capacity(symbol_len);
+    symbol_bytes.extend_from_slice(reader.read_bytes(symbol_len)?);
+    let symbol: Symbol = rkyv::from_bytes(&symbol_bytes)
+        .map_err(|e| codec_error(&format!("invalid symbol: {}", e)))?;
+
+    let mut quotes = Vec::with_capacity(count);
+    while quotes.len() < count {
+        let block_len = reader.read_u32()? as usize;
+        if block_len == 0 || quotes.len() + block_len > count {
+            return Err(codec_error("invalid block length"));
+        }
+        let times = reader.read_deltas(block_len)?;
+        let asks = reader.read_decimals(block_len)?;
+        let bids = reader.read_decimals(block_len)?;
+        let ask_volumes = reader.read_decimals(block_len)?;
+        let bid_volumes = reader.read_decimals(block_len)?;
+        for index in 0..block_len {
+            let nanos = i64::try_from(times[index]).map_err(|_| codec_error("invalid time"))?;
+            quotes.push(BaseDataEnum::Quote(Quote {
+                symbol: symbol.clone(),
+                ask: asks[index],
+                bid: bids[index],
+                ask_volume: ask_volumes[index],
+                bid_volume: bid_volumes[index],
+                time: DateTime::<Utc>::from_timestamp_nanos(nanos).to_string(),
+            }));
+        }
+    }
+    Ok(quotes)
+}
+
+fn codec_error(message: &str) -> FundForgeError {
+    FundForgeError::ClientSideErrorDebug(format!("Failed to decode compact quotes: {}", message))
+}
+
+/// The time as nanoseconds, `None` if decoding the nanoseconds would not give back the same string.
+fn encoded_time(time: &str) -> Option<i64> {
+    let nanos = DateTime::<Utc>::from_str(time).ok()?.timestamp_nanos_opt()?;
+    match DateTime::<Utc>::from_timestamp_nanos(nanos).to_string() == time {
+        true => Some(nanos),
+        false => None,
+    }
+}
+
+/// Writes the decimals at the widest scale of the column, then the scale of each decimal.
+fn write_decimals(bytes: &mut Vec<u8>, decimals: impl Iterator<Item = Decimal> + Clone) -> Option<()> {
+    let scale = decimals.clone().map(|decimal| decimal.scale()).max().unwrap_or(0);
+    let mut mantissas = vec![];
+    let mut scales = vec![];
+    for decimal in decimals {
+        let mut rescaled = decimal;
+        rescaled.rescale(scale);
+        // Rescaling reduces the scale instead of overflowing, which would lose digits.
+        if rescaled.scale() != scale {
+            return None;
+        }
+        mantissas.push(rescaled.mantissa());
+        scales.push(decimal.scale() as u8);
+    }
+    bytes.push(scale as u8);
+    write_deltas(bytes, &mantissas);
+    bytes.extend_from_slice(&scales);
+    Some(())
+}
+
+fn write_deltas(bytes: &mut Vec<u8>, values: &[i128]) {
+    let mut previous = 0;
+    for value in values {
+        write_varint(bytes, zigzag(value - previous));
+        previous = *value;
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn zigzag(value: i128) -> u128 {
+    ((value << 1) ^ (value >> 127)) as u128
+}
+
+fn unzigzag(value: u128) -> i128 {
+    ((value >> 1) as i128) ^ -((value & 1) as i128)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], FundForgeError> {
+        let end = self.position.checked_add(len).filter(|end| *end <= self.data.len())
+            .ok_or_else(|| codec_error("unexpected end of data"))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, FundForgeError> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn read_varint(&mut self) -> Result<u128, FundForgeError> {
+        let mut value: u128 = 0;
+        for shift in (0..128).step_by(7) {
+            let byte = self.read_bytes(1)?[0];
+            value |= ((byte & 0x7F) as u128) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+        Err(codec_error("invalid varint"))
+    }
+
+    fn read_deltas(&mut self, len: usize) -> Result<Vec<i128>, FundForgeError> {
+        let mut values = Vec::with_capacity(len);
+        let mut previous: i128 = 0;
+        for _ in 0..len {
+            previous = previous.checked_add(unzigzag(self.read_varint()?)).ok_or_else(|| codec_error("invalid delta"))?;
+            values.push(previous);
+        }
+        Ok(values)
+    }
+
+    fn read_decimals(&mut self, len: usize) -> Result<Vec<Decimal>, FundForgeError> {
+        let scale = self.read_bytes(1)?[0] as u32;
+        let mantissas = self.read_deltas(len)?;
+        let scales = self.read_bytes(len)?;
+        mantissas.into_iter().zip(scales)
+            .map(|(mantissa, decimal_scale)| {
+                let mut decimal = Decimal::try_from_i128_with_scale(mantissa, scale).map_err(|_| codec_error("invalid decimal"))?;
+                decimal.rescale(*decimal_scale as u32);
+                Ok(decimal)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::MarketType;
+
+    fn quote(symbol: &Symbol, time: DateTime<Utc>, bid: Decimal) -> BaseDataEnum {
+        BaseDataEnum::Quote(Quote {
+            symbol: symbol.clone(),
+            ask: bid + dec!(0.00012),
+            bid,
+            ask_volume: dec!(1000000),
+            bid_volume: dec!(2500000.5),
+            time: time.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_quote_codec_round_trip() {
+        let symbol = Symbol::new("EUR-USD".to_string(), DataVendor::Oanda, MarketType::Forex);
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap();
+        // More than a block, with sub second times, prices of different scales and a falling price.
+        let quotes: Vec<BaseDataEnum> = (0..(BLOCK_LEN as i64 + 100))
+            .map(|i| {
+                let bid = match i % 3 {
+                    0 => dec!(1.08450),
+                    1 => dec!(1.0846),
+                    _ => dec!(1.08391),
+                };
+                quote(&symbol, start + Duration::milliseconds(i * 250) + Duration::nanoseconds(i % 7), bid)
+            })
+            .collect();
+        let bytes = encode_quotes(&quotes).unwrap();
+        assert!(is_compact_quotes(&bytes));
+        let decoded = decode_quotes(&bytes).unwrap();
+        assert_eq!(decoded, quotes);
+        // The scale of each price is kept, so the prices print the same as they were saved.
+        match (&decoded[0], &quotes[0]) {
+            (BaseDataEnum::Quote(decoded), BaseDataEnum::Quote(quote)) => assert_eq!(decoded.bid.to_string(), quote.bid.to_string()),
+            _ => unreachable!(),
+        }
+        assert!(bytes.len() * 4 < BaseDataEnum::vec_to_bytes(quotes.clone()).len());
+        assert!(decode_quotes(&bytes[..bytes.len() - 1]).is_err());
+
+        let tick = BaseDataEnum::Tick(Tick::new(symbol.clone(), dec!(1.0845), start.to_string(), dec!(1), Aggressor::Buy));
+        assert!(encode_quotes(&[quotes[0].clone(), tick]).is_none());
+        let other = Symbol::new("AUD-CAD".to_string(), DataVendor::Oanda, MarketType::Forex);
+        assert!(encode_quotes(&[quotes[0].clone(), quote(&other, start, dec!(0.9))]).is_none());
+        let mut unusual_time = quotes[0].clone();
+        if let BaseDataEnum::Quote(quote) = &mut unusual_time {
+            quote.time = "2024-06-03T00:00:00Z".to_string();
+        }
+        assert!(encode_quotes(&[unusual_time]).is_none());
+        assert!(encode_quotes(&[]).is_none());
+    }
+}
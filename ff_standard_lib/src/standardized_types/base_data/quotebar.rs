@@ -13,6 +13,7 @@ use crate::standardized_types::datavendor_enum::DataVendor;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::new_types::{Price, TimeString, Volume};
 use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::base_data::data_quality::DataQuality;
 
 /// Represents a single quote bar in a financial chart, commonly used
 /// in the financial technical analysis of price patterns.
@@ -33,6 +34,7 @@ use crate::standardized_types::resolution::Resolution;
 /// - `time`: The opening time of the quote bar as a Unix timestamp.
 /// - `spread`: The difference between the highest ask price and the lowest bid price.
 /// - `is_closed`: Indicates whether the quote bar is closed.
+/// - `quality`: Quality flags reported by the vendor or set during consolidation, see `DataQuality`.
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
@@ -55,6 +57,7 @@ pub struct QuoteBar {
     pub is_closed: bool,
     pub resolution: Resolution,
     pub candle_type: CandleType,
+    pub quality: DataQuality,
 }
 
 
@@ -74,11 +77,11 @@ impl BaseData for QuoteBar {
     }
 
     fn time_closed_utc(&self) -> DateTime<Utc> {
-        self.time_utc() + self.resolution.as_duration()
+        self.resolution.close_time(self.time_utc())
     }
 
     fn time_closed_local(&self, time_zone: &Tz) -> DateTime<Tz> {
-        self.time_local(time_zone) + self.resolution.as_duration()
+        time_zone.from_utc_datetime(&self.time_closed_utc().naive_utc())
     }
 
     fn data_vendor(&self) -> DataVendor {
@@ -135,6 +138,7 @@ impl QuoteBar {
     /// - `time`: The opening time of the quote bar as a Unix timestamp.
     /// - `spread`: The difference between the highest ask price and the lowest bid price.
     /// - `is_closed`: Indicates whether the quote bar is closed.
+/// - `quality`: Quality flags reported by the vendor or set during consolidation, see `DataQuality`.
     /// - `data_vendor`: The data vendor that provided the quote bar.
     /// - `resolution`: The resolution of the quote bar.
     /// - `candle_type`: The type of candlestick.
@@ -168,6 +172,7 @@ impl QuoteBar {
             is_closed: false,
             resolution,
             candle_type,
+            quality: DataQuality::default(),
         }
     }
 
@@ -237,6 +242,7 @@ impl QuoteBar {
             is_closed: true,
             resolution,
             candle_type,
+            quality: DataQuality::default(),
         }
     }
 }
@@ -272,8 +278,8 @@ impl Debug for QuoteBar {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "QuoteBar {{ resolution: {}, symbol: {:?}, bid_high: {}, bid_low: {}, bid_open: {}, bid_close: {}, ask_high: {}, ask_low: {}, ask_open: {}, ask_close: {}, volume: {}, ask_volume: {}, bid_volume: {}, range: {}, spread: {}, time: {}, is_closed: {} }}",
-            self.resolution , self.symbol, self.bid_high, self.bid_low, self.bid_open, self.bid_close, self.ask_high, self.ask_low, self.ask_open, self.ask_close, self.volume, self.ask_volume, self.bid_volume ,self.range, self.spread, self.time, self.is_closed
+            "QuoteBar {{ resolution: {}, symbol: {:?}, bid_high: {}, bid_low: {}, bid_open: {}, bid_close: {}, ask_high: {}, ask_low: {}, ask_open: {}, ask_close: {}, volume: {}, ask_volume: {}, bid_volume: {}, range: {}, spread: {}, time: {}, is_closed: {}, quality: {} }}",
+            self.resolution , self.symbol, self.bid_high, self.bid_low, self.bid_open, self.bid_close, self.ask_high, self.ask_low, self.ask_open, self.ask_close, self.volume, self.ask_volume, self.bid_volume ,self.range, self.spread, self.time, self.is_closed, self.quality
         )
     }
 }
@@ -330,6 +336,7 @@ pub fn generate_5_day_quote_bar_data() -> Vec<QuoteBar> {
                 is_closed: true,                    // Assume quote bars are closed
                 resolution: Resolution::Hours(1),   // 1-hour resolution
                 candle_type: CandleType::CandleStick,  // Quote bar type
+                quality: DataQuality::default(),
             });
         }
     }
@@ -12,6 +12,7 @@ use crate::standardized_types::datavendor_enum::DataVendor;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::new_types::{Price, TimeString, Volume};
 use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::base_data::data_quality::DataQuality;
 
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq)]
 #[archive(compare(PartialEq), check_bytes)]
@@ -23,17 +24,26 @@ use crate::standardized_types::resolution::Resolution;
 /// 2. `price` - The price of the asset.
 /// 3. `time` - The time the price was recorded.
 /// 4. `volume` - The volume of the trade.
+/// 5. `aggressor` - The side which initiated the trade.
+/// 6. `quality` - Quality flags reported by the vendor, see `DataQuality`.
+/// 7. `conditions` - The trade condition codes reported by the vendor, `None` when the vendor does not report conditions.
 pub struct Tick {
     pub symbol: Symbol,
     pub price: Price,
     pub time: TimeString,
     pub volume: Volume,
-    pub aggressor: Aggressor
+    pub aggressor: Aggressor,
+    pub quality: DataQuality,
+    pub conditions: Option<Vec<TradeCondition>>,
 }
 
+/// A vendor specific trade condition code, for example an implied, spread leg or out of sequence trade flag.
+pub type TradeCondition = String;
+
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Display, Debug)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
+/// The side which crossed the spread to initiate the trade, `None` when the vendor does not know the side.
 pub enum Aggressor {
     Buy,
     Sell,
@@ -111,8 +121,15 @@ impl Tick {
             time,
             volume,
             aggressor,
+            quality: DataQuality::default(),
+            conditions: None,
         }
     }
+
+    /// Returns true if the vendor reported the condition code for this trade.
+    pub fn has_condition(&self, condition: &str) -> bool {
+        self.conditions.as_ref().is_some_and(|conditions| conditions.iter().any(|c| c == condition))
+    }
 }
 
 impl fmt::Display for Tick {
@@ -129,8 +146,8 @@ impl fmt::Debug for Tick {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Tick {{ symbol: {:?}, price: {}, volume: {}, aggressor: {}, time: {}}}",
-            self.symbol, self.price, self.volume, self.aggressor, self.time
+            "Tick {{ symbol: {:?}, price: {}, volume: {}, aggressor: {}, time: {}, quality: {}, conditions: {:?}}}",
+            self.symbol, self.price, self.volume, self.aggressor, self.time, self.quality, self.conditions
         )
     }
 }
@@ -0,0 +1,203 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use tokio::sync::Mutex;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::history::get_compressed_historical_data;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::standardized_types::time_slices::TimeSlice;
+
+/// How long downloaded warmup data is shared, long enough to cover a strategy subscribing all of its consolidators and indicators on start up.
+const BATCH_WINDOW: Duration = Duration::from_secs(60);
+
+/// The data downloaded for a base subscription, keyed by the closing time of each slice in nanoseconds.
+struct FetchedRange {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    data: BTreeMap<i64, TimeSlice>,
+    fetched_at: Instant,
+}
+
+lazy_static! {
+    static ref WARMUP_BATCHES: DashMap<DataSubscription, Arc<Mutex<Option<FetchedRange>>>> = DashMap::new();
+}
+
+/// Gets the data to warm up a consolidator or indicator, sharing the download between every consumer warming up from the same base subscription.
+///
+/// Warming up many consolidators and indicators on the same symbol and resolution would otherwise download the same data once per consumer.
+/// Requests for a base subscription which arrive while a download is in progress wait for it, and requests within a recent download are served from memory.
+/// A request outside a recent download only downloads the missing part of the range.
+pub(crate) async fn get_warmup_data(
+    subscription: &DataSubscription,
+    from_time: DateTime<Utc>,
+    to_time: DateTime<Utc>,
+) -> Result<BTreeMap<i64, TimeSlice>, FundForgeError> {
+    let batch = WARMUP_BATCHES.entry(subscription.clone()).or_default().clone();
+    let mut fetched = batch.lock().await;
+    let result = match fetched.as_mut() {
+        Some(range) if range.fetched_at.elapsed() < BATCH_WINDOW => {
+            for (from, to) in missing_ranges(range.from, range.to, from_time, to_time) {
+                let data = get_compressed_historical_data(vec![subscription.clone()], from, to).await?;
+                // The boundary slices are complete in the new download, so they replace the existing ones.
+                range.data.extend(data);
+            }
+            range.from = range.from.min(from_time);
+            range.to = range.to.max(to_time);
+            slice_range(&range.data, from_time, to_time)
+        }
+        _ => {
+            let data = get_compressed_historical_data(vec![subscription.clone()], from_time, to_time).await?;
+            let result = slice_range(&data, from_time, to_time);
+            *fetched = Some(FetchedRange { from: from_time, to: to_time, data, fetched_at: Instant::now() });
+            result
+        }
+    };
+    drop(fetched);
+    remove_expired();
+    Ok(result)
+}
+
+/// Downloads the warmup data of several base subscriptions in a single request and shares it through the batches, see `strategy.subscribe_many()`.
+///
+/// The request covers the earliest and latest time of all the ranges, so every subscription is fetched for the combined range.
+/// The consumers which warm up from the subscriptions within the batch window are then served from memory by `get_warmup_data()`.
+pub(crate) async fn prefetch_warmup_data(
+    requests: Vec<(DataSubscription, DateTime<Utc>, DateTime<Utc>)>,
+) -> Result<(), FundForgeError> {
+    let (from_time, to_time) = match (requests.iter().map(|(_, from, _)| *from).min(), requests.iter().map(|(_, _, to)| *to).max()) {
+        (Some(from_time), Some(to_time)) => (from_time, to_time),
+        _ => return Ok(()),
+    };
+    let mut subscriptions: Vec<DataSubscription> = vec![];
+    for (subscription, _, _) in requests {
+        if !subscriptions.contains(&subscription) {
+            subscriptions.push(subscription);
+        }
+    }
+    let data = get_compressed_historical_data(subscriptions.clone(), from_time, to_time).await?;
+    let mut split = split_by_subscription(data, &subscriptions);
+    let fetched_at = Instant::now();
+    for subscription in subscriptions {
+        let data = split.remove(&subscription).unwrap_or_default();
+        let batch = WARMUP_BATCHES.entry(subscription).or_default().clone();
+        *batch.lock().await = Some(FetchedRange { from: from_time, to: to_time, data, fetched_at });
+    }
+    Ok(())
+}
+
+/// Splits the slices of a combined download into the slices of each subscription, data for other subscriptions is dropped.
+fn split_by_subscription(
+    data: BTreeMap<i64, TimeSlice>,
+    subscriptions: &[DataSubscription],
+) -> HashMap<DataSubscription, BTreeMap<i64, TimeSlice>> {
+    let mut split: HashMap<DataSubscription, BTreeMap<i64, TimeSlice>> = subscriptions.iter()
+        .map(|subscription| (subscription.clone(), BTreeMap::new()))
+        .collect();
+    for (time, slice) in data {
+        for base_data in slice.iter() {
+            if let Some(subscription_data) = split.get_mut(&base_data.subscription()) {
+                subscription_data.entry(time).or_insert_with(TimeSlice::new).add(base_data.clone());
+            }
+        }
+    }
+    split
+}
+
+/// The parts of the requested range which are not within the fetched range.
+fn missing_ranges(
+    fetched_from: DateTime<Utc>,
+    fetched_to: DateTime<Utc>,
+    from_time: DateTime<Utc>,
+    to_time: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    if to_time < fetched_from || from_time > fetched_to {
+        // Download the gap as well, so the fetched range stays continuous.
+        return vec![(from_time.min(fetched_from), to_time.max(fetched_to))];
+    }
+    let mut missing = vec![];
+    if from_time < fetched_from {
+        missing.push((from_time, fetched_from));
+    }
+    if to_time > fetched_to {
+        missing.push((fetched_to, to_time));
+    }
+    missing
+}
+
+fn slice_range(data: &BTreeMap<i64, TimeSlice>, from_time: DateTime<Utc>, to_time: DateTime<Utc>) -> BTreeMap<i64, TimeSlice> {
+    let from = from_time.timestamp_nanos_opt().unwrap_or(i64::MIN);
+    let to = to_time.timestamp_nanos_opt().unwrap_or(i64::MAX);
+    data.range(from..=to).map(|(time, slice)| (*time, slice.clone())).collect()
+}
+
+/// Drops the data for batches which have not been downloaded within the batch window, batches in use are kept.
+fn remove_expired() {
+    WARMUP_BATCHES.retain(|_, batch| match batch.try_lock() {
+        Ok(fetched) => fetched.as_ref().is_some_and(|range| range.fetched_at.elapsed() < BATCH_WINDOW),
+        Err(_) => true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+    use crate::standardized_types::base_data::base_data_type::BaseDataType;
+    use crate::standardized_types::base_data::candle::Candle;
+    use crate::standardized_types::base_data::data_quality::DataQuality;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::MarketType;
+    use crate::standardized_types::resolution::Resolution;
+    use crate::standardized_types::subscriptions::{CandleType, Symbol};
+
+    #[test]
+    fn test_missing_ranges() {
+        let time = |day| Utc.with_ymd_and_hms(2024, 6, day, 0, 0, 0).unwrap();
+        assert!(missing_ranges(time(5), time(10), time(6), time(9)).is_empty());
+        assert_eq!(missing_ranges(time(5), time(10), time(3), time(9)), vec![(time(3), time(5))]);
+        assert_eq!(missing_ranges(time(5), time(10), time(3), time(12)), vec![(time(3), time(5)), (time(10), time(12))]);
+        assert_eq!(missing_ranges(time(5), time(10), time(12), time(14)), vec![(time(5), time(14))]);
+    }
+
+    #[test]
+    fn test_split_by_subscription() {
+        let time = Utc.with_ymd_and_hms(2024, 6, 5, 13, 0, 0).unwrap();
+        let candle = |symbol_name: &str, resolution: Resolution| BaseDataEnum::Candle(Candle {
+            symbol: Symbol::new(symbol_name.to_string(), DataVendor::Oanda, MarketType::Forex),
+            high: dec!(1),
+            low: dec!(1),
+            open: dec!(1),
+            close: dec!(1),
+            volume: dec!(1),
+            ask_volume: dec!(0),
+            bid_volume: dec!(0),
+            range: dec!(0),
+            time: time.to_string(),
+            is_closed: true,
+            resolution,
+            candle_type: CandleType::CandleStick,
+            quality: DataQuality::default(),
+        });
+        let slice: TimeSlice = vec![
+            candle("EUR-USD", Resolution::Minutes(1)),
+            candle("AUD-USD", Resolution::Minutes(1)),
+            candle("EUR-USD", Resolution::Minutes(5)),
+        ].into_iter().collect();
+        let nanos = time.timestamp_nanos_opt().unwrap();
+        let eur_usd = DataSubscription::new("EUR-USD".to_string(), DataVendor::Oanda, Resolution::Minutes(1), BaseDataType::Candles, MarketType::Forex);
+        let gbp_usd = DataSubscription::new("GBP-USD".to_string(), DataVendor::Oanda, Resolution::Minutes(1), BaseDataType::Candles, MarketType::Forex);
+
+        let split = split_by_subscription(BTreeMap::from([(nanos, slice)]), &[eur_usd.clone(), gbp_usd.clone()]);
+        let eur_usd_data: Vec<&BaseDataEnum> = split[&eur_usd][&nanos].iter().collect();
+        assert_eq!(eur_usd_data.len(), 1);
+        assert_eq!(eur_usd_data[0].resolution(), Resolution::Minutes(1));
+        // Subscriptions without data get an empty range, so the batch is still marked as fetched.
+        assert!(split[&gbp_usd].is_empty());
+    }
+}
@@ -6,6 +6,7 @@ use chrono_tz::{America, Tz};
 use chrono_tz::Tz::UTC;
 use crate::apis::rithmic::rithmic_systems::RithmicSystem;
 use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::orders::TriggerSource;
 
 #[derive(Serialize, Deserialize, Clone, Eq, Serialize_rkyv, Deserialize_rkyv,
     Archive, PartialEq, Debug, Hash, PartialOrd, Ord, Copy)]
@@ -27,6 +28,17 @@ impl Brokerage {
             Brokerage::Oanda => UTC,
         }
     }
+
+    /// The price the brokerage compares to the trigger price of stop orders when the order does not set a `TriggerSource`.
+    /// Backtests use the same trigger source, so a backtest triggers stops like the live account would.
+    pub fn native_trigger_source(&self) -> TriggerSource {
+        match self {
+            Brokerage::Test => TriggerSource::BidAsk,
+            Brokerage::Rithmic(_) => TriggerSource::Last,
+            Brokerage::Bitget => TriggerSource::Last,
+            Brokerage::Oanda => TriggerSource::BidAsk,
+        }
+    }
 }
 
 impl fmt::Display for Brokerage {
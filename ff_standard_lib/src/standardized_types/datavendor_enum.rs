@@ -16,6 +16,8 @@ pub enum DataVendor {
     Rithmic,
     Bitget,
     Oanda,
+    /// The ForexFactory economic calendar, `Fundamentals` only, the symbol names are currencies, eg. "USD".
+    ForexFactory,
 }
 
 impl fmt::Display for DataVendor {
@@ -25,6 +27,7 @@ impl fmt::Display for DataVendor {
             DataVendor::Rithmic => "Rithmic".to_string(),
             DataVendor::Bitget => "Bitget".to_string(),
             DataVendor::Oanda => "Oanda".to_string(),
+            DataVendor::ForexFactory => "ForexFactory".to_string(),
         };
         write!(f, "{}", s)
     }
@@ -42,6 +45,8 @@ impl FromStr for DataVendor {
             Ok(DataVendor::Bitget)
         }  else if s == "Oanda" {
             Ok(DataVendor::Oanda)
+        }  else if s == "ForexFactory" {
+            Ok(DataVendor::ForexFactory)
         }
         else {
             Err(FundForgeError::ClientSideErrorDebug(format!(
@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde_derive::{Deserialize, Serialize};
+use crate::product_maps::rithmic::maps::{extract_symbol_from_contract, get_futures_symbol_info};
+use crate::standardized_types::subscriptions::SymbolName;
+
+/// The expected market impact of an economic event, as rated by the calendar vendor.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventImpact {
+    /// Bank holidays and other non economic events.
+    Holiday,
+    Low,
+    Medium,
+    High,
+}
+
+impl EventImpact {
+    /// Parses the ForexFactory impact, unknown ratings are treated as `Holiday` so they never cause a blackout.
+    pub fn from_str(string: &str) -> Self {
+        match string {
+            "High" => EventImpact::High,
+            "Medium" => EventImpact::Medium,
+            "Low" => EventImpact::Low,
+            _ => EventImpact::Holiday,
+        }
+    }
+}
+
+impl fmt::Display for EventImpact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventImpact::Holiday => write!(f, "Holiday"),
+            EventImpact::Low => write!(f, "Low"),
+            EventImpact::Medium => write!(f, "Medium"),
+            EventImpact::High => write!(f, "High"),
+        }
+    }
+}
+
+/// A scheduled economic release, such as Non-Farm Payrolls or a central bank rate decision.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EconomicEvent {
+    /// The currency the release affects, eg. "USD".
+    pub currency: String,
+    pub title: String,
+    /// The scheduled release time.
+    #[serde(with = "timestamp")]
+    pub time: DateTime<Utc>,
+    pub impact: EventImpact,
+    pub forecast: Option<String>,
+    pub previous: Option<String>,
+}
+
+/// Serializes the release time as a unix timestamp in seconds.
+mod timestamp {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(time.timestamp())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        let seconds = i64::deserialize(deserializer)?;
+        DateTime::from_timestamp(seconds, 0).ok_or_else(|| serde::de::Error::custom(format!("Invalid timestamp: {}", seconds)))
+    }
+}
+
+impl fmt::Display for EconomicEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} ({})", self.time, self.currency, self.title, self.impact)
+    }
+}
+
+/// The scheduled economic events of the currencies a strategy subscribed to, built from the `Fundamental::economic_calendar()` data it receives.
+///
+/// The calendar data arrives ahead of the releases, so in backtests and live trading the strategy knows the upcoming events at the same point in time,
+/// see `FundForgeStrategy::in_news_blackout()`.
+#[derive(Default, Debug)]
+pub struct EconomicCalendar {
+    events: DashMap<String, BTreeMap<DateTime<Utc>, Vec<EconomicEvent>>>,
+}
+
+impl EconomicCalendar {
+    /// Adds the schedule of a currency, the events already known between the first and last event of the schedule are replaced so rescheduled releases move.
+    pub fn update(&self, currency: &str, schedule: Vec<EconomicEvent>) {
+        let (first, last) = match (schedule.iter().map(|event| event.time).min(), schedule.iter().map(|event| event.time).max()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return,
+        };
+        let mut events = self.events.entry(currency.to_string()).or_default();
+        events.retain(|time, _| *time < first || *time > last);
+        for event in schedule {
+            events.entry(event.time).or_default().push(event);
+        }
+    }
+
+    /// The events of the currencies scheduled between `from` and `to` inclusive, with at least the `min_impact`, in time order.
+    pub fn events_between(&self, currencies: &[String], from: DateTime<Utc>, to: DateTime<Utc>, min_impact: EventImpact) -> Vec<EconomicEvent> {
+        let mut found: Vec<EconomicEvent> = vec![];
+        if from > to {
+            return found;
+        }
+        for currency in currencies {
+            if let Some(events) = self.events.get(currency) {
+                for (_, events) in events.range(from..=to) {
+                    found.extend(events.iter().filter(|event| event.impact >= min_impact).cloned());
+                }
+            }
+        }
+        found.sort_by_key(|event| event.time);
+        found
+    }
+
+    /// True if an event of the currencies with at least the `min_impact` is scheduled from `before` ahead of the time until `after` behind it.
+    pub fn in_blackout(&self, currencies: &[String], time: DateTime<Utc>, before: Duration, after: Duration, min_impact: EventImpact) -> bool {
+        !self.events_between(currencies, time - after, time + before, min_impact).is_empty()
+    }
+
+    /// Removes the events scheduled before the time.
+    pub fn prune(&self, before: DateTime<Utc>) {
+        for mut events in self.events.iter_mut() {
+            events.retain(|time, _| *time >= before);
+        }
+    }
+}
+
+/// The currencies whose economic events affect the symbol.
+/// FX and crypto pairs use both currencies of the pair, eg. "EUR-USD" is EUR and USD, stable coins count as USD.
+/// Futures use the currencies of the product, eg. "MNQZ4" is USD and "6E" is EUR and USD. Unknown symbols have no currencies.
+pub fn symbol_currencies(symbol_name: &SymbolName) -> Vec<String> {
+    let mut currencies: Vec<String> = vec![];
+    let mut add = |currency: String| {
+        let currency = match currency.as_str() {
+            "USDT" | "USDC" => "USD".to_string(),
+            _ => currency,
+        };
+        if !currencies.contains(&currency) {
+            currencies.push(currency);
+        }
+    };
+    let parts: Vec<&str> = symbol_name.split(['-', '_', '/']).collect();
+    if parts.len() == 2 {
+        add(parts[0].to_uppercase());
+        add(parts[1].to_uppercase());
+    } else if let Ok(info) = get_futures_symbol_info(&extract_symbol_from_contract(symbol_name)) {
+        if let Some(base_currency) = info.base_currency {
+            add(base_currency.to_string());
+        }
+        add(info.pnl_currency.to_string());
+    }
+    currencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(currency: &str, title: &str, time: DateTime<Utc>, impact: EventImpact) -> EconomicEvent {
+        EconomicEvent {
+            currency: currency.to_string(),
+            title: title.to_string(),
+            time,
+            impact,
+            forecast: None,
+            previous: None,
+        }
+    }
+
+    #[test]
+    fn test_news_blackout() {
+        let nfp = Utc.with_ymd_and_hms(2024, 6, 7, 12, 30, 0).unwrap();
+        let calendar = EconomicCalendar::default();
+        calendar.update("USD", vec![
+            event("USD", "Non-Farm Employment Change", nfp, EventImpact::High),
+            event("USD", "Consumer Credit m/m", nfp + Duration::hours(7), EventImpact::Low),
+        ]);
+        let usd = vec!["USD".to_string()];
+        let eur = vec!["EUR".to_string()];
+
+        assert!(calendar.in_blackout(&usd, nfp - Duration::minutes(20), Duration::minutes(30), Duration::minutes(15), EventImpact::High));
+        assert!(calendar.in_blackout(&usd, nfp + Duration::minutes(10), Duration::minutes(30), Duration::minutes(15), EventImpact::High));
+        assert!(!calendar.in_blackout(&usd, nfp - Duration::minutes(45), Duration::minutes(30), Duration::minutes(15), EventImpact::High));
+        assert!(!calendar.in_blackout(&usd, nfp + Duration::minutes(20), Duration::minutes(30), Duration::minutes(15), EventImpact::High));
+        assert!(!calendar.in_blackout(&eur, nfp, Duration::minutes(30), Duration::minutes(15), EventImpact::High));
+        // The low impact event only counts when the minimum impact allows it.
+        let credit = nfp + Duration::hours(7);
+        assert!(!calendar.in_blackout(&usd, credit, Duration::minutes(30), Duration::minutes(15), EventImpact::High));
+        assert!(calendar.in_blackout(&usd, credit, Duration::minutes(30), Duration::minutes(15), EventImpact::Low));
+
+        // A new schedule of the week moves a rescheduled release and drops the cancelled one.
+        let pmi = nfp - Duration::days(4);
+        calendar.update("USD", vec![
+            event("USD", "ISM Manufacturing PMI", pmi, EventImpact::Medium),
+            event("USD", "Non-Farm Employment Change", nfp + Duration::days(1), EventImpact::High),
+        ]);
+        assert!(!calendar.in_blackout(&usd, nfp, Duration::minutes(30), Duration::minutes(15), EventImpact::High));
+        assert!(calendar.in_blackout(&usd, nfp + Duration::days(1), Duration::minutes(30), Duration::minutes(15), EventImpact::High));
+        assert_eq!(calendar.events_between(&usd, pmi, nfp + Duration::days(2), EventImpact::Holiday).len(), 2);
+
+        calendar.prune(nfp);
+        assert_eq!(calendar.events_between(&usd, pmi, nfp + Duration::days(2), EventImpact::Holiday).len(), 1);
+    }
+
+    #[test]
+    fn test_symbol_currencies() {
+        assert_eq!(symbol_currencies(&"EUR-USD".to_string()), vec!["EUR".to_string(), "USD".to_string()]);
+        assert_eq!(symbol_currencies(&"BTC-USDT".to_string()), vec!["BTC".to_string(), "USD".to_string()]);
+        assert_eq!(symbol_currencies(&"MNQ".to_string()), vec!["USD".to_string()]);
+        assert!(symbol_currencies(&"UNKNOWN".to_string()).is_empty());
+    }
+}
@@ -55,6 +55,8 @@ pub enum MarketType {
     Crypto,
     ETF,
     Fundamentals,
+    /// Option contracts, the contract is described by the `SymbolInfo::option_contract`, see `OptionContract`.
+    Options,
 }
 
 impl MarketType {
@@ -67,6 +69,7 @@ impl MarketType {
             MarketType::Crypto => value.round_dp(decimal_accuracy),
             MarketType::ETF => value.round_dp(decimal_accuracy),
             MarketType::Fundamentals => value.round_dp(decimal_accuracy),
+            MarketType::Options => round_to_tick_size(value, tick_size),
         }
     }
 }
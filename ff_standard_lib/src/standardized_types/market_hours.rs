@@ -1,5 +1,6 @@
-use chrono::{DateTime, Datelike, NaiveTime, Timelike, Utc, Weekday};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc, Weekday};
 use chrono_tz::Tz;
+use std::fmt;
 
 #[derive(Debug, Clone)]
 pub struct DaySession {
@@ -19,6 +20,17 @@ impl DaySession {
     }
 }
 
+impl fmt::Display for DaySession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.open, self.close) {
+            (Some(open), Some(close)) => write!(f, "{}-{}", open.format("%H:%M"), close.format("%H:%M")),
+            (Some(open), None) => write!(f, "{}-", open.format("%H:%M")),
+            (None, Some(close)) => write!(f, "-{}", close.format("%H:%M")),
+            (None, None) => write!(f, "Closed"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, )]
 pub struct TradingHours {
     pub timezone: Tz,
@@ -32,13 +44,24 @@ pub struct TradingHours {
     pub week_start: Weekday,
 }
 
-impl TradingHours {
-    pub fn is_market_open(&self, current_time: DateTime<Utc>) -> bool {
-        let market_time = current_time.with_timezone(&self.timezone);
-        let current_time_naive = market_time.time();
-        let current_weekday = market_time.weekday();
+/// The weekly sessions in the exchange time zone from the `week_start`, eg. "America/Chicago: Sun 17:00-, Mon -16:00, Tue 17:00-16:00, ...",
+/// a session which closes before it opens trades overnight.
+impl fmt::Display for TradingHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.timezone)?;
+        let mut weekday = self.week_start;
+        for day in 0..7 {
+            let separator = if day == 0 { "" } else { "," };
+            write!(f, "{} {} {}", separator, weekday, self.session(weekday))?;
+            weekday = weekday.succ();
+        }
+        Ok(())
+    }
+}
 
-        let current_session = match current_weekday {
+impl TradingHours {
+    pub fn session(&self, weekday: Weekday) -> &DaySession {
+        match weekday {
             Weekday::Sun => &self.sunday,
             Weekday::Mon => &self.monday,
             Weekday::Tue => &self.tuesday,
@@ -46,7 +69,81 @@ impl TradingHours {
             Weekday::Thu => &self.thursday,
             Weekday::Fri => &self.friday,
             Weekday::Sat => &self.saturday,
-        };
+        }
+    }
+
+    /// The most recent session close at or before the time, used to find where one trading session ends and the next begins.
+    /// Overnight sessions are not split at midnight, the CME session which opens Sunday 17:00 CT ends at the Monday 16:00 CT close.
+    pub fn last_close(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let market_date = time.with_timezone(&self.timezone).date_naive();
+        (0..=7).filter_map(|days_back| {
+            let date = market_date - Duration::days(days_back);
+            let close = self.session(date.weekday()).close?;
+            self.timezone.from_local_datetime(&date.and_time(close)).earliest()
+        })
+            .map(|close| close.to_utc())
+            .find(|close| *close <= time)
+    }
+
+    /// The first session close after the time.
+    pub fn next_close(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let market_date = time.with_timezone(&self.timezone).date_naive();
+        (0..=7).filter_map(|days_forward| {
+            let date = market_date + Duration::days(days_forward);
+            let close = self.session(date.weekday()).close?;
+            self.timezone.from_local_datetime(&date.and_time(close)).earliest()
+        })
+            .map(|close| close.to_utc())
+            .find(|close| *close > time)
+    }
+
+    /// The start of the trading week containing the time, the open of the most recent `week_start` day at or before the time.
+    /// If the `week_start` day has no open the week starts at midnight of that day.
+    pub fn week_open(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let market_date = time.with_timezone(&self.timezone).date_naive();
+        (0..=7).filter_map(|days_back| {
+            let date = market_date - Duration::days(days_back);
+            if date.weekday() != self.week_start {
+                return None;
+            }
+            let open = self.session(date.weekday()).open.unwrap_or(NaiveTime::MIN);
+            self.timezone.from_local_datetime(&date.and_time(open)).earliest()
+        })
+            .map(|open| open.to_utc())
+            .find(|open| *open <= time)
+    }
+
+    /// The trading date of the session the time belongs to, the exchange date of the next session close,
+    /// eg. the CME session which opens Sunday 17:00 CT trades for Monday. `None` if the hours have no closes.
+    pub fn trading_date(&self, time: DateTime<Utc>) -> Option<NaiveDate> {
+        self.next_close(time).map(|close| close.with_timezone(&self.timezone).date_naive())
+    }
+
+    /// The close of the session trading for the date, `None` if no session closes on the date.
+    pub fn session_close(&self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        let close = self.session(date.weekday()).close?;
+        self.timezone.from_local_datetime(&date.and_time(close)).earliest().map(|close| close.to_utc())
+    }
+
+    /// The open of the session trading for the date, the last open between the previous session close and the close,
+    /// which is on an earlier day for overnight sessions. If no open is scheduled between the closes the session opens at the previous close.
+    pub fn session_open(&self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        let close = self.session_close(date)?;
+        let previous_close = self.last_close(close - Duration::seconds(1));
+        (0..=7).filter_map(|days_back| {
+            let date = date - Duration::days(days_back);
+            let open = self.session(date.weekday()).open?;
+            self.timezone.from_local_datetime(&date.and_time(open)).earliest()
+        })
+            .map(|open| open.to_utc())
+            .find(|open| *open < close && previous_close.is_none_or(|previous_close| *open >= previous_close))
+            .or(previous_close)
+    }
+
+    pub fn is_market_open(&self, current_time: DateTime<Utc>) -> bool {
+        let market_time = current_time.with_timezone(&self.timezone);
+        let current_time_naive = market_time.time();
+        let current_session = self.session(market_time.weekday());
 
         current_session.is_trading_time(current_time_naive)
     }
@@ -54,17 +151,7 @@ impl TradingHours {
     pub fn seconds_until_close(&self, current_time: DateTime<Utc>) -> Option<i64> {
         let market_time = current_time.with_timezone(&self.timezone);
         let current_time_naive = market_time.time();
-        let current_weekday = market_time.weekday();
-
-        let current_session = match current_weekday {
-            Weekday::Sun => &self.sunday,
-            Weekday::Mon => &self.monday,
-            Weekday::Tue => &self.tuesday,
-            Weekday::Wed => &self.wednesday,
-            Weekday::Thu => &self.thursday,
-            Weekday::Fri => &self.friday,
-            Weekday::Sat => &self.saturday,
-        };
+        let current_session = self.session(market_time.weekday());
 
         match (current_session.open, current_session.close) {
             (Some(open), Some(close)) if close > open => {
@@ -104,11 +191,77 @@ impl TradingHours {
     }
 }
 
+/// The full day closures of the US equity markets for the year.
+/// Holidays on a Sunday are observed on the Monday after and holidays on a Saturday on the Friday before, except New Year's Day which is not observed on a Saturday.
+/// US futures exchanges halt early or trade a shortened session on most of these days, rather than closing for the whole day.
+pub fn us_market_holidays(year: i32) -> Vec<NaiveDate> {
+    let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    let mut holidays = vec![
+        nth_weekday(year, 1, Weekday::Mon, 3),
+        nth_weekday(year, 2, Weekday::Mon, 3),
+        easter_sunday(year) - Duration::days(2),
+        last_weekday(year, 5, Weekday::Mon),
+        observed(date(7, 4)),
+        nth_weekday(year, 9, Weekday::Mon, 1),
+        nth_weekday(year, 11, Weekday::Thu, 4),
+        observed(date(12, 25)),
+    ];
+    if date(1, 1).weekday() != Weekday::Sat {
+        holidays.push(observed(date(1, 1)));
+    }
+    if year >= 2022 {
+        holidays.push(observed(date(6, 19)));
+    }
+    holidays.sort();
+    holidays
+}
+
+pub fn is_us_market_holiday(date: NaiveDate) -> bool {
+    us_market_holidays(date.year()).contains(&date)
+}
+
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date - Duration::days(1),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date
+    }
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n).unwrap()
+}
+
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, 5)
+        .unwrap_or_else(|| nth_weekday(year, month, weekday, 4))
+}
+
+/// The anonymous Gregorian algorithm.
+fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{TimeZone};
     use chrono_tz::America::Chicago;
     use crate::product_maps::rithmic::maps::CME_HOURS;
+    use super::*;
 
     #[test]
     fn test_sunday_monday_cycle() {
@@ -210,4 +363,55 @@ mod tests {
         assert!(!trading_hours.is_market_open(test_time));
         assert_eq!(trading_hours.seconds_until_close(test_time), None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_session_boundaries() {
+        let trading_hours = CME_HOURS;
+
+        // The Sunday overnight session is not split at midnight, the last close is the Friday close.
+        let monday_night = Chicago.with_ymd_and_hms(2024, 1, 8, 1, 0, 0).unwrap().to_utc();
+        assert_eq!(trading_hours.last_close(monday_night), Some(Chicago.with_ymd_and_hms(2024, 1, 5, 16, 0, 0).unwrap().to_utc()));
+        let tuesday_morning = Chicago.with_ymd_and_hms(2024, 1, 9, 9, 0, 0).unwrap().to_utc();
+        assert_eq!(trading_hours.last_close(tuesday_morning), Some(Chicago.with_ymd_and_hms(2024, 1, 8, 16, 0, 0).unwrap().to_utc()));
+
+        let week_open = Chicago.with_ymd_and_hms(2024, 1, 7, 17, 0, 0).unwrap().to_utc();
+        assert_eq!(trading_hours.week_open(tuesday_morning), Some(week_open));
+        assert_eq!(trading_hours.week_open(week_open), Some(week_open));
+        let sunday_before_open = Chicago.with_ymd_and_hms(2024, 1, 7, 12, 0, 0).unwrap().to_utc();
+        assert_eq!(trading_hours.week_open(sunday_before_open), Some(Chicago.with_ymd_and_hms(2023, 12, 31, 17, 0, 0).unwrap().to_utc()));
+    }
+
+    #[test]
+    fn test_trading_date_sessions() {
+        let trading_hours = CME_HOURS;
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+
+        // The Sunday evening session trades for Monday, the Monday evening session for Tuesday.
+        let sunday_evening = Chicago.with_ymd_and_hms(2024, 1, 7, 18, 0, 0).unwrap().to_utc();
+        assert_eq!(trading_hours.trading_date(sunday_evening), Some(monday));
+        let monday_evening = Chicago.with_ymd_and_hms(2024, 1, 8, 17, 30, 0).unwrap().to_utc();
+        assert_eq!(trading_hours.trading_date(monday_evening), monday.succ_opt());
+
+        assert_eq!(trading_hours.session_open(monday), Some(Chicago.with_ymd_and_hms(2024, 1, 7, 17, 0, 0).unwrap().to_utc()));
+        assert_eq!(trading_hours.session_close(monday), Some(Chicago.with_ymd_and_hms(2024, 1, 8, 16, 0, 0).unwrap().to_utc()));
+        let friday = NaiveDate::from_ymd_opt(2024, 1, 12).unwrap();
+        assert_eq!(trading_hours.session_open(friday), Some(Chicago.with_ymd_and_hms(2024, 1, 11, 17, 0, 0).unwrap().to_utc()));
+        let saturday = NaiveDate::from_ymd_opt(2024, 1, 6).unwrap();
+        assert_eq!(trading_hours.session_open(saturday), None);
+    }
+
+    #[test]
+    fn test_us_market_holidays() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        assert_eq!(us_market_holidays(2024), vec![
+            date(2024, 1, 1), date(2024, 1, 15), date(2024, 2, 19), date(2024, 3, 29), date(2024, 5, 27),
+            date(2024, 6, 19), date(2024, 7, 4), date(2024, 9, 2), date(2024, 11, 28), date(2024, 12, 25),
+        ]);
+        // New Year's Day on a Saturday is not observed, Juneteenth and Christmas on a Sunday are observed on the Monday.
+        let holidays = us_market_holidays(2022);
+        assert!(!holidays.contains(&date(2021, 12, 31)) && !holidays.contains(&date(2022, 1, 1)));
+        assert!(is_us_market_holiday(date(2022, 6, 20)));
+        assert!(is_us_market_holiday(date(2022, 12, 26)));
+        assert!(is_us_market_holiday(date(2022, 4, 15)));
+    }
+}
@@ -14,3 +14,6 @@ pub mod position;
 pub mod books;
 pub mod accounts;
 pub mod market_hours;
+pub mod vendor_capabilities;
+pub mod economic_calendar;
+pub mod options;
@@ -0,0 +1,118 @@
+use chrono::NaiveDate;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::Display;
+use crate::helpers::converters::fund_forge_formatted_symbol_name;
+use crate::standardized_types::new_types::Price;
+use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
+
+/// The expiry format of `OptionContract.expiry`.
+const EXPIRY_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Serialize, Deserialize, Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialOrd, Eq, Ord, PartialEq, Copy, Debug, Display, Hash)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum OptionRight {
+    Call,
+    Put,
+}
+
+/// Describes an option contract, see `SymbolInfo::option_contract` and `DataVendor::options_chain()`.
+///
+/// Options are `MarketType::Options` symbols named with `OptionContract::symbol_name()`, so each contract has its own subscriptions and stored data,
+/// the contract details are not part of the `Symbol` so the stored data format is the same for every market.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq, Serialize, Deserialize, PartialOrd)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct OptionContract {
+    /// The symbol name of the underlying, eg. "ES" or "AAPL".
+    pub underlying: SymbolName,
+    /// The contract of the underlying for options on futures, eg. "ESZ4", `None` for options on equities.
+    pub underlying_code: Option<SymbolCode>,
+    pub strike: Price,
+    /// The expiry date as `YYYY-MM-DD`, parse with `expiry_date()`.
+    pub expiry: String,
+    pub right: OptionRight,
+    /// The units of the underlying per contract, eg. 100 for US equity options or 50 for options on ES.
+    pub multiplier: Decimal,
+}
+
+impl OptionContract {
+    pub fn new(underlying: SymbolName, strike: Price, expiry: NaiveDate, right: OptionRight, multiplier: Decimal) -> Self {
+        Self {
+            underlying,
+            underlying_code: None,
+            strike,
+            expiry: expiry.format(EXPIRY_FORMAT).to_string(),
+            right,
+            multiplier,
+        }
+    }
+
+    pub fn with_underlying_code(mut self, underlying_code: SymbolCode) -> Self {
+        self.underlying_code = Some(underlying_code);
+        self
+    }
+
+    pub fn expiry_date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.expiry, EXPIRY_FORMAT).ok()
+    }
+
+    /// The name of the contract symbol, the underlying contract or symbol, the expiry, the right and the strike, eg. "ESZ4-20241220-C-5000".
+    pub fn symbol_name(&self) -> SymbolName {
+        let underlying = self.underlying_code.as_ref().unwrap_or(&self.underlying);
+        let right = match self.right {
+            OptionRight::Call => "C",
+            OptionRight::Put => "P",
+        };
+        let expiry = self.expiry.replace('-', "");
+        fund_forge_formatted_symbol_name(&format!("{}-{}-{}-{}", underlying, expiry, right, self.strike.normalize()))
+    }
+
+    /// The value of exercising one unit of the underlying at the underlying price, zero when the option is out of the money.
+    pub fn intrinsic_value(&self, underlying_price: Price) -> Price {
+        let value = match self.right {
+            OptionRight::Call => underlying_price - self.strike,
+            OptionRight::Put => self.strike - underlying_price,
+        };
+        value.max(Decimal::ZERO)
+    }
+}
+
+/// The greeks of an option contract, sent by vendors as a `Fundamental`, see `Fundamental::greeks()`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Greeks {
+    pub delta: Decimal,
+    pub gamma: Decimal,
+    /// The change in value per day.
+    pub theta: Decimal,
+    /// The change in value for a 1 point change in the implied volatility.
+    pub vega: Decimal,
+    pub rho: Decimal,
+    /// The implied volatility as an annual fraction, eg. 0.2 for 20%.
+    pub implied_volatility: Decimal,
+    /// The price of the underlying the greeks were calculated at, if the vendor provides it.
+    pub underlying_price: Option<Price>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_option_contract() {
+        let expiry = NaiveDate::from_ymd_opt(2024, 12, 20).unwrap();
+        let call = OptionContract::new("ES".to_string(), dec!(5000.00), expiry, OptionRight::Call, dec!(50))
+            .with_underlying_code("ESZ4".to_string());
+        assert_eq!(call.symbol_name(), "ESZ4-20241220-C-5000");
+        assert_eq!(call.expiry_date(), Some(expiry));
+        assert_eq!(call.intrinsic_value(dec!(5025)), dec!(25));
+        assert_eq!(call.intrinsic_value(dec!(4975)), dec!(0));
+
+        let put = OptionContract::new("AAPL".to_string(), dec!(182.5), expiry, OptionRight::Put, dec!(100));
+        assert_eq!(put.symbol_name(), "AAPL-20241220-P-182.5");
+        assert_eq!(put.intrinsic_value(dec!(180)), dec!(2.5));
+    }
+}
@@ -11,6 +11,7 @@ use rust_decimal_macros::dec;
 use strum_macros::Display;
 use crate::standardized_types::broker_enum::Brokerage;
 use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::position::PositionId;
 
 #[derive(
     Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug, Serialize, Deserialize,
@@ -23,6 +24,8 @@ pub enum OrderRequest {
     Update{account: Account, order_id: OrderId, update: OrderUpdateType },
     CancelAll{account: Account},
     FlattenAllFor{account: Account},
+    /// Places the orders together, with all-or-none or best effort acceptance, see `FundForgeStrategy::submit_basket()`.
+    Basket{account: Account, basket_id: BasketId, orders: Vec<Order>, policy: BasketPolicy},
 }
 
 impl OrderRequest {
@@ -33,6 +36,7 @@ impl OrderRequest {
             OrderRequest::Update { account,.. } => account.brokerage.clone(),
             OrderRequest::CancelAll { account,.. } => account.brokerage.clone(),
             OrderRequest::FlattenAllFor { account,.. } => account.brokerage.clone(),
+            OrderRequest::Basket { account,.. } => account.brokerage.clone(),
         }
     }
 
@@ -43,6 +47,7 @@ impl OrderRequest {
             OrderRequest::Update { account,.. } => &account.account_id,
             OrderRequest::CancelAll { account,.. } => &account.account_id,
             OrderRequest::FlattenAllFor { account,.. } => &account.account_id,
+            OrderRequest::Basket { account,.. } => &account.account_id,
         }
     }
 
@@ -53,6 +58,7 @@ impl OrderRequest {
             OrderRequest::Update { account,.. } =>  &account.account_id,
             OrderRequest::CancelAll { account,.. } =>  &account.account_id,
             OrderRequest::FlattenAllFor { account,.. } =>  &account.account_id,
+            OrderRequest::Basket { account,.. } =>  &account.account_id,
         }
     }
 }
@@ -79,13 +85,34 @@ pub enum OrderError {
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
 pub enum TimeInForce {
+    /// Good till cancelled.
     GTC,
+    /// Immediate or cancel, fills what it can when it is placed and cancels the rest.
     IOC,
+    /// Fill or kill, fills the whole quantity when it is placed or is cancelled.
     FOK,
+    /// Expires at the close of the trading day.
     Day,
+    /// Good till date, expires at the Utc timestamp in seconds.
+    Time(i64),
+    /// Good till date, expires at the Utc timestamp in nanoseconds, see `TimeInForce::good_till()`.
+    TimeNanos(i64)
+}
+
+impl TimeInForce {
+    /// Good till date, the order expires at `time` to the nanosecond.
+    pub fn good_till(time: DateTime<Utc>) -> Self {
+        TimeInForce::TimeNanos(time.timestamp_nanos_opt().unwrap_or(i64::MAX))
+    }
 
-    //Utc Timestamp
-    Time(i64)
+    /// The time a good till date order expires, `None` for the other time in force or a timestamp out of range.
+    pub fn expiry_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            TimeInForce::Time(seconds) => DateTime::<Utc>::from_timestamp(*seconds, 0),
+            TimeInForce::TimeNanos(nanos) => Some(Utc.timestamp_nanos(*nanos)),
+            _ => None
+        }
+    }
 }
 
 #[derive(Archive, Clone, rkyv::Serialize, rkyv::Deserialize, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
@@ -105,6 +132,20 @@ pub enum ProtectiveOrder {
     },
 }
 
+/// The market price compared to the trigger price of stop, stop limit and market if touched orders.
+/// Brokers trigger differently, a stop on the last trade can be filled far from the quotes in a thin market, while a stop on the bid or ask can be triggered by a quote which never traded.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Eq, Debug, Serialize, Deserialize, Copy, Hash, Display)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum TriggerSource {
+    /// The price of the last trade.
+    Last,
+    /// The ask for buy orders and the bid for sell orders.
+    BidAsk,
+    /// The midpoint of the best bid and ask.
+    Midpoint,
+}
+
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug, Serialize, Deserialize)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
@@ -161,7 +202,13 @@ pub struct Order {
     pub state: OrderState,
     pub fees: Price,
     pub value: Price,
-    pub exchange: Option<String>
+    pub exchange: Option<String>,
+    /// The position the order closes in a hedging account, see `FundForgeStrategy::exit_position()`.
+    /// `None` fills against the open positions of the symbol as usual.
+    pub position_id: Option<PositionId>,
+    /// The price which triggers a stop, stop limit or market if touched order.
+    /// `None` uses the native trigger of the brokerage, see `Brokerage::native_trigger_source()`.
+    pub trigger_source: Option<TriggerSource>
 }
 
 impl Order {
@@ -169,6 +216,11 @@ impl Order {
         self.time_created_utc = time.to_string();
     }
 
+    /// The trigger source of the order, or the native trigger source of the brokerage if the order does not set one.
+    pub fn trigger_source(&self) -> TriggerSource {
+        self.trigger_source.unwrap_or_else(|| self.account.brokerage.native_trigger_source())
+    }
+
     pub fn limit_order(
         symbol_name: SymbolName,
         symbol_code: Option<SymbolCode>,
@@ -205,7 +257,9 @@ impl Order {
             state: OrderState::Created,
             fees: dec!(0.0),
             value: dec!(0.0),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -245,7 +299,9 @@ impl Order {
             fees: dec!(0.0),
             value: dec!(0.0),
             account: account.clone(),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -285,7 +341,9 @@ impl Order {
             fees: dec!(0.0),
             value: dec!(0.0),
             account: account.clone(),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -326,7 +384,9 @@ impl Order {
             fees: dec!(0.0),
             value: dec!(0.0),
             account: account.clone(),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -364,7 +424,9 @@ impl Order {
             fees: dec!(0.0),
             value: dec!(0.0),
             account: account.clone(),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -401,7 +463,9 @@ impl Order {
             fees: dec!(0.0),
             value: dec!(0.0),
             account: account.clone(),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -438,7 +502,9 @@ impl Order {
             fees: dec!(0.0),
             value: dec!(0.0),
             account: account.clone(),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -475,7 +541,9 @@ impl Order {
             fees: dec!(0.0),
             value: dec!(0.0),
             account: account.clone(),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -512,7 +580,9 @@ impl Order {
             state: OrderState::Created,
             fees: dec!(0.0),
             value: dec!(0.0),
-            exchange
+            exchange,
+            position_id: None,
+            trigger_source: None
         }
     }
 
@@ -532,6 +602,34 @@ impl Order {
         false
     }
 
+    /// Checks the update can be applied to the order, returning the reason for the `OrderUpdateEvent::OrderUpdateRejected` if it can not.
+    /// Only working orders can be modified and only the prices the order type has, the new quantity is the open quantity and must be more than 0.
+    pub fn validate_update(&self, update: &OrderUpdateType) -> Result<(), String> {
+        match &self.state {
+            OrderState::Filled => return Err("Order is already filled".to_string()),
+            OrderState::Cancelled => return Err("Order is already cancelled".to_string()),
+            OrderState::Rejected(_) => return Err("Order was rejected".to_string()),
+            _ => {}
+        }
+        if !self.can_cancel() {
+            return Err(format!("{:?} orders can not be modified", self.order_type));
+        }
+        match update {
+            OrderUpdateType::LimitPrice(_) if self.limit_price.is_none() => Err(format!("{:?} orders have no limit price", self.order_type)),
+            OrderUpdateType::TriggerPrice(_) if self.trigger_price.is_none() => Err(format!("{:?} orders have no trigger price", self.order_type)),
+            OrderUpdateType::Quantity(quantity) if *quantity <= dec!(0) => Err(format!("Invalid quantity: {}", quantity)),
+            _ => Ok(())
+        }
+    }
+
+    pub fn apply_update(&mut self, update: &OrderUpdateType) {
+        match update {
+            OrderUpdateType::LimitPrice(price) => self.limit_price = Some(*price),
+            OrderUpdateType::TriggerPrice(price) => self.trigger_price = Some(*price),
+            OrderUpdateType::Quantity(quantity) => self.quantity_open = *quantity,
+        }
+    }
+
     pub fn time_created_utc(&self) -> DateTime<Utc> {
         DateTime::from_str(&self.time_created_utc).unwrap()
     }
@@ -704,3 +802,195 @@ impl fmt::Display for OrderUpdateEvent {
         }
     }
 }
+
+pub type BasketId = String;
+
+/// How the orders of a basket are accepted, see `FundForgeStrategy::submit_basket()`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Eq, Debug, Serialize, Deserialize, Copy, Display)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum BasketPolicy {
+    /// If any order would be rejected none of the orders are placed.
+    /// Live brokerages have no atomic basket, orders placed before a rejection are cancelled, but any quantity they filled stays filled.
+    AllOrNone,
+    /// The orders which are accepted are placed and the rejected orders are dropped.
+    BestEffort,
+}
+
+/// An order of a basket, the id and creation time are set when the basket is submitted.
+/// Each constructor sets the prices its order type needs, the symbol code and exchange can be set with the builder functions.
+/// # Example
+/// ```rust,ignore
+/// // Buy the spread between NQ and ES
+/// let orders = vec![
+///     OrderSpec::market(account.clone(), "MNQ".to_string(), OrderSide::Buy, dec!(1), "Spread".to_string()),
+///     OrderSpec::market(account.clone(), "MES".to_string(), OrderSide::Sell, dec!(2), "Spread".to_string()),
+/// ];
+/// let (basket_id, order_ids) = strategy.submit_basket(orders, BasketPolicy::AllOrNone).await;
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct OrderSpec {
+    account: Account,
+    symbol_name: SymbolName,
+    symbol_code: Option<SymbolCode>,
+    exchange: Option<String>,
+    side: OrderSide,
+    quantity: Volume,
+    order_type: OrderType,
+    limit_price: Option<Price>,
+    trigger_price: Option<Price>,
+    time_in_force: TimeInForce,
+    trigger_source: Option<TriggerSource>,
+    tag: String,
+}
+
+impl OrderSpec {
+    fn new(account: Account, symbol_name: SymbolName, side: OrderSide, quantity: Volume, order_type: OrderType, time_in_force: TimeInForce, tag: String) -> Self {
+        Self {
+            account,
+            symbol_name,
+            symbol_code: None,
+            exchange: None,
+            side,
+            quantity,
+            order_type,
+            limit_price: None,
+            trigger_price: None,
+            time_in_force,
+            trigger_source: None,
+            tag,
+        }
+    }
+
+    pub fn market(account: Account, symbol_name: SymbolName, side: OrderSide, quantity: Volume, tag: String) -> Self {
+        Self::new(account, symbol_name, side, quantity, OrderType::Market, TimeInForce::FOK, tag)
+    }
+
+    pub fn limit(account: Account, symbol_name: SymbolName, side: OrderSide, quantity: Volume, limit_price: Price, tif: TimeInForce, tag: String) -> Self {
+        let mut spec = Self::new(account, symbol_name, side, quantity, OrderType::Limit, tif, tag);
+        spec.limit_price = Some(limit_price);
+        spec
+    }
+
+    pub fn stop(account: Account, symbol_name: SymbolName, side: OrderSide, quantity: Volume, trigger_price: Price, tif: TimeInForce, tag: String) -> Self {
+        let mut spec = Self::new(account, symbol_name, side, quantity, OrderType::StopMarket, tif, tag);
+        spec.trigger_price = Some(trigger_price);
+        spec
+    }
+
+    pub fn stop_limit(account: Account, symbol_name: SymbolName, side: OrderSide, quantity: Volume, limit_price: Price, trigger_price: Price, tif: TimeInForce, tag: String) -> Self {
+        let mut spec = Self::new(account, symbol_name, side, quantity, OrderType::StopLimit, tif, tag);
+        spec.limit_price = Some(limit_price);
+        spec.trigger_price = Some(trigger_price);
+        spec
+    }
+
+    pub fn market_if_touched(account: Account, symbol_name: SymbolName, side: OrderSide, quantity: Volume, trigger_price: Price, tif: TimeInForce, tag: String) -> Self {
+        let mut spec = Self::new(account, symbol_name, side, quantity, OrderType::MarketIfTouched, tif, tag);
+        spec.trigger_price = Some(trigger_price);
+        spec
+    }
+
+    pub fn with_symbol_code(mut self, symbol_code: SymbolCode) -> Self {
+        self.symbol_code = Some(symbol_code);
+        self
+    }
+
+    pub fn with_exchange(mut self, exchange: String) -> Self {
+        self.exchange = Some(exchange);
+        self
+    }
+
+    /// The price compared to the trigger price of stop, stop limit and market if touched orders, see `TriggerSource`.
+    pub fn with_trigger_source(mut self, trigger_source: TriggerSource) -> Self {
+        self.trigger_source = Some(trigger_source);
+        self
+    }
+
+    pub fn account(&self) -> &Account {
+        &self.account
+    }
+
+    pub fn to_order(&self, order_id: OrderId, time: DateTime<Utc>) -> Order {
+        let mut order = Order::market_order(self.symbol_name.clone(), self.symbol_code.clone(), &self.account, self.quantity, self.side, self.tag.clone(), order_id, time, self.exchange.clone());
+        order.order_type = self.order_type.clone();
+        order.limit_price = self.limit_price;
+        order.trigger_price = self.trigger_price;
+        order.time_in_force = self.time_in_force.clone();
+        order.trigger_source = self.trigger_source;
+        order
+    }
+}
+
+/// The outcome of a basket submitted with `FundForgeStrategy::submit_basket()`, the orders of the basket also send their own `OrderUpdateEvent`s.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum BasketEvent {
+    /// Every placed order of the basket filled.
+    Filled {account: Account, basket_id: BasketId, order_ids: Vec<OrderId>, time: String},
+
+    /// Some orders of a `BasketPolicy::BestEffort` basket were rejected, the `placed` orders are working.
+    PartiallyPlaced {account: Account, basket_id: BasketId, placed: Vec<OrderId>, rejected: Vec<OrderId>, time: String},
+
+    /// No orders of the basket are working, the reason is the first rejection of an order.
+    Rejected {account: Account, basket_id: BasketId, reason: String, time: String},
+}
+
+impl BasketEvent {
+    pub fn basket_id(&self) -> &BasketId {
+        match self {
+            BasketEvent::Filled { basket_id, .. } => basket_id,
+            BasketEvent::PartiallyPlaced { basket_id, .. } => basket_id,
+            BasketEvent::Rejected { basket_id, .. } => basket_id,
+        }
+    }
+
+    pub fn time_utc(&self) -> DateTime<Utc> {
+        match self {
+            BasketEvent::Filled { time, .. } => DateTime::from_str(time).unwrap(),
+            BasketEvent::PartiallyPlaced { time, .. } => DateTime::from_str(time).unwrap(),
+            BasketEvent::Rejected { time, .. } => DateTime::from_str(time).unwrap(),
+        }
+    }
+}
+
+impl fmt::Display for BasketEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BasketEvent::Filled { account, basket_id, order_ids, .. } => {
+                write!(f, "Basket Filled: Account: {}, Basket ID: {}, Orders: {}", account, basket_id, order_ids.len())
+            }
+            BasketEvent::PartiallyPlaced { account, basket_id, placed, rejected, .. } => {
+                write!(f, "Basket Partially Placed: Account: {}, Basket ID: {}, Placed: {}, Rejected: {}", account, basket_id, placed.len(), rejected.len())
+            }
+            BasketEvent::Rejected { account, basket_id, reason, .. } => {
+                write!(f, "Basket Rejected: Account: {}, Basket ID: {}. Reason: {}", account, basket_id, reason)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_update() {
+        let account = Account::new(Brokerage::Test, "test".to_string());
+        let mut order = Order::limit_order("MNQ".to_string(), None, &account, dec!(2), OrderSide::Buy, "test".to_string(), "1".to_string(), Utc::now(), dec!(18000), TimeInForce::GTC, None);
+        assert!(order.validate_update(&OrderUpdateType::TriggerPrice(dec!(18001))).is_err());
+        assert!(order.validate_update(&OrderUpdateType::Quantity(dec!(0))).is_err());
+
+        let update = OrderUpdateType::LimitPrice(dec!(17990));
+        assert!(order.validate_update(&update).is_ok());
+        order.apply_update(&update);
+        assert_eq!(order.limit_price, Some(dec!(17990)));
+
+        order.state = OrderState::Filled;
+        assert_eq!(order.validate_update(&update), Err("Order is already filled".to_string()));
+
+        let market = Order::market_order("MNQ".to_string(), None, &account, dec!(1), OrderSide::Buy, "test".to_string(), "2".to_string(), Utc::now(), None);
+        assert!(market.validate_update(&OrderUpdateType::Quantity(dec!(2))).is_err());
+    }
+}
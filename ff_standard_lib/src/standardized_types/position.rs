@@ -222,9 +222,14 @@ impl fmt::Display for PositionUpdateEvent {
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq, Serialize, Deserialize, PartialOrd,)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
+/// The entry lots closed first when a position is reduced, which decides the entry price each exit books its pnl against.
 pub enum PositionCalculationMode {
+    /// The oldest entry lot is closed first.
     FIFO,
+    /// The newest entry lot is closed first.
     LIFO,
+    /// The entry lots with the same tag as the exit order are closed first oldest first, then the remaining lots FIFO.
+    SpecificLot,
 }
 
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq, Serialize, Deserialize, PartialOrd,)]
@@ -233,12 +238,18 @@ pub enum PositionCalculationMode {
 pub struct EntryPrice {
     pub volume: Volume,
     pub price: Price,
-    pub order_id: OrderId
+    pub order_id: OrderId,
+    /// The tag of the entry order, used to select the lot with `PositionCalculationMode::SpecificLot`.
+    #[serde(default)]
+    pub tag: String,
+    /// The entry time of the lot, empty for lots saved before entry times were recorded.
+    #[serde(default)]
+    pub time: String,
 }
 
 impl EntryPrice {
-    pub fn new(volume: Volume, price: Price, order_id: OrderId) -> Self {
-        Self { volume, price, order_id }
+    pub fn new(volume: Volume, price: Price, order_id: OrderId, tag: String, time: DateTime<Utc>) -> Self {
+        Self { volume, price, order_id, tag, time: time.to_string() }
     }
 }
 
@@ -337,6 +348,7 @@ impl Position {
         time: DateTime<Utc>,
         position_calculation_mode: PositionCalculationMode
     ) -> Self {
+        let entry = EntryPrice::new(quantity, average_price, entry_order_id, tag.clone(), time);
         Self {
             symbol_name,
             symbol_code,
@@ -358,7 +370,7 @@ impl Position {
             exchange_rate_multiplier,
             tag,
             position_calculation_mode,
-            open_entry_prices: VecDeque::from(vec![EntryPrice::new(quantity, average_price, entry_order_id)]),
+            open_entry_prices: VecDeque::from(vec![entry]),
             completed_trades: vec![]
         }
     }
@@ -409,48 +421,69 @@ impl Position {
         }
     }
 
-    /// Returns the open pnl for the paper position
-    pub(crate) fn update_base_data(&mut self, base_data: &BaseDataEnum, account_currency: Currency) -> Decimal {
+    /// Returns the open pnl for the paper position.
+    /// Updates the position with the data of a symbol in time order, the recorded highs and lows include every data point,
+    /// and the open pnl is calculated once at the price of the last data point, so a slice of ticks costs one pnl calculation.
+    pub(crate) fn mark_to_market(&mut self, data: &[&BaseDataEnum], account_currency: Currency) -> Decimal {
         if self.is_closed {
             return dec!(0)
         }
 
-        // Extract market price, highest price, and lowest price from base data
-        let (market_price, highest_price, lowest_price) = match base_data {
-            BaseDataEnum::Candle(candle) => (candle.close, candle.high, candle.low),
-            BaseDataEnum::Tick(tick) => (tick.price, tick.price, tick.price),
-            BaseDataEnum::QuoteBar(bar) => match self.side {
-                PositionSide::Long => (bar.ask_close, bar.ask_high, bar.ask_low),
-                PositionSide::Short => (bar.bid_close, bar.bid_high, bar.bid_low),
-                _ => unreachable!("This shouldn't be possible"),
-            },
-            BaseDataEnum::Quote(quote) => match self.side {
-                PositionSide::Long => (quote.ask, quote.ask, quote.ask),
-                PositionSide::Short => (quote.bid, quote.bid, quote.bid),
-                _ => unreachable!("This shouldn't be possible"),
-            },
-            BaseDataEnum::Fundamental(_) => panic!("Fundamentals should not be here"),
-        };
+        let mut market_price = None;
+        for base_data in data {
+            // Extract market price, highest price, and lowest price from base data
+            let (price, highest_price, lowest_price) = match base_data {
+                BaseDataEnum::Candle(candle) => (candle.close, candle.high, candle.low),
+                BaseDataEnum::Tick(tick) => (tick.price, tick.price, tick.price),
+                BaseDataEnum::QuoteBar(bar) => match self.side {
+                    PositionSide::Long => (bar.ask_close, bar.ask_high, bar.ask_low),
+                    PositionSide::Short => (bar.bid_close, bar.bid_high, bar.bid_low),
+                    _ => unreachable!("This shouldn't be possible"),
+                },
+                BaseDataEnum::Quote(quote) => match self.side {
+                    PositionSide::Long => (quote.ask, quote.ask, quote.ask),
+                    PositionSide::Short => (quote.bid, quote.bid, quote.bid),
+                    _ => unreachable!("This shouldn't be possible"),
+                },
+                BaseDataEnum::Footprint(footprint) => (footprint.close, footprint.high, footprint.low),
+                BaseDataEnum::Fundamental(_) => panic!("Fundamentals should not be here"),
+            };
 
-        // Update highest and lowest recorded prices
-        self.highest_recoded_price = self.highest_recoded_price.max(highest_price);
-        self.lowest_recoded_price = self.lowest_recoded_price.min(lowest_price);
+            // Update highest and lowest recorded prices
+            self.highest_recoded_price = self.highest_recoded_price.max(highest_price);
+            self.lowest_recoded_price = self.lowest_recoded_price.min(lowest_price);
+            market_price = Some(price);
+        }
 
         // Calculate the open PnL
-        self.open_pnl = calculate_theoretical_pnl(
-            self.account.brokerage,
-            self.side,
-            self.average_price,
-            market_price,
-            self.quantity_open,
-            &self.symbol_info,
-            self.exchange_rate_multiplier,
-            account_currency
-        );
+        if let Some(market_price) = market_price {
+            self.open_pnl = calculate_theoretical_pnl(
+                self.account.brokerage,
+                self.side,
+                self.average_price,
+                market_price,
+                self.quantity_open,
+                &self.symbol_info,
+                self.exchange_rate_multiplier,
+                account_currency
+            );
+        }
 
         self.open_pnl.clone()
     }
 
+    /// The index of the entry lot the next exit closes, see `PositionCalculationMode`.
+    fn next_lot(&self, exit_tag: &str) -> Option<usize> {
+        if self.open_entry_prices.is_empty() {
+            return None;
+        }
+        match self.position_calculation_mode {
+            PositionCalculationMode::FIFO => Some(0),
+            PositionCalculationMode::LIFO => Some(self.open_entry_prices.len() - 1),
+            PositionCalculationMode::SpecificLot => Some(self.open_entry_prices.iter().position(|entry| entry.tag == exit_tag).unwrap_or(0)),
+        }
+    }
+
     /// Reduces position size a position event, this event will include a booked_pnl property
     pub(crate) async fn reduce_position_size(&mut self, market_price: Price, quantity: Volume, order_id: OrderId, account_currency: Currency, exchange_rate: Decimal, time: DateTime<Utc>, tag: String) -> PositionUpdateEvent {
         if quantity > self.quantity_open {
@@ -461,15 +494,10 @@ impl Position {
         let mut remaining_exit_quantity = quantity;
         let mut total_booked_pnl = dec!(0.0);
 
-        // Create a temporary queue/stack for processing to avoid borrow checker issues
-        let mut temp_entries = VecDeque::new();
-
-        // Keep processing entry prices until we've covered the full exit quantity
+        // Keep processing entry lots until we've covered the full exit quantity
         while remaining_exit_quantity > dec!(0.0) {
-            let entry = match self.position_calculation_mode {
-                PositionCalculationMode::FIFO => self.open_entry_prices.pop_front(),
-                PositionCalculationMode::LIFO => self.open_entry_prices.pop_back(),
-            }.expect("No entry prices available but position still open");
+            let index = self.next_lot(&tag).expect("No entry prices available but position still open");
+            let entry = self.open_entry_prices[index].clone();
 
             // Calculate how much we can exit from this entry price level
             let exit_quantity = remaining_exit_quantity.min(entry.volume);
@@ -508,7 +536,10 @@ impl Position {
                 entry_quantity: exit_quantity,
                 exit_price: market_price,
                 exit_quantity,
-                entry_time: self.open_time.clone(), // We could add entry_time to EntryPrice if we need exact times
+                entry_time: match entry.time.is_empty() {
+                    true => self.open_time.clone(),
+                    false => entry.time.clone(),
+                },
                 exit_time: time.to_string(),
                 profit: portion_booked_pnl,
                 exit_order_id: order_id.clone(),
@@ -516,29 +547,17 @@ impl Position {
                 commissions
             });
 
-            // If we didn't use all of this entry, we need to put back the remainder
-            let remaining_entry_volume = entry.volume - exit_quantity;
-            if remaining_entry_volume > dec!(0.0) {
-                let remaining_entry = EntryPrice::new(remaining_entry_volume, entry.price, entry.order_id.clone());
-                match self.position_calculation_mode {
-                    PositionCalculationMode::FIFO => temp_entries.push_back(remaining_entry),
-                    PositionCalculationMode::LIFO => temp_entries.push_front(remaining_entry),
-                }
+            // The remainder of a partly closed lot keeps its place
+            if exit_quantity < entry.volume {
+                self.open_entry_prices[index].volume -= exit_quantity;
+            } else {
+                self.open_entry_prices.remove(index);
             }
 
             total_booked_pnl += portion_booked_pnl;
             remaining_exit_quantity -= exit_quantity;
         }
 
-        // Put any remaining entries back in the correct order
-        // No need to reverse the order when restoring since we maintained it during temp storage
-        while let Some(entry) = temp_entries.pop_front() {
-            match self.position_calculation_mode {
-                PositionCalculationMode::FIFO => self.open_entry_prices.push_front(entry),
-                PositionCalculationMode::LIFO => self.open_entry_prices.push_back(entry),
-            }
-        }
-
         // Recalculate average entry price from remaining entries
         if !self.open_entry_prices.is_empty() {
             let (total_volume, total_weighted_price) = self.open_entry_prices.iter()
@@ -616,7 +635,7 @@ impl Position {
 
     pub(crate) async fn add_to_position(&mut self, mode: StrategyMode, is_simulating_pnl: bool, order_id: OrderId, account_currency: Currency, market_price: Price, quantity: Volume, time: DateTime<Utc>, tag: String) -> PositionUpdateEvent {
         // Add new entry price
-        self.open_entry_prices.push_back(EntryPrice::new(quantity, market_price, order_id));
+        self.open_entry_prices.push_back(EntryPrice::new(quantity, market_price, order_id, tag.clone(), time));
 
         // Recalculate average price from all entries
         let (total_volume, total_weighted_price) = self.open_entry_prices.iter()
@@ -883,6 +902,31 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_specific_lot_position_handling() {
+        let mut position = setup_basic_position();
+        position.position_calculation_mode = PositionCalculationMode::SpecificLot;
+        let entry_time = Utc::now();
+        position.add_to_position(StrategyMode::Backtest, true, "Test".to_string(), Currency::USD, dec!(17525.0), dec!(1.0), entry_time, "breakout".to_string()).await;
+        position.add_to_position(StrategyMode::Backtest, true, "Test".to_string(), Currency::USD, dec!(17550.0), dec!(1.0), Utc::now(), "pullback".to_string()).await;
+
+        // The exit tag closes the breakout lot, then the oldest lot once the breakout lot is closed.
+        position.reduce_position_size(dec!(17575.0), dec!(1.5), "Test".to_string(), Currency::USD, dec!(1.0), Utc::now(), "breakout".to_string()).await;
+        assert_eq!(position.completed_trades.len(), 2);
+        assert_eq!(position.completed_trades[0].entry_price, dec!(17525.0));
+        assert_eq!(position.completed_trades[0].entry_time, entry_time.to_string());
+        assert_eq!(position.completed_trades[1].entry_price, dec!(17500.0));
+        assert_eq!(position.completed_trades[1].exit_quantity, dec!(0.5));
+        assert_eq!(position.open_entry_prices.len(), 2);
+        assert_eq!(position.open_entry_prices[0].volume, dec!(0.5));
+        assert_eq!(position.open_entry_prices[1].price, dec!(17550.0));
+
+        // An exit tag without a lot falls back to FIFO.
+        position.reduce_position_size(dec!(17575.0), dec!(0.5), "Test".to_string(), Currency::USD, dec!(1.0), Utc::now(), "stop".to_string()).await;
+        assert_eq!(position.completed_trades[2].entry_price, dec!(17500.0));
+        assert_eq!(position.average_price, dec!(17550.0));
+    }
+
     #[tokio::test]
     async fn test_partial_position_reduction() {
         let mut position = setup_basic_position();
@@ -950,6 +994,29 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_mark_to_market_batch() {
+        let mut position = setup_basic_position();
+        let quote = |price: Decimal| BaseDataEnum::Quote(Quote {
+            symbol: Symbol::new("NQ".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME)),
+            bid: price,
+            ask_volume: dec!(100),
+            ask: price,
+            time: Utc::now().to_string(),
+            bid_volume: dec!(100),
+        });
+        let (high, low, last) = (quote(dec!(17600.0)), quote(dec!(17400.0)), quote(dec!(17550.0)));
+
+        // The extremes of the whole batch are recorded, the pnl is marked at the last price.
+        let open_pnl = position.mark_to_market(&[&high, &low, &last], Currency::USD);
+        assert_eq!(position.highest_recoded_price, dec!(17600.0));
+        assert_eq!(position.lowest_recoded_price, dec!(17400.0));
+
+        let mut single = setup_basic_position();
+        assert_eq!(single.mark_to_market(&[&last], Currency::USD), open_pnl);
+        assert_eq!(position.mark_to_market(&[], Currency::USD), open_pnl);
+    }
+
     #[tokio::test]
     async fn test_average_price_calculation() {
         let mut position = setup_basic_position();
@@ -1013,7 +1080,7 @@ mod test {
             bid_volume: dec!(100),
         });
 
-        let open_pnl = position.update_base_data(&mock_data, Currency::USD);
+        let open_pnl = position.mark_to_market(&[&mock_data], Currency::USD);
         assert!(open_pnl > dec!(0.0));
 
         // Close half position and verify booked PnL
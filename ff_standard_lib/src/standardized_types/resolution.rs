@@ -1,10 +1,11 @@
 use std::fmt;
 use std::str::FromStr;
-use chrono::Duration;
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
 use serde_derive::{Deserialize, Serialize};
 use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
 
 ///The resolution of a data point, which determines the time period it covers.
+/// `Day`, `Week` and `Month` are trading periods, their bars open with the first trading session of the period in the exchange time zone rather than at midnight UTC.
 
 #[derive(Serialize, Deserialize, Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialOrd, Eq, Ord, PartialEq, Copy, Debug, Hash)]
 #[archive(compare(PartialEq), check_bytes)]
@@ -16,6 +17,8 @@ pub enum Resolution {
     Minutes(u64),
     Hours(u64),
     Day,
+    Week,
+    Month,
 }
 
 impl Default for Resolution {
@@ -30,6 +33,15 @@ impl FromStr for Resolution {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let resolution_string = s.to_uppercase();
 
+        // The resolutions without a number, as written by `to_string()`
+        match resolution_string.as_str() {
+            "I" => return Ok(Resolution::Instant),
+            "D" => return Ok(Resolution::Day),
+            "W" => return Ok(Resolution::Week),
+            "MO" => return Ok(Resolution::Month),
+            _ => {}
+        }
+
         // Handle both "-" and "_" separators
         let parts: Vec<&str> = if resolution_string.contains('-') {
             resolution_string.split('-').collect()
@@ -46,6 +58,11 @@ impl FromStr for Resolution {
         let number = parts[0].parse::<u64>()
             .map_err(|_| format!("Invalid number in {}", s))?;
 
+        // "MO" is checked before the first character, which would parse months as minutes
+        if parts[1].trim().starts_with("MO") {
+            return Ok(Resolution::Month);
+        }
+
         // Trim any whitespace and get_requests first character
         match parts[1].trim().chars().next() {
             Some('I') => Ok(Resolution::Instant),
@@ -54,6 +71,7 @@ impl FromStr for Resolution {
             Some('M') => Ok(Resolution::Minutes(number)),
             Some('H') => Ok(Resolution::Hours(number)),
             Some('D') => Ok(Resolution::Day),
+            Some('W') => Ok(Resolution::Week),
             Some(c) => Err(format!("Invalid resolution type '{}' in {}", c, s)),
             None => Err(format!("Empty resolution type in {}", s)),
         }
@@ -72,6 +90,28 @@ impl Resolution {
             Resolution::Minutes(val) => Duration::minutes(*val as i64),
             Resolution::Hours(val) => Duration::hours(*val as i64),
             Resolution::Day => Duration::days(1),
+            Resolution::Week => Duration::days(7),
+            Resolution::Month => Duration::days(31),
+        }
+    }
+
+    /// Day, Week and Month bars cover the trading sessions of a period of the exchange calendar, see `TradingPeriodConsolidator`.
+    pub fn is_trading_period(&self) -> bool {
+        matches!(self, Resolution::Day | Resolution::Week | Resolution::Month)
+    }
+
+    /// The close time of a bar which opened at the time, used for the `time_closed_utc()` of bars.
+    /// Month bars close after the number of days in the month the bar trades, the month of the first week after the open,
+    /// because the first session of a month can open on the last day of the previous month.
+    pub fn close_time(&self, open: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Resolution::Month => {
+                let month = open + Duration::days(7);
+                let first_day = month.date_naive().with_day(1).unwrap();
+                let days = (first_day + Months::new(1) - first_day).num_days();
+                open + Duration::days(days)
+            }
+            _ => open + self.as_duration(),
         }
     }
 
@@ -83,6 +123,8 @@ impl Resolution {
             Resolution::Minutes(val) => val.clone(),
             Resolution::Hours(val) => val.clone(),
             Resolution::Day => 1,
+            Resolution::Week => 1,
+            Resolution::Month => 1,
         }
     }
 
@@ -111,6 +153,8 @@ impl Resolution {
             Resolution::Minutes(val) => format!("{}-M", val),
             Resolution::Hours(val) => format!("{}-H", val),
             Resolution::Day => "D".to_string(),
+            Resolution::Week => "W".to_string(),
+            Resolution::Month => "MO".to_string(),
         }
     }
 }
@@ -125,6 +169,29 @@ impl fmt::Display for Resolution {
             Resolution::Minutes(val) => write!(f, "{}-Minute", val),
             Resolution::Hours(val) => write!(f, "{}-Hour", val),
             Resolution::Day => write!(f, "Day"),
+            Resolution::Week => write!(f, "Week"),
+            Resolution::Month => write!(f, "Month"),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_trading_period_resolutions() {
+        for resolution in [Resolution::Day, Resolution::Week, Resolution::Month, Resolution::Minutes(5)] {
+            assert_eq!(Resolution::from_str(&resolution.to_string()).unwrap(), resolution);
+        }
+        assert_eq!(Resolution::from_str("1-MONTH").unwrap(), Resolution::Month);
+        assert_eq!(Resolution::from_str("1-Min").unwrap(), Resolution::Minutes(1));
+        assert_eq!(Resolution::from_str("1-W").unwrap(), Resolution::Week);
+        assert!(Resolution::Hours(4) < Resolution::Day && Resolution::Day < Resolution::Week && Resolution::Week < Resolution::Month);
+
+        // The CME October 2024 bar opens Monday 30 September 17:00 CT, and closes after the 31 days of October.
+        let open = Utc.with_ymd_and_hms(2024, 9, 30, 22, 0, 0).unwrap();
+        assert_eq!(Resolution::Month.close_time(open), Utc.with_ymd_and_hms(2024, 10, 31, 22, 0, 0).unwrap());
+        assert_eq!(Resolution::Week.close_time(open), Utc.with_ymd_and_hms(2024, 10, 7, 22, 0, 0).unwrap());
+    }
+}
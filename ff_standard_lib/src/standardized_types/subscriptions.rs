@@ -1,4 +1,5 @@
 use crate::helpers::converters::fund_forge_formatted_symbol_name;
+use crate::product_maps::symbol_aliases::canonical_symbol_name;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::enums::{MarketType, PrimarySubscription};
 use rkyv::ser::serializers::AllocSerializer;
@@ -8,6 +9,12 @@ use std::fmt;
 use std::fmt::{Debug, Display, Error, Formatter};
 use crate::standardized_types::datavendor_enum::DataVendor;
 use crate::standardized_types::resolution::Resolution;
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use crate::standardized_types::market_hours::{is_us_market_holiday, TradingHours};
+use crate::standardized_types::options::OptionContract;
+use crate::strategies::feed_quality::QualityAlert;
 
 pub type SymbolName = String;
 pub type SymbolCode = String;
@@ -35,6 +42,11 @@ impl Symbol {
             data_vendor,
         }
     }
+
+    /// The `MarketType::Options` symbol of the option contract, named with `OptionContract::symbol_name()`.
+    pub fn option(contract: &OptionContract, data_vendor: DataVendor) -> Self {
+        Symbol::new(contract.symbol_name(), data_vendor, MarketType::Options)
+    }
 }
 
 #[derive(Debug, Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Eq, PartialOrd, Ord, Hash, )]
@@ -76,7 +88,70 @@ impl Display for CandleType {
     }
 }
 
-#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, )]
+/// How the time based consolidators fill periods with no data, set per subscription with `DataSubscription::with_fill_policy()`.
+///
+/// `sessions_only` only fills periods which open while the market is open, `skip_holidays` does not fill on US market holidays.
+/// Both checks use the trading hours of the symbol, without trading hours only the holiday check is made, using the UTC date.
+#[derive(Clone, Copy, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum FillPolicy {
+    /// Periods with no data have no bar.
+    None,
+    /// Flat bars at the last close, flagged `DataQuality::ESTIMATED`.
+    LastClose { sessions_only: bool, skip_holidays: bool },
+    /// Bars with zero prices and volume flagged `DataQuality::EMPTY`, marking the gap without inventing a price.
+    /// Data arriving during an empty bar replaces it.
+    Empty { sessions_only: bool, skip_holidays: bool },
+}
+
+impl FillPolicy {
+    /// The policy used when the subscription has no policy of its own, from the `fill_forward` bool passed when subscribing.
+    pub fn from_fill_forward(fill_forward: bool) -> Self {
+        match fill_forward {
+            true => FillPolicy::LastClose { sessions_only: false, skip_holidays: false },
+            false => FillPolicy::None,
+        }
+    }
+
+    /// Returns true if a bar should be filled for the period opening at `open_time`.
+    pub fn should_fill(&self, open_time: DateTime<Utc>, hours: Option<&TradingHours>) -> bool {
+        let (sessions_only, skip_holidays) = match self {
+            FillPolicy::None => return false,
+            FillPolicy::LastClose { sessions_only, skip_holidays } => (*sessions_only, *skip_holidays),
+            FillPolicy::Empty { sessions_only, skip_holidays } => (*sessions_only, *skip_holidays),
+        };
+        if skip_holidays {
+            let date = match hours {
+                Some(hours) => open_time.with_timezone(&hours.timezone).date_naive(),
+                None => open_time.date_naive(),
+            };
+            if is_us_market_holiday(date) {
+                return false;
+            }
+        }
+        match (sessions_only, hours) {
+            (true, Some(hours)) => hours.is_market_open(open_time),
+            _ => true,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, FillPolicy::Empty { .. })
+    }
+}
+
+impl Display for FillPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FillPolicy::None => write!(f, "No Fill"),
+            FillPolicy::LastClose { sessions_only, skip_holidays } => write!(f, "Last Close (sessions only: {}, skip holidays: {})", sessions_only, skip_holidays),
+            FillPolicy::Empty { sessions_only, skip_holidays } => write!(f, "Empty (sessions only: {}, skip holidays: {})", sessions_only, skip_holidays),
+        }
+    }
+}
+
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
 /// Subscription struct is used to define requests for `Vec<BaseDataEnum>` data.
@@ -89,12 +164,51 @@ impl Display for CandleType {
 /// * `base_data_type` - The base data type of the subscription. [BaseDataType](crate::base_data::base_data_type::BaseDataType)
 /// * `market_type` - The market type of the subscription.
 /// * `candle_type` - The option CandleType for candle or quote bar data feeds
+/// * `fill_policy` - The optional FillPolicy for consolidated data, `None` uses the `fill_forward` bool passed when subscribing.
+/// * `fallback_vendors` - The vendors the server uses, in order, for the history the vendor of the symbol does not have and for live data while its feed is down.
+///
+/// The fill policy and fallback vendors only change how the data is sourced and consolidated, they are ignored when comparing or hashing subscriptions,
+/// so the subscription rebuilt from a data point with `data.subscription()` still matches the subscription it was consolidated for.
 pub struct DataSubscription {
     pub symbol: Symbol,
     pub resolution: Resolution,
     pub base_data_type: BaseDataType,
     pub market_type: MarketType,
     pub candle_type: Option<CandleType>,
+    pub fill_policy: Option<FillPolicy>,
+    pub fallback_vendors: Vec<DataVendor>,
+}
+
+impl DataSubscription {
+    fn key(&self) -> (&Symbol, &Resolution, &BaseDataType, &MarketType, &Option<CandleType>) {
+        (&self.symbol, &self.resolution, &self.base_data_type, &self.market_type, &self.candle_type)
+    }
+}
+
+impl PartialEq for DataSubscription {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for DataSubscription {}
+
+impl Hash for DataSubscription {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl PartialOrd for DataSubscription {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DataSubscription {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
 }
 
 impl Display for DataSubscription {
@@ -136,7 +250,7 @@ impl DataSubscription {
         base_data_type: BaseDataType,
         market_type: MarketType,
     ) -> Self {
-        let cleaned_symbol_name = fund_forge_formatted_symbol_name(&symbol_name);
+        let cleaned_symbol_name = canonical_symbol_name(&symbol_name);
         let symbol = Symbol::new(cleaned_symbol_name, data_vendor, market_type.clone());
         let candle_type = match base_data_type {
             BaseDataType::Candles => Some(CandleType::CandleStick),
@@ -159,9 +273,42 @@ impl DataSubscription {
             base_data_type,
             market_type,
             candle_type,
+            fill_policy: None,
+            fallback_vendors: vec![],
         }
     }
 
+    /// Sets how the consolidator fills periods with no data, overriding the `fill_forward` bool passed when subscribing.
+    pub fn with_fill_policy(mut self, fill_policy: FillPolicy) -> Self {
+        self.fill_policy = Some(fill_policy);
+        self
+    }
+
+    /// Sets the vendors the server falls back to, in order, when the vendor of the symbol is missing history or its live feed is down.
+    /// The fallback data is delivered as if it came from the vendor of the symbol, so the subscription is unchanged for the strategy,
+    /// the active live vendor is reported with `DataSubscriptionEvent::ActiveVendor`.
+    pub fn with_fallback_vendors(mut self, fallback_vendors: Vec<DataVendor>) -> Self {
+        self.fallback_vendors = fallback_vendors.into_iter()
+            .filter(|vendor| *vendor != self.symbol.data_vendor)
+            .collect();
+        self
+    }
+
+    /// The same subscription from another vendor, without fallback vendors.
+    pub fn for_vendor(&self, data_vendor: DataVendor) -> DataSubscription {
+        let mut subscription = self.clone();
+        subscription.symbol.data_vendor = data_vendor;
+        subscription.fallback_vendors = vec![];
+        subscription
+    }
+
+    /// The subscription from the vendor of the symbol followed by the subscription from each fallback vendor, in order.
+    pub fn vendor_subscriptions(&self) -> Vec<DataSubscription> {
+        let mut subscriptions = vec![self.for_vendor(self.symbol.data_vendor)];
+        subscriptions.extend(self.fallback_vendors.iter().map(|vendor| self.for_vendor(*vendor)));
+        subscriptions
+    }
+
     /// We can use this to consolidate custom candle types which are not represented by the base data types
     pub fn new_custom(
         symbol_name: String,
@@ -170,7 +317,7 @@ impl DataSubscription {
         market_type: MarketType,
         candle_type: CandleType,
     ) -> Self {
-        let cleaned_symbol_name = fund_forge_formatted_symbol_name(&symbol_name);
+        let cleaned_symbol_name = canonical_symbol_name(&symbol_name);
         let symbol = Symbol::new(cleaned_symbol_name, data_vendor, market_type.clone());
 
         DataSubscription {
@@ -179,6 +326,8 @@ impl DataSubscription {
             base_data_type: BaseDataType::Candles,
             market_type,
             candle_type: Some(candle_type),
+            fill_policy: None,
+            fallback_vendors: vec![],
         }
     }
 
@@ -192,6 +341,8 @@ impl DataSubscription {
             base_data_type: BaseDataType::Fundamentals,
             market_type: MarketType::Fundamentals,
             candle_type: None,
+            fill_policy: None,
+            fallback_vendors: vec![],
         }
     }
 
@@ -212,6 +363,8 @@ impl DataSubscription {
             base_data_type,
             market_type,
             candle_type,
+            fill_policy: None,
+            fallback_vendors: vec![],
         }
     }
 
@@ -265,6 +418,12 @@ pub enum DataSubscriptionEvent {
     Unsubscribed(DataSubscription),
     FailedToSubscribe(DataSubscription, String),
     FailedUnSubscribed(DataSubscription, String),
+    /// The vendor live data for the subscription is coming from, sent when a subscription with fallback vendors starts and whenever the server switches vendor.
+    ActiveVendor(DataSubscription, DataVendor, String),
+    /// A problem with the live data of the subscription, or the end of a problem, see `strategy.set_feed_quality()`.
+    QualityAlert(QualityAlert),
+    /// The consolidator of the subscription could not consolidate a data point, the data point is skipped and the subscription stays active.
+    ConsolidationError(DataSubscription, String),
 }
 impl fmt::Display for DataSubscriptionEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -277,6 +436,13 @@ impl fmt::Display for DataSubscriptionEvent {
             DataSubscriptionEvent::FailedUnSubscribed(sub, reason) => {
                 write!(f, "Failed to unsubscribe from: {}. Reason: {}", sub, reason)
             }
+            DataSubscriptionEvent::ActiveVendor(sub, vendor, reason) => {
+                write!(f, "Live data for: {} from: {}. Reason: {}", sub, vendor, reason)
+            }
+            DataSubscriptionEvent::QualityAlert(alert) => write!(f, "Quality alert: {}", alert),
+            DataSubscriptionEvent::ConsolidationError(sub, reason) => {
+                write!(f, "Failed to consolidate: {}. Reason: {}", sub, reason)
+            }
         }
     }
 }
@@ -326,6 +492,10 @@ pub fn filter_resolutions(
                 (Resolution::Minutes(_), Resolution::Hours(_)) => true,
                 (Resolution::Ticks(1), Resolution::Hours(_)) => true,
                 (Resolution::Seconds(_), Resolution::Hours(_)) => true,
+                // Weeks do not divide into months
+                (Resolution::Week, Resolution::Month) => false,
+                (Resolution::Ticks(num), data_resolution) if data_resolution.is_trading_period() => num == 1,
+                (resolution, data_resolution) if data_resolution.is_trading_period() => resolution != Resolution::Instant && resolution < data_resolution,
                 _ => false,
             }
         })
@@ -1,10 +1,27 @@
 use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
 use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal_macros::dec;
 use crate::standardized_types::accounts::Currency;
-use crate::standardized_types::new_types::Price;
-use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::subscriptions::{Symbol, SymbolCode, SymbolName};
 use serde_derive::{Deserialize, Serialize};
-use crate::standardized_types::enums::FuturesExchange;
+use crate::standardized_types::enums::{FuturesExchange, MarketType};
+use chrono::{DateTime, Utc};
+use crate::product_maps::oanda::maps::OANDA_SYMBOL_INFO;
+use crate::product_maps::rithmic::maps::{extract_symbol_from_contract, futures_code_to_name, get_futures_symbol_info, get_futures_trading_hours};
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::options::OptionContract;
+
+/// How order quantities which are not a multiple of the lot size of the symbol are handled, see `SymbolInfo::lot_size`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LotRounding {
+    /// Reject the order.
+    Reject,
+    /// Round the quantity down to the lot size, orders which round down to less than the minimum quantity are rejected.
+    Down,
+}
 
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq, Serialize, Deserialize, PartialOrd,)]
 #[archive(compare(PartialEq), check_bytes)]
@@ -15,7 +32,16 @@ pub struct SymbolInfo {
     pub pnl_currency: Currency,
     pub value_per_tick: Price,
     pub tick_size: Price,
-    pub decimal_accuracy: u32
+    pub decimal_accuracy: u32,
+    /// The contract details of `MarketType::Options` symbols, `None` for other markets.
+    #[serde(default)]
+    pub option_contract: Option<OptionContract>,
+    /// The step order quantities must be a multiple of, eg. 1 contract for futures, 1 unit for forex or 0.0001 coins for crypto, `None` if any quantity is accepted.
+    #[serde(default)]
+    pub lot_size: Option<Volume>,
+    /// The smallest order quantity, `None` if the smallest quantity is the lot size.
+    #[serde(default)]
+    pub min_quantity: Option<Volume>,
 }
 
 impl SymbolInfo {
@@ -34,8 +60,52 @@ impl SymbolInfo {
             value_per_tick,
             tick_size,
             decimal_accuracy,
+            option_contract: None,
+            lot_size: None,
+            min_quantity: None,
         }
     }
+
+    pub fn with_option_contract(mut self, option_contract: OptionContract) -> Self {
+        self.option_contract = Some(option_contract);
+        self
+    }
+
+    pub fn with_lot_size(mut self, lot_size: Volume, min_quantity: Option<Volume>) -> Self {
+        self.lot_size = Some(lot_size);
+        self.min_quantity = min_quantity;
+        self
+    }
+
+    /// Validates the order quantity against the lot size and minimum quantity, rounding it down to the lot size when the `LotRounding` allows.
+    /// Returns the reason the order should be rejected.
+    pub fn validate_quantity(&self, quantity: Volume, rounding: LotRounding) -> Result<Volume, String> {
+        let mut quantity = quantity;
+        if let Some(lot_size) = self.lot_size.filter(|lot_size| *lot_size > dec!(0)) {
+            let lots = quantity / lot_size;
+            if lots != lots.trunc() {
+                quantity = match rounding {
+                    LotRounding::Reject => return Err(format!("Quantity {} is not a multiple of the {} lot size {}", quantity, self.symbol_name, lot_size)),
+                    LotRounding::Down => (lots.trunc() * lot_size).normalize(),
+                };
+            }
+        }
+        let min_quantity = self.min_quantity.or(self.lot_size).unwrap_or(dec!(0));
+        if quantity <= dec!(0) || quantity < min_quantity {
+            return Err(format!("Quantity {} is below the {} minimum quantity {}", quantity, self.symbol_name, min_quantity));
+        }
+        Ok(quantity)
+    }
+
+    /// Rounds the price to the precision of the symbol, futures and options round to the tick size and other markets to the decimal accuracy.
+    pub fn round_price(&self, market_type: MarketType, price: Price) -> Price {
+        market_type.round_price(price, self.tick_size, self.decimal_accuracy)
+    }
+
+    /// Converts a vendor float price to a `Price` rounded to the precision of the symbol, `None` if the float is not a finite number.
+    pub fn price_from_f64(&self, market_type: MarketType, price: f64) -> Option<Price> {
+        Decimal::from_f64(price).map(|price| self.round_price(market_type, price))
+    }
 }
 
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq, Serialize, Deserialize, PartialOrd,)]
@@ -46,6 +116,31 @@ pub struct CommissionInfo {
     pub currency: Currency,
 }
 
+/// The overnight financing of a symbol, charged or paid on the value of positions held through the daily rollover.
+///
+/// The rates are annual fractions of the position value, negative rates are charged to the account and positive rates are paid to it,
+/// eg. a `long_rate` of -0.05 charges 5% per year on long positions.
+/// `days_charged` is the number of days of financing applied at the rollover of each weekday, starting with Monday.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq, Serialize, Deserialize)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct FinancingRate {
+    pub long_rate: Decimal,
+    pub short_rate: Decimal,
+    pub days_charged: [u32; 7],
+}
+
+impl FinancingRate {
+    /// Uses the forex convention of charging the weekend at the Wednesday rollover, CFDs usually charge the weekend on Friday.
+    pub fn new(long_rate: Decimal, short_rate: Decimal) -> Self {
+        Self {
+            long_rate,
+            short_rate,
+            days_charged: [1, 1, 3, 1, 1, 0, 0],
+        }
+    }
+}
+
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq, Serialize, Deserialize, PartialOrd)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
@@ -68,4 +163,177 @@ impl FrontMonthInfo {
             symbol_code
         }
     }
-}
\ No newline at end of file
+}
+
+/// An instrument returned by a symbol search, see `DataVendor::symbol_search()`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct SymbolSearchResult {
+    pub symbol: Symbol,
+    /// The tradable contract code for futures, eg. "MNQZ4", `None` for markets without contracts.
+    pub symbol_code: Option<SymbolCode>,
+    pub description: String,
+    pub exchange: Option<String>,
+    /// The expiration date in the vendor format, for contracts which expire.
+    pub expiration: Option<String>,
+}
+
+impl SymbolSearchResult {
+    /// True if the query is contained in the symbol name, contract code or description, ignoring case.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_uppercase();
+        self.symbol.name.to_uppercase().contains(&query)
+            || self.symbol_code.as_ref().is_some_and(|code| code.to_uppercase().contains(&query))
+            || self.description.to_uppercase().contains(&query)
+    }
+}
+
+/// The data stored on the server for one resolution and data type of a symbol, see `SymbolMetadata`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct AvailableHistory {
+    pub resolution: Resolution,
+    pub base_data_type: BaseDataType,
+    /// The utc time of the first stored data point as a string, parse with `earliest_utc()`.
+    pub earliest: String,
+    /// The utc time of the last stored data point as a string, parse with `latest_utc()`.
+    pub latest: String,
+}
+
+impl AvailableHistory {
+    pub fn new(resolution: Resolution, base_data_type: BaseDataType, earliest: DateTime<Utc>, latest: DateTime<Utc>) -> Self {
+        Self {
+            resolution,
+            base_data_type,
+            earliest: earliest.to_string(),
+            latest: latest.to_string(),
+        }
+    }
+
+    pub fn earliest_utc(&self) -> Option<DateTime<Utc>> {
+        self.earliest.parse().ok()
+    }
+
+    pub fn latest_utc(&self) -> Option<DateTime<Utc>> {
+        self.latest.parse().ok()
+    }
+}
+
+/// A symbol in the server catalog with the metadata known to the server and the history it has stored, see `DataVendor::symbol_catalog()`.
+/// Unlike `SymbolSearchResult` the catalog is built by the server from the product maps and its data folder, so it does not need the vendor api to be connected.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct SymbolMetadata {
+    pub symbol: Symbol,
+    pub description: String,
+    pub exchange: Option<String>,
+    pub tick_size: Option<Price>,
+    pub decimal_accuracy: Option<u32>,
+    pub value_per_tick: Option<Price>,
+    pub pnl_currency: Option<Currency>,
+    pub base_currency: Option<Currency>,
+    /// The weekly sessions in the exchange time zone, eg. "America/Chicago: Sun 17:00-, Mon -16:00, Tue 17:00-16:00, ...", `None` if unknown.
+    pub trading_hours: Option<String>,
+    /// The stored history of the symbol, empty if the server has no data for it.
+    pub history: Vec<AvailableHistory>,
+}
+
+impl SymbolMetadata {
+    /// The metadata of the symbol from the product maps, without history.
+    pub fn from_product_maps(symbol: Symbol) -> Self {
+        let mut metadata = SymbolMetadata {
+            description: symbol.name.clone(),
+            exchange: None,
+            tick_size: None,
+            decimal_accuracy: None,
+            value_per_tick: None,
+            pnl_currency: None,
+            base_currency: None,
+            trading_hours: None,
+            history: vec![],
+            symbol,
+        };
+        let info = match metadata.symbol.market_type {
+            MarketType::Futures(exchange) => {
+                let symbol_name = extract_symbol_from_contract(&metadata.symbol.name);
+                metadata.exchange = Some(exchange.to_string());
+                if let Some(name) = futures_code_to_name().get(symbol_name.as_str()) {
+                    metadata.description = name.to_string();
+                }
+                metadata.trading_hours = get_futures_trading_hours(&symbol_name).map(|hours| hours.to_string());
+                get_futures_symbol_info(&symbol_name).ok()
+            }
+            _ => OANDA_SYMBOL_INFO.get(&metadata.symbol.name).cloned(),
+        };
+        if let Some(info) = info {
+            metadata.tick_size = Some(info.tick_size);
+            metadata.decimal_accuracy = Some(info.decimal_accuracy);
+            metadata.value_per_tick = Some(info.value_per_tick);
+            metadata.pnl_currency = Some(info.pnl_currency);
+            metadata.base_currency = info.base_currency;
+        }
+        metadata
+    }
+
+    /// True if the query is contained in the symbol name or description ignoring case, an empty query matches every symbol.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_uppercase();
+        self.symbol.name.to_uppercase().contains(&query)
+            || self.description.to_uppercase().contains(&query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::MarketType;
+
+    #[test]
+    fn test_symbol_search_matches() {
+        let result = SymbolSearchResult {
+            symbol: Symbol::new("MNQ".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME)),
+            symbol_code: Some("MNQZ4".to_string()),
+            description: "Micro E-mini Nasdaq-100".to_string(),
+            exchange: Some("CME".to_string()),
+            expiration: Some("20241220".to_string()),
+        };
+        assert!(result.matches("mnq"));
+        assert!(result.matches("Z4"));
+        assert!(result.matches("nasdaq"));
+        assert!(!result.matches("ES"));
+    }
+
+    #[test]
+    fn test_price_from_f64_rounds_to_symbol_precision() {
+        let futures = SymbolInfo::new("MNQ".to_string(), None, Currency::USD, dec!(0.5), dec!(0.25), 2);
+        let cme = MarketType::Futures(FuturesExchange::CME);
+        assert_eq!(futures.price_from_f64(cme, 20100.2600000001), Some(dec!(20100.25)));
+        assert_eq!(futures.round_price(cme, dec!(20100.13)), dec!(20100.25));
+
+        let forex = SymbolInfo::new("EUR-USD".to_string(), Some(Currency::EUR), Currency::USD, dec!(0.0001), dec!(0.00001), 5);
+        assert_eq!(forex.price_from_f64(MarketType::Forex, 0.1 + 0.2), Some(dec!(0.3)));
+        assert_eq!(forex.price_from_f64(MarketType::Forex, 1.0834549999), Some(dec!(1.08345)));
+        assert_eq!(forex.price_from_f64(MarketType::Forex, f64::NAN), None);
+    }
+
+    #[test]
+    fn test_validate_quantity_against_lot_size() {
+        let futures = SymbolInfo::new("MNQ".to_string(), None, Currency::USD, dec!(0.5), dec!(0.25), 2).with_lot_size(dec!(1), None);
+        assert_eq!(futures.validate_quantity(dec!(3), LotRounding::Reject), Ok(dec!(3)));
+        assert!(futures.validate_quantity(dec!(2.5), LotRounding::Reject).is_err());
+        assert_eq!(futures.validate_quantity(dec!(2.5), LotRounding::Down), Ok(dec!(2)));
+        assert!(futures.validate_quantity(dec!(0.5), LotRounding::Down).is_err());
+
+        let crypto = SymbolInfo::new("BTC-USDT".to_string(), None, Currency::USD, dec!(0.1), dec!(0.1), 1).with_lot_size(dec!(0.0001), Some(dec!(0.001)));
+        assert_eq!(crypto.validate_quantity(dec!(0.01234), LotRounding::Down), Ok(dec!(0.0123)));
+        assert!(crypto.validate_quantity(dec!(0.0005), LotRounding::Reject).is_err());
+
+        let unrestricted = SymbolInfo::new("ETH-USD".to_string(), Some(Currency::ETH), Currency::USD, dec!(0.01), dec!(0.01), 2);
+        assert_eq!(unrestricted.validate_quantity(dec!(0.37), LotRounding::Reject), Ok(dec!(0.37)));
+        assert!(unrestricted.validate_quantity(dec!(0), LotRounding::Reject).is_err());
+    }
+}
@@ -1,12 +1,15 @@
 use std::collections::btree_map::Entry;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use chrono::{DateTime, Utc};
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::base_data::traits::BaseData;
 use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
 use crate::messages::data_server_messaging::FundForgeError;
 use crate::standardized_types::bytes_trait::Bytes;
-use crate::standardized_types::subscriptions::DataSubscription;
+use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::quote::Quote;
+use crate::standardized_types::subscriptions::{DataSubscription, SymbolName};
 
 /// A `TimeSlice` is a consolidated slice of data that is consolidated into a single point in time, you could have 1 hundred Ticks, 1 Quotebar and 3 Candles of different time frames,
 /// if they all occurred at the same time, not all the data types will be the same time
@@ -105,6 +108,41 @@ impl TimeSlice {
         })
     }
 
+    /// The data of the slice grouped by symbol name, each group in time order.
+    pub fn by_symbol(&self) -> BTreeMap<SymbolName, Vec<&BaseDataEnum>> {
+        let mut symbols: BTreeMap<SymbolName, Vec<&BaseDataEnum>> = BTreeMap::new();
+        for item in self.iter() {
+            // Only the first item of each symbol clones the name.
+            match symbols.get_mut(&item.symbol().name) {
+                Some(items) => items.push(item),
+                None => {
+                    symbols.insert(item.symbol().name.clone(), vec![item]);
+                }
+            }
+        }
+        symbols
+    }
+
+    /// The candles of the subscription in the slice, in time order.
+    pub fn candles_for(&self, subscription: &DataSubscription) -> Vec<&Candle> {
+        self.iter()
+            .filter_map(|item| match item {
+                BaseDataEnum::Candle(candle) if item.subscription() == *subscription => Some(candle),
+                _ => None
+            })
+            .collect()
+    }
+
+    /// The most recent quote for the symbol name in the slice.
+    pub fn latest_quote(&self, symbol_name: &SymbolName) -> Option<&Quote> {
+        self.iter()
+            .filter_map(|item| match item {
+                BaseDataEnum::Quote(quote) if &quote.symbol.name == symbol_name => Some(quote),
+                _ => None
+            })
+            .last()
+    }
+
     pub fn merge(&mut self, other: TimeSlice) {
         for (time, mut items) in other.data {
             match self.data.entry(time) {
@@ -120,3 +158,87 @@ impl TimeSlice {
         }
     }
 }
+
+/// The closed bars of several subscriptions which share a close time, see `strategy.aligned_bars()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlignedBars {
+    pub time_closed: DateTime<Utc>,
+    /// The bars in the order of the subscriptions.
+    pub bars: Vec<BaseDataEnum>,
+}
+
+/// Aligns the histories on the close time of the bars, returning up to `count` rows, newest first, where every history has a bar.
+/// Each history is newest first, the way the strategy keeps its history, close times missing from any history are skipped.
+pub(crate) fn align_bars(histories: &[Vec<BaseDataEnum>], count: usize) -> Vec<AlignedBars> {
+    let (first, others) = match histories.split_first() {
+        Some(split) => split,
+        None => return vec![],
+    };
+    let others: Vec<HashMap<DateTime<Utc>, &BaseDataEnum>> = others.iter()
+        .map(|history| history.iter().map(|bar| (bar.time_closed_utc(), bar)).collect())
+        .collect();
+    first.iter()
+        .filter_map(|bar| {
+            let time_closed = bar.time_closed_utc();
+            let mut bars = vec![bar.clone()];
+            for history in &others {
+                bars.push((*history.get(&time_closed)?).clone());
+            }
+            Some(AlignedBars { time_closed, bars })
+        })
+        .take(count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::data_quality::DataQuality;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::resolution::Resolution;
+    use crate::standardized_types::subscriptions::{CandleType, Symbol};
+
+    fn candle(symbol_name: &str, time: DateTime<Utc>) -> BaseDataEnum {
+        BaseDataEnum::Candle(Candle {
+            symbol: Symbol::new(symbol_name.to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME)),
+            high: dec!(100),
+            low: dec!(100),
+            open: dec!(100),
+            close: dec!(100),
+            volume: dec!(1),
+            ask_volume: dec!(0),
+            bid_volume: dec!(0),
+            range: dec!(0),
+            time: time.to_string(),
+            is_closed: true,
+            resolution: Resolution::Minutes(1),
+            candle_type: CandleType::CandleStick,
+            quality: DataQuality::default(),
+        })
+    }
+
+    #[test]
+    fn test_symbol_helpers_and_alignment() {
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 14, 0, 0).unwrap();
+        let minute = |minutes: i64| start + Duration::minutes(minutes);
+        let slice: TimeSlice = vec![candle("ES", minute(0)), candle("NQ", minute(0)), candle("ES", minute(1))].into_iter().collect();
+        let symbols = slice.by_symbol();
+        assert_eq!(symbols["ES"].len(), 2);
+        assert_eq!(symbols["NQ"].len(), 1);
+        let subscription = slice.first().unwrap().subscription();
+        assert_eq!(slice.candles_for(&subscription).len(), 2);
+        assert!(slice.latest_quote(&"ES".to_string()).is_none());
+
+        // NQ is missing the 14:02 bar, so the rows close at 14:03 and 14:01.
+        let es: Vec<BaseDataEnum> = (0..3).rev().map(|i| candle("ES", minute(i))).collect();
+        let nq: Vec<BaseDataEnum> = [2, 0].into_iter().map(|i| candle("NQ", minute(i))).collect();
+        let aligned = align_bars(&[es, nq], 5);
+        assert_eq!(aligned.len(), 2);
+        assert_eq!(aligned[0].time_closed, minute(3));
+        assert_eq!(aligned[0].bars[1].symbol().name, "NQ");
+        assert_eq!(aligned[1].time_closed, minute(1));
+    }
+}
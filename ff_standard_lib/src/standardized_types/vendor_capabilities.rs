@@ -0,0 +1,72 @@
+use rkyv::{Archive, Deserialize, Serialize};
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::datavendor_enum::DataVendor;
+use crate::standardized_types::orders::OrderType;
+
+/// What a `DataVendor` supports on the server, requested once per vendor with `DataVendor::capabilities()` and cached by the client.
+///
+/// Strategies can use the capabilities to choose the best available data instead of failing when a vendor lacks a data type,
+/// eg. subscribing to quote bars when the vendor has no tick data.
+///
+/// The data types depend on the `StrategyMode` of the requesting strategy, some vendors have data types live which are not available historically.
+#[derive(Clone, Serialize, Deserialize, Archive, Debug, PartialEq)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct VendorCapabilities {
+    pub data_vendor: DataVendor,
+    /// The base data types the vendor provides for the strategy mode.
+    pub base_data_types: Vec<BaseDataType>,
+    /// True if the vendor provides market depth beyond the best bid and offer.
+    pub market_depth: bool,
+    /// The earliest date historical data can be downloaded from the vendor as a UTC date string, `None` if it is unknown.
+    pub historical_from: Option<String>,
+    /// The max requests per second the vendor api allows, `None` if it is unknown or not limited.
+    pub max_requests_per_second: Option<u32>,
+    /// The order types which can be placed with the vendor's brokerage, empty if the vendor only provides data.
+    pub order_types: Vec<OrderType>,
+}
+
+impl VendorCapabilities {
+    pub fn supports(&self, base_data_type: BaseDataType) -> bool {
+        self.base_data_types.contains(&base_data_type)
+    }
+
+    pub fn supports_ticks(&self) -> bool {
+        self.supports(BaseDataType::Ticks)
+    }
+
+    pub fn supports_quotes(&self) -> bool {
+        self.supports(BaseDataType::Quotes)
+    }
+
+    pub fn supports_order_type(&self, order_type: &OrderType) -> bool {
+        self.order_types.contains(order_type)
+    }
+
+    /// The first of the `preferred` base data types the vendor supports, in order of preference.
+    pub fn best_base_data_type(&self, preferred: &[BaseDataType]) -> Option<BaseDataType> {
+        preferred.iter().find(|base_data_type| self.supports(**base_data_type)).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_base_data_type() {
+        let capabilities = VendorCapabilities {
+            data_vendor: DataVendor::Oanda,
+            base_data_types: vec![BaseDataType::QuoteBars],
+            market_depth: false,
+            historical_from: None,
+            max_requests_per_second: Some(120),
+            order_types: vec![OrderType::Market, OrderType::Limit],
+        };
+        assert!(!capabilities.supports_ticks());
+        assert_eq!(capabilities.best_base_data_type(&[BaseDataType::Ticks, BaseDataType::QuoteBars, BaseDataType::Candles]), Some(BaseDataType::QuoteBars));
+        assert_eq!(capabilities.best_base_data_type(&[BaseDataType::Ticks, BaseDataType::Candles]), None);
+        assert!(capabilities.supports_order_type(&OrderType::Limit));
+        assert!(!capabilities.supports_order_type(&OrderType::StopLimit));
+    }
+}
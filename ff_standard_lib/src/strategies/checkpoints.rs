@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use dashmap::DashMap;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::enums::StrategyMode;
+use crate::standardized_types::orders::{Order, OrderId};
+use crate::strategies::handlers::indicator_handler::IndicatorHandler;
+use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
+use crate::strategies::indicators::indicator_values::IndicatorValues;
+use crate::strategies::indicators::indicators_trait::IndicatorName;
+use crate::strategies::ledgers::ledger_service::LedgerService;
+use crate::strategies::ledgers::ledger_snapshot::LedgerSnapshot;
+
+/// The strategy side state saved by a checkpoint, so a live strategy which crashed can resume with its positions, orders and trade history instead of assuming it is flat.
+/// Checkpoints are written periodically with `strategy.enable_checkpoints()` and restored with `strategy.restore_checkpoint()`.
+///
+/// The ledgers and open orders are restored into the strategy. Indicators and consolidators are rebuilt by the warm up when they are subscribed,
+/// the indicator histories and open bars are saved so the strategy can compare them with the warmed up state after a restore.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct StrategySnapshot {
+    /// The UTC time the snapshot was taken.
+    pub time: String,
+    pub mode: StrategyMode,
+    pub ledgers: Vec<LedgerSnapshot>,
+    pub open_orders: Vec<Order>,
+    pub indicator_histories: Vec<(IndicatorName, Vec<IndicatorValues>)>,
+    /// The open candles and quote bars of the consolidated subscriptions.
+    pub open_bars: Vec<BaseDataEnum>,
+}
+
+impl StrategySnapshot {
+    /// Writes the snapshot to a temporary file and then renames it over the path, so a crash while writing never leaves a partial checkpoint.
+    pub fn save(&self, path: &Path) -> Result<(), FundForgeError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create checkpoint folder: {}", e)))?;
+        }
+        let bytes = rkyv::to_bytes::<_, 4096>(self)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to serialize checkpoint: {}", e)))?;
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, &bytes)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to write checkpoint {}: {}", temp_path.display(), e)))?;
+        fs::rename(&temp_path, path)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to replace checkpoint {}: {}", path.display(), e)))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FundForgeError> {
+        let bytes = fs::read(path)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to read checkpoint {}: {}", path.display(), e)))?;
+        rkyv::from_bytes::<StrategySnapshot>(&bytes)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to deserialize checkpoint {}: {}", path.display(), e)))
+    }
+}
+
+/// The handlers a snapshot is taken from, shared with the checkpoint task.
+#[derive(Clone)]
+pub(crate) struct CheckpointSources {
+    pub(crate) mode: StrategyMode,
+    pub(crate) accounts: Vec<Account>,
+    pub(crate) ledger_service: Arc<LedgerService>,
+    pub(crate) open_order_cache: Arc<DashMap<OrderId, Order>>,
+    pub(crate) indicator_handler: Arc<IndicatorHandler>,
+    pub(crate) subscription_handler: Arc<SubscriptionHandler>,
+}
+
+impl CheckpointSources {
+    pub(crate) fn snapshot(&self) -> StrategySnapshot {
+        StrategySnapshot {
+            time: Utc::now().to_string(),
+            mode: self.mode,
+            ledgers: self.accounts.iter().filter_map(|account| self.ledger_service.snapshot(account)).collect(),
+            open_orders: self.open_order_cache.iter().map(|order| order.value().clone()).collect(),
+            indicator_histories: self.indicator_handler.histories(),
+            open_bars: self.subscription_handler.open_data(),
+        }
+    }
+
+    /// Saves a snapshot to the path every interval until the strategy process exits.
+    pub(crate) fn run(self, path: PathBuf, interval: Duration) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.snapshot().save(&path) {
+                    tracing::error!("Checkpoint: {}", e);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::broker_enum::Brokerage;
+    use crate::standardized_types::enums::OrderSide;
+    use crate::strategies::statistics::streaming::StreamingStatistics;
+
+    #[test]
+    fn test_checkpoint_save_and_load() {
+        let account = Account::new(Brokerage::Test, "TestAccount".to_string());
+        let mut statistics = StreamingStatistics::default();
+        statistics.update(dec!(125.5), dec!(1.5));
+        let ledger = LedgerSnapshot {
+            account: account.clone(),
+            cash_value: dec!(100125.5),
+            cash_available: dec!(99000),
+            cash_used: dec!(1125.5),
+            portfolio_credit: dec!(0),
+            total_booked_pnl: dec!(125.5),
+            total_financing: dec!(0),
+            open_positions: vec![],
+            closed_positions: vec![],
+            symbol_closed_pnl: vec![("MNQZ4".to_string(), dec!(125.5))],
+            margin_positions: vec![],
+            statistics,
+        };
+        let order = Order::market_order("MNQ".to_string(), Some("MNQZ4".to_string()), &account, dec!(1), OrderSide::Buy, "Entry".to_string(), "1".to_string(), Utc::now(), None);
+        let snapshot = StrategySnapshot {
+            time: Utc::now().to_string(),
+            mode: StrategyMode::Live,
+            ledgers: vec![ledger],
+            open_orders: vec![order],
+            indicator_histories: vec![],
+            open_bars: vec![],
+        };
+
+        let path = std::env::temp_dir().join(format!("ff_checkpoint_{}", std::process::id())).join("strategy.checkpoint");
+        snapshot.save(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+        assert_eq!(StrategySnapshot::load(&path).unwrap(), snapshot);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,7 +1,6 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use tokio::sync::oneshot;
 use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
 use crate::product_maps::oanda::maps::{get_oanda_symbol_names, OANDA_SYMBOL_INFO, SYMBOL_DIVISORS};
 use crate::product_maps::rithmic::maps::{find_base_symbol, get_available_rithmic_symbol_names, get_rithmic_intraday_margin_in_usd, get_futures_symbol_info};
@@ -9,9 +8,9 @@ use crate::standardized_types::accounts::{AccountId, AccountInfo, Currency};
 use crate::standardized_types::broker_enum::Brokerage;
 use crate::standardized_types::new_types::{Price, Volume};
 use crate::standardized_types::subscriptions::SymbolName;
-use crate::standardized_types::symbol_info::{CommissionInfo, SymbolInfo};
+use crate::standardized_types::symbol_info::{CommissionInfo, FinancingRate, SymbolInfo};
 use crate::strategies::client_features::connection_types::ConnectionType;
-use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::client_features::request_handler::callback_request;
 
 impl Brokerage {
     pub async fn intraday_margin_required(&self, symbol_name: &SymbolName, quantity: Volume, price: Price, account_currency: Currency, base_currency: Option<Currency>, position_currency: Currency, conversion_rate: Decimal) -> Result<Option<Decimal>, FundForgeError> {
@@ -93,16 +92,13 @@ impl Brokerage {
                     brokerage: self.clone(),
                     symbol_name,
                 };
-                let (sender, receiver) = oneshot::channel();
-                let msg = StrategyRequest::CallBack(ConnectionType::Broker(self.clone()), request, sender);
-                send_request(msg).await;
-                match receiver.await {
+                match callback_request(ConnectionType::Broker(self.clone()), request).await {
                     Ok(response) => match response {
                         DataServerResponse::SymbolInfo { symbol_info, .. } => Ok(symbol_info),
                         DataServerResponse::Error { error, .. } => Err(error),
                         _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                     },
-                    Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+                    Err(e) => Err(e)
                 }
             }
         }
@@ -113,16 +109,13 @@ impl Brokerage {
             callback_id: 0,
             brokerage: self.clone(),
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Broker(self.clone()), request, sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Broker(self.clone()), request).await {
             Ok(response) => match response {
                 DataServerResponse::Accounts { accounts, .. } => Ok(accounts),
                 DataServerResponse::Error { error, .. } => Err(error),
                 _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
         }
     }
 
@@ -140,16 +133,13 @@ impl Brokerage {
                     brokerage: self.clone(),
                     time
                 };
-                let (sender, receiver) = oneshot::channel();
-                let msg = StrategyRequest::CallBack(ConnectionType::Broker(self.clone()), request, sender);
-                send_request(msg).await;
-                match receiver.await {
+                match callback_request(ConnectionType::Broker(self.clone()), request).await {
                     Ok(response) => match response {
                         DataServerResponse::SymbolNames { symbol_names, .. } => Ok(symbol_names),
                         DataServerResponse::Error { error, .. } => Err(error),
                         _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                     },
-                    Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+                    Err(e) => Err(e)
                 }
             }
         }
@@ -161,16 +151,30 @@ impl Brokerage {
             brokerage: self.clone(),
             symbol_name,
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Broker(self.clone()), request, sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Broker(self.clone()), request).await {
             Ok(response) => match response {
                 DataServerResponse::CommissionInfo { commission_info, .. } => Ok(commission_info),
                 DataServerResponse::Error { error, .. } => Err(error),
                 _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
+        }
+    }
+
+    /// The overnight financing rates the brokerage currently applies to the symbol, only Oanda provides financing rates.
+    pub async fn financing_rate(&self, symbol_name: SymbolName) -> Result<FinancingRate, FundForgeError> {
+        let request = DataServerRequest::FinancingRate {
+            callback_id: 0,
+            brokerage: self.clone(),
+            symbol_name,
+        };
+        match callback_request(ConnectionType::Broker(self.clone()), request).await {
+            Ok(response) => match response {
+                DataServerResponse::FinancingRate { financing_rate, .. } => Ok(financing_rate),
+                DataServerResponse::Error { error, .. } => Err(error),
+                _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
+            },
+            Err(e) => Err(e)
         }
     }
 
@@ -180,16 +184,13 @@ impl Brokerage {
             brokerage: self.clone(),
             account_id
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Broker(self.clone()), request, sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Broker(self.clone()), request).await {
             Ok(response) => match response {
                 DataServerResponse::AccountInfo { account_info, .. } => Ok(account_info),
                 DataServerResponse::Error { error, .. } => Err(error),
                 _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
         }
     }
 }
\ No newline at end of file
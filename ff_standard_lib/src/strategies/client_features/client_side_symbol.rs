@@ -1,10 +1,8 @@
-use tokio::sync::oneshot;
-
 use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
 use crate::standardized_types::new_types::Price;
 use crate::standardized_types::subscriptions::{Symbol, SymbolName};
 use crate::strategies::client_features::connection_types::ConnectionType;
-use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::client_features::request_handler::callback_request;
 impl Symbol {
     pub async fn tick_size(&self) -> Result<Price, FundForgeError> {
         let request = DataServerRequest::TickSize {
@@ -12,11 +10,7 @@ impl Symbol {
             data_vendor: self.data_vendor.clone(),
             symbol_name: self.name.clone(),
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.data_vendor.clone()), request, sender);
-        send_request(msg).await;
-
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.data_vendor.clone()), request).await {
             Ok(response) => match response {
                 DataServerResponse::TickSize { tick_size, .. } => Ok(tick_size),
                 DataServerResponse::Error { error, .. } => Err(error),
@@ -24,10 +18,7 @@ impl Symbol {
                     "Incorrect response received at callback".to_string(),
                 )),
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!(
-                "Receiver error at callback recv: {}",
-                e
-            ))),
+            Err(e) => Err(e),
         }
     }
 
@@ -37,11 +28,7 @@ impl Symbol {
             data_vendor: self.data_vendor.clone(),
             symbol_name,
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.data_vendor.clone()), request, sender);
-        send_request(msg).await;
-
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.data_vendor.clone()), request).await {
             Ok(response) => match response {
                 DataServerResponse::DecimalAccuracy { accuracy, .. } => Ok(accuracy),
                 DataServerResponse::Error { error, .. } => Err(error),
@@ -49,10 +36,7 @@ impl Symbol {
                     "Incorrect response received at callback".to_string(),
                 )),
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!(
-                "Receiver error at callback recv: {}",
-                e
-            ))),
+            Err(e) => Err(e),
         }
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
-use chrono::{DateTime, Utc};
-use tokio::sync::oneshot;
+use chrono::{DateTime, NaiveDate, Utc};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
 use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
 use crate::product_maps::oanda::maps::OANDA_SYMBOL_INFO;
 use crate::product_maps::rithmic::maps::get_futures_symbol_info;
@@ -8,8 +9,15 @@ use crate::standardized_types::datavendor_enum::DataVendor;
 use crate::standardized_types::enums::{MarketType, PrimarySubscription};
 use crate::standardized_types::new_types::Price;
 use crate::standardized_types::subscriptions::{Symbol, SymbolName};
+use crate::standardized_types::symbol_info::{SymbolMetadata, SymbolSearchResult};
+use crate::standardized_types::options::OptionContract;
+use crate::standardized_types::vendor_capabilities::VendorCapabilities;
 use crate::strategies::client_features::connection_types::ConnectionType;
-use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::client_features::request_handler::callback_request;
+
+lazy_static! {
+    static ref VENDOR_CAPABILITIES: DashMap<DataVendor, VendorCapabilities> = DashMap::new();
+}
 
 impl DataVendor {
     pub async fn symbols(&self, market_type: MarketType, time: Option<DateTime<Utc>>) -> Result<Vec<Symbol>, FundForgeError> {
@@ -23,10 +31,7 @@ impl DataVendor {
             data_vendor: self.clone(),
             market_type,
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.clone()), request,sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
             Ok(response) => {
                 match response {
                     DataServerResponse::Symbols { symbols, .. } => Ok(symbols),
@@ -34,7 +39,68 @@ impl DataVendor {
                     _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                 }
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Searches the vendor instruments for the `query`, matching the symbol name, contract code or description.
+    /// For futures vendors the results include the tradable contract codes, eg. searching "MNQ" with Rithmic returns each listed MNQ contract.
+    pub async fn symbol_search(&self, query: &str) -> Result<Vec<SymbolSearchResult>, FundForgeError> {
+        let request = DataServerRequest::SymbolSearch {
+            callback_id: 0,
+            data_vendor: self.clone(),
+            query: query.to_string(),
+        };
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
+            Ok(response) => {
+                match response {
+                    DataServerResponse::SymbolSearch { results, .. } => Ok(results),
+                    DataServerResponse::Error {error,..} => Err(error),
+                    _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
+                }
+            },
+            Err(e) => Err(e)
+        }
+    }
+
+    /// The option contracts listed on the `underlying`, only those expiring on the `expiry` date if it is `Some`.
+    /// Subscribe to a contract with its `Symbol::option()`, vendors which don't support options return an error.
+    pub async fn options_chain(&self, underlying: &str, expiry: Option<NaiveDate>) -> Result<Vec<OptionContract>, FundForgeError> {
+        let request = DataServerRequest::OptionsChain {
+            callback_id: 0,
+            data_vendor: *self,
+            underlying: underlying.to_string(),
+            expiry: expiry.map(|expiry| expiry.format("%Y-%m-%d").to_string()),
+        };
+        match callback_request(ConnectionType::Vendor(*self), request).await {
+            Ok(response) => {
+                match response {
+                    DataServerResponse::OptionsChain { contracts, .. } => Ok(contracts),
+                    DataServerResponse::Error {error,..} => Err(error),
+                    _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
+                }
+            },
+            Err(e) => Err(e)
+        }
+    }
+
+    /// The server catalog of the vendor symbols matching the `query`, with the tick size, currencies, trading hours and the history stored on the server.
+    /// The catalog does not need the vendor api to be connected, an empty query returns every symbol the server knows.
+    pub async fn symbol_catalog(&self, query: &str) -> Result<Vec<SymbolMetadata>, FundForgeError> {
+        let request = DataServerRequest::SymbolCatalog {
+            callback_id: 0,
+            data_vendor: self.clone(),
+            query: query.to_string(),
+        };
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
+            Ok(response) => {
+                match response {
+                    DataServerResponse::SymbolCatalog { symbols, .. } => Ok(symbols),
+                    DataServerResponse::Error {error,..} => Err(error),
+                    _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
+                }
+            },
+            Err(e) => Err(e)
         }
     }
 
@@ -43,10 +109,7 @@ impl DataVendor {
             callback_id: 0,
             data_vendor: self.clone(),
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.clone()), request,sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
             Ok(response) => {
                 match response {
                     DataServerResponse::BaseDataTypes { base_data_types, .. } => Ok(base_data_types),
@@ -54,7 +117,31 @@ impl DataVendor {
                     _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                 }
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
+        }
+    }
+
+    /// The capabilities of the vendor, requested from the server once and cached for the life of the strategy.
+    pub async fn capabilities(&self) -> Result<VendorCapabilities, FundForgeError> {
+        if let Some(capabilities) = VENDOR_CAPABILITIES.get(self) {
+            return Ok(capabilities.clone());
+        }
+        let request = DataServerRequest::VendorCapabilities {
+            callback_id: 0,
+            data_vendor: self.clone(),
+        };
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
+            Ok(response) => {
+                match response {
+                    DataServerResponse::VendorCapabilities { capabilities, .. } => {
+                        VENDOR_CAPABILITIES.insert(self.clone(), capabilities.clone());
+                        Ok(capabilities)
+                    },
+                    DataServerResponse::Error {error,..} => Err(error),
+                    _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
+                }
+            },
+            Err(e) => Err(e)
         }
     }
 
@@ -64,10 +151,7 @@ impl DataVendor {
             data_vendor: self.clone(),
             market_type,
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.clone()), request,sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
             Ok(response) => {
                 match response {
                     DataServerResponse::Resolutions { subscription_resolutions_types, .. } => Ok(subscription_resolutions_types),
@@ -75,7 +159,7 @@ impl DataVendor {
                     _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                 }
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
         }
     }
 
@@ -85,10 +169,7 @@ impl DataVendor {
             data_vendor: self.clone(),
             market_type,
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.clone()), request,sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
             Ok(response) => {
                 match response {
                     DataServerResponse::Resolutions { subscription_resolutions_types, .. } => Ok(subscription_resolutions_types),
@@ -96,7 +177,7 @@ impl DataVendor {
                     _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                 }
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
         }
     }
 
@@ -105,10 +186,7 @@ impl DataVendor {
             callback_id: 0,
             data_vendor: self.clone(),
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.clone()), request,sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
             Ok(response) => {
                 match response {
                     DataServerResponse::Markets { markets, .. } => Ok(markets),
@@ -116,7 +194,7 @@ impl DataVendor {
                     _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                 }
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
         }
     }
 
@@ -142,10 +220,7 @@ impl DataVendor {
             data_vendor: self.clone(),
             symbol_name,
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.clone()), request,sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
             Ok(response) => {
                 match response {
                     DataServerResponse::DecimalAccuracy { accuracy, .. } => Ok(accuracy),
@@ -153,7 +228,7 @@ impl DataVendor {
                     _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                 }
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
         }
     }
 
@@ -166,7 +241,7 @@ impl DataVendor {
                     Err(e) => return Err(FundForgeError::ClientSideErrorDebug(format!("Error getting tick size: {}", e)))
                 };
             }
-            DataVendor::Bitget => {}
+            DataVendor::Bitget | DataVendor::ForexFactory => {}
             DataVendor::Oanda => {
                 return match OANDA_SYMBOL_INFO.get(&symbol_name) {
                     Some(info) => Ok(info.tick_size),
@@ -181,10 +256,7 @@ impl DataVendor {
             data_vendor: self.clone(),
             symbol_name,
         };
-        let (sender, receiver) = oneshot::channel();
-        let msg = StrategyRequest::CallBack(ConnectionType::Vendor(self.clone()), request,sender);
-        send_request(msg).await;
-        match receiver.await {
+        match callback_request(ConnectionType::Vendor(self.clone()), request).await {
             Ok(response) => {
                 match response {
                     DataServerResponse::TickSize { tick_size, .. } => Ok(tick_size),
@@ -192,7 +264,7 @@ impl DataVendor {
                     _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
                 }
             },
-            Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+            Err(e) => Err(e)
         }
     }
 }
\ No newline at end of file
@@ -2,11 +2,13 @@ pub mod client_settings {
     use crate::helpers::{get_resources, get_toml_file_path};
     use crate::strategies::client_features::connection_types::ConnectionType;
     use crate::messages::data_server_messaging::FundForgeError;
+    use crate::messages::stream_compression::StreamCompression;
     use serde_derive::{Deserialize, Serialize};
     use std::collections::HashMap;
-    use std::net::SocketAddr;
+    use std::net::{SocketAddr, SocketAddrV4};
     use std::path::PathBuf;
     use std::str::FromStr;
+    use std::time::Duration;
 
     #[derive(Debug, Serialize, Deserialize)]
     struct SettingsMap {
@@ -46,6 +48,13 @@ pub mod client_settings {
                 server_name: String::from("fundforge"),
                 address: SocketAddr::from_str("127.0.0.1:8083").unwrap(),
                 stream_address: SocketAddr::from_str("127.0.0.1:8084").unwrap(),
+                stream_compression: StreamCompression::None,
+                history_compression: StreamCompression::None,
+                local_fan_out: None,
+                heartbeat_interval_secs: default_heartbeat_interval_secs(),
+                heartbeat_timeout_secs: default_heartbeat_timeout_secs(),
+                request_timeout_secs: default_request_timeout_secs(),
+                api_key: None,
             };
             map.insert(ConnectionType::StrategyRegistry, dafault_registry_settings);
 
@@ -90,7 +99,76 @@ pub mod client_settings {
         /// the listener for async streaming type communications
         pub address: SocketAddr,
 
-        pub stream_address: SocketAddr
+        pub stream_address: SocketAddr,
+
+        /// The compression requested for the live data stream, useful when connecting to a remote server over WAN.
+        #[serde(default)]
+        pub stream_compression: StreamCompression,
+
+        /// The compression requested for historical data files, `Zstd` has the server recompress the stored gzip files, which decompress faster on the client.
+        #[serde(default)]
+        pub history_compression: StreamCompression,
+
+        /// A local multicast group eg. "239.255.70.70:8085", only for strategies running on the same machine as the data server.
+        /// When set, market data is received from the group instead of the TLS stream, so identical streams are not duplicated per strategy.
+        #[serde(default)]
+        pub local_fan_out: Option<SocketAddrV4>,
+
+        /// Seconds between the heartbeats sent to the server, 0 disables heartbeats, the server drops strategies it does not hear from within its own timeout.
+        #[serde(default = "default_heartbeat_interval_secs")]
+        pub heartbeat_interval_secs: u64,
+
+        /// The server is treated as dead if no message or heartbeat arrives within this many seconds, the connection is then dropped and reconnected
+        /// and requests waiting on a response fail with `FundForgeError::ConnectionNotFound`, 0 waits forever.
+        #[serde(default = "default_heartbeat_timeout_secs")]
+        pub heartbeat_timeout_secs: u64,
+
+        /// Requests waiting on a response for longer than this many seconds fail with `FundForgeError::RequestTimedOut`, 0 waits forever.
+        /// Historical data is streamed in parts and is not limited by the timeout.
+        #[serde(default = "default_request_timeout_secs")]
+        pub request_timeout_secs: u64,
+
+        /// The key sent when registering with the server, required when the server has api keys configured, the permissions of the key decide which requests the server accepts.
+        #[serde(default)]
+        pub api_key: Option<String>,
+    }
+
+    fn default_heartbeat_interval_secs() -> u64 {
+        10
+    }
+
+    fn default_heartbeat_timeout_secs() -> u64 {
+        30
+    }
+
+    fn default_request_timeout_secs() -> u64 {
+        60
+    }
+
+    impl ConnectionSettings {
+        /// The interval heartbeats are sent at, `None` if heartbeats are disabled.
+        pub fn heartbeat_interval(&self) -> Option<Duration> {
+            match self.heartbeat_interval_secs {
+                0 => None,
+                seconds => Some(Duration::from_secs(seconds)),
+            }
+        }
+
+        /// The time without any message from the server after which it is treated as dead, `None` if disabled.
+        pub fn heartbeat_timeout(&self) -> Option<Duration> {
+            match self.heartbeat_timeout_secs {
+                0 => None,
+                seconds => Some(Duration::from_secs(seconds)),
+            }
+        }
+
+        /// The time to wait for the response to a request, `None` if requests wait forever.
+        pub fn request_timeout(&self) -> Option<Duration> {
+            match self.request_timeout_secs {
+                0 => None,
+                seconds => Some(Duration::from_secs(seconds)),
+            }
+        }
     }
 
     impl Default for ConnectionSettings {
@@ -100,7 +178,38 @@ pub mod client_settings {
                 server_name: String::from("fundforge"),
                 address: SocketAddr::from_str("127.0.0.1:8081").unwrap(),
                 stream_address: SocketAddr::from_str("127.0.0.1:8082").unwrap(),
+                stream_compression: StreamCompression::None,
+                history_compression: StreamCompression::None,
+                local_fan_out: None,
+                heartbeat_interval_secs: default_heartbeat_interval_secs(),
+                heartbeat_timeout_secs: default_heartbeat_timeout_secs(),
+                request_timeout_secs: default_request_timeout_secs(),
+                api_key: None,
             }
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_heartbeat_settings() {
+            let toml_content = r#"
+                ssl_auth_folder = "./resources/keys"
+                server_name = "fundforge"
+                address = "127.0.0.1:8081"
+                stream_address = "127.0.0.1:8082"
+            "#;
+            let settings: ConnectionSettings = toml::from_str(toml_content).unwrap();
+            assert_eq!(settings.heartbeat_interval(), Some(Duration::from_secs(10)));
+            assert_eq!(settings.heartbeat_timeout(), Some(Duration::from_secs(30)));
+            assert_eq!(settings.request_timeout(), Some(Duration::from_secs(60)));
+
+            let disabled = format!("{}\nheartbeat_interval_secs = 0\nheartbeat_timeout_secs = 0", toml_content);
+            let settings: ConnectionSettings = toml::from_str(&disabled).unwrap();
+            assert_eq!(settings.heartbeat_interval(), None);
+            assert_eq!(settings.heartbeat_timeout(), None);
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use crate::strategies::client_features::connection_settings::client_settings::ConnectionSettings;
 use crate::messages::data_server_messaging::FundForgeError;
-use rustls::pki_types::ServerName;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
 use rustls::ClientConfig;
 use std::fs::File;
 use std::io::BufReader;
@@ -30,6 +30,7 @@ use tokio_rustls::{TlsConnector, TlsStream};
 /// # Parameters
 /// - `addr`: A reference to the `SocketAddr` of the server to connect to.
 /// - `ca_file`: A reference to the `Path` of the CA certificate file used for verifying the server's certificate.
+/// - `client_cert`: The certificate chain and private key presented to servers which require client certificates, see `load_client_cert()`.
 /// - `server_name`: A reference to a `String` containing the expected server name, used for SNI and certificate verification.
 ///
 /// # Returns
@@ -45,6 +46,7 @@ use tokio_rustls::{TlsConnector, TlsStream};
 async fn initialise_connection(
     addr: &SocketAddr,
     ca_file: &Path,
+    client_cert: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
     server_name: &String,
 ) -> Result<TlsStream<TcpStream>, FundForgeError> {
     let mut root_cert_store = rustls::RootCertStore::empty();
@@ -74,9 +76,14 @@ async fn initialise_connection(
         }
     }
 
-    let config = ClientConfig::builder()
-        .with_root_certificates(root_cert_store)
-        .with_no_client_auth();
+    let builder = ClientConfig::builder()
+        .with_root_certificates(root_cert_store);
+    let config = match client_cert {
+        None => builder.with_no_client_auth(),
+        Some((certs, key)) => builder.with_client_auth_cert(certs, key).map_err(|e| {
+            FundForgeError::ClientSideErrorDebug(format!("Invalid client certificate: {}", e))
+        })?,
+    };
 
     let connector = TlsConnector::from(Arc::new(config));
     let stream = match TcpStream::connect(addr).await {
@@ -110,6 +117,26 @@ async fn initialise_connection(
     }
 }
 
+/// Loads the `client.crt` and `client.key` from the ssl folder, presented to data servers launched with `--client_auth 1`.
+/// Returns `None` if the files do not exist, the connection is then made without a client certificate.
+fn load_client_cert(ssl_auth_folder: &Path) -> Result<Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>, FundForgeError> {
+    let cert_path = ssl_auth_folder.join("client.crt");
+    let key_path = ssl_auth_folder.join("client.key");
+    if !cert_path.exists() || !key_path.exists() {
+        return Ok(None);
+    }
+    let open = |path: &Path| File::open(path).map(BufReader::new).map_err(|e| {
+        FundForgeError::ClientSideErrorDebug(format!("Failed to open client certificate file {}: {}", path.display(), e))
+    });
+    let certs = rustls_pemfile::certs(&mut open(&cert_path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse client certificate: {}", e)))?;
+    let key = rustls_pemfile::private_key(&mut open(&key_path)?)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse client key: {}", e)))?
+        .ok_or_else(|| FundForgeError::ClientSideErrorDebug("No private key found in client.key".to_string()))?;
+    Ok(Some((certs, key)))
+}
+
 pub(crate) async fn create_async_api_client(
     settings: &ConnectionSettings,
     is_stream: bool
@@ -119,8 +146,9 @@ pub(crate) async fn create_async_api_client(
         true => settings.stream_address.clone(),
         false => settings.address.clone()
     };
+    let client_cert = load_client_cert(&settings.ssl_auth_folder)?;
     let stream =
-        match initialise_connection(&address, &ca_path, &settings.server_name).await {
+        match initialise_connection(&address, &ca_path, client_cert, &settings.server_name).await {
             Ok(stream) => stream,
             Err(e) => {
                 return Err(FundForgeError::ClientSideErrorDebug(format!(
@@ -132,3 +160,20 @@ pub(crate) async fn create_async_api_client(
 
     Ok(stream)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_load_client_cert() {
+        let folder = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join("ff_data_server").join("resources").join("keys");
+        let (certs, key) = load_client_cert(&folder).unwrap().expect("client.crt and client.key exist");
+        let root_cert_store = rustls::RootCertStore::empty();
+        assert!(ClientConfig::builder().with_root_certificates(root_cert_store).with_client_auth_cert(certs, key).is_ok());
+
+        // Without the files the connection is made without a client certificate.
+        assert!(load_client_cert(&folder.join("missing")).unwrap().is_none());
+    }
+}
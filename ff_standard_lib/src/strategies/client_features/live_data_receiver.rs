@@ -1,28 +1,130 @@
 use std::collections::BTreeMap;
+use std::net::SocketAddrV4;
 use std::time::Duration;
+use ahash::AHashMap;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use tokio::sync::mpsc;
 use tokio::sync::mpsc::{Sender};
 use std::sync::Arc;
 use chrono::{Utc};
 use tokio::runtime::Runtime;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use tokio::net::{TcpStream, UdpSocket};
 use tokio_rustls::TlsStream;
+use tracing::{Instrument, Span};
 use crate::messages::data_server_messaging::DataServerRequest;
+use crate::messages::stream_compression::StreamCompression;
+use crate::messages::local_fan_out::{fan_out_receiver_socket, missed_frames, FanOutFrame, MAX_DATAGRAM_SIZE};
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::bytes_trait::Bytes;
 use crate::standardized_types::time_slices::TimeSlice;
 use crate::strategies::client_features::connection_settings::client_settings::ConnectionSettings;
+use crate::strategies::client_features::connection_types::ConnectionType;
 use crate::strategies::client_features::init_clients::create_async_api_client;
-use crate::strategies::client_features::server_connections::set_warmup_complete;
+use crate::strategies::client_features::live_subscriptions;
+use crate::strategies::client_features::server_connections::{is_warmup_complete, next_reconnect_delay, set_warmup_complete, INITIAL_RECONNECT_DELAY, RESUBSCRIBE_DELAY};
 use crate::strategies::handlers::indicator_handler::IndicatorHandler;
 use crate::strategies::handlers::live_warmup::WARMUP_COMPLETE_BROADCASTER;
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
 use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
 use crate::strategies::historical_time::{get_backtest_time, update_backtest_time};
 use crate::strategies::ledgers::ledger_service::LedgerService;
-use crate::strategies::strategy_events::StrategyEvent;
+use crate::strategies::strategy_events::{ConnectionEvent, StrategyEvent};
+use crate::standardized_types::subscriptions::{DataSubscription, DataSubscriptionEvent};
+use crate::strategies::feed_quality::{feed_monitor, QualityAlert};
 
+lazy_static! {
+    /// The latest live stream of each connection, a stream replaced after its server connection was restored stops reconnecting.
+    static ref STREAM_GENERATIONS: DashMap<ConnectionType, u64> = DashMap::new();
+}
+
+fn is_current_stream(connection: &ConnectionType, generation: u64) -> bool {
+    STREAM_GENERATIONS.get(connection).is_some_and(|current| *current == generation)
+}
+
+/// Opens the live stream of the connection and registers it with the server.
+async fn register_streamer(
+    connection_settings: &ConnectionSettings,
+    stream_name: u16,
+    buffer_duration: Duration,
+    local_fan_out: Option<SocketAddrV4>,
+) -> Result<TlsStream<TcpStream>, String> {
+    let mut stream_client = create_async_api_client(connection_settings, true).await
+        .map_err(|e| format!("Unable to establish connection to server @ address: {:?}: {}", connection_settings, e))?;
+
+    let stream_registration = DataServerRequest::RegisterStreamer {
+        port: stream_name,
+        secs: buffer_duration.as_secs(),
+        subsec: buffer_duration.subsec_nanos(),
+        compression: connection_settings.stream_compression,
+        local_fan_out,
+        api_key: connection_settings.api_key.clone(),
+    };
+    let data = stream_registration.to_bytes();
+    let length: [u8; 4] = (data.len() as u32).to_be_bytes();
+    let mut prefixed_msg = Vec::new();
+    prefixed_msg.extend_from_slice(&length);
+    prefixed_msg.extend_from_slice(&data);
+
+    stream_client.write_all(&prefixed_msg).await.map_err(|e| format!("Failed to register stream: {}", e))?;
+    Ok(stream_client)
+}
+
+/// Registers the stream again when the stream dropped while the server connection stayed up, retrying with the same backoff as `server_connections::reconnect()`.
+/// The feeds of the old stream are gone, so the subscriptions served by the connection are subscribed again once the stream is registered.
+/// Returns `None` if the server connection was restored in the meantime, its registration starts a new stream.
+#[allow(clippy::too_many_arguments)]
+async fn reconnect_stream(
+    connection: &ConnectionType,
+    connection_settings: &ConnectionSettings,
+    stream_name: u16,
+    buffer_duration: Duration,
+    local_fan_out: Option<SocketAddrV4>,
+    generation: u64,
+    subscription_handler: &Arc<SubscriptionHandler>,
+    strategy_event_sender: &Sender<StrategyEvent>,
+) -> Option<TlsStream<TcpStream>> {
+    let stream = format!("{:?} live stream", connection);
+    let disconnected_at = Utc::now();
+    tracing::warn!("{} disconnected, reconnecting", stream);
+    let event = ConnectionEvent::Disconnected { connection: stream.clone(), time: disconnected_at.to_string(), reason: "The live data stream disconnected".to_string() };
+    let _ = strategy_event_sender.send(StrategyEvent::ConnectionEvent(event)).await;
+
+    let mut delay = INITIAL_RECONNECT_DELAY;
+    let mut attempts: u32 = 0;
+    loop {
+        tokio::time::sleep(delay).await;
+        if !is_current_stream(connection, generation) {
+            return None;
+        }
+        attempts += 1;
+        match register_streamer(connection_settings, stream_name, buffer_duration, local_fan_out).await {
+            Ok(stream_client) => {
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+                live_subscriptions::resubscribe(connection, subscription_handler).await;
+                let reconnected_at = Utc::now();
+                let event = ConnectionEvent::Reconnected {
+                    connection: stream,
+                    disconnected_at: disconnected_at.to_string(),
+                    reconnected_at: reconnected_at.to_string(),
+                    outage_seconds: (reconnected_at - disconnected_at).num_seconds(),
+                    attempts,
+                };
+                let _ = strategy_event_sender.send(StrategyEvent::ConnectionEvent(event)).await;
+                return Some(stream_client);
+            }
+            Err(e) => {
+                tracing::error!("Unable to reconnect the {}, attempt {}: {}", stream, attempts, e);
+                delay = next_reconnect_delay(delay);
+            }
+        }
+    }
+}
+
+/// Receives the live data of the connection, if the stream drops it is registered again, see `reconnect_stream()`.
 pub async fn handle_live_data(
+    connection: ConnectionType,
     connection_settings: ConnectionSettings,
     stream_name: u16,
     buffer_duration: Duration,
@@ -32,48 +134,173 @@ pub async fn handle_live_data(
     subscription_handler: Arc<SubscriptionHandler>,
     market_price_service: Arc<MarketPriceService>,
 ) {
+    let generation = {
+        let mut current = STREAM_GENERATIONS.entry(connection.clone()).or_insert(0);
+        *current += 1;
+        *current
+    };
 
-    let mut stream_client = match create_async_api_client(&connection_settings, true).await {
-        Ok(client) => client,
-        Err(e) => {
-            panic!("Unable to establish connection to server @ address: {:?}: {}", connection_settings, e);
-        }
+    // Join the local multicast group before registering, if we can't join we fall back to receiving data on the tls stream.
+    let fan_out_socket = match connection_settings.local_fan_out {
+        Some(group) => match fan_out_receiver_socket(group) {
+            Ok(socket) => Some(socket),
+            Err(e) => {
+                tracing::error!("Unable to join local fan out group {}, using the tls stream: {}", group, e);
+                None
+            }
+        },
+        None => None
+    };
+    let local_fan_out = match fan_out_socket.is_some() {
+        true => connection_settings.local_fan_out,
+        false => None
     };
 
     // Register with server
-    let stream_registration = DataServerRequest::RegisterStreamer {
-        port: stream_name,
-        secs: buffer_duration.as_secs(),
-        subsec: buffer_duration.subsec_nanos()
+    let compression = connection_settings.stream_compression;
+    let mut stream_client = match register_streamer(&connection_settings, stream_name, buffer_duration, local_fan_out).await {
+        Ok(stream_client) => stream_client,
+        Err(e) => {
+            tracing::error!("{}", e);
+            return;
+        }
     };
-    let data = stream_registration.to_bytes();
-    let length: [u8; 4] = (data.len() as u32).to_be_bytes();
-    let mut prefixed_msg = Vec::new();
-    prefixed_msg.extend_from_slice(&length);
-    prefixed_msg.extend_from_slice(&data);
-
-    if let Err(e) = stream_client.write_all(&prefixed_msg).await {
-        eprintln!("Failed to register stream: {}", e);
-        return;
-    }
 
+    // The messages of the stream keep the span of the connection when the tasks move to the stream runtime
+    let span = Span::current();
     let _ = tokio::task::spawn_blocking(move || {
         let rt = Runtime::new().unwrap();
         rt.block_on(async {
-            receive_and_process(
-                stream_client,
-                strategy_event_sender,
-                ledger_service,
-                indicator_handler,
-                subscription_handler,
-                market_price_service,
-            ).await;
-        });
+            let (fan_out_sender, mut fan_out_receiver) = mpsc::channel(1000);
+            if let Some(socket) = fan_out_socket {
+                match UdpSocket::from_std(socket) {
+                    Ok(socket) => {
+                        tokio::spawn(receive_fan_out(socket, subscription_handler.clone(), fan_out_sender).in_current_span());
+                    }
+                    Err(e) => tracing::error!("Unable to use local fan out socket: {}", e)
+                }
+            }
+            loop {
+                receive_and_process(
+                    stream_client,
+                    compression,
+                    &mut fan_out_receiver,
+                    &connection,
+                    generation,
+                    strategy_event_sender.clone(),
+                    ledger_service.clone(),
+                    indicator_handler.clone(),
+                    subscription_handler.clone(),
+                    market_price_service.clone(),
+                ).await;
+                if !is_current_stream(&connection, generation) {
+                    return;
+                }
+                stream_client = match reconnect_stream(&connection, &connection_settings, stream_name, buffer_duration, local_fan_out, generation, &subscription_handler, &strategy_event_sender).await {
+                    Some(stream_client) => stream_client,
+                    None => return,
+                };
+            }
+        }.instrument(span));
     });
 }
 
+/// Receives market data frames from the local multicast group, keeping only the frames for our own subscriptions.
+/// Frames lost by the multicast group are logged, the data of a lost frame is not recovered.
+async fn receive_fan_out(
+    socket: UdpSocket,
+    subscription_handler: Arc<SubscriptionHandler>,
+    sender: mpsc::Sender<TimeSlice>,
+) {
+    let mut buffer = vec![0u8; MAX_DATAGRAM_SIZE];
+    let mut sequences: AHashMap<DataSubscription, u64> = AHashMap::new();
+    loop {
+        let length = match socket.recv(&mut buffer).await {
+            Ok(length) => length,
+            Err(e) => {
+                tracing::error!("Error reading local fan out frame: {}", e);
+                break;
+            }
+        };
+        let frame = match FanOutFrame::from_bytes(&buffer[..length]) {
+            Ok(frame) => frame,
+            Err(_) => continue
+        };
+        if !subscription_handler.primary_subscriptions().await.contains(&frame.subscription) {
+            continue;
+        }
+        if let Some(last) = sequences.insert(frame.subscription.clone(), frame.sequence) {
+            let missed = missed_frames(last, frame.sequence);
+            if missed > 0 {
+                tracing::warn!("Local fan out: Missed {} frames for {}", missed, frame.subscription);
+            }
+        }
+        let time_slice: TimeSlice = frame.data.into_iter().collect();
+        if sender.send(time_slice).await.is_err() {
+            break;
+        }
+    }
+}
+
+fn buffer_time_slice(buffered_data: &mut BTreeMap<i64, TimeSlice>, time_slice: TimeSlice) {
+    for data in time_slice.iter() {
+        let timestamp = data.time_closed_utc().timestamp_nanos_opt().unwrap();
+        buffered_data.entry(timestamp)
+            .and_modify(|slice| slice.extend(time_slice.clone()))
+            .or_insert_with(|| {
+                let mut new_slice = TimeSlice::new();
+                new_slice.extend(time_slice.clone());
+                new_slice
+            });
+    }
+}
+
+async fn process_live_time_slice(
+    time_slice: TimeSlice,
+    strategy_event_sender: &Sender<StrategyEvent>,
+    ledger_service: &Arc<LedgerService>,
+    indicator_handler: &Arc<IndicatorHandler>,
+    subscription_handler: &Arc<SubscriptionHandler>,
+    price_service: &Arc<MarketPriceService>,
+) {
+    let mut strategy_time_slice = TimeSlice::new();
+    if !time_slice.is_empty() {
+        if let Some(monitor) = feed_monitor() {
+            send_quality_alerts(monitor.check_time_slice(&time_slice, Utc::now()), strategy_event_sender).await;
+        }
+        let arc_slice = Arc::new(time_slice.clone());
+        price_service.update_market_data(arc_slice.clone());
+        ledger_service.timeslice_updates(arc_slice.clone()).await;
+
+        if let Some(consolidated_data) = subscription_handler.update_time_slice(arc_slice).await {
+            strategy_time_slice.extend(consolidated_data);
+        }
+        strategy_time_slice.extend(time_slice);
+        //the indicator update will not be garanteed to be in sync with the time slice, but it should be close enough and this prevents very resource intense indicators from slowing down the strategy.
+         if let Some(indicator_slice) = indicator_handler.update_time_slice(&strategy_time_slice).await {
+            let _ = strategy_event_sender.send(StrategyEvent::IndicatorEvent(indicator_slice)).await;
+        };
+        let _ = strategy_event_sender.send(StrategyEvent::TimeSlice(strategy_time_slice)).await;
+    }
+}
+
+async fn send_quality_alerts(alerts: Vec<QualityAlert>, strategy_event_sender: &Sender<StrategyEvent>) {
+    for alert in alerts {
+        match alert.is_recovery() {
+            true => tracing::info!("{}", alert),
+            false => tracing::warn!("{}", alert),
+        }
+        let _ = strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(DataSubscriptionEvent::QualityAlert(alert))).await;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn receive_and_process(
     mut stream_client: TlsStream<TcpStream>,
+    compression: StreamCompression,
+    fan_out_receiver: &mut mpsc::Receiver<TimeSlice>,
+    connection: &ConnectionType,
+    generation: u64,
     strategy_event_sender: Sender<StrategyEvent>,
     ledger_service: Arc<LedgerService>,
     indicator_handler: Arc<IndicatorHandler>,
@@ -82,80 +309,85 @@ async fn receive_and_process(
 ) {
     const LENGTH: usize = 4;
     let mut length_bytes = [0u8; LENGTH];
-    let mut  buffered_data: BTreeMap<i64, TimeSlice> =BTreeMap::new();
-    let mut warmup_completion_receiver = WARMUP_COMPLETE_BROADCASTER.subscribe();
-    #[allow(unused_assignments)]
-    let mut warm_up_end = Utc::now();
-    // First phase: Buffer data during warmup
-    loop {
-        tokio::select! {
-            result = stream_client.read_exact(&mut length_bytes) => {
-                match result {
-                    Ok(_) => {
-                        let msg_length = u32::from_be_bytes(length_bytes) as usize;
-                        let mut message_body = vec![0u8; msg_length];
+    // A stream registered again after a dropped connection was restored goes straight to live processing.
+    if !is_warmup_complete() {
+        let mut  buffered_data: BTreeMap<i64, TimeSlice> =BTreeMap::new();
+        let mut warmup_completion_receiver = WARMUP_COMPLETE_BROADCASTER.subscribe();
+        #[allow(unused_assignments)]
+        let mut warm_up_end = Utc::now();
+        // First phase: Buffer data during warmup
+        loop {
+            tokio::select! {
+                result = stream_client.read_exact(&mut length_bytes) => {
+                    match result {
+                        Ok(_) => {
+                            let msg_length = u32::from_be_bytes(length_bytes) as usize;
+                            let mut message_body = vec![0u8; msg_length];
+
+                            if let Err(e) = stream_client.read_exact(&mut message_body).await {
+                                tracing::error!("Error reading message body: {}", e);
+                                continue;
+                            }
 
-                        if let Err(e) = stream_client.read_exact(&mut message_body).await {
-                            eprintln!("Error reading message body: {}", e);
-                            continue;
-                        }
+                            let message_body = match compression.decompress(message_body) {
+                                Ok(body) => body,
+                                Err(e) => {
+                                    tracing::error!("{}", e);
+                                    continue;
+                                }
+                            };
 
-                        if let Ok(time_slice) = TimeSlice::from_bytes(&message_body) {
-                            for data in time_slice.iter() {
-                                let timestamp = data.time_closed_utc().timestamp_nanos_opt().unwrap();
-                                buffered_data.entry(timestamp.clone())
-                                    .and_modify(|slice| slice.extend(time_slice.clone()))
-                                    .or_insert_with(|| {
-                                        let mut new_slice = TimeSlice::new();
-                                        new_slice.extend(time_slice.clone());
-                                        new_slice
-                                    });
+                            if let Ok(time_slice) = TimeSlice::from_bytes(&message_body) {
+                                buffer_time_slice(&mut buffered_data, time_slice);
                             }
                         }
-                    }
-                    Err(e) => {
-                        eprintln!("Error reading length bytes: {}", e);
-                        return;
+                        Err(e) => {
+                            tracing::error!("Error reading length bytes: {}", e);
+                            return;
+                        }
                     }
                 }
-            }
-            Ok(time) = warmup_completion_receiver.recv() => {
-                warm_up_end = time;
-                break;
+                Some(time_slice) = fan_out_receiver.recv() => {
+                    buffer_time_slice(&mut buffered_data, time_slice);
+                }
+                Ok(time) = warmup_completion_receiver.recv() => {
+                    warm_up_end = time;
+                    break;
+                }
             }
         }
-    }
-    drop(warmup_completion_receiver);
-    let range_start = warm_up_end.timestamp();
-    let range_end = Utc::now().timestamp();
-    if range_start < range_end {
-        // Process buffered data
-        for (time, slice) in buffered_data
-            .range(..=Utc::now().timestamp())
-            .filter(|(_, slice)| !slice.is_empty())
-        {
-            if *time <= get_backtest_time().timestamp() {
-                continue;
-            }
-            let mut strategy_time_slice = TimeSlice::new();
-            let arc_slice = Arc::new(slice.clone());
+        drop(warmup_completion_receiver);
+        let range_start = warm_up_end.timestamp();
+        let range_end = Utc::now().timestamp();
+        if range_start < range_end {
+            // Process buffered data
+            for (time, slice) in buffered_data
+                .range(..=Utc::now().timestamp())
+                .filter(|(_, slice)| !slice.is_empty())
+            {
+                if *time <= get_backtest_time().timestamp() {
+                    continue;
+                }
+                let mut strategy_time_slice = TimeSlice::new();
+                let arc_slice = Arc::new(slice.clone());
 
-            price_service.update_market_data(arc_slice.clone());
-            ledger_service.timeslice_updates(arc_slice.clone()).await;
+                price_service.update_market_data(arc_slice.clone());
+                ledger_service.timeslice_updates(arc_slice.clone()).await;
 
-            if let Some(consolidated_data) = subscription_handler.update_time_slice(arc_slice).await {
-                strategy_time_slice.extend(consolidated_data);
-            }
-            strategy_time_slice.extend(slice.clone());
+                if let Some(consolidated_data) = subscription_handler.update_time_slice(arc_slice).await {
+                    strategy_time_slice.extend(consolidated_data);
+                }
+                strategy_time_slice.extend(slice.clone());
 
-            if let Some(events) = indicator_handler.update_time_slice(&strategy_time_slice).await {
-                let _ = strategy_event_sender.send(StrategyEvent::IndicatorEvent(events)).await;
+                if let Some(events) = indicator_handler.update_time_slice(&strategy_time_slice).await {
+                    let _ = strategy_event_sender.send(StrategyEvent::IndicatorEvent(events)).await;
+                }
+                let _ = strategy_event_sender.send(StrategyEvent::TimeSlice(strategy_time_slice)).await;
             }
-            let _ = strategy_event_sender.send(StrategyEvent::TimeSlice(strategy_time_slice)).await;
         }
+        drop(buffered_data);
+        set_warmup_complete();
     }
-    drop(buffered_data);
-    set_warmup_complete();
 
 
     let now = tokio::time::Instant::now();
@@ -179,7 +411,14 @@ async fn receive_and_process(
                     };
                     let _ = strategy_event_sender.send(StrategyEvent::TimeSlice(consolidated_data)).await;
                 }
+                if let Some(monitor) = feed_monitor() {
+                    send_quality_alerts(monitor.check_stale(&subscription_handler.primary_subscriptions().await, now), &strategy_event_sender).await;
+                }
                 update_backtest_time(now);
+                // the server connection was restored and registered a new stream
+                if !is_current_stream(connection, generation) {
+                    return;
+                }
             }
             result = stream_client.read_exact(&mut length_bytes) => {
                 match result {
@@ -188,41 +427,34 @@ async fn receive_and_process(
                         let mut message_body = vec![0u8; msg_length];
 
                         if let Err(e) = stream_client.read_exact(&mut message_body).await {
-                            eprintln!("Error reading message body: {}", e);
+                            tracing::error!("Error reading message body: {}", e);
                             continue;
                         }
 
-                        if let Ok(time_slice) = TimeSlice::from_bytes(&message_body) {
-                             let mut strategy_time_slice = TimeSlice::new();
-                            if !time_slice.is_empty() {
-                                let arc_slice = Arc::new(time_slice.clone());
-                                price_service.update_market_data(arc_slice.clone());
-                                ledger_service.timeslice_updates(arc_slice.clone()).await;
-
-                                if let Some(consolidated_data) = subscription_handler.update_time_slice(arc_slice).await {
-                                    strategy_time_slice.extend(consolidated_data);
-                                }
-                                strategy_time_slice.extend(time_slice);
-                                //the indicator update will not be garanteed to be in sync with the time slice, but it should be close enough and this prevents very resource intense indicators from slowing down the strategy.
-                                 if let Some(indicator_slice) = indicator_handler.update_time_slice(&strategy_time_slice).await {
-                                    let _ = strategy_event_sender.send(StrategyEvent::IndicatorEvent(indicator_slice)).await;
-                                };
-                                let _ = strategy_event_sender.send(StrategyEvent::TimeSlice(strategy_time_slice)).await;
+                        let message_body = match compression.decompress(message_body) {
+                            Ok(body) => body,
+                            Err(e) => {
+                                tracing::error!("{}", e);
+                                continue;
                             }
+                        };
+
+                        if let Ok(time_slice) = TimeSlice::from_bytes(&message_body) {
+                            process_live_time_slice(time_slice, &strategy_event_sender, &ledger_service, &indicator_handler, &subscription_handler, &price_service).await;
                         }
                     }
                     Err(e) => {
-                        eprintln!("Error reading length bytes: {}", e);
+                        tracing::error!("Error reading length bytes: {}", e);
                         break;
                     }
                 }
             }
+            Some(time_slice) = fan_out_receiver.recv() => {
+                process_live_time_slice(time_slice, &strategy_event_sender, &ledger_service, &indicator_handler, &subscription_handler, &price_service).await;
+            }
         }
     }
-
-    let _ = strategy_event_sender
-        .send(StrategyEvent::ShutdownEvent(String::from("Live stream disconnected")))
-        .await;
+    // The stream is registered again by `handle_live_data()`, or by the new stream if the server connection was restored, see `server_connections::reconnect()`.
 }
 
 
@@ -259,7 +491,7 @@ pub async fn handle_live_data(
     match stream_client.write_all(&prefixed_msg).await {
         Ok(_) => { }
         Err(e) => {
-            eprintln!("{}", e);
+            tracing::error!("{}", e);
         }
     }
 
@@ -287,7 +519,7 @@ pub async fn handle_live_data(
                         if !strategy_time_slice.is_empty() {
                             match strategy_event_sender.send(StrategyEvent::TimeSlice(strategy_time_slice)).await {
                                 Ok(_) => {}
-                                Err(e) => eprintln!("Live Handler: {}", e)
+                                Err(e) => tracing::error!("Live Handler: {}", e)
                             }
                         }
                     }
@@ -298,14 +530,14 @@ pub async fn handle_live_data(
                                 let mut message_body = vec![0u8; msg_length];
 
                                 if let Err(e) = stream_client.read_exact(&mut message_body).await {
-                                    eprintln!("Error reading message body: {}", e);
+                                    tracing::error!("Error reading message body: {}", e);
                                     continue;
                                 }
 
                                 let time_slice = match TimeSlice::from_bytes(&message_body) {
                                     Ok(ts) => ts,
                                     Err(e) => {
-                                        eprintln!("Error parsing TimeSlice: {}", e);
+                                        tracing::error!("Error parsing TimeSlice: {}", e);
                                         continue;
                                     }
                                 };
@@ -334,12 +566,12 @@ pub async fn handle_live_data(
                                     indicator_handler.update_time_slice(&strategy_time_slice).await;
                                     match strategy_event_sender.send(StrategyEvent::TimeSlice(strategy_time_slice)).await {
                                         Ok(_) => {}
-                                        Err(e) => eprintln!("Live Handler: {}", e)
+                                        Err(e) => tracing::error!("Live Handler: {}", e)
                                     }
                                 }
                             }
                             Err(e) => {
-                                eprintln!("Error reading length bytes: {}", e);
+                                tracing::error!("Error reading length bytes: {}", e);
                                 break;
                             }
                         }
@@ -348,7 +580,7 @@ pub async fn handle_live_data(
             }
             match strategy_event_sender.send(StrategyEvent::ShutdownEvent(String::from("Disconnected Live Stream"))).await {
                 Ok(_) => {}
-                Err(e) => eprintln!("Live Handler: {}", e)
+                Err(e) => tracing::error!("Live Handler: {}", e)
             }
         });
     });
@@ -6,6 +6,7 @@ use crate::strategies::client_features::connection_settings::client_settings::in
 use crate::strategies::client_features::connection_types::ConnectionType;
 use crate::strategies::client_features::request_handler::StrategyRequest;
 use crate::strategies::client_features::request_handler;
+use crate::strategies::client_features::server_connections::resolve_connection_type;
 use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
 
 pub(crate) async fn live_subscription_handler(
@@ -20,12 +21,12 @@ pub(crate) async fn live_subscription_handler(
     let mut subscription_update_channel = subscription_handler.subscribe_primary_subscription_updates();
 
     let settings_map_ref = settings_map.clone();
-    println!("Handler: Start Live handler");
+    tracing::info!("Handler: Start Live handler");
     tokio::task::spawn(async move {
         let mut current_subscriptions = subscription_handler.primary_subscriptions().await.clone();
         {
             let mut subscribed = vec![];
-            println!("Handler: {:?}", current_subscriptions);
+            tracing::info!("Handler: {:?}", current_subscriptions);
             for subscription in &*current_subscriptions {
                 let request = DataServerRequest::StreamRequest {
                     request: StreamRequest::Subscribe(subscription.clone())
@@ -36,7 +37,8 @@ pub(crate) async fn live_subscription_handler(
                     false => ConnectionType::Default
                 };
                 if !subscribed.contains(&connection_type) {
-                    let register = StrategyRequest::OneWay(connection_type.clone(), DataServerRequest::Register(mode.clone()));
+                    let api_key = settings_map_ref.get(&connection_type).and_then(|settings| settings.api_key.clone());
+                    let register = StrategyRequest::OneWay(connection_type.clone(), DataServerRequest::Register{mode, api_key});
                     request_handler::send_request(register).await;
                     subscribed.push(connection_type.clone());
                 }
@@ -89,3 +91,16 @@ pub(crate) async fn live_subscription_handler(
         }
     });
 }
+
+/// Subscribes the primary subscriptions served by the connection again, used to resume the session after a dropped connection is restored.
+/// The server dropped the subscriptions of the old stream, so the subscriptions are sent as new subscriptions.
+pub(crate) async fn resubscribe(connection: &ConnectionType, subscription_handler: &Arc<SubscriptionHandler>) {
+    for subscription in &*subscription_handler.primary_subscriptions().await {
+        let connection_type = resolve_connection_type(ConnectionType::Vendor(subscription.symbol.data_vendor));
+        if connection_type != *connection {
+            continue;
+        }
+        let request = DataServerRequest::StreamRequest { request: StreamRequest::Subscribe(subscription.clone()) };
+        request_handler::send_request(StrategyRequest::OneWay(connection_type, request)).await;
+    }
+}
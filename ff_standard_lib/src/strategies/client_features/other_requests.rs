@@ -1,13 +1,16 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use tokio::sync::oneshot;
+use tokio::sync::Notify;
 use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
 use crate::product_maps::oanda::maps::{OANDA_FX_SYMBOLS};
 use crate::standardized_types::accounts::Currency;
 use crate::standardized_types::datavendor_enum::DataVendor;
 use crate::standardized_types::enums::OrderSide;
 use crate::strategies::client_features::connection_types::ConnectionType;
-use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::client_features::request_handler::callback_request;
 
 pub async fn get_exchange_rate(from_currency: Currency, to_currency: Currency, date_time: DateTime<Utc>, side: OrderSide) -> Result<Decimal, FundForgeError> {
     let currency_pair_string = format!("{}-{}", from_currency.to_string(), to_currency.to_string());
@@ -30,15 +33,95 @@ pub async fn get_exchange_rate(from_currency: Currency, to_currency: Currency, d
         side
     };
     //eprintln!("Getting exchange rate for {}-{} at {}", from_currency.to_string(), to_currency.to_string(), date_time);
-    let (sender, receiver) = oneshot::channel();
-    let msg = StrategyRequest::CallBack(ConnectionType::Vendor(data_vendor), request, sender);
-    send_request(msg).await;
-    match receiver.await {
+    match callback_request(ConnectionType::Vendor(data_vendor), request).await {
         Ok(response) => match response {
             DataServerResponse::ExchangeRate { rate, .. } => Ok(rate),
             DataServerResponse::Error { error, .. } => Err(error),
             _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
         },
-        Err(e) => Err(FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+        Err(e) => Err(e)
     }
-}
\ No newline at end of file
+}
+
+/// Cancels the requests wrapped with `cancellable()`, clones share the cancellation so one token can cancel a group of requests.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the pending requests using the token, requests started with a cancelled token are cancelled immediately.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes when the token is cancelled.
+    pub async fn cancelled(&self) {
+        let notified = self.notify.notified();
+        tokio::pin!(notified);
+        // register for the notification before checking the flag, so a cancel between the two is not missed
+        notified.as_mut().enable();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Waits for the request unless the token is cancelled first, then the request is dropped and `FundForgeError::RequestCancelled` is returned.
+/// The callback of a cancelled request is dropped by the request handler, so a late response from the server is ignored.
+/// ```rust,no_run
+/// use ff_standard_lib::apis::rithmic::rithmic_systems::RithmicSystem;
+/// use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+/// use ff_standard_lib::strategies::client_features::other_requests::{cancellable, CancellationToken};
+///
+/// # async fn example() {
+/// let token = CancellationToken::new();
+/// let info = cancellable(Brokerage::Rithmic(RithmicSystem::Apex).symbol_info("MNQ".to_string()), &token).await;
+/// # }
+/// ```
+pub async fn cancellable<F, T>(request: F, token: &CancellationToken) -> Result<T, FundForgeError>
+where
+    F: Future<Output = Result<T, FundForgeError>>,
+{
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(FundForgeError::RequestCancelled("Request cancelled before the response arrived".to_string())),
+        result = request => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancellable() {
+        let token = CancellationToken::new();
+        let result = cancellable(async { Ok::<u64, FundForgeError>(5) }, &token).await;
+        assert_eq!(result, Ok(5));
+
+        let cancel_token = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cancel_token.cancel();
+        });
+        let result = cancellable(std::future::pending::<Result<u64, FundForgeError>>(), &token).await;
+        assert!(matches!(result, Err(FundForgeError::RequestCancelled(_))));
+
+        // a request started with a cancelled token is never polled
+        let result = cancellable(async { Ok::<u64, FundForgeError>(5) }, &token).await;
+        assert!(matches!(result, Err(FundForgeError::RequestCancelled(_))));
+    }
+}
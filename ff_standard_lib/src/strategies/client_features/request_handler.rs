@@ -1,33 +1,59 @@
 use tokio::sync::{mpsc, oneshot};
 use dashmap::DashMap;
 use std::sync::Arc;
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use tokio::net::TcpStream;
 use tokio::sync::mpsc::Sender;
 use tokio_rustls::TlsStream;
-use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse};
+use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
 use crate::strategies::client_features::connection_types::ConnectionType;
-use crate::strategies::client_features::server_connections::SETTINGS_MAP;
+use crate::strategies::client_features::server_connections::{resolve_connection_type, SETTINGS_MAP};
 use tokio::io::{AsyncWriteExt, WriteHalf};
+use tokio::time::timeout;
 
 #[derive(Debug)]
 pub(crate) enum StrategyRequest {
     CallBack(ConnectionType, DataServerRequest, oneshot::Sender<DataServerResponse>),
     OneWay(ConnectionType, DataServerRequest),
+    /// A request answered with a sequence of responses, the responses are forwarded to the sender until the final response, see `STREAM_CALLBACKS`.
+    Stream(ConnectionType, DataServerRequest, Sender<DataServerResponse>),
+    /// The connection was re-established, the write half replaces the write half of the dropped connection.
+    Reconnected(ConnectionType, WriteHalf<TlsStream<TcpStream>>),
 }
 
 pub(crate) static DATA_SERVER_SENDER: OnceCell<Sender<StrategyRequest>> = OnceCell::new();
 
+/// The connection and sender of `StrategyRequest::Stream` requests by callback id, the response handler removes them when the final response arrives.
+pub(crate) static STREAM_CALLBACKS: Lazy<DashMap<u64, (ConnectionType, Sender<DataServerResponse>)>> = Lazy::new(DashMap::new);
+
 #[inline(always)]
 pub(crate) async fn send_request(req: StrategyRequest) {
     DATA_SERVER_SENDER.get().unwrap().send(req).await.unwrap();
 }
 
+/// Sends a request answered with a single response and waits for the response.
+/// Returns `FundForgeError::RequestTimedOut` if no response arrives within the `request_timeout_secs` of the connection settings,
+/// the callback of a timed out request is dropped by the request handler.
+pub(crate) async fn callback_request(connection_type: ConnectionType, request: DataServerRequest) -> Result<DataServerResponse, FundForgeError> {
+    let request_timeout = SETTINGS_MAP.get(&resolve_connection_type(connection_type.clone()))
+        .and_then(|settings| settings.request_timeout());
+    let (sender, receiver) = oneshot::channel();
+    send_request(StrategyRequest::CallBack(connection_type.clone(), request, sender)).await;
+    let result = match request_timeout {
+        Some(request_timeout) => match timeout(request_timeout, receiver).await {
+            Ok(result) => result,
+            Err(_) => return Err(FundForgeError::RequestTimedOut(format!("No response from {:?} server within {} seconds", connection_type, request_timeout.as_secs())))
+        },
+        None => receiver.await,
+    };
+    result.map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Receiver error at callback recv: {}", e)))
+}
+
 /// This response handler is also acting as a live engine.
 pub(crate) async fn request_handler(
     receiver: mpsc::Receiver<StrategyRequest>,
     server_senders: DashMap<ConnectionType, WriteHalf<TlsStream<TcpStream>>>,
-    callbacks: Arc<DashMap<u64, oneshot::Sender<DataServerResponse>>>,
+    callbacks: Arc<DashMap<u64, (ConnectionType, oneshot::Sender<DataServerResponse>)>>,
 ) {
     let mut receiver = receiver;
     let callbacks_ref = callbacks.clone();
@@ -38,16 +64,39 @@ pub(crate) async fn request_handler(
         let callbacks = callbacks_ref.clone();
         while let Some(outgoing_message) = receiver.recv().await {
             match outgoing_message {
+                StrategyRequest::Stream(connection_type, mut request, sender) => {
+                    callback_id_counter = callback_id_counter.wrapping_add(1);
+                    request.set_callback_id(callback_id_counter);
+                    let connection_type = match settings_map.contains_key(&connection_type) {
+                        true => connection_type,
+                        false => ConnectionType::Default
+                    };
+                    STREAM_CALLBACKS.insert(callback_id_counter, (connection_type.clone(), sender));
+                    if let Some(mut sender) = server_senders.get_mut(&connection_type) {
+                        let data = request.to_bytes();
+                        let mut prefixed_msg = Vec::with_capacity(4 + data.len());
+                        prefixed_msg.extend_from_slice(&(data.len() as u32).to_be_bytes());
+                        prefixed_msg.extend_from_slice(&data);
+                        if let Err(e) =  sender.value_mut().write_all(&prefixed_msg).await {
+                            tracing::error!("Error sending message: {:?}", e);
+                        }
+                        if let Err(e) = sender.flush().await {
+                            tracing::error!("Error flushing message: {:?}", e);
+                        }
+                    }
+                }
                 StrategyRequest::CallBack(connection_type, mut request, oneshot) => {
                     callback_id_counter = callback_id_counter.wrapping_add(1);
                     let callbacks = callbacks.clone();
                     let id = callback_id_counter.clone();
-                    callbacks.insert(id, oneshot);
                     request.set_callback_id(id.clone());
                     let connection_type = match settings_map.contains_key(&connection_type) {
                         true => connection_type,
                         false => ConnectionType::Default
                     };
+                    // the receivers of timed out and cancelled requests are dropped, so their callbacks will never be answered
+                    callbacks.retain(|_, (_, sender)| !sender.is_closed());
+                    callbacks.insert(id, (connection_type.clone(), oneshot));
                     if let Some(mut sender) = server_senders.get_mut(&connection_type) {
                         // Prepare the message with a 8-byte length header in big-endian format
                         let data = request.to_bytes();
@@ -56,12 +105,12 @@ pub(crate) async fn request_handler(
                         prefixed_msg.extend_from_slice(&data);
                         // Lock the mutex to get_requests mutable access
                         if let Err(e) =  sender.value_mut().write_all(&prefixed_msg).await {
-                            eprintln!("Error sending message: {:?}", e);
+                            tracing::error!("Error sending message: {:?}", e);
                         }
                         match sender.flush().await {
                             Ok(_) => {}
                             Err(e) => {
-                                eprintln!("Error flushing message: {:?}", e);
+                                tracing::error!("Error flushing message: {:?}", e);
                             }
                         }
                     }
@@ -79,16 +128,19 @@ pub(crate) async fn request_handler(
                         prefixed_msg.extend_from_slice(&data);
                         // Lock the mutex to get_requests mutable access
                         if let Err(e) =  sender.value_mut().write_all(&prefixed_msg).await {
-                            eprintln!("Error sending message: {:?}", e);
+                            tracing::error!("Error sending message: {:?}", e);
                         }
                         match sender.flush().await {
                             Ok(_) => {}
                             Err(e) => {
-                                eprintln!("Error flushing message: {:?}", e);
+                                tracing::error!("Error flushing message: {:?}", e);
                             }
                         }
                     }
                 }
+                StrategyRequest::Reconnected(connection_type, write_half) => {
+                    server_senders.insert(connection_type, write_half);
+                }
             }
         }
     });
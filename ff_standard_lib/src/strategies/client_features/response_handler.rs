@@ -1,33 +1,38 @@
 use std::time::Duration;
+use crate::strategies::handlers::chart_publisher::receive_chart_updates;
 use dashmap::DashMap;
 use tokio::io::{AsyncReadExt, ReadHalf};
 use tokio_rustls::TlsStream;
 use tokio::net::TcpStream;
 use std::sync::Arc;
 use tokio::sync::oneshot;
+use tokio::time::timeout;
 use tokio::sync::mpsc::Sender;
+use tracing::Instrument;
 use chrono::{DateTime, Utc};
 use std::str::FromStr;
-use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse};
+use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
 use crate::standardized_types::bytes_trait::Bytes;
 use crate::standardized_types::enums::StrategyMode;
 use crate::standardized_types::orders::OrderUpdateEvent;
 use crate::standardized_types::subscriptions::DataSubscriptionEvent;
 use crate::strategies::client_features::connection_types::ConnectionType;
 use crate::strategies::client_features::{live_data_receiver, request_handler};
-use crate::strategies::client_features::request_handler::StrategyRequest;
-use crate::strategies::client_features::server_connections::SETTINGS_MAP;
+use crate::strategies::client_features::request_handler::{StrategyRequest, STREAM_CALLBACKS};
+use crate::strategies::client_features::server_connections;
+use crate::strategies::client_features::server_connections::{Outage, SETTINGS_MAP};
 use crate::strategies::handlers::indicator_handler::IndicatorHandler;
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
 use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
 use crate::strategies::ledgers::ledger_service::LedgerService;
 use crate::strategies::strategy_events::StrategyEvent;
+use crate::helpers::logging::stream_span;
 
 pub async fn response_handler(
     mode: StrategyMode,
     buffer_duration: Duration,
     server_receivers: DashMap<ConnectionType, ReadHalf<TlsStream<TcpStream>>>,
-    callbacks: Arc<DashMap<u64, oneshot::Sender<DataServerResponse>>>,
+    callbacks: Arc<DashMap<u64, (ConnectionType, oneshot::Sender<DataServerResponse>)>>,
     order_updates_sender: Sender<(OrderUpdateEvent, DateTime<Utc>)>,
     synchronise_accounts: bool,
     strategy_event_sender: Sender<StrategyEvent>,
@@ -40,9 +45,18 @@ pub async fn response_handler(
     for (connection, settings) in settings_map.iter() {
         let order_updates_sender = order_updates_sender.clone();
         if let Some((connection, stream)) = server_receivers.remove(connection) {
-            let register_message = StrategyRequest::OneWay(connection.clone(), DataServerRequest::Register(mode.clone()));
+            let register_message = StrategyRequest::OneWay(connection.clone(), DataServerRequest::Register{mode, api_key: settings.api_key.clone()});
             request_handler::send_request(register_message).await;
 
+            // the strategy registry does not answer heartbeats, without heartbeats an idle server can't be told apart from a dead one
+            let heartbeat_timeout = match (connection != ConnectionType::StrategyRegistry, settings.heartbeat_interval()) {
+                (true, Some(interval)) => {
+                    tokio::task::spawn(send_heartbeats(connection.clone(), interval));
+                    settings.heartbeat_timeout()
+                }
+                _ => None
+            };
+
             let mut receiver = stream;
             let callbacks = callbacks.clone();
             let settings = settings.clone();
@@ -54,91 +68,194 @@ pub async fn response_handler(
             tokio::task::spawn(async move {
                 const LENGTH: usize = 8;
                 let mut length_bytes = [0u8; LENGTH];
-                while let Ok(_) = receiver.read_exact(&mut length_bytes).await {
-                    let msg_length = u64::from_be_bytes(length_bytes) as usize;
-                    let mut message_body = vec![0u8; msg_length];
+                // Set while a re-established connection waits for the server to register the strategy again.
+                let mut outage: Option<Outage> = None;
+                loop {
+                    let error = loop {
+                        if let Err(e) = read_length(&mut receiver, &mut length_bytes, heartbeat_timeout, &connection).await {
+                            break e;
+                        }
+                        let msg_length = u64::from_be_bytes(length_bytes) as usize;
+                        let mut message_body = vec![0u8; msg_length];
 
-                    match receiver.read_exact(&mut message_body).await {
-                        Ok(_) => {},
-                        Err(_) => {
-                            //eprintln!("Error reading message body: {}", e);
-                            continue;
+                        match receiver.read_exact(&mut message_body).await {
+                            Ok(_) => {},
+                            Err(_) => {
+                                //eprintln!("Error reading message body: {}", e);
+                                continue;
+                            }
                         }
-                    }
 
-                    let response = DataServerResponse::from_bytes(&message_body).unwrap();
-                    match response.get_callback_id() {
-                        None => {
-                            match response {
-                                DataServerResponse::SubscribeResponse { success, subscription, reason } => {
-                                    let event = if success {
-                                        DataSubscriptionEvent::Subscribed(subscription.clone())
-                                    } else {
-                                        DataSubscriptionEvent::FailedToSubscribe(subscription.clone(), reason.unwrap())
-                                    };
-                                    let event = StrategyEvent::DataSubscriptionEvent(event);
-                                    match strategy_event_sender.send(event).await {
-                                        Ok(_) => {}
-                                        Err(_) => {}
+                        let response = DataServerResponse::from_bytes(&message_body).unwrap();
+                        match response.get_callback_id() {
+                            None => {
+                                match response {
+                                    DataServerResponse::SubscribeResponse { success, subscription, reason } => {
+                                        let event = if success {
+                                            DataSubscriptionEvent::Subscribed(subscription.clone())
+                                        } else {
+                                            DataSubscriptionEvent::FailedToSubscribe(subscription.clone(), reason.unwrap_or_else(|| "No reason given by the data server".to_string()))
+                                        };
+                                        let event = StrategyEvent::DataSubscriptionEvent(event);
+                                        match strategy_event_sender.send(event).await {
+                                            Ok(_) => {}
+                                            Err(_) => {}
+                                        }
                                     }
-                                }
-                                DataServerResponse::UnSubscribeResponse { success, subscription, reason } => {
-                                    let event = if success {
-                                        DataSubscriptionEvent::Unsubscribed(subscription)
-                                    } else {
-                                        DataSubscriptionEvent::FailedUnSubscribed(subscription, reason.unwrap())
-                                    };
-                                    let event = StrategyEvent::DataSubscriptionEvent(event);
-                                    match strategy_event_sender.send(event).await {
-                                        Ok(_) => {}
-                                        Err(_) => {}
+                                    DataServerResponse::UnSubscribeResponse { success, subscription, reason } => {
+                                        let event = if success {
+                                            DataSubscriptionEvent::Unsubscribed(subscription)
+                                        } else {
+                                            DataSubscriptionEvent::FailedUnSubscribed(subscription, reason.unwrap_or_else(|| "No reason given by the data server".to_string()))
+                                        };
+                                        let event = StrategyEvent::DataSubscriptionEvent(event);
+                                        match strategy_event_sender.send(event).await {
+                                            Ok(_) => {}
+                                            Err(_) => {}
+                                        }
                                     }
-                                }
-                                DataServerResponse::OrderUpdates{ event, time} => {
-                                    //println!("Event received: {}", update_event);
-                                    let time = DateTime::<Utc>::from_str(&time).unwrap();
-                                    match order_updates_sender.send((event, time)).await {
-                                        Ok(_) => {}
-                                        Err(_) => {}//eprintln!("Order Update Sender Error: {}", e)
+                                    DataServerResponse::SubscriptionVendor { subscription, vendor, reason } => {
+                                        let event = StrategyEvent::DataSubscriptionEvent(DataSubscriptionEvent::ActiveVendor(subscription, vendor, reason));
+                                        let _ = strategy_event_sender.send(event).await;
                                     }
+                                    DataServerResponse::OrderUpdates{ event, time} => {
+                                        //println!("Event received: {}", update_event);
+                                        let time = DateTime::<Utc>::from_str(&time).unwrap();
+                                        match order_updates_sender.send((event, time)).await {
+                                            Ok(_) => {}
+                                            Err(_) => {}//tracing::error!("Order Update Sender Error: {}", e)
+                                        }
+                                    }
+                                    DataServerResponse::LiveAccountUpdates { account, cash_value, cash_available, cash_used } => {
+                                        let ledger_service = ledger_service.clone();
+                                        tokio::task::spawn(async move {
+                                            ledger_service.live_account_updates(&account, cash_value, cash_available, cash_used).await;
+                                        });
+                                    }
+                                    DataServerResponse::LivePositionUpdates { symbol_name, symbol_code, account, open_quantity, average_price, side, open_pnl, time } => {
+                                       if synchronise_accounts {
+                                           //println!("Live Position: {:?}", position);
+                                            //tokio::task::spawn(async move {
+                                            ledger_service.synchronize_live_position(symbol_name, symbol_code, account, open_quantity, average_price, side, open_pnl, time).await
+                                            //});
+                                        }
+                                    }
+                                    DataServerResponse::RegistrationResponse(port) => {
+                                        //println!("Connected to server port: {}", port);
+                                        if mode != StrategyMode::Backtest {
+                                            live_data_receiver::handle_live_data(connection.clone(), settings.clone(), port, buffer_duration, strategy_event_sender.clone(), ledger_service.clone(), indicator_handler.clone(), subscription_handler.clone(), market_price_service.clone())
+                                                .instrument(stream_span(port))
+                                                .await;
+                                        }
+                                        if let Some(outage) = outage.take() {
+                                            tokio::task::spawn(server_connections::resume_session(outage, mode, subscription_handler.clone(), ledger_service.clone(), strategy_event_sender.clone()));
+                                        }
+                                    }
+                                    DataServerResponse::Heartbeat => {}
+                                    DataServerResponse::ChartUpdates { chart_name, updates } => {
+                                        receive_chart_updates(chart_name, updates);
+                                    }
+                                    DataServerResponse::CustomMessage { topic, payload } => {
+                                        if let Err(e) = strategy_event_sender.send(StrategyEvent::CustomMessage { topic, payload }).await {
+                                            tracing::error!("Failed to forward custom message: {}", e);
+                                        }
+                                    }
+                                    _ => unreachable!("Incorrect response here: {:?}", response)
                                 }
-                                DataServerResponse::LiveAccountUpdates { account, cash_value, cash_available, cash_used } => {
-                                    let ledger_service = ledger_service.clone();
-                                    tokio::task::spawn(async move {
-                                        ledger_service.live_account_updates(&account, cash_value, cash_available, cash_used).await;
-                                    });
+                            }
+                            Some(id) => {
+                                //eprintln!("Response with callback id: {}", id); //todo: remove this after debugging historical data deadlock
+                                if let DataServerResponse::Error { error: FundForgeError::InvalidApiKey, .. } = &response {
+                                    tracing::error!("{:?} server rejected the api key, check `api_key` in the connection settings", connection);
+                                    continue;
                                 }
-                                DataServerResponse::LivePositionUpdates { symbol_name, symbol_code, account, open_quantity, average_price, side, open_pnl, time } => {
-                                   if synchronise_accounts {
-                                       //println!("Live Position: {:?}", position);
-                                        //tokio::task::spawn(async move {
-                                        ledger_service.synchronize_live_position(symbol_name, symbol_code, account, open_quantity, average_price, side, open_pnl, time).await
-                                        //});
+                                if let Some(stream_sender) = STREAM_CALLBACKS.get(&id).map(|entry| entry.value().1.clone()) {
+                                    let is_final = !matches!(response, DataServerResponse::HistoricalDataPart { .. });
+                                    if is_final {
+                                        STREAM_CALLBACKS.remove(&id);
                                     }
-                                }
-                                DataServerResponse::RegistrationResponse(port) => {
-                                    //println!("Connected to server port: {}", port);
-                                    if mode != StrategyMode::Backtest {
-                                        live_data_receiver::handle_live_data(settings.clone(), port, buffer_duration, strategy_event_sender.clone(), ledger_service.clone(), indicator_handler.clone(), subscription_handler.clone(), market_price_service.clone()).await;
+                                    // the server only sends `HISTORY_PARTS_IN_FLIGHT` parts ahead of the acknowledgements, so this does not block the connection.
+                                    if stream_sender.send(response).await.is_err() {
+                                        STREAM_CALLBACKS.remove(&id);
                                     }
+                                    continue;
                                 }
-                                _ => unreachable!("Incorrect response here: {:?}", response)
-                            }
-                        }
-                        Some(id) => {
-                            //eprintln!("Response with callback id: {}", id); //todo: remove this after debugging historical data deadlock
-                            if let Some((_, callback_sender)) = callbacks.remove(&id) {
-                                match callback_sender.send(response) {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Error sending callback: {:?}", e)
+                                if let Some((_, (_, callback_sender))) = callbacks.remove(&id) {
+                                    // the receiver is dropped if the request timed out or was cancelled
+                                    let _ = callback_sender.send(response);
+                                } else {
+                                    tracing::error!("No callback found for id: {}", id);
                                 }
-                            } else {
-                                eprintln!("No callback found for id: {}", id);
                             }
                         }
+                    };
+                    fail_pending_requests(&connection, &callbacks, &error).await;
+                    let (new_receiver, new_outage) = server_connections::reconnect(&connection, &settings, mode, &error, &strategy_event_sender).await;
+                    receiver = new_receiver;
+                    match mode {
+                        // backtests have no live stream so the server does not send a registration response
+                        StrategyMode::Backtest => {
+                            tokio::task::spawn(server_connections::resume_session(new_outage, mode, subscription_handler.clone(), ledger_service.clone(), strategy_event_sender.clone()));
+                        }
+                        _ => outage = Some(new_outage),
                     }
                 }
             });
         }
     }
-}
\ No newline at end of file
+}
+/// Sends a heartbeat to the server every interval, the request handler writes it to the current connection so heartbeats continue after a reconnect.
+async fn send_heartbeats(connection: ConnectionType, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        request_handler::send_request(StrategyRequest::OneWay(connection.clone(), DataServerRequest::Heartbeat)).await;
+    }
+}
+
+/// Reads the length prefix of the next response, a server that sends nothing, not even a heartbeat, within the `heartbeat_timeout` is treated as dead.
+async fn read_length(
+    receiver: &mut ReadHalf<TlsStream<TcpStream>>,
+    length_bytes: &mut [u8; 8],
+    heartbeat_timeout: Option<Duration>,
+    connection: &ConnectionType
+) -> Result<(), FundForgeError> {
+    let result = match heartbeat_timeout {
+        Some(heartbeat_timeout) => match timeout(heartbeat_timeout, receiver.read_exact(length_bytes)).await {
+            Ok(result) => result,
+            Err(_) => return Err(FundForgeError::ConnectionNotFound(format!("No heartbeat from {:?} server for {} seconds", connection, heartbeat_timeout.as_secs())))
+        },
+        None => receiver.read_exact(length_bytes).await,
+    };
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(FundForgeError::ConnectionNotFound(format!("Connection to {:?} server lost: {}", connection, e)))
+    }
+}
+
+/// Answers the requests still waiting on a response from the dropped connection with the error, so they fail instead of waiting forever.
+async fn fail_pending_requests(
+    connection: &ConnectionType,
+    callbacks: &DashMap<u64, (ConnectionType, oneshot::Sender<DataServerResponse>)>,
+    error: &FundForgeError
+) {
+    let pending: Vec<u64> = callbacks.iter()
+        .filter(|entry| entry.value().0 == *connection)
+        .map(|entry| *entry.key())
+        .collect();
+    for callback_id in pending {
+        if let Some((_, (_, callback_sender))) = callbacks.remove(&callback_id) {
+            let _ = callback_sender.send(DataServerResponse::Error { callback_id, error: error.clone() });
+        }
+    }
+
+    let pending: Vec<u64> = STREAM_CALLBACKS.iter()
+        .filter(|entry| entry.value().0 == *connection)
+        .map(|entry| *entry.key())
+        .collect();
+    for callback_id in pending {
+        if let Some((_, (_, stream_sender))) = STREAM_CALLBACKS.remove(&callback_id) {
+            let _ = stream_sender.send(DataServerResponse::Error { callback_id, error: error.clone() }).await;
+        }
+    }
+}
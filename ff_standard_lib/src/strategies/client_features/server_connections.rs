@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use crate::strategies::client_features::init_clients::create_async_api_client;
 use crate::strategies::client_features::connection_settings::client_settings::{initialise_settings, ConnectionSettings};
-use crate::messages::data_server_messaging::DataServerResponse;
+use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
@@ -17,11 +17,13 @@ use tokio_rustls::TlsStream;
 use crate::strategies::client_features::connection_types::ConnectionType;
 use crate::strategies::handlers::indicator_handler::IndicatorHandler;
 use crate::standardized_types::enums::StrategyMode;
-use crate::strategies::strategy_events::StrategyEvent;
+use crate::strategies::strategy_events::{ConnectionEvent, StrategyEvent};
+use crate::strategies::notifications::{notify_engine_event, NotificationLevel};
+use crate::standardized_types::accounts::Account;
 use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
 use crate::standardized_types::orders::OrderUpdateEvent;
-use crate::strategies::client_features::{request_handler, response_handler};
-use crate::strategies::client_features::request_handler::DATA_SERVER_SENDER;
+use crate::strategies::client_features::{live_subscriptions, request_handler, response_handler};
+use crate::strategies::client_features::request_handler::{StrategyRequest, DATA_SERVER_SENDER};
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
 use crate::strategies::ledgers::ledger_service::LedgerService;
 
@@ -30,6 +32,12 @@ lazy_static! {
     pub(crate) static ref SETTINGS_MAP: Arc<HashMap<ConnectionType, ConnectionSettings>> = Arc::new(initialise_settings().unwrap());
 }
 
+/// The delay before the first reconnection attempt, doubled after each failed attempt up to `MAX_RECONNECT_DELAY`.
+pub(crate) const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+/// Gives the server time to register the new live stream before the subscriptions are sent again.
+pub(crate) const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
 #[inline(always)]
 pub(crate) fn set_warmup_complete() {
     WARM_UP_COMPLETE.store(true, Ordering::SeqCst);
@@ -62,7 +70,7 @@ pub(crate) async fn init_connections(
         // set up async client
         let async_client = match create_async_api_client(&settings, false).await {
             Ok(client) => client,
-            Err(__e) => { eprintln!("{}", format!("Unable to establish connection to: {:?} server @ address: {:?}", connection_type, settings));
+            Err(__e) => { tracing::error!("{}", format!("Unable to establish connection to: {:?} server @ address: {:?}", connection_type, settings));
                 continue;
             }
         };
@@ -75,7 +83,124 @@ pub(crate) async fn init_connections(
         tx
     }).clone();
 
-    let callbacks: Arc<DashMap<u64, oneshot::Sender<DataServerResponse>>> = Default::default();
+    let callbacks: Arc<DashMap<u64, (ConnectionType, oneshot::Sender<DataServerResponse>)>> = Default::default();
     request_handler::request_handler(rx, server_senders, callbacks.clone()).await;
     response_handler::response_handler(mode, buffer_duration, server_receivers, callbacks, order_updates_sender, synchronise_accounts, strategy_event_sender, ledger_service, indicator_handler, subscription_handler, market_price_service).await;
 }
+
+/// The connection that serves a connection type, connection types not specified in the server_settings.toml are served by the `Default` connection.
+pub(crate) fn resolve_connection_type(connection_type: ConnectionType) -> ConnectionType {
+    match SETTINGS_MAP.contains_key(&connection_type) {
+        true => connection_type,
+        false => ConnectionType::Default
+    }
+}
+
+/// The delay before the next reconnection attempt.
+pub(crate) fn next_reconnect_delay(delay: Duration) -> Duration {
+    (delay * 2).min(MAX_RECONNECT_DELAY)
+}
+
+/// A dropped connection which was re-established, the session is resumed with `resume_session()`.
+pub(crate) struct Outage {
+    pub connection: ConnectionType,
+    pub disconnected_at: DateTime<Utc>,
+    pub reconnected_at: DateTime<Utc>,
+    pub attempts: u32,
+}
+
+/// Reconnects a dropped connection, retrying with an exponential backoff until the server accepts the connection.
+/// The new write half replaces the old one in the request handler and the strategy is registered with the server again,
+/// the read half is returned so the response handler can continue reading from the new connection.
+pub(crate) async fn reconnect(
+    connection: &ConnectionType,
+    settings: &ConnectionSettings,
+    mode: StrategyMode,
+    reason: &FundForgeError,
+    strategy_event_sender: &Sender<StrategyEvent>
+) -> (ReadHalf<TlsStream<TcpStream>>, Outage) {
+    let disconnected_at = Utc::now();
+    tracing::warn!("{}, reconnecting", reason);
+    if mode != StrategyMode::Backtest {
+        notify_engine_event(NotificationLevel::Critical, format!("{:?}: {}, reconnecting", connection, reason));
+    }
+    let event = ConnectionEvent::Disconnected { connection: format!("{:?}", connection), time: disconnected_at.to_string(), reason: reason.to_string() };
+    let _ = strategy_event_sender.send(StrategyEvent::ConnectionEvent(event)).await;
+
+    let mut delay = INITIAL_RECONNECT_DELAY;
+    let mut attempts: u32 = 0;
+    loop {
+        tokio::time::sleep(delay).await;
+        attempts += 1;
+        match create_async_api_client(settings, false).await {
+            Ok(client) => {
+                let (read_half, write_half) = io::split(client);
+                request_handler::send_request(StrategyRequest::Reconnected(connection.clone(), write_half)).await;
+                request_handler::send_request(StrategyRequest::OneWay(connection.clone(), DataServerRequest::Register{mode, api_key: settings.api_key.clone()})).await;
+                let outage = Outage {
+                    connection: connection.clone(),
+                    disconnected_at,
+                    reconnected_at: Utc::now(),
+                    attempts,
+                };
+                return (read_half, outage);
+            }
+            Err(e) => {
+                tracing::error!("Unable to reconnect to {:?} server, attempt {}: {}", connection, attempts, e);
+                delay = next_reconnect_delay(delay);
+            }
+        }
+    }
+}
+
+/// Resumes the session of a re-established connection once the server has registered the strategy again.
+/// In live modes the primary subscriptions served by the connection are subscribed again and the accounts of live ledgers served by the connection are refreshed,
+/// then a `ConnectionEvent::Reconnected` describing the outage is sent to the strategy.
+pub(crate) async fn resume_session(
+    outage: Outage,
+    mode: StrategyMode,
+    subscription_handler: Arc<SubscriptionHandler>,
+    ledger_service: Arc<LedgerService>,
+    strategy_event_sender: Sender<StrategyEvent>
+) {
+    if mode != StrategyMode::Backtest {
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+        live_subscriptions::resubscribe(&outage.connection, &subscription_handler).await;
+    }
+    if mode == StrategyMode::Live {
+        let accounts: Vec<Account> = ledger_service.ledgers.iter()
+            .filter(|ledger| ledger.value().mode == StrategyMode::Live)
+            .map(|ledger| ledger.key().clone())
+            .filter(|account| resolve_connection_type(ConnectionType::Broker(account.brokerage)) == outage.connection)
+            .collect();
+        for account in accounts {
+            if let Err(e) = ledger_service.refresh_live_account(&account).await {
+                tracing::error!("Unable to refresh account {} after reconnecting: {}", account, e);
+            }
+        }
+    }
+    let event = ConnectionEvent::Reconnected {
+        connection: format!("{:?}", outage.connection),
+        disconnected_at: outage.disconnected_at.to_string(),
+        reconnected_at: outage.reconnected_at.to_string(),
+        outage_seconds: (outage.reconnected_at - outage.disconnected_at).num_seconds(),
+        attempts: outage.attempts,
+    };
+    let _ = strategy_event_sender.send(StrategyEvent::ConnectionEvent(event)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_reconnect_delay() {
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        let mut delays = vec![];
+        for _ in 0..8 {
+            delays.push(delay.as_secs());
+            delay = next_reconnect_delay(delay);
+        }
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+    }
+}
@@ -0,0 +1,151 @@
+use std::fs::{self, create_dir_all};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::history::get_data_version;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::statistics::backtest_cache::stable_hash;
+
+static BAR_CACHE: OnceLock<BarCache> = OnceLock::new();
+
+/// Enables the consolidated bar cache, this must be called before `FundForgeStrategy::initialize()`.
+///
+/// Consolidating the warm up history or a history range of a consolidated subscription, eg. 15 minute Heikin Ashi candles from ticks,
+/// saves the closed bars to the `folder`, keyed by the subscription, the consolidator parameters, the source subscription, the range and the version of the source data on the server.
+/// Later runs with identical inputs load the bars instead of downloading and consolidating the source data again,
+/// only the source data after the last cached bar is consolidated so the open bar of the consolidator is the same as without the cache.
+///
+/// Changing the stored source data, the fill policy, the trading hours or the version of the library invalidates the cached bars.
+pub fn enable_bar_cache(folder: &str) -> Result<(), FundForgeError> {
+    create_dir_all(folder)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create directory {}: {}", folder, e)))?;
+    BAR_CACHE.set(BarCache::new(folder))
+        .map_err(|_| FundForgeError::ClientSideErrorDebug("The bar cache is already enabled".to_string()))
+}
+
+pub(crate) fn bar_cache() -> Option<&'static BarCache> {
+    BAR_CACHE.get()
+}
+
+/// Everything which determines the bars of a consolidator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BarCacheKey {
+    pub subscription: String,
+    /// The parameters of the consolidator which are not part of the subscription, see `ConsolidatorEnum::parameters()`.
+    pub parameters: String,
+    pub source: String,
+    pub from: String,
+    pub to: String,
+    pub data_version: u64,
+    pub library_version: String,
+}
+
+impl BarCacheKey {
+    pub fn new(subscription: &DataSubscription, parameters: &str, source: &DataSubscription, from: DateTime<Utc>, to: DateTime<Utc>, data_version: u64) -> Self {
+        Self {
+            subscription: subscription.to_string(),
+            parameters: parameters.to_string(),
+            source: source.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            data_version,
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    pub fn hash(&self) -> String {
+        let key = serde_json::to_string(self).unwrap_or_default();
+        format!("{:016x}", stable_hash(key.as_bytes()))
+    }
+}
+
+pub(crate) struct BarCache {
+    folder: PathBuf,
+}
+
+impl BarCache {
+    fn new(folder: &str) -> Self {
+        Self {
+            folder: PathBuf::from(folder),
+        }
+    }
+
+    /// The key is saved next to the bars, so a hash collision is not mistaken for a hit.
+    fn paths(&self, key: &BarCacheKey) -> (PathBuf, PathBuf) {
+        let hash = key.hash();
+        (self.folder.join(format!("{}.json", hash)), self.folder.join(format!("{}.bars", hash)))
+    }
+
+    /// Creates the key for the consolidated bars, requesting the version of the source data from the server.
+    pub(crate) async fn key(&self, subscription: &DataSubscription, parameters: &str, source: &DataSubscription, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<BarCacheKey, FundForgeError> {
+        let data_version = get_data_version(vec![source.clone()], from, to).await?;
+        Ok(BarCacheKey::new(subscription, parameters, source, from, to, data_version))
+    }
+
+    /// The closed bars saved under the key, `None` if the bars were not found.
+    pub(crate) fn load(&self, key: &BarCacheKey) -> Option<Vec<BaseDataEnum>> {
+        let (key_path, bars_path) = self.paths(key);
+        let saved_key = serde_json::from_str::<BarCacheKey>(&fs::read_to_string(&key_path).ok()?).ok()?;
+        if &saved_key != key {
+            return None;
+        }
+        let bytes = fs::read(&bars_path).ok()?;
+        match BaseDataEnum::from_array_bytes(&bytes) {
+            Ok(bars) => Some(bars),
+            Err(e) => {
+                tracing::error!("Bar Cache: Failed to parse {}: {}", bars_path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Saves the closed bars under the key, the bars are written before the key so an interrupted write is never loaded.
+    pub(crate) fn store(&self, key: &BarCacheKey, bars: &[BaseDataEnum]) -> Result<(), FundForgeError> {
+        let (key_path, bars_path) = self.paths(key);
+        fs::write(&bars_path, BaseDataEnum::vec_to_bytes(bars.to_vec()))
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to write {}: {}", bars_path.display(), e)))?;
+        let content = serde_json::to_string_pretty(key)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to serialize bar cache key: {}", e)))?;
+        fs::write(&key_path, content)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to write {}: {}", key_path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::candle::Candle;
+    use crate::standardized_types::base_data::base_data_type::BaseDataType;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::resolution::Resolution;
+    use crate::standardized_types::subscriptions::CandleType;
+
+    #[test]
+    fn test_store_and_load_bars() {
+        let folder = tempfile::tempdir().unwrap();
+        let cache = BarCache::new(folder.path().to_str().unwrap());
+        let market_type = MarketType::Futures(FuturesExchange::CME);
+        let subscription = DataSubscription::new_custom("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(15), market_type, CandleType::HeikinAshi);
+        let source = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Ticks(1), BaseDataType::Ticks, market_type);
+        let from = Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2024, 6, 4, 0, 0, 0).unwrap();
+        let key = BarCacheKey::new(&subscription, "fill=None", &source, from, to, 1);
+        assert_ne!(key.hash(), BarCacheKey::new(&subscription, "fill=None", &source, from, to, 2).hash());
+        assert_ne!(key.hash(), BarCacheKey::new(&subscription, "fill=LastClose", &source, from, to, 1).hash());
+        assert!(cache.load(&key).is_none());
+
+        let mut candle = Candle::new(subscription.symbol.clone(), dec!(100), dec!(5), dec!(2), dec!(3), from.to_string(), Resolution::Minutes(15), CandleType::HeikinAshi);
+        candle.is_closed = true;
+        let bars = vec![BaseDataEnum::Candle(candle)];
+        cache.store(&key, &bars).unwrap();
+        assert_eq!(cache.load(&key), Some(bars));
+        // A different data version is a miss
+        assert!(cache.load(&BarCacheKey::new(&subscription, "fill=None", &source, from, to, 2)).is_none());
+    }
+}
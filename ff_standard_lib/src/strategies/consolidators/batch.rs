@@ -0,0 +1,252 @@
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::helpers::converters::open_time;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::data_quality::DataQuality;
+use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::subscriptions::{CandleType, DataSubscription};
+
+/// Consolidates large arrays of historical ticks into candles in a single pass, for pre-processing data before a backtest
+/// rather than consolidating one data point at a time.
+///
+/// The result is the same as the closed candles of a `CandleStickConsolidator` updated with each tick, without the per tick clones and time parsing,
+/// the only allocations are the output candles.
+/// The last candle is returned open if the ticks end before it closes.
+///
+/// `tick_size` and `decimal_accuracy` are used to round the candle range, see `DataVendor::tick_size()` and `DataVendor::decimal_accuracy()`.
+#[derive(Clone, Debug)]
+pub struct BatchConsolidator {
+    subscription: DataSubscription,
+    tick_size: Decimal,
+    decimal_accuracy: u32,
+}
+
+/// The candle being built, the time string and symbol are only created when the candle is finished.
+struct OpenCandle {
+    open_time: DateTime<Utc>,
+    close_time: DateTime<Utc>,
+    open: Price,
+    high: Price,
+    low: Price,
+    close: Price,
+    volume: Volume,
+    ask_volume: Volume,
+    bid_volume: Volume,
+    quality: DataQuality,
+}
+
+impl BatchConsolidator {
+    pub fn new(subscription: DataSubscription, tick_size: Decimal, decimal_accuracy: u32) -> Result<Self, FundForgeError> {
+        match subscription.resolution {
+            Resolution::Seconds(_) | Resolution::Minutes(_) | Resolution::Hours(_) => {}
+            _ => return Err(FundForgeError::ClientSideErrorDebug(format!(
+                "{:?} is an Invalid resolution for BatchConsolidator",
+                subscription.resolution
+            )))
+        }
+        if subscription.candle_type != Some(CandleType::CandleStick) {
+            return Err(FundForgeError::ClientSideErrorDebug(format!(
+                "{} is an Invalid subscription for BatchConsolidator, only candlesticks are supported",
+                subscription
+            )))
+        }
+        Ok(Self {
+            subscription,
+            tick_size,
+            decimal_accuracy,
+        })
+    }
+
+    pub fn subscription(&self) -> &DataSubscription {
+        &self.subscription
+    }
+
+    /// Consolidates the ticks, which must be in time order, ticks before the open of the current candle are ignored.
+    pub fn consolidate_ticks(&self, ticks: &[Tick]) -> Result<Vec<Candle>, FundForgeError> {
+        let resolution = self.subscription.resolution.as_duration();
+        let mut candles = Vec::with_capacity(estimated_candles(ticks, resolution));
+        let mut current: Option<OpenCandle> = None;
+        let mut last_bar_open = DateTime::<Utc>::MIN_UTC;
+        for tick in ticks {
+            let time = DateTime::<Utc>::from_str(&tick.time)
+                .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Invalid tick time {}: {}", tick.time, e)))?;
+            if let Some(candle) = current.as_mut() {
+                if time < candle.open_time {
+                    continue;
+                }
+                if time < candle.close_time {
+                    candle.high = candle.high.max(tick.price);
+                    candle.low = candle.low.min(tick.price);
+                    candle.close = tick.price;
+                    match tick.aggressor {
+                        Aggressor::Buy => candle.bid_volume += tick.volume,
+                        Aggressor::Sell => candle.ask_volume += tick.volume,
+                        Aggressor::None => {}
+                    }
+                    candle.volume += tick.volume;
+                    candle.quality.merge(&tick.quality);
+                    continue;
+                }
+            }
+            if let Some(candle) = current.take() {
+                candles.push(self.finish(candle, true));
+            }
+            let mut open_time = open_time(&self.subscription, time);
+            if open_time == last_bar_open {
+                open_time += resolution;
+            }
+            last_bar_open = open_time;
+            let (ask_volume, bid_volume) = match tick.aggressor {
+                Aggressor::Buy => (dec!(0), tick.volume),
+                Aggressor::Sell => (tick.volume, dec!(0)),
+                Aggressor::None => (dec!(0), dec!(0)),
+            };
+            current = Some(OpenCandle {
+                open_time,
+                close_time: open_time + resolution,
+                open: tick.price,
+                high: tick.price,
+                low: tick.price,
+                close: tick.price,
+                volume: tick.volume,
+                ask_volume,
+                bid_volume,
+                quality: tick.quality,
+            });
+        }
+        if let Some(candle) = current {
+            candles.push(self.finish(candle, false));
+        }
+        Ok(candles)
+    }
+
+    fn finish(&self, candle: OpenCandle, is_closed: bool) -> Candle {
+        let mut quality = candle.quality;
+        quality.insert(DataQuality::CONSOLIDATED);
+        Candle {
+            symbol: self.subscription.symbol.clone(),
+            high: candle.high,
+            low: candle.low,
+            open: candle.open,
+            close: candle.close,
+            volume: candle.volume,
+            ask_volume: candle.ask_volume,
+            bid_volume: candle.bid_volume,
+            range: self.subscription.market_type.round_price(candle.high - candle.low, self.tick_size, self.decimal_accuracy),
+            time: candle.open_time.to_string(),
+            is_closed,
+            resolution: self.subscription.resolution,
+            candle_type: CandleType::CandleStick,
+            quality,
+        }
+    }
+}
+
+/// Consolidates the ticks of each partition, eg. one partition per symbol, the results are in the same order as the partitions.
+/// If `parallel` is true the partitions are consolidated on the rayon thread pool.
+pub fn consolidate_partitions(partitions: &[(BatchConsolidator, &[Tick])], parallel: bool) -> Vec<Result<Vec<Candle>, FundForgeError>> {
+    match parallel {
+        true => partitions.par_iter().map(|(consolidator, ticks)| consolidator.consolidate_ticks(ticks)).collect(),
+        false => partitions.iter().map(|(consolidator, ticks)| consolidator.consolidate_ticks(ticks)).collect(),
+    }
+}
+
+/// An upper estimate of the number of candles from the first and last tick times, so the output is allocated once.
+fn estimated_candles(ticks: &[Tick], resolution: chrono::Duration) -> usize {
+    let (first, last) = match (ticks.first(), ticks.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return 0,
+    };
+    match (DateTime::<Utc>::from_str(&first.time), DateTime::<Utc>::from_str(&last.time)) {
+        (Ok(first), Ok(last)) if resolution.num_seconds() > 0 => {
+            let span = (last - first).num_seconds().max(0) / resolution.num_seconds();
+            (span as usize + 1).min(ticks.len())
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use chrono::{Duration, TimeZone};
+    use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+    use crate::standardized_types::base_data::base_data_type::BaseDataType;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::strategies::consolidators::candlesticks::CandleStickConsolidator;
+    use crate::standardized_types::subscriptions::FillPolicy;
+
+    fn subscription() -> DataSubscription {
+        DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(1), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME))
+    }
+
+    fn ticks(count: i64) -> Vec<Tick> {
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 13, 30, 0).unwrap();
+        let symbol = subscription().symbol;
+        (0..count).map(|i| {
+            let price = dec!(18000) + Decimal::from(i % 37) * dec!(0.25) - Decimal::from(i % 11) * dec!(0.5);
+            let aggressor = match i % 3 {
+                0 => Aggressor::Buy,
+                1 => Aggressor::Sell,
+                _ => Aggressor::None,
+            };
+            Tick::new(symbol.clone(), price, (start + Duration::milliseconds(i * 250)).to_string(), Decimal::from(1 + i % 4), aggressor)
+        }).collect()
+    }
+
+    /// The closed candles from the per data point consolidator.
+    async fn update_loop(ticks: &[Tick]) -> Vec<Candle> {
+        let mut consolidator = CandleStickConsolidator::new(subscription(), FillPolicy::None, None, 2, dec!(0.25)).await.unwrap();
+        let mut candles = vec![];
+        for tick in ticks {
+            if let Some(BaseDataEnum::Candle(candle)) = consolidator.update(&BaseDataEnum::Tick(tick.clone())).unwrap().closed_data {
+                candles.push(candle);
+            }
+        }
+        candles
+    }
+
+    #[tokio::test]
+    async fn test_batch_matches_update_loop() {
+        let ticks = ticks(2_000);
+        let batch = BatchConsolidator::new(subscription(), dec!(0.25), 2).unwrap();
+        let mut candles = batch.consolidate_ticks(&ticks).unwrap();
+        assert!(!candles.pop().unwrap().is_closed);
+        assert_eq!(candles, update_loop(&ticks).await);
+
+        let results = consolidate_partitions(&[(batch.clone(), &ticks[..1_000]), (batch, &ticks[1_000..])], true);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().len(), 5);
+    }
+
+    /// Run with `cargo test --release -p ff_standard_lib bench_batch_consolidation -- --ignored --nocapture`.
+    #[tokio::test]
+    #[ignore]
+    async fn bench_batch_consolidation() {
+        let ticks = ticks(1_000_000);
+        let start = Instant::now();
+        let loop_candles = update_loop(&ticks).await;
+        let loop_time = start.elapsed();
+
+        let batch = BatchConsolidator::new(subscription(), dec!(0.25), 2).unwrap();
+        let start = Instant::now();
+        let batch_candles = batch.consolidate_ticks(&ticks).unwrap();
+        let batch_time = start.elapsed();
+
+        let partitions: Vec<(BatchConsolidator, &[Tick])> = ticks.chunks(250_000).map(|chunk| (batch.clone(), chunk)).collect();
+        let start = Instant::now();
+        consolidate_partitions(&partitions, true);
+        let parallel_time = start.elapsed();
+
+        assert_eq!(loop_candles.len() + 1, batch_candles.len());
+        println!("update loop: {:?}, batch: {:?}, 4 partitions in parallel: {:?}", loop_time, batch_time, parallel_time);
+    }
+}
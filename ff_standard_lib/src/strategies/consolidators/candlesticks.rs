@@ -3,14 +3,15 @@ use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::base_data::candle::Candle;
 use crate::standardized_types::base_data::quotebar::QuoteBar;
+use crate::standardized_types::base_data::data_quality::DataQuality;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::enums::{MarketType};
-use crate::standardized_types::subscriptions::{CandleType, DataSubscription};
+use crate::standardized_types::subscriptions::{CandleType, DataSubscription, FillPolicy};
+use crate::standardized_types::market_hours::TradingHours;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use crate::strategies::consolidators::consolidator_enum::ConsolidatedData;
-use crate::helpers::converters::open_time;
+use crate::strategies::consolidators::consolidator_enum::{hours_parameter, ConsolidatedData};
 use crate::messages::data_server_messaging::FundForgeError;
 use crate::standardized_types::base_data::tick::Aggressor;
 use crate::standardized_types::new_types::Price;
@@ -24,12 +25,24 @@ pub struct CandleStickConsolidator {
     last_close: Option<Price>,
     last_ask_close: Option<Price>,
     last_bid_close: Option<Price>,
-    fill_forward: bool,
+    fill_policy: FillPolicy,
+    hours: Option<TradingHours>,
     market_type: MarketType,
     last_bar_open: DateTime<Utc>,
 }
 
 impl CandleStickConsolidator {
+    /// The parameters which change the bars of the consolidator but are not part of the subscription, used to key the `BarCache`.
+    pub(crate) fn parameters(&self) -> String {
+        format!("fill_policy={:?};hours={};decimal_accuracy={};tick_size={}", self.fill_policy, hours_parameter(self.hours.as_ref()), self.decimal_accuracy, self.tick_size)
+    }
+
+    /// Continues from a closed bar of an earlier run, so the consolidator fills forward from its close.
+    pub(crate) fn resume(&mut self, last_bar: &BaseDataEnum) {
+        self.record_close(last_bar);
+        self.last_bar_open = last_bar.time_utc();
+    }
+
     pub fn update_time(&mut self, time: DateTime<Utc>) -> Option<BaseDataEnum> {
         if let Some(current_bar) = &self.current_data {
             if time < current_bar.time_utc() {
@@ -41,6 +54,7 @@ impl CandleStickConsolidator {
                 let mut return_data = current_data.clone();
                 return_data.set_is_closed(true);
                 self.current_data = None;
+                self.record_close(&return_data);
                 self.fill_forward(time);
                 return Some(return_data)
             }
@@ -50,92 +64,126 @@ impl CandleStickConsolidator {
         None
     }
 
-    fn fill_forward(&mut self, time:DateTime<Utc>) {
-        if self.fill_forward {
-            match self.subscription.base_data_type {
-                BaseDataType::QuoteBars => {
-                    if let (Some(last_bid_close), Some(last_ask_close)) = (self.last_bid_close, self.last_ask_close) {
-                        let mut time = converters::open_time(&self.subscription, time);
-                        if time == self.last_bar_open {
-                            time += self.subscription.resolution.as_duration();
-                        }
-                        self.last_bar_open = time.clone();
-                        let spread = self.market_type.round_price(last_ask_close - last_bid_close, self.tick_size, self.decimal_accuracy);
+    /// Fills the period after the last bar according to the fill policy, once the consolidator has a close to fill from.
+    fn fill_forward(&mut self, time: DateTime<Utc>) {
+        let mut open = converters::open_time(&self.subscription, time);
+        if open == self.last_bar_open {
+            open += self.subscription.resolution.as_duration();
+        }
+        if !self.fill_policy.should_fill(open, self.hours.as_ref()) {
+            return;
+        }
+        let empty = self.fill_policy.is_empty();
+        let quality = match empty {
+            true => DataQuality::new(DataQuality::EMPTY | DataQuality::CONSOLIDATED),
+            false => DataQuality::new(DataQuality::ESTIMATED | DataQuality::CONSOLIDATED),
+        };
+        match self.subscription.base_data_type {
+            BaseDataType::QuoteBars => {
+                let (last_bid_close, last_ask_close) = match (self.last_bid_close, self.last_ask_close) {
+                    (Some(_), Some(_)) if empty => (dec!(0.0), dec!(0.0)),
+                    (Some(last_bid_close), Some(last_ask_close)) => (last_bid_close, last_ask_close),
+                    _ => return,
+                };
+                self.last_bar_open = open;
+                let spread = self.market_type.round_price(last_ask_close - last_bid_close, self.tick_size, self.decimal_accuracy);
 
-                        self.current_data = Some(BaseDataEnum::QuoteBar(QuoteBar {
-                            symbol: self.subscription.symbol.clone(),
-                            ask_open: last_ask_close,
-                            ask_high: last_ask_close,
-                            ask_low: last_ask_close,
-                            ask_close: last_ask_close,
-                            bid_open: last_bid_close,
-                            bid_high: last_bid_close,
-                            bid_low: last_bid_close,
-                            bid_close: last_bid_close,
-                            volume: dec!(0.0),
-                            ask_volume: dec!(0.0),
-                            bid_volume: dec!(0.0),
-                            time: open_time(&self.subscription, time).to_string(),
-                            resolution: self.subscription.resolution.clone(),
-                            is_closed: false,
-                            range: dec!(0.0),
-                            candle_type: CandleType::CandleStick,
-                            spread,
-                        }));
-                    }
-                }
-                BaseDataType::Candles => {
-                    if let Some(last_close) = self.last_close {
-                        let mut time = converters::open_time(&self.subscription, time);
-                        if time == self.last_bar_open {
-                            time += self.subscription.resolution.as_duration();
-                        }
-                        self.last_bar_open = time.clone();
-                        self.current_data = Some(BaseDataEnum::Candle(Candle {
-                            symbol: self.subscription.symbol.clone(),
-                            open: last_close,
-                            high: last_close,
-                            low: last_close,
-                            close: last_close,
-                            volume: dec!(0.0),
-                            ask_volume: dec!(0.0),
-                            bid_volume: dec!(0.0),
-                            time: open_time(&self.subscription, time).to_string(),
-                            resolution: self.subscription.resolution.clone(),
-                            is_closed: false,
-                            range: dec!(0.0),
-                            candle_type: CandleType::CandleStick,
-                        }));
-                    }
-                }
-                _ => {}
+                self.current_data = Some(BaseDataEnum::QuoteBar(QuoteBar {
+                    symbol: self.subscription.symbol.clone(),
+                    ask_open: last_ask_close,
+                    ask_high: last_ask_close,
+                    ask_low: last_ask_close,
+                    ask_close: last_ask_close,
+                    bid_open: last_bid_close,
+                    bid_high: last_bid_close,
+                    bid_low: last_bid_close,
+                    bid_close: last_bid_close,
+                    volume: dec!(0.0),
+                    ask_volume: dec!(0.0),
+                    bid_volume: dec!(0.0),
+                    time: open.to_string(),
+                    resolution: self.subscription.resolution.clone(),
+                    is_closed: false,
+                    range: dec!(0.0),
+                    candle_type: CandleType::CandleStick,
+                    spread,
+                    quality,
+                }));
+            }
+            BaseDataType::Candles => {
+                let last_close = match self.last_close {
+                    Some(_) if empty => dec!(0.0),
+                    Some(last_close) => last_close,
+                    None => return,
+                };
+                self.last_bar_open = open;
+                self.current_data = Some(BaseDataEnum::Candle(Candle {
+                    symbol: self.subscription.symbol.clone(),
+                    open: last_close,
+                    high: last_close,
+                    low: last_close,
+                    close: last_close,
+                    volume: dec!(0.0),
+                    ask_volume: dec!(0.0),
+                    bid_volume: dec!(0.0),
+                    time: open.to_string(),
+                    resolution: self.subscription.resolution.clone(),
+                    is_closed: false,
+                    range: dec!(0.0),
+                    candle_type: CandleType::CandleStick,
+                    quality,
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    /// Keeps the close of a closed bar to fill forward from, empty bars have no close.
+    fn record_close(&mut self, bar: &BaseDataEnum) {
+        if bar.quality().is_empty() {
+            return;
+        }
+        match bar {
+            BaseDataEnum::Candle(candle) => {
+                self.last_close = Some(candle.close);
+            }
+            BaseDataEnum::QuoteBar(quote_bar) => {
+                self.last_ask_close = Some(quote_bar.ask_close);
+                self.last_bid_close = Some(quote_bar.bid_close);
+            }
+            _ => {}
+        }
+    }
+
+    /// Data arriving during an empty bar replaces the bar, rather than being merged into its zero prices.
+    fn clear_empty_bar(&mut self, base_data: &BaseDataEnum) {
+        if let Some(current_bar) = &self.current_data {
+            if current_bar.quality().is_empty() && base_data.time_utc() >= current_bar.time_utc() && base_data.time_utc() < current_bar.time_closed_utc() {
+                self.current_data = None;
+                self.last_bar_open = DateTime::<Utc>::MIN_UTC;
             }
         }
     }
 
-    fn update_candles(&mut self, base_data: &BaseDataEnum) -> ConsolidatedData {
+    fn update_candles(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
+        self.clear_empty_bar(base_data);
         if self.current_data.is_none() {
-            let data = self.new_candle(base_data);
-            self.current_data = Some(BaseDataEnum::Candle(data));
-            return ConsolidatedData::with_open(self.current_data.clone().unwrap())
+            let data = BaseDataEnum::Candle(self.new_candle(base_data)?);
+            self.current_data = Some(data.clone());
+            return Ok(ConsolidatedData::with_open(data))
         }
         else if let Some(current_bar) = self.current_data.as_mut() {
             let time = base_data.time_closed_utc();
             if time < current_bar.time_utc() {
-                return ConsolidatedData::with_open(current_bar.clone());
+                return Ok(ConsolidatedData::with_open(current_bar.clone()));
             }
             if base_data.time_utc() >= current_bar.time_closed_utc() {
                 let mut consolidated_bar = current_bar.clone();
                 consolidated_bar.set_is_closed(true);
-                match &consolidated_bar {
-                    BaseDataEnum::Candle(candle) => {
-                        self.last_close = Some(candle.close.clone());
-                    }
-                    _ => {}
-                }
-                let new_bar = self.new_candle(base_data);
+                self.record_close(&consolidated_bar);
+                let new_bar = self.new_candle(base_data)?;
                 self.current_data = Some(BaseDataEnum::Candle(new_bar.clone()));
-                return ConsolidatedData::with_closed(BaseDataEnum::Candle(new_bar), consolidated_bar);
+                return Ok(ConsolidatedData::with_closed(BaseDataEnum::Candle(new_bar), consolidated_bar));
             } else {
                 match current_bar {
                     BaseDataEnum::Candle(candle) =>
@@ -153,7 +201,8 @@ impl CandleStickConsolidator {
                                 }
 
                                 candle.volume += tick.volume;
-                                return ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone()))
+                                candle.quality.merge(&tick.quality);
+                                return Ok(ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone())))
                             }
                             BaseDataEnum::Candle(new_candle) => {
                                 candle.high = candle.high.max(new_candle.high);
@@ -163,27 +212,22 @@ impl CandleStickConsolidator {
                                 candle.volume += new_candle.volume;
                                 candle.ask_volume += new_candle.ask_volume;
                                 candle.bid_volume += new_candle.bid_volume;
-                                return ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone()))
+                                candle.quality.merge(&new_candle.quality);
+                                return Ok(ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone())))
                             }
-                            _ => panic!(
-                                "Invalid base data type for Candle consolidator: {}",
-                                base_data.base_data_type()
-                            ),
+                            _ => {}
                         },
-                    _ => panic!(
-                        "Invalid base data type for Candle consolidator: {}",
-                        base_data.base_data_type()
-                    ),
+                    _ => {}
                 }
             }
         }
-        panic!(
+        Err(FundForgeError::ClientSideErrorDebug(format!(
             "Invalid base data type for Candle consolidator: {}",
             base_data.base_data_type()
-        )
+        )))
     }
 
-    fn new_quote_bar(&mut self, new_data: &BaseDataEnum) -> QuoteBar {
+    fn new_quote_bar(&mut self, new_data: &BaseDataEnum) -> Result<QuoteBar, FundForgeError> {
         let mut time = converters::open_time(&self.subscription, new_data.time_utc());
         if time == self.last_bar_open {
             time += self.subscription.resolution.as_duration();
@@ -195,10 +239,11 @@ impl CandleStickConsolidator {
                 new_bar.is_closed = false;
                 new_bar.time = time.to_string();
                 new_bar.resolution = self.subscription.resolution.clone();
-                new_bar
+                new_bar.quality.insert(DataQuality::CONSOLIDATED);
+                Ok(new_bar)
             }
             BaseDataEnum::Quote(quote) => {
-                QuoteBar::new(
+                let mut new_bar = QuoteBar::new(
                     self.subscription.symbol.clone(),
                     quote.bid,
                     quote.ask,
@@ -208,36 +253,33 @@ impl CandleStickConsolidator {
                     time.to_string(),
                     self.subscription.resolution.clone(),
                     CandleType::CandleStick,
-                )
+                );
+                new_bar.quality.insert(DataQuality::CONSOLIDATED);
+                Ok(new_bar)
             },
-            _ => panic!("Invalid base data type for QuoteBar consolidator"),
+            _ => Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for QuoteBar consolidator: {}", new_data.base_data_type()))),
         }
     }
 
     /// We can use if time == some multiple of resolution then we can consolidate, we dont need to know the actual algo time, because we can get_requests time from the historical if self.last_time >
-    fn update_quote_bars(&mut self, base_data: &BaseDataEnum) -> ConsolidatedData {
+    fn update_quote_bars(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
+        self.clear_empty_bar(base_data);
         if self.current_data.is_none() {
-            let data = self.new_quote_bar(base_data);
-            self.current_data = Some(BaseDataEnum::QuoteBar(data));
-            return ConsolidatedData::with_open(self.current_data.clone().unwrap())
+            let data = BaseDataEnum::QuoteBar(self.new_quote_bar(base_data)?);
+            self.current_data = Some(data.clone());
+            return Ok(ConsolidatedData::with_open(data))
         } else if let Some(current_bar) = self.current_data.as_mut() {
             let time = base_data.time_utc();
             if time < current_bar.time_utc() {
-                return ConsolidatedData::with_open(current_bar.clone());
+                return Ok(ConsolidatedData::with_open(current_bar.clone()));
             }
             if base_data.time_utc() >= current_bar.time_closed_utc()  {
                 let mut consolidated_bar = current_bar.clone();
                 consolidated_bar.set_is_closed(true);
-                let new_bar = self.new_quote_bar(base_data);
-                match &consolidated_bar {
-                    BaseDataEnum::QuoteBar(quote_bar) => {
-                        self.last_ask_close = Some(quote_bar.ask_close.clone());
-                        self.last_bid_close = Some(quote_bar.bid_close.clone());
-                    }
-                    _ => {}
-                }
+                let new_bar = self.new_quote_bar(base_data)?;
+                self.record_close(&consolidated_bar);
                 self.current_data = Some(BaseDataEnum::QuoteBar(new_bar.clone()));
-                return ConsolidatedData::with_closed(BaseDataEnum::QuoteBar(new_bar), consolidated_bar);
+                return Ok(ConsolidatedData::with_closed(BaseDataEnum::QuoteBar(new_bar), consolidated_bar));
             } else {
                 match current_bar {
                     BaseDataEnum::QuoteBar(quote_bar) =>
@@ -254,7 +296,7 @@ impl CandleStickConsolidator {
                                 quote_bar.ask_volume += quote.ask_volume;
                                 quote_bar.range = self.market_type.round_price(quote_bar.ask_high - quote_bar.bid_low, self.tick_size, self.decimal_accuracy);
                                 quote_bar.spread = self.market_type.round_price(quote_bar.ask_close - quote_bar.bid_close, self.tick_size, self.decimal_accuracy);
-                                return ConsolidatedData::with_open(BaseDataEnum::QuoteBar(quote_bar.clone()))
+                                return Ok(ConsolidatedData::with_open(BaseDataEnum::QuoteBar(quote_bar.clone())))
                             }
                             BaseDataEnum::QuoteBar(bar) => {
                                 quote_bar.ask_high = quote_bar.ask_high.max(bar.ask_high);
@@ -268,27 +310,22 @@ impl CandleStickConsolidator {
                                 quote_bar.ask_volume += bar.ask_volume;
                                 quote_bar.range = self.market_type.round_price(quote_bar.ask_high - quote_bar.bid_low, self.tick_size, self.decimal_accuracy);
                                 quote_bar.spread = self.market_type.round_price(quote_bar.ask_close - quote_bar.bid_close, self.tick_size, self.decimal_accuracy);
-                                return ConsolidatedData::with_open(BaseDataEnum::QuoteBar(quote_bar.clone()))
+                                quote_bar.quality.merge(&bar.quality);
+                                return Ok(ConsolidatedData::with_open(BaseDataEnum::QuoteBar(quote_bar.clone())))
                             }
-                            _ => panic!(
-                                "Invalid base data type for QuoteBar consolidator: {}",
-                                base_data.base_data_type()
-                            ),
+                            _ => {}
                         },
-                    _ => panic!(
-                        "Invalid base data type for QuoteBar consolidator: {}",
-                        base_data.base_data_type()
-                    ),
+                    _ => {}
                 }
             }
         }
-        panic!(
+        Err(FundForgeError::ClientSideErrorDebug(format!(
             "Invalid base data type for QuoteBar consolidator: {}",
             base_data.base_data_type()
-        )
+        )))
     }
 
-    fn new_candle(&mut self, new_data: &BaseDataEnum) -> Candle {
+    fn new_candle(&mut self, new_data: &BaseDataEnum) -> Result<Candle, FundForgeError> {
         let mut time = converters::open_time(&self.subscription, new_data.time_utc());
         if time == self.last_bar_open {
             time += self.subscription.resolution.as_duration();
@@ -301,7 +338,7 @@ impl CandleStickConsolidator {
                     Aggressor::Sell => (tick.volume, dec!(0.0)),
                     Aggressor::None => (dec!(0), dec!(0))
                 };
-                Candle::new(
+                let mut candle = Candle::new(
                     self.subscription.symbol.clone(),
                     tick.price,
                     tick.volume,
@@ -309,27 +346,32 @@ impl CandleStickConsolidator {
                     bid_volume,
                     time.to_string(),
                     self.subscription.resolution.clone(),
-                    self.subscription.candle_type.clone().unwrap(),
-                )
+                    self.subscription.candle_type.clone().unwrap_or(CandleType::CandleStick),
+                );
+                candle.quality = tick.quality;
+                candle.quality.insert(DataQuality::CONSOLIDATED);
+                Ok(candle)
             },
             BaseDataEnum::Candle(candle) => {
                 let mut consolidated_candle = candle.clone();
                 consolidated_candle.is_closed = false;
                 consolidated_candle.resolution = self.subscription.resolution.clone();
                 consolidated_candle.time = time.to_string();
-                consolidated_candle
+                consolidated_candle.quality.insert(DataQuality::CONSOLIDATED);
+                Ok(consolidated_candle)
             }
-            _ => panic!("Invalid base data type for Candle consolidator"),
+            _ => Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for Candle consolidator: {}", new_data.base_data_type()))),
         }
     }
 
     pub(crate) async fn new(
         subscription: DataSubscription,
-        fill_forward: bool,
+        fill_policy: FillPolicy,
+        hours: Option<TradingHours>,
         decimal_accuracy: u32,
         tick_size: Decimal,
     ) -> Result<Self, FundForgeError> {
-        println!("Creating Consolidator For: {}", subscription);
+        tracing::debug!("Creating Consolidator For: {}", subscription);
         if subscription.base_data_type == BaseDataType::Fundamentals {
             return Err(FundForgeError::ClientSideErrorDebug(format!(
                     "{} is an Invalid base data type for TimeConsolidator",
@@ -357,12 +399,13 @@ impl CandleStickConsolidator {
             last_close: None,
             last_ask_close: None,
             last_bid_close: None,
-            fill_forward,
+            fill_policy,
+            hours,
             last_bar_open: DateTime::<Utc>::MIN_UTC
         })
     }
 
-    pub fn update(&mut self, base_data: &BaseDataEnum) -> ConsolidatedData {
+    pub fn update(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
         match base_data.base_data_type() {
             BaseDataType::Ticks => {
                 self.update_candles(base_data)
@@ -376,7 +419,80 @@ impl CandleStickConsolidator {
             BaseDataType::Candles => {
                 self.update_candles(base_data)
             }
-            BaseDataType::Fundamentals => panic!("Fundamentals are not supported"),
+            BaseDataType::Fundamentals | BaseDataType::Footprints => Err(FundForgeError::ClientSideErrorDebug(format!(
+                "{} are not supported by the Candle consolidator",
+                base_data.base_data_type()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, TimeZone, Weekday};
+    use chrono_tz::America::New_York;
+    use crate::standardized_types::base_data::tick::Tick;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::FuturesExchange;
+    use crate::standardized_types::market_hours::DaySession;
+
+    fn hours() -> TradingHours {
+        let session = DaySession { open: NaiveTime::from_hms_opt(9, 30, 0), close: NaiveTime::from_hms_opt(16, 0, 0) };
+        let closed = DaySession { open: None, close: None };
+        TradingHours {
+            timezone: New_York,
+            sunday: closed.clone(),
+            monday: session.clone(),
+            tuesday: session.clone(),
+            wednesday: session.clone(),
+            thursday: session.clone(),
+            friday: session,
+            saturday: closed,
+            week_start: Weekday::Sun,
+        }
+    }
+
+    fn tick(subscription: &DataSubscription, time: DateTime<Utc>, price: Price) -> BaseDataEnum {
+        BaseDataEnum::Tick(Tick::new(subscription.symbol.clone(), price, time.to_string(), dec!(1), Aggressor::Buy))
+    }
+
+    #[tokio::test]
+    async fn test_fill_policy() {
+        let fill_policy = FillPolicy::Empty { sessions_only: true, skip_holidays: true };
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(1), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME))
+            .with_fill_policy(fill_policy);
+        let mut consolidator = CandleStickConsolidator::new(subscription.clone(), fill_policy, Some(hours()), 2, dec!(0.25)).await.unwrap();
+        let time = |hour, minute, second| New_York.with_ymd_and_hms(2024, 6, 3, hour, minute, second).unwrap().to_utc();
+
+        consolidator.update(&tick(&subscription, time(15, 58, 10), dec!(18000))).unwrap();
+        let closed = consolidator.update_time(time(15, 59, 0)).unwrap();
+        assert_eq!(closed.subscription(), subscription);
+        let empty = consolidator.current_data.clone().unwrap();
+        assert!(empty.quality().is_empty());
+        assert_eq!(empty.time_utc(), time(15, 59, 0));
+
+        // data during the empty bar replaces it
+        let consolidated = consolidator.update(&tick(&subscription, time(15, 59, 30), dec!(18001))).unwrap();
+        assert!(consolidated.closed_data.is_none());
+        match consolidated.open_data {
+            BaseDataEnum::Candle(candle) => {
+                assert_eq!(candle.open, dec!(18001));
+                assert_eq!(candle.time_utc(), time(15, 59, 0));
+                assert!(!candle.quality.is_empty());
+            }
+            data => panic!("unexpected data {:?}", data),
         }
+
+        // the market closes at 16:00 so the next period is not filled
+        assert!(consolidator.update_time(time(16, 0, 0)).is_some());
+        assert!(consolidator.current_data.is_none());
+
+        let last_close = FillPolicy::LastClose { sessions_only: false, skip_holidays: true };
+        let independence_day = New_York.with_ymd_and_hms(2024, 7, 4, 11, 0, 0).unwrap().to_utc();
+        assert!(!last_close.should_fill(independence_day, Some(&hours())));
+        assert!(last_close.should_fill(time(20, 0, 0), Some(&hours())));
+        assert!(!fill_policy.should_fill(time(20, 0, 0), Some(&hours())));
+        assert!(!FillPolicy::None.should_fill(time(12, 0, 0), None));
     }
 }
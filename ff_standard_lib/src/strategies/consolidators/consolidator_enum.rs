@@ -4,17 +4,20 @@ use crate::strategies::consolidators::heikinashi::HeikinAshiConsolidator;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::enums::{MarketType, StrategyMode};
 use crate::standardized_types::rolling_window::RollingWindow;
-use crate::standardized_types::subscriptions::{filter_resolutions, CandleType, DataSubscription};
+use crate::standardized_types::subscriptions::{filter_resolutions, CandleType, DataSubscription, FillPolicy};
 use chrono::{DateTime, Datelike, Duration, Utc, Weekday};
 use crate::product_maps::rithmic::maps::extract_symbol_from_contract;
+use crate::messages::data_server_messaging::FundForgeError;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
-use crate::standardized_types::base_data::history::{get_compressed_historical_data};
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::base_data::warmup_batch::get_warmup_data;
 use crate::standardized_types::market_hours::TradingHours;
 use crate::standardized_types::resolution::Resolution;
 use crate::strategies::consolidators::daily_candles::DailyConsolidator;
 use crate::strategies::consolidators::daily_quotebars::DailyQuoteConsolidator;
-use crate::strategies::consolidators::weekly::WeeklyCandleConsolidator;
-use crate::strategies::consolidators::weekly_quotebars::WeeklyQuoteConsolidator;
+use crate::strategies::consolidators::footprint::FootprintConsolidator;
+use crate::strategies::consolidators::trading_periods::TradingPeriodConsolidator;
+use crate::strategies::consolidators::bar_cache::{bar_cache, BarCacheKey};
 
 pub enum ConsolidatorEnum {
     Count(CountConsolidator),
@@ -22,81 +25,73 @@ pub enum ConsolidatorEnum {
     HeikinAshi(HeikinAshiConsolidator),
     DailyCandles(DailyConsolidator),
     DailyQuoteBars(DailyQuoteConsolidator),
-    WeeklyCandles(WeeklyCandleConsolidator),
-    WeeklyQuoteBars(WeeklyQuoteConsolidator),
+    TradingPeriod(TradingPeriodConsolidator),
+    Footprint(FootprintConsolidator),
 }
 
 impl ConsolidatorEnum {
     /// Creates a new consolidator based on the subscription. if is_warmed_up is true, the consolidator will warm up to the to_time on its own.
+    /// Returns an error if the subscription can not be consolidated, or the vendor does not know the symbol.
     pub async fn create_consolidator(
         subscription: DataSubscription,
         fill_forward: bool,
         hours: Option<TradingHours>,
-    ) -> ConsolidatorEnum {
+    ) -> Result<ConsolidatorEnum, FundForgeError> {
 
         let symbol_name = match subscription.market_type {
             MarketType::Futures(_) => extract_symbol_from_contract(&subscription.symbol.name),
             _ => subscription.symbol.name.clone(),
         };
-        let decimal_accuracy = subscription.symbol.data_vendor.decimal_accuracy(symbol_name.clone()).await.unwrap();
-        let tick_size = subscription.symbol.data_vendor.tick_size(symbol_name.clone()).await.unwrap();
-
-        match subscription.resolution {
-            Resolution::Day => {
-                match subscription.base_data_type {
-                    BaseDataType::QuoteBars => {
-                        return ConsolidatorEnum::DailyQuoteBars(
-                            DailyQuoteConsolidator::new(subscription.clone(), decimal_accuracy, tick_size,hours.unwrap())
-                                .unwrap(),
-                        );
-                    }
-                    BaseDataType::Candles => {
-                        return ConsolidatorEnum::DailyCandles(
-                            DailyConsolidator::new(subscription.clone(), decimal_accuracy, tick_size,hours.unwrap())
-                                .unwrap(),
-                        );
-                    }
-                    _ => {}
+        if subscription.base_data_type == BaseDataType::Footprints {
+            return Ok(ConsolidatorEnum::Footprint(FootprintConsolidator::new(subscription)?));
+        }
+
+        let decimal_accuracy = subscription.symbol.data_vendor.decimal_accuracy(symbol_name.clone()).await?;
+        let tick_size = subscription.symbol.data_vendor.tick_size(symbol_name.clone()).await?;
+
+        if let (Resolution::Day, Some(daily_hours)) = (subscription.resolution, &hours) {
+            match subscription.base_data_type {
+                BaseDataType::QuoteBars => {
+                    return Ok(ConsolidatorEnum::DailyQuoteBars(
+                        DailyQuoteConsolidator::new(subscription.clone(), decimal_accuracy, tick_size, daily_hours.clone())?,
+                    ));
                 }
+                BaseDataType::Candles => {
+                    return Ok(ConsolidatorEnum::DailyCandles(
+                        DailyConsolidator::new(subscription.clone(), decimal_accuracy, tick_size, daily_hours.clone())?,
+                    ));
+                }
+                _ => {}
             }
-            _ => {}
         }
 
-        //todo handle errors here gracefully
-        let is_tick = match subscription.resolution {
-            Resolution::Ticks(_) => true,
-            _ => false,
-        };
+        // Weeks and months, and days without trading hours which use UTC days
+        if subscription.resolution.is_trading_period() {
+            return Ok(ConsolidatorEnum::TradingPeriod(TradingPeriodConsolidator::new(subscription, hours)?));
+        }
 
-        if is_tick {
-           return ConsolidatorEnum::Count(
-                CountConsolidator::new(subscription.clone(), decimal_accuracy, tick_size)
-                    .await
-                    .unwrap(),
-            )
+        if let Resolution::Ticks(_) = subscription.resolution {
+           return Ok(ConsolidatorEnum::Count(
+                CountConsolidator::new(subscription.clone(), decimal_accuracy, tick_size).await?,
+            ))
         }
 
+        let fill_policy = subscription.fill_policy.unwrap_or(FillPolicy::from_fill_forward(fill_forward));
         let consolidator = match &subscription.candle_type {
-            Some(candle_type) => match candle_type {
-                CandleType::HeikinAshi => ConsolidatorEnum::HeikinAshi(
-                    HeikinAshiConsolidator::new(subscription.clone(), fill_forward, decimal_accuracy, tick_size)
-                        .await
-                        .unwrap(),
-                ),
-                CandleType::CandleStick => ConsolidatorEnum::CandleStickConsolidator(
-                    CandleStickConsolidator::new(subscription.clone(), fill_forward, decimal_accuracy, tick_size)
-                        .await
-                        .unwrap(),
-                ),
-            },
-            _ => panic!("Candle type is required for CandleStickConsolidator"),
+            Some(CandleType::HeikinAshi) => ConsolidatorEnum::HeikinAshi(
+                HeikinAshiConsolidator::new(subscription.clone(), fill_policy, hours, decimal_accuracy, tick_size).await?,
+            ),
+            Some(CandleType::CandleStick) => ConsolidatorEnum::CandleStickConsolidator(
+                CandleStickConsolidator::new(subscription.clone(), fill_policy, hours, decimal_accuracy, tick_size).await?,
+            ),
+            None => return Err(FundForgeError::ClientSideErrorDebug(format!("Candle type is required to consolidate: {}", subscription))),
         };
 
-       consolidator
+        Ok(consolidator)
     }
 
-    /// Updates the consolidator with the new data point.
-    pub fn update(&mut self, base_data: &BaseDataEnum) -> ConsolidatedData {
+    /// Updates the consolidator with the new data point, returns an error if the consolidator can not consolidate the type of data.
+    pub fn update(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
         match self {
             ConsolidatorEnum::Count(count_consolidator) => count_consolidator.update(base_data),
             ConsolidatorEnum::CandleStickConsolidator(time_consolidator) => {
@@ -107,8 +102,8 @@ impl ConsolidatorEnum {
             }
             ConsolidatorEnum::DailyCandles(consolidator) => consolidator.update(base_data),
             ConsolidatorEnum::DailyQuoteBars(consolidator) => consolidator.update(base_data),
-            ConsolidatorEnum::WeeklyCandles(consolidator) => consolidator.update(base_data),
-            ConsolidatorEnum::WeeklyQuoteBars(consolidator) => consolidator.update(base_data),
+            ConsolidatorEnum::TradingPeriod(consolidator) => consolidator.update(base_data),
+            ConsolidatorEnum::Footprint(consolidator) => consolidator.update(base_data),
         }
     }
 
@@ -124,8 +119,8 @@ impl ConsolidatorEnum {
             }
             ConsolidatorEnum::DailyCandles(consolidator) => &consolidator.subscription,
             ConsolidatorEnum::DailyQuoteBars(consolidator) => &consolidator.subscription,
-            ConsolidatorEnum::WeeklyCandles(consolidator) => &consolidator.subscription,
-            ConsolidatorEnum::WeeklyQuoteBars(consolidator) => &consolidator.subscription,
+            ConsolidatorEnum::TradingPeriod(consolidator) => &consolidator.subscription,
+            ConsolidatorEnum::Footprint(consolidator) => &consolidator.subscription,
         }
     }
 
@@ -147,10 +142,10 @@ impl ConsolidatorEnum {
             ConsolidatorEnum::DailyQuoteBars(consolidator) => {
                 &consolidator.subscription.resolution
             }
-            ConsolidatorEnum::WeeklyCandles(consolidator) => {
+            ConsolidatorEnum::TradingPeriod(consolidator) => {
                 &consolidator.subscription.resolution
             }
-            ConsolidatorEnum::WeeklyQuoteBars(consolidator) => {
+            ConsolidatorEnum::Footprint(consolidator) => {
                 &consolidator.subscription.resolution
             }
         }
@@ -172,30 +167,233 @@ impl ConsolidatorEnum {
             ConsolidatorEnum::DailyQuoteBars(consolidator) => {
                 consolidator.update_time(time)
             }
-            ConsolidatorEnum::WeeklyCandles(consolidator) => {
+            ConsolidatorEnum::TradingPeriod(consolidator) => {
                 consolidator.update_time(time)
             }
-            ConsolidatorEnum::WeeklyQuoteBars(consolidator) => {
+            ConsolidatorEnum::Footprint(consolidator) => {
                 consolidator.update_time(time)
             }
         }
     }
 
+    /// The parameters which change the bars of the consolidator but are not part of the subscription, used to key the `BarCache`.
+    /// `None` for tick bars, which close on a count rather than a time so the source data of the next bar can not be found from the last bar.
+    pub(crate) fn parameters(&self) -> Option<String> {
+        match self {
+            ConsolidatorEnum::Count(_) => None,
+            ConsolidatorEnum::CandleStickConsolidator(consolidator) => Some(consolidator.parameters()),
+            ConsolidatorEnum::HeikinAshi(consolidator) => Some(consolidator.parameters()),
+            ConsolidatorEnum::DailyCandles(consolidator) => Some(consolidator.parameters()),
+            ConsolidatorEnum::DailyQuoteBars(consolidator) => Some(consolidator.parameters()),
+            ConsolidatorEnum::TradingPeriod(consolidator) => Some(consolidator.parameters()),
+            ConsolidatorEnum::Footprint(_) => Some(String::new()),
+        }
+    }
+
+    /// Continues from the last closed bar of an earlier run loaded from the `BarCache`, returns the time to consolidate the source data from.
+    /// Bars which close again from the source data are already in the cache and should be skipped.
+    pub(crate) fn resume(&mut self, last_bar: &BaseDataEnum) -> DateTime<Utc> {
+        match self {
+            ConsolidatorEnum::CandleStickConsolidator(consolidator) => consolidator.resume(last_bar),
+            ConsolidatorEnum::HeikinAshi(consolidator) => return consolidator.resume(last_bar),
+            ConsolidatorEnum::TradingPeriod(consolidator) => return consolidator.resume(last_bar),
+            _ => {}
+        }
+        last_bar.time_closed_utc()
+    }
+
+    /// The key of the consolidated bars and the bars saved under it, `None` if the bar cache is disabled or the consolidator can not be cached.
+    async fn cached_bars(
+        &self,
+        source: &DataSubscription,
+        from_time: DateTime<Utc>,
+        to_time: DateTime<Utc>,
+    ) -> Option<(BarCacheKey, Option<Vec<BaseDataEnum>>)> {
+        let cache = bar_cache()?;
+        let parameters = self.parameters()?;
+        match cache.key(self.subscription(), &parameters, source, from_time, to_time).await {
+            Ok(key) => {
+                let bars = cache.load(&key);
+                Some((key, bars))
+            }
+            Err(e) => {
+                tracing::warn!("Bar Cache: Unable to get the data version of {}: {}", source, e);
+                None
+            }
+        }
+    }
+
+    fn store_bars(key: &BarCacheKey, bars: &[BaseDataEnum]) {
+        if let Some(cache) = bar_cache() {
+            if let Err(e) = cache.store(key, bars) {
+                tracing::warn!("Bar Cache: {}", e);
+            }
+        }
+    }
+
     pub async fn warmup(
+        consolidator: ConsolidatorEnum,
+        to_time: DateTime<Utc>,
+        history_to_retain: i32,
+        strategy_mode: StrategyMode,
+    ) -> (ConsolidatorEnum, RollingWindow<BaseDataEnum>) {
+        ConsolidatorEnum::warmup_from_source(consolidator, None, to_time, history_to_retain, strategy_mode).await
+    }
+
+    /// Warms up the consolidator from an explicit source subscription, for example stored 1 minute candles instead of ticks.
+    /// If the source is `None`, or it can not be consolidated into the consolidator subscription, the best resolution offered by the vendor is used.
+    /// If the source is the same as the consolidator subscription, the data is added to the history without consolidation.
+    pub async fn warmup_from_source(
         mut consolidator: ConsolidatorEnum,
+        source: Option<DataSubscription>,
         to_time: DateTime<Utc>,
         history_to_retain: i32,
         _strategy_mode: StrategyMode,
     ) -> (ConsolidatorEnum, RollingWindow<BaseDataEnum>) {
         let subscription = consolidator.subscription();
+        let base_subscription = match source {
+            Some(source) if is_valid_warmup_source(&source, subscription) => source,
+            source => {
+                if let Some(source) = source {
+                    tracing::warn!("Warmup source {} can not be consolidated into {}, using vendor resolutions", source, subscription);
+                }
+                match ConsolidatorEnum::vendor_warmup_subscription(subscription).await {
+                    Some(base_subscription) => base_subscription,
+                    None => return (consolidator, RollingWindow::new(history_to_retain as usize)),
+                }
+            }
+        };
+        let is_same_subscription = &base_subscription == subscription;
+
+        let from_time = warmup_from_time(*consolidator.resolution(), to_time, history_to_retain);
+
+        let mut history = RollingWindow::new(history_to_retain as usize);
+        // Only the source data after the last cached bar is consolidated, which leaves the consolidator with the same open bar as consolidating the whole range.
+        let cached = match is_same_subscription {
+            true => None,
+            false => consolidator.cached_bars(&base_subscription, from_time, to_time).await,
+        };
+        let mut consolidate_from = from_time;
+        let mut last_cached_bar = DateTime::<Utc>::MIN_UTC;
+        let mut closed_bars = vec![];
+        if let Some((_, Some(bars))) = &cached {
+            if let Some(last_bar) = bars.last() {
+                consolidate_from = consolidator.resume(last_bar);
+                last_cached_bar = last_bar.time_utc();
+            }
+            for bar in bars {
+                history.add(bar.clone());
+            }
+        }
+        //eprintln!("Warmup from: {} to: {}", from_time, to_time);
+        let data = match get_warmup_data(&base_subscription, consolidate_from, to_time).await {
+            Ok(data) => data,
+            Err(_) => {
+                //eprintln!("No data available or error: {}", e);
+                return  (consolidator, history)
+            }
+        };
+        //eprintln!("Data length: {}", data.len());
+
+        for (_time, time_slice) in data {
+            for base_data in time_slice.iter() {
+                if is_same_subscription {
+                    history.add(base_data.clone());
+                    continue;
+                }
+                if base_data.time_utc() < consolidate_from {
+                    continue;
+                }
+                let consolidated_data = match consolidator.update(&base_data) {
+                    Ok(consolidated_data) => consolidated_data,
+                    Err(e) => {
+                        tracing::warn!("Warmup of {} skipped data: {}", base_subscription, e);
+                        continue;
+                    }
+                };
+                if let Some(closed_data) = consolidated_data.closed_data {
+                    if closed_data.time_utc() <= last_cached_bar {
+                        continue;
+                    }
+                    closed_bars.push(closed_data.clone());
+                    history.add(closed_data);
+                }
+               //println!("time: {}", base_data.time_local(&Australia__Brisbane));
+            }
+        }
+        if let Some((key, None)) = &cached {
+            ConsolidatorEnum::store_bars(key, &closed_bars);
+        }
+        //eprintln!("Warmup complete: {}", history.len());
+        (consolidator, history)
+    }
+
+    /// The base subscription and range `warmup()` downloads for a consolidator of the subscription, so the warmup data of many subscriptions can be downloaded together.
+    pub(crate) async fn warmup_request(
+        subscription: &DataSubscription,
+        to_time: DateTime<Utc>,
+        history_to_retain: i32,
+    ) -> Option<(DataSubscription, DateTime<Utc>, DateTime<Utc>)> {
+        let base_subscription = ConsolidatorEnum::vendor_warmup_subscription(subscription).await?;
+        Some((base_subscription, warmup_from_time(subscription.resolution, to_time, history_to_retain), to_time))
+    }
+
+    /// Consolidates the history of the consolidator subscription from the best resolution offered by the vendor, for an ad hoc range rather than a number of bars.
+    /// Only data which closes between the times is returned, in time order.
+    pub async fn consolidate_range(
+        mut consolidator: ConsolidatorEnum,
+        from_time: DateTime<Utc>,
+        to_time: DateTime<Utc>,
+    ) -> Vec<BaseDataEnum> {
+        let subscription = consolidator.subscription().clone();
+        let base_subscription = match ConsolidatorEnum::vendor_warmup_subscription(&subscription).await {
+            Some(base_subscription) => base_subscription,
+            None => return vec![],
+        };
+        // The bar open at the from time needs the data from its open time.
+        let fetch_from = from_time - subscription.resolution.as_duration();
+        let cached = match base_subscription == subscription {
+            true => None,
+            false => consolidator.cached_bars(&base_subscription, fetch_from, to_time).await,
+        };
+        if let Some((_, Some(bars))) = &cached {
+            return bars.clone();
+        }
+        let data = match get_warmup_data(&base_subscription, fetch_from, to_time).await {
+            Ok(data) => data,
+            Err(_) => return vec![],
+        };
+        let mut history = vec![];
+        for (_time, time_slice) in data {
+            for base_data in time_slice.iter() {
+                let closed_data = match base_subscription == subscription {
+                    true => Some(base_data.clone()),
+                    false => consolidator.update(base_data).ok().and_then(|consolidated_data| consolidated_data.closed_data),
+                };
+                if let Some(closed_data) = closed_data {
+                    let closed = closed_data.time_closed_utc();
+                    if closed >= from_time && closed <= to_time {
+                        history.push(closed_data);
+                    }
+                }
+            }
+        }
+        if let Some((key, None)) = &cached {
+            ConsolidatorEnum::store_bars(key, &history);
+        }
+        history
+    }
+
+    /// The subscription the vendor can supply which is closest to the resolution of the subscription, used as the default warmup source.
+    pub(crate) async fn vendor_warmup_subscription(subscription: &DataSubscription) -> Option<DataSubscription> {
         let mut vendor_resolutions = filter_resolutions(
             subscription
                 .symbol
                 .data_vendor
                 .warm_up_resolutions(subscription.market_type.clone())
                 .await
-                .unwrap(),
-            consolidator.subscription().resolution,
+                .ok()?,
+            subscription.resolution,
         );
 
         //eprintln!("Vendor resolutions: {:?}", vendor_resolutions);
@@ -206,55 +404,68 @@ impl ConsolidatorEnum {
                     || (base_subscription.base_data_type == BaseDataType::Candles && base_subscription.resolution == Resolution::Seconds(1) && subscription.resolution > Resolution::Seconds(1))
             });
         }
-        let max_resolution = vendor_resolutions.iter().max_by_key(|r| r.resolution);
-        let min_resolution = match max_resolution.is_none() {
-            true => {
-                return (consolidator, RollingWindow::new(history_to_retain as usize));
-            },
-            false => match max_resolution {
-                Some(res) => res,
-                None => return (consolidator, RollingWindow::new(history_to_retain as usize)),
-            },
-        };
-        //eprintln!("Min resolution: {:?}", min_resolution);
-
-        let subtract_duration: Duration = consolidator.resolution().as_duration() * history_to_retain;
-        let mut from_time = to_time - subtract_duration ;
-
-        if to_time.weekday() == Weekday::Sun {
-            from_time -= Duration::days(3);
+        if subscription.base_data_type == BaseDataType::Footprints {
+            vendor_resolutions.retain(|base_subscription| base_subscription.base_data_type == BaseDataType::Ticks && base_subscription.resolution == Resolution::Ticks(1));
         }
+        let min_resolution = vendor_resolutions.iter().max_by_key(|r| r.resolution)?;
+        //eprintln!("Min resolution: {:?}", min_resolution);
 
-        let base_subscription = DataSubscription::new(
+        Some(DataSubscription::new(
             subscription.symbol.name.clone(),
             subscription.symbol.data_vendor.clone(),
             min_resolution.resolution,
             min_resolution.base_data_type,
             subscription.market_type.clone(),
-        );
+        ).with_fallback_vendors(subscription.fallback_vendors.clone()))
+    }
+}
 
-        let mut history = RollingWindow::new(history_to_retain as usize);
-        //eprintln!("Warmup from: {} to: {}", from_time, to_time);
-        let data = match get_compressed_historical_data(vec![base_subscription.clone()], from_time, to_time).await {
-            Ok(data) => data,
-            Err(_) => {
-                //eprintln!("No data available or error: {}", e);
-                return  (consolidator, history)
-            }
-        };
-        //eprintln!("Data length: {}", data.len());
+/// The trading hours as a consolidator parameter of the `BarCache` key.
+pub(crate) fn hours_parameter(hours: Option<&TradingHours>) -> String {
+    match hours {
+        Some(hours) => hours.to_string(),
+        None => "None".to_string(),
+    }
+}
 
-        for (_time, time_slice) in data {
-            for base_data in time_slice.iter() {
-                let consolidated_data = consolidator.update(&base_data);
-                if let Some(closed_data) = consolidated_data.closed_data {
-                    history.add(closed_data);
-                }
-               //println!("time: {}", base_data.time_local(&Australia__Brisbane));
-            }
+/// The start of the warmup range for the history of a consolidator, ranges ending on a Sunday start 3 days earlier to cover the weekend.
+fn warmup_from_time(resolution: Resolution, to_time: DateTime<Utc>, history_to_retain: i32) -> DateTime<Utc> {
+    let from_time = to_time - resolution.as_duration() * history_to_retain;
+    match to_time.weekday() == Weekday::Sun {
+        true => from_time - Duration::days(3),
+        false => from_time,
+    }
+}
+
+/// Returns true if the source data can be consolidated into the subscription, or is the same as the subscription.
+pub fn is_valid_warmup_source(source: &DataSubscription, subscription: &DataSubscription) -> bool {
+    if source == subscription {
+        return true
+    }
+    if source.symbol.name != subscription.symbol.name || source.symbol.data_vendor != subscription.symbol.data_vendor {
+        return false
+    }
+    let compatible_types = match (source.base_data_type, subscription.base_data_type) {
+        (BaseDataType::Ticks, BaseDataType::Candles) | (BaseDataType::Ticks, BaseDataType::Footprints) => source.resolution == Resolution::Ticks(1),
+        (BaseDataType::Quotes, BaseDataType::QuoteBars) => true,
+        (BaseDataType::Candles, BaseDataType::Candles) | (BaseDataType::QuoteBars, BaseDataType::QuoteBars) => {
+            subscription.candle_type != Some(CandleType::HeikinAshi) || source.resolution == Resolution::Seconds(1)
+        }
+        _ => false
+    };
+    if !compatible_types {
+        return false
+    }
+    match (source.resolution, subscription.resolution) {
+        (Resolution::Ticks(_), _) | (Resolution::Instant, _) => true,
+        (_, Resolution::Ticks(_)) | (_, Resolution::Instant) => false,
+        (Resolution::Week, Resolution::Month) => false,
+        (source_resolution, resolution) if resolution.is_trading_period() => source_resolution < resolution,
+        (source_resolution, resolution) => {
+            let source_seconds = source_resolution.as_duration().num_seconds();
+            let seconds = resolution.as_duration().num_seconds();
+            source_seconds > 0 && source_seconds < seconds && seconds % source_seconds == 0
         }
-        //eprintln!("Warmup complete: {}", history.len());
-        (consolidator, history)
     }
 }
 
@@ -280,3 +491,83 @@ impl ConsolidatedData {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::FuturesExchange;
+
+    fn subscription(resolution: Resolution, base_data_type: BaseDataType) -> DataSubscription {
+        DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, resolution, base_data_type, MarketType::Futures(FuturesExchange::CME))
+    }
+
+    #[test]
+    fn test_is_valid_warmup_source() {
+        let five_minute = subscription(Resolution::Minutes(5), BaseDataType::Candles);
+        assert!(is_valid_warmup_source(&subscription(Resolution::Minutes(1), BaseDataType::Candles), &five_minute));
+        assert!(is_valid_warmup_source(&subscription(Resolution::Ticks(1), BaseDataType::Ticks), &five_minute));
+        assert!(is_valid_warmup_source(&five_minute, &five_minute));
+        // 3 minute bars can not be consolidated into 5 minute bars
+        assert!(!is_valid_warmup_source(&subscription(Resolution::Minutes(3), BaseDataType::Candles), &five_minute));
+        // A higher resolution or a different data type is not a valid source
+        assert!(!is_valid_warmup_source(&subscription(Resolution::Minutes(15), BaseDataType::Candles), &five_minute));
+        assert!(!is_valid_warmup_source(&subscription(Resolution::Minutes(1), BaseDataType::QuoteBars), &five_minute));
+        // Footprints need the aggressor of each trade
+        let five_minute_footprint = subscription(Resolution::Minutes(5), BaseDataType::Footprints);
+        assert!(is_valid_warmup_source(&subscription(Resolution::Ticks(1), BaseDataType::Ticks), &five_minute_footprint));
+        assert!(!is_valid_warmup_source(&subscription(Resolution::Minutes(1), BaseDataType::Candles), &five_minute_footprint));
+        // Trading periods consolidate from intraday bars or shorter periods, weeks do not divide into months
+        let month = subscription(Resolution::Month, BaseDataType::Candles);
+        assert!(is_valid_warmup_source(&subscription(Resolution::Day, BaseDataType::Candles), &month));
+        assert!(is_valid_warmup_source(&subscription(Resolution::Minutes(3), BaseDataType::Candles), &month));
+        assert!(!is_valid_warmup_source(&subscription(Resolution::Week, BaseDataType::Candles), &month));
+        assert!(!is_valid_warmup_source(&month, &subscription(Resolution::Week, BaseDataType::Candles)));
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_cached_bars() {
+        use chrono::TimeZone;
+        use rust_decimal_macros::dec;
+        use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+        use crate::standardized_types::subscriptions::FillPolicy;
+        use crate::strategies::consolidators::heikinashi::HeikinAshiConsolidator;
+
+        let heikin_ashi = DataSubscription::new_custom("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(5), MarketType::Futures(FuturesExchange::CME), CandleType::HeikinAshi);
+        let consolidator = || async {
+            ConsolidatorEnum::HeikinAshi(HeikinAshiConsolidator::new(heikin_ashi.clone(), FillPolicy::None, None, 2, dec!(0.25)).await.unwrap())
+        };
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 14, 0, 0).unwrap();
+        let ticks: Vec<BaseDataEnum> = (0..60).map(|minute| {
+            let price = dec!(18000) + rust_decimal::Decimal::from((minute * 7) % 11);
+            BaseDataEnum::Tick(Tick::new(heikin_ashi.symbol.clone(), price, (start + Duration::minutes(minute)).to_string(), dec!(1), Aggressor::Buy))
+        }).collect();
+
+        // Consolidating every tick
+        let mut whole_range = consolidator().await;
+        let mut bars = vec![];
+        let mut open_bar = None;
+        for tick in &ticks {
+            let consolidated = whole_range.update(tick).unwrap();
+            bars.extend(consolidated.closed_data);
+            open_bar = Some(consolidated.open_data);
+        }
+        assert_eq!(bars.len(), 11);
+
+        // The first 6 bars were cached by an earlier run, only the ticks after them are consolidated
+        let mut resumed = consolidator().await;
+        assert!(resumed.parameters().is_some());
+        // Heikin Ashi bars rebuild the last cached bar, which is skipped when it closes again
+        let consolidate_from = resumed.resume(&bars[5]);
+        assert_eq!(consolidate_from, start + Duration::minutes(25));
+        let mut resumed_bars = bars[..6].to_vec();
+        let mut resumed_open_bar = None;
+        for tick in ticks.iter().filter(|tick| tick.time_utc() >= consolidate_from) {
+            let consolidated = resumed.update(tick).unwrap();
+            resumed_bars.extend(consolidated.closed_data.filter(|bar| bar.time_utc() > bars[5].time_utc()));
+            resumed_open_bar = Some(consolidated.open_data);
+        }
+        assert_eq!(resumed_bars, bars);
+        assert_eq!(resumed_open_bar, open_bar);
+    }
+}
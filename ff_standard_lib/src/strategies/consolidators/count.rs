@@ -29,7 +29,7 @@ impl CountConsolidator {
         decimal_accuracy: u32,
         tick_size: Decimal,
     ) -> Result<Self, FundForgeError> {
-        println!("Creating Consolidator For: {}", subscription);
+        tracing::debug!("Creating Consolidator For: {}", subscription);
         let number = match subscription.resolution {
             Resolution::Ticks(num) => num,
             _ => {
@@ -46,7 +46,10 @@ impl CountConsolidator {
                 dec!(0.0),
                 "".to_string(),
                 Resolution::Ticks(number),
-                subscription.candle_type.clone().unwrap(),
+                match subscription.candle_type.clone() {
+                    Some(candle_type) => candle_type,
+                    None => return Err(FundForgeError::ClientSideErrorDebug("Candle type is required for CountConsolidator".to_string()))
+                },
             ),
             _ => {
                 return Err(FundForgeError::ClientSideErrorDebug(format!("{} is an Invalid base data type for CountConsolidator", subscription.base_data_type)))
@@ -67,7 +70,7 @@ impl CountConsolidator {
     }
 
     /// Returns a candle if the count is reached
-    pub(crate) fn update(&mut self, base_data: &BaseDataEnum) -> ConsolidatedData {
+    pub(crate) fn update(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
         match base_data {
             BaseDataEnum::Tick(tick) => {
                 if self.counter == 0 {
@@ -88,31 +91,25 @@ impl CountConsolidator {
                     let mut consolidated_candle = self.current_data.clone();
                     consolidated_candle.is_closed = true;
                     self.counter = 0;
-                    self.current_data = match self.subscription.base_data_type {
-                        BaseDataType::Ticks => Candle::new(
-                            self.subscription.symbol.clone(),
-                            dec!(0.0),
-                            dec!(0.0),
-                            dec!(0.0),
-                            dec!(0.0),
-                            base_data.time_utc().to_string(),
-                            Resolution::Ticks(self.number),
-                            self.subscription.candle_type.clone().unwrap(),
-                        ),
-                        _ => panic!(
-                            "Invalid base data type for CountConsolidator: {}",
-                            self.subscription.base_data_type
-                        ),
-                    };
-                    ConsolidatedData::with_closed(BaseDataEnum::Candle(self.current_data.clone()), BaseDataEnum::Candle(consolidated_candle))
+                    self.current_data = Candle::new(
+                        self.subscription.symbol.clone(),
+                        dec!(0.0),
+                        dec!(0.0),
+                        dec!(0.0),
+                        dec!(0.0),
+                        base_data.time_utc().to_string(),
+                        Resolution::Ticks(self.number),
+                        consolidated_candle.candle_type.clone(),
+                    );
+                    Ok(ConsolidatedData::with_closed(BaseDataEnum::Candle(self.current_data.clone()), BaseDataEnum::Candle(consolidated_candle)))
                 } else {
-                    ConsolidatedData::with_open(BaseDataEnum::Candle(self.current_data.clone()))
+                    Ok(ConsolidatedData::with_open(BaseDataEnum::Candle(self.current_data.clone())))
                 }
             }
-            _ => panic!(
+            _ => Err(FundForgeError::ClientSideErrorDebug(format!(
                 "Invalid base data type for CountConsolidator: {}",
                 base_data.base_data_type()
-            ),
+            ))),
         }
     }
 }
@@ -6,13 +6,14 @@ use crate::messages::data_server_messaging::FundForgeError;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::data_quality::DataQuality;
 use crate::standardized_types::base_data::tick::Aggressor;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::enums::MarketType;
 use crate::standardized_types::market_hours::{DaySession, TradingHours};
 use crate::standardized_types::new_types::Price;
-use crate::standardized_types::subscriptions::DataSubscription;
-use crate::strategies::consolidators::consolidator_enum::ConsolidatedData;
+use crate::standardized_types::subscriptions::{CandleType, DataSubscription};
+use crate::strategies::consolidators::consolidator_enum::{hours_parameter, ConsolidatedData};
 
 #[derive(Debug, Clone)]
 pub struct SessionTime {
@@ -54,6 +55,11 @@ pub struct DailyConsolidator {
 }
 
 impl DailyConsolidator {
+    /// The parameters which change the bars of the consolidator but are not part of the subscription, used to key the `BarCache`.
+    pub(crate) fn parameters(&self) -> String {
+        format!("hours={};decimal_accuracy={};tick_size={}", hours_parameter(Some(&self.trading_hours)), self.decimal_accuracy, self.tick_size)
+    }
+
 
     pub(crate) fn new(
         subscription: DataSubscription,
@@ -100,7 +106,7 @@ impl DailyConsolidator {
         let target_end = current_time + Duration::days(Self::DAYS_AHEAD);
 
         if target_end >= last_session_end {
-            println!("Need to extend sessions. Current last end: {}, Target end: {}", last_session_end, target_end);
+            tracing::debug!("Need to extend sessions. Current last end: {}, Target end: {}", last_session_end, target_end);
 
             // Find the start point for new sessions
             let start_date = if self.session_map.is_empty() {
@@ -114,7 +120,7 @@ impl DailyConsolidator {
                     .date_naive()
             };
 
-            println!("Starting session extension from date: {}", start_date);
+            tracing::debug!("Starting session extension from date: {}", start_date);
 
             // Generate enough days to reach our target
             let days_to_generate = (target_end - last_session_end).num_days() + 1;
@@ -123,7 +129,7 @@ impl DailyConsolidator {
                 let current_date = start_date + Duration::days(days_offset);
                 let weekday = current_date.weekday();
 
-                println!("Generating sessions for {}: {}", weekday, current_date);
+                tracing::debug!("Generating sessions for {}: {}", weekday, current_date);
 
                 // Handle the special Sunday->Monday case
                 if weekday == Weekday::Sun {
@@ -147,7 +153,7 @@ impl DailyConsolidator {
                                 .unwrap()
                                 .with_timezone(&Utc);
 
-                            println!("Adding Sunday session: {} -> {}", session_open, close_time);
+                            tracing::debug!("Adding Sunday session: {} -> {}", session_open, close_time);
 
                             self.session_map.insert(
                                 session_open,
@@ -192,7 +198,7 @@ impl DailyConsolidator {
                         .unwrap()
                         .with_timezone(&Utc);
 
-                    println!("Adding regular session: {} -> {}", session_open, close_utc);
+                    tracing::debug!("Adding regular session: {} -> {}", session_open, close_utc);
 
                     self.session_map.insert(
                         session_open,
@@ -210,9 +216,9 @@ impl DailyConsolidator {
         let cutoff = current_time - Duration::days(Self::DAYS_TO_KEEP);
         self.session_map.retain(|_, session| session.close >= cutoff);
 
-        println!("\nSession map after extension:");
+        tracing::debug!("Session map after extension:");
         for (start, session) in &self.session_map {
-            println!("  {} -> {}", start, session.close);
+            tracing::debug!("  {} -> {}", start, session.close);
         }
     }
 
@@ -267,31 +273,31 @@ impl DailyConsolidator {
         }
     }
 
-    pub fn update(&mut self, base_data: &BaseDataEnum) -> ConsolidatedData {
+    pub fn update(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
         let time = base_data.time_utc();
 
-        println!("Processing update for time: {}", time);
+        tracing::debug!("Processing update for time: {}", time);
 
         // First check if time update would close any bars
         if let Some(closed_bar) = self.update_time(time) {
-            println!("Time update closed bar at: {}", time);
-            return ConsolidatedData::with_closed(base_data.clone(), closed_bar);
+            tracing::debug!("Time update closed bar at: {}", time);
+            return Ok(ConsolidatedData::with_closed(base_data.clone(), closed_bar));
         }
 
         // Get current session without holding borrow
         let current_session = self.get_current_session(time).cloned();
 
         match &current_session {
-            Some(session) => println!("Current session: {} -> {}", session.open, session.close),
-            None => println!("No current session for time: {}", time),
+            Some(session) => tracing::debug!("Current session: {} -> {}", session.open, session.close),
+            None => tracing::debug!("No current session for time: {}", time),
         }
 
         match current_session {
             Some(session) if self.current_data.is_none() => {
-                println!("Creating new bar for session starting at: {}", session.open);
-                let new_bar = self.create_bar(base_data, session.open);
+                tracing::debug!("Creating new bar for session starting at: {}", session.open);
+                let new_bar = self.create_bar(base_data, session.open)?;
                 self.current_data = Some(BaseDataEnum::Candle(new_bar.clone()));
-                ConsolidatedData::with_open(BaseDataEnum::Candle(new_bar))
+                Ok(ConsolidatedData::with_open(BaseDataEnum::Candle(new_bar)))
             }
             Some(_) => {
                 if let Some(ref mut current_bar) = self.current_data {
@@ -300,14 +306,14 @@ impl DailyConsolidator {
                         tick_size: self.tick_size,
                         decimal_accuracy: self.decimal_accuracy,
                     };
-                    Self::update_bar(&params, current_bar, base_data);
-                    println!("Updated existing bar");
+                    Self::update_bar(&params, current_bar, base_data)?;
+                    tracing::debug!("Updated existing bar");
                 }
-                ConsolidatedData::with_open(base_data.clone())
+                Ok(ConsolidatedData::with_open(base_data.clone()))
             }
             None => {
-                println!("No session found for time: {}", time);
-                ConsolidatedData::with_open(base_data.clone())
+                tracing::debug!("No session found for time: {}", time);
+                Ok(ConsolidatedData::with_open(base_data.clone()))
             }
         }
     }
@@ -405,7 +411,7 @@ impl DailyConsolidator {
         params: &UpdateParams,
         current_bar: &mut BaseDataEnum,
         new_data: &BaseDataEnum
-    ) {
+    ) -> Result<(), FundForgeError> {
         match (current_bar, new_data) {
             (BaseDataEnum::Candle(candle), BaseDataEnum::Tick(tick)) => {
                 candle.high = candle.high.max(tick.price);
@@ -438,8 +444,9 @@ impl DailyConsolidator {
                 current.ask_volume += new.ask_volume;
                 current.bid_volume += new.bid_volume;
             }
-            _ => panic!("Invalid base data type combination for update"),
+            (_, new_data) => return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for DailyCandle consolidator: {}", new_data.base_data_type()))),
         }
+        Ok(())
     }
 
     #[allow(dead_code)]
@@ -463,8 +470,8 @@ impl DailyConsolidator {
         }
     }
 
-    fn create_bar(&self, base_data: &BaseDataEnum, session_open: DateTime<Utc>) -> Candle {
-        match base_data {
+    fn create_bar(&self, base_data: &BaseDataEnum, session_open: DateTime<Utc>) -> Result<Candle, FundForgeError> {
+        let mut bar = match base_data {
             BaseDataEnum::Tick(tick) => {
                 let (ask_volume, bid_volume) = match tick.aggressor {
                     Aggressor::Buy => (dec!(0.0), tick.volume),
@@ -480,7 +487,7 @@ impl DailyConsolidator {
                     bid_volume,
                     session_open.to_string(),
                     self.subscription.resolution.clone(),
-                    self.subscription.candle_type.clone().unwrap(),
+                    self.subscription.candle_type.clone().unwrap_or(CandleType::CandleStick),
                 )
             }
             BaseDataEnum::Candle(candle) => {
@@ -490,8 +497,11 @@ impl DailyConsolidator {
                 new_candle.time = session_open.to_string();
                 new_candle
             }
-            _ => panic!("Invalid base data type for Candle consolidator"),
-        }
+            _ => return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for DailyCandle consolidator: {}", base_data.base_data_type()))),
+        };
+        bar.quality.merge(&base_data.quality());
+        bar.quality.insert(DataQuality::CONSOLIDATED);
+        Ok(bar)
     }
 
     // Add these constants for session management
@@ -567,7 +577,7 @@ mod tests {
     use chrono_tz::America::New_York;
     use crate::standardized_types::datavendor_enum::DataVendor;
     use crate::standardized_types::resolution::Resolution;
-    use crate::standardized_types::subscriptions::{CandleType, Symbol};
+    use crate::standardized_types::subscriptions::Symbol;
 
     fn setup_trading_hours() -> TradingHours {
         TradingHours {
@@ -610,6 +620,8 @@ mod tests {
             resolution: Resolution::Day,
             candle_type: Some(CandleType::CandleStick),
             market_type: MarketType::CFD,
+            fill_policy: None,
+            fallback_vendors: vec![],
         };
 
         let mut consolidator = DailyConsolidator::new(
@@ -645,7 +657,7 @@ mod tests {
             CandleType::CandleStick,
         );
 
-        consolidator.update(&BaseDataEnum::Candle(candle));
+        consolidator.update(&BaseDataEnum::Candle(candle)).unwrap();
 
         // Verify old sessions are cleaned up
         let oldest_session = consolidator.session_map.first_key_value().unwrap().1.open;
@@ -715,7 +727,7 @@ mod tests {
                 daily_bars.push(closed_bar);
             }
 
-            let result = consolidator.update(&BaseDataEnum::Candle(candle));
+            let result = consolidator.update(&BaseDataEnum::Candle(candle)).unwrap();
             if let Some(closed_bar) = result.closed_data {
                 daily_bars.push(closed_bar);
             }
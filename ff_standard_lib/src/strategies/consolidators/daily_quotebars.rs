@@ -5,12 +5,13 @@ use crate::messages::data_server_messaging::FundForgeError;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::base_data::quotebar::QuoteBar;
+use crate::standardized_types::base_data::data_quality::DataQuality;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::enums::MarketType;
 use crate::standardized_types::market_hours::{DaySession, TradingHours};
 use crate::standardized_types::new_types::Price;
 use crate::standardized_types::subscriptions::{CandleType, DataSubscription};
-use crate::strategies::consolidators::consolidator_enum::ConsolidatedData;
+use crate::strategies::consolidators::consolidator_enum::{hours_parameter, ConsolidatedData};
 use crate::strategies::consolidators::daily_candles::{SessionTime, TimeAction, UpdateParams};
 
 #[derive(Debug, Clone)]
@@ -32,6 +33,11 @@ pub struct DailyQuoteConsolidator {
 }
 
 impl DailyQuoteConsolidator {
+    /// The parameters which change the bars of the consolidator but are not part of the subscription, used to key the `BarCache`.
+    pub(crate) fn parameters(&self) -> String {
+        format!("hours={};decimal_accuracy={};tick_size={}", hours_parameter(Some(&self.trading_hours)), self.decimal_accuracy, self.tick_size)
+    }
+
     pub(crate) fn new(
         subscription: DataSubscription,
         decimal_accuracy: u32,
@@ -73,7 +79,7 @@ impl DailyQuoteConsolidator {
         // If we have no sessions or our earliest session is too late, reinitialize
         if self.session_map.is_empty() ||
             self.session_map.first_key_value().map(|(t, _)| *t).unwrap_or(current_time) > earliest_needed {
-            println!("Reinitializing quote session map for time: {}", current_time);
+            tracing::debug!("Reinitializing quote session map for time: {}", current_time);
 
             // Start from earliest needed time
             let start_week = earliest_needed
@@ -98,7 +104,7 @@ impl DailyQuoteConsolidator {
                 .unwrap_or(current_time);
 
             if current_time + Duration::days(1) >= last_session_end {
-                println!("Extending quote sessions forward from: {}", last_session_end);
+                tracing::debug!("Extending quote sessions forward from: {}", last_session_end);
 
                 let extension_start = last_session_end
                     .with_timezone(&tz)
@@ -115,9 +121,9 @@ impl DailyQuoteConsolidator {
         let cutoff = current_time - Duration::days(Self::DAYS_TO_KEEP);
         self.session_map.retain(|_, session| session.close >= cutoff);
 
-        println!("Quote session map after extension:");
+        tracing::debug!("Quote session map after extension:");
         for (start, session) in &self.session_map {
-            println!("  {} -> {}", start, session.close);
+            tracing::debug!("  {} -> {}", start, session.close);
         }
     }
 
@@ -174,31 +180,31 @@ impl DailyQuoteConsolidator {
         }
     }
 
-    pub fn update(&mut self, base_data: &BaseDataEnum) -> ConsolidatedData {
+    pub fn update(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
         let time = base_data.time_utc();
 
-        println!("Processing quote update for time: {}", time);
+        tracing::debug!("Processing quote update for time: {}", time);
 
         // First check if time update would close any bars
         if let Some(closed_bar) = self.update_time(time) {
-            println!("Time update closed quote bar at: {}", time);
-            return ConsolidatedData::with_closed(base_data.clone(), closed_bar);
+            tracing::debug!("Time update closed quote bar at: {}", time);
+            return Ok(ConsolidatedData::with_closed(base_data.clone(), closed_bar));
         }
 
         // Get current session without holding borrow
         let current_session = self.get_current_session(time).cloned();
 
         match &current_session {
-            Some(session) => println!("Current quote session: {} -> {}", session.open, session.close),
-            None => println!("No current quote session for time: {}", time),
+            Some(session) => tracing::debug!("Current quote session: {} -> {}", session.open, session.close),
+            None => tracing::debug!("No current quote session for time: {}", time),
         }
 
         match current_session {
             Some(session) if self.current_data.is_none() => {
-                println!("Creating new quote bar for session starting at: {}", session.open);
-                let new_bar = self.create_bar(base_data, session.open);
+                tracing::debug!("Creating new quote bar for session starting at: {}", session.open);
+                let new_bar = self.create_bar(base_data, session.open)?;
                 self.current_data = Some(BaseDataEnum::QuoteBar(new_bar.clone()));
-                ConsolidatedData::with_open(BaseDataEnum::QuoteBar(new_bar))
+                Ok(ConsolidatedData::with_open(BaseDataEnum::QuoteBar(new_bar)))
             }
             Some(_) => {
                 if let Some(ref mut current_bar) = self.current_data {
@@ -207,14 +213,14 @@ impl DailyQuoteConsolidator {
                         tick_size: self.tick_size,
                         decimal_accuracy: self.decimal_accuracy,
                     };
-                    Self::update_bar(&params, current_bar, base_data);
-                    println!("Updated existing quote bar");
+                    Self::update_bar(&params, current_bar, base_data)?;
+                    tracing::debug!("Updated existing quote bar");
                 }
-                ConsolidatedData::with_open(base_data.clone())
+                Ok(ConsolidatedData::with_open(base_data.clone()))
             }
             None => {
-                println!("No quote session found for time: {}", time);
-                ConsolidatedData::with_open(base_data.clone())
+                tracing::debug!("No quote session found for time: {}", time);
+                Ok(ConsolidatedData::with_open(base_data.clone()))
             }
         }
     }
@@ -393,7 +399,7 @@ impl DailyQuoteConsolidator {
         params: &UpdateParams,
         current_bar: &mut BaseDataEnum,
         new_data: &BaseDataEnum
-    ) {
+    ) -> Result<(), FundForgeError> {
         match (current_bar, new_data) {
             (BaseDataEnum::QuoteBar(quote_bar), BaseDataEnum::Quote(quote)) => {
                 quote_bar.ask_high = quote_bar.ask_high.max(quote.ask);
@@ -437,8 +443,9 @@ impl DailyQuoteConsolidator {
                     params.decimal_accuracy,
                 );
             }
-            _ => panic!("Invalid base data type combination for update"),
+            (_, new_data) => return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for DailyQuoteBar consolidator: {}", new_data.base_data_type()))),
         }
+        Ok(())
     }
 
     // Add these constants for session management
@@ -506,8 +513,8 @@ impl DailyQuoteConsolidator {
         }
     }
 
-    fn create_bar(&self, base_data: &BaseDataEnum, session_open: DateTime<Utc>) -> QuoteBar {
-        match base_data {
+    fn create_bar(&self, base_data: &BaseDataEnum, session_open: DateTime<Utc>) -> Result<QuoteBar, FundForgeError> {
+        let mut bar = match base_data {
             BaseDataEnum::Quote(quote) => {
                 QuoteBar::new(
                     self.subscription.symbol.clone(),
@@ -528,8 +535,11 @@ impl DailyQuoteConsolidator {
                 new_quote_bar.time = session_open.to_string();
                 new_quote_bar
             }
-            _ => panic!("Invalid base data type for QuoteBar consolidator"),
-        }
+            _ => return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for DailyQuoteBar consolidator: {}", base_data.base_data_type()))),
+        };
+        bar.quality.merge(&base_data.quality());
+        bar.quality.insert(DataQuality::CONSOLIDATED);
+        Ok(bar)
     }
 }
 
@@ -597,6 +607,8 @@ mod tests {
             resolution: Resolution::Day,
             candle_type: Some(CandleType::CandleStick),
             market_type: MarketType::CFD,
+            fill_policy: None,
+            fallback_vendors: vec![],
         };
 
         // Get the start time from the test data and print it
@@ -641,7 +653,7 @@ mod tests {
                 daily_bars.push(closed_bar);
             }
 
-            let result = consolidator.update(&BaseDataEnum::QuoteBar(quote_bar.clone()));
+            let result = consolidator.update(&BaseDataEnum::QuoteBar(quote_bar.clone())).unwrap();
             if let Some(closed_bar) = result.closed_data {
                 println!("Data update closed bar at {}", time);
                 daily_bars.push(closed_bar);
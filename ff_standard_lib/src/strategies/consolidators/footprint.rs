@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use crate::helpers::converters::open_time;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::base_data::data_quality::DataQuality;
+use crate::standardized_types::base_data::footprint::Footprint;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::consolidators::consolidator_enum::ConsolidatedData;
+
+/// Consolidates ticks into time based `Footprint` bars.
+/// Supports Ticks only, bars with no trades are not filled forward since a footprint with no levels has nothing to show.
+pub struct FootprintConsolidator {
+    current_data: Option<Footprint>,
+    pub(crate) subscription: DataSubscription,
+}
+
+impl FootprintConsolidator {
+    pub(crate) fn new(subscription: DataSubscription) -> Result<Self, FundForgeError> {
+        tracing::debug!("Creating Consolidator For: {}", subscription);
+        if subscription.base_data_type != BaseDataType::Footprints {
+            return Err(FundForgeError::ClientSideErrorDebug(format!("{} is an Invalid base data type for FootprintConsolidator", subscription.base_data_type)))
+        }
+        match subscription.resolution {
+            Resolution::Seconds(_) | Resolution::Minutes(_) | Resolution::Hours(_) => {}
+            _ => return Err(FundForgeError::ClientSideErrorDebug(format!("{:?} is an Invalid resolution for FootprintConsolidator", subscription.resolution)))
+        }
+        Ok(FootprintConsolidator {
+            current_data: None,
+            subscription,
+        })
+    }
+
+    pub(crate) fn update(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
+        let tick = match base_data {
+            BaseDataEnum::Tick(tick) => tick,
+            _ => return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for Footprint consolidator: {}", base_data.base_data_type())))
+        };
+
+        let mut closed_data = None;
+        if let Some(current_bar) = self.current_data.as_mut() {
+            if tick.time_utc() < current_bar.time_utc() {
+                return Ok(ConsolidatedData::with_open(BaseDataEnum::Footprint(current_bar.clone())));
+            }
+            if tick.time_utc() < current_bar.time_closed_utc() {
+                current_bar.update(tick);
+                return Ok(ConsolidatedData::with_open(BaseDataEnum::Footprint(current_bar.clone())));
+            }
+            let mut consolidated_bar = current_bar.clone();
+            consolidated_bar.is_closed = true;
+            closed_data = Some(BaseDataEnum::Footprint(consolidated_bar));
+        }
+
+        let time = open_time(&self.subscription, tick.time_utc());
+        let mut new_bar = Footprint::new(tick, time.to_string(), self.subscription.resolution);
+        new_bar.quality.insert(DataQuality::CONSOLIDATED);
+        self.current_data = Some(new_bar.clone());
+        Ok(ConsolidatedData {
+            open_data: BaseDataEnum::Footprint(new_bar),
+            closed_data,
+        })
+    }
+
+    /// Closes the current bar if the time has passed its closing time.
+    pub(crate) fn update_time(&mut self, time: DateTime<Utc>) -> Option<BaseDataEnum> {
+        match &self.current_data {
+            Some(current_bar) if time >= current_bar.time_closed_utc() => {
+                let mut consolidated_bar = self.current_data.take().unwrap();
+                consolidated_bar.is_closed = true;
+                Some(BaseDataEnum::Footprint(consolidated_bar))
+            }
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::new_types::Price;
+
+    #[test]
+    fn test_footprint_consolidator() {
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(1), BaseDataType::Footprints, MarketType::Futures(FuturesExchange::CME));
+        let mut consolidator = FootprintConsolidator::new(subscription.clone()).unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 14, 30, 0).unwrap();
+        let tick = |seconds: i64, price: Price, aggressor: Aggressor| BaseDataEnum::Tick(Tick {
+            symbol: subscription.symbol.clone(),
+            price,
+            time: (start + Duration::seconds(seconds)).to_string(),
+            volume: dec!(1),
+            aggressor,
+            quality: DataQuality::default(),
+            conditions: None,
+        });
+
+        assert!(consolidator.update(&tick(5, dec!(100.25), Aggressor::Buy)).unwrap().closed_data.is_none());
+        let open = consolidator.update(&tick(20, dec!(100.00), Aggressor::Sell)).unwrap().open_data;
+        match open {
+            BaseDataEnum::Footprint(footprint) => {
+                assert_eq!(footprint.time_utc(), start);
+                assert_eq!(footprint.levels.len(), 2);
+                assert_eq!(footprint.delta(), dec!(0));
+            }
+            _ => panic!("Expected a footprint")
+        }
+
+        // The first tick of the next minute closes the bar.
+        let consolidated = consolidator.update(&tick(61, dec!(100.50), Aggressor::Buy)).unwrap();
+        match consolidated.closed_data {
+            Some(BaseDataEnum::Footprint(footprint)) => {
+                assert!(footprint.is_closed);
+                assert_eq!(footprint.close, dec!(100.00));
+            }
+            _ => panic!("Expected a closed footprint")
+        }
+        assert_eq!(consolidated.open_data.time_utc(), start + Duration::minutes(1));
+
+        assert!(consolidator.update_time(start + Duration::seconds(90)).is_none());
+        assert!(consolidator.update_time(start + Duration::minutes(2)).unwrap().is_closed());
+        assert!(consolidator.update_time(start + Duration::minutes(3)).is_none());
+    }
+}
@@ -1,14 +1,15 @@
-use crate::helpers::converters::open_time;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::data_quality::DataQuality;
 use crate::standardized_types::base_data::traits::BaseData;
-use crate::standardized_types::subscriptions::{CandleType, DataSubscription};
+use crate::standardized_types::subscriptions::{CandleType, DataSubscription, FillPolicy};
+use crate::standardized_types::market_hours::TradingHours;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use crate::helpers::converters;
-use crate::strategies::consolidators::consolidator_enum::ConsolidatedData;
+use crate::strategies::consolidators::consolidator_enum::{hours_parameter, ConsolidatedData};
 use crate::messages::data_server_messaging::FundForgeError;
 use crate::standardized_types::base_data::tick::Aggressor;
 use crate::standardized_types::enums::{MarketType};
@@ -21,12 +22,28 @@ pub struct HeikinAshiConsolidator {
     previous_ha_open: Price,
     decimal_accuracy: u32, //todo, we might need to use tick size to round cme_futures and decimal accuracy to round other products
     tick_size: Decimal,
-    fill_forward: bool,
+    fill_policy: FillPolicy,
+    hours: Option<TradingHours>,
     market_type: MarketType,
     last_bar_open: DateTime<Utc>,
 }
 
 impl HeikinAshiConsolidator {
+    /// The parameters which change the bars of the consolidator but are not part of the subscription, used to key the `BarCache`.
+    pub(crate) fn parameters(&self) -> String {
+        format!("fill_policy={:?};hours={};decimal_accuracy={};tick_size={}", self.fill_policy, hours_parameter(self.hours.as_ref()), self.decimal_accuracy, self.tick_size)
+    }
+
+    /// Rebuilds a closed bar of an earlier run from its source data, the Heikin Ashi values of the next bar are taken when the bar opens rather than when it closes.
+    /// The bar is rebuilt with the same open. Returns the time the source data of the bar starts.
+    pub(crate) fn resume(&mut self, last_bar: &BaseDataEnum) -> DateTime<Utc> {
+        if let BaseDataEnum::Candle(candle) = last_bar {
+            self.previous_ha_open = candle.open;
+            self.previous_ha_close = candle.open;
+        }
+        last_bar.time_utc()
+    }
+
     fn candle_from_base_data(
         &self,
         ha_open: Price,
@@ -54,17 +71,18 @@ impl HeikinAshiConsolidator {
             is_closed,
             range,
             candle_type: CandleType::HeikinAshi,
+            quality: DataQuality::new(DataQuality::CONSOLIDATED),
         }
     }
 
-    fn new_heikin_ashi_candle(&mut self, new_data: &BaseDataEnum) -> Candle {
+    fn new_heikin_ashi_candle(&mut self, new_data: &BaseDataEnum) -> Result<Candle, FundForgeError> {
         let mut time = converters::open_time(&self.subscription, new_data.time_utc());
         if time == self.last_bar_open {
             time += self.subscription.resolution.as_duration();
         }
         self.last_bar_open = time.clone();
 
-        match new_data {
+        let candle = match new_data {
             BaseDataEnum::Candle(candle) => {
                 if self.previous_ha_close == dec!(0.0) && self.previous_ha_open == dec!(0.0) {
                     self.previous_ha_close = candle.close;
@@ -179,15 +197,17 @@ impl HeikinAshiConsolidator {
                     ha_high - ha_low,
                 )
             }
-            _ => panic!("Invalid base data type for Heikin Ashi calculation"),
-        }
+            _ => return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for Heikin Ashi calculation: {}", new_data.base_data_type()))),
+        };
+        Ok(candle)
     }
 }
 
 impl HeikinAshiConsolidator {
     pub(crate) async fn new(
         subscription: DataSubscription,
-        fill_forward: bool,
+        fill_policy: FillPolicy,
+        hours: Option<TradingHours>,
         decimal_accuracy: u32,
         tick_size: Decimal,
     ) -> Result<HeikinAshiConsolidator, FundForgeError> {
@@ -199,7 +219,7 @@ impl HeikinAshiConsolidator {
             ));
         }
 
-        println!("Creating Consolidator For: {}", subscription);
+        tracing::debug!("Creating Consolidator For: {}", subscription);
         if let Some(candle_type) = &subscription.candle_type {
             if candle_type != &CandleType::HeikinAshi {
                 return Err(FundForgeError::ClientSideErrorDebug(format!(
@@ -220,7 +240,8 @@ impl HeikinAshiConsolidator {
             previous_ha_open: dec!(0.0),
             decimal_accuracy,
             tick_size,
-            fill_forward,
+            fill_policy,
+            hours,
             last_bar_open: DateTime::<Utc>::MIN_UTC,
         })
     }
@@ -246,51 +267,74 @@ impl HeikinAshiConsolidator {
         None
     }
 
+    /// Fills the period after the last bar according to the fill policy, once the consolidator has a Heikin Ashi close to fill from.
     fn fill_forward(&mut self, time: DateTime<Utc>) {
-        if self.fill_forward {
-            let ha_open =  self.market_type.round_price((self.previous_ha_open + self.previous_ha_close) / dec!(2.0), self.tick_size, self.decimal_accuracy);
-            let mut time = converters::open_time(&self.subscription, time);
-            if time == self.last_bar_open {
-                time += self.subscription.resolution.as_duration();
+        if self.previous_ha_close == dec!(0.0) && self.previous_ha_open == dec!(0.0) {
+            return;
+        }
+        let mut open = converters::open_time(&self.subscription, time);
+        if open == self.last_bar_open {
+            open += self.subscription.resolution.as_duration();
+        }
+        if !self.fill_policy.should_fill(open, self.hours.as_ref()) {
+            return;
+        }
+        let (ha_open, quality) = match self.fill_policy.is_empty() {
+            true => (dec!(0.0), DataQuality::new(DataQuality::EMPTY | DataQuality::CONSOLIDATED)),
+            false => (
+                self.market_type.round_price((self.previous_ha_open + self.previous_ha_close) / dec!(2.0), self.tick_size, self.decimal_accuracy),
+                DataQuality::new(DataQuality::ESTIMATED | DataQuality::CONSOLIDATED)
+            ),
+        };
+        self.last_bar_open = open;
+        self.current_data = Some(BaseDataEnum::Candle(Candle {
+            symbol: self.subscription.symbol.clone(),
+            open: ha_open,
+            high: ha_open,
+            low: ha_open,
+            close: ha_open,
+            volume: dec!(0.0),
+            ask_volume: dec!(0.0),
+            bid_volume: dec!(0.0),
+            time: open.to_string(),
+            resolution: self.subscription.resolution.clone(),
+            is_closed: false,
+            range: dec!(0.0),
+            candle_type: CandleType::HeikinAshi,
+            quality,
+        }));
+    }
+
+    /// Data arriving during an empty bar replaces the bar, rather than being merged into its zero prices.
+    fn clear_empty_bar(&mut self, base_data: &BaseDataEnum) {
+        if let Some(current_bar) = &self.current_data {
+            if current_bar.quality().is_empty() && base_data.time_utc() >= current_bar.time_utc() && base_data.time_utc() < current_bar.time_closed_utc() {
+                self.current_data = None;
+                self.last_bar_open = DateTime::<Utc>::MIN_UTC;
             }
-            self.last_bar_open = time.clone();
-            self.current_data = Some(BaseDataEnum::Candle(Candle {
-                symbol: self.subscription.symbol.clone(),
-                open: ha_open,
-                high: ha_open,
-                low: ha_open,
-                close: ha_open,
-                volume: dec!(0.0),
-                ask_volume: dec!(0.0),
-                bid_volume: dec!(0.0),
-                time: open_time(&self.subscription, time).to_string(),
-                resolution: self.subscription.resolution.clone(),
-                is_closed: false,
-                range: dec!(0.0),
-                candle_type: CandleType::HeikinAshi,
-            }));
         }
     }
 
     //problem where this is returning a closed candle constantly
-    pub(crate) fn update(&mut self, base_data: &BaseDataEnum) -> ConsolidatedData {
+    pub(crate) fn update(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
+        self.clear_empty_bar(base_data);
         if self.current_data.is_none() {
-            let data = self.new_heikin_ashi_candle(base_data);
-            self.current_data = Some(BaseDataEnum::Candle(data));
-            return ConsolidatedData::with_open(self.current_data.clone().unwrap())
+            let data = BaseDataEnum::Candle(self.new_heikin_ashi_candle(base_data)?);
+            self.current_data = Some(data.clone());
+            return Ok(ConsolidatedData::with_open(data))
         } else if let Some(current_bar) = self.current_data.as_mut() {
             let time = base_data.time_closed_utc();
             if time < current_bar.time_utc() {
                 // We've already processed data for this time or earlier, so we skip it
-                return ConsolidatedData::with_open(current_bar.clone());
+                return Ok(ConsolidatedData::with_open(current_bar.clone()));
             }
 
             if base_data.time_utc() >= current_bar.time_closed_utc() {
                 let mut consolidated_bar = current_bar.clone();
                 consolidated_bar.set_is_closed(true);
-                let new_bar = self.new_heikin_ashi_candle(base_data);
+                let new_bar = self.new_heikin_ashi_candle(base_data)?;
                 self.current_data = Some(BaseDataEnum::Candle(new_bar.clone()));
-                return ConsolidatedData::with_closed(BaseDataEnum::Candle(new_bar), consolidated_bar);
+                return Ok(ConsolidatedData::with_closed(BaseDataEnum::Candle(new_bar), consolidated_bar));
             }
             match current_bar {
                 BaseDataEnum::Candle(candle) =>
@@ -306,7 +350,7 @@ impl HeikinAshiConsolidator {
                                 Aggressor::None => {}
                             };
                             candle.close = self.market_type.round_price((candle.open + candle.high + candle.low + candle.close) / dec!(4.0), self.tick_size, self.decimal_accuracy);
-                            return ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone()))
+                            return Ok(ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone())))
                         }
                         BaseDataEnum::Candle(new_candle) => {
                             candle.high = new_candle.high.max(candle.high);
@@ -316,7 +360,7 @@ impl HeikinAshiConsolidator {
                             candle.ask_volume += new_candle.ask_volume;
                             candle.bid_volume += new_candle.bid_volume;
                             candle.close = self.market_type.round_price((candle.open + candle.high + candle.low + candle.close) / dec!(4.0), self.tick_size, self.decimal_accuracy);
-                            return ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone()))
+                            return Ok(ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone())))
                         }
                         BaseDataEnum::QuoteBar(bar) => {
                             candle.high = bar.bid_high.max(candle.high);
@@ -326,7 +370,7 @@ impl HeikinAshiConsolidator {
                             candle.bid_volume += bar.bid_volume;
                             candle.ask_volume += bar.ask_volume;
                             candle.close = self.market_type.round_price((candle.open + candle.high + candle.low + candle.close) / dec!(4.0), self.tick_size, self.decimal_accuracy);
-                            return ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone()))
+                            return Ok(ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone())))
                         }
                         BaseDataEnum::Quote(quote) => {
                             candle.high = candle.high.max(quote.bid);
@@ -336,22 +380,16 @@ impl HeikinAshiConsolidator {
                             candle.volume += quote.bid_volume + quote.ask_volume;
                             candle.range = self.market_type.round_price(candle.high - candle.low, self.tick_size, self.decimal_accuracy);
                             candle.close = self.market_type.round_price((candle.open + candle.high + candle.low + candle.close) / dec!(4.0), self.tick_size, self.decimal_accuracy);
-                            return ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone()))
+                            return Ok(ConsolidatedData::with_open(BaseDataEnum::Candle(candle.clone())))
                         }
-                        _ => panic!(
-                            "Invalid base data type for Heikin Ashi consolidator: {}",
-                            base_data.base_data_type()
-                        ),
+                        _ => {}
                     },
-                _ => panic!(
-                    "Invalid base data type for Candle consolidator: {}",
-                    base_data.base_data_type()
-                ),
+                _ => {}
             }
         }
-        panic!(
-            "Invalid base data type for Candle consolidator: {}",
+        Err(FundForgeError::ClientSideErrorDebug(format!(
+            "Invalid base data type for Heikin Ashi consolidator: {}",
             base_data.base_data_type()
-        )
+        )))
     }
 }
@@ -0,0 +1,350 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc, Weekday};
+use rust_decimal_macros::dec;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::data_quality::DataQuality;
+use crate::standardized_types::base_data::quotebar::QuoteBar;
+use crate::standardized_types::base_data::tick::Aggressor;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::enums::MarketType;
+use crate::standardized_types::market_hours::{is_us_market_holiday, TradingHours};
+use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::subscriptions::{CandleType, DataSubscription};
+use crate::strategies::consolidators::consolidator_enum::{hours_parameter, ConsolidatedData};
+
+/// Consolidates `Resolution::Day`, `Resolution::Week` and `Resolution::Month` bars aligned to the trading days of the exchange rather than midnight UTC.
+///
+/// Each data point belongs to the trading date of its session, see `TradingHours::trading_date()`, eg. the CME session which opens Sunday 17:00 CT trades for Monday,
+/// so a weekly bar runs from the Sunday open to the Friday close and a monthly bar from the open of the first session trading in the month to the close of the last.
+/// US market holidays are skipped for equities and ETFs when finding the first and last session of a period, futures trade a shortened session on most of them.
+/// Without trading hours the periods are UTC days, weeks starting on Monday and calendar months, eg. for crypto.
+///
+/// The bar closes when the time passes the close of the last session of the period, or when data trading for a later period arrives.
+/// Candles are consolidated from ticks or candles and quote bars from quotes or quote bars, no rounding is needed because the prices are not changed.
+pub struct TradingPeriodConsolidator {
+    current_data: Option<BaseDataEnum>,
+    pub(crate) subscription: DataSubscription,
+    hours: Option<TradingHours>,
+    /// The first date of the period of the current bar, kept after the bar closes so late data for the period is ignored.
+    period_start: Option<NaiveDate>,
+    /// The close of the last session of the period of the current bar.
+    period_close: Option<DateTime<Utc>>,
+}
+
+impl TradingPeriodConsolidator {
+    /// The parameters which change the bars of the consolidator but are not part of the subscription, used to key the `BarCache`.
+    pub(crate) fn parameters(&self) -> String {
+        format!("hours={}", hours_parameter(self.hours.as_ref()))
+    }
+
+    pub fn new(subscription: DataSubscription, hours: Option<TradingHours>) -> Result<Self, FundForgeError> {
+        if !subscription.resolution.is_trading_period() {
+            return Err(FundForgeError::ClientSideErrorDebug(format!("{} is an Invalid resolution for TradingPeriodConsolidator", subscription.resolution)));
+        }
+        match subscription.base_data_type {
+            BaseDataType::Candles | BaseDataType::QuoteBars => {}
+            _ => return Err(FundForgeError::ClientSideErrorDebug(format!("{} is an Invalid base data type for TradingPeriodConsolidator", subscription.base_data_type)))
+        }
+        Ok(Self {
+            current_data: None,
+            subscription,
+            hours,
+            period_start: None,
+            period_close: None,
+        })
+    }
+
+    fn trading_date(&self, time: DateTime<Utc>) -> NaiveDate {
+        self.hours.as_ref()
+            .and_then(|hours| hours.trading_date(time))
+            .unwrap_or_else(|| time.date_naive())
+    }
+
+    /// The first date of the period the trading date belongs to.
+    fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self.subscription.resolution {
+            Resolution::Week => {
+                let week_start = self.hours.as_ref().map(|hours| hours.week_start).unwrap_or(Weekday::Mon);
+                date.week(week_start).first_day()
+            }
+            Resolution::Month => date.with_day(1).unwrap(),
+            _ => date,
+        }
+    }
+
+    /// The first date after the period.
+    fn period_end(&self, start: NaiveDate) -> NaiveDate {
+        match self.subscription.resolution {
+            Resolution::Week => start + Duration::days(7),
+            Resolution::Month => start + Months::new(1),
+            _ => start + Duration::days(1),
+        }
+    }
+
+    fn is_trading_day(&self, hours: &TradingHours, date: NaiveDate) -> bool {
+        let is_holiday = match self.subscription.market_type {
+            MarketType::Equities(_) | MarketType::ETF => is_us_market_holiday(date),
+            _ => false,
+        };
+        !is_holiday && hours.session_close(date).is_some()
+    }
+
+    /// The open of the first session of the period, midnight UTC of the first date without trading hours.
+    fn period_open(&self, start: NaiveDate) -> DateTime<Utc> {
+        let midnight = start.and_time(Default::default()).and_utc();
+        let hours = match &self.hours {
+            Some(hours) => hours,
+            None => return midnight,
+        };
+        start.iter_days()
+            .take_while(|date| *date < self.period_end(start))
+            .find(|date| self.is_trading_day(hours, *date))
+            .and_then(|date| hours.session_open(date))
+            .unwrap_or(midnight)
+    }
+
+    /// The close of the last session of the period, midnight UTC after the period without trading hours.
+    fn period_close(&self, start: NaiveDate) -> DateTime<Utc> {
+        let end = self.period_end(start);
+        let midnight = end.and_time(Default::default()).and_utc();
+        let hours = match &self.hours {
+            Some(hours) => hours,
+            None => return midnight,
+        };
+        (1..=(end - start).num_days())
+            .map(|days_back| end - Duration::days(days_back))
+            .find(|date| self.is_trading_day(hours, *date))
+            .and_then(|date| hours.session_close(date))
+            .unwrap_or(midnight)
+    }
+
+    /// The open of the period the time trades in, the time of its bar.
+    pub(crate) fn period_open_of(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        self.period_open(self.period_start(self.trading_date(time)))
+    }
+
+    /// Continues from a closed bar of an earlier run, later data for its period is ignored. Returns the close of the period of the bar.
+    pub(crate) fn resume(&mut self, last_bar: &BaseDataEnum) -> DateTime<Utc> {
+        let start = self.period_start(self.trading_date(last_bar.time_utc()));
+        self.period_start = Some(start);
+        self.period_close(start)
+    }
+
+    /// Closes the current bar once the time passes the close of the period.
+    pub fn update_time(&mut self, time: DateTime<Utc>) -> Option<BaseDataEnum> {
+        match self.period_close {
+            Some(period_close) if time >= period_close => self.close_bar(),
+            _ => None,
+        }
+    }
+
+    fn close_bar(&mut self) -> Option<BaseDataEnum> {
+        self.period_close = None;
+        let mut closed_bar = self.current_data.take()?;
+        closed_bar.set_is_closed(true);
+        Some(closed_bar)
+    }
+
+    pub fn update(&mut self, base_data: &BaseDataEnum) -> Result<ConsolidatedData, FundForgeError> {
+        let period_start = self.period_start(self.trading_date(base_data.time_utc()));
+        let closed_bar = match self.period_start {
+            // Late data for a period which already closed
+            Some(current_start) if period_start < current_start || (period_start == current_start && self.current_data.is_none()) => {
+                return Ok(ConsolidatedData::with_open(base_data.clone()))
+            }
+            Some(current_start) if period_start > current_start => self.close_bar(),
+            _ => None,
+        };
+
+        if let Some(current_bar) = self.current_data.as_mut() {
+            Self::merge(current_bar, base_data)?;
+            return Ok(ConsolidatedData::with_open(current_bar.clone()));
+        }
+
+        let new_bar = self.new_bar(base_data, self.period_open(period_start))?;
+        self.period_start = Some(period_start);
+        self.period_close = Some(self.period_close(period_start));
+        self.current_data = Some(new_bar.clone());
+        match closed_bar {
+            Some(closed_bar) => Ok(ConsolidatedData::with_closed(new_bar, closed_bar)),
+            None => Ok(ConsolidatedData::with_open(new_bar)),
+        }
+    }
+
+    fn new_bar(&self, base_data: &BaseDataEnum, open: DateTime<Utc>) -> Result<BaseDataEnum, FundForgeError> {
+        let symbol = self.subscription.symbol.clone();
+        let resolution = self.subscription.resolution;
+        let bar = match (self.subscription.base_data_type, base_data) {
+            (BaseDataType::Candles, BaseDataEnum::Tick(tick)) => {
+                let (ask_volume, bid_volume) = match tick.aggressor {
+                    Aggressor::Buy => (dec!(0.0), tick.volume),
+                    Aggressor::Sell => (tick.volume, dec!(0.0)),
+                    Aggressor::None => (dec!(0), dec!(0))
+                };
+                let mut candle = Candle::new(symbol, tick.price, tick.volume, ask_volume, bid_volume, open.to_string(), resolution, CandleType::CandleStick);
+                candle.quality = tick.quality;
+                candle.quality.insert(DataQuality::CONSOLIDATED);
+                BaseDataEnum::Candle(candle)
+            }
+            (BaseDataType::Candles, BaseDataEnum::Candle(candle)) => {
+                let mut candle = candle.clone();
+                candle.time = open.to_string();
+                candle.resolution = resolution;
+                candle.is_closed = false;
+                candle.quality.insert(DataQuality::CONSOLIDATED);
+                BaseDataEnum::Candle(candle)
+            }
+            (BaseDataType::QuoteBars, BaseDataEnum::Quote(quote)) => {
+                let mut quote_bar = QuoteBar::new(symbol, quote.bid, quote.ask, quote.ask_volume + quote.bid_volume, quote.ask_volume, quote.bid_volume, open.to_string(), resolution, CandleType::CandleStick);
+                quote_bar.quality.insert(DataQuality::CONSOLIDATED);
+                BaseDataEnum::QuoteBar(quote_bar)
+            }
+            (BaseDataType::QuoteBars, BaseDataEnum::QuoteBar(quote_bar)) => {
+                let mut quote_bar = quote_bar.clone();
+                quote_bar.time = open.to_string();
+                quote_bar.resolution = resolution;
+                quote_bar.is_closed = false;
+                quote_bar.quality.insert(DataQuality::CONSOLIDATED);
+                BaseDataEnum::QuoteBar(quote_bar)
+            }
+            _ => return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for {} consolidator: {}", self.subscription.base_data_type, base_data.base_data_type()))),
+        };
+        Ok(bar)
+    }
+
+    fn merge(bar: &mut BaseDataEnum, base_data: &BaseDataEnum) -> Result<(), FundForgeError> {
+        match (bar, base_data) {
+            (BaseDataEnum::Candle(candle), BaseDataEnum::Tick(tick)) => {
+                candle.high = candle.high.max(tick.price);
+                candle.low = candle.low.min(tick.price);
+                candle.close = tick.price;
+                candle.range = candle.high - candle.low;
+                match tick.aggressor {
+                    Aggressor::Buy => candle.bid_volume += tick.volume,
+                    Aggressor::Sell => candle.ask_volume += tick.volume,
+                    _ => {}
+                }
+                candle.volume += tick.volume;
+                candle.quality.merge(&tick.quality);
+            }
+            (BaseDataEnum::Candle(candle), BaseDataEnum::Candle(new_candle)) => {
+                candle.high = candle.high.max(new_candle.high);
+                candle.low = candle.low.min(new_candle.low);
+                candle.close = new_candle.close;
+                candle.range = candle.high - candle.low;
+                candle.volume += new_candle.volume;
+                candle.ask_volume += new_candle.ask_volume;
+                candle.bid_volume += new_candle.bid_volume;
+                candle.quality.merge(&new_candle.quality);
+            }
+            (BaseDataEnum::QuoteBar(quote_bar), BaseDataEnum::Quote(quote)) => {
+                quote_bar.ask_high = quote_bar.ask_high.max(quote.ask);
+                quote_bar.ask_low = quote_bar.ask_low.min(quote.ask);
+                quote_bar.bid_high = quote_bar.bid_high.max(quote.bid);
+                quote_bar.bid_low = quote_bar.bid_low.min(quote.bid);
+                quote_bar.ask_close = quote.ask;
+                quote_bar.bid_close = quote.bid;
+                quote_bar.volume += quote.ask_volume + quote.bid_volume;
+                quote_bar.ask_volume += quote.ask_volume;
+                quote_bar.bid_volume += quote.bid_volume;
+                quote_bar.range = quote_bar.ask_high - quote_bar.bid_low;
+                quote_bar.spread = quote_bar.ask_close - quote_bar.bid_close;
+            }
+            (BaseDataEnum::QuoteBar(quote_bar), BaseDataEnum::QuoteBar(bar)) => {
+                quote_bar.ask_high = quote_bar.ask_high.max(bar.ask_high);
+                quote_bar.ask_low = quote_bar.ask_low.min(bar.ask_low);
+                quote_bar.bid_high = quote_bar.bid_high.max(bar.bid_high);
+                quote_bar.bid_low = quote_bar.bid_low.min(bar.bid_low);
+                quote_bar.ask_close = bar.ask_close;
+                quote_bar.bid_close = bar.bid_close;
+                quote_bar.volume += bar.volume;
+                quote_bar.ask_volume += bar.ask_volume;
+                quote_bar.bid_volume += bar.bid_volume;
+                quote_bar.range = quote_bar.ask_high - quote_bar.bid_low;
+                quote_bar.spread = quote_bar.ask_close - quote_bar.bid_close;
+                quote_bar.quality.merge(&bar.quality);
+            }
+            (bar, base_data) => return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid base data type for {} consolidator: {}", bar.base_data_type(), base_data.base_data_type()))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono::NaiveTime;
+    use chrono_tz::America::{Chicago, New_York};
+    use crate::product_maps::rithmic::maps::CME_HOURS;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::FuturesExchange;
+    use crate::standardized_types::market_hours::DaySession;
+
+    fn hourly_candle(subscription: &DataSubscription, time: DateTime<Utc>, price: i64) -> BaseDataEnum {
+        let candle = Candle::new(subscription.symbol.clone(), price.into(), dec!(10), dec!(5), dec!(5), time.to_string(), Resolution::Hours(1), CandleType::CandleStick);
+        BaseDataEnum::Candle(candle)
+    }
+
+    #[test]
+    fn test_weekly_bars_align_to_the_trading_week() {
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Week, BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME));
+        let mut consolidator = TradingPeriodConsolidator::new(subscription.clone(), Some(CME_HOURS)).unwrap();
+        let week_open = Chicago.with_ymd_and_hms(2024, 1, 7, 17, 0, 0).unwrap().to_utc();
+
+        // The first candle is Monday morning, the bar still opens with the Sunday evening session.
+        let monday = Chicago.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap().to_utc();
+        let open = consolidator.update(&hourly_candle(&subscription, monday, 100)).unwrap().open_data;
+        assert_eq!(open.time_utc(), week_open);
+        let friday = Chicago.with_ymd_and_hms(2024, 1, 12, 15, 0, 0).unwrap().to_utc();
+        consolidator.update(&hourly_candle(&subscription, friday, 90)).unwrap();
+
+        // The bar closes with the Friday close, not midnight UTC.
+        let friday_close = Chicago.with_ymd_and_hms(2024, 1, 12, 16, 0, 0).unwrap().to_utc();
+        assert!(consolidator.update_time(friday_close - Duration::seconds(1)).is_none());
+        match consolidator.update_time(friday_close) {
+            Some(BaseDataEnum::Candle(candle)) => {
+                assert!(candle.is_closed);
+                assert_eq!((candle.open, candle.high, candle.low, candle.close, candle.volume), (dec!(100), dec!(100), dec!(90), dec!(90), dec!(20)));
+                assert!(candle.time_closed_utc() >= friday_close);
+            }
+            data => panic!("Expected a closed candle, got {:?}", data),
+        }
+
+        // The Sunday evening session trades for the next week.
+        let sunday_evening = Chicago.with_ymd_and_hms(2024, 1, 14, 18, 0, 0).unwrap().to_utc();
+        let open = consolidator.update(&hourly_candle(&subscription, sunday_evening, 95)).unwrap().open_data;
+        assert_eq!(open.time_utc(), Chicago.with_ymd_and_hms(2024, 1, 14, 17, 0, 0).unwrap().to_utc());
+    }
+
+    #[test]
+    fn test_monthly_bars_skip_holidays() {
+        let subscription = DataSubscription::new("SPY".to_string(), DataVendor::DataBento, Resolution::Month, BaseDataType::Candles, MarketType::ETF);
+        let session = DaySession { open: NaiveTime::from_hms_opt(9, 30, 0), close: NaiveTime::from_hms_opt(16, 0, 0) };
+        let closed = DaySession { open: None, close: None };
+        let hours = TradingHours {
+            timezone: New_York,
+            sunday: closed.clone(),
+            monday: session.clone(),
+            tuesday: session.clone(),
+            wednesday: session.clone(),
+            thursday: session.clone(),
+            friday: session,
+            saturday: closed,
+            week_start: Weekday::Mon,
+        };
+        let consolidator = TradingPeriodConsolidator::new(subscription, Some(hours)).unwrap();
+        // 1 January 2024 is a holiday, the first session is on the 2nd.
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert_eq!(consolidator.period_open(start), New_York.with_ymd_and_hms(2024, 1, 2, 9, 30, 0).unwrap().to_utc());
+        assert_eq!(consolidator.period_close(start), New_York.with_ymd_and_hms(2024, 1, 31, 16, 0, 0).unwrap().to_utc());
+
+        // Without trading hours the month is the calendar month in UTC.
+        let subscription = DataSubscription::new("BTC-USDT".to_string(), DataVendor::Bitget, Resolution::Month, BaseDataType::Candles, MarketType::Crypto);
+        let consolidator = TradingPeriodConsolidator::new(subscription, None).unwrap();
+        assert_eq!(consolidator.period_start(NaiveDate::from_ymd_opt(2024, 2, 17).unwrap()), NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        assert_eq!(consolidator.period_close(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()), Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+    }
+}
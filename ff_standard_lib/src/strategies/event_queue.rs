@@ -0,0 +1,266 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::mpsc;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::strategies::strategy_events::StrategyEvent;
+
+static EVENT_OVERFLOW_POLICY: OnceLock<EventOverflowPolicy> = OnceLock::new();
+
+/// What the engine does when the strategy is not receiving its events as fast as they are produced and the `strategy_event_sender` channel is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventOverflowPolicy {
+    /// The engine waits for space in the channel before continuing, no events are lost but a slow strategy stalls data, orders and timed events.
+    /// This is the default.
+    Block,
+    /// Events that do not fit in the channel are queued by the engine, when more than `max_queued` are waiting the oldest queued market data event is dropped.
+    /// The strategy receives a `StrategyEvent::EventQueueOverflow` with the number of dropped events before the next queued event.
+    /// Only `StrategyEvent::TimeSlice` and `StrategyEvent::IndicatorEvent` are dropped, so this is for strategies where only the most recent data matters,
+    /// order, position, connection and other events are always delivered and the queue grows past `max_queued` while only they are waiting.
+    DropOldest { max_queued: usize },
+    /// Events that do not fit in the channel are queued by the engine without a limit, nothing is lost and the engine never waits,
+    /// but memory grows for as long as the strategy falls behind.
+    Expand,
+}
+
+/// Sets the `EventOverflowPolicy` of the strategy event channel, this must be called before `FundForgeStrategy::initialize()`.
+pub fn set_event_overflow_policy(policy: EventOverflowPolicy) -> Result<(), FundForgeError> {
+    EVENT_OVERFLOW_POLICY.set(policy)
+        .map_err(|_| FundForgeError::ClientSideErrorDebug("The event overflow policy is already set".to_string()))
+}
+
+pub(crate) fn event_overflow_policy() -> EventOverflowPolicy {
+    EVENT_OVERFLOW_POLICY.get().cloned().unwrap_or(EventOverflowPolicy::Block)
+}
+
+/// A snapshot of the strategy event queue, see `strategy.event_queue_metrics()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventQueueMetrics {
+    pub policy: EventOverflowPolicy,
+    /// The capacity of the channel passed to `FundForgeStrategy::initialize()`.
+    pub channel_capacity: usize,
+    /// The events waiting to be received by the strategy, in the channel and queued by the engine.
+    pub depth: usize,
+    /// The events queued by the engine because the channel was full, always 0 with `EventOverflowPolicy::Block`.
+    pub queued: usize,
+    /// The highest depth seen, with `EventOverflowPolicy::Block` only the depths seen when the metrics were taken are known.
+    pub peak_depth: usize,
+    /// The events dropped by `EventOverflowPolicy::DropOldest`.
+    pub dropped: u64,
+}
+
+/// Applies the `EventOverflowPolicy` between the engine and the channel of the strategy and keeps track of the queue depth.
+pub(crate) struct EventQueue {
+    policy: EventOverflowPolicy,
+    strategy_sender: mpsc::Sender<StrategyEvent>,
+    queued: AtomicUsize,
+    peak_depth: AtomicUsize,
+    dropped: AtomicU64,
+}
+
+impl EventQueue {
+    /// Wraps the strategy channel, the returned sender is used by the engine in place of the strategy sender.
+    /// With `EventOverflowPolicy::Block` the engine sends directly to the strategy channel,
+    /// otherwise events go through a task which forwards them to the strategy and queues them while the channel is full.
+    pub(crate) fn new(strategy_sender: mpsc::Sender<StrategyEvent>, policy: EventOverflowPolicy) -> (mpsc::Sender<StrategyEvent>, Arc<Self>) {
+        let (engine_sender, receiver) = match policy {
+            EventOverflowPolicy::Block => (strategy_sender.clone(), None),
+            EventOverflowPolicy::DropOldest { .. } | EventOverflowPolicy::Expand => {
+                let (sender, receiver) = mpsc::channel(strategy_sender.max_capacity());
+                (sender, Some(receiver))
+            }
+        };
+        let queue = Arc::new(EventQueue {
+            policy,
+            strategy_sender,
+            queued: AtomicUsize::new(0),
+            peak_depth: AtomicUsize::new(0),
+            dropped: AtomicU64::new(0),
+        });
+        if let Some(receiver) = receiver {
+            tokio::task::spawn(EventQueue::forward(queue.clone(), receiver));
+        }
+        (engine_sender, queue)
+    }
+
+    fn channel_depth(&self) -> usize {
+        self.strategy_sender.max_capacity() - self.strategy_sender.capacity()
+    }
+
+    pub(crate) fn metrics(&self) -> EventQueueMetrics {
+        let queued = self.queued.load(Ordering::SeqCst);
+        let depth = self.channel_depth() + queued;
+        let peak_depth = self.peak_depth.fetch_max(depth, Ordering::SeqCst).max(depth);
+        EventQueueMetrics {
+            policy: self.policy,
+            channel_capacity: self.strategy_sender.max_capacity(),
+            depth,
+            queued,
+            peak_depth,
+            dropped: self.dropped.load(Ordering::SeqCst),
+        }
+    }
+
+    async fn forward(queue: Arc<EventQueue>, mut receiver: mpsc::Receiver<StrategyEvent>) {
+        let mut backlog = EventBacklog::new(queue.policy);
+        loop {
+            tokio::select! {
+                biased;
+                permit = queue.strategy_sender.reserve(), if backlog.has_next() => {
+                    let permit = match permit {
+                        Ok(permit) => permit,
+                        Err(_) => return,
+                    };
+                    if let Some(event) = backlog.next(queue.dropped.load(Ordering::SeqCst)) {
+                        permit.send(event);
+                    }
+                }
+                event = receiver.recv() => {
+                    let event = match event {
+                        Some(event) => event,
+                        None => break,
+                    };
+                    let dropped = backlog.push(event);
+                    queue.dropped.fetch_add(dropped, Ordering::SeqCst);
+                }
+            }
+            queue.queued.store(backlog.len(), Ordering::SeqCst);
+            queue.peak_depth.fetch_max(queue.channel_depth() + backlog.len(), Ordering::SeqCst);
+        }
+
+        // The engine has dropped its senders, deliver what is left.
+        while let Some(event) = backlog.next(queue.dropped.load(Ordering::SeqCst)) {
+            if queue.strategy_sender.send(event).await.is_err() {
+                return;
+            }
+        }
+        queue.queued.store(0, Ordering::SeqCst);
+    }
+}
+
+/// Market data is the only kind of event `EventOverflowPolicy::DropOldest` drops, a newer time slice or indicator value replaces what was lost.
+fn is_droppable(event: &StrategyEvent) -> bool {
+    matches!(event, StrategyEvent::TimeSlice(_) | StrategyEvent::IndicatorEvent(_))
+}
+
+/// The events queued by `EventQueue::forward()` while the strategy channel is full.
+struct EventBacklog {
+    policy: EventOverflowPolicy,
+    events: VecDeque<StrategyEvent>,
+    dropped_since_warning: u64,
+}
+
+impl EventBacklog {
+    fn new(policy: EventOverflowPolicy) -> Self {
+        EventBacklog { policy, events: VecDeque::new(), dropped_since_warning: 0 }
+    }
+
+    fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Queues the event and applies the policy, returns the number of events dropped.
+    fn push(&mut self, event: StrategyEvent) -> u64 {
+        self.events.push_back(event);
+        let max_queued = match self.policy {
+            EventOverflowPolicy::DropOldest { max_queued } => max_queued.max(1),
+            EventOverflowPolicy::Block | EventOverflowPolicy::Expand => return 0,
+        };
+        let mut dropped = 0;
+        while self.events.len() > max_queued {
+            match self.events.iter().position(is_droppable) {
+                Some(index) => {
+                    self.events.remove(index);
+                    dropped += 1;
+                }
+                None => break,
+            }
+        }
+        self.dropped_since_warning += dropped;
+        dropped
+    }
+
+    fn has_next(&self) -> bool {
+        !self.events.is_empty() || self.dropped_since_warning > 0
+    }
+
+    /// The next event for the strategy, a `StrategyEvent::EventQueueOverflow` is sent first when events were dropped since the last one.
+    fn next(&mut self, total_dropped: u64) -> Option<StrategyEvent> {
+        if self.dropped_since_warning > 0 {
+            let dropped = std::mem::take(&mut self.dropped_since_warning);
+            return Some(StrategyEvent::EventQueueOverflow { dropped, total_dropped });
+        }
+        self.events.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standardized_types::accounts::Account;
+    use crate::standardized_types::broker_enum::Brokerage;
+    use crate::standardized_types::orders::OrderUpdateEvent;
+    use crate::standardized_types::time_slices::TimeSlice;
+
+    fn cancelled(order_id: &str) -> StrategyEvent {
+        StrategyEvent::OrderEvents(OrderUpdateEvent::OrderCancelled {
+            account: Account::new(Brokerage::Test, "Test".to_string()),
+            symbol_name: "MNQ".to_string(),
+            symbol_code: "MNQZ4".to_string(),
+            order_id: order_id.to_string(),
+            reason: "Cancelled".to_string(),
+            tag: "Exit".to_string(),
+            time: "2024-06-03 14:30:00 UTC".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let mut backlog = EventBacklog::new(EventOverflowPolicy::DropOldest { max_queued: 2 });
+        assert_eq!(backlog.push(StrategyEvent::TimeSlice(TimeSlice::new())), 0);
+        assert_eq!(backlog.push(cancelled("1")), 0);
+        // only the time slices are dropped to make room
+        assert_eq!(backlog.push(StrategyEvent::TimeSlice(TimeSlice::new())), 1);
+        assert_eq!(backlog.push(StrategyEvent::TimedEvent("timer".to_string())), 1);
+        assert_eq!(backlog.len(), 2);
+        // with no market data left to drop the queue grows past the limit
+        assert_eq!(backlog.push(cancelled("2")), 0);
+        assert_eq!(backlog.len(), 3);
+
+        assert_eq!(backlog.next(2), Some(StrategyEvent::EventQueueOverflow { dropped: 2, total_dropped: 2 }));
+        assert_eq!(backlog.next(2), Some(cancelled("1")));
+        assert_eq!(backlog.next(2), Some(StrategyEvent::TimedEvent("timer".to_string())));
+        assert_eq!(backlog.next(2), Some(cancelled("2")));
+        assert!(!backlog.has_next());
+        assert_eq!(backlog.next(2), None);
+    }
+
+    #[test]
+    fn test_expand() {
+        let mut backlog = EventBacklog::new(EventOverflowPolicy::Expand);
+        for i in 1..=5 {
+            assert_eq!(backlog.push(StrategyEvent::TimeSlice(TimeSlice::new())), 0);
+            assert_eq!(backlog.push(StrategyEvent::TimedEvent(i.to_string())), 0);
+        }
+        assert_eq!(backlog.len(), 10);
+        for i in 1..=5 {
+            assert_eq!(backlog.next(0), Some(StrategyEvent::TimeSlice(TimeSlice::new())));
+            assert_eq!(backlog.next(0), Some(StrategyEvent::TimedEvent(i.to_string())));
+        }
+        assert!(!backlog.has_next());
+    }
+
+    #[tokio::test]
+    async fn test_forward_delivers_in_order() {
+        let (strategy_sender, mut strategy_receiver) = mpsc::channel(1);
+        let (sender, queue) = EventQueue::new(strategy_sender, EventOverflowPolicy::DropOldest { max_queued: 2 });
+        for i in 1..=5 {
+            sender.send(cancelled(&i.to_string())).await.unwrap();
+        }
+        drop(sender);
+        for i in 1..=5 {
+            assert_eq!(strategy_receiver.recv().await, Some(cancelled(&i.to_string())));
+        }
+        assert_eq!(queue.metrics().dropped, 0);
+    }
+}
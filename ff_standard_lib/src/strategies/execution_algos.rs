@@ -0,0 +1,221 @@
+use std::fmt;
+use chrono::{DateTime, Duration, Utc};
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::enums::OrderSide;
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
+
+pub type AlgoId = String;
+
+/// How the quantity of a `ParentOrder` is spread over its window.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SliceSchedule {
+    /// An equal share of the quantity in each slice.
+    Twap,
+    /// A share of the quantity in each slice in proportion to the volume expected in the slice,
+    /// eg. the average volume of each 5 minute bucket of the last 20 sessions, the weights do not need to add up to 1.
+    Vwap { volume_profile: Vec<Decimal> },
+}
+
+/// A parent order worked by an execution algo, see `strategy.submit_algo()`.
+///
+/// The window starts when the algo is submitted and is split into `slices` equal intervals, a market child order is sent at the start of each interval
+/// for the quantity the `SliceSchedule` has due by then which was not sent yet. Child quantities are rounded down to the lot size of the symbol,
+/// or to the decimal places of the parent quantity when the lot size is unknown, the remainder is sent with a later slice.
+///
+/// With a `participation_cap` the quantity sent never exceeds the share of the market volume traded since the algo started, so the algo can fall behind its schedule.
+/// Any quantity which was not sent by the end of the window is left unfilled and the algo expires.
+/// The cap needs tick or candle data for the symbol, without traded volume the cap is not applied.
+///
+/// Backtests approximate the algo by filling the child orders as market orders at the first time slice after each slice starts.
+/// # Example
+/// ```rust,ignore
+/// // Buy 20 MNQ over the next hour in 12 slices, trading no more than 10% of the market volume
+/// let parent = ParentOrder::twap(account.clone(), "MNQ".to_string(), OrderSide::Buy, dec!(20), Duration::hours(1), 12, "Accumulate".to_string())
+///     .with_participation_cap(dec!(0.1));
+/// let algo_id = strategy.submit_algo(parent).await;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParentOrder {
+    pub account: Account,
+    pub symbol_name: SymbolName,
+    pub symbol_code: Option<SymbolCode>,
+    pub side: OrderSide,
+    pub quantity: Volume,
+    pub duration: Duration,
+    pub slices: u32,
+    pub schedule: SliceSchedule,
+    /// The largest share of the market volume the algo trades, eg. `dec!(0.1)` for 10%, `None` follows the schedule regardless of volume.
+    pub participation_cap: Option<Decimal>,
+    pub tag: String,
+    /// The lot size of the symbol, set by `strategy.submit_algo()` from the `SymbolInfo`.
+    pub lot_size: Option<Volume>,
+}
+
+impl ParentOrder {
+    pub fn twap(account: Account, symbol_name: SymbolName, side: OrderSide, quantity: Volume, duration: Duration, slices: u32, tag: String) -> Self {
+        Self {
+            account,
+            symbol_name,
+            symbol_code: None,
+            side,
+            quantity,
+            duration,
+            slices: slices.max(1),
+            schedule: SliceSchedule::Twap,
+            participation_cap: None,
+            tag,
+            lot_size: None,
+        }
+    }
+
+    /// One slice for each weight of the volume profile.
+    pub fn vwap(account: Account, symbol_name: SymbolName, side: OrderSide, quantity: Volume, duration: Duration, volume_profile: Vec<Decimal>, tag: String) -> Self {
+        let mut parent = Self::twap(account, symbol_name, side, quantity, duration, volume_profile.len() as u32, tag);
+        parent.schedule = SliceSchedule::Vwap { volume_profile };
+        parent
+    }
+
+    pub fn with_symbol_code(mut self, symbol_code: SymbolCode) -> Self {
+        self.symbol_code = Some(symbol_code);
+        self
+    }
+
+    pub fn with_participation_cap(mut self, participation_cap: Decimal) -> Self {
+        self.participation_cap = Some(participation_cap);
+        self
+    }
+
+    pub fn slice_interval(&self) -> Duration {
+        self.duration / self.slices as i32
+    }
+
+    /// The quantity the schedule has due by the time, including the quantity of the earlier slices.
+    pub(crate) fn scheduled_quantity(&self, start: DateTime<Utc>, time: DateTime<Utc>) -> Volume {
+        if time < start {
+            return dec!(0);
+        }
+        let interval = self.slice_interval().num_nanoseconds().unwrap_or(i64::MAX).max(1);
+        let elapsed = (time - start).num_nanoseconds().unwrap_or(i64::MAX);
+        let slices_started = (elapsed / interval + 1).min(self.slices as i64) as usize;
+        if slices_started == self.slices as usize {
+            return self.quantity;
+        }
+        let share = match &self.schedule {
+            SliceSchedule::Twap => Decimal::from(slices_started) / Decimal::from(self.slices),
+            SliceSchedule::Vwap { volume_profile } => {
+                let total: Decimal = volume_profile.iter().sum();
+                match total > dec!(0) {
+                    true => volume_profile.iter().take(slices_started).sum::<Decimal>() / total,
+                    false => Decimal::from(slices_started) / Decimal::from(self.slices),
+                }
+            }
+        };
+        self.round_quantity(self.quantity * share)
+    }
+
+    /// The quantity the participation cap allows to be sent once the market traded the volume since the algo started.
+    pub(crate) fn capped_quantity(&self, market_volume: Volume) -> Option<Volume> {
+        self.participation_cap.map(|cap| self.round_quantity(market_volume * cap))
+    }
+
+    /// Rounds down to the lot size, or to the decimal places of the parent quantity if the lot size is unknown.
+    fn round_quantity(&self, quantity: Volume) -> Volume {
+        match self.lot_size.filter(|lot_size| *lot_size > dec!(0)) {
+            Some(lot_size) => (quantity / lot_size).trunc() * lot_size,
+            None => quantity.round_dp_with_strategy(self.quantity.scale(), RoundingStrategy::ToZero),
+        }
+    }
+}
+
+/// Sent to the strategy as a `StrategyEvent::AlgoEvents` while an algo submitted with `strategy.submit_algo()` is working, the child orders also send their own `OrderUpdateEvent`s.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum AlgoEvent {
+    /// A child order filled, `remaining` is the quantity of the parent order which has not filled.
+    Progress {account: Account, algo_id: AlgoId, symbol_name: SymbolName, filled: Volume, remaining: Volume, average_price: Option<Price>, time: String},
+
+    /// The parent order filled.
+    Completed {account: Account, algo_id: AlgoId, symbol_name: SymbolName, filled: Volume, average_price: Option<Price>, time: String},
+
+    /// The window ended before the participation cap allowed the parent order to fill, or child orders were rejected.
+    Expired {account: Account, algo_id: AlgoId, symbol_name: SymbolName, filled: Volume, remaining: Volume, average_price: Option<Price>, time: String},
+
+    /// The algo was cancelled with `strategy.cancel_algo()`, the working child orders were cancelled.
+    Cancelled {account: Account, algo_id: AlgoId, symbol_name: SymbolName, filled: Volume, remaining: Volume, average_price: Option<Price>, time: String},
+
+    /// The algo was not started, eg. the `TradingSchedule` of the strategy is idle.
+    Rejected {account: Account, algo_id: AlgoId, symbol_name: SymbolName, reason: String, time: String},
+}
+
+impl AlgoEvent {
+    pub fn algo_id(&self) -> &AlgoId {
+        match self {
+            AlgoEvent::Progress { algo_id, .. } => algo_id,
+            AlgoEvent::Completed { algo_id, .. } => algo_id,
+            AlgoEvent::Expired { algo_id, .. } => algo_id,
+            AlgoEvent::Cancelled { algo_id, .. } => algo_id,
+            AlgoEvent::Rejected { algo_id, .. } => algo_id,
+        }
+    }
+}
+
+impl fmt::Display for AlgoEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlgoEvent::Progress { account, algo_id, symbol_name, filled, remaining, .. } => {
+                write!(f, "Algo Progress: Account: {}, Algo ID: {}, Symbol: {}, Filled: {}, Remaining: {}", account, algo_id, symbol_name, filled, remaining)
+            }
+            AlgoEvent::Completed { account, algo_id, symbol_name, filled, average_price, .. } => {
+                write!(f, "Algo Completed: Account: {}, Algo ID: {}, Symbol: {}, Filled: {}, Average Price: {:?}", account, algo_id, symbol_name, filled, average_price)
+            }
+            AlgoEvent::Expired { account, algo_id, symbol_name, filled, remaining, .. } => {
+                write!(f, "Algo Expired: Account: {}, Algo ID: {}, Symbol: {}, Filled: {}, Remaining: {}", account, algo_id, symbol_name, filled, remaining)
+            }
+            AlgoEvent::Cancelled { account, algo_id, symbol_name, filled, remaining, .. } => {
+                write!(f, "Algo Cancelled: Account: {}, Algo ID: {}, Symbol: {}, Filled: {}, Remaining: {}", account, algo_id, symbol_name, filled, remaining)
+            }
+            AlgoEvent::Rejected { account, algo_id, symbol_name, reason, .. } => {
+                write!(f, "Algo Rejected: Account: {}, Algo ID: {}, Symbol: {}. Reason: {}", account, algo_id, symbol_name, reason)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::standardized_types::broker_enum::Brokerage;
+
+    #[test]
+    fn test_scheduled_quantity() {
+        let account = Account::new(Brokerage::Test, "test".to_string());
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 14, 30, 0).unwrap();
+        let twap = ParentOrder::twap(account.clone(), "MNQ".to_string(), OrderSide::Buy, dec!(10), Duration::hours(1), 4, "test".to_string());
+        assert_eq!(twap.scheduled_quantity(start, start - Duration::seconds(1)), dec!(0));
+        // The first slice is due at the start, 2.5 rounds down to 2
+        assert_eq!(twap.scheduled_quantity(start, start), dec!(2));
+        assert_eq!(twap.scheduled_quantity(start, start + Duration::minutes(15)), dec!(5));
+        assert_eq!(twap.scheduled_quantity(start, start + Duration::minutes(44)), dec!(7));
+        assert_eq!(twap.scheduled_quantity(start, start + Duration::minutes(45)), dec!(10));
+        assert_eq!(twap.scheduled_quantity(start, start + Duration::hours(2)), dec!(10));
+
+        let vwap = ParentOrder::vwap(account.clone(), "MNQ".to_string(), OrderSide::Buy, dec!(10), Duration::hours(1), vec![dec!(500), dec!(200), dec!(300)], "test".to_string())
+            .with_participation_cap(dec!(0.1));
+        assert_eq!(vwap.slices, 3);
+        assert_eq!(vwap.scheduled_quantity(start, start), dec!(5));
+        assert_eq!(vwap.scheduled_quantity(start, start + Duration::minutes(20)), dec!(7));
+        assert_eq!(vwap.scheduled_quantity(start, start + Duration::minutes(40)), dec!(10));
+        assert_eq!(vwap.capped_quantity(dec!(45)), Some(dec!(4)));
+
+        let mut crypto = ParentOrder::twap(account, "BTC-USDT".to_string(), OrderSide::Sell, dec!(0.1), Duration::hours(1), 3, "test".to_string());
+        assert_eq!(crypto.scheduled_quantity(start, start), dec!(0.0));
+        crypto.lot_size = Some(dec!(0.001));
+        assert_eq!(crypto.scheduled_quantity(start, start), dec!(0.033));
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use chrono::{DateTime, Duration, Utc};
+use lazy_static::lazy_static;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::market_hours::TradingHours;
+use crate::standardized_types::new_types::Price;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::standardized_types::time_slices::TimeSlice;
+
+lazy_static! {
+    static ref FEED_MONITOR: RwLock<Option<Arc<FeedMonitor>>> = RwLock::new(None);
+}
+
+/// The checks of the live feed monitor, see `strategy.set_feed_quality()`.
+/// Each problem is sent to the strategy once as a `DataSubscriptionEvent::QualityAlert`, followed by another alert when the problem clears,
+/// so a strategy can pause trading on bad data and resume when the feed is healthy again.
+#[derive(Clone, Debug)]
+pub struct FeedQualitySettings {
+    /// A subscription which receives no data for this long is stale, `None` disables the check. 60 seconds by default.
+    pub stale_after: Option<Duration>,
+    /// Only checks for stale subscriptions while these hours are open, so quiet sessions and weekends are not reported. `None` checks at all times.
+    pub trading_hours: Option<TradingHours>,
+    /// Reports quotes and quote bars where the bid is at or above the ask. True by default.
+    pub detect_crossed_books: bool,
+    /// Reports a price change larger than this many standard deviations of the recent price changes, `None` disables the check.
+    pub spike_sigma: Option<f64>,
+    /// The number of recent price changes the standard deviation of a spike is measured over, 100 by default.
+    pub spike_lookback: usize,
+}
+
+impl Default for FeedQualitySettings {
+    fn default() -> Self {
+        Self {
+            stale_after: Some(Duration::seconds(60)),
+            trading_hours: None,
+            detect_crossed_books: true,
+            spike_sigma: None,
+            spike_lookback: 100,
+        }
+    }
+}
+
+impl FeedQualitySettings {
+    pub fn with_stale_after(mut self, stale_after: Option<Duration>) -> Self {
+        self.stale_after = stale_after;
+        self
+    }
+
+    pub fn with_trading_hours(mut self, trading_hours: TradingHours) -> Self {
+        self.trading_hours = Some(trading_hours);
+        self
+    }
+
+    pub fn with_crossed_books(mut self, detect_crossed_books: bool) -> Self {
+        self.detect_crossed_books = detect_crossed_books;
+        self
+    }
+
+    pub fn with_spike_detection(mut self, sigma: f64, lookback: usize) -> Self {
+        self.spike_sigma = Some(sigma);
+        self.spike_lookback = lookback.max(2);
+        self
+    }
+}
+
+/// A problem with the live data of a subscription, or the end of a problem.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum QualityIssue {
+    /// No data was received for the number of seconds.
+    StaleFeed { seconds: i64 },
+    /// Data was received again after a `StaleFeed`.
+    FeedResumed,
+    /// The bid is above the ask.
+    CrossedBook { bid: Price, ask: Price },
+    /// The bid is equal to the ask.
+    LockedBook { price: Price },
+    /// The bid is below the ask again after a `CrossedBook` or `LockedBook`.
+    BookRestored,
+    /// The price moved by `sigma` standard deviations of the recent price changes.
+    PriceSpike { previous: Price, price: Price, sigma: f64 },
+}
+
+/// Sent to the strategy as a `DataSubscriptionEvent::QualityAlert`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct QualityAlert {
+    pub subscription: DataSubscription,
+    pub issue: QualityIssue,
+    pub time: String,
+}
+
+impl QualityAlert {
+    /// True if the alert reports the end of a problem.
+    pub fn is_recovery(&self) -> bool {
+        matches!(self.issue, QualityIssue::FeedResumed | QualityIssue::BookRestored)
+    }
+}
+
+impl fmt::Display for QualityAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.issue {
+            QualityIssue::StaleFeed { seconds } => write!(f, "{}: No data for {} seconds", self.subscription, seconds),
+            QualityIssue::FeedResumed => write!(f, "{}: Data resumed", self.subscription),
+            QualityIssue::CrossedBook { bid, ask } => write!(f, "{}: Crossed book, bid: {}, ask: {}", self.subscription, bid, ask),
+            QualityIssue::LockedBook { price } => write!(f, "{}: Locked book at {}", self.subscription, price),
+            QualityIssue::BookRestored => write!(f, "{}: Book restored", self.subscription),
+            QualityIssue::PriceSpike { previous, price, sigma } => write!(f, "{}: Price spike from {} to {}, {:.1} sigma", self.subscription, previous, price, sigma),
+        }
+    }
+}
+
+#[derive(Default)]
+struct FeedState {
+    last_data: Option<DateTime<Utc>>,
+    stale: bool,
+    crossed: bool,
+    last_price: Option<Price>,
+    changes: VecDeque<f64>,
+}
+
+/// Checks the live data of the primary subscriptions, see `FeedQualitySettings`.
+pub(crate) struct FeedMonitor {
+    settings: FeedQualitySettings,
+    feeds: Mutex<HashMap<DataSubscription, FeedState>>,
+}
+
+impl FeedMonitor {
+    pub fn new(settings: FeedQualitySettings) -> Self {
+        Self {
+            settings,
+            feeds: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks the data received at the time.
+    pub fn check_time_slice(&self, time_slice: &TimeSlice, time: DateTime<Utc>) -> Vec<QualityAlert> {
+        let mut alerts = vec![];
+        let mut feeds = self.feeds.lock().unwrap();
+        for data in time_slice.iter() {
+            let subscription = data.subscription();
+            let state = feeds.entry(subscription.clone()).or_default();
+            let mut alert = |issue: QualityIssue| alerts.push(QualityAlert { subscription: subscription.clone(), issue, time: time.to_string() });
+
+            state.last_data = Some(time);
+            if state.stale {
+                state.stale = false;
+                alert(QualityIssue::FeedResumed);
+            }
+
+            if self.settings.detect_crossed_books {
+                if let Some((bid, ask)) = bid_ask(data) {
+                    match (bid >= ask, state.crossed) {
+                        (true, false) => {
+                            state.crossed = true;
+                            match bid == ask {
+                                true => alert(QualityIssue::LockedBook { price: bid }),
+                                false => alert(QualityIssue::CrossedBook { bid, ask }),
+                            }
+                        }
+                        (false, true) => {
+                            state.crossed = false;
+                            alert(QualityIssue::BookRestored);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if let (Some(sigma_limit), Some(price)) = (self.settings.spike_sigma, data_price(data)) {
+                if let Some(previous) = state.last_price.replace(price) {
+                    let change = (price - previous).to_f64().unwrap_or(0.0);
+                    // The window must be full before a spike can be measured.
+                    if state.changes.len() == self.settings.spike_lookback {
+                        let sigma = standard_deviation(&state.changes);
+                        if sigma > 0.0 && change.abs() / sigma > sigma_limit {
+                            alert(QualityIssue::PriceSpike { previous, price, sigma: change.abs() / sigma });
+                        }
+                        state.changes.pop_front();
+                    }
+                    state.changes.push_back(change);
+                }
+            }
+        }
+        alerts
+    }
+
+    /// Checks the subscriptions for stale data, the state of subscriptions which are no longer subscribed is dropped.
+    pub fn check_stale(&self, subscriptions: &[DataSubscription], time: DateTime<Utc>) -> Vec<QualityAlert> {
+        let mut feeds = self.feeds.lock().unwrap();
+        feeds.retain(|subscription, _| subscriptions.contains(subscription));
+        let stale_after = match self.settings.stale_after {
+            Some(stale_after) => stale_after,
+            None => return vec![],
+        };
+        let is_open = self.settings.trading_hours.as_ref().is_none_or(|hours| hours.is_market_open(time));
+        let mut alerts = vec![];
+        for subscription in subscriptions {
+            let state = feeds.entry(subscription.clone()).or_default();
+            // The time is measured from the subscription, or from the open when the market was closed.
+            let last_data = match (is_open, state.last_data) {
+                (true, Some(last_data)) => last_data,
+                _ => {
+                    state.last_data = Some(time);
+                    continue;
+                }
+            };
+            let silence = time - last_data;
+            if !state.stale && silence >= stale_after {
+                state.stale = true;
+                alerts.push(QualityAlert { subscription: subscription.clone(), issue: QualityIssue::StaleFeed { seconds: silence.num_seconds() }, time: time.to_string() });
+            }
+        }
+        alerts
+    }
+}
+
+/// Replaces the feed monitor, `None` stops the monitor.
+pub(crate) fn set_feed_quality(settings: Option<FeedQualitySettings>) {
+    *FEED_MONITOR.write().unwrap() = settings.map(|settings| Arc::new(FeedMonitor::new(settings)));
+}
+
+pub(crate) fn feed_monitor() -> Option<Arc<FeedMonitor>> {
+    FEED_MONITOR.read().unwrap().clone()
+}
+
+fn bid_ask(data: &BaseDataEnum) -> Option<(Price, Price)> {
+    match data {
+        BaseDataEnum::Quote(quote) => Some((quote.bid, quote.ask)),
+        BaseDataEnum::QuoteBar(bar) => Some((bar.bid_close, bar.ask_close)),
+        _ => None,
+    }
+}
+
+/// The trade price, or the mid price of quotes.
+fn data_price(data: &BaseDataEnum) -> Option<Price> {
+    match data {
+        BaseDataEnum::Tick(tick) => Some(tick.price),
+        BaseDataEnum::Candle(candle) => Some(candle.close),
+        BaseDataEnum::Quote(quote) => Some((quote.bid + quote.ask) / dec!(2)),
+        BaseDataEnum::QuoteBar(bar) => Some((bar.bid_close + bar.ask_close) / dec!(2)),
+        _ => None,
+    }
+}
+
+fn standard_deviation(values: &VecDeque<f64>) -> f64 {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    variance.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+    use crate::standardized_types::base_data::quote::Quote;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::MarketType;
+    use crate::standardized_types::subscriptions::Symbol;
+
+    fn quote(bid: Decimal, ask: Decimal, time: DateTime<Utc>) -> TimeSlice {
+        let mut time_slice = TimeSlice::new();
+        time_slice.add(BaseDataEnum::Quote(Quote {
+            symbol: Symbol::new("EUR-USD".to_string(), DataVendor::Oanda, MarketType::Forex),
+            ask,
+            bid,
+            ask_volume: dec!(1),
+            bid_volume: dec!(1),
+            time: time.to_string(),
+        }));
+        time_slice
+    }
+
+    #[test]
+    fn test_feed_monitor() {
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 12, 0, 0).unwrap();
+        let monitor = FeedMonitor::new(FeedQualitySettings::default().with_spike_detection(5.0, 10));
+        let subscription = quote(dec!(1.0845), dec!(1.0846), start).iter().next().unwrap().subscription();
+        let subscriptions = vec![subscription.clone()];
+
+        // Stale once after 60 seconds without data, then resumed by the next quote.
+        assert!(monitor.check_stale(&subscriptions, start).is_empty());
+        assert!(monitor.check_stale(&subscriptions, start + Duration::seconds(59)).is_empty());
+        let alerts = monitor.check_stale(&subscriptions, start + Duration::seconds(60));
+        assert_eq!(alerts[0].issue, QualityIssue::StaleFeed { seconds: 60 });
+        assert!(monitor.check_stale(&subscriptions, start + Duration::seconds(90)).is_empty());
+        let time = start + Duration::seconds(91);
+        let alerts = monitor.check_time_slice(&quote(dec!(1.0845), dec!(1.0846), time), time);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].is_recovery());
+
+        // Crossed and locked books are reported once, until the book is restored.
+        let alerts = monitor.check_time_slice(&quote(dec!(1.0847), dec!(1.0846), time), time);
+        assert_eq!(alerts[0].issue, QualityIssue::CrossedBook { bid: dec!(1.0847), ask: dec!(1.0846) });
+        assert!(monitor.check_time_slice(&quote(dec!(1.0846), dec!(1.0846), time), time).is_empty());
+        assert_eq!(monitor.check_time_slice(&quote(dec!(1.0845), dec!(1.0846), time), time)[0].issue, QualityIssue::BookRestored);
+
+        // A jump of 100 pips after small alternating moves is a spike.
+        for i in 0..12 {
+            let bid = dec!(1.0845) + Decimal::new(i % 2, 4);
+            assert!(monitor.check_time_slice(&quote(bid, bid + dec!(0.0001), time), time).is_empty());
+        }
+        let alerts = monitor.check_time_slice(&quote(dec!(1.0945), dec!(1.0946), time), time);
+        assert!(matches!(alerts[0].issue, QualityIssue::PriceSpike { .. }));
+
+        // Unsubscribed feeds are dropped.
+        assert!(monitor.check_stale(&[], time).is_empty());
+        assert!(monitor.feeds.lock().unwrap().is_empty());
+    }
+}
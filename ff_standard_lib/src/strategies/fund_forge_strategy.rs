@@ -1,4 +1,10 @@
 use ahash::AHashMap;
+use crate::strategies::event_queue::{event_overflow_policy, EventQueue, EventQueueMetrics};
+use crate::strategies::session_recording::{launch_session_replay, session_recording, session_replay, SessionRecorder};
+use crate::messages::chart_updates::{ChartName, ChartUpdate};
+use crate::gui_types::drawing_objects::chart_objects::ChartObject;
+use crate::strategies::handlers::chart_publisher::{subscribe_chart, unsubscribe_chart};
+use crate::strategies::statistics::backtest_cache::{cached_backtest, CachedBacktest};
 use chrono::{DateTime, Duration as ChronoDuration, NaiveDateTime, TimeZone, Utc};
 use chrono_tz::Tz;
 use crate::strategies::handlers::drawing_object_handler::DrawingObjectHandler;
@@ -6,53 +12,106 @@ use crate::gui_types::drawing_objects::drawing_tool_enum::DrawingTool;
 use crate::strategies::handlers::indicator_handler::IndicatorHandler;
 use crate::strategies::indicators::indicators_trait::{IndicatorName, Indicators};
 use crate::strategies::indicators::indicator_values::IndicatorValues;
+use crate::standardized_types::base_data::warmup_batch::get_warmup_data;
 use crate::standardized_types::base_data::history::range_history_data;
-use crate::standardized_types::enums::{OrderSide, StrategyMode, PrimarySubscription, FuturesExchange, PositionSide};
+use crate::standardized_types::enums::{OrderSide, StrategyMode, PrimarySubscription, FuturesExchange, PositionSide, MarketType};
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::strategies::consolidators::consolidator_enum::ConsolidatorEnum;
 use crate::standardized_types::rolling_window::RollingWindow;
 use crate::strategies::strategy_events::StrategyEvent;
 use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
-use crate::standardized_types::subscriptions::{DataSubscription, SymbolCode, SymbolName};
+use crate::standardized_types::subscriptions::{DataSubscription, DataSubscriptionEvent, SymbolCode, SymbolName};
+use crate::standardized_types::datavendor_enum::DataVendor;
+use crate::standardized_types::vendor_capabilities::VendorCapabilities;
 use crate::strategies::handlers::timed_events_handler::{TimedEvent, TimedEventHandler};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::fs;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use dashmap::DashMap;
 use rust_decimal::Decimal;
-use tokio::sync::{mpsc, oneshot, Notify};
+use rust_decimal_macros::dec;
+use tokio::sync::{broadcast, mpsc, Notify};
 use tokio::sync::mpsc::Sender;
 use uuid::Uuid;
 use crate::helpers::converters::{naive_date_time_to_tz, naive_date_time_to_utc, resolve_market_datetime_in_timezone};
 use crate::helpers::decimal_calculators::round_to_tick_size;
 use crate::strategies::client_features::server_connections::{init_connections, is_warmup_complete};
 use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::base_data::footprint::Footprint;
 use crate::standardized_types::base_data::quote::Quote;
 use crate::standardized_types::base_data::quotebar::QuoteBar;
 use crate::standardized_types::base_data::tick::Tick;
 use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse};
+use crate::strategies::statistics::streaming::StreamingStatistics;
+use crate::strategies::statistics::chart_export::ChartExport;
+use crate::strategies::checkpoints::{CheckpointSources, StrategySnapshot};
+use crate::strategies::subscription_profiles::{get_subscription_profile, save_subscription_profile, SubscriptionProfile};
+use crate::product_maps::rithmic::maps::{extract_symbol_from_contract, get_futures_trading_hours};
 use crate::product_maps::rithmic::rollover::get_front_month;
 use crate::standardized_types::accounts::{Account, Currency};
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::time_slices::{align_bars, AlignedBars};
 use crate::standardized_types::broker_enum::Brokerage;
 use crate::standardized_types::market_hours::TradingHours;
 use crate::standardized_types::new_types::{Price, Volume};
-use crate::standardized_types::orders::{Order, OrderId, OrderRequest, OrderType, OrderUpdateType, TimeInForce};
-use crate::standardized_types::position::Position;
+use crate::standardized_types::orders::{BasketEvent, BasketId, BasketPolicy, Order, OrderId, OrderRequest, OrderSpec, OrderState, OrderType, OrderUpdateEvent, OrderUpdateType, TimeInForce, TriggerSource};
+use crate::standardized_types::position::{Position, PositionId};
+use crate::standardized_types::symbol_info::{LotRounding, SymbolInfo};
 use crate::strategies::client_features::connection_types::ConnectionType;
 use crate::strategies::client_features::live_subscriptions::live_subscription_handler;
-use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::client_features::request_handler::{callback_request, send_request, StrategyRequest};
 use crate::strategies::handlers::live_warmup::live_warm_up;
 use crate::strategies::handlers::market_handler::backtest_matching_engine;
 use crate::strategies::handlers::market_handler::backtest_matching_engine::BackTestEngineMessage;
 use crate::strategies::handlers::market_handler::live_order_matching::live_order_handler;
+use crate::strategies::handlers::market_handler::baskets::track_basket;
+use crate::strategies::handlers::market_handler::paper_simulation::{set_paper_simulation, PaperSimulationProfile};
+use crate::strategies::handlers::market_handler::price_bands::{PriceBand, PriceValidation};
+use crate::strategies::handlers::market_handler::session_levels::{SessionLevelSettings, SessionLevels};
+use crate::strategies::handlers::market_handler::queue_position::LimitFillModel;
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
-use crate::strategies::historical_engine::HistoricalEngine;
+use crate::strategies::historical_engine::{valid_buffer_duration, HistoricalEngine};
 use crate::strategies::historical_time::{get_backtest_time, update_backtest_time};
 use crate::strategies::indicators::indicator_events::IndicatorEvents;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::messages::message_bus::encode_message;
+use rkyv::ser::serializers::AllocSerializer;
+use crate::strategies::indicators::indicator_warmup::IndicatorWarmup;
+use crate::strategies::indicators::derived_series::DerivedSeries;
+use crate::standardized_types::economic_calendar::{symbol_currencies, EconomicCalendar, EventImpact};
+use crate::strategies::ledgers::journal::TradeFilter;
+use crate::strategies::ledgers::ledger::{ClosedTrade, TradeExport};
 use crate::strategies::ledgers::ledger_service::LedgerService;
+use crate::strategies::ledgers::statement_import::{parse_statement, StatementFormat, StatementImport};
+use crate::strategies::ledgers::tag_view::TagSummary;
+use crate::strategies::ledgers::campaigns::{Campaign, CampaignGrouping};
+use crate::strategies::ledgers::trade_formats::TradeExportFormat;
+use crate::strategies::ledgers::account_statement::AccountStatement;
+use crate::helpers::logging::{init_default_logging, order_span};
+use crate::strategies::live_parameters::{LiveParameterHandler, LiveParameters, ParameterChange};
+use crate::strategies::position_sizing::{contract_legs, ContractDownsizing, PositionSizer};
+use crate::strategies::handlers::schedule_supervisor::ScheduleSupervisor;
+use crate::strategies::handlers::universe_selector::UniverseSelector;
+use crate::strategies::handlers::algo_executor::AlgoExecutor;
+use crate::strategies::execution_algos::{AlgoEvent, AlgoId, ParentOrder};
+use crate::strategies::order_routing::RoutingTable;
+use crate::strategies::universe_selection::UniverseSelection;
+use crate::strategies::lifecycle::{LifecycleHooks, SessionClose};
+use crate::strategies::notifications;
+use crate::strategies::notifications::{NotificationLevel, NotificationSettings};
+use crate::strategies::feed_quality::{self, FeedQualitySettings};
+use crate::strategies::ledgers::drawdown_guard::RiskStage;
+use crate::strategies::trading_schedule::TradingSchedule;
 
 /// The `FundForgeStrategy` struct is the main_window struct for the FundForge strategy. It contains the state of the strategy and the callback function for data updates.
 
+/// The number of order requests kept by dry run mode, the oldest request is dropped when a new one is logged.
+const MAX_DRY_RUN_ORDERS: usize = 10_000;
+
 /// # Properties
 #[allow(dead_code)]
 pub struct FundForgeStrategy {
@@ -86,12 +145,36 @@ pub struct FundForgeStrategy {
 
     ledger_service: Arc<LedgerService>,
 
-    market_price_service: Arc<MarketPriceService>
+    market_price_service: Arc<MarketPriceService>,
+
+    position_sizers: DashMap<Account, Arc<dyn PositionSizer>>,
+
+    contract_downsizing: DashMap<Account, ContractDownsizing>,
+
+    live_parameters: Arc<LiveParameterHandler>,
+
+    strategy_event_sender: mpsc::Sender<StrategyEvent>,
+
+    schedule_supervisor: Arc<ScheduleSupervisor>,
+
+    universe_selector: Arc<UniverseSelector>,
+
+    algo_executor: Arc<AlgoExecutor>,
 
+    routing_table: RwLock<Option<RoutingTable>>,
+
+    dry_run: Arc<AtomicBool>,
+
+    dry_run_orders: Mutex<VecDeque<(DateTime<Utc>, OrderRequest)>>,
+
+    checkpoints_enabled: AtomicBool,
+
+    event_queue: Arc<EventQueue>,
 }
 
 impl FundForgeStrategy {
     /// Initializes a new `FundForgeStrategy` instance with the provided parameters.
+    /// Engine messages are logged to the console at `LogLevel::Info` unless `init_logging()` was called first.
     ///
     /// # Arguments
     /// `strategy_mode: StrategyMode`: The mode of the strategy (Backtest, Live, LivePaperTrading).
@@ -108,11 +191,11 @@ impl FundForgeStrategy {
     ///
     /// `subscriptions: Vec<DataSubscription>`: The initial data subscriptions for the strategy.
     ///
-    /// `fill_forward: bool`: If true we will fill forward with flat bars based on the last close when there is no data, this is only for consolidated data and applies to the initial subscriptions.
+    /// `fill_forward: bool`: If true we will fill forward with flat bars based on the last close when there is no data, this is only for consolidated data and applies to the initial subscriptions without a `FillPolicy`.
     ///
     /// `retain_history: usize`: The number of bars to retain in memory for the strategy. This is useful for strategies that need to reference previous bars for calculations, this is only for our initial subscriptions.
     ///
-    /// `strategy_event_sender: mpsc::Sender<EventTimeSlice>`: The sender for strategy events.
+    /// `strategy_event_sender: mpsc::Sender<EventTimeSlice>`: The sender for strategy events, what happens when the channel is full depends on the `EventOverflowPolicy` set with `set_event_overflow_policy()`.
     ///
     /// `replay_delay_ms: Option<u64>`: The delay in milliseconds between time slices for market replay style backtesting. \
     ///  any additional subscriptions added later will be able to specify their own history requirements.
@@ -122,7 +205,8 @@ impl FundForgeStrategy {
     /// In live trading our strategy will capture the tick stream in a buffer and pass it to the strategy in the correct resolution/durations, this helps to prevent spamming our on_data_received() fn.
     /// If we don't need to make strategy decisions on every tick, we can just consolidate the tick stream into buffered time slice events.
     /// This also helps us get_requests consistent results between backtesting and live trading.
-    /// If 0 then it will default to a 1-millisecond buffer.
+    /// If 0 the strategy is unbuffered, every time slice is forwarded as it occurs in both backtesting and live trading, this is for latency-sensitive strategies at the cost of many more events.
+    /// Buffers can be as fine as `MIN_BUFFER_DURATION` (1ms), time slices, order events and ledger times keep the full nanosecond precision of the data.
     ///
     /// `gui_enabled: bool`: If true the engine will forward all StrategyEventSlice's sent to the strategy, to the strategy registry so they can be used by GUI implementations.
     ///
@@ -147,6 +231,13 @@ impl FundForgeStrategy {
         synchronize_accounts: bool,
         accounts: Vec<Account>
     ) -> FundForgeStrategy {
+        init_default_logging();
+        let buffering_duration = valid_buffer_duration(buffering_duration);
+        let (strategy_event_sender, event_queue) = EventQueue::new(strategy_event_sender, event_overflow_policy());
+        let strategy_event_sender = match (strategy_mode, session_recording()) {
+            (StrategyMode::Live | StrategyMode::LivePaperTrading, Some(path)) => SessionRecorder::start(path, strategy_event_sender),
+            _ => strategy_event_sender,
+        };
 
         let price_service = Arc::new(MarketPriceService::new());
         let ledger_service = Arc::new(LedgerService::new(strategy_event_sender.clone(), price_service.clone()));
@@ -187,6 +278,15 @@ impl FundForgeStrategy {
             }
         };
 
+        let replay = session_replay().filter(|_| strategy_mode == StrategyMode::Backtest);
+        let dry_run = Arc::new(AtomicBool::new(replay.is_some()));
+        let schedule_supervisor = Arc::new(ScheduleSupervisor::new(strategy_mode, accounts.clone(), open_order_cache.clone(), paper_order_sender.clone(), strategy_event_sender.clone(), dry_run.clone(), ledger_service.clone()));
+        timed_event_handler.set_schedule_supervisor(schedule_supervisor.clone());
+        let universe_selector = Arc::new(UniverseSelector::new(strategy_mode, subscription_handler.clone(), strategy_event_sender.clone()));
+        timed_event_handler.set_universe_selector(universe_selector.clone());
+        let algo_executor = Arc::new(AlgoExecutor::new(strategy_mode, open_order_cache.clone(), paper_order_sender.clone(), strategy_event_sender.clone(), price_service.clone(), dry_run.clone()));
+        timed_event_handler.set_algo_executor(algo_executor.clone());
+
         let strategy = FundForgeStrategy {
             historical_message_sender: paper_order_sender.clone(),
             backtest_accounts_starting_cash,
@@ -203,12 +303,27 @@ impl FundForgeStrategy {
             synchronize_accounts,
             accounts: accounts.clone(),
             ledger_service: ledger_service.clone(),
-            market_price_service: price_service.clone()
+            market_price_service: price_service.clone(),
+            position_sizers: DashMap::new(),
+            contract_downsizing: DashMap::new(),
+            live_parameters: Arc::new(LiveParameterHandler::new(strategy_event_sender.clone())),
+            strategy_event_sender: strategy_event_sender.clone(),
+            schedule_supervisor,
+            universe_selector,
+            algo_executor,
+            routing_table: RwLock::new(None),
+            dry_run,
+            dry_run_orders: Mutex::new(VecDeque::new()),
+            checkpoints_enabled: AtomicBool::new(false),
+            event_queue,
         };
 
 
-        match strategy_mode {
-            StrategyMode::Backtest => {
+        match (strategy_mode, replay) {
+            (StrategyMode::Backtest, Some(replay)) => {
+                launch_session_replay(replay.clone(), strategy_event_sender.clone(), price_service.clone(), timed_event_handler.clone()).await;
+            }
+            (StrategyMode::Backtest, None) => {
                 let engine = HistoricalEngine::new(
                     strategy_mode.clone(),
                     start_time.to_utc(),
@@ -229,7 +344,7 @@ impl FundForgeStrategy {
 
                 HistoricalEngine::launch(engine).await;
             }
-            StrategyMode::LivePaperTrading | StrategyMode::Live  => {
+            (StrategyMode::LivePaperTrading | StrategyMode::Live, _) => {
                 TimedEventHandler::run_time_updates(timed_event_handler.clone()).await;
                 live_subscription_handler(strategy_mode.clone(), subscription_handler.clone()).await;
             },
@@ -290,22 +405,19 @@ impl FundForgeStrategy {
                     exchange,
                     brokerage,
                 };
-                let (sender, receiver) = oneshot::channel();
-                let msg = StrategyRequest::CallBack(ConnectionType::Broker(brokerage), request, sender);
-                send_request(msg).await;
-                match receiver.await {
+                match callback_request(ConnectionType::Broker(brokerage), request).await {
                     Ok(response) => {
                         match response {
                             DataServerResponse::FrontMonthInfo { info, .. } => Some(info.symbol_code),
                             DataServerResponse::Error { error, .. } => {
-                                eprintln!("Error getting front month: {:?}", error);
+                                tracing::error!("Error getting front month: {:?}", error);
                                 None
                             },
                             _ => None
                         }
                     },
                     Err(e) => {
-                        eprintln!("Error getting front month: {:?}", e);
+                        tracing::error!("Error getting front month: {:?}", e);
                         None
                     }
                 }
@@ -350,14 +462,172 @@ impl FundForgeStrategy {
         self.ledger_service.is_short(account, name)
     }
 
+    /// Sets the `PositionSizer` for the account, the sizer will be consulted by `enter_long()` and `enter_short()` to adjust the requested quantity.
+    /// See `ff_standard_lib::strategies::position_sizing::DrawdownPositionSizer` for a drawdown aware implementation.
+    pub fn set_position_sizer(&self, account: &Account, sizer: Arc<dyn PositionSizer>) {
+        self.position_sizers.insert(account.clone(), sizer);
+    }
+
+    /// Removes the `PositionSizer` for the account, orders will use the quantity passed in by the strategy.
+    pub fn remove_position_sizer(&self, account: &Account) {
+        self.position_sizers.remove(account);
+    }
+
+    /// Returns the quantity after consulting the accounts `PositionSizer` and reducing it for the `RiskStage` of the accounts `DrawdownPolicy`, then applying the live parameters `size_multiplier` and `max_order_quantity`.
+    /// If there is no sizer, no drawdown policy and no live parameters have been set the quantity is returned unchanged.
+    pub fn sized_quantity(&self, account: &Account, symbol_name: &SymbolName, quantity: Volume) -> Volume {
+        let quantity = match self.position_sizers.get(account) {
+            Some(sizer) => {
+                let equity = self.ledger_service.balance(account) + self.ledger_service.open_pnl(account);
+                sizer.value().size(account, symbol_name, quantity, equity)
+            }
+            None => quantity
+        };
+        let quantity = self.schedule_supervisor.allowed_quantity(account, quantity);
+        self.live_parameters.limit_quantity(quantity)
+    }
+
+    /// Downsizes `enter_long()` and `enter_short()` orders for mini contracts to the related micro contract, eg. ES to MES, see `ContractDownsizing`.
+    /// Orders for symbols without a related micro contract are not changed.
+    pub fn set_contract_downsizing(&self, account: &Account, downsizing: ContractDownsizing) {
+        self.contract_downsizing.insert(account.clone(), downsizing);
+    }
+
+    /// Removes the `ContractDownsizing` for the account, orders will be placed for the symbol the strategy requested.
+    pub fn remove_contract_downsizing(&self, account: &Account) {
+        self.contract_downsizing.remove(account);
+    }
+
+    /// The symbol name, symbol code and quantity of each order needed to enter the sized quantity, after applying the accounts `ContractDownsizing`.
+    fn contract_legs(&self, account: &Account, symbol_name: &SymbolName, symbol_code: Option<SymbolCode>, quantity: Volume) -> Vec<(SymbolName, Option<SymbolCode>, Volume)> {
+        let downsizing = self.contract_downsizing.get(account).map(|downsizing| *downsizing.value());
+        contract_legs(downsizing, symbol_name, symbol_code, quantity)
+    }
+
+    /// The net position of the account across a mini contract and its related micro contract, in minis, long positive and short negative.
+    pub fn mini_equivalent_position(&self, account: &Account, symbol_name: &SymbolName) -> Decimal {
+        self.ledger_service.mini_equivalent_position(account, symbol_name)
+    }
+
+    /// The open pnl of the account across a mini contract and its related micro contract.
+    pub fn related_contracts_pnl(&self, account: &Account, symbol_name: &SymbolName) -> Decimal {
+        self.ledger_service.related_contracts_pnl(account, symbol_name)
+    }
+
+    /// Loads the live parameters from the TOML file and watches it for changes, see `LiveParameters` for the file format.
+    /// Each parameter changed while the strategy is running is applied immediately and sent to the strategy as a `StrategyEvent::ParameterChanged`,
+    /// the strategy can then react to the change or restore the old value with `strategy.veto_parameter_change()`.
+    pub fn watch_parameters_file(&self, path: &str) -> Result<(), FundForgeError> {
+        self.live_parameters.watch(PathBuf::from(path), Duration::from_secs(1))
+    }
+
+    /// A copy of the current live parameters.
+    pub fn parameters(&self) -> LiveParameters {
+        self.live_parameters.parameters()
+    }
+
+    /// Returns true if the session is listed in the live parameters `enabled_sessions`, or if no sessions are listed.
+    pub fn is_session_enabled(&self, session: &str) -> bool {
+        self.live_parameters.parameters().is_session_enabled(session)
+    }
+
+    /// Restores the value the parameter had before the change, if the parameter has not been changed again since.
+    pub fn veto_parameter_change(&self, change: &ParameterChange) {
+        self.live_parameters.veto(change);
+    }
+
     async fn order_id(
         &self,
     ) -> OrderId {
         let guid = Uuid::new_v4();
-        format!(
-            "{}",
-            guid
-        )
+        match self.is_dry_run() {
+            true => format!("DRY-{}", guid),
+            false => format!("{}", guid),
+        }
+    }
+
+    /// In dry run mode orders are validated, sized and logged with simulated order ids, but no order requests are sent to the broker or the matching engine.
+    /// Useful for verifying a new strategy against live data before enabling execution, the logged requests are available from `strategy.dry_run_orders()`.
+    /// Because no orders are sent there will be no order or position events, and the ledgers will not change.
+    pub fn set_dry_run(&self, enabled: bool) {
+        self.dry_run.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.load(Ordering::SeqCst)
+    }
+
+    /// The order requests logged in dry run mode with the strategy time they would have been sent, oldest first.
+    /// Only the latest 10,000 requests are kept.
+    pub fn dry_run_orders(&self) -> Vec<(DateTime<Utc>, OrderRequest)> {
+        self.dry_run_orders.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Sends the order request to the broker when live, or to the matching engine in backtest and paper modes, new live orders are added to the open order cache.
+    /// New orders and account wide requests are routed by the `RoutingTable`, then new orders are validated against the lot size of the routed symbol.
+    /// In dry run mode the request is only logged.
+    async fn send_order_request(&self, mut order_request: OrderRequest) {
+        let time = self.time_utc();
+        match &mut order_request {
+            OrderRequest::Create { account, order, .. } => {
+                if let Err(reason) = self.route_order(order) {
+                    self.reject_order(order, reason).await;
+                    return;
+                }
+                if let Err(reason) = self.market_price_service.lot_sizes.validate_order(order).await {
+                    self.reject_order(order, reason).await;
+                    return;
+                }
+                *account = order.account.clone();
+            }
+            OrderRequest::CancelAll { account } | OrderRequest::FlattenAllFor { account } => {
+                if let Some(table) = self.routing_table.read().unwrap().as_ref() {
+                    *account = table.route_account(account);
+                }
+            }
+            // Basket orders are routed and validated before the basket is checked, and cancels and updates use the account of the routed order.
+            _ => {}
+        }
+        match &order_request {
+            OrderRequest::Create { account, order, order_type } => order_span(&order.id, account, time)
+                .in_scope(|| tracing::debug!("Order Request: {:?} {} {} {}", order_type, order.side, order.quantity_open, order.symbol_name)),
+            OrderRequest::Cancel { account, order_id } | OrderRequest::Update { account, order_id, .. } => order_span(order_id, account, time)
+                .in_scope(|| tracing::debug!("Order Request: {:?}", order_request)),
+            OrderRequest::CancelAll { .. } | OrderRequest::FlattenAllFor { .. } => tracing::debug!("Order Request: {:?}", order_request),
+            OrderRequest::Basket { account, basket_id, orders, policy } => {
+                for order in orders {
+                    order_span(&order.id, account, time)
+                        .in_scope(|| tracing::debug!("Order Request: Basket {} {}: {:?} {} {} {}", basket_id, policy, order.order_type, order.side, order.quantity_open, order.symbol_name));
+                }
+            }
+        }
+        if self.is_dry_run() {
+            tracing::info!("Dry Run: {}: {:?}", time, order_request);
+            let mut dry_run_orders = self.dry_run_orders.lock().unwrap();
+            if dry_run_orders.len() == MAX_DRY_RUN_ORDERS {
+                dry_run_orders.pop_front();
+            }
+            dry_run_orders.push_back((time, order_request));
+            return;
+        }
+        if self.mode == StrategyMode::Live {
+            match &order_request {
+                OrderRequest::Create { order, .. } => {
+                    self.open_order_cache.insert(order.id.clone(), order.clone());
+                }
+                OrderRequest::Basket { orders, .. } => {
+                    for order in orders {
+                        self.open_order_cache.insert(order.id.clone(), order.clone());
+                    }
+                }
+                _ => {}
+            }
+            let connection_type = ConnectionType::Broker(order_request.brokerage());
+            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
+            send_request(request).await;
+        } else if let Some(historical_message_sender) = &self.historical_message_sender {
+            historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
+        }
     }
 
     //todo[Strategy]
@@ -367,6 +637,10 @@ impl FundForgeStrategy {
 
 
     /// Enters a long position and closes any short positions open for the account and symbol
+    /// If a `PositionSizer` has been set for the account, the quantity will be adjusted by the sizer.
+    /// If a `ContractDownsizing` has been set for the account, part or all of the quantity may be entered with the micro contract,
+    /// in which case an order is placed for each contract and the id of the first order is returned.
+    /// If the quantity is sized down to 0, eg. at the maximum drawdown, no order is sent, the open position is not closed and an `OrderRejected` event is sent for the returned id.
     pub async fn enter_long(
         &self,
         symbol_name: &SymbolName,
@@ -376,32 +650,40 @@ impl FundForgeStrategy {
         quantity: Volume,
         tag: String,
     ) -> OrderId {
-        let order_id = self.order_id().await;
-        let order = Order::enter_long(
-            symbol_name.clone(),
-            symbol_code,
-            account,
-            quantity,
-            tag,
-            order_id.clone(),
-            self.time_utc(),
-            exchange
-        );
-        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::EnterLong };
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
+        let quantity = self.sized_quantity(account, symbol_name, quantity);
+        let legs = self.contract_legs(account, symbol_name, symbol_code.clone(), quantity);
+        if legs.is_empty() {
+            let order = Order::enter_long(symbol_name.clone(), symbol_code, account, quantity, tag, self.order_id().await, self.time_utc(), exchange);
+            return self.reject_sized_to_zero(order).await;
+        }
+        let mut first_order_id = None;
+        for (symbol_name, symbol_code, quantity) in legs {
+            let order_id = self.order_id().await;
+            first_order_id.get_or_insert(order_id.clone());
+            let order = Order::enter_long(
+                symbol_name,
+                symbol_code,
+                account,
+                quantity,
+                tag.clone(),
+                order_id.clone(),
+                self.time_utc(),
+                exchange.clone()
+            );
+            if !self.supervisor_allows(&order).await {
+                continue;
             }
+            let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::EnterLong };
+            self.send_order_request(order_request).await;
         }
-        order_id
+        first_order_id.unwrap_or_default()
     }
 
     /// Enters a short position and closes any long positions open for the account and symbol
+    /// If a `PositionSizer` has been set for the account, the quantity will be adjusted by the sizer.
+    /// If a `ContractDownsizing` has been set for the account, part or all of the quantity may be entered with the micro contract,
+    /// in which case an order is placed for each contract and the id of the first order is returned.
+    /// If the quantity is sized down to 0, eg. at the maximum drawdown, no order is sent, the open position is not closed and an `OrderRejected` event is sent for the returned id.
     pub async fn enter_short(
         &self,
         symbol_name: &SymbolName,
@@ -411,29 +693,33 @@ impl FundForgeStrategy {
         quantity: Volume,
         tag: String,
     ) -> OrderId {
-        let order_id = self.order_id().await;
-        let order = Order::enter_short(
-            symbol_name.clone(),
-            symbol_code,
-            account,
-            quantity,
-            tag,
-            order_id.clone(),
-            self.time_utc(),
-            exchange
-        );
-        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::EnterShort};
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
+        let quantity = self.sized_quantity(account, symbol_name, quantity);
+        let legs = self.contract_legs(account, symbol_name, symbol_code.clone(), quantity);
+        if legs.is_empty() {
+            let order = Order::enter_short(symbol_name.clone(), symbol_code, account, quantity, tag, self.order_id().await, self.time_utc(), exchange);
+            return self.reject_sized_to_zero(order).await;
+        }
+        let mut first_order_id = None;
+        for (symbol_name, symbol_code, quantity) in legs {
+            let order_id = self.order_id().await;
+            first_order_id.get_or_insert(order_id.clone());
+            let order = Order::enter_short(
+                symbol_name,
+                symbol_code,
+                account,
+                quantity,
+                tag.clone(),
+                order_id.clone(),
+                self.time_utc(),
+                exchange.clone()
+            );
+            if !self.supervisor_allows(&order).await {
+                continue;
             }
+            let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::EnterShort };
+            self.send_order_request(order_request).await;
         }
-        order_id
+        first_order_id.unwrap_or_default()
     }
 
     /// Exits a long position or does nothing if no long position
@@ -458,16 +744,7 @@ impl FundForgeStrategy {
             exchange
         );
         let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::ExitLong};
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
-        }
+        self.send_order_request(order_request).await;
         order_id
     }
 
@@ -493,19 +770,36 @@ impl FundForgeStrategy {
             exchange
         );
         let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::ExitShort};
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
-        }
+        self.send_order_request(order_request).await;
         order_id
     }
 
+    /// Exits the open position with the id, used to close one of several positions held on a symbol by a hedging account.
+    /// A `quantity` of `None` exits the whole position, returns `None` if the account has no open position with the id.
+    ///
+    /// Live brokerages receive the `position_id` of the order but close positions by their own rules, see `Order::position_id`.
+    pub async fn exit_position(
+        &self,
+        account: &Account,
+        position_id: &PositionId,
+        quantity: Option<Volume>,
+        exchange: Option<String>,
+        tag: String,
+    ) -> Option<OrderId> {
+        let position = self.ledger_service.position(account, position_id)?;
+        let quantity = quantity.unwrap_or(position.quantity_open);
+        let order_id = self.order_id().await;
+        let (mut order, order_type) = match position.side {
+            PositionSide::Long => (Order::exit_long(position.symbol_name, Some(position.symbol_code), account, quantity, tag, order_id.clone(), self.time_utc(), exchange), OrderType::ExitLong),
+            PositionSide::Short => (Order::exit_short(position.symbol_name, Some(position.symbol_code), account, quantity, tag, order_id.clone(), self.time_utc(), exchange), OrderType::ExitShort),
+            PositionSide::Flat => return None,
+        };
+        order.position_id = Some(position_id.clone());
+        let order_request = OrderRequest::Create{ account: account.clone(), order, order_type};
+        self.send_order_request(order_request).await;
+        Some(order_id)
+    }
+
     /// Buys the market and effects any open positions, or creates a new one
     pub async fn buy_market(
         &self,
@@ -528,18 +822,12 @@ impl FundForgeStrategy {
             self.time_utc(),
             exchange
         );
+        if !self.supervisor_allows(&order).await {
+            return order_id;
+        }
         let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::Market};
 
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
-        }
+        self.send_order_request(order_request).await;
         order_id
     }
 
@@ -565,18 +853,393 @@ impl FundForgeStrategy {
             self.time_utc(),
             exchange
         );
+        if !self.supervisor_allows(&order).await {
+            return order_id;
+        }
         let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::Market};
+        self.send_order_request(order_request).await;
+        order_id
+    }
+
+    /// Validates the limit and trigger prices of a live order against the tick size and the price band, see `set_price_validation()`.
+    /// Paper and backtest orders are validated by the backtest engine, which also sends the rejection.
+    async fn validate_live_order_prices(&self, order: &mut Order) -> bool {
+        if self.mode != StrategyMode::Live {
+            return true;
+        }
+        match self.market_price_service.price_bands.validate_order(order).await {
+            Ok(()) => true,
+            Err(reason) => {
+                self.reject_order(order, reason).await;
+                false
+            }
+        }
+    }
+
+    /// Orders which would open or increase a position are rejected while the `TradingSchedule` is idle or the `DrawdownPolicy` of the account blocks entries, exits are always allowed.
+    async fn supervisor_allows(&self, order: &Order) -> bool {
+        match self.supervisor_rejection(order) {
+            Some(reason) => {
+                self.reject_order(order, reason).await;
+                false
+            }
+            None => true
+        }
+    }
+
+    /// The reason the schedule or drawdown policy rejects the order, see `supervisor_allows()`.
+    fn supervisor_rejection(&self, order: &Order) -> Option<String> {
+        let reason = if !self.schedule_supervisor.is_active(self.time_utc()) {
+            "Outside of the trading schedule"
+        } else if self.schedule_supervisor.risk_stage(&order.account).blocks_entries() {
+            "Entries blocked by the drawdown policy"
+        } else {
+            return None;
+        };
+        let reduces_position = match order.order_type {
+            OrderType::ExitLong | OrderType::ExitShort => true,
+            OrderType::EnterLong | OrderType::EnterShort => false,
+            _ => match order.side {
+                OrderSide::Buy => self.is_short(&order.account, &order.symbol_name),
+                OrderSide::Sell => self.is_long(&order.account, &order.symbol_name),
+            }
+        };
+        match reduces_position {
+            true => None,
+            false => Some(reason.to_string())
+        }
+    }
+
+    /// Rejects an entry which was sized down to a quantity of 0 and returns the id of the rejected order.
+    async fn reject_sized_to_zero(&self, order: Order) -> OrderId {
+        self.reject_order(&order, "Sized to a quantity of 0".to_string()).await;
+        order.id
+    }
+
+    async fn reject_order(&self, order: &Order, reason: String) {
+        let event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderRejected {
+            account: order.account.clone(),
+            symbol_name: order.symbol_name.clone(),
+            symbol_code: order.symbol_code.clone(),
+            order_id: order.id.clone(),
+            reason,
+            tag: order.tag.clone(),
+            time: self.time_utc().to_string()
+        });
+        if let Err(e) = self.strategy_event_sender.send(event).await {
+            tracing::error!("Failed to send order rejected event: {}", e);
+        }
+    }
+
+    /// Only trades inside the windows of the schedule, `None` removes the schedule.
+    /// While the schedule is idle orders which would open or increase a position are rejected, and when each window closes the strategy is flattened if `flatten_outside` is true.
+    /// Data subscriptions and indicators keep updating outside the windows, a `StrategyEvent::ScheduleEvent` is sent each time the schedule becomes active or idle.
+    pub fn set_trading_schedule(&self, schedule: Option<TradingSchedule>) {
+        self.schedule_supervisor.set_schedule(schedule, self.time_utc());
+    }
+
+    /// The stage of the `DrawdownPolicy` of the account, `RiskStage::Normal` if no policy is set, see `set_drawdown_policy()`.
+    pub fn risk_stage(&self, account: &Account) -> RiskStage {
+        self.schedule_supervisor.risk_stage(account)
+    }
+
+    /// Sends a `ScheduleEvent::SessionClose` and calls `LifecycleHooks::on_session_close()` at each session close of the trading hours,
+    /// and flattens the accounts at the `flatten_at` time if it is set, see `SessionClose`. `None` removes the session close.
+    /// # Example
+    /// ```rust,ignore
+    /// // Flatten at 15:55 Chicago time, before the CME session closes
+    /// strategy.set_session_close(Some(SessionClose::new(CME_HOURS).with_flatten_at(NaiveTime::from_hms_opt(15, 55, 0).unwrap())));
+    /// ```
+    pub fn set_session_close(&self, session_close: Option<SessionClose>) {
+        self.schedule_supervisor.set_session_close(session_close, self.time_utc());
+    }
+
+    /// Sets the `LifecycleHooks` run by the engine, `None` removes the hooks.
+    pub fn set_lifecycle_hooks(&self, hooks: Option<Arc<dyn LifecycleHooks>>) {
+        self.schedule_supervisor.set_hooks(hooks);
+    }
+
+    /// Writes an `AccountStatement` html file for each account to the folder at each session close of the `SessionClose` in live modes, and when the strategy shuts down.
+    /// Each statement covers the period since the previous statement, the first starts now, `None` stops the statements.
+    /// Each statement is passed to `LifecycleHooks::on_account_statement()`, eg. to email it.
+    /// # Example
+    /// ```rust,ignore
+    /// strategy.set_session_close(Some(SessionClose::new(CME_HOURS)));
+    /// strategy.set_account_statements(Some("./statements".to_string()));
+    /// ```
+    pub fn set_account_statements(&self, folder: Option<String>) {
+        self.schedule_supervisor.set_account_statements(folder, self.time_utc());
+    }
+
+    /// Sends notifications to the Discord, Slack, Telegram or webhook targets of the settings, `None` stops the notifications.
+    /// With `NotificationSettings::engine_events` the engine also sends order rejections, `RiskStage` changes and dropped connections in live modes.
+    /// # Example
+    /// ```rust,ignore
+    /// let settings = NotificationSettings::new(vec![NotificationTarget::Discord { webhook_url: "https://discord.com/api/webhooks/...".to_string() }])
+    ///     .with_min_level(NotificationLevel::Warning);
+    /// strategy.set_notifications(Some(settings));
+    /// ```
+    pub fn set_notifications(&self, settings: Option<NotificationSettings>) {
+        notifications::set_notifications(settings);
+    }
+
+    /// Sends a notification to the targets set with `set_notifications()`.
+    /// The notification is delivered on its own task, so this never waits on the network, notifications over the rate limit are dropped.
+    pub fn notify(&self, level: NotificationLevel, message: impl Into<String>) {
+        notifications::notify(level, message.into());
+    }
+
+    /// If true the open orders are cancelled and the accounts are flattened when the strategy shuts down, false by default.
+    pub fn set_flatten_on_shutdown(&self, flatten_on_shutdown: bool) {
+        self.schedule_supervisor.set_flatten_on_shutdown(flatten_on_shutdown);
+    }
+
+    /// Shuts the strategy down, flattening the accounts if `set_flatten_on_shutdown()` is true and calling `LifecycleHooks::on_shutdown()`.
+    /// Backtests shut down when they complete, live strategies should call this when they end, eg. when a `StrategyEvent::ShutdownEvent` is received.
+    /// Only the first shutdown has any effect.
+    pub async fn shutdown(&self, reason: String) {
+        self.schedule_supervisor.shutdown(reason).await;
+    }
+
+    /// True if there is no trading schedule or the current time is inside one of its windows.
+    pub fn is_trading_window(&self) -> bool {
+        self.schedule_supervisor.is_active(self.time_utc())
+    }
+
+    /// Monitors the live data of the primary subscriptions for stale feeds, crossed or locked books and price spikes, `None` stops the monitor.
+    /// Each problem is sent as a `DataSubscriptionEvent::QualityAlert`, and again when it clears, so the strategy can pause trading on bad data.
+    /// The monitor is only used in live modes.
+    /// # Example
+    /// ```rust,ignore
+    /// let settings = FeedQualitySettings::default()
+    ///     .with_stale_after(Some(Duration::seconds(30)))
+    ///     .with_trading_hours(CME_HOURS)
+    ///     .with_spike_detection(6.0, 200);
+    /// strategy.set_feed_quality(Some(settings));
+    /// ```
+    pub fn set_feed_quality(&self, settings: Option<FeedQualitySettings>) {
+        if self.mode == StrategyMode::Backtest {
+            tracing::warn!("The feed quality monitor is only used in live modes, ignoring the settings in {:?} mode", self.mode);
+            return;
+        }
+        feed_quality::set_feed_quality(settings);
+    }
+
+    /// Simulates broker rejections and latency for live paper orders, `None` disables the simulation, see `PaperSimulationProfile`.
+    /// The simulation is only used in `StrategyMode::LivePaperTrading`.
+    pub fn set_paper_simulation(&self, profile: Option<PaperSimulationProfile>) {
+        if self.mode != StrategyMode::LivePaperTrading {
+            tracing::warn!("Paper simulation is only used in live paper trading, ignoring the profile in {:?} mode", self.mode);
+            return;
+        }
+        set_paper_simulation(profile);
+    }
+
+    /// Loads a `PaperSimulationProfile` from a toml file and uses it for live paper orders.
+    pub fn load_paper_simulation_profile(&self, path: &str) -> Result<(), FundForgeError> {
+        let profile = PaperSimulationProfile::from_file(path)?;
+        tracing::info!("Paper Simulation: {:?}", profile);
+        self.set_paper_simulation(Some(profile));
+        Ok(())
+    }
+
+    /// Sets how the backtest matching engine fills resting limit orders, by default `LimitFillModel::Simple` fills them as soon as the market reaches the limit price.
+    /// The model applies to limit orders placed after it is set, it is used in `StrategyMode::Backtest` and `StrategyMode::LivePaperTrading`.
+    pub fn set_limit_fill_model(&self, model: LimitFillModel) {
         if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
+            tracing::warn!("The limit fill model is only used by the paper matching engine, ignoring the model in {:?} mode", self.mode);
+            return;
+        }
+        self.market_price_service.queue_positions.set_model(model);
+    }
+
+    /// Sets how order prices which are not on a tick, or which are outside the price band, are handled.
+    /// By default prices are rounded to the nearest tick and orders outside the price band are rejected.
+    pub fn set_price_validation(&self, validation: PriceValidation) {
+        self.market_price_service.price_bands.set_validation(validation);
+    }
+
+    /// Sets how order quantities which are not a multiple of the lot size of the symbol are handled, see `SymbolInfo::lot_size`.
+    /// By default the orders are rejected, quantities below the minimum quantity of the symbol are always rejected.
+    pub fn set_lot_rounding(&self, rounding: LotRounding) {
+        self.market_price_service.lot_sizes.set_rounding(rounding);
+    }
+
+    /// Validates the order quantities of the symbol against the lot size and minimum quantity of the symbol info, instead of the symbol info of the brokerage.
+    /// # Example
+    /// ```rust,ignore
+    /// // Trade forex in micro lots of 1000 units
+    /// let info = account.brokerage.symbol_info("EUR-USD".to_string()).await.unwrap().with_lot_size(dec!(1000), None);
+    /// strategy.set_lot_size(info);
+    /// ```
+    pub fn set_lot_size(&self, symbol_info: SymbolInfo) {
+        self.market_price_service.lot_sizes.set_symbol_info(symbol_info);
+    }
+
+    /// Sets the daily price limit for the symbol, replacing the exchange default, `None` disables the price band for the symbol.
+    pub fn set_price_band(&self, symbol_name: &SymbolName, band: Option<PriceBand>) {
+        self.market_price_service.price_bands.set_band(symbol_name.clone(), band);
+    }
+
+    /// Sets the reference price of the price band for the current trading day, eg. the settlement price of the previous day.
+    /// Otherwise the last price of the previous trading day received by the strategy is used.
+    pub fn set_reference_price(&self, symbol_name: &SymbolName, reference_price: Price) {
+        self.market_price_service.price_bands.set_reference_price(symbol_name.clone(), reference_price, self.time_utc());
+    }
+
+    /// The lower and upper price limits for the symbol on the current trading day, `None` if the symbol has no price band or no reference price yet.
+    pub fn price_limits(&self, symbol_name: &SymbolName) -> Option<(Price, Price)> {
+        self.market_price_service.price_bands.limits(symbol_name)
+    }
+
+    /// Tracks the session open, high and low, the overnight high and low and the opening range of the symbol, see `SessionLevelSettings`.
+    /// The levels are updated with each time slice, including the warm up, so they are available with `session_levels()` once the strategy is warmed up.
+    pub fn track_session_levels(&self, symbol_name: &SymbolName, settings: SessionLevelSettings) {
+        self.market_price_service.session_levels.track(symbol_name.clone(), settings);
+    }
+
+    pub fn untrack_session_levels(&self, symbol_name: &SymbolName) {
+        self.market_price_service.session_levels.untrack(symbol_name);
+    }
+
+    /// The levels of the current session of the symbol, `None` if the symbol is not tracked or has no data in the current session.
+    pub fn session_levels(&self, symbol_name: &SymbolName) -> Option<SessionLevels> {
+        self.market_price_service.session_levels.levels(symbol_name)
+    }
+
+    /// Submits the orders as a basket, eg. the legs of a pair trade or a hedged entry, returning the basket id and the order ids in the order of the specs.
+    ///
+    /// With `BasketPolicy::AllOrNone` the orders are only placed if none of them would be rejected, with `BasketPolicy::BestEffort` the accepted orders are placed.
+    /// The engine, or the server in live trading, checks every order before placing any, the orders send their usual `OrderUpdateEvent`s.
+    /// The outcome of the basket is sent as a `StrategyEvent::BasketEvents`, `BasketEvent::Filled` once every placed order is filled,
+    /// `BasketEvent::PartiallyPlaced` if some orders of a best effort basket were rejected, or `BasketEvent::Rejected` if no orders were placed.
+    ///
+    /// All orders of a basket must be for the same account, position sizers and contract downsizing are not applied.
+    pub async fn submit_basket(&self, orders: Vec<OrderSpec>, policy: BasketPolicy) -> (BasketId, Vec<OrderId>) {
+        let basket_id = self.order_id().await;
+        let time = self.time_utc();
+        let account = match orders.first() {
+            Some(spec) => spec.account().clone(),
+            None => return (basket_id, vec![]),
+        };
+        let mut order_ids = vec![];
+        let mut placed = vec![];
+        let mut rejected = vec![];
+        let mut routed_account: Option<Account> = None;
+        for spec in &orders {
+            let order_id = self.order_id().await;
+            order_ids.push(order_id.clone());
+            let mut order = spec.to_order(order_id, time);
+            let rejection = if spec.account() != &account {
+                Some("All orders of a basket must be for the same account".to_string())
+            } else if let Err(reason) = self.route_order(&mut order) {
+                Some(reason)
+            } else if *routed_account.get_or_insert_with(|| order.account.clone()) != order.account {
+                Some("All orders of a basket must be routed to the same account".to_string())
+            } else if let Err(reason) = self.market_price_service.lot_sizes.validate_order(&mut order).await {
+                Some(reason)
+            } else if let Some(reason) = self.supervisor_rejection(&order) {
+                Some(reason)
+            } else if self.mode == StrategyMode::Live {
+                self.market_price_service.price_bands.validate_order(&mut order).await.err()
+            } else {
+                None
+            };
+            match rejection {
+                Some(reason) => rejected.push((order, reason)),
+                None => placed.push(order),
+            }
+        }
+
+        if !rejected.is_empty() && (policy == BasketPolicy::AllOrNone || placed.is_empty()) {
+            let reason = rejected[0].1.clone();
+            for (order, order_reason) in &rejected {
+                self.reject_order(order, order_reason.clone()).await;
+            }
+            for order in &placed {
+                self.reject_order(order, format!("Basket {} rejected: {}", basket_id, reason)).await;
+            }
+            let event = BasketEvent::Rejected { account, basket_id: basket_id.clone(), reason, time: time.to_string() };
+            if let Err(e) = self.strategy_event_sender.send(StrategyEvent::BasketEvents(event)).await {
+                tracing::error!("Failed to send basket rejected event: {}", e);
+            }
+            return (basket_id, order_ids);
+        }
+        for (order, reason) in &rejected {
+            self.reject_order(order, reason.clone()).await;
+        }
+        if !self.is_dry_run() {
+            let rejected = rejected.into_iter().map(|(order, reason)| (order.id, reason)).collect();
+            track_basket(basket_id.clone(), account.clone(), policy, placed.iter().map(|order| order.id.clone()).collect(), rejected);
+        }
+        let account = routed_account.unwrap_or(account);
+        let order_request = OrderRequest::Basket { account, basket_id: basket_id.clone(), orders: placed, policy };
+        self.send_order_request(order_request).await;
+        (basket_id, order_ids)
+    }
+
+    /// Starts an execution algo which works the parent order with market child orders over its window, see `ParentOrder`, returning the id of the algo.
+    ///
+    /// The progress of the algo is sent as `StrategyEvent::AlgoEvents`, a `AlgoEvent::Progress` each time a child order fills and a `AlgoEvent::Completed` once the parent order is filled,
+    /// or a `AlgoEvent::Expired` if the window ended first. The child orders send their usual `OrderUpdateEvent`s with the tag of the parent order.
+    ///
+    /// Algos which would open or increase a position are rejected while the `TradingSchedule` is idle or the `DrawdownPolicy` of the account blocks entries,
+    /// the child orders of a working algo are not checked again. Position sizers and contract downsizing are not applied.
+    pub async fn submit_algo(&self, mut parent: ParentOrder) -> AlgoId {
+        let algo_id = self.order_id().await;
+        let time = self.time_utc();
+        let mut order = Order::market_order(parent.symbol_name.clone(), parent.symbol_code.clone(), &parent.account, parent.quantity, parent.side, parent.tag.clone(), algo_id.clone(), time, None);
+        let rejection = if parent.quantity <= dec!(0) {
+            Some("Order Quantity Must Be Greater Than Zero".to_string())
+        } else if let Err(reason) = self.route_order(&mut order) {
+            Some(reason)
+        } else if let Err(reason) = self.market_price_service.lot_sizes.validate_order(&mut order).await {
+            Some(reason)
         } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
+            self.supervisor_rejection(&order)
+        };
+        if let Some(reason) = rejection {
+            let event = AlgoEvent::Rejected { account: parent.account, algo_id: algo_id.clone(), symbol_name: parent.symbol_name, reason, time: time.to_string() };
+            if let Err(e) = self.strategy_event_sender.send(StrategyEvent::AlgoEvents(event)).await {
+                tracing::error!("Failed to send algo rejected event: {}", e);
             }
+            return algo_id;
+        }
+        // The child orders are sent by the executor, so the parent takes the routing of the whole quantity.
+        parent.account = order.account;
+        parent.symbol_name = order.symbol_name;
+        parent.symbol_code = Some(order.symbol_code);
+        parent.quantity = order.quantity_open;
+        parent.lot_size = self.market_price_service.lot_sizes.lot_size(parent.account.brokerage, &parent.symbol_name).await;
+        self.algo_executor.submit(algo_id.clone(), parent, time);
+        algo_id
+    }
+
+    /// Stops the algo and cancels its working child orders, the quantity already filled is kept. Returns false if the algo is not working.
+    pub async fn cancel_algo(&self, algo_id: &AlgoId) -> bool {
+        self.algo_executor.cancel(algo_id, self.time_utc()).await
+    }
+
+    /// Routes the orders of the strategy to the brokerage, account, contract and exchange of the matching rule, `None` sends the orders as they are created, see `RoutingTable`.
+    /// Orders already sent keep their routing.
+    pub fn set_routing_table(&self, table: Option<RoutingTable>) {
+        *self.routing_table.write().unwrap() = table;
+    }
+
+    /// Loads a `RoutingTable` from a toml file and uses it for the orders of the strategy.
+    pub fn load_routing_table(&self, path: &str) -> Result<(), FundForgeError> {
+        let table = RoutingTable::from_file(path)?;
+        tracing::info!("Routing Table: {:?}", table);
+        self.set_routing_table(Some(table));
+        Ok(())
+    }
+
+    /// Applies the routing table to the order, returns the reason the order is rejected if it can not be routed.
+    fn route_order(&self, order: &mut Order) -> Result<(), String> {
+        match self.routing_table.read().unwrap().as_ref() {
+            Some(table) => table.route_order(order),
+            None => Ok(()),
         }
-        order_id
     }
 
     /// Will wait for limit price to be hit to fill, if TIF == TimeInForce::Day, it will be cancelled in backtests when the day is over.
@@ -593,22 +1256,17 @@ impl FundForgeStrategy {
         tag: String,
     ) -> OrderId {
         let order_id = self.order_id().await;
-        let order = Order::limit_order(symbol_name.clone(), symbol_code, account, quantity, side, tag, order_id.clone(), self.time_utc(), limit_price, tif, exchange);
-        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::Limit};
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
+        let mut order = Order::limit_order(symbol_name.clone(), symbol_code, account, quantity, side, tag, order_id.clone(), self.time_utc(), limit_price, tif, exchange);
+        if !self.supervisor_allows(&order).await || !self.validate_live_order_prices(&mut order).await {
+            return order_id;
         }
+        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::Limit};
+        self.send_order_request(order_request).await;
         order_id
     }
 
     /// Will trigger if trigger price is hit and buy or sell at market price.
+    /// The trigger price is compared to the price of the `trigger_source`, see `stop_order()`.
     pub async fn market_if_touched (
         &self,
         symbol_name: &SymbolName,
@@ -620,24 +1278,21 @@ impl FundForgeStrategy {
         trigger_price: Price,
         tif: TimeInForce,
         tag: String,
+        trigger_source: Option<TriggerSource>,
     ) -> OrderId {
         let order_id = self.order_id().await;
-        let order = Order::market_if_touched(symbol_name.clone(), symbol_code, account, quantity, side, tag, order_id.clone(), self.time_utc(),trigger_price, tif, exchange);
-        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::MarketIfTouched};
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
+        let mut order = Order::market_if_touched(symbol_name.clone(), symbol_code, account, quantity, side, tag, order_id.clone(), self.time_utc(),trigger_price, tif, exchange);
+        order.trigger_source = trigger_source;
+        if !self.supervisor_allows(&order).await || !self.validate_live_order_prices(&mut order).await {
+            return order_id;
         }
+        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::MarketIfTouched};
+        self.send_order_request(order_request).await;
         order_id
     }
 
-    /// Will buy or sell market price if trigger is hit
+    /// Will buy or sell market price if trigger is hit.
+    /// `trigger_source` sets the price compared to the trigger price, `None` uses the native trigger of the brokerage, see `TriggerSource`.
     pub async fn stop_order (
         &self,
         symbol_name: &SymbolName,
@@ -649,24 +1304,21 @@ impl FundForgeStrategy {
         trigger_price: Price,
         tif: TimeInForce,
         tag: String,
+        trigger_source: Option<TriggerSource>,
     ) -> OrderId {
         let order_id = self.order_id().await;
-        let order = Order::stop(symbol_name.clone(), symbol_code, account, quantity, side, tag, order_id.clone(), self.time_utc(),trigger_price, tif, exchange);
-        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::StopMarket};
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
+        let mut order = Order::stop(symbol_name.clone(), symbol_code, account, quantity, side, tag, order_id.clone(), self.time_utc(),trigger_price, tif, exchange);
+        order.trigger_source = trigger_source;
+        if !self.supervisor_allows(&order).await || !self.validate_live_order_prices(&mut order).await {
+            return order_id;
         }
+        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::StopMarket};
+        self.send_order_request(order_request).await;
         order_id
     }
 
     /// Will trigger on trigger price but fill only when price is on the correct side of limit price, will partially fill in backtest if we have order book data present.
+    /// The trigger price is compared to the price of the `trigger_source`, see `stop_order()`.
     pub async fn stop_limit (
         &self,
         symbol_name: &SymbolName,
@@ -678,21 +1330,17 @@ impl FundForgeStrategy {
         tag: String,
         limit_price: Price,
         trigger_price: Price,
-        tif: TimeInForce
+        tif: TimeInForce,
+        trigger_source: Option<TriggerSource>,
     ) -> OrderId {
         let order_id = self.order_id().await;
-        let order = Order::stop_limit(symbol_name.clone(), symbol_code, account, quantity, side, tag, order_id.clone(), self.time_utc(),limit_price, trigger_price, tif, exchange);
-        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::StopLimit};
-        if self.mode == StrategyMode::Live {
-            self.open_order_cache.insert(order_id.clone(), order.clone());
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
+        let mut order = Order::stop_limit(symbol_name.clone(), symbol_code, account, quantity, side, tag, order_id.clone(), self.time_utc(),limit_price, trigger_price, tif, exchange);
+        order.trigger_source = trigger_source;
+        if !self.supervisor_allows(&order).await || !self.validate_live_order_prices(&mut order).await {
+            return order_id;
         }
+        let order_request = OrderRequest::Create{ account: account.clone(), order: order.clone(), order_type: OrderType::StopLimit};
+        self.send_order_request(order_request).await;
         order_id
     }
 
@@ -711,26 +1359,44 @@ impl FundForgeStrategy {
             account
         };
 
-        if self.mode == StrategyMode::Live {
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
-        }
+        self.send_order_request(order_request).await;
     }
 
-    /// Updates the order if it is not filled, cancelled or rejected.
-    pub async fn update_order(&self, order_id: OrderId, order_update_type: OrderUpdateType) {
-        // Clone the necessary data from the Ref
-        //todo need a market handler update for this
-        let account = if let Some(id_order_ref) = self.open_order_cache.get(&order_id) {
-            id_order_ref.account.clone()
-        } else {
-            return; // Order not found, exit the function
+    /// Amends the price or quantity of a working order, `OrderUpdateType::Quantity` sets the open quantity of the order.
+    /// In live trading the update is sent to the brokerage, in backtests and live paper trading the simulated order is updated.
+    /// If the order is already filled, cancelled or rejected, is a market order or does not have the price being updated,
+    /// the strategy receives an `OrderUpdateEvent::OrderUpdateRejected` and the order is not changed.
+    /// ```rust,ignore
+    /// strategy.modify_order(stop_id.clone(), OrderUpdateType::TriggerPrice(new_stop)).await;
+    /// ```
+    pub async fn modify_order(&self, order_id: OrderId, mut order_update_type: OrderUpdateType) {
+        let (account, symbol_name, validation) = match self.open_order_cache.get(&order_id) {
+            Some(order) => (order.account.clone(), order.symbol_name.clone(), order.validate_update(&order_update_type)),
+            None => match self.closed_order_cache.get(&order_id) {
+                Some(order) => (order.account.clone(), order.symbol_name.clone(), Err(order.validate_update(&order_update_type).err().unwrap_or_else(|| "Order is closed".to_string()))),
+                None => {
+                    tracing::error!("Unable to modify order {}: No order found for id", order_id);
+                    return;
+                }
+            }
         };
+        let validation = match (validation, &mut order_update_type) {
+            (Ok(()), OrderUpdateType::Quantity(quantity)) => self.market_price_service.lot_sizes.validate_quantity(account.brokerage, &symbol_name, *quantity).await
+                .map(|valid_quantity| *quantity = valid_quantity),
+            (validation, _) => validation,
+        };
+        if let Err(reason) = validation {
+            let event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderUpdateRejected {
+                account,
+                order_id,
+                reason,
+                time: self.time_utc().to_string()
+            });
+            if let Err(e) = self.strategy_event_sender.send(event).await {
+                tracing::error!("Failed to send order update rejected event: {}", e);
+            }
+            return;
+        }
 
         let order_request = OrderRequest::Update {
             order_id,
@@ -738,15 +1404,12 @@ impl FundForgeStrategy {
             update: order_update_type,
         };
 
-        if self.mode == StrategyMode::Live {
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
-        }
+        self.send_order_request(order_request).await;
+    }
+
+    /// Updates the order if it is not filled, cancelled or rejected, see `modify_order()`.
+    pub async fn update_order(&self, order_id: OrderId, order_update_type: OrderUpdateType) {
+        self.modify_order(order_id, order_update_type).await;
     }
 
     /// Cancel all pending orders on the account for the symbol_name, this cancels only orders on the symbol which were created by the strategy
@@ -768,28 +1431,15 @@ impl FundForgeStrategy {
                 }
             }
         } else {
-            let order_request = OrderRequest::CancelAll {account};
             if self.mode == StrategyMode::Live {
-                let connection_type = ConnectionType::Broker(order_request.brokerage());
-                let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-                send_request(request).await;
+                self.send_order_request(OrderRequest::CancelAll {account}).await;
             }
         }
     }
 
     /// Flatten all positions on the account. this will try to flatten the whole account regardless of is_synchronize_accounts.
     pub async fn flatten_all_for(&self, account: Account) {
-        if self.mode == StrategyMode::Live {
-            let order_request = OrderRequest::FlattenAllFor {account};
-            let connection_type = ConnectionType::Broker(order_request.brokerage());
-            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
-            send_request(request).await;
-        } else {
-            let order_request = OrderRequest::FlattenAllFor {account};
-            if let Some(historical_message_sender) = &self.historical_message_sender {
-                historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await.unwrap();
-            }
-        }
+        self.send_order_request(OrderRequest::FlattenAllFor {account}).await;
     }
 
     /// get_requests the last price for the symbol name
@@ -806,6 +1456,49 @@ impl FundForgeStrategy {
         self.closed_order_cache.clone()
     }
 
+    /// The open orders the strategy placed on the account, oldest first.
+    pub fn open_orders(&self, account: &Account) -> Vec<Order> {
+        let mut orders: Vec<Order> = self.open_order_cache.iter()
+            .filter(|order| &order.account == account)
+            .map(|order| order.value().clone())
+            .collect();
+        orders.sort_by(|a, b| a.time_created_utc.cmp(&b.time_created_utc));
+        orders
+    }
+
+    /// The open orders the strategy placed on the account for the symbol name, oldest first.
+    pub fn open_orders_symbol(&self, account: &Account, symbol_name: &SymbolName) -> Vec<Order> {
+        self.open_orders(account).into_iter()
+            .filter(|order| &order.symbol_name == symbol_name)
+            .collect()
+    }
+
+    /// The open and closed orders placed with the tag on any account, oldest first. Check `order.state` to tell them apart.
+    /// ```rust,ignore
+    /// for order in strategy.orders_with_tag("Breakout Stop") {
+    ///     if order.state == OrderState::Accepted {
+    ///         strategy.modify_order(order.id.clone(), OrderUpdateType::TriggerPrice(new_stop)).await;
+    ///     }
+    /// }
+    /// ```
+    pub fn orders_with_tag(&self, tag: &str) -> Vec<Order> {
+        let mut orders: Vec<Order> = self.open_order_cache.iter()
+            .chain(self.closed_order_cache.iter())
+            .filter(|order| order.tag == tag)
+            .map(|order| order.value().clone())
+            .collect();
+        orders.sort_by(|a, b| a.time_created_utc.cmp(&b.time_created_utc));
+        orders
+    }
+
+    /// The state of an order the strategy placed, `None` if the order id is unknown.
+    pub fn order_state(&self, order_id: &OrderId) -> Option<OrderState> {
+        if let Some(order) = self.open_order_cache.get(order_id) {
+            return Some(order.state.clone());
+        }
+        self.closed_order_cache.get(order_id).map(|order| order.state.clone())
+    }
+
     /// Adds a timed event which will trigger a time message to the receiver at the time (or after time updates again if time has passed in backtest)
     /// see the timed_event_handler.rs for more details
     pub async fn add_timed_event(&self, timed_event: TimedEvent) {
@@ -826,10 +1519,32 @@ impl FundForgeStrategy {
     /// * `trading_hours: Option<TradingHours>` - The trading hours for the indicator, this is only used for non-intraday resolutions. for example daily or weekly bars will be constructed based on the market hours.
     /// * `week_start: Option<Weekday>` - The day of the week to start the week on, this is only used for weekly subscriptions.
     pub async fn subscribe_indicator(&self, indicator: Box<dyn Indicators>, trading_hours: Option<TradingHours>) {
+        self.add_indicator(indicator, trading_hours, None).await;
+    }
+
+    /// Subscribes to an indicator and warms it up immediately using the warmup override, see `IndicatorWarmup` for more details.
+    /// # Arguments
+    /// * `indicator: Box<dyn Indicators>` - The indicator to subscribe to.
+    /// * `trading_hours: Option<TradingHours>` - The trading hours for the indicator, this is only used for non-intraday resolutions.
+    /// * `warmup: IndicatorWarmup` - The source subscription and number of bars to warm up the indicator with.
+    pub async fn subscribe_indicator_with_warmup(&self, indicator: Box<dyn Indicators>, trading_hours: Option<TradingHours>, warmup: IndicatorWarmup) {
+        self.add_indicator(indicator, trading_hours, Some(warmup)).await;
+    }
+
+    /// Subscribes to an indicator that is calculated on a plot of another indicator's output, for example an SMA of an ATR, see `DerivedSeries` for more details.
+    /// The source indicator must be subscribed first, removing the source indicator also removes the indicators derived from it.
+    /// # Arguments
+    /// * `indicator: Box<dyn Indicators>` - The derived indicator, created with the subscription of the source indicator.
+    /// * `series: DerivedSeries` - The source indicator name and the plot to use as the data.
+    pub async fn subscribe_derived_indicator(&self, indicator: Box<dyn Indicators>, series: DerivedSeries) -> Result<(), FundForgeError> {
+        self.indicator_handler.add_derived_indicator(indicator, series).map(|_| ())
+    }
+
+    async fn add_indicator(&self, indicator: Box<dyn Indicators>, trading_hours: Option<TradingHours>, warmup_override: Option<IndicatorWarmup>) {
         match self.mode {
             StrategyMode::Backtest => {
                 self.indicator_handler
-                    .add_indicator(indicator, self.time_utc(), trading_hours)
+                    .add_indicator(indicator, self.time_utc(), trading_hours, warmup_override)
                     .await;
                 //add_buffer(self.time_utc(), StrategyEvent::IndicatorEvent(event)).await;
             }
@@ -837,7 +1552,7 @@ impl FundForgeStrategy {
                 let indicator_handler = self.indicator_handler.clone();
                // tokio::task::spawn(async move {
                     indicator_handler
-                        .add_indicator(indicator, Utc::now(), trading_hours)
+                        .add_indicator(indicator, Utc::now(), trading_hours, warmup_override)
                         .await;
                    // add_buffer(Utc::now(), StrategyEvent::IndicatorEvent(event)).await;
                // });
@@ -871,6 +1586,56 @@ impl FundForgeStrategy {
         self.indicator_handler.current(name)
     }
 
+    /// Republishes the closed bars and indicator values of every time slice through the default data server under the chart name,
+    /// so external charting front-ends can plot exactly what the strategy sees, including custom indicators and consolidated bars.
+    /// Front-ends receive the updates by sending a `DataServerRequest::SubscribeChart` with the same chart name, or with `strategy.subscribe_chart()`.
+    /// Publishing is opt-in and can only be enabled once, returns false if the strategy is already publishing.
+    pub fn publish_chart_updates(&self, chart_name: &str) -> bool {
+        self.indicator_handler.publish_chart_updates(chart_name.to_string())
+    }
+
+    /// Subscribes to the chart updates published by another strategy connected to the default data server, see `strategy.publish_chart_updates()`.
+    /// The receiver gets the updates for every chart this strategy is subscribed to, keyed by chart name.
+    pub async fn subscribe_chart(&self, chart_name: &str) -> broadcast::Receiver<(ChartName, Vec<ChartUpdate>)> {
+        subscribe_chart(chart_name.to_string()).await
+    }
+
+    pub async fn unsubscribe_chart(&self, chart_name: &str) {
+        unsubscribe_chart(chart_name.to_string()).await
+    }
+
+    /// Draws the object on the chart of its subscription, eg. a stop, a target or a session range, see `ChartObject`.
+    /// The object is sent with the chart updates in order with the bars, so charting clients render it live and when playing back a backtest.
+    /// Returns false if the strategy is not publishing chart updates, see `strategy.publish_chart_updates()`.
+    pub fn draw(&self, object: ChartObject) -> bool {
+        self.indicator_handler.send_chart_update(ChartUpdate::Draw(object))
+    }
+
+    /// Removes the object with the id from the chart of the subscription, returns false if the strategy is not publishing chart updates.
+    pub fn erase(&self, subscription: &DataSubscription, id: &str) -> bool {
+        self.indicator_handler.send_chart_update(ChartUpdate::Erase { subscription: subscription.clone(), id: id.to_string() })
+    }
+
+    /// Publishes the message to every strategy subscribed to the topic through the default data server, see `strategy.subscribe_topic()`.
+    /// The message can be any rkyv struct, subscribers receive it as a `StrategyEvent::CustomMessage` and decode it with `decode_message::<T>(&payload)`.
+    /// ```rust,ignore
+    /// strategy.publish_message("signals", &Signal { symbol_name: "MNQ".to_string(), long: true }).await?;
+    /// ```
+    pub async fn publish_message<T: rkyv::Serialize<AllocSerializer<256>>>(&self, topic: &str, message: &T) -> Result<(), FundForgeError> {
+        let payload = encode_message(message)?;
+        send_request(StrategyRequest::OneWay(ConnectionType::Default, DataServerRequest::PublishMessage { topic: topic.to_string(), payload })).await;
+        Ok(())
+    }
+
+    /// Subscribes to the messages other strategies publish on the topic, including this strategy if it publishes on the topic.
+    pub async fn subscribe_topic(&self, topic: &str) {
+        send_request(StrategyRequest::OneWay(ConnectionType::Default, DataServerRequest::SubscribeTopic { topic: topic.to_string() })).await;
+    }
+
+    pub async fn unsubscribe_topic(&self, topic: &str) {
+        send_request(StrategyRequest::OneWay(ConnectionType::Default, DataServerRequest::UnsubscribeTopic { topic: topic.to_string() })).await;
+    }
+
     /// Returns the account balance
     /// In live rithmic doesn't update the balance until after a position is opened, to avoid a balance of 0 before placing orders we can do this in live trading
     /// ```rust
@@ -952,11 +1717,126 @@ impl FundForgeStrategy {
         self.subscription_handler.strategy_subscriptions().await
     }
 
+    /// The capabilities of the data vendor, requested from the server once and cached.
+    pub async fn vendor_capabilities(&self, data_vendor: DataVendor) -> Result<VendorCapabilities, FundForgeError> {
+        data_vendor.capabilities().await
+    }
+
+    /// The first of the `candidates` whose base data type is available from its data vendor, in order of preference.
+    /// Used to fall back to a lower fidelity subscription, eg. from ticks to 1 second candles when the vendor has no tick data.
+    /// Candidates whose vendor capabilities can not be retrieved are skipped.
+    pub async fn best_available_subscription(&self, candidates: Vec<DataSubscription>) -> Option<DataSubscription> {
+        for subscription in candidates {
+            if let Ok(capabilities) = subscription.symbol.data_vendor.capabilities().await {
+                if capabilities.supports(subscription.base_data_type) {
+                    return Some(subscription);
+                }
+            }
+        }
+        None
+    }
+
+    /// Saves the profile on the server so other strategies can load it by name, see `SubscriptionProfile`.
+    pub async fn save_subscription_profile(&self, profile: SubscriptionProfile) -> Result<(), FundForgeError> {
+        save_subscription_profile(profile).await
+    }
+
+    /// A snapshot of the ledgers, open orders, indicator histories and open bars of the strategy, see `StrategySnapshot`.
+    pub fn snapshot(&self) -> StrategySnapshot {
+        self.checkpoint_sources().snapshot()
+    }
+
+    /// Saves a `StrategySnapshot` to the file every interval, so a crashed live strategy can be resumed with `strategy.restore_checkpoint()`.
+    /// Checkpoints can only be enabled once, returns false if they are already enabled or the strategy is backtesting.
+    /// ```rust,ignore
+    /// if strategy.restore_checkpoint("./checkpoints/my_strategy.checkpoint").await.is_err() {
+    ///     println!("No checkpoint, starting flat");
+    /// }
+    /// strategy.enable_checkpoints("./checkpoints/my_strategy.checkpoint", Duration::from_secs(30));
+    /// ```
+    pub fn enable_checkpoints(&self, path: &str, interval: Duration) -> bool {
+        if self.mode == StrategyMode::Backtest || self.checkpoints_enabled.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        self.checkpoint_sources().run(PathBuf::from(path), interval);
+        true
+    }
+
+    /// Restores the ledgers and open orders saved by `strategy.enable_checkpoints()`, call this after `initialize()` and before placing orders.
+    /// Ledgers are only restored for accounts the strategy trades, open orders are only restored if the strategy does not already know the order id.
+    /// Returns the snapshot so the strategy can inspect the saved indicator histories and open bars, the indicators themselves are rebuilt by the warm up.
+    /// Checkpoints can not be restored when backtesting.
+    pub async fn restore_checkpoint(&self, path: &str) -> Result<StrategySnapshot, FundForgeError> {
+        if self.mode == StrategyMode::Backtest {
+            return Err(FundForgeError::ClientSideErrorDebug("Checkpoints can not be restored in backtests".to_string()));
+        }
+        let snapshot = StrategySnapshot::load(Path::new(path))?;
+        for ledger in &snapshot.ledgers {
+            if !self.accounts.contains(&ledger.account) {
+                tracing::warn!("Checkpoint Restore: skipping ledger for {}, the strategy does not trade the account", ledger.account);
+                continue;
+            }
+            let restore = self.ledger_service.restore_snapshot(ledger.clone()).await?;
+            tracing::info!("Checkpoint Restore: {}: {} open positions, {} closed positions, {} conflicting positions", ledger.account, restore.open_positions, restore.closed_positions, restore.conflicting_positions);
+        }
+        for order in &snapshot.open_orders {
+            if self.accounts.contains(&order.account) && !self.closed_order_cache.contains_key(&order.id) {
+                self.open_order_cache.entry(order.id.clone()).or_insert_with(|| order.clone());
+            }
+        }
+        Ok(snapshot)
+    }
+
+    fn checkpoint_sources(&self) -> CheckpointSources {
+        CheckpointSources {
+            mode: self.mode,
+            accounts: self.accounts.clone(),
+            ledger_service: self.ledger_service.clone(),
+            open_order_cache: self.open_order_cache.clone(),
+            indicator_handler: self.indicator_handler.clone(),
+            subscription_handler: self.subscription_handler.clone(),
+        }
+    }
+
+    /// Loads the profile saved on the server with the name and subscribes to its subscriptions and indicators, usually called before the strategy starts processing data.
+    /// Futures subscriptions and indicators use the trading hours of the symbol.
+    pub async fn load_subscription_profile(&self, name: &str) -> Result<SubscriptionProfile, FundForgeError> {
+        let profile = get_subscription_profile(name).await?;
+        let trading_hours = |subscription: &DataSubscription| match subscription.market_type {
+            MarketType::Futures(_) => get_futures_trading_hours(&extract_symbol_from_contract(&subscription.symbol.name)).cloned(),
+            _ => None,
+        };
+        for profile_subscription in &profile.subscriptions {
+            let subscription = profile_subscription.subscription.clone();
+            let hours = trading_hours(&subscription);
+            self.subscribe(None, subscription, profile_subscription.history_to_retain as usize, profile_subscription.fill_forward, hours).await;
+        }
+        for profile_indicator in &profile.indicators {
+            let hours = trading_hours(&profile_indicator.subscription);
+            self.subscribe_indicator(profile_indicator.build().await, hours).await;
+        }
+        Ok(profile)
+    }
+
     /// Subscribes to a new subscription, we can only subscribe to a subscription once.
     /// In live mode we will warm up the subscription as a background task, in backtest we will block the main thread.
     /// Using unwrap on historical index() data in live mode should still be safe when using the current data as reference for the new subscription,
     /// because we won't forward bars until the consolidator is warmed up.
     pub async fn subscribe(&self, primary_source: Option<PrimarySubscription>, subscription: DataSubscription, history_to_retain: usize, fill_forward: bool, trading_hours: Option<TradingHours>) {
+        // Consolidated subscriptions only need the vendor to provide the primary data type, footprints are always consolidated from ticks.
+        let vendor_data_type = match (&primary_source, subscription.base_data_type) {
+            (_, BaseDataType::Footprints) => BaseDataType::Ticks,
+            (Some(primary), _) => primary.base_data_type,
+            (None, base_data_type) => base_data_type,
+        };
+        if let Ok(capabilities) = subscription.symbol.data_vendor.capabilities().await {
+            if !capabilities.supports(vendor_data_type) {
+                let msg = format!("{} does not provide {} data, see `strategy.best_available_subscription()`", subscription.symbol.data_vendor, vendor_data_type);
+                let event = DataSubscriptionEvent::FailedToSubscribe(subscription, msg);
+                let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(event)).await;
+                return;
+            }
+        }
         match self.mode {
             StrategyMode::Backtest => {
                 let _ = self.subscription_handler
@@ -974,6 +1854,39 @@ impl FundForgeStrategy {
         }
     }
 
+    /// Subscribes to the subscriptions together, each subscription the vendor does not provide directly is consolidated from the closest resolution the vendor streams,
+    /// so subscriptions on the same symbol share a primary subscription.
+    /// After the initial warm up, the warmup data for all the subscriptions is downloaded in a single request, the engine is updated with the new primary subscriptions once,
+    /// and the `DataSubscriptionEvent`s are sent together when every subscription is created.
+    /// ```rust,ignore
+    /// strategy.subscribe_many(vec![eur_usd_15m, eur_usd_60m, aud_usd_15m], 100).await;
+    /// ```
+    pub async fn subscribe_many(&self, subscriptions: Vec<DataSubscription>, history_to_retain: usize) {
+        let time = match self.mode {
+            StrategyMode::Backtest => self.time_utc(),
+            StrategyMode::Live | StrategyMode::LivePaperTrading => Utc::now(),
+        };
+        self.subscription_handler.subscribe_many(subscriptions, time, history_to_retain).await;
+    }
+
+    /// Adds a universe which is scanned every `scan_interval` on the strategy time, subscribing the strategy to the top ranked candidates, see `UniverseSelection`.
+    /// The first scan runs with the next time update, adding a universe with the name of an existing universe replaces it.
+    /// A `StrategyEvent::UniverseChanged` is sent each time the members change.
+    /// The members are unsubscribed when they drop out of the universe, so do not subscribe to a candidate subscription separately.
+    pub async fn add_universe(&self, selection: UniverseSelection) {
+        self.universe_selector.add_universe(selection, self.time_utc()).await;
+    }
+
+    /// Removes the universe and unsubscribes its members, returns false if there is no universe with the name.
+    pub async fn remove_universe(&self, name: &str) -> bool {
+        self.universe_selector.remove_universe(name).await
+    }
+
+    /// The members of the universe after the last scan, highest score first.
+    pub async fn universe(&self, name: &str) -> Vec<DataSubscription> {
+        self.universe_selector.members(name).await
+    }
+
     /// Unsubscribes from a subscription.
     pub async fn unsubscribe(&self,subscription: DataSubscription) {
         self.subscription_handler
@@ -995,6 +1908,11 @@ impl FundForgeStrategy {
         self.subscription_handler.open_candle(subscription)
     }
 
+    /// Returns currently open `Footprint` for the subscription
+    pub fn open_footprint(&self, subscription: &DataSubscription) -> Option<Footprint> {
+        self.subscription_handler.open_footprint(subscription)
+    }
+
     /// Returns `Candle` at the specified index, where 0 is current closed `Candle` and 1 is last closed and 10 closed 10 candles ago (11th).
     pub fn candle_index(&self, subscription: &DataSubscription, index: usize) -> Option<Candle> {
         self.subscription_handler.candle_index(subscription, index)
@@ -1005,6 +1923,11 @@ impl FundForgeStrategy {
         self.subscription_handler.bar_index(subscription, index)
     }
 
+    /// Returns `Footprint` at the specified index, where 0 is current closed `Footprint` and 1 is last closed and 10 closed 10 `Footprint`s ago (11th).
+    pub fn footprint_index(&self, subscription: &DataSubscription, index: usize) -> Option<Footprint> {
+        self.subscription_handler.footprint_index(subscription, index)
+    }
+
     /// Returns `Tick` at the specified index, where 0 is current `Tick` and 1 is 2nd last `Tick` and 10 is 10 `Ticks`s ago (11th).
     pub fn tick_index(&self, subscription: &DataSubscription, index: usize) -> Option<Tick> {
         self.subscription_handler.tick_index(subscription, index)
@@ -1015,6 +1938,44 @@ impl FundForgeStrategy {
         self.subscription_handler.quote_index(subscription, index)
     }
 
+    /// The closed bars of the subscriptions which share a close time, newest first, up to `count` rows, so pairs and basket strategies can compare synchronized bars.
+    /// The subscriptions must be candle, quote bar or footprint subscriptions, the rows come from the retained history of each subscription,
+    /// so a close time missing from any subscription, eg. a bar not yet received or no trades in the period, is skipped.
+    /// # Example
+    /// ```rust,ignore
+    /// for row in strategy.aligned_bars(&[es_subscription.clone(), nq_subscription.clone()], 20) {
+    ///     if let (BaseDataEnum::Candle(es), BaseDataEnum::Candle(nq)) = (&row.bars[0], &row.bars[1]) {
+    ///         let ratio = es.close / nq.close;
+    ///     }
+    /// }
+    /// ```
+    pub fn aligned_bars(&self, subscriptions: &[DataSubscription], count: usize) -> Vec<AlignedBars> {
+        let histories: Vec<Vec<BaseDataEnum>> = subscriptions.iter()
+            .map(|subscription| self.subscription_handler.closed_bars(subscription))
+            .collect();
+        align_bars(&histories, count)
+    }
+
+    /// Returns true if a high impact economic release affecting the symbol is scheduled from `minutes_before` ahead of the strategy time until `minutes_after` behind it,
+    /// so FX and futures strategies can stand aside around the releases, the same in backtests and live.
+    /// The releases come from economic calendar subscriptions, subscribe to the currencies of the symbol with `DataSubscription::new_fundamental("USD".to_string(), DataVendor::ForexFactory)`,
+    /// see `symbol_currencies()` for the currencies of a symbol. Without a calendar subscription this is always false.
+    /// # Example
+    /// ```rust,ignore
+    /// if strategy.in_news_blackout(&"EUR-USD".to_string(), 30, 15) {
+    ///     continue;
+    /// }
+    /// ```
+    pub fn in_news_blackout(&self, symbol_name: &SymbolName, minutes_before: i64, minutes_after: i64) -> bool {
+        let currencies = symbol_currencies(symbol_name);
+        self.subscription_handler.economic_calendar().in_blackout(&currencies, self.time_utc(), ChronoDuration::minutes(minutes_before), ChronoDuration::minutes(minutes_after), EventImpact::High)
+    }
+
+    /// The scheduled economic releases received from the economic calendar subscriptions, see `in_news_blackout()`.
+    pub fn economic_calendar(&self) -> &EconomicCalendar {
+        self.subscription_handler.economic_calendar()
+    }
+
     /// Current Tz time, depends on the `StrategyMode`. \
     /// Backtest will return the last data point time, live will return the current time.
     pub fn time_local(&self) -> DateTime<Tz> {
@@ -1103,6 +2064,63 @@ impl FundForgeStrategy {
         range_history_data(start_date.to_utc(), end_date, subscription.clone(), self.mode, trading_hours).await
     }
 
+    /// Fetches the history of the subscription between the times on demand, for lookbacks longer than the history retained by the subscription, eg. yesterday's high.
+    /// The subscription does not need to be subscribed, resolutions the vendor supplies are returned as stored on the data server,
+    /// other resolutions are consolidated on the client from the best resolution the vendor offers.
+    ///
+    /// In backtests the data comes from the stored data up to the simulated time, live it is requested from the data server.
+    /// If `to` is after the strategy time it is changed to the strategy time to avoid lookahead bias.
+    /// Returns a BTreeMap of BaseDataEnum where data.time_closed_utc() is key and data is value.
+    /// Repeated requests for the same subscription within a minute share the download.
+    ///
+    /// Daily bars the vendor does not supply are consolidated with the trading hours of the symbol, so only futures can be consolidated to daily resolution.
+    pub async fn history_range(
+        &self,
+        subscription: &DataSubscription,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> BTreeMap<DateTime<Utc>, BaseDataEnum> {
+        let to = to.min(self.time_utc());
+        if from >= to {
+            return BTreeMap::new();
+        }
+        if ConsolidatorEnum::vendor_warmup_subscription(subscription).await.as_ref() == Some(subscription) {
+            let data = get_warmup_data(subscription, from, to).await.unwrap_or_default();
+            return data.into_values()
+                .flat_map(|slice| slice.iter().cloned().collect::<Vec<BaseDataEnum>>())
+                .filter(|base_data| base_data.time_closed_utc() >= from && base_data.time_closed_utc() <= to)
+                .map(|base_data| (base_data.time_closed_utc(), base_data))
+                .collect();
+        }
+        let trading_hours = match subscription.market_type {
+            MarketType::Futures(_) => get_futures_trading_hours(&extract_symbol_from_contract(&subscription.symbol.name)).cloned(),
+            _ => None,
+        };
+        let consolidator = match ConsolidatorEnum::create_consolidator(subscription.clone(), false, trading_hours).await {
+            Ok(consolidator) => consolidator,
+            Err(e) => {
+                tracing::warn!("History range: Unable to consolidate {}: {}", subscription, e);
+                return BTreeMap::new();
+            }
+        };
+        ConsolidatorEnum::consolidate_range(consolidator, from, to).await
+            .into_iter()
+            .map(|base_data| (base_data.time_closed_utc(), base_data))
+            .collect()
+    }
+
+    /// The trade statistics of the account, updated as each position closes so they can be queried at any time in live trading, see `StreamingStatistics`.
+    pub fn stats(&self, account: &Account) -> Option<StreamingStatistics> {
+        self.ledger_service.statistics(account)
+    }
+
+    /// Limits the closed positions kept in memory per symbol code for the account, the oldest positions are dropped once the limit is reached.
+    /// `strategy.stats()` still includes the dropped positions, but exports and the ledger statistics string only include the positions kept.
+    /// Use `None` to keep all closed positions, which is the default.
+    pub fn set_closed_position_limit(&self, account: &Account, limit: Option<usize>) {
+        self.ledger_service.set_closed_position_limit(account, limit);
+    }
+
     /// Prints a ledgers statistics
     pub fn print_ledger(&self, account: &Account) {
         self.ledger_service.print_ledger(account);
@@ -1125,6 +2143,86 @@ impl FundForgeStrategy {
         self.ledger_service.export_trades_to_csv(account, directory);
     }
 
+    /// Exports the trades of the account to a file in the directory in the `TradeExportFormat` and returns the file path.
+    /// Use `TradeExportFormat::NinjaTraderCsv` or `TradeExportFormat::QuantConnectCsv` to compare results with those platforms,
+    /// and `TradeExportFormat::RoundTrip` for files that are reloaded with `BacktestRun::from_trades_json()`.
+    pub fn export_trades(&self, account: &Account, directory: &str, format: TradeExportFormat) -> Result<String, FundForgeError> {
+        self.ledger_service.export_trades(account, directory, format)
+    }
+
+    /// Writes a self-contained html chart of the subscription between `from` and `to` to the directory and returns the file path, see `ChartExport`.
+    /// The chart shows the candles, the entries and exits of the account's closed trades for the symbol with their tags, and the named indicators.
+    /// Indicators in `overlays` are drawn over the candles, indicators in `panes` are drawn in a pane below the candles.
+    ///
+    /// The candles are consolidated from the stored data with `history_range()`, the indicator values come from the indicator history,
+    /// so only the values retained by each indicator are plotted, increase the `history_to_retain` of an indicator to plot the full range.
+    /// ```rust,ignore
+    /// strategy.export_chart_html(&account, &subscription, start, end, &["fast_ema".to_string()], &["atr".to_string()], "./charts").await?;
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub async fn export_chart_html(
+        &self,
+        account: &Account,
+        subscription: &DataSubscription,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        overlays: &[IndicatorName],
+        panes: &[IndicatorName],
+        directory: &str,
+    ) -> Result<String, FundForgeError> {
+        let history = self.history_range(subscription, from, to).await;
+        let trades = self.ledger_service.trade_exports(account).into_iter()
+            .filter(|trade| trade.symbol_code == subscription.symbol.name || extract_symbol_from_contract(&trade.symbol_code) == subscription.symbol.name)
+            .collect();
+        let mut export = ChartExport::new(subscription.clone(), history.values()).with_trades(trades);
+        let indicators = overlays.iter().map(|name| (name, true)).chain(panes.iter().map(|name| (name, false)));
+        for (name, overlay) in indicators {
+            match self.indicator_history(name) {
+                Some(history) => export = export.with_indicator(name, history.history(), overlay),
+                None => tracing::warn!("Chart export: no indicator named {}", name),
+            }
+        }
+        export.export_html(directory)
+    }
+
+    /// The depth of the strategy event queue and the events dropped by the `EventOverflowPolicy` set with `set_event_overflow_policy()`.
+    pub fn event_queue_metrics(&self) -> EventQueueMetrics {
+        self.event_queue.metrics()
+    }
+
+    /// The results loaded from the backtest cache when an identical backtest was found, see `enable_backtest_cache()`.
+    /// When this is `Some` the backtest was skipped and the ledgers are empty.
+    pub fn cached_backtest(&self) -> Option<CachedBacktest> {
+        cached_backtest()
+    }
+
+    /// Persists balance updates, position events, order events and completed trades for all accounts to a SQLite database at the path.
+    /// The database is created if it does not exist, an existing journal is appended to.
+    /// Order events are journaled as they are received from the broker in live trading.
+    pub fn enable_journal(&self, path: &str) -> Result<(), FundForgeError> {
+        self.ledger_service.enable_journal(path)
+    }
+
+    /// Query the completed trades recorded in the journal, see `strategy.enable_journal()`.
+    /// # Example
+    /// ```rust,ignore
+    /// let filter = TradeFilter { tag_prefix: Some("Breakout".to_string()), from: Some(Utc::now() - Duration::days(7)), ..Default::default() };
+    /// let trades = strategy.query_trades(&filter)?;
+    /// ```
+    pub fn query_trades(&self, filter: &TradeFilter) -> Result<Vec<TradeExport>, FundForgeError> {
+        self.ledger_service.query_trades(filter)
+    }
+
+    /// Rebuilds the closed positions, trades and open positions of the account from a broker statement export, see `StatementFormat` for the supported files.
+    /// Call this after `initialize()` and before placing orders, so the ledger statistics include the trading done before the strategy started.
+    /// Times in the statement without an offset are read in the strategy time zone.
+    pub async fn import_statement(&self, account: &Account, path: &str, format: StatementFormat) -> Result<StatementImport, FundForgeError> {
+        let fills = parse_statement(Path::new(path), format, &account.account_id, &self.time_zone)?;
+        let summary = self.ledger_service.import_statement(account, fills).await?;
+        tracing::info!("Statement Import: {}: {} fills, {} closed positions, {} open positions, booked pnl {}", account, summary.fills, summary.closed_positions, summary.open_positions, summary.booked_pnl);
+        Ok(summary)
+    }
+
     /// Save positions to a json file in the directory
     /// Useful for machine learning etc.
     pub fn save_positions_to_file(&self, account: &Account, file_path: &str) {
@@ -1143,6 +2241,41 @@ impl FundForgeStrategy {
         positions
     }
 
+    /// The completed trades of the closed positions on the account, oldest exit first.
+    /// Positions which scaled out have a trade for each exit, positions still open are not included.
+    /// # Example
+    /// ```rust,ignore
+    /// let losing_streak = strategy.closed_trades(&account).iter().rev().take_while(|trade| trade.pnl < dec!(0)).count();
+    /// if losing_streak >= 3 {
+    ///     // stop trading for the day
+    /// }
+    /// ```
+    pub fn closed_trades(&self, account: &Account) -> Vec<ClosedTrade> {
+        self.ledger_service.closed_trades(account)
+    }
+
+    /// The statement of the account for the trades closed between `from` and `to`, the balance and open positions are the current values of the ledger.
+    pub fn account_statement(&self, account: &Account, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<AccountStatement> {
+        self.ledger_service.account_statement(account, from, to)
+    }
+
+    /// Writes the statement of each account for the trades closed between `from` and `to` to a html file in the folder and returns the file paths.
+    pub fn export_account_statements(&self, from: DateTime<Utc>, to: DateTime<Utc>, folder: &str) -> Result<Vec<String>, FundForgeError> {
+        let mut paths = vec![];
+        for account in &self.accounts {
+            if let Some(statement) = self.ledger_service.account_statement(account, from, to) {
+                paths.push(statement.export_html(folder)?);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// The closed positions on the account for the symbol name, oldest close first.
+    /// Futures positions on every contract of the symbol are included, check `position.symbol_code` for the contract.
+    pub fn position_history(&self, account: &Account, symbol_name: &SymbolName) -> Vec<Position> {
+        self.ledger_service.position_history(account, symbol_name)
+    }
+
     /// Get a clone of closed positions for the account
     pub fn get_positions(&self, account: &Account) -> DashMap<SymbolCode, Vec<Position>> {
         self.ledger_service.get_positions(account)
@@ -1183,7 +2316,54 @@ impl FundForgeStrategy {
         self.ledger_service.position_size(account, symbol_name)
     }
 
+    /// True if the account holds a separate position for each entry, see `set_hedging_account()`.
+    pub fn is_hedging(&self, account: &Account) -> bool {
+        self.ledger_service.is_hedging(account)
+    }
+
+    /// The open positions of the account for the symbol code oldest first, a netting account has one position at most.
+    pub fn open_positions(&self, account: &Account, symbol_code: &SymbolCode) -> Vec<Position> {
+        self.ledger_service.open_positions(account, symbol_code)
+    }
+
+    /// The open position with the id, use the `position_id` of the `PositionUpdateEvent::PositionOpened` event.
+    pub fn position(&self, account: &Account, position_id: &PositionId) -> Option<Position> {
+        self.ledger_service.position(account, position_id)
+    }
+
+    /// The open quantity of the long or short positions of the account for the symbol code.
+    pub fn side_quantity(&self, account: &Account, symbol_code: &SymbolCode, side: PositionSide) -> Volume {
+        self.ledger_service.side_quantity(account, symbol_code, side)
+    }
+
     pub fn booked_pnl_account(&self, account: &Account) -> Decimal {
         self.ledger_service.booked_pnl_account(account)
     }
+
+    /// The pnl and open quantity of the positions on the account opened by orders with a tag starting with the prefix, keyed by the full tag.
+    /// Useful for tracking the performance of each signal when a strategy runs multiple signal families on one account.
+    /// # Example
+    /// ```rust,ignore
+    /// for (tag, summary) in strategy.tag_summaries(&account, "momo:*") {
+    ///     println!("{}: pnl: {}, open: {:?}", tag, summary.total_pnl(), summary.open_quantity);
+    /// }
+    /// ```
+    pub fn tag_summaries(&self, account: &Account, tag_prefix: &str) -> BTreeMap<String, TagSummary> {
+        self.ledger_service.tag_summaries(account, tag_prefix)
+    }
+
+    /// A single summary of all the positions on the account opened by orders with a tag starting with the prefix.
+    pub fn tag_summary(&self, account: &Account, tag_prefix: &str) -> TagSummary {
+        self.ledger_service.tag_summary(account, tag_prefix)
+    }
+
+    /// The positions on the account grouped into campaigns, eg. all the entries of a pyramid, with the campaign pnl, size progression and drawdown.
+    pub fn campaigns(&self, account: &Account, grouping: &CampaignGrouping) -> Vec<Campaign> {
+        self.ledger_service.campaigns(account, grouping)
+    }
+
+    /// Exports the campaigns of the account to a csv file in the directory, see `campaigns()`.
+    pub fn export_campaigns_to_csv(&self, account: &Account, directory: &str, grouping: &CampaignGrouping) {
+        self.ledger_service.export_campaigns_to_csv(account, directory, grouping);
+    }
 }
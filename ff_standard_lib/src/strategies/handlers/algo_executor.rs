@@ -0,0 +1,295 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::Sender;
+use uuid::Uuid;
+use crate::messages::data_server_messaging::DataServerRequest;
+use crate::standardized_types::enums::StrategyMode;
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::orders::{Order, OrderId, OrderRequest, OrderType, OrderUpdateEvent};
+use crate::strategies::client_features::connection_types::ConnectionType;
+use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::execution_algos::{AlgoEvent, AlgoId, ParentOrder};
+use crate::strategies::handlers::market_handler::backtest_matching_engine::BackTestEngineMessage;
+use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
+use crate::strategies::strategy_events::StrategyEvent;
+
+lazy_static! {
+    /// The fills of the child orders sent by the algos, updated from the order events with `update_child_order()`.
+    static ref CHILD_ORDERS: DashMap<OrderId, ChildOrder> = DashMap::new();
+}
+
+#[derive(Default)]
+struct ChildOrder {
+    filled: Volume,
+    fill_value: Decimal,
+    /// The order filled, was cancelled or was rejected.
+    done: bool,
+}
+
+/// Adds the fill of a child order, the matching engine and the live order handler call this with each order event.
+pub(crate) fn update_child_order(event: &OrderUpdateEvent) {
+    let mut child = match CHILD_ORDERS.get_mut(event.order_id()) {
+        Some(child) => child,
+        None => return,
+    };
+    match event {
+        OrderUpdateEvent::OrderFilled { price, quantity, .. } => {
+            child.filled += quantity;
+            child.fill_value += price * quantity;
+            child.done = true;
+        }
+        OrderUpdateEvent::OrderPartiallyFilled { price, quantity, .. } => {
+            child.filled += quantity;
+            child.fill_value += price * quantity;
+        }
+        OrderUpdateEvent::OrderCancelled { .. } | OrderUpdateEvent::OrderRejected { .. } => child.done = true,
+        _ => {}
+    }
+}
+
+struct WorkingAlgo {
+    algo_id: AlgoId,
+    parent: ParentOrder,
+    start: DateTime<Utc>,
+    market_volume_at_start: Volume,
+    sent: Volume,
+    children: Vec<OrderId>,
+    filled: Volume,
+    fill_value: Decimal,
+    finished: bool,
+}
+
+impl WorkingAlgo {
+    fn average_price(&self) -> Option<Price> {
+        match self.filled > dec!(0) {
+            true => Some(self.fill_value / self.filled),
+            false => None,
+        }
+    }
+
+    /// Updates the fills from the child orders, returns true if the filled quantity changed.
+    fn update_fills(&mut self) -> bool {
+        let (mut filled, mut fill_value) = (dec!(0), dec!(0));
+        for order_id in &self.children {
+            if let Some(child) = CHILD_ORDERS.get(order_id) {
+                filled += child.filled;
+                fill_value += child.fill_value;
+            }
+        }
+        let changed = filled != self.filled;
+        self.filled = filled;
+        self.fill_value = fill_value;
+        changed
+    }
+
+    fn children_done(&self) -> bool {
+        self.children.iter().all(|order_id| CHILD_ORDERS.get(order_id).is_none_or(|child| child.done))
+    }
+
+    /// Stops tracking the child orders once the algo is finished.
+    fn finish(&mut self) {
+        self.finished = true;
+        for order_id in &self.children {
+            CHILD_ORDERS.remove(order_id);
+        }
+    }
+}
+
+/// Works the parent orders submitted with `strategy.submit_algo()`, sending the child orders as the strategy time reaches each slice, see `ParentOrder`.
+/// The executor is updated with the strategy time by the `TimedEventHandler`, so backtests slice on the historical time.
+pub(crate) struct AlgoExecutor {
+    mode: StrategyMode,
+    open_order_cache: Arc<DashMap<OrderId, Order>>,
+    historical_message_sender: Option<Sender<BackTestEngineMessage>>,
+    strategy_event_sender: Sender<StrategyEvent>,
+    market_price_service: Arc<MarketPriceService>,
+    dry_run: Arc<AtomicBool>,
+    algos: DashMap<AlgoId, Arc<Mutex<WorkingAlgo>>>,
+}
+
+impl AlgoExecutor {
+    pub fn new(
+        mode: StrategyMode,
+        open_order_cache: Arc<DashMap<OrderId, Order>>,
+        historical_message_sender: Option<Sender<BackTestEngineMessage>>,
+        strategy_event_sender: Sender<StrategyEvent>,
+        market_price_service: Arc<MarketPriceService>,
+        dry_run: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            mode,
+            open_order_cache,
+            historical_message_sender,
+            strategy_event_sender,
+            market_price_service,
+            dry_run,
+            algos: DashMap::new(),
+        }
+    }
+
+    /// Starts the algo, the first child order is sent with the next time update.
+    pub fn submit(&self, algo_id: AlgoId, parent: ParentOrder, time: DateTime<Utc>) {
+        let market_volume_at_start = self.market_price_service.traded_volume(&parent.symbol_name).unwrap_or_default();
+        let algo = WorkingAlgo {
+            algo_id: algo_id.clone(),
+            parent,
+            start: time,
+            market_volume_at_start,
+            sent: dec!(0),
+            children: vec![],
+            filled: dec!(0),
+            fill_value: dec!(0),
+            finished: false,
+        };
+        self.algos.insert(algo_id, Arc::new(Mutex::new(algo)));
+    }
+
+    /// Cancels the working child orders of the algo, returns false if there is no working algo with the id.
+    pub async fn cancel(&self, algo_id: &AlgoId, time: DateTime<Utc>) -> bool {
+        let algo = match self.algos.remove(algo_id) {
+            Some((_, algo)) => algo,
+            None => return false,
+        };
+        let mut algo = algo.lock().await;
+        if algo.finished {
+            return false;
+        }
+        algo.update_fills();
+        let working: Vec<OrderId> = algo.children.iter()
+            .filter(|order_id| CHILD_ORDERS.get(*order_id).is_some_and(|child| !child.done))
+            .cloned()
+            .collect();
+        for order_id in working {
+            self.send_order_request(OrderRequest::Cancel { account: algo.parent.account.clone(), order_id }).await;
+        }
+        let event = AlgoEvent::Cancelled {
+            account: algo.parent.account.clone(),
+            algo_id: algo.algo_id.clone(),
+            symbol_name: algo.parent.symbol_name.clone(),
+            filled: algo.filled,
+            remaining: algo.parent.quantity - algo.filled,
+            average_price: algo.average_price(),
+            time: time.to_string(),
+        };
+        algo.finish();
+        self.send_event(event).await;
+        true
+    }
+
+    pub async fn update_time(&self, time: DateTime<Utc>) {
+        if self.algos.is_empty() {
+            return;
+        }
+        let algos: Vec<Arc<Mutex<WorkingAlgo>>> = self.algos.iter().map(|entry| entry.value().clone()).collect();
+        for algo in algos {
+            let mut algo = algo.lock().await;
+            if algo.finished {
+                continue;
+            }
+            if let Some(event) = self.update_algo(&mut algo, time).await {
+                if algo.finished {
+                    self.algos.remove(&algo.algo_id);
+                }
+                self.send_event(event).await;
+            }
+        }
+    }
+
+    /// Sends the child order due by the time, returns the event of the algo if the fills changed or the algo finished.
+    async fn update_algo(&self, algo: &mut WorkingAlgo, time: DateTime<Utc>) -> Option<AlgoEvent> {
+        let fills_changed = algo.update_fills();
+        let remaining = algo.parent.quantity - algo.filled;
+        if remaining <= dec!(0) {
+            algo.finish();
+            return Some(AlgoEvent::Completed {
+                account: algo.parent.account.clone(),
+                algo_id: algo.algo_id.clone(),
+                symbol_name: algo.parent.symbol_name.clone(),
+                filled: algo.filled,
+                average_price: algo.average_price(),
+                time: time.to_string(),
+            });
+        }
+        if time >= algo.start + algo.parent.duration {
+            // The window is over, the algo expires once the last child order is done.
+            if !algo.children_done() {
+                return None;
+            }
+            algo.finish();
+            return Some(AlgoEvent::Expired {
+                account: algo.parent.account.clone(),
+                algo_id: algo.algo_id.clone(),
+                symbol_name: algo.parent.symbol_name.clone(),
+                filled: algo.filled,
+                remaining,
+                average_price: algo.average_price(),
+                time: time.to_string(),
+            });
+        }
+
+        let mut due = algo.parent.scheduled_quantity(algo.start, time);
+        if let Some(market_volume) = self.market_price_service.traded_volume(&algo.parent.symbol_name) {
+            if let Some(capped) = algo.parent.capped_quantity(market_volume - algo.market_volume_at_start) {
+                due = due.min(capped);
+            }
+        }
+        let quantity = due - algo.sent;
+        if quantity > dec!(0) {
+            let order_id = match self.dry_run.load(Ordering::SeqCst) {
+                true => format!("DRY-{}", Uuid::new_v4()),
+                false => Uuid::new_v4().to_string(),
+            };
+            let parent = &algo.parent;
+            let order = Order::market_order(parent.symbol_name.clone(), parent.symbol_code.clone(), &parent.account, quantity, parent.side, parent.tag.clone(), order_id.clone(), time, None);
+            CHILD_ORDERS.insert(order_id.clone(), ChildOrder::default());
+            algo.children.push(order_id);
+            algo.sent += quantity;
+            let order_request = OrderRequest::Create { account: parent.account.clone(), order, order_type: OrderType::Market };
+            self.send_order_request(order_request).await;
+        }
+
+        match fills_changed {
+            true => Some(AlgoEvent::Progress {
+                account: algo.parent.account.clone(),
+                algo_id: algo.algo_id.clone(),
+                symbol_name: algo.parent.symbol_name.clone(),
+                filled: algo.filled,
+                remaining,
+                average_price: algo.average_price(),
+                time: time.to_string(),
+            }),
+            false => None,
+        }
+    }
+
+    async fn send_event(&self, event: AlgoEvent) {
+        if let Err(e) = self.strategy_event_sender.send(StrategyEvent::AlgoEvents(event)).await {
+            tracing::error!("Algo Executor: Failed to send event: {}", e);
+        }
+    }
+
+    async fn send_order_request(&self, order_request: OrderRequest) {
+        if self.dry_run.load(Ordering::SeqCst) {
+            tracing::info!("Dry Run: Algo Executor: {:?}", order_request);
+            return;
+        }
+        if self.mode == StrategyMode::Live {
+            if let OrderRequest::Create { order, .. } = &order_request {
+                self.open_order_cache.insert(order.id.clone(), order.clone());
+            }
+            let connection_type = ConnectionType::Broker(order_request.brokerage());
+            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
+            send_request(request).await;
+        } else if let Some(historical_message_sender) = &self.historical_message_sender {
+            if let Err(e) = historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await {
+                tracing::error!("Algo Executor: Failed to send order request: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use lazy_static::lazy_static;
+use tokio::sync::{broadcast, mpsc};
+use crate::messages::chart_updates::{ChartName, ChartUpdate};
+use crate::messages::data_server_messaging::DataServerRequest;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::time_slices::TimeSlice;
+use crate::strategies::client_features::connection_types::ConnectionType;
+use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::indicators::indicator_values::IndicatorValues;
+
+/// The max number of updates sent to the server in a single request.
+const MAX_BATCH: usize = 500;
+
+/// Updates waiting to be sent, when the buffer is full new updates are dropped rather than slowing down the strategy.
+const BUFFER_SIZE: usize = 100_000;
+
+lazy_static! {
+    /// Chart updates received from the data server for the charts this strategy subscribed to.
+    static ref RECEIVED_CHART_UPDATES: broadcast::Sender<(ChartName, Vec<ChartUpdate>)> = broadcast::channel(1000).0;
+}
+
+/// Batches the closed bars and indicator values of each time slice and republishes them through the data server under the chart name.
+pub(crate) struct ChartPublisher {
+    sender: mpsc::Sender<ChartUpdate>,
+}
+
+impl ChartPublisher {
+    pub fn new(chart_name: ChartName) -> Self {
+        let (sender, mut receiver) = mpsc::channel(BUFFER_SIZE);
+        tokio::spawn(async move {
+            let mut batch = Vec::with_capacity(MAX_BATCH);
+            while receiver.recv_many(&mut batch, MAX_BATCH).await > 0 {
+                let request = DataServerRequest::PublishChartUpdates { chart_name: chart_name.clone(), updates: std::mem::take(&mut batch) };
+                send_request(StrategyRequest::OneWay(ConnectionType::Default, request)).await;
+            }
+        });
+        Self {
+            sender
+        }
+    }
+
+    /// Sends a single update after the updates already waiting, returns false if the buffer is full.
+    pub fn send(&self, update: ChartUpdate) -> bool {
+        match self.sender.try_send(update) {
+            Ok(_) => true,
+            Err(_) => {
+                tracing::warn!("Chart Publisher: Buffer full, dropping chart updates");
+                false
+            }
+        }
+    }
+
+    pub fn publish(&self, time_slice: &TimeSlice, indicator_values: &[IndicatorValues]) {
+        let bars = time_slice.iter()
+            .filter(|data| matches!(data, BaseDataEnum::Candle(_) | BaseDataEnum::QuoteBar(_)) && data.is_closed())
+            .map(|data| ChartUpdate::Bar(data.clone()));
+        let indicators = indicator_values.iter().map(|values| ChartUpdate::Indicator(values.clone()));
+        for update in bars.chain(indicators) {
+            if self.sender.try_send(update).is_err() {
+                tracing::warn!("Chart Publisher: Buffer full, dropping chart updates");
+                return;
+            }
+        }
+    }
+}
+
+/// Forwards chart updates received from the data server to the chart subscribers.
+pub(crate) fn receive_chart_updates(chart_name: ChartName, updates: Vec<ChartUpdate>) {
+    let _ = RECEIVED_CHART_UPDATES.send((chart_name, updates));
+}
+
+/// Subscribes to the chart updates published by any strategy connected to the default data server under the chart name.
+pub(crate) async fn subscribe_chart(chart_name: ChartName) -> broadcast::Receiver<(ChartName, Vec<ChartUpdate>)> {
+    let receiver = RECEIVED_CHART_UPDATES.subscribe();
+    send_request(StrategyRequest::OneWay(ConnectionType::Default, DataServerRequest::SubscribeChart { chart_name })).await;
+    receiver
+}
+
+pub(crate) async fn unsubscribe_chart(chart_name: ChartName) {
+    send_request(StrategyRequest::OneWay(ConnectionType::Default, DataServerRequest::UnsubscribeChart { chart_name })).await;
+}
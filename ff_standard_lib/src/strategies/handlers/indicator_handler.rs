@@ -1,28 +1,41 @@
 use std::collections::BTreeMap;
-use std::ops::Deref;
-use std::sync::Arc;
+use std::cmp::Reverse;
+use std::sync::{Arc, OnceLock};
 use crate::standardized_types::enums::StrategyMode;
 use crate::standardized_types::rolling_window::RollingWindow;
 use crate::standardized_types::subscriptions::DataSubscription;
 use crate::standardized_types::time_slices::TimeSlice;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use crate::strategies::consolidators::consolidator_enum::ConsolidatorEnum;
+use crate::strategies::consolidators::consolidator_enum::{is_valid_warmup_source, ConsolidatorEnum};
 use crate::strategies::indicators::indicator_events::IndicatorEvents;
 use crate::strategies::indicators::indicators_trait::{IndicatorName, Indicators};
 use crate::strategies::indicators::indicator_values::IndicatorValues;
+use crate::strategies::indicators::indicator_warmup::IndicatorWarmup;
+use crate::strategies::indicators::derived_series::DerivedSeries;
+use crate::messages::data_server_messaging::FundForgeError;
 use crate::strategies::client_features::server_connections::is_warmup_complete;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
-use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::market_hours::TradingHours;
 use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
+use crate::messages::chart_updates::{ChartName, ChartUpdate};
+use crate::strategies::handlers::chart_publisher::ChartPublisher;
 
 pub struct IndicatorHandler {
     indicators: Arc<DashMap<DataSubscription, DashMap<IndicatorName, Box<dyn Indicators>>>>,
     strategy_mode: StrategyMode,
     subscription_map: DashMap<IndicatorName, DataSubscription>, //used to quickly find the subscription of an indicator by name.
     subscription_handler: Arc<SubscriptionHandler>,
+    chart_publisher: OnceLock<ChartPublisher>,
+    derived_indicators: DashMap<IndicatorName, DerivedIndicator>,
+    dependents: DashMap<IndicatorName, Vec<IndicatorName>>, //the derived indicators of each source indicator, in the order they were added.
+}
+
+/// An indicator that is updated with the output of another indicator instead of a data subscription.
+struct DerivedIndicator {
+    series: DerivedSeries,
+    indicator: Box<dyn Indicators>,
 }
 
 impl IndicatorHandler {
@@ -32,11 +45,28 @@ impl IndicatorHandler {
             strategy_mode,
             subscription_map: Default::default(),
             subscription_handler,
+            chart_publisher: OnceLock::new(),
+            derived_indicators: Default::default(),
+            dependents: Default::default(),
         };
         handler
     }
 
-    pub async fn add_indicator(&self, indicator: Box<dyn Indicators>, time: DateTime<Utc>, market_hours: Option<TradingHours>) -> IndicatorEvents {
+    /// Republishes the closed bars and indicator values of each time slice under the chart name, returns false if already publishing.
+    pub fn publish_chart_updates(&self, chart_name: ChartName) -> bool {
+        self.chart_publisher.set(ChartPublisher::new(chart_name)).is_ok()
+    }
+
+    /// Sends the update with the chart updates, returns false if the strategy is not publishing chart updates or the buffer is full.
+    pub fn send_chart_update(&self, update: ChartUpdate) -> bool {
+        match self.chart_publisher.get() {
+            Some(chart_publisher) => chart_publisher.send(update),
+            None => false,
+        }
+    }
+
+    /// Indicators are warmed up when they are added after the strategy warm up is complete, or immediately if a warmup override is supplied.
+    pub async fn add_indicator(&self, indicator: Box<dyn Indicators>, time: DateTime<Utc>, market_hours: Option<TradingHours>, warmup_override: Option<IndicatorWarmup>) -> IndicatorEvents {
         let subscription = indicator.subscription().clone();
 
         if !self.indicators.contains_key(&subscription) {
@@ -45,26 +75,115 @@ impl IndicatorHandler {
 
         let name = indicator.name().clone();
 
-        let indicator = match is_warmup_complete() {
-            true => warmup(time, self.strategy_mode.clone(), indicator, self.subscription_handler.clone(), market_hours).await,
+        let indicator = match warmup_override.is_some() || is_warmup_complete() {
+            true => warmup(time, self.strategy_mode.clone(), indicator, self.subscription_handler.clone(), market_hours, warmup_override.unwrap_or_default()).await,
             false => indicator,
         };
 
-        let event = if !self.subscription_map.contains_key(&name) {
+        let event = if !self.subscription_map.contains_key(&name) && !self.derived_indicators.contains_key(&name) {
             IndicatorEvents::IndicatorAdded(name.clone())
         } else {
            IndicatorEvents::Replaced(name.clone())
         };
 
+        self.remove_derived(&name);
         if let Some(map) = self.indicators.get(&subscription) {
             map.insert(indicator.name(), indicator);
         }
         self.subscription_map.insert(name.clone(), subscription.clone());
+        self.warmup_dependents(&name);
 
         event
     }
 
+    /// Adds an indicator that is updated with a plot of the source indicator's output, see `DerivedSeries`.
+    /// The derived indicator is warmed up from the history of the source, then the indicators derived from it are warmed up again in dependency order.
+    /// Returns an error if the source indicator is not subscribed or the series would make the indicator depend on itself.
+    pub fn add_derived_indicator(&self, mut indicator: Box<dyn Indicators>, series: DerivedSeries) -> Result<IndicatorEvents, FundForgeError> {
+        let name = indicator.name();
+        if !self.subscription_map.contains_key(&series.source) && !self.derived_indicators.contains_key(&series.source) {
+            return Err(FundForgeError::ClientSideErrorDebug(format!("Source indicator {} is not subscribed", series.source)));
+        }
+        // Walk up the sources of the series, an indicator can not be derived from its own output.
+        let mut source = Some(series.source.clone());
+        while let Some(source_name) = source {
+            if source_name == name {
+                return Err(FundForgeError::ClientSideErrorDebug(format!("Indicator {} can not be derived from its own output", name)));
+            }
+            source = self.derived_indicators.get(&source_name).map(|derived| derived.series.source.clone());
+        }
+
+        let event = if !self.subscription_map.contains_key(&name) && !self.derived_indicators.contains_key(&name) {
+            IndicatorEvents::IndicatorAdded(name.clone())
+        } else {
+            IndicatorEvents::Replaced(name.clone())
+        };
+
+        if let Some(subscription) = self.subscription_map.remove(&name).map(|(_, subscription)| subscription) {
+            if let Some(map) = self.indicators.get(&subscription) {
+                map.remove(&name);
+            }
+        }
+        self.remove_derived(&name);
+
+        if let Some(history) = self.history(&series.source) {
+            feed_history(&mut indicator, &series, &history);
+        }
+        self.dependents.entry(series.source.clone()).or_default().push(name.clone());
+        self.derived_indicators.insert(name.clone(), DerivedIndicator { series, indicator });
+        self.warmup_dependents(&name);
+
+        Ok(event)
+    }
+
+    /// Removes the derived indicator from the map and from the dependents of its source, the indicators derived from it are kept.
+    fn remove_derived(&self, name: &IndicatorName) -> bool {
+        match self.derived_indicators.remove(name) {
+            Some((_, derived)) => {
+                if let Some(mut dependents) = self.dependents.get_mut(&derived.series.source) {
+                    dependents.retain(|dependent| dependent != name);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the indicators derived from the source and everything derived from them.
+    fn remove_dependents(&self, source: &IndicatorName) {
+        if let Some((_, dependents)) = self.dependents.remove(source) {
+            for dependent in dependents {
+                self.derived_indicators.remove(&dependent);
+                self.remove_dependents(&dependent);
+            }
+        }
+    }
+
+    /// Resets the indicators derived from the source and warms them up again from its history, parents always before their dependents.
+    fn warmup_dependents(&self, source: &IndicatorName) {
+        let dependents = match self.dependents.get(source) {
+            Some(dependents) => dependents.clone(),
+            None => return,
+        };
+        let history = self.history(source);
+        for dependent in dependents {
+            if let Some(mut derived) = self.derived_indicators.get_mut(&dependent) {
+                let derived = derived.value_mut();
+                derived.indicator.reset();
+                if let Some(history) = &history {
+                    feed_history(&mut derived.indicator, &derived.series, history);
+                }
+            }
+            self.warmup_dependents(&dependent);
+        }
+    }
+
+    /// Removing an indicator also removes the indicators derived from its output.
     pub async fn remove_indicator(&self, indicator_name: &IndicatorName) -> Option<IndicatorEvents>  {
+        self.remove_dependents(indicator_name);
+        if self.remove_derived(indicator_name) {
+            return Some(IndicatorEvents::IndicatorRemoved(indicator_name.clone()))
+        }
         if let Some(subscription) = self.subscription_map.get(indicator_name) {
             if let Some(map) = self.indicators.get(&subscription.value()) {
                 map.remove(indicator_name);
@@ -78,10 +197,13 @@ impl IndicatorHandler {
 
     pub async fn indicators_unsubscribe_subscription(&self, subscription: &DataSubscription) {
         self.indicators.remove(subscription);
-        for sub in self.subscription_map.iter() {
-            if sub.value() == subscription {
-                self.subscription_map.remove(sub.key());
-            }
+        let names: Vec<IndicatorName> = self.subscription_map.iter()
+            .filter(|sub| sub.value() == subscription)
+            .map(|sub| sub.key().clone())
+            .collect();
+        for name in names {
+            self.subscription_map.remove(&name);
+            self.remove_dependents(&name);
         }
     }
 
@@ -102,14 +224,66 @@ impl IndicatorHandler {
             }
         }
 
-        if !results.is_empty() {
-            let results_vec: Vec<IndicatorValues> = results.into_values().flatten().collect();
+        // Derived indicators are updated after their source, the queue follows the dependency graph from the subscription indicators down.
+        let mut queue: Vec<(IndicatorName, Vec<IndicatorValues>)> = results.iter().map(|(name, values)| (name.clone(), values.clone())).collect();
+        while let Some((source, source_values)) = queue.pop() {
+            let dependents = match self.dependents.get(&source) {
+                Some(dependents) => dependents.clone(),
+                None => continue,
+            };
+            for dependent in dependents {
+                if let Some(mut derived) = self.derived_indicators.get_mut(&dependent) {
+                    let derived = derived.value_mut();
+                    let mut derived_values = vec![];
+                    for values in &source_values {
+                        if let Some(base_data) = derived.series.to_base_data(values) {
+                            if let Some(indicator_data) = derived.indicator.update_base_data(&base_data) {
+                                derived_values.extend(indicator_data);
+                            }
+                        }
+                    }
+                    if !derived_values.is_empty() {
+                        results.entry(dependent.clone())
+                            .or_default()
+                            .extend(derived_values.clone());
+                        queue.push((dependent, derived_values));
+                    }
+                }
+            }
+        }
+
+        let results_vec: Vec<IndicatorValues> = results.into_values().flatten().collect();
+        if let Some(chart_publisher) = self.chart_publisher.get() {
+            chart_publisher.publish(time_slice, &results_vec);
+        }
+        if !results_vec.is_empty() {
             return Some(IndicatorEvents::IndicatorTimeSlice(results_vec))
         }
         None
     }
 
+    /// The history of every indicator, keyed by indicator name.
+    pub fn histories(&self) -> Vec<(IndicatorName, Vec<IndicatorValues>)> {
+        let mut histories = vec![];
+        for map in self.indicators.iter() {
+            for indicator in map.value().iter() {
+                histories.push((indicator.key().clone(), indicator.history().history()));
+            }
+        }
+        for derived in self.derived_indicators.iter() {
+            histories.push((derived.key().clone(), derived.indicator.history().history()));
+        }
+        histories
+    }
+
     pub fn history(&self, name: &IndicatorName) -> Option<RollingWindow<IndicatorValues>> {
+        if let Some(derived) = self.derived_indicators.get(name) {
+            let history = derived.indicator.history();
+            return match history.is_empty() {
+                true => None,
+                false => Some(history),
+            };
+        }
         let subscription = match self.subscription_map.get(name) {
             Some(sub) => sub.clone(),
             None => return None,
@@ -127,6 +301,9 @@ impl IndicatorHandler {
     }
 
     pub fn current(&self, name: &IndicatorName) -> Option<IndicatorValues> {
+        if let Some(derived) = self.derived_indicators.get(name) {
+            return derived.indicator.current();
+        }
         let subscription = match self.subscription_map.get(name) {
             Some(sub) => sub.clone(),
             None => return None,
@@ -142,6 +319,9 @@ impl IndicatorHandler {
     }
 
     pub fn index(&self, name: &IndicatorName, index: usize) -> Option<IndicatorValues> {
+        if let Some(derived) = self.derived_indicators.get(name) {
+            return derived.indicator.index(index);
+        }
         let subscription = match self.subscription_map.get(name) {
             Some(sub) => sub.clone(),
             None => return None,
@@ -157,7 +337,17 @@ impl IndicatorHandler {
     }
 }
 
+/// Updates the derived indicator with the source history, oldest values first.
+fn feed_history(indicator: &mut Box<dyn Indicators>, series: &DerivedSeries, history: &RollingWindow<IndicatorValues>) {
+    for values in history.history().iter().rev() {
+        if let Some(base_data) = series.to_base_data(values) {
+            let _ = indicator.update_base_data(&base_data);
+        }
+    }
+}
+
 /// This will warm up the indicator if possible.
+/// The history the strategy already retains is used first, the warmup data is only downloaded when the retained history is too short, see `retained_warmup()`.
 /// Indicators that use fundamental data will need to be managed manually.
 async fn warmup( //todo make async task version for live mode
     to_time: DateTime<Utc>,
@@ -165,62 +355,73 @@ async fn warmup( //todo make async task version for live mode
     mut indicator: Box<dyn Indicators>,
      subscription_handler: Arc<SubscriptionHandler>,
      market_hours: Option<TradingHours>,
+     warmup_override: IndicatorWarmup,
 ) -> Box<dyn Indicators> {
-   //1. Check if we have history for the indicator.subscription
-    let subscription =  indicator.subscription();
-    match subscription.base_data_type {
-        BaseDataType::Ticks => {
-            if let Some(history) = subscription_handler.tick_history(&subscription) {
-                if history.len() >= indicator.data_required_warmup() as usize {
-                    for data in history.history {
-                        let base_data = BaseDataEnum::Tick(data);
-                        indicator.update_base_data(&base_data);
-                    }
-                    return indicator
-                }
-            }
+    let data_required = warmup_override.length.unwrap_or_else(|| indicator.data_required_warmup());
+    // Without a length override all the retained history is used.
+    let history_limit = warmup_override.length.map_or(usize::MAX, |length| length as usize);
+    let subscription = indicator.subscription().clone();
+    if let Some(history) = retained_warmup(&subscription, warmup_override.source.as_ref(), data_required as usize, &subscription_handler, market_hours.clone()).await {
+        for data in &history[history.len().saturating_sub(history_limit)..] {
+            indicator.update_base_data(data);
         }
-        BaseDataType::Quotes => {
-            if let Some(history) = subscription_handler.quote_history(&subscription) {
-                if history.len() >= indicator.data_required_warmup() as usize {
-                    for data in history.history {
-                        let base_data = BaseDataEnum::Quote(data);
-                        indicator.update_base_data(&base_data);
-                    }
-                    return indicator
-                }
-            }
+        return indicator
+    }
+    let consolidator = match ConsolidatorEnum::create_consolidator(subscription.clone(), false, market_hours).await {
+        Ok(consolidator) => consolidator,
+        Err(e) => {
+            tracing::warn!("Indicator warmup: Unable to consolidate {}: {}", subscription, e);
+            return indicator
         }
-        BaseDataType::QuoteBars => {
-            if let Some(history) = subscription_handler.bar_history(&subscription) {
-                if history.len() >= indicator.data_required_warmup() as usize {
-                    for data in history.history {
-                        let base_data = BaseDataEnum::QuoteBar(data);
-                        indicator.update_base_data(&base_data);
-                    }
-                    return indicator
-                }
+    };
+    let (_, window) = ConsolidatorEnum::warmup_from_source(consolidator, warmup_override.source, to_time, (data_required + 1) as i32, strategy_mode).await;
+    for data in window.history.iter().rev() {
+        let _ = indicator.update_base_data(data);
+    }
+    indicator
+}
 
-            }
+/// The warmup data for the subscription from the history the strategy already retains, oldest first.
+/// The retained history of the subscription itself is used as is, the history of a lower resolution subscription is consolidated into the subscription.
+/// An explicit source is only used if the strategy retains it, otherwise every retained subscription which can be consolidated into the subscription is tried, the closest resolution first.
+/// Returns `None` if no retained history holds `data_required` bars, so the caller can download the warmup data instead.
+async fn retained_warmup(
+    subscription: &DataSubscription,
+    source: Option<&DataSubscription>,
+    data_required: usize,
+    subscription_handler: &SubscriptionHandler,
+    market_hours: Option<TradingHours>,
+) -> Option<Vec<BaseDataEnum>> {
+    let mut sources: Vec<DataSubscription> = match source {
+        Some(source) => vec![source.clone()],
+        None => subscription_handler.retained_subscriptions(),
+    };
+    sources.retain(|source| is_valid_warmup_source(source, subscription));
+    sources.sort_by_key(|source| (source != subscription, Reverse(source.resolution)));
+    for source in sources {
+        let history = match subscription_handler.retained_history(&source) {
+            Some(history) => history,
+            None => continue,
+        };
+        // Each source data point closes at most one bar, so a shorter history can not be enough.
+        if history.len() < data_required {
+            continue;
         }
-        BaseDataType::Candles => {
-            if let Some(history) = subscription_handler.candle_history(&subscription) {
-                if history.len() >= indicator.data_required_warmup() as usize {
-                    for data in history.history {
-                        let base_data = BaseDataEnum::Candle(data);
-                        indicator.update_base_data(&base_data);
-                    }
-                    return indicator
-                }
-            }
+        if &source == subscription {
+            return Some(history);
+        }
+        let mut consolidator = match ConsolidatorEnum::create_consolidator(subscription.clone(), false, market_hours.clone()).await {
+            Ok(consolidator) => consolidator,
+            Err(_) => continue,
+        };
+        // The first bar is skipped, the retained history may start part way through it.
+        let consolidated: Vec<BaseDataEnum> = history.iter()
+            .filter_map(|data| consolidator.update(data).ok().and_then(|consolidated_data| consolidated_data.closed_data))
+            .skip(1)
+            .collect();
+        if consolidated.len() >= data_required {
+            return Some(consolidated);
         }
-        _ => {}
-    }
-    let _ = subscription_handler.deref();
-    let consolidator = ConsolidatorEnum::create_consolidator(subscription.clone(), false, market_hours).await;
-    let (_, window) = ConsolidatorEnum::warmup(consolidator, to_time, (indicator.data_required_warmup() + 1) as i32, strategy_mode).await;
-    for data in window.history {
-        let _ = indicator.update_base_data(&data);
     }
-    indicator
+    None
 }
@@ -10,6 +10,7 @@ use crate::strategies::handlers::indicator_handler::IndicatorHandler;
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
 use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
 use crate::strategies::handlers::timed_events_handler::TimedEventHandler;
+use crate::strategies::historical_engine::next_feed_time;
 use crate::strategies::historical_time::update_backtest_time;
 use crate::strategies::ledgers::ledger_service::LedgerService;
 use crate::strategies::strategy_events::StrategyEvent;
@@ -32,7 +33,7 @@ pub(crate) async fn live_warm_up(
     market_price_service: Arc<MarketPriceService>
 ) {
     tokio::task::spawn(async move {
-        println!("Live Warmup: Warming up the strategy...");
+        tracing::info!("Live Warmup: Warming up the strategy...");
 
         // Get initial subscriptions
         let mut primary_subscriptions = loop {
@@ -40,7 +41,7 @@ pub(crate) async fn live_warm_up(
             if !subscriptions.is_empty() {
                 break subscriptions;
             }
-            println!("Live Warmup: Waiting for primary subscriptions...");
+            tracing::info!("Live Warmup: Waiting for primary subscriptions...");
             tokio::time::sleep(Duration::from_millis(200)).await;
         };
 
@@ -49,11 +50,11 @@ pub(crate) async fn live_warm_up(
         // Log subscriptions only in debug/development
         #[cfg(debug_assertions)] {
             for subscription in &primary_subscriptions {
-                println!("Live Warmup: Primary Subscription: {}", subscription);
+                tracing::info!("Live Warmup: Primary Subscription: {}", subscription);
             }
             let strategy_subscriptions = subscription_handler.strategy_subscriptions().await;
             for subscription in &strategy_subscriptions {
-                println!("Live Warmup: Strategy Subscription: {}", subscription);
+                tracing::info!("Live Warmup: Strategy Subscription: {}", subscription);
             }
         }
 
@@ -74,7 +75,7 @@ pub(crate) async fn live_warm_up(
             if last_time >= Utc::now() {
                 WARMUP_COMPLETE_BROADCASTER.send(last_time).unwrap();
                 if let Err(e) = strategy_event_sender.send(StrategyEvent::WarmUpComplete).await {
-                    eprintln!("Live Warmup: Failed to send event: {}", e);
+                    tracing::error!("Live Warmup: Failed to send event: {}", e);
                 }
                 break 'main_loop;
             }
@@ -82,7 +83,7 @@ pub(crate) async fn live_warm_up(
             let mut time_slices = match get_compressed_historical_data(primary_subscriptions.clone(), last_time, to_time).await {
                 Ok(time_slices) => {
                     if time_slices.is_empty() {
-                        println!("Live Warmup: No data period, weekend or holiday: skipping to next day");
+                        tracing::info!("Live Warmup: No data period, weekend or holiday: skipping to next day");
                         last_time = to_time + Duration::from_nanos(1);
                         continue 'main_loop
                     }
@@ -96,13 +97,13 @@ pub(crate) async fn live_warm_up(
 
             let mut time = last_time;
             'day_loop: while time <= to_time {
-                time += buffer_duration;
+                time = next_feed_time(time, buffer_duration, &time_slices, to_time);
 
                 // Early exit check
                 if time >= Utc::now() {
                     WARMUP_COMPLETE_BROADCASTER.send(time).unwrap();
                     if let Err(e) = strategy_event_sender.send(StrategyEvent::WarmUpComplete).await {
-                        eprintln!("Live Warmup: Failed to send event: {}", e);
+                        tracing::error!("Live Warmup: Failed to send event: {}", e);
                     }
                     break 'main_loop;
                 }
@@ -154,12 +155,12 @@ pub(crate) async fn live_warm_up(
                         // Update indicators and send events in parallel
                         if let Some(events) = indicator_handler.update_time_slice(&strategy_time_slice).await {
                             if let Err(e) = strategy_event_sender.send(StrategyEvent::IndicatorEvent(events)).await {
-                                eprintln!("Live Warmup: Failed to send indicator event: {}", e);
+                                tracing::error!("Live Warmup: Failed to send indicator event: {}", e);
                             }
                         }
 
                         if let Err(e) = strategy_event_sender.send(StrategyEvent::TimeSlice(strategy_time_slice)).await {
-                            eprintln!("Live Warmup: Failed to send time slice event: {}", e);
+                            tracing::error!("Live Warmup: Failed to send time slice event: {}", e);
                         }
                     }
                 }
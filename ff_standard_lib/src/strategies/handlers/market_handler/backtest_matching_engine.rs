@@ -6,13 +6,19 @@ use rust_decimal_macros::dec;
 use tokio::sync::mpsc::{Sender};
 use tokio::sync::oneshot;
 use crate::helpers::converters::{time_convert_utc_to_local};
+use crate::helpers::logging::log_order_event;
 use crate::standardized_types::broker_enum::Brokerage;
-use crate::standardized_types::enums::{OrderSide};
+use crate::standardized_types::enums::{OrderSide, PositionSide};
 use crate::product_maps::rithmic::maps::get_futures_trading_hours;
 use crate::standardized_types::new_types::{Price, Volume};
-use crate::standardized_types::orders::{Order, OrderId, OrderRequest, OrderState, OrderType, OrderUpdateEvent, OrderUpdateType, TimeInForce};
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::orders::{BasketPolicy, Order, OrderId, OrderRequest, OrderState, OrderType, OrderUpdateEvent, TimeInForce};
+use crate::strategies::handlers::market_handler::baskets::basket_events;
+use crate::strategies::handlers::algo_executor::update_child_order;
+use crate::strategies::handlers::market_handler::paper_simulation::{simulated_latency, simulated_rejection};
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
 use crate::strategies::historical_time::get_backtest_time;
+use crate::strategies::ledgers::hedging::PositionTarget;
 use crate::strategies::ledgers::ledger_service::{LedgerService};
 use crate::strategies::strategy_events::StrategyEvent;
 
@@ -36,124 +42,39 @@ pub(crate) async fn backtest_matching_engine(
             match backtest_message {
                 BackTestEngineMessage::OrderRequest(order_request) => {
                     //println!("{:?}", order_request);
+                    if let Some(latency) = simulated_latency() {
+                        tokio::time::sleep(latency).await;
+                    }
                     let time = get_backtest_time();
                     match order_request {
                         OrderRequest::Create { account, mut order, .. } => {
-                            let market_price = match market_price_service.get_market_price(order.side, &order.symbol_name, &order.symbol_code) {
-                                Some(price) => price,
-                                None => panic!("No market price found")
-                            };
-                            //eprintln!("Market Price: {}", market_price);
                             if order.quantity_open <= dec!(0) {
                                 open_order_cache.remove(&order.id);
-                                let fail_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderRejected {
-                                    account,
-                                    symbol_name: order.symbol_name,
-                                    symbol_code: order.symbol_code,
-                                    order_id: order.id.clone(), reason: String::from("Order Quantity Must Be Greater Than Zero"),
-                                    tag: order.tag,
-                                    time: time.to_string()
-                                });
-                                match strategy_event_sender.send(fail_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
-                                continue
                             }
-                            // check limit price
-                            if (order.order_type == OrderType::StopLimit || order.order_type == OrderType::Limit) &&
-                                ((order.side == OrderSide::Buy && order.limit_price.unwrap() > market_price)
-                                || (order.side == OrderSide::Sell && order.limit_price.unwrap() < market_price))
-                            {
-                                let side_string = match order.side {
-                                    OrderSide::Buy => "Below",
-                                    OrderSide::Sell => "Above"
-                                };
-                                let fail_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderRejected {
-                                    account,
-                                    symbol_name: order.symbol_name,
-                                    symbol_code: order.symbol_code,
-                                    order_id: order.id.clone(), reason: String::from(format!("{} Limit Price Must Be {} or Equal to Market Price", order.side, side_string)),
-                                    tag: order.tag,
-                                    time: time.to_string()
-                                });
-                                match strategy_event_sender.send(fail_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
-                                continue
-                            }
-                            //check trigger price
-                            if (order.order_type == OrderType::StopMarket || order.order_type == OrderType::StopLimit || order.order_type == OrderType::MarketIfTouched) &&
-                                ((order.side == OrderSide::Sell && order.trigger_price.unwrap() >= market_price) ||
-                                    (order.side == OrderSide::Buy && order.trigger_price.unwrap() <= market_price))
-                            {
-                                let side_string = match order.side {
-                                    OrderSide::Buy => "Above",  // Changed from "Below"
-                                    OrderSide::Sell => "Below"  // Changed from "Above"
-                                };
-                                let fail_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderRejected {
-                                    account,
-                                    symbol_name: order.symbol_name,
-                                    symbol_code: order.symbol_code,
-                                    order_id: order.id.clone(), reason: String::from(format!("{} Trigger Price Must Be {} Market Price", order.side, side_string)),
-                                    tag: order.tag,
-                                    time: time.to_string()
-                                });
-                                match strategy_event_sender.send(fail_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
-                                continue
+                            match order_rejection(&mut order, &ledger_service, &market_price_service).await {
+                                Some(reason) => send_event(&strategy_event_sender, rejected_event(order, reason, time)).await,
+                                None => accept_order(order, account, &open_order_cache, &strategy_event_sender, &market_price_service, time).await,
                             }
-
-                            if order.order_type == OrderType::ExitLong && !ledger_service.is_long(&account, &order.symbol_code) {
-                                let fail_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderRejected {
-                                    account,
-                                    symbol_name: order.symbol_name,
-                                    symbol_code: order.symbol_code,
-                                    order_id: order.id.clone(), reason: String::from("No Long Position To Exit"),
-                                    tag: order.tag,
-                                    time: time.to_string()
-                                });
-                                match strategy_event_sender.send(fail_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
+                            simulated_order_matching(&open_order_cache, &closed_order_cache, strategy_event_sender.clone(), &ledger_service, &market_price_service).await;
+                        }
+                        OrderRequest::Basket { account, basket_id, orders, policy } => {
+                            let mut accepted = vec![];
+                            let mut rejected = vec![];
+                            for mut order in orders {
+                                match order_rejection(&mut order, &ledger_service, &market_price_service).await {
+                                    Some(reason) => rejected.push((order, reason)),
+                                    None => accepted.push(order),
                                 }
-                                continue
                             }
-
-                            if order.order_type == OrderType::ExitShort && !ledger_service.is_short(&account, &order.symbol_code) {
-                                let fail_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderRejected {
-                                    account,
-                                    symbol_name: order.symbol_name,
-                                    symbol_code: order.symbol_code,
-                                    order_id: order.id.clone(), reason: String::from("No Short Position To Exit"),
-                                    tag: order.tag,
-                                    time: time.to_string()
-                                });
-                                match strategy_event_sender.send(fail_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
-                                continue
+                            if policy == BasketPolicy::AllOrNone && !rejected.is_empty() {
+                                let reason = format!("Basket {} rejected: {}", basket_id, rejected[0].1);
+                                rejected.extend(accepted.drain(..).map(|order| (order, reason.clone())));
                             }
-
-                            order.state = OrderState::Accepted;
-                            {
-                                open_order_cache.insert(order.id.clone(), order.clone());
+                            for (order, reason) in rejected {
+                                send_event(&strategy_event_sender, rejected_event(order, reason, time)).await;
                             }
-                            let accept_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderAccepted {
-                                account: account.clone(),
-                                symbol_name: order.symbol_name.clone(),
-                                symbol_code: order.symbol_code.clone(),
-                                order_id: order.id.clone(),
-                                tag: order.tag.clone(),
-                                time: time.to_string()
-                            });
-                            match strategy_event_sender.send(accept_event).await {
-                                Ok(_) => {}
-                                Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
+                            for order in accepted {
+                                accept_order(order, account.clone(), &open_order_cache, &strategy_event_sender, &market_price_service, time).await;
                             }
                             simulated_order_matching(&open_order_cache, &closed_order_cache, strategy_event_sender.clone(), &ledger_service, &market_price_service).await;
                         }
@@ -168,58 +89,42 @@ pub(crate) async fn backtest_matching_engine(
                                     time: time.to_string(),
                                     reason: "User Request".to_string(),
                                 });
-                                match strategy_event_sender.send(cancel_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
+                                send_event(&strategy_event_sender, cancel_event).await;
                                 //eprintln!("Order Cancelled: {:?}", order_id);
                                 closed_order_cache.insert(order_id, order);
                             } else {
                                 let fail_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderUpdateRejected {
                                     account, order_id, reason: String::from("No pending order found"), time: time.to_string()
                                 });
-                                match strategy_event_sender.send(fail_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
+                                send_event(&strategy_event_sender, fail_event).await;
                             }
                             simulated_order_matching(&open_order_cache, &closed_order_cache, strategy_event_sender.clone(), &ledger_service, &market_price_service).await;
                         }
                         OrderRequest::Update { account, order_id, update } => {
-                            if let Some((order_id, mut order)) = open_order_cache.remove(&order_id) {
-                                match &update {
-                                    OrderUpdateType::LimitPrice(price) => {
-                                        if let Some(ref mut limit_price) = order.limit_price {
-                                            *limit_price = price.clone();
+                            let event = match open_order_cache.get_mut(&order_id) {
+                                Some(mut order) => match order.validate_update(&update) {
+                                    Ok(()) => {
+                                        order.apply_update(&update);
+                                        OrderUpdateEvent::OrderUpdated {
+                                            account, symbol_name: order.symbol_name.clone(), symbol_code: order.symbol_code.clone(),
+                                            order_id: order.id.clone(), update_type: update, text: "User Request".to_string(), tag: order.tag.clone(), time: time.to_string()
                                         }
                                     }
-                                    OrderUpdateType::TriggerPrice(price) => {
-                                        if let Some(ref mut trigger_price) = order.trigger_price {
-                                            *trigger_price = price.clone();
-                                        }
+                                    Err(reason) => OrderUpdateEvent::OrderUpdateRejected {
+                                        account, order_id, reason, time: time.to_string()
                                     }
-                                    OrderUpdateType::Quantity(quantity) => {
-                                        order.quantity_open = quantity.clone();
+                                },
+                                None => {
+                                    let reason = match closed_order_cache.get(&order_id) {
+                                        Some(order) => order.validate_update(&update).err().unwrap_or_else(|| String::from("Order is closed")),
+                                        None => String::from("No pending order found"),
+                                    };
+                                    OrderUpdateEvent::OrderUpdateRejected {
+                                        account, order_id, reason, time: time.to_string()
                                     }
                                 }
-                                let update_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderUpdated {
-                                    account, symbol_name: order.symbol_name.clone(), symbol_code: order.symbol_name.clone(),
-                                    order_id: order.id.clone(), update_type: update, text: "User Request".to_string(), tag: order.tag.clone(), time: time.to_string()
-                                });
-                                open_order_cache.insert(order_id, order);
-                                match strategy_event_sender.send(update_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
-                            } else {
-                                let fail_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderUpdateRejected {
-                                    account, order_id, reason: String::from("No pending order found"), time: time.to_string()
-                                });
-                                match strategy_event_sender.send(fail_event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
-                            }
+                            };
+                            send_event(&strategy_event_sender, StrategyEvent::OrderEvents(event)).await;
                             simulated_order_matching(&open_order_cache, &closed_order_cache, strategy_event_sender.clone(), &ledger_service, &market_price_service).await;
                         }
                         OrderRequest::CancelAll { account } => {
@@ -242,10 +147,7 @@ pub(crate) async fn backtest_matching_engine(
                                             tag: order.tag.clone(),
                                             time: time.to_string(),
                                         });
-                                    match strategy_event_sender.send(cancel_event).await {
-                                        Ok(_) => {}
-                                        Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                    }
+                                    send_event(&strategy_event_sender, cancel_event).await;
                                     closed_order_cache.insert(order_id, order);
                                 }
                             }
@@ -269,10 +171,7 @@ pub(crate) async fn backtest_matching_engine(
                                     tag: order.tag.clone(),
                                     time: time.to_string(),
                                 });
-                                match strategy_event_sender.send(event).await {
-                                    Ok(_) => {}
-                                    Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
-                                }
+                                send_event(&strategy_event_sender, event).await;
                                 closed_order_cache.insert(order.id.clone(), order);
                             }
                             ledger_service.flatten_all_for_paper_account(account, time).await;
@@ -303,6 +202,8 @@ pub(crate) async fn simulated_order_matching (
     let mut cancelled = Vec::new();
     let mut filled = Vec::new();
     let mut partially_filled = Vec::new();
+    let mut immediate = Vec::new();
+    market_price_service.queue_positions.retain(|order_id| open_order_cache.contains_key(order_id));
     for order in open_order_cache.iter() {
         //println!("Order matching: {:?}", order.value());
         match &order.time_in_force {
@@ -370,20 +271,11 @@ pub(crate) async fn simulated_order_matching (
                     continue
                 }
             }
-            TimeInForce::IOC=> {
-               /* if time > order.time_created_utc() + Duration::seconds(1) {
-                    let reason = "Time In Force Expired: TimeInForce::IOC".to_string();
-                    cancelled.push((order.id.clone(), reason));
-                }*/
-            }
-            TimeInForce::FOK => {
-                /*if time > order.time_created_utc() + buffer_resolution  {
-                   let reason = "Time In Force Expired: TimeInForce::FOK".to_string();
-                    cancelled.push((order.id.clone(), reason));
-                }*/
-            }
-            TimeInForce::Time(cancel_time) => {
-                let cancel_time = match DateTime::<Utc>::from_timestamp(*cancel_time, 0) {
+            // Orders are matched as soon as they are accepted, so an IOC or FOK order is only ever matched once, see `enforce_immediate_time_in_force()`.
+            TimeInForce::IOC => immediate.push((order.id.clone(), false)),
+            TimeInForce::FOK => immediate.push((order.id.clone(), true)),
+            TimeInForce::Time(_) | TimeInForce::TimeNanos(_) => {
+                let cancel_time = match order.time_in_force.expiry_time() {
                     Some(time) => time,
                     None => {
                         eprintln!("Backtest Matching Engine: Invalid TimeInForce::Time value");
@@ -399,6 +291,12 @@ pub(crate) async fn simulated_order_matching (
                 }
             }
         }
+        // A market locked at the price limit has no liquidity on the side of the order.
+        if let Some(market_price) = market_price_service.get_market_price(order.side, &order.symbol_name, &order.symbol_code) {
+            if market_price_service.price_bands.is_locked(&order.symbol_name, order.side, market_price) {
+                continue;
+            }
+        }
         //3. respond with an order event
         match &order.order_type {
             OrderType::Limit => {
@@ -411,6 +309,17 @@ pub(crate) async fn simulated_order_matching (
                     OrderSide::Buy => market_price <= order.limit_price.unwrap(),
                     OrderSide::Sell => market_price >= order.limit_price.unwrap()
                 };
+                // A queued order fills at its limit price once the volume ahead of it has traded, see `LimitFillModel::QueuePosition`.
+                if let Some(volume_filled) = market_price_service.queue_positions.fillable(&order.id, order.quantity_open) {
+                    if volume_filled > dec!(0) {
+                        market_price_service.queue_positions.filled(&order.id, volume_filled);
+                        match volume_filled == order.quantity_open {
+                            true => filled.push((order.id.clone(), order.limit_price.unwrap())),
+                            false => partially_filled.push((order.id.clone(), order.limit_price.unwrap(), volume_filled))
+                        }
+                    }
+                    continue;
+                }
                 if is_fill_triggered {
                     let (market_fill_price, volume_filled) = match market_price_service.estimate_limit_fill(order.side, &order.symbol_name, &order.symbol_code, order.quantity_open, order.limit_price.unwrap()) {
                         Some((price, volume)) => {
@@ -433,7 +342,7 @@ pub(crate) async fn simulated_order_matching (
             },
             // Handle OrderType::StopMarket separately
             OrderType::StopMarket => {
-                let market_price = match market_price_service.get_market_price(order.side, &order.symbol_name, &order.symbol_code) {
+                let market_price = match market_price_service.get_trigger_price(order.trigger_source(), order.side, &order.symbol_name, &order.symbol_code) {
                     Some(price) => price,
                     None => panic!("No market price found")
                 };
@@ -455,7 +364,7 @@ pub(crate) async fn simulated_order_matching (
 
             // Handle OrderType::MarketIfTouched separately
             OrderType::MarketIfTouched => {
-                let market_price = match market_price_service.get_market_price(order.side, &order.symbol_name, &order.symbol_code) {
+                let market_price = match market_price_service.get_trigger_price(order.trigger_source(), order.side, &order.symbol_name, &order.symbol_code) {
                     Some(price) => price,
                     None => panic!("No market price found")
                 };
@@ -498,7 +407,7 @@ pub(crate) async fn simulated_order_matching (
                 }
             }
             OrderType::StopLimit => {
-                let market_price = match market_price_service.get_market_price(order.side, &order.symbol_name, &order.symbol_code) {
+                let market_price = match market_price_service.get_trigger_price(order.trigger_source(), order.side, &order.symbol_name, &order.symbol_code) {
                     Some(price) => price,
                     None => panic!("No market price found")
                 };
@@ -521,7 +430,7 @@ pub(crate) async fn simulated_order_matching (
                 }
             },
             OrderType::EnterLong => {
-                if ledger_service.is_short(&order.account, &order.symbol_code) {
+                if !ledger_service.is_hedging(&order.account) && ledger_service.is_short(&order.account, &order.symbol_code) {
                     let quantity = ledger_service.position_size(&order.account, &order.symbol_code);
                     let market_fill_price = match market_price_service.estimate_fill_price(order.side, &order.symbol_name, &order.symbol_code, quantity) {
                         Some(price) => price,
@@ -536,7 +445,7 @@ pub(crate) async fn simulated_order_matching (
                 filled.push((order.id.clone(), market_fill_price));
             }
             OrderType::EnterShort => {
-                if ledger_service.is_long(&order.account, &order.symbol_code) {
+                if !ledger_service.is_hedging(&order.account) && ledger_service.is_long(&order.account, &order.symbol_code) {
                     let quantity = ledger_service.position_size(&order.account, &order.symbol_code);
                     let market_fill_price = match market_price_service.estimate_fill_price(order.side, &order.symbol_name, &order.symbol_code, quantity) {
                         Some(price) => price,
@@ -551,9 +460,8 @@ pub(crate) async fn simulated_order_matching (
                 filled.push((order.id.clone(), market_fill_price));
             }
             OrderType::ExitLong => {
-                let long_quantity = ledger_service.position_size(&order.account, &order.symbol_code);
-                let is_long = ledger_service.is_long(&order.account, &order.symbol_code);
-                if long_quantity <= dec!(0.0) || !is_long {
+                let long_quantity = exit_quantity(ledger_service, &order, PositionSide::Long);
+                if long_quantity <= dec!(0.0) {
                     let reason = "No Long Position To Exit".to_string();
                     rejected.push((order.id.clone(), reason));
                     continue;
@@ -570,9 +478,8 @@ pub(crate) async fn simulated_order_matching (
                 filled.push((order.id.clone(), market_fill_price));
             }
             OrderType::ExitShort => {
-                let short_quantity = ledger_service.position_size(&order.account, &order.symbol_code);
-                let is_short = ledger_service.is_short(&order.account, &order.symbol_code);
-                if short_quantity <= dec!(0.0) || !is_short {
+                let short_quantity = exit_quantity(ledger_service, &order, PositionSide::Short);
+                if short_quantity <= dec!(0.0) {
                     let reason = "No Short Position To Exit".to_string();
                     rejected.push((order.id.clone(), reason));
                     continue;
@@ -590,13 +497,23 @@ pub(crate) async fn simulated_order_matching (
         }
     }
 
+    enforce_immediate_time_in_force(immediate, &rejected, &filled, &mut partially_filled, &mut cancelled);
+
     for (order_id, reason) in rejected {
         reject_order(reason, &order_id, time, &open_order_cache, closed_order_cache, &strategy_event_sender).await;
     }
     for (order_id, price) in filled {
+        let price = match open_order_cache.get(&order_id) {
+            Some(order) => market_price_service.price_bands.clamp_fill(&order.symbol_name, price),
+            None => price
+        };
         fill_order(&order_id, time, price, &open_order_cache, &closed_order_cache, &strategy_event_sender, &ledger_service).await;
     }
     for (order_id, price, volume) in partially_filled {
+        let price = match open_order_cache.get(&order_id) {
+            Some(order) => market_price_service.price_bands.clamp_fill(&order.symbol_name, price),
+            None => price
+        };
         partially_fill_order(&order_id, time, price, volume, &open_order_cache, &closed_order_cache, &strategy_event_sender, &ledger_service).await;
     }
 
@@ -605,6 +522,130 @@ pub(crate) async fn simulated_order_matching (
     }
 }
 
+/// The reason the engine rejects a new order, `None` if the order is accepted.
+async fn order_rejection(order: &mut Order, ledger_service: &LedgerService, market_price_service: &MarketPriceService) -> Option<String> {
+    let market_price = match market_price_service.get_market_price(order.side, &order.symbol_name, &order.symbol_code) {
+        Some(price) => price,
+        None => panic!("No market price found")
+    };
+    //eprintln!("Market Price: {}", market_price);
+    if order.quantity_open <= dec!(0) {
+        return Some(String::from("Order Quantity Must Be Greater Than Zero"));
+    }
+    if let Err(reason) = market_price_service.price_bands.validate_order(order).await {
+        return Some(reason);
+    }
+    // check limit price
+    if (order.order_type == OrderType::StopLimit || order.order_type == OrderType::Limit) &&
+        ((order.side == OrderSide::Buy && order.limit_price.unwrap() > market_price)
+        || (order.side == OrderSide::Sell && order.limit_price.unwrap() < market_price))
+    {
+        let side_string = match order.side {
+            OrderSide::Buy => "Below",
+            OrderSide::Sell => "Above"
+        };
+        return Some(format!("{} Limit Price Must Be {} or Equal to Market Price", order.side, side_string));
+    }
+    //check trigger price
+    if (order.order_type == OrderType::StopMarket || order.order_type == OrderType::StopLimit || order.order_type == OrderType::MarketIfTouched) &&
+        ((order.side == OrderSide::Sell && order.trigger_price.unwrap() >= market_price) ||
+            (order.side == OrderSide::Buy && order.trigger_price.unwrap() <= market_price))
+    {
+        let side_string = match order.side {
+            OrderSide::Buy => "Above",  // Changed from "Below"
+            OrderSide::Sell => "Below"  // Changed from "Above"
+        };
+        return Some(format!("{} Trigger Price Must Be {} Market Price", order.side, side_string));
+    }
+    if order.order_type == OrderType::ExitLong && exit_quantity(ledger_service, order, PositionSide::Long) <= dec!(0) {
+        return Some(String::from("No Long Position To Exit"));
+    }
+    if order.order_type == OrderType::ExitShort && exit_quantity(ledger_service, order, PositionSide::Short) <= dec!(0) {
+        return Some(String::from("No Short Position To Exit"));
+    }
+    simulated_rejection()
+}
+
+fn rejected_event(order: Order, reason: String, time: DateTime<Utc>) -> StrategyEvent {
+    StrategyEvent::OrderEvents(OrderUpdateEvent::OrderRejected {
+        account: order.account,
+        symbol_name: order.symbol_name,
+        symbol_code: order.symbol_code,
+        order_id: order.id,
+        reason,
+        tag: order.tag,
+        time: time.to_string()
+    })
+}
+
+async fn accept_order(
+    mut order: Order,
+    account: Account,
+    open_order_cache: &DashMap<OrderId, Order>,
+    strategy_event_sender: &Sender<StrategyEvent>,
+    market_price_service: &MarketPriceService,
+    time: DateTime<Utc>
+) {
+    order.state = OrderState::Accepted;
+    // Only orders which rest on the book wait in the queue, a marketable limit order takes liquidity.
+    if order.order_type == OrderType::Limit {
+        if let Some(market_price) = market_price_service.get_market_price(order.side, &order.symbol_name, &order.symbol_code) {
+            if order.limit_price != Some(market_price) {
+                let book_volume = market_price_service.book_volume_at(order.side, &order.symbol_name, &order.symbol_code, order.limit_price.unwrap());
+                market_price_service.queue_positions.join(&order, book_volume);
+            }
+        }
+    }
+    open_order_cache.insert(order.id.clone(), order.clone());
+    let accept_event = StrategyEvent::OrderEvents(OrderUpdateEvent::OrderAccepted {
+        account,
+        symbol_name: order.symbol_name.clone(),
+        symbol_code: order.symbol_code.clone(),
+        order_id: order.id.clone(),
+        tag: order.tag.clone(),
+        time: time.to_string()
+    });
+    send_event(strategy_event_sender, accept_event).await;
+}
+
+/// The open quantity an exit order can close, the quantity of the targeted position when the order has a `position_id`.
+fn exit_quantity(ledger_service: &LedgerService, order: &Order, side: PositionSide) -> Volume {
+    match &order.position_id {
+        Some(position_id) => ledger_service.position(&order.account, position_id)
+            .filter(|position| position.side == side && position.symbol_code == order.symbol_code)
+            .map(|position| position.quantity_open)
+            .unwrap_or_else(|| dec!(0)),
+        None => ledger_service.side_quantity(&order.account, &order.symbol_code, side),
+    }
+}
+
+/// Cancels the IOC and FOK orders which were not completely filled by this match, an IOC order keeps its partial fill and cancels the rest,
+/// an FOK order is cancelled without filling unless the whole quantity can be filled.
+/// The cancellations are processed after the fills so the partially filled remainder of an IOC order is cancelled.
+fn enforce_immediate_time_in_force(
+    immediate: Vec<(OrderId, bool)>,
+    rejected: &[(OrderId, String)],
+    filled: &[(OrderId, Price)],
+    partially_filled: &mut Vec<(OrderId, Price, Volume)>,
+    cancelled: &mut Vec<(OrderId, String)>,
+) {
+    for (order_id, is_fill_or_kill) in immediate {
+        if filled.iter().any(|(id, _)| *id == order_id) || rejected.iter().any(|(id, _)| *id == order_id) {
+            continue;
+        }
+        let is_partial = partially_filled.iter().any(|(id, _, _)| *id == order_id);
+        let reason = match (is_fill_or_kill, is_partial) {
+            (true, _) => {
+                partially_filled.retain(|(id, _, _)| *id != order_id);
+                "Time In Force: TimeInForce::FOK could not be filled completely"
+            }
+            (false, true) => "Time In Force: TimeInForce::IOC unfilled quantity cancelled",
+            (false, false) => "Time In Force: TimeInForce::IOC could not be filled",
+        };
+        cancelled.push((order_id, reason.to_string()));
+    }
+}
+
 async fn fill_order(
     order_id: &OrderId,
     time: DateTime<Utc>,
@@ -616,16 +657,13 @@ async fn fill_order(
 ) {
     if let Some((_, mut order)) = open_order_cache.remove(order_id) {  // Remove the order here
        let (sender, receiver) = oneshot::channel();
-        ledger_service.update_or_create_position(&order.account, order.symbol_name.clone(), order.symbol_code.clone(), order.quantity_open.clone(), order.side.clone(), time.clone(), market_price, order.tag.clone(), Some(sender), order_id.clone()).await;
+        ledger_service.update_or_create_position(&order.account, order.symbol_name.clone(), order.symbol_code.clone(), order.quantity_open.clone(), order.side.clone(), time.clone(), market_price, order.tag.clone(), Some(sender), order_id.clone(), PositionTarget::from_order(&order)).await;
         match receiver.await {
             Ok(event) => {
                 match event {
                     Some(event) => {
                         order.state = OrderState::Rejected("Insufficient Funds".to_string());
-                        match strategy_event_sender.send(StrategyEvent::OrderEvents(event)).await {
-                            Ok(_) => {}
-                            Err(e) => eprintln!("Backtest Matching Engine: Failed to send event: {}", e)
-                        }
+                        send_event(&strategy_event_sender, StrategyEvent::OrderEvents(event)).await;
                         closed_order_cache.insert(order.id.clone(), order);
                     }
                     None => {
@@ -644,15 +682,12 @@ async fn fill_order(
                         });
                         order.quantity_filled += order.quantity_open.clone();
                         order.quantity_open = dec!(0.0);
-                        match strategy_event_sender.send(order_event).await {
-                            Ok(_) => {}
-                            Err(e) => eprintln!("Backtest Matching Engine: Failed to send event: {}", e)
-                        }
+                        send_event(&strategy_event_sender, order_event).await;
                         closed_order_cache.insert(order.id.clone(), order);
                     }
                 }
             }
-            Err(e) => eprintln!("Backtest Matching Engine: Failed to receive event: {}", e)
+            Err(e) => tracing::error!("Backtest Matching Engine: Failed to receive event: {}", e)
         }
     }
 }
@@ -669,17 +704,14 @@ async fn partially_fill_order(
 ) {
     if let Some((_, mut order)) = open_order_cache.remove(order_id) {
         let (sender, receiver) = oneshot::channel();
-        ledger_service.update_or_create_position(&order.account, order.symbol_name.clone(),  order.symbol_code.clone(), fill_volume, order.side.clone(), time, fill_price, order.tag.clone(), Some(sender), order_id.clone()).await;
+        ledger_service.update_or_create_position(&order.account, order.symbol_name.clone(),  order.symbol_code.clone(), fill_volume, order.side.clone(), time, fill_price, order.tag.clone(), Some(sender), order_id.clone(), PositionTarget::from_order(&order)).await;
 
         match receiver.await {
             Ok(event) => {
                 match event {
                     Some(event) => {
                         order.state = OrderState::Rejected("Insufficient Funds".to_string());
-                        match strategy_event_sender.send(StrategyEvent::OrderEvents(event)).await {
-                            Ok(_) => {}
-                            Err(e) => eprintln!("Backtest Matching Engine: Failed to send event: {}", e)
-                        }
+                        send_event(&strategy_event_sender, StrategyEvent::OrderEvents(event)).await;
                         closed_order_cache.insert(order.id.clone(), order);
                     }
                     None => {
@@ -712,10 +744,7 @@ async fn partially_fill_order(
                                 side: order.side.clone(),
                             }
                         };
-                        match strategy_event_sender.send(StrategyEvent::OrderEvents(order_event)).await {
-                            Ok(_) => {}
-                            Err(e) => eprintln!("Backtest Matching Engine: Failed to send event: {}", e)
-                        }
+                        send_event(&strategy_event_sender, StrategyEvent::OrderEvents(order_event)).await;
                         if is_fully_filled {
                             closed_order_cache.insert(order.id.clone(), order);
                         } else {
@@ -724,7 +753,7 @@ async fn partially_fill_order(
                     }
                 }
             }
-            Err(e) => eprintln!("Backtest Matching Engine: Failed to receive event: {}", e)
+            Err(e) => tracing::error!("Backtest Matching Engine: Failed to receive event: {}", e)
         }
     }
 }
@@ -751,10 +780,7 @@ async fn reject_order(
                 symbol_code: order.symbol_code.clone(),
             });
         closed_order_cache.insert(order.id.clone(), order.clone());
-        match strategy_event_sender.send(event).await {
-            Ok(_) => {}
-            Err(e) => eprintln!("Backtest Matching Engine: Failed to send event: {}", e)
-        }
+        send_event(&strategy_event_sender, event).await;
     }
 }
 
@@ -780,9 +806,79 @@ async fn cancel_order(
             symbol_code: order.symbol_code.clone(),
         });
         closed_order_cache.insert(order.id.clone(), order.clone());
-        match strategy_event_sender.send(event).await {
-            Ok(_) => {}
-            Err(e) => eprintln!("Backtest Matching Engine: Failed to send event: {}", e)
+        send_event(&strategy_event_sender, event).await;
+    }
+}
+
+/// Logs order events in the span of the order before sending the event to the strategy.
+async fn send_event(strategy_event_sender: &Sender<StrategyEvent>, event: StrategyEvent) {
+    let basket_events = match &event {
+        StrategyEvent::OrderEvents(order_event) => {
+            log_order_event(order_event);
+            update_child_order(order_event);
+            basket_events(order_event)
         }
+        _ => vec![],
+    };
+    if let Err(e) = strategy_event_sender.send(event).await {
+        tracing::error!("Backtest Matching Engine: Failed to send event: {}", e);
+    }
+    for basket_event in basket_events {
+        if let Err(e) = strategy_event_sender.send(StrategyEvent::BasketEvents(basket_event)).await {
+            tracing::error!("Backtest Matching Engine: Failed to send event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_immediate_time_in_force() {
+        let id = |id: &str| id.to_string();
+        // IOC partial, IOC unfilled, FOK partial, FOK filled
+        let immediate = vec![(id("ioc_partial"), false), (id("ioc_none"), false), (id("fok_partial"), true), (id("fok_filled"), true)];
+        let filled = vec![(id("fok_filled"), dec!(100))];
+        let mut partially_filled = vec![(id("ioc_partial"), dec!(100), dec!(1)), (id("fok_partial"), dec!(100), dec!(1))];
+        let mut cancelled = vec![];
+        enforce_immediate_time_in_force(immediate, &[], &filled, &mut partially_filled, &mut cancelled);
+
+        assert_eq!(partially_filled, vec![(id("ioc_partial"), dec!(100), dec!(1))]);
+        let cancelled: Vec<OrderId> = cancelled.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(cancelled, vec![id("ioc_partial"), id("ioc_none"), id("fok_partial")]);
+    }
+
+    #[test]
+    fn test_trigger_sources() {
+        use crate::standardized_types::accounts::Account;
+        use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+        use crate::standardized_types::base_data::quote::Quote;
+        use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+        use crate::standardized_types::datavendor_enum::DataVendor;
+        use crate::standardized_types::enums::{FuturesExchange, MarketType};
+        use crate::standardized_types::orders::TriggerSource;
+        use crate::standardized_types::subscriptions::Symbol;
+        use crate::standardized_types::time_slices::TimeSlice;
+
+        let symbol = Symbol::new("MNQ".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME));
+        let name = symbol.name.clone();
+        let service = MarketPriceService::new();
+        let mut slice = TimeSlice::new();
+        slice.add(BaseDataEnum::Quote(Quote::new(symbol.clone(), dec!(100.5), dec!(100), dec!(1), dec!(1), "2024-06-03 14:30:00 UTC".to_string())));
+        slice.add(BaseDataEnum::Tick(Tick::new(symbol.clone(), dec!(99), "2024-06-03 14:30:00 UTC".to_string(), dec!(1), Aggressor::Sell)));
+        service.update_market_data(Arc::new(slice));
+
+        assert_eq!(service.get_trigger_price(TriggerSource::Last, OrderSide::Buy, &name, &name), Some(dec!(99)));
+        assert_eq!(service.get_trigger_price(TriggerSource::BidAsk, OrderSide::Buy, &name, &name), Some(dec!(100.5)));
+        assert_eq!(service.get_trigger_price(TriggerSource::BidAsk, OrderSide::Sell, &name, &name), Some(dec!(100)));
+        assert_eq!(service.get_trigger_price(TriggerSource::Midpoint, OrderSide::Sell, &name, &name), Some(dec!(100.25)));
+
+        // Orders without a trigger source trigger like the brokerage.
+        let rithmic = Account::new(Brokerage::Rithmic(crate::apis::rithmic::rithmic_systems::RithmicSystem::Apex), "APEX-1".to_string());
+        let mut order = Order::stop(name.clone(), None, &rithmic, dec!(1), OrderSide::Sell, "stop".to_string(), "1".to_string(), Utc::now(), dec!(99), TimeInForce::GTC, None);
+        assert_eq!(order.trigger_source(), TriggerSource::Last);
+        order.trigger_source = Some(TriggerSource::Midpoint);
+        assert_eq!(order.trigger_source(), TriggerSource::Midpoint);
     }
 }
@@ -0,0 +1,184 @@
+use std::sync::Mutex;
+use ahash::AHashMap;
+use lazy_static::lazy_static;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::orders::{BasketEvent, BasketId, BasketPolicy, OrderId, OrderUpdateEvent};
+
+lazy_static! {
+    static ref BASKETS: Mutex<BasketTracker> = Mutex::new(BasketTracker::default());
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum LegState {
+    Pending,
+    Placed,
+    Filled,
+    Cancelled,
+    Rejected(String),
+}
+
+struct Basket {
+    account: Account,
+    policy: BasketPolicy,
+    legs: Vec<(OrderId, LegState)>,
+    placement_reported: bool,
+}
+
+impl Basket {
+    /// The basket events once every leg has been accepted or rejected, and true once the basket is complete and no longer needs to be tracked.
+    fn events(&mut self, basket_id: &BasketId, time: &str) -> (Vec<BasketEvent>, bool) {
+        let mut events = vec![];
+        if self.legs.iter().any(|(_, state)| *state == LegState::Pending) {
+            return (events, false);
+        }
+        if !self.placement_reported {
+            self.placement_reported = true;
+            let rejected: Vec<(&OrderId, &String)> = self.legs.iter()
+                .filter_map(|(order_id, state)| match state {
+                    LegState::Rejected(reason) => Some((order_id, reason)),
+                    _ => None,
+                })
+                .collect();
+            if let Some((_, reason)) = rejected.first() {
+                if self.policy == BasketPolicy::AllOrNone || rejected.len() == self.legs.len() {
+                    events.push(BasketEvent::Rejected { account: self.account.clone(), basket_id: basket_id.clone(), reason: reason.to_string(), time: time.to_string() });
+                    return (events, true);
+                }
+                events.push(BasketEvent::PartiallyPlaced {
+                    account: self.account.clone(),
+                    basket_id: basket_id.clone(),
+                    placed: self.placed().cloned().collect(),
+                    rejected: rejected.iter().map(|(order_id, _)| (*order_id).clone()).collect(),
+                    time: time.to_string(),
+                });
+            }
+        }
+        let placed: Vec<&LegState> = self.legs.iter().map(|(_, state)| state).filter(|state| !matches!(state, LegState::Rejected(_))).collect();
+        if placed.iter().all(|state| **state == LegState::Filled) {
+            events.push(BasketEvent::Filled { account: self.account.clone(), basket_id: basket_id.clone(), order_ids: self.placed().cloned().collect(), time: time.to_string() });
+            return (events, true);
+        }
+        // A cancelled leg means the basket will not fill, the strategy has the cancel events of the orders.
+        let complete = placed.iter().any(|state| **state == LegState::Cancelled);
+        (events, complete)
+    }
+
+    fn placed(&self) -> impl Iterator<Item = &OrderId> {
+        self.legs.iter().filter(|(_, state)| !matches!(state, LegState::Rejected(_))).map(|(order_id, _)| order_id)
+    }
+}
+
+/// Follows the orders of the baskets submitted with `FundForgeStrategy::submit_basket()` and creates the `BasketEvent`s from their order events.
+#[derive(Default)]
+struct BasketTracker {
+    baskets: AHashMap<BasketId, Basket>,
+    legs: AHashMap<OrderId, BasketId>,
+}
+
+impl BasketTracker {
+    fn track(&mut self, basket_id: BasketId, account: Account, policy: BasketPolicy, order_ids: Vec<OrderId>, rejected: Vec<(OrderId, String)>) {
+        let mut legs: Vec<(OrderId, LegState)> = order_ids.into_iter().map(|order_id| (order_id, LegState::Pending)).collect();
+        legs.extend(rejected.into_iter().map(|(order_id, reason)| (order_id, LegState::Rejected(reason))));
+        for (order_id, _) in &legs {
+            self.legs.insert(order_id.clone(), basket_id.clone());
+        }
+        self.baskets.insert(basket_id, Basket { account, policy, legs, placement_reported: false });
+    }
+
+    fn update(&mut self, event: &OrderUpdateEvent) -> Vec<BasketEvent> {
+        let basket_id = match self.legs.get(event.order_id()) {
+            Some(basket_id) => basket_id.clone(),
+            None => return vec![],
+        };
+        let (new_state, time) = match event {
+            OrderUpdateEvent::OrderAccepted { time, .. } | OrderUpdateEvent::OrderPartiallyFilled { time, .. } => (LegState::Placed, time),
+            OrderUpdateEvent::OrderFilled { time, .. } => (LegState::Filled, time),
+            OrderUpdateEvent::OrderCancelled { time, .. } => (LegState::Cancelled, time),
+            OrderUpdateEvent::OrderRejected { reason, time, .. } => (LegState::Rejected(reason.clone()), time),
+            OrderUpdateEvent::OrderUpdated { .. } | OrderUpdateEvent::OrderUpdateRejected { .. } => return vec![],
+        };
+        let basket = match self.baskets.get_mut(&basket_id) {
+            Some(basket) => basket,
+            None => return vec![],
+        };
+        if let Some((_, state)) = basket.legs.iter_mut().find(|(order_id, _)| order_id == event.order_id()) {
+            // A late accept does not undo a fill or a cancel.
+            if new_state != LegState::Placed || *state == LegState::Pending {
+                *state = new_state;
+            }
+        }
+        let (events, complete) = basket.events(&basket_id, time);
+        if complete {
+            if let Some(basket) = self.baskets.remove(&basket_id) {
+                for (order_id, _) in basket.legs {
+                    self.legs.remove(&order_id);
+                }
+            }
+        }
+        events
+    }
+}
+
+/// Tracks a basket sent to the engine or the brokerage, `rejected` are the orders the strategy rejected before sending the basket.
+pub(crate) fn track_basket(basket_id: BasketId, account: Account, policy: BasketPolicy, order_ids: Vec<OrderId>, rejected: Vec<(OrderId, String)>) {
+    BASKETS.lock().unwrap().track(basket_id, account, policy, order_ids, rejected);
+}
+
+/// The basket events caused by the order event, empty if the order is not part of a basket.
+pub(crate) fn basket_events(event: &OrderUpdateEvent) -> Vec<BasketEvent> {
+    BASKETS.lock().unwrap().update(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::broker_enum::Brokerage;
+    use crate::standardized_types::enums::OrderSide;
+
+    fn filled(account: &Account, order_id: &str) -> OrderUpdateEvent {
+        OrderUpdateEvent::OrderFilled {
+            account: account.clone(), symbol_name: "MNQ".to_string(), symbol_code: "MNQ".to_string(), order_id: order_id.to_string(),
+            side: OrderSide::Buy, price: dec!(20000), quantity: dec!(1), tag: "test".to_string(), time: "2024-10-01 14:30:00 UTC".to_string()
+        }
+    }
+
+    fn rejected(account: &Account, order_id: &str) -> OrderUpdateEvent {
+        OrderUpdateEvent::OrderRejected {
+            account: account.clone(), symbol_name: "MES".to_string(), symbol_code: "MES".to_string(), order_id: order_id.to_string(),
+            reason: "No margin".to_string(), tag: "test".to_string(), time: "2024-10-01 14:30:00 UTC".to_string()
+        }
+    }
+
+    #[test]
+    fn test_best_effort_basket() {
+        let account = Account::new(Brokerage::Test, "test".to_string());
+        let mut tracker = BasketTracker::default();
+        tracker.track("basket".to_string(), account.clone(), BasketPolicy::BestEffort, vec!["1".to_string(), "2".to_string(), "3".to_string()], vec![]);
+
+        assert!(tracker.update(&filled(&account, "1")).is_empty());
+        assert!(tracker.update(&rejected(&account, "2")).is_empty());
+        let events = tracker.update(&filled(&account, "3"));
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], BasketEvent::PartiallyPlaced { placed, rejected, .. } if placed.len() == 2 && rejected == &vec!["2".to_string()]));
+        assert!(matches!(&events[1], BasketEvent::Filled { order_ids, .. } if order_ids == &vec!["1".to_string(), "3".to_string()]));
+        assert!(tracker.baskets.is_empty() && tracker.legs.is_empty());
+    }
+
+    #[test]
+    fn test_all_or_none_basket() {
+        let account = Account::new(Brokerage::Test, "test".to_string());
+        let mut tracker = BasketTracker::default();
+        tracker.track("basket".to_string(), account.clone(), BasketPolicy::AllOrNone, vec!["1".to_string(), "2".to_string()], vec![]);
+
+        assert!(tracker.update(&rejected(&account, "1")).is_empty());
+        let events = tracker.update(&rejected(&account, "2"));
+        assert!(matches!(&events[..], [BasketEvent::Rejected { reason, .. }] if reason == "No margin"));
+        assert!(tracker.update(&filled(&account, "2")).is_empty());
+
+        // The strategy rejected a leg before sending the basket
+        tracker.track("basket 2".to_string(), account.clone(), BasketPolicy::BestEffort, vec!["3".to_string()], vec![("4".to_string(), "Outside of the trading schedule".to_string())]);
+        let events = tracker.update(&filled(&account, "3"));
+        assert!(matches!(&events[..], [BasketEvent::PartiallyPlaced { .. }, BasketEvent::Filled { .. }]));
+    }
+}
@@ -4,9 +4,14 @@ use dashmap::DashMap;
 use rust_decimal_macros::dec;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::{Receiver};
-use crate::standardized_types::orders::{Order, OrderId, OrderState, OrderUpdateEvent, OrderUpdateType};
+use crate::standardized_types::orders::{Order, OrderId, OrderState, OrderUpdateEvent};
+use crate::strategies::ledgers::hedging::PositionTarget;
 use crate::strategies::ledgers::ledger_service::{LedgerService};
 use crate::strategies::strategy_events::StrategyEvent;
+use crate::strategies::handlers::market_handler::baskets::basket_events;
+use crate::strategies::handlers::algo_executor::update_child_order;
+use crate::helpers::logging::log_order_event;
+use crate::strategies::notifications::{notify_engine_event, NotificationLevel};
 
 //todo, this probably isnt needed
 
@@ -22,6 +27,11 @@ pub(crate) fn live_order_handler(
     //todo, we need a message que for ledger, where orders and positions are update the ledger 1 at a time per symbol_code, this should fix the possible race conditions of positions updates
     tokio::task::spawn(async move {
         while let Some((ref order_update_event, time_utc)) = order_event_receiver.recv().await {
+            ledger_service.journal_order_event(order_update_event);
+            log_order_event(order_update_event);
+            if let OrderUpdateEvent::OrderRejected { .. } | OrderUpdateEvent::OrderUpdateRejected { .. } = order_update_event {
+                notify_engine_event(NotificationLevel::Warning, order_update_event.to_string());
+            }
             match order_update_event {
                 #[allow(unused)]
                 OrderUpdateEvent::OrderAccepted { account, symbol_name, symbol_code, order_id, tag, time } => {
@@ -33,7 +43,7 @@ pub(crate) fn live_order_handler(
                         order.symbol_code = symbol_code.clone();
                         match strategy_event_sender.send(StrategyEvent::OrderEvents(order_update_event.clone())).await {
                             Ok(_) => {}
-                            Err(e) => eprintln!("{}", e)
+                            Err(e) => tracing::error!("{}", e)
                         }
                     }
                 }
@@ -52,11 +62,11 @@ pub(crate) fn live_order_handler(
                          order.state = OrderState::Filled;
                          closed_order_cache.insert(order_id.clone(), order.clone());
                          //println!("{}", order_update_event);
-                         ledger_service.update_or_create_position(&account, symbol_name.clone(), symbol_code.clone(), quantity, side.clone(), time_utc, *price, tag.to_string(), None, order_id).await;
+                         ledger_service.update_or_create_position(&account, symbol_name.clone(), symbol_code.clone(), quantity, side.clone(), time_utc, *price, tag.to_string(), None, order_id, PositionTarget::from_order(&order)).await;
 
                          match strategy_event_sender.send(StrategyEvent::OrderEvents(order_update_event.clone())).await {
                              Ok(_) => {}
-                             Err(e) => eprintln!("{}", e)
+                             Err(e) => tracing::error!("{}", e)
                          }
                     }
                 }
@@ -71,10 +81,10 @@ pub(crate) fn live_order_handler(
                        order.quantity_filled += quantity;
                        order.quantity_open -= quantity;
                        order.time_filled_utc = Some(time.clone());
-                       ledger_service.update_or_create_position(&account, symbol_name.clone(), symbol_code.clone(), quantity.clone(), side.clone(), time_utc, *price, tag.to_string(), None, order_id.clone()).await;
+                       ledger_service.update_or_create_position(&account, symbol_name.clone(), symbol_code.clone(), quantity.clone(), side.clone(), time_utc, *price, tag.to_string(), None, order_id.clone(), PositionTarget::from_order(&order)).await;
                        match strategy_event_sender.send(StrategyEvent::OrderEvents(order_update_event.clone())).await {
                            Ok(_) => {}
-                           Err(e) => eprintln!("{}", e)
+                           Err(e) => tracing::error!("{}", e)
                        }
                    }
                 }
@@ -86,7 +96,7 @@ pub(crate) fn live_order_handler(
                         closed_order_cache.insert(order_id.clone(), order);
                         match strategy_event_sender.send(StrategyEvent::OrderEvents(order_update_event.clone())).await {
                             Ok(_) => {}
-                            Err(e) => eprintln!("{}", e)
+                            Err(e) => tracing::error!("{}", e)
                         }
                     }
                 }
@@ -98,35 +108,34 @@ pub(crate) fn live_order_handler(
                         closed_order_cache.insert(order_id.clone(), order);
                         match strategy_event_sender.send(StrategyEvent::OrderEvents(order_update_event.clone())).await {
                             Ok(_) => {}
-                            Err(e) => eprintln!("{}", e)
+                            Err(e) => tracing::error!("{}", e)
                         }
                     }
                 }
                 OrderUpdateEvent::OrderUpdated { order_id, symbol_code, update_type,.. } => {
                     if let Some(mut order) = open_order_cache.get_mut(order_id) {
                         order.symbol_code = symbol_code.clone();
-                        match &update_type {
-                            OrderUpdateType::LimitPrice(price) => order.limit_price = Some(price.clone()),
-                            OrderUpdateType::TriggerPrice(price) => order.trigger_price = Some(price.clone()),
-                            OrderUpdateType::Quantity(quantity) => order.quantity_open = quantity.clone(),
-                        }
+                        order.apply_update(update_type);
                         match strategy_event_sender.send(StrategyEvent::OrderEvents(order_update_event.clone())).await {
                             Ok(_) => {}
-                            Err(e) => eprintln!("{}", e)
+                            Err(e) => tracing::error!("{}", e)
                         }
                     }
                 }
-                OrderUpdateEvent::OrderUpdateRejected { order_id, .. } => {
-                    //todo not sure if we remove here, depends if update id is its own order
-                    if let Some((order_id, order)) = open_order_cache.remove(order_id) {
-                        closed_order_cache.insert(order_id.clone(), order);
-                        match strategy_event_sender.send(StrategyEvent::OrderEvents(order_update_event.clone())).await {
-                            Ok(_) => {}
-                            Err(e) => eprintln!("{}", e)
-                        }
+                // A rejected update leaves the order working unchanged.
+                OrderUpdateEvent::OrderUpdateRejected { .. } => {
+                    match strategy_event_sender.send(StrategyEvent::OrderEvents(order_update_event.clone())).await {
+                        Ok(_) => {}
+                        Err(e) => tracing::error!("{}", e)
                     }
                 }
             }
+            update_child_order(order_update_event);
+            for basket_event in basket_events(order_update_event) {
+                if let Err(e) = strategy_event_sender.send(StrategyEvent::BasketEvents(basket_event)).await {
+                    tracing::error!("{}", e)
+                }
+            }
         }
     });
 }
\ No newline at end of file
@@ -0,0 +1,86 @@
+use std::sync::RwLock;
+use dashmap::DashMap;
+use crate::standardized_types::broker_enum::Brokerage;
+use crate::standardized_types::new_types::Volume;
+use crate::standardized_types::orders::Order;
+use crate::standardized_types::subscriptions::SymbolName;
+use crate::standardized_types::symbol_info::{LotRounding, SymbolInfo};
+
+/// Validates order quantities against the lot size and minimum quantity of the `SymbolInfo`, so futures trade whole contracts,
+/// forex whole units and crypto the fractions the exchange accepts.
+///
+/// The symbol info is requested from the brokerage once per symbol, symbols without symbol info are not validated.
+pub(crate) struct LotSizes {
+    rounding: RwLock<LotRounding>,
+    symbol_info: DashMap<SymbolName, Option<SymbolInfo>>,
+}
+
+impl LotSizes {
+    pub fn new() -> Self {
+        Self {
+            rounding: RwLock::new(LotRounding::Reject),
+            symbol_info: DashMap::new(),
+        }
+    }
+
+    pub fn set_rounding(&self, rounding: LotRounding) {
+        *self.rounding.write().unwrap() = rounding;
+    }
+
+    /// Replaces the symbol info of the symbol, eg. to trade fractional units with a brokerage which accepts them.
+    pub fn set_symbol_info(&self, symbol_info: SymbolInfo) {
+        self.symbol_info.insert(symbol_info.symbol_name.clone(), Some(symbol_info));
+    }
+
+    /// Caches the symbol info of the symbol, so it is only requested once.
+    async fn symbol_info(&self, brokerage: Brokerage, symbol_name: &SymbolName) -> Option<SymbolInfo> {
+        if let Some(symbol_info) = self.symbol_info.get(symbol_name) {
+            return symbol_info.clone();
+        }
+        let symbol_info = brokerage.symbol_info(symbol_name.clone()).await.ok();
+        self.symbol_info.insert(symbol_name.clone(), symbol_info.clone());
+        symbol_info
+    }
+
+    /// The quantity rounded to the lot size of the symbol depending on the `LotRounding`, returns the reason the quantity should be rejected.
+    pub async fn validate_quantity(&self, brokerage: Brokerage, symbol_name: &SymbolName, quantity: Volume) -> Result<Volume, String> {
+        let rounding = *self.rounding.read().unwrap();
+        match self.symbol_info(brokerage, symbol_name).await {
+            Some(symbol_info) => symbol_info.validate_quantity(quantity, rounding),
+            None => Ok(quantity),
+        }
+    }
+
+    /// The lot size of the symbol, `None` if the quantity is not restricted or the symbol info is unknown.
+    pub async fn lot_size(&self, brokerage: Brokerage, symbol_name: &SymbolName) -> Option<Volume> {
+        self.symbol_info(brokerage, symbol_name).await?.lot_size
+    }
+
+    /// Validates the open quantity of the order, rounding it in place depending on the `LotRounding`.
+    pub async fn validate_order(&self, order: &mut Order) -> Result<(), String> {
+        order.quantity_open = self.validate_quantity(order.account.brokerage, &order.symbol_name, order.quantity_open).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::accounts::{Account, Currency};
+    use crate::standardized_types::enums::OrderSide;
+
+    #[tokio::test]
+    async fn test_validate_order_quantity() {
+        let lot_sizes = LotSizes::new();
+        let account = Account::new(Brokerage::Test, "test".to_string());
+        lot_sizes.set_symbol_info(SymbolInfo::new("BTC-USDT".to_string(), Some(Currency::BTC), Currency::USD, dec!(0.1), dec!(0.1), 1).with_lot_size(dec!(0.001), None));
+
+        let mut order = Order::market_order("BTC-USDT".to_string(), None, &account, dec!(0.0125), OrderSide::Buy, "test".to_string(), "1".to_string(), Utc::now(), None);
+        assert!(lot_sizes.validate_order(&mut order).await.is_err());
+        lot_sizes.set_rounding(LotRounding::Down);
+        lot_sizes.validate_order(&mut order).await.unwrap();
+        assert_eq!(order.quantity_open, dec!(0.012));
+    }
+}
@@ -1,3 +1,9 @@
-pub mod backtest_matching_engine;
-pub mod live_order_matching;
-pub mod price_service;
\ No newline at end of file
+pub(crate) mod backtest_matching_engine;
+pub(crate) mod live_order_matching;
+pub(crate) mod baskets;
+pub(crate) mod price_service;
+pub mod price_bands;
+pub(crate) mod lot_sizes;
+pub mod paper_simulation;
+pub mod queue_position;
+pub mod session_levels;
@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use rand::Rng;
+use serde_derive::Deserialize;
+use crate::messages::data_server_messaging::FundForgeError;
+
+lazy_static! {
+    static ref PAPER_SIMULATION: Mutex<Option<PaperSimulation>> = Mutex::new(None);
+}
+
+/// Broker behaviour simulated for live paper trading orders, so the error handling of a strategy can be rehearsed before trading live.
+///
+/// # File Format
+/// The profile can be loaded from a toml file with `strategy.load_paper_simulation_profile()`, missing values disable that part of the simulation.
+/// ```toml
+/// # Each order request waits a random latency between the min and max before it is processed.
+/// min_latency_ms = 50
+/// max_latency_ms = 400
+/// # The probability from 0 to 1 that a new order is rejected for insufficient margin.
+/// margin_reject_probability = 0.05
+/// # New orders over this many in the last second are rejected as rate limited.
+/// max_orders_per_second = 5
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct PaperSimulationProfile {
+    #[serde(default)]
+    pub min_latency_ms: u64,
+    #[serde(default)]
+    pub max_latency_ms: u64,
+    #[serde(default)]
+    pub margin_reject_probability: f64,
+    #[serde(default)]
+    pub max_orders_per_second: Option<u32>,
+}
+
+impl PaperSimulationProfile {
+    pub fn from_file(path: &str) -> Result<Self, FundForgeError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to read paper simulation profile {}: {}", path, e)))?;
+        toml::from_str(&content)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse paper simulation profile {}: {}", path, e)))
+    }
+}
+
+struct PaperSimulation {
+    profile: PaperSimulationProfile,
+    recent_orders: VecDeque<Instant>,
+}
+
+impl PaperSimulation {
+    /// The reason a new order is rejected, orders which are rate limited are not counted towards the rate limit.
+    fn reject_reason(&mut self, now: Instant, margin_roll: f64) -> Option<String> {
+        if let Some(max) = self.profile.max_orders_per_second {
+            while self.recent_orders.front().is_some_and(|time| now.duration_since(*time) >= Duration::from_secs(1)) {
+                self.recent_orders.pop_front();
+            }
+            if self.recent_orders.len() >= max as usize {
+                return Some(format!("Simulated rate limit: more than {} orders per second", max));
+            }
+            self.recent_orders.push_back(now);
+        }
+        if margin_roll < self.profile.margin_reject_probability {
+            return Some("Simulated rejection: insufficient margin".to_string());
+        }
+        None
+    }
+}
+
+/// Sets the profile used by the paper matching engine, `None` disables the simulation.
+pub(crate) fn set_paper_simulation(profile: Option<PaperSimulationProfile>) {
+    *PAPER_SIMULATION.lock().unwrap() = profile.map(|profile| PaperSimulation { profile, recent_orders: VecDeque::new() });
+}
+
+/// A random latency from the profile, `None` if no simulation is set.
+pub(crate) fn simulated_latency() -> Option<Duration> {
+    let simulation = PAPER_SIMULATION.lock().unwrap();
+    let profile = &simulation.as_ref()?.profile;
+    let latency = match profile.max_latency_ms > profile.min_latency_ms {
+        true => rand::thread_rng().gen_range(profile.min_latency_ms..=profile.max_latency_ms),
+        false => profile.min_latency_ms,
+    };
+    Some(Duration::from_millis(latency))
+}
+
+/// The reason a new order should be rejected by the simulation, if any.
+pub(crate) fn simulated_rejection() -> Option<String> {
+    let mut simulation = PAPER_SIMULATION.lock().unwrap();
+    let margin_roll = rand::thread_rng().gen::<f64>();
+    simulation.as_mut()?.reject_reason(Instant::now(), margin_roll)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_rejections() {
+        let profile: PaperSimulationProfile = toml::from_str("max_orders_per_second = 2\nmargin_reject_probability = 0.1").unwrap();
+        assert_eq!(profile.max_latency_ms, 0);
+        let mut simulation = PaperSimulation { profile, recent_orders: VecDeque::new() };
+        let start = Instant::now();
+        assert_eq!(simulation.reject_reason(start, 0.5), None);
+        assert!(simulation.reject_reason(start, 0.05).unwrap().contains("insufficient margin"));
+        assert!(simulation.reject_reason(start + Duration::from_millis(500), 0.5).unwrap().contains("rate limit"));
+        assert_eq!(simulation.reject_reason(start + Duration::from_secs(1), 0.5), None);
+    }
+}
@@ -0,0 +1,248 @@
+use std::sync::RwLock;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::standardized_types::broker_enum::Brokerage;
+use crate::standardized_types::enums::OrderSide;
+use crate::standardized_types::new_types::Price;
+use crate::standardized_types::orders::{Order, OrderType};
+use crate::standardized_types::subscriptions::SymbolName;
+
+/// How order prices which are not a multiple of the tick size are handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickRounding {
+    /// Reject the order.
+    Reject,
+    /// Round to the nearest tick.
+    Nearest,
+    /// Round to the tick which makes the order less aggressive, eg. a buy limit is rounded down and a buy stop is rounded up.
+    Passive,
+}
+
+/// How order prices outside the price band are handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BandViolation {
+    /// Reject the order.
+    Reject,
+    /// Move the price to the nearest price limit.
+    Clamp,
+}
+
+/// The local validation applied to order prices before they are sent to the broker or the backtest engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceValidation {
+    pub tick_rounding: TickRounding,
+    pub band_violation: BandViolation,
+}
+
+impl Default for PriceValidation {
+    fn default() -> Self {
+        Self {
+            tick_rounding: TickRounding::Nearest,
+            band_violation: BandViolation::Reject,
+        }
+    }
+}
+
+/// The daily price limit of a symbol as a percentage of the reference price, the settlement or close of the previous trading day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceBand {
+    pub percent: Decimal,
+}
+
+/// The exchange price limits known for futures, CME Group equity index futures are limited to 7% above or below the reference price outside of regular trading hours.
+/// The wider regular trading hours limits are not modeled, so during regular trading hours these bands are conservative.
+fn default_price_band(symbol_name: &str) -> Option<PriceBand> {
+    match symbol_name {
+        "ES" | "MES" | "NQ" | "MNQ" | "RTY" | "M2K" | "EMD" | "YM" | "MYM" => Some(PriceBand { percent: dec!(7) }),
+        _ => None
+    }
+}
+
+/// The trading date of the time, futures trading days start at 17:00 Chicago time.
+fn trading_date(time: DateTime<Utc>) -> NaiveDate {
+    (time.with_timezone(&chrono_tz::America::Chicago) + Duration::hours(7)).date_naive()
+}
+
+#[derive(Clone, Debug)]
+struct SessionPrices {
+    trading_date: NaiveDate,
+    reference: Option<Price>,
+    last_price: Price,
+}
+
+/// Tracks the reference price of each symbol and validates orders against the tick size and the price band.
+///
+/// The reference price is the last price of the previous trading day seen by the strategy, or the price set with `strategy.set_reference_price()`.
+/// Until a reference price is known, order prices are only checked against the tick size.
+pub(crate) struct PriceBands {
+    bands: DashMap<SymbolName, Option<PriceBand>>,
+    validation: RwLock<PriceValidation>,
+    tick_sizes: DashMap<SymbolName, Price>,
+    sessions: DashMap<SymbolName, SessionPrices>,
+}
+
+impl PriceBands {
+    pub fn new() -> Self {
+        Self {
+            bands: DashMap::new(),
+            validation: RwLock::new(PriceValidation::default()),
+            tick_sizes: DashMap::new(),
+            sessions: DashMap::new(),
+        }
+    }
+
+    pub fn set_validation(&self, validation: PriceValidation) {
+        *self.validation.write().unwrap() = validation;
+    }
+
+    /// Sets the price band for the symbol, `None` removes any band including the exchange default.
+    pub fn set_band(&self, symbol_name: SymbolName, band: Option<PriceBand>) {
+        self.bands.insert(symbol_name, band);
+    }
+
+    pub fn set_reference_price(&self, symbol_name: SymbolName, reference: Price, time: DateTime<Utc>) {
+        let mut session = self.sessions.entry(symbol_name).or_insert(SessionPrices { trading_date: trading_date(time), reference: None, last_price: reference });
+        session.trading_date = trading_date(time);
+        session.reference = Some(reference);
+    }
+
+    /// Records the last price, when the trading date changes the last price of the previous day becomes the reference price.
+    pub fn update_price(&self, symbol_name: &SymbolName, price: Price, time: DateTime<Utc>) {
+        let date = trading_date(time);
+        match self.sessions.get_mut(symbol_name) {
+            Some(mut session) => {
+                if session.trading_date < date {
+                    session.reference = Some(session.last_price);
+                    session.trading_date = date;
+                }
+                session.last_price = price;
+            }
+            None => {
+                self.sessions.insert(symbol_name.clone(), SessionPrices { trading_date: date, reference: None, last_price: price });
+            }
+        }
+    }
+
+    fn band(&self, symbol_name: &SymbolName) -> Option<PriceBand> {
+        match self.bands.get(symbol_name) {
+            Some(band) => *band,
+            None => default_price_band(symbol_name)
+        }
+    }
+
+    /// The lower and upper price limits for the current trading day, rounded inside the band to the tick size when it is known.
+    pub fn limits(&self, symbol_name: &SymbolName) -> Option<(Price, Price)> {
+        let band = self.band(symbol_name)?;
+        let reference = self.sessions.get(symbol_name)?.reference?;
+        let distance = reference * band.percent / dec!(100);
+        let (lower, upper) = (reference - distance, reference + distance);
+        match self.tick_sizes.get(symbol_name) {
+            Some(tick_size) if *tick_size > dec!(0) => Some(((lower / *tick_size).ceil() * *tick_size, (upper / *tick_size).floor() * *tick_size)),
+            _ => Some((lower, upper))
+        }
+    }
+
+    /// Caches the tick size of the symbol, so it is only requested once.
+    pub async fn tick_size(&self, brokerage: Brokerage, symbol_name: &SymbolName) -> Option<Price> {
+        if let Some(tick_size) = self.tick_sizes.get(symbol_name) {
+            return Some(*tick_size);
+        }
+        let tick_size = brokerage.symbol_info(symbol_name.clone()).await.ok()?.tick_size;
+        self.tick_sizes.insert(symbol_name.clone(), tick_size);
+        Some(tick_size)
+    }
+
+    /// True if the market is locked at the price limit on the side of the order, a buy can not fill when the market is limit up and a sell can not fill when it is limit down.
+    pub fn is_locked(&self, symbol_name: &SymbolName, side: OrderSide, market_price: Price) -> bool {
+        match self.limits(symbol_name) {
+            Some((lower, upper)) => match side {
+                OrderSide::Buy => market_price >= upper,
+                OrderSide::Sell => market_price <= lower,
+            },
+            None => false
+        }
+    }
+
+    /// Limits a fill price to the price band, no fills are possible beyond the price limits.
+    pub fn clamp_fill(&self, symbol_name: &SymbolName, price: Price) -> Price {
+        match self.limits(symbol_name) {
+            Some((lower, upper)) => price.max(lower).min(upper),
+            None => price
+        }
+    }
+
+    /// Validates the limit and trigger prices of the order, rounding or clamping them in place depending on the `PriceValidation`.
+    /// Returns the reason the order should be rejected.
+    pub async fn validate_order(&self, order: &mut Order) -> Result<(), String> {
+        let tick_size = self.tick_size(order.account.brokerage, &order.symbol_name).await;
+        // Stop triggers are passive on the opposite side of the market to limit prices.
+        let is_stop = matches!(order.order_type, OrderType::StopMarket | OrderType::StopLimit);
+        let limit_price = match order.limit_price {
+            Some(price) => Some(self.validate_price(&order.symbol_name, "Limit", price, tick_size, order.side, false)?),
+            None => None
+        };
+        let trigger_price = match order.trigger_price {
+            Some(price) => Some(self.validate_price(&order.symbol_name, "Trigger", price, tick_size, order.side, is_stop)?),
+            None => None
+        };
+        order.limit_price = limit_price;
+        order.trigger_price = trigger_price;
+        Ok(())
+    }
+
+    fn validate_price(&self, symbol_name: &SymbolName, name: &str, price: Price, tick_size: Option<Price>, side: OrderSide, is_stop: bool) -> Result<Price, String> {
+        let validation = *self.validation.read().unwrap();
+        let mut price = price;
+        if let Some(tick_size) = tick_size.filter(|tick_size| *tick_size > dec!(0)) {
+            let ticks = price / tick_size;
+            if ticks != ticks.trunc() {
+                let round_down = matches!((side, is_stop), (OrderSide::Buy, false) | (OrderSide::Sell, true));
+                price = match validation.tick_rounding {
+                    TickRounding::Reject => return Err(format!("{} price {} is not a multiple of the {} tick size {}", name, price, symbol_name, tick_size)),
+                    TickRounding::Nearest => ticks.round() * tick_size,
+                    TickRounding::Passive if round_down => ticks.floor() * tick_size,
+                    TickRounding::Passive => ticks.ceil() * tick_size,
+                };
+            }
+        }
+        if let Some((lower, upper)) = self.limits(symbol_name) {
+            if price < lower || price > upper {
+                price = match validation.band_violation {
+                    BandViolation::Reject => return Err(format!("{} price {} is outside the {} price band {} to {}", name, price, symbol_name, lower, upper)),
+                    BandViolation::Clamp => price.max(lower).min(upper),
+                };
+            }
+        }
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_price_band_limits_and_rounding() {
+        let bands = PriceBands::new();
+        let symbol_name = "MES".to_string();
+        bands.tick_sizes.insert(symbol_name.clone(), dec!(0.25));
+        // 16:00 Chicago on Monday, then 18:00 Chicago which is Tuesday's trading day.
+        bands.update_price(&symbol_name, dec!(5000), Utc.with_ymd_and_hms(2024, 6, 3, 21, 0, 0).unwrap());
+        assert_eq!(bands.limits(&symbol_name), None);
+        bands.update_price(&symbol_name, dec!(5010), Utc.with_ymd_and_hms(2024, 6, 3, 23, 0, 0).unwrap());
+        assert_eq!(bands.limits(&symbol_name), Some((dec!(4650), dec!(5350))));
+        assert!(bands.is_locked(&symbol_name, OrderSide::Buy, dec!(5350)));
+        assert!(!bands.is_locked(&symbol_name, OrderSide::Sell, dec!(5350)));
+        assert_eq!(bands.clamp_fill(&symbol_name, dec!(5400)), dec!(5350));
+
+        bands.set_validation(PriceValidation { tick_rounding: TickRounding::Passive, band_violation: BandViolation::Clamp });
+        assert_eq!(bands.validate_price(&symbol_name, "Limit", dec!(5000.1), Some(dec!(0.25)), OrderSide::Buy, false), Ok(dec!(5000)));
+        assert_eq!(bands.validate_price(&symbol_name, "Trigger", dec!(5000.1), Some(dec!(0.25)), OrderSide::Buy, true), Ok(dec!(5000.25)));
+        assert_eq!(bands.validate_price(&symbol_name, "Limit", dec!(5500), Some(dec!(0.25)), OrderSide::Sell, false), Ok(dec!(5350)));
+        bands.set_validation(PriceValidation::default());
+        assert!(bands.validate_price(&symbol_name, "Limit", dec!(5500), Some(dec!(0.25)), OrderSide::Sell, false).is_err());
+    }
+}
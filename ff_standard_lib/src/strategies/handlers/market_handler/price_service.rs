@@ -6,16 +6,27 @@ use rust_decimal_macros::dec;
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::books::BookLevel;
 use crate::standardized_types::enums::OrderSide;
-use crate::standardized_types::new_types::Price;
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::orders::TriggerSource;
 use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
 use crate::standardized_types::time_slices::TimeSlice;
 use crate::standardized_types::base_data::tick::Aggressor;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::strategies::handlers::market_handler::lot_sizes::LotSizes;
+use crate::strategies::handlers::market_handler::price_bands::PriceBands;
+use crate::strategies::handlers::market_handler::queue_position::QueuePositions;
+use crate::strategies::handlers::market_handler::session_levels::SessionLevelsTracker;
 
 pub struct MarketPriceService {
     bid_books: DashMap<SymbolName, BTreeMap<u16, BookLevel>>,
     ask_books: DashMap<SymbolName, BTreeMap<u16, BookLevel>>,
     has_quotes: DashMap<SymbolName, bool>,
     last_price: DashMap<SymbolName, Price>,
+    traded_volume: DashMap<SymbolName, Volume>,
+    pub(crate) price_bands: PriceBands,
+    pub(crate) lot_sizes: LotSizes,
+    pub(crate) queue_positions: QueuePositions,
+    pub(crate) session_levels: SessionLevelsTracker,
 }
 
 impl MarketPriceService {
@@ -25,58 +36,85 @@ impl MarketPriceService {
             ask_books: DashMap::new(),
             has_quotes: DashMap::new(),
             last_price: DashMap::new(),
+            traded_volume: DashMap::new(),
+            price_bands: PriceBands::new(),
+            lot_sizes: LotSizes::new(),
+            queue_positions: QueuePositions::new(),
+            session_levels: SessionLevelsTracker::new(),
         }
     }
 
     pub fn update_market_data(&self, time_slice: Arc<TimeSlice>) {
         for base_data in time_slice.iter() {
+            self.queue_positions.update(base_data);
+            self.session_levels.update(base_data);
             match base_data {
                 BaseDataEnum::Candle(candle) => {
-                    self.last_price.insert(candle.symbol.name.clone(), candle.close);
+                    self.set_last_price(&candle.symbol.name, candle.close);
+                    self.add_traded_volume(&candle.symbol.name, candle.volume);
+                    self.price_bands.update_price(&candle.symbol.name, candle.close, candle.time_closed_utc());
                 }
                 BaseDataEnum::QuoteBar(quotebar) => {
                     let symbol_name = &quotebar.symbol.name;
+                    self.price_bands.update_price(symbol_name, quotebar.bid_close, quotebar.time_closed_utc());
                     if self.has_quotes.contains_key(symbol_name) {
                         continue;
                     }
-
-                    let mut bid_book = self.bid_books.entry(symbol_name.clone()).or_insert_with(BTreeMap::new);
-                    let mut ask_book = self.ask_books.entry(symbol_name.clone()).or_insert_with(BTreeMap::new);
-
-                    bid_book.insert(0, BookLevel::new(0, quotebar.bid_close, dec!(0.0)));
-                    ask_book.insert(0, BookLevel::new(0, quotebar.ask_close, dec!(0.0)));
+                    set_top_of_book(&self.bid_books, symbol_name, BookLevel::new(0, quotebar.bid_close, dec!(0.0)));
+                    set_top_of_book(&self.ask_books, symbol_name, BookLevel::new(0, quotebar.ask_close, dec!(0.0)));
                 }
                 BaseDataEnum::Tick(tick) => {
                     let symbol_name = &tick.symbol.name;
-                    self.last_price.insert(symbol_name.clone(), tick.price);
+                    self.set_last_price(symbol_name, tick.price);
+                    self.add_traded_volume(symbol_name, tick.volume);
+                    self.price_bands.update_price(symbol_name, tick.price, tick.time_utc());
 
                     if tick.aggressor != Aggressor::None && !self.has_quotes.contains_key(symbol_name) {
-                        let mut bid_book = self.bid_books.entry(symbol_name.clone()).or_insert_with(BTreeMap::new);
-                        let mut ask_book = self.ask_books.entry(symbol_name.clone()).or_insert_with(BTreeMap::new);
-
                         match tick.aggressor {
-                            Aggressor::Buy => ask_book.insert(0, BookLevel::new(0, tick.price, dec!(0.0))),
-                            Aggressor::Sell => bid_book.insert(0, BookLevel::new(0, tick.price, dec!(0.0))),
-                            _ => None,
+                            Aggressor::Buy => set_top_of_book(&self.ask_books, symbol_name, BookLevel::new(0, tick.price, dec!(0.0))),
+                            Aggressor::Sell => set_top_of_book(&self.bid_books, symbol_name, BookLevel::new(0, tick.price, dec!(0.0))),
+                            _ => {}
                         };
                     }
                 }
                 BaseDataEnum::Quote(quote) => {
                     let symbol_name = &quote.symbol.name;
+                    self.price_bands.update_price(symbol_name, quote.bid, quote.time_utc());
                     if !self.has_quotes.contains_key(symbol_name) {
                         self.has_quotes.insert(symbol_name.clone(), true);
-                        let mut bid_book = self.bid_books.entry(symbol_name.clone()).or_insert_with(BTreeMap::new);
-                        let mut ask_book = self.ask_books.entry(symbol_name.clone()).or_insert_with(BTreeMap::new);
-
-                        bid_book.insert(0, BookLevel::new(0, quote.bid, quote.bid_volume));
-                        ask_book.insert(0, BookLevel::new(0, quote.ask, quote.ask_volume));
+                        set_top_of_book(&self.bid_books, symbol_name, BookLevel::new(0, quote.bid, quote.bid_volume));
+                        set_top_of_book(&self.ask_books, symbol_name, BookLevel::new(0, quote.ask, quote.ask_volume));
                     }
                 }
-                _ => eprintln!("Market Price Service: Incorrect data type in Market Updates: {}", base_data.base_data_type())
+                _ => tracing::warn!("Market Price Service: Incorrect data type in Market Updates: {}", base_data.base_data_type())
             }
         }
     }
 
+    /// Updates the last price in place, the symbol name is only cloned the first time the symbol is seen.
+    fn set_last_price(&self, symbol_name: &SymbolName, price: Price) {
+        match self.last_price.get_mut(symbol_name) {
+            Some(mut last_price) => *last_price = price,
+            None => {
+                self.last_price.insert(symbol_name.clone(), price);
+            }
+        }
+    }
+
+    fn add_traded_volume(&self, symbol_name: &SymbolName, volume: Volume) {
+        match self.traded_volume.get_mut(symbol_name) {
+            Some(mut traded_volume) => *traded_volume += volume,
+            None => {
+                self.traded_volume.insert(symbol_name.clone(), volume);
+            }
+        }
+    }
+
+    /// The volume of the ticks and candles received for the symbol, `None` if there was no tick or candle data for the symbol.
+    pub fn traded_volume(&self, symbol_name: &SymbolName) -> Option<Volume> {
+        self.traded_volume.get(symbol_name).map(|volume| *volume)
+    }
+
     pub fn get_market_price(&self, order_side: OrderSide, symbol_name: &SymbolName, symbol_code: &SymbolCode) -> Option<Decimal> {
         let order_book = match order_side {
             OrderSide::Buy => self.ask_books.get(symbol_code).or_else(|| self.ask_books.get(symbol_name)),
@@ -94,6 +132,34 @@ impl MarketPriceService {
         }
     }
 
+    /// The price compared to the trigger price of an order, if the data has no price for the trigger source the market price of the order side is used.
+    pub fn get_trigger_price(&self, trigger_source: TriggerSource, order_side: OrderSide, symbol_name: &SymbolName, symbol_code: &SymbolCode) -> Option<Price> {
+        let price = match trigger_source {
+            TriggerSource::Last => self.last_price.get(symbol_name).map(|price| *price),
+            TriggerSource::BidAsk => None,
+            TriggerSource::Midpoint => {
+                let bid = self.get_market_price(OrderSide::Sell, symbol_name, symbol_code);
+                let ask = self.get_market_price(OrderSide::Buy, symbol_name, symbol_code);
+                match (bid, ask) {
+                    (Some(bid), Some(ask)) => Some((bid + ask) / dec!(2)),
+                    _ => None
+                }
+            }
+        };
+        price.or_else(|| self.get_market_price(order_side, symbol_name, symbol_code))
+    }
+
+    /// The volume on the book of the order side at the price, `None` if the book has no volume at the price.
+    pub fn book_volume_at(&self, order_side: OrderSide, symbol_name: &SymbolName, symbol_code: &SymbolCode, price: Price) -> Option<Volume> {
+        let order_book = match order_side {
+            OrderSide::Buy => self.bid_books.get(symbol_code).or_else(|| self.bid_books.get(symbol_name)),
+            OrderSide::Sell => self.ask_books.get(symbol_code).or_else(|| self.ask_books.get(symbol_name)),
+        }?;
+        order_book.values()
+            .find(|level| level.price == price && level.volume > dec!(0))
+            .map(|level| level.volume)
+    }
+
     pub fn estimate_fill_price(&self, order_side: OrderSide, symbol_name: &SymbolName, symbol_code: &SymbolCode, volume: Decimal) -> Option<Decimal> {
         let order_book = match order_side {
             OrderSide::Buy => self.ask_books.get(symbol_code).or_else(|| self.ask_books.get(symbol_name)),
@@ -190,3 +256,14 @@ impl MarketPriceService {
     }
 }
 
+/// Sets the top level of the book in place, the symbol name is only cloned the first time the symbol is seen.
+fn set_top_of_book(books: &DashMap<SymbolName, BTreeMap<u16, BookLevel>>, symbol_name: &SymbolName, level: BookLevel) {
+    match books.get_mut(symbol_name) {
+        Some(mut book) => {
+            book.insert(0, level);
+        }
+        None => {
+            books.insert(symbol_name.clone(), BTreeMap::from([(0, level)]));
+        }
+    }
+}
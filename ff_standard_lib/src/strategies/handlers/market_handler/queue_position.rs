@@ -0,0 +1,203 @@
+use std::sync::RwLock;
+use dashmap::DashMap;
+use rust_decimal_macros::dec;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::enums::OrderSide;
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::orders::{Order, OrderId};
+use crate::standardized_types::subscriptions::SymbolName;
+
+/// How the backtest matching engine fills resting limit orders.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum LimitFillModel {
+    /// Limit orders fill as soon as the market price reaches the limit price.
+    #[default]
+    Simple,
+    /// Limit orders join the back of the queue at their limit price and only fill once the volume queued ahead of them has traded.
+    ///
+    /// The volume ahead is the book volume at the limit price when the order is accepted, or `default_queue` if the book has no volume at the price.
+    /// Each trade at the limit price reduces the volume ahead, the traded volume left over fills the order, so large orders can be partially filled.
+    /// When the market trades through the limit price the queue is cleared and the order fills in full.
+    /// With bar data only trading through the limit fills the order, since the volume traded at the limit price is unknown.
+    /// Orders which are marketable when placed take liquidity and fill the same as the `Simple` model.
+    QueuePosition { default_queue: Volume },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct QueuedOrder {
+    symbol_name: SymbolName,
+    side: OrderSide,
+    limit_price: Price,
+    /// The volume queued ahead of the order, negative once more than the queue has traded, the surplus can fill the order.
+    volume_ahead: Volume,
+    traded_through: bool,
+}
+
+impl QueuedOrder {
+    /// True if the price is better than the limit price for the opposite side, so the market has traded through the queue.
+    fn is_through(&self, price: Price) -> bool {
+        match self.side {
+            OrderSide::Buy => price < self.limit_price,
+            OrderSide::Sell => price > self.limit_price,
+        }
+    }
+
+    fn update(&mut self, data: &BaseDataEnum) {
+        match data {
+            BaseDataEnum::Tick(tick) => {
+                if self.is_through(tick.price) {
+                    self.traded_through = true;
+                } else if tick.price == self.limit_price {
+                    self.volume_ahead -= tick.volume;
+                }
+            }
+            BaseDataEnum::Quote(quote) => {
+                let opposite = match self.side {
+                    OrderSide::Buy => quote.ask,
+                    OrderSide::Sell => quote.bid,
+                };
+                if self.is_through(opposite) {
+                    self.traded_through = true;
+                }
+            }
+            BaseDataEnum::Candle(candle) if candle.is_closed => {
+                let extreme = match self.side {
+                    OrderSide::Buy => candle.low,
+                    OrderSide::Sell => candle.high,
+                };
+                if self.is_through(extreme) {
+                    self.traded_through = true;
+                }
+            }
+            BaseDataEnum::QuoteBar(bar) if bar.is_closed => {
+                let extreme = match self.side {
+                    OrderSide::Buy => bar.ask_low,
+                    OrderSide::Sell => bar.bid_high,
+                };
+                if self.is_through(extreme) {
+                    self.traded_through = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn fillable(&self, quantity_open: Volume) -> Volume {
+        match self.traded_through {
+            true => quantity_open,
+            false => (-self.volume_ahead).max(dec!(0)).min(quantity_open),
+        }
+    }
+}
+
+/// The queue position of the resting limit orders of a backtest, see `LimitFillModel::QueuePosition`.
+pub(crate) struct QueuePositions {
+    model: RwLock<LimitFillModel>,
+    queues: DashMap<OrderId, QueuedOrder>,
+}
+
+impl QueuePositions {
+    pub fn new() -> Self {
+        Self {
+            model: RwLock::new(LimitFillModel::default()),
+            queues: DashMap::new(),
+        }
+    }
+
+    pub fn set_model(&self, model: LimitFillModel) {
+        *self.model.write().unwrap() = model;
+        if model == LimitFillModel::Simple {
+            self.queues.clear();
+        }
+    }
+
+    /// Places a resting limit order at the back of the queue, `book_volume` is the volume on the book at the limit price, if known.
+    pub fn join(&self, order: &Order, book_volume: Option<Volume>) {
+        let default_queue = match *self.model.read().unwrap() {
+            LimitFillModel::QueuePosition { default_queue } => default_queue,
+            LimitFillModel::Simple => return,
+        };
+        let limit_price = match order.limit_price {
+            Some(price) => price,
+            None => return,
+        };
+        self.queues.insert(order.id.clone(), QueuedOrder {
+            symbol_name: order.symbol_name.clone(),
+            side: order.side,
+            limit_price,
+            volume_ahead: book_volume.filter(|volume| *volume > dec!(0)).unwrap_or(default_queue),
+            traded_through: false,
+        });
+    }
+
+    pub fn update(&self, data: &BaseDataEnum) {
+        if self.queues.is_empty() {
+            return;
+        }
+        let symbol_name = &data.symbol().name;
+        for mut queued in self.queues.iter_mut() {
+            if &queued.symbol_name == symbol_name {
+                queued.update(data);
+            }
+        }
+    }
+
+    /// The volume of the order which can be filled, `None` if the order is not queued and fills with the `Simple` model.
+    pub fn fillable(&self, order_id: &OrderId, quantity_open: Volume) -> Option<Volume> {
+        self.queues.get(order_id).map(|queued| queued.fillable(quantity_open))
+    }
+
+    /// Records a fill of a queued order, the filled volume is no longer available to the order.
+    pub fn filled(&self, order_id: &OrderId, volume: Volume) {
+        if let Some(mut queued) = self.queues.get_mut(order_id) {
+            queued.volume_ahead += volume;
+        }
+    }
+
+    /// Removes the queues of the orders which are no longer open.
+    pub fn retain(&self, is_open: impl Fn(&OrderId) -> bool) {
+        self.queues.retain(|order_id, _| is_open(order_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::standardized_types::accounts::Account;
+    use crate::standardized_types::base_data::tick::{Aggressor, Tick};
+    use crate::standardized_types::broker_enum::Brokerage;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::orders::TimeInForce;
+    use crate::standardized_types::subscriptions::Symbol;
+
+    #[test]
+    fn test_queue_position_fills() {
+        let queues = QueuePositions::new();
+        let account = Account::new(Brokerage::Test, "TestAccount".to_string());
+        let order = Order::limit_order("MNQ".to_string(), None, &account, dec!(3), OrderSide::Buy, "test".to_string(), "1".to_string(), Utc::now(), dec!(100), TimeInForce::GTC, None);
+        let symbol = Symbol::new("MNQ".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME));
+        let tick = |price, volume| BaseDataEnum::Tick(Tick::new(symbol.clone(), price, "2024-06-03 14:30:00 UTC".to_string(), volume, Aggressor::Sell));
+
+        // the simple model does not queue orders
+        queues.join(&order, Some(dec!(5)));
+        assert_eq!(queues.fillable(&order.id, dec!(3)), None);
+
+        queues.set_model(LimitFillModel::QueuePosition { default_queue: dec!(10) });
+        queues.join(&order, Some(dec!(5)));
+        queues.update(&tick(dec!(100), dec!(4)));
+        queues.update(&tick(dec!(100.25), dec!(20)));
+        assert_eq!(queues.fillable(&order.id, dec!(3)), Some(dec!(0)));
+        queues.update(&tick(dec!(100), dec!(3)));
+        assert_eq!(queues.fillable(&order.id, dec!(3)), Some(dec!(2)));
+        queues.filled(&order.id, dec!(2));
+        assert_eq!(queues.fillable(&order.id, dec!(1)), Some(dec!(0)));
+        queues.update(&tick(dec!(99.75), dec!(1)));
+        assert_eq!(queues.fillable(&order.id, dec!(1)), Some(dec!(1)));
+
+        queues.retain(|_| false);
+        assert_eq!(queues.fillable(&order.id, dec!(1)), None);
+    }
+}
@@ -0,0 +1,209 @@
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use dashmap::DashMap;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::market_hours::TradingHours;
+use crate::standardized_types::new_types::Price;
+use crate::standardized_types::subscriptions::SymbolName;
+
+/// How the session levels of a symbol are tracked, see `strategy.track_session_levels()`.
+///
+/// Sessions are separated by the session close of the `trading_hours`, so the CME 17:00 - 16:00 CT session is one session.
+/// The prices from the session start until the `regular_open` are the overnight session, the session open, high and low are from the regular open onwards
+/// and the opening range is the first `opening_range` of the regular session.
+#[derive(Clone, Debug)]
+pub struct SessionLevelSettings {
+    pub trading_hours: TradingHours,
+    /// The open of the regular session in the time zone of the trading hours, eg. 08:30 for CME equity index futures,
+    /// `None` starts the regular session at the session start, so there is no overnight session.
+    pub regular_open: Option<NaiveTime>,
+    pub opening_range: Duration,
+}
+
+impl SessionLevelSettings {
+    pub fn new(trading_hours: TradingHours, opening_range: Duration) -> Self {
+        Self {
+            trading_hours,
+            regular_open: None,
+            opening_range,
+        }
+    }
+
+    pub fn with_regular_open(mut self, regular_open: NaiveTime) -> Self {
+        self.regular_open = Some(regular_open);
+        self
+    }
+}
+
+/// The levels of the current session of a symbol, the prices are `None` until the part of the session they belong to has traded.
+/// Candles, quote bars and footprints are added when they close, quote bars use the bid prices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionLevels {
+    /// The close of the previous session.
+    pub session_start: DateTime<Utc>,
+    pub regular_open: DateTime<Utc>,
+    pub opening_range_end: DateTime<Utc>,
+    /// The first price of the regular session.
+    pub session_open: Option<Price>,
+    pub session_high: Option<Price>,
+    pub session_low: Option<Price>,
+    pub overnight_high: Option<Price>,
+    pub overnight_low: Option<Price>,
+    pub opening_range_high: Option<Price>,
+    pub opening_range_low: Option<Price>,
+    /// The close time of the last data added.
+    pub last_update: DateTime<Utc>,
+}
+
+impl SessionLevels {
+    fn new(session_start: DateTime<Utc>, settings: &SessionLevelSettings) -> Self {
+        let regular_open = match settings.regular_open {
+            Some(regular_open) => next_time(&settings.trading_hours, session_start, regular_open),
+            None => session_start,
+        };
+        Self {
+            session_start,
+            regular_open,
+            opening_range_end: regular_open + settings.opening_range,
+            session_open: None,
+            session_high: None,
+            session_low: None,
+            overnight_high: None,
+            overnight_low: None,
+            opening_range_high: None,
+            opening_range_low: None,
+            last_update: session_start,
+        }
+    }
+
+    /// True once the data received has reached the end of the opening range.
+    pub fn is_opening_range_complete(&self) -> bool {
+        self.last_update >= self.opening_range_end
+    }
+
+    /// `time` is the open time of the data, so a bar which opens inside the opening range belongs to it.
+    fn update(&mut self, open: Price, high: Price, low: Price, time: DateTime<Utc>, time_closed: DateTime<Utc>) {
+        self.last_update = self.last_update.max(time_closed);
+        if time < self.regular_open {
+            extend(&mut self.overnight_high, &mut self.overnight_low, high, low);
+            return;
+        }
+        self.session_open.get_or_insert(open);
+        extend(&mut self.session_high, &mut self.session_low, high, low);
+        if time < self.opening_range_end {
+            extend(&mut self.opening_range_high, &mut self.opening_range_low, high, low);
+        }
+    }
+}
+
+fn extend(highest: &mut Option<Price>, lowest: &mut Option<Price>, high: Price, low: Price) {
+    *highest = Some(highest.map_or(high, |highest| highest.max(high)));
+    *lowest = Some(lowest.map_or(low, |lowest| lowest.min(low)));
+}
+
+/// The first time of day after the time in the time zone of the trading hours.
+fn next_time(trading_hours: &TradingHours, after: DateTime<Utc>, time_of_day: NaiveTime) -> DateTime<Utc> {
+    let mut date = after.with_timezone(&trading_hours.timezone).date_naive();
+    loop {
+        if let Some(time) = trading_hours.timezone.from_local_datetime(&date.and_time(time_of_day)).earliest() {
+            let time = time.to_utc();
+            if time > after {
+                return time;
+            }
+        }
+        date += Duration::days(1);
+    }
+}
+
+struct SessionTracker {
+    settings: SessionLevelSettings,
+    levels: Option<SessionLevels>,
+}
+
+/// Tracks the session levels of the symbols added with `strategy.track_session_levels()`, updated with each time slice.
+pub(crate) struct SessionLevelsTracker {
+    trackers: DashMap<SymbolName, SessionTracker>,
+}
+
+impl SessionLevelsTracker {
+    pub fn new() -> Self {
+        Self {
+            trackers: DashMap::new(),
+        }
+    }
+
+    /// Starts tracking the symbol, replacing the settings and levels if the symbol is already tracked.
+    pub fn track(&self, symbol_name: SymbolName, settings: SessionLevelSettings) {
+        self.trackers.insert(symbol_name, SessionTracker { settings, levels: None });
+    }
+
+    pub fn untrack(&self, symbol_name: &SymbolName) {
+        self.trackers.remove(symbol_name);
+    }
+
+    pub fn levels(&self, symbol_name: &SymbolName) -> Option<SessionLevels> {
+        self.trackers.get(symbol_name)?.levels.clone()
+    }
+
+    pub fn update(&self, base_data: &BaseDataEnum) {
+        if self.trackers.is_empty() || !base_data.is_closed() {
+            return;
+        }
+        let (symbol_name, open, high, low) = match base_data {
+            BaseDataEnum::Tick(tick) => (&tick.symbol.name, tick.price, tick.price, tick.price),
+            BaseDataEnum::Candle(candle) => (&candle.symbol.name, candle.open, candle.high, candle.low),
+            BaseDataEnum::QuoteBar(bar) => (&bar.symbol.name, bar.bid_open, bar.bid_high, bar.bid_low),
+            BaseDataEnum::Footprint(footprint) => (&footprint.symbol.name, footprint.open, footprint.high, footprint.low),
+            _ => return,
+        };
+        let mut tracker = match self.trackers.get_mut(symbol_name) {
+            Some(tracker) => tracker,
+            None => return,
+        };
+        let time = base_data.time_utc();
+        if !tracker.settings.trading_hours.is_market_open(time) {
+            return;
+        }
+        let session_start = match tracker.settings.trading_hours.last_close(time) {
+            Some(session_start) => session_start,
+            None => return,
+        };
+        if tracker.levels.as_ref().is_none_or(|levels| levels.session_start != session_start) {
+            tracker.levels = Some(SessionLevels::new(session_start, &tracker.settings));
+        }
+        if let Some(levels) = tracker.levels.as_mut() {
+            levels.update(open, high, low, time, base_data.time_closed_utc());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use crate::product_maps::rithmic::maps::CME_HOURS;
+
+    #[test]
+    fn test_session_levels() {
+        let settings = SessionLevelSettings::new(CME_HOURS, Duration::minutes(30))
+            .with_regular_open(NaiveTime::from_hms_opt(8, 30, 0).unwrap());
+        let time = |time: &str| DateTime::<Utc>::from_str(time).unwrap();
+        // The Wednesday session starts at the Tuesday 16:00 CT close, 21:00 UTC in June, and the regular session opens at 08:30 CT.
+        let mut levels = SessionLevels::new(time("2024-06-04T21:00:00Z"), &settings);
+        assert_eq!(levels.regular_open, time("2024-06-05T13:30:00Z"));
+        assert_eq!(levels.opening_range_end, time("2024-06-05T14:00:00Z"));
+
+        levels.update(dec!(100), dec!(105), dec!(95), time("2024-06-05T02:00:00Z"), time("2024-06-05T02:05:00Z"));
+        levels.update(dec!(101), dec!(110), dec!(99), time("2024-06-05T13:30:00Z"), time("2024-06-05T13:35:00Z"));
+        assert!(!levels.is_opening_range_complete());
+        levels.update(dec!(108), dec!(108), dec!(97), time("2024-06-05T13:55:00Z"), time("2024-06-05T14:00:00Z"));
+        assert!(levels.is_opening_range_complete());
+        levels.update(dec!(100), dec!(115), dec!(100), time("2024-06-05T15:00:00Z"), time("2024-06-05T15:05:00Z"));
+
+        assert_eq!((levels.overnight_high, levels.overnight_low), (Some(dec!(105)), Some(dec!(95))));
+        assert_eq!(levels.session_open, Some(dec!(101)));
+        assert_eq!((levels.session_high, levels.session_low), (Some(dec!(115)), Some(dec!(97))));
+        assert_eq!((levels.opening_range_high, levels.opening_range_low), (Some(dec!(110)), Some(dec!(97))));
+    }
+}
@@ -3,5 +3,8 @@ pub(crate) mod registry_handler;
 pub(crate) mod subscription_handler;
 pub(crate) mod timed_events_handler;
 pub(crate) mod indicator_handler;
-pub(crate) mod market_handler;
-pub(crate) mod live_warmup;
\ No newline at end of file
+pub mod market_handler;
+pub(crate) mod live_warmup;
+pub(crate) mod chart_publisher;pub(crate) mod schedule_supervisor;
+pub(crate) mod universe_selector;
+pub(crate) mod algo_executor;
@@ -0,0 +1,315 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use tokio::sync::mpsc::Sender;
+use crate::messages::data_server_messaging::DataServerRequest;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::enums::StrategyMode;
+use crate::standardized_types::new_types::Volume;
+use crate::standardized_types::orders::{Order, OrderId, OrderRequest};
+use crate::strategies::client_features::connection_types::ConnectionType;
+use crate::strategies::client_features::request_handler::{send_request, StrategyRequest};
+use crate::strategies::client_features::server_connections::is_warmup_complete;
+use crate::strategies::historical_time::get_backtest_time;
+use crate::strategies::handlers::market_handler::backtest_matching_engine::BackTestEngineMessage;
+use crate::strategies::ledgers::drawdown_guard::{drawdown_policy, DrawdownGuard, RiskStage};
+use crate::strategies::ledgers::ledger_service::LedgerService;
+use crate::strategies::strategy_events::StrategyEvent;
+use crate::strategies::lifecycle::{LifecycleHooks, SessionClose};
+use crate::strategies::notifications::{notify_engine_event, NotificationLevel};
+use crate::strategies::trading_schedule::{ScheduleEvent, TradingSchedule};
+
+/// Switches the strategy between active and idle as the windows of its `TradingSchedule` open and close, moves the accounts through the stages of their `DrawdownPolicy`
+/// and applies the `SessionClose` and `LifecycleHooks` of the strategy.
+/// The supervisor is updated with the strategy time by the `TimedEventHandler`, so it follows the historical time in backtests.
+pub(crate) struct ScheduleSupervisor {
+    mode: StrategyMode,
+    schedule: RwLock<Option<TradingSchedule>>,
+    active: AtomicBool,
+    accounts: Vec<Account>,
+    open_order_cache: Arc<DashMap<OrderId, Order>>,
+    historical_message_sender: Option<Sender<BackTestEngineMessage>>,
+    strategy_event_sender: Sender<StrategyEvent>,
+    dry_run: Arc<AtomicBool>,
+    ledger_service: Arc<LedgerService>,
+    drawdown_guards: DashMap<Account, DrawdownGuard>,
+    session_close: RwLock<Option<SessionCloseState>>,
+    hooks: RwLock<Option<Arc<dyn LifecycleHooks>>>,
+    flatten_on_shutdown: AtomicBool,
+    is_shutdown: AtomicBool,
+    statements: RwLock<Option<StatementState>>,
+}
+
+struct StatementState {
+    folder: String,
+    /// The end of the last statement, the next statement starts here.
+    from: DateTime<Utc>,
+}
+
+struct SessionCloseState {
+    session_close: SessionClose,
+    next_close: Option<DateTime<Utc>>,
+    next_flatten: Option<DateTime<Utc>>,
+}
+
+impl ScheduleSupervisor {
+    pub fn new(
+        mode: StrategyMode,
+        accounts: Vec<Account>,
+        open_order_cache: Arc<DashMap<OrderId, Order>>,
+        historical_message_sender: Option<Sender<BackTestEngineMessage>>,
+        strategy_event_sender: Sender<StrategyEvent>,
+        dry_run: Arc<AtomicBool>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        Self {
+            mode,
+            schedule: RwLock::new(None),
+            active: AtomicBool::new(true),
+            accounts,
+            open_order_cache,
+            historical_message_sender,
+            strategy_event_sender,
+            dry_run,
+            ledger_service,
+            drawdown_guards: DashMap::new(),
+            session_close: RwLock::new(None),
+            hooks: RwLock::new(None),
+            flatten_on_shutdown: AtomicBool::new(false),
+            is_shutdown: AtomicBool::new(false),
+            statements: RwLock::new(None),
+        }
+    }
+
+    /// Replaces the schedule, the current state is taken from the new schedule without flattening.
+    pub fn set_schedule(&self, schedule: Option<TradingSchedule>, time: DateTime<Utc>) {
+        let active = schedule.as_ref().is_none_or(|schedule| schedule.is_active(time));
+        *self.schedule.write().unwrap() = schedule;
+        self.active.store(active, Ordering::SeqCst);
+    }
+
+    /// True if there is no schedule or the time is inside a trading window.
+    pub fn is_active(&self, time: DateTime<Utc>) -> bool {
+        self.schedule.read().unwrap().as_ref().is_none_or(|schedule| schedule.is_active(time))
+    }
+
+    pub async fn update_time(&self, time: DateTime<Utc>) {
+        // The state only changes after warm up, so the strategy is not flattened by historical windows.
+        if !is_warmup_complete() {
+            return;
+        }
+        self.update_drawdown_guards(time).await;
+        self.update_session_close(time).await;
+        let (active, flatten_outside) = match self.schedule.read().unwrap().as_ref() {
+            Some(schedule) => (schedule.is_active(time), schedule.flatten_outside),
+            None => return
+        };
+        if self.active.swap(active, Ordering::SeqCst) == active {
+            return;
+        }
+        let event = match active {
+            true => ScheduleEvent::Active { time: time.to_string() },
+            false => {
+                if flatten_outside {
+                    self.flatten(&self.accounts).await;
+                }
+                ScheduleEvent::Idle { time: time.to_string(), flattened: flatten_outside }
+            }
+        };
+        self.send_event(event).await;
+    }
+
+    /// Replaces the `SessionClose`, the next session close and flatten are scheduled from the time.
+    pub fn set_session_close(&self, session_close: Option<SessionClose>, time: DateTime<Utc>) {
+        *self.session_close.write().unwrap() = session_close.map(|session_close| SessionCloseState {
+            next_close: session_close.trading_hours.next_close(time),
+            next_flatten: session_close.next_flatten(time),
+            session_close,
+        });
+    }
+
+    pub fn set_hooks(&self, hooks: Option<Arc<dyn LifecycleHooks>>) {
+        *self.hooks.write().unwrap() = hooks;
+    }
+
+    pub fn set_flatten_on_shutdown(&self, flatten_on_shutdown: bool) {
+        self.flatten_on_shutdown.store(flatten_on_shutdown, Ordering::SeqCst);
+    }
+
+    /// Writes the account statements to the folder at each session close in live modes and at shutdown, the first statement starts at the time.
+    pub fn set_account_statements(&self, folder: Option<String>, time: DateTime<Utc>) {
+        *self.statements.write().unwrap() = folder.map(|folder| StatementState { folder, from: time });
+    }
+
+    /// Flattens the accounts if `flatten_on_shutdown` is set, writes the account statements and calls `LifecycleHooks::on_shutdown()`, only the first call has any effect.
+    pub async fn shutdown(&self, reason: String) {
+        if self.is_shutdown.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        if self.flatten_on_shutdown.load(Ordering::SeqCst) {
+            tracing::info!("Schedule Supervisor: Flattening the accounts on shutdown");
+            self.flatten(&self.accounts).await;
+        }
+        let time = match self.mode {
+            StrategyMode::Backtest => get_backtest_time(),
+            _ => Utc::now(),
+        };
+        self.write_statements(time).await;
+        let hooks = self.hooks.read().unwrap().clone();
+        if let Some(hooks) = hooks {
+            hooks.on_shutdown(reason).await;
+        }
+    }
+
+    async fn update_session_close(&self, time: DateTime<Utc>) {
+        let (flatten, close) = {
+            let mut state = self.session_close.write().unwrap();
+            let state = match state.as_mut() {
+                Some(state) => state,
+                None => return
+            };
+            let flatten = state.next_flatten.is_some_and(|next_flatten| time >= next_flatten);
+            if flatten {
+                state.next_flatten = state.session_close.next_flatten(time);
+            }
+            let close = state.next_close.filter(|next_close| time >= *next_close);
+            if close.is_some() {
+                state.next_close = state.session_close.trading_hours.next_close(time);
+            }
+            (flatten, close)
+        };
+        if flatten {
+            tracing::info!("Schedule Supervisor: Flattening the accounts at {}", time);
+            self.flatten(&self.accounts).await;
+            self.send_event(ScheduleEvent::AutoFlattened { time: time.to_string() }).await;
+        }
+        if let Some(close) = close {
+            let hooks = self.hooks.read().unwrap().clone();
+            if let Some(hooks) = hooks {
+                hooks.on_session_close(close).await;
+            }
+            if self.mode != StrategyMode::Backtest {
+                self.write_statements(close).await;
+            }
+            self.send_event(ScheduleEvent::SessionClose { time: close.to_string() }).await;
+        }
+    }
+
+    /// Writes the statement of each account for the period since the last statement and passes it to `LifecycleHooks::on_account_statement()`.
+    async fn write_statements(&self, time: DateTime<Utc>) {
+        let (folder, from) = {
+            let mut state = self.statements.write().unwrap();
+            let state = match state.as_mut() {
+                Some(state) => state,
+                None => return
+            };
+            let from = state.from;
+            state.from = time;
+            (state.folder.clone(), from)
+        };
+        let hooks = self.hooks.read().unwrap().clone();
+        for account in &self.accounts {
+            let statement = match self.ledger_service.account_statement(account, from, time) {
+                Some(statement) => statement,
+                None => continue
+            };
+            match statement.export_html(&folder) {
+                Ok(path) => {
+                    tracing::info!("Schedule Supervisor: Wrote the statement of {} to {}", account, path);
+                    if let Some(hooks) = &hooks {
+                        hooks.on_account_statement(&statement, &path).await;
+                    }
+                }
+                Err(e) => tracing::error!("Schedule Supervisor: Failed to write the statement of {}: {}", account, e),
+            }
+        }
+    }
+
+    async fn send_event(&self, event: ScheduleEvent) {
+        if let Err(e) = self.strategy_event_sender.send(StrategyEvent::ScheduleEvent(event)).await {
+            tracing::error!("Schedule Supervisor: Failed to send event: {}", e);
+        }
+    }
+
+    /// The stage of the `DrawdownPolicy` of the account, `RiskStage::Normal` if the account has no policy.
+    pub fn risk_stage(&self, account: &Account) -> RiskStage {
+        match drawdown_policy(account) {
+            Some(_) => self.drawdown_guards.get(account).map(|guard| guard.stage).unwrap_or(RiskStage::Normal),
+            None => RiskStage::Normal
+        }
+    }
+
+    /// The quantity allowed for an entry on the account at its current `RiskStage`.
+    pub fn allowed_quantity(&self, account: &Account, quantity: Volume) -> Volume {
+        match drawdown_policy(account) {
+            Some(policy) => policy.allowed_quantity(self.risk_stage(account), quantity),
+            None => quantity
+        }
+    }
+
+    async fn update_drawdown_guards(&self, time: DateTime<Utc>) {
+        for account in &self.accounts {
+            let policy = match drawdown_policy(account) {
+                Some(policy) => policy,
+                None => {
+                    self.drawdown_guards.remove(account);
+                    continue;
+                }
+            };
+            let equity = self.ledger_service.balance(account) + self.ledger_service.open_pnl(account);
+            let change = self.drawdown_guards.entry(account.clone())
+                .or_insert_with(DrawdownGuard::new)
+                .update(&policy, equity, time);
+            let (stage, drawdown_percent) = match change {
+                Some(change) => change,
+                None => continue
+            };
+            tracing::warn!("{}: Drawdown of {}%, risk stage is now {:?}", account, drawdown_percent, stage);
+            if self.mode != StrategyMode::Backtest {
+                let level = match stage {
+                    RiskStage::Flattened => NotificationLevel::Critical,
+                    _ => NotificationLevel::Warning,
+                };
+                notify_engine_event(level, format!("{}: Drawdown of {}%, risk stage is now {:?}", account, drawdown_percent, stage));
+            }
+            if stage == RiskStage::Flattened {
+                self.flatten(std::slice::from_ref(account)).await;
+            }
+            let event = StrategyEvent::RiskStageChanged { account: account.clone(), stage, drawdown_percent, time: time.to_string() };
+            if let Err(e) = self.strategy_event_sender.send(event).await {
+                tracing::error!("Schedule Supervisor: Failed to send event: {}", e);
+            }
+        }
+    }
+
+    /// Cancels the open orders and flattens the accounts.
+    async fn flatten(&self, accounts: &[Account]) {
+        let cancels: Vec<OrderRequest> = self.open_order_cache.iter()
+            .filter(|order| accounts.contains(&order.account))
+            .map(|order| OrderRequest::Cancel { order_id: order.id.clone(), account: order.account.clone() })
+            .collect();
+        for order_request in cancels {
+            self.send_order_request(order_request).await;
+        }
+        for account in accounts {
+            self.send_order_request(OrderRequest::FlattenAllFor { account: account.clone() }).await;
+        }
+    }
+
+    async fn send_order_request(&self, order_request: OrderRequest) {
+        if self.dry_run.load(Ordering::SeqCst) {
+            tracing::info!("Dry Run: Schedule Supervisor: {:?}", order_request);
+            return;
+        }
+        if self.mode == StrategyMode::Live {
+            let connection_type = ConnectionType::Broker(order_request.brokerage());
+            let request = StrategyRequest::OneWay(connection_type, DataServerRequest::OrderRequest { request: order_request });
+            send_request(request).await;
+        } else if let Some(historical_message_sender) = &self.historical_message_sender {
+            if let Err(e) = historical_message_sender.send(BackTestEngineMessage::OrderRequest(order_request)).await {
+                tracing::error!("Schedule Supervisor: Failed to send order request: {}", e);
+            }
+        }
+    }
+}
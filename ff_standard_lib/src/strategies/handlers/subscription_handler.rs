@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::sync::{Arc};
 use ahash::AHashMap;
-use crate::strategies::consolidators::consolidator_enum::{ConsolidatedData, ConsolidatorEnum};
+use crate::strategies::consolidators::consolidator_enum::{is_valid_warmup_source, ConsolidatedData, ConsolidatorEnum};
 use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use crate::standardized_types::base_data::base_data_type::BaseDataType;
 use crate::standardized_types::enums::{StrategyMode, PrimarySubscription};
@@ -16,6 +16,7 @@ use futures::StreamExt;
 use tokio::sync::RwLock;
 use crate::strategies::client_features::server_connections::{is_warmup_complete};
 use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::base_data::footprint::Footprint;
 use crate::standardized_types::base_data::fundamental::Fundamental;
 use crate::standardized_types::base_data::quote::Quote;
 use crate::standardized_types::base_data::quotebar::QuoteBar;
@@ -25,8 +26,10 @@ use crate::standardized_types::resolution::Resolution;
 use crate::strategies::strategy_events::StrategyEvent;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc::Sender;
-use crate::standardized_types::base_data::history::{get_compressed_historical_data};
+use crate::standardized_types::base_data::warmup_batch::{get_warmup_data, prefetch_warmup_data};
 use crate::standardized_types::market_hours::TradingHours;
+use crate::standardized_types::economic_calendar::EconomicCalendar;
+use crate::messages::data_server_messaging::FundForgeError;
 
 /// Manages all subscriptions for a strategy. each strategy has its own subscription handler.
 pub struct SubscriptionHandler {
@@ -43,9 +46,13 @@ pub struct SubscriptionHandler {
     tick_history: DashMap<DataSubscription, RollingWindow<Tick>>,
     quote_history: DashMap<DataSubscription, RollingWindow<Quote>>,
     fundamental_history: DashMap<DataSubscription, RollingWindow<Fundamental>>,
+    footprint_history: DashMap<DataSubscription, RollingWindow<Footprint>>,
     open_candles: DashMap<DataSubscription, Candle>,
     open_bars: DashMap<DataSubscription, QuoteBar>,
-    strategy_event_sender: Sender<StrategyEvent>
+    open_footprints: DashMap<DataSubscription, Footprint>,
+    strategy_event_sender: Sender<StrategyEvent>,
+    /// The scheduled releases from the economic calendar subscriptions, see `Fundamental::economic_calendar()`.
+    economic_calendar: EconomicCalendar,
 }
 
 impl SubscriptionHandler {
@@ -63,8 +70,11 @@ impl SubscriptionHandler {
             tick_history: Default::default(),
             quote_history: Default::default(),
             fundamental_history: Default::default(),
+            footprint_history: Default::default(),
             open_candles: Default::default(),
             open_bars: Default::default(),
+            open_footprints: Default::default(),
+            economic_calendar: Default::default(),
         }
     }
 
@@ -92,13 +102,95 @@ impl SubscriptionHandler {
         broadcast: bool,
         hours: Option<TradingHours>,
     ) {
+        let events = self.add_subscription(primary_source, new_subscription, current_time, fill_forward, history_to_retain, broadcast, hours).await;
+        for event in events {
+            let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(event)).await;
+        }
+    }
+
+    /// Subscribes to the subscriptions together, see `FundForgeStrategy::subscribe_many()`.
+    /// After the initial warm up, the warmup data of every new primary subscription and consolidator is downloaded in a single request before the subscriptions are created.
+    /// The primary subscriptions are broadcast once, and the events of every subscription are sent after the last subscription is created.
+    pub async fn subscribe_many(&self, subscriptions: Vec<DataSubscription>, current_time: DateTime<Utc>, history_to_retain: usize) {
+        let mut events = vec![];
+        let mut resolved = vec![];
+        for subscription in subscriptions {
+            match streamed_primary_source(&subscription).await {
+                Ok(primary_source) => resolved.push((primary_source, subscription)),
+                Err(msg) => events.push(DataSubscriptionEvent::FailedToSubscribe(subscription, msg)),
+            }
+        }
+        if is_warmup_complete() {
+            let mut requests = vec![];
+            for (primary_source, subscription) in &resolved {
+                requests.extend(self.warmup_requests(*primary_source, subscription, current_time, history_to_retain).await);
+            }
+            if let Err(e) = prefetch_warmup_data(requests).await {
+                tracing::warn!("Failed to download the warmup data, subscriptions will warm up individually: {}", e);
+            }
+        }
+        for (primary_source, subscription) in resolved {
+            events.extend(self.add_subscription(primary_source, subscription, current_time, false, history_to_retain, false, None).await);
+        }
+        let subscriptions = self.primary_subscriptions().await;
+        let _ = self.primary_subscriptions_broadcaster.send(subscriptions);
+        for event in events {
+            let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(event)).await;
+        }
+    }
+
+    /// The base subscriptions and ranges downloaded to warm up the subscription, primary subscriptions which already exist are not warmed up again.
+    async fn warmup_requests(
+        &self,
+        primary_source: Option<PrimarySubscription>,
+        subscription: &DataSubscription,
+        to_time: DateTime<Utc>,
+        history_to_retain: usize,
+    ) -> Vec<(DataSubscription, DateTime<Utc>, DateTime<Utc>)> {
+        if subscription.base_data_type == BaseDataType::Fundamentals {
+            return vec![];
+        }
+        let existing_primaries = self.primary_subscriptions().await;
+        let mut requests = vec![];
+        match resolve_primary_source(primary_source, subscription) {
+            Some(primary) => {
+                let new_primary = DataSubscription::new(subscription.symbol.name.clone(), subscription.symbol.data_vendor, primary.resolution, primary.base_data_type, subscription.market_type)
+                    .with_fallback_vendors(subscription.fallback_vendors.clone());
+                if !existing_primaries.contains(&new_primary) {
+                    requests.push((new_primary.clone(), primary_warmup_from_time(&new_primary, to_time, history_to_retain), to_time));
+                }
+                requests.extend(ConsolidatorEnum::warmup_request(subscription, to_time, history_to_retain as i32).await);
+            }
+            None => {
+                if !existing_primaries.contains(subscription) {
+                    requests.push((subscription.clone(), primary_warmup_from_time(subscription, to_time, history_to_retain), to_time));
+                }
+            }
+        }
+        requests
+    }
+
+    /// Subscribes to the subscription and returns the events to send to the strategy.
+    #[allow(clippy::too_many_arguments)]
+    async fn add_subscription(
+        &self,
+        primary_source: Option<PrimarySubscription>,
+        new_subscription: DataSubscription,
+        current_time: DateTime<Utc>,
+        fill_forward: bool,
+        history_to_retain: usize,
+        broadcast: bool,
+        hours: Option<TradingHours>,
+    ) -> Vec<DataSubscriptionEvent> {
+        let mut events = vec![];
         let mut strategy_subscriptions = self.strategy_subscriptions.write().await;
-        if !strategy_subscriptions.contains(&new_subscription) {
+        // Only a subscription added here is removed again if subscribing fails.
+        let is_new_subscription = !strategy_subscriptions.contains(&new_subscription);
+        if is_new_subscription {
             strategy_subscriptions.push(new_subscription.clone());
         } else {
             let msg = format!("{}: Already subscribed: {}", new_subscription.symbol.data_vendor, new_subscription.symbol.name);
-            let event = DataSubscriptionEvent::FailedToSubscribe(new_subscription.clone(), msg);
-            let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(event)).await;
+            events.push(DataSubscriptionEvent::FailedToSubscribe(new_subscription.clone(), msg));
         }
 
         let _ = strategy_subscriptions.deref();
@@ -113,18 +205,35 @@ impl SubscriptionHandler {
                 Ok(_) => {}
                 Err(_) => {}
             }
-            let event = DataSubscriptionEvent::Subscribed(new_subscription.clone());
-            let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(event)).await;
+            events.push(DataSubscriptionEvent::Subscribed(new_subscription.clone()));
         }
 
         if !self.symbol_subscriptions.contains_key(&new_subscription.symbol) {
-            let symbol_handler = SymbolSubscriptionHandler::new(
-                new_subscription.symbol.clone(),
-            ).await;
-            self.symbol_subscriptions.insert(new_subscription.symbol.clone(), symbol_handler);
+            match SymbolSubscriptionHandler::new(new_subscription.symbol.clone()).await {
+                Ok(symbol_handler) => {
+                    self.symbol_subscriptions.insert(new_subscription.symbol.clone(), symbol_handler);
+                }
+                Err(e) => {
+                    if is_new_subscription {
+                        strategy_subscriptions.retain(|subscription| subscription != &new_subscription);
+                    }
+                    events.push(DataSubscriptionEvent::FailedToSubscribe(new_subscription.clone(), e.to_string()));
+                    return events;
+                }
+            }
         }
 
-        let symbol_subscriptions = self.symbol_subscriptions.get(&new_subscription.symbol).unwrap();
+        let primary_source = resolve_primary_source(primary_source, &new_subscription);
+        let symbol_subscriptions = match self.symbol_subscriptions.get(&new_subscription.symbol) {
+            Some(symbol_subscriptions) => symbol_subscriptions,
+            None => {
+                if is_new_subscription {
+                    strategy_subscriptions.retain(|subscription| subscription != &new_subscription);
+                }
+                events.push(DataSubscriptionEvent::FailedToSubscribe(new_subscription.clone(), "Symbol handler was removed while subscribing".to_string()));
+                return events;
+            }
+        };
         let windows = symbol_subscriptions.value().subscribe(
                 primary_source,
                 new_subscription.clone(),
@@ -195,6 +304,17 @@ impl SubscriptionHandler {
                                 }
                             }
                         }
+                        BaseDataType::Footprints => {
+                            self.footprint_history.insert(subscription.clone(), RollingWindow::new(history_to_retain));
+                            if let Some(mut footprint_window) = self.footprint_history.get_mut(&subscription) {
+                                for data in window.history {
+                                    match data {
+                                        BaseDataEnum::Footprint(footprint) => footprint_window.value_mut().add(footprint),
+                                        _ => {}
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
                 if broadcast {
@@ -204,13 +324,28 @@ impl SubscriptionHandler {
                         Err(_) => {}
                     }
                 }
-                let event = DataSubscriptionEvent::Subscribed(new_subscription.clone());
-                let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(event)).await;
+                events.push(DataSubscriptionEvent::Subscribed(new_subscription.clone()));
             }
             Err(e) => {
-                let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(e)).await;
+                if is_new_subscription {
+                    strategy_subscriptions.retain(|subscription| subscription != &new_subscription);
+                }
+                events.push(e)
+            },
+        }
+        events
+    }
+
+    /// Unsubscribes from the subscriptions together, the primary subscriptions are broadcast once, subscriptions the strategy is not subscribed to are ignored.
+    pub async fn unsubscribe_many(&self, subscriptions: Vec<DataSubscription>) {
+        for subscription in subscriptions {
+            if !self.strategy_subscriptions.read().await.contains(&subscription) {
+                continue;
             }
+            self.unsubscribe(subscription, false).await;
         }
+        let subscriptions = self.primary_subscriptions().await;
+        let _ = self.primary_subscriptions_broadcaster.send(subscriptions);
     }
 
     /// Unsubscribes from a data subscription
@@ -237,14 +372,15 @@ impl SubscriptionHandler {
             return;
         }
 
-        let event =  self.symbol_subscriptions.get(&subscription.symbol).unwrap().unsubscribe(&subscription).await;
+        let event = match self.symbol_subscriptions.get(&subscription.symbol) {
+            Some(symbol_subscriptions) => symbol_subscriptions.unsubscribe(&subscription).await,
+            None => DataSubscriptionEvent::FailedUnSubscribed(subscription.clone(), format!("No subscriptions for symbol: {}", subscription.symbol.name)),
+        };
         let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(event)).await;
 
         let mut strategy_subscriptions = self.strategy_subscriptions.write().await;
         strategy_subscriptions.retain(|x| x != &subscription);
-        if self.symbol_subscriptions.get(&subscription.symbol).unwrap().active_count() == 0 {
-            self.symbol_subscriptions.remove(&subscription.symbol);
-        }
+        self.symbol_subscriptions.remove_if(&subscription.symbol, |_, symbol_subscriptions| symbol_subscriptions.active_count() == 0);
         match subscription.base_data_type {
             BaseDataType::Ticks => {
                 self.tick_history.remove(&subscription);
@@ -261,6 +397,10 @@ impl SubscriptionHandler {
             BaseDataType::Fundamentals => {
                 self.fundamental_history.remove(&subscription);
             }
+            BaseDataType::Footprints => {
+                self.footprint_history.remove(&subscription);
+                self.open_footprints.remove(&subscription);
+            }
         }
         if broadcast {
             let subscriptions = self.primary_subscriptions().await;
@@ -330,12 +470,22 @@ impl SubscriptionHandler {
                         history.add(q.clone());
                     }
                 }
-                BaseDataEnum::Fundamental(_) => {}
+                BaseDataEnum::Fundamental(fundamental) => {
+                    if let Some(events) = fundamental.economic_events() {
+                        self.economic_calendar.update(&fundamental.symbol.name, events);
+                        // Keep a week of past releases so long backtests don't accumulate the whole calendar.
+                        self.economic_calendar.prune(fundamental.time_utc() - chrono::Duration::days(7));
+                    }
+                    if let Some(mut history) = self.fundamental_history.get_mut(&fundamental.subscription()) {
+                        history.add(fundamental.clone());
+                    }
+                }
+                BaseDataEnum::Footprint(_) => {}
             }
 
             update_futures.push(async move {
                 if let Some(handler) = symbol_subscriptions.get(&symbol) {
-                    handler.update(&base_data).await
+                    handler.update(&base_data)
                 } else {
                     Vec::new()
                 }
@@ -345,6 +495,13 @@ impl SubscriptionHandler {
         let mut all_bars: BTreeMap<(DataSubscription, DateTime<Utc>), BaseDataEnum> = BTreeMap::new();
         while let Some(data) = update_futures.next().await {
             for consolidated_bars in data {
+                let consolidated_bars = match consolidated_bars {
+                    Ok(consolidated_bars) => consolidated_bars,
+                    Err(event) => {
+                        let _ = self.strategy_event_sender.send(StrategyEvent::DataSubscriptionEvent(event)).await;
+                        continue;
+                    }
+                };
                 if let Some(consolidated_bar) = consolidated_bars.closed_data {
                     let key = (consolidated_bar.subscription(), consolidated_bar.time_utc());
                     all_bars.entry(key).or_insert(consolidated_bar);
@@ -381,6 +538,11 @@ impl SubscriptionHandler {
                         rolling_window.add(fund.clone());
                     }
                 }
+                BaseDataEnum::Footprint(footprint) => {
+                    if let Some(mut rolling_window) = self.footprint_history.get_mut(&subscription) {
+                        rolling_window.add(footprint.clone());
+                    }
+                }
             }
             time_slice_bars.add(data);
         }
@@ -393,6 +555,9 @@ impl SubscriptionHandler {
                 BaseDataEnum::QuoteBar(qb) => {
                     self.open_bars.insert(subscription.clone(), qb.clone());
                 }
+                BaseDataEnum::Footprint(footprint) => {
+                    self.open_footprints.insert(subscription.clone(), footprint.clone());
+                }
                 _ => {}
             }
             time_slice_bars.add(data);
@@ -405,6 +570,10 @@ impl SubscriptionHandler {
         }
     }
 
+    pub fn economic_calendar(&self) -> &EconomicCalendar {
+        &self.economic_calendar
+    }
+
     pub fn bar_history(&self, subscription: &DataSubscription) -> Option<RollingWindow<QuoteBar>> {
         if let Some(window) = self.bar_history.get(subscription) {
             return Some(window.value().clone())
@@ -419,6 +588,13 @@ impl SubscriptionHandler {
         None
     }
 
+    pub fn footprint_history(&self, subscription: &DataSubscription) -> Option<RollingWindow<Footprint>> {
+        if let Some(window) = self.footprint_history.get(subscription) {
+            return Some(window.value().clone())
+        }
+        None
+    }
+
     pub fn tick_history(&self, subscription: &DataSubscription) -> Option<RollingWindow<Tick>> {
         if let Some(window) = self.tick_history.get(subscription) {
             return Some(window.value().clone())
@@ -447,6 +623,21 @@ impl SubscriptionHandler {
         }
     }
 
+    pub fn open_footprint(&self, subscription: &DataSubscription) -> Option<Footprint> {
+        match self.open_footprints.get(subscription) {
+            None => None,
+            Some(data) => Some(data.value().clone())
+        }
+    }
+
+    /// The open candles, quote bars and footprints of every consolidated subscription.
+    pub fn open_data(&self) -> Vec<BaseDataEnum> {
+        self.open_candles.iter().map(|candle| BaseDataEnum::Candle(candle.value().clone()))
+            .chain(self.open_bars.iter().map(|bar| BaseDataEnum::QuoteBar(bar.value().clone())))
+            .chain(self.open_footprints.iter().map(|footprint| BaseDataEnum::Footprint(footprint.value().clone())))
+            .collect()
+    }
+
     pub fn candle_index(&self, subscription: &DataSubscription, index: usize) -> Option<Candle> {
         if let Some(window) = self.candle_history.get(subscription) {
             return match window.get(index) {
@@ -467,6 +658,16 @@ impl SubscriptionHandler {
         None
     }
 
+    pub fn footprint_index(&self, subscription: &DataSubscription, index: usize) -> Option<Footprint> {
+        if let Some(window) = self.footprint_history.get(subscription) {
+            return match window.get(index) {
+                None => None,
+                Some(data) => Some(data.clone())
+            }
+        }
+        None
+    }
+
     pub fn tick_index(&self, subscription: &DataSubscription, index: usize) -> Option<Tick> {
         if let Some(window) = self.tick_history.get(subscription) {
             return match window.get(index) {
@@ -487,6 +688,46 @@ impl SubscriptionHandler {
         None
     }
 
+    /// The closed candles, quote bars or footprints retained for the subscription, newest first.
+    pub fn closed_bars(&self, subscription: &DataSubscription) -> Vec<BaseDataEnum> {
+        match subscription.base_data_type {
+            BaseDataType::Candles => self.candle_history.get(subscription)
+                .map(|window| window.history.iter().cloned().map(BaseDataEnum::Candle).collect()),
+            BaseDataType::QuoteBars => self.bar_history.get(subscription)
+                .map(|window| window.history.iter().cloned().map(BaseDataEnum::QuoteBar).collect()),
+            BaseDataType::Footprints => self.footprint_history.get(subscription)
+                .map(|window| window.history.iter().cloned().map(BaseDataEnum::Footprint).collect()),
+            _ => None
+        }.unwrap_or_default()
+    }
+
+    /// The data retained for the subscription, oldest first, `None` if the strategy does not retain history for the subscription.
+    pub fn retained_history(&self, subscription: &DataSubscription) -> Option<Vec<BaseDataEnum>> {
+        match subscription.base_data_type {
+            BaseDataType::Ticks => self.tick_history(subscription)
+                .map(|window| window.history.into_iter().rev().map(BaseDataEnum::Tick).collect()),
+            BaseDataType::Quotes => self.quote_history(subscription)
+                .map(|window| window.history.into_iter().rev().map(BaseDataEnum::Quote).collect()),
+            BaseDataType::Candles => self.candle_history(subscription)
+                .map(|window| window.history.into_iter().rev().map(BaseDataEnum::Candle).collect()),
+            BaseDataType::QuoteBars => self.bar_history(subscription)
+                .map(|window| window.history.into_iter().rev().map(BaseDataEnum::QuoteBar).collect()),
+            BaseDataType::Footprints => self.footprint_history(subscription)
+                .map(|window| window.history.into_iter().rev().map(BaseDataEnum::Footprint).collect()),
+            _ => None
+        }
+    }
+
+    /// The primary and consolidated subscriptions the strategy retains price data for.
+    pub fn retained_subscriptions(&self) -> Vec<DataSubscription> {
+        self.tick_history.iter().map(|entry| entry.key().clone())
+            .chain(self.quote_history.iter().map(|entry| entry.key().clone()))
+            .chain(self.candle_history.iter().map(|entry| entry.key().clone()))
+            .chain(self.bar_history.iter().map(|entry| entry.key().clone()))
+            .chain(self.footprint_history.iter().map(|entry| entry.key().clone()))
+            .collect()
+    }
+
     //todo need a live version of this, where we record which consolidators had data and which didnt, we update time for thise that didn't
     pub async fn update_consolidators_time(&self, time: DateTime<Utc>) -> Option<TimeSlice> {
         let symbol_subscriptions = self.symbol_subscriptions.clone();
@@ -533,6 +774,11 @@ impl SubscriptionHandler {
                                 rolling_window.add(fund.clone());
                             }
                         }
+                        BaseDataEnum::Footprint(ref footprint) => {
+                            if let Some(mut rolling_window) = self.footprint_history.get_mut(&subscription) {
+                                rolling_window.add(footprint.clone());
+                            }
+                        }
                     }
                 }
                 for base_data in data {
@@ -561,16 +807,17 @@ pub struct SymbolSubscriptionHandler {
 }
 
 impl SymbolSubscriptionHandler {
+    /// Returns an error if the data vendor resolutions for the symbol can not be retrieved.
     pub async fn new(
         symbol: Symbol,
-    ) -> Self {
-        let vendor_primary_resolutions = symbol.data_vendor.resolutions(symbol.market_type.clone()).await.unwrap();
+    ) -> Result<Self, FundForgeError> {
+        let vendor_primary_resolutions = symbol.data_vendor.resolutions(symbol.market_type.clone()).await?;
         let handler = SymbolSubscriptionHandler {
             primary_subscriptions: DashMap::new(),
             secondary_subscriptions: DashMap::new(),
             vendor_primary_resolutions,
         };
-        handler
+        Ok(handler)
     }
 
     pub fn active_count(&self) -> usize {
@@ -583,7 +830,8 @@ impl SymbolSubscriptionHandler {
         count
     }
 
-    pub async fn update(&self, base_data_enum: &BaseDataEnum) -> Vec<ConsolidatedData> {
+    /// Updates the consolidators of the data, a consolidator that can not consolidate the data returns a `DataSubscriptionEvent::ConsolidationError`.
+    pub fn update(&self, base_data_enum: &BaseDataEnum) -> Vec<Result<ConsolidatedData, DataSubscriptionEvent>> {
          // Read the secondary subscriptions
         if self.secondary_subscriptions.is_empty() {
             return vec![];
@@ -592,8 +840,9 @@ impl SymbolSubscriptionHandler {
         let sub_res = PrimarySubscription::new(base_data_enum.resolution().clone(), base_data_enum.base_data_type());
         if let Some(mut base_data_consoldiators) = self.secondary_subscriptions.get_mut(&sub_res){
             let mut data = vec![];
-            for (_, consolidator) in base_data_consoldiators.iter_mut() {
-                let consolidated_data = consolidator.update(&base_data_enum);
+            for (subscription, consolidator) in base_data_consoldiators.iter_mut() {
+                let consolidated_data = consolidator.update(&base_data_enum)
+                    .map_err(|e| DataSubscriptionEvent::ConsolidationError(subscription.clone(), e.to_string()));
                 data.push(consolidated_data);
             }
             return data
@@ -648,20 +897,12 @@ impl SymbolSubscriptionHandler {
         if let Some(primary) = primary_source {
             // Handle primary subscription if it doesn't exist
             if !self.primary_subscriptions.contains_key(&primary) {
-                let new_primary = DataSubscription::new(new_subscription.symbol.name.clone(), new_subscription.symbol.data_vendor.clone(), primary.resolution, primary.base_data_type, new_subscription.market_type.clone());
+                let new_primary = DataSubscription::new(new_subscription.symbol.name.clone(), new_subscription.symbol.data_vendor.clone(), primary.resolution, primary.base_data_type, new_subscription.market_type.clone())
+                    .with_fallback_vendors(new_subscription.fallback_vendors.clone());
                 self.primary_subscriptions.insert(new_primary.subscription_resolution_type(), new_primary.clone());
                 if is_warmed_up {
-                    let from_time = match new_primary.resolution == Resolution::Instant {
-                        true => {
-                            let subtract_duration: Duration = Duration::seconds(2) * history_to_retain as i32;
-                            warm_up_to_time - subtract_duration - Duration::days(5)
-                        }
-                        false => {
-                            let subtract_duration: Duration = new_primary.resolution.as_duration() * history_to_retain as i32;
-                            warm_up_to_time - subtract_duration - Duration::days(5)
-                        }
-                    };
-                    let data = get_compressed_historical_data(vec![new_primary.clone()], from_time, warm_up_to_time).await.unwrap_or_else(|_e| BTreeMap::new());
+                    let from_time = primary_warmup_from_time(&new_primary, warm_up_to_time, history_to_retain);
+                    let data = get_warmup_data(&new_primary, from_time, warm_up_to_time).await.unwrap_or_else(|_e| BTreeMap::new());
                     let mut history = RollingWindow::new(history_to_retain);
                     for (_, slice) in data {
                         for data in slice.iter() {
@@ -675,7 +916,8 @@ impl SymbolSubscriptionHandler {
             }
 
             // Handle secondary subscription
-            let consolidator = ConsolidatorEnum::create_consolidator(new_subscription.clone(), fill_forward.clone(), hours).await;
+            let consolidator = ConsolidatorEnum::create_consolidator(new_subscription.clone(), fill_forward.clone(), hours).await
+                .map_err(|e| DataSubscriptionEvent::FailedToSubscribe(new_subscription.clone(), e.to_string()))?;
             let (final_consolidator, window) = match is_warmed_up {
                 true => {
                     let (final_consolidator, window) = ConsolidatorEnum::warmup(consolidator, warm_up_to_time, history_to_retain as i32, strategy_mode).await;
@@ -697,17 +939,8 @@ impl SymbolSubscriptionHandler {
             if !self.primary_subscriptions.contains_key(&new_subscription.subscription_resolution_type()) {
                 self.primary_subscriptions.insert(new_subscription.subscription_resolution_type(), new_subscription.clone());
                 if is_warmed_up {
-                    let from_time = match new_subscription.resolution == Resolution::Instant {
-                        true => {
-                            let subtract_duration: Duration = chrono::Duration::seconds(2) * history_to_retain as i32;
-                            warm_up_to_time - subtract_duration - Duration::days(5)
-                        }
-                        false => {
-                            let subtract_duration: Duration = new_subscription.resolution.as_duration() * history_to_retain as i32;
-                            warm_up_to_time - subtract_duration - Duration::days(5)
-                        }
-                    };
-                    let data = get_compressed_historical_data(vec![new_subscription.clone()], from_time, warm_up_to_time).await.unwrap_or_else(|_e| BTreeMap::new());
+                    let from_time = primary_warmup_from_time(&new_subscription, warm_up_to_time, history_to_retain);
+                    let data = get_warmup_data(&new_subscription, from_time, warm_up_to_time).await.unwrap_or_else(|_e| BTreeMap::new());
                     let mut history = RollingWindow::new(history_to_retain);
                     for (_, slice) in data {
                         for data in slice.iter() {
@@ -772,4 +1005,50 @@ impl SymbolSubscriptionHandler {
     }
 }
 
+/// The primary subscription `subscribe_many()` consolidates the subscription from, the closest resolution the vendor streams, `None` if the vendor streams the subscription itself.
+async fn streamed_primary_source(subscription: &DataSubscription) -> Result<Option<PrimarySubscription>, String> {
+    let vendor = &subscription.symbol.data_vendor;
+    // Footprints are consolidated from ticks by the subscription handler.
+    let vendor_data_type = match subscription.base_data_type {
+        BaseDataType::Footprints => BaseDataType::Ticks,
+        base_data_type => base_data_type,
+    };
+    if let Ok(capabilities) = vendor.capabilities().await {
+        if !capabilities.supports(vendor_data_type) {
+            return Err(format!("{} does not provide {} data, see `strategy.best_available_subscription()`", vendor, vendor_data_type));
+        }
+    }
+    if subscription.base_data_type == BaseDataType::Footprints {
+        return Ok(None);
+    }
+    let resolutions = vendor.resolutions(subscription.market_type).await.map_err(|e| e.to_string())?;
+    if resolutions.contains(&subscription.subscription_resolution_type()) {
+        return Ok(None);
+    }
+    resolutions.into_iter()
+        .filter(|primary| {
+            let source = DataSubscription::new(subscription.symbol.name.clone(), *vendor, primary.resolution, primary.base_data_type, subscription.market_type);
+            is_valid_warmup_source(&source, subscription)
+        })
+        .max_by_key(|primary| primary.resolution)
+        .map(Some)
+        .ok_or_else(|| format!("{} does not provide data which can be consolidated into {}", vendor, subscription))
+}
+
+/// Footprints are always consolidated from 1 tick data, so each trade has an aggressor side.
+fn resolve_primary_source(primary_source: Option<PrimarySubscription>, subscription: &DataSubscription) -> Option<PrimarySubscription> {
+    match subscription.base_data_type {
+        BaseDataType::Footprints => Some(PrimarySubscription::new(Resolution::Ticks(1), BaseDataType::Ticks)),
+        _ => primary_source,
+    }
+}
 
+/// The start of the warmup range for a primary subscription, instant data is counted as one data point every 2 seconds.
+/// The range starts 5 days earlier so the history can be filled over weekends and holidays.
+fn primary_warmup_from_time(subscription: &DataSubscription, to_time: DateTime<Utc>, history_to_retain: usize) -> DateTime<Utc> {
+    let data_duration = match subscription.resolution {
+        Resolution::Instant => Duration::seconds(2),
+        resolution => resolution.as_duration(),
+    };
+    to_time - data_duration * history_to_retain as i32 - Duration::days(5)
+}
@@ -1,10 +1,13 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use chrono::{DateTime, Datelike, Duration, Timelike, Utc, Weekday};
 use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
 use tokio::task;
 use tokio::time::{interval, sleep, Duration as TokioDuration};
+use crate::strategies::handlers::schedule_supervisor::ScheduleSupervisor;
+use crate::strategies::handlers::universe_selector::UniverseSelector;
+use crate::strategies::handlers::algo_executor::AlgoExecutor;
 use crate::strategies::strategy_events::StrategyEvent;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -126,7 +129,10 @@ impl TimedEvent {
 pub struct TimedEventHandler {
     pub(crate) schedule: Arc<RwLock<Vec<TimedEvent>>>,
     last_fired: Arc<RwLock<HashMap<String, DateTime<Utc>>>>,
-    strategy_event_sender: Sender<StrategyEvent>
+    strategy_event_sender: Sender<StrategyEvent>,
+    schedule_supervisor: OnceLock<Arc<ScheduleSupervisor>>,
+    universe_selector: OnceLock<Arc<UniverseSelector>>,
+    algo_executor: OnceLock<Arc<AlgoExecutor>>,
 }
 
 impl TimedEventHandler {
@@ -134,7 +140,32 @@ impl TimedEventHandler {
         TimedEventHandler {
             schedule: Default::default(),
             last_fired: Arc::new(RwLock::new(HashMap::new())),
-            strategy_event_sender
+            strategy_event_sender,
+            schedule_supervisor: OnceLock::new(),
+            universe_selector: OnceLock::new(),
+            algo_executor: OnceLock::new(),
+        }
+    }
+
+    /// The supervisor is updated with each time update, so trading windows open and close with the strategy time.
+    pub(crate) fn set_schedule_supervisor(&self, supervisor: Arc<ScheduleSupervisor>) {
+        let _ = self.schedule_supervisor.set(supervisor);
+    }
+
+    /// The selector is updated with each time update, so universes are scanned on the strategy time.
+    pub(crate) fn set_universe_selector(&self, selector: Arc<UniverseSelector>) {
+        let _ = self.universe_selector.set(selector);
+    }
+
+    /// The executor is updated with each time update, so algos slice on the strategy time.
+    pub(crate) fn set_algo_executor(&self, executor: Arc<AlgoExecutor>) {
+        let _ = self.algo_executor.set(executor);
+    }
+
+    /// Runs the shutdown of the supervisor, see `ScheduleSupervisor::shutdown()`.
+    pub(crate) async fn shutdown(&self, reason: String) {
+        if let Some(supervisor) = self.schedule_supervisor.get() {
+            supervisor.shutdown(reason).await;
         }
     }
 
@@ -175,6 +206,15 @@ impl TimedEventHandler {
     }
 
     pub async fn update_time(&self, current_time: DateTime<Utc>) {
+        if let Some(supervisor) = self.schedule_supervisor.get() {
+            supervisor.update_time(current_time).await;
+        }
+        if let Some(selector) = self.universe_selector.get() {
+            selector.update_time(current_time).await;
+        }
+        if let Some(executor) = self.algo_executor.get() {
+            executor.update_time(current_time).await;
+        }
         let mut schedule = self.schedule.write().await;
         let mut last_fired = self.last_fired.write().await;
         if schedule.is_empty() {
@@ -205,7 +245,7 @@ impl TimedEventHandler {
                     let strategy_event = StrategyEvent::TimedEvent(event.name.clone());
                     match self.strategy_event_sender.send(strategy_event).await {
                         Ok(_) => {}
-                        Err(e) => eprintln!("Timed Event Handler: Failed to send event: {}", e)
+                        Err(e) => tracing::error!("Timed Event Handler: Failed to send event: {}", e)
                     }
                     last_fired.insert(event.name.clone(), current_time);
 
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::Sender;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::history::get_compressed_historical_data;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::enums::StrategyMode;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
+use crate::strategies::strategy_events::StrategyEvent;
+use crate::strategies::universe_selection::{UniverseChange, UniverseSelection};
+
+struct UniverseState {
+    selection: UniverseSelection,
+    members: Vec<DataSubscription>,
+    next_scan: DateTime<Utc>,
+}
+
+/// Scans the universes of the strategy as the strategy time reaches each scan, see `UniverseSelection`.
+/// Backtests scan inline so the members change at the same point of the data on every run, live strategies scan on a background task.
+pub(crate) struct UniverseSelector {
+    mode: StrategyMode,
+    subscription_handler: Arc<SubscriptionHandler>,
+    strategy_event_sender: Sender<StrategyEvent>,
+    universes: DashMap<String, Arc<Mutex<UniverseState>>>,
+}
+
+impl UniverseSelector {
+    pub fn new(mode: StrategyMode, subscription_handler: Arc<SubscriptionHandler>, strategy_event_sender: Sender<StrategyEvent>) -> Self {
+        Self {
+            mode,
+            subscription_handler,
+            strategy_event_sender,
+            universes: DashMap::new(),
+        }
+    }
+
+    /// Adds the universe, replacing a universe with the same name, the first scan runs with the next time update.
+    /// The members of a replaced universe are kept until the scan, so members which are still selected stay subscribed.
+    pub async fn add_universe(&self, selection: UniverseSelection, time: DateTime<Utc>) {
+        let members = match self.universes.remove(&selection.name) {
+            Some((_, state)) => state.lock().await.members.clone(),
+            None => vec![],
+        };
+        let name = selection.name.clone();
+        let state = UniverseState { selection, members, next_scan: time };
+        self.universes.insert(name, Arc::new(Mutex::new(state)));
+    }
+
+    /// Removes the universe and unsubscribes its members, returns false if there is no universe with the name.
+    pub async fn remove_universe(&self, name: &str) -> bool {
+        let state = match self.universes.remove(name) {
+            Some((_, state)) => state,
+            None => return false,
+        };
+        let members = state.lock().await.members.clone();
+        self.subscription_handler.unsubscribe_many(members).await;
+        true
+    }
+
+    /// The members of the universe after the last scan, highest score first.
+    pub async fn members(&self, name: &str) -> Vec<DataSubscription> {
+        let state = match self.universes.get(name) {
+            Some(state) => state.value().clone(),
+            None => return vec![],
+        };
+        let members = state.lock().await.members.clone();
+        members
+    }
+
+    pub async fn update_time(&self, time: DateTime<Utc>) {
+        if self.universes.is_empty() {
+            return;
+        }
+        let universes: Vec<Arc<Mutex<UniverseState>>> = self.universes.iter().map(|entry| entry.value().clone()).collect();
+        for universe in universes {
+            match self.mode {
+                StrategyMode::Backtest => {
+                    let mut state = universe.lock().await;
+                    if time >= state.next_scan {
+                        scan(&mut state, time, &self.subscription_handler, &self.strategy_event_sender).await;
+                    }
+                }
+                StrategyMode::Live | StrategyMode::LivePaperTrading => {
+                    // A universe which is locked is still being scanned.
+                    let mut state = match universe.try_lock_owned() {
+                        Ok(state) => state,
+                        Err(_) => continue,
+                    };
+                    if time < state.next_scan {
+                        continue;
+                    }
+                    let subscription_handler = self.subscription_handler.clone();
+                    let strategy_event_sender = self.strategy_event_sender.clone();
+                    tokio::task::spawn(async move {
+                        scan(&mut state, time, &subscription_handler, &strategy_event_sender).await;
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Ranks the candidates of the universe and updates the subscriptions of the strategy to the new members.
+/// A scan which fails to download the ranking data keeps the members until the next scan.
+async fn scan(state: &mut UniverseState, time: DateTime<Utc>, subscription_handler: &SubscriptionHandler, strategy_event_sender: &Sender<StrategyEvent>) {
+    state.next_scan = time + state.selection.scan_interval;
+    let mut ranking_subscriptions: Vec<DataSubscription> = vec![];
+    for candidate in &state.selection.candidates {
+        let ranking_subscription = state.selection.ranking_subscription(candidate);
+        if !ranking_subscriptions.contains(&ranking_subscription) {
+            ranking_subscriptions.push(ranking_subscription);
+        }
+    }
+    let data = match get_compressed_historical_data(ranking_subscriptions, time - state.selection.lookback, time).await {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::warn!("Universe {}: Failed to download the ranking data: {}", state.selection.name, e);
+            return;
+        }
+    };
+    let mut ranking_data: HashMap<DataSubscription, Vec<BaseDataEnum>> = HashMap::new();
+    for (_, slice) in data {
+        // Only data which closed by the scan time, so backtests do not rank on future data.
+        for base_data in slice.iter().filter(|base_data| base_data.time_closed_utc() <= time) {
+            ranking_data.entry(base_data.subscription()).or_default().push(base_data.clone());
+        }
+    }
+    let members = state.selection.select(&ranking_data);
+    let change = match UniverseChange::new(state.selection.name.clone(), &state.members, members.clone(), time.to_string()) {
+        Some(change) => change,
+        None => return,
+    };
+    subscription_handler.unsubscribe_many(change.removed.clone()).await;
+    subscription_handler.subscribe_many(change.added.clone(), time, state.selection.history_to_retain).await;
+    state.members = members;
+    let _ = strategy_event_sender.send(StrategyEvent::UniverseChanged(change)).await;
+}
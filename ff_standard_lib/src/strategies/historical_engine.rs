@@ -10,6 +10,9 @@ use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 use crate::standardized_types::subscriptions::DataSubscription;
 use tokio::sync::{broadcast, mpsc, Notify};
+use tokio::task::JoinHandle;
+use futures::StreamExt;
+use crate::messages::data_server_messaging::FundForgeError;
 use crate::strategies::handlers::indicator_handler::IndicatorHandler;
 use crate::strategies::handlers::market_handler::backtest_matching_engine::BackTestEngineMessage;
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
@@ -17,6 +20,7 @@ use crate::strategies::handlers::subscription_handler::SubscriptionHandler;
 use crate::strategies::handlers::timed_events_handler::TimedEventHandler;
 use crate::strategies::historical_time::update_backtest_time;
 use crate::strategies::ledgers::ledger_service::LedgerService;
+use crate::strategies::statistics::backtest_cache::{backtest_cache, cached_backtest, BacktestCacheKey};
 
 #[allow(dead_code)]
 pub(crate) struct HistoricalEngine {
@@ -85,10 +89,26 @@ impl HistoricalEngine {
         if self.mode != StrategyMode::Backtest {
             panic!("Engine: Trying to launch backtest engine in live mode");
         }
-        println!("Engine: Initializing the strategy...");
+        tracing::info!("Engine: Initializing the strategy...");
         tokio::spawn(async move {
             let warm_up_start_time = self.start_time - self.warmup_duration;
 
+            let cache_key = self.backtest_cache_key(warm_up_start_time).await;
+            if let (Some(cache), Some(key)) = (backtest_cache(), &cache_key) {
+                if cache.load(key) {
+                    tracing::info!("Engine: Found cached results for backtest {}, skipping backtest", key.hash());
+                    if let Some(cached) = cached_backtest() {
+                        cached.print();
+                    }
+                    self.timed_event_handler.shutdown("Backtest Complete (Cached)".to_string()).await;
+                    let event = StrategyEvent::ShutdownEvent("Backtest Complete (Cached)".to_string());
+                    if let Err(e) = self.strategy_event_sender.send(event).await {
+                        tracing::error!("Historical Engine: Failed to send event: {}", e);
+                    }
+                    return;
+                }
+            }
+
             match self.mode {
                 StrategyMode::Backtest => {
                     self.historical_data_feed(warm_up_start_time, self.end_time, self.buffer_resolution, self.mode).await;
@@ -96,11 +116,19 @@ impl HistoricalEngine {
                 StrategyMode::Live | StrategyMode::LivePaperTrading => panic!("Incorrect engine for Live modes"),
             }
 
+            if let (Some(cache), Some(key)) = (backtest_cache(), &cache_key) {
+                match cache.store(key, &self.ledger_service) {
+                    Ok(_) => tracing::info!("Engine: Saved results for backtest {} to cache", key.hash()),
+                    Err(e) => tracing::error!("Engine: Failed to cache backtest results: {}", e)
+                }
+            }
+
             match self.mode {
                 StrategyMode::Backtest => {
+                    self.timed_event_handler.shutdown("Backtest Complete".to_string()).await;
                     let event = StrategyEvent::ShutdownEvent("Backtest Complete".to_string());
                     if let Err(e) = self.strategy_event_sender.send(event).await {
-                        eprintln!("Historical Engine: Failed to send event: {}", e);
+                        tracing::error!("Historical Engine: Failed to send event: {}", e);
                     }
                 }
                 _ => panic!("Incorrect engine for Live modes"),
@@ -108,8 +136,27 @@ impl HistoricalEngine {
         });
     }
 
+    /// The key for the backtest results cache, `None` if the cache is not enabled, the strategy has no subscriptions or the data version could not be retrieved.
+    async fn backtest_cache_key(&self, warm_up_start_time: DateTime<Utc>) -> Option<BacktestCacheKey> {
+        let cache = backtest_cache()?;
+        let primary_subscriptions = self.subscription_handler.primary_subscriptions().await;
+        if primary_subscriptions.is_empty() {
+            tracing::warn!("Engine: The strategy has no subscriptions, results will not be cached");
+            return None;
+        }
+        let subscriptions = self.subscription_handler.subscriptions().await;
+        match cache.key(&subscriptions, primary_subscriptions, warm_up_start_time, self.end_time).await {
+            Ok(key) => Some(key),
+            Err(e) => {
+                tracing::error!("Engine: Unable to create backtest cache key, results will not be cached: {}", e);
+                None
+            }
+        }
+    }
+
     /// Feeds the historical data to the strategy, along with any events that were created.
-    /// Simulates trading with a live buffer, where we catch events for x duration before forwarding to the strategy
+    /// Simulates trading with a live buffer, where we catch events for x duration before forwarding to the strategy,
+    /// a zero buffer duration forwards each data point as its own time slice, see `next_feed_time()`.
     async fn historical_data_feed(
         &mut self,
         warm_up_start_time: DateTime<Utc>,
@@ -117,7 +164,7 @@ impl HistoricalEngine {
         buffer_duration: Duration,
         mode: StrategyMode,
     ) {
-        println!("Historical Engine: Warming up the strategy...");
+        tracing::info!("Historical Engine: Warming up the strategy...");
         // here we are looping through 1 day at a time, if the strategy updates its subscriptions we will stop the data feed, download the historical data again to include updated symbols, and resume from the next time to be processed.
         let mut warm_up_complete = false;
         let mut primary_subscriptions = loop {
@@ -125,21 +172,22 @@ impl HistoricalEngine {
             if !subscriptions.is_empty() {
                 break subscriptions;
             }
-            println!("Historical Engine: Waiting for primary subscriptions...");
+            tracing::info!("Historical Engine: Waiting for primary subscriptions...");
             tokio::time::sleep(Duration::from_millis(200)).await;
         };
 
         for subscription in &primary_subscriptions {
-            println!("Historical Engine: Primary Subscription: {}", subscription);
+            tracing::info!("Historical Engine: Primary Subscription: {}", subscription);
         }
         let strategy_subscriptions = self.subscription_handler.strategy_subscriptions().await;
         for subscription in &strategy_subscriptions {
-            println!("Historical Engine: Strategy Subscription: {}", subscription);
+            tracing::info!("Historical Engine: Strategy Subscription: {}", subscription);
         }
 
         let mut last_time = warm_up_start_time.clone();
         let mut early_return = false;
         let mut last_date = last_time.date_naive();
+        let mut prefetcher: Option<HistoryPrefetcher> = None;
         'main_loop: while last_time <= end_time {
             // Assuming `last_time` is a `DateTime<Utc>`
             if !early_return && last_time.date_naive() == last_date {
@@ -153,12 +201,29 @@ impl HistoricalEngine {
                     Utc,
                 );
             }
-            let end_of_day = last_time.date_naive().and_hms_nano_opt(23, 59, 59, 999_999_999).unwrap();
-            let to_time = Utc.from_utc_datetime(&end_of_day);
+            let to_time = end_of_day(last_time);
             //eprintln!("Historical Engine: Processing data from {} to {}", last_time, to_time);
 
-            let mut time_slices = match get_compressed_historical_data(primary_subscriptions.clone(), last_time.clone(), to_time).await {
-                Ok(time_slices) => {
+            // The prefetcher is restarted from the current time when the subscriptions change or it is out of step with the feed.
+            let history = match prefetcher.as_mut() {
+                Some(prefetcher) => prefetcher.next_day(last_time, to_time).await,
+                None => None,
+            };
+            let history = match history {
+                Some(history) => history,
+                None => {
+                    let mut restarted = HistoryPrefetcher::start(primary_subscriptions.clone(), last_time, end_time);
+                    let history = restarted.next_day(last_time, to_time).await
+                        .unwrap_or_else(|| Err(FundForgeError::ClientSideErrorDebug("History prefetcher stopped".to_string())));
+                    prefetcher = Some(restarted);
+                    history
+                }
+            };
+
+            let mut time_slices = match history {
+                Ok(mut time_slices) => {
+                    // The prefetched day can start before the feed, when the feed resumes part way through the day.
+                    time_slices = time_slices.split_off(&last_time.timestamp_nanos_opt().unwrap());
                     if time_slices.is_empty() && !self.tick_over_no_data {
                         //eprintln!("Historical Engine: No data period, weekend or holiday: skipping");
                         last_time = to_time + ChronoDuration::nanoseconds(1);
@@ -182,21 +247,21 @@ impl HistoricalEngine {
 
             let mut time = last_time;
             'day_loop: while time <= to_time {
-                time += buffer_duration;
+                time = next_feed_time(time, buffer_duration, &time_slices, to_time);
                 if !warm_up_complete {
                     if time >= self.start_time {
-                        eprintln!("Historical Engine: Warm up complete: {}", time);
+                        tracing::info!("Historical Engine: Warm up complete: {}", time);
                         warm_up_complete = true;
                         set_warmup_complete();
                         let event = StrategyEvent::WarmUpComplete;
                         match self.strategy_event_sender.send(event).await {
                             Ok(_) => {}
-                            Err(e) => eprintln!("Historical Engine: Failed to send event: {}", e)
+                            Err(e) => tracing::error!("Historical Engine: Failed to send event: {}", e)
                         }
                         if mode == StrategyMode::Live || mode == StrategyMode::LivePaperTrading {
                             break 'main_loop
                         }
-                        println!("Historical Engine: Start Backtest");
+                        tracing::info!("Historical Engine: Start Backtest");
                     }
                 }
 
@@ -205,6 +270,7 @@ impl HistoricalEngine {
                     Ok(updates) => {
                         if updates != primary_subscriptions {
                             primary_subscriptions = updates;
+                            prefetcher = None;
                             early_return = true;
                             break 'day_loop
                         }
@@ -232,7 +298,7 @@ impl HistoricalEngine {
                 let mut strategy_time_slice: TimeSlice = TimeSlice::new();
                 // update our consolidators and create the strategies time slice with any new data or just create empty slice.
                 if !time_slice.is_empty() {
-                    let arc_slice = Arc::new(time_slice.clone());
+                    let arc_slice = Arc::new(time_slice);
                     self.market_price_service.update_market_data(arc_slice.clone());
                     self.ledger_service.timeslice_updates(arc_slice.clone()).await;
 
@@ -241,7 +307,8 @@ impl HistoricalEngine {
                         strategy_time_slice.extend(consolidated_data);
                     }
 
-                    strategy_time_slice.extend(time_slice);
+                    // The slice is only cloned if the ledgers are still holding it.
+                    strategy_time_slice.extend(Arc::unwrap_or_clone(arc_slice));
                 }
 
 
@@ -264,7 +331,7 @@ impl HistoricalEngine {
                     if let Some(events) = self.indicator_handler.update_time_slice(&strategy_time_slice).await {
                         match self.strategy_event_sender.send(StrategyEvent::IndicatorEvent(events)).await {
                             Ok(_) => {}
-                            Err(e) => eprintln!("Historical Engine: Failed to send event: {}", e)
+                            Err(e) => tracing::error!("Historical Engine: Failed to send event: {}", e)
                         }
                     }
 
@@ -273,7 +340,7 @@ impl HistoricalEngine {
                     );
                     match self.strategy_event_sender.send(slice_event).await {
                         Ok(_) => {}
-                        Err(e) => eprintln!("Historical Engine: Failed to send event: {}", e)
+                        Err(e) => tracing::error!("Historical Engine: Failed to send event: {}", e)
                     }
                 }
                 self.notified.notified().await;
@@ -282,3 +349,157 @@ impl HistoricalEngine {
         }
     }
 }
+
+/// The days of history downloaded ahead of the day being replayed.
+const PREFETCH_DAYS: usize = 2;
+
+/// A day of history downloaded by the `HistoryPrefetcher`.
+struct PrefetchedDay {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    time_slices: Result<BTreeMap<i64, TimeSlice>, FundForgeError>,
+}
+
+/// Downloads and decodes the days after the day being replayed on a background task, so the feed does not wait on the data server between days.
+/// Up to `PREFETCH_DAYS` days are downloaded at once and at most `PREFETCH_DAYS` finished days wait in the queue, which caps the memory used by data waiting to be replayed.
+struct HistoryPrefetcher {
+    receiver: mpsc::Receiver<PrefetchedDay>,
+    task: JoinHandle<()>,
+}
+
+impl HistoryPrefetcher {
+    fn start(subscriptions: Vec<DataSubscription>, from: DateTime<Utc>, end_time: DateTime<Utc>) -> Self {
+        let (sender, receiver) = mpsc::channel(PREFETCH_DAYS);
+        let task = tokio::task::spawn(async move {
+            let mut days = futures::stream::iter(prefetch_ranges(from, end_time))
+                .map(|(from, to)| {
+                    let subscriptions = subscriptions.clone();
+                    async move {
+                        let time_slices = get_compressed_historical_data(subscriptions, from, to).await;
+                        PrefetchedDay { from, to, time_slices }
+                    }
+                })
+                .buffered(PREFETCH_DAYS);
+            while let Some(day) = days.next().await {
+                if sender.send(day).await.is_err() {
+                    return;
+                }
+            }
+        });
+        Self { receiver, task }
+    }
+
+    /// The history of the next day, `None` if the next prefetched day does not cover the range or the prefetcher has stopped.
+    async fn next_day(&mut self, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<Result<BTreeMap<i64, TimeSlice>, FundForgeError>> {
+        let day = self.receiver.recv().await?;
+        match day.from <= from && day.to == to {
+            true => Some(day.time_slices),
+            false => None,
+        }
+    }
+}
+
+impl Drop for HistoryPrefetcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The last nanosecond of the day of the time.
+fn end_of_day(time: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&time.date_naive().and_hms_nano_opt(23, 59, 59, 999_999_999).unwrap())
+}
+
+/// The ranges the prefetcher downloads, the rest of the day of `from` and then each whole day until the end time.
+fn prefetch_ranges(from: DateTime<Utc>, end_time: DateTime<Utc>) -> impl Iterator<Item = (DateTime<Utc>, DateTime<Utc>)> {
+    std::iter::successors(Some(from), |from| Some(end_of_day(*from) + ChronoDuration::nanoseconds(1)))
+        .take_while(move |from| *from <= end_time)
+        .map(|from| (from, end_of_day(from)))
+}
+
+/// The smallest non zero buffer duration, time slices are keyed to the nanosecond but stepping the feed more finely than this only adds empty slices.
+pub const MIN_BUFFER_DURATION: Duration = Duration::from_millis(1);
+
+/// Raises a non zero buffer duration below `MIN_BUFFER_DURATION` to the minimum, a zero buffer duration stays unbuffered.
+pub(crate) fn valid_buffer_duration(buffer_duration: Duration) -> Duration {
+    if buffer_duration.is_zero() || buffer_duration >= MIN_BUFFER_DURATION {
+        return buffer_duration;
+    }
+    tracing::warn!("Buffer duration {:?} is below the minimum, using {:?}", buffer_duration, MIN_BUFFER_DURATION);
+    MIN_BUFFER_DURATION
+}
+
+/// The next time a historical feed steps to.
+///
+/// A buffered feed steps by the buffer duration. When the buffer duration is zero the feed is unbuffered, it steps straight to the time of the next data point
+/// so every time slice is forwarded as it occurs, and past the end of the day when there is no data left.
+pub(crate) fn next_feed_time(time: DateTime<Utc>, buffer_duration: Duration, time_slices: &BTreeMap<i64, TimeSlice>, to_time: DateTime<Utc>) -> DateTime<Utc> {
+    if !buffer_duration.is_zero() {
+        return time + buffer_duration;
+    }
+    match time_slices.range(time.timestamp_nanos_opt().unwrap()..).next() {
+        Some((next, _)) => Utc.timestamp_nanos(*next),
+        None => to_time + ChronoDuration::nanoseconds(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standardized_types::orders::TimeInForce;
+
+    #[test]
+    fn test_next_feed_time() {
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap();
+        let to_time = Utc.with_ymd_and_hms(2024, 6, 3, 23, 59, 59).unwrap();
+        let mut time_slices = BTreeMap::new();
+        for millis in [0, 250, 1_700] {
+            let time = start + ChronoDuration::milliseconds(millis);
+            time_slices.insert(time.timestamp_nanos_opt().unwrap(), TimeSlice::new());
+        }
+
+        assert_eq!(next_feed_time(start, Duration::from_secs(1), &time_slices, to_time), start + ChronoDuration::seconds(1));
+
+        // Unbuffered, data at the start of the range is included and then each data point is stepped to in turn.
+        let mut time = start;
+        let mut steps = vec![];
+        while time <= to_time {
+            time = next_feed_time(time, Duration::ZERO, &time_slices, to_time);
+            time_slices.remove(&time.timestamp_nanos_opt().unwrap());
+            steps.push(time);
+        }
+        assert_eq!(steps, vec![
+            start,
+            start + ChronoDuration::milliseconds(250),
+            start + ChronoDuration::milliseconds(1_700),
+            to_time + ChronoDuration::nanoseconds(1),
+        ]);
+    }
+
+    #[test]
+    fn test_prefetch_ranges() {
+        let from = Utc.with_ymd_and_hms(2024, 6, 3, 14, 30, 0).unwrap();
+        let end_time = Utc.with_ymd_and_hms(2024, 6, 5, 9, 0, 0).unwrap();
+        let ranges: Vec<_> = prefetch_ranges(from, end_time).collect();
+        assert_eq!(ranges, vec![
+            (from, end_of_day(from)),
+            (Utc.with_ymd_and_hms(2024, 6, 4, 0, 0, 0).unwrap(), end_of_day(Utc.with_ymd_and_hms(2024, 6, 4, 0, 0, 0).unwrap())),
+            (Utc.with_ymd_and_hms(2024, 6, 5, 0, 0, 0).unwrap(), end_of_day(end_time)),
+        ]);
+        assert_eq!(end_of_day(from) + ChronoDuration::nanoseconds(1), Utc.with_ymd_and_hms(2024, 6, 4, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_sub_second_buffer() {
+        assert_eq!(valid_buffer_duration(Duration::ZERO), Duration::ZERO);
+        assert_eq!(valid_buffer_duration(Duration::from_micros(10)), MIN_BUFFER_DURATION);
+        assert_eq!(valid_buffer_duration(Duration::from_millis(5)), Duration::from_millis(5));
+
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap() + ChronoDuration::nanoseconds(123_456_789);
+        let to_time = Utc.with_ymd_and_hms(2024, 6, 3, 23, 59, 59).unwrap();
+        let time = next_feed_time(start, MIN_BUFFER_DURATION, &BTreeMap::new(), to_time);
+        assert_eq!(time.timestamp_nanos_opt().unwrap() - start.timestamp_nanos_opt().unwrap(), 1_000_000);
+        assert_eq!(TimeInForce::good_till(start).expiry_time(), Some(start));
+        assert_eq!(TimeInForce::Time(start.timestamp()).expiry_time(), Some(start - ChronoDuration::nanoseconds(123_456_789)));
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::gui_types::settings::Color;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::rolling_window::RollingWindow;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::indicators::indicator_values::{IndicatorPlot, IndicatorValues};
+use crate::strategies::indicators::indicators_trait::{IndicatorName, Indicators};
+
+/// Top of Book Imbalance
+/// The imbalance between the volume resting at the best bid and the best ask, `(bid volume - ask volume) / (bid volume + ask volume)`.
+/// The ratio is between -1 and 1, positive when the bid is heavier and negative when the ask is heavier.
+///
+/// # Plots
+/// - "imbalance": The imbalance of the latest quote
+/// - "average_imbalance": The average imbalance of the last `period` quotes, only plotted once `period` quotes have been received
+///
+/// # Parameters
+/// - period: The number of quotes averaged, the average smooths the flicker of the book for threshold checks
+///
+/// Use a `BaseDataType::Quotes` subscription, quotes with no volume on either side are ignored.
+///
+/// # Usage
+/// A persistent imbalance above a threshold such as 0.6 shows buying pressure on the book, below -0.6 selling pressure.
+#[derive(Clone, Debug)]
+pub struct BookImbalance {
+    name: IndicatorName,
+    subscription: DataSubscription,
+    history: RollingWindow<IndicatorValues>,
+    period: usize,
+    imbalances: VecDeque<Decimal>,
+    imbalance_color: Color,
+    average_color: Color,
+    is_ready: bool,
+}
+
+impl Display for BookImbalance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let last = self.history.last();
+        match last {
+            Some(last) => write!(f, "{}\n{}", &self.name, last),
+            None => write!(f, "{}: No Values", &self.name),
+        }
+    }
+}
+
+impl BookImbalance {
+    #[allow(dead_code)]
+    pub fn new(
+        name: IndicatorName,
+        subscription: DataSubscription,
+        history_to_retain: usize,
+        period: usize,
+        imbalance_color: Color,
+        average_color: Color,
+    ) -> Box<Self> {
+        let book_imbalance = BookImbalance {
+            name,
+            subscription,
+            history: RollingWindow::new(history_to_retain),
+            period: period.max(1),
+            imbalances: VecDeque::with_capacity(period.max(1)),
+            imbalance_color,
+            average_color,
+            is_ready: false,
+        };
+        Box::new(book_imbalance)
+    }
+
+    fn get_imbalance(data: &BaseDataEnum) -> Option<Decimal> {
+        match data {
+            BaseDataEnum::Quote(quote) => {
+                let total = quote.bid_volume + quote.ask_volume;
+                if total <= dec!(0) {
+                    return None;
+                }
+                Some((quote.bid_volume - quote.ask_volume) / total)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Indicators for BookImbalance {
+    fn name(&self) -> IndicatorName {
+        self.name.clone()
+    }
+
+    fn history_to_retain(&self) -> usize {
+        self.history.number as usize
+    }
+
+    fn update_base_data(&mut self, base_data: &BaseDataEnum) -> Option<Vec<IndicatorValues>> {
+        let imbalance = Self::get_imbalance(base_data)?.round_dp(4);
+        if self.imbalances.len() == self.period {
+            self.imbalances.pop_front();
+        }
+        self.imbalances.push_back(imbalance);
+
+        let mut plots = BTreeMap::new();
+        plots.insert(
+            "imbalance".to_string(),
+            IndicatorPlot::new("Imbalance".to_string(), imbalance, self.imbalance_color.clone()),
+        );
+        if self.imbalances.len() == self.period {
+            let average = self.imbalances.iter().sum::<Decimal>() / Decimal::from(self.period);
+            plots.insert(
+                "average_imbalance".to_string(),
+                IndicatorPlot::new("Average Imbalance".to_string(), average.round_dp(4), self.average_color.clone()),
+            );
+        }
+
+        let values = IndicatorValues::new(
+            self.name.clone(),
+            self.subscription.clone(),
+            plots,
+            base_data.time_closed_utc(),
+        );
+
+        self.history.add(values.clone());
+        self.is_ready = true;
+        Some(vec![values])
+    }
+
+    fn subscription(&self) -> &DataSubscription {
+        &self.subscription
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+        self.imbalances.clear();
+        self.is_ready = false;
+    }
+
+    fn index(&self, index: usize) -> Option<IndicatorValues> {
+        if !self.is_ready {
+            return None;
+        }
+        self.history.get(index).cloned()
+    }
+
+    fn current(&self) -> Option<IndicatorValues> {
+        if !self.is_ready {
+            return None;
+        }
+        self.history.last().cloned()
+    }
+
+    fn plots(&self) -> RollingWindow<IndicatorValues> {
+        self.history.clone()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.is_ready
+    }
+
+    fn history(&self) -> RollingWindow<IndicatorValues> {
+        self.history.clone()
+    }
+
+    fn data_required_warmup(&self) -> u64 {
+        self.period as u64
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::gui_types::settings::Color;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::new_types::Price;
+use crate::standardized_types::rolling_window::RollingWindow;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::indicators::indicator_values::{IndicatorPlot, IndicatorValues, PlotName};
+use crate::strategies::indicators::indicators_trait::{IndicatorName, Indicators};
+
+/// The candle patterns `CandlePatterns` can detect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CandlePattern {
+    /// A bullish bar whose body covers the body of the previous bearish bar.
+    BullishEngulfing,
+    /// A bearish bar whose body covers the body of the previous bullish bar.
+    BearishEngulfing,
+    /// A bar with almost no body compared to its range.
+    Doji,
+    /// A bar whose high and low are inside the range of the previous bar.
+    InsideBar,
+}
+
+impl CandlePattern {
+    pub fn all() -> Vec<CandlePattern> {
+        vec![CandlePattern::BullishEngulfing, CandlePattern::BearishEngulfing, CandlePattern::Doji, CandlePattern::InsideBar]
+    }
+
+    /// The plot name the pattern is reported under.
+    pub fn plot_name(&self) -> PlotName {
+        match self {
+            CandlePattern::BullishEngulfing => "bullish_engulfing",
+            CandlePattern::BearishEngulfing => "bearish_engulfing",
+            CandlePattern::Doji => "doji",
+            CandlePattern::InsideBar => "inside_bar",
+        }.to_string()
+    }
+
+    /// 1 for bullish patterns, -1 for bearish patterns and 0 for patterns without a direction.
+    pub fn direction(&self) -> Decimal {
+        match self {
+            CandlePattern::BullishEngulfing => dec!(1),
+            CandlePattern::BearishEngulfing => dec!(-1),
+            CandlePattern::Doji | CandlePattern::InsideBar => dec!(0),
+        }
+    }
+}
+
+impl Display for CandlePattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CandlePattern::BullishEngulfing => write!(f, "Bullish Engulfing"),
+            CandlePattern::BearishEngulfing => write!(f, "Bearish Engulfing"),
+            CandlePattern::Doji => write!(f, "Doji"),
+            CandlePattern::InsideBar => write!(f, "Inside Bar"),
+        }
+    }
+}
+
+/// The patterns to detect and the body and wick thresholds, all ratios are of the bar range (high - low).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandlePatternSettings {
+    pub patterns: Vec<CandlePattern>,
+    /// A doji body is at most this ratio of the range, 0.1 by default.
+    pub doji_max_body_ratio: Decimal,
+    /// Both doji wicks are at least this ratio of the range, 0 by default so any doji qualifies, 0.3 only allows long legged dojis.
+    pub doji_min_wick_ratio: Decimal,
+    /// The body of an engulfing bar is at least this ratio of its range, 0.5 by default so long wicked bars are ignored.
+    pub engulfing_min_body_ratio: Decimal,
+    /// When true an inside bar must not share the high or low of the previous bar, true by default.
+    pub inside_bar_strict: bool,
+}
+
+impl Default for CandlePatternSettings {
+    fn default() -> Self {
+        Self {
+            patterns: CandlePattern::all(),
+            doji_max_body_ratio: dec!(0.1),
+            doji_min_wick_ratio: dec!(0.0),
+            engulfing_min_body_ratio: dec!(0.5),
+            inside_bar_strict: true,
+        }
+    }
+}
+
+impl CandlePatternSettings {
+    pub fn with_patterns(mut self, patterns: Vec<CandlePattern>) -> Self {
+        self.patterns = patterns;
+        self
+    }
+
+    pub fn with_doji_thresholds(mut self, max_body_ratio: Decimal, min_wick_ratio: Decimal) -> Self {
+        self.doji_max_body_ratio = max_body_ratio;
+        self.doji_min_wick_ratio = min_wick_ratio;
+        self
+    }
+
+    pub fn with_engulfing_min_body_ratio(mut self, min_body_ratio: Decimal) -> Self {
+        self.engulfing_min_body_ratio = min_body_ratio;
+        self
+    }
+
+    pub fn with_inside_bar_strict(mut self, strict: bool) -> Self {
+        self.inside_bar_strict = strict;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Bar {
+    open: Price,
+    high: Price,
+    low: Price,
+    close: Price,
+}
+
+impl Bar {
+    fn body(&self) -> Price {
+        (self.close - self.open).abs()
+    }
+
+    fn range(&self) -> Price {
+        self.high - self.low
+    }
+
+    fn is_bullish(&self) -> bool {
+        self.close > self.open
+    }
+
+    fn is_bearish(&self) -> bool {
+        self.close < self.open
+    }
+}
+
+/// Candle Patterns
+/// Detects candle patterns on the closed bars of a subscription, values are only produced for the bars where at least one configured pattern occurs,
+/// so each `IndicatorEvents::IndicatorTimeSlice` containing this indicator is a pattern notification.
+///
+/// # Plots
+/// One plot per detected pattern, named by `CandlePattern::plot_name()`: "bullish_engulfing", "bearish_engulfing", "doji", "inside_bar".
+/// The plot value is the pattern direction, 1 for bullish, -1 for bearish and 0 for doji and inside bars.
+///
+/// # Usage
+/// Useful as a signal filter, for example only taking long entries on a bar where `values.get_plot(&"bullish_engulfing".to_string())` is some.
+/// The history only holds the bars with patterns, so `strategy.indicator_current()` is the last pattern, compare its time to the bar time before using it as a signal.
+/// Quote bars use the bid prices.
+#[derive(Clone, Debug)]
+pub struct CandlePatterns {
+    name: IndicatorName,
+    subscription: DataSubscription,
+    history: RollingWindow<IndicatorValues>,
+    settings: CandlePatternSettings,
+    bullish_color: Color,
+    bearish_color: Color,
+    neutral_color: Color,
+    last_bar: Option<Bar>,
+    is_ready: bool,
+}
+
+impl Display for CandlePatterns {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let last = self.history.last();
+        match last {
+            Some(last) => write!(f, "{}\n{}", &self.name, last),
+            None => write!(f, "{}: No Values", &self.name),
+        }
+    }
+}
+
+impl CandlePatterns {
+    #[allow(dead_code)]
+    pub fn new(
+        name: IndicatorName,
+        subscription: DataSubscription,
+        history_to_retain: usize,
+        settings: CandlePatternSettings,
+        bullish_color: Color,
+        bearish_color: Color,
+        neutral_color: Color,
+    ) -> Box<Self> {
+        let candle_patterns = CandlePatterns {
+            name,
+            subscription,
+            history: RollingWindow::new(history_to_retain),
+            settings,
+            bullish_color,
+            bearish_color,
+            neutral_color,
+            last_bar: None,
+            is_ready: false,
+        };
+        Box::new(candle_patterns)
+    }
+
+    fn get_bar(data: &BaseDataEnum) -> Option<Bar> {
+        match data {
+            BaseDataEnum::QuoteBar(bar) => Some(Bar {
+                open: bar.bid_open,
+                high: bar.bid_high,
+                low: bar.bid_low,
+                close: bar.bid_close,
+            }),
+            BaseDataEnum::Candle(candle) => Some(Bar {
+                open: candle.open,
+                high: candle.high,
+                low: candle.low,
+                close: candle.close,
+            }),
+            _ => None,
+        }
+    }
+
+    fn detect(&self, previous: &Bar, current: &Bar, pattern: CandlePattern) -> bool {
+        let settings = &self.settings;
+        match pattern {
+            CandlePattern::BullishEngulfing => {
+                previous.is_bearish()
+                    && current.is_bullish()
+                    && current.body() >= current.range() * settings.engulfing_min_body_ratio
+                    && current.open <= previous.close
+                    && current.close >= previous.open
+                    && current.body() > previous.body()
+            }
+            CandlePattern::BearishEngulfing => {
+                previous.is_bullish()
+                    && current.is_bearish()
+                    && current.body() >= current.range() * settings.engulfing_min_body_ratio
+                    && current.open >= previous.close
+                    && current.close <= previous.open
+                    && current.body() > previous.body()
+            }
+            CandlePattern::Doji => {
+                let range = current.range();
+                let upper_wick = current.high - current.open.max(current.close);
+                let lower_wick = current.open.min(current.close) - current.low;
+                range > dec!(0)
+                    && current.body() <= range * settings.doji_max_body_ratio
+                    && upper_wick.min(lower_wick) >= range * settings.doji_min_wick_ratio
+            }
+            CandlePattern::InsideBar => match settings.inside_bar_strict {
+                true => current.high < previous.high && current.low > previous.low,
+                false => current.high <= previous.high && current.low >= previous.low && current.range() < previous.range(),
+            },
+        }
+    }
+}
+
+impl Indicators for CandlePatterns {
+    fn name(&self) -> IndicatorName {
+        self.name.clone()
+    }
+
+    fn history_to_retain(&self) -> usize {
+        self.history.number as usize
+    }
+
+    fn update_base_data(&mut self, base_data: &BaseDataEnum) -> Option<Vec<IndicatorValues>> {
+        if !base_data.is_closed() {
+            return None;
+        }
+        let current = Self::get_bar(base_data)?;
+        let previous = self.last_bar.replace(current)?;
+        self.is_ready = true;
+
+        let mut plots = BTreeMap::new();
+        for pattern in &self.settings.patterns {
+            if !self.detect(&previous, &current, *pattern) {
+                continue;
+            }
+            let direction = pattern.direction();
+            let color = match direction {
+                d if d > dec!(0) => self.bullish_color.clone(),
+                d if d < dec!(0) => self.bearish_color.clone(),
+                _ => self.neutral_color.clone(),
+            };
+            plots.insert(pattern.plot_name(), IndicatorPlot::new(pattern.to_string(), direction, color));
+        }
+        if plots.is_empty() {
+            return None;
+        }
+
+        let values = IndicatorValues::new(
+            self.name.clone(),
+            self.subscription.clone(),
+            plots,
+            base_data.time_closed_utc(),
+        );
+
+        self.history.add(values.clone());
+        Some(vec![values])
+    }
+
+    fn subscription(&self) -> &DataSubscription {
+        &self.subscription
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+        self.last_bar = None;
+        self.is_ready = false;
+    }
+
+    fn index(&self, index: usize) -> Option<IndicatorValues> {
+        if !self.is_ready {
+            return None;
+        }
+        self.history.get(index).cloned()
+    }
+
+    fn current(&self) -> Option<IndicatorValues> {
+        if !self.is_ready {
+            return None;
+        }
+        self.history.last().cloned()
+    }
+
+    fn plots(&self) -> RollingWindow<IndicatorValues> {
+        self.history.clone()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.is_ready
+    }
+
+    fn history(&self) -> RollingWindow<IndicatorValues> {
+        self.history.clone()
+    }
+
+    fn data_required_warmup(&self) -> u64 {
+        2
+    }
+}
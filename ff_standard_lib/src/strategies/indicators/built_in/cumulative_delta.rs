@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use chrono::{DateTime, Utc};
+use rust_decimal_macros::dec;
+use crate::gui_types::settings::Color;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::tick::Aggressor;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::market_hours::TradingHours;
+use crate::standardized_types::new_types::Volume;
+use crate::standardized_types::rolling_window::RollingWindow;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::indicators::indicator_values::{IndicatorPlot, IndicatorValues};
+use crate::strategies::indicators::indicators_trait::{IndicatorName, Indicators};
+
+/// Cumulative Volume Delta (CVD)
+/// The aggressive buying less the aggressive selling, for each bar and accumulated through the session.
+///
+/// # Plots
+/// - "bar_delta": The delta of the bar, or of the trade for tick subscriptions
+/// - "cumulative_delta": The delta accumulated since the start of the session
+///
+/// # Parameters
+/// - trading_hours: The session is reset at each session close of the trading hours, `None` accumulates from the first data and never resets
+///
+/// Use a `BaseDataType::Footprints` subscription for bar deltas, or a tick subscription to update on every trade.
+/// Trades without an aggressor are not counted.
+///
+/// # Usage
+/// Confirms moves with the aggressive flow, eg. a new high on a falling cumulative delta is a divergence,
+/// and a bar delta above a threshold marks aggressive buying.
+#[derive(Clone, Debug)]
+pub struct CumulativeDelta {
+    name: IndicatorName,
+    subscription: DataSubscription,
+    history: RollingWindow<IndicatorValues>,
+    trading_hours: Option<TradingHours>,
+    bar_delta_color: Color,
+    cumulative_delta_color: Color,
+    cumulative_delta: Volume,
+    last_update: Option<DateTime<Utc>>,
+    is_ready: bool,
+}
+
+impl Display for CumulativeDelta {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let last = self.history.last();
+        match last {
+            Some(last) => write!(f, "{}\n{}", &self.name, last),
+            None => write!(f, "{}: No Values", &self.name),
+        }
+    }
+}
+
+impl CumulativeDelta {
+    #[allow(dead_code)]
+    pub fn new(
+        name: IndicatorName,
+        subscription: DataSubscription,
+        history_to_retain: usize,
+        trading_hours: Option<TradingHours>,
+        bar_delta_color: Color,
+        cumulative_delta_color: Color,
+    ) -> Box<Self> {
+        let cumulative_delta = CumulativeDelta {
+            name,
+            subscription,
+            history: RollingWindow::new(history_to_retain),
+            trading_hours,
+            bar_delta_color,
+            cumulative_delta_color,
+            cumulative_delta: dec!(0),
+            last_update: None,
+            is_ready: false,
+        };
+        Box::new(cumulative_delta)
+    }
+
+    fn get_delta(data: &BaseDataEnum) -> Option<Volume> {
+        match data {
+            BaseDataEnum::Footprint(footprint) => Some(footprint.delta()),
+            BaseDataEnum::Tick(tick) => match tick.aggressor {
+                Aggressor::Buy => Some(tick.volume),
+                Aggressor::Sell => Some(-tick.volume),
+                Aggressor::None => Some(dec!(0)),
+            },
+            _ => None,
+        }
+    }
+
+    /// True if a session closed since the last update, `time` is the open time of the data.
+    fn should_reset(&self, time: DateTime<Utc>) -> bool {
+        let (last_update, trading_hours) = match (self.last_update, &self.trading_hours) {
+            (Some(last_update), Some(trading_hours)) => (last_update, trading_hours),
+            _ => return false,
+        };
+        match trading_hours.last_close(time) {
+            Some(last_close) => last_update < last_close,
+            None => false,
+        }
+    }
+}
+
+impl Indicators for CumulativeDelta {
+    fn name(&self) -> IndicatorName {
+        self.name.clone()
+    }
+
+    fn history_to_retain(&self) -> usize {
+        self.history.number as usize
+    }
+
+    fn update_base_data(&mut self, base_data: &BaseDataEnum) -> Option<Vec<IndicatorValues>> {
+        if !base_data.is_closed() {
+            return None;
+        }
+        let bar_delta = Self::get_delta(base_data)?;
+
+        // Sessions use the open time of the data, so the bar which closes at the session close belongs to that session.
+        let data_time = base_data.time_utc();
+        if self.should_reset(data_time) {
+            self.cumulative_delta = dec!(0);
+        }
+        self.last_update = Some(data_time);
+        self.cumulative_delta += bar_delta;
+
+        let mut plots = BTreeMap::new();
+        plots.insert(
+            "bar_delta".to_string(),
+            IndicatorPlot::new("Bar Delta".to_string(), bar_delta, self.bar_delta_color.clone()),
+        );
+        plots.insert(
+            "cumulative_delta".to_string(),
+            IndicatorPlot::new("Cumulative Delta".to_string(), self.cumulative_delta, self.cumulative_delta_color.clone()),
+        );
+
+        let values = IndicatorValues::new(
+            self.name.clone(),
+            self.subscription.clone(),
+            plots,
+            base_data.time_closed_utc(),
+        );
+
+        self.history.add(values.clone());
+        self.is_ready = true;
+        Some(vec![values])
+    }
+
+    fn subscription(&self) -> &DataSubscription {
+        &self.subscription
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+        self.cumulative_delta = dec!(0);
+        self.last_update = None;
+        self.is_ready = false;
+    }
+
+    fn index(&self, index: usize) -> Option<IndicatorValues> {
+        if !self.is_ready {
+            return None;
+        }
+        self.history.get(index).cloned()
+    }
+
+    fn current(&self) -> Option<IndicatorValues> {
+        if !self.is_ready {
+            return None;
+        }
+        self.history.last().cloned()
+    }
+
+    fn plots(&self) -> RollingWindow<IndicatorValues> {
+        self.history.clone()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.is_ready
+    }
+
+    fn history(&self) -> RollingWindow<IndicatorValues> {
+        self.history.clone()
+    }
+
+    fn data_required_warmup(&self) -> u64 {
+        1
+    }
+}
@@ -34,3 +34,7 @@ pub mod fibonacci_retracement;
 pub mod volume_profile_distribution;
 pub mod donchian_momentum_indicator;
 pub mod market_exhaustion;
+pub mod super_trend;
+pub mod candle_patterns;
+pub mod cumulative_delta;
+pub mod book_imbalance;
@@ -155,7 +155,7 @@ impl Renko {
 
         let color = if close > open { self.up_color.clone() } else { self.down_color.clone() };
 
-      /*  println!("Creating block - Before reset: Volume: {}, Buy: {}, Sell: {}",
+      /*  tracing::debug!("Creating block - Before reset: Volume: {}, Buy: {}, Sell: {}",
                  self.volume, self.buy_aggressors, self.sell_aggressors);*/
 
         // Only create if it's a full block
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::gui_types::settings::Color;
+use crate::helpers::decimal_calculators::round_to_tick_size;
+use crate::product_maps::rithmic::maps::extract_symbol_from_contract;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::enums::MarketType;
+use crate::standardized_types::new_types::Price;
+use crate::standardized_types::rolling_window::RollingWindow;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::indicators::indicator_values::{IndicatorPlot, IndicatorValues};
+use crate::strategies::indicators::indicators_trait::{IndicatorName, Indicators};
+
+/// SuperTrend
+/// A trailing stop style trend line, ATR bands around the bar median which only move in the direction of the trend.
+///
+/// # Plots
+/// - "super_trend": The lower band in an up trend and the upper band in a down trend, colored by the trend
+/// - "direction": 1 in an up trend, -1 in a down trend
+///
+/// # Parameters
+/// - atr_period: Period for the Wilder ATR, 10 is standard
+/// - multiplier: ATR multiplier for the band distance, 3 is standard
+///
+/// # Usage
+/// The trend flips when a bar closes through the line, the line can be used as a trailing stop.
+#[derive(Clone, Debug)]
+pub struct SuperTrend {
+    name: IndicatorName,
+    subscription: DataSubscription,
+    history: RollingWindow<IndicatorValues>,
+    #[allow(unused)]
+    market_type: MarketType,
+    tick_size: Decimal,
+    decimal_accuracy: u32,
+    is_ready: bool,
+    up_color: Color,
+    down_color: Color,
+    atr_period: u64,
+    multiplier: Decimal,
+    tick_rounding: bool,
+    true_ranges: Vec<Price>,
+    last_close: Option<Price>,
+    last_atr: Option<Price>,
+    upper_band: Option<Price>,
+    lower_band: Option<Price>,
+    up_trend: bool,
+}
+
+impl Display for SuperTrend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let last = self.history.last();
+        match last {
+            Some(last) => write!(f, "{}\n{}", &self.name, last),
+            None => write!(f, "{}: No Values", &self.name),
+        }
+    }
+}
+
+impl SuperTrend {
+    #[allow(dead_code)]
+    pub async fn new(
+        name: IndicatorName,
+        subscription: DataSubscription,
+        history_to_retain: usize,
+        atr_period: u64,
+        multiplier: Decimal,
+        up_color: Color,
+        down_color: Color,
+        tick_rounding: bool,
+    ) -> Box<Self> {
+        let symbol_name = match subscription.market_type {
+            MarketType::Futures(_) => extract_symbol_from_contract(&subscription.symbol.name),
+            _ => subscription.symbol.name.clone(),
+        };
+        let decimal_accuracy = subscription.symbol.data_vendor.decimal_accuracy(symbol_name.clone()).await.unwrap();
+        let tick_size = subscription.symbol.data_vendor.tick_size(symbol_name.clone()).await.unwrap();
+
+        let super_trend = SuperTrend {
+            name,
+            market_type: subscription.symbol.market_type.clone(),
+            subscription,
+            history: RollingWindow::new(history_to_retain),
+            is_ready: false,
+            tick_size,
+            decimal_accuracy,
+            up_color,
+            down_color,
+            atr_period: atr_period.max(1),
+            multiplier,
+            tick_rounding,
+            true_ranges: vec![],
+            last_close: None,
+            last_atr: None,
+            upper_band: None,
+            lower_band: None,
+            up_trend: true,
+        };
+        Box::new(super_trend)
+    }
+
+    fn get_price_data(data: &BaseDataEnum) -> Option<(Price, Price, Price)> {
+        match data {
+            BaseDataEnum::QuoteBar(bar) => Some((
+                bar.bid_high,
+                bar.bid_low,
+                bar.bid_close,
+            )),
+            BaseDataEnum::Candle(candle) => Some((
+                candle.high,
+                candle.low,
+                candle.close,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Wilder's ATR, seeded with the average of the first `atr_period` true ranges.
+    fn calculate_atr(&mut self, true_range: Price) -> Option<Price> {
+        let period = Decimal::from(self.atr_period);
+        let atr = match self.last_atr {
+            Some(last_atr) => (last_atr * (period - dec!(1.0)) + true_range) / period,
+            None => {
+                self.true_ranges.push(true_range);
+                if (self.true_ranges.len() as u64) < self.atr_period {
+                    return None;
+                }
+                let atr = self.true_ranges.iter().sum::<Decimal>() / period;
+                self.true_ranges.clear();
+                atr
+            }
+        };
+        self.last_atr = Some(atr);
+        Some(atr)
+    }
+
+    fn round_value(&self, value: Decimal) -> Price {
+        match self.tick_rounding {
+            true => round_to_tick_size(value, self.tick_size),
+            false => value.round_dp(self.decimal_accuracy),
+        }
+    }
+}
+
+impl Indicators for SuperTrend {
+    fn name(&self) -> IndicatorName {
+        self.name.clone()
+    }
+
+    fn history_to_retain(&self) -> usize {
+        self.history.number as usize
+    }
+
+    fn update_base_data(&mut self, base_data: &BaseDataEnum) -> Option<Vec<IndicatorValues>> {
+        if !base_data.is_closed() {
+            return None;
+        }
+        let (high, low, close) = Self::get_price_data(base_data)?;
+
+        let previous_close = self.last_close.replace(close);
+        let true_range = match previous_close {
+            Some(previous_close) => (high - low).max((high - previous_close).abs()).max((low - previous_close).abs()),
+            None => high - low,
+        };
+        let atr = self.calculate_atr(true_range)?;
+
+        let median = (high + low) / dec!(2.0);
+        let basic_upper = median + atr * self.multiplier;
+        let basic_lower = median - atr * self.multiplier;
+
+        // The bands only tighten while price stays inside them
+        let upper_band = match (self.upper_band, previous_close) {
+            (Some(upper_band), Some(previous_close)) if basic_upper > upper_band && previous_close <= upper_band => upper_band,
+            _ => basic_upper,
+        };
+        let lower_band = match (self.lower_band, previous_close) {
+            (Some(lower_band), Some(previous_close)) if basic_lower < lower_band && previous_close >= lower_band => lower_band,
+            _ => basic_lower,
+        };
+
+        // The trend flips when a bar closes through the band it is trailing
+        self.up_trend = match self.is_ready {
+            true => match self.up_trend {
+                true => close >= lower_band,
+                false => close > upper_band,
+            },
+            false => close >= median,
+        };
+        self.upper_band = Some(upper_band);
+        self.lower_band = Some(lower_band);
+        self.is_ready = true;
+
+        let (line, color, direction) = match self.up_trend {
+            true => (lower_band, self.up_color.clone(), dec!(1)),
+            false => (upper_band, self.down_color.clone(), dec!(-1)),
+        };
+
+        let mut plots = BTreeMap::new();
+        plots.insert(
+            "super_trend".to_string(),
+            IndicatorPlot::new("SuperTrend".to_string(), self.round_value(line), color.clone()),
+        );
+        plots.insert(
+            "direction".to_string(),
+            IndicatorPlot::new("Direction".to_string(), direction, color),
+        );
+
+        let values = IndicatorValues::new(
+            self.name.clone(),
+            self.subscription.clone(),
+            plots,
+            base_data.time_closed_utc(),
+        );
+
+        self.history.add(values.clone());
+        Some(vec![values])
+    }
+
+    fn subscription(&self) -> &DataSubscription {
+        &self.subscription
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+        self.is_ready = false;
+        self.true_ranges.clear();
+        self.last_close = None;
+        self.last_atr = None;
+        self.upper_band = None;
+        self.lower_band = None;
+        self.up_trend = true;
+    }
+
+    fn index(&self, index: usize) -> Option<IndicatorValues> {
+        if !self.is_ready {
+            return None;
+        }
+        self.history.get(index).cloned()
+    }
+
+    fn current(&self) -> Option<IndicatorValues> {
+        if !self.is_ready {
+            return None;
+        }
+        self.history.last().cloned()
+    }
+
+    fn plots(&self) -> RollingWindow<IndicatorValues> {
+        self.history.clone()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.is_ready
+    }
+
+    fn history(&self) -> RollingWindow<IndicatorValues> {
+        self.history.clone()
+    }
+
+    fn data_required_warmup(&self) -> u64 {
+        self.atr_period + 1
+    }
+}
@@ -13,11 +13,23 @@ use std::collections::BTreeMap;
 use std::fmt::{self, Display, Formatter};
 use rust_decimal::{Decimal, MathematicalOps};
 use rust_decimal_macros::dec;
-use chrono::{DateTime, Datelike, Utc};
+use chrono::{DateTime, Utc};
 use crate::standardized_types::market_hours::TradingHours;
 
+/// The point the VWAP accumulates from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VwapAnchor {
+    /// Reset at the first data of each trading session, sessions are separated by the session close of the trading hours,
+    /// so overnight sessions such as the CME 17:00 - 16:00 CT session are not reset at midnight.
+    Session,
+    /// Reset at the first data of each trading week, see `TradingHours::week_open()`.
+    Week,
+    /// Accumulate from the anchor time and never reset, data before the anchor is ignored.
+    Custom(DateTime<Utc>),
+}
+
 /// Volume Weighted Average Price (VWAP)
-/// Price average weighted by volume, typically reset daily.
+/// Price average weighted by volume, accumulated from the anchor.
 ///
 /// # Plots
 /// - "vwap": Main VWAP line
@@ -25,28 +37,14 @@ use crate::standardized_types::market_hours::TradingHours;
 /// - "lower_band": Lower standard deviation band
 ///
 /// # Parameters
-/// - std_dev_multiplier: Band width multiplier
-/// - trading_hours: Market hours configuration
-///
-/// # Usage
-/// Identifies fair value and potential support/resistance levels.
-
-/// Bollinger Bands
-/// Dynamic volatility bands around a moving average.
+/// - std_dev_multiplier: Band width multiplier, the bands are the volume weighted standard deviation of price from the VWAP
+/// - trading_hours: Market hours configuration, data outside the trading hours is ignored
+/// - anchor: Where the VWAP resets, see `VwapAnchor`
 ///
-/// # Plots
-/// - "middle": Middle band (simple moving average)
-/// - "upper": Upper volatility band
-/// - "lower": Lower volatility band
-/// - "bandwidth": Distance between bands
-/// - "percent_b": Price position relative to bands (0-100)
-///
-/// # Parameters
-/// - period: MA period (typically 20)
-/// - num_std_dev: Standard deviation multiplier (typically 2)
+/// Candles and quote bars use the typical price of each closed bar, ticks use the traded price.
 ///
 /// # Usage
-/// Measures volatility and identifies potential price extremes.
+/// Identifies fair value and potential support/resistance levels.
 #[derive(Clone, Debug)]
 pub struct VolumeWeightedAveragePrice {
     name: IndicatorName,
@@ -65,10 +63,11 @@ pub struct VolumeWeightedAveragePrice {
     cumulative_pv: Decimal,
     cumulative_volume: Decimal,
     std_dev_multiplier: Decimal,
-    last_reset_day: Option<u32>,
-    squared_diff_sum: Decimal,
+    /// The cumulative volume weighted squared price, used for the band variance.
+    cumulative_pv2: Decimal,
     trading_hours: TradingHours,
-    was_last_bar_in_session: bool,
+    anchor: VwapAnchor,
+    last_update: Option<DateTime<Utc>>,
 }
 
 impl Display for VolumeWeightedAveragePrice {
@@ -93,6 +92,7 @@ impl VolumeWeightedAveragePrice {
         lower_band_color: Color,
         tick_rounding: bool,
         trading_hours: TradingHours,
+        anchor: VwapAnchor,
     ) -> Box<Self> {
         let symbol_name = match subscription.market_type {
             MarketType::Futures(_) => extract_symbol_from_contract(&subscription.symbol.name),
@@ -116,10 +116,10 @@ impl VolumeWeightedAveragePrice {
             cumulative_pv: dec!(0.0),
             cumulative_volume: dec!(0.0),
             std_dev_multiplier,
-            last_reset_day: None,
-            squared_diff_sum: dec!(0.0),
+            cumulative_pv2: dec!(0.0),
             trading_hours,
-            was_last_bar_in_session: false,
+            anchor,
+            last_update: None,
         };
         Box::new(vwap)
     }
@@ -134,32 +134,25 @@ impl VolumeWeightedAveragePrice {
                 let typical_price = (candle.high + candle.low + candle.close) / dec!(3.0);
                 Some((typical_price, Decimal::from(candle.volume)))
             },
+            BaseDataEnum::Tick(tick) => Some((tick.price, tick.volume)),
             _ => None,
         }
     }
 
-    fn should_reset(&mut self, time: DateTime<Utc>) -> bool {
-        // Reset if:
-        // 1. We're in a new session after being out of session
-        // 2. We're on a new day and before/at session start
-        let is_in_session = self.trading_hours.is_market_open(time);
-
-        if !is_in_session {
-            self.was_last_bar_in_session = false;
-            return false;
-        }
-
-        if !self.was_last_bar_in_session {
-            return true;
-        }
-
-        let market_time = time.with_timezone(&self.trading_hours.timezone);
-        match self.last_reset_day {
-            None => true,
-            Some(last_day) => {
-                let current_day = market_time.day();
-                current_day != last_day
-            }
+    /// True if the anchor has been crossed since the last update, `time` is the open time of the data.
+    fn should_reset(&self, time: DateTime<Utc>) -> bool {
+        let last_update = match self.last_update {
+            None => return true,
+            Some(last_update) => last_update,
+        };
+        let anchor_time = match &self.anchor {
+            VwapAnchor::Session => self.trading_hours.last_close(time),
+            VwapAnchor::Week => self.trading_hours.week_open(time),
+            VwapAnchor::Custom(_) => None,
+        };
+        match anchor_time {
+            Some(anchor_time) => last_update < anchor_time,
+            None => false,
         }
     }
 
@@ -181,7 +174,8 @@ impl VolumeWeightedAveragePrice {
             return None;
         }
 
-        let variance = self.squared_diff_sum / self.cumulative_volume;
+        let mean = self.cumulative_pv / self.cumulative_volume;
+        let variance = (self.cumulative_pv2 / self.cumulative_volume - mean * mean).max(dec!(0.0));
         let std_dev = variance.sqrt()?;
         let band_width = std_dev * self.std_dev_multiplier;
 
@@ -213,25 +207,27 @@ impl Indicators for VolumeWeightedAveragePrice {
             return None;
         }
 
+        // Sessions and anchors use the open time of the data, so the bar which closes at the session close belongs to that session.
+        let data_time = base_data.time_utc();
         let current_time = base_data.time_closed_utc();
 
+        if let VwapAnchor::Custom(anchor_time) = self.anchor {
+            if data_time < anchor_time {
+                return None;
+            }
+        }
+
         // Only process if we're in market hours
-        if !self.trading_hours.is_market_open(current_time) {
-            self.was_last_bar_in_session = false;
+        if !self.trading_hours.is_market_open(data_time) {
             return None;
         }
 
-        // Check for reset conditions
-        if self.should_reset(current_time) {
+        if self.should_reset(data_time) {
             self.cumulative_pv = dec!(0.0);
             self.cumulative_volume = dec!(0.0);
-            self.squared_diff_sum = dec!(0.0);
-            self.last_reset_day = Some(current_time
-                .with_timezone(&self.trading_hours.timezone)
-                .day());
+            self.cumulative_pv2 = dec!(0.0);
         }
-
-        self.was_last_bar_in_session = true;
+        self.last_update = Some(data_time);
 
         // Get price and volume data
         let (typical_price, volume) = Self::get_typical_price(base_data)?;
@@ -239,6 +235,7 @@ impl Indicators for VolumeWeightedAveragePrice {
         // Update cumulative values
         self.cumulative_pv += typical_price * volume;
         self.cumulative_volume += volume;
+        self.cumulative_pv2 += typical_price * typical_price * volume;
 
         // Calculate VWAP
         let vwap = self.calculate_vwap();
@@ -246,10 +243,6 @@ impl Indicators for VolumeWeightedAveragePrice {
             return None;
         }
 
-        // Update standard deviation calculation
-        let price_diff = typical_price - vwap;
-        self.squared_diff_sum += price_diff * price_diff * volume;
-
         // Calculate bands
         let bands = self.calculate_bands(vwap);
 
@@ -292,9 +285,8 @@ impl Indicators for VolumeWeightedAveragePrice {
         self.is_ready = false;
         self.cumulative_pv = dec!(0.0);
         self.cumulative_volume = dec!(0.0);
-        self.squared_diff_sum = dec!(0.0);
-        self.last_reset_day = None;
-        self.was_last_bar_in_session = false;
+        self.cumulative_pv2 = dec!(0.0);
+        self.last_update = None;
     }
 
     fn index(&self, index: usize) -> Option<IndicatorValues> {
@@ -0,0 +1,97 @@
+use rust_decimal_macros::dec;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::candle::Candle;
+use crate::standardized_types::subscriptions::CandleType;
+use crate::strategies::indicators::indicator_values::{IndicatorValues, PlotName};
+use crate::strategies::indicators::indicators_trait::IndicatorName;
+
+/// The output of another indicator used as the data source of a derived indicator, subscribed with `strategy.subscribe_derived_indicator()`.
+///
+/// Each value of the source plot is sent to the derived indicator as a closed candle with the open, high, low and close all set to the plot value,
+/// the candle closes at the time of the source values, so any indicator that works on candles can be used on the output of another indicator.
+/// The derived indicator should be created with the subscription of the source indicator, since that is the subscription of the candles it receives.
+///
+/// Derived indicators are updated in the same time slice as their source, after it, and are warmed up from the history of the source.
+///
+/// # Example
+/// A 14 period SMA of a 5 period ATR.
+/// ```rust,ignore
+/// let atr = AverageTrueRange::new(IndicatorName::from("atr"), subscription.clone(), 100, 5, Color::new(50, 50, 255), false).await;
+/// strategy.subscribe_indicator(atr, None).await;
+///
+/// let atr_sma = MovingAverage::new(IndicatorName::from("atr_sma"), subscription.clone(), 100, 14, Color::new(255, 165, 0), false).await;
+/// strategy.subscribe_derived_indicator(atr_sma, DerivedSeries::new(IndicatorName::from("atr"), PlotName::from("atr"))).await.unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DerivedSeries {
+    /// The name of the indicator the series is taken from, it must be subscribed before the derived indicator.
+    pub source: IndicatorName,
+    /// The plot of the source indicator to use as the series.
+    pub plot: PlotName,
+}
+
+impl DerivedSeries {
+    pub fn new(source: IndicatorName, plot: PlotName) -> Self {
+        Self {
+            source,
+            plot,
+        }
+    }
+
+    /// Converts the source indicator values to the closed candle sent to the derived indicator, returns None if the values have no plot for the series.
+    pub fn to_base_data(&self, values: &IndicatorValues) -> Option<BaseDataEnum> {
+        let plot = values.plots.get(&self.plot)?;
+        let subscription = &values.subscription;
+        let time = values.time_utc() - subscription.resolution.as_duration();
+        let mut candle = Candle::new(
+            subscription.symbol.clone(),
+            plot.value,
+            dec!(0.0),
+            dec!(0.0),
+            dec!(0.0),
+            time.to_string(),
+            subscription.resolution,
+            subscription.candle_type.clone().unwrap_or(CandleType::CandleStick),
+        );
+        candle.is_closed = true;
+        Some(BaseDataEnum::Candle(candle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use chrono::{TimeZone, Utc};
+    use crate::gui_types::settings::Color;
+    use crate::standardized_types::base_data::base_data_type::BaseDataType;
+    use crate::standardized_types::base_data::traits::BaseData;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::MarketType;
+    use crate::standardized_types::resolution::Resolution;
+    use crate::standardized_types::subscriptions::DataSubscription;
+    use crate::strategies::indicators::indicator_values::IndicatorPlot;
+
+    #[test]
+    fn test_to_base_data() {
+        let subscription = DataSubscription::new("EUR-USD".to_string(), DataVendor::Oanda, Resolution::Minutes(5), BaseDataType::Candles, MarketType::Forex);
+        let close_time = Utc.with_ymd_and_hms(2024, 6, 3, 10, 5, 0).unwrap();
+        let mut plots = BTreeMap::new();
+        plots.insert("atr".to_string(), IndicatorPlot::new("atr".to_string(), dec!(0.0012), Color::new(0, 0, 0)));
+        let values = IndicatorValues::new("atr".to_string(), subscription.clone(), plots, close_time);
+
+        let series = DerivedSeries::new("atr".to_string(), "atr".to_string());
+        let candle = match series.to_base_data(&values) {
+            Some(BaseDataEnum::Candle(candle)) => candle,
+            _ => panic!("expected a candle"),
+        };
+        assert!(candle.is_closed);
+        assert_eq!(candle.open, dec!(0.0012));
+        assert_eq!(candle.close, dec!(0.0012));
+        assert_eq!(candle.time_closed_utc(), close_time);
+        assert_eq!(candle.subscription(), subscription);
+
+        let missing = DerivedSeries::new("atr".to_string(), "signal".to_string());
+        assert!(missing.to_base_data(&values).is_none());
+    }
+}
@@ -0,0 +1,37 @@
+use crate::standardized_types::subscriptions::DataSubscription;
+
+/// Overrides how an indicator is warmed up when it is subscribed with `strategy.subscribe_indicator_with_warmup()`.
+///
+/// By default an indicator is warmed up from the strategy's retained history, or by consolidating the best resolution offered by the vendor,
+/// with enough data to satisfy `indicator.data_required_warmup()`.
+/// Overriding the source or the length makes the cost of warming up an indicator predictable, and the result the same between runs.
+///
+/// # Example
+/// Warm a 5 minute ATR from stored 1 minute candles instead of ticks, using only 20 bars.
+/// ```rust,ignore
+/// let source = DataSubscription::new(SymbolName::from("MNQ"), DataVendor::Rithmic, Resolution::Minutes(1), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME));
+/// let warmup = IndicatorWarmup::from_source(source).with_length(20);
+/// strategy.subscribe_indicator_with_warmup(atr_5min, None, warmup).await;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndicatorWarmup {
+    /// The subscription to consolidate the warm up data from, it must be the same symbol and vendor as the indicator subscription and a lower resolution.
+    /// If the source can not be consolidated into the indicator subscription, the vendor resolutions are used.
+    pub source: Option<DataSubscription>,
+    /// The number of indicator subscription bars to warm up with, instead of `indicator.data_required_warmup()`.
+    pub length: Option<u64>,
+}
+
+impl IndicatorWarmup {
+    pub fn from_source(source: DataSubscription) -> Self {
+        Self {
+            source: Some(source),
+            length: None,
+        }
+    }
+
+    pub fn with_length(mut self, length: u64) -> Self {
+        self.length = Some(length);
+        self
+    }
+}
@@ -3,4 +3,6 @@ pub mod indicators_trait;
 pub mod traits;
 pub mod indicator_events;
 pub mod indicator_values;
+pub mod indicator_warmup;
 
+pub mod derived_series;
@@ -0,0 +1,282 @@
+use std::fs::{create_dir_all, write};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::accounts::{Account, Currency};
+use crate::standardized_types::enums::PositionSide;
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::subscriptions::SymbolCode;
+use crate::strategies::ledgers::ledger::{ClosedTrade, Ledger};
+
+/// An open position at the end of an `AccountStatement`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementPosition {
+    pub symbol_code: SymbolCode,
+    pub side: PositionSide,
+    pub quantity: Volume,
+    pub average_price: Price,
+    pub open_pnl: Price,
+}
+
+/// A summary of the account over a period, see `strategy.export_account_statements()` and `strategy.set_account_statements()`.
+///
+/// The statement lists the trades closed in the period with their fees, the realized and unrealized pnl, the balance after each trade and the open positions at the end of the period.
+/// The starting balance is the ending balance less the pnl booked by the trades of the period, so financing and cash adjustments made during the period are only included in the ending balance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountStatement {
+    pub account: Account,
+    pub currency: Currency,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub starting_balance: Decimal,
+    pub ending_balance: Decimal,
+    /// The trades with an exit time in the period, oldest exit first.
+    pub trades: Vec<ClosedTrade>,
+    /// The commissions of the trades.
+    pub fees: Decimal,
+    /// The booked pnl of the trades, after commissions.
+    pub realized_pnl: Decimal,
+    /// The open pnl of the open positions at the end of the period.
+    pub unrealized_pnl: Decimal,
+    /// The balance after each trade, starting with the starting balance at the start of the period.
+    pub balances: Vec<(DateTime<Utc>, Decimal)>,
+    pub open_positions: Vec<StatementPosition>,
+}
+
+impl AccountStatement {
+    /// Creates the statement from the closed trades of the account, trades which did not exit in the period are ignored.
+    pub fn new(
+        account: Account,
+        currency: Currency,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        ending_balance: Decimal,
+        closed_trades: Vec<ClosedTrade>,
+        open_positions: Vec<StatementPosition>,
+    ) -> Self {
+        let mut trades: Vec<ClosedTrade> = closed_trades.into_iter()
+            .filter(|trade| trade.exit_time > from && trade.exit_time <= to)
+            .collect();
+        trades.sort_by_key(|trade| trade.exit_time);
+        let fees = trades.iter().map(|trade| trade.commissions).sum();
+        let realized_pnl: Decimal = trades.iter().map(|trade| trade.pnl).sum();
+        let unrealized_pnl = open_positions.iter().map(|position| position.open_pnl).sum();
+        let starting_balance = ending_balance - realized_pnl;
+        let mut balance = starting_balance;
+        let mut balances = vec![(from, starting_balance)];
+        for trade in &trades {
+            balance += trade.pnl;
+            balances.push((trade.exit_time, balance));
+        }
+        Self {
+            account,
+            currency,
+            from,
+            to,
+            starting_balance,
+            ending_balance,
+            trades,
+            fees,
+            realized_pnl,
+            unrealized_pnl,
+            balances,
+            open_positions,
+        }
+    }
+
+    /// Returns the statement as a self-contained html document.
+    pub fn to_html(&self) -> String {
+        let summary = [
+            ("Starting Balance", self.starting_balance),
+            ("Realized PnL", self.realized_pnl),
+            ("Fees", self.fees),
+            ("Ending Balance", self.ending_balance),
+            ("Unrealized PnL", self.unrealized_pnl),
+            ("Ending Equity", self.ending_balance + self.unrealized_pnl),
+        ].iter()
+            .map(|(label, value)| format!("<tr><th>{}</th><td>{}</td></tr>", label, money(*value)))
+            .collect::<String>();
+
+        let trades = self.trades.iter()
+            .map(|trade| format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td{}>{}</td></tr>",
+                trade.entry_time.format("%Y-%m-%d %H:%M:%S"),
+                trade.exit_time.format("%Y-%m-%d %H:%M:%S"),
+                html_escape(&trade.symbol_code),
+                trade.side,
+                trade.exit_quantity.normalize(),
+                trade.entry_price.normalize(),
+                trade.exit_price.normalize(),
+                html_escape(&trade.tag),
+                money(trade.commissions),
+                pnl_class(trade.pnl),
+                money(trade.pnl),
+            ))
+            .collect::<String>();
+
+        let balances = self.balances.iter()
+            .map(|(time, balance)| format!("<tr><td>{}</td><td>{}</td></tr>", time.format("%Y-%m-%d %H:%M:%S"), money(*balance)))
+            .collect::<String>();
+
+        let positions = self.open_positions.iter()
+            .map(|position| format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td{}>{}</td></tr>",
+                html_escape(&position.symbol_code),
+                position.side,
+                position.quantity.normalize(),
+                position.average_price.normalize(),
+                pnl_class(position.open_pnl),
+                money(position.open_pnl),
+            ))
+            .collect::<String>();
+
+        STATEMENT_TEMPLATE
+            .replace("{{ACCOUNT}}", &html_escape(&self.account.to_string()))
+            .replace("{{CURRENCY}}", &self.currency.to_string())
+            .replace("{{FROM}}", &self.from.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .replace("{{TO}}", &self.to.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .replace("{{SUMMARY}}", &summary)
+            .replace("{{TRADES}}", &trades)
+            .replace("{{BALANCES}}", &balances)
+            .replace("{{POSITIONS}}", &positions)
+    }
+
+    /// Writes the statement to a html file in the folder and returns the file path.
+    pub fn export_html(&self, folder: &str) -> Result<String, FundForgeError> {
+        create_dir_all(folder)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create directory {}: {}", folder, e)))?;
+        let date = self.to.format("%Y%m%d_%H%M").to_string();
+        let file_name = format!("{}/Statement_{}_{}_{}.html", folder, self.account.brokerage, self.account.account_id, date);
+        write(&file_name, self.to_html())
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to write statement {}: {}", file_name, e)))?;
+        Ok(file_name)
+    }
+}
+
+impl Ledger {
+    /// The statement of the ledger for the trades closed between `from` and `to`, the balance and open positions are taken from the current state of the ledger.
+    pub fn account_statement(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> AccountStatement {
+        let open_positions = self.positions.iter()
+            .filter(|position| !position.is_closed)
+            .map(|position| StatementPosition {
+                symbol_code: position.symbol_code.clone(),
+                side: position.side,
+                quantity: position.quantity_open,
+                average_price: position.average_price,
+                open_pnl: position.open_pnl,
+            })
+            .collect();
+        AccountStatement::new(self.account.clone(), self.currency, from, to, self.balance(), self.closed_trades(), open_positions)
+    }
+}
+
+fn money(value: Decimal) -> String {
+    value.round_dp(2).to_string()
+}
+
+fn pnl_class(value: Decimal) -> &'static str {
+    match value {
+        value if value > Decimal::ZERO => " class=\"win\"",
+        value if value < Decimal::ZERO => " class=\"loss\"",
+        _ => "",
+    }
+}
+
+fn html_escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+const STATEMENT_TEMPLATE: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Statement {{ACCOUNT}}</title>
+<style>
+    body { font-family: sans-serif; margin: 24px; color: #222; }
+    h1 { font-size: 20px; margin-bottom: 4px; }
+    h2 { font-size: 16px; margin-top: 28px; }
+    .period { color: #666; }
+    table { border-collapse: collapse; font-size: 13px; }
+    th, td { border: 1px solid #ddd; padding: 4px 10px; text-align: right; }
+    th { background: #f4f4f4; }
+    td.win { color: #1a7f37; }
+    td.loss { color: #cf222e; }
+</style>
+</head>
+<body>
+<h1>Account Statement: {{ACCOUNT}}</h1>
+<div class="period">{{FROM}} to {{TO}}, amounts in {{CURRENCY}}</div>
+<h2>Summary</h2>
+<table>{{SUMMARY}}</table>
+<h2>Trades</h2>
+<table>
+<tr><th>Entry Time</th><th>Exit Time</th><th>Symbol</th><th>Side</th><th>Quantity</th><th>Entry Price</th><th>Exit Price</th><th>Tag</th><th>Fees</th><th>PnL</th></tr>
+{{TRADES}}
+</table>
+<h2>Balance</h2>
+<table>
+<tr><th>Time</th><th>Balance</th></tr>
+{{BALANCES}}
+</table>
+<h2>Open Positions</h2>
+<table>
+<tr><th>Symbol</th><th>Side</th><th>Quantity</th><th>Average Price</th><th>Open PnL</th></tr>
+{{POSITIONS}}
+</table>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::broker_enum::Brokerage;
+    use crate::standardized_types::position::TradeResult;
+
+    #[test]
+    fn test_account_statement() {
+        let trade = |exit_hour: u32, pnl: Decimal| ClosedTrade {
+            symbol_name: "NQ".to_string(),
+            symbol_code: "NQZ4".to_string(),
+            position_id: format!("Long-{}", exit_hour),
+            side: PositionSide::Long,
+            entry_price: dec!(17500),
+            entry_quantity: dec!(1),
+            exit_price: dec!(17550),
+            exit_quantity: dec!(1),
+            entry_time: Utc.with_ymd_and_hms(2024, 1, 2, exit_hour - 1, 0, 0).unwrap(),
+            exit_time: Utc.with_ymd_and_hms(2024, 1, 2, exit_hour, 0, 0).unwrap(),
+            pnl,
+            commissions: dec!(2.1),
+            tag: "<Enter>".to_string(),
+            result: TradeResult::Win,
+        };
+        let position = StatementPosition {
+            symbol_code: "NQZ4".to_string(),
+            side: PositionSide::Short,
+            quantity: dec!(1),
+            average_price: dec!(17600),
+            open_pnl: dec!(-40),
+        };
+        let from = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2024, 1, 2, 22, 0, 0).unwrap();
+        let account = Account::new(Brokerage::Test, "Test_Account_1".to_string());
+        // The trade exiting at 23:00 is after the period.
+        let trades = vec![trade(23, dec!(50)), trade(14, dec!(-100)), trade(10, dec!(200))];
+        let statement = AccountStatement::new(account, Currency::USD, from, to, dec!(100100), trades, vec![position]);
+
+        assert_eq!(statement.trades.len(), 2);
+        assert_eq!(statement.fees, dec!(4.2));
+        assert_eq!(statement.realized_pnl, dec!(100));
+        assert_eq!(statement.unrealized_pnl, dec!(-40));
+        assert_eq!(statement.starting_balance, dec!(100000));
+        let balances: Vec<Decimal> = statement.balances.iter().map(|(_, balance)| *balance).collect();
+        assert_eq!(balances, vec![dec!(100000), dec!(100200), dec!(100100)]);
+
+        let html = statement.to_html();
+        assert!(html.contains("&lt;Enter&gt;"));
+        assert!(html.contains("<th>Ending Equity</th><td>100060</td>"));
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::fs::create_dir_all;
+use std::path::Path;
+use std::str::FromStr;
+use chrono::{DateTime, Duration, Utc};
+use csv::Writer;
+use rust_decimal_macros::dec;
+use serde_derive::{Deserialize, Serialize};
+use crate::standardized_types::enums::PositionSide;
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::position::{Position, PositionId};
+use crate::standardized_types::subscriptions::SymbolName;
+use crate::strategies::ledgers::ledger::Ledger;
+
+/// How positions are grouped into campaigns, positions are only grouped with positions on the same symbol name and side.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CampaignGrouping {
+    /// A position which opens more than `max_gap` after every earlier position in the campaign has closed starts a new campaign.
+    /// `None` groups all positions into one campaign.
+    pub max_gap: Option<Duration>,
+    /// If true only positions with the same tag are grouped.
+    pub by_tag: bool,
+}
+
+impl CampaignGrouping {
+    pub fn time_gap(max_gap: Duration) -> Self {
+        Self { max_gap: Some(max_gap), by_tag: false }
+    }
+
+    pub fn tag() -> Self {
+        Self { max_gap: None, by_tag: true }
+    }
+}
+
+/// Consecutive positions which are logically one trade, eg. the entries of a pyramiding strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Campaign {
+    pub symbol_name: SymbolName,
+    pub side: PositionSide,
+    /// The tag of the first position.
+    pub tag: String,
+    pub position_ids: Vec<PositionId>,
+    pub start_time: DateTime<Utc>,
+    /// `None` while a position in the campaign is open.
+    pub end_time: Option<DateTime<Utc>>,
+    pub booked_pnl: Price,
+    pub open_pnl: Price,
+    /// The open quantity of the campaign after each position opens and after each exit, in time order.
+    /// Quantity added to an open position is recorded at the time the position opened, the ledger does not keep the time of each entry.
+    pub size_progression: Vec<(DateTime<Utc>, Volume)>,
+    pub max_quantity: Volume,
+    /// The largest fall of the campaign pnl from its high, using the booked pnl after each exit and the open pnl of open campaigns.
+    pub max_drawdown: Price,
+    pub trades: usize,
+}
+
+impl Campaign {
+    pub fn total_pnl(&self) -> Price {
+        self.booked_pnl + self.open_pnl
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.end_time.is_none()
+    }
+
+    fn to_export(&self) -> CampaignExport {
+        CampaignExport {
+            symbol_name: self.symbol_name.clone(),
+            side: self.side.to_string(),
+            tag: self.tag.clone(),
+            positions: self.position_ids.len(),
+            trades: self.trades,
+            start_time: self.start_time.to_string(),
+            end_time: self.end_time.map(|time| time.to_string()).unwrap_or_default(),
+            booked_pnl: self.booked_pnl,
+            open_pnl: self.open_pnl,
+            max_quantity: self.max_quantity,
+            max_drawdown: self.max_drawdown,
+            size_progression: self.size_progression.iter()
+                .map(|(time, quantity)| format!("{}@{}", quantity, time))
+                .collect::<Vec<String>>()
+                .join(";"),
+        }
+    }
+}
+
+/// A single campaign as written by `export_campaigns_to_csv()`, the size progression is written as `quantity@time` separated by `;`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CampaignExport {
+    pub symbol_name: String,
+    pub side: String,
+    pub tag: String,
+    pub positions: usize,
+    pub trades: usize,
+    pub start_time: String,
+    pub end_time: String,
+    pub booked_pnl: Price,
+    pub open_pnl: Price,
+    pub max_quantity: Volume,
+    pub max_drawdown: Price,
+    pub size_progression: String,
+}
+
+/// The parts of a position used to build campaigns.
+#[derive(Debug, Clone)]
+struct CampaignLeg {
+    symbol_name: SymbolName,
+    side: PositionSide,
+    tag: String,
+    position_id: PositionId,
+    open_time: DateTime<Utc>,
+    close_time: Option<DateTime<Utc>>,
+    entry_quantity: Volume,
+    open_pnl: Price,
+    /// The time, quantity and pnl of each exit.
+    exits: Vec<(DateTime<Utc>, Volume, Price)>,
+}
+
+impl CampaignLeg {
+    fn from_position(position: &Position) -> Option<Self> {
+        let time = |time: &str| DateTime::<Utc>::from_str(time).ok();
+        let exits: Vec<(DateTime<Utc>, Volume, Price)> = position.completed_trades.iter()
+            .filter_map(|trade| Some((time(&trade.exit_time)?, trade.exit_quantity, trade.profit)))
+            .collect();
+        let exited: Volume = exits.iter().map(|(_, quantity, _)| *quantity).sum();
+        Some(Self {
+            symbol_name: position.symbol_name.clone(),
+            side: position.side,
+            tag: position.tag.clone(),
+            position_id: position.position_id.clone(),
+            open_time: time(&position.open_time)?,
+            close_time: match position.is_closed {
+                true => position.close_time.as_deref().and_then(time),
+                false => None,
+            },
+            entry_quantity: exited + if position.is_closed { dec!(0) } else { position.quantity_open },
+            open_pnl: if position.is_closed { dec!(0) } else { position.open_pnl },
+            exits,
+        })
+    }
+}
+
+/// Groups the legs in open time order, a leg joins the latest campaign for its key unless it opens more than the max gap after the campaign closed.
+fn group_legs(mut legs: Vec<CampaignLeg>, grouping: &CampaignGrouping) -> Vec<Campaign> {
+    legs.sort_by_key(|leg| leg.open_time);
+    let mut groups: Vec<Vec<CampaignLeg>> = vec![];
+    let mut latest: BTreeMap<(SymbolName, PositionSide, String), usize> = BTreeMap::new();
+    for leg in legs {
+        let tag = if grouping.by_tag { leg.tag.clone() } else { String::new() };
+        let key = (leg.symbol_name.clone(), leg.side, tag);
+        let joins = latest.get(&key).filter(|index| {
+            let group = &groups[**index];
+            // The campaign is open until every leg has closed.
+            let closed_at = group.iter().map(|leg| leg.close_time).collect::<Option<Vec<_>>>().and_then(|times| times.into_iter().max());
+            match (grouping.max_gap, closed_at) {
+                (Some(max_gap), Some(closed_at)) => leg.open_time - closed_at <= max_gap,
+                _ => true,
+            }
+        }).cloned();
+        match joins {
+            Some(index) => groups[index].push(leg),
+            None => {
+                latest.insert(key, groups.len());
+                groups.push(vec![leg]);
+            }
+        }
+    }
+    groups.into_iter().map(campaign_from_legs).collect()
+}
+
+fn campaign_from_legs(legs: Vec<CampaignLeg>) -> Campaign {
+    let first = &legs[0];
+    let mut changes: Vec<(DateTime<Utc>, Volume, Price)> = vec![];
+    for leg in &legs {
+        changes.push((leg.open_time, leg.entry_quantity, dec!(0)));
+        changes.extend(leg.exits.iter().map(|(time, quantity, pnl)| (*time, -*quantity, *pnl)));
+    }
+    changes.sort_by_key(|(time, _, _)| *time);
+
+    let open_pnl: Price = legs.iter().map(|leg| leg.open_pnl).sum();
+    let end_time = legs.iter().map(|leg| leg.close_time).collect::<Option<Vec<_>>>().and_then(|times| times.into_iter().max());
+    let (mut quantity, mut max_quantity) = (dec!(0), dec!(0));
+    let (mut pnl, mut high, mut max_drawdown) = (dec!(0), dec!(0), dec!(0));
+    let mut size_progression = vec![];
+    for (time, change, booked) in &changes {
+        quantity += change;
+        max_quantity = max_quantity.max(quantity);
+        size_progression.push((*time, quantity));
+        pnl += booked;
+        high = high.max(pnl);
+        max_drawdown = max_drawdown.max(high - pnl);
+    }
+    if end_time.is_none() {
+        max_drawdown = max_drawdown.max(high - (pnl + open_pnl));
+    }
+
+    Campaign {
+        symbol_name: first.symbol_name.clone(),
+        side: first.side,
+        tag: first.tag.clone(),
+        position_ids: legs.iter().map(|leg| leg.position_id.clone()).collect(),
+        start_time: first.open_time,
+        end_time,
+        booked_pnl: pnl,
+        open_pnl,
+        size_progression,
+        max_quantity,
+        max_drawdown,
+        trades: legs.iter().map(|leg| leg.exits.len()).sum(),
+    }
+}
+
+impl Ledger {
+    /// The open and closed positions of the ledger grouped into campaigns, in start time order.
+    pub fn campaigns(&self, grouping: &CampaignGrouping) -> Vec<Campaign> {
+        let mut legs = vec![];
+        for position in self.positions.iter() {
+            if !position.is_closed {
+                legs.extend(CampaignLeg::from_position(position.value()));
+            }
+        }
+        for closed in self.positions_closed.iter() {
+            legs.extend(closed.value().iter().filter_map(CampaignLeg::from_position));
+        }
+        group_legs(legs, grouping)
+    }
+
+    pub fn export_campaigns_to_csv(&self, folder: &str, grouping: &CampaignGrouping) {
+        if let Err(e) = create_dir_all(folder) {
+            tracing::error!("Failed to create directory {}: {}", folder, e);
+            return;
+        }
+
+        let date = Utc::now().format("%Y%m%d_%H%M").to_string();
+        let brokerage = self.account.brokerage.to_string();
+        let file_name = format!("{}/{:?}_CampaignResults_{}_{}_{}.csv", folder, self.mode, brokerage, self.account.account_id, date);
+
+        let file_path = Path::new(&file_name);
+        match Writer::from_path(file_path) {
+            Ok(mut wtr) => {
+                for campaign in self.campaigns(grouping) {
+                    if let Err(e) = wtr.serialize(campaign.to_export()) {
+                        tracing::error!("Failed to write campaign data to {}: {}", file_path.display(), e);
+                    }
+                }
+
+                if let Err(e) = wtr.flush() {
+                    tracing::error!("Failed to flush CSV writer for {}: {}", file_path.display(), e);
+                } else {
+                    tracing::info!("Successfully exported all campaigns to {}", file_path.display());
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to create CSV writer for {}: {}", file_path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_group_legs_by_time_gap() {
+        let time = |hour| Utc.with_ymd_and_hms(2024, 6, 3, hour, 0, 0).unwrap();
+        let leg = |id: &str, open: u32, close: Option<u32>, quantity: Volume, exits: Vec<(u32, Volume, Price)>, open_pnl: Price| CampaignLeg {
+            symbol_name: "MNQ".to_string(),
+            side: PositionSide::Long,
+            tag: "pyramid".to_string(),
+            position_id: id.to_string(),
+            open_time: time(open),
+            close_time: close.map(time),
+            entry_quantity: quantity,
+            open_pnl,
+            exits: exits.into_iter().map(|(hour, quantity, pnl)| (time(hour), quantity, pnl)).collect(),
+        };
+        let legs = vec![
+            leg("1", 1, Some(3), dec!(2), vec![(3, dec!(2), dec!(100))], dec!(0)),
+            leg("2", 2, Some(4), dec!(1), vec![(4, dec!(1), dec!(-150))], dec!(0)),
+            // Opens within the gap of the campaign closing at 04:00.
+            leg("3", 5, None, dec!(3), vec![], dec!(20)),
+            leg("4", 9, Some(10), dec!(1), vec![(10, dec!(1), dec!(10))], dec!(0)),
+        ];
+        let campaigns = group_legs(legs.clone(), &CampaignGrouping::time_gap(Duration::hours(1)));
+        assert_eq!(campaigns.len(), 1);
+
+        let campaigns = group_legs(legs[..2].iter().cloned().chain([legs[3].clone()]).collect(), &CampaignGrouping::time_gap(Duration::hours(1)));
+        assert_eq!(campaigns.len(), 2);
+        let first = &campaigns[0];
+        assert_eq!(first.position_ids, vec!["1".to_string(), "2".to_string()]);
+        assert_eq!(first.max_quantity, dec!(3));
+        assert_eq!(first.booked_pnl, dec!(-50));
+        assert_eq!(first.max_drawdown, dec!(150));
+        assert_eq!(first.end_time, Some(time(4)));
+        assert_eq!(first.size_progression.last(), Some(&(time(4), dec!(0))));
+    }
+}
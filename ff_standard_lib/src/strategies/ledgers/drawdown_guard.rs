@@ -0,0 +1,227 @@
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::new_types::Volume;
+
+lazy_static! {
+    static ref DRAWDOWN_POLICIES: DashMap<Account, DrawdownPolicy> = DashMap::new();
+}
+
+/// Sets the staged drawdown response for the account, see `DrawdownPolicy`.
+/// The policy can be set or replaced at any time, it takes effect at the next time update of the strategy.
+pub fn set_drawdown_policy(account: Account, policy: DrawdownPolicy) {
+    DRAWDOWN_POLICIES.insert(account, policy);
+}
+
+/// Removes the policy of the account, the account returns to `RiskStage::Normal` at the next time update.
+pub fn remove_drawdown_policy(account: &Account) {
+    DRAWDOWN_POLICIES.remove(account);
+}
+
+/// The drawdown policy of the account, `None` if the account has no policy.
+pub fn drawdown_policy(account: &Account) -> Option<DrawdownPolicy> {
+    DRAWDOWN_POLICIES.get(account).map(|policy| policy.value().clone())
+}
+
+/// The stage of the drawdown response of an account, each stage includes the restrictions of the stages before it.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug, Copy, Ord, PartialOrd, Eq)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum RiskStage {
+    Normal,
+    /// Entry quantities are multiplied by the `size_multiplier` of the policy.
+    ReducedSize,
+    /// Orders which would open or increase a position are rejected.
+    EntriesBlocked,
+    /// The open orders were cancelled and the account flattened.
+    Flattened,
+}
+
+impl RiskStage {
+    /// True if orders which would open or increase a position are rejected.
+    pub fn blocks_entries(&self) -> bool {
+        *self >= RiskStage::EntriesBlocked
+    }
+}
+
+/// A staged response to the intraday drawdown of an account, each threshold is a percentage of the highest equity reached since the last reset, eg. `dec!(2)` for 2%.
+///
+/// At `reduce_size_at` the quantity of entries is multiplied by `size_multiplier` and rounded down to a multiple of `lot_size`,
+/// at `block_entries_at` orders which would open or increase a position are rejected and at `flatten_at` the open orders are cancelled and the account is flattened.
+/// A threshold of `None` skips the stage.
+///
+/// The equity is the balance plus the open pnl of the account.
+/// Within a session the stage only moves up, recovering equity does not lift the restrictions.
+/// At `reset_time` the peak is reset to the current equity and the account returns to `RiskStage::Normal`.
+///
+/// A `StrategyEvent::RiskStageChanged` is sent each time the stage changes, so the strategy can also react.
+/// # Example
+/// ```rust
+/// use rust_decimal_macros::dec;
+/// use ff_standard_lib::apis::rithmic::rithmic_systems::RithmicSystem;
+/// use ff_standard_lib::standardized_types::accounts::Account;
+/// use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+/// use ff_standard_lib::strategies::ledgers::drawdown_guard::{set_drawdown_policy, DrawdownPolicy};
+///
+/// let account = Account::new(Brokerage::Rithmic(RithmicSystem::Apex), "APEX-1234".to_string());
+/// let mut policy = DrawdownPolicy::default();
+/// policy.reduce_size_at = Some(dec!(1));
+/// policy.block_entries_at = Some(dec!(2));
+/// policy.flatten_at = Some(dec!(3));
+/// set_drawdown_policy(account, policy);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawdownPolicy {
+    pub reduce_size_at: Option<Decimal>,
+    pub size_multiplier: Decimal,
+    pub block_entries_at: Option<Decimal>,
+    pub flatten_at: Option<Decimal>,
+    pub lot_size: Volume,
+    pub reset_time_zone: Tz,
+    pub reset_time: NaiveTime,
+}
+
+impl Default for DrawdownPolicy {
+    /// No stages, entries are halved once `reduce_size_at` is set, sessions reset at 5pm New York time.
+    fn default() -> Self {
+        Self {
+            reduce_size_at: None,
+            size_multiplier: dec!(0.5),
+            block_entries_at: None,
+            flatten_at: None,
+            lot_size: dec!(1),
+            reset_time_zone: chrono_tz::America::New_York,
+            reset_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        }
+    }
+}
+
+impl DrawdownPolicy {
+    /// The highest stage reached at the drawdown percentage.
+    pub fn stage(&self, drawdown_percent: Decimal) -> RiskStage {
+        let reached = |threshold: Option<Decimal>| threshold.is_some_and(|threshold| drawdown_percent >= threshold);
+        if reached(self.flatten_at) {
+            RiskStage::Flattened
+        } else if reached(self.block_entries_at) {
+            RiskStage::EntriesBlocked
+        } else if reached(self.reduce_size_at) {
+            RiskStage::ReducedSize
+        } else {
+            RiskStage::Normal
+        }
+    }
+
+    /// The quantity allowed for an entry at the stage.
+    pub fn allowed_quantity(&self, stage: RiskStage, quantity: Volume) -> Volume {
+        match stage {
+            RiskStage::Normal => quantity,
+            RiskStage::ReducedSize => {
+                let scaled = quantity * self.size_multiplier;
+                match self.lot_size > dec!(0) {
+                    true => (scaled / self.lot_size).floor() * self.lot_size,
+                    false => scaled
+                }
+            }
+            RiskStage::EntriesBlocked | RiskStage::Flattened => dec!(0),
+        }
+    }
+
+    /// The first reset after the time.
+    pub fn next_reset(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let mut date = time.with_timezone(&self.reset_time_zone).date_naive();
+        loop {
+            // A reset inside a daylight saving gap is skipped.
+            if let Some(reset) = self.reset_time_zone.from_local_datetime(&date.and_time(self.reset_time)).earliest() {
+                let reset = reset.with_timezone(&Utc);
+                if reset > time {
+                    return reset;
+                }
+            }
+            date += Duration::days(1);
+        }
+    }
+}
+
+/// The intraday state of a `DrawdownPolicy` for one account.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DrawdownGuard {
+    pub stage: RiskStage,
+    pub peak_equity: Decimal,
+    next_reset: Option<DateTime<Utc>>,
+}
+
+impl DrawdownGuard {
+    pub fn new() -> Self {
+        Self {
+            stage: RiskStage::Normal,
+            peak_equity: dec!(0),
+            next_reset: None,
+        }
+    }
+
+    /// The drawdown from the peak equity as a percentage.
+    pub fn drawdown_percent(&self, equity: Decimal) -> Decimal {
+        if self.peak_equity <= dec!(0) || equity >= self.peak_equity {
+            return dec!(0);
+        }
+        (self.peak_equity - equity) / self.peak_equity * dec!(100)
+    }
+
+    /// Updates the peak and the stage with the equity, returns the new stage and the drawdown percentage if the stage changed.
+    pub fn update(&mut self, policy: &DrawdownPolicy, equity: Decimal, time: DateTime<Utc>) -> Option<(RiskStage, Decimal)> {
+        let previous = self.stage;
+        if self.next_reset.is_none_or(|reset| time >= reset) {
+            self.next_reset = Some(policy.next_reset(time));
+            self.peak_equity = equity;
+            self.stage = RiskStage::Normal;
+        }
+        if equity > self.peak_equity {
+            self.peak_equity = equity;
+        }
+        let drawdown = self.drawdown_percent(equity);
+        self.stage = self.stage.max(policy.stage(drawdown));
+        match self.stage != previous {
+            true => Some((self.stage, drawdown.round_dp(2))),
+            false => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_drawdown_stages() {
+        let mut policy = DrawdownPolicy::default();
+        policy.reduce_size_at = Some(dec!(1));
+        policy.block_entries_at = Some(dec!(2));
+        policy.flatten_at = Some(dec!(3));
+        let time = |time: &str| DateTime::<Utc>::from_str(time).unwrap();
+
+        let mut guard = DrawdownGuard::new();
+        assert_eq!(guard.update(&policy, dec!(100000), time("2024-06-04T14:00:00Z")), None);
+        assert_eq!(guard.update(&policy, dec!(102000), time("2024-06-04T14:01:00Z")), None);
+        // 1,530 from the 102,000 peak is a 1.5% drawdown.
+        assert_eq!(guard.update(&policy, dec!(100470), time("2024-06-04T14:02:00Z")), Some((RiskStage::ReducedSize, dec!(1.5))));
+        assert_eq!(policy.allowed_quantity(guard.stage, dec!(3)), dec!(1));
+        assert_eq!(guard.update(&policy, dec!(99960), time("2024-06-04T14:03:00Z")), Some((RiskStage::EntriesBlocked, dec!(2))));
+        assert!(guard.stage.blocks_entries());
+
+        // Recovering does not lift the restrictions until the reset.
+        assert_eq!(guard.update(&policy, dec!(101500), time("2024-06-04T15:00:00Z")), None);
+        assert_eq!(guard.stage, RiskStage::EntriesBlocked);
+        assert_eq!(guard.update(&policy, dec!(98000), time("2024-06-04T16:00:00Z")), Some((RiskStage::Flattened, dec!(3.92))));
+        assert_eq!(policy.allowed_quantity(guard.stage, dec!(3)), dec!(0));
+
+        // 17:00 New York is 21:00 UTC in June.
+        assert_eq!(guard.update(&policy, dec!(98000), time("2024-06-04T21:00:00Z")), Some((RiskStage::Normal, dec!(0))));
+        assert_eq!(guard.peak_equity, dec!(98000));
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::helpers::decimal_calculators::to_account_currency;
+use crate::standardized_types::broker_enum::Brokerage;
+use crate::standardized_types::enums::{OrderSide, PositionSide};
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::subscriptions::SymbolName;
+use crate::standardized_types::symbol_info::{FinancingRate, SymbolInfo};
+use crate::strategies::ledgers::ledger::Ledger;
+
+lazy_static! {
+    static ref FINANCING_MODELS: DashMap<Brokerage, FinancingModel> = DashMap::new();
+    /// The rates received from the brokerages, `None` when the brokerage has no rate for the symbol.
+    static ref BROKER_RATES: DashMap<(Brokerage, SymbolName), Option<FinancingRate>> = DashMap::new();
+}
+
+/// Replaces the financing model used by the paper ledgers of the brokerage, see `FinancingModel`.
+pub fn set_financing_model(brokerage: Brokerage, model: FinancingModel) {
+    FINANCING_MODELS.insert(brokerage, model);
+}
+
+/// The financing model for the brokerage, the default model is used until one is set.
+pub fn financing_model(brokerage: Brokerage) -> FinancingModel {
+    FINANCING_MODELS.entry(brokerage)
+        .or_insert_with(|| FinancingModel::default_for(brokerage))
+        .clone()
+}
+
+/// The overnight financing applied by the paper ledgers to positions held through the daily rollover.
+///
+/// Each time the engine time crosses the rollover, the open positions are charged or paid the financing of the days charged for the weekday of the rollover,
+/// `position value * rate * days charged / days_per_year`, converted to the account currency and applied to the cash of the ledger.
+///
+/// The `rates` are used first, with `use_broker_rates` symbols without a rate use the rates the brokerage currently applies, see `Brokerage::financing_rate()`.
+/// Brokerage rates are today's rates, set `rates` to backtest with the rates of the period being tested.
+/// Symbols with no rate are not financed.
+/// # Example
+/// ```rust
+/// use rust_decimal_macros::dec;
+/// use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+/// use ff_standard_lib::standardized_types::symbol_info::FinancingRate;
+/// use ff_standard_lib::strategies::ledgers::financing::{financing_model, set_financing_model};
+///
+/// let mut model = financing_model(Brokerage::Oanda);
+/// model.rates.insert("EUR-USD".to_string(), FinancingRate::new(dec!(-0.0612), dec!(0.0215)));
+/// set_financing_model(Brokerage::Oanda, model);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinancingModel {
+    pub rollover_time_zone: Tz,
+    pub rollover_time: NaiveTime,
+    pub days_per_year: Decimal,
+    pub rates: HashMap<SymbolName, FinancingRate>,
+    pub use_broker_rates: bool,
+}
+
+impl FinancingModel {
+    /// The rollover is 5pm New York time, Oanda uses its own rates, other brokerages are not financed until rates are added.
+    pub fn default_for(brokerage: Brokerage) -> Self {
+        Self {
+            rollover_time_zone: chrono_tz::America::New_York,
+            rollover_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            days_per_year: dec!(365),
+            rates: HashMap::new(),
+            use_broker_rates: brokerage == Brokerage::Oanda,
+        }
+    }
+
+    /// The first rollover after the time.
+    pub fn next_rollover(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let mut date = time.with_timezone(&self.rollover_time_zone).date_naive();
+        loop {
+            // A rollover inside a daylight saving gap is skipped.
+            if let Some(rollover) = self.rollover_time_zone.from_local_datetime(&date.and_time(self.rollover_time)).earliest() {
+                let rollover = rollover.with_timezone(&Utc);
+                if rollover > time {
+                    return rollover;
+                }
+            }
+            date += Duration::days(1);
+        }
+    }
+
+    /// The number of days of financing charged at the rollover.
+    pub fn days_charged(&self, rate: &FinancingRate, rollover: DateTime<Utc>) -> u32 {
+        let weekday = rollover.with_timezone(&self.rollover_time_zone).weekday();
+        rate.days_charged[weekday.num_days_from_monday() as usize]
+    }
+
+    /// The financing of a position worth `value` for the days in the pnl currency of the symbol, negative when charged.
+    pub fn financing(&self, rate: &FinancingRate, side: PositionSide, value: Price, days: u32) -> Price {
+        let annual_rate = match side {
+            PositionSide::Long => rate.long_rate,
+            PositionSide::Short => rate.short_rate,
+            _ => return dec!(0),
+        };
+        if self.days_per_year <= dec!(0) {
+            return dec!(0);
+        }
+        value * annual_rate * Decimal::from(days) / self.days_per_year
+    }
+
+    async fn rate(&self, brokerage: Brokerage, symbol_name: &SymbolName) -> Option<FinancingRate> {
+        if let Some(rate) = self.rates.get(symbol_name) {
+            return Some(rate.clone());
+        }
+        if !self.use_broker_rates {
+            return None;
+        }
+        let key = (brokerage, symbol_name.clone());
+        if let Some(rate) = BROKER_RATES.get(&key) {
+            return rate.value().clone();
+        }
+        let rate = match brokerage.financing_rate(symbol_name.clone()).await {
+            Ok(rate) => Some(rate),
+            Err(e) => {
+                tracing::warn!("{}: No financing rate for {}, the positions will not be financed: {}", brokerage, symbol_name, e);
+                None
+            }
+        };
+        BROKER_RATES.insert(key, rate.clone());
+        rate
+    }
+}
+
+/// The value of the quantity at the price in the pnl currency of the symbol.
+fn position_value(symbol_info: &SymbolInfo, quantity: Volume, price: Price) -> Price {
+    if symbol_info.tick_size <= dec!(0) {
+        return dec!(0);
+    }
+    quantity * price / symbol_info.tick_size * symbol_info.value_per_tick
+}
+
+impl Ledger {
+    /// Applies the financing of each rollover the engine time has crossed since the last update, the first update only schedules the next rollover.
+    pub(crate) async fn accrue_financing(&mut self, time: DateTime<Utc>) {
+        let next_rollover = match self.next_rollover {
+            Some(next_rollover) => next_rollover,
+            None => {
+                self.next_rollover = Some(financing_model(self.account.brokerage).next_rollover(time));
+                return;
+            }
+        };
+        if time < next_rollover {
+            return;
+        }
+        let model = financing_model(self.account.brokerage);
+        let mut rollover = next_rollover;
+        while rollover <= time {
+            self.apply_financing(&model, rollover).await;
+            rollover = model.next_rollover(rollover);
+        }
+        self.next_rollover = Some(rollover);
+    }
+
+    async fn apply_financing(&mut self, model: &FinancingModel, rollover: DateTime<Utc>) {
+        let positions: Vec<_> = self.positions.iter()
+            .filter(|position| !position.is_closed)
+            .map(|position| (position.symbol_name.clone(), position.symbol_code.clone(), position.side, position.quantity_open, position.average_price, position.symbol_info.clone(), position.exchange_rate_multiplier))
+            .collect();
+        let mut total = dec!(0);
+        for (symbol_name, symbol_code, side, quantity, average_price, symbol_info, exchange_rate_multiplier) in positions {
+            let rate = match model.rate(self.account.brokerage, &symbol_name).await {
+                Some(rate) => rate,
+                None => continue,
+            };
+            let days = model.days_charged(&rate, rollover);
+            if days == 0 {
+                continue;
+            }
+            // Positions are valued at the price they would close at.
+            let close_side = match side {
+                PositionSide::Long => OrderSide::Sell,
+                _ => OrderSide::Buy,
+            };
+            let price = self.market_price_service.get_market_price(close_side, &symbol_name, &symbol_code).unwrap_or(average_price);
+            let financing = model.financing(&rate, side, position_value(&symbol_info, quantity, price), days);
+            let financing = to_account_currency(financing, price, &symbol_info, exchange_rate_multiplier, self.currency);
+            tracing::debug!("{}: {} financing of {} {} for {} days at {}", self.account, symbol_code, financing.round_dp(2), self.currency, days, rollover);
+            total += financing;
+        }
+        self.cash_available += total;
+        self.total_financing += total;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use crate::standardized_types::accounts::Currency;
+
+    #[test]
+    fn test_rollover_schedule() {
+        let model = FinancingModel::default_for(Brokerage::Oanda);
+        // 17:00 New York is 21:00 UTC in June and 22:00 UTC in December.
+        let time = DateTime::<Utc>::from_str("2024-06-04T15:00:00Z").unwrap();
+        assert_eq!(model.next_rollover(time), DateTime::<Utc>::from_str("2024-06-04T21:00:00Z").unwrap());
+        let rollover = model.next_rollover(DateTime::<Utc>::from_str("2024-06-04T21:00:00Z").unwrap());
+        assert_eq!(rollover, DateTime::<Utc>::from_str("2024-06-05T21:00:00Z").unwrap());
+        assert_eq!(model.next_rollover(DateTime::<Utc>::from_str("2024-12-04T21:30:00Z").unwrap()), DateTime::<Utc>::from_str("2024-12-04T22:00:00Z").unwrap());
+
+        // Wednesday carries the weekend.
+        let rate = FinancingRate::new(dec!(-0.0612), dec!(0.0215));
+        assert_eq!(model.days_charged(&rate, rollover), 3);
+        assert_eq!(model.days_charged(&rate, DateTime::<Utc>::from_str("2024-06-08T21:00:00Z").unwrap()), 0);
+        assert!(!FinancingModel::default_for(Brokerage::Test).use_broker_rates);
+    }
+
+    #[test]
+    fn test_financing_amount() {
+        let model = FinancingModel::default_for(Brokerage::Oanda);
+        let rate = FinancingRate::new(dec!(-0.0365), dec!(0.0073));
+        let symbol_info = SymbolInfo::new("EUR-USD".to_string(), Some(Currency::EUR), Currency::USD, dec!(0.00001), dec!(0.00001), 5);
+
+        // 100,000 EUR at 1.1 is worth 110,000 USD, one day at -3.65% per year charges 11 USD.
+        let value = position_value(&symbol_info, dec!(100000), dec!(1.1));
+        assert_eq!(value, dec!(110000));
+        assert_eq!(model.financing(&rate, PositionSide::Long, value, 1), dec!(-11));
+        assert_eq!(model.financing(&rate, PositionSide::Short, value, 3), dec!(6.6));
+        assert_eq!(to_account_currency(dec!(-11), dec!(1.1), &symbol_info, dec!(1), Currency::EUR), dec!(-10));
+    }
+}
@@ -0,0 +1,354 @@
+use std::cmp::min;
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use rust_decimal_macros::dec;
+use tokio::sync::oneshot;
+use crate::standardized_types::accounts::{Account, Currency};
+use crate::standardized_types::enums::{OrderSide, PositionSide, StrategyMode};
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::orders::{Order, OrderId, OrderType, OrderUpdateEvent};
+use crate::standardized_types::position::{Position, PositionId, PositionUpdateEvent};
+use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
+use crate::strategies::client_features::other_requests::get_exchange_rate;
+use crate::strategies::ledgers::ledger::Ledger;
+use crate::strategies::strategy_events::StrategyEvent;
+
+lazy_static! {
+    static ref HEDGING_ACCOUNTS: DashMap<Account, bool> = DashMap::new();
+}
+
+/// Sets whether the paper ledger of the account is a hedging account, this must be called before `FundForgeStrategy::initialize()`.
+/// Live ledgers use the hedging mode reported by the brokerage in `AccountInfo::is_hedging`.
+pub fn set_hedging_account(account: Account, is_hedging: bool) {
+    HEDGING_ACCOUNTS.insert(account, is_hedging);
+}
+
+/// True if the paper ledger of the account holds separate positions per fill, paper accounts are netting accounts unless set with `set_hedging_account()`.
+pub fn is_hedging_account(account: &Account) -> bool {
+    HEDGING_ACCOUNTS.get(account).map(|is_hedging| *is_hedging).unwrap_or(false)
+}
+
+/// The positions a fill applies to in a hedging ledger, netting ledgers always fill against the position of the symbol code.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PositionTarget {
+    /// Opens a new position.
+    New,
+    /// Reduces the positions of the side, oldest first.
+    Side(PositionSide),
+    /// Reduces the position with the id.
+    Id(PositionId),
+}
+
+impl PositionTarget {
+    pub(crate) fn from_order(order: &Order) -> Self {
+        if let Some(position_id) = &order.position_id {
+            return PositionTarget::Id(position_id.clone());
+        }
+        match order.order_type {
+            OrderType::ExitLong => PositionTarget::Side(PositionSide::Long),
+            OrderType::ExitShort => PositionTarget::Side(PositionSide::Short),
+            _ => PositionTarget::New,
+        }
+    }
+}
+
+impl Ledger {
+    /// The positions of the symbol code, one at most for a netting ledger.
+    fn symbol_positions(&self, symbol_code: &SymbolCode) -> Vec<Position> {
+        match self.is_hedging {
+            false => self.positions.get(symbol_code).map(|position| vec![position.value().clone()]).unwrap_or_default(),
+            true => self.positions.iter()
+                .filter(|position| position.symbol_code == *symbol_code)
+                .map(|position| position.value().clone())
+                .collect(),
+        }
+    }
+
+    /// The open positions of the symbol code, oldest first.
+    pub fn open_positions(&self, symbol_code: &SymbolCode) -> Vec<Position> {
+        let mut positions = self.symbol_positions(symbol_code);
+        positions.sort_by_key(|position| DateTime::<Utc>::from_str(&position.open_time).ok());
+        positions
+    }
+
+    /// The open position with the id.
+    pub fn position(&self, position_id: &PositionId) -> Option<Position> {
+        self.positions.iter()
+            .find(|position| position.position_id == *position_id)
+            .map(|position| position.value().clone())
+    }
+
+    /// The total open quantity of the positions of the side for the symbol code.
+    pub fn side_quantity(&self, symbol_code: &SymbolCode, side: PositionSide) -> Volume {
+        self.symbol_positions(symbol_code).iter()
+            .filter(|position| position.side == side)
+            .map(|position| position.quantity_open)
+            .sum()
+    }
+
+    pub(crate) fn any_position(&self, symbol_code: &SymbolCode, predicate: impl Fn(&Position) -> bool) -> bool {
+        self.symbol_positions(symbol_code).iter().any(predicate)
+    }
+
+    pub(crate) fn sum_positions(&self, symbol_code: &SymbolCode, value: impl Fn(&Position) -> Price) -> Price {
+        self.symbol_positions(symbol_code).iter().map(value).sum()
+    }
+
+    /// The exchange rate used to book the pnl of the currency, cached in the ledger rates.
+    pub(crate) async fn pnl_exchange_rate(&mut self, pnl_currency: Currency, time: DateTime<Utc>, side: OrderSide) -> Price {
+        if self.currency == pnl_currency {
+            return dec!(1.0);
+        }
+        match get_exchange_rate(self.currency, pnl_currency, time, side).await {
+            Ok(rate) => {
+                self.rates.insert(pnl_currency, rate);
+                rate
+            },
+            Err(_e) => self.get_exchange_multiplier(pnl_currency)
+        }
+    }
+
+    /// Books the pnl of a reduced or closed position stored under `key`, the position is returned to the open positions unless it closed.
+    /// Paper ledgers also release the margin and apply the pnl to the cash of the account.
+    pub(crate) async fn book_reduction(&mut self, key: &SymbolCode, position: Position, event: &PositionUpdateEvent) {
+        let is_paper = self.mode != StrategyMode::Live;
+        let booked_pnl = match event {
+            PositionUpdateEvent::PositionReduced { booked_pnl, .. } => {
+                if is_paper {
+                    self.rescale_margin_used(key, position.quantity_open);
+                }
+                *booked_pnl
+            }
+            PositionUpdateEvent::PositionClosed { booked_pnl, .. } => {
+                if is_paper {
+                    self.release_margin_used(key).await;
+                }
+                *booked_pnl
+            }
+            _ => panic!("This shouldn't happen")
+        };
+        self.symbol_closed_pnl
+            .entry(position.symbol_code.clone())
+            .and_modify(|pnl| *pnl += booked_pnl)
+            .or_insert(booked_pnl);
+        self.total_booked_pnl += booked_pnl;
+        if is_paper {
+            self.cash_available += booked_pnl;
+            self.cash_value = self.cash_used + self.cash_available;
+        }
+        match position.is_closed {
+            true => self.record_closed_position(position.symbol_code.clone(), position),
+            false => {
+                self.positions.insert(key.clone(), position);
+            }
+        }
+    }
+
+    /// Applies a fill to a hedging ledger, positions are stored by position id so each entry keeps its own position.
+    ///
+    /// Entries open a new position, exits reduce the targeted position or the positions of the side oldest first.
+    /// Exit quantity beyond the targeted positions is ignored, an exit never opens an opposite position.
+    /// Paper ledgers return the rejection of a fill through the `paper_response_sender`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn update_or_create_hedged_position(
+        &mut self,
+        symbol_name: SymbolName,
+        symbol_code: SymbolCode,
+        quantity: Volume,
+        side: OrderSide,
+        time: DateTime<Utc>,
+        market_fill_price: Price,
+        tag: String,
+        order_id: OrderId,
+        target: PositionTarget,
+        paper_response_sender: Option<oneshot::Sender<Option<OrderUpdateEvent>>>
+    ) {
+        let is_paper = self.mode != StrategyMode::Live;
+        let reduced_side = match side {
+            OrderSide::Buy => PositionSide::Short,
+            OrderSide::Sell => PositionSide::Long,
+        };
+        let mut position_events = vec![];
+        let result = match target {
+            PositionTarget::New => self.open_hedged_position(symbol_name.clone(), symbol_code.clone(), quantity, side, time, market_fill_price, tag.clone(), order_id.clone(), is_paper).await
+                .map(|event| position_events.push(event)),
+            PositionTarget::Side(_) | PositionTarget::Id(_) => {
+                let keys: Vec<PositionId> = match &target {
+                    PositionTarget::Id(position_id) => match self.positions.get(position_id) {
+                        Some(position) if position.symbol_code == symbol_code && position.side == reduced_side => vec![position_id.clone()],
+                        _ => vec![],
+                    },
+                    _ => self.open_positions(&symbol_code).into_iter()
+                        .filter(|position| position.side == reduced_side)
+                        .map(|position| position.position_id)
+                        .collect(),
+                };
+                match keys.is_empty() {
+                    true => Err(format!("No {} position to exit: {}", reduced_side, symbol_code)),
+                    false => {
+                        let mut remaining_quantity = quantity;
+                        for key in keys {
+                            if remaining_quantity <= dec!(0) {
+                                break;
+                            }
+                            let mut position = match self.positions.remove(&key) {
+                                Some((_, position)) => position,
+                                None => continue
+                            };
+                            let reduce_quantity = min(remaining_quantity, position.quantity_open);
+                            remaining_quantity -= reduce_quantity;
+                            let exchange_rate = self.pnl_exchange_rate(position.symbol_info.pnl_currency, time, side).await;
+                            let event = position.reduce_position_size(market_fill_price, reduce_quantity, order_id.clone(), self.currency, exchange_rate, time, tag.clone()).await;
+                            self.book_reduction(&key, position, &event).await;
+                            position_events.push(event);
+                        }
+                        if remaining_quantity > dec!(0) {
+                            tracing::debug!("{}: {} exit quantity exceeds the open {} positions by {}", self.account, symbol_code, reduced_side, remaining_quantity);
+                        }
+                        Ok(())
+                    }
+                }
+            }
+        };
+
+        if let Err(reason) = result {
+            if let Some(sender) = paper_response_sender {
+                let event = OrderUpdateEvent::OrderRejected {
+                    account: self.account.clone(),
+                    symbol_name,
+                    symbol_code,
+                    order_id,
+                    reason,
+                    tag,
+                    time: time.to_string()
+                };
+                let _ = sender.send(Some(event));
+            }
+            return;
+        }
+        if let Some(sender) = paper_response_sender {
+            let _ = sender.send(None);
+        }
+        for event in position_events {
+            self.journal_position_event(&event);
+            if let Err(e) = self.strategy_sender.send(StrategyEvent::PositionEvents(event)).await {
+                tracing::error!("Error sending position event: {}", e);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn open_hedged_position(
+        &mut self,
+        symbol_name: SymbolName,
+        symbol_code: SymbolCode,
+        quantity: Volume,
+        side: OrderSide,
+        time: DateTime<Utc>,
+        market_fill_price: Price,
+        tag: String,
+        order_id: OrderId,
+        is_paper: bool
+    ) -> Result<PositionUpdateEvent, String> {
+        let position_side = match side {
+            OrderSide::Buy => PositionSide::Long,
+            OrderSide::Sell => PositionSide::Short,
+        };
+        let info = self.symbol_info(self.account.brokerage, &symbol_name).await;
+        let id = self.generate_id(position_side);
+        if is_paper {
+            // The margin is keyed by the position id, so each position releases its own margin.
+            self.commit_margin(&symbol_name, &id, quantity, market_fill_price, time, side, info.base_currency, info.pnl_currency).await
+                .map_err(|e| e.to_string())?;
+            self.cash_value = self.cash_used + self.cash_available;
+        }
+        let exchange_rate = self.pnl_exchange_rate(info.pnl_currency, time, side).await;
+        let position = Position::new(
+            symbol_name.clone(),
+            symbol_code.clone(),
+            order_id,
+            self.account.clone(),
+            position_side,
+            quantity,
+            market_fill_price,
+            id.clone(),
+            info,
+            exchange_rate,
+            tag.clone(),
+            time,
+            self.position_calculation_mode.clone()
+        );
+        self.positions.insert(id.clone(), position);
+        if symbol_name != symbol_code {
+            let mut codes = self.symbol_code_map.entry(symbol_name.clone()).or_default();
+            if !codes.contains(&symbol_code) {
+                codes.push(symbol_code.clone());
+            }
+        }
+        Ok(PositionUpdateEvent::PositionOpened {
+            average_price: market_fill_price,
+            side: position_side,
+            symbol_name,
+            symbol_code,
+            position_id: id,
+            account: self.account.clone(),
+            originating_order_tag: tag,
+            time: time.to_string()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standardized_types::broker_enum::Brokerage;
+    use crate::strategies::ledgers::ledger::test::setup_test_ledger;
+
+    async fn fill(ledger: &mut Ledger, side: OrderSide, quantity: Volume, price: Price, target: PositionTarget, order_id: &str) -> Option<OrderUpdateEvent> {
+        let (sender, receiver) = oneshot::channel();
+        let time = DateTime::<Utc>::from_str("2024-06-04T15:00:00Z").unwrap();
+        ledger.update_or_create_hedged_position("NQ".to_string(), "NQZ4".to_string(), quantity, side, time, price, "Test".to_string(), order_id.to_string(), target, Some(sender)).await;
+        receiver.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hedged_positions() {
+        let (mut ledger, _receiver) = setup_test_ledger().await;
+        ledger.is_hedging = true;
+        let symbol_code = "NQZ4".to_string();
+
+        assert!(fill(&mut ledger, OrderSide::Buy, dec!(2), dec!(17500), PositionTarget::New, "1").await.is_none());
+        assert!(fill(&mut ledger, OrderSide::Sell, dec!(1), dec!(17510), PositionTarget::New, "2").await.is_none());
+        assert!(fill(&mut ledger, OrderSide::Buy, dec!(1), dec!(17520), PositionTarget::New, "3").await.is_none());
+        assert_eq!(ledger.open_positions(&symbol_code).len(), 3);
+        assert_eq!(ledger.side_quantity(&symbol_code, PositionSide::Long), dec!(3));
+        assert_eq!(ledger.side_quantity(&symbol_code, PositionSide::Short), dec!(1));
+        assert!(ledger.is_long(&symbol_code) && ledger.is_short(&symbol_code));
+        assert_eq!(ledger.position_size(&symbol_code), dec!(4));
+        assert_eq!(ledger.margin_used.len(), 3);
+
+        // The second long position is closed by id, the first long and the short stay open.
+        let second_long = ledger.open_positions(&symbol_code).into_iter()
+            .find(|position| position.average_price == dec!(17520))
+            .unwrap();
+        assert!(fill(&mut ledger, OrderSide::Sell, dec!(1), dec!(17530), PositionTarget::Id(second_long.position_id.clone()), "4").await.is_none());
+        assert!(ledger.position(&second_long.position_id).is_none());
+        assert_eq!(ledger.side_quantity(&symbol_code, PositionSide::Long), dec!(2));
+        assert_eq!(ledger.side_quantity(&symbol_code, PositionSide::Short), dec!(1));
+        assert_eq!(ledger.positions_closed.get(&symbol_code).unwrap().len(), 1);
+        assert_eq!(ledger.margin_used.len(), 2);
+
+        // Exiting more than the open shorts closes them without opening a long.
+        assert!(fill(&mut ledger, OrderSide::Buy, dec!(3), dec!(17500), PositionTarget::Side(PositionSide::Short), "5").await.is_none());
+        assert_eq!(ledger.side_quantity(&symbol_code, PositionSide::Short), dec!(0));
+        assert_eq!(ledger.side_quantity(&symbol_code, PositionSide::Long), dec!(2));
+        assert_eq!(ledger.open_positions(&symbol_code).len(), 1);
+
+        // The exit of a closed position is rejected.
+        assert!(fill(&mut ledger, OrderSide::Sell, dec!(1), dec!(17530), PositionTarget::Id(second_long.position_id), "6").await.is_some());
+
+        assert!(!is_hedging_account(&Account::new(Brokerage::Test, "Test_Account_Netting".to_string())));
+    }
+}
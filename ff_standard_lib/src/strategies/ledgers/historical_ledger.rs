@@ -10,6 +10,7 @@ use crate::standardized_types::orders::{OrderId, OrderUpdateEvent};
 use crate::standardized_types::position::{Position, PositionUpdateEvent};
 use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
 use crate::strategies::client_features::other_requests::get_exchange_rate;
+use crate::strategies::ledgers::margin_engine::{margin_engine, MarginedPosition};
 use crate::strategies::strategy_events::StrategyEvent;
 
 impl Ledger {
@@ -17,12 +18,87 @@ impl Ledger {
         // First get_requests the margin amount without removing it
         if let Some((_,margin_used)) = self.margin_used.remove(symbol_code) {
             //eprintln!("release_margin_used: {}", symbol_code);
+            self.margin_positions.remove(symbol_code);
+            // Releasing a hedged position can reduce the portfolio credit of the positions which remain.
+            let credit = self.calculate_portfolio_credit(None);
+            let released = margin_used - (self.portfolio_credit - credit);
+            self.portfolio_credit = credit;
             // Update cash values first
-            self.cash_used -= margin_used;
-            self.cash_available += margin_used;
+            self.cash_used -= released;
+            self.cash_available += released;
         }
     }
 
+    /// Scales the margin of a reduced position to the quantity still open, reducing a position is never rejected for insufficient funds.
+    pub(crate) fn rescale_margin_used(&mut self, symbol_code: &SymbolCode, quantity_open: Volume) {
+        let mut margined = match self.margin_positions.get(symbol_code) {
+            Some(margined) => margined.value().clone(),
+            None => return
+        };
+        if margined.quantity == dec!(0) {
+            return;
+        }
+        let scale = quantity_open / margined.quantity.abs();
+        let margin = margined.margin * scale;
+        let released = margined.margin - margin;
+        margined.quantity *= scale;
+        margined.margin = margin;
+        self.margin_used.insert(symbol_code.clone(), margin);
+        self.margin_positions.insert(symbol_code.clone(), margined);
+        let credit = self.calculate_portfolio_credit(None);
+        let released = released - (self.portfolio_credit - credit);
+        self.portfolio_credit = credit;
+        self.cash_used -= released;
+        self.cash_available += released;
+    }
+
+    /// The portfolio credit of the margined positions, with `projected` replacing the margined position for its symbol code.
+    fn calculate_portfolio_credit(&self, projected: Option<(&SymbolCode, &MarginedPosition)>) -> Price {
+        let mut positions: Vec<MarginedPosition> = self.margin_positions.iter()
+            .filter(|entry| projected.is_none_or(|(symbol_code, _)| entry.key() != symbol_code))
+            .map(|entry| entry.value().clone())
+            .collect();
+        if let Some((_, position)) = projected {
+            positions.push(position.clone());
+        }
+        margin_engine(self.account.brokerage).portfolio_credit(&positions)
+    }
+
+    /// Re-margins the open positions when the margin engine switches between the intraday and overnight margins.
+    /// Positions are never closed for the change, if the overnight margin exceeds the cash available the ledger is left with negative cash available until the positions are reduced.
+    pub(crate) fn update_margin_period(&mut self, time: DateTime<Utc>) {
+        let engine = margin_engine(self.account.brokerage);
+        let period = engine.margin_period(time);
+        if period == self.margin_period {
+            return;
+        }
+        let previous_period = self.margin_period;
+        self.margin_period = period;
+
+        let brokerage = self.account.brokerage;
+        let mut change = dec!(0);
+        for mut entry in self.margin_positions.iter_mut() {
+            let margined = entry.value_mut();
+            let (previous, current) = match (engine.margin_per_contract(brokerage, &margined.symbol_name, previous_period), engine.margin_per_contract(brokerage, &margined.symbol_name, period)) {
+                (Some(previous), Some(current)) if previous > dec!(0) => (previous, current),
+                _ => continue,
+            };
+            let margin = margined.margin * current / previous;
+            change += margin - margined.margin;
+            margined.margin = margin;
+            self.margin_used.insert(entry.key().clone(), margin);
+        }
+        let credit = self.calculate_portfolio_credit(None);
+        let change = change - (credit - self.portfolio_credit);
+        self.portfolio_credit = credit;
+        self.cash_used += change;
+        self.cash_available -= change;
+        if self.cash_available < dec!(0) {
+            tracing::warn!("{}: {} margin of {} exceeds the cash available by {}", self.account, period, self.cash_used, -self.cash_available);
+        }
+    }
+
+    /// Commits the margin for a fill which opens or adds to a position, the margin required is reduced by any portfolio credit the fill creates, see `MarginEngine`.
     pub(crate) async fn commit_margin(&mut self, symbol_name: &SymbolName, symbol_code: &SymbolCode, quantity: Volume, market_price: Price, time: DateTime<Utc>, side: OrderSide, base_currency: Option<Currency>, position_currency: Currency) -> Result<(), FundForgeError> {
         //eprintln!("commit_margin: {}", symbol_code);
         let rate = if position_currency == self.currency {
@@ -38,28 +114,40 @@ impl Ledger {
             }
         };
 
-        let margin = self.account.brokerage.intraday_margin_required(symbol_name, quantity, market_price, self.currency, base_currency, position_currency, rate).await?
-            .unwrap_or_else(|| quantity * market_price * rate);
+        let margin = match margin_engine(self.account.brokerage).margin_required(self.account.brokerage, symbol_name, quantity, time) {
+            Some(margin) => margin * rate,
+            None => self.account.brokerage.intraday_margin_required(symbol_name, quantity, market_price, self.currency, base_currency, position_currency, rate).await?
+                .unwrap_or_else(|| quantity * market_price * rate)
+        };
+
+        let mut margined = match self.margin_positions.get(symbol_code) {
+            Some(margined) => margined.value().clone(),
+            None => MarginedPosition { symbol_name: symbol_name.clone(), quantity: dec!(0), margin: dec!(0) }
+        };
+        margined.quantity += match side {
+            OrderSide::Buy => quantity,
+            OrderSide::Sell => -quantity,
+        };
+        margined.margin += margin;
+        let credit = self.calculate_portfolio_credit(Some((symbol_code, &margined)));
+        let required = margin - (credit - self.portfolio_credit);
 
         // Check available cash first
-        if  self.cash_available < margin {
+        if  self.cash_available < required {
             return Err(FundForgeError::ClientSideErrorDebug(format!(
                 "Insufficient funds: Required {}, Available {}",
-                margin,
+                required,
                 self.cash_available
             )));
         }
 
         // Update margin tracking before updating cash
         // Add to existing margin instead of replacing
-        let total_margin = if let Some(existing_margin) = self.margin_used.get(symbol_code) {
-            margin + existing_margin.value()
-        } else {
-            margin
-        };
-        self.margin_used.insert(symbol_code.clone(), total_margin);
-        self.cash_used += margin;
-        self.cash_available -= margin;
+        self.margin_used.insert(symbol_code.clone(), margined.margin);
+        self.margin_positions.insert(symbol_code.clone(), margined);
+        self.portfolio_credit = credit;
+        self.cash_used += required;
+        self.cash_available -= required;
         //println!("Margin Used: {}", margin_used);
 
         Ok(())
@@ -73,7 +161,7 @@ impl Ledger {
         market_price: Price,
         tag: String
     ) {
-        if let Some((symbol_name, mut existing_position)) = self.positions.remove(symbol_code) {
+        if let Some((_, mut existing_position)) = self.positions.remove(symbol_code) {
             // Mark the position as closed
             existing_position.is_closed = true;
             self.release_margin_used(&symbol_code).await;
@@ -98,7 +186,7 @@ impl Ledger {
                 PositionUpdateEvent::PositionClosed { booked_pnl, .. } => {
                     // TODO[Strategy]: Add option to mirror account position or use internal position curating.
                     self.symbol_closed_pnl
-                        .entry(symbol_name.clone())
+                        .entry(existing_position.symbol_code.clone())
                         .and_modify(|pnl| *pnl += booked_pnl)
                         .or_insert(booked_pnl.clone());
                     self.total_booked_pnl += booked_pnl;
@@ -111,11 +199,9 @@ impl Ledger {
             self.cash_value = self.cash_used + self.cash_available;
 
             // Add the closed position to the positions_closed DashMap
-            self.positions_closed
-                .entry(symbol_name.clone())                  // Access the entry for the symbol name
-                .or_insert_with(Vec::new)                    // If no entry exists, create a new Vec
-                .push(existing_position);     // Push the closed position to the Vec
+            self.record_closed_position(existing_position.symbol_code.clone(), existing_position);
 
+            self.journal_position_event(&event);
             self.strategy_sender.send(StrategyEvent::PositionEvents(event)).await.unwrap();
         }
     }
@@ -149,60 +235,9 @@ impl Ledger {
             if is_reducing {
                 let quantity = min(remaining_quantity, existing_position.quantity_open);
                 remaining_quantity -= existing_position.quantity_open;
-                let exchange_rate = if self.currency != existing_position.symbol_info.pnl_currency {
-                    match get_exchange_rate(self.currency, existing_position.symbol_info.pnl_currency, time, side).await {
-                        Ok(rate) => {
-                            self.rates.insert(existing_position.symbol_info.pnl_currency, rate);
-                            rate
-                        },
-                        Err(_e) => self.get_exchange_multiplier(existing_position.symbol_info.pnl_currency)
-                    }
-                } else {
-                    dec!(1.0)
-                };
-
+                let exchange_rate = self.pnl_exchange_rate(existing_position.symbol_info.pnl_currency, time, side).await;
                 let event = existing_position.reduce_position_size(market_fill_price, quantity, order_id.clone(), self.currency,exchange_rate, time, tag.clone()).await;
-
-                match &event {
-                    PositionUpdateEvent::PositionReduced { booked_pnl, .. } => {
-                        self.release_margin_used(&symbol_code).await;
-                        self.commit_margin(&symbol_name, &symbol_code, existing_position.quantity_open, existing_position.average_price, time, side, existing_position.symbol_info.base_currency, existing_position.symbol_info.pnl_currency).await.unwrap();
-                        self.positions.insert(symbol_code.clone(), existing_position);
-
-                        self.symbol_closed_pnl
-                            .entry(symbol_code.clone())
-                            .and_modify(|pnl| *pnl += booked_pnl)
-                            .or_insert(booked_pnl.clone());
-
-                        self.total_booked_pnl += booked_pnl;
-
-                        self.cash_available += booked_pnl;
-
-                        //println!("Reduced Position: {}", symbol_name);
-                    }
-                    PositionUpdateEvent::PositionClosed { booked_pnl, .. } => {
-                        self.release_margin_used(&symbol_code).await;
-                        self.symbol_closed_pnl
-                            .entry(symbol_code.clone())
-                            .and_modify(|pnl| *pnl += booked_pnl)
-                            .or_insert(booked_pnl.clone());
-
-                        self.total_booked_pnl += booked_pnl;
-                        self.cash_available += booked_pnl;
-
-                        if !self.positions_closed.contains_key(&symbol_code) {
-                            self.positions_closed.insert(symbol_code.clone(), vec![]);
-                        }
-                        self.positions_closed
-                            .entry(symbol_code.clone())
-                            .or_insert_with(Vec::new)
-                            .push(existing_position);
-                        //println!("Closed Position: {}", symbol_name);
-                    }
-                    _ => panic!("This shouldn't happen")
-                }
-
-                self.cash_value = self.cash_used + self.cash_available;
+                self.book_reduction(&symbol_code, existing_position, &event).await;
 
                 position_events.push(event);
             } else {
@@ -328,9 +363,10 @@ impl Ledger {
         }
         paper_response_sender.send(None).unwrap();
         for event in position_events {
+            self.journal_position_event(&event);
             match self.strategy_sender.send(StrategyEvent::PositionEvents(event)).await {
                 Ok(_) => {}
-                Err(e) => eprintln!("Error sending position event: {}", e)
+                Err(e) => tracing::error!("Error sending position event: {}", e)
             }
         }
     }
@@ -339,7 +375,8 @@ impl Ledger {
         let positions_to_close: Vec<_> = self.positions.iter()
             .map(|position| {
                 (
-                    position.key().clone(),  // symbol_code
+                    position.key().clone(),
+                    position.symbol_code.clone(),
                     position.side,
                     position.symbol_name.clone(),
                     position.quantity_open
@@ -348,7 +385,7 @@ impl Ledger {
             .collect();
 
         // Then close each position
-        for (symbol_code, side, symbol_name, quantity) in positions_to_close {
+        for (key, symbol_code, side, symbol_name, quantity) in positions_to_close {
             let order_side = match side {
                 PositionSide::Long => OrderSide::Sell,
                 PositionSide::Short => OrderSide::Buy,
@@ -366,7 +403,7 @@ impl Ledger {
             };
 
             const FLATTEN_ALL_ID_TAG: &str = "Flatten All"; //use this as tag and order_id
-            self.paper_exit_position(FLATTEN_ALL_ID_TAG.to_string(), &symbol_code, time, market_price, FLATTEN_ALL_ID_TAG.to_string()).await;
+            self.paper_exit_position(FLATTEN_ALL_ID_TAG.to_string(), &key, time, market_price, FLATTEN_ALL_ID_TAG.to_string()).await;
         }
     }
 }
\ No newline at end of file
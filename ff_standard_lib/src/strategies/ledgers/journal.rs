@@ -0,0 +1,340 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use chrono::{DateTime, Utc};
+use rusqlite::{params_from_iter, Connection};
+use rusqlite::types::Value;
+use rust_decimal::Decimal;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::enums::PositionSide;
+use crate::standardized_types::orders::OrderUpdateEvent;
+use crate::standardized_types::position::{Position, PositionUpdateEvent};
+use crate::standardized_types::subscriptions::SymbolCode;
+use crate::strategies::ledgers::ledger::TradeExport;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS balance_updates (
+        account TEXT NOT NULL,
+        time INTEGER NOT NULL,
+        cash_value TEXT NOT NULL,
+        cash_available TEXT NOT NULL,
+        cash_used TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS position_events (
+        account TEXT NOT NULL,
+        event_type TEXT NOT NULL,
+        position_id TEXT NOT NULL,
+        symbol_code TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        time TEXT NOT NULL,
+        event TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS order_events (
+        account TEXT NOT NULL,
+        event_type TEXT NOT NULL,
+        order_id TEXT NOT NULL,
+        symbol_code TEXT,
+        time TEXT NOT NULL,
+        event TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS trades (
+        account TEXT NOT NULL,
+        position_id TEXT NOT NULL,
+        trade_index INTEGER NOT NULL,
+        symbol_code TEXT NOT NULL,
+        side TEXT NOT NULL,
+        entry_price TEXT NOT NULL,
+        entry_quantity TEXT NOT NULL,
+        exit_price TEXT NOT NULL,
+        exit_quantity TEXT NOT NULL,
+        entry_time TEXT NOT NULL,
+        exit_time TEXT NOT NULL,
+        exit_timestamp INTEGER,
+        pnl TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        result TEXT NOT NULL,
+        PRIMARY KEY (account, position_id, trade_index)
+    );
+    CREATE INDEX IF NOT EXISTS trades_exit_timestamp ON trades (exit_timestamp);
+";
+
+/// Filters for `AccountJournal::query_trades()`, all fields are optional and `None` matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct TradeFilter {
+    pub account: Option<Account>,
+    pub symbol_code: Option<SymbolCode>,
+    pub side: Option<PositionSide>,
+    /// Only trades whose originating order tag starts with this prefix.
+    pub tag_prefix: Option<String>,
+    /// Only trades which exited at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Only trades which exited at or before this time.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// The most records the writer commits in one transaction.
+const MAX_BATCH: usize = 500;
+
+enum JournalMessage {
+    Write { sql: &'static str, values: Vec<Value> },
+    Query { filter: TradeFilter, reply: mpsc::Sender<Result<Vec<TradeExport>, FundForgeError>> },
+}
+
+/// Persists account balance updates, position events, order events and completed trades to an embedded SQLite database,
+/// so live history can be analyzed without parsing csv exports.
+///
+/// Enable it with `strategy.enable_journal()` and query it with `strategy.query_trades()`.
+/// Completed trades are written when their position closes. Journaling never interrupts trading, records are sent to a writer thread
+/// which commits them in batches, write errors are only logged. Queries are answered by the writer after the records sent before them.
+pub struct AccountJournal {
+    sender: mpsc::Sender<JournalMessage>,
+}
+
+impl AccountJournal {
+    /// Opens or creates the journal database at the path.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FundForgeError> {
+        let connection = Connection::open(path).map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to open journal: {}", e)))?;
+        Self::from_connection(connection)
+    }
+
+    /// A journal which is not persisted to disk.
+    pub fn in_memory() -> Result<Self, FundForgeError> {
+        let connection = Connection::open_in_memory().map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to open journal: {}", e)))?;
+        Self::from_connection(connection)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self, FundForgeError> {
+        connection.execute_batch(SCHEMA).map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create journal tables: {}", e)))?;
+        let (sender, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("account_journal".to_string())
+            .spawn(move || journal_writer(connection, receiver))
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to start journal writer: {}", e)))?;
+        Ok(Self { sender })
+    }
+
+    fn execute(&self, sql: &'static str, values: Vec<Value>) {
+        if self.sender.send(JournalMessage::Write { sql, values }).is_err() {
+            tracing::error!("Journal: The writer has stopped, the record was not written");
+        }
+    }
+
+    pub fn record_balance(&self, account: &Account, cash_value: Decimal, cash_available: Decimal, cash_used: Decimal, time: DateTime<Utc>) {
+        self.execute(
+            "INSERT INTO balance_updates (account, time, cash_value, cash_available, cash_used) VALUES (?1, ?2, ?3, ?4, ?5)",
+            vec![text(account), Value::Integer(time.timestamp()), text(cash_value), text(cash_available), text(cash_used)],
+        );
+    }
+
+    pub fn record_position_event(&self, event: &PositionUpdateEvent) {
+        let (event_type, position_id, symbol_code, tag, time) = match event {
+            PositionUpdateEvent::PositionOpened { position_id, symbol_code, originating_order_tag, time, .. } => ("PositionOpened", position_id, symbol_code, originating_order_tag, time),
+            PositionUpdateEvent::Increased { position_id, symbol_code, originating_order_tag, time, .. } => ("Increased", position_id, symbol_code, originating_order_tag, time),
+            PositionUpdateEvent::PositionReduced { position_id, symbol_code, originating_order_tag, time, .. } => ("PositionReduced", position_id, symbol_code, originating_order_tag, time),
+            PositionUpdateEvent::PositionClosed { position_id, symbol_code, originating_order_tag, time, .. } => ("PositionClosed", position_id, symbol_code, originating_order_tag, time),
+        };
+        self.execute(
+            "INSERT INTO position_events (account, event_type, position_id, symbol_code, tag, time, event) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            vec![text(event.account()), text(event_type), text(position_id), text(symbol_code), text(tag), text(time), Value::Text(format!("{:?}", event))],
+        );
+    }
+
+    pub fn record_order_event(&self, event: &OrderUpdateEvent) {
+        let (event_type, account, time) = match event {
+            OrderUpdateEvent::OrderAccepted { account, time, .. } => ("OrderAccepted", account, time),
+            OrderUpdateEvent::OrderFilled { account, time, .. } => ("OrderFilled", account, time),
+            OrderUpdateEvent::OrderPartiallyFilled { account, time, .. } => ("OrderPartiallyFilled", account, time),
+            OrderUpdateEvent::OrderCancelled { account, time, .. } => ("OrderCancelled", account, time),
+            OrderUpdateEvent::OrderRejected { account, time, .. } => ("OrderRejected", account, time),
+            OrderUpdateEvent::OrderUpdated { account, time, .. } => ("OrderUpdated", account, time),
+            OrderUpdateEvent::OrderUpdateRejected { account, time, .. } => ("OrderUpdateRejected", account, time),
+        };
+        self.execute(
+            "INSERT INTO order_events (account, event_type, order_id, symbol_code, time, event) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            vec![text(account), text(event_type), text(event.order_id()), Value::from(event.symbol_code()), text(time), Value::Text(format!("{:?}", event))],
+        );
+    }
+
+    /// Records the completed trades of a closed position, recording the same position again replaces its trades.
+    pub fn record_closed_position(&self, position: &Position) {
+        for (index, trade) in position.completed_trades.iter().enumerate() {
+            let exit_timestamp = DateTime::<Utc>::from_str(&trade.exit_time).ok().map(|time| time.timestamp());
+            self.execute(
+                "INSERT OR REPLACE INTO trades (account, position_id, trade_index, symbol_code, side, entry_price, entry_quantity, exit_price, exit_quantity, entry_time, exit_time, exit_timestamp, pnl, tag, result)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                vec![
+                    text(&position.account),
+                    text(&position.position_id),
+                    Value::Integer(index as i64),
+                    text(&position.symbol_code),
+                    text(position.side),
+                    text(trade.entry_price),
+                    text(trade.entry_quantity),
+                    text(trade.exit_price),
+                    text(trade.exit_quantity),
+                    text(&trade.entry_time),
+                    text(&trade.exit_time),
+                    Value::from(exit_timestamp),
+                    text(trade.profit),
+                    text(&position.tag),
+                    text(&trade.result)
+                ],
+            );
+        }
+    }
+
+    /// Returns the journaled trades matching the filter, ordered by exit time, including the trades recorded before the query.
+    pub fn query_trades(&self, filter: &TradeFilter) -> Result<Vec<TradeExport>, FundForgeError> {
+        let stopped = || FundForgeError::ClientSideErrorDebug("The journal writer has stopped".to_string());
+        let (reply, response) = mpsc::channel();
+        self.sender.send(JournalMessage::Query { filter: filter.clone(), reply }).map_err(|_| stopped())?;
+        response.recv().map_err(|_| stopped())?
+    }
+}
+
+fn text(value: impl ToString) -> Value {
+    Value::Text(value.to_string())
+}
+
+/// Writes the records sent to the journal, the records waiting when the writer wakes are committed in one transaction.
+/// A query commits the records sent before it first, so it sees them.
+fn journal_writer(mut connection: Connection, receiver: mpsc::Receiver<JournalMessage>) {
+    let mut batch = vec![];
+    while let Ok(message) = receiver.recv() {
+        let mut next = Some(message);
+        while let Some(message) = next.take() {
+            match message {
+                JournalMessage::Write { sql, values } => {
+                    batch.push((sql, values));
+                    if batch.len() < MAX_BATCH {
+                        next = receiver.try_recv().ok();
+                    }
+                }
+                JournalMessage::Query { filter, reply } => {
+                    write_batch(&mut connection, &mut batch);
+                    let _ = reply.send(query_trades(&connection, &filter));
+                    next = receiver.try_recv().ok();
+                }
+            }
+        }
+        write_batch(&mut connection, &mut batch);
+    }
+}
+
+fn write_batch(connection: &mut Connection, batch: &mut Vec<(&'static str, Vec<Value>)>) {
+    if batch.is_empty() {
+        return;
+    }
+    let result = connection.transaction().and_then(|transaction| {
+        for (sql, values) in batch.drain(..) {
+            if let Err(e) = transaction.execute(sql, params_from_iter(values)) {
+                tracing::error!("Journal: Failed to write: {}", e);
+            }
+        }
+        transaction.commit()
+    });
+    if let Err(e) = result {
+        tracing::error!("Journal: Failed to commit: {}", e);
+    }
+    batch.clear();
+}
+
+fn query_trades(connection: &Connection, filter: &TradeFilter) -> Result<Vec<TradeExport>, FundForgeError> {
+    let mut sql = "SELECT symbol_code, position_id, side, entry_price, entry_quantity, exit_price, exit_quantity, entry_time, exit_time, pnl, tag, result FROM trades WHERE 1 = 1".to_string();
+    let mut values: Vec<Value> = vec![];
+    if let Some(account) = &filter.account {
+        sql.push_str(" AND account = ?");
+        values.push(Value::Text(account.to_string()));
+    }
+    if let Some(symbol_code) = &filter.symbol_code {
+        sql.push_str(" AND symbol_code = ?");
+        values.push(Value::Text(symbol_code.clone()));
+    }
+    if let Some(side) = &filter.side {
+        sql.push_str(" AND side = ?");
+        values.push(Value::Text(side.to_string()));
+    }
+    if let Some(prefix) = &filter.tag_prefix {
+        sql.push_str(" AND substr(tag, 1, length(?)) = ?");
+        values.push(Value::Text(prefix.clone()));
+        values.push(Value::Text(prefix.clone()));
+    }
+    if let Some(from) = &filter.from {
+        sql.push_str(" AND exit_timestamp >= ?");
+        values.push(Value::Integer(from.timestamp()));
+    }
+    if let Some(to) = &filter.to {
+        sql.push_str(" AND exit_timestamp <= ?");
+        values.push(Value::Integer(to.timestamp()));
+    }
+    sql.push_str(" ORDER BY exit_timestamp, position_id, trade_index");
+
+    let to_error = |e: rusqlite::Error| FundForgeError::ClientSideErrorDebug(format!("Failed to query journal: {}", e));
+    let mut statement = connection.prepare(&sql).map_err(to_error)?;
+    let rows = statement.query_map(params_from_iter(values), |row| {
+        let decimal = |index: usize| -> rusqlite::Result<Decimal> {
+            let text: String = row.get(index)?;
+            Decimal::from_str(&text).map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e)))
+        };
+        Ok(TradeExport {
+            symbol_code: row.get(0)?,
+            position_id: row.get(1)?,
+            side: row.get(2)?,
+            entry_price: decimal(3)?,
+            entry_quantity: decimal(4)?,
+            exit_price: decimal(5)?,
+            exit_quantity: decimal(6)?,
+            entry_time: row.get(7)?,
+            exit_time: row.get(8)?,
+            pnl: decimal(9)?,
+            tag: row.get(10)?,
+            result: row.get(11)?,
+        })
+    }).map_err(to_error)?;
+    rows.collect::<Result<Vec<TradeExport>, rusqlite::Error>>().map_err(to_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+    use crate::apis::rithmic::rithmic_systems::RithmicSystem;
+    use crate::product_maps::rithmic::maps::get_futures_symbol_info;
+    use crate::standardized_types::broker_enum::Brokerage;
+    use crate::standardized_types::position::PositionCalculationMode;
+
+    #[tokio::test]
+    async fn test_query_trades_with_filters() {
+        let journal = AccountJournal::in_memory().unwrap();
+        let account = Account::new(Brokerage::Rithmic(RithmicSystem::Rithmic01), "TEST".to_string());
+        let info = get_futures_symbol_info("MNQ").unwrap();
+        let open_time = Utc.with_ymd_and_hms(2024, 6, 3, 14, 0, 0).unwrap();
+        for (index, tag) in ["Breakout Long", "Reversal Long"].iter().enumerate() {
+            let mut position = Position::new(
+                "MNQ".to_string(), "MNQZ4".to_string(), format!("order {}", index), account.clone(), PositionSide::Long, dec!(1), dec!(20000),
+                format!("position {}", index), info.clone(), dec!(1), tag.to_string(), open_time, PositionCalculationMode::FIFO
+            );
+            let exit_time = open_time + chrono::Duration::hours(index as i64 + 1);
+            position.reduce_position_size(dec!(20010), dec!(1), format!("exit {}", index), info.pnl_currency, dec!(1), exit_time, tag.to_string()).await;
+            journal.record_closed_position(&position);
+            // Recording the same position again should not duplicate its trades
+            journal.record_closed_position(&position);
+        }
+
+        assert_eq!(journal.query_trades(&TradeFilter::default()).unwrap().len(), 2);
+
+        let filter = TradeFilter { tag_prefix: Some("Breakout".to_string()), ..Default::default() };
+        let trades = journal.query_trades(&filter).unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].position_id, "position 0");
+        assert_eq!(trades[0].exit_price, dec!(20010));
+
+        let filter = TradeFilter { from: Some(open_time + chrono::Duration::minutes(90)), account: Some(account), ..Default::default() };
+        let trades = journal.query_trades(&filter).unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].tag, "Reversal Long");
+    }
+}
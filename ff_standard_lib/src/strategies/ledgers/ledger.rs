@@ -1,4 +1,5 @@
 use std::cmp::min;
+use std::collections::BTreeMap;
 use dashmap::DashMap;
 use tokio::sync::{oneshot};
 use rust_decimal::Decimal;
@@ -7,15 +8,16 @@ use std::fs::create_dir_all;
 use std::path::Path;
 use std::str::FromStr;
 use csv::Writer;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal_macros::dec;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 use tokio::sync::mpsc::{Receiver, Sender};
 use uuid::Uuid;
 use crate::helpers::converters::format_duration;
 use crate::product_maps::oanda::maps::OANDA_SYMBOL_INFO;
-use crate::product_maps::rithmic::maps::{find_base_symbol, get_futures_symbol_info};
+use crate::product_maps::rithmic::maps::{find_base_symbol, get_futures_symbol_info, get_related_contracts};
 use crate::standardized_types::accounts::{Account, AccountInfo, Currency};
 use crate::standardized_types::base_data::traits::BaseData;
 use crate::standardized_types::broker_enum::Brokerage;
@@ -28,6 +30,13 @@ use crate::standardized_types::symbol_info::SymbolInfo;
 use crate::standardized_types::time_slices::TimeSlice;
 use crate::strategies::client_features::other_requests::get_exchange_rate;
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
+use crate::strategies::ledgers::hedging::PositionTarget;
+use crate::strategies::ledgers::journal::AccountJournal;
+use crate::strategies::ledgers::margin_engine::{MarginedPosition, MarginPeriod};
+use crate::strategies::ledgers::statement_import::{StatementFill, StatementImport};
+use crate::strategies::ledgers::ledger_snapshot::{LedgerRestore, LedgerSnapshot};
+use crate::strategies::ledgers::trade_formats::TradeExportFormat;
+use crate::strategies::statistics::streaming::StreamingStatistics;
 use crate::strategies::strategy_events::StrategyEvent;
 
 /*
@@ -38,12 +47,14 @@ use crate::strategies::strategy_events::StrategyEvent;
 
 #[derive(Debug)]
 pub enum LedgerMessage {
-    SyncPosition{symbol_name: SymbolName, symbol_code: SymbolCode, account: Account, open_quantity: f64, average_price: f64, side: PositionSide, open_pnl: f64, time: String},
-    UpdateOrCreatePosition{symbol_name: SymbolName, symbol_code: SymbolCode, quantity: Volume, side: OrderSide, time: DateTime<Utc>, market_fill_price: Price, tag: String, paper_response_sender: Option<oneshot::Sender<Option<OrderUpdateEvent>>>, order_id: OrderId},
+    SyncPosition{symbol_name: SymbolName, symbol_code: SymbolCode, account: Account, open_quantity: Volume, average_price: Price, side: PositionSide, open_pnl: Price, time: String},
+    UpdateOrCreatePosition{symbol_name: SymbolName, symbol_code: SymbolCode, quantity: Volume, side: OrderSide, time: DateTime<Utc>, market_fill_price: Price, tag: String, paper_response_sender: Option<oneshot::Sender<Option<OrderUpdateEvent>>>, order_id: OrderId, target: PositionTarget},
     TimeSliceUpdate{time_slice: Arc<TimeSlice>},
     LiveAccountUpdate{cash_value: Decimal, cash_available: Decimal, cash_used: Decimal},
     ExitPaperPosition{symbol_code: SymbolCode, order_id: OrderId, time: DateTime<Utc>, market_fill_price: Price, tag: String},
     PaperFlattenAll{time: DateTime<Utc>},
+    ImportStatement{fills: Vec<StatementFill>, response_sender: oneshot::Sender<StatementImport>},
+    RestoreSnapshot{snapshot: LedgerSnapshot, response_sender: oneshot::Sender<LedgerRestore>},
 }
 
 /// A ledger specific to the strategy which will ignore positions not related to the strategy but will update its balances relative to the actual account balances for live trading.
@@ -57,17 +68,34 @@ pub struct Ledger {
     pub last_update: DashMap<SymbolCode, DateTime<Utc>>,
     pub symbol_code_map: DashMap<SymbolName, Vec<String>>,
     pub margin_used: DashMap<SymbolCode, Price>,
+    /// The margined quantity of each symbol code, used by the margin engine to credit spreads and offsetting positions.
+    pub margin_positions: DashMap<SymbolCode, MarginedPosition>,
+    /// The amount `cash_used` is reduced below the sum of `margin_used` by the margin engine.
+    pub portfolio_credit: Price,
+    /// The margin schedule the margin of the open positions was last calculated with.
+    pub(crate) margin_period: MarginPeriod,
     pub positions_closed: DashMap<SymbolCode, Vec<Position>>,
     pub symbol_closed_pnl: DashMap<SymbolCode, Decimal>,
     pub(crate) symbol_info: DashMap<SymbolName, SymbolInfo>,
     pub open_pnl: DashMap<SymbolCode, Price>,
     pub total_booked_pnl: Price,
+    /// The overnight financing paid to the ledger, negative when the financing charged exceeds the financing paid, see `FinancingModel`.
+    pub total_financing: Price,
+    /// The next rollover at which the paper ledger applies financing, `None` until the ledger receives data.
+    pub(crate) next_rollover: Option<DateTime<Utc>>,
     pub mode: StrategyMode,
     pub is_simulating_pnl: bool,
+    /// Hedging ledgers hold a position for each entry keyed by position id, netting ledgers hold one position per symbol code keyed by symbol code.
+    pub is_hedging: bool,
     pub(crate) strategy_sender: Sender<StrategyEvent>,
     pub rates: Arc<DashMap<Currency, Decimal>>,
     pub position_calculation_mode: PositionCalculationMode,
-    pub market_price_service: Arc<MarketPriceService>
+    pub market_price_service: Arc<MarketPriceService>,
+    pub(crate) journal: Arc<OnceLock<AccountJournal>>,
+    /// Updated as each position closes, see `StreamingStatistics`.
+    pub(crate) statistics: Mutex<StreamingStatistics>,
+    /// The maximum number of closed positions kept per symbol code, the statistics still include the dropped positions.
+    pub(crate) closed_position_limit: AtomicUsize,
     //todo, add daily max loss, max order size etc to ledger
 }
 
@@ -78,18 +106,22 @@ impl Ledger {
         synchronise_accounts: bool,
         strategy_sender: Sender<StrategyEvent>,
         position_calculation_mode: PositionCalculationMode,
-        market_price_service: Arc<MarketPriceService>
-
+        market_price_service: Arc<MarketPriceService>,
+        journal: Arc<OnceLock<AccountJournal>>
     ) -> Self {
         let is_simulating_pnl = match synchronise_accounts {
             true => false,
             false => true
         };
-        println!("Ledger Created: {} {}", account_info.brokerage, account_info.account_id);
+        tracing::info!("Ledger Created: {} {}", account_info.brokerage, account_info.account_id);
+        let is_hedging = account_info.is_hedging;
         let positions: DashMap<SymbolName, Position> = account_info
             .positions
             .into_iter()
-            .map(|position| (position.symbol_code.clone(), position))
+            .map(|position| match is_hedging {
+                true => (position.position_id.clone(), position),
+                false => (position.symbol_code.clone(), position),
+            })
             .collect();
 
         let contract_map: DashMap<SymbolName, Vec<String>> = DashMap::new();
@@ -110,17 +142,26 @@ impl Ledger {
             last_update: Default::default(),
             symbol_code_map: contract_map,
             margin_used: Default::default(),
+            margin_positions: Default::default(),
+            portfolio_credit: dec!(0),
+            margin_period: MarginPeriod::Intraday,
             positions_closed: DashMap::new(),
             symbol_closed_pnl: Default::default(),
             symbol_info: DashMap::new(),
             open_pnl: DashMap::new(),
             total_booked_pnl: dec!(0),
+            total_financing: dec!(0),
+            next_rollover: None,
             mode,
             is_simulating_pnl,
+            is_hedging,
             strategy_sender,
             rates: Arc::new(Default::default()),
             position_calculation_mode,
             market_price_service,
+            journal,
+            statistics: Default::default(),
+            closed_position_limit: AtomicUsize::new(usize::MAX),
         };
         ledger
     }
@@ -172,8 +213,8 @@ impl Ledger {
         tokio::spawn(async move {
             while let Some(message) = receiver.recv().await {
                /* match &message {
-                    LedgerMessage::SyncPosition { .. } => eprintln!("{:?}", message),
-                    LedgerMessage::UpdateOrCreatePosition { .. } => eprintln!("{:?}", message),
+                    LedgerMessage::SyncPosition { .. } => tracing::debug!("{:?}", message),
+                    LedgerMessage::UpdateOrCreatePosition { .. } => tracing::debug!("{:?}", message),
                     _ => {}
                 }*/
                 //let mut position_locks = AHashMap::new();
@@ -190,7 +231,13 @@ impl Ledger {
                             }
                         }*/
                     }
-                    LedgerMessage::UpdateOrCreatePosition { symbol_name, symbol_code, quantity, side, time, market_fill_price, tag , paper_response_sender, order_id} => {
+                    LedgerMessage::UpdateOrCreatePosition { symbol_name, symbol_code, quantity, side, time, market_fill_price, tag , paper_response_sender, order_id, target} => {
+                        if static_self.is_hedging {
+                            if mode != StrategyMode::Live || static_self.is_simulating_pnl {
+                                static_self.update_or_create_hedged_position(symbol_name, symbol_code, quantity, side, time, market_fill_price, tag, order_id, target, paper_response_sender).await;
+                            }
+                            continue;
+                        }
                         match mode {
                             StrategyMode::Backtest | StrategyMode::LivePaperTrading => static_self.update_or_create_paper_position(symbol_name, symbol_code, quantity, side, time, market_fill_price, tag, order_id.clone(), paper_response_sender.unwrap()).await,
                             StrategyMode::Live => {
@@ -205,6 +252,9 @@ impl Ledger {
                     }
                     LedgerMessage::LiveAccountUpdate { cash_value, cash_available, cash_used } => {
                         static_self.update(cash_value, cash_available, cash_used);
+                        if let Some(journal) = static_self.journal.get() {
+                            journal.record_balance(&static_self.account, cash_value, cash_available, cash_used, Utc::now());
+                        }
                     }
                     LedgerMessage::ExitPaperPosition { symbol_code, order_id,time, market_fill_price, tag } => {
                         static_self.paper_exit_position(order_id, &symbol_code, time, market_fill_price, tag).await;
@@ -212,28 +262,25 @@ impl Ledger {
                     LedgerMessage::PaperFlattenAll { time } => {
                         static_self.flatten_all_for_paper_account(time).await;
                     }
+                    LedgerMessage::ImportStatement { fills, response_sender } => {
+                        let summary = static_self.import_statement_fills(fills).await;
+                        let _ = response_sender.send(summary);
+                    }
+                    LedgerMessage::RestoreSnapshot { snapshot, response_sender } => {
+                        let restore = static_self.restore_snapshot(snapshot);
+                        let _ = response_sender.send(restore);
+                    }
                 }
             }
         });
     }
 
-    /*async fn synchronize_live_position(&mut self, symbol_name: SymbolName, symbol_code: SymbolCode, account: Account, open_quantity: f64, average_price: f64, side: PositionSide, open_pnl: f64, time: String) {
+    /*async fn synchronize_live_position(&mut self, symbol_name: SymbolName, symbol_code: SymbolCode, account: Account, open_quantity: Volume, average_price: Price, side: PositionSide, open_pnl: Price, time: String) {
         //sleep(std::time::Duration::from_millis(100)).await;
         let mut to_remove = false;
         let mut to_create = true;
-        let pnl = match Decimal::from_f64(open_pnl) {
-            Some(pnl) => pnl,
-            None => return
-        };
-        let quantity = match Decimal::from_f64(open_quantity) {
-            Some(quantity) => quantity,
-            None => return
-        };
-
-        let average_price = match Decimal::from_f64(average_price) {
-            Some(price) => price,
-            None => return
-        };
+        let pnl = open_pnl;
+        let quantity = open_quantity;
 
         let position_exists = self.positions.contains_key(&symbol_code);
         if position_exists {
@@ -336,7 +383,7 @@ impl Ledger {
                 }
                 if to_remove {
                     match self.positions.remove(&symbol_code) {
-                        Some((_,p)) => self.positions_closed.entry(symbol_code.clone()).or_insert_with(Vec::new).push(p),
+                        Some((_,p)) => self.record_closed_position(symbol_code.clone(), p),
                         None => {}
                     }
                 }
@@ -365,50 +412,68 @@ impl Ledger {
         }
     }*/
 
+    /// The open pnl of the positions is positive, a hedging ledger uses the total of the positions of the symbol.
     pub fn in_profit(&self, symbol_name: &SymbolName) -> bool {
-        if let Some(position) = self.positions.get(symbol_name) {
-            if position.value().open_pnl > dec!(0.0) {
-                return true
-            }
-        }
-        false
+        self.is_open(symbol_name) && self.pnl(symbol_name) > dec!(0.0)
     }
 
     pub fn in_drawdown(&self, symbol_name: &SymbolName) -> bool {
-        if let Some(position) = self.positions.get(symbol_name) {
-            if position.value().open_pnl < dec!(0.0) {
-                return true
-            }
-        }
-        false
+        self.is_open(symbol_name) && self.pnl(symbol_name) < dec!(0.0)
     }
 
     pub fn pnl(&self, symbol_name: &SymbolName) -> Decimal {
-        if let Some(position) = self.positions.get(symbol_name) {
-            return position.value().open_pnl.clone()
-        }
-        dec!(0)
+        self.sum_positions(symbol_name, |position| position.open_pnl)
     }
 
+    /// The open quantity of the position, for a hedging ledger the gross quantity of the long and short positions, see `side_quantity()`.
     pub fn position_size(&self, symbol_name: &SymbolName) -> Decimal {
-        if let Some(position) = self.positions.get(symbol_name) {
-            return position.value().quantity_open.clone()
+        self.sum_positions(symbol_name, |position| position.quantity_open)
+    }
+
+    /// The net open quantity of the mini contract and its related micro contract in minis, long positive and short negative.
+    /// Symbols without a related contract return the net open quantity of the symbol.
+    pub fn mini_equivalent_position(&self, symbol_name: &SymbolName) -> Decimal {
+        let contracts = get_related_contracts(symbol_name);
+        let mut quantity = dec!(0);
+        for position in self.positions.iter() {
+            let position = position.value();
+            let minis = match &contracts {
+                Some(contracts) => contracts.mini_equivalent(&position.symbol_name, position.quantity_open),
+                None if position.symbol_name == *symbol_name => Some(position.quantity_open),
+                None => None,
+            };
+            if let Some(minis) = minis {
+                match position.side {
+                    PositionSide::Long => quantity += minis,
+                    PositionSide::Short => quantity -= minis,
+                    PositionSide::Flat => {}
+                }
+            }
         }
-        dec!(0)
+        quantity
+    }
+
+    /// The open pnl of the mini contract and its related micro contract, or of the symbol if it has no related contract.
+    pub fn related_contracts_pnl(&self, symbol_name: &SymbolName) -> Decimal {
+        let contracts = get_related_contracts(symbol_name);
+        self.positions.iter()
+            .filter(|position| match &contracts {
+                Some(contracts) => position.symbol_name == contracts.mini || position.symbol_name == contracts.micro,
+                None => position.symbol_name == *symbol_name,
+            })
+            .map(|position| position.open_pnl)
+            .sum()
     }
 
     pub fn booked_pnl(&self, symbol_name: &SymbolName) -> Decimal {
-        if let Some(position) = self.positions.get(symbol_name) {
-            return position.value().booked_pnl.clone()
-        }
-        dec!(0)
+        self.sum_positions(symbol_name, |position| position.booked_pnl)
     }
 
     // Function to export closed positions to CSV
     pub fn export_positions_to_csv(&self, folder: &str) {
         // Create the folder if it does not exist
         if let Err(e) = create_dir_all(folder) {
-            eprintln!("Failed to create directory {}: {}", folder, e);
+            tracing::error!("Failed to create directory {}: {}", folder, e);
             return;
         }
 
@@ -428,20 +493,20 @@ impl Ledger {
                     for position in entry.value() {
                         let export = position.to_export(); // Assuming `to_export` provides a suitable data representation
                         if let Err(e) = wtr.serialize(export) {
-                            eprintln!("Failed to write position data to {}: {}", file_path.display(), e);
+                            tracing::error!("Failed to write position data to {}: {}", file_path.display(), e);
                         }
                     }
                 }
 
                 // Ensure all data is flushed to the file
                 if let Err(e) = wtr.flush() {
-                    eprintln!("Failed to flush CSV writer for {}: {}", file_path.display(), e);
+                    tracing::error!("Failed to flush CSV writer for {}: {}", file_path.display(), e);
                 } else {
-                    println!("Successfully exported all positions to {}", file_path.display());
+                    tracing::info!("Successfully exported all positions to {}", file_path.display());
                 }
             }
             Err(e) => {
-                eprintln!("Failed to create CSV writer for {}: {}", file_path.display(), e);
+                tracing::error!("Failed to create CSV writer for {}: {}", file_path.display(), e);
             }
         }
     }
@@ -480,6 +545,25 @@ impl Ledger {
         }
     }
 
+    /// Moves the position to the closed positions and updates the statistics, the oldest closed positions of the symbol code are dropped beyond the `closed_position_limit`.
+    pub(crate) fn record_closed_position(&self, symbol_code: SymbolCode, position: Position) {
+        let commissions = position.completed_trades.iter().map(|trade| trade.commissions).sum();
+        if let Ok(mut statistics) = self.statistics.lock() {
+            statistics.update(position.booked_pnl, commissions);
+        }
+        let limit = self.closed_position_limit.load(Ordering::SeqCst);
+        let mut closed = self.positions_closed.entry(symbol_code).or_default();
+        closed.push(position);
+        if closed.len() > limit {
+            let excess = closed.len() - limit;
+            closed.drain(..excess);
+        }
+    }
+
+    pub fn statistics(&self) -> StreamingStatistics {
+        self.statistics.lock().map(|statistics| statistics.clone()).unwrap_or_default()
+    }
+
     pub fn get_open_pnl(&self) -> Price {
         let mut pnl = dec!(0);
         for price in self.open_pnl.iter() {
@@ -488,29 +572,21 @@ impl Ledger {
         pnl
     }
 
+    /// True if there is an open long position, a hedging ledger can be long and short at the same time.
     pub fn is_long(&self, symbol_name: &SymbolName) -> bool {
-        if let Some(position) = self.positions.get(symbol_name) {
-            if position.value().side == PositionSide::Long {
-                return true;
-            }
-        }
-        false
+        self.any_position(symbol_name, |position| position.side == PositionSide::Long)
     }
 
     pub fn is_short(&self, symbol_name: &SymbolName) -> bool {
-        if let Some(position) = self.positions.get(symbol_name) {
-            if position.value().side == PositionSide::Short {
-                return true
-            }
-        }
-       false
+        self.any_position(symbol_name, |position| position.side == PositionSide::Short)
     }
 
     pub fn is_flat(&self, symbol_name: &SymbolName) -> bool {
-        if let Some(_) = self.positions.get(symbol_name) {
-            return false;
-        }
-        true
+        !self.is_open(symbol_name)
+    }
+
+    fn is_open(&self, symbol_name: &SymbolName) -> bool {
+        self.any_position(symbol_name, |_| true)
     }
 
     pub fn ledger_statistics_to_string(&self) -> String {
@@ -620,7 +696,7 @@ impl Ledger {
          Profit Factor: {}, Quality Ratio: {},  Pain to Gain Ratio: {}, \
          Max Drawdown: {}, Total profit: {}, Total Wins: {}, Total Losses: {}, \
          Break Even: {}, Total Positions: {}, Open Positions: {}, \
-         Cash Used: {}, Cash Available: {}, Commission Paid: {}",
+         Cash Used: {}, Cash Available: {}, Commission Paid: {}, Financing: {}",
             self.account,
             cash_value.round_dp(2),
             self.currency,
@@ -638,7 +714,8 @@ impl Ledger {
             self.positions.len(),
             cash_used.round_dp(2),
             cash_available.round_dp(2),
-            commission_paid
+            commission_paid,
+            self.total_financing.round_dp(2)
         )
     }
 
@@ -657,13 +734,44 @@ impl Ledger {
         )
     }
 
+    /// Marks the open positions to the data of the slice, each position is marked once per symbol with the data of the symbol in time order.
     pub async fn timeslice_update(&mut self, time_slice: Arc<TimeSlice>) {
-        for base_data_enum in time_slice.iter() {
-            let data_symbol_name = &base_data_enum.symbol().name;
+        let symbols = match self.positions.is_empty() {
+            true => BTreeMap::new(),
+            false => time_slice.by_symbol(),
+        };
+        for (data_symbol_name, data) in &symbols {
+            if self.is_hedging {
+                if self.mode == StrategyMode::Live && !self.is_simulating_pnl {
+                    continue;
+                }
+                let mut open_pnl = dec!(0);
+                let mut is_updated = false;
+                let mut closed = vec![];
+                for mut position in self.positions.iter_mut() {
+                    if position.is_closed || (position.symbol_name != *data_symbol_name && position.symbol_code != *data_symbol_name) {
+                        continue;
+                    }
+                    open_pnl += position.mark_to_market(data, self.currency);
+                    is_updated = true;
+                    if position.is_closed {
+                        closed.push(position.key().clone());
+                    }
+                }
+                if is_updated {
+                    self.open_pnl.insert(data_symbol_name.clone(), open_pnl);
+                }
+                for position_id in closed {
+                    if let Some((_, position)) = self.positions.remove(&position_id) {
+                        self.record_closed_position(position.symbol_code.clone(), position);
+                    }
+                }
+                continue;
+            }
             if let Some(codes) = self.symbol_code_map.get(data_symbol_name) {
                 for code in codes.value() {
                     if let Some(mut position) = self.positions.get_mut(code) {
-                        let open_pnl = position.update_base_data(&base_data_enum, self.currency);
+                        let open_pnl = position.mark_to_market(data, self.currency);
                         self.open_pnl.insert(data_symbol_name.clone(), open_pnl);
                     }
                 }
@@ -673,20 +781,24 @@ impl Ledger {
                 }
 
                 if self.mode != StrategyMode::Live || self.is_simulating_pnl {
-                    let open_pnl = position.update_base_data(&base_data_enum, self.currency);
+                    let open_pnl = position.mark_to_market(data, self.currency);
                     self.open_pnl.insert(data_symbol_name.clone(), open_pnl);
                 }
 
                 if position.is_closed {
+                    drop(position);
                     // Move the position to the closed positions map
                     let (symbol_name, position) = self.positions.remove(data_symbol_name).unwrap();
-                    self.positions_closed
-                        .entry(symbol_name)
-                        .or_insert_with(Vec::new)
-                        .push(position);
+                    self.record_closed_position(symbol_name, position);
                 }
             }
         }
+        if self.mode != StrategyMode::Live || self.is_simulating_pnl {
+            if let Some(time) = time_slice.iter().map(|data| data.time_closed_utc()).max() {
+                self.update_margin_period(time);
+                self.accrue_financing(time).await;
+            }
+        }
         if self.mode != StrategyMode::Live {
             self.cash_value = self.cash_used + self.cash_available;
         }
@@ -753,13 +865,7 @@ impl Ledger {
 
                             self.total_booked_pnl += booked_pnl;
                         }
-                        if !self.positions_closed.contains_key(&symbol_code) {
-                            self.positions_closed.insert(symbol_code.clone(), vec![]);
-                        }
-                        self.positions_closed
-                            .entry(symbol_code.clone())
-                            .or_insert_with(Vec::new)
-                            .push(existing_position);
+                        self.record_closed_position(symbol_code.clone(), existing_position);
                         //println!("Closed Position: {}", symbol_name);
                     }
                     _ => panic!("This shouldn't happen")
@@ -845,67 +951,109 @@ impl Ledger {
             position_events.push(event);
         }
         for event in position_events {
+            self.journal_position_event(&event);
             match self.strategy_sender.send(StrategyEvent::PositionEvents(event)).await {
                 Ok(_) => {}
-                Err(e) => eprintln!("Error sending position event: {}", e)
+                Err(e) => tracing::error!("Error sending position event: {}", e)
             }
         }
     }
 
-    // Function to export individual trades to CSV
-    pub fn export_trades_to_csv(&self, folder: &str) {
-        // Create the folder if it does not exist
-        if let Err(e) = create_dir_all(folder) {
-            eprintln!("Failed to create directory {}: {}", folder, e);
-            return;
+    /// Records the event in the journal if it is enabled, a closed position also records its completed trades and the account balance.
+    pub(crate) fn journal_position_event(&self, event: &PositionUpdateEvent) {
+        let journal = match self.journal.get() {
+            Some(journal) => journal,
+            None => return
+        };
+        journal.record_position_event(event);
+        if let PositionUpdateEvent::PositionClosed { symbol_code, position_id, time, .. } = event {
+            if let Some(closed) = self.positions_closed.get(symbol_code) {
+                if let Some(position) = closed.iter().rev().find(|position| &position.position_id == position_id) {
+                    journal.record_closed_position(position);
+                }
+            }
+            // Live balances are journaled when the broker sends an account update
+            if self.mode != StrategyMode::Live {
+                let time = DateTime::<Utc>::from_str(time).unwrap_or_else(|_| Utc::now());
+                journal.record_balance(&self.account, self.cash_value, self.cash_available, self.cash_used, time);
+            }
         }
+    }
 
-        // Get current date in desired format
-        let date = Utc::now().format("%Y%m%d_%H%M").to_string();
-
-        // Use brokerage and account ID to format the filename
-        let brokerage = self.account.brokerage.to_string();
-        let file_name = format!("{}/{:?}_TradeResults_{}_{}_{}.csv", folder, self.mode, brokerage, self.account.account_id, date);
-
-        // Create a writer for the CSV file
-        let file_path = Path::new(&file_name);
-        match Writer::from_path(file_path) {
-            Ok(mut wtr) => {
-                // Iterate over all closed positions and their trades
-                for entry in self.positions_closed.iter() {
-                    for position in entry.value() {
-                        for trade in &position.completed_trades {
-                            let export = TradeExport {
-                                symbol_code: position.symbol_code.clone(),
-                                position_id: position.position_id.clone(),
-                                side: position.side.to_string(),
-                                entry_price: trade.entry_price,
-                                entry_quantity: trade.entry_quantity,
-                                exit_price: trade.exit_price,
-                                exit_quantity: trade.exit_quantity,
-                                entry_time: trade.entry_time.clone(),
-                                exit_time: trade.exit_time.clone(),
-                                pnl: trade.profit,
-                                tag: position.tag.clone(),
-                                result: trade.result.to_string()
-                            };
-
-                            if let Err(e) = wtr.serialize(export) {
-                                eprintln!("Failed to write trade data to {}: {}", file_path.display(), e);
-                            }
-                        }
-                    }
+    /// Every completed trade of the closed positions, in the format written by `export_trades_to_csv()`.
+    pub fn trade_exports(&self) -> Vec<TradeExport> {
+        let mut exports = vec![];
+        for entry in self.positions_closed.iter() {
+            for position in entry.value() {
+                for trade in &position.completed_trades {
+                    exports.push(TradeExport {
+                        symbol_code: position.symbol_code.clone(),
+                        position_id: position.position_id.clone(),
+                        side: position.side.to_string(),
+                        entry_price: trade.entry_price,
+                        entry_quantity: trade.entry_quantity,
+                        exit_price: trade.exit_price,
+                        exit_quantity: trade.exit_quantity,
+                        entry_time: trade.entry_time.clone(),
+                        exit_time: trade.exit_time.clone(),
+                        pnl: trade.profit,
+                        tag: position.tag.clone(),
+                        result: trade.result.to_string()
+                    });
                 }
+            }
+        }
+        exports
+    }
 
-                if let Err(e) = wtr.flush() {
-                    eprintln!("Failed to flush CSV writer for {}: {}", file_path.display(), e);
-                } else {
-                    println!("Successfully exported all trades to {}", file_path.display());
+    /// The completed trades of every closed position, oldest exit first.
+    pub fn closed_trades(&self) -> Vec<ClosedTrade> {
+        let mut trades = vec![];
+        for entry in self.positions_closed.iter() {
+            for position in entry.value() {
+                for trade in &position.completed_trades {
+                    let (entry_time, exit_time) = match (DateTime::from_str(&trade.entry_time), DateTime::from_str(&trade.exit_time)) {
+                        (Ok(entry_time), Ok(exit_time)) => (entry_time, exit_time),
+                        _ => continue,
+                    };
+                    trades.push(ClosedTrade {
+                        symbol_name: position.symbol_name.clone(),
+                        symbol_code: position.symbol_code.clone(),
+                        position_id: position.position_id.clone(),
+                        side: position.side,
+                        entry_price: trade.entry_price,
+                        entry_quantity: trade.entry_quantity,
+                        exit_price: trade.exit_price,
+                        exit_quantity: trade.exit_quantity,
+                        entry_time,
+                        exit_time,
+                        pnl: trade.profit,
+                        commissions: trade.commissions,
+                        tag: position.tag.clone(),
+                        result: trade.result.clone(),
+                    });
                 }
             }
-            Err(e) => {
-                eprintln!("Failed to create CSV writer for {}: {}", file_path.display(), e);
-            }
+        }
+        trades.sort_by_key(|trade| trade.exit_time);
+        trades
+    }
+
+    /// The closed positions for the symbol name on any contract, oldest close first.
+    pub fn position_history(&self, symbol_name: &SymbolName) -> Vec<Position> {
+        let mut positions: Vec<Position> = self.positions_closed.iter()
+            .flat_map(|entry| entry.value().clone())
+            .filter(|position| &position.symbol_name == symbol_name)
+            .collect();
+        positions.sort_by_key(|position| position.close_time.as_ref().and_then(|time| DateTime::<Utc>::from_str(time).ok()));
+        positions
+    }
+
+    // Function to export individual trades to CSV
+    pub fn export_trades_to_csv(&self, folder: &str) {
+        match self.export_trades(folder, TradeExportFormat::FundForgeCsv) {
+            Ok(file_name) => tracing::info!("Successfully exported all trades to {}", file_name),
+            Err(e) => tracing::error!("Failed to export trades: {}", e),
         }
     }
 
@@ -959,9 +1107,9 @@ impl Ledger {
 
         // Calculate derived statistics
         let win_rate = if total_trades > 0 {
-            (wins as f64 / total_trades as f64 * 100.0).round()
+            (Decimal::from(wins) / Decimal::from(total_trades) * dec!(100)).round()
         } else {
-            0.0
+            dec!(0)
         };
 
         let avg_win = if wins > 0 {
@@ -1041,30 +1189,52 @@ impl Ledger {
     }
 }
 
-#[derive(Debug, Serialize)]
-struct TradeExport {
-    symbol_code: String,
-    position_id: String,
-    side: String,
-    entry_price: Decimal,
-    entry_quantity: Decimal,
-    exit_price: Decimal,
-    exit_quantity: Decimal,
-    entry_time: String,
-    exit_time: String,
-    pnl: Decimal,
-    tag: String,
-    result: String,
+/// A single completed trade as written by `export_trades_to_csv()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeExport {
+    pub symbol_code: String,
+    pub position_id: String,
+    pub side: String,
+    pub entry_price: Decimal,
+    pub entry_quantity: Decimal,
+    pub exit_price: Decimal,
+    pub exit_quantity: Decimal,
+    pub entry_time: String,
+    pub exit_time: String,
+    pub pnl: Decimal,
+    pub tag: String,
+    pub result: String,
+}
+
+/// A single completed trade of a closed position, see `FundForgeStrategy::closed_trades()`.
+/// A position which is scaled out of has a trade for each exit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosedTrade {
+    pub symbol_name: SymbolName,
+    pub symbol_code: SymbolCode,
+    pub position_id: PositionId,
+    pub side: PositionSide,
+    pub entry_price: Price,
+    pub entry_quantity: Volume,
+    pub exit_price: Price,
+    pub exit_quantity: Volume,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: DateTime<Utc>,
+    /// The booked pnl of the trade in the account currency, after commissions.
+    pub pnl: Price,
+    pub commissions: Decimal,
+    pub tag: String,
+    pub result: TradeResult,
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use super::*;
     use rust_decimal_macros::dec;
     use crate::apis::rithmic::rithmic_systems::RithmicSystem;
     use crate::product_maps::rithmic::maps::get_futures_commissions_info;
 
-    async fn setup_test_ledger() -> (Ledger, tokio::sync::mpsc::Receiver<StrategyEvent>) {
+    pub(crate) async fn setup_test_ledger() -> (Ledger, tokio::sync::mpsc::Receiver<StrategyEvent>) {
         let (strategy_sender, strategy_receiver) = tokio::sync::mpsc::channel(100);
         let market_price_service = Arc::new(MarketPriceService::new());
         let account_info = AccountInfo {
@@ -1094,7 +1264,8 @@ mod test {
             false,
             strategy_sender,
             PositionCalculationMode::FIFO,
-            market_price_service
+            market_price_service,
+            Arc::new(OnceLock::new())
         );
 
         (ledger, strategy_receiver)
@@ -1656,6 +1827,18 @@ mod test {
             ledger.total_booked_pnl
         );
 
+        let trades = ledger.closed_trades();
+        assert_eq!(trades.len(), 3);
+        assert!(trades.iter().all(|trade| trade.result == TradeResult::Loss && trade.pnl < dec!(0)));
+        assert_eq!(trades.iter().map(|trade| trade.exit_time).collect::<Vec<_>>(), vec![time + Duration::minutes(10), time + Duration::minutes(15), time + Duration::minutes(20)]);
+        assert_eq!(trades[1].symbol_name, symbol2);
+        assert_eq!(trades[1].side, PositionSide::Short);
+        assert_eq!(trades.iter().map(|trade| trade.pnl).sum::<Decimal>(), expected_total_pnl);
+
+        let history = ledger.position_history(&symbol1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].completed_trades.len(), 2);
+
         println!("\nFinal Ledger Statistics:");
         println!("{}", ledger.ledger_statistics_to_string());
 
@@ -1,4 +1,6 @@
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use chrono::{DateTime, Utc};
 use crate::standardized_types::enums::{OrderSide, PositionSide, StrategyMode};
 use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
@@ -6,21 +8,33 @@ use dashmap::DashMap;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tokio::sync::{oneshot};
-use crate::standardized_types::position::{Position, PositionCalculationMode};
+use crate::standardized_types::position::{Position, PositionId};
 use crate::standardized_types::accounts::{Account, Currency};
-use crate::standardized_types::broker_enum::Brokerage;
 use crate::standardized_types::new_types::{Price, Volume};
 use crate::standardized_types::orders::{OrderId, OrderUpdateEvent};
 use crate::standardized_types::time_slices::TimeSlice;
 use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
-use crate::strategies::ledgers::ledger::{Ledger, LedgerMessage};
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::strategies::ledgers::journal::{AccountJournal, TradeFilter};
+use crate::strategies::ledgers::ledger::{ClosedTrade, Ledger, LedgerMessage, TradeExport};
+use crate::strategies::ledgers::statement_import::{StatementFill, StatementImport};
+use crate::strategies::ledgers::ledger_snapshot::{LedgerRestore, LedgerSnapshot};
+use crate::strategies::ledgers::campaigns::{Campaign, CampaignGrouping};
+use crate::strategies::ledgers::hedging::{is_hedging_account, PositionTarget};
+use crate::strategies::ledgers::lot_accounting::lot_accounting;
+use crate::strategies::ledgers::tag_view::TagSummary;
+use crate::strategies::ledgers::trade_formats::TradeExportFormat;
+use crate::strategies::ledgers::account_statement::AccountStatement;
+use crate::strategies::statistics::streaming::StreamingStatistics;
 use crate::strategies::strategy_events::StrategyEvent;
+use crate::strategies::ledgers::margin_engine::MarginPeriod;
 
 pub(crate) struct LedgerService {
     pub (crate) ledgers: DashMap<Account, &'static Ledger>,
     ledger_senders: DashMap<Account, tokio::sync::mpsc::Sender<LedgerMessage>>,
     strategy_sender: tokio::sync::mpsc::Sender<StrategyEvent>,
-    market_price_service: Arc<MarketPriceService>
+    market_price_service: Arc<MarketPriceService>,
+    journal: Arc<OnceLock<AccountJournal>>
 }
 
 impl LedgerService {
@@ -29,11 +43,34 @@ impl LedgerService {
             ledgers: Default::default(),
             ledger_senders: Default::default(),
             strategy_sender,
-            market_price_service
+            market_price_service,
+            journal: Arc::new(OnceLock::new())
         }
     }
 
-    pub async fn synchronize_live_position(&self, symbol_name: SymbolName, symbol_code: SymbolCode, account: Account, open_quantity: f64, average_price: f64, side: PositionSide, open_pnl: f64, time: String) {
+    /// Opens the journal database, the journal is shared by all ledgers and can only be enabled once.
+    pub fn enable_journal(&self, path: &str) -> Result<(), FundForgeError> {
+        if self.journal.get().is_some() {
+            return Err(FundForgeError::ClientSideErrorDebug("Journal is already enabled".to_string()))
+        }
+        let journal = AccountJournal::open(path)?;
+        self.journal.set(journal).map_err(|_| FundForgeError::ClientSideErrorDebug("Journal is already enabled".to_string()))
+    }
+
+    pub fn journal_order_event(&self, event: &OrderUpdateEvent) {
+        if let Some(journal) = self.journal.get() {
+            journal.record_order_event(event);
+        }
+    }
+
+    pub fn query_trades(&self, filter: &TradeFilter) -> Result<Vec<TradeExport>, FundForgeError> {
+        match self.journal.get() {
+            Some(journal) => journal.query_trades(filter),
+            None => Err(FundForgeError::ClientSideErrorDebug("Journal is not enabled, use strategy.enable_journal()".to_string()))
+        }
+    }
+
+    pub async fn synchronize_live_position(&self, symbol_name: SymbolName, symbol_code: SymbolCode, account: Account, open_quantity: Volume, average_price: Price, side: PositionSide, open_pnl: Price, time: String) {
         if let Some(sender) = self.ledger_senders.get(&account) {
             let msg = LedgerMessage::SyncPosition{symbol_name, symbol_code, account, open_quantity, average_price, side, open_pnl, time};
             sender.send(msg).await.unwrap();
@@ -47,6 +84,33 @@ impl LedgerService {
         }
     }
 
+    /// Rebuilds the positions and trades from the statement fills into the account ledger.
+    pub async fn import_statement(&self, account: &Account, fills: Vec<StatementFill>) -> Result<StatementImport, FundForgeError> {
+        let sender = self.ledger_senders.get(account)
+            .ok_or(FundForgeError::ClientSideErrorDebug(format!("No ledger for account: {}", account)))?;
+        let (response_sender, response_receiver) = oneshot::channel();
+        sender.send(LedgerMessage::ImportStatement { fills, response_sender }).await
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to send statement to ledger: {}", e)))?;
+        response_receiver.await
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Ledger did not import statement: {}", e)))
+    }
+
+    pub fn snapshot(&self, account: &Account) -> Option<LedgerSnapshot> {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.snapshot())
+    }
+
+    pub async fn restore_snapshot(&self, snapshot: LedgerSnapshot) -> Result<LedgerRestore, FundForgeError> {
+        let account = snapshot.account.clone();
+        let sender = self.ledger_senders.get(&account)
+            .ok_or(FundForgeError::ClientSideErrorDebug(format!("No ledger for account: {}", account)))?;
+        let (response_sender, response_receiver) = oneshot::channel();
+        sender.send(LedgerMessage::RestoreSnapshot { snapshot, response_sender }).await
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to send snapshot to ledger: {}", e)))?;
+        response_receiver.await
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Ledger did not restore snapshot: {}", e)))
+    }
+
     pub fn get_positions(&self, account: &Account) -> DashMap<SymbolCode, Vec<Position>> {
         if let Some(ledger) = self.ledgers.get(account) {
             ledger.value().positions_closed.clone()
@@ -78,10 +142,11 @@ impl LedgerService {
         market_fill_price: Price, // we use the passed in price because we don't know what sort of order was filled, limit or market
         tag: String,
         paper_response_sender: Option<oneshot::Sender<Option<OrderUpdateEvent>>>,
-        order_id: OrderId
+        order_id: OrderId,
+        target: PositionTarget
     ) {
         if let Some(sender) = self.ledger_senders.get(account) {
-            let msg = LedgerMessage::UpdateOrCreatePosition{symbol_name, symbol_code, quantity, side, time, market_fill_price, tag, paper_response_sender, order_id};
+            let msg = LedgerMessage::UpdateOrCreatePosition{symbol_name, symbol_code, quantity, side, time, market_fill_price, tag, paper_response_sender, order_id, target};
             sender.send(msg).await.unwrap();
         }
     }
@@ -114,10 +179,17 @@ impl LedgerService {
         }
     }
 
+    /// Requests a new account snapshot from the brokerage and updates the balances of the live ledger, used to resume the session after a dropped connection is restored.
+    pub async fn refresh_live_account(&self, account: &Account) -> Result<(), FundForgeError> {
+        let account_info = account.brokerage.account_info(account.account_id.clone()).await?;
+        self.live_account_updates(account, account_info.cash_value, account_info.cash_available, account_info.cash_used).await;
+        Ok(())
+    }
+
     pub fn print_ledgers(&self) {
         for ledger in self.ledgers.iter() {
             let msg = ledger.value().ledger_statistics_to_string();
-            println!("{}", msg);
+            tracing::info!("{}", msg);
         }
     }
 
@@ -127,6 +199,38 @@ impl LedgerService {
         }
     }
 
+    pub fn export_trades(&self, account: &Account, directory: &str, format: TradeExportFormat) -> Result<String, FundForgeError> {
+        match self.ledgers.get(account) {
+            Some(ledger) => ledger.export_trades(directory, format),
+            None => Err(FundForgeError::ClientSideErrorDebug(format!("No ledger for account: {}", account)))
+        }
+    }
+
+    pub fn trade_exports(&self, account: &Account) -> Vec<TradeExport> {
+        match self.ledgers.get(account) {
+            Some(ledger) => ledger.trade_exports(),
+            None => vec![]
+        }
+    }
+
+    pub fn closed_trades(&self, account: &Account) -> Vec<ClosedTrade> {
+        match self.ledgers.get(account) {
+            Some(ledger) => ledger.closed_trades(),
+            None => vec![]
+        }
+    }
+
+    pub fn account_statement(&self, account: &Account, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<AccountStatement> {
+        self.ledgers.get(account).map(|ledger| ledger.account_statement(from, to))
+    }
+
+    pub fn position_history(&self, account: &Account, symbol_name: &SymbolName) -> Vec<Position> {
+        match self.ledgers.get(account) {
+            Some(ledger) => ledger.position_history(symbol_name),
+            None => vec![]
+        }
+    }
+
     pub fn export_positions_to_csv(&self, account: &Account, directory: &str) {
         if let Some(ledger) = self.ledgers.get(account) {
             ledger.export_positions_to_csv(directory);
@@ -136,7 +240,7 @@ impl LedgerService {
     pub fn print_trade_statistics(&self, account: &Account) {
         if let Some(ledger) = self.ledgers.get(account) {
             let msg = ledger.trade_statistics_to_string();
-            println!("{}", msg);
+            tracing::info!("{}", msg);
         }
     }
 
@@ -151,7 +255,7 @@ impl LedgerService {
     pub fn print_ledger(&self, account: &Account) {
        if let Some(ledger) = self.ledgers.get(account) {
            let string = ledger.value().ledger_statistics_to_string(); //todo need to return the string here
-           println!("{}", string);
+           tracing::info!("{}", string);
        }
     }
 
@@ -163,10 +267,7 @@ impl LedgerService {
     }
 
     pub async fn init_ledger(&self, account: &Account, strategy_mode: StrategyMode, synchronize_accounts: bool, starting_cash: Decimal, currency: Currency) {
-        let position_calculation_mode = match account.brokerage {
-            Brokerage::Rithmic(_) => PositionCalculationMode::LIFO,
-            _ => PositionCalculationMode::LIFO,
-        };
+        let position_calculation_mode = lot_accounting(account);
         if !self.ledgers.contains_key(account) {
             match strategy_mode {
                 StrategyMode::Live => {
@@ -185,6 +286,7 @@ impl LedgerService {
                         self.strategy_sender.clone(),
                         position_calculation_mode,
                         self.market_price_service.clone(),
+                        self.journal.clone(),
                     ));
                     let static_ledger: &'static Ledger = Box::leak(ledger);
 
@@ -211,18 +313,26 @@ impl LedgerService {
                         last_update: Default::default(),
                         symbol_code_map: Default::default(),
                         margin_used: Default::default(),
+                        margin_positions: Default::default(),
+                        portfolio_credit: dec!(0),
+                        margin_period: MarginPeriod::Intraday,
                         positions_closed: Default::default(),
                         symbol_closed_pnl: Default::default(),
                         symbol_info: Default::default(),
                         open_pnl: Default::default(),
                         total_booked_pnl: dec!(0),
+                        total_financing: dec!(0),
+                        next_rollover: None,
                         mode: strategy_mode.clone(),
                         is_simulating_pnl: true,
+                        is_hedging: is_hedging_account(account),
                         strategy_sender: self.strategy_sender.clone(),
                         rates: Arc::new(DashMap::new()),
                         position_calculation_mode,
                         market_price_service: self.market_price_service.clone(),
-
+                        journal: self.journal.clone(),
+                        statistics: Default::default(),
+                        closed_position_limit: AtomicUsize::new(usize::MAX),
                     });
                     let static_ledger: &'static Ledger = Box::leak(ledger);
 
@@ -241,6 +351,17 @@ impl LedgerService {
             }
         }
     }
+    pub fn statistics(&self, account: &Account) -> Option<StreamingStatistics> {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.statistics())
+    }
+
+    pub fn set_closed_position_limit(&self, account: &Account, limit: Option<usize>) {
+        if let Some(ledger) = self.ledgers.get(account) {
+            ledger.closed_position_limit.store(limit.unwrap_or(usize::MAX), Ordering::SeqCst);
+        }
+    }
+
     pub fn is_long(&self, account: &Account, symbol_name: &SymbolName) -> bool {
         self.ledgers.get(account)
              .map(|ledger| ledger.is_long(symbol_name))
@@ -265,6 +386,29 @@ impl LedgerService {
             .unwrap_or_else(|| dec!(0))
     }
 
+    pub fn is_hedging(&self, account: &Account) -> bool {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.is_hedging)
+            .unwrap_or(false)
+    }
+
+    pub fn open_positions(&self, account: &Account, symbol_code: &SymbolCode) -> Vec<Position> {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.open_positions(symbol_code))
+            .unwrap_or_default()
+    }
+
+    pub fn position(&self, account: &Account, position_id: &PositionId) -> Option<Position> {
+        self.ledgers.get(account)
+            .and_then(|ledger| ledger.position(position_id))
+    }
+
+    pub fn side_quantity(&self, account: &Account, symbol_code: &SymbolCode, side: PositionSide) -> Volume {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.side_quantity(symbol_code, side))
+            .unwrap_or_else(|| dec!(0))
+    }
+
     pub fn open_pnl(&self, account: &Account) -> Decimal {
         self.ledgers.get(account)
              .map(|ledger| ledger.get_open_pnl())
@@ -277,12 +421,48 @@ impl LedgerService {
             .unwrap_or_else(|| dec!(0))
     }
 
+    pub fn mini_equivalent_position(&self, account: &Account, symbol_name: &SymbolName) -> Decimal {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.mini_equivalent_position(symbol_name))
+            .unwrap_or_else(|| dec!(0))
+    }
+
+    pub fn related_contracts_pnl(&self, account: &Account, symbol_name: &SymbolName) -> Decimal {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.related_contracts_pnl(symbol_name))
+            .unwrap_or_else(|| dec!(0))
+    }
+
     pub fn booked_pnl(&self, account: &Account, symbol_name: &SymbolName) -> Decimal {
         self.ledgers.get(account)
              .map(|ledger| ledger.booked_pnl(symbol_name))
             .unwrap_or_else(|| dec!(0))
     }
 
+    pub fn tag_summaries(&self, account: &Account, tag_prefix: &str) -> BTreeMap<String, TagSummary> {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.tag_summaries(tag_prefix))
+            .unwrap_or_default()
+    }
+
+    pub fn tag_summary(&self, account: &Account, tag_prefix: &str) -> TagSummary {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.tag_summary(tag_prefix))
+            .unwrap_or_default()
+    }
+
+    pub fn campaigns(&self, account: &Account, grouping: &CampaignGrouping) -> Vec<Campaign> {
+        self.ledgers.get(account)
+            .map(|ledger| ledger.campaigns(grouping))
+            .unwrap_or_default()
+    }
+
+    pub fn export_campaigns_to_csv(&self, account: &Account, directory: &str, grouping: &CampaignGrouping) {
+        if let Some(ledger) = self.ledgers.get(account) {
+            ledger.export_campaigns_to_csv(directory, grouping);
+        }
+    }
+
     pub fn in_profit(&self, account: &Account, symbol_name: &SymbolName) -> bool {
         self.ledgers.get(account)
              .map(|ledger| ledger.in_profit(symbol_name))
@@ -0,0 +1,119 @@
+use std::sync::atomic::Ordering;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::new_types::Price;
+use crate::standardized_types::position::Position;
+use crate::standardized_types::subscriptions::SymbolCode;
+use crate::strategies::ledgers::ledger::Ledger;
+use crate::strategies::ledgers::margin_engine::MarginedPosition;
+use crate::strategies::statistics::streaming::StreamingStatistics;
+
+/// The state of a ledger saved in a strategy checkpoint, see `StrategySnapshot`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct LedgerSnapshot {
+    pub account: Account,
+    pub cash_value: Price,
+    pub cash_available: Price,
+    pub cash_used: Price,
+    pub portfolio_credit: Price,
+    pub total_booked_pnl: Price,
+    pub total_financing: Price,
+    pub open_positions: Vec<Position>,
+    pub closed_positions: Vec<Position>,
+    pub symbol_closed_pnl: Vec<(SymbolCode, Decimal)>,
+    pub margin_positions: Vec<(SymbolCode, MarginedPosition)>,
+    pub statistics: StreamingStatistics,
+}
+
+/// What was restored from a `LedgerSnapshot`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LedgerRestore {
+    pub open_positions: usize,
+    pub closed_positions: usize,
+    /// Open positions in the snapshot which were not restored because the ledger already has a different position for the symbol code.
+    pub conflicting_positions: usize,
+}
+
+impl Ledger {
+    pub(crate) fn snapshot(&self) -> LedgerSnapshot {
+        LedgerSnapshot {
+            account: self.account.clone(),
+            cash_value: self.cash_value,
+            cash_available: self.cash_available,
+            cash_used: self.cash_used,
+            portfolio_credit: self.portfolio_credit,
+            total_booked_pnl: self.total_booked_pnl,
+            total_financing: self.total_financing,
+            open_positions: self.positions.iter().map(|position| position.value().clone()).collect(),
+            closed_positions: self.positions_closed.iter().flat_map(|positions| positions.value().clone()).collect(),
+            symbol_closed_pnl: self.symbol_closed_pnl.iter().map(|pnl| (pnl.key().clone(), *pnl.value())).collect(),
+            margin_positions: self.margin_positions.iter().map(|margined| (margined.key().clone(), margined.value().clone())).collect(),
+            statistics: self.statistics(),
+        }
+    }
+
+    /// Restores the positions of a snapshot, only call this on a ledger which has not closed any positions.
+    ///
+    /// Open positions already reported by the broker are kept when they differ from the snapshot, matching positions are replaced by the snapshot
+    /// so they keep their id, tag and trades. Balances and margin are only restored when the ledger simulates pnl, synchronized ledgers use the broker balances.
+    pub(crate) fn restore_snapshot(&mut self, snapshot: LedgerSnapshot) -> LedgerRestore {
+        let mut restore = LedgerRestore { open_positions: 0, closed_positions: 0, conflicting_positions: 0 };
+        for position in snapshot.open_positions {
+            let symbol_code = position.symbol_code.clone();
+            if let Some(existing) = self.positions.get(&symbol_code) {
+                if existing.side != position.side || existing.quantity_open != position.quantity_open {
+                    tracing::warn!("Checkpoint Restore: {} snapshot position {} {} does not match the broker position {} {}, keeping the broker position",
+                              symbol_code, position.side, position.quantity_open, existing.side, existing.quantity_open);
+                    restore.conflicting_positions += 1;
+                    continue;
+                }
+            }
+            if position.symbol_name != symbol_code {
+                let mut codes = self.symbol_code_map.entry(position.symbol_name.clone()).or_default();
+                if !codes.contains(&symbol_code) {
+                    codes.push(symbol_code.clone());
+                }
+            }
+            let key = match self.is_hedging {
+                true => position.position_id.clone(),
+                false => symbol_code,
+            };
+            self.positions.insert(key, position);
+            restore.open_positions += 1;
+        }
+
+        let limit = self.closed_position_limit.load(Ordering::SeqCst);
+        for position in snapshot.closed_positions {
+            let mut closed = self.positions_closed.entry(position.symbol_code.clone()).or_default();
+            closed.push(position);
+            if closed.len() > limit {
+                closed.remove(0);
+            }
+            restore.closed_positions += 1;
+        }
+        for (symbol_code, pnl) in snapshot.symbol_closed_pnl {
+            *self.symbol_closed_pnl.entry(symbol_code).or_insert(dec!(0)) += pnl;
+        }
+        self.total_booked_pnl += snapshot.total_booked_pnl;
+        if let Ok(mut statistics) = self.statistics.lock() {
+            *statistics = snapshot.statistics;
+        }
+
+        if self.is_simulating_pnl {
+            self.cash_value = snapshot.cash_value;
+            self.cash_available = snapshot.cash_available;
+            self.cash_used = snapshot.cash_used;
+            self.portfolio_credit = snapshot.portfolio_credit;
+            self.total_financing = snapshot.total_financing;
+            for (symbol_code, margined) in snapshot.margin_positions {
+                self.margin_used.insert(symbol_code.clone(), margined.margin);
+                self.margin_positions.insert(symbol_code, margined);
+            }
+        }
+        restore
+    }
+}
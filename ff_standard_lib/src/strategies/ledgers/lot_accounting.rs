@@ -0,0 +1,19 @@
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::position::PositionCalculationMode;
+
+lazy_static! {
+    static ref LOT_ACCOUNTING: DashMap<Account, PositionCalculationMode> = DashMap::new();
+}
+
+/// Sets the entry lots the positions of the account close first on partial exits, this must be called before `FundForgeStrategy::initialize()`.
+/// The lot accounting decides the entry price the pnl of each exit is booked against, the total pnl of a position closed in full is the same for every mode.
+pub fn set_lot_accounting(account: Account, mode: PositionCalculationMode) {
+    LOT_ACCOUNTING.insert(account, mode);
+}
+
+/// The lot accounting of the account, `PositionCalculationMode::FIFO` until one is set.
+pub fn lot_accounting(account: &Account) -> PositionCalculationMode {
+    LOT_ACCOUNTING.get(account).map(|mode| mode.value().clone()).unwrap_or(PositionCalculationMode::FIFO)
+}
@@ -0,0 +1,377 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use chrono::{DateTime, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_derive::Deserialize;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::product_maps::rithmic::maps::{get_all_related_contracts, get_overnight_margin, get_rithmic_intraday_margin_in_usd};
+use crate::standardized_types::broker_enum::Brokerage;
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::subscriptions::SymbolName;
+use crate::strategies::trading_schedule::{TradingSchedule, TradingWindow};
+
+lazy_static! {
+    static ref MARGIN_ENGINES: DashMap<Brokerage, MarginEngine> = DashMap::new();
+}
+
+/// Replaces the margin engine used by the paper ledgers of the brokerage, see `MarginEngine`.
+pub fn set_margin_engine(brokerage: Brokerage, engine: MarginEngine) {
+    MARGIN_ENGINES.insert(brokerage, engine);
+}
+
+/// The margin engine for the brokerage, the default engine is used until one is set.
+pub fn margin_engine(brokerage: Brokerage) -> MarginEngine {
+    MARGIN_ENGINES.entry(brokerage)
+        .or_insert_with(|| MarginEngine::default_for(brokerage))
+        .clone()
+}
+
+/// An exchange style spread credit, one spread is `quantity_a` of `leg_a` held against `quantity_b` of `leg_b` on the opposite side.
+/// The credit is the `credit_rate` of the margin of both legs of each spread, eg. 10 MES long against 1 ES short with a rate of 1 requires no margin.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct SpreadCredit {
+    pub leg_a: SymbolName,
+    pub quantity_a: Volume,
+    pub leg_b: SymbolName,
+    pub quantity_b: Volume,
+    pub credit_rate: Decimal,
+}
+
+impl SpreadCredit {
+    pub fn new(leg_a: &str, quantity_a: Volume, leg_b: &str, quantity_b: Volume, credit_rate: Decimal) -> Self {
+        Self {
+            leg_a: leg_a.to_string(),
+            quantity_a,
+            leg_b: leg_b.to_string(),
+            quantity_b,
+            credit_rate,
+        }
+    }
+}
+
+/// Which margin schedule applies, see `MarginEngine::intraday_schedule`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MarginPeriod {
+    Intraday,
+    Overnight,
+}
+
+impl fmt::Display for MarginPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarginPeriod::Intraday => write!(f, "Intraday"),
+            MarginPeriod::Overnight => write!(f, "Overnight"),
+        }
+    }
+}
+
+/// A position held on margin, `quantity` is signed, long positive and short negative, `margin` is the margin required for the position on its own.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct MarginedPosition {
+    pub symbol_name: SymbolName,
+    pub quantity: Volume,
+    pub margin: Price,
+}
+
+/// Calculates the margin for the positions of an account as a portfolio rather than one position at a time.
+///
+/// Positions in different contracts of the same symbol name are netted, and offsetting positions in related symbols receive the spread credits,
+/// so a hedged account is not charged the full margin of both sides.
+/// The paper ledgers use the engine when committing margin for each fill, so orders which are covered by a spread credit are not rejected for insufficient funds.
+///
+/// `margin_overrides` replace the broker intraday margin per contract for a symbol name, in the pnl currency of the symbol.
+/// `overnight_margins` replace the overnight margin per contract, Rithmic symbols without an override use the exchange overnight margin.
+/// The intraday margins apply inside the windows of the `intraday_schedule` and the overnight margins outside them,
+/// without a schedule the intraday margins always apply. The paper ledgers re-margin their open positions when the schedule switches.
+/// # Example
+/// ```rust
+/// use chrono::NaiveTime;
+/// use rust_decimal_macros::dec;
+/// use ff_standard_lib::apis::rithmic::rithmic_systems::RithmicSystem;
+/// use ff_standard_lib::standardized_types::broker_enum::Brokerage;
+/// use ff_standard_lib::strategies::ledgers::margin_engine::{margin_engine, set_margin_engine, SpreadCredit};
+/// use ff_standard_lib::strategies::trading_schedule::{TradingSchedule, TradingWindow};
+///
+/// let mut engine = margin_engine(Brokerage::Rithmic(RithmicSystem::Apex));
+/// engine.spread_credits.push(SpreadCredit::new("ES", dec!(1), "NQ", dec!(1), dec!(0.5)));
+/// engine.margin_overrides.insert("MNQ".to_string(), dec!(100));
+/// let session = TradingWindow::weekdays(NaiveTime::from_hms_opt(8, 30, 0).unwrap(), NaiveTime::from_hms_opt(15, 45, 0).unwrap());
+/// engine.intraday_schedule = Some(TradingSchedule::new(chrono_tz::America::Chicago, vec![session]));
+/// set_margin_engine(Brokerage::Rithmic(RithmicSystem::Apex), engine);
+/// ```
+/// The engines can also be loaded from a file, see `load_margin_engines()`.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct MarginEngine {
+    pub spread_credits: Vec<SpreadCredit>,
+    pub margin_overrides: HashMap<SymbolName, Price>,
+    pub overnight_margins: HashMap<SymbolName, Price>,
+    pub intraday_schedule: Option<TradingSchedule>,
+}
+
+impl MarginEngine {
+    /// Futures brokers get full credits between the micro and standard contracts of the same underlying, other brokerages have no credits.
+    pub fn default_for(brokerage: Brokerage) -> Self {
+        let spread_credits = match brokerage {
+            Brokerage::Rithmic(_) => get_all_related_contracts().into_iter()
+                .map(|contracts| SpreadCredit::new(&contracts.micro, contracts.micros_per_mini, &contracts.mini, dec!(1), dec!(1)))
+                .collect(),
+            _ => vec![],
+        };
+        Self {
+            spread_credits,
+            margin_overrides: HashMap::new(),
+            overnight_margins: HashMap::new(),
+            intraday_schedule: None,
+        }
+    }
+
+    pub fn margin_period(&self, time: DateTime<Utc>) -> MarginPeriod {
+        match &self.intraday_schedule {
+            Some(schedule) if !schedule.is_active(time) => MarginPeriod::Overnight,
+            _ => MarginPeriod::Intraday,
+        }
+    }
+
+    /// The margin per contract for the period in the pnl currency of the symbol, `None` if only the brokerage knows the margin.
+    /// Symbols with no overnight margin use the intraday margin overnight.
+    pub fn margin_per_contract(&self, brokerage: Brokerage, symbol_name: &SymbolName, period: MarginPeriod) -> Option<Price> {
+        let intraday = || match self.margin_overrides.get(symbol_name) {
+            Some(margin) => Some(*margin),
+            None => match brokerage {
+                Brokerage::Rithmic(_) => get_rithmic_intraday_margin_in_usd(symbol_name),
+                _ => None,
+            }
+        };
+        match period {
+            MarginPeriod::Intraday => intraday(),
+            MarginPeriod::Overnight => match self.overnight_margins.get(symbol_name) {
+                Some(margin) => Some(*margin),
+                None => match brokerage {
+                    Brokerage::Rithmic(_) => get_overnight_margin(symbol_name).or_else(intraday),
+                    _ => intraday(),
+                }
+            }
+        }
+    }
+
+    /// The margin for the quantity at the time, in the pnl currency of the symbol.
+    pub fn margin_required(&self, brokerage: Brokerage, symbol_name: &SymbolName, quantity: Volume, time: DateTime<Utc>) -> Option<Price> {
+        self.margin_per_contract(brokerage, symbol_name, self.margin_period(time))
+            .map(|margin| margin * quantity)
+    }
+
+    /// The margin of the positions as a portfolio.
+    pub fn portfolio_margin<'a>(&self, positions: impl IntoIterator<Item = &'a MarginedPosition>) -> Price {
+        let netted = net_positions(positions);
+        let margin: Price = netted.values().map(|(quantity, per_contract)| quantity.abs() * per_contract).sum();
+        margin - self.credit_netted(netted)
+    }
+
+    /// The reduction from the sum of the margins of the positions, from netting contracts of the same symbol and the spread credits.
+    pub fn portfolio_credit<'a>(&self, positions: impl IntoIterator<Item = &'a MarginedPosition> + Clone) -> Price {
+        let standalone: Price = positions.clone().into_iter().map(|position| position.margin).sum();
+        standalone - self.portfolio_margin(positions)
+    }
+
+    fn credit_netted(&self, mut netted: BTreeMap<SymbolName, (Volume, Price)>) -> Price {
+        let mut credit = dec!(0);
+        for spread in &self.spread_credits {
+            let (quantity_a, margin_a) = netted.get(&spread.leg_a).cloned().unwrap_or_default();
+            let (quantity_b, margin_b) = netted.get(&spread.leg_b).cloned().unwrap_or_default();
+            if quantity_a.is_sign_positive() == quantity_b.is_sign_positive() || spread.quantity_a <= dec!(0) || spread.quantity_b <= dec!(0) {
+                continue;
+            }
+            let spreads = (quantity_a.abs() / spread.quantity_a).min(quantity_b.abs() / spread.quantity_b).floor();
+            if spreads <= dec!(0) {
+                continue;
+            }
+            credit += spreads * (spread.quantity_a * margin_a + spread.quantity_b * margin_b) * spread.credit_rate;
+            // The contracts used by this spread can not be used by another spread.
+            netted.insert(spread.leg_a.clone(), (reduce_towards_zero(quantity_a, spreads * spread.quantity_a), margin_a));
+            netted.insert(spread.leg_b.clone(), (reduce_towards_zero(quantity_b, spreads * spread.quantity_b), margin_b));
+        }
+        credit
+    }
+}
+
+#[derive(Deserialize)]
+struct MarginFile {
+    #[serde(default)]
+    brokerages: HashMap<String, MarginEngineFile>,
+}
+
+#[derive(Deserialize)]
+struct MarginEngineFile {
+    intraday_session: Option<MarginSessionFile>,
+    #[serde(default)]
+    intraday_margins: HashMap<SymbolName, Price>,
+    #[serde(default)]
+    overnight_margins: HashMap<SymbolName, Price>,
+    #[serde(default)]
+    spread_credits: Vec<SpreadCredit>,
+}
+
+#[derive(Deserialize)]
+struct MarginSessionFile {
+    time_zone: String,
+    start: String,
+    end: String,
+    days: Option<Vec<String>>,
+}
+
+impl MarginSessionFile {
+    fn schedule(&self) -> Result<TradingSchedule, String> {
+        let time_zone = Tz::from_str(&self.time_zone).map_err(|e| format!("Invalid time zone {}: {}", self.time_zone, e))?;
+        let time = |time: &str| NaiveTime::parse_from_str(time, "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+            .map_err(|e| format!("Invalid session time {}: {}", time, e));
+        let (start, end) = (time(&self.start)?, time(&self.end)?);
+        let window = match &self.days {
+            Some(days) => {
+                let days = days.iter()
+                    .map(|day| Weekday::from_str(day).map_err(|_| format!("Invalid session day: {}", day)))
+                    .collect::<Result<Vec<Weekday>, String>>()?;
+                TradingWindow::new(days, start, end)
+            }
+            None => TradingWindow::weekdays(start, end),
+        };
+        Ok(TradingSchedule::new(time_zone, vec![window]))
+    }
+}
+
+/// Parses the margin file and sets the margin engine of each brokerage in it, returning the brokerages which were set.
+/// The spread credits in the file are added to the default credits of the brokerage, see `MarginEngine::default_for()`.
+///
+/// # File Format
+/// The brokerages are named as they are displayed, eg. `"Rithmic Apex"`, `"Oanda"`. Margins are per contract in the pnl currency of the symbol.
+/// ```toml
+/// [brokerages."Rithmic Apex"]
+/// # The intraday margins apply inside the session, the overnight margins outside it. The days default to Monday to Friday.
+/// intraday_session = { time_zone = "America/Chicago", start = "08:30", end = "15:45", days = ["Mon", "Tue", "Wed", "Thu", "Fri"] }
+/// intraday_margins = { MNQ = 100, MES = 50 }
+/// overnight_margins = { MNQ = 2100, MES = 1500 }
+///
+/// [[brokerages."Rithmic Apex".spread_credits]]
+/// leg_a = "ES"
+/// quantity_a = 1
+/// leg_b = "NQ"
+/// quantity_b = 1
+/// credit_rate = 0.5
+/// ```
+pub fn load_margin_engines(path: &Path) -> Result<Vec<Brokerage>, FundForgeError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to read margin file {}: {}", path.display(), e)))?;
+    let engines = parse_margin_engines(&content)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse margin file {}: {}", path.display(), e)))?;
+    let brokerages = engines.iter().map(|(brokerage, _)| *brokerage).collect();
+    for (brokerage, engine) in engines {
+        set_margin_engine(brokerage, engine);
+    }
+    Ok(brokerages)
+}
+
+fn parse_margin_engines(content: &str) -> Result<Vec<(Brokerage, MarginEngine)>, String> {
+    let file = toml::from_str::<MarginFile>(content).map_err(|e| e.to_string())?;
+    let mut engines = vec![];
+    for (name, section) in file.brokerages {
+        let brokerage = Brokerage::from_str(&name).map_err(|e| format!("{:?}", e))?;
+        let mut engine = MarginEngine::default_for(brokerage);
+        engine.spread_credits.extend(section.spread_credits);
+        engine.margin_overrides = section.intraday_margins;
+        engine.overnight_margins = section.overnight_margins;
+        engine.intraday_schedule = match section.intraday_session {
+            Some(session) => Some(session.schedule()?),
+            None => None,
+        };
+        engines.push((brokerage, engine));
+    }
+    Ok(engines)
+}
+
+fn reduce_towards_zero(quantity: Volume, used: Volume) -> Volume {
+    match quantity > dec!(0) {
+        true => quantity - used,
+        false => quantity + used
+    }
+}
+
+/// The net quantity and the margin per contract of each symbol name.
+fn net_positions<'a>(positions: impl IntoIterator<Item = &'a MarginedPosition>) -> BTreeMap<SymbolName, (Volume, Price)> {
+    let mut totals: BTreeMap<SymbolName, (Volume, Volume, Price)> = BTreeMap::new();
+    for position in positions {
+        let (net, gross, margin) = totals.entry(position.symbol_name.clone()).or_default();
+        *net += position.quantity;
+        *gross += position.quantity.abs();
+        *margin += position.margin;
+    }
+    totals.into_iter()
+        .filter(|(_, (_, gross, _))| *gross > dec!(0))
+        .map(|(symbol_name, (net, gross, margin))| (symbol_name, (net, margin / gross)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standardized_types::broker_enum::Brokerage::Rithmic;
+    use crate::apis::rithmic::rithmic_systems::RithmicSystem;
+
+    #[test]
+    fn test_portfolio_margin_credits() {
+        let engine = MarginEngine::default_for(Rithmic(RithmicSystem::Apex));
+        let position = |symbol_name: &str, quantity: Volume, per_contract: Price| MarginedPosition { symbol_name: symbol_name.to_string(), quantity, margin: quantity.abs() * per_contract };
+
+        // 12 MES long against 1 ES short leaves 2 MES margined.
+        let positions = vec![position("MES", dec!(12), dec!(50)), position("ES", dec!(-1), dec!(500))];
+        assert_eq!(engine.portfolio_margin(&positions), dec!(100));
+        assert_eq!(engine.portfolio_credit(&positions), dec!(1000));
+
+        // Contracts of the same symbol net, same side legs get no credit.
+        let positions = vec![position("MES", dec!(3), dec!(50)), position("MES", dec!(-1), dec!(50)), position("ES", dec!(1), dec!(500))];
+        assert_eq!(engine.portfolio_margin(&positions), dec!(600));
+        assert_eq!(MarginEngine::default().portfolio_margin(&positions), dec!(600));
+    }
+
+    #[test]
+    fn test_margin_schedule_from_file() {
+        let content = r#"
+            [brokerages."Rithmic Apex"]
+            intraday_session = { time_zone = "America/Chicago", start = "08:30", end = "15:45" }
+            intraday_margins = { MNQ = 100 }
+            overnight_margins = { MNQ = 2100 }
+
+            [[brokerages."Rithmic Apex".spread_credits]]
+            leg_a = "ES"
+            quantity_a = 1
+            leg_b = "NQ"
+            quantity_b = 1
+            credit_rate = 0.5
+        "#;
+        let engines = parse_margin_engines(content).unwrap();
+        let (brokerage, engine) = engines[0].clone();
+        assert_eq!(brokerage, Rithmic(RithmicSystem::Apex));
+        assert_eq!(engine.spread_credits.last(), Some(&SpreadCredit::new("ES", dec!(1), "NQ", dec!(1), dec!(0.5))));
+
+        // 10:00 and 17:00 Chicago time on a Tuesday.
+        let intraday = DateTime::<Utc>::from_str("2024-06-04T15:00:00Z").unwrap();
+        let overnight = DateTime::<Utc>::from_str("2024-06-04T22:00:00Z").unwrap();
+        assert_eq!(engine.margin_period(intraday), MarginPeriod::Intraday);
+        assert_eq!(engine.margin_period(overnight), MarginPeriod::Overnight);
+        assert_eq!(engine.margin_required(brokerage, &"MNQ".to_string(), dec!(2), intraday), Some(dec!(200)));
+        assert_eq!(engine.margin_required(brokerage, &"MNQ".to_string(), dec!(2), overnight), Some(dec!(4200)));
+        assert_eq!(engine.margin_required(Brokerage::Test, &"MNQ".to_string(), dec!(2), overnight), Some(dec!(4200)));
+        assert_eq!(MarginEngine::default().margin_required(Brokerage::Test, &"MNQ".to_string(), dec!(2), overnight), None);
+
+        assert!(parse_margin_engines("[brokerages.\"Unknown\"]").is_err());
+    }
+}
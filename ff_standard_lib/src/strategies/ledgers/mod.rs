@@ -1,3 +1,15 @@
 pub mod ledger;
 pub mod ledger_service;
+pub mod journal;
+pub mod tag_view;
+pub mod statement_import;
+pub mod margin_engine;
+pub mod financing;
+pub mod hedging;
+pub mod lot_accounting;
+pub mod drawdown_guard;
+pub mod campaigns;
+pub mod ledger_snapshot;
+pub mod trade_formats;
+pub mod account_statement;
 pub(crate) mod historical_ledger;
\ No newline at end of file
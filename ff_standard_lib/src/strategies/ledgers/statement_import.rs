@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::product_maps::rithmic::maps::find_base_symbol;
+use crate::standardized_types::accounts::AccountId;
+use crate::standardized_types::enums::{OrderSide, PositionSide};
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::orders::OrderId;
+use crate::standardized_types::position::{Position, PositionUpdateEvent};
+use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
+use crate::strategies::ledgers::ledger::Ledger;
+
+/// The broker statement formats which can be imported into a ledger, see `strategy.import_statement()`.
+///
+/// # Rithmic
+/// The order history exported from R|Trader Pro, rows which are not filled are skipped.
+/// Required columns: `Symbol`, `Buy/Sell`, `Qty Filled`, `Avg Fill Price`, `Update Time`.
+/// Optional columns: `Account`, `Status`, `Order Number`.
+///
+/// # Oanda
+/// The transaction history exported from the Oanda hub, only `ORDER_FILL` transactions are imported.
+/// Required columns: `TRANSACTION TYPE`, `INSTRUMENT`, `UNITS` (negative for sells), `PRICE`, `TIME`.
+/// Optional columns: `ACCOUNT ID`, `TRANSACTION ID`.
+///
+/// Column names are not case sensitive, times without an offset are read in the strategy time zone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatementFormat {
+    Rithmic,
+    Oanda,
+}
+
+/// A single fill read from a broker statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementFill {
+    pub symbol_name: SymbolName,
+    pub symbol_code: SymbolCode,
+    pub side: OrderSide,
+    pub quantity: Volume,
+    pub price: Price,
+    pub time: DateTime<Utc>,
+    pub order_id: OrderId,
+}
+
+/// The positions rebuilt from a statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementImport {
+    pub fills: usize,
+    pub closed_positions: usize,
+    pub open_positions: usize,
+    pub booked_pnl: Decimal,
+}
+
+/// Reads the fills for the account from a statement file, sorted by time.
+pub fn parse_statement(path: &Path, format: StatementFormat, account_id: &AccountId, time_zone: &Tz) -> Result<Vec<StatementFill>, FundForgeError> {
+    let file = File::open(path)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to open statement {}: {}", path.display(), e)))?;
+    let mut fills = match format {
+        StatementFormat::Rithmic => parse_rithmic(file, account_id, time_zone),
+        StatementFormat::Oanda => parse_oanda(file, account_id, time_zone),
+    }.map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse statement {}: {}", path.display(), e)))?;
+    fills.sort_by_key(|fill| fill.time);
+    Ok(fills)
+}
+
+/// The statement columns by their upper case header names.
+struct Columns(HashMap<String, usize>);
+
+impl Columns {
+    fn new(headers: &csv::StringRecord) -> Self {
+        Self(headers.iter().enumerate().map(|(index, header)| (header.trim().to_uppercase(), index)).collect())
+    }
+
+    fn get<'a>(&self, record: &'a csv::StringRecord, name: &str) -> Option<&'a str> {
+        self.0.get(name).and_then(|index| record.get(*index)).map(|value| value.trim())
+    }
+
+    fn required<'a>(&self, record: &'a csv::StringRecord, name: &str) -> Result<&'a str, String> {
+        self.get(record, name).ok_or(format!("Missing column: {}", name))
+    }
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal, String> {
+    value.replace(',', "").parse::<Decimal>().map_err(|e| format!("Invalid number {}: {}", value, e))
+}
+
+fn parse_time(value: &str, time_zone: &Tz) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M:%S", "%m/%d/%Y %H:%M:%S%.f", "%m/%d/%Y %H:%M:%S", "%m/%d/%Y %I:%M:%S %p"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(value, format) {
+            return time_zone.from_local_datetime(&naive).earliest()
+                .map(|time| time.with_timezone(&Utc))
+                .ok_or(format!("Invalid local time {} in {}", value, time_zone));
+        }
+    }
+    Err(format!("Invalid time: {}", value))
+}
+
+fn parse_rithmic<R: Read>(reader: R, account_id: &AccountId, time_zone: &Tz) -> Result<Vec<StatementFill>, String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let columns = Columns::new(reader.headers().map_err(|e| e.to_string())?);
+    let mut fills = vec![];
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| e.to_string())?;
+        if columns.get(&record, "ACCOUNT").is_some_and(|account| account != account_id) {
+            continue;
+        }
+        if columns.get(&record, "STATUS").is_some_and(|status| !status.to_lowercase().contains("fill") && !status.to_lowercase().contains("complete")) {
+            continue;
+        }
+        let quantity = match columns.required(&record, "QTY FILLED")? {
+            "" => continue,
+            quantity => parse_decimal(quantity)?
+        };
+        if quantity <= dec!(0) {
+            continue;
+        }
+        let side = match columns.required(&record, "BUY/SELL")?.to_uppercase().as_str() {
+            "B" | "BUY" => OrderSide::Buy,
+            "S" | "SELL" => OrderSide::Sell,
+            side => return Err(format!("Invalid side on row {}: {}", row + 1, side))
+        };
+        let symbol_code = columns.required(&record, "SYMBOL")?.to_string();
+        let symbol_name = find_base_symbol(&symbol_code)
+            .ok_or(format!("Unknown futures symbol on row {}: {}", row + 1, symbol_code))?;
+        fills.push(StatementFill {
+            symbol_name,
+            symbol_code,
+            side,
+            quantity,
+            price: parse_decimal(columns.required(&record, "AVG FILL PRICE")?)?,
+            time: parse_time(columns.required(&record, "UPDATE TIME")?, time_zone)?,
+            order_id: columns.get(&record, "ORDER NUMBER").map(|id| id.to_string()).unwrap_or(format!("statement-{}", row + 1)),
+        });
+    }
+    Ok(fills)
+}
+
+fn parse_oanda<R: Read>(reader: R, account_id: &AccountId, time_zone: &Tz) -> Result<Vec<StatementFill>, String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let columns = Columns::new(reader.headers().map_err(|e| e.to_string())?);
+    let mut fills = vec![];
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| e.to_string())?;
+        if columns.get(&record, "ACCOUNT ID").is_some_and(|account| account != account_id) {
+            continue;
+        }
+        if columns.required(&record, "TRANSACTION TYPE")? != "ORDER_FILL" {
+            continue;
+        }
+        let units = parse_decimal(columns.required(&record, "UNITS")?)?;
+        if units == dec!(0) {
+            continue;
+        }
+        // Oanda instruments are formatted "EUR_USD", the symbol names are "EUR-USD".
+        let symbol_name = columns.required(&record, "INSTRUMENT")?.replace(['_', '/'], "-");
+        fills.push(StatementFill {
+            symbol_code: symbol_name.clone(),
+            symbol_name,
+            side: if units > dec!(0) { OrderSide::Buy } else { OrderSide::Sell },
+            quantity: units.abs(),
+            price: parse_decimal(columns.required(&record, "PRICE")?)?,
+            time: parse_time(columns.required(&record, "TIME")?, time_zone)?,
+            order_id: columns.get(&record, "TRANSACTION ID").map(|id| id.to_string()).unwrap_or(format!("statement-{}", row + 1)),
+        });
+    }
+    Ok(fills)
+}
+
+impl Ledger {
+    /// Replays the statement fills to rebuild the closed positions and the trades into the ledger statistics.
+    /// The booked pnl is calculated from the fill prices in the same way as the paper ledger, so it will not include broker fees or financing which are not in the commission maps.
+    /// Imported pnl is not added to the cash balances, which are already known from the broker or the starting cash.
+    ///
+    /// Positions still open at the end of the statement are added to the ledger, unless the ledger already has a position for the symbol code from the broker,
+    /// in which case the broker position is kept.
+    pub(crate) async fn import_statement_fills(&mut self, fills: Vec<StatementFill>) -> StatementImport {
+        let mut open: HashMap<SymbolCode, Position> = HashMap::new();
+        let mut summary = StatementImport { fills: fills.len(), closed_positions: 0, open_positions: 0, booked_pnl: dec!(0) };
+        for fill in fills {
+            let mut remaining_quantity = fill.quantity;
+            if let Some(mut position) = open.remove(&fill.symbol_code) {
+                let is_reducing = (position.side == PositionSide::Long && fill.side == OrderSide::Sell)
+                    || (position.side == PositionSide::Short && fill.side == OrderSide::Buy);
+                if is_reducing {
+                    let quantity = remaining_quantity.min(position.quantity_open);
+                    remaining_quantity -= quantity;
+                    let exchange_rate = self.get_exchange_multiplier(position.symbol_info.pnl_currency);
+                    let event = position.reduce_position_size(fill.price, quantity, fill.order_id.clone(), self.currency, exchange_rate, fill.time, "Statement".to_string()).await;
+                    let booked_pnl = match event {
+                        PositionUpdateEvent::PositionReduced { booked_pnl, .. } | PositionUpdateEvent::PositionClosed { booked_pnl, .. } => booked_pnl,
+                        _ => dec!(0)
+                    };
+                    self.symbol_closed_pnl
+                        .entry(fill.symbol_code.clone())
+                        .and_modify(|pnl| *pnl += booked_pnl)
+                        .or_insert(booked_pnl);
+                    self.total_booked_pnl += booked_pnl;
+                    summary.booked_pnl += booked_pnl;
+                    if position.is_closed {
+                        summary.closed_positions += 1;
+                        self.record_closed_position(fill.symbol_code.clone(), position);
+                    } else {
+                        open.insert(fill.symbol_code.clone(), position);
+                    }
+                } else {
+                    position.add_to_position(self.mode, self.is_simulating_pnl, fill.order_id.clone(), self.currency, fill.price, remaining_quantity, fill.time, "Statement".to_string()).await;
+                    open.insert(fill.symbol_code.clone(), position);
+                    remaining_quantity = dec!(0);
+                }
+            }
+            if remaining_quantity > dec!(0) {
+                let info = self.symbol_info(self.account.brokerage, &fill.symbol_name).await;
+                let side = match fill.side {
+                    OrderSide::Buy => PositionSide::Long,
+                    OrderSide::Sell => PositionSide::Short,
+                };
+                let position = Position::new(
+                    fill.symbol_name.clone(),
+                    fill.symbol_code.clone(),
+                    fill.order_id,
+                    self.account.clone(),
+                    side,
+                    remaining_quantity,
+                    fill.price,
+                    self.generate_id(side),
+                    info.clone(),
+                    self.get_exchange_multiplier(info.pnl_currency),
+                    "Statement".to_string(),
+                    fill.time,
+                    self.position_calculation_mode.clone()
+                );
+                open.insert(fill.symbol_code, position);
+            }
+        }
+
+        for (symbol_code, position) in open {
+            if let Some(existing) = self.positions.get(&symbol_code) {
+                if existing.side != position.side || existing.quantity_open != position.quantity_open {
+                    tracing::warn!("Statement Import: {} statement position {} {} does not match the broker position {} {}, keeping the broker position",
+                              symbol_code, position.side, position.quantity_open, existing.side, existing.quantity_open);
+                }
+                continue;
+            }
+            if position.symbol_name != symbol_code {
+                let mut codes = self.symbol_code_map.entry(position.symbol_name.clone()).or_insert(vec![]);
+                if !codes.contains(&symbol_code) {
+                    codes.push(symbol_code.clone());
+                }
+            }
+            summary.open_positions += 1;
+            let key = match self.is_hedging {
+                true => position.position_id.clone(),
+                false => symbol_code,
+            };
+            self.positions.insert(key, position);
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_statements() {
+        let rithmic = "Account,Status,Buy/Sell,Qty Filled,Symbol,Avg Fill Price,Update Time,Order Number\n\
+            APEX-1,Filled,B,2,MNQZ4,20100.25,2024-11-04 09:30:01,11\n\
+            APEX-1,Cancelled,S,,MNQZ4,,2024-11-04 09:31:00,12\n\
+            APEX-2,Filled,S,1,MNQZ4,20110.00,2024-11-04 09:32:00,13\n\
+            APEX-1,Filled,S,2,MNQZ4,20120.50,2024-11-04 09:35:00,14\n";
+        let fills = parse_rithmic(rithmic.as_bytes(), &"APEX-1".to_string(), &chrono_tz::America::Chicago).unwrap();
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].symbol_name, "MNQ");
+        assert_eq!(fills[0].side, OrderSide::Buy);
+        assert_eq!(fills[0].time, Utc.with_ymd_and_hms(2024, 11, 4, 15, 30, 1).unwrap());
+        assert_eq!(fills[1].price, dec!(20120.50));
+
+        let oanda = "TRANSACTION ID,ACCOUNT ID,TRANSACTION TYPE,INSTRUMENT,UNITS,PRICE,TIME\n\
+            101,001-1,MARKET_ORDER,EUR_USD,\"-1,000\",,2024-11-04T09:30:00Z\n\
+            102,001-1,ORDER_FILL,EUR_USD,\"-1,000\",1.08512,2024-11-04T09:30:00.5Z\n";
+        let fills = parse_oanda(oanda.as_bytes(), &"001-1".to_string(), &chrono_tz::UTC).unwrap();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].symbol_name, "EUR-USD");
+        assert_eq!(fills[0].side, OrderSide::Sell);
+        assert_eq!(fills[0].quantity, dec!(1000));
+        assert_eq!(fills[0].order_id, "102");
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use rust_decimal_macros::dec;
+use crate::standardized_types::enums::PositionSide;
+use crate::standardized_types::new_types::{Price, Volume};
+use crate::standardized_types::position::Position;
+use crate::standardized_types::subscriptions::SymbolCode;
+use crate::strategies::ledgers::ledger::Ledger;
+
+/// The ledger state of the positions opened by orders with a given tag.
+/// Positions are attributed to the tag of the order which opened them, so tag each signal family with its own prefix, eg. "momo:breakout", "momo:pullback", "mr:fade".
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TagSummary {
+    /// The signed open quantity for each symbol code, long positions are positive and short positions are negative.
+    pub open_quantity: BTreeMap<SymbolCode, Volume>,
+    pub open_pnl: Price,
+    /// Includes pnl booked by open positions that have been partially reduced.
+    pub booked_pnl: Price,
+    pub open_positions: usize,
+    pub closed_positions: usize,
+    pub winning_positions: usize,
+    pub losing_positions: usize,
+}
+
+impl TagSummary {
+    pub fn total_pnl(&self) -> Price {
+        self.open_pnl + self.booked_pnl
+    }
+
+    /// The percentage of closed positions with a positive booked pnl.
+    pub fn win_rate(&self) -> Price {
+        if self.closed_positions == 0 {
+            return dec!(0)
+        }
+        (Price::from(self.winning_positions) / Price::from(self.closed_positions) * dec!(100)).round_dp(2)
+    }
+
+    /// Combines another summary into this one, used to aggregate all tags matching a prefix.
+    pub fn merge(&mut self, other: &TagSummary) {
+        for (symbol_code, quantity) in &other.open_quantity {
+            *self.open_quantity.entry(symbol_code.clone()).or_insert(dec!(0)) += quantity;
+        }
+        self.open_pnl += other.open_pnl;
+        self.booked_pnl += other.booked_pnl;
+        self.open_positions += other.open_positions;
+        self.closed_positions += other.closed_positions;
+        self.winning_positions += other.winning_positions;
+        self.losing_positions += other.losing_positions;
+    }
+
+    fn add_open(&mut self, position: &Position) {
+        let quantity = match position.side {
+            PositionSide::Long => position.quantity_open,
+            PositionSide::Short => -position.quantity_open,
+            PositionSide::Flat => dec!(0),
+        };
+        *self.open_quantity.entry(position.symbol_code.clone()).or_insert(dec!(0)) += quantity;
+        self.open_pnl += position.open_pnl;
+        self.booked_pnl += position.booked_pnl;
+        self.open_positions += 1;
+    }
+
+    fn add_closed(&mut self, position: &Position) {
+        self.booked_pnl += position.booked_pnl;
+        self.closed_positions += 1;
+        if position.booked_pnl > dec!(0) {
+            self.winning_positions += 1;
+        } else if position.booked_pnl < dec!(0) {
+            self.losing_positions += 1;
+        }
+    }
+}
+
+/// A tag matches if it starts with the prefix, a trailing `*` is ignored so "momo:*" and "momo:" are the same.
+pub fn tag_matches(tag: &str, tag_prefix: &str) -> bool {
+    tag.starts_with(tag_prefix.trim_end_matches('*'))
+}
+
+impl Ledger {
+    /// Summaries of the open and closed positions for each tag starting with the prefix, keyed by the full tag.
+    pub fn tag_summaries(&self, tag_prefix: &str) -> BTreeMap<String, TagSummary> {
+        let mut summaries: BTreeMap<String, TagSummary> = BTreeMap::new();
+        for position in self.positions.iter() {
+            if !position.is_closed && tag_matches(&position.tag, tag_prefix) {
+                summaries.entry(position.tag.clone()).or_default().add_open(position.value());
+            }
+        }
+        for closed in self.positions_closed.iter() {
+            for position in closed.value() {
+                if tag_matches(&position.tag, tag_prefix) {
+                    summaries.entry(position.tag.clone()).or_default().add_closed(position);
+                }
+            }
+        }
+        summaries
+    }
+
+    /// A single summary of all the positions with a tag starting with the prefix.
+    pub fn tag_summary(&self, tag_prefix: &str) -> TagSummary {
+        let mut summary = TagSummary::default();
+        for tag_summary in self.tag_summaries(tag_prefix).values() {
+            summary.merge(tag_summary);
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_matches() {
+        assert!(tag_matches("momo:breakout", "momo:*"));
+        assert!(tag_matches("momo:breakout", "momo:"));
+        assert!(tag_matches("momo:breakout", "*"));
+        assert!(!tag_matches("mr:fade", "momo:*"));
+    }
+
+    #[test]
+    fn test_merge_summaries() {
+        let mut long = TagSummary { open_pnl: dec!(50), booked_pnl: dec!(100), open_positions: 1, closed_positions: 2, winning_positions: 2, ..Default::default() };
+        long.open_quantity.insert("MNQZ4".to_string(), dec!(2));
+        let mut short = TagSummary { booked_pnl: dec!(-40), closed_positions: 2, winning_positions: 1, losing_positions: 1, ..Default::default() };
+        short.open_quantity.insert("MNQZ4".to_string(), dec!(-3));
+
+        let mut total = TagSummary::default();
+        total.merge(&long);
+        total.merge(&short);
+        assert_eq!(total.open_quantity.get("MNQZ4"), Some(&dec!(-1)));
+        assert_eq!(total.total_pnl(), dec!(110));
+        assert_eq!(total.win_rate(), dec!(75));
+    }
+}
@@ -0,0 +1,305 @@
+use std::fs::{create_dir_all, File};
+use std::io::Write;
+use chrono::{DateTime, Utc};
+use csv::Writer;
+use rust_decimal::Decimal;
+use serde_derive::Serialize;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::accounts::{Account, Currency};
+use crate::standardized_types::position::TradeResult;
+use crate::strategies::ledgers::ledger::{ClosedTrade, Ledger, TradeExport};
+
+/// The file formats the trades of a ledger can be exported to, see `strategy.export_trades()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TradeExportFormat {
+    /// The fund forge csv written by `strategy.export_trades_to_csv()`, one `TradeExport` per row.
+    FundForgeCsv,
+    /// The columns of the NinjaTrader trade performance grid, so results can be compared with a NinjaTrader Strategy Analyzer export.
+    NinjaTraderCsv,
+    /// The columns of the QuantConnect backtest trade list.
+    QuantConnectCsv,
+    /// A json document with the account and the trades, times are RFC 3339 and prices, quantities and pnl are exact decimal strings.
+    Json,
+    /// The `TradeExport` records as json with exact decimals, reloaded with `BacktestRun::from_trades_json()`.
+    RoundTrip,
+}
+
+impl TradeExportFormat {
+    fn file_label(&self) -> &'static str {
+        match self {
+            TradeExportFormat::FundForgeCsv => "TradeResults",
+            TradeExportFormat::NinjaTraderCsv => "NinjaTraderTrades",
+            TradeExportFormat::QuantConnectCsv => "QuantConnectTrades",
+            TradeExportFormat::Json => "Trades",
+            TradeExportFormat::RoundTrip => "RoundTripTrades",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            TradeExportFormat::FundForgeCsv | TradeExportFormat::NinjaTraderCsv | TradeExportFormat::QuantConnectCsv => "csv",
+            TradeExportFormat::Json | TradeExportFormat::RoundTrip => "json",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct NinjaTraderTrade {
+    #[serde(rename = "Trade number")]
+    trade_number: usize,
+    #[serde(rename = "Instrument")]
+    instrument: String,
+    #[serde(rename = "Account")]
+    account: String,
+    #[serde(rename = "Market pos.")]
+    market_position: String,
+    #[serde(rename = "Qty")]
+    quantity: Decimal,
+    #[serde(rename = "Entry price")]
+    entry_price: Decimal,
+    #[serde(rename = "Exit price")]
+    exit_price: Decimal,
+    #[serde(rename = "Entry time")]
+    entry_time: String,
+    #[serde(rename = "Exit time")]
+    exit_time: String,
+    #[serde(rename = "Entry name")]
+    entry_name: String,
+    #[serde(rename = "Profit")]
+    profit: Decimal,
+    #[serde(rename = "Cum. net profit")]
+    cumulative_profit: Decimal,
+    #[serde(rename = "Commission")]
+    commission: Decimal,
+}
+
+#[derive(Serialize)]
+struct QuantConnectTrade {
+    #[serde(rename = "Entry Time")]
+    entry_time: String,
+    #[serde(rename = "Symbols")]
+    symbol: String,
+    #[serde(rename = "Direction")]
+    direction: String,
+    #[serde(rename = "Entry Price")]
+    entry_price: Decimal,
+    #[serde(rename = "Exit Time")]
+    exit_time: String,
+    #[serde(rename = "Exit Price")]
+    exit_price: Decimal,
+    #[serde(rename = "Quantity")]
+    quantity: Decimal,
+    #[serde(rename = "P&L")]
+    pnl: Decimal,
+    #[serde(rename = "Fees")]
+    fees: Decimal,
+    #[serde(rename = "Is Win")]
+    is_win: u8,
+    #[serde(rename = "Duration")]
+    duration: String,
+}
+
+#[derive(Serialize)]
+struct JsonTradeDocument {
+    brokerage: String,
+    account_id: String,
+    currency: Currency,
+    exported_at: String,
+    trades: Vec<JsonTrade>,
+}
+
+#[derive(Serialize)]
+struct JsonTrade {
+    symbol_name: String,
+    symbol_code: String,
+    position_id: String,
+    side: String,
+    entry_time: String,
+    exit_time: String,
+    entry_price: Decimal,
+    entry_quantity: Decimal,
+    exit_price: Decimal,
+    exit_quantity: Decimal,
+    pnl: Decimal,
+    commissions: Decimal,
+    tag: String,
+    result: String,
+}
+
+fn write_csv<T: serde::Serialize>(rows: Vec<T>) -> Result<String, FundForgeError> {
+    let mut writer = Writer::from_writer(vec![]);
+    for row in rows {
+        writer.serialize(row)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to write trade: {}", e)))?;
+    }
+    let bytes = writer.into_inner()
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to flush trades: {}", e)))?;
+    String::from_utf8(bytes).map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Invalid trade csv: {}", e)))
+}
+
+/// The trades as a NinjaTrader trade performance csv, the trades must be in exit order for the cumulative profit.
+pub(crate) fn ninjatrader_csv(account: &Account, trades: &[ClosedTrade]) -> Result<String, FundForgeError> {
+    let mut cumulative_profit = Decimal::ZERO;
+    let rows = trades.iter().enumerate().map(|(index, trade)| {
+        cumulative_profit += trade.pnl;
+        NinjaTraderTrade {
+            trade_number: index + 1,
+            instrument: trade.symbol_code.clone(),
+            account: account.account_id.clone(),
+            market_position: trade.side.to_string(),
+            quantity: trade.exit_quantity,
+            entry_price: trade.entry_price,
+            exit_price: trade.exit_price,
+            entry_time: trade.entry_time.format("%-m/%-d/%Y %-I:%M:%S %p").to_string(),
+            exit_time: trade.exit_time.format("%-m/%-d/%Y %-I:%M:%S %p").to_string(),
+            entry_name: trade.tag.clone(),
+            profit: trade.pnl,
+            cumulative_profit,
+            commission: trade.commissions,
+        }
+    }).collect();
+    write_csv::<NinjaTraderTrade>(rows)
+}
+
+/// The trades as a QuantConnect trade list csv, durations are written as `d.hh:mm:ss` like a .NET TimeSpan.
+pub(crate) fn quantconnect_csv(trades: &[ClosedTrade]) -> Result<String, FundForgeError> {
+    let rows = trades.iter().map(|trade| {
+        let duration = trade.exit_time - trade.entry_time;
+        let seconds = duration.num_seconds();
+        QuantConnectTrade {
+            entry_time: trade.entry_time.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            symbol: trade.symbol_code.clone(),
+            direction: trade.side.to_string(),
+            entry_price: trade.entry_price,
+            exit_time: trade.exit_time.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            exit_price: trade.exit_price,
+            quantity: trade.exit_quantity,
+            pnl: trade.pnl,
+            fees: trade.commissions,
+            is_win: (trade.result == TradeResult::Win) as u8,
+            duration: format!("{}.{:02}:{:02}:{:02}", seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60, seconds % 60),
+        }
+    }).collect();
+    write_csv::<QuantConnectTrade>(rows)
+}
+
+/// The trades as the generic json document.
+pub(crate) fn trades_json(account: &Account, currency: Currency, trades: &[ClosedTrade], exported_at: DateTime<Utc>) -> Result<String, FundForgeError> {
+    let document = JsonTradeDocument {
+        brokerage: account.brokerage.to_string(),
+        account_id: account.account_id.clone(),
+        currency,
+        exported_at: exported_at.to_rfc3339(),
+        trades: trades.iter().map(|trade| JsonTrade {
+            symbol_name: trade.symbol_name.clone(),
+            symbol_code: trade.symbol_code.clone(),
+            position_id: trade.position_id.clone(),
+            side: trade.side.to_string(),
+            entry_time: trade.entry_time.to_rfc3339(),
+            exit_time: trade.exit_time.to_rfc3339(),
+            entry_price: trade.entry_price,
+            entry_quantity: trade.entry_quantity,
+            exit_price: trade.exit_price,
+            exit_quantity: trade.exit_quantity,
+            pnl: trade.pnl,
+            commissions: trade.commissions,
+            tag: trade.tag.clone(),
+            result: trade.result.to_string(),
+        }).collect(),
+    };
+    serde_json::to_string_pretty(&document).map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to serialize trades: {}", e)))
+}
+
+/// The trades in the round trip format.
+pub(crate) fn round_trip_json(trades: &[TradeExport]) -> Result<String, FundForgeError> {
+    serde_json::to_string_pretty(trades).map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to serialize trades: {}", e)))
+}
+
+impl Ledger {
+    /// Writes the completed trades of the closed positions to a file in the folder in the format and returns the file path.
+    pub fn export_trades(&self, folder: &str, format: TradeExportFormat) -> Result<String, FundForgeError> {
+        create_dir_all(folder)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create directory {}: {}", folder, e)))?;
+
+        let now = Utc::now();
+        let date = now.format("%Y%m%d_%H%M").to_string();
+        let brokerage = self.account.brokerage.to_string();
+        let file_name = format!("{}/{:?}_{}_{}_{}_{}.{}", folder, self.mode, format.file_label(), brokerage, self.account.account_id, date, format.extension());
+
+        let contents = match format {
+            TradeExportFormat::FundForgeCsv => write_csv(self.trade_exports())?,
+            TradeExportFormat::NinjaTraderCsv => ninjatrader_csv(&self.account, &self.closed_trades())?,
+            TradeExportFormat::QuantConnectCsv => quantconnect_csv(&self.closed_trades())?,
+            TradeExportFormat::Json => trades_json(&self.account, self.currency, &self.closed_trades(), now)?,
+            TradeExportFormat::RoundTrip => round_trip_json(&self.trade_exports())?,
+        };
+
+        let mut file = File::create(&file_name)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create {}: {}", file_name, e)))?;
+        file.write_all(contents.as_bytes())
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to write {}: {}", file_name, e)))?;
+        Ok(file_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::broker_enum::Brokerage;
+    use crate::standardized_types::enums::PositionSide;
+
+    fn trades() -> Vec<ClosedTrade> {
+        let trade = |side: PositionSide, entry_hour: u32, pnl: Decimal, result: TradeResult| ClosedTrade {
+            symbol_name: "NQ".to_string(),
+            symbol_code: "NQZ4".to_string(),
+            position_id: format!("{}-1", side),
+            side,
+            entry_price: dec!(17500),
+            entry_quantity: dec!(2),
+            exit_price: dec!(17550),
+            exit_quantity: dec!(2),
+            entry_time: Utc.with_ymd_and_hms(2024, 1, 2, entry_hour, 0, 0).unwrap(),
+            exit_time: Utc.with_ymd_and_hms(2024, 1, 2, entry_hour + 1, 30, 5).unwrap(),
+            pnl,
+            commissions: dec!(4.2),
+            tag: "Enter".to_string(),
+            result,
+        };
+        vec![
+            trade(PositionSide::Long, 9, dec!(195.8), TradeResult::Win),
+            trade(PositionSide::Short, 14, dec!(-204.2), TradeResult::Loss),
+        ]
+    }
+
+    #[test]
+    fn test_ninjatrader_csv() {
+        let account = Account::new(Brokerage::Test, "Test_Account_1".to_string());
+        let csv = ninjatrader_csv(&account, &trades()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "Trade number,Instrument,Account,Market pos.,Qty,Entry price,Exit price,Entry time,Exit time,Entry name,Profit,Cum. net profit,Commission");
+        assert_eq!(lines[1], "1,NQZ4,Test_Account_1,Long,2,17500,17550,1/2/2024 9:00:00 AM,1/2/2024 10:30:05 AM,Enter,195.8,195.8,4.2");
+        assert_eq!(lines[2], "2,NQZ4,Test_Account_1,Short,2,17500,17550,1/2/2024 2:00:00 PM,1/2/2024 3:30:05 PM,Enter,-204.2,-8.4,4.2");
+    }
+
+    #[test]
+    fn test_quantconnect_csv() {
+        let csv = quantconnect_csv(&trades()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "Entry Time,Symbols,Direction,Entry Price,Exit Time,Exit Price,Quantity,P&L,Fees,Is Win,Duration");
+        assert_eq!(lines[1], "2024-01-02T09:00:00Z,NQZ4,Long,17500,2024-01-02T10:30:05Z,17550,2,195.8,4.2,1,0.01:30:05");
+    }
+
+    #[test]
+    fn test_trades_json() {
+        let account = Account::new(Brokerage::Test, "Test_Account_1".to_string());
+        let json = trades_json(&account, Currency::USD, &trades(), Utc.with_ymd_and_hms(2024, 1, 3, 0, 0, 0).unwrap()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["account_id"], "Test_Account_1");
+        assert_eq!(value["trades"].as_array().unwrap().len(), 2);
+        assert_eq!(value["trades"][1]["pnl"], "-204.2");
+        assert_eq!(value["trades"][0]["entry_price"], "17500");
+        assert_eq!(value["trades"][0]["entry_time"], "2024-01-02T09:00:00+00:00");
+    }
+}
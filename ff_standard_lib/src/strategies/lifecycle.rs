@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use crate::standardized_types::market_hours::TradingHours;
+use crate::strategies::ledgers::account_statement::AccountStatement;
+
+/// Code run by the engine at points in the life of the strategy, set with `strategy.set_lifecycle_hooks()`.
+///
+/// The hooks run on the engine tasks rather than in the strategy event loop, so they still run when the strategy code is busy or stuck,
+/// and the engine waits for each hook to return, so keep them short, eg. saving state, sending a notification or writing a report.
+/// The hooks have an empty default implementation.
+/// # Example
+/// ```rust,ignore
+/// struct Notifier;
+///
+/// #[async_trait]
+/// impl LifecycleHooks for Notifier {
+///     async fn on_shutdown(&self, reason: String) {
+///         println!("Strategy stopped: {}", reason);
+///     }
+/// }
+///
+/// strategy.set_lifecycle_hooks(Some(Arc::new(Notifier)));
+/// ```
+#[async_trait]
+pub trait LifecycleHooks: Send + Sync {
+    /// Called at each session close of the `SessionClose` set with `strategy.set_session_close()`, `time` is the session close.
+    async fn on_session_close(&self, _time: DateTime<Utc>) {}
+
+    /// Called once when the strategy shuts down, when a backtest completes or when `strategy.shutdown()` is called.
+    async fn on_shutdown(&self, _reason: String) {}
+
+    /// Called with each statement written by `strategy.set_account_statements()` and the path of its html file, eg. to email the statement.
+    async fn on_account_statement(&self, _statement: &AccountStatement, _path: &str) {}
+}
+
+/// The exchange session of the strategy, see `strategy.set_session_close()`.
+///
+/// A `ScheduleEvent::SessionClose` is sent and `LifecycleHooks::on_session_close()` is called at each session close of the `trading_hours`.
+/// If `flatten_at` is set, the open orders are cancelled and the accounts are flattened at that time of day in the time zone of the trading hours,
+/// on each day the market is open at that time, eg. 15:55 to avoid holding positions through the close.
+/// The flatten is sent by the engine, so it happens even if the strategy is not processing its events.
+#[derive(Clone, Debug)]
+pub struct SessionClose {
+    pub trading_hours: TradingHours,
+    pub flatten_at: Option<NaiveTime>,
+}
+
+impl SessionClose {
+    pub fn new(trading_hours: TradingHours) -> Self {
+        Self {
+            trading_hours,
+            flatten_at: None,
+        }
+    }
+
+    pub fn with_flatten_at(mut self, flatten_at: NaiveTime) -> Self {
+        self.flatten_at = Some(flatten_at);
+        self
+    }
+
+    /// The first time after the time at which the accounts are flattened, `None` if there is no `flatten_at` or the market is never open at it.
+    pub fn next_flatten(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let flatten_at = self.flatten_at?;
+        let timezone = self.trading_hours.timezone;
+        let date = time.with_timezone(&timezone).date_naive();
+        (0..=7).filter_map(|days_forward| timezone.from_local_datetime(&(date + Duration::days(days_forward)).and_time(flatten_at)).earliest())
+            .map(|flatten| flatten.to_utc())
+            .find(|flatten| *flatten > time && self.trading_hours.is_market_open(*flatten))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use crate::product_maps::rithmic::maps::CME_HOURS;
+
+    #[test]
+    fn test_session_close_times() {
+        let session_close = SessionClose::new(CME_HOURS).with_flatten_at(NaiveTime::from_hms_opt(15, 55, 0).unwrap());
+        let time = |time: &str| DateTime::<Utc>::from_str(time).unwrap();
+        // 16:00 Chicago time is 21:00 UTC in June.
+        assert_eq!(session_close.trading_hours.next_close(time("2024-06-05T15:00:00Z")), Some(time("2024-06-05T21:00:00Z")));
+        assert_eq!(session_close.next_flatten(time("2024-06-05T15:00:00Z")), Some(time("2024-06-05T20:55:00Z")));
+        // After the Friday close the next flatten is on Monday, the market is closed at 15:55 on the weekend.
+        assert_eq!(session_close.next_flatten(time("2024-06-07T21:00:00Z")), Some(time("2024-06-10T20:55:00Z")));
+        assert_eq!(session_close.trading_hours.next_close(time("2024-06-07T21:00:00Z")), Some(time("2024-06-10T21:00:00Z")));
+    }
+}
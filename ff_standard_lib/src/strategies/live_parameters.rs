@@ -0,0 +1,256 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use tokio::sync::mpsc;
+use toml::{Table, Value};
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::new_types::Volume;
+use crate::strategies::strategy_events::StrategyEvent;
+
+/// The parameters which can be changed while the strategy is running, any other keys in the file are ignored.
+pub const LIVE_PARAMETER_NAMES: [&str; 3] = ["max_order_quantity", "size_multiplier", "enabled_sessions"];
+
+/// Risk limits and parameters which can be updated at runtime by editing a TOML file, see `strategy.watch_parameters_file()`.
+///
+/// # File Format
+/// ```toml
+/// # Orders placed with enter_long() and enter_short() are capped at this quantity, remove the line for no limit.
+/// max_order_quantity = 5
+/// # Order quantities placed with enter_long() and enter_short() are multiplied by this value.
+/// size_multiplier = 0.5
+/// # The sessions the strategy logic should trade, check with strategy.is_session_enabled(), remove the line to enable all sessions.
+/// enabled_sessions = ["london", "new_york"]
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiveParameters {
+    pub max_order_quantity: Option<Volume>,
+    pub size_multiplier: Decimal,
+    pub enabled_sessions: Option<Vec<String>>,
+}
+
+impl Default for LiveParameters {
+    fn default() -> Self {
+        Self {
+            max_order_quantity: None,
+            size_multiplier: dec!(1),
+            enabled_sessions: None,
+        }
+    }
+}
+
+impl LiveParameters {
+    /// Applies the size multiplier and the max order quantity to the quantity.
+    pub fn limit_quantity(&self, quantity: Volume) -> Volume {
+        let quantity = quantity * self.size_multiplier.max(dec!(0));
+        match self.max_order_quantity {
+            Some(max) => quantity.min(max),
+            None => quantity
+        }
+    }
+
+    pub fn is_session_enabled(&self, session: &str) -> bool {
+        match &self.enabled_sessions {
+            Some(sessions) => sessions.iter().any(|enabled| enabled == session),
+            None => true
+        }
+    }
+
+    /// The value of the parameter formatted as it would be written in the TOML file, an empty string if it is not set.
+    pub fn value_string(&self, name: &str) -> String {
+        match name {
+            "max_order_quantity" => self.max_order_quantity.map(|max| max.to_string()).unwrap_or_default(),
+            "size_multiplier" => self.size_multiplier.to_string(),
+            "enabled_sessions" => self.enabled_sessions.as_ref()
+                .map(|sessions| Value::Array(sessions.iter().map(|session| Value::String(session.clone())).collect()).to_string())
+                .unwrap_or_default(),
+            _ => String::new()
+        }
+    }
+
+    /// Sets the parameter from a TOML value, `None` resets it to the default.
+    pub fn set(&mut self, name: &str, value: Option<&Value>) -> Result<(), String> {
+        match name {
+            "max_order_quantity" => self.max_order_quantity = value.map(decimal).transpose()?,
+            "size_multiplier" => self.size_multiplier = value.map(decimal).transpose()?.unwrap_or(dec!(1)),
+            "enabled_sessions" => self.enabled_sessions = value.map(|value| match value {
+                Value::Array(sessions) => sessions.iter()
+                    .map(|session| session.as_str().map(|session| session.to_string()).ok_or(format!("{} is not a string", session)))
+                    .collect::<Result<Vec<String>, String>>(),
+                _ => Err(format!("{} is not an array of sessions", value))
+            }).transpose()?,
+            _ => return Err(format!("{} is not a live parameter", name))
+        }
+        Ok(())
+    }
+
+    /// Copies a single parameter from another set of parameters.
+    fn copy_from(&mut self, other: &LiveParameters, name: &str) {
+        match name {
+            "max_order_quantity" => self.max_order_quantity = other.max_order_quantity,
+            "size_multiplier" => self.size_multiplier = other.size_multiplier,
+            "enabled_sessions" => self.enabled_sessions = other.enabled_sessions.clone(),
+            _ => {}
+        }
+    }
+
+    /// Applies the whitelisted parameters in the table, returning the changes.
+    /// Parameters missing from the table are reset to their defaults, invalid values leave the parameter unchanged.
+    pub fn apply_table(&mut self, table: &Table) -> Vec<ParameterChange> {
+        for key in table.keys() {
+            if !LIVE_PARAMETER_NAMES.contains(&key.as_str()) {
+                tracing::warn!("Live Parameters: {} can not be changed at runtime, ignoring", key);
+            }
+        }
+        let mut changes = vec![];
+        for name in LIVE_PARAMETER_NAMES {
+            let old_value = self.value_string(name);
+            let mut updated = self.clone();
+            if let Err(e) = updated.set(name, table.get(name)) {
+                tracing::warn!("Live Parameters: Invalid value for {}: {}", name, e);
+                continue;
+            }
+            let new_value = updated.value_string(name);
+            if new_value != old_value {
+                *self = updated;
+                changes.push(ParameterChange { name: name.to_string(), old_value, new_value });
+            }
+        }
+        changes
+    }
+}
+
+fn decimal(value: &Value) -> Result<Decimal, String> {
+    match value {
+        Value::Integer(integer) => Ok(Decimal::from(*integer)),
+        Value::Float(float) => Decimal::try_from(*float).map_err(|e| e.to_string()),
+        Value::String(string) => string.parse::<Decimal>().map_err(|e| e.to_string()),
+        _ => Err(format!("{} is not a number", value))
+    }
+}
+
+/// A live parameter which was changed by editing the parameters file.
+/// The new value is already in effect when the strategy receives the `StrategyEvent::ParameterChanged`, use `strategy.veto_parameter_change()` to restore the old value.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct ParameterChange {
+    pub name: String,
+    /// The old value formatted as TOML, empty if it was not set.
+    pub old_value: String,
+    /// The new value formatted as TOML, empty if it was removed.
+    pub new_value: String,
+}
+
+/// Holds the live parameters for the strategy and watches the parameters file for changes.
+pub(crate) struct LiveParameterHandler {
+    parameters: RwLock<LiveParameters>,
+    /// The parameters before the last change to each parameter, used to veto changes.
+    previous: RwLock<LiveParameters>,
+    strategy_event_sender: mpsc::Sender<StrategyEvent>,
+}
+
+impl LiveParameterHandler {
+    pub fn new(strategy_event_sender: mpsc::Sender<StrategyEvent>) -> Self {
+        Self {
+            parameters: RwLock::new(LiveParameters::default()),
+            previous: RwLock::new(LiveParameters::default()),
+            strategy_event_sender,
+        }
+    }
+
+    pub fn parameters(&self) -> LiveParameters {
+        self.parameters.read().unwrap().clone()
+    }
+
+    pub fn limit_quantity(&self, quantity: Volume) -> Volume {
+        self.parameters.read().unwrap().limit_quantity(quantity)
+    }
+
+    pub fn veto(&self, change: &ParameterChange) {
+        let previous = self.previous.read().unwrap();
+        let mut parameters = self.parameters.write().unwrap();
+        if parameters.value_string(&change.name) == change.new_value {
+            parameters.copy_from(&previous, &change.name);
+        }
+    }
+
+    /// Reads the file and applies any changes, without sending events, used to load the initial parameters.
+    fn load(&self, path: &PathBuf) -> Result<Vec<ParameterChange>, FundForgeError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to read {}: {}", path.display(), e)))?;
+        let table: Table = content.parse()
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse {}: {}", path.display(), e)))?;
+        let mut parameters = self.parameters.write().unwrap();
+        let before = parameters.clone();
+        let changes = parameters.apply_table(&table);
+        let mut previous = self.previous.write().unwrap();
+        for change in &changes {
+            previous.copy_from(&before, &change.name);
+        }
+        Ok(changes)
+    }
+
+    /// Loads the file, then polls it for modifications, each changed parameter is sent to the strategy as a `StrategyEvent::ParameterChanged`.
+    pub fn watch(self: &Arc<Self>, path: PathBuf, poll_interval: Duration) -> Result<(), FundForgeError> {
+        self.load(&path)?;
+        let handler = self.clone();
+        tokio::spawn(async move {
+            let mut last_modified = modified_time(&path);
+            let mut interval = tokio::time::interval(poll_interval);
+            loop {
+                interval.tick().await;
+                let modified = modified_time(&path);
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+                let changes = match handler.load(&path) {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        tracing::error!("Live Parameters: {}", e);
+                        continue;
+                    }
+                };
+                for change in changes {
+                    tracing::info!("Live Parameters: {} changed from '{}' to '{}'", change.name, change.old_value, change.new_value);
+                    if handler.strategy_event_sender.send(StrategyEvent::ParameterChanged(change)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+fn modified_time(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_table_whitelist_and_veto() {
+        let mut parameters = LiveParameters::default();
+        let table: Table = "max_order_quantity = 5\nsize_multiplier = 0.5\nenabled_sessions = [\"london\"]\nstop_loss = 10".parse().unwrap();
+        let changes = parameters.apply_table(&table);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(parameters.limit_quantity(dec!(20)), dec!(5));
+        assert_eq!(parameters.limit_quantity(dec!(4)), dec!(2));
+        assert!(parameters.is_session_enabled("london"));
+        assert!(!parameters.is_session_enabled("new_york"));
+
+        // Removing a parameter resets it, invalid values are ignored
+        let table: Table = "size_multiplier = \"abc\"\nenabled_sessions = [\"london\"]".parse().unwrap();
+        let changes = parameters.apply_table(&table);
+        assert_eq!(changes, vec![ParameterChange { name: "max_order_quantity".to_string(), old_value: "5".to_string(), new_value: String::new() }]);
+        assert_eq!(parameters.size_multiplier, dec!(0.5));
+        assert_eq!(parameters.max_order_quantity, None);
+    }
+}
@@ -7,4 +7,17 @@ pub mod fund_forge_strategy;
 pub mod ledgers;
 pub mod handlers;
 pub mod statistics;
-pub mod client_features;
\ No newline at end of file
+pub mod client_features;
+pub mod position_sizing;
+pub mod live_parameters;
+pub mod trading_schedule;
+pub mod lifecycle;
+pub mod subscription_profiles;
+pub mod checkpoints;
+pub mod event_queue;
+pub mod notifications;
+pub mod universe_selection;
+pub mod session_recording;
+pub mod feed_quality;
+pub mod execution_algos;
+pub mod order_routing;
@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use std::time::Duration as StdDuration;
+use chrono::{DateTime, Duration, Utc};
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use strum_macros::Display;
+use tokio::sync::mpsc;
+
+/// The notifications waiting to be delivered, when the queue is full new notifications are dropped.
+const QUEUE_CAPACITY: usize = 100;
+
+/// The time allowed for each delivery, so an unresponsive endpoint does not hold up the notifications behind it.
+const DELIVERY_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+
+lazy_static! {
+    static ref NOTIFIER: RwLock<Option<Arc<Notifier>>> = RwLock::new(None);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Display)]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Where notifications are sent, see `NotificationSettings`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NotificationTarget {
+    /// A Discord channel webhook url.
+    Discord { webhook_url: String },
+    /// A Slack incoming webhook url.
+    Slack { webhook_url: String },
+    /// A Telegram bot token and the id of the chat the bot posts to.
+    Telegram { bot_token: String, chat_id: String },
+    /// Any http endpoint, the notification is posted as json with the fields `level`, `message` and `time`.
+    Webhook { url: String },
+}
+
+impl NotificationTarget {
+    /// The url and json body of the post request which delivers the notification.
+    fn request(&self, notification: &Notification) -> (String, Value) {
+        let text = notification.to_string();
+        match self {
+            NotificationTarget::Discord { webhook_url } => (webhook_url.clone(), json!({ "content": text })),
+            NotificationTarget::Slack { webhook_url } => (webhook_url.clone(), json!({ "text": text })),
+            NotificationTarget::Telegram { bot_token, chat_id } => (
+                format!("https://api.telegram.org/bot{}/sendMessage", bot_token),
+                json!({ "chat_id": chat_id, "text": text })
+            ),
+            NotificationTarget::Webhook { url } => (url.clone(), json!({
+                "level": notification.level.to_string(),
+                "message": notification.message,
+                "time": notification.time.to_rfc3339(),
+            })),
+        }
+    }
+}
+
+/// The notification settings of the strategy, see `strategy.set_notifications()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NotificationSettings {
+    pub targets: Vec<NotificationTarget>,
+    /// Notifications below this level are not sent, `NotificationLevel::Info` by default.
+    pub min_level: NotificationLevel,
+    /// The most notifications sent in any minute, notifications over the limit are dropped and counted in the next notification sent, 20 by default.
+    pub max_per_minute: usize,
+    /// If true order rejections, `RiskStage` changes and dropped connections are sent automatically in live modes, true by default.
+    pub engine_events: bool,
+}
+
+impl NotificationSettings {
+    pub fn new(targets: Vec<NotificationTarget>) -> Self {
+        Self {
+            targets,
+            min_level: NotificationLevel::Info,
+            max_per_minute: 20,
+            engine_events: true,
+        }
+    }
+
+    pub fn with_min_level(mut self, min_level: NotificationLevel) -> Self {
+        self.min_level = min_level;
+        self
+    }
+
+    pub fn with_max_per_minute(mut self, max_per_minute: usize) -> Self {
+        self.max_per_minute = max_per_minute;
+        self
+    }
+
+    pub fn with_engine_events(mut self, engine_events: bool) -> Self {
+        self.engine_events = engine_events;
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Notification {
+    level: NotificationLevel,
+    message: String,
+    time: DateTime<Utc>,
+}
+
+impl std::fmt::Display for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.level, self.message)
+    }
+}
+
+/// Limits the notifications sent in any minute.
+#[derive(Debug)]
+struct RateLimiter {
+    max_per_minute: usize,
+    sent: VecDeque<DateTime<Utc>>,
+    /// The notifications dropped since the last notification sent.
+    dropped: u64,
+}
+
+impl RateLimiter {
+    fn new(max_per_minute: usize) -> Self {
+        Self {
+            max_per_minute,
+            sent: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// True if a notification can be sent at the time, otherwise the notification is counted as dropped.
+    fn allow(&mut self, time: DateTime<Utc>) -> bool {
+        while self.sent.front().is_some_and(|sent| time - *sent >= Duration::minutes(1)) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= self.max_per_minute {
+            self.dropped += 1;
+            return false;
+        }
+        self.sent.push_back(time);
+        true
+    }
+}
+
+/// Delivers notifications on its own task, so sending a notification never waits on the network.
+struct Notifier {
+    settings: NotificationSettings,
+    sender: mpsc::Sender<Notification>,
+}
+
+impl Notifier {
+    fn new(settings: NotificationSettings) -> Self {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::task::spawn(Notifier::deliver(settings.clone(), receiver));
+        Self {
+            settings,
+            sender,
+        }
+    }
+
+    fn send(&self, level: NotificationLevel, message: String) {
+        if level < self.settings.min_level {
+            return;
+        }
+        let notification = Notification { level, message, time: Utc::now() };
+        if let Err(e) = self.sender.try_send(notification) {
+            tracing::warn!("Notifications: Dropped a notification: {}", e);
+        }
+    }
+
+    async fn deliver(settings: NotificationSettings, mut receiver: mpsc::Receiver<Notification>) {
+        let client = match reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Notifications: Failed to create the http client: {}", e);
+                return;
+            }
+        };
+        let mut rate_limiter = RateLimiter::new(settings.max_per_minute);
+        while let Some(mut notification) = receiver.recv().await {
+            let dropped = rate_limiter.dropped;
+            if !rate_limiter.allow(notification.time) {
+                continue;
+            }
+            if dropped > 0 {
+                notification.message = format!("{} ({} notifications were dropped by the rate limit)", notification.message, dropped);
+                rate_limiter.dropped = 0;
+            }
+            for target in &settings.targets {
+                let (url, body) = target.request(&notification);
+                match client.post(&url).json(&body).send().await {
+                    Ok(response) if !response.status().is_success() => tracing::error!("Notifications: Delivery failed with status {}", response.status()),
+                    Ok(_) => {}
+                    // The error can include the url, which holds the token of the webhook or bot.
+                    Err(e) => tracing::error!("Notifications: Delivery failed: {}", e.without_url()),
+                }
+            }
+        }
+    }
+}
+
+/// Replaces the notifier, `None` stops the notifications.
+pub(crate) fn set_notifications(settings: Option<NotificationSettings>) {
+    *NOTIFIER.write().unwrap() = settings.map(|settings| Arc::new(Notifier::new(settings)));
+}
+
+/// Queues a notification, does nothing if notifications are not set.
+pub(crate) fn notify(level: NotificationLevel, message: String) {
+    let notifier = NOTIFIER.read().unwrap().clone();
+    if let Some(notifier) = notifier {
+        notifier.send(level, message);
+    }
+}
+
+/// Queues a notification of an engine event, if `NotificationSettings::engine_events` is set.
+pub(crate) fn notify_engine_event(level: NotificationLevel, message: String) {
+    let notifier = NOTIFIER.read().unwrap().clone();
+    if let Some(notifier) = notifier.filter(|notifier| notifier.settings.engine_events) {
+        notifier.send(level, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_rate_limiter() {
+        let time = |time: &str| DateTime::<Utc>::from_str(time).unwrap();
+        let mut rate_limiter = RateLimiter::new(2);
+        assert!(rate_limiter.allow(time("2024-06-05T13:00:00Z")));
+        assert!(rate_limiter.allow(time("2024-06-05T13:00:30Z")));
+        assert!(!rate_limiter.allow(time("2024-06-05T13:00:59Z")));
+        assert_eq!(rate_limiter.dropped, 1);
+        // The first notification is a minute old.
+        assert!(rate_limiter.allow(time("2024-06-05T13:01:00Z")));
+        assert!(!rate_limiter.allow(time("2024-06-05T13:01:10Z")));
+    }
+
+    #[test]
+    fn test_target_requests() {
+        let notification = Notification {
+            level: NotificationLevel::Critical,
+            message: "Disconnected".to_string(),
+            time: DateTime::<Utc>::from_str("2024-06-05T13:00:00Z").unwrap(),
+        };
+        let telegram = NotificationTarget::Telegram { bot_token: "123:abc".to_string(), chat_id: "42".to_string() };
+        assert_eq!(telegram.request(&notification), (
+            "https://api.telegram.org/bot123:abc/sendMessage".to_string(),
+            json!({ "chat_id": "42", "text": "[Critical] Disconnected" })
+        ));
+        let discord = NotificationTarget::Discord { webhook_url: "https://discord.com/api/webhooks/1".to_string() };
+        assert_eq!(discord.request(&notification).1, json!({ "content": "[Critical] Disconnected" }));
+        let webhook = NotificationTarget::Webhook { url: "http://localhost:9000".to_string() };
+        assert_eq!(webhook.request(&notification).1, json!({ "level": "Critical", "message": "Disconnected", "time": "2024-06-05T13:00:00+00:00" }));
+    }
+}
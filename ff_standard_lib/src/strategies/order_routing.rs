@@ -0,0 +1,232 @@
+use std::fs;
+use std::str::FromStr;
+use rust_decimal_macros::dec;
+use serde::{Deserialize as _, Deserializer};
+use serde_derive::Deserialize;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::product_maps::rithmic::maps::get_related_contracts;
+use crate::standardized_types::accounts::{Account, AccountId};
+use crate::standardized_types::broker_enum::Brokerage;
+use crate::standardized_types::orders::Order;
+use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
+
+/// The contract a `RoutingRule` places orders for when the symbol has a related micro or mini contract, eg. ES and MES.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum ContractSize {
+    /// Orders for the mini are placed for the micro, the quantity is multiplied by the micros per mini so the notional is the same.
+    Micro,
+    /// Orders for the micro are placed for the mini, orders which are not a whole number of minis are rejected.
+    Mini,
+}
+
+/// Where the orders for a symbol and account are sent, see `RoutingTable`.
+/// A rule matches an order when its `symbol_name` and `account_id` are `None` or equal to the order, the other fields are applied to the order and are left unchanged when `None`.
+#[derive(Clone, Debug, PartialEq, Default, Deserialize)]
+pub struct RoutingRule {
+    /// The symbol name the strategy orders, `None` matches every symbol and routes the account wide requests, eg. `strategy.flatten_all_for()`.
+    #[serde(default)]
+    pub symbol_name: Option<SymbolName>,
+    /// The account id the strategy orders with, `None` matches every account.
+    #[serde(default)]
+    pub account_id: Option<AccountId>,
+    /// The brokerage connection the orders are sent to, eg. "Rithmic Apex" or "Oanda".
+    #[serde(default, deserialize_with = "deserialize_brokerage")]
+    pub brokerage: Option<Brokerage>,
+    /// The account id the orders are placed with.
+    #[serde(default)]
+    pub to_account_id: Option<AccountId>,
+    #[serde(default)]
+    pub contract: Option<ContractSize>,
+    /// The contract code, eg. "MESZ4", the code is applied after the `contract`.
+    #[serde(default)]
+    pub symbol_code: Option<SymbolCode>,
+    #[serde(default)]
+    pub exchange: Option<String>,
+}
+
+impl RoutingRule {
+    fn matches(&self, account: &Account, symbol_name: Option<&SymbolName>) -> bool {
+        self.account_id.as_ref().is_none_or(|account_id| *account_id == account.account_id)
+            && match (&self.symbol_name, symbol_name) {
+                (None, _) => true,
+                (Some(rule_symbol), Some(symbol_name)) => rule_symbol == symbol_name,
+                (Some(_), None) => false,
+            }
+    }
+
+    /// Rules for a symbol and account are more specific than rules for a symbol, which are more specific than rules for an account.
+    fn specificity(&self) -> u8 {
+        self.symbol_name.is_some() as u8 * 2 + self.account_id.is_some() as u8
+    }
+
+    fn route_account(&self, account: &Account) -> Account {
+        Account::new(
+            self.brokerage.unwrap_or(account.brokerage),
+            self.to_account_id.clone().unwrap_or_else(|| account.account_id.clone()),
+        )
+    }
+}
+
+fn deserialize_brokerage<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Brokerage>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(brokerage) => Brokerage::from_str(&brokerage).map(Some).map_err(|e| serde::de::Error::custom(format!("{:?}", e))),
+        None => Ok(None),
+    }
+}
+
+/// Maps the symbol and account of each order to the brokerage connection, account, contract and exchange it is placed with,
+/// so a strategy can be moved between brokerages or between mini and micro contracts without changing its code, see `strategy.set_routing_table()`.
+///
+/// Orders are routed when they are sent, the most specific matching rule is used and orders without a matching rule are sent as the strategy created them.
+/// The orders and positions are tracked under the routed account and symbol, eg. an order for ES routed to MES opens a MES position.
+///
+/// # File Format
+/// ```toml
+/// # Trade the micros on the evaluation account
+/// [[routes]]
+/// symbol_name = "ES"
+/// account_id = "APEX-1234"
+/// contract = "Micro"
+///
+/// # Move every order of the account, and its account wide requests, to another connection
+/// [[routes]]
+/// account_id = "APEX-1234"
+/// brokerage = "Rithmic TopstepTrader"
+/// to_account_id = "TS-5678"
+///
+/// [[routes]]
+/// symbol_name = "NQ"
+/// symbol_code = "NQZ4"
+/// exchange = "CME"
+/// ```
+#[derive(Clone, Debug, PartialEq, Default, Deserialize)]
+pub struct RoutingTable {
+    #[serde(default, rename = "routes")]
+    pub rules: Vec<RoutingRule>,
+}
+
+impl RoutingTable {
+    pub fn new(rules: Vec<RoutingRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, FundForgeError> {
+        toml::from_str(content)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse routing table: {}", e)))
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, FundForgeError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to read routing table {}: {}", path, e)))?;
+        Self::from_toml(&content)
+    }
+
+    /// The most specific rule matching the account and symbol, the first of equally specific rules.
+    fn rule(&self, account: &Account, symbol_name: Option<&SymbolName>) -> Option<&RoutingRule> {
+        let mut best: Option<&RoutingRule> = None;
+        for rule in self.rules.iter().filter(|rule| rule.matches(account, symbol_name)) {
+            if best.is_none_or(|best| rule.specificity() > best.specificity()) {
+                best = Some(rule);
+            }
+        }
+        best
+    }
+
+    /// The account the account wide requests of the account are sent to.
+    pub fn route_account(&self, account: &Account) -> Account {
+        match self.rule(account, None) {
+            Some(rule) => rule.route_account(account),
+            None => account.clone(),
+        }
+    }
+
+    /// Applies the matching rule to the order, returns the reason the order is rejected if it can not be routed.
+    pub fn route_order(&self, order: &mut Order) -> Result<(), String> {
+        let rule = match self.rule(&order.account, Some(&order.symbol_name)) {
+            Some(rule) => rule,
+            None => return Ok(()),
+        };
+        if let Some(contract) = rule.contract {
+            if let Some(contracts) = get_related_contracts(&order.symbol_name) {
+                match contract {
+                    ContractSize::Micro if order.symbol_name == contracts.mini => {
+                        order.symbol_code = contracts.micro_code(&order.symbol_code).unwrap_or_else(|| contracts.micro.clone());
+                        order.symbol_name = contracts.micro.clone();
+                        order.quantity_open *= contracts.micros_per_mini;
+                    }
+                    ContractSize::Mini if order.symbol_name == contracts.micro => {
+                        let minis = order.quantity_open / contracts.micros_per_mini;
+                        if minis.fract() != dec!(0) {
+                            return Err(format!("Routing: {} {} is not a whole number of {}", order.quantity_open, contracts.micro, contracts.mini));
+                        }
+                        order.symbol_code = match order.symbol_code.strip_prefix(contracts.micro.as_str()) {
+                            Some(expiry) => format!("{}{}", contracts.mini, expiry),
+                            None => contracts.mini.clone(),
+                        };
+                        order.symbol_name = contracts.mini.clone();
+                        order.quantity_open = minis;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if let Some(symbol_code) = &rule.symbol_code {
+            order.symbol_code = symbol_code.clone();
+        }
+        if rule.exchange.is_some() {
+            order.exchange = rule.exchange.clone();
+        }
+        order.account = rule.route_account(&order.account);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::apis::rithmic::rithmic_systems::RithmicSystem;
+    use crate::standardized_types::enums::OrderSide;
+
+    #[test]
+    fn test_routing_table() {
+        let table = RoutingTable::from_toml(r#"
+            [[routes]]
+            symbol_name = "ES"
+            account_id = "APEX-1234"
+            contract = "Micro"
+
+            [[routes]]
+            account_id = "APEX-1234"
+            brokerage = "Rithmic TopstepTrader"
+            to_account_id = "TS-5678"
+
+            [[routes]]
+            symbol_name = "MNQ"
+            contract = "Mini"
+            exchange = "CME"
+        "#).unwrap();
+        let apex = Account::new(Brokerage::Rithmic(RithmicSystem::Apex), "APEX-1234".to_string());
+        let other = Account::new(Brokerage::Rithmic(RithmicSystem::Apex), "APEX-9999".to_string());
+
+        // The symbol and account rule is used over the account rule, so the account is not changed.
+        let mut order = Order::market_order("ES".to_string(), Some("ESZ4".to_string()), &apex, dec!(2), OrderSide::Buy, "test".to_string(), "1".to_string(), Utc::now(), None);
+        table.route_order(&mut order).unwrap();
+        assert_eq!((order.symbol_name.as_str(), order.symbol_code.as_str(), order.quantity_open), ("MES", "MESZ4", dec!(20)));
+        assert_eq!(order.account, apex);
+
+        let mut order = Order::market_order("NQ".to_string(), None, &apex, dec!(1), OrderSide::Buy, "test".to_string(), "2".to_string(), Utc::now(), None);
+        table.route_order(&mut order).unwrap();
+        assert_eq!(order.account, Account::new(Brokerage::Rithmic(RithmicSystem::TopstepTrader), "TS-5678".to_string()));
+        assert_eq!(table.route_account(&apex), order.account);
+        assert_eq!(table.route_account(&other), other);
+
+        let mut order = Order::market_order("MNQ".to_string(), Some("MNQZ4".to_string()), &other, dec!(30), OrderSide::Sell, "test".to_string(), "3".to_string(), Utc::now(), None);
+        table.route_order(&mut order).unwrap();
+        assert_eq!((order.symbol_name.as_str(), order.symbol_code.as_str(), order.quantity_open), ("NQ", "NQZ4", dec!(3)));
+        assert_eq!(order.exchange, Some("CME".to_string()));
+
+        let mut order = Order::market_order("MNQ".to_string(), None, &other, dec!(5), OrderSide::Sell, "test".to_string(), "4".to_string(), Utc::now(), None);
+        assert!(table.route_order(&mut order).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::standardized_types::accounts::Account;
+use crate::standardized_types::new_types::Volume;
+use crate::product_maps::rithmic::maps::get_related_contracts;
+use crate::standardized_types::subscriptions::{SymbolCode, SymbolName};
+
+/// A sizing layer which is consulted by the `enter_long()` and `enter_short()` helpers on `FundForgeStrategy`.
+/// The sizer is passed the quantity the strategy requested and the current account equity (balance + open pnl),
+/// it returns the quantity that will actually be sent to the market.
+///
+/// Implement this trait to create custom sizing logic, then pass it to `strategy.set_position_sizer()`.
+pub trait PositionSizer: Send + Sync {
+    fn size(&self, account: &Account, symbol_name: &SymbolName, quantity: Volume, equity: Decimal) -> Volume;
+}
+
+/// Scales order quantity down as the account drawdown from peak equity increases, and back up as the account recovers.
+///
+/// The curve is a list of `(drawdown_percent, multiplier)` points, the multiplier is linearly interpolated between points.
+/// For example `vec![(dec!(0), dec!(1)), (dec!(5), dec!(0.5)), (dec!(10), dec!(0))]` will trade full size at the peak,
+/// half size at a 5% drawdown and stop trading at a 10% drawdown.
+/// Drawdowns beyond the last point use the last multiplier.
+///
+/// Peak equity is tracked per account, starting from the first time the sizer is consulted.
+pub struct DrawdownPositionSizer {
+    curve: Vec<(Decimal, Decimal)>,
+    lot_size: Volume,
+    minimum_quantity: Volume,
+    peak_equity: DashMap<Account, Decimal>,
+}
+
+impl DrawdownPositionSizer {
+    /// # Arguments
+    /// `curve: Vec<(Decimal, Decimal)>`: The `(drawdown_percent, multiplier)` points, they will be sorted by drawdown.
+    ///
+    /// `lot_size: Volume`: The quantity will be rounded down to a multiple of this size, use `dec!(1)` for futures.
+    ///
+    /// `minimum_quantity: Volume`: If the scaled quantity is less than this (but the multiplier is not 0) the minimum is used instead.
+    pub fn new(mut curve: Vec<(Decimal, Decimal)>, lot_size: Volume, minimum_quantity: Volume) -> Self {
+        curve.sort_by_key(|point| point.0);
+        Self {
+            curve,
+            lot_size,
+            minimum_quantity,
+            peak_equity: DashMap::new(),
+        }
+    }
+
+    /// The current drawdown from peak for the account as a percentage, updating the peak if the equity is a new high.
+    pub fn drawdown_percent(&self, account: &Account, equity: Decimal) -> Decimal {
+        let mut peak = self.peak_equity.entry(account.clone()).or_insert(equity);
+        if equity > *peak {
+            *peak = equity;
+        }
+        if *peak <= dec!(0) {
+            return dec!(0)
+        }
+        ((*peak - equity) / *peak * dec!(100)).max(dec!(0))
+    }
+
+    /// The multiplier for a given drawdown percentage.
+    pub fn multiplier(&self, drawdown_percent: Decimal) -> Decimal {
+        let first = match self.curve.first() {
+            Some(point) => point,
+            None => return dec!(1)
+        };
+        if drawdown_percent <= first.0 {
+            return first.1
+        }
+        for window in self.curve.windows(2) {
+            let (low_dd, low_mult) = window[0];
+            let (high_dd, high_mult) = window[1];
+            if drawdown_percent <= high_dd {
+                if high_dd == low_dd {
+                    return high_mult
+                }
+                let fraction = (drawdown_percent - low_dd) / (high_dd - low_dd);
+                return low_mult + (high_mult - low_mult) * fraction
+            }
+        }
+        self.curve.last().unwrap().1
+    }
+}
+
+impl PositionSizer for DrawdownPositionSizer {
+    fn size(&self, account: &Account, _symbol_name: &SymbolName, quantity: Volume, equity: Decimal) -> Volume {
+        let drawdown = self.drawdown_percent(account, equity);
+        let multiplier = self.multiplier(drawdown).max(dec!(0));
+        if multiplier == dec!(0) {
+            return dec!(0)
+        }
+        let scaled = quantity * multiplier;
+        let scaled = match self.lot_size > dec!(0) {
+            true => (scaled / self.lot_size).floor() * self.lot_size,
+            false => scaled
+        };
+        scaled.max(self.minimum_quantity).min(quantity)
+    }
+}
+
+/// How orders for a mini contract are downsized to the related micro contract, see `strategy.set_contract_downsizing()`.
+///
+/// The quantity passed to the downsizing is the sized quantity of the mini, so a `PositionSizer` which returns fractional quantities,
+/// eg. a `DrawdownPositionSizer` with a `lot_size` of `dec!(0.1)`, can trade a fraction of the minis notional with micros.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContractDownsizing {
+    /// Trade the micro when the quantity is less than one mini, otherwise trade whole minis.
+    MicroBelowOneMini,
+    /// Trade whole minis and make up the remaining notional with micros.
+    MixMiniAndMicro,
+}
+
+impl ContractDownsizing {
+    /// Splits a quantity of minis into whole `(minis, micros)`, the notional is rounded down to a whole micro.
+    pub fn split(&self, quantity: Volume, micros_per_mini: Volume) -> (Volume, Volume) {
+        let minis = quantity.floor();
+        match self {
+            ContractDownsizing::MicroBelowOneMini if minis >= dec!(1) => (minis, dec!(0)),
+            ContractDownsizing::MicroBelowOneMini => (dec!(0), (quantity * micros_per_mini).floor()),
+            ContractDownsizing::MixMiniAndMicro => (minis, ((quantity - minis) * micros_per_mini).floor()),
+        }
+    }
+}
+
+/// The symbol name, symbol code and quantity of each order needed to enter the sized quantity, after applying the `ContractDownsizing`.
+/// Legs with no quantity are left out, so a quantity sized down to 0 has no legs.
+pub(crate) fn contract_legs(downsizing: Option<ContractDownsizing>, symbol_name: &SymbolName, symbol_code: Option<SymbolCode>, quantity: Volume) -> Vec<(SymbolName, Option<SymbolCode>, Volume)> {
+    if quantity <= dec!(0) {
+        return vec![];
+    }
+    let (downsizing, contracts) = match (downsizing, get_related_contracts(symbol_name)) {
+        (Some(downsizing), Some(contracts)) if contracts.mini == *symbol_name => (downsizing, contracts),
+        _ => return vec![(symbol_name.clone(), symbol_code, quantity)]
+    };
+    let (minis, micros) = downsizing.split(quantity, contracts.micros_per_mini);
+    let mut legs = vec![];
+    if minis > dec!(0) {
+        legs.push((symbol_name.clone(), symbol_code.clone(), minis));
+    }
+    if micros > dec!(0) {
+        let micro_code = symbol_code.and_then(|code| contracts.micro_code(&code));
+        legs.push((contracts.micro.clone(), micro_code, micros));
+    }
+    legs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apis::rithmic::rithmic_systems::RithmicSystem;
+    use crate::standardized_types::broker_enum::Brokerage;
+
+    fn sizer() -> DrawdownPositionSizer {
+        DrawdownPositionSizer::new(vec![(dec!(10), dec!(0)), (dec!(0), dec!(1)), (dec!(5), dec!(0.5))], dec!(1), dec!(1))
+    }
+
+    #[test]
+    fn test_multiplier_interpolation() {
+        let sizer = sizer();
+        assert_eq!(sizer.multiplier(dec!(0)), dec!(1));
+        assert_eq!(sizer.multiplier(dec!(2.5)), dec!(0.75));
+        assert_eq!(sizer.multiplier(dec!(5)), dec!(0.5));
+        assert_eq!(sizer.multiplier(dec!(20)), dec!(0));
+    }
+
+    #[test]
+    fn test_size_scales_down_and_recovers() {
+        let sizer = sizer();
+        let account = Account::new(Brokerage::Rithmic(RithmicSystem::Rithmic01), "TEST".to_string());
+        let symbol = "NQ".to_string();
+        assert_eq!(sizer.size(&account, &symbol, dec!(10), dec!(100000)), dec!(10));
+        // 5% drawdown -> half size
+        assert_eq!(sizer.size(&account, &symbol, dec!(10), dec!(95000)), dec!(5));
+        // 10% drawdown -> no trading
+        assert_eq!(sizer.size(&account, &symbol, dec!(10), dec!(90000)), dec!(0));
+        // recovery to the peak restores full size
+        assert_eq!(sizer.size(&account, &symbol, dec!(10), dec!(100000)), dec!(10));
+    }
+
+    #[test]
+    fn test_contract_downsizing_split() {
+        assert_eq!(ContractDownsizing::MicroBelowOneMini.split(dec!(0.75), dec!(10)), (dec!(0), dec!(7)));
+        assert_eq!(ContractDownsizing::MicroBelowOneMini.split(dec!(2.5), dec!(10)), (dec!(2), dec!(0)));
+        assert_eq!(ContractDownsizing::MixMiniAndMicro.split(dec!(2.5), dec!(10)), (dec!(2), dec!(5)));
+        assert_eq!(ContractDownsizing::MixMiniAndMicro.split(dec!(0.05), dec!(10)), (dec!(0), dec!(0)));
+    }
+
+    #[test]
+    fn test_no_order_at_full_drawdown() {
+        let sizer = sizer();
+        let account = Account::new(Brokerage::Rithmic(RithmicSystem::Rithmic01), "TEST".to_string());
+        let symbol = "NQ".to_string();
+        assert_eq!(contract_legs(None, &symbol, None, sizer.size(&account, &symbol, dec!(10), dec!(100000))), vec![(symbol.clone(), None, dec!(10))]);
+        let quantity = sizer.size(&account, &symbol, dec!(10), dec!(90000));
+        assert!(contract_legs(None, &symbol, None, quantity).is_empty());
+        assert!(contract_legs(Some(ContractDownsizing::MixMiniAndMicro), &symbol, None, quantity).is_empty());
+        // Less than one micro of notional rounds down to no legs rather than a 0 quantity mini order
+        assert!(contract_legs(Some(ContractDownsizing::MixMiniAndMicro), &symbol, None, dec!(0.05)).is_empty());
+    }
+}
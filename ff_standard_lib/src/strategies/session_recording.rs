@@ -0,0 +1,280 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use rkyv::{AlignedVec, Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use tokio::sync::mpsc;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::strategies::handlers::market_handler::price_service::MarketPriceService;
+use crate::strategies::handlers::timed_events_handler::TimedEventHandler;
+use crate::strategies::historical_time::update_backtest_time;
+use crate::strategies::strategy_events::StrategyEvent;
+
+static SESSION_RECORDING: OnceLock<PathBuf> = OnceLock::new();
+static SESSION_REPLAY: OnceLock<SessionReplay> = OnceLock::new();
+
+/// The magic bytes and version at the start of a recording file.
+const RECORDING_MAGIC: [u8; 4] = *b"FFSR";
+const RECORDING_VERSION: u32 = 1;
+
+/// An event received by the strategy during a recorded session, with the utc time it was received in nanoseconds.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+struct RecordedEvent {
+    time: i64,
+    event: StrategyEvent,
+}
+
+/// Records every `StrategyEvent` received by a live or live paper strategy to the file, so the session can be replayed with `set_session_replay()`.
+/// This must be called before `FundForgeStrategy::initialize()`, the file is replaced if it exists. Recording is ignored in backtests.
+pub fn set_session_recording(path: impl Into<PathBuf>) -> Result<(), FundForgeError> {
+    SESSION_RECORDING.set(path.into())
+        .map_err(|_| FundForgeError::ClientSideErrorDebug("The session recording is already set".to_string()))
+}
+
+pub(crate) fn session_recording() -> Option<&'static PathBuf> {
+    SESSION_RECORDING.get()
+}
+
+/// Replays a session recorded with `set_session_recording()` to a backtest strategy, see `set_session_replay()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionReplay {
+    pub path: PathBuf,
+    /// The replay speed as a multiple of the recorded speed, eg. 10.0 replays a minute of the session in 6 seconds, `None` replays the events without waiting.
+    /// 1.0 by default.
+    pub speed: Option<f64>,
+}
+
+impl SessionReplay {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            speed: Some(1.0),
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = Some(speed);
+        self
+    }
+
+    /// Replays the events one after another without waiting for the recorded time between them.
+    pub fn without_delay(mut self) -> Self {
+        self.speed = None;
+        self
+    }
+
+    /// The time to wait between events recorded at `previous` and `time`.
+    fn delay(&self, previous: DateTime<Utc>, time: DateTime<Utc>) -> Option<Duration> {
+        let speed = self.speed.filter(|speed| *speed > 0.0)?;
+        let elapsed = (time - previous).to_std().ok()?;
+        Some(elapsed.div_f64(speed))
+    }
+}
+
+/// Replays a recorded session instead of running the backtest, this must be called before `FundForgeStrategy::initialize()` with `StrategyMode::Backtest`.
+///
+/// The recorded events are sent to the strategy in the order they were received live, with the strategy time set to the time each event was received.
+/// The recorded `TimeSlice`s update the market prices, indicator, order, position and timed events are replayed as they were recorded, they are not generated again.
+/// The strategy is put in dry run mode, so the orders it places during the replay are logged to `strategy.dry_run_orders()` and can be compared with the recorded order events.
+pub fn set_session_replay(replay: SessionReplay) -> Result<(), FundForgeError> {
+    SESSION_REPLAY.set(replay)
+        .map_err(|_| FundForgeError::ClientSideErrorDebug("The session replay is already set".to_string()))
+}
+
+pub(crate) fn session_replay() -> Option<&'static SessionReplay> {
+    SESSION_REPLAY.get()
+}
+
+/// Reads the events of a recording, with the time each event was received.
+/// A recording which ends part way through an event, eg. because the strategy process was killed, is read up to the last complete event.
+pub fn read_session_recording(path: &Path) -> Result<Vec<(DateTime<Utc>, StrategyEvent)>, FundForgeError> {
+    let file = File::open(path)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to open recording {}: {}", path.display(), e)))?;
+    let mut reader = BufReader::new(file);
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to read recording {}: {}", path.display(), e)))?;
+    if header[..4] != RECORDING_MAGIC {
+        return Err(FundForgeError::ClientSideErrorDebug(format!("{} is not a session recording", path.display())));
+    }
+    let version = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    if version != RECORDING_VERSION {
+        return Err(FundForgeError::ClientSideErrorDebug(format!("Unsupported recording version {}", version)));
+    }
+
+    let mut events = vec![];
+    let mut length = [0u8; 4];
+    loop {
+        match reader.read_exact(&mut length) {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(FundForgeError::ClientSideErrorDebug(format!("Failed to read recording {}: {}", path.display(), e))),
+        }
+        let mut bytes = vec![0u8; u32::from_le_bytes(length) as usize];
+        match reader.read_exact(&mut bytes) {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(FundForgeError::ClientSideErrorDebug(format!("Failed to read recording {}: {}", path.display(), e))),
+        }
+        // The archive must be aligned to be validated.
+        let mut aligned = AlignedVec::with_capacity(bytes.len());
+        aligned.extend_from_slice(&bytes);
+        let recorded = rkyv::from_bytes::<RecordedEvent>(&aligned)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to deserialize recorded event: {}", e)))?;
+        events.push((DateTime::from_timestamp_nanos(recorded.time), recorded.event));
+    }
+    Ok(events)
+}
+
+/// Writes the events sent to the strategy to the recording file before forwarding them to the strategy.
+pub(crate) struct SessionRecorder;
+
+impl SessionRecorder {
+    /// Wraps the strategy sender, the returned sender is used by the engine in place of the strategy sender.
+    /// If the file can not be created the strategy sender is returned and the session is not recorded.
+    pub(crate) fn start(path: &Path, strategy_sender: mpsc::Sender<StrategyEvent>) -> mpsc::Sender<StrategyEvent> {
+        let mut writer = match File::create(path) {
+            Ok(file) => BufWriter::new(file),
+            Err(e) => {
+                tracing::error!("Session Recording: Failed to create {}: {}", path.display(), e);
+                return strategy_sender;
+            }
+        };
+        let mut header = RECORDING_MAGIC.to_vec();
+        header.extend_from_slice(&RECORDING_VERSION.to_le_bytes());
+        if let Err(e) = writer.write_all(&header) {
+            tracing::error!("Session Recording: Failed to write {}: {}", path.display(), e);
+            return strategy_sender;
+        }
+
+        let (sender, mut receiver) = mpsc::channel::<StrategyEvent>(strategy_sender.max_capacity());
+        let path = path.to_path_buf();
+        tokio::task::spawn(async move {
+            let mut is_recording = true;
+            while let Some(event) = receiver.recv().await {
+                if is_recording {
+                    let recorded = RecordedEvent { time: Utc::now().timestamp_nanos_opt().unwrap(), event };
+                    let bytes = rkyv::to_bytes::<_, 1024>(&recorded).unwrap();
+                    let mut write = || -> std::io::Result<()> {
+                        writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+                        writer.write_all(&bytes)?;
+                        // Flushed whenever the strategy has caught up, so the file is complete up to the latest event without a write per event.
+                        if receiver.is_empty() {
+                            writer.flush()?;
+                        }
+                        Ok(())
+                    };
+                    if let Err(e) = write() {
+                        tracing::error!("Session Recording: Failed to write {}, recording stopped: {}", path.display(), e);
+                        is_recording = false;
+                    }
+                    if strategy_sender.send(recorded.event).await.is_err() {
+                        break;
+                    }
+                } else if strategy_sender.send(event).await.is_err() {
+                    break;
+                }
+            }
+            let _ = writer.flush();
+        });
+        sender
+    }
+}
+
+/// Sends the events of the recording to the strategy, then shuts the strategy down.
+pub(crate) async fn launch_session_replay(
+    replay: SessionReplay,
+    strategy_event_sender: mpsc::Sender<StrategyEvent>,
+    market_price_service: Arc<MarketPriceService>,
+    timed_event_handler: Arc<TimedEventHandler>,
+) {
+    tokio::task::spawn(async move {
+        let message = match read_session_recording(&replay.path) {
+            Ok(events) => {
+                tracing::info!("Session Replay: Replaying {} events from {}", events.len(), replay.path.display());
+                let mut previous: Option<DateTime<Utc>> = None;
+                for (time, event) in events {
+                    // The shutdown of the recorded session is replaced by the shutdown at the end of the replay.
+                    if let StrategyEvent::ShutdownEvent(_) = event {
+                        continue;
+                    }
+                    if let Some(delay) = previous.and_then(|previous| replay.delay(previous, time)) {
+                        tokio::time::sleep(delay).await;
+                    }
+                    previous = Some(time);
+                    update_backtest_time(time);
+                    if let StrategyEvent::TimeSlice(time_slice) = &event {
+                        market_price_service.update_market_data(Arc::new(time_slice.clone()));
+                    }
+                    if let Err(e) = strategy_event_sender.send(event).await {
+                        tracing::error!("Session Replay: Failed to send event: {}", e);
+                        return;
+                    }
+                }
+                "Session Replay Complete".to_string()
+            }
+            Err(e) => {
+                tracing::error!("Session Replay: {}", e);
+                format!("Session Replay Failed: {}", e)
+            }
+        };
+        timed_event_handler.shutdown(message.clone()).await;
+        if let Err(e) = strategy_event_sender.send(StrategyEvent::ShutdownEvent(message)).await {
+            tracing::error!("Session Replay: Failed to send event: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_session_recording() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.ffsr");
+        let (strategy_sender, mut strategy_receiver) = mpsc::channel(10);
+        let engine_sender = SessionRecorder::start(&path, strategy_sender);
+        let events = vec![
+            StrategyEvent::WarmUpComplete,
+            StrategyEvent::TimedEvent("Open".to_string()),
+            StrategyEvent::ShutdownEvent("Stopped".to_string()),
+        ];
+        for event in events.clone() {
+            engine_sender.send(event).await.unwrap();
+        }
+        drop(engine_sender);
+
+        // Every event is forwarded to the strategy, the recording is complete once the recorder stops.
+        let mut forwarded = vec![];
+        while let Some(event) = strategy_receiver.recv().await {
+            forwarded.push(event);
+        }
+        assert_eq!(forwarded, events);
+        let recorded = read_session_recording(&path).unwrap();
+        assert_eq!(recorded.iter().map(|(_, event)| event.clone()).collect::<Vec<_>>(), events);
+        assert!(recorded.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+        // A partly written event at the end is ignored.
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.extend_from_slice(&100u32.to_le_bytes());
+        bytes.extend_from_slice(&[1, 2, 3]);
+        std::fs::write(&path, bytes).unwrap();
+        assert_eq!(read_session_recording(&path).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_replay_delay() {
+        let previous = Utc.with_ymd_and_hms(2024, 6, 3, 13, 30, 0).unwrap();
+        let time = previous + chrono::Duration::seconds(10);
+        assert_eq!(SessionReplay::new("session.ffsr").delay(previous, time), Some(Duration::from_secs(10)));
+        assert_eq!(SessionReplay::new("session.ffsr").with_speed(4.0).delay(previous, time), Some(Duration::from_millis(2500)));
+        assert_eq!(SessionReplay::new("session.ffsr").without_delay().delay(previous, time), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::fs::{self, create_dir_all};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::history::get_data_version;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::ledgers::ledger::TradeExport;
+use crate::strategies::ledgers::ledger_service::LedgerService;
+use crate::strategies::statistics::backtest_comparison::BacktestRun;
+
+static BACKTEST_CACHE: OnceLock<BacktestCache> = OnceLock::new();
+
+/// Enables the backtest results cache, this must be called before `FundForgeStrategy::initialize()`.
+///
+/// When the backtest starts, the engine hashes the `parameters`, the strategy subscriptions, the date range including the warm up,
+/// the version of the data on the server and the version of the strategy executable.
+/// If a run with an identical hash is found in the `folder`, the backtest is skipped and the strategy receives the `StrategyEvent::ShutdownEvent` immediately,
+/// the cached results are printed and can be accessed with `strategy.cached_backtest()`.
+/// Otherwise the results are saved to the `folder` when the backtest completes.
+///
+/// `parameters` should describe any strategy inputs which are not subscriptions, eg. "fast=10;slow=50;stop=2.5".
+pub fn enable_backtest_cache(folder: &str, parameters: &str) -> Result<(), FundForgeError> {
+    create_dir_all(folder)
+        .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create directory {}: {}", folder, e)))?;
+    BACKTEST_CACHE.set(BacktestCache::new(folder, parameters))
+        .map_err(|_| FundForgeError::ClientSideErrorDebug("The backtest cache is already enabled".to_string()))
+}
+
+pub(crate) fn backtest_cache() -> Option<&'static BacktestCache> {
+    BACKTEST_CACHE.get()
+}
+
+/// The cached results used instead of running the backtest, `None` if the cache is disabled or the backtest was not found in the cache.
+pub fn cached_backtest() -> Option<CachedBacktest> {
+    BACKTEST_CACHE.get().and_then(|cache| cache.hit.get().cloned())
+}
+
+/// FNV-1a, used where the hash must be the same between builds and machines.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// The version of the strategy code, based on the length and modified time of the running executable, so rebuilding the strategy invalidates the cache.
+fn code_version() -> u64 {
+    let metadata = std::env::current_exe().and_then(fs::metadata);
+    match metadata {
+        Ok(metadata) => {
+            let modified = metadata.modified().ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos())
+                .unwrap_or_default();
+            stable_hash(format!("{}|{}", metadata.len(), modified).as_bytes())
+        }
+        Err(_) => 0
+    }
+}
+
+/// Everything which determines the results of a backtest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestCacheKey {
+    pub parameters: String,
+    /// The subscriptions as strings, sorted so the key does not depend on the order of subscribing.
+    pub subscriptions: Vec<String>,
+    pub start: String,
+    pub end: String,
+    pub data_version: u64,
+    pub code_version: u64,
+}
+
+impl BacktestCacheKey {
+    pub fn new(parameters: &str, subscriptions: &[DataSubscription], start: DateTime<Utc>, end: DateTime<Utc>, data_version: u64, code_version: u64) -> Self {
+        let mut subscriptions: Vec<String> = subscriptions.iter().map(|subscription| subscription.to_string()).collect();
+        subscriptions.sort();
+        subscriptions.dedup();
+        Self {
+            parameters: parameters.to_string(),
+            subscriptions,
+            start: start.to_string(),
+            end: end.to_string(),
+            data_version,
+            code_version,
+        }
+    }
+
+    pub fn hash(&self) -> String {
+        let key = serde_json::to_string(self).unwrap_or_default();
+        format!("{:016x}", stable_hash(key.as_bytes()))
+    }
+}
+
+/// The results of a single account in a cached backtest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedAccountResults {
+    pub ledger_statistics: String,
+    pub trade_statistics: String,
+    pub trades: Vec<TradeExport>,
+}
+
+/// The results of a backtest loaded from the cache, keyed by "{brokerage}_{account_id}".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedBacktest {
+    pub key: BacktestCacheKey,
+    pub saved_at: String,
+    pub accounts: BTreeMap<String, CachedAccountResults>,
+}
+
+impl CachedBacktest {
+    /// The trades of the account as a `BacktestRun` so cached runs can be compared, see `BacktestComparison`.
+    pub fn run(&self, account: &str) -> Option<BacktestRun> {
+        self.accounts.get(account)
+            .map(|results| BacktestRun::new(format!("{}_{}", account, self.key.hash()), results.trades.clone()))
+    }
+
+    pub fn print(&self) {
+        for (account, results) in &self.accounts {
+            tracing::info!("Cached Backtest {}: {}", self.key.hash(), account);
+            tracing::info!("{}", results.ledger_statistics);
+            tracing::info!("{}", results.trade_statistics);
+        }
+    }
+}
+
+pub(crate) struct BacktestCache {
+    folder: PathBuf,
+    parameters: String,
+    hit: OnceLock<CachedBacktest>,
+}
+
+impl BacktestCache {
+    fn new(folder: &str, parameters: &str) -> Self {
+        Self {
+            folder: PathBuf::from(folder),
+            parameters: parameters.to_string(),
+            hit: OnceLock::new(),
+        }
+    }
+
+    fn path(&self, key: &BacktestCacheKey) -> PathBuf {
+        self.folder.join(format!("{}.json", key.hash()))
+    }
+
+    /// Creates the key for the run, requesting the version of the primary subscription data from the server.
+    pub(crate) async fn key(&self, subscriptions: &[DataSubscription], primary_subscriptions: Vec<DataSubscription>, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<BacktestCacheKey, FundForgeError> {
+        let data_version = get_data_version(primary_subscriptions, start, end).await?;
+        Ok(BacktestCacheKey::new(&self.parameters, subscriptions, start, end, data_version, code_version()))
+    }
+
+    /// Loads the cached results for the key, returns true if they were found.
+    pub(crate) fn load(&self, key: &BacktestCacheKey) -> bool {
+        let path = self.path(key);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => return false
+        };
+        match serde_json::from_str::<CachedBacktest>(&content) {
+            Ok(cached) if &cached.key == key => {
+                let _ = self.hit.set(cached);
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                tracing::error!("Backtest Cache: Failed to parse {}: {}", path.display(), e);
+                false
+            }
+        }
+    }
+
+    /// Saves the results of each ledger under the key.
+    pub(crate) fn store(&self, key: &BacktestCacheKey, ledger_service: &LedgerService) -> Result<(), FundForgeError> {
+        let mut accounts = BTreeMap::new();
+        for ledger in ledger_service.ledgers.iter() {
+            let account = ledger.key();
+            accounts.insert(format!("{}_{}", account.brokerage, account.account_id), CachedAccountResults {
+                ledger_statistics: ledger.ledger_statistics_to_string(),
+                trade_statistics: ledger.trade_statistics_to_string(),
+                trades: ledger.trade_exports(),
+            });
+        }
+        let cached = CachedBacktest {
+            key: key.clone(),
+            saved_at: Utc::now().to_string(),
+            accounts,
+        };
+        let path = self.path(key);
+        let content = serde_json::to_string_pretty(&cached)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to serialize backtest results: {}", e)))?;
+        fs::write(&path, content)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_key_hash_changes_with_inputs() {
+        let start = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 6, 30, 0, 0, 0).unwrap();
+        let key = BacktestCacheKey::new("fast=10;slow=50", &[], start, end, 1, 2);
+        assert_eq!(key.hash(), BacktestCacheKey::new("fast=10;slow=50", &[], start, end, 1, 2).hash());
+        assert_ne!(key.hash(), BacktestCacheKey::new("fast=10;slow=40", &[], start, end, 1, 2).hash());
+        assert_ne!(key.hash(), BacktestCacheKey::new("fast=10;slow=50", &[], start, end, 3, 2).hash());
+        assert_eq!(stable_hash(b"fund forge"), stable_hash(b"fund forge"));
+        assert_ne!(stable_hash(b"fund forge"), stable_hash(b"fund forgf"));
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as FmtWrite;
+use std::fs::{create_dir_all, read_to_string};
+use std::path::Path;
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use csv::{Reader, Writer};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::strategies::ledgers::ledger::TradeExport;
+
+/// The trades of a single backtest, loaded from a file created by `strategy.export_trades_to_csv()` or `strategy.export_trades()`.
+#[derive(Debug, Clone)]
+pub struct BacktestRun {
+    pub name: String,
+    pub trades: Vec<TradeExport>,
+}
+
+impl BacktestRun {
+    pub fn new(name: String, mut trades: Vec<TradeExport>) -> Self {
+        trades.sort_by(|a, b| a.exit_time.cmp(&b.exit_time));
+        Self {
+            name,
+            trades
+        }
+    }
+
+    /// Loads a run from a trades csv file, the name is used to label the run in reports.
+    pub fn from_trades_csv(name: &str, path: &Path) -> Result<Self, FundForgeError> {
+        let mut reader = Reader::from_path(path)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to open {}: {}", path.display(), e)))?;
+        let mut trades = vec![];
+        for record in reader.deserialize() {
+            let trade: TradeExport = record
+                .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse trade in {}: {}", path.display(), e)))?;
+            trades.push(trade);
+        }
+        Ok(Self::new(name.to_string(), trades))
+    }
+
+    /// Loads a run from a file created by `strategy.export_trades()` with `TradeExportFormat::RoundTrip`.
+    pub fn from_trades_json(name: &str, path: &Path) -> Result<Self, FundForgeError> {
+        let contents = read_to_string(path)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to open {}: {}", path.display(), e)))?;
+        let trades: Vec<TradeExport> = serde_json::from_str(&contents)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to parse trades in {}: {}", path.display(), e)))?;
+        Ok(Self::new(name.to_string(), trades))
+    }
+
+    pub fn total_pnl(&self) -> Decimal {
+        self.trades.iter().map(|trade| trade.pnl).sum()
+    }
+
+    pub fn win_rate(&self) -> Decimal {
+        if self.trades.is_empty() {
+            return dec!(0)
+        }
+        let wins = self.trades.iter().filter(|trade| trade.pnl > dec!(0)).count();
+        (Decimal::from(wins) / Decimal::from(self.trades.len()) * dec!(100)).round_dp(2)
+    }
+
+    /// The cumulative pnl at the exit time of each trade.
+    pub fn equity_curve(&self) -> Vec<(DateTime<Utc>, Decimal)> {
+        let mut running_pnl = dec!(0);
+        let mut curve = Vec::with_capacity(self.trades.len());
+        for trade in &self.trades {
+            running_pnl += trade.pnl;
+            if let Ok(time) = DateTime::<Utc>::from_str(&trade.exit_time) {
+                curve.push((time, running_pnl));
+            }
+        }
+        curve
+    }
+
+    pub fn max_drawdown(&self) -> Decimal {
+        let mut peak = dec!(0);
+        let mut max_drawdown = dec!(0);
+        for (_, equity) in self.equity_curve() {
+            if equity > peak {
+                peak = equity;
+            }
+            max_drawdown = max_drawdown.max(peak - equity);
+        }
+        max_drawdown
+    }
+
+    /// Booked pnl grouped by the month of the trade exit, keyed as "YYYY-MM".
+    pub fn monthly_pnl(&self) -> BTreeMap<String, Decimal> {
+        let mut months = BTreeMap::new();
+        for trade in &self.trades {
+            if let Ok(time) = DateTime::<Utc>::from_str(&trade.exit_time) {
+                *months.entry(time.format("%Y-%m").to_string()).or_insert(dec!(0)) += trade.pnl;
+            }
+        }
+        months
+    }
+
+    fn match_key(trade: &TradeExport) -> (String, String, String, String) {
+        (trade.symbol_code.clone(), trade.tag.clone(), trade.side.clone(), trade.entry_time.clone())
+    }
+}
+
+/// A trade from the baseline run paired with the trade from another run which has the same symbol, tag, side and entry time.
+#[derive(Debug, Clone)]
+pub struct TradeMatch {
+    pub baseline: TradeExport,
+    pub other: TradeExport,
+    pub pnl_difference: Decimal,
+}
+
+/// The result of matching the trades of the baseline run against another run.
+#[derive(Debug, Clone)]
+pub struct TradeMatchResult {
+    pub run_name: String,
+    /// Trades that exist in both runs but the exit or pnl changed.
+    pub changed: Vec<TradeMatch>,
+    pub unchanged: usize,
+    /// Trades the baseline took which the other run did not.
+    pub baseline_only: Vec<TradeExport>,
+    /// Trades the other run took which the baseline did not.
+    pub other_only: Vec<TradeExport>,
+}
+
+/// Compares 2 or more backtest runs, the first run is treated as the baseline.
+/// Useful for seeing where parameter or code changes altered the behaviour of a strategy.
+/// ```rust
+/// use std::path::Path;
+/// use ff_standard_lib::strategies::statistics::backtest_comparison::{BacktestComparison, BacktestRun};
+///
+/// fn compare() {
+///     let baseline = BacktestRun::from_trades_csv("baseline", Path::new("./trades/baseline.csv")).unwrap();
+///     let changed = BacktestRun::from_trades_csv("new_stop", Path::new("./trades/new_stop.csv")).unwrap();
+///     let comparison = BacktestComparison::new(vec![baseline, changed]).unwrap();
+///     println!("{}", comparison.report());
+///     comparison.export_equity_curves_csv("./comparison").unwrap();
+/// }
+/// ```
+pub struct BacktestComparison {
+    runs: Vec<BacktestRun>,
+}
+
+impl BacktestComparison {
+    pub fn new(runs: Vec<BacktestRun>) -> Result<Self, FundForgeError> {
+        if runs.len() < 2 {
+            return Err(FundForgeError::ClientSideErrorDebug("A comparison requires at least 2 backtest runs".to_string()));
+        }
+        Ok(Self {
+            runs
+        })
+    }
+
+    pub fn runs(&self) -> &Vec<BacktestRun> {
+        &self.runs
+    }
+
+    /// The monthly pnl of each run, in the order the runs were passed in, months where a run had no trades are 0.
+    pub fn monthly_pnl(&self) -> BTreeMap<String, Vec<Decimal>> {
+        let run_months: Vec<BTreeMap<String, Decimal>> = self.runs.iter().map(|run| run.monthly_pnl()).collect();
+        let all_months: BTreeSet<String> = run_months.iter().flat_map(|months| months.keys().cloned()).collect();
+        all_months.into_iter()
+            .map(|month| {
+                let values = run_months.iter().map(|months| months.get(&month).cloned().unwrap_or(dec!(0))).collect();
+                (month, values)
+            })
+            .collect()
+    }
+
+    /// Matches the trades of each run against the baseline (the first run).
+    pub fn match_trades(&self) -> Vec<TradeMatchResult> {
+        let baseline = &self.runs[0];
+        let mut results = vec![];
+        for run in self.runs.iter().skip(1) {
+            let mut other_trades: BTreeMap<(String, String, String, String), Vec<&TradeExport>> = BTreeMap::new();
+            for trade in &run.trades {
+                other_trades.entry(BacktestRun::match_key(trade)).or_default().push(trade);
+            }
+
+            let mut result = TradeMatchResult {
+                run_name: run.name.clone(),
+                changed: vec![],
+                unchanged: 0,
+                baseline_only: vec![],
+                other_only: vec![],
+            };
+            for trade in &baseline.trades {
+                let matched = other_trades.get_mut(&BacktestRun::match_key(trade)).and_then(|trades| {
+                    match trades.is_empty() {
+                        true => None,
+                        false => Some(trades.remove(0))
+                    }
+                });
+                match matched {
+                    None => result.baseline_only.push(trade.clone()),
+                    Some(other) => {
+                        if other.pnl == trade.pnl && other.exit_time == trade.exit_time && other.exit_price == trade.exit_price {
+                            result.unchanged += 1;
+                        } else {
+                            result.changed.push(TradeMatch {
+                                baseline: trade.clone(),
+                                other: other.clone(),
+                                pnl_difference: other.pnl - trade.pnl,
+                            });
+                        }
+                    }
+                }
+            }
+            for trades in other_trades.into_values() {
+                for trade in trades {
+                    result.other_only.push(trade.clone());
+                }
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    /// Writes the equity curves of all runs to a single csv file in the folder, so they can be overlaid in a spreadsheet or charting tool.
+    /// Each row is a trade exit time, each run has a column with its cumulative pnl at that time.
+    pub fn export_equity_curves_csv(&self, folder: &str) -> Result<(), FundForgeError> {
+        create_dir_all(folder)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create directory {}: {}", folder, e)))?;
+        let date = Utc::now().format("%Y%m%d_%H%M").to_string();
+        let file_name = format!("{}/Comparison_EquityCurves_{}.csv", folder, date);
+        let mut writer = Writer::from_path(&file_name)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create CSV writer for {}: {}", file_name, e)))?;
+
+        let mut header = vec!["time".to_string()];
+        header.extend(self.runs.iter().map(|run| run.name.clone()));
+        writer.write_record(&header)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(e.to_string()))?;
+
+        let mut points: BTreeMap<DateTime<Utc>, Vec<Option<Decimal>>> = BTreeMap::new();
+        for (index, run) in self.runs.iter().enumerate() {
+            for (time, equity) in run.equity_curve() {
+                let row = points.entry(time).or_insert_with(|| vec![None; self.runs.len()]);
+                row[index] = Some(equity);
+            }
+        }
+
+        // carry the last equity value forward so every column has a value on every row
+        let mut last_values = vec![dec!(0); self.runs.len()];
+        for (time, row) in points {
+            let mut record = vec![time.to_string()];
+            for (index, value) in row.into_iter().enumerate() {
+                if let Some(value) = value {
+                    last_values[index] = value;
+                }
+                record.push(last_values[index].to_string());
+            }
+            writer.write_record(&record)
+                .map_err(|e| FundForgeError::ClientSideErrorDebug(e.to_string()))?;
+        }
+        writer.flush()
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(e.to_string()))?;
+        tracing::info!("Successfully exported equity curves to {}", file_name);
+        Ok(())
+    }
+
+    pub fn report(&self) -> String {
+        let mut report = String::from("\nBacktest Comparison:\n");
+        for run in &self.runs {
+            writeln!(
+                report,
+                "{}: Trades: {}, Total PnL: {}, Win Rate: {}%, Max Drawdown: {}",
+                run.name,
+                run.trades.len(),
+                run.total_pnl().round_dp(2),
+                run.win_rate(),
+                run.max_drawdown().round_dp(2)
+            ).unwrap();
+        }
+
+        let baseline_name = &self.runs[0].name;
+        writeln!(report, "\nMonthly PnL (difference vs {}):", baseline_name).unwrap();
+        for (month, values) in self.monthly_pnl() {
+            let baseline = values[0];
+            let mut line = format!("{}: {}: {}", month, baseline_name, baseline.round_dp(2));
+            for (run, value) in self.runs.iter().zip(values.iter()).skip(1) {
+                write!(line, ", {}: {} ({:+})", run.name, value.round_dp(2), (*value - baseline).round_dp(2)).unwrap();
+            }
+            writeln!(report, "{}", line).unwrap();
+        }
+
+        for result in self.match_trades() {
+            writeln!(
+                report,
+                "\nTrades {} vs {}: Unchanged: {}, Changed: {}, Only in {}: {}, Only in {}: {}",
+                result.run_name,
+                baseline_name,
+                result.unchanged,
+                result.changed.len(),
+                baseline_name,
+                result.baseline_only.len(),
+                result.run_name,
+                result.other_only.len()
+            ).unwrap();
+            for changed in &result.changed {
+                writeln!(
+                    report,
+                    "  Changed: {} {} {} entry: {}, exit: {} -> {}, pnl: {} -> {} ({:+})",
+                    changed.baseline.symbol_code,
+                    changed.baseline.side,
+                    changed.baseline.tag,
+                    changed.baseline.entry_time,
+                    changed.baseline.exit_time,
+                    changed.other.exit_time,
+                    changed.baseline.pnl.round_dp(2),
+                    changed.other.pnl.round_dp(2),
+                    changed.pnl_difference.round_dp(2)
+                ).unwrap();
+            }
+            for trade in &result.baseline_only {
+                writeln!(report, "  Only in {}: {} {} {} entry: {}, pnl: {}", baseline_name, trade.symbol_code, trade.side, trade.tag, trade.entry_time, trade.pnl.round_dp(2)).unwrap();
+            }
+            for trade in &result.other_only {
+                writeln!(report, "  Only in {}: {} {} {} entry: {}, pnl: {}", result.run_name, trade.symbol_code, trade.side, trade.tag, trade.entry_time, trade.pnl.round_dp(2)).unwrap();
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::ledgers::trade_formats::round_trip_json;
+
+    fn trade(tag: &str, entry_time: &str, exit_time: &str, pnl: Decimal) -> TradeExport {
+        TradeExport {
+            symbol_code: "NQZ4".to_string(),
+            position_id: "Long-1".to_string(),
+            side: "Long".to_string(),
+            entry_price: dec!(17500),
+            entry_quantity: dec!(1),
+            exit_price: dec!(17550),
+            exit_quantity: dec!(1),
+            entry_time: entry_time.to_string(),
+            exit_time: exit_time.to_string(),
+            pnl,
+            tag: tag.to_string(),
+            result: "Win".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_match_trades_and_monthly_pnl() {
+        let baseline = BacktestRun::new("baseline".to_string(), vec![
+            trade("Enter Long", "2024-01-02 14:00:00 UTC", "2024-01-02 15:00:00 UTC", dec!(100)),
+            trade("Enter Long", "2024-02-02 14:00:00 UTC", "2024-02-02 15:00:00 UTC", dec!(-50)),
+        ]);
+        let changed = BacktestRun::new("changed".to_string(), vec![
+            trade("Enter Long", "2024-01-02 14:00:00 UTC", "2024-01-02 15:00:00 UTC", dec!(100)),
+            trade("Enter Long", "2024-02-02 14:00:00 UTC", "2024-02-02 14:30:00 UTC", dec!(-20)),
+            trade("Enter Long", "2024-02-05 14:00:00 UTC", "2024-02-05 15:00:00 UTC", dec!(30)),
+        ]);
+        let comparison = BacktestComparison::new(vec![baseline, changed]).unwrap();
+
+        let results = comparison.match_trades();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].unchanged, 1);
+        assert_eq!(results[0].changed.len(), 1);
+        assert_eq!(results[0].changed[0].pnl_difference, dec!(30));
+        assert!(results[0].baseline_only.is_empty());
+        assert_eq!(results[0].other_only.len(), 1);
+
+        let monthly = comparison.monthly_pnl();
+        assert_eq!(monthly.get("2024-01").unwrap(), &vec![dec!(100), dec!(100)]);
+        assert_eq!(monthly.get("2024-02").unwrap(), &vec![dec!(-50), dec!(10)]);
+    }
+
+    #[test]
+    fn test_from_trades_json() {
+        let trades = vec![
+            trade("Enter Long", "2024-01-02 14:00:00 UTC", "2024-01-02 15:00:00 UTC", dec!(100.125)),
+            trade("Enter Long", "2024-02-02 14:00:00 UTC", "2024-02-02 15:00:00 UTC", dec!(-50)),
+        ];
+        let folder = std::env::temp_dir().join("ff_test_from_trades_json");
+        create_dir_all(&folder).unwrap();
+        let path = folder.join("trades.json");
+        std::fs::write(&path, round_trip_json(&trades).unwrap()).unwrap();
+
+        let run = BacktestRun::from_trades_json("round_trip", &path).unwrap();
+        assert_eq!(run.trades, trades);
+        assert_eq!(run.total_pnl(), dec!(50.125));
+        let _ = std::fs::remove_dir_all(folder);
+    }
+}
@@ -0,0 +1,473 @@
+use std::fs::{create_dir_all, write};
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::indicators::indicator_values::IndicatorValues;
+use crate::strategies::indicators::indicators_trait::IndicatorName;
+use crate::strategies::ledgers::ledger::TradeExport;
+
+#[derive(Debug, Clone, PartialEq)]
+struct ChartBar {
+    open_time: DateTime<Utc>,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: f64,
+}
+
+#[derive(Debug, Clone)]
+struct ChartIndicator {
+    name: IndicatorName,
+    values: Vec<IndicatorValues>,
+    overlay: bool,
+}
+
+/// A self-contained html chart of what a strategy did over a range of bars, for quick visual inspection of a backtest.
+/// The chart shows the candles, the entry and exit of each trade with its tag, and the plots of the selected indicators.
+/// The data and the drawing script are embedded in the file, so it opens in any browser without a network connection.
+///
+/// Trades and indicator values are drawn on the bar which contains their time, a time equal to the close of a bar is drawn on that bar.
+/// Use the mouse wheel to zoom, drag to scroll and hover a bar to see its values and trades.
+/// ```rust,ignore
+/// let export = ChartExport::new(subscription.clone(), history.values())
+///     .with_trades(trades)
+///     .with_indicator(atr.name(), atr_values, false);
+/// export.export_html("./charts")?;
+/// ```
+/// Strategies can use `strategy.export_chart_html()` to build the chart from the strategy history, ledger and indicators.
+#[derive(Debug, Clone)]
+pub struct ChartExport {
+    subscription: DataSubscription,
+    bars: Vec<ChartBar>,
+    trades: Vec<TradeExport>,
+    indicators: Vec<ChartIndicator>,
+}
+
+impl ChartExport {
+    /// Creates the chart from candles or quote bars, quote bars are drawn using the mid prices. Other data types are ignored.
+    pub fn new<'a>(subscription: DataSubscription, data: impl Iterator<Item = &'a BaseDataEnum>) -> Self {
+        let mut bars: Vec<ChartBar> = data.filter_map(|base_data| {
+            let (open, high, low, close, volume) = match base_data {
+                BaseDataEnum::Candle(candle) => (candle.open, candle.high, candle.low, candle.close, candle.volume),
+                BaseDataEnum::QuoteBar(bar) => (
+                    (bar.bid_open + bar.ask_open) / Decimal::TWO,
+                    (bar.bid_high + bar.ask_high) / Decimal::TWO,
+                    (bar.bid_low + bar.ask_low) / Decimal::TWO,
+                    (bar.bid_close + bar.ask_close) / Decimal::TWO,
+                    bar.volume
+                ),
+                _ => return None
+            };
+            Some(ChartBar {
+                open_time: base_data.time_utc(),
+                open: to_f64(open),
+                high: to_f64(high),
+                low: to_f64(low),
+                close: to_f64(close),
+                volume: to_f64(volume),
+            })
+        }).collect();
+        bars.sort_by_key(|bar| bar.open_time);
+        Self {
+            subscription,
+            bars,
+            trades: vec![],
+            indicators: vec![],
+        }
+    }
+
+    /// Adds the trades to the chart, trades with no entry or exit inside the range of the bars are not drawn.
+    pub fn with_trades(mut self, trades: Vec<TradeExport>) -> Self {
+        self.trades.extend(trades);
+        self
+    }
+
+    /// Adds an indicator, overlays are drawn on the price scale, other indicators are drawn in a pane below the candles.
+    pub fn with_indicator(mut self, name: &str, values: Vec<IndicatorValues>, overlay: bool) -> Self {
+        self.indicators.push(ChartIndicator {
+            name: name.to_string(),
+            values,
+            overlay,
+        });
+        self
+    }
+
+    /// The index of the bar which contains the time, the bar where `open_time < time <= close_time`.
+    fn bar_index(&self, time: DateTime<Utc>) -> Option<usize> {
+        let first = self.bars.first()?;
+        let last = self.bars.last()?;
+        if time < first.open_time || time > last.open_time + self.subscription.resolution.as_duration() {
+            return None;
+        }
+        Some(self.bars.partition_point(|bar| bar.open_time < time).saturating_sub(1))
+    }
+
+    fn markers(&self) -> Vec<Value> {
+        let mut markers = vec![];
+        for trade in &self.trades {
+            let long = trade.side == "Long";
+            let points = [
+                (&trade.entry_time, trade.entry_price, trade.entry_quantity, true),
+                (&trade.exit_time, trade.exit_price, trade.exit_quantity, false),
+            ];
+            for (time, price, quantity, entry) in points {
+                let index = match DateTime::<Utc>::from_str(time).ok().and_then(|time| self.bar_index(time)) {
+                    Some(index) => index,
+                    None => continue
+                };
+                markers.push(json!({
+                    "bar": index,
+                    "price": to_f64(price),
+                    "entry": entry,
+                    "buy": long == entry,
+                    "label": format!("{} {} {} @ {}", if entry { "Entry" } else { "Exit" }, trade.side, quantity, price),
+                    "tag": trade.tag,
+                    "pnl": if entry { Value::Null } else { json!(trade.pnl.to_string()) },
+                    "time": time,
+                }));
+            }
+        }
+        markers
+    }
+
+    fn indicator_series(&self) -> Vec<Value> {
+        let mut series = vec![];
+        for indicator in &self.indicators {
+            let mut plots: Vec<(String, String, Vec<Value>)> = vec![];
+            for values in &indicator.values {
+                let index = match DateTime::<Utc>::from_str(&values.time).ok().and_then(|time| self.bar_index(time)) {
+                    Some(index) => index,
+                    None => continue
+                };
+                for (plot_name, plot) in &values.plots {
+                    let color = format!("rgb({},{},{})", plot.color.red, plot.color.green, plot.color.blue);
+                    let point = json!([index, to_f64(plot.value)]);
+                    match plots.iter_mut().find(|(name, ..)| name == plot_name) {
+                        Some((_, _, points)) => points.push(point),
+                        None => plots.push((plot_name.clone(), color, vec![point])),
+                    }
+                }
+            }
+            for (plot_name, color, points) in plots {
+                series.push(json!({
+                    "name": format!("{} {}", indicator.name, plot_name),
+                    "color": color,
+                    "overlay": indicator.overlay,
+                    "points": points,
+                }));
+            }
+        }
+        series
+    }
+
+    /// The chart data embedded in the html file.
+    fn data(&self) -> Value {
+        let bars: Vec<Value> = self.bars.iter()
+            .map(|bar| json!([bar.open_time.format("%Y-%m-%d %H:%M:%S").to_string(), bar.open, bar.high, bar.low, bar.close, bar.volume]))
+            .collect();
+        json!({
+            "title": data_title(&self.subscription),
+            "bars": bars,
+            "markers": self.markers(),
+            "indicators": self.indicator_series(),
+        })
+    }
+
+    /// Returns the chart as a self-contained html document.
+    pub fn to_html(&self) -> String {
+        let data = self.data();
+        // Escape `<` so no value can close the script element.
+        let data = data.to_string().replace('<', "\\u003c");
+        let title = html_escape(data_title(&self.subscription).as_str());
+        CHART_TEMPLATE
+            .replace("{{TITLE}}", &title)
+            .replace("{{DATA}}", &data)
+    }
+
+    /// Writes the chart to a html file in the folder and returns the file path.
+    pub fn export_html(&self, folder: &str) -> Result<String, FundForgeError> {
+        if self.bars.is_empty() {
+            return Err(FundForgeError::ClientSideErrorDebug(format!("No candles or quote bars to chart for {}", self.subscription)));
+        }
+        create_dir_all(folder)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create directory {}: {}", folder, e)))?;
+        let date = Utc::now().format("%Y%m%d_%H%M").to_string();
+        let file_name = format!("{}/Chart_{}_{}_{}.html", folder, self.subscription.symbol.name, self.subscription.resolution, date);
+        write(&file_name, self.to_html())
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to write chart {}: {}", file_name, e)))?;
+        tracing::info!("Successfully exported chart to {}", file_name);
+        Ok(file_name)
+    }
+}
+
+fn data_title(subscription: &DataSubscription) -> String {
+    format!("{} {}", subscription.symbol.name, subscription.resolution)
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+fn html_escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+const CHART_TEMPLATE: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{TITLE}}</title>
+<style>
+body { margin: 0; background: #111418; color: #d8dde3; font: 12px monospace; }
+#header { padding: 6px 10px; }
+#chart { display: block; width: 100vw; height: calc(100vh - 60px); cursor: crosshair; }
+#info { padding: 4px 10px; white-space: pre; height: 18px; }
+</style>
+</head>
+<body>
+<div id="header"></div>
+<canvas id="chart"></canvas>
+<div id="info"></div>
+<script>
+const data = {{DATA}};
+const canvas = document.getElementById("chart");
+const ctx = canvas.getContext("2d");
+const info = document.getElementById("info");
+document.getElementById("header").textContent = data.title + "  |  " + data.bars.length + " bars  |  " + data.markers.length + " fills  |  " + data.indicators.map(s => s.name).join(", ");
+const lowerSeries = data.indicators.filter(s => !s.overlay);
+const scale = 58;
+let barWidth = 8;
+let end = data.bars.length;
+let hover = null;
+let drag = null;
+
+function visible(width) {
+  const count = Math.max(1, Math.floor((width - scale) / barWidth));
+  end = Math.min(Math.max(end, Math.min(count, data.bars.length)), data.bars.length);
+  return [Math.max(0, end - count), end];
+}
+
+function range(from, to, values) {
+  let low = Infinity, high = -Infinity;
+  for (const v of values(from, to)) { low = Math.min(low, v); high = Math.max(high, v); }
+  if (low === Infinity) { return [0, 1]; }
+  if (low === high) { low -= 1; high += 1; }
+  const pad = (high - low) * 0.05;
+  return [low - pad, high + pad];
+}
+
+function* priceValues(from, to) {
+  for (let i = from; i < to; i++) { yield data.bars[i][2]; yield data.bars[i][3]; }
+  for (const s of data.indicators) { if (s.overlay) { for (const p of s.points) { if (p[0] >= from && p[0] < to) { yield p[1]; } } } }
+}
+
+function* lowerValues(from, to) {
+  for (const s of lowerSeries) { for (const p of s.points) { if (p[0] >= from && p[0] < to) { yield p[1]; } } }
+}
+
+function axis(top, height, low, high, width) {
+  ctx.fillStyle = "#7d8590";
+  for (let i = 0; i <= 4; i++) {
+    const value = high - (high - low) * i / 4;
+    const y = top + height * i / 4;
+    ctx.fillRect(0, y, width - scale, 1);
+    ctx.fillText(value.toFixed(2), width - scale + 4, y + 4);
+  }
+}
+
+function lines(series, from, to, x, y) {
+  for (const s of series) {
+    ctx.strokeStyle = s.color;
+    ctx.beginPath();
+    let started = false;
+    for (const p of s.points) {
+      if (p[0] < from || p[0] >= to) { continue; }
+      if (started) { ctx.lineTo(x(p[0]), y(p[1])); } else { ctx.moveTo(x(p[0]), y(p[1])); started = true; }
+    }
+    ctx.stroke();
+  }
+}
+
+function draw() {
+  const width = canvas.width = canvas.clientWidth;
+  const height = canvas.height = canvas.clientHeight;
+  ctx.fillStyle = "#111418";
+  ctx.fillRect(0, 0, width, height);
+  ctx.font = "11px monospace";
+  const [from, to] = visible(width);
+  const priceHeight = lowerSeries.length > 0 ? height * 0.72 : height - 16;
+  const [low, high] = range(from, to, priceValues);
+  const x = i => (i - from) * barWidth + barWidth / 2;
+  const y = v => priceHeight - (v - low) / (high - low) * priceHeight;
+  ctx.globalAlpha = 0.35;
+  axis(0, priceHeight, low, high, width);
+  ctx.globalAlpha = 1;
+
+  for (let i = from; i < to; i++) {
+    const [, open, barHigh, barLow, close] = data.bars[i];
+    ctx.strokeStyle = ctx.fillStyle = close >= open ? "#26a69a" : "#ef5350";
+    ctx.beginPath();
+    ctx.moveTo(x(i), y(barHigh));
+    ctx.lineTo(x(i), y(barLow));
+    ctx.stroke();
+    const body = Math.max(1, Math.abs(y(open) - y(close)));
+    ctx.fillRect(x(i) - barWidth * 0.35, Math.min(y(open), y(close)), Math.max(1, barWidth * 0.7), body);
+  }
+  lines(data.indicators.filter(s => s.overlay), from, to, x, y);
+
+  for (const m of data.markers) {
+    if (m.bar < from || m.bar >= to) { continue; }
+    const px = x(m.bar), py = y(m.price), size = 6;
+    ctx.fillStyle = m.buy ? "#2196f3" : "#ff9800";
+    ctx.beginPath();
+    if (m.buy) { ctx.moveTo(px, py); ctx.lineTo(px - size, py + size * 1.6); ctx.lineTo(px + size, py + size * 1.6); }
+    else { ctx.moveTo(px, py); ctx.lineTo(px - size, py - size * 1.6); ctx.lineTo(px + size, py - size * 1.6); }
+    ctx.fill();
+    if (barWidth >= 6) {
+      ctx.fillText((m.entry ? "" : "x ") + m.tag, px + size + 2, m.buy ? py + size * 1.6 + 10 : py - size * 1.6 - 2);
+    }
+  }
+
+  if (lowerSeries.length > 0) {
+    const top = priceHeight + 12;
+    const lowerHeight = height - top - 16;
+    const [lowerLow, lowerHigh] = range(from, to, lowerValues);
+    ctx.globalAlpha = 0.35;
+    axis(top, lowerHeight, lowerLow, lowerHigh, width);
+    ctx.globalAlpha = 1;
+    lines(lowerSeries, from, to, x, v => top + lowerHeight - (v - lowerLow) / (lowerHigh - lowerLow) * lowerHeight);
+  }
+
+  if (hover !== null && hover >= from && hover < to) {
+    ctx.fillStyle = "rgba(255,255,255,0.08)";
+    ctx.fillRect(x(hover) - barWidth / 2, 0, barWidth, height);
+  }
+  ctx.fillStyle = "#7d8590";
+  for (let i = from; i < to; i += Math.max(1, Math.ceil(140 / barWidth))) {
+    ctx.fillText(data.bars[i][0], x(i), height - 4);
+  }
+}
+
+function describe(i) {
+  const [time, open, high, low, close, volume] = data.bars[i];
+  let text = time + " UTC  O " + open + "  H " + high + "  L " + low + "  C " + close + "  V " + volume;
+  for (const s of data.indicators) {
+    const p = s.points.find(p => p[0] === i);
+    if (p) { text += "  |  " + s.name + " " + p[1]; }
+  }
+  for (const m of data.markers.filter(m => m.bar === i)) {
+    text += "\n" + m.label + "  " + m.tag + (m.pnl === null ? "" : "  pnl " + m.pnl) + "  " + m.time;
+  }
+  return text;
+}
+
+canvas.addEventListener("mousemove", e => {
+  const [from, to] = visible(canvas.clientWidth);
+  if (drag !== null) {
+    const shift = Math.round((drag - e.offsetX) / barWidth);
+    if (shift !== 0) { end = Math.max(1, Math.min(data.bars.length, end + shift)); drag = e.offsetX; }
+  }
+  const i = from + Math.floor(e.offsetX / barWidth);
+  hover = i < to ? i : null;
+  info.textContent = hover === null ? "" : describe(hover);
+  info.style.height = (18 * info.textContent.split("\n").length) + "px";
+  draw();
+});
+canvas.addEventListener("mousedown", e => { drag = e.offsetX; });
+window.addEventListener("mouseup", () => { drag = null; });
+canvas.addEventListener("wheel", e => {
+  e.preventDefault();
+  barWidth = Math.min(40, Math.max(2, barWidth * (e.deltaY < 0 ? 1.2 : 1 / 1.2)));
+  draw();
+}, { passive: false });
+window.addEventListener("resize", draw);
+draw();
+</script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+    use crate::gui_types::settings::Color;
+    use crate::standardized_types::base_data::base_data_type::BaseDataType;
+    use crate::standardized_types::base_data::candle::Candle;
+    use crate::standardized_types::base_data::data_quality::DataQuality;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::resolution::Resolution;
+    use crate::standardized_types::subscriptions::CandleType;
+    use crate::strategies::indicators::indicator_values::IndicatorPlot;
+
+    #[test]
+    fn test_chart_export_maps_trades_and_indicators_to_bars() {
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(5), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME));
+        let start = DateTime::<Utc>::from_str("2024-11-01 14:30:00 UTC").unwrap();
+        let data: Vec<BaseDataEnum> = (0..4).map(|i| {
+            let open = dec!(100) + Decimal::from(i);
+            BaseDataEnum::Candle(Candle {
+                symbol: subscription.symbol.clone(),
+                high: open + dec!(2),
+                low: open - dec!(1),
+                open,
+                close: open + dec!(1),
+                volume: dec!(10),
+                ask_volume: dec!(0),
+                bid_volume: dec!(0),
+                range: dec!(3),
+                time: (start + Duration::minutes(5 * i)).to_string(),
+                is_closed: true,
+                resolution: Resolution::Minutes(5),
+                candle_type: CandleType::CandleStick,
+                quality: DataQuality::default(),
+            })
+        }).collect();
+
+        let trade = TradeExport {
+            symbol_code: "MNQZ4".to_string(),
+            position_id: "1".to_string(),
+            side: "Long".to_string(),
+            entry_price: dec!(101),
+            entry_quantity: dec!(1),
+            exit_price: dec!(103.5),
+            exit_quantity: dec!(1),
+            entry_time: (start + Duration::minutes(5)).to_string(),
+            exit_time: (start + Duration::minutes(12)).to_string(),
+            pnl: dec!(5),
+            tag: "Breakout</script>".to_string(),
+            result: "Win".to_string(),
+        };
+        let mut plots = BTreeMap::new();
+        plots.insert("rsi".to_string(), IndicatorPlot::new("rsi".to_string(), dec!(55), Color::new(50, 50, 255)));
+        let rsi = IndicatorValues::new("RSI".to_string(), subscription.clone(), plots, start + Duration::minutes(20));
+
+        let export = ChartExport::new(subscription, data.iter().rev())
+            .with_trades(vec![trade])
+            .with_indicator("RSI", vec![rsi], false);
+        assert_eq!(export.bars.first().unwrap().open_time, start);
+        assert_eq!(export.bar_index(start + Duration::minutes(5)), Some(0));
+        assert_eq!(export.bar_index(start + Duration::minutes(12)), Some(2));
+        assert_eq!(export.bar_index(start + Duration::minutes(25)), None);
+
+        let markers = export.markers();
+        assert_eq!((markers[0]["bar"].as_u64(), markers[0]["buy"].as_bool()), (Some(0), Some(true)));
+        assert_eq!((markers[1]["bar"].as_u64(), markers[1]["buy"].as_bool()), (Some(2), Some(false)));
+        let series = export.indicator_series();
+        assert_eq!(series[0]["points"], json!([[3, 55.0]]));
+
+        let html = export.to_html();
+        assert!(html.contains("Breakout\\u003c/script>"));
+        assert!(!html.contains("{{DATA}}"));
+    }
+}
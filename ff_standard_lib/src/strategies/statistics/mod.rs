@@ -0,0 +1,5 @@
+pub mod backtest_comparison;
+pub mod seasonality;
+pub mod backtest_cache;
+pub mod streaming;
+pub mod chart_export;
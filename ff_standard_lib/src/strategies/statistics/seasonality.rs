@@ -0,0 +1,284 @@
+use std::collections::BTreeMap;
+use std::fs::create_dir_all;
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use csv::Writer;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use crate::messages::data_server_messaging::FundForgeError;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::base_data::history::get_compressed_historical_data;
+use crate::standardized_types::base_data::traits::BaseData;
+use crate::standardized_types::subscriptions::DataSubscription;
+
+/// Settings for `intraday_seasonality()`.
+#[derive(Debug, Clone)]
+pub struct SeasonalitySettings {
+    /// The width of each time of day bucket in minutes, bars are assigned to a bucket by their open time.
+    pub bucket_minutes: u32,
+    /// The time zone used for the time of day and day of week buckets, usually the exchange time zone.
+    pub time_zone: Tz,
+    /// The confidence level of the intervals, for example 0.95.
+    pub confidence: f64,
+    /// Skip bars which were estimated, corrected or reported late by the vendor.
+    pub exclude_suspect: bool,
+}
+
+impl Default for SeasonalitySettings {
+    fn default() -> Self {
+        Self {
+            bucket_minutes: 30,
+            time_zone: Tz::UTC,
+            confidence: 0.95,
+            exclude_suspect: true,
+        }
+    }
+}
+
+/// The statistics for a single time of day or day of week bucket.
+/// Returns are the percentage change from the open to the close of each bar, volatility is the standard deviation of the returns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BucketStatistics {
+    pub label: String,
+    pub samples: usize,
+    pub mean_return: f64,
+    pub return_ci_low: f64,
+    pub return_ci_high: f64,
+    pub volatility: f64,
+    pub mean_volume: f64,
+    pub volume_ci_low: f64,
+    pub volume_ci_high: f64,
+}
+
+#[derive(Default)]
+struct BucketSamples {
+    returns: Vec<f64>,
+    volumes: Vec<f64>,
+}
+
+impl BucketSamples {
+    fn statistics(&self, label: String, z: f64) -> BucketStatistics {
+        let (mean_return, volatility) = mean_and_deviation(&self.returns);
+        let (mean_volume, volume_deviation) = mean_and_deviation(&self.volumes);
+        let root_n = (self.returns.len() as f64).sqrt();
+        let return_margin = z * volatility / root_n;
+        let volume_margin = z * volume_deviation / root_n;
+        BucketStatistics {
+            label,
+            samples: self.returns.len(),
+            mean_return,
+            return_ci_low: mean_return - return_margin,
+            return_ci_high: mean_return + return_margin,
+            volatility,
+            mean_volume,
+            volume_ci_low: mean_volume - volume_margin,
+            volume_ci_high: mean_volume + volume_margin,
+        }
+    }
+}
+
+/// Average return, volatility and volume of a subscription grouped by time of day and by day of week.
+/// Used to design session filters, for example to only trade the hours with the highest volatility.
+///
+/// # Example
+/// ```rust,ignore
+/// let settings = SeasonalitySettings { bucket_minutes: 15, time_zone: Tz::America__Chicago, ..Default::default() };
+/// let report = intraday_seasonality(&subscription, from, to, &settings).await?;
+/// for bucket in &report.time_of_day {
+///     println!("{}: {:.4}% ({:.4}% to {:.4}%)", bucket.label, bucket.mean_return, bucket.return_ci_low, bucket.return_ci_high);
+/// }
+/// report.export_csv("seasonality")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SeasonalityReport {
+    pub subscription: DataSubscription,
+    pub confidence: f64,
+    /// Buckets ordered by time of day, labelled by the bucket start time "HH:MM".
+    pub time_of_day: Vec<BucketStatistics>,
+    /// Buckets ordered from Monday to Sunday.
+    pub day_of_week: Vec<BucketStatistics>,
+}
+
+impl SeasonalityReport {
+    /// Builds the report from candles or quote bars, quote bars use the mid price. Other data types are ignored.
+    pub fn from_data<'a>(subscription: DataSubscription, data: impl Iterator<Item = &'a BaseDataEnum>, settings: &SeasonalitySettings) -> Self {
+        let bucket_minutes = settings.bucket_minutes.clamp(1, 24 * 60);
+        let mut time_of_day: BTreeMap<u32, BucketSamples> = BTreeMap::new();
+        let mut day_of_week: BTreeMap<u32, BucketSamples> = BTreeMap::new();
+        for base_data in data {
+            if settings.exclude_suspect && base_data.is_suspect() {
+                continue;
+            }
+            let (open, close, volume) = match base_data {
+                BaseDataEnum::Candle(candle) => (candle.open, candle.close, candle.volume),
+                BaseDataEnum::QuoteBar(bar) => ((bar.bid_open + bar.ask_open) / dec!(2), (bar.bid_close + bar.ask_close) / dec!(2), bar.volume),
+                _ => continue
+            };
+            if open == dec!(0) {
+                continue;
+            }
+            let bar_return = match ((close - open) / open * dec!(100)).to_f64() {
+                Some(bar_return) => bar_return,
+                None => continue
+            };
+            let volume = volume.to_f64().unwrap_or_default();
+            let time = base_data.time_utc().with_timezone(&settings.time_zone);
+            let minute_of_day = time.hour() * 60 + time.minute();
+            let bucket = minute_of_day / bucket_minutes * bucket_minutes;
+            for samples in [time_of_day.entry(bucket).or_default(), day_of_week.entry(time.weekday().num_days_from_monday()).or_default()] {
+                samples.returns.push(bar_return);
+                samples.volumes.push(volume);
+            }
+        }
+
+        let z = z_score(settings.confidence);
+        SeasonalityReport {
+            subscription,
+            confidence: settings.confidence,
+            time_of_day: time_of_day.iter()
+                .map(|(bucket, samples)| samples.statistics(format!("{:02}:{:02}", bucket / 60, bucket % 60), z))
+                .collect(),
+            day_of_week: day_of_week.iter()
+                .filter_map(|(day, samples)| weekday_from_monday(*day).map(|weekday| samples.statistics(weekday.to_string(), z)))
+                .collect(),
+        }
+    }
+
+    /// Writes both the time of day and day of week buckets to a single csv file in the folder.
+    pub fn export_csv(&self, folder: &str) -> Result<(), FundForgeError> {
+        create_dir_all(folder)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create directory {}: {}", folder, e)))?;
+        let date = Utc::now().format("%Y%m%d_%H%M").to_string();
+        let file_name = format!("{}/Seasonality_{}_{}_{}.csv", folder, self.subscription.symbol.name, self.subscription.resolution, date);
+        let mut writer = Writer::from_path(&file_name)
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(format!("Failed to create CSV writer for {}: {}", file_name, e)))?;
+
+        writer.write_record(["bucket_type", "bucket", "samples", "mean_return", "return_ci_low", "return_ci_high", "volatility", "mean_volume", "volume_ci_low", "volume_ci_high"])
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(e.to_string()))?;
+        let rows = self.time_of_day.iter().map(|bucket| ("time_of_day", bucket))
+            .chain(self.day_of_week.iter().map(|bucket| ("day_of_week", bucket)));
+        for (bucket_type, bucket) in rows {
+            writer.write_record([
+                bucket_type.to_string(),
+                bucket.label.clone(),
+                bucket.samples.to_string(),
+                bucket.mean_return.to_string(),
+                bucket.return_ci_low.to_string(),
+                bucket.return_ci_high.to_string(),
+                bucket.volatility.to_string(),
+                bucket.mean_volume.to_string(),
+                bucket.volume_ci_low.to_string(),
+                bucket.volume_ci_high.to_string(),
+            ]).map_err(|e| FundForgeError::ClientSideErrorDebug(e.to_string()))?;
+        }
+        writer.flush()
+            .map_err(|e| FundForgeError::ClientSideErrorDebug(e.to_string()))?;
+        tracing::info!("Successfully exported seasonality to {}", file_name);
+        Ok(())
+    }
+}
+
+/// Computes the intraday seasonality of a candle or quote bar subscription from the stored historical data between `from` and `to`.
+pub async fn intraday_seasonality(subscription: &DataSubscription, from: DateTime<Utc>, to: DateTime<Utc>, settings: &SeasonalitySettings) -> Result<SeasonalityReport, FundForgeError> {
+    if subscription.base_data_type != BaseDataType::Candles && subscription.base_data_type != BaseDataType::QuoteBars {
+        return Err(FundForgeError::ClientSideErrorDebug(format!("Seasonality requires candles or quote bars, not {}", subscription.base_data_type)))
+    }
+    let data = get_compressed_historical_data(vec![subscription.clone()], from, to).await?;
+    let data = data.values().flat_map(|time_slice| time_slice.iter());
+    Ok(SeasonalityReport::from_data(subscription.clone(), data, settings))
+}
+
+fn mean_and_deviation(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0)
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0)
+    }
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+fn weekday_from_monday(day: u32) -> Option<Weekday> {
+    Weekday::try_from(day as u8).ok()
+}
+
+/// The two sided z score for the confidence level, using the normal approximation.
+fn z_score(confidence: f64) -> f64 {
+    let confidence = confidence.clamp(0.5, 0.9999);
+    let p = 1.0 - (1.0 - confidence) / 2.0;
+    // Abramowitz and Stegun 26.2.23
+    let t = (-2.0 * (1.0 - p).ln()).sqrt();
+    t - (2.515517 + 0.802853 * t + 0.010328 * t * t) / (1.0 + 1.432788 * t + 0.189269 * t * t + 0.001308 * t * t * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal::Decimal;
+    use crate::standardized_types::base_data::candle::Candle;
+    use crate::standardized_types::base_data::data_quality::DataQuality;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::resolution::Resolution;
+    use crate::standardized_types::subscriptions::{CandleType, Symbol};
+
+    fn candle(time: DateTime<Utc>, open: Decimal, close: Decimal, volume: Decimal) -> BaseDataEnum {
+        let symbol = Symbol::new("MNQ".to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME));
+        BaseDataEnum::Candle(Candle {
+            symbol,
+            high: open.max(close),
+            low: open.min(close),
+            open,
+            close,
+            volume,
+            ask_volume: dec!(0),
+            bid_volume: dec!(0),
+            range: (open - close).abs(),
+            time: time.to_string(),
+            is_closed: true,
+            resolution: Resolution::Hours(1),
+            candle_type: CandleType::CandleStick,
+            quality: DataQuality::default(),
+        })
+    }
+
+    #[test]
+    fn test_seasonality_buckets() {
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Hours(1), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME));
+        // Monday 2024-06-03, two weeks of 09:00 and 10:00 bars
+        let monday = Utc.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap();
+        let mut data = vec![];
+        for week in 0..2 {
+            let day = monday + Duration::weeks(week);
+            data.push(candle(day, dec!(100), dec!(101), dec!(10)));
+            data.push(candle(day + Duration::hours(1), dec!(100), dec!(99), dec!(30)));
+        }
+        let mut suspect = candle(monday + Duration::hours(2), dec!(100), dec!(150), dec!(10));
+        if let BaseDataEnum::Candle(candle) = &mut suspect {
+            candle.quality.insert(DataQuality::ESTIMATED);
+        }
+        data.push(suspect);
+
+        let settings = SeasonalitySettings { bucket_minutes: 60, ..Default::default() };
+        let report = SeasonalityReport::from_data(subscription, data.iter(), &settings);
+
+        assert_eq!(report.time_of_day.len(), 2);
+        assert_eq!(report.time_of_day[0].label, "09:00");
+        assert_eq!(report.time_of_day[0].samples, 2);
+        assert!((report.time_of_day[0].mean_return - 1.0).abs() < 1e-9);
+        assert_eq!(report.time_of_day[0].volatility, 0.0);
+        assert!((report.time_of_day[1].mean_volume - 30.0).abs() < 1e-9);
+
+        assert_eq!(report.day_of_week.len(), 1);
+        assert_eq!(report.day_of_week[0].label, "Mon");
+        assert_eq!(report.day_of_week[0].samples, 4);
+        assert!(report.day_of_week[0].return_ci_low < 0.0 && report.day_of_week[0].return_ci_high > 0.0);
+
+        assert!((z_score(0.95) - 1.96).abs() < 0.01);
+    }
+}
@@ -0,0 +1,125 @@
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+
+/// Trade statistics updated incrementally as each position closes, so the current statistics are available at any time without reprocessing the closed positions.
+///
+/// The variance of the trade pnl uses Welford's algorithm, which stays numerically stable over millions of trades,
+/// drawdown is measured on the cumulative booked pnl from the start of the strategy.
+/// The statistics of an account are returned by `strategy.stats(&account)`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, Debug, PartialEq, Default)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct StreamingStatistics {
+    pub trades: u64,
+    pub wins: u64,
+    pub losses: u64,
+    /// The sum of the winning trades.
+    pub gross_profit: Decimal,
+    /// The sum of the losing trades, as a negative number.
+    pub gross_loss: Decimal,
+    pub net_pnl: Decimal,
+    pub commissions: Decimal,
+    /// The highest cumulative pnl reached.
+    pub peak_pnl: Decimal,
+    pub max_drawdown: Decimal,
+    mean: f64,
+    m2: f64,
+}
+
+impl StreamingStatistics {
+    /// Adds a closed trade, the `pnl` is the booked pnl after commissions.
+    pub fn update(&mut self, pnl: Decimal, commissions: Decimal) {
+        self.trades += 1;
+        if pnl > dec!(0) {
+            self.wins += 1;
+            self.gross_profit += pnl;
+        } else if pnl < dec!(0) {
+            self.losses += 1;
+            self.gross_loss += pnl;
+        }
+        self.net_pnl += pnl;
+        self.commissions += commissions;
+        self.peak_pnl = self.peak_pnl.max(self.net_pnl);
+        self.max_drawdown = self.max_drawdown.max(self.drawdown());
+
+        let value = pnl.to_f64().unwrap_or_default();
+        let delta = value - self.mean;
+        self.mean += delta / self.trades as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// The current drawdown from the peak cumulative pnl.
+    pub fn drawdown(&self) -> Decimal {
+        self.peak_pnl - self.net_pnl
+    }
+
+    pub fn mean_pnl(&self) -> f64 {
+        self.mean
+    }
+
+    /// The sample variance of the trade pnl, 0 until there are 2 trades.
+    pub fn variance(&self) -> f64 {
+        match self.trades > 1 {
+            true => self.m2 / (self.trades - 1) as f64,
+            false => 0.0
+        }
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// The per trade Sharpe ratio, the mean trade pnl divided by the standard deviation of the trade pnl, with no risk free rate.
+    pub fn sharpe_ratio(&self) -> f64 {
+        let std_dev = self.std_dev();
+        match std_dev > 0.0 {
+            true => self.mean / std_dev,
+            false => 0.0
+        }
+    }
+
+    /// The percentage of trades which were winners.
+    pub fn win_rate(&self) -> Decimal {
+        match self.trades > 0 {
+            true => Decimal::from(self.wins) / Decimal::from(self.trades) * dec!(100),
+            false => dec!(0)
+        }
+    }
+
+    pub fn profit_factor(&self) -> Decimal {
+        match self.gross_loss < dec!(0) {
+            true => self.gross_profit / -self.gross_loss,
+            false => dec!(0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_statistics_match_batch() {
+        let trades = [dec!(120), dec!(-40), dec!(-60), dec!(200), dec!(0), dec!(-25.5)];
+        let mut stats = StreamingStatistics::default();
+        for pnl in trades {
+            stats.update(pnl, dec!(1.5));
+        }
+
+        let values: Vec<f64> = trades.iter().map(|pnl| pnl.to_f64().unwrap()).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+        assert!((stats.mean_pnl() - mean).abs() < 1e-9);
+        assert!((stats.variance() - variance).abs() < 1e-9);
+        assert!((stats.sharpe_ratio() - mean / variance.sqrt()).abs() < 1e-9);
+
+        assert_eq!((stats.trades, stats.wins, stats.losses), (6, 2, 3));
+        assert_eq!(stats.net_pnl, dec!(194.5));
+        assert_eq!(stats.commissions, dec!(9));
+        assert_eq!(stats.max_drawdown, dec!(100));
+        assert_eq!(stats.drawdown(), dec!(25.5));
+        assert_eq!(stats.profit_factor(), dec!(320) / dec!(125.5));
+    }
+}
@@ -12,7 +12,16 @@ use rkyv::validation::validators::DefaultValidator;
 use rkyv::vec::ArchivedVec;
 use crate::strategies::indicators::indicator_events::IndicatorEvents;
 use crate::standardized_types::position::PositionUpdateEvent;
-use crate::standardized_types::orders::OrderUpdateEvent;
+use crate::standardized_types::accounts::Account;
+use crate::strategies::ledgers::drawdown_guard::RiskStage;
+use rust_decimal::Decimal;
+use crate::standardized_types::orders::{BasketEvent, OrderUpdateEvent};
+use crate::strategies::live_parameters::ParameterChange;
+use crate::strategies::trading_schedule::ScheduleEvent;
+use crate::strategies::universe_selection::UniverseChange;
+use crate::strategies::execution_algos::AlgoEvent;
+use crate::messages::message_bus::Topic;
+use crate::helpers::logging::LogLevel;
 
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug, Copy, Ord, PartialOrd, Eq)]
 #[archive(compare(PartialEq), check_bytes)]
@@ -27,7 +36,16 @@ pub enum StrategyEventType {
     WarmUpComplete,
     IndicatorEvent,
     PositionEvents,
-    TimedEvents
+    TimedEvents,
+    ParameterChanged,
+    ScheduleEvents,
+    CustomMessages,
+    ConnectionEvents,
+    EventQueueOverflow,
+    RiskStageEvents,
+    UniverseEvents,
+    BasketEvents,
+    AlgoEvents
 }
 
 /// All strategies can be sent or received by the strategy or the UI.
@@ -93,7 +111,37 @@ pub enum StrategyEvent {
 
     PositionEvents(PositionUpdateEvent),
 
-    TimedEvent(String)
+    TimedEvent(String),
+
+    /// A live parameter was changed by editing the file passed to `strategy.watch_parameters_file()`.
+    ParameterChanged(ParameterChange),
+
+    /// The `TradingSchedule` set with `strategy.set_trading_schedule()` became active or idle.
+    ScheduleEvent(ScheduleEvent),
+
+    /// A message published by another strategy on a topic subscribed with `strategy.subscribe_topic()`.
+    /// The `payload` is the rkyv serialized message, decode it with `decode_message::<T>(&payload)`.
+    CustomMessage { topic: Topic, payload: Vec<u8> },
+
+    /// A connection to a data server dropped or was restored, see `ConnectionEvent`.
+    ConnectionEvent(ConnectionEvent),
+
+    /// Events were dropped because the strategy fell behind, see `EventOverflowPolicy::DropOldest`.
+    /// `dropped` is the number of events dropped since the last warning and `total_dropped` the number dropped since the strategy started.
+    EventQueueOverflow { dropped: u64, total_dropped: u64 },
+
+    /// The `DrawdownPolicy` of the account moved to a new stage, `drawdown_percent` is the drawdown from the intraday peak equity.
+    /// The orders of the strategy are restricted for the stage, the event lets the strategy also react, eg. by cancelling its resting entries.
+    RiskStageChanged { account: Account, stage: RiskStage, drawdown_percent: Decimal, time: String },
+
+    /// A scan changed the members of a universe added with `strategy.add_universe()`, the strategy was subscribed to the added members and unsubscribed from the removed members.
+    UniverseChanged(UniverseChange),
+
+    /// A basket submitted with `strategy.submit_basket()` filled, was partially placed or was rejected.
+    BasketEvents(BasketEvent),
+
+    /// An algo submitted with `strategy.submit_algo()` filled a child order, completed, expired, was cancelled or was rejected.
+    AlgoEvents(AlgoEvent)
 }
 
 impl StrategyEvent {
@@ -135,7 +183,16 @@ impl StrategyEvent {
             StrategyEvent::IndicatorEvent(_) => StrategyEventType::IndicatorEvent,
             StrategyEvent::PositionEvents(_) => StrategyEventType::PositionEvents,
             StrategyEvent::DataSubscriptionEvent(_) => StrategyEventType::DataSubscriptionEvents,
-            StrategyEvent::TimedEvent(_) => StrategyEventType::TimedEvents
+            StrategyEvent::TimedEvent(_) => StrategyEventType::TimedEvents,
+            StrategyEvent::ParameterChanged(_) => StrategyEventType::ParameterChanged,
+            StrategyEvent::ScheduleEvent(_) => StrategyEventType::ScheduleEvents,
+            StrategyEvent::CustomMessage { .. } => StrategyEventType::CustomMessages,
+            StrategyEvent::ConnectionEvent(_) => StrategyEventType::ConnectionEvents,
+            StrategyEvent::EventQueueOverflow { .. } => StrategyEventType::EventQueueOverflow,
+            StrategyEvent::RiskStageChanged { .. } => StrategyEventType::RiskStageEvents,
+            StrategyEvent::UniverseChanged(_) => StrategyEventType::UniverseEvents,
+            StrategyEvent::BasketEvents(_) => StrategyEventType::BasketEvents,
+            StrategyEvent::AlgoEvents(_) => StrategyEventType::AlgoEvents
         }
     }
 
@@ -161,6 +218,21 @@ pub enum ShutdownEvent {
     Success(String),
 }
 
+/// Sent to the strategy as a `StrategyEvent::ConnectionEvent` when a connection to a data server drops and when it is restored.
+/// The `connection` is the `ConnectionType` of the server_settings.toml entry, eg. "Default" or "Broker(Rithmic)", times are utc strings.
+/// If only the live data stream of a connection drops the `connection` is eg. "Default live stream", the stream is registered again and its subscriptions re-subscribed.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum ConnectionEvent {
+    /// The connection dropped or the server stopped answering heartbeats, the strategy keeps reconnecting with an increasing delay until the server is reachable.
+    /// Requests waiting on a response fail with the `reason`, requests made while disconnected are lost and live data stops until the connection is restored.
+    Disconnected { connection: String, time: String, reason: String },
+    /// The connection was restored after `attempts` attempts, the live data stream was registered again,
+    /// the subscriptions were re-subscribed and the live account snapshots were requested again.
+    Reconnected { connection: String, disconnected_at: String, reconnected_at: String, outage_seconds: i64, attempts: u32 },
+}
+
 #[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
 #[archive(compare(PartialEq), check_bytes)]
 #[archive_attr(derive(Debug))]
@@ -196,7 +268,9 @@ pub enum StrategyControls {
     /// Use Strings to set custom commands to the strategy
     Custom(String),
     /// Send bytes over TCP for larger more complex commands that can be deserialized to concrete types by a u64 identifier
-    CustomBytes(u64, Vec<u8>)
+    CustomBytes(u64, Vec<u8>),
+    /// Used to change the log level while the strategy runs, pass the level to `set_log_level()`.
+    SetLogLevel(LogLevel),
 }
 #[derive(Clone, PartialEq, Debug)]
 pub struct StrategyEventBuffer {
@@ -0,0 +1,170 @@
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use crate::gui_types::settings::Color;
+use crate::messages::data_server_messaging::{DataServerRequest, DataServerResponse, FundForgeError};
+use crate::standardized_types::subscriptions::DataSubscription;
+use crate::strategies::client_features::connection_types::ConnectionType;
+use crate::strategies::client_features::request_handler::callback_request;
+use crate::strategies::indicators::built_in::average_true_range::AverageTrueRange;
+use crate::strategies::indicators::built_in::bollinger_bands::BollingerBands;
+use crate::strategies::indicators::built_in::exponential_moving_average::ExponentialMovingAverage;
+use crate::strategies::indicators::built_in::moving_average::MovingAverage;
+use crate::strategies::indicators::built_in::rsi::RelativeStrengthIndex;
+use crate::strategies::indicators::indicators_trait::{IndicatorName, Indicators};
+
+/// A named set of subscriptions and indicators saved on the server, so research and production strategies can start from the same configuration.
+/// Profiles are saved with `strategy.save_subscription_profile()` and applied with `strategy.load_subscription_profile()`.
+///
+/// The server stores each profile in `{data_folder}/subscription_profiles/{name}.profile`, saving a profile with an existing name replaces it.
+/// Names may only contain letters, numbers, `-` and `_`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct SubscriptionProfile {
+    pub name: String,
+    pub subscriptions: Vec<ProfileSubscription>,
+    pub indicators: Vec<ProfileIndicator>,
+}
+
+impl SubscriptionProfile {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            subscriptions: vec![],
+            indicators: vec![],
+        }
+    }
+
+    pub fn with_subscription(mut self, subscription: DataSubscription, history_to_retain: usize, fill_forward: bool) -> Self {
+        self.subscriptions.push(ProfileSubscription {
+            subscription,
+            history_to_retain: history_to_retain as u64,
+            fill_forward,
+        });
+        self
+    }
+
+    pub fn with_indicator(mut self, name: &str, subscription: DataSubscription, history_to_retain: usize, kind: ProfileIndicatorKind, plot_color: Color) -> Self {
+        self.indicators.push(ProfileIndicator {
+            name: name.to_string(),
+            subscription,
+            history_to_retain: history_to_retain as u64,
+            kind,
+            plot_color,
+        });
+        self
+    }
+}
+
+/// Profile names are used as file names on the server.
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct ProfileSubscription {
+    pub subscription: DataSubscription,
+    pub history_to_retain: u64,
+    pub fill_forward: bool,
+}
+
+/// The built-in indicators which can be saved in a profile.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum ProfileIndicatorKind {
+    AverageTrueRange { period: u64 },
+    MovingAverage { period: u64 },
+    ExponentialMovingAverage { period: u64 },
+    RelativeStrengthIndex { period: u64 },
+    BollingerBands { period: u64, num_std_dev: Decimal },
+}
+
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct ProfileIndicator {
+    pub name: IndicatorName,
+    pub subscription: DataSubscription,
+    pub history_to_retain: u64,
+    pub kind: ProfileIndicatorKind,
+    pub plot_color: Color,
+}
+
+impl ProfileIndicator {
+    /// Creates the indicator, the values are rounded to the tick size of the symbol.
+    pub async fn build(&self) -> Box<dyn Indicators> {
+        let name = self.name.clone();
+        let subscription = self.subscription.clone();
+        let history_to_retain = self.history_to_retain as usize;
+        let color = self.plot_color.clone();
+        match &self.kind {
+            ProfileIndicatorKind::AverageTrueRange { period } => AverageTrueRange::new(name, subscription, history_to_retain, *period, color, true).await,
+            ProfileIndicatorKind::MovingAverage { period } => MovingAverage::new(name, subscription, history_to_retain, *period, color, true).await,
+            ProfileIndicatorKind::ExponentialMovingAverage { period } => ExponentialMovingAverage::new(name, subscription, history_to_retain, *period, color, true).await,
+            ProfileIndicatorKind::RelativeStrengthIndex { period } => RelativeStrengthIndex::new(name, subscription, history_to_retain, *period, color, true).await,
+            ProfileIndicatorKind::BollingerBands { period, num_std_dev } => BollingerBands::new(name, subscription, history_to_retain, *period, *num_std_dev, color.clone(), color.clone(), color, true).await,
+        }
+    }
+}
+
+/// Saves the profile on the server, replacing any profile with the same name.
+pub async fn save_subscription_profile(profile: SubscriptionProfile) -> Result<(), FundForgeError> {
+    if !is_valid_profile_name(&profile.name) {
+        return Err(FundForgeError::ClientSideErrorDebug(format!("Invalid subscription profile name: {}", profile.name)));
+    }
+    let request = DataServerRequest::SaveSubscriptionProfile {
+        callback_id: 0,
+        profile,
+    };
+    match subscription_profile_request(request).await? {
+        DataServerResponse::SubscriptionProfile { .. } => Ok(()),
+        _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
+    }
+}
+
+/// Gets the profile saved on the server with the name.
+pub async fn get_subscription_profile(name: &str) -> Result<SubscriptionProfile, FundForgeError> {
+    let request = DataServerRequest::SubscriptionProfile {
+        callback_id: 0,
+        name: name.to_string(),
+    };
+    match subscription_profile_request(request).await? {
+        DataServerResponse::SubscriptionProfile { profile, .. } => Ok(profile),
+        _ => Err(FundForgeError::ClientSideErrorDebug("Incorrect response received at callback".to_string()))
+    }
+}
+
+async fn subscription_profile_request(request: DataServerRequest) -> Result<DataServerResponse, FundForgeError> {
+    match callback_request(ConnectionType::Default, request).await? {
+        DataServerResponse::Error { error, .. } => Err(error),
+        response => Ok(response),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::base_data_type::BaseDataType;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::resolution::Resolution;
+
+    #[test]
+    fn test_subscription_profile_round_trip() {
+        let subscription = DataSubscription::new("MNQ".to_string(), DataVendor::Rithmic, Resolution::Minutes(5), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME));
+        let profile = SubscriptionProfile::new("mnq_5m")
+            .with_subscription(subscription.clone(), 100, false)
+            .with_indicator("bands", subscription, 10, ProfileIndicatorKind::BollingerBands { period: 20, num_std_dev: dec!(2) }, Color::new(50, 50, 255));
+
+        let bytes = rkyv::to_bytes::<_, 1024>(&profile).unwrap();
+        assert_eq!(rkyv::from_bytes::<SubscriptionProfile>(&bytes).unwrap(), profile);
+
+        assert!(is_valid_profile_name("mnq_5m-v2"));
+        assert!(!is_valid_profile_name("../mnq"));
+        assert!(!is_valid_profile_name(""));
+    }
+}
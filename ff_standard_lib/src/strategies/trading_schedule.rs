@@ -0,0 +1,122 @@
+use std::collections::BTreeSet;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use crate::standardized_types::market_hours::is_us_market_holiday;
+
+/// A window in which the strategy trades, `start` and `end` are in the time zone of the `TradingSchedule`.
+/// If `end` is before `start` the window runs overnight, `days` are the days the window opens.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TradingWindow {
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TradingWindow {
+    pub fn new(days: Vec<Weekday>, start: NaiveTime, end: NaiveTime) -> Self {
+        Self {
+            days,
+            start,
+            end,
+        }
+    }
+
+    /// A window from `start` to `end` Monday to Friday.
+    pub fn weekdays(start: NaiveTime, end: NaiveTime) -> Self {
+        Self::new(vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri], start, end)
+    }
+}
+
+/// The windows in which a strategy is allowed to trade, see `strategy.set_trading_schedule()`.
+///
+/// While the schedule is idle, orders which would open or increase a position are rejected, the data subscriptions, indicators and consolidators keep updating,
+/// so the strategy is warm when the next window opens.
+/// If `flatten_outside` is true, the open orders are cancelled and the accounts are flattened when each window closes.
+/// # Example
+/// ```rust,ignore
+/// // 09:25 to 15:55 New York time on weekdays, not trading on US market holidays.
+/// let window = TradingWindow::weekdays(NaiveTime::from_hms_opt(9, 25, 0).unwrap(), NaiveTime::from_hms_opt(15, 55, 0).unwrap());
+/// strategy.set_trading_schedule(Some(TradingSchedule::new(chrono_tz::America::New_York, vec![window])));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TradingSchedule {
+    pub time_zone: Tz,
+    pub windows: Vec<TradingWindow>,
+    /// Dates on which no windows open, in addition to the US market holidays.
+    pub closed_dates: BTreeSet<NaiveDate>,
+    /// If true no windows open on the full day US market holidays, see `us_market_holidays()`.
+    pub us_market_holidays: bool,
+    pub flatten_outside: bool,
+}
+
+impl TradingSchedule {
+    /// A schedule which observes the US market holidays and flattens when each window closes.
+    pub fn new(time_zone: Tz, windows: Vec<TradingWindow>) -> Self {
+        Self {
+            time_zone,
+            windows,
+            closed_dates: BTreeSet::new(),
+            us_market_holidays: true,
+            flatten_outside: true,
+        }
+    }
+
+    pub fn is_closed_date(&self, date: NaiveDate) -> bool {
+        self.closed_dates.contains(&date) || (self.us_market_holidays && is_us_market_holiday(date))
+    }
+
+    /// True if the time is inside one of the windows.
+    pub fn is_active(&self, time: DateTime<Utc>) -> bool {
+        let local = time.with_timezone(&self.time_zone).naive_local();
+        let (date, time) = (local.date(), local.time());
+        let opens_on = |date: NaiveDate, window: &TradingWindow| window.days.contains(&date.weekday()) && !self.is_closed_date(date);
+        self.windows.iter().any(|window| match window.start < window.end {
+            true => time >= window.start && time < window.end && opens_on(date, window),
+            false if time >= window.start => opens_on(date, window),
+            false => time < window.end && opens_on(date - Duration::days(1), window),
+        })
+    }
+}
+
+/// Sent to the strategy as a `StrategyEvent::ScheduleEvent` when the `TradingSchedule` changes state and at the times of the `SessionClose`.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub enum ScheduleEvent {
+    /// A trading window opened.
+    Active { time: String },
+    /// The trading window closed, `flattened` is true if the open orders were cancelled and the accounts flattened.
+    Idle { time: String, flattened: bool },
+    /// The open orders were cancelled and the accounts flattened at the `flatten_at` time of the `SessionClose`.
+    AutoFlattened { time: String },
+    /// The session of the `SessionClose` closed.
+    SessionClose { time: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::America::New_York;
+
+    #[test]
+    fn test_schedule_windows_and_holidays() {
+        let time = |month, day, hour, minute| New_York.with_ymd_and_hms(2024, month, day, hour, minute, 0).unwrap().to_utc();
+        let hms = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        let mut schedule = TradingSchedule::new(New_York, vec![TradingWindow::weekdays(hms(9, 25), hms(15, 55))]);
+        assert!(schedule.is_active(time(7, 3, 9, 25)));
+        assert!(!schedule.is_active(time(7, 3, 15, 55)));
+        // Independence Day and a Saturday.
+        assert!(!schedule.is_active(time(7, 4, 10, 0)));
+        assert!(!schedule.is_active(time(7, 6, 10, 0)));
+        schedule.closed_dates.insert(NaiveDate::from_ymd_opt(2024, 7, 5).unwrap());
+        assert!(!schedule.is_active(time(7, 5, 10, 0)));
+
+        // An overnight window belongs to the day it opens.
+        schedule.windows = vec![TradingWindow::new(vec![Weekday::Sun], hms(18, 0), hms(2, 0))];
+        assert!(schedule.is_active(time(7, 7, 20, 0)));
+        assert!(schedule.is_active(time(7, 8, 1, 0)));
+        assert!(!schedule.is_active(time(7, 8, 2, 0)));
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use chrono::Duration;
+use rkyv::{Archive, Deserialize as Deserialize_rkyv, Serialize as Serialize_rkyv};
+use rust_decimal::Decimal;
+use crate::standardized_types::base_data::base_data_enum::BaseDataEnum;
+use crate::standardized_types::base_data::base_data_type::BaseDataType;
+use crate::standardized_types::resolution::Resolution;
+use crate::standardized_types::subscriptions::DataSubscription;
+
+/// Scores a candidate from its ranking data, oldest first. Higher scores rank first, `None` leaves the candidate out of the universe.
+pub type RankingFunction = Arc<dyn Fn(&DataSubscription, &[BaseDataEnum]) -> Option<Decimal> + Send + Sync>;
+
+/// A universe of subscriptions chosen from a list of candidates by a ranking function, see `strategy.add_universe()`.
+///
+/// Each scan downloads the ranking data of every candidate from the data server, the daily candles of the last 20 days by default,
+/// scores the candidates with the ranking function and subscribes the strategy to the `top_n` highest scoring candidates, unsubscribing the candidates which dropped out.
+/// A `StrategyEvent::UniverseChanged` is sent each time the members change.
+#[derive(Clone)]
+pub struct UniverseSelection {
+    pub name: String,
+    /// The subscriptions the members are chosen from, the strategy is subscribed to the subscription of each member.
+    pub candidates: Vec<DataSubscription>,
+    pub top_n: usize,
+    /// The resolution of the ranking data, `Resolution::Day` by default.
+    pub ranking_resolution: Resolution,
+    /// The base data type of the ranking data, `BaseDataType::Candles` by default, use `BaseDataType::QuoteBars` for vendors which only provide quotes.
+    pub ranking_data_type: BaseDataType,
+    /// The span of ranking data passed to the ranking function, 20 days by default.
+    pub lookback: Duration,
+    /// The time between scans, 1 day by default.
+    pub scan_interval: Duration,
+    /// The history retained for the subscriptions of the members, 100 by default.
+    pub history_to_retain: usize,
+    ranking: RankingFunction,
+}
+
+impl fmt::Debug for UniverseSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniverseSelection")
+            .field("name", &self.name)
+            .field("candidates", &self.candidates)
+            .field("top_n", &self.top_n)
+            .field("ranking_resolution", &self.ranking_resolution)
+            .field("ranking_data_type", &self.ranking_data_type)
+            .field("lookback", &self.lookback)
+            .field("scan_interval", &self.scan_interval)
+            .field("history_to_retain", &self.history_to_retain)
+            .finish()
+    }
+}
+
+impl UniverseSelection {
+    pub fn new(
+        name: impl Into<String>,
+        candidates: Vec<DataSubscription>,
+        top_n: usize,
+        ranking: impl Fn(&DataSubscription, &[BaseDataEnum]) -> Option<Decimal> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            candidates,
+            top_n,
+            ranking_resolution: Resolution::Day,
+            ranking_data_type: BaseDataType::Candles,
+            lookback: Duration::days(20),
+            scan_interval: Duration::days(1),
+            history_to_retain: 100,
+            ranking: Arc::new(ranking),
+        }
+    }
+
+    pub fn with_ranking_data(mut self, resolution: Resolution, base_data_type: BaseDataType) -> Self {
+        self.ranking_resolution = resolution;
+        self.ranking_data_type = base_data_type;
+        self
+    }
+
+    pub fn with_lookback(mut self, lookback: Duration) -> Self {
+        self.lookback = lookback;
+        self
+    }
+
+    pub fn with_scan_interval(mut self, scan_interval: Duration) -> Self {
+        self.scan_interval = scan_interval;
+        self
+    }
+
+    pub fn with_history_to_retain(mut self, history_to_retain: usize) -> Self {
+        self.history_to_retain = history_to_retain;
+        self
+    }
+
+    /// The subscription of the ranking data for the candidate.
+    pub(crate) fn ranking_subscription(&self, candidate: &DataSubscription) -> DataSubscription {
+        DataSubscription::new(
+            candidate.symbol.name.clone(),
+            candidate.symbol.data_vendor,
+            self.ranking_resolution,
+            self.ranking_data_type,
+            candidate.market_type,
+        )
+    }
+
+    /// The members chosen from the ranking data, keyed by the ranking subscription of each candidate, highest score first.
+    /// Candidates with equal scores keep the order of the candidate list.
+    pub(crate) fn select(&self, ranking_data: &HashMap<DataSubscription, Vec<BaseDataEnum>>) -> Vec<DataSubscription> {
+        let mut scores: Vec<(Decimal, &DataSubscription)> = self.candidates.iter()
+            .filter_map(|candidate| {
+                let data = ranking_data.get(&self.ranking_subscription(candidate)).map(|data| data.as_slice()).unwrap_or(&[]);
+                (self.ranking)(candidate, data).map(|score| (score, candidate))
+            })
+            .collect();
+        scores.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scores.into_iter().take(self.top_n).map(|(_, candidate)| candidate.clone()).collect()
+    }
+}
+
+/// Sent to the strategy as a `StrategyEvent::UniverseChanged` when a scan changes the members of a universe.
+#[derive(Clone, Serialize_rkyv, Deserialize_rkyv, Archive, PartialEq, Debug)]
+#[archive(compare(PartialEq), check_bytes)]
+#[archive_attr(derive(Debug))]
+pub struct UniverseChange {
+    pub name: String,
+    /// The subscriptions the strategy was subscribed to.
+    pub added: Vec<DataSubscription>,
+    /// The subscriptions the strategy was unsubscribed from.
+    pub removed: Vec<DataSubscription>,
+    /// The members after the scan, highest score first.
+    pub members: Vec<DataSubscription>,
+    pub time: String,
+}
+
+impl UniverseChange {
+    /// The change from the previous members, `None` if the scan selected the same members.
+    pub(crate) fn new(name: String, previous: &[DataSubscription], members: Vec<DataSubscription>, time: String) -> Option<Self> {
+        let added: Vec<DataSubscription> = members.iter().filter(|member| !previous.contains(member)).cloned().collect();
+        let removed: Vec<DataSubscription> = previous.iter().filter(|member| !members.contains(member)).cloned().collect();
+        if added.is_empty() && removed.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name,
+            added,
+            removed,
+            members,
+            time,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+    use crate::standardized_types::base_data::candle::Candle;
+    use crate::standardized_types::base_data::data_quality::DataQuality;
+    use crate::standardized_types::datavendor_enum::DataVendor;
+    use crate::standardized_types::enums::{FuturesExchange, MarketType};
+    use crate::standardized_types::subscriptions::{CandleType, Symbol};
+
+    fn candle(symbol_name: &str, time: DateTime<Utc>, volume: Decimal) -> BaseDataEnum {
+        BaseDataEnum::Candle(Candle {
+            symbol: Symbol::new(symbol_name.to_string(), DataVendor::Rithmic, MarketType::Futures(FuturesExchange::CME)),
+            high: dec!(100),
+            low: dec!(100),
+            open: dec!(100),
+            close: dec!(100),
+            volume,
+            ask_volume: dec!(0),
+            bid_volume: dec!(0),
+            range: dec!(0),
+            time: time.to_string(),
+            is_closed: true,
+            resolution: Resolution::Day,
+            candle_type: CandleType::CandleStick,
+            quality: DataQuality::default(),
+        })
+    }
+
+    #[test]
+    fn test_universe_selection() {
+        let subscription = |symbol_name: &str| DataSubscription::new(symbol_name.to_string(), DataVendor::Rithmic, Resolution::Minutes(5), BaseDataType::Candles, MarketType::Futures(FuturesExchange::CME));
+        let candidates = vec![subscription("MES"), subscription("MNQ"), subscription("M2K"), subscription("MYM")];
+        // Ranks by the total volume, candidates without data are left out.
+        let selection = UniverseSelection::new("Most Active", candidates, 2, |_candidate, data| {
+            match data.is_empty() {
+                true => None,
+                false => Some(data.iter().map(|data| match data {
+                    BaseDataEnum::Candle(candle) => candle.volume,
+                    _ => dec!(0),
+                }).sum())
+            }
+        });
+        let time = Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap();
+        let mut ranking_data = HashMap::new();
+        for (symbol_name, volume) in [("MES", dec!(500)), ("MNQ", dec!(900)), ("M2K", dec!(500))] {
+            let ranking_subscription = selection.ranking_subscription(&subscription(symbol_name));
+            assert_eq!(ranking_subscription.resolution, Resolution::Day);
+            ranking_data.insert(ranking_subscription, vec![candle(symbol_name, time, volume)]);
+        }
+        let members = selection.select(&ranking_data);
+        // MES and M2K tie, MES is first in the candidate list.
+        assert_eq!(members, vec![subscription("MNQ"), subscription("MES")]);
+
+        let change = UniverseChange::new("Most Active".to_string(), &[subscription("MES"), subscription("MYM")], members.clone(), time.to_string()).unwrap();
+        assert_eq!(change.added, vec![subscription("MNQ")]);
+        assert_eq!(change.removed, vec![subscription("MYM")]);
+        assert!(UniverseChange::new("Most Active".to_string(), &[subscription("MES"), subscription("MNQ")], members, time.to_string()).is_none());
+    }
+}
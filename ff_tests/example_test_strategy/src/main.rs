@@ -2,6 +2,7 @@ use chrono::{Duration, NaiveDate};
 use chrono_tz::Australia;
 use colored::Colorize;
 use rust_decimal::Decimal;
+use ff_standard_lib::helpers::logging::set_log_level;
 use ff_standard_lib::strategies::indicators::indicator_events::IndicatorEvents;
 use ff_standard_lib::standardized_types::base_data::base_data_enum::BaseDataEnum;
 use ff_standard_lib::standardized_types::base_data::base_data_type::BaseDataType;
@@ -233,6 +234,7 @@ pub async fn on_data_received(
                         }
                         BaseDataEnum::Candle(_candle) => {}
                         BaseDataEnum::Fundamental(_fundamental) => {}
+                        BaseDataEnum::Footprint(_footprint) => {}
                     }
                 }
             }
@@ -272,6 +274,11 @@ pub async fn on_data_received(
                     StrategyControls::Delay(_) => {}
                     StrategyControls::Custom(_) => {}
                     StrategyControls::CustomBytes(_, _) => {}
+                    StrategyControls::SetLogLevel(level) => {
+                        if let Err(e) = set_log_level(level) {
+                            eprintln!("{}", e);
+                        }
+                    }
                 }
             }
 
@@ -336,6 +343,33 @@ pub async fn on_data_received(
             StrategyEvent::TimedEvent(name) => {
                 println!("{} has triggered", name);
             }
+            StrategyEvent::ParameterChanged(change) => {
+                println!("{} changed from '{}' to '{}'", change.name, change.old_value, change.new_value);
+            }
+            StrategyEvent::ScheduleEvent(event) => {
+                println!("{:?}", event);
+            }
+            StrategyEvent::CustomMessage { topic, payload } => {
+                println!("Message on {}: {} bytes", topic, payload.len());
+            }
+            StrategyEvent::ConnectionEvent(event) => {
+                println!("{:?}", event);
+            }
+            StrategyEvent::EventQueueOverflow { dropped, total_dropped } => {
+                println!("Strategy fell behind, {} events dropped, {} in total", dropped, total_dropped);
+            }
+            StrategyEvent::RiskStageChanged { account, stage, drawdown_percent, time } => {
+                println!("{}: Risk stage {:?} at {}% drawdown, {}", account, stage, drawdown_percent, time);
+            }
+            StrategyEvent::UniverseChanged(change) => {
+                println!("Universe {}: added {:?}, removed {:?}, {}", change.name, change.added, change.removed, change.time);
+            }
+            StrategyEvent::BasketEvents(event) => {
+                println!("{}", event);
+            }
+            StrategyEvent::AlgoEvents(event) => {
+                println!("{}", event);
+            }
         }
     }
     strategy.export_positions_to_csv(&String::from("./trades exports"));
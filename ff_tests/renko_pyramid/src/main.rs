@@ -193,7 +193,7 @@ pub async fn on_data_received(
                                             let quantity = strategy.position_size(&account, &symbol_code);
                                             if quantity < MAX_SIZE {
                                                 println!("Entering Long: Time: {}", strategy.time_local());
-                                                let tif = TimeInForce::Time((strategy.time_utc() + Duration::seconds(LIMIT_ORDER_EXPIRE_IN_SECS)).timestamp());
+                                                let tif = TimeInForce::good_till(strategy.time_utc() + Duration::seconds(LIMIT_ORDER_EXPIRE_IN_SECS));
                                                 entry_order_id = Some(strategy.limit_order(&symbol_name, Some(symbol_code.clone()), &account, None, SIZE, OrderSide::Buy, block_close.value, tif, String::from("Enter Long")).await);
                                             }
                                         }
@@ -207,7 +207,7 @@ pub async fn on_data_received(
                                             let profit = strategy.pnl(&account, &symbol_code);
                                             let quantity = strategy.position_size(&account, &symbol_code);
                                             if profit > INCREMENTAL_SCALP_PNL && quantity == MAX_SIZE && exit_order_id == None && tp_id == None {
-                                                let tif = TimeInForce::Time((strategy.time_utc() + Duration::seconds(LIMIT_ORDER_EXPIRE_IN_SECS)).timestamp());
+                                                let tif = TimeInForce::good_till(strategy.time_utc() + Duration::seconds(LIMIT_ORDER_EXPIRE_IN_SECS));
                                                 tp_id = Some(strategy.limit_order(&symbol_name, Some(symbol_code.clone()), &account, None, SIZE, OrderSide::Sell, last_close + RENKO_RANGE * dec!(4), tif, String::from("Partial TP Long")).await);
                                             }
                                         }
@@ -222,7 +222,7 @@ pub async fn on_data_received(
                                             let quantity = strategy.position_size(&account, &symbol_code);
                                             if quantity < MAX_SIZE {
                                                 println!("Entering Short: Time: {}", strategy.time_local());
-                                                let tif = TimeInForce::Time((strategy.time_utc() + Duration::seconds(LIMIT_ORDER_EXPIRE_IN_SECS)).timestamp());
+                                                let tif = TimeInForce::good_till(strategy.time_utc() + Duration::seconds(LIMIT_ORDER_EXPIRE_IN_SECS));
                                                 entry_order_id = Some(strategy.limit_order(&symbol_name, Some(symbol_code.clone()), &account, None, SIZE, OrderSide::Sell, block_close.value, tif, String::from("Enter Short")).await);
                                             }
                                         }
@@ -236,7 +236,7 @@ pub async fn on_data_received(
                                             let profit = strategy.pnl(&account, &symbol_code);
                                             let quantity = strategy.position_size(&account, &symbol_code);
                                             if profit > INCREMENTAL_SCALP_PNL && quantity == MAX_SIZE && exit_order_id == None && tp_id == None {
-                                                let tif = TimeInForce::Time((strategy.time_utc() + Duration::seconds(LIMIT_ORDER_EXPIRE_IN_SECS)).timestamp());
+                                                let tif = TimeInForce::good_till(strategy.time_utc() + Duration::seconds(LIMIT_ORDER_EXPIRE_IN_SECS));
                                                 tp_id = Some(strategy.limit_order(&symbol_name, Some(symbol_code.clone()), &account, None, SIZE, OrderSide::Buy, last_close - RENKO_RANGE * dec!(4), tif, String::from("Partial TP Short")).await);
                                             }
                                         }
@@ -8,6 +8,7 @@ use ff_gui::control_panel::panel::{new_strategy_control, window_settings, Strate
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tokio::sync::mpsc;
+use ff_standard_lib::helpers::logging::set_log_level;
 use ff_standard_lib::apis::rithmic::rithmic_systems::RithmicSystem;
 use ff_standard_lib::gui_types::settings::Color;
 use ff_standard_lib::product_maps::rithmic::maps::{get_futures_exchange, get_futures_symbol_info, get_futures_trading_hours};
@@ -403,7 +404,7 @@ pub async fn on_data_received(
                             let stop_price = strategy.calculate_stop_price(average_price, PositionSide::Long, absolute_sl_value, symbol_info.value_per_tick, symbol_info.tick_size, total_size);
                             let price = max(stop_price, SAFTEY_LEVEL);
                             //eprintln!("Stop Price: {}", stop_price);
-                            hard_stop = Some(strategy.stop_order(&symbol_name, Some(symbol_code.clone()), &account, None, total_size, OrderSide::Sell, price, TimeInForce::Day, "Hard Stop".to_string()).await);
+                            hard_stop = Some(strategy.stop_order(&symbol_name, Some(symbol_code.clone()), &account, None, total_size, OrderSide::Sell, price, TimeInForce::Day, "Hard Stop".to_string(), None).await);
                         }
                     }
                     PositionUpdateEvent::Increased { average_price, .. } => {
@@ -414,7 +415,7 @@ pub async fn on_data_received(
                         let stop_price = strategy.calculate_stop_price(average_price, PositionSide::Long, absolute_sl_value, symbol_info.value_per_tick, symbol_info.tick_size, total_size);
                         let price = max(stop_price, SAFTEY_LEVEL);
                         //eprintln!("Stop Price: {}", stop_price);
-                        hard_stop = Some(strategy.stop_order(&symbol_name, Some(symbol_code.clone()), &account, None, total_size, OrderSide::Sell, price, TimeInForce::Day, "Hard Stop".to_string()).await);
+                        hard_stop = Some(strategy.stop_order(&symbol_name, Some(symbol_code.clone()), &account, None, total_size, OrderSide::Sell, price, TimeInForce::Day, "Hard Stop".to_string(), None).await);
                     }
                     PositionUpdateEvent::PositionReduced { .. } => {
                         strategy.print_ledger(event.account());
@@ -535,6 +536,33 @@ pub async fn on_data_received(
             StrategyEvent::TimedEvent(name) => {
                 println!("{} has triggered", name);
             }
+            StrategyEvent::ParameterChanged(change) => {
+                println!("{} changed from '{}' to '{}'", change.name, change.old_value, change.new_value);
+            }
+            StrategyEvent::ScheduleEvent(event) => {
+                println!("{:?}", event);
+            }
+            StrategyEvent::CustomMessage { topic, payload } => {
+                println!("Message on {}: {} bytes", topic, payload.len());
+            }
+            StrategyEvent::ConnectionEvent(event) => {
+                println!("{:?}", event);
+            }
+            StrategyEvent::EventQueueOverflow { dropped, total_dropped } => {
+                println!("Strategy fell behind, {} events dropped, {} in total", dropped, total_dropped);
+            }
+            StrategyEvent::RiskStageChanged { account, stage, drawdown_percent, time } => {
+                println!("{}: Risk stage {:?} at {}% drawdown, {}", account, stage, drawdown_percent, time);
+            }
+            StrategyEvent::UniverseChanged(change) => {
+                println!("Universe {}: added {:?}, removed {:?}, {}", change.name, change.added, change.removed, change.time);
+            }
+            StrategyEvent::BasketEvents(event) => {
+                println!("{}", event);
+            }
+            StrategyEvent::AlgoEvents(event) => {
+                println!("{}", event);
+            }
             StrategyEvent::StrategyControls(control_message) => {
                 match control_message {
                     StrategyControls::Continue => {
@@ -576,6 +604,11 @@ pub async fn on_data_received(
                         //sleep(std::time::Duration::from_secs(2))
                     }
                     StrategyControls::CustomBytes(_, _) => {}
+                    StrategyControls::SetLogLevel(level) => {
+                        if let Err(e) = set_log_level(level) {
+                            eprintln!("{}", e);
+                        }
+                    }
                 }
             }
             _ => {}